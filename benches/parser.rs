@@ -0,0 +1,52 @@
+// Measures tokens per second and full parse time over the pages in benches/corpus: a small page, a medium article and a Wikipedia-sized article.
+// Run with `cargo bench --bench parser`, or `cargo bench --bench parser -- tokenize` for one group.
+// Scripting is disabled so the parse time is that of the parser and not of the scripts on the page.
+
+use std::fs;
+use std::hint::black_box;
+use std::path::Path;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use web_engine::tokenizer::Tokenizer;
+
+const CORPUS: [&str; 3] = ["small", "medium", "large"];
+
+fn load_corpus() -> Vec<(&'static str, String)> {
+    let corpus_directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("benches").join("corpus");
+    return CORPUS.iter().map(|name| {
+        let html = fs::read_to_string(corpus_directory.join(format!("{}.html", name))).expect("Corpus page could not be read!");
+        (*name, html)
+    }).collect();
+}
+
+fn tokenize(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("tokenize");
+    for (name, html) in load_corpus() {
+        // The throughput is reported in tokens per second
+        let token_count = Tokenizer::from_html(html.clone()).count();
+        group.throughput(Throughput::Elements(token_count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &html, |bencher, html| {
+            bencher.iter(|| Tokenizer::from_html(black_box(html.clone())).count());
+        });
+    }
+    group.finish();
+}
+
+fn parse(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("parse");
+    for (name, html) in load_corpus() {
+        group.throughput(Throughput::Bytes(html.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &html, |bencher, html| {
+            bencher.iter(|| {
+                let mut tokenizer = Tokenizer::from_html(black_box(html.clone()));
+                tokenizer.set_scripting_enabled(false);
+                tokenizer.run();
+                tokenizer.document()
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, tokenize, parse);
+criterion_main!(benches);
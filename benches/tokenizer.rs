@@ -0,0 +1,42 @@
+// Measures how long the tokenizer takes on a text-heavy document and how many tokens it produces.
+// Run with `cargo bench --bench tokenizer`.
+
+use std::fs;
+use std::time::Instant;
+
+use web_engine::tokenizer::Tokenizer;
+
+const PARAGRAPHS: usize = 2000;
+const ITERATIONS: u32 = 5;
+
+fn build_text_heavy_document() -> String {
+    let mut document = String::from("<!DOCTYPE html>\n<html>\n<head>\n<title>Benchmark</title>\n</head>\n<body>\n");
+
+    for index in 0..PARAGRAPHS {
+        document.push_str(&format!("<p>Paragraph {} of the benchmark document. Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.</p>\n", index));
+    }
+
+    document.push_str("</body>\n</html>\n");
+    document
+}
+
+fn main() {
+    let document = build_text_heavy_document();
+    let path = std::env::temp_dir().join("web_engine_tokenizer_bench.html");
+    fs::write(&path, &document).expect("Benchmark document could not be written!");
+
+    let mut total_tokens = 0;
+    let start = Instant::now();
+
+    for _ in 0..ITERATIONS {
+        let tokenizer = Tokenizer::new(path.to_string_lossy().to_string());
+        total_tokens = tokenizer.count();
+    }
+
+    let elapsed = start.elapsed() / ITERATIONS;
+    let megabytes_per_second = (document.len() as f64 / (1024.0 * 1024.0)) / elapsed.as_secs_f64();
+
+    println!("tokenizer/text_heavy: {} bytes, {} tokens, {:?} per iteration ({:.2} MiB/s)", document.len(), total_tokens, elapsed, megabytes_per_second);
+
+    fs::remove_file(&path).ok();
+}
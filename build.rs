@@ -0,0 +1,38 @@
+// Generates the named character reference table from src/entities.json at compile time so the
+// tokenizer does not have to parse JSON whenever it is created.
+// The JSON file uses the same layout as https://html.spec.whatwg.org/entities.json
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/entities.json");
+
+    let source = fs::read_to_string("src/entities.json").expect("src/entities.json could not be read!");
+    let entities: Value = serde_json::from_str(&source).expect("src/entities.json is not valid JSON!");
+
+    let mut named_character_references: Vec<(String, String)> = entities.as_object().unwrap().iter()
+        .map(|(name, entity)| {
+            // The leading '&' is already consumed by the tokenizer before the name is matched
+            let name = name.trim_start_matches('&').to_string();
+            let characters = entity["characters"].as_str().unwrap().to_string();
+            (name, characters)
+        })
+        .collect();
+
+    // Sorted by name so the table can be binary searched for both exact and prefix matches
+    named_character_references.sort();
+
+    // The table is private to src/named_character_references.rs, which is how the rest of the engine looks names up
+    let mut generated = String::from("static NAMED_CHARACTER_REFERENCES: &[(&str, &str)] = &[\n");
+    for (name, characters) in named_character_references.iter() {
+        generated.push_str(&format!("    ({:?}, {:?}),\n", name, characters));
+    }
+    generated.push_str("];\n");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("named_character_references.rs"), generated).unwrap();
+}
@@ -0,0 +1,23 @@
+// Checks that any sequence of bytes parses into a document with an html element, without panicking.
+// Scripting is disabled, as the scripts of untrusted input are not run by the parser.
+// The document is serialized as well, so a walk of the tree that overflows the stack on deeply nested input aborts the fuzzer.
+// Run with `cargo +nightly fuzz run document fuzz/corpus/tokenizer` from the repository root.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use web_engine::html_document_parser::ParserOptions;
+use web_engine::node::NodeData;
+use web_engine::serializer::serialize_children;
+use web_engine::tokenizer::Tokenizer;
+
+fuzz_target!(|data: &[u8]| {
+    let options = ParserOptions { scripting_enabled: false, print_parse_errors: false, ..ParserOptions::default() };
+    let mut tokenizer = Tokenizer::from_bytes(data.to_vec(), options);
+    tokenizer.run();
+
+    let document = tokenizer.document();
+    let document = document.borrow();
+    assert!(matches!(document.data, NodeData::Document(_)));
+    assert!(document.childNodes.iter().any(|child| matches!(&child.borrow().data, NodeData::Element(element) if element.local_name() == "html")));
+    serialize_children(&tokenizer.document());
+});
@@ -0,0 +1,14 @@
+// Feeds arbitrary input through the tokenizer, which also drives the tree builder.
+// Scripting is disabled, as the scripts of untrusted input are not run by the parser.
+// Run with `cargo +nightly fuzz run tokenizer fuzz/corpus/tokenizer` from the repository root.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use web_engine::html_document_parser::ParserOptions;
+use web_engine::tokenizer::Tokenizer;
+
+fuzz_target!(|data: &[u8]| {
+    let options = ParserOptions { scripting_enabled: false, print_parse_errors: false, ..ParserOptions::default() };
+    let mut tokenizer = Tokenizer::from_bytes(data.to_vec(), options);
+    tokenizer.run();
+});
@@ -0,0 +1,57 @@
+#ifndef WEB_ENGINE_H
+#define WEB_ENGINE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/* The C interface of the parser, the declarations of the functions in src/ffi.rs, which are kept in step with them */
+
+/* The result of a call, the values are part of the interface and never change meaning */
+typedef enum WebEngineStatus {
+  WEB_ENGINE_STATUS_OK = 0,
+  /* A pointer that has to point to something was null */
+  WEB_ENGINE_STATUS_NULL_ARGUMENT = 1,
+  /* A string argument is not UTF-8 */
+  WEB_ENGINE_STATUS_INVALID_UTF8 = 2,
+  WEB_ENGINE_STATUS_INVALID_SELECTOR = 3,
+  /* The node index is past the last node of the document */
+  WEB_ENGINE_STATUS_NO_SUCH_NODE = 4,
+  /* The node is not an element, or the element does not have the attribute */
+  WEB_ENGINE_STATUS_NOT_FOUND = 5,
+  /* The library panicked, which is a bug in it, the document is still safe to use and free */
+  WEB_ENGINE_STATUS_INTERNAL_ERROR = 6,
+} WebEngineStatus;
+
+/* A parsed document */
+typedef struct WebEngineDocument WebEngineDocument;
+
+/* The indices of the nodes a query found, in tree order */
+typedef struct WebEngineNodeList WebEngineNodeList;
+
+WebEngineStatus web_engine_parse(const uint8_t *html, size_t length, WebEngineDocument **document_out);
+
+void web_engine_document_free(WebEngineDocument *document);
+
+size_t web_engine_document_node_count(const WebEngineDocument *document);
+
+size_t web_engine_document_parse_error_count(const WebEngineDocument *document);
+
+WebEngineStatus web_engine_query_selector_all(const WebEngineDocument *document, const char *selectors, WebEngineNodeList **list_out);
+
+size_t web_engine_node_list_length(const WebEngineNodeList *list);
+
+WebEngineStatus web_engine_node_list_get(const WebEngineNodeList *list, size_t index, size_t *node_out);
+
+void web_engine_node_list_free(WebEngineNodeList *list);
+
+WebEngineStatus web_engine_node_text_content(const WebEngineDocument *document, size_t node, char **text_out);
+
+WebEngineStatus web_engine_node_get_attribute(const WebEngineDocument *document, size_t node, const char *name, char **value_out);
+
+WebEngineStatus web_engine_node_local_name(const WebEngineDocument *document, size_t node, char **name_out);
+
+void web_engine_string_free(char *string);
+
+const char *web_engine_status_message(int status);
+
+#endif /* WEB_ENGINE_H */
@@ -0,0 +1,388 @@
+use serde::Serialize;
+use crate::forms::checked;
+use crate::language::{directionality, language};
+use crate::layout::{build_box_tree, BoxType, LayoutBox};
+use crate::metadata::document_title;
+use crate::node::{descendants, NodeData, RefNode};
+use crate::selector::PseudoElement;
+use crate::unicode::{ascii_lowercase, is_ascii_case_insensitive_match};
+
+// https://w3c.github.io/html-aam/
+// The accessibility tree of a document, the tree assistive technologies are given instead of the DOM
+// Elements are mapped to roles by their tag and attributes unless they have a role attribute, and elements with no semantics (e.g. div and span) are left out with their children taking their place
+// TODO: States other than level, checked and disabled are not exposed, and names are not computed for every role that allows them
+
+#[derive(Debug, Serialize)]
+pub struct AccessibleNode {
+    // https://w3c.github.io/aria/#roles
+    pub role: String,
+    // https://w3c.github.io/accname/#dfn-accessible-name
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    // https://w3c.github.io/aria/#aria-level
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<u32>,
+    // https://w3c.github.io/aria/#aria-checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checked: Option<bool>,
+    // https://w3c.github.io/aria/#aria-disabled
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+    // https://w3c.github.io/html-aam/#att-lang
+    // The language of the node where it differs from its parent's, and of the document
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    // https://w3c.github.io/html-aam/#att-dir
+    // The directionality of the node where it differs from its parent's, and of the document
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<AccessibleNode>,
+}
+
+impl AccessibleNode {
+    fn new(role: &str, name: String) -> AccessibleNode {
+        return AccessibleNode { role: role.to_string(), name, level: None, checked: None, disabled: false, language: None, direction: None, children: Vec::new() };
+    }
+
+    pub fn to_json(&self) -> String {
+        return serde_json::to_string_pretty(self).unwrap_or_default();
+    }
+}
+
+// The accessibility tree of document, rooted at a node with the document role named by its title
+// Only elements that are rendered are in the tree, so it is built from the box tree, None when the document element is not rendered
+pub fn build_accessibility_tree(document: &RefNode) -> Option<AccessibleNode> {
+    let root_box = build_box_tree(document)?;
+    let mut root = AccessibleNode::new("document", document_title(document));
+    root.children = accessible_children(document, &root_box, false);
+    let root_language = language(&root_box.node);
+    let root_direction = directionality(&root_box.node).as_str().to_string();
+    for child in &mut root.children {
+        remove_inherited_language(child, &root_language, &root_direction);
+    }
+    root.language = Some(root_language).filter(|language| !language.is_empty());
+    root.direction = Some(root_direction);
+    return Some(root);
+}
+
+// The language and direction of a node are only exposed where they change, the nodes left out of the tree (e.g. a div) may have set them for their children
+fn remove_inherited_language(node: &mut AccessibleNode, parent_language: &str, parent_direction: &str) {
+    let node_language = node.language.take().unwrap_or_else(|| parent_language.to_string());
+    let node_direction = node.direction.take().unwrap_or_else(|| parent_direction.to_string());
+    for child in &mut node.children {
+        remove_inherited_language(child, &node_language, &node_direction);
+    }
+    if node_language != parent_language {
+        node.language = Some(node_language);
+    }
+    if node_direction != parent_direction {
+        node.direction = Some(node_direction);
+    }
+}
+
+// The accessible nodes of the children of layout_box, text is left out when it is already the name of the parent
+fn accessible_children(document: &RefNode, layout_box: &LayoutBox, name_from_content: bool) -> Vec<AccessibleNode> {
+    let mut children = Vec::new();
+    for child in &layout_box.children {
+        append_accessible_nodes(document, child, &mut children);
+    }
+
+    for child in children.iter_mut().filter(|child| child.role == "text") {
+        child.name = collapse_white_space(&child.name);
+    }
+    children.retain(|child| child.role != "text" || (!child.name.is_empty() && !name_from_content));
+    return children;
+}
+
+fn append_accessible_nodes(document: &RefNode, layout_box: &LayoutBox, nodes: &mut Vec<AccessibleNode>) {
+    // The text of adjacent text nodes, including that of the elements left out between them, is one text node
+    if let BoxType::Text(text) = &layout_box.box_type {
+        match nodes.last_mut().filter(|node| node.role == "text") {
+            Some(node) => node.name.push_str(text),
+            None => nodes.push(AccessibleNode::new("text", text.clone()))
+        }
+        return;
+    }
+
+    // https://w3c.github.io/accname/#step2F.ii
+    // The text of ::before and ::after is text of their element, and list item markers are left out as the role of the list item already says it is one
+    // TODO: The markers are not exposed, so a list's numbering is not either
+    match layout_box.pseudo_element {
+        Some(PseudoElement::Marker) => return,
+        Some(_) => {
+            for child in &layout_box.children {
+                append_accessible_nodes(document, child, nodes);
+            }
+            return;
+        },
+        None => ()
+    }
+
+    // https://w3c.github.io/aria/#aria-hidden
+    if layout_box.get_attribute("aria-hidden").is_some_and(|aria_hidden| is_ascii_case_insensitive_match(aria_hidden.trim(), "true")) {
+        return;
+    }
+
+    let role = role(layout_box);
+    // https://w3c.github.io/aria/#presentation
+    // https://w3c.github.io/aria/#generic
+    if matches!(role.as_str(), "none" | "presentation" | "generic") {
+        for child in &layout_box.children {
+            append_accessible_nodes(document, child, nodes);
+        }
+        return;
+    }
+
+    let (name, name_from_content) = accessible_name(document, &layout_box.node, &role);
+    let mut node = AccessibleNode::new(&role, name);
+
+    let local_name = layout_box.local_name();
+    node.level = layout_box.get_attribute("aria-level").and_then(|level| level.trim().parse::<u32>().ok()).or_else(|| match local_name.as_str() {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" if role == "heading" => local_name[1..].parse::<u32>().ok(),
+        _ => None
+    });
+    if matches!(role.as_str(), "checkbox" | "radio" | "switch" | "menuitemcheckbox" | "menuitemradio") {
+        node.checked = match layout_box.get_attribute("aria-checked") {
+            Some(aria_checked) => Some(is_ascii_case_insensitive_match(aria_checked.trim(), "true")),
+            None => Some(checked(&layout_box.node))
+        };
+    }
+    node.disabled = layout_box.get_attribute("disabled").is_some() || layout_box.get_attribute("aria-disabled").is_some_and(|aria_disabled| is_ascii_case_insensitive_match(aria_disabled.trim(), "true"));
+    // Left out again where they are the same as the parent's once the tree is built, see remove_inherited_language
+    node.language = Some(language(&layout_box.node));
+    node.direction = Some(directionality(&layout_box.node).as_str().to_string());
+
+    node.children = accessible_children(document, layout_box, name_from_content);
+    nodes.push(node);
+}
+
+// https://w3c.github.io/html-aam/#html-element-role-mappings
+// The role of the element, from its role attribute if it has one and otherwise from its tag
+fn role(layout_box: &LayoutBox) -> String {
+    // https://w3c.github.io/aria/#document-handling_author-errors_roles
+    // TODO: The first role token the engine knows should be used, rather than the first token
+    if let Some(role) = layout_box.get_attribute("role").and_then(|role| role.split_ascii_whitespace().next().map(|role| ascii_lowercase(role).into_owned())) {
+        return role;
+    }
+
+    let has_attribute = |name: &str| layout_box.get_attribute(name).is_some();
+    let role = match layout_box.local_name().as_str() {
+        "a" | "area" if has_attribute("href") => "link",
+        "article" => "article",
+        "aside" => "complementary",
+        "blockquote" => "blockquote",
+        "button" => "button",
+        "caption" => "caption",
+        "code" => "code",
+        "dd" => "definition",
+        "del" | "s" => "deletion",
+        "details" => "group",
+        "dialog" => "dialog",
+        "dt" => "term",
+        "em" => "emphasis",
+        "fieldset" => "group",
+        "figure" => "figure",
+        // TODO: footer and header are only contentinfo and banner when they are not inside an article, aside, main, nav or section
+        "footer" => "contentinfo",
+        "form" => "form",
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => "heading",
+        "header" => "banner",
+        "hr" => "separator",
+        // An image with an empty alt is decorative
+        "img" if layout_box.get_attribute("alt").is_some_and(|alt| alt.is_empty()) => "presentation",
+        "img" => "img",
+        "input" => {
+            match ascii_lowercase(&layout_box.get_attribute("type").unwrap_or_default()).as_ref() {
+                "button" | "image" | "reset" | "submit" => "button",
+                "checkbox" => "checkbox",
+                "hidden" => "none",
+                "number" => "spinbutton",
+                "radio" => "radio",
+                "range" => "slider",
+                "search" => "searchbox",
+                _ => "textbox"
+            }
+        },
+        "ins" => "insertion",
+        "li" => "listitem",
+        "main" => "main",
+        "mark" => "mark",
+        "menu" | "ol" | "ul" => "list",
+        "meter" => "meter",
+        "nav" => "navigation",
+        "optgroup" => "group",
+        "option" => "option",
+        "output" => "status",
+        "p" => "paragraph",
+        "progress" => "progressbar",
+        // A section is only a landmark when it has a name
+        "section" if has_attribute("aria-label") || has_attribute("aria-labelledby") => "region",
+        "select" if has_attribute("multiple") || layout_box.get_attribute("size").and_then(|size| size.trim().parse::<u32>().ok()).is_some_and(|size| size > 1) => "listbox",
+        "select" => "combobox",
+        "strong" => "strong",
+        "sub" => "subscript",
+        "sup" => "superscript",
+        "table" => "table",
+        "tbody" | "tfoot" | "thead" => "rowgroup",
+        "td" => "cell",
+        "textarea" => "textbox",
+        "th" => "columnheader",
+        "time" => "time",
+        "tr" => "row",
+        _ => "generic"
+    };
+    return role.to_string();
+}
+
+// https://w3c.github.io/accname/#computation-steps
+// The accessible name of element, and whether it was computed from its content
+// TODO: Embedded controls, CSS generated content and recursion through aria-labelledby are not handled
+fn accessible_name(document: &RefNode, element: &RefNode, role: &str) -> (String, bool) {
+    let get_attribute = |name: &str| match &element.borrow().data {
+        NodeData::Element(element) => element.get_attribute(name).cloned(),
+        _ => None
+    };
+    let local_name = match &element.borrow().data {
+        NodeData::Element(element) => element.local_name().to_string(),
+        _ => String::new()
+    };
+
+    // 2B. if computing a name, and the current node has an aria-labelledby attribute that contains at least one valid IDREF, and the current node is not already part of an aria-labelledby traversal,
+    // process its IDREFs in order
+    if let Some(aria_labelledby) = get_attribute("aria-labelledby") {
+        let labels: Vec<String> = aria_labelledby.split_ascii_whitespace().filter_map(|id| element_by_id(document, id)).map(|label| text_from_content(&label)).collect();
+        if !labels.is_empty() {
+            return (collapse_white_space(&labels.join(" ")), false);
+        }
+    }
+
+    // 2D. Otherwise, if the current node has an aria-label attribute whose value is not undefined, not the empty string, nor, when trimmed of whitespace, is not the empty string
+    if let Some(aria_label) = get_attribute("aria-label").filter(|aria_label| !aria_label.trim().is_empty()) {
+        return (collapse_white_space(&aria_label), false);
+    }
+
+    // 2E. Otherwise, if the current node's native markup provides an attribute (e.g. alt) or element (e.g. HTML label or SVG title) that defines a text alternative, return that alternative
+    // https://w3c.github.io/html-aam/#accessible-name-computations-by-html-element
+    let native_name = match local_name.as_str() {
+        "img" | "area" => get_attribute("alt"),
+        "input" => {
+            match ascii_lowercase(&get_attribute("type").unwrap_or_default()).as_ref() {
+                "image" => get_attribute("alt").or_else(|| get_attribute("value")).or_else(|| Some("Submit".to_string())),
+                "submit" => get_attribute("value").or_else(|| Some("Submit".to_string())),
+                "reset" => get_attribute("value").or_else(|| Some("Reset".to_string())),
+                "button" => get_attribute("value"),
+                _ => label_text(document, element)
+            }
+        },
+        "select" | "textarea" | "meter" | "output" | "progress" => label_text(document, element),
+        "fieldset" => first_child_element_text(element, "legend"),
+        "figure" => first_child_element_text(element, "figcaption"),
+        "table" => first_child_element_text(element, "caption"),
+        _ => None
+    };
+    if let Some(native_name) = native_name.map(|native_name| collapse_white_space(&native_name)).filter(|native_name| !native_name.is_empty()) {
+        return (native_name, false);
+    }
+
+    // 2F. Otherwise, if the current node's role allows name from content, or if the current node is referenced by aria-labelledby, aria-describedby, or is a native host language text alternative element
+    // https://w3c.github.io/aria/#namefromcontent
+    let allows_name_from_content = matches!(role, "button" | "cell" | "checkbox" | "columnheader" | "gridcell" | "heading" | "link" | "menuitem" | "menuitemcheckbox"
+        | "menuitemradio" | "option" | "radio" | "row" | "rowheader" | "switch" | "tab" | "tooltip" | "treeitem" | "caption" | "code" | "term");
+    if allows_name_from_content {
+        let content = collapse_white_space(&text_from_content(element));
+        if !content.is_empty() {
+            return (content, true);
+        }
+    }
+
+    // 2I. Otherwise, if the current node has a Tooltip attribute, return its value.
+    return (get_attribute("title").map(|title| collapse_white_space(&title)).unwrap_or_default(), false);
+}
+
+// https://w3c.github.io/accname/#step2F
+// The text of the descendants of node, with the text alternatives of the images and labelled elements among them, and without hidden elements
+fn text_from_content(node: &RefNode) -> String {
+    let mut text = String::new();
+    for child in &node.borrow().childNodes {
+        match &child.borrow().data {
+            NodeData::Text(child_text) => text.push_str(&child_text.character_data.data),
+            NodeData::Element(element) => {
+                let hidden = element.has_attribute("hidden") || element.get_attribute("aria-hidden").is_some_and(|aria_hidden| is_ascii_case_insensitive_match(aria_hidden.trim(), "true"));
+                if hidden || matches!(element.local_name().as_str(), "script" | "style" | "template") {
+                    continue;
+                }
+
+                if let Some(aria_label) = element.get_attribute("aria-label").filter(|aria_label| !aria_label.trim().is_empty()) {
+                    text.push_str(aria_label);
+                } else if element.local_name() == "img" {
+                    text.push_str(element.get_attribute("alt").map(|alt| alt.as_str()).unwrap_or(""));
+                } else {
+                    text.push_str(&text_from_content(child));
+                }
+                // Each element is a word of its own in the name, so the text of adjacent blocks does not run together
+                // TODO: Inline elements should not be separated from the text around them
+                text.push(' ');
+            },
+            _ => {}
+        }
+    }
+    return text;
+}
+
+// https://html.spec.whatwg.org/multipage/forms.html#labeled-control
+// The text of the label elements of a form control, those whose for attribute is its id and the one it is inside
+fn label_text(document: &RefNode, element: &RefNode) -> Option<String> {
+    let id = match &element.borrow().data {
+        NodeData::Element(element) => element.get_attribute("id").cloned().filter(|id| !id.is_empty()),
+        _ => None
+    };
+
+    let mut labels: Vec<String> = Vec::new();
+    if let Some(id) = id {
+        for label in descendants(document).into_iter().filter(|node| is_element_with_local_name(node, "label")) {
+            let is_for_element = match &label.borrow().data {
+                NodeData::Element(label) => label.get_attribute("for") == Some(&id),
+                _ => false
+            };
+            if is_for_element {
+                labels.push(text_from_content(&label));
+            }
+        }
+    }
+
+    let mut ancestor = element.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+    while let Some(node) = ancestor {
+        if is_element_with_local_name(&node, "label") {
+            labels.push(text_from_content(&node));
+            break;
+        }
+        ancestor = node.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+    }
+
+    if labels.is_empty() {
+        return None;
+    }
+    return Some(labels.join(" "));
+}
+
+fn first_child_element_text(element: &RefNode, local_name: &str) -> Option<String> {
+    return element.borrow().childNodes.iter().find(|child| is_element_with_local_name(child, local_name)).map(text_from_content);
+}
+
+// https://dom.spec.whatwg.org/#dom-nonelementparentnode-getelementbyid
+fn element_by_id(document: &RefNode, id: &str) -> Option<RefNode> {
+    return descendants(document).into_iter().find(|node| match &node.borrow().data {
+        NodeData::Element(element) => element.get_attribute("id").is_some_and(|element_id| element_id == id),
+        _ => false
+    });
+}
+
+fn is_element_with_local_name(node: &RefNode, local_name: &str) -> bool {
+    return matches!(&node.borrow().data, NodeData::Element(element) if element.local_name() == local_name);
+}
+
+// Names are flattened to a single line, as whitespace in markup is not meaningful
+fn collapse_white_space(text: &str) -> String {
+    return text.split_ascii_whitespace().collect::<Vec<&str>>().join(" ");
+}
@@ -0,0 +1,492 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::interpreter::{argument, completion_value, create_normal_completion, create_value_completion, return_if_abrupt, CompletionRecord, Interpreter, JSObject, JSValue, PropertyKey, ReferenceRecordOrJsValue};
+
+// https://tc39.es/ecma262/#sec-properties-of-the-array-prototype-object
+// TODO: Only the methods scripts commonly use are implemented, and there is no Array constructor yet
+
+// https://tc39.es/ecma262/#sec-properties-of-the-array-prototype-object
+pub(crate) fn install_array(interpreter: &mut Interpreter) {
+    // The Array prototype object is an Array exotic object
+    let array_prototype = interpreter.array_create(0);
+    array_prototype.borrow_mut().prototype = interpreter.intrinsic("Object.prototype");
+
+    Interpreter::define_builtin_function(&array_prototype, "push", array_prototype_push);
+    Interpreter::define_builtin_function(&array_prototype, "pop", array_prototype_pop);
+    Interpreter::define_builtin_function(&array_prototype, "slice", array_prototype_slice);
+    Interpreter::define_builtin_function(&array_prototype, "indexOf", array_prototype_index_of);
+    Interpreter::define_builtin_function(&array_prototype, "map", array_prototype_map);
+    Interpreter::define_builtin_function(&array_prototype, "forEach", array_prototype_for_each);
+    Interpreter::define_builtin_function(&array_prototype, "join", array_prototype_join);
+    Interpreter::define_builtin_function(&array_prototype, "toString", array_prototype_to_string);
+
+    interpreter.set_intrinsic("Array.prototype", array_prototype);
+}
+
+// https://tc39.es/ecma262/#sec-array.prototype.push
+fn array_prototype_push(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let O be ? ToObject(this value).
+    let object = return_if_abrupt!(to_object(interpreter, &this_value));
+
+    // 2. Let len be ? LengthOfArrayLike(O).
+    let mut length = return_if_abrupt!(length_of_array_like(interpreter, &object));
+
+    // 3. Let argCount be the number of elements in items.
+    // 4. If len + argCount > 2^53 - 1, throw a TypeError exception.
+    if length + arguments.len() as f64 > MAX_SAFE_INTEGER {
+        return interpreter.throw_type_error(String::from("Pushing the elements would make the length too large"));
+    }
+
+    // 5. For each element E of items, do
+    for element in arguments {
+        // a. Perform ? Set(O, ! ToString(𝔽(len)), E, true).
+        return_if_abrupt!(set(interpreter, &object, Interpreter::number_to_string(length), element));
+
+        // b. Set len to len + 1.
+        length += 1.0;
+    }
+
+    // 6. Perform ? Set(O, "length", 𝔽(len), true).
+    return_if_abrupt!(set(interpreter, &object, String::from("length"), Rc::new(RefCell::new(JSValue::Numeric(length)))));
+
+    // 7. Return 𝔽(len).
+    return create_value_completion(JSValue::Numeric(length));
+}
+
+// https://tc39.es/ecma262/#sec-array.prototype.pop
+fn array_prototype_pop(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let O be ? ToObject(this value).
+    let object = return_if_abrupt!(to_object(interpreter, &this_value));
+
+    // 2. Let len be ? LengthOfArrayLike(O).
+    let length = return_if_abrupt!(length_of_array_like(interpreter, &object));
+
+    // 3. If len = 0, then
+    if length == 0.0 {
+        // a. Perform ? Set(O, "length", +0𝔽, true).
+        return_if_abrupt!(set(interpreter, &object, String::from("length"), Rc::new(RefCell::new(JSValue::Numeric(0.0)))));
+
+        // b. Return undefined.
+        return create_value_completion(JSValue::Undefined);
+    }
+
+    // 4. Else,
+    // a. Assert: len > 0.
+    // b. Let newLen be 𝔽(len - 1).
+    let new_length = length - 1.0;
+
+    // c. Let index be ! ToString(newLen).
+    let index = Interpreter::number_to_string(new_length);
+
+    // d. Let element be ? Get(O, index).
+    let element = return_if_abrupt!(get(interpreter, &object, index.clone()));
+
+    // e. Perform ? DeletePropertyOrThrow(O, index).
+    return_if_abrupt!(delete_property_or_throw(interpreter, &object, index));
+
+    // f. Perform ? Set(O, "length", newLen, true).
+    return_if_abrupt!(set(interpreter, &object, String::from("length"), Rc::new(RefCell::new(JSValue::Numeric(new_length)))));
+
+    // g. Return element.
+    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(element)));
+}
+
+// https://tc39.es/ecma262/#sec-array.prototype.slice
+fn array_prototype_slice(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let O be ? ToObject(this value).
+    let object = return_if_abrupt!(to_object(interpreter, &this_value));
+
+    // 2. Let len be ? LengthOfArrayLike(O).
+    let length = return_if_abrupt!(length_of_array_like(interpreter, &object));
+
+    // 3. Let relativeStart be ? ToIntegerOrInfinity(start).
+    let relative_start = return_if_abrupt!(to_integer_or_infinity(interpreter, &argument(&arguments, 0)));
+
+    // 4. If relativeStart = -∞, let k be 0.
+    // 5. Else if relativeStart < 0, let k be max(len + relativeStart, 0).
+    // 6. Else, let k be min(relativeStart, len).
+    let mut k = relative_index(relative_start, length);
+
+    // 7. If end is undefined, let relativeEnd be len; else let relativeEnd be ? ToIntegerOrInfinity(end).
+    let end = argument(&arguments, 1);
+    let relative_end = if matches!(*end.borrow(), JSValue::Undefined) {
+        length
+    } else {
+        return_if_abrupt!(to_integer_or_infinity(interpreter, &end))
+    };
+
+    // 8. If relativeEnd = -∞, let final be 0.
+    // 9. Else if relativeEnd < 0, let final be max(len + relativeEnd, 0).
+    // 10. Else, let final be min(relativeEnd, len).
+    let final_index = relative_index(relative_end, length);
+
+    // 11. Let count be max(final - k, 0).
+    let count = (final_index - k).max(0.0);
+
+    // 12. Let A be ? ArraySpeciesCreate(O, count).
+    // TODO: Species constructors are not supported, the result is always an Array
+    let array = interpreter.array_create(count as u32);
+
+    // 13. Let n be 0.
+    let mut n: f64 = 0.0;
+
+    // 14. Repeat, while k < final,
+    while k < final_index {
+        // a. Let Pk be ! ToString(𝔽(k)).
+        let property_key = Interpreter::number_to_string(k);
+
+        // b. Let kPresent be ? HasProperty(O, Pk).
+        // c. If kPresent is true, then
+        if has_property(&object, property_key.clone()) {
+            // i. Let kValue be ? Get(O, Pk).
+            let k_value = return_if_abrupt!(get(interpreter, &object, property_key));
+
+            // ii. Perform ? CreateDataPropertyOrThrow(A, ! ToString(𝔽(n)), kValue).
+            Interpreter::create_data_property(&array, &Interpreter::number_to_string(n), k_value);
+        }
+
+        // d. Set k to k + 1.
+        k += 1.0;
+
+        // e. Set n to n + 1.
+        n += 1.0;
+    }
+
+    // 15. Perform ? Set(A, "length", 𝔽(n), true).
+    return_if_abrupt!(set(interpreter, &array, String::from("length"), Rc::new(RefCell::new(JSValue::Numeric(n)))));
+
+    // 16. Return A.
+    return create_value_completion(JSValue::Object(array));
+}
+
+// https://tc39.es/ecma262/#sec-array.prototype.indexof
+fn array_prototype_index_of(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let O be ? ToObject(this value).
+    let object = return_if_abrupt!(to_object(interpreter, &this_value));
+
+    // 2. Let len be ? LengthOfArrayLike(O).
+    let length = return_if_abrupt!(length_of_array_like(interpreter, &object));
+
+    // 3. If len = 0, return -1𝔽.
+    if length == 0.0 {
+        return create_value_completion(JSValue::Numeric(-1.0));
+    }
+
+    // 4. Let n be ? ToIntegerOrInfinity(fromIndex).
+    // 5. Assert: If fromIndex is undefined, then n is 0.
+    let n = return_if_abrupt!(to_integer_or_infinity(interpreter, &argument(&arguments, 1)));
+
+    // 6. If n = +∞, return -1𝔽.
+    if n == f64::INFINITY {
+        return create_value_completion(JSValue::Numeric(-1.0));
+    }
+
+    // 7. Else if n = -∞, set n to 0.
+    // 8. If n ≥ 0, then
+    //     a. Let k be n.
+    // 9. Else,
+    //     a. Let k be len + n.
+    //     b. If k < 0, set k to 0.
+    let mut k = relative_index(n, length);
+
+    let search_element = argument(&arguments, 0);
+
+    // 10. Repeat, while k < len,
+    while k < length {
+        let property_key = Interpreter::number_to_string(k);
+
+        // a. Let kPresent be ? HasProperty(O, ! ToString(𝔽(k))).
+        // b. If kPresent is true, then
+        if has_property(&object, property_key.clone()) {
+            // i. Let elementK be ? Get(O, ! ToString(𝔽(k))).
+            let element_k = return_if_abrupt!(get(interpreter, &object, property_key));
+
+            // ii. If IsStrictlyEqual(searchElement, elementK) is true, return 𝔽(k).
+            if Interpreter::is_strictly_equal(&search_element.borrow(), &element_k.borrow()) {
+                return create_value_completion(JSValue::Numeric(k));
+            }
+        }
+
+        // c. Set k to k + 1.
+        k += 1.0;
+    }
+
+    // 11. Return -1𝔽.
+    return create_value_completion(JSValue::Numeric(-1.0));
+}
+
+// https://tc39.es/ecma262/#sec-array.prototype.map
+fn array_prototype_map(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let O be ? ToObject(this value).
+    let object = return_if_abrupt!(to_object(interpreter, &this_value));
+
+    // 2. Let len be ? LengthOfArrayLike(O).
+    let length = return_if_abrupt!(length_of_array_like(interpreter, &object));
+
+    // 3. If IsCallable(callbackfn) is false, throw a TypeError exception.
+    let callback = argument(&arguments, 0);
+    if !Interpreter::is_callable(&callback.borrow()) {
+        return interpreter.throw_type_error(String::from("Array.prototype.map callback is not a function"));
+    }
+    let this_argument = argument(&arguments, 1);
+
+    // 4. Let A be ? ArraySpeciesCreate(O, len).
+    // TODO: Species constructors are not supported, the result is always an Array
+    let array = interpreter.array_create(length as u32);
+
+    // 5. Let k be 0.
+    let mut k: f64 = 0.0;
+
+    // 6. Repeat, while k < len,
+    while k < length {
+        // a. Let Pk be ! ToString(𝔽(k)).
+        let property_key = Interpreter::number_to_string(k);
+
+        // b. Let kPresent be ? HasProperty(O, Pk).
+        // c. If kPresent is true, then
+        if has_property(&object, property_key.clone()) {
+            // i. Let kValue be ? Get(O, Pk).
+            let k_value = return_if_abrupt!(get(interpreter, &object, property_key.clone()));
+
+            // ii. Let mappedValue be ? Call(callbackfn, thisArg, « kValue, 𝔽(k), O »).
+            let k_argument = Rc::new(RefCell::new(JSValue::Numeric(k)));
+            let object_argument = Rc::new(RefCell::new(JSValue::Object(Rc::clone(&object))));
+            let mapped_value = return_if_abrupt!(completion_value(interpreter.call(Rc::clone(&callback), Rc::clone(&this_argument), vec![k_value, k_argument, object_argument])));
+
+            // iii. Perform ? CreateDataPropertyOrThrow(A, Pk, mappedValue).
+            Interpreter::create_data_property(&array, &property_key, mapped_value);
+        }
+
+        // d. Set k to k + 1.
+        k += 1.0;
+    }
+
+    // 7. Return A.
+    return create_value_completion(JSValue::Object(array));
+}
+
+// https://tc39.es/ecma262/#sec-array.prototype.foreach
+fn array_prototype_for_each(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let O be ? ToObject(this value).
+    let object = return_if_abrupt!(to_object(interpreter, &this_value));
+
+    // 2. Let len be ? LengthOfArrayLike(O).
+    let length = return_if_abrupt!(length_of_array_like(interpreter, &object));
+
+    // 3. If IsCallable(callbackfn) is false, throw a TypeError exception.
+    let callback = argument(&arguments, 0);
+    if !Interpreter::is_callable(&callback.borrow()) {
+        return interpreter.throw_type_error(String::from("Array.prototype.forEach callback is not a function"));
+    }
+    let this_argument = argument(&arguments, 1);
+
+    // 4. Let k be 0.
+    let mut k: f64 = 0.0;
+
+    // 5. Repeat, while k < len,
+    while k < length {
+        // a. Let Pk be ! ToString(𝔽(k)).
+        let property_key = Interpreter::number_to_string(k);
+
+        // b. Let kPresent be ? HasProperty(O, Pk).
+        // c. If kPresent is true, then
+        if has_property(&object, property_key.clone()) {
+            // i. Let kValue be ? Get(O, Pk).
+            let k_value = return_if_abrupt!(get(interpreter, &object, property_key));
+
+            // ii. Perform ? Call(callbackfn, thisArg, « kValue, 𝔽(k), O »).
+            let k_argument = Rc::new(RefCell::new(JSValue::Numeric(k)));
+            let object_argument = Rc::new(RefCell::new(JSValue::Object(Rc::clone(&object))));
+            return_if_abrupt!(completion_value(interpreter.call(Rc::clone(&callback), Rc::clone(&this_argument), vec![k_value, k_argument, object_argument])));
+        }
+
+        // d. Set k to k + 1.
+        k += 1.0;
+    }
+
+    // 6. Return undefined.
+    return create_value_completion(JSValue::Undefined);
+}
+
+// https://tc39.es/ecma262/#sec-array.prototype.join
+// TODO: Arrays that contain themselves recurse forever instead of joining the cycle as an empty string
+fn array_prototype_join(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let O be ? ToObject(this value).
+    let object = return_if_abrupt!(to_object(interpreter, &this_value));
+
+    // 2. Let len be ? LengthOfArrayLike(O).
+    let length = return_if_abrupt!(length_of_array_like(interpreter, &object));
+
+    // 3. If separator is undefined, let sep be ",".
+    // 4. Else, let sep be ? ToString(separator).
+    let separator = argument(&arguments, 0);
+    let separator = if matches!(*separator.borrow(), JSValue::Undefined) {
+        String::from(",")
+    } else {
+        return_if_abrupt!(to_string(interpreter, separator))
+    };
+
+    // 5. Let R be the empty String.
+    let mut result = String::new();
+
+    // 6. Let k be 0.
+    let mut k: f64 = 0.0;
+
+    // 7. Repeat, while k < len,
+    while k < length {
+        // b. Let element be ? Get(O, ! ToString(𝔽(k))).
+        let element = return_if_abrupt!(get(interpreter, &object, Interpreter::number_to_string(k)));
+
+        // c. If element is either undefined or null, let next be the empty String; otherwise, let next be ? ToString(element).
+        let is_undefined_or_null = matches!(*element.borrow(), JSValue::Undefined | JSValue::Null);
+        let next = if is_undefined_or_null { String::new() } else { return_if_abrupt!(to_string(interpreter, element)) };
+
+        // The length of an array can be far more than its elements, so R is counted against the maximum heap size as it grows
+        let separator_length = if k > 0.0 { separator.len() } else { 0 };
+        if let Some(range_error) = interpreter.reserve_heap(separator_length + next.len(), result.len()) {
+            return range_error;
+        }
+
+        // a. If k > 0, set R to the string-concatenation of R and sep.
+        if k > 0.0 {
+            result.push_str(&separator);
+        }
+
+        // d. Set R to the string-concatenation of R and next.
+        result.push_str(&next);
+
+        // e. Set k to k + 1.
+        k += 1.0;
+    }
+
+    // 8. Return R.
+    return create_value_completion(JSValue::String(result));
+}
+
+// https://tc39.es/ecma262/#sec-array.prototype.tostring
+fn array_prototype_to_string(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let array be ? ToObject(this value).
+    let array = return_if_abrupt!(to_object(interpreter, &this_value));
+
+    // 2. Let func be ? Get(array, "join").
+    let function = return_if_abrupt!(get(interpreter, &array, String::from("join")));
+
+    // 3. If IsCallable(func) is false, set func to the intrinsic function %Object.prototype.toString%.
+    // TODO: Object.prototype.toString does not exist yet, so its result is returned directly
+    if !Interpreter::is_callable(&function.borrow()) {
+        return create_value_completion(JSValue::String(String::from("[object Array]")));
+    }
+
+    // 4. Return ? Call(func, array).
+    return interpreter.call(function, Rc::new(RefCell::new(JSValue::Object(array))), Vec::new());
+}
+
+// https://tc39.es/ecma262/#sec-number.max_safe_integer
+const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+// https://tc39.es/ecma262/#sec-toobject
+// TODO: Primitives should be wrapped in their wrapper objects
+fn to_object(interpreter: &mut Interpreter, value: &Rc<RefCell<JSValue>>) -> Result<Rc<RefCell<JSObject>>, CompletionRecord> {
+    match &*value.borrow() {
+        JSValue::Object(object) => Ok(Rc::clone(object)),
+        JSValue::Undefined | JSValue::Null => Err(interpreter.throw_type_error(String::from("Array.prototype method called on null or undefined"))),
+        _ => Err(interpreter.throw_type_error(String::from("Array.prototype methods on primitives are not supported")))
+    }
+}
+
+// https://tc39.es/ecma262/#sec-get-o-p
+fn get(interpreter: &mut Interpreter, object: &Rc<RefCell<JSObject>>, key: String) -> Result<Rc<RefCell<JSValue>>, CompletionRecord> {
+    // 1. Return ? O.[[Get]](P, O).
+    let receiver = Rc::new(RefCell::new(JSValue::Object(Rc::clone(object))));
+    return completion_value(interpreter.get(object, &PropertyKey::String(key), receiver));
+}
+
+// https://tc39.es/ecma262/#sec-set-o-p-v-throw
+fn set(interpreter: &mut Interpreter, object: &Rc<RefCell<JSObject>>, key: String, value: Rc<RefCell<JSValue>>) -> Result<(), CompletionRecord> {
+    // 1. Let success be ? O.[[Set]](P, V, O).
+    let receiver = Rc::new(RefCell::new(JSValue::Object(Rc::clone(object))));
+    let success = completion_value(interpreter.set_property(object, PropertyKey::String(key.clone()), value, receiver))?;
+
+    // 2. If success is false and Throw is true, throw a TypeError exception.
+    if matches!(*success.borrow(), JSValue::Boolean(false)) {
+        return Err(interpreter.throw_type_error(format!("Cannot assign to read only property '{}'", key)));
+    }
+
+    // 3. Return unused.
+    return Ok(());
+}
+
+// https://tc39.es/ecma262/#sec-deletepropertyorthrow
+fn delete_property_or_throw(interpreter: &mut Interpreter, object: &Rc<RefCell<JSObject>>, key: String) -> Result<(), CompletionRecord> {
+    // 1. Let success be ? O.[[Delete]](P).
+    let success = object.borrow_mut().delete(&PropertyKey::String(key.clone()));
+
+    // 2. If success is false, throw a TypeError exception.
+    if !success {
+        return Err(interpreter.throw_type_error(format!("Cannot delete property '{}'", key)));
+    }
+
+    // 3. Return unused.
+    return Ok(());
+}
+
+// https://tc39.es/ecma262/#sec-hasproperty
+fn has_property(object: &Rc<RefCell<JSObject>>, key: String) -> bool {
+    // 1. Return ? O.[[HasProperty]](P).
+    let has_property = object.borrow().has_property(PropertyKey::String(key));
+    return matches!(&*has_property.value, ReferenceRecordOrJsValue::JSValue(value) if matches!(*value.borrow(), JSValue::Boolean(true)));
+}
+
+// https://tc39.es/ecma262/#sec-lengthofarraylike
+fn length_of_array_like(interpreter: &mut Interpreter, object: &Rc<RefCell<JSObject>>) -> Result<f64, CompletionRecord> {
+    // 1. Return ℝ(? ToLength(? Get(obj, "length"))).
+    let length = get(interpreter, object, String::from("length"))?;
+
+    // https://tc39.es/ecma262/#sec-tolength
+    // 1. Let len be ? ToIntegerOrInfinity(argument).
+    let length = to_integer_or_infinity(interpreter, &length)?;
+
+    // 2. If len ≤ 0, return +0𝔽.
+    // 3. Return 𝔽(min(len, 2^53 - 1)).
+    return Ok(length.clamp(0.0, MAX_SAFE_INTEGER));
+}
+
+// https://tc39.es/ecma262/#sec-tointegerorinfinity
+fn to_integer_or_infinity(interpreter: &mut Interpreter, value: &Rc<RefCell<JSValue>>) -> Result<f64, CompletionRecord> {
+    // FIXME: ToNumber is not implemented for symbols and objects yet
+    let is_primitive = !matches!(*value.borrow(), JSValue::Symbol(_) | JSValue::Object(_));
+    if !is_primitive {
+        return Err(interpreter.throw_type_error(String::from("Cannot convert value to a number")));
+    }
+
+    // 1. Let number be ? ToNumber(argument).
+    let number = match *completion_value(Interpreter::to_number(Rc::clone(value)))?.borrow() {
+        JSValue::Numeric(number) => number,
+        _ => unreachable!()
+    };
+
+    // 2. If number is one of NaN, +0𝔽, or -0𝔽, return 0.
+    if number.is_nan() || number == 0.0 {
+        return Ok(0.0);
+    }
+
+    // 3. If number is +∞𝔽, return +∞.
+    // 4. If number is -∞𝔽, return -∞.
+    // 5. Return truncate(ℝ(number)).
+    return Ok(number.trunc());
+}
+
+fn to_string(interpreter: &mut Interpreter, value: Rc<RefCell<JSValue>>) -> Result<String, CompletionRecord> {
+    match &*completion_value(interpreter.to_string_coercing_objects(value))?.borrow() {
+        JSValue::String(string) => Ok(string.clone()),
+        _ => unreachable!()
+    }
+}
+
+// Resolves a relative index the way slice and indexOf do, negative indices count back from the end
+fn relative_index(relative: f64, length: f64) -> f64 {
+    if relative < 0.0 {
+        return (length + relative).max(0.0);
+    }
+    return relative.min(length);
+}
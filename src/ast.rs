@@ -2,15 +2,106 @@
 // between the Parser and Interpreter.
 
 use std::rc::Rc;
-use crate::token::{Token, Literal};
+use crate::token::{Token, TokenType, Literal};
+use crate::atom::Atom;
 
+#[derive(Debug)]
+// https://tc39.es/ecma262/#prod-Script
+pub struct Script {
+    pub statements: Vec<Statement>,
+    // https://tc39.es/ecma262/#sec-strict-mode-code
+    // Whether the Directive Prologue of the script contains a Use Strict Directive
+    pub strict: bool,
+}
+
+#[derive(Debug)]
 // https://tc39.es/ecma262/#prod-Statement
 pub enum Statement {
     // TODO: Support a list of VariableDeclaration's as seen in the spec
     // Currently we only support one declaration on a single line
     VariableStatement(Box<VariableDeclarationStatement>),
+    // https://tc39.es/ecma262/#prod-LexicalDeclaration
+    LexicalDeclaration(Box<LexicalDeclaration>),
     ExpressionStatement(Box<ExpressionStatement>),
-    BlockStatement(Box<BlockStatement>)
+    BlockStatement(Box<BlockStatement>),
+    // https://tc39.es/ecma262/#prod-HoistableDeclaration
+    FunctionDeclaration(Box<FunctionDeclaration>),
+    ReturnStatement(Box<ReturnStatement>),
+    ThrowStatement(Box<ThrowStatement>),
+    TryStatement(Box<TryStatement>)
+}
+
+impl Statement {
+    // https://tc39.es/ecma262/#sec-static-semantics-vardeclarednames
+    // Function declarations are not included, they are collected by function_declarations
+    pub fn var_declared_names(statements: &[Statement]) -> Vec<Atom> {
+        let mut names = Vec::new();
+        for statement in statements {
+            match statement {
+                Statement::VariableStatement(declaration) => names.push(declaration.binding_identifier.lexeme.clone()),
+                Statement::BlockStatement(block) => names.append(&mut Statement::var_declared_names(&block.statements)),
+                Statement::TryStatement(try_statement) => {
+                    for block in try_statement.blocks() {
+                        names.append(&mut Statement::var_declared_names(&block.statements));
+                    }
+                },
+                // Declarations inside a function body belong to that function
+                Statement::LexicalDeclaration(_) | Statement::ExpressionStatement(_) | Statement::FunctionDeclaration(_) | Statement::ReturnStatement(_) | Statement::ThrowStatement(_) => {}
+            }
+        }
+        return names;
+    }
+
+    // https://tc39.es/ecma262/#sec-static-semantics-varscopeddeclarations
+    // TODO: Function declarations in blocks should be scoped to the block, they are hoisted to the function like var for now
+    pub fn function_declarations(statements: &[Statement]) -> Vec<&FunctionDeclaration> {
+        let mut declarations = Vec::new();
+        for statement in statements {
+            match statement {
+                Statement::FunctionDeclaration(declaration) => declarations.push(&**declaration),
+                Statement::BlockStatement(block) => declarations.append(&mut Statement::function_declarations(&block.statements)),
+                Statement::TryStatement(try_statement) => {
+                    for block in try_statement.blocks() {
+                        declarations.append(&mut Statement::function_declarations(&block.statements));
+                    }
+                },
+                Statement::VariableStatement(_) | Statement::LexicalDeclaration(_) | Statement::ExpressionStatement(_) | Statement::ReturnStatement(_) | Statement::ThrowStatement(_) => {}
+            }
+        }
+        return declarations;
+    }
+
+    // https://tc39.es/ecma262/#sec-static-semantics-lexicallyscopeddeclarations
+    // Only the declarations directly in the statement list, a nested block has its own scope
+    pub fn lexically_scoped_declarations(statements: &[Statement]) -> Vec<&LexicalDeclaration> {
+        let mut declarations = Vec::new();
+        for statement in statements {
+            if let Statement::LexicalDeclaration(declaration) = statement {
+                declarations.push(&**declaration);
+            }
+        }
+        return declarations;
+    }
+
+    // https://tc39.es/ecma262/#sec-block-static-semantics-early-errors
+    // It is a Syntax Error if the LexicallyDeclaredNames of StatementList contains any duplicate entries,
+    // or if any of them also occurs in the VarDeclaredNames of StatementList
+    // The binding identifier of the offending declaration is returned
+    pub fn redeclared_lexical_name(statements: &[Statement]) -> Option<&Token> {
+        let mut var_names = Statement::var_declared_names(statements);
+        var_names.extend(Statement::function_declarations(statements).iter().map(|declaration| declaration.binding_identifier.lexeme.clone()));
+
+        let mut lexical_names: Vec<&Atom> = Vec::new();
+        for declaration in Statement::lexically_scoped_declarations(statements) {
+            let name = &declaration.binding_identifier.lexeme;
+            if lexical_names.contains(&name) || var_names.contains(name) {
+                return Some(&declaration.binding_identifier);
+            }
+            lexical_names.push(name);
+        }
+
+        return None;
+    }
 }
 
 #[derive(Debug)]
@@ -23,10 +114,11 @@ pub struct PropertyDefinition {
 
 #[derive(Debug)]
 //https://tc39.es/ecma262/#prod-PropertyName
-// TODO: Support computed property names: https://tc39.es/ecma262/#prod-ComputedPropertyName
 pub enum PropertyName {
     IdentifierName(Token),
     LiteralPropertyName(Literal),
+    // https://tc39.es/ecma262/#prod-ComputedPropertyName
+    ComputedPropertyName(Box<ExpressionStatement>),
 }
 
 #[derive(Debug)]
@@ -35,11 +127,20 @@ pub struct ObjectLiteralExpression {
     pub property_definitions: Vec<PropertyDefinition>,
 }
 
+#[derive(Debug)]
+// https://tc39.es/ecma262/#prod-ArrayLiteral
+pub struct ArrayLiteralExpression {
+    // An Elision is represented as None
+    // TODO: SpreadElement is not supported
+    pub element_list: Vec<Option<ExpressionStatement>>,
+}
+
 // https://tc39.es/ecma262/#prod-VariableStatement
 pub struct VariableStatement {
     pub declarations: Vec<VariableDeclarationStatement>,
 }
 
+#[derive(Debug)]
 // https://tc39.es/ecma262/#prod-VariableDeclaration
 pub struct VariableDeclarationStatement {
     pub binding_identifier: Token,
@@ -47,6 +148,24 @@ pub struct VariableDeclarationStatement {
     pub initializer: Option<Box<AssignmentExpression>>
 }
 
+#[derive(Debug)]
+// https://tc39.es/ecma262/#prod-LexicalDeclaration
+// TODO: Support a BindingList, like VariableStatement only one binding is supported
+pub struct LexicalDeclaration {
+    // The let or const keyword
+    pub let_or_const: Token,
+    pub binding_identifier: Token,
+    // A const declaration always has an initializer, the parser reports one that is missing
+    pub initializer: Option<ExpressionStatement>,
+}
+
+impl LexicalDeclaration {
+    // https://tc39.es/ecma262/#sec-static-semantics-isconstantdeclaration
+    pub fn is_constant_declaration(&self) -> bool {
+        return self.let_or_const.token_type == TokenType::CONST;
+    }
+}
+
 #[derive(Debug)]
 // https://tc39.es/ecma262/#prod-AssignmentExpression
 pub struct AssignmentExpression {
@@ -59,34 +178,130 @@ pub struct AssignmentExpression {
     pub left_hand_side_expression: Rc<ExpressionStatement>
 }
 
+#[derive(Debug)]
 // https://tc39.es/ecma262/#prod-FunctionBody
 pub struct FunctionBody {
     // https://tc39.es/ecma262/#prod-FunctionStatementList
     // -> https://tc39.es/ecma262/#prod-StatementList
     //  -> https://tc39.es/ecma262/#prod-StatementListItem
     //   -> https://tc39.es/ecma262/#prod-Statement
-    statements: Vec<Statement>,
-
+    pub statements: Vec<Statement>,
+    // https://tc39.es/ecma262/#sec-strict-mode-code
+    // Whether the body is strict mode code, because of its own Use Strict Directive or the code it is nested in
+    pub strict: bool,
 }
 
+#[derive(Debug)]
 // https://tc39.es/ecma262/#prod-FormalParameter
 pub struct FormalParameter {
     // https://tc39.es/ecma262/#prod-BindingElement
     // -> https://tc39.es/ecma262/#prod-SingleNameBinding
     //  -> https://tc39.es/ecma262/#prod-BindingIdentifier
-    binding_identifier: Token,
+    // TODO: Initializers, rest parameters and binding patterns are not supported
+    pub binding_identifier: Token,
 
 }
+
+#[derive(Debug)]
 // https://tc39.es/ecma262/#prod-FormalParameters
 pub struct FormalParameters {
-    parameters: Vec<FormalParameter>,
+    pub parameters: Vec<FormalParameter>,
 }
 
+// The parameters and body are shared with the function objects created from them, which outlive the parsed script
+#[derive(Debug)]
 //https://tc39.es/ecma262/#prod-FunctionDeclaration
 pub struct FunctionDeclaration {
     pub binding_identifier: Token,
-    pub formal_parameters: FormalParameters,
-    pub function_body: FunctionBody,
+    pub formal_parameters: Rc<FormalParameters>,
+    pub function_body: Rc<FunctionBody>,
+}
+
+#[derive(Debug)]
+// https://tc39.es/ecma262/#prod-FunctionExpression
+pub struct FunctionExpression {
+    pub binding_identifier: Option<Token>,
+    pub formal_parameters: Rc<FormalParameters>,
+    pub function_body: Rc<FunctionBody>,
+}
+
+#[derive(Debug)]
+// https://tc39.es/ecma262/#prod-ArrowFunction
+pub struct ArrowFunction {
+    // https://tc39.es/ecma262/#prod-ArrowParameters
+    pub formal_parameters: Rc<FormalParameters>,
+    // https://tc39.es/ecma262/#prod-ConciseBody
+    // An ExpressionBody is parsed as a FunctionBody containing a single return statement
+    pub function_body: Rc<FunctionBody>,
+}
+
+#[derive(Debug)]
+// https://tc39.es/ecma262/#prod-TemplateLiteral
+// The cooked strings surround the substitutions, so there is always one more string than there are substitutions
+// TODO: Tagged templates are not supported
+pub struct TemplateLiteral {
+    pub strings: Vec<String>,
+    pub substitutions: Vec<ExpressionStatement>,
+}
+
+#[derive(Debug)]
+// https://tc39.es/ecma262/#prod-PrimaryExpression
+// PrimaryExpression : this
+pub struct ThisExpression;
+
+#[derive(Debug)]
+// https://tc39.es/ecma262/#prod-NewExpression
+// new MemberExpression Arguments, or new NewExpression when there are no arguments
+pub struct NewExpression {
+    // The new keyword, where the construction is reported in stack traces
+    pub keyword: Token,
+    pub callee: Box<ExpressionStatement>,
+    pub arguments: Vec<ExpressionStatement>,
+}
+
+#[derive(Debug)]
+// https://tc39.es/ecma262/#prod-ReturnStatement
+pub struct ReturnStatement {
+    pub expression: Option<ExpressionStatement>,
+}
+
+#[derive(Debug)]
+// https://tc39.es/ecma262/#prod-ThrowStatement
+pub struct ThrowStatement {
+    // The throw keyword, its line is recorded in the stack of errors
+    pub keyword: Token,
+    pub expression: ExpressionStatement,
+}
+
+#[derive(Debug)]
+// https://tc39.es/ecma262/#prod-TryStatement
+// try Block Catch, try Block Finally or try Block Catch Finally
+pub struct TryStatement {
+    pub block: BlockStatement,
+    pub catch: Option<Catch>,
+    pub finally: Option<BlockStatement>,
+}
+
+impl TryStatement {
+    // The try, catch and finally blocks in source order
+    pub fn blocks(&self) -> Vec<&BlockStatement> {
+        let mut blocks = vec![&self.block];
+        if let Some(catch) = &self.catch {
+            blocks.push(&catch.block);
+        }
+        if let Some(finally) = &self.finally {
+            blocks.push(finally);
+        }
+        return blocks;
+    }
+}
+
+#[derive(Debug)]
+// https://tc39.es/ecma262/#prod-Catch
+// TODO: Binding patterns are not supported as the catch parameter
+pub struct Catch {
+    pub parameter: Option<Token>,
+    pub block: BlockStatement,
 }
 
 #[derive(Debug)]
@@ -97,7 +312,22 @@ pub struct CallExpression {
     pub(crate) arguments: Vec<ExpressionStatement>,
 }
 
+impl CallExpression {
+    // Where the call is reported in stack traces, the name of the function being called when it has one, e.g. the b of a.b()
+    pub fn position(&self) -> &Token {
+        return match &*self.callee {
+            ExpressionStatement::IdentifierExpression(identifier) => &identifier.binding_identifier,
+            ExpressionStatement::MemberExpression(member) => match &member.property {
+                MemberProperty::IdentifierName(name) => name,
+                MemberProperty::Expression(_) => &self.paren
+            },
+            _ => &self.paren
+        };
+    }
+}
+
 
+#[derive(Debug)]
 // https://tc39.es/ecma262/#prod-BlockStatement
 // BlockStatement[Yield, Await, Return] :
 //  Block[?Yield, ?Await, ?Return]
@@ -125,7 +355,41 @@ pub enum ExpressionStatement {
     IdentifierExpression(Box<IdentifierExpression>),
     CallExpression(Box<CallExpression>),
     ObjectLiteralExpression(Box<ObjectLiteralExpression>),
-    AssignmentExpression(Box<AssignmentExpression>)
+    ArrayLiteralExpression(Box<ArrayLiteralExpression>),
+    FunctionExpression(Box<FunctionExpression>),
+    ArrowFunction(Box<ArrowFunction>),
+    ThisExpression(Box<ThisExpression>),
+    NewExpression(Box<NewExpression>),
+    TemplateLiteral(Box<TemplateLiteral>),
+    AssignmentExpression(Box<AssignmentExpression>),
+    MemberExpression(Box<MemberExpression>)
+}
+
+impl ExpressionStatement {
+    // https://tc39.es/ecma262/#sec-isanonymousfunctiondefinition
+    pub fn is_anonymous_function_definition(&self) -> bool {
+        match self {
+            ExpressionStatement::FunctionExpression(function_expression) => function_expression.binding_identifier.is_none(),
+            ExpressionStatement::ArrowFunction(_) => true,
+            ExpressionStatement::ParenthesizedExpression(parenthesized_expression) => parenthesized_expression.expression.is_anonymous_function_definition(),
+            _ => false
+        }
+    }
+}
+
+#[derive(Debug)]
+// https://tc39.es/ecma262/#prod-MemberExpression
+pub struct MemberExpression {
+    pub object: Box<ExpressionStatement>,
+    pub property: MemberProperty,
+}
+
+#[derive(Debug)]
+pub enum MemberProperty {
+    // MemberExpression . IdentifierName
+    IdentifierName(Token),
+    // MemberExpression [ Expression ]
+    Expression(Box<ExpressionStatement>),
 }
 
 #[derive(Debug)]
@@ -167,11 +431,23 @@ pub trait AstVisitor<R> {
     fn visit_parenthesized(&mut self, expression: &ParenthesizedExpression) -> R;
     fn visit_unary(&mut self, expression: &UnaryExpression) -> R;
     fn visit_variable_declaration(&mut self, expression: &VariableDeclarationStatement) -> R;
+    fn visit_lexical_declaration(&mut self, declaration: &LexicalDeclaration) -> R;
     fn visit_identifier_expression(&mut self, expression: &IdentifierExpression) -> R;
     fn visit_call_expression(&mut self, expression: &CallExpression) -> R;
     fn visit_block_statement(&mut self, expression: &BlockStatement) -> R;
     fn visit_object_literal_expression(&mut self, expression: &ObjectLiteralExpression) -> R;
+    fn visit_array_literal_expression(&mut self, expression: &ArrayLiteralExpression) -> R;
+    fn visit_function_declaration(&mut self, declaration: &FunctionDeclaration) -> R;
+    fn visit_function_expression(&mut self, expression: &FunctionExpression) -> R;
+    fn visit_arrow_function(&mut self, expression: &ArrowFunction) -> R;
+    fn visit_this_expression(&mut self, expression: &ThisExpression) -> R;
+    fn visit_new_expression(&mut self, expression: &NewExpression) -> R;
+    fn visit_template_literal(&mut self, expression: &TemplateLiteral) -> R;
+    fn visit_return_statement(&mut self, statement: &ReturnStatement) -> R;
+    fn visit_throw_statement(&mut self, statement: &ThrowStatement) -> R;
+    fn visit_try_statement(&mut self, statement: &TryStatement) -> R;
     fn visit_assignment_expression(&mut self, expression: &AssignmentExpression) -> R;
+    fn visit_member_expression(&mut self, expression: &MemberExpression) -> R;
 }
 
 impl<R> Accept<R> for Statement {
@@ -179,7 +455,12 @@ impl<R> Accept<R> for Statement {
         match self {
             Statement::ExpressionStatement(e) => { visitor.visit_expression_statement(e) }
             Statement::VariableStatement(v) => { visitor.visit_variable_declaration(v) }
+            Statement::LexicalDeclaration(l) => { visitor.visit_lexical_declaration(l) }
             Statement::BlockStatement(b) => { visitor.visit_block_statement(b) }
+            Statement::FunctionDeclaration(f) => { visitor.visit_function_declaration(f) }
+            Statement::ReturnStatement(r) => { visitor.visit_return_statement(r) }
+            Statement::ThrowStatement(t) => { visitor.visit_throw_statement(t) }
+            Statement::TryStatement(t) => { visitor.visit_try_statement(t) }
         }
     }
 }
@@ -194,7 +475,14 @@ impl<R> Accept<R> for ExpressionStatement {
             ExpressionStatement::IdentifierExpression(v) => visitor.visit_identifier_expression(v),
             ExpressionStatement::CallExpression(c) => visitor.visit_call_expression(c),
             ExpressionStatement::ObjectLiteralExpression(o) => visitor.visit_object_literal_expression(o),
+            ExpressionStatement::ArrayLiteralExpression(a) => visitor.visit_array_literal_expression(a),
+            ExpressionStatement::FunctionExpression(f) => visitor.visit_function_expression(f),
+            ExpressionStatement::ArrowFunction(a) => visitor.visit_arrow_function(a),
+            ExpressionStatement::ThisExpression(t) => visitor.visit_this_expression(t),
+            ExpressionStatement::NewExpression(n) => visitor.visit_new_expression(n),
+            ExpressionStatement::TemplateLiteral(t) => visitor.visit_template_literal(t),
             ExpressionStatement::AssignmentExpression(a) => visitor.visit_assignment_expression(a),
+            ExpressionStatement::MemberExpression(m) => visitor.visit_member_expression(m),
             _=> unimplemented!()
         }
     }
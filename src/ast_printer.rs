@@ -1,10 +1,47 @@
 // This file contains the ASTPrettyPrinter implementation that was extracted from interpreter.rs
 
-use crate::ast::{AstVisitor, ExpressionStatement, BinaryExpression, LiteralExpression, ParenthesizedExpression, UnaryExpression, IdentifierExpression, VariableDeclarationStatement, Accept, CallExpression, BlockStatement, Statement, ObjectLiteralExpression, AssignmentExpression};
+use crate::ast::{AstVisitor, ExpressionStatement, BinaryExpression, LiteralExpression, ParenthesizedExpression, UnaryExpression, IdentifierExpression, VariableDeclarationStatement, Accept, CallExpression, BlockStatement, Statement, ObjectLiteralExpression, ArrayLiteralExpression, AssignmentExpression, MemberExpression, MemberProperty, FunctionDeclaration, FunctionExpression, ArrowFunction, ReturnStatement, FormalParameters, ThisExpression, NewExpression, ThrowStatement, TryStatement, TemplateLiteral, LexicalDeclaration};
 use crate::token::Literal;
 
 pub struct ASTPrettyPrinter;
 
+// An expression written back as JavaScript, as error messages show it, e.g. document.createTextNode
+// The parser does not keep the source text of expressions, so it is rebuilt and can differ from the source in its spacing and parentheses
+// The bodies of functions and the contents of object and array literals are left out
+pub fn source_text(expression: &ExpressionStatement) -> String {
+    let list = |expressions: &[ExpressionStatement]| expressions.iter().map(source_text).collect::<Vec<String>>().join(", ");
+    return match expression {
+        ExpressionStatement::BinaryExpression(expression) => format!("{} {} {}", source_text(&expression.left), expression.operator.lexeme, source_text(&expression.right)),
+        ExpressionStatement::LiteralExpression(expression) => match &expression.value {
+            Literal::Numeric(number) => number.to_string(),
+            Literal::String(string) => format!("{:?}", string),
+            Literal::Boolean(boolean) => boolean.to_string(),
+            Literal::Null() => String::from("null"),
+            Literal::RegExp { pattern, flags } => format!("/{}/{}", pattern, flags)
+        },
+        ExpressionStatement::ParenthesizedExpression(expression) => format!("({})", source_text(&expression.expression)),
+        ExpressionStatement::UnaryExpression(expression) if expression.operator.lexeme.chars().all(char::is_alphabetic) => format!("{} {}", expression.operator.lexeme, source_text(&expression.right)),
+        ExpressionStatement::UnaryExpression(expression) => format!("{}{}", expression.operator.lexeme, source_text(&expression.right)),
+        ExpressionStatement::IdentifierExpression(expression) => expression.binding_identifier.lexeme.to_string(),
+        ExpressionStatement::CallExpression(expression) => format!("{}({})", source_text(&expression.callee), list(&expression.arguments)),
+        ExpressionStatement::ObjectLiteralExpression(_) => String::from("{...}"),
+        ExpressionStatement::ArrayLiteralExpression(_) => String::from("[...]"),
+        ExpressionStatement::FunctionExpression(expression) => match &expression.binding_identifier {
+            Some(binding_identifier) => format!("function {}", binding_identifier.lexeme),
+            None => String::from("function")
+        },
+        ExpressionStatement::ArrowFunction(_) => String::from("(...) => {...}"),
+        ExpressionStatement::ThisExpression(_) => String::from("this"),
+        ExpressionStatement::NewExpression(expression) => format!("new {}({})", source_text(&expression.callee), list(&expression.arguments)),
+        ExpressionStatement::TemplateLiteral(_) => String::from("`...`"),
+        ExpressionStatement::AssignmentExpression(expression) => format!("{} = {}", source_text(&expression.left_hand_side_expression), source_text(&expression.expression)),
+        ExpressionStatement::MemberExpression(expression) => match &expression.property {
+            MemberProperty::IdentifierName(name) => format!("{}.{}", source_text(&expression.object), name.lexeme),
+            MemberProperty::Expression(property) => format!("{}[{}]", source_text(&expression.object), source_text(property))
+        }
+    };
+}
+
 impl ASTPrettyPrinter {
     fn parenthesize(&mut self, name: String, exprs: Vec<&ExpressionStatement>) -> String {
         let mut builder = String::new();
@@ -37,6 +74,13 @@ impl ASTPrettyPrinter {
 
         builder
     }
+
+    fn format_parameters(&mut self, formal_parameters: &FormalParameters) -> String {
+        let parameters: Vec<String> = formal_parameters.parameters.iter()
+            .map(|parameter| parameter.binding_identifier.lexeme.to_string())
+            .collect();
+        return parameters.join(", ");
+    }
 }
 
 impl AstVisitor<String> for ASTPrettyPrinter {
@@ -56,7 +100,8 @@ impl AstVisitor<String> for ASTPrettyPrinter {
             Literal::Numeric(n) => format!("NumericLiteral {}", n.to_string()),
             Literal::String(s) => format!("StringLiteral {}", s),
             &Literal::Boolean(b) => format!("BooleanLiteral {}", b),
-            &Literal::Null() => "NullLiteral null".to_string()
+            &Literal::Null() => "NullLiteral null".to_string(),
+            Literal::RegExp { pattern, flags } => format!("RegularExpressionLiteral /{}/{}", pattern, flags)
         }
     }
 
@@ -108,11 +153,63 @@ impl AstVisitor<String> for ASTPrettyPrinter {
                     vec![]
                 )
             },
+            ExpressionStatement::ArrayLiteralExpression(_) => {
+                return self.parenthesize(
+                    format!("ArrayLiteralExpression"),
+                    vec![]
+                )
+            },
+            ExpressionStatement::FunctionExpression(_) => {
+                return self.parenthesize(
+                    format!("FunctionExpression"),
+                    vec![]
+                )
+            },
+            ExpressionStatement::ArrowFunction(_) => {
+                return self.parenthesize(
+                    format!("ArrowFunction"),
+                    vec![]
+                )
+            },
+            ExpressionStatement::ThisExpression(_) => {
+                return self.parenthesize(
+                    format!("ThisExpression"),
+                    vec![]
+                )
+            },
+            ExpressionStatement::NewExpression(node) => {
+                return self.parenthesize(
+                    format!("NewExpression"),
+                    vec![&*node.callee]
+                )
+            },
+            ExpressionStatement::TemplateLiteral(node) => {
+                return self.parenthesize(
+                    format!("TemplateLiteral {:?}", node.strings),
+                    vec![]
+                )
+            },
             ExpressionStatement::AssignmentExpression(node) => {
                 return self.parenthesize(
                     format!("AssignmentExpression"),
                     vec![&*node.left_hand_side_expression, &*node.expression]
                 )
+            },
+            ExpressionStatement::MemberExpression(node) => {
+                match &node.property {
+                    MemberProperty::IdentifierName(property_name) => {
+                        return self.parenthesize(
+                            format!("ParenthesizedExpression MemberExpression {:?}", property_name.lexeme),
+                            vec![&*node.object]
+                        )
+                    },
+                    MemberProperty::Expression(property_expression) => {
+                        return self.parenthesize(
+                            format!("ParenthesizedExpression MemberExpression"),
+                            vec![&*node.object, &**property_expression]
+                        )
+                    }
+                }
             }
         }
     }
@@ -157,6 +254,14 @@ impl AstVisitor<String> for ASTPrettyPrinter {
         }
     }
 
+    fn visit_lexical_declaration(&mut self, declaration: &LexicalDeclaration) -> String {
+        let name = format!("LexicalDeclaration {} {:?}", declaration.let_or_const.lexeme, declaration.binding_identifier.lexeme);
+        match &declaration.initializer {
+            Some(initializer) => self.parenthesize(name, vec![initializer]),
+            None => self.parenthesize(name, vec![])
+        }
+    }
+
     fn visit_object_literal_expression(&mut self, expression: &ObjectLiteralExpression) -> String {
         let mut assignment_expressions: Vec<String> = Vec::new();
         for property_definition in &expression.property_definitions {
@@ -168,6 +273,20 @@ impl AstVisitor<String> for ASTPrettyPrinter {
         )
     }
 
+    fn visit_array_literal_expression(&mut self, expression: &ArrayLiteralExpression) -> String {
+        let mut elements: Vec<String> = Vec::new();
+        for element in &expression.element_list {
+            match element {
+                Some(element) => elements.push(element.accept(self)),
+                None => elements.push("Elision".to_string())
+            }
+        }
+        return self.parenthesize(
+            format!("ArrayLiteralExpression ElementList {}", elements.join(", ")),
+            vec![]
+        )
+    }
+
     fn visit_call_expression(&mut self, expression: &CallExpression) -> String {
         let mut args_to_string: String = String::new();
         args_to_string.push_str("(");
@@ -182,10 +301,125 @@ impl AstVisitor<String> for ASTPrettyPrinter {
         )
     }
 
+    fn visit_member_expression(&mut self, expression: &MemberExpression) -> String {
+        match &expression.property {
+            MemberProperty::IdentifierName(property_name) => {
+                return self.parenthesize(
+                    format!("MemberExpression {:?}", property_name.lexeme),
+                    vec![&*expression.object]
+                )
+            },
+            MemberProperty::Expression(property_expression) => {
+                return self.parenthesize(
+                    format!("MemberExpression"),
+                    vec![&*expression.object, &**property_expression]
+                )
+            }
+        }
+    }
+
     fn visit_block_statement(&mut self, expression: &BlockStatement) -> String {
         self.parenthesize_statement(
             format!("BlockStatement"),
             &*expression.statements
         )
     }
+
+    fn visit_function_declaration(&mut self, declaration: &FunctionDeclaration) -> String {
+        let parameters = self.format_parameters(&declaration.formal_parameters);
+        return self.parenthesize_statement(
+            format!("FunctionDeclaration {:?} FormalParameters ({})", declaration.binding_identifier.lexeme, parameters),
+            &declaration.function_body.statements
+        )
+    }
+
+    fn visit_function_expression(&mut self, expression: &FunctionExpression) -> String {
+        let name = match &expression.binding_identifier {
+            Some(binding_identifier) => binding_identifier.lexeme.to_string(),
+            None => String::new()
+        };
+        let parameters = self.format_parameters(&expression.formal_parameters);
+        return self.parenthesize_statement(
+            format!("FunctionExpression {:?} FormalParameters ({})", name, parameters),
+            &expression.function_body.statements
+        )
+    }
+
+    fn visit_arrow_function(&mut self, expression: &ArrowFunction) -> String {
+        let parameters = self.format_parameters(&expression.formal_parameters);
+        return self.parenthesize_statement(
+            format!("ArrowFunction FormalParameters ({})", parameters),
+            &expression.function_body.statements
+        )
+    }
+
+    fn visit_this_expression(&mut self, _expression: &ThisExpression) -> String {
+        return self.parenthesize(
+            format!("ThisExpression"),
+            vec![]
+        )
+    }
+
+    fn visit_new_expression(&mut self, expression: &NewExpression) -> String {
+        let mut exprs = vec![&*expression.callee];
+        exprs.extend(expression.arguments.iter());
+        return self.parenthesize(
+            format!("NewExpression"),
+            exprs
+        )
+    }
+
+    fn visit_template_literal(&mut self, expression: &TemplateLiteral) -> String {
+        return self.parenthesize(
+            format!("TemplateLiteral {:?}", expression.strings),
+            expression.substitutions.iter().collect()
+        )
+    }
+
+    fn visit_return_statement(&mut self, statement: &ReturnStatement) -> String {
+        match &statement.expression {
+            Some(expression) => {
+                return self.parenthesize(
+                    format!("ReturnStatement"),
+                    vec![expression]
+                )
+            },
+            None => {
+                return self.parenthesize(
+                    format!("ReturnStatement"),
+                    vec![]
+                )
+            }
+        }
+    }
+
+    fn visit_throw_statement(&mut self, statement: &ThrowStatement) -> String {
+        return self.parenthesize(
+            format!("ThrowStatement"),
+            vec![&statement.expression]
+        )
+    }
+
+    fn visit_try_statement(&mut self, statement: &TryStatement) -> String {
+        let mut builder = String::from("(TryStatement ");
+        builder.push_str(&self.visit_block_statement(&statement.block));
+
+        if let Some(catch) = &statement.catch {
+            builder.push_str(" (Catch ");
+            if let Some(parameter) = &catch.parameter {
+                builder.push_str(&format!("{:?} ", parameter.lexeme));
+            }
+            builder.push_str(&self.visit_block_statement(&catch.block));
+            builder.push(')');
+        }
+
+        if let Some(finally) = &statement.finally {
+            builder.push_str(" (Finally ");
+            builder.push_str(&self.visit_block_statement(finally));
+            builder.push(')');
+        }
+
+        builder.push(')');
+        return builder;
+    }
 }
@@ -0,0 +1,176 @@
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::rc::Rc;
+
+// An interned string, for the names that are repeated throughout a document or script: tag names, attribute names and JavaScript identifiers
+// Every atom with the same text shares one allocation, so comparing two atoms compares pointers, and a name used a thousand times is stored once
+// The atom table only holds an atom for as long as some Atom refers to it, an atom is removed from the table when its last Atom is dropped
+// Comparing an atom with a str still compares the text, so names that are compared with literals do not need to be interned first
+#[derive(Clone)]
+pub struct Atom(Rc<str>);
+
+thread_local! {
+    // The table is per thread, like the documents and interpreters that use it
+    static ATOM_TABLE: RefCell<HashSet<Rc<str>>> = RefCell::new(HashSet::new());
+}
+
+impl Atom {
+    pub fn new(text: &str) -> Atom {
+        return ATOM_TABLE.with(|table| {
+            let mut table = table.borrow_mut();
+            if let Some(atom) = table.get(text) {
+                return Atom(Rc::clone(atom));
+            }
+            let atom: Rc<str> = Rc::from(text);
+            table.insert(Rc::clone(&atom));
+            return Atom(atom);
+        });
+    }
+
+    pub fn as_str(&self) -> &str {
+        return &self.0;
+    }
+
+    // The number of atoms in the table of this thread
+    pub fn table_len() -> usize {
+        return ATOM_TABLE.with(|table| table.borrow().len());
+    }
+}
+
+impl Drop for Atom {
+    fn drop(&mut self) {
+        // The table holds the other reference, so this is the last Atom for the text
+        if Rc::strong_count(&self.0) == 2 {
+            // The table may already be gone when the thread is exiting
+            let _ = ATOM_TABLE.try_with(|table| {
+                if let Ok(mut table) = table.try_borrow_mut() {
+                    table.remove(&*self.0);
+                }
+            });
+        }
+    }
+}
+
+impl Default for Atom {
+    fn default() -> Atom {
+        return Atom::new("");
+    }
+}
+
+impl Deref for Atom {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        return &self.0;
+    }
+}
+
+impl AsRef<str> for Atom {
+    fn as_ref(&self) -> &str {
+        return &self.0;
+    }
+}
+
+// The hash is the hash of the text, so a map keyed by atoms can be looked up with a str
+impl Borrow<str> for Atom {
+    fn borrow(&self) -> &str {
+        return &self.0;
+    }
+}
+
+impl PartialEq for Atom {
+    fn eq(&self, other: &Atom) -> bool {
+        return Rc::ptr_eq(&self.0, &other.0);
+    }
+}
+
+impl Eq for Atom {}
+
+impl PartialEq<str> for Atom {
+    fn eq(&self, other: &str) -> bool {
+        return &*self.0 == other;
+    }
+}
+
+impl PartialEq<&str> for Atom {
+    fn eq(&self, other: &&str) -> bool {
+        return &*self.0 == *other;
+    }
+}
+
+impl PartialEq<String> for Atom {
+    fn eq(&self, other: &String) -> bool {
+        return *self.0 == **other;
+    }
+}
+
+impl PartialEq<Atom> for &str {
+    fn eq(&self, other: &Atom) -> bool {
+        return **self == *other.0;
+    }
+}
+
+impl PartialEq<Atom> for String {
+    fn eq(&self, other: &Atom) -> bool {
+        return **self == *other.0;
+    }
+}
+
+impl Hash for Atom {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl PartialOrd for Atom {
+    fn partial_cmp(&self, other: &Atom) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Ord for Atom {
+    fn cmp(&self, other: &Atom) -> Ordering {
+        return self.0.cmp(&other.0);
+    }
+}
+
+impl From<&str> for Atom {
+    fn from(text: &str) -> Atom {
+        return Atom::new(text);
+    }
+}
+
+impl From<String> for Atom {
+    fn from(text: String) -> Atom {
+        return Atom::new(&text);
+    }
+}
+
+impl From<&String> for Atom {
+    fn from(text: &String) -> Atom {
+        return Atom::new(text);
+    }
+}
+
+impl From<Atom> for String {
+    fn from(atom: Atom) -> String {
+        return atom.0.to_string();
+    }
+}
+
+impl fmt::Display for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return f.write_str(&self.0);
+    }
+}
+
+impl fmt::Debug for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return fmt::Debug::fmt(&*self.0, f);
+    }
+}
@@ -0,0 +1,161 @@
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::document_stats::DocumentStats;
+use crate::html_document_parser::ParserOptions;
+use crate::source_location::SourcePosition;
+use crate::tokenizer::Tokenizer;
+use crate::tree_limits::TreeLimits;
+
+// Parses many HTML files side by side, each on one of a number of threads
+// A document and its parser are not Send, so each file is read, parsed and dropped on the thread that takes it and only its report crosses threads
+// Scripting is disabled, a batch parse is of the markup of the files and not of what their scripts do
+// A file that goes over one of the tree limits is a failure, with the error of the limit it went over
+
+// What parsing one file found
+#[derive(Debug, Clone)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub bytes: usize,
+    pub parse_time: Duration,
+    // The parse errors of the file in the order they were found
+    pub parse_errors: Vec<String>,
+    // Where each parse error was found, None for one that has no position in the input
+    pub parse_error_positions: Vec<Option<SourcePosition>>,
+    // The node counts and estimated heap usage of the document when they were asked for, counted after the parse so they are not part of the parse time
+    pub stats: Option<DocumentStats>,
+    // Why the file could not be parsed, e.g. it could not be read or the parser panicked
+    pub failure: Option<String>,
+}
+
+// The reports of a batch, in the order the files were given, and how long the whole batch took
+#[derive(Debug, Clone)]
+pub struct BatchReport {
+    pub files: Vec<FileReport>,
+    pub wall_time: Duration,
+}
+
+impl BatchReport {
+    // The time the files took to parse added up, which is more than the wall time when they were parsed side by side
+    pub fn total_parse_time(&self) -> Duration {
+        return self.files.iter().map(|file| file.parse_time).sum();
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        return self.files.iter().map(|file| file.bytes).sum();
+    }
+
+    pub fn total_parse_errors(&self) -> usize {
+        return self.files.iter().map(|file| file.parse_errors.len()).sum();
+    }
+
+    pub fn total_stats(&self) -> DocumentStats {
+        let mut stats = DocumentStats::default();
+        for file_stats in self.files.iter().filter_map(|file| file.stats.as_ref()) {
+            stats.add(file_stats);
+        }
+        return stats;
+    }
+
+    pub fn failures(&self) -> usize {
+        return self.files.iter().filter(|file| file.failure.is_some()).count();
+    }
+}
+
+// The .html and .htm files under path in the order of their paths, or path itself when it is a file
+pub fn html_files(path: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    if path.is_dir() {
+        collect_html_files(path, &mut files)?;
+        files.sort();
+    } else if path.is_file() {
+        files.push(path.to_path_buf());
+    } else {
+        return Err(format!("{} is not a file or a directory", path.display()));
+    }
+    return Ok(files);
+}
+
+fn collect_html_files(directory: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(directory).map_err(|error| format!("{}: {}", directory.display(), error))?;
+    for entry in entries {
+        let path = entry.map_err(|error| format!("{}: {}", directory.display(), error))?.path();
+        if path.is_dir() {
+            collect_html_files(&path, files)?;
+        } else if matches!(path.extension().and_then(|extension| extension.to_str()), Some("html") | Some("htm")) {
+            files.push(path);
+        }
+    }
+    return Ok(());
+}
+
+// Parses files with the given number of threads, which take the next file that has not been taken until there are none left
+// The stats of the documents are only counted when stats is set
+pub fn parse_files(files: &[PathBuf], jobs: usize, tree_limits: TreeLimits, stats: bool) -> BatchReport {
+    let start = Instant::now();
+    let next_file = AtomicUsize::new(0);
+    let reports: Mutex<Vec<Option<FileReport>>> = Mutex::new(vec![None; files.len()]);
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, files.len().max(1)) {
+            scope.spawn(|| {
+                loop {
+                    let index = next_file.fetch_add(1, Ordering::SeqCst);
+                    let Some(path) = files.get(index) else { break };
+                    let report = parse_file(path, tree_limits, stats);
+                    reports.lock().unwrap()[index] = Some(report);
+                }
+            });
+        }
+    });
+
+    let files = reports.into_inner().unwrap().into_iter().flatten().collect();
+    return BatchReport { files, wall_time: start.elapsed() };
+}
+
+// Reads and parses the file at path, a panic in the parser is the failure of this file rather than of the batch
+pub fn parse_file(path: &Path, tree_limits: TreeLimits, stats: bool) -> FileReport {
+    return match fs::read(path) {
+        Ok(bytes) => parse_bytes(path, bytes, tree_limits, stats),
+        Err(error) => FileReport { path: path.to_path_buf(), bytes: 0, parse_time: Duration::ZERO, parse_errors: Vec::new(), parse_error_positions: Vec::new(), stats: None, failure: Some(error.to_string()) }
+    };
+}
+
+// Parses HTML that was read from somewhere other than a file, e.g. stdin, and reports it as the file at path
+pub fn parse_bytes(path: &Path, bytes: Vec<u8>, tree_limits: TreeLimits, stats: bool) -> FileReport {
+    let mut report = FileReport { path: path.to_path_buf(), bytes: 0, parse_time: Duration::ZERO, parse_errors: Vec::new(), parse_error_positions: Vec::new(), stats: None, failure: None };
+    let html = String::from_utf8_lossy(&bytes).to_string();
+    report.bytes = html.len();
+
+    let start = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let options = ParserOptions { scripting_enabled: false, print_parse_errors: false, tree_limits, ..ParserOptions::default() };
+        let mut tokenizer = Tokenizer::with_options(html, options);
+        tokenizer.run();
+        let parse_time = start.elapsed();
+        (tokenizer.parse_errors().to_vec(), tokenizer.parse_error_positions().to_vec(), stats.then(|| tokenizer.document_stats()), tokenizer.tree_limit_error().map(|error| error.to_string()), parse_time)
+    }));
+    report.parse_time = start.elapsed();
+
+    match result {
+        Ok((parse_errors, parse_error_positions, stats, tree_limit_error, parse_time)) => {
+            report.parse_errors = parse_errors;
+            report.parse_error_positions = parse_error_positions;
+            report.stats = stats;
+            report.failure = tree_limit_error;
+            report.parse_time = parse_time;
+        },
+        Err(payload) => {
+            let message = payload.downcast_ref::<&str>().map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "the parser panicked".to_string());
+            report.failure = Some(format!("panicked: {}", message));
+        }
+    }
+    return report;
+}
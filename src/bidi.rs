@@ -0,0 +1,52 @@
+use std::ops::Range;
+use unicode_bidi::{BidiInfo, Level};
+use crate::language::Direction;
+
+// https://www.unicode.org/reports/tr9/
+// https://drafts.csswg.org/css-writing-modes-4/#text-direction
+// The Unicode Bidirectional Algorithm, which puts the characters of a line of mixed left-to-right and right-to-left text in the order they are displayed, e.g.
+// "abc אבג" in a left-to-right paragraph is displayed as "abc גבא"
+// The paragraph level is the directionality of the block the text is in (see language::directionality) rather than the direction of its first strong character
+// TODO: The levels are resolved for each line rather than for the whole paragraph before it is broken into lines, the unicode-bidi property and the dir of
+// inline elements do not embed or isolate their text, and mirrored characters (e.g. parentheses) are not mirrored
+
+fn paragraph_level(direction: Direction) -> Level {
+    match direction {
+        Direction::Ltr => Level::ltr(),
+        Direction::Rtl => Level::rtl()
+    }
+}
+
+// Whether a line of text in a paragraph of direction is displayed in another order than it is written, a line of only left-to-right text in a left-to-right paragraph is not
+pub fn needs_reordering(line: &str, direction: Direction) -> bool {
+    return direction == Direction::Rtl || BidiInfo::new(line, Some(Level::ltr())).has_rtl();
+}
+
+// https://www.unicode.org/reports/tr9/#Reordering_Resolved_Levels
+// The characters of one line in the order they are displayed from left to right
+pub fn reorder_line(line: &str, direction: Direction) -> String {
+    if !needs_reordering(line, direction) {
+        return line.to_string();
+    }
+    let bidi_info = BidiInfo::new(line, Some(paragraph_level(direction)));
+    let mut visual = String::new();
+    for paragraph in &bidi_info.paragraphs {
+        visual.push_str(&bidi_info.reorder_line(paragraph, paragraph.range.clone()));
+    }
+    return visual;
+}
+
+// The runs of one line at the same level in the order they are displayed from left to right, each the byte range of the line it covers and whether it is
+// right-to-left, in which case its characters are displayed in reverse
+pub fn visual_runs(line: &str, direction: Direction) -> Vec<(Range<usize>, bool)> {
+    let bidi_info = BidiInfo::new(line, Some(paragraph_level(direction)));
+    let mut runs = Vec::new();
+    for paragraph in &bidi_info.paragraphs {
+        let (levels, level_runs) = bidi_info.visual_runs(paragraph, paragraph.range.clone());
+        for run in level_runs {
+            let rtl = levels[run.start].is_rtl();
+            runs.push((run, rtl));
+        }
+    }
+    return runs;
+}
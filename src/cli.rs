@@ -0,0 +1,228 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::process::exit;
+use std::str::FromStr;
+
+// The command line of web_engine, a subcommand followed by its options and paths, e.g. web_engine dump page.html --format json --output page.json
+// Options are given as --name value or --name=value and flags as --name, anything else is a path
+// The input is read from stdin when no path is given or the path is -, so documents can be piped through, e.g. curl -s https://example.com/ | web_engine dump --format json > page.json
+// Each subcommand lists the options it takes, an unknown option or one that is missing its value is an error that is printed with the usage of the subcommand
+
+// An option a subcommand takes
+pub struct OptionSpec {
+    pub name: &'static str,
+    pub short: Option<char>,
+    // The name of the option's value in the usage, e.g. FILE, None for a flag
+    pub value: Option<&'static str>,
+    pub help: &'static str,
+}
+
+pub struct Command {
+    pub name: &'static str,
+    // What follows the name in the usage, e.g. [PATH]
+    pub paths: &'static str,
+    pub about: &'static str,
+    // The values --format takes, the first one is the default, empty for a subcommand without --format
+    pub formats: &'static [&'static str],
+    pub options: &'static [&'static [OptionSpec]],
+}
+
+// Every subcommand takes --output
+pub const OUTPUT_OPTION: OptionSpec = OptionSpec { name: "output", short: Some('o'), value: Some("FILE"), help: "Writes to FILE instead of stdout" };
+
+// The options every subcommand that prints a document or a report takes
+pub const OUTPUT_OPTIONS: &[OptionSpec] = &[
+    OptionSpec { name: "format", short: Some('f'), value: Some("FORMAT"), help: "What to print" },
+    OUTPUT_OPTION,
+    OptionSpec { name: "quiet", short: Some('q'), value: None, help: "Prints nothing on stderr but failures" },
+    OptionSpec { name: "verbose", short: Some('v'), value: None, help: "Prints parse errors, timings and other details on stderr" },
+];
+
+// How much a subcommand says on stderr besides what it prints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+}
+
+// The options and paths given to a subcommand
+pub struct Arguments {
+    command: &'static Command,
+    pub paths: Vec<String>,
+    // The options in the order they were given, with their values, a flag has no value
+    options: Vec<(&'static str, Option<String>)>,
+}
+
+impl Command {
+    fn option(&self, name: &str) -> Option<&'static OptionSpec> {
+        return self.options.iter().flat_map(|options| options.iter()).find(|option| option.name == name);
+    }
+
+    fn short_option(&self, short: char) -> Option<&'static OptionSpec> {
+        return self.options.iter().flat_map(|options| options.iter()).find(|option| option.short == Some(short));
+    }
+
+    pub fn usage(&self) -> String {
+        let mut usage = format!("Usage: web_engine {} {} [OPTIONS]\n\n{}\n\nOptions:\n", self.name, self.paths, self.about);
+        for option in self.options.iter().flat_map(|options| options.iter()) {
+            let short = option.short.map(|short| format!("-{}, ", short)).unwrap_or_default();
+            let name = match option.value {
+                Some(value) => format!("{}--{} {}", short, option.name, value),
+                None => format!("{}--{}", short, option.name)
+            };
+            let help = match option.name {
+                "format" => format!("{}, one of {} (default {})", option.help, self.formats.join(", "), self.formats[0]),
+                _ => option.help.to_string()
+            };
+            usage.push_str(&format!("  {:<28} {}\n", name, help));
+        }
+        return usage;
+    }
+
+    // Parses the arguments that follow the name of the subcommand
+    // Err is the message for an argument that is not one of its options, or an option without its value
+    pub fn parse(&'static self, arguments: &[String]) -> Result<Arguments, String> {
+        let mut parsed = Arguments { command: self, paths: Vec::new(), options: Vec::new() };
+        let mut arguments = arguments.iter();
+        while let Some(argument) = arguments.next() {
+            // Everything after -- is a path, even when it starts with -
+            if argument == "--" {
+                parsed.paths.extend(arguments.by_ref().cloned());
+                break;
+            }
+
+            let (option, inline_value) = if let Some(long) = argument.strip_prefix("--") {
+                let (name, value) = match long.split_once('=') {
+                    Some((name, value)) => (name, Some(value.to_string())),
+                    None => (long, None)
+                };
+                (self.option(name).ok_or(format!("Unknown option --{}", name))?, value)
+            } else if argument.len() == 2 && argument.starts_with('-') && argument != "-" {
+                let short = argument.chars().nth(1).unwrap();
+                (self.short_option(short).ok_or(format!("Unknown option -{}", short))?, None)
+            } else {
+                parsed.paths.push(argument.to_string());
+                continue;
+            };
+
+            let value = match (option.value, inline_value) {
+                (None, None) => None,
+                (None, Some(_)) => return Err(format!("--{} does not take a value", option.name)),
+                (Some(_), Some(value)) => Some(value),
+                (Some(value_name), None) => Some(arguments.next().cloned().ok_or(format!("--{} needs a {}", option.name, value_name))?)
+            };
+            parsed.options.push((option.name, value));
+        }
+
+        if let Some(format) = parsed.value("format") {
+            if !self.formats.contains(&format) {
+                return Err(format!("--format needs one of {}", self.formats.join(", ")));
+            }
+        }
+        if parsed.flag("quiet") && parsed.flag("verbose") {
+            return Err("--quiet and --verbose can not be given together".to_string());
+        }
+        return Ok(parsed);
+    }
+}
+
+impl Arguments {
+    pub fn flag(&self, name: &str) -> bool {
+        return self.options.iter().any(|(option, _)| *option == name);
+    }
+
+    // The value of an option, the last one when it is given more than once
+    pub fn value(&self, name: &str) -> Option<&str> {
+        return self.options.iter().rev().find(|(option, _)| *option == name).and_then(|(_, value)| value.as_deref());
+    }
+
+    // Every value of an option that can be given more than once, e.g. --event
+    pub fn values(&self, name: &str) -> Vec<&str> {
+        return self.options.iter().filter(|(option, _)| *option == name).filter_map(|(_, value)| value.as_deref()).collect();
+    }
+
+    // The value of an option as a number or another type, exits with a usage error when it is not one, e.g. --columns 80
+    pub fn parsed_value<T: FromStr>(&self, name: &str, example: &str) -> Option<T> {
+        let value = self.value(name)?;
+        match value.parse::<T>() {
+            Ok(value) => return Some(value),
+            Err(_) => self.usage_error(&format!("--{} can not be {}, e.g. {}", name, value, example))
+        }
+    }
+
+    pub fn format(&self) -> &str {
+        return self.value("format").unwrap_or(self.command.formats[0]);
+    }
+
+    pub fn verbosity(&self) -> Verbosity {
+        if self.flag("quiet") {
+            return Verbosity::Quiet;
+        }
+        if self.flag("verbose") {
+            return Verbosity::Verbose;
+        }
+        return Verbosity::Normal;
+    }
+
+    // The paths of a subcommand that takes any number of them, stdin when there are none
+    pub fn input_paths(&self) -> Vec<&str> {
+        if self.paths.is_empty() {
+            return vec!["-"];
+        }
+        return self.paths.iter().map(|path| path.as_str()).collect();
+    }
+
+    // The only path of a subcommand that takes one, - for stdin when there is none, exits with a usage error when there is more than one
+    pub fn path(&self) -> &str {
+        match self.paths.as_slice() {
+            [path] => return path,
+            [] => return "-",
+            _ => self.usage_error(&format!("Only one path can be given, {} were", self.paths.len()))
+        }
+    }
+
+    // Where the subcommand prints to, stdout unless --output is given
+    pub fn output(&self) -> Box<dyn Write> {
+        match self.value("output") {
+            Some(path) if path != "-" => match File::create(path) {
+                Ok(file) => return Box::new(BufWriter::new(file)),
+                Err(error) => {
+                    eprintln!("Could not create {}: {}", path, error);
+                    exit(1);
+                }
+            },
+            _ => return Box::new(BufWriter::new(io::stdout()))
+        }
+    }
+
+    // Exits with the message and the usage of the subcommand, like a mistyped option
+    pub fn usage_error(&self, message: &str) -> ! {
+        eprintln!("{}\n\n{}", message, self.command.usage());
+        exit(2);
+    }
+}
+
+// The bytes of the file at path, or of stdin when the path is -, exits when they can not be read
+pub fn read_input(path: &str) -> Vec<u8> {
+    let result = if path == "-" {
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes).map(|_| bytes)
+    } else {
+        std::fs::read(path)
+    };
+    match result {
+        Ok(bytes) => return bytes,
+        Err(error) => {
+            eprintln!("Could not read {}: {}", if path == "-" { "stdin" } else { path }, error);
+            exit(1);
+        }
+    }
+}
+
+// Writes to the output, a failure to write (e.g. a closed pipe) ends the program as there is nowhere left to print to
+pub fn write_output(output: &mut dyn Write, text: &str) {
+    if output.write_all(text.as_bytes()).and_then(|_| output.flush()).is_err() {
+        exit(1);
+    }
+}
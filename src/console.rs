@@ -0,0 +1,380 @@
+use std::cell::RefCell;
+use std::io::Write;
+use std::rc::Rc;
+use crate::interpreter::{create_normal_completion, CompletionRecord, Interpreter, JSObject, JSValue, PlatformObject, PropertyKey, PropertyType, ReferenceRecordOrJsValue};
+use crate::error::error_to_string;
+use crate::node::NodeData;
+use crate::promise::PromiseState;
+use crate::regexp::escape_regexp_pattern;
+use crate::date::date_to_iso_string;
+
+// https://console.spec.whatwg.org/#loglevel-severity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogLevel {
+    Log,
+    Warn,
+    Error,
+}
+
+// https://console.spec.whatwg.org/#printer
+// The printer is implementation defined, embedders provide their own to decide where console output goes
+pub trait Logger {
+    fn print(&mut self, log_level: LogLevel, message: &str);
+}
+
+// Prints log messages to stdout and warnings and errors to stderr
+pub struct StandardStreamsLogger;
+
+impl Logger for StandardStreamsLogger {
+    fn print(&mut self, log_level: LogLevel, message: &str) {
+        match log_level {
+            LogLevel::Log => {
+                println!("{}", message);
+                std::io::stdout().flush().unwrap();
+            },
+            LogLevel::Warn | LogLevel::Error => {
+                eprintln!("{}", message);
+            }
+        }
+    }
+}
+
+// https://console.spec.whatwg.org/#console-namespace
+pub(crate) fn install_console(interpreter: &mut Interpreter) {
+    let mut console = JSObject::new();
+    console.extensible = true;
+    let console = Rc::new(RefCell::new(console));
+
+    Interpreter::define_builtin_function(&console, "log", console_log);
+    Interpreter::define_builtin_function(&console, "warn", console_warn);
+    Interpreter::define_builtin_function(&console, "error", console_error);
+
+    interpreter.define_global_property("console", Rc::new(RefCell::new(JSValue::Object(console))));
+}
+
+// https://console.spec.whatwg.org/#log
+fn console_log(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Perform Logger("log", data).
+    return logger(interpreter, LogLevel::Log, &arguments);
+}
+
+// https://console.spec.whatwg.org/#warn
+fn console_warn(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Perform Logger("warn", data).
+    return logger(interpreter, LogLevel::Warn, &arguments);
+}
+
+// https://console.spec.whatwg.org/#error
+fn console_error(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Perform Logger("error", data).
+    return logger(interpreter, LogLevel::Error, &arguments);
+}
+
+// https://console.spec.whatwg.org/#logger
+fn logger(interpreter: &mut Interpreter, log_level: LogLevel, arguments: &[Rc<RefCell<JSValue>>]) -> CompletionRecord {
+    // 1. If args is empty, return.
+    // 2-5. Let Result be Formatter(args) if the first argument is a string, otherwise args.
+    if !arguments.is_empty() {
+        let message = format_arguments(arguments);
+
+        // 6. Perform Printer(logLevel, Result).
+        interpreter.print(log_level, &message);
+    }
+
+    // 7. Return undefined.
+    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+}
+
+// https://console.spec.whatwg.org/#formatter
+// Applies the format specifiers of the first argument and joins what remains with spaces
+pub(crate) fn format_arguments(arguments: &[Rc<RefCell<JSValue>>]) -> String {
+    let mut remaining_arguments = arguments.iter().skip(1);
+    let mut output = String::new();
+
+    match &*arguments[0].borrow() {
+        JSValue::String(target) => {
+            let mut characters = target.chars().peekable();
+            while let Some(character) = characters.next() {
+                if character != '%' {
+                    output.push(character);
+                    continue;
+                }
+
+                // https://console.spec.whatwg.org/#formatting-specifiers
+                let specifier = match characters.peek() {
+                    Some(specifier) => *specifier,
+                    None => {
+                        output.push('%');
+                        continue;
+                    }
+                };
+
+                if specifier == '%' {
+                    characters.next();
+                    output.push('%');
+                    continue;
+                }
+
+                if !matches!(specifier, 's' | 'd' | 'i' | 'f' | 'o' | 'O' | 'c') {
+                    output.push('%');
+                    continue;
+                }
+
+                // Specifiers without a matching argument are printed as they are
+                let current = match remaining_arguments.next() {
+                    Some(current) => current,
+                    None => {
+                        output.push('%');
+                        continue;
+                    }
+                };
+                characters.next();
+
+                match specifier {
+                    // Element which substitutes is converted to a string
+                    's' => output.push_str(&format_value(&current.borrow())),
+                    // Element which substitutes is converted to an integer
+                    'd' | 'i' => output.push_str(&Interpreter::number_to_string(argument_to_number(&current.borrow()).trunc())),
+                    // Element which substitutes is converted to a float
+                    'f' => output.push_str(&Interpreter::number_to_string(argument_to_number(&current.borrow()))),
+                    // Element is displayed as an object
+                    'o' | 'O' => output.push_str(&inspect(&current.borrow(), &mut Vec::new())),
+                    // Applies CSS style rules, which a terminal can not show
+                    _ => {}
+                }
+            }
+        },
+        first_argument => {
+            output.push_str(&format_value(first_argument));
+        }
+    }
+
+    for argument in remaining_arguments {
+        output.push(' ');
+        output.push_str(&format_value(&argument.borrow()));
+    }
+
+    return output;
+}
+
+// TODO: Use ToNumber once it supports strings and objects
+fn argument_to_number(value: &JSValue) -> f64 {
+    match value {
+        JSValue::Numeric(number) => *number,
+        JSValue::Boolean(boolean) => if *boolean { 1.0 } else { 0.0 },
+        JSValue::Null => 0.0,
+        JSValue::String(string) => string.trim().parse::<f64>().unwrap_or(f64::NAN),
+        _ => f64::NAN
+    }
+}
+
+// Top level strings are printed as they are, everything else is formatted the way it would be inside an object
+pub(crate) fn format_value(value: &JSValue) -> String {
+    match value {
+        JSValue::String(string) => string.clone(),
+        _ => inspect(value, &mut Vec::new())
+    }
+}
+
+// Formats a value the way it would be printed inside an object, used by the REPL to show results
+pub(crate) fn inspect_value(value: &JSValue) -> String {
+    return inspect(value, &mut Vec::new());
+}
+
+// Formats an uncaught exception, error objects are printed with their stack
+pub(crate) fn format_error(value: &JSValue) -> String {
+    if let JSValue::Object(object) = value {
+        if object.borrow().error_data {
+            return format_error_object(object);
+        }
+    }
+
+    return format_value(value);
+}
+
+// The stack of an error already starts with its name and message
+fn format_error_object(error: &Rc<RefCell<JSObject>>) -> String {
+    match own_string_property(&error.borrow(), "stack") {
+        Some(stack) => stack,
+        None => error_to_string(error)
+    }
+}
+
+fn own_string_property(object: &JSObject, key: &str) -> Option<String> {
+    let property = object.own_property(&PropertyKey::String(key.to_string()))?;
+    match &*property {
+        PropertyType::DataProperty(data_property) => {
+            match &*data_property.value.borrow() {
+                JSValue::String(string) => Some(string.clone()),
+                _ => None
+            }
+        },
+        PropertyType::AccessorProperty(_) => None
+    }
+}
+
+// https://console.spec.whatwg.org/#generic-javascript-object-formatting
+// Objects that have already been visited are printed as [Circular] so cyclic objects can be logged
+fn inspect(value: &JSValue, visited: &mut Vec<*const RefCell<JSObject>>) -> String {
+    match value {
+        JSValue::Undefined => String::from("undefined"),
+        JSValue::Null => String::from("null"),
+        JSValue::Boolean(boolean) => boolean.to_string(),
+        JSValue::Numeric(number) => Interpreter::number_to_string(*number),
+        JSValue::String(string) => format!("'{}'", string.replace('\\', "\\\\").replace('\'', "\\'").replace('\n', "\\n")),
+        JSValue::Symbol(symbol) => format!("Symbol({})", symbol.description()),
+        JSValue::Object(object) => {
+            if visited.contains(&Rc::as_ptr(object)) {
+                return String::from("[Circular]");
+            }
+
+            let object_ref = object.borrow();
+
+            if Interpreter::is_callable(value) {
+                return match own_string_property(&object_ref, "name") {
+                    Some(name) if !name.is_empty() => format!("[Function: {}]", name),
+                    _ => String::from("[Function (anonymous)]")
+                };
+            }
+
+            if let Some(PlatformObject::Node(node)) = &object_ref.platform_object {
+                return match &node.borrow().data {
+                    NodeData::Element(element) => {
+                        let mut tag = format!("<{}", element.local_name());
+                        for attribute in element.attribute_list() {
+                            tag.push_str(&format!(" {}=\"{}\"", attribute.local_name, attribute.value));
+                        }
+                        tag.push('>');
+                        tag
+                    },
+                    NodeData::Document(_) => String::from("#document"),
+                    NodeData::DocumentType(_) => String::from("<!DOCTYPE html>"),
+                    NodeData::Text(text) => format!("#text {}", inspect(&JSValue::String(text.character_data.data.clone()), visited)),
+                    NodeData::Comment(comment) => format!("<!--{}-->", comment.character_data.data),
+                    NodeData::CharacterData(_) => String::from("#text"),
+                };
+            }
+
+            // Promises show their state and result the way Node.js does
+            if let Some(promise_data) = &object_ref.promise_data {
+                return match promise_data.state {
+                    PromiseState::Pending => String::from("Promise { <pending> }"),
+                    PromiseState::Fulfilled => format!("Promise {{ {} }}", inspect(&promise_data.result.borrow(), visited)),
+                    PromiseState::Rejected => format!("Promise {{ <rejected> {} }}", inspect(&promise_data.result.borrow(), visited)),
+                };
+            }
+
+            // Regular expressions and dates are printed as their source and ISO string the way Node.js does
+            if let Some(regexp_data) = &object_ref.regexp_data {
+                return format!("/{}/{}", escape_regexp_pattern(&regexp_data.original_source), regexp_data.original_flags);
+            }
+
+            if let Some(time_value) = object_ref.date_value {
+                return date_to_iso_string(time_value).unwrap_or_else(|| String::from("Invalid Date"));
+            }
+
+            let error = if object_ref.error_data { Some(format_error_object(object)) } else { None };
+
+            visited.push(Rc::as_ptr(object));
+
+            // Arrays list their elements in order, runs of holes are printed as empty items
+            let mut elements = Vec::new();
+            let mut length: u32 = 0;
+            if object_ref.array {
+                if let Some(PropertyType::DataProperty(data_property)) = object_ref.own_property(&PropertyKey::String(String::from("length"))).as_deref() {
+                    if let JSValue::Numeric(number) = *data_property.value.borrow() {
+                        length = number as u32;
+                    }
+                }
+
+                let mut empty_items = 0;
+                for index in 0..length {
+                    match object_ref.own_property(&PropertyKey::String(index.to_string())) {
+                        Some(property) => {
+                            if empty_items > 0 {
+                                elements.push(format_empty_items(empty_items));
+                                empty_items = 0;
+                            }
+                            elements.push(inspect_property(&property, visited));
+                        },
+                        None => empty_items += 1
+                    }
+                }
+                if empty_items > 0 {
+                    elements.push(format_empty_items(empty_items));
+                }
+            }
+
+            let mut properties = Vec::new();
+            for key in object_ref.own_property_keys() {
+                // Elements and the length of an array have already been printed
+                if object_ref.array {
+                    if let PropertyKey::String(key) = key {
+                        if key == "length" || Interpreter::array_index(key).is_some_and(|index| index < length) {
+                            continue;
+                        }
+                    }
+                }
+
+                // Non enumerable properties such as the constructor of a prototype are not shown
+                if let Some(PropertyType::DataProperty(data_property)) = object_ref.own_property(key).as_deref() {
+                    if !data_property.enumerable {
+                        continue;
+                    }
+                }
+
+                let key_string = match key {
+                    PropertyKey::String(key) => key.clone(),
+                    PropertyKey::Symbol(symbol) => format!("[Symbol({})]", symbol.description()),
+                };
+
+                let value_string = inspect_property(&object_ref.own_property(key).unwrap(), visited);
+                properties.push(format!("{}: {}", key_string, value_string));
+            }
+
+            visited.pop();
+
+            if object_ref.array {
+                elements.append(&mut properties);
+                if elements.is_empty() {
+                    return String::from("[]");
+                }
+                return format!("[ {} ]", elements.join(", "));
+            }
+
+            // Errors are shown by their stack, followed by any properties that have been added to them
+            if let Some(error) = error {
+                if properties.is_empty() {
+                    return error;
+                }
+                return format!("{} {{ {} }}", error, properties.join(", "));
+            }
+
+            if properties.is_empty() {
+                return String::from("{}");
+            }
+
+            return format!("{{ {} }}", properties.join(", "));
+        }
+    }
+}
+
+fn inspect_property(property: &PropertyType, visited: &mut Vec<*const RefCell<JSObject>>) -> String {
+    match property {
+        PropertyType::DataProperty(data_property) => inspect(&data_property.value.borrow(), visited),
+        PropertyType::AccessorProperty(accessor_property) => {
+            match (&accessor_property.get, &accessor_property.set) {
+                (Some(_), Some(_)) => String::from("[Getter/Setter]"),
+                (Some(_), None) => String::from("[Getter]"),
+                _ => String::from("[Setter]"),
+            }
+        }
+    }
+}
+
+fn format_empty_items(count: u32) -> String {
+    if count == 1 {
+        return String::from("<1 empty item>");
+    }
+    return format!("<{} empty items>", count);
+}
@@ -0,0 +1,594 @@
+use std::rc::Rc;
+use indexmap::IndexMap;
+use crate::css_parser::{CSSToken, ComponentValue};
+use crate::css_values::{components, single_component, CssWideKeyword, PropertyValue};
+use crate::layout::{process_white_space, BoxType, LayoutBox};
+use crate::node::{NodeData, RefNode};
+use crate::selector::PseudoElement;
+use crate::transitions::function_arguments;
+use crate::unicode::ascii_lowercase;
+
+// https://drafts.csswg.org/css-lists-3/
+// https://drafts.csswg.org/css-content-3/
+// CSS counters, and the text of the markers of list items and of the ::before and ::after pseudo-elements, which generate boxes of their own in the box tree
+// The value of a counter depends on every box before it in the tree, so counters are resolved in a pass over the box tree once it is built (see resolve_counters),
+// which fills in the text of the boxes of the pseudo-elements
+// TODO: @counter-style rules, symbols(), the quotes property and images in content and list-style-image are not supported
+
+// https://drafts.csswg.org/css-counter-styles-3/#predefined-counters
+// TODO: An unknown counter style name is decimal, as there are no @counter-style rules to define one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CounterStyle {
+    Decimal,
+    DecimalLeadingZero,
+    LowerRoman,
+    UpperRoman,
+    LowerAlpha,
+    UpperAlpha,
+    LowerGreek,
+    Disc,
+    Circle,
+    Square,
+    // https://drafts.csswg.org/css-lists-3/#valdef-list-style-type-none
+    // No representation at all
+    None,
+}
+
+impl CounterStyle {
+    pub fn parse(value: &str) -> Option<CounterStyle> {
+        match single_component(value)? {
+            ComponentValue::Token(CSSToken::Ident(ident)) => return CounterStyle::from_name(&ident),
+            _ => return None
+        }
+    }
+
+    fn from_name(name: &str) -> Option<CounterStyle> {
+        match ascii_lowercase(name).as_ref() {
+            "decimal" => return Some(CounterStyle::Decimal),
+            "decimal-leading-zero" => return Some(CounterStyle::DecimalLeadingZero),
+            "lower-roman" => return Some(CounterStyle::LowerRoman),
+            "upper-roman" => return Some(CounterStyle::UpperRoman),
+            "lower-alpha" | "lower-latin" => return Some(CounterStyle::LowerAlpha),
+            "upper-alpha" | "upper-latin" => return Some(CounterStyle::UpperAlpha),
+            "lower-greek" => return Some(CounterStyle::LowerGreek),
+            "disc" => return Some(CounterStyle::Disc),
+            "circle" => return Some(CounterStyle::Circle),
+            "square" => return Some(CounterStyle::Square),
+            "none" => return Some(CounterStyle::None),
+            // The CSS-wide keywords and default are not counter style names
+            "initial" | "inherit" | "unset" | "default" => return None,
+            _ => return Some(CounterStyle::Decimal)
+        }
+    }
+
+    // https://drafts.csswg.org/css-counter-styles-3/#generate-a-counter
+    // The representation of value, a value out of the range of the counter style is represented in decimal, its fallback
+    pub fn representation(&self, value: i32) -> String {
+        match self {
+            CounterStyle::Decimal => return value.to_string(),
+            // https://drafts.csswg.org/css-counter-styles-3/#decimal-leading-zero
+            // Padded to two digits, after the negative sign of a negative value
+            CounterStyle::DecimalLeadingZero if value < 0 => return format!("-{:02}", value.unsigned_abs()),
+            CounterStyle::DecimalLeadingZero => return format!("{:02}", value),
+            CounterStyle::LowerRoman | CounterStyle::UpperRoman if (1..=3999).contains(&value) => {
+                let roman = roman_numeral(value as u32);
+                return if *self == CounterStyle::UpperRoman { roman.to_ascii_uppercase() } else { roman };
+            },
+            CounterStyle::LowerAlpha if value >= 1 => return alphabetic(value as u32, &('a'..='z').collect::<Vec<char>>()),
+            CounterStyle::UpperAlpha if value >= 1 => return alphabetic(value as u32, &('A'..='Z').collect::<Vec<char>>()),
+            CounterStyle::LowerGreek if value >= 1 => return alphabetic(value as u32, &"αβγδεζηθικλμνξοπρστυφχψω".chars().collect::<Vec<char>>()),
+            // https://drafts.csswg.org/css-counter-styles-3/#simple-symbolic
+            // The same symbol whatever the value
+            CounterStyle::Disc => return "•".to_string(),
+            CounterStyle::Circle => return "◦".to_string(),
+            CounterStyle::Square => return "▪".to_string(),
+            CounterStyle::None => return String::new(),
+            _ => return value.to_string()
+        }
+    }
+
+    // https://drafts.csswg.org/css-lists-3/#content-property
+    // The text of the marker of a list item whose list-item counter is value, which is followed by a space for a symbol and by a period and a space for a number
+    pub fn marker_text(&self, value: i32) -> String {
+        match self {
+            CounterStyle::None => return String::new(),
+            CounterStyle::Disc | CounterStyle::Circle | CounterStyle::Square => return format!("{} ", self.representation(value)),
+            _ => return format!("{}. ", self.representation(value))
+        }
+    }
+}
+
+// https://drafts.csswg.org/css-counter-styles-3/#additive-system
+fn roman_numeral(value: u32) -> String {
+    const SYMBOLS: [(u32, &str); 13] = [
+        (1000, "m"), (900, "cm"), (500, "d"), (400, "cd"), (100, "c"), (90, "xc"), (50, "l"), (40, "xl"), (10, "x"), (9, "ix"), (5, "v"), (4, "iv"), (1, "i")
+    ];
+    let mut value = value;
+    let mut numeral = String::new();
+    for (weight, symbol) in SYMBOLS {
+        while value >= weight {
+            numeral.push_str(symbol);
+            value -= weight;
+        }
+    }
+    return numeral;
+}
+
+// https://drafts.csswg.org/css-counter-styles-3/#alphabetic-system
+// a, b, ..., z, aa, ab, ... for the latin alphabet
+fn alphabetic(value: u32, symbols: &[char]) -> String {
+    let length = symbols.len() as u32;
+    let mut value = value;
+    let mut representation = Vec::new();
+    while value > 0 {
+        value -= 1;
+        representation.push(symbols[(value % length) as usize]);
+        value /= length;
+    }
+    return representation.iter().rev().collect();
+}
+
+// https://drafts.csswg.org/css-lists-3/#text-markers
+// A counter style the list-item counter is represented in, or a string that is the marker itself
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListStyleType {
+    CounterStyle(CounterStyle),
+    String(String),
+}
+
+impl ListStyleType {
+    pub fn parse(value: &str) -> Option<ListStyleType> {
+        match single_component(value)? {
+            ComponentValue::Token(CSSToken::String(string)) => return Some(ListStyleType::String(string)),
+            _ => return CounterStyle::parse(value).map(ListStyleType::CounterStyle)
+        }
+    }
+}
+
+// https://drafts.csswg.org/css-lists-3/#list-style-position-property
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListStylePosition {
+    Inside,
+    // The marker is outside the principal box of the list item, before the start of its first line
+    #[default]
+    Outside,
+}
+
+impl ListStylePosition {
+    pub fn parse(value: &str) -> Option<ListStylePosition> {
+        match ascii_lowercase(value.trim()).as_ref() {
+            "inside" => return Some(ListStylePosition::Inside),
+            "outside" => return Some(ListStylePosition::Outside),
+            _ => return None
+        }
+    }
+}
+
+// https://drafts.csswg.org/css-content-3/#content-property
+// normal is none for ::before and ::after, and the marker list-style-type gives for ::marker
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Content {
+    #[default]
+    Normal,
+    None,
+    Items(Vec<ContentItem>),
+}
+
+// https://drafts.csswg.org/css-content-3/#typedef-content-content-list
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContentItem {
+    String(String),
+    // https://drafts.csswg.org/css-content-3/#valdef-content-attr
+    // The value of the attribute of the element, the empty string when it has none
+    Attr(String),
+    // https://drafts.csswg.org/css-lists-3/#counter-functions
+    // counter(name, style) is the innermost counter named name and counters(name, separator, style) all of them from the outermost, joined by separator
+    Counter(String, CounterStyle),
+    Counters(String, String, CounterStyle),
+    // https://drafts.csswg.org/css-content-3/#quote-values
+    OpenQuote,
+    CloseQuote,
+    NoOpenQuote,
+    NoCloseQuote,
+}
+
+impl Content {
+    pub fn parse(value: &str) -> Option<Content> {
+        let components = components(value);
+        if let [ComponentValue::Token(CSSToken::Ident(ident))] = components.as_slice() {
+            match ascii_lowercase(ident).as_ref() {
+                "normal" => return Some(Content::Normal),
+                "none" => return Some(Content::None),
+                _ => ()
+            }
+        }
+        if components.is_empty() {
+            return None;
+        }
+        return components.iter().map(ContentItem::parse).collect::<Option<Vec<ContentItem>>>().map(Content::Items);
+    }
+}
+
+impl ContentItem {
+    fn parse(component: &ComponentValue) -> Option<ContentItem> {
+        match component {
+            ComponentValue::Token(CSSToken::String(string)) => return Some(ContentItem::String(string.clone())),
+            ComponentValue::Token(CSSToken::Ident(ident)) => {
+                match ascii_lowercase(ident).as_ref() {
+                    "open-quote" => return Some(ContentItem::OpenQuote),
+                    "close-quote" => return Some(ContentItem::CloseQuote),
+                    "no-open-quote" => return Some(ContentItem::NoOpenQuote),
+                    "no-close-quote" => return Some(ContentItem::NoCloseQuote),
+                    _ => return None
+                }
+            },
+            ComponentValue::Function(function) => {
+                let arguments = function_arguments(&function.value)?;
+                let ident = |index: usize| match arguments.get(index).map(|argument| argument.as_slice()) {
+                    Some([ComponentValue::Token(CSSToken::Ident(ident))]) => Some(ident.clone()),
+                    _ => None
+                };
+                let style = |index: usize| match arguments.get(index) {
+                    Some(_) => ident(index).and_then(|name| CounterStyle::from_name(&name)),
+                    None => Some(CounterStyle::Decimal)
+                };
+                match ascii_lowercase(&function.name).as_ref() {
+                    // TODO: The type and fallback of attr() are not supported
+                    "attr" if arguments.len() == 1 => return Some(ContentItem::Attr(ascii_lowercase(&ident(0)?).into_owned())),
+                    "counter" if arguments.len() <= 2 => return Some(ContentItem::Counter(ident(0)?, style(1)?)),
+                    "counters" if arguments.len() <= 3 => {
+                        let separator = match arguments.get(1).map(|argument| argument.as_slice()) {
+                            Some([ComponentValue::Token(CSSToken::String(separator))]) => separator.clone(),
+                            _ => return None
+                        };
+                        return Some(ContentItem::Counters(ident(0)?, separator, style(2)?));
+                    },
+                    _ => return None
+                }
+            },
+            _ => return None
+        }
+    }
+}
+
+// https://drafts.csswg.org/css-lists-3/#counter-properties
+// A counter a box resets, increments or sets, and the value it is reset to, incremented by or set to
+// A reversed counter that is reset without a value counts down to 1, see reversed_counter_start
+#[derive(Debug, Clone, PartialEq)]
+pub struct CounterChange {
+    pub name: String,
+    pub value: Option<i32>,
+    pub reversed: bool,
+}
+
+impl CounterChange {
+    // [ <counter-name> <integer>? ]+ | none, a counter without an integer gets default_value
+    // reversed(<counter-name>) is only allowed where allow_reversed is, which is counter-reset
+    pub fn parse_list(value: &str, default_value: i32, allow_reversed: bool) -> Option<Vec<CounterChange>> {
+        let components = components(value);
+        if let [ComponentValue::Token(CSSToken::Ident(ident))] = components.as_slice() {
+            if ident.eq_ignore_ascii_case("none") {
+                return Some(Vec::new());
+            }
+        }
+
+        let mut counter_changes: Vec<CounterChange> = Vec::new();
+        // Whether the last counter has its integer, only one can follow each name
+        let mut has_integer = false;
+        for component in components {
+            match component {
+                ComponentValue::Token(CSSToken::Ident(name)) if is_counter_name(&name) => {
+                    counter_changes.push(CounterChange { name, value: Some(default_value), reversed: false });
+                    has_integer = false;
+                },
+                ComponentValue::Function(function) if allow_reversed && function.name.eq_ignore_ascii_case("reversed") => {
+                    let name = match function_arguments(&function.value)?.as_slice() {
+                        [argument] => match argument.as_slice() {
+                            [ComponentValue::Token(CSSToken::Ident(name))] if is_counter_name(name) => name.clone(),
+                            _ => return None
+                        },
+                        _ => return None
+                    };
+                    counter_changes.push(CounterChange { name, value: None, reversed: true });
+                    has_integer = false;
+                },
+                ComponentValue::Token(CSSToken::Number(number)) if number.is_integer && !has_integer => {
+                    counter_changes.last_mut()?.value = Some(number.value.clamp(i32::MIN as f64, i32::MAX as f64) as i32);
+                    has_integer = true;
+                },
+                _ => return None
+            }
+        }
+        if counter_changes.is_empty() {
+            return None;
+        }
+        return Some(counter_changes);
+    }
+}
+
+// https://drafts.csswg.org/css-lists-3/#typedef-counter-name
+// A custom identifier other than none and the CSS-wide keywords
+fn is_counter_name(name: &str) -> bool {
+    return !matches!(ascii_lowercase(name).as_ref(), "none" | "initial" | "inherit" | "unset" | "default");
+}
+
+// The properties of a box its counters and generated content are resolved from, see resolve_counters
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CounterProperties {
+    pub counter_reset: Vec<CounterChange>,
+    pub counter_increment: Vec<CounterChange>,
+    pub counter_set: Vec<CounterChange>,
+    // None when the box inherits its list-style-type
+    pub list_style_type: Option<ListStyleType>,
+    pub content: Content,
+}
+
+// The counter properties of an element, or of one of its pseudo-elements, from its specified values
+// The properties an element has no declarations for are given their values from the user agent style sheet
+// TODO: inherit is treated as initial for counter-reset, counter-increment, counter-set and content
+pub(crate) fn counter_properties(element: &RefNode, pseudo_element: Option<PseudoElement>, values: &IndexMap<String, PropertyValue>) -> CounterProperties {
+    let mut properties = match pseudo_element {
+        Some(_) => CounterProperties::default(),
+        None => user_agent_counter_properties(element)
+    };
+
+    let counter_changes = |property: &str, default: &[CounterChange]| match values.get(property) {
+        Some(PropertyValue::CounterChanges(counter_changes)) => counter_changes.clone(),
+        Some(_) => Vec::new(),
+        None => default.to_vec()
+    };
+    properties.counter_reset = counter_changes("counter-reset", &properties.counter_reset);
+    properties.counter_increment = counter_changes("counter-increment", &properties.counter_increment);
+    properties.counter_set = counter_changes("counter-set", &properties.counter_set);
+
+    // list-style-type is inherited, and its initial value is disc
+    properties.list_style_type = match values.get("list-style-type") {
+        Some(PropertyValue::ListStyleType(list_style_type)) => Some(list_style_type.clone()),
+        Some(PropertyValue::CssWideKeyword(CssWideKeyword::Initial)) => Some(ListStyleType::CounterStyle(CounterStyle::Disc)),
+        Some(_) => None,
+        None => properties.list_style_type
+    };
+    properties.content = match values.get("content") {
+        Some(PropertyValue::Content(content)) => content.clone(),
+        _ => Content::Normal
+    };
+    return properties;
+}
+
+// https://html.spec.whatwg.org/multipage/rendering.html#lists
+fn user_agent_counter_properties(element: &RefNode) -> CounterProperties {
+    let mut properties = CounterProperties::default();
+    let node = element.borrow();
+    let element = match &node.data {
+        NodeData::Element(element) => element,
+        _ => return properties
+    };
+
+    let is_list = |local_name: &str| matches!(local_name, "dir" | "menu" | "ol" | "ul");
+    let local_name = element.local_name().to_string();
+    let local_name = local_name.as_str();
+    match local_name {
+        // dir, menu, ol, ul { counter-reset: list-item; }
+        // The start and reversed attributes of ol are presentational hints, the counter is reset to one less than start so its first item is start, or one more when it is reversed
+        "ol" if element.has_attribute("reversed") => {
+            let value = element.integer_attribute("start").map(|start| start.saturating_add(1).clamp(i32::MIN as i64, i32::MAX as i64) as i32);
+            properties.counter_reset = vec![CounterChange { name: "list-item".to_string(), value, reversed: true }];
+        },
+        "ol" => {
+            let value = element.integer_attribute("start").map_or(0, |start| start.saturating_sub(1).clamp(i32::MIN as i64, i32::MAX as i64) as i32);
+            properties.counter_reset = vec![CounterChange { name: "list-item".to_string(), value: Some(value), reversed: false }];
+        },
+        "dir" | "menu" | "ul" => properties.counter_reset = vec![CounterChange { name: "list-item".to_string(), value: Some(0), reversed: false }],
+        // li[value] sets the list-item counter to its value
+        "li" => {
+            if let Some(value) = element.integer_attribute("value") {
+                properties.counter_set = vec![CounterChange { name: "list-item".to_string(), value: Some(value.clamp(i32::MIN as i64, i32::MAX as i64) as i32), reversed: false }];
+            }
+        },
+        _ => ()
+    }
+
+    // ol { list-style-type: decimal; }, and the bullets of lists nested in other lists are circles and then squares
+    properties.list_style_type = match local_name {
+        "ol" => Some(ListStyleType::CounterStyle(CounterStyle::Decimal)),
+        "dir" | "menu" | "ul" => {
+            let mut depth = 0;
+            let mut ancestor = node.parentNode.as_ref().and_then(|parent| parent.upgrade());
+            while let Some(current) = ancestor {
+                if let NodeData::Element(ancestor_element) = &current.borrow().data {
+                    if is_list(ancestor_element.local_name()) {
+                        depth += 1;
+                    }
+                }
+                ancestor = current.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+            }
+            Some(ListStyleType::CounterStyle([CounterStyle::Disc, CounterStyle::Circle, CounterStyle::Square][depth.min(2)]))
+        },
+        _ => None
+    };
+
+    // The type attribute of ol, ul and li is a presentational hint for list-style-type, the values of ol are case-sensitive and those of ul are not
+    let list_type = element.get_attribute("type").map(|list_type| list_type.trim().to_string());
+    let ordered_type = |list_type: &str| match list_type {
+        "1" => Some(CounterStyle::Decimal),
+        "a" => Some(CounterStyle::LowerAlpha),
+        "A" => Some(CounterStyle::UpperAlpha),
+        "i" => Some(CounterStyle::LowerRoman),
+        "I" => Some(CounterStyle::UpperRoman),
+        _ => None
+    };
+    let unordered_type = |list_type: &str| match ascii_lowercase(list_type).as_ref() {
+        "none" => Some(CounterStyle::None),
+        "disc" => Some(CounterStyle::Disc),
+        "circle" => Some(CounterStyle::Circle),
+        "square" => Some(CounterStyle::Square),
+        _ => None
+    };
+    let type_style = list_type.and_then(|list_type| match local_name {
+        "ol" => ordered_type(&list_type),
+        "ul" => unordered_type(&list_type),
+        "li" => ordered_type(&list_type).or_else(|| unordered_type(&list_type)),
+        _ => None
+    });
+    if let Some(type_style) = type_style {
+        properties.list_style_type = Some(ListStyleType::CounterStyle(type_style));
+    }
+    return properties;
+}
+
+// A counter in scope of the box being resolved
+struct Counter {
+    name: String,
+    value: i32,
+    reversed: bool,
+    // The depth in the box tree of the box that created the counter, its scope is that box, the boxes after it with the same parent and their descendants
+    depth: usize,
+}
+
+// https://drafts.csswg.org/css-lists-3/#creating-counters
+// https://drafts.csswg.org/css-lists-3/#counters-without-boxes
+// Resolves the counters of the box tree in the order of the tree, and generates the text of the boxes of the markers and the ::before and ::after pseudo-elements
+// Elements that generate no box (e.g. display: none) do not change any counter
+pub(crate) fn resolve_counters(root: &mut LayoutBox) {
+    let mut resolver = CounterResolver { counters: Vec::new(), quote_depth: 0 };
+    resolver.resolve_box(root, 0, &ListStyleType::CounterStyle(CounterStyle::Disc));
+}
+
+struct CounterResolver {
+    // The counters in scope, the innermost of each name last
+    counters: Vec<Counter>,
+    // https://drafts.csswg.org/css-content-3/#quote-nesting-depth
+    quote_depth: usize,
+}
+
+impl CounterResolver {
+    fn resolve_box(&mut self, layout_box: &mut LayoutBox, depth: usize, parent_list_style_type: &ListStyleType) {
+        if matches!(layout_box.box_type, BoxType::Text(_)) {
+            return;
+        }
+
+        // Counters are reset, then incremented, then set
+        let properties = &layout_box.counter_properties;
+        for counter_reset in &properties.counter_reset {
+            let value = counter_reset.value.unwrap_or_else(|| reversed_counter_start(layout_box, &counter_reset.name));
+            self.reset(&counter_reset.name, value, counter_reset.reversed, depth);
+        }
+        for counter_increment in &properties.counter_increment {
+            let counter = self.counter(&counter_increment.name, depth);
+            counter.value = counter.value.wrapping_add(counter_increment.value.unwrap_or(1));
+        }
+        // https://drafts.csswg.org/css-lists-3/#list-item-counter
+        // A list item increments the list-item counter by 1, or by -1 when it is reversed, unless its counter-increment says what to increment it by
+        let is_list_item = layout_box.box_type == BoxType::ListItem && layout_box.pseudo_element.is_none();
+        if is_list_item && !properties.counter_increment.iter().any(|counter_increment| counter_increment.name == "list-item") {
+            let counter = self.counter("list-item", depth);
+            counter.value = counter.value.wrapping_add(if counter.reversed { -1 } else { 1 });
+        }
+        for counter_set in &properties.counter_set {
+            self.counter(&counter_set.name, depth).value = counter_set.value.unwrap_or(0);
+        }
+
+        let list_style_type = properties.list_style_type.clone().unwrap_or_else(|| parent_list_style_type.clone());
+        if let Some(pseudo_element) = layout_box.pseudo_element {
+            let text = self.generated_text(layout_box, pseudo_element, &list_style_type, depth);
+            let white_space = layout_box.white_space;
+            layout_box.children = match text.is_empty() {
+                true => Vec::new(),
+                false => vec![LayoutBox::new(BoxType::Text(process_white_space(&text, white_space)), Rc::clone(&layout_box.node), white_space)]
+            };
+            return;
+        }
+
+        // The counters the children create go out of scope at the end of their parent
+        let scope_start = self.counters.len();
+        for child in &mut layout_box.children {
+            self.resolve_box(child, depth + 1, &list_style_type);
+        }
+        self.counters.truncate(scope_start);
+    }
+
+    // https://drafts.csswg.org/css-lists-3/#instantiating-counters
+    // A counter reset by a box replaces the one of the same name a box before it with the same parent reset, rather than nesting in it
+    fn reset(&mut self, name: &str, value: i32, reversed: bool, depth: usize) {
+        let counter = Counter { name: name.to_string(), value, reversed, depth };
+        match self.counters.iter_mut().rev().find(|counter| counter.name == name).filter(|counter| counter.depth == depth) {
+            Some(sibling_counter) => *sibling_counter = counter,
+            None => self.counters.push(counter)
+        }
+    }
+
+    // The innermost counter named name, a box that increments, sets or uses a counter that is not in scope creates it with the value 0
+    fn counter(&mut self, name: &str, depth: usize) -> &mut Counter {
+        match self.counters.iter().rposition(|counter| counter.name == name) {
+            Some(index) => return &mut self.counters[index],
+            None => {
+                self.counters.push(Counter { name: name.to_string(), value: 0, reversed: false, depth });
+                return self.counters.last_mut().unwrap();
+            }
+        }
+    }
+
+    // https://drafts.csswg.org/css-content-3/#content-property
+    // The text of the box of a pseudo-element
+    fn generated_text(&mut self, layout_box: &LayoutBox, pseudo_element: PseudoElement, list_style_type: &ListStyleType, depth: usize) -> String {
+        let items = match &layout_box.counter_properties.content {
+            Content::Items(items) => items,
+            Content::None => return String::new(),
+            // https://drafts.csswg.org/css-lists-3/#marker-properties
+            // The marker of a list item is its list-item counter in the style of its list-style-type, or the string list-style-type gives
+            Content::Normal if pseudo_element == PseudoElement::Marker => {
+                match list_style_type {
+                    ListStyleType::String(string) => return string.clone(),
+                    ListStyleType::CounterStyle(counter_style) => return counter_style.marker_text(self.counter("list-item", depth).value)
+                }
+            },
+            Content::Normal => return String::new()
+        };
+
+        let mut text = String::new();
+        for item in items {
+            match item {
+                ContentItem::String(string) => text.push_str(string),
+                ContentItem::Attr(name) => {
+                    if let NodeData::Element(element) = &layout_box.node.borrow().data {
+                        text.push_str(element.get_attribute(name).map(|value| value.as_str()).unwrap_or(""));
+                    }
+                },
+                ContentItem::Counter(name, counter_style) => text.push_str(&counter_style.representation(self.counter(name, depth).value)),
+                ContentItem::Counters(name, separator, counter_style) => {
+                    self.counter(name, depth);
+                    let representations: Vec<String> = self.counters.iter().filter(|counter| counter.name == *name).map(|counter| counter_style.representation(counter.value)).collect();
+                    text.push_str(&representations.join(separator));
+                },
+                // The quotes of the initial value of quotes, double quotes outside and single quotes inside
+                ContentItem::OpenQuote => {
+                    text.push(if self.quote_depth % 2 == 0 { '“' } else { '‘' });
+                    self.quote_depth += 1;
+                },
+                ContentItem::CloseQuote if self.quote_depth > 0 => {
+                    self.quote_depth -= 1;
+                    text.push(if self.quote_depth % 2 == 0 { '”' } else { '’' });
+                },
+                ContentItem::CloseQuote => (),
+                ContentItem::NoOpenQuote => self.quote_depth += 1,
+                ContentItem::NoCloseQuote => self.quote_depth = self.quote_depth.saturating_sub(1)
+            }
+        }
+        return text;
+    }
+}
+
+// https://drafts.csswg.org/css-lists-3/#instantiating-counters
+// The value a reversed counter reset without one starts at, one more than the number of times it is counted down in its scope, so the last box to count it down is 1
+// TODO: Only the children of the box that resets the counter are counted, not the other boxes in its scope
+fn reversed_counter_start(layout_box: &LayoutBox, name: &str) -> i32 {
+    let mut count: i32 = 0;
+    for child in layout_box.children.iter().filter(|child| child.pseudo_element.is_none()) {
+        match child.counter_properties.counter_increment.iter().find(|counter_increment| counter_increment.name == name) {
+            Some(counter_increment) => count = count.wrapping_sub(counter_increment.value.unwrap_or(1)),
+            None if name == "list-item" && child.box_type == BoxType::ListItem => count += 1,
+            None => ()
+        }
+    }
+    return count.wrapping_add(1);
+}
@@ -0,0 +1,1093 @@
+// https://www.w3.org/TR/css-syntax-3/
+// Turns CSS text into rules and declarations, what they mean is left to the CSSOM and the style system
+// TODO: Parse errors are not reported, the input is always a string rather than a byte stream with an encoding
+
+// https://www.w3.org/TR/css-syntax-3/#tokenization
+#[derive(Debug, Clone, PartialEq)]
+pub enum CSSToken {
+    Ident(String),
+    Function(String),
+    AtKeyword(String),
+    Hash(String, HashType),
+    String(String),
+    BadString,
+    Url(String),
+    BadUrl,
+    Delim(char),
+    Number(NumericValue),
+    Percentage(NumericValue),
+    Dimension(NumericValue, String),
+    Whitespace,
+    CDO,
+    CDC,
+    Colon,
+    Semicolon,
+    Comma,
+    LeftSquareBracket,
+    RightSquareBracket,
+    LeftParenthesis,
+    RightParenthesis,
+    LeftCurlyBracket,
+    RightCurlyBracket,
+    EOF,
+}
+
+// https://www.w3.org/TR/css-syntax-3/#typedef-hash-token
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HashType {
+    Id,
+    Unrestricted,
+}
+
+// The numeric value of a number, percentage or dimension token, the representation is kept so it serializes as it was written
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumericValue {
+    pub value: f64,
+    pub is_integer: bool,
+    pub representation: String,
+}
+
+// https://www.w3.org/TR/css-syntax-3/#component-value
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComponentValue {
+    Token(CSSToken),
+    Function(Function),
+    SimpleBlock(SimpleBlock),
+}
+
+// https://www.w3.org/TR/css-syntax-3/#function
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub value: Vec<ComponentValue>,
+}
+
+// https://www.w3.org/TR/css-syntax-3/#simple-block
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimpleBlock {
+    pub associated_token: CSSToken,
+    pub value: Vec<ComponentValue>,
+}
+
+// https://www.w3.org/TR/css-syntax-3/#qualified-rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualifiedRule {
+    pub prelude: Vec<ComponentValue>,
+    pub block: SimpleBlock,
+}
+
+// https://www.w3.org/TR/css-syntax-3/#at-rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct AtRule {
+    pub name: String,
+    pub prelude: Vec<ComponentValue>,
+    pub block: Option<SimpleBlock>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rule {
+    Qualified(QualifiedRule),
+    At(AtRule),
+}
+
+// https://www.w3.org/TR/css-syntax-3/#declaration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declaration {
+    pub name: String,
+    pub value: Vec<ComponentValue>,
+    pub important: bool,
+}
+
+// https://www.w3.org/TR/css-syntax-3/#parse-stylesheet
+pub fn parse_a_stylesheet(input: &str) -> Vec<Rule> {
+    let mut parser = Parser::new(tokenize(input));
+
+    // 3. Consume a list of rules from input, with the top-level flag set, and set the stylesheet's value to the result.
+    return parser.consume_a_list_of_rules(true);
+}
+
+// https://www.w3.org/TR/css-syntax-3/#parse-rule
+// Returns None for a syntax error
+pub fn parse_a_rule(input: &str) -> Option<Rule> {
+    let mut parser = Parser::new(tokenize(input));
+
+    // 2. While the next input token from input is a <whitespace-token>, consume the next input token from input.
+    parser.consume_whitespace();
+
+    let rule = match parser.next_input_token() {
+        // 3. If the next input token from input is an <EOF-token>, return a syntax error.
+        ComponentValue::Token(CSSToken::EOF) => return None,
+        // Otherwise, if the next input token from input is an <at-keyword-token>, consume an at-rule from input, and let rule be the return value.
+        ComponentValue::Token(CSSToken::AtKeyword(_)) => Rule::At(parser.consume_an_at_rule()),
+        // Otherwise, consume a qualified rule from input and let rule be the return value. If nothing was returned, return a syntax error.
+        _ => Rule::Qualified(parser.consume_a_qualified_rule()?)
+    };
+
+    // 4. While the next input token from input is a <whitespace-token>, consume the next input token from input.
+    parser.consume_whitespace();
+
+    // 5. If the next input token from input is an <EOF-token>, return rule. Otherwise, return a syntax error.
+    match parser.next_input_token() {
+        ComponentValue::Token(CSSToken::EOF) => Some(rule),
+        _ => None
+    }
+}
+
+// https://www.w3.org/TR/css-syntax-3/#parse-list-of-declarations
+// TODO: At-rules in a list of declarations are dropped, nothing uses them yet
+pub fn parse_a_list_of_declarations(input: &str) -> Vec<Declaration> {
+    return Parser::new(tokenize(input)).consume_a_list_of_declarations();
+}
+
+// The contents of a style rule's block are parsed as a list of declarations
+pub fn parse_a_list_of_declarations_from_values(values: Vec<ComponentValue>) -> Vec<Declaration> {
+    return Parser::new(values).consume_a_list_of_declarations();
+}
+
+// https://www.w3.org/TR/css-syntax-3/#parse-list-of-component-values
+pub fn parse_a_list_of_component_values(input: &str) -> Vec<ComponentValue> {
+    let mut parser = Parser::new(tokenize(input));
+    let mut values = Vec::new();
+
+    // 2. Repeatedly consume a component value from input until an <EOF-token> is returned, appending the returned values (except the final <EOF-token>) into a list. Return the list.
+    loop {
+        match parser.consume_a_component_value() {
+            ComponentValue::Token(CSSToken::EOF) => return values,
+            value => values.push(value)
+        }
+    }
+}
+
+// https://www.w3.org/TR/css-syntax-3/#serialization
+// FIXME: Comments are not inserted between tokens that would otherwise tokenize differently when read back
+pub fn serialize_component_values(values: &[ComponentValue]) -> String {
+    let mut serialization = String::new();
+    for value in values {
+        match value {
+            ComponentValue::Token(token) => serialization.push_str(&serialize_token(token)),
+            ComponentValue::Function(function) => {
+                serialization.push_str(&serialize_an_identifier(&function.name));
+                serialization.push('(');
+                serialization.push_str(&serialize_component_values(&function.value));
+                serialization.push(')');
+            },
+            ComponentValue::SimpleBlock(block) => {
+                let (opening, closing) = match block.associated_token {
+                    CSSToken::LeftCurlyBracket => ("{", "}"),
+                    CSSToken::LeftSquareBracket => ("[", "]"),
+                    _ => ("(", ")")
+                };
+                serialization.push_str(opening);
+                serialization.push_str(&serialize_component_values(&block.value));
+                serialization.push_str(closing);
+            }
+        }
+    }
+    return serialization;
+}
+
+fn serialize_token(token: &CSSToken) -> String {
+    match token {
+        CSSToken::Ident(value) => serialize_an_identifier(value),
+        CSSToken::Function(name) => format!("{}(", serialize_an_identifier(name)),
+        CSSToken::AtKeyword(name) => format!("@{}", serialize_an_identifier(name)),
+        CSSToken::Hash(value, _) => format!("#{}", serialize_a_name(value)),
+        CSSToken::String(value) => serialize_a_string(value),
+        CSSToken::BadString => String::new(),
+        CSSToken::Url(value) => format!("url({})", serialize_a_string(value)),
+        CSSToken::BadUrl => String::from("url()"),
+        CSSToken::Delim('\\') => String::from("\\\n"),
+        CSSToken::Delim(character) => character.to_string(),
+        CSSToken::Number(number) => number.representation.clone(),
+        CSSToken::Percentage(number) => format!("{}%", number.representation),
+        CSSToken::Dimension(number, unit) => format!("{}{}", number.representation, serialize_a_name(unit)),
+        CSSToken::Whitespace => String::from(" "),
+        CSSToken::CDO => String::from("<!--"),
+        CSSToken::CDC => String::from("-->"),
+        CSSToken::Colon => String::from(":"),
+        CSSToken::Semicolon => String::from(";"),
+        CSSToken::Comma => String::from(","),
+        CSSToken::LeftSquareBracket => String::from("["),
+        CSSToken::RightSquareBracket => String::from("]"),
+        CSSToken::LeftParenthesis => String::from("("),
+        CSSToken::RightParenthesis => String::from(")"),
+        CSSToken::LeftCurlyBracket => String::from("{"),
+        CSSToken::RightCurlyBracket => String::from("}"),
+        CSSToken::EOF => String::new(),
+    }
+}
+
+// https://drafts.csswg.org/cssom/#serialize-an-identifier
+pub fn serialize_an_identifier(identifier: &str) -> String {
+    let characters: Vec<char> = identifier.chars().collect();
+    let mut serialization = String::new();
+    for (index, &character) in characters.iter().enumerate() {
+        match character {
+            // If the character is NULL (U+0000), then the REPLACEMENT CHARACTER (U+FFFD).
+            '\u{0}' => serialization.push('\u{FFFD}'),
+            // If the character is in the range [\1-\1f] (U+0001 to U+001F) or is U+007F, then the character escaped as code point.
+            '\u{1}'..='\u{1F}' | '\u{7F}' => serialization.push_str(&format!("\\{:x} ", character as u32)),
+            // If the character is the first character and is in the range [0-9] (U+0030 to U+0039), then the character escaped as code point.
+            '0'..='9' if index == 0 => serialization.push_str(&format!("\\{:x} ", character as u32)),
+            // If the character is the second character and is in the range [0-9] (U+0030 to U+0039) and the first character is a "-" (U+002D), then the character escaped as code point.
+            '0'..='9' if index == 1 && characters[0] == '-' => serialization.push_str(&format!("\\{:x} ", character as u32)),
+            // If the character is the first character and is a "-" (U+002D), and there is no second character, then the escaped character.
+            '-' if characters.len() == 1 => serialization.push_str("\\-"),
+            // If the character is not handled by one of the above rules and is greater than or equal to U+0080, is "-" (U+002D) or "_" (U+005F), or is in one of the ranges [0-9], [A-Z], or [a-z], then the character itself.
+            character if character as u32 >= 0x80 || character == '-' || character == '_' || character.is_ascii_alphanumeric() => serialization.push(character),
+            // Otherwise, the escaped character.
+            character => {
+                serialization.push('\\');
+                serialization.push(character);
+            }
+        }
+    }
+    return serialization;
+}
+
+// A name may start with a digit, e.g. the value of a hash token or the unit of a dimension
+fn serialize_a_name(name: &str) -> String {
+    let mut serialization = String::new();
+    for character in name.chars() {
+        if is_ident_code_point(character) {
+            serialization.push(character);
+        } else {
+            serialization.push_str(&serialize_an_identifier(&character.to_string()));
+        }
+    }
+    return serialization;
+}
+
+// https://drafts.csswg.org/cssom/#serialize-a-string
+pub fn serialize_a_string(string: &str) -> String {
+    let mut serialization = String::from("\"");
+    for character in string.chars() {
+        match character {
+            '\u{0}' => serialization.push('\u{FFFD}'),
+            '\u{1}'..='\u{1F}' | '\u{7F}' => serialization.push_str(&format!("\\{:x} ", character as u32)),
+            '"' | '\\' => {
+                serialization.push('\\');
+                serialization.push(character);
+            },
+            character => serialization.push(character)
+        }
+    }
+    serialization.push('"');
+    return serialization;
+}
+
+// https://www.w3.org/TR/css-syntax-3/#ident-start-code-point
+fn is_ident_start_code_point(character: char) -> bool {
+    return character.is_ascii_alphabetic() || character as u32 >= 0x80 || character == '_';
+}
+
+// https://www.w3.org/TR/css-syntax-3/#ident-code-point
+fn is_ident_code_point(character: char) -> bool {
+    return is_ident_start_code_point(character) || character.is_ascii_digit() || character == '-';
+}
+
+// https://www.w3.org/TR/css-syntax-3/#whitespace
+// Carriage returns and form feeds have been turned into newlines by preprocessing
+fn is_whitespace(character: Option<char>) -> bool {
+    return matches!(character, Some('\n' | '\t' | ' '));
+}
+
+// https://www.w3.org/TR/css-syntax-3/#non-printable-code-point
+fn is_non_printable_code_point(character: char) -> bool {
+    return matches!(character, '\u{0}'..='\u{8}' | '\u{B}' | '\u{E}'..='\u{1F}' | '\u{7F}');
+}
+
+// https://www.w3.org/TR/css-syntax-3/#starts-with-a-valid-escape
+fn is_valid_escape(first: Option<char>, second: Option<char>) -> bool {
+    // 1. If the first code point is not U+005C REVERSE SOLIDUS (\), return false.
+    // 2. Otherwise, if the second code point is a newline, return false.
+    // 3. Otherwise, return true.
+    return first == Some('\\') && second != Some('\n');
+}
+
+// https://www.w3.org/TR/css-syntax-3/#would-start-an-identifier
+fn would_start_an_ident_sequence(first: Option<char>, second: Option<char>, third: Option<char>) -> bool {
+    match first {
+        // If the second code point is an ident-start code point or a U+002D HYPHEN-MINUS, or the second and third code points are a valid escape, return true. Otherwise, return false.
+        Some('-') => second.is_some_and(|second| is_ident_start_code_point(second) || second == '-') || is_valid_escape(second, third),
+        // If the first and second code points are a valid escape, return true. Otherwise, return false.
+        Some('\\') => is_valid_escape(first, second),
+        // Return true.
+        Some(first) if is_ident_start_code_point(first) => true,
+        // Return false.
+        _ => false
+    }
+}
+
+// https://www.w3.org/TR/css-syntax-3/#starts-with-a-number
+fn would_start_a_number(first: Option<char>, second: Option<char>, third: Option<char>) -> bool {
+    match first {
+        // If the second code point is a digit, return true. Otherwise, if the second code point is a U+002E FULL STOP (.) and the third code point is a digit, return true. Otherwise, return false.
+        Some('+' | '-') => second.is_some_and(|second| second.is_ascii_digit()) || (second == Some('.') && third.is_some_and(|third| third.is_ascii_digit())),
+        // If the second code point is a digit, return true. Otherwise, return false.
+        Some('.') => second.is_some_and(|second| second.is_ascii_digit()),
+        Some(first) => first.is_ascii_digit(),
+        None => false
+    }
+}
+
+// https://www.w3.org/TR/css-syntax-3/#css-tokenize
+fn tokenize(input: &str) -> Vec<ComponentValue> {
+    // https://www.w3.org/TR/css-syntax-3/#input-preprocessing
+    // Replace any U+000D CARRIAGE RETURN (CR) code points, U+000C FORM FEED (FF) code points, or pairs of U+000D CARRIAGE RETURN (CR) followed by U+000A LINE FEED (LF) in input by a single U+000A LINE FEED (LF) code point.
+    // Replace any U+0000 NULL or surrogate code points in input with U+FFFD REPLACEMENT CHARACTER (�).
+    let input = input.replace("\r\n", "\n").replace(['\r', '\u{C}'], "\n").replace('\u{0}', "\u{FFFD}");
+
+    let mut tokenizer = Tokenizer { input: input.chars().collect(), position: 0 };
+    let mut tokens = Vec::new();
+    loop {
+        let token = tokenizer.consume_a_token();
+        if token == CSSToken::EOF {
+            return tokens;
+        }
+        tokens.push(ComponentValue::Token(token));
+    }
+}
+
+struct Tokenizer {
+    input: Vec<char>,
+    position: usize,
+}
+
+impl Tokenizer {
+    // The code point offset code points after the next input code point, None is EOF
+    fn peek(&self, offset: usize) -> Option<char> {
+        return self.input.get(self.position + offset).copied();
+    }
+
+    fn consume(&mut self) -> Option<char> {
+        let character = self.peek(0);
+        self.position += 1;
+        return character;
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#reconsume-the-current-input-code-point
+    fn reconsume(&mut self) {
+        self.position -= 1;
+    }
+
+    fn consume_whitespace(&mut self) {
+        while is_whitespace(self.peek(0)) {
+            self.position += 1;
+        }
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-token
+    fn consume_a_token(&mut self) -> CSSToken {
+        // 1. Consume comments.
+        self.consume_comments();
+
+        // 2. Consume the next input code point.
+        let current = self.consume();
+        match current {
+            // whitespace: Consume as much whitespace as possible. Return a <whitespace-token>.
+            Some('\n' | '\t' | ' ') => {
+                self.consume_whitespace();
+                return CSSToken::Whitespace;
+            },
+            // U+0022 QUOTATION MARK ("), U+0027 APOSTROPHE ('): Consume a string token and return it.
+            Some(ending @ ('"' | '\'')) => {
+                return self.consume_a_string_token(ending);
+            },
+            // U+0023 NUMBER SIGN (#)
+            Some('#') => {
+                // If the next input code point is an ident code point or the next two input code points are a valid escape, then:
+                if self.peek(0).is_some_and(is_ident_code_point) || is_valid_escape(self.peek(0), self.peek(1)) {
+                    // 2. If the next 3 input code points would start an ident sequence, set the <hash-token>'s type flag to "id".
+                    let type_ = match would_start_an_ident_sequence(self.peek(0), self.peek(1), self.peek(2)) {
+                        true => HashType::Id,
+                        false => HashType::Unrestricted
+                    };
+
+                    // 3. Consume an ident sequence, and set the <hash-token>'s value to the returned string.
+                    return CSSToken::Hash(self.consume_an_ident_sequence(), type_);
+                }
+
+                // Otherwise, return a <delim-token> with its value set to the current input code point.
+                return CSSToken::Delim('#');
+            },
+            Some('(') => return CSSToken::LeftParenthesis,
+            Some(')') => return CSSToken::RightParenthesis,
+            // U+002B PLUS SIGN (+): If the input stream starts with a number, reconsume the current input code point, consume a numeric token, and return it.
+            Some('+') => {
+                if would_start_a_number(current, self.peek(0), self.peek(1)) {
+                    self.reconsume();
+                    return self.consume_a_numeric_token();
+                }
+                return CSSToken::Delim('+');
+            },
+            Some(',') => return CSSToken::Comma,
+            // U+002D HYPHEN-MINUS (-)
+            Some('-') => {
+                // If the input stream starts with a number, reconsume the current input code point, consume a numeric token, and return it.
+                if would_start_a_number(current, self.peek(0), self.peek(1)) {
+                    self.reconsume();
+                    return self.consume_a_numeric_token();
+                }
+
+                // Otherwise, if the next 2 input code points are U+002D HYPHEN-MINUS U+003E GREATER-THAN SIGN (->), consume them and return a <CDC-token>.
+                if self.peek(0) == Some('-') && self.peek(1) == Some('>') {
+                    self.position += 2;
+                    return CSSToken::CDC;
+                }
+
+                // Otherwise, if the input stream starts with an ident sequence, reconsume the current input code point, consume an ident-like token, and return it.
+                if would_start_an_ident_sequence(current, self.peek(0), self.peek(1)) {
+                    self.reconsume();
+                    return self.consume_an_ident_like_token();
+                }
+
+                return CSSToken::Delim('-');
+            },
+            // U+002E FULL STOP (.): If the input stream starts with a number, reconsume the current input code point, consume a numeric token, and return it.
+            Some('.') => {
+                if would_start_a_number(current, self.peek(0), self.peek(1)) {
+                    self.reconsume();
+                    return self.consume_a_numeric_token();
+                }
+                return CSSToken::Delim('.');
+            },
+            Some(':') => return CSSToken::Colon,
+            Some(';') => return CSSToken::Semicolon,
+            // U+003C LESS-THAN SIGN (<): If the next 3 input code points are U+0021 EXCLAMATION MARK U+002D HYPHEN-MINUS U+002D HYPHEN-MINUS (!--), consume them and return a <CDO-token>.
+            Some('<') => {
+                if self.peek(0) == Some('!') && self.peek(1) == Some('-') && self.peek(2) == Some('-') {
+                    self.position += 3;
+                    return CSSToken::CDO;
+                }
+                return CSSToken::Delim('<');
+            },
+            // U+0040 COMMERCIAL AT (@): If the next 3 input code points would start an ident sequence, consume an ident sequence, create an <at-keyword-token> with its value set to the returned value, and return it.
+            Some('@') => {
+                if would_start_an_ident_sequence(self.peek(0), self.peek(1), self.peek(2)) {
+                    return CSSToken::AtKeyword(self.consume_an_ident_sequence());
+                }
+                return CSSToken::Delim('@');
+            },
+            Some('[') => return CSSToken::LeftSquareBracket,
+            // U+005C REVERSE SOLIDUS (\): If the input stream starts with a valid escape, reconsume the current input code point, consume an ident-like token, and return it.
+            // Otherwise, this is a parse error. Return a <delim-token> with its value set to the current input code point.
+            Some('\\') => {
+                if is_valid_escape(current, self.peek(0)) {
+                    self.reconsume();
+                    return self.consume_an_ident_like_token();
+                }
+                return CSSToken::Delim('\\');
+            },
+            Some(']') => return CSSToken::RightSquareBracket,
+            Some('{') => return CSSToken::LeftCurlyBracket,
+            Some('}') => return CSSToken::RightCurlyBracket,
+            // digit: Reconsume the current input code point, consume a numeric token, and return it.
+            Some(character) if character.is_ascii_digit() => {
+                self.reconsume();
+                return self.consume_a_numeric_token();
+            },
+            // ident-start code point: Reconsume the current input code point, consume an ident-like token, and return it.
+            Some(character) if is_ident_start_code_point(character) => {
+                self.reconsume();
+                return self.consume_an_ident_like_token();
+            },
+            // anything else: Return a <delim-token> with its value set to the current input code point.
+            Some(character) => return CSSToken::Delim(character),
+            // EOF: Return an <EOF-token>.
+            None => return CSSToken::EOF
+        }
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-comment
+    fn consume_comments(&mut self) {
+        // If the next two input code point are U+002F SOLIDUS (/) followed by a U+002A ASTERISK (*), consume them and all following code points up to and including the first U+002A ASTERISK (*) followed by a U+002F SOLIDUS (/), or up to an EOF code point.
+        // Repeat until the next two input code points are not a comment.
+        while self.peek(0) == Some('/') && self.peek(1) == Some('*') {
+            self.position += 2;
+            loop {
+                match self.consume() {
+                    Some('*') if self.peek(0) == Some('/') => {
+                        self.position += 1;
+                        break;
+                    },
+                    Some(_) => {},
+                    None => {
+                        self.reconsume();
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-numeric-token
+    fn consume_a_numeric_token(&mut self) -> CSSToken {
+        // 1. Consume a number and let number be the result.
+        let number = self.consume_a_number();
+
+        // 2. If the next 3 input code points would start an ident sequence, then:
+        if would_start_an_ident_sequence(self.peek(0), self.peek(1), self.peek(2)) {
+            // Consume an ident sequence. Set the <dimension-token>'s unit to the returned value. Return the <dimension-token>.
+            let unit = self.consume_an_ident_sequence();
+            return CSSToken::Dimension(number, unit);
+        }
+
+        // 3. Otherwise, if the next input code point is U+0025 PERCENTAGE SIGN (%), consume it. Create a <percentage-token> with the same value as number, and return it.
+        if self.peek(0) == Some('%') {
+            self.position += 1;
+            return CSSToken::Percentage(number);
+        }
+
+        // 4. Otherwise, create a <number-token> with the same value and type flag as number, and return it.
+        return CSSToken::Number(number);
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-number
+    fn consume_a_number(&mut self) -> NumericValue {
+        // 1. Initially set type to "integer". Let repr be the empty string.
+        let mut is_integer = true;
+        let mut representation = String::new();
+
+        // 2. If the next input code point is U+002B PLUS SIGN (+) or U+002D HYPHEN-MINUS (-), consume it and append it to repr.
+        if let Some(sign @ ('+' | '-')) = self.peek(0) {
+            self.position += 1;
+            representation.push(sign);
+        }
+
+        // 3. While the next input code point is a digit, consume it and append it to repr.
+        self.consume_digits(&mut representation);
+
+        // 4. If the next 2 input code points are U+002E FULL STOP (.) followed by a digit, then:
+        if self.peek(0) == Some('.') && self.peek(1).is_some_and(|character| character.is_ascii_digit()) {
+            // Consume them. Append them to repr. Set type to "number". While the next input code point is a digit, consume it and append it to repr.
+            representation.push('.');
+            self.position += 1;
+            is_integer = false;
+            self.consume_digits(&mut representation);
+        }
+
+        // 5. If the next 2 or 3 input code points are U+0045 LATIN CAPITAL LETTER E (E) or U+0065 LATIN SMALL LETTER E (e), optionally followed by U+002D HYPHEN-MINUS (-) or U+002B PLUS SIGN (+), followed by a digit, then:
+        let is_digit = |character: Option<char>| character.is_some_and(|character| character.is_ascii_digit());
+        if matches!(self.peek(0), Some('e' | 'E')) && (is_digit(self.peek(1)) || (matches!(self.peek(1), Some('+' | '-')) && is_digit(self.peek(2)))) {
+            // Consume them. Append them to repr. Set type to "number". While the next input code point is a digit, consume it and append it to repr.
+            representation.push(self.consume().unwrap());
+            if let Some(sign @ ('+' | '-')) = self.peek(0) {
+                self.position += 1;
+                representation.push(sign);
+            }
+            is_integer = false;
+            self.consume_digits(&mut representation);
+        }
+
+        // 6. Convert repr to a number, and set the value to the returned value.
+        let value = representation.parse::<f64>().unwrap_or(0.0);
+
+        // 7. Return value and type.
+        return NumericValue { value, is_integer, representation };
+    }
+
+    fn consume_digits(&mut self, representation: &mut String) {
+        while let Some(digit) = self.peek(0).filter(|character| character.is_ascii_digit()) {
+            self.position += 1;
+            representation.push(digit);
+        }
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-ident-like-token
+    fn consume_an_ident_like_token(&mut self) -> CSSToken {
+        // Consume an ident sequence, and let string be the result.
+        let string = self.consume_an_ident_sequence();
+
+        // If string's value is an ASCII case-insensitive match for "url", and the next input code point is U+0028 LEFT PARENTHESIS ((), consume it.
+        if string.eq_ignore_ascii_case("url") && self.peek(0) == Some('(') {
+            self.position += 1;
+
+            // While the next two input code points are whitespace, consume the next input code point.
+            while is_whitespace(self.peek(0)) && is_whitespace(self.peek(1)) {
+                self.position += 1;
+            }
+
+            // If the next one or two input code points are U+0022 QUOTATION MARK ("), U+0027 APOSTROPHE ('), or whitespace followed by U+0022 QUOTATION MARK (") or U+0027 APOSTROPHE ('), then create a <function-token> with its value set to string and return it.
+            let is_quote = |character: Option<char>| matches!(character, Some('"' | '\''));
+            if is_quote(self.peek(0)) || (is_whitespace(self.peek(0)) && is_quote(self.peek(1))) {
+                return CSSToken::Function(string);
+            }
+
+            // Otherwise, consume a url token, and return it.
+            return self.consume_a_url_token();
+        }
+
+        // Otherwise, if the next input code point is U+0028 LEFT PARENTHESIS ((), consume it. Create a <function-token> with its value set to string and return it.
+        if self.peek(0) == Some('(') {
+            self.position += 1;
+            return CSSToken::Function(string);
+        }
+
+        // Otherwise, create an <ident-token> with its value set to string and return it.
+        return CSSToken::Ident(string);
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-string-token
+    fn consume_a_string_token(&mut self, ending: char) -> CSSToken {
+        let mut string = String::new();
+
+        // Repeatedly consume the next input code point from the stream:
+        loop {
+            match self.consume() {
+                // ending code point: Return the <string-token>.
+                Some(character) if character == ending => return CSSToken::String(string),
+                // EOF: This is a parse error. Return the <string-token>.
+                None => {
+                    self.reconsume();
+                    return CSSToken::String(string);
+                },
+                // newline: This is a parse error. Reconsume the current input code point, create a <bad-string-token>, and return it.
+                Some('\n') => {
+                    self.reconsume();
+                    return CSSToken::BadString;
+                },
+                // U+005C REVERSE SOLIDUS (\)
+                Some('\\') => {
+                    match self.peek(0) {
+                        // If the next input code point is EOF, do nothing.
+                        None => {},
+                        // Otherwise, if the next input code point is a newline, consume it.
+                        Some('\n') => self.position += 1,
+                        // Otherwise, (the stream starts with a valid escape) consume an escaped code point and append the returned code point to the <string-token>'s value.
+                        Some(_) => string.push(self.consume_an_escaped_code_point())
+                    }
+                },
+                // anything else: Append the current input code point to the <string-token>'s value.
+                Some(character) => string.push(character)
+            }
+        }
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-url-token
+    fn consume_a_url_token(&mut self) -> CSSToken {
+        // 1. Initially create a <url-token> with its value set to the empty string.
+        let mut value = String::new();
+
+        // 2. Consume as much whitespace as possible.
+        self.consume_whitespace();
+
+        // 3. Repeatedly consume the next input code point from the stream:
+        loop {
+            match self.consume() {
+                // U+0029 RIGHT PARENTHESIS ()): Return the <url-token>.
+                Some(')') => return CSSToken::Url(value),
+                // EOF: This is a parse error. Return the <url-token>.
+                None => {
+                    self.reconsume();
+                    return CSSToken::Url(value);
+                },
+                // whitespace: Consume as much whitespace as possible.
+                Some('\n' | '\t' | ' ') => {
+                    self.consume_whitespace();
+
+                    // If the next input code point is U+0029 RIGHT PARENTHESIS ()) or EOF, consume it and return the <url-token> (if EOF was encountered, this is a parse error);
+                    match self.peek(0) {
+                        Some(')') => {
+                            self.position += 1;
+                            return CSSToken::Url(value);
+                        },
+                        None => return CSSToken::Url(value),
+                        // otherwise, consume the remnants of a bad url, create a <bad-url-token>, and return it.
+                        Some(_) => {
+                            self.consume_the_remnants_of_a_bad_url();
+                            return CSSToken::BadUrl;
+                        }
+                    }
+                },
+                // U+0022 QUOTATION MARK ("), U+0027 APOSTROPHE ('), U+0028 LEFT PARENTHESIS ((), non-printable code point:
+                // This is a parse error. Consume the remnants of a bad url, create a <bad-url-token>, and return it.
+                Some(character) if matches!(character, '"' | '\'' | '(') || is_non_printable_code_point(character) => {
+                    self.consume_the_remnants_of_a_bad_url();
+                    return CSSToken::BadUrl;
+                },
+                // U+005C REVERSE SOLIDUS (\)
+                Some('\\') => {
+                    // If the stream starts with a valid escape, consume an escaped code point and append the returned code point to the <url-token>'s value.
+                    if is_valid_escape(Some('\\'), self.peek(0)) {
+                        value.push(self.consume_an_escaped_code_point());
+                    } else {
+                        // Otherwise, this is a parse error. Consume the remnants of a bad url, create a <bad-url-token>, and return it.
+                        self.consume_the_remnants_of_a_bad_url();
+                        return CSSToken::BadUrl;
+                    }
+                },
+                // anything else: Append the current input code point to the <url-token>'s value.
+                Some(character) => value.push(character)
+            }
+        }
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-remnants-of-bad-url
+    fn consume_the_remnants_of_a_bad_url(&mut self) {
+        loop {
+            match self.consume() {
+                // U+0029 RIGHT PARENTHESIS ()), EOF: Return.
+                Some(')') => return,
+                None => {
+                    self.reconsume();
+                    return;
+                },
+                // the input stream starts with a valid escape: Consume an escaped code point.
+                Some('\\') if is_valid_escape(Some('\\'), self.peek(0)) => {
+                    self.consume_an_escaped_code_point();
+                },
+                // anything else: Do nothing.
+                Some(_) => {}
+            }
+        }
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-escaped-code-point
+    // The U+005C REVERSE SOLIDUS (\) has already been consumed
+    fn consume_an_escaped_code_point(&mut self) -> char {
+        match self.consume() {
+            // hex digit: Consume as many hex digits as possible, but no more than 5.
+            Some(character) if character.is_ascii_hexdigit() => {
+                let mut hex_digits = String::from(character);
+                while hex_digits.len() < 6 && self.peek(0).is_some_and(|character| character.is_ascii_hexdigit()) {
+                    hex_digits.push(self.consume().unwrap());
+                }
+
+                // If the next input code point is whitespace, consume it as well.
+                if is_whitespace(self.peek(0)) {
+                    self.position += 1;
+                }
+
+                // Interpret the hex digits as a hexadecimal number. If this number is zero, or is for a surrogate, or is greater than the maximum allowed code point, return U+FFFD REPLACEMENT CHARACTER (�).
+                let code_point = u32::from_str_radix(&hex_digits, 16).unwrap();
+                return match code_point {
+                    0 => '\u{FFFD}',
+                    code_point => char::from_u32(code_point).unwrap_or('\u{FFFD}')
+                };
+            },
+            // EOF: This is a parse error. Return U+FFFD REPLACEMENT CHARACTER (�).
+            None => {
+                self.reconsume();
+                return '\u{FFFD}';
+            },
+            // anything else: Return the current input code point.
+            Some(character) => return character
+        }
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-name
+    fn consume_an_ident_sequence(&mut self) -> String {
+        let mut result = String::new();
+
+        // Repeatedly consume the next input code point from the stream:
+        loop {
+            match self.consume() {
+                // ident code point: Append the code point to result.
+                Some(character) if is_ident_code_point(character) => result.push(character),
+                // the stream starts with a valid escape: Consume an escaped code point. Append the returned code point to result.
+                Some('\\') if is_valid_escape(Some('\\'), self.peek(0)) => {
+                    result.push(self.consume_an_escaped_code_point());
+                },
+                // anything else: Reconsume the current input code point. Return result.
+                _ => {
+                    self.reconsume();
+                    return result;
+                }
+            }
+        }
+    }
+}
+
+// https://www.w3.org/TR/css-syntax-3/#parsing
+// The input is a list of component values, tokens straight from the tokenizer are component values too
+struct Parser {
+    input: Vec<ComponentValue>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(input: Vec<ComponentValue>) -> Parser {
+        return Parser { input, position: 0 };
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#next-input-token
+    fn next_input_token(&self) -> ComponentValue {
+        return self.input.get(self.position).cloned().unwrap_or(ComponentValue::Token(CSSToken::EOF));
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-the-next-input-token
+    fn consume_the_next_input_token(&mut self) -> ComponentValue {
+        let token = self.next_input_token();
+        self.position += 1;
+        return token;
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#reconsume-the-current-input-token
+    fn reconsume_the_current_input_token(&mut self) {
+        self.position -= 1;
+    }
+
+    fn consume_whitespace(&mut self) {
+        while self.next_input_token() == ComponentValue::Token(CSSToken::Whitespace) {
+            self.position += 1;
+        }
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-list-of-rules
+    fn consume_a_list_of_rules(&mut self, top_level: bool) -> Vec<Rule> {
+        // Create an initially empty list of rules.
+        let mut rules = Vec::new();
+
+        // Repeatedly consume the next input token:
+        loop {
+            match self.consume_the_next_input_token() {
+                // <whitespace-token>: Do nothing.
+                ComponentValue::Token(CSSToken::Whitespace) => {},
+                // <EOF-token>: Return the list of rules.
+                ComponentValue::Token(CSSToken::EOF) => return rules,
+                // <CDO-token>, <CDC-token>: If the top-level flag is set, do nothing.
+                ComponentValue::Token(CSSToken::CDO | CSSToken::CDC) if top_level => {},
+                // <at-keyword-token>: Reconsume the current input token. Consume an at-rule, and append the returned value to the list of rules.
+                ComponentValue::Token(CSSToken::AtKeyword(_)) => {
+                    self.reconsume_the_current_input_token();
+                    rules.push(Rule::At(self.consume_an_at_rule()));
+                },
+                // anything else: Reconsume the current input token. Consume a qualified rule. If anything is returned, append it to the list of rules.
+                _ => {
+                    self.reconsume_the_current_input_token();
+                    if let Some(qualified_rule) = self.consume_a_qualified_rule() {
+                        rules.push(Rule::Qualified(qualified_rule));
+                    }
+                }
+            }
+        }
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-at-rule
+    fn consume_an_at_rule(&mut self) -> AtRule {
+        // Consume the next input token. Create a new at-rule with its name set to the value of the current input token, its prelude initially set to an empty list, and its value initially set to nothing.
+        let name = match self.consume_the_next_input_token() {
+            ComponentValue::Token(CSSToken::AtKeyword(name)) => name,
+            _ => unreachable!()
+        };
+        let mut at_rule = AtRule { name, prelude: Vec::new(), block: None };
+
+        // Repeatedly consume the next input token:
+        loop {
+            match self.consume_the_next_input_token() {
+                // <semicolon-token>: Return the at-rule.
+                ComponentValue::Token(CSSToken::Semicolon) => return at_rule,
+                // <EOF-token>: This is a parse error. Return the at-rule.
+                ComponentValue::Token(CSSToken::EOF) => return at_rule,
+                // <{-token>: Consume a simple block and assign it to the at-rule's block. Return the at-rule.
+                ComponentValue::Token(CSSToken::LeftCurlyBracket) => {
+                    at_rule.block = Some(self.consume_a_simple_block(CSSToken::LeftCurlyBracket));
+                    return at_rule;
+                },
+                // simple block with an associated token of <{-token>: Assign the block to the at-rule's block. Return the at-rule.
+                ComponentValue::SimpleBlock(block) if block.associated_token == CSSToken::LeftCurlyBracket => {
+                    at_rule.block = Some(block);
+                    return at_rule;
+                },
+                // anything else: Reconsume the current input token. Consume a component value. Append the returned value to the at-rule's prelude.
+                _ => {
+                    self.reconsume_the_current_input_token();
+                    let value = self.consume_a_component_value();
+                    at_rule.prelude.push(value);
+                }
+            }
+        }
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-qualified-rule
+    fn consume_a_qualified_rule(&mut self) -> Option<QualifiedRule> {
+        // Create a new qualified rule with its prelude initially set to an empty list, and its value initially set to nothing.
+        let mut prelude = Vec::new();
+
+        // Repeatedly consume the next input token:
+        loop {
+            match self.consume_the_next_input_token() {
+                // <EOF-token>: This is a parse error. Return nothing.
+                ComponentValue::Token(CSSToken::EOF) => return None,
+                // <{-token>: Consume a simple block and assign it to the qualified rule's block. Return the qualified rule.
+                ComponentValue::Token(CSSToken::LeftCurlyBracket) => {
+                    let block = self.consume_a_simple_block(CSSToken::LeftCurlyBracket);
+                    return Some(QualifiedRule { prelude, block });
+                },
+                // simple block with an associated token of <{-token>: Assign the block to the qualified rule's block. Return the qualified rule.
+                ComponentValue::SimpleBlock(block) if block.associated_token == CSSToken::LeftCurlyBracket => {
+                    return Some(QualifiedRule { prelude, block });
+                },
+                // anything else: Reconsume the current input token. Consume a component value. Append the returned value to the qualified rule's prelude.
+                _ => {
+                    self.reconsume_the_current_input_token();
+                    let value = self.consume_a_component_value();
+                    prelude.push(value);
+                }
+            }
+        }
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-list-of-declarations
+    fn consume_a_list_of_declarations(&mut self) -> Vec<Declaration> {
+        // Create an initially empty list of declarations.
+        let mut declarations = Vec::new();
+
+        // Repeatedly consume the next input token:
+        loop {
+            match self.consume_the_next_input_token() {
+                // <whitespace-token>, <semicolon-token>: Do nothing.
+                ComponentValue::Token(CSSToken::Whitespace | CSSToken::Semicolon) => {},
+                // <EOF-token>: Return the list of declarations.
+                ComponentValue::Token(CSSToken::EOF) => return declarations,
+                // <at-keyword-token>: Reconsume the current input token. Consume an at-rule. Append the returned rule to the list of declarations.
+                // The at-rule is consumed so its block is skipped, but it is not kept
+                ComponentValue::Token(CSSToken::AtKeyword(_)) => {
+                    self.reconsume_the_current_input_token();
+                    self.consume_an_at_rule();
+                },
+                // <ident-token>
+                ComponentValue::Token(CSSToken::Ident(name)) => {
+                    // Initialize a temporary list initially filled with the current input token.
+                    let mut temporary_list = vec![ComponentValue::Token(CSSToken::Ident(name))];
+
+                    // As long as the next input token is anything other than a <semicolon-token> or <EOF-token>, consume a component value and append it to the temporary list.
+                    while !matches!(self.next_input_token(), ComponentValue::Token(CSSToken::Semicolon | CSSToken::EOF)) {
+                        temporary_list.push(self.consume_a_component_value());
+                    }
+
+                    // Consume a declaration from the temporary list. If anything was returned, append it to the list of declarations.
+                    if let Some(declaration) = Parser::new(temporary_list).consume_a_declaration() {
+                        declarations.push(declaration);
+                    }
+                },
+                // anything else: This is a parse error. Reconsume the current input token.
+                // As long as the next input token is anything other than a <semicolon-token> or <EOF-token>, consume a component value and throw away the returned value.
+                _ => {
+                    self.reconsume_the_current_input_token();
+                    while !matches!(self.next_input_token(), ComponentValue::Token(CSSToken::Semicolon | CSSToken::EOF)) {
+                        self.consume_a_component_value();
+                    }
+                }
+            }
+        }
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-declaration
+    fn consume_a_declaration(&mut self) -> Option<Declaration> {
+        // 1. Consume the next input token. Create a new declaration with its name set to the value of the current input token and its value initially set to an empty list.
+        let name = match self.consume_the_next_input_token() {
+            ComponentValue::Token(CSSToken::Ident(name)) => name,
+            _ => unreachable!()
+        };
+
+        // 2. While the next input token is a <whitespace-token>, consume the next input token.
+        self.consume_whitespace();
+
+        // 3. If the next input token is anything other than a <colon-token>, this is a parse error. Return nothing.
+        if self.next_input_token() != ComponentValue::Token(CSSToken::Colon) {
+            return None;
+        }
+        // Otherwise, consume the next input token.
+        self.position += 1;
+
+        // 4. While the next input token is a <whitespace-token>, consume the next input token.
+        self.consume_whitespace();
+
+        // 5. As long as the next input token is anything other than an <EOF-token>, consume a component value and append it to the declaration's value.
+        let mut value = Vec::new();
+        while self.next_input_token() != ComponentValue::Token(CSSToken::EOF) {
+            value.push(self.consume_a_component_value());
+        }
+
+        // 6. If the last two non-<whitespace-token>s in the declaration's value are a <delim-token> with the value "!" followed by an <ident-token> with a value that is an ASCII case-insensitive match for "important",
+        //    remove them from the declaration's value and set the declaration's important flag to true.
+        let mut important = false;
+        let non_whitespace: Vec<usize> = value.iter().enumerate().filter(|(_, value)| **value != ComponentValue::Token(CSSToken::Whitespace)).map(|(index, _)| index).collect();
+        if let [.., bang, ident] = non_whitespace[..] {
+            let is_bang = value[bang] == ComponentValue::Token(CSSToken::Delim('!'));
+            let is_important = matches!(&value[ident], ComponentValue::Token(CSSToken::Ident(ident)) if ident.eq_ignore_ascii_case("important"));
+            if is_bang && is_important {
+                value.truncate(bang);
+                important = true;
+            }
+        }
+
+        // 7. While the last token in the declaration's value is a <whitespace-token>, remove that token.
+        while value.last() == Some(&ComponentValue::Token(CSSToken::Whitespace)) {
+            value.pop();
+        }
+
+        // 8. Return the declaration.
+        return Some(Declaration { name, value, important });
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-component-value
+    fn consume_a_component_value(&mut self) -> ComponentValue {
+        // Consume the next input token.
+        match self.consume_the_next_input_token() {
+            // If the current input token is a <{-token>, <[-token>, or <(-token>, consume a simple block and return it.
+            ComponentValue::Token(token @ (CSSToken::LeftCurlyBracket | CSSToken::LeftSquareBracket | CSSToken::LeftParenthesis)) => {
+                return ComponentValue::SimpleBlock(self.consume_a_simple_block(token));
+            },
+            // Otherwise, if the current input token is a <function-token>, consume a function and return it.
+            ComponentValue::Token(CSSToken::Function(name)) => {
+                return ComponentValue::Function(self.consume_a_function(name));
+            },
+            // Otherwise, return the current input token.
+            value => return value
+        }
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-simple-block
+    fn consume_a_simple_block(&mut self, associated_token: CSSToken) -> SimpleBlock {
+        // The ending token is the mirror variant of the current input token.
+        let ending_token = match associated_token {
+            CSSToken::LeftCurlyBracket => CSSToken::RightCurlyBracket,
+            CSSToken::LeftSquareBracket => CSSToken::RightSquareBracket,
+            _ => CSSToken::RightParenthesis
+        };
+
+        // Create a simple block with its associated token set to the current input token and with its value initially set to an empty list.
+        let mut block = SimpleBlock { associated_token, value: Vec::new() };
+
+        // Repeatedly consume the next input token and process it as follows:
+        loop {
+            match self.consume_the_next_input_token() {
+                // ending token: Return the block.
+                ComponentValue::Token(token) if token == ending_token => return block,
+                // <EOF-token>: This is a parse error. Return the block.
+                ComponentValue::Token(CSSToken::EOF) => return block,
+                // anything else: Reconsume the current input token. Consume a component value and append it to the value of the block.
+                _ => {
+                    self.reconsume_the_current_input_token();
+                    let value = self.consume_a_component_value();
+                    block.value.push(value);
+                }
+            }
+        }
+    }
+
+    // https://www.w3.org/TR/css-syntax-3/#consume-function
+    fn consume_a_function(&mut self, name: String) -> Function {
+        // Create a function with its name equal to the value of the current input token and with its value initially set to an empty list.
+        let mut function = Function { name, value: Vec::new() };
+
+        // Repeatedly consume the next input token and process as follows:
+        loop {
+            match self.consume_the_next_input_token() {
+                // <)-token>: Return the function.
+                ComponentValue::Token(CSSToken::RightParenthesis) => return function,
+                // <EOF-token>: This is a parse error. Return the function.
+                ComponentValue::Token(CSSToken::EOF) => return function,
+                // anything else: Reconsume the current input token. Consume a component value and append the returned value to the function's value.
+                _ => {
+                    self.reconsume_the_current_input_token();
+                    let value = self.consume_a_component_value();
+                    function.value.push(value);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,938 @@
+use std::fmt;
+use serde::{Serialize, Serializer};
+use crate::counters::{Content, CounterChange, ListStylePosition, ListStyleType};
+use crate::css_parser::{parse_a_list_of_component_values, serialize_component_values, CSSToken, ComponentValue};
+use crate::layout::{Display, WhiteSpace};
+use crate::transitions::EasingFunction;
+
+// https://drafts.csswg.org/css-values-4/
+// https://drafts.csswg.org/css-cascade-4/#specified
+// The values of the core properties parsed into types, so the cascade can drop declarations that are invalid and layout does not work on the text of a value
+// Shorthands are expanded into their longhands before the cascade, so a later longhand overrides part of an earlier shorthand
+// TODO: Only the properties below are typed, the others are kept as text, and calc() and the units other than px and em are not supported
+
+// https://drafts.csswg.org/css-cascade-4/#defaulting-keywords
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CssWideKeyword {
+    Initial,
+    Inherit,
+    Unset,
+}
+
+// https://drafts.csswg.org/css-color-4/#color-syntax
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    // The alpha is from 0 (transparent) to 1 (opaque)
+    Rgba { red: u8, green: u8, blue: u8, alpha: f32 },
+    // https://drafts.csswg.org/css-color-4/#currentcolor-color
+    CurrentColor,
+}
+
+// https://drafts.csswg.org/css-color-4/#serializing-sRGB-values
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Color::Rgba { red, green, blue, alpha } if *alpha >= 1.0 => write!(f, "rgb({}, {}, {})", red, green, blue),
+            Color::Rgba { red, green, blue, alpha } => write!(f, "rgba({}, {}, {}, {})", red, green, blue, alpha),
+            Color::CurrentColor => write!(f, "currentcolor")
+        }
+    }
+}
+
+// A color is serialized the way CSS serializes it, e.g. "rgb(255, 0, 0)"
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return serializer.serialize_str(&self.to_string());
+    }
+}
+
+// https://drafts.csswg.org/css-values-4/#lengths
+// A percentage is of a size that depends on the property, e.g. the width of the containing block for margins
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    Px(f32),
+    Em(f32),
+    Percentage(f32),
+    Auto,
+}
+
+// https://drafts.csswg.org/css-fonts-4/#absolute-size-mapping
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbsoluteSize {
+    XxSmall,
+    XSmall,
+    Small,
+    Medium,
+    Large,
+    XLarge,
+    XxLarge,
+    XxxLarge,
+}
+
+impl AbsoluteSize {
+    // The size in CSS pixels with a medium font size of 16px, scaled by the factors of the absolute size table
+    pub fn px(&self) -> f32 {
+        let scaling_factor = match self {
+            AbsoluteSize::XxSmall => 3.0 / 5.0,
+            AbsoluteSize::XSmall => 3.0 / 4.0,
+            AbsoluteSize::Small => 8.0 / 9.0,
+            AbsoluteSize::Medium => 1.0,
+            AbsoluteSize::Large => 6.0 / 5.0,
+            AbsoluteSize::XLarge => 3.0 / 2.0,
+            AbsoluteSize::XxLarge => 2.0,
+            AbsoluteSize::XxxLarge => 3.0
+        };
+        return 16.0 * scaling_factor;
+    }
+}
+
+// https://drafts.csswg.org/css-fonts-4/#font-size-prop
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FontSize {
+    Absolute(AbsoluteSize),
+    // https://drafts.csswg.org/css-fonts-4/#valdef-font-size-relative-size
+    Larger,
+    Smaller,
+    Length(Length),
+}
+
+// https://drafts.csswg.org/css-backgrounds-3/#border-style
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BorderStyle {
+    None,
+    Hidden,
+    Dotted,
+    Dashed,
+    Solid,
+    Double,
+    Groove,
+    Ridge,
+    Inset,
+    Outset,
+}
+
+impl BorderStyle {
+    pub fn parse(value: &str) -> Option<BorderStyle> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "none" => Some(BorderStyle::None),
+            "hidden" => Some(BorderStyle::Hidden),
+            "dotted" => Some(BorderStyle::Dotted),
+            "dashed" => Some(BorderStyle::Dashed),
+            "solid" => Some(BorderStyle::Solid),
+            "double" => Some(BorderStyle::Double),
+            "groove" => Some(BorderStyle::Groove),
+            "ridge" => Some(BorderStyle::Ridge),
+            "inset" => Some(BorderStyle::Inset),
+            "outset" => Some(BorderStyle::Outset),
+            _ => None
+        }
+    }
+}
+
+// https://drafts.csswg.org/css-position-3/#position-property
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Position {
+    Static,
+    Relative,
+    Absolute,
+    Fixed,
+    Sticky,
+}
+
+impl Position {
+    pub fn parse(value: &str) -> Option<Position> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "static" => Some(Position::Static),
+            "relative" => Some(Position::Relative),
+            "absolute" => Some(Position::Absolute),
+            "fixed" => Some(Position::Fixed),
+            "sticky" => Some(Position::Sticky),
+            _ => None
+        }
+    }
+
+    // https://drafts.csswg.org/css-position-3/#absolute-positioning-containing-block
+    // An absolutely positioned box is taken out of the flow, the boxes after it are laid out as if it were not there
+    pub fn is_out_of_flow(&self) -> bool {
+        return matches!(self, Position::Absolute | Position::Fixed);
+    }
+}
+
+// https://drafts.csswg.org/css-overflow-3/#overflow-control
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Overflow {
+    Visible,
+    Hidden,
+    Clip,
+    Scroll,
+    Auto,
+}
+
+impl Overflow {
+    pub fn parse(value: &str) -> Option<Overflow> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "visible" => Some(Overflow::Visible),
+            "hidden" => Some(Overflow::Hidden),
+            "clip" => Some(Overflow::Clip),
+            "scroll" => Some(Overflow::Scroll),
+            "auto" => Some(Overflow::Auto),
+            _ => None
+        }
+    }
+
+    // https://drafts.csswg.org/css-overflow-3/#scroll-container
+    // A box whose overflow is hidden, scroll or auto clips its content and can be scrolled, by a script if not by the user
+    pub fn is_scrollable(&self) -> bool {
+        return matches!(self, Overflow::Hidden | Overflow::Scroll | Overflow::Auto);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue {
+    CssWideKeyword(CssWideKeyword),
+    Color(Color),
+    Length(Length),
+    FontSize(FontSize),
+    Display(Display),
+    WhiteSpace(WhiteSpace),
+    BorderStyle(BorderStyle),
+    Overflow(Overflow),
+    Position(Position),
+    // https://drafts.csswg.org/css2/#z-index
+    // The value of z-index, None for auto
+    ZIndex(Option<i32>),
+    // https://drafts.csswg.org/css-values-4/#numbers
+    Number(f32),
+    // https://drafts.csswg.org/css-transitions-1/#transition-property-property
+    // The properties a transition-property lists, in lowercase, all stands for every property and none is an empty list
+    TransitionProperties(Vec<String>),
+    // https://drafts.csswg.org/css-values-4/#time
+    // A list of times in milliseconds, e.g. the value of transition-duration
+    Times(Vec<f32>),
+    // https://drafts.csswg.org/css-easing-1/#typedef-easing-function
+    EasingFunctions(Vec<EasingFunction>),
+    // https://drafts.csswg.org/css-lists-3/#counter-properties
+    CounterChanges(Vec<CounterChange>),
+    ListStyleType(ListStyleType),
+    ListStylePosition(ListStylePosition),
+    Content(Content),
+    // The value of a property that is not typed yet, as it was written
+    Unparsed(String),
+}
+
+// https://drafts.csswg.org/css-syntax-3/#parse-grammar
+// The value of property_name, None when it does not match the grammar of the property
+pub fn parse_specified_value(property_name: &str, value: &str) -> Option<PropertyValue> {
+    let value = value.trim();
+    if let Some(keyword) = parse_css_wide_keyword(value) {
+        return Some(PropertyValue::CssWideKeyword(keyword));
+    }
+
+    match property_name {
+        "color" | "background-color" | "border-top-color" | "border-right-color" | "border-bottom-color" | "border-left-color" | "outline-color" | "text-decoration-color" => {
+            return parse_color(value).map(PropertyValue::Color);
+        },
+        // https://drafts.csswg.org/css-box-4/#margin-physical
+        // https://drafts.csswg.org/css-position-3/#insets
+        "margin-top" | "margin-right" | "margin-bottom" | "margin-left" | "top" | "right" | "bottom" | "left" => {
+            return parse_length(value, true, true).map(PropertyValue::Length);
+        },
+        // https://drafts.csswg.org/css-sizing-3/#sizing-values
+        "width" | "height" | "min-width" | "min-height" => return parse_length(value, true, false).map(PropertyValue::Length),
+        // https://drafts.csswg.org/css-box-4/#padding-physical
+        "padding-top" | "padding-right" | "padding-bottom" | "padding-left" => return parse_length(value, false, false).map(PropertyValue::Length),
+        // https://drafts.csswg.org/css-text-3/#text-indent-property
+        "text-indent" => return parse_length(value, false, true).map(PropertyValue::Length),
+        // https://drafts.csswg.org/css-backgrounds-3/#border-width
+        "border-top-width" | "border-right-width" | "border-bottom-width" | "border-left-width" => return parse_border_width(value).map(PropertyValue::Length),
+        "border-top-style" | "border-right-style" | "border-bottom-style" | "border-left-style" => return BorderStyle::parse(value).map(PropertyValue::BorderStyle),
+        "font-size" => return parse_font_size(value).map(PropertyValue::FontSize),
+        "display" => return Display::parse(value).map(PropertyValue::Display),
+        "white-space" => return WhiteSpace::parse(value).map(PropertyValue::WhiteSpace),
+        "overflow-x" | "overflow-y" => return Overflow::parse(value).map(PropertyValue::Overflow),
+        "position" => return Position::parse(value).map(PropertyValue::Position),
+        "z-index" => return parse_z_index(value).map(PropertyValue::ZIndex),
+        // https://drafts.csswg.org/css-color-4/#transparency
+        "opacity" => return parse_alpha_value(value).map(PropertyValue::Number),
+        "transition-property" => return parse_transition_properties(value).map(PropertyValue::TransitionProperties),
+        // https://drafts.csswg.org/css-transitions-1/#transition-duration-property
+        // https://drafts.csswg.org/css-transitions-1/#transition-delay-property
+        // A duration can not be negative, a delay can
+        "transition-duration" => return parse_list(value, |value| parse_time(value).filter(|time| *time >= 0.0)).map(PropertyValue::Times),
+        "transition-delay" => return parse_list(value, parse_time).map(PropertyValue::Times),
+        "transition-timing-function" => return parse_list(value, EasingFunction::parse).map(PropertyValue::EasingFunctions),
+        // https://drafts.csswg.org/css-lists-3/#counter-properties
+        // A counter is reset to 0, incremented by 1 and set to 0 when no integer follows its name
+        "counter-reset" => return CounterChange::parse_list(value, 0, true).map(PropertyValue::CounterChanges),
+        "counter-increment" => return CounterChange::parse_list(value, 1, false).map(PropertyValue::CounterChanges),
+        "counter-set" => return CounterChange::parse_list(value, 0, false).map(PropertyValue::CounterChanges),
+        "list-style-type" => return ListStyleType::parse(value).map(PropertyValue::ListStyleType),
+        "list-style-position" => return ListStylePosition::parse(value).map(PropertyValue::ListStylePosition),
+        "content" => return Content::parse(value).map(PropertyValue::Content),
+        _ => return Some(PropertyValue::Unparsed(value.to_string()))
+    }
+}
+
+// https://drafts.csswg.org/css-cascade-4/#shorthand-property
+// The longhand declarations a declaration stands for, the declaration itself when it is not of a shorthand, and none when its value is invalid
+pub fn longhand_declarations(property_name: &str, value: &str) -> Vec<(String, String)> {
+    let longhands = match shorthand_longhands(property_name) {
+        Some(longhands) => longhands,
+        None => {
+            if parse_specified_value(property_name, value).is_none() {
+                return Vec::new();
+            }
+            return vec![(property_name.to_string(), value.trim().to_string())];
+        }
+    };
+
+    // A CSS-wide keyword on its own sets every longhand to it
+    if parse_css_wide_keyword(value.trim()).is_some() {
+        return longhands.iter().map(|longhand| (longhand.to_string(), value.trim().to_string())).collect();
+    }
+
+    let values = match property_name {
+        "border" | "border-top" | "border-right" | "border-bottom" | "border-left" => border_values(longhands, value),
+        "transition" => transition_values(value),
+        "overflow" => overflow_values(value),
+        "list-style" => list_style_values(value),
+        _ => side_values(value)
+    };
+    let declarations: Vec<(String, String)> = match values {
+        Some(values) => longhands.iter().zip(values).map(|(longhand, value)| (longhand.to_string(), value)).collect(),
+        None => return Vec::new()
+    };
+
+    // The shorthand is invalid when any of its values is invalid for its longhand, e.g. a negative padding
+    if declarations.iter().any(|(longhand, value)| parse_css_wide_keyword(value).is_some() || parse_specified_value(longhand, value).is_none()) {
+        return Vec::new();
+    }
+    return declarations;
+}
+
+// The longhands of a shorthand property, None when property_name is not a shorthand
+pub fn shorthand_longhands(property_name: &str) -> Option<&'static [&'static str]> {
+    match property_name {
+        // https://drafts.csswg.org/css-box-4/#margin-shorthand
+        "margin" => Some(&["margin-top", "margin-right", "margin-bottom", "margin-left"]),
+        // https://drafts.csswg.org/css-box-4/#padding-shorthand
+        "padding" => Some(&["padding-top", "padding-right", "padding-bottom", "padding-left"]),
+        // https://drafts.csswg.org/css-backgrounds-3/#border-shorthands
+        "border-width" => Some(&["border-top-width", "border-right-width", "border-bottom-width", "border-left-width"]),
+        "border-style" => Some(&["border-top-style", "border-right-style", "border-bottom-style", "border-left-style"]),
+        "border-color" => Some(&["border-top-color", "border-right-color", "border-bottom-color", "border-left-color"]),
+        "border-top" => Some(&["border-top-width", "border-top-style", "border-top-color"]),
+        "border-right" => Some(&["border-right-width", "border-right-style", "border-right-color"]),
+        "border-bottom" => Some(&["border-bottom-width", "border-bottom-style", "border-bottom-color"]),
+        "border-left" => Some(&["border-left-width", "border-left-style", "border-left-color"]),
+        "border" => Some(&[
+            "border-top-width", "border-right-width", "border-bottom-width", "border-left-width",
+            "border-top-style", "border-right-style", "border-bottom-style", "border-left-style",
+            "border-top-color", "border-right-color", "border-bottom-color", "border-left-color"
+        ]),
+        // https://drafts.csswg.org/css-transitions-1/#transition-shorthand-property
+        "transition" => Some(&["transition-property", "transition-duration", "transition-timing-function", "transition-delay"]),
+        // https://drafts.csswg.org/css-overflow-3/#overflow-properties
+        "overflow" => Some(&["overflow-x", "overflow-y"]),
+        // https://drafts.csswg.org/css-lists-3/#list-style-property
+        "list-style" => Some(&["list-style-position", "list-style-image", "list-style-type"]),
+        _ => None
+    }
+}
+
+// The shorthands, in the order they are preferred in when a declaration block is serialized, a shorthand before the ones whose longhands are a part of its own
+pub const SHORTHANDS: &[&str] = &[
+    "border", "border-top", "border-right", "border-bottom", "border-left", "border-width", "border-style", "border-color",
+    "margin", "padding", "transition", "overflow", "list-style"
+];
+
+// https://drafts.csswg.org/cssom/#serialize-a-css-value
+// The value of a shorthand given the values of its longhands, in the order shorthand_longhands gives them, None when the shorthand can not represent them
+// TODO: The longhands that have their initial values are not left out, e.g. border: 1px solid currentcolor rather than border: 1px solid
+pub fn shorthand_value(property_name: &str, values: &[&str]) -> Option<String> {
+    // A CSS-wide keyword is only the value of the shorthand when every longhand has it
+    if values.iter().any(|value| parse_css_wide_keyword(value).is_some()) {
+        return match values.iter().all(|value| value.eq_ignore_ascii_case(values[0])) {
+            true => Some(values[0].to_string()),
+            false => None
+        };
+    }
+
+    match (property_name, values) {
+        ("margin" | "padding" | "border-width" | "border-style" | "border-color", [top, right, bottom, left]) => {
+            // The fewest values that set the same sides, the reverse of side_values
+            let side_values = match (top == bottom, right == left) {
+                (true, true) if top == right => vec![*top],
+                (true, true) => vec![*top, *right],
+                (false, true) => vec![*top, *right, *bottom],
+                _ => vec![*top, *right, *bottom, *left]
+            };
+            return Some(side_values.join(" "));
+        },
+        ("border-top" | "border-right" | "border-bottom" | "border-left" | "list-style", _) => return Some(values.join(" ")),
+        // Every side has to be the same, as border sets every side to one width, style and color
+        ("border", _) if values.len() == 12 => {
+            let sides = values.chunks(4).map(|side| side.iter().all(|value| *value == side[0]).then_some(side[0])).collect::<Option<Vec<&str>>>()?;
+            return Some(sides.join(" "));
+        },
+        ("overflow", [x, y]) => return Some(if x == y { x.to_string() } else { format!("{} {}", x, y) }),
+        // Each transition of the list is made of the values at its index in the lists of the longhands
+        ("transition", _) => {
+            let lists: Vec<Vec<&str>> = values.iter().map(|value| value.split(',').map(str::trim).collect()).collect();
+            if lists.iter().any(|list| list.len() != lists[0].len()) {
+                return None;
+            }
+            let transitions: Vec<String> = (0..lists[0].len()).map(|index| lists.iter().map(|list| list[index]).collect::<Vec<&str>>().join(" ")).collect();
+            return Some(transitions.join(", "));
+        },
+        _ => return None
+    }
+}
+
+// The values of the top, right, bottom and left longhands of a shorthand such as margin
+// If there is only one component value, it applies to all sides. If there are two values, the top and bottom are set to the first value and the right and left are set to the second.
+// If there are three values, the top is set to the first value, the left and right are set to the second, and the bottom is set to the third. If there are four values they apply to the top, right, bottom, and left, respectively.
+fn side_values(value: &str) -> Option<Vec<String>> {
+    let components = component_texts(value);
+    let side_values = match components.as_slice() {
+        [all] => [all, all, all, all],
+        [vertical, horizontal] => [vertical, horizontal, vertical, horizontal],
+        [top, horizontal, bottom] => [top, horizontal, bottom, horizontal],
+        [top, right, bottom, left] => [top, right, bottom, left],
+        _ => return None
+    };
+    return Some(side_values.iter().map(|value| value.to_string()).collect());
+}
+
+// https://drafts.csswg.org/css-overflow-3/#propdef-overflow
+// The first value is overflow-x and the second overflow-y, if the second value is omitted it is copied from the first
+fn overflow_values(value: &str) -> Option<Vec<String>> {
+    match component_texts(value).as_slice() {
+        [both] => return Some(vec![both.clone(), both.clone()]),
+        [x, y] => return Some(vec![x.clone(), y.clone()]),
+        _ => return None
+    }
+}
+
+// https://drafts.csswg.org/css-lists-3/#list-style-property
+// <'list-style-position'> || <'list-style-image'> || <'list-style-type'>, in any order and each at most once, those left out are set to their initial values
+// none is the image or the type, whichever is not given otherwise, and both when neither is
+fn list_style_values(value: &str) -> Option<Vec<String>> {
+    let (mut position, mut image, mut list_style_type) = (None, None, None);
+    let mut none_count = 0;
+    for component in components(value) {
+        let text = serialize_component_values(std::slice::from_ref(&component));
+        let slot = match &component {
+            ComponentValue::Token(CSSToken::Ident(ident)) if ident.eq_ignore_ascii_case("none") => {
+                none_count += 1;
+                continue;
+            },
+            ComponentValue::Token(CSSToken::Url(_)) => &mut image,
+            ComponentValue::Function(function) if function.name.eq_ignore_ascii_case("url") => &mut image,
+            _ if ListStylePosition::parse(&text).is_some() => &mut position,
+            _ if ListStyleType::parse(&text).is_some() => &mut list_style_type,
+            _ => return None
+        };
+        if slot.is_some() {
+            return None;
+        }
+        *slot = Some(text);
+    }
+
+    match none_count {
+        0 => (),
+        1 if list_style_type.is_none() => list_style_type = Some("none".to_string()),
+        1 if image.is_none() => image = Some("none".to_string()),
+        2 if list_style_type.is_none() && image.is_none() => {
+            list_style_type = Some("none".to_string());
+            image = Some("none".to_string());
+        },
+        _ => return None
+    }
+    return Some(vec![position.unwrap_or("outside".to_string()), image.unwrap_or("none".to_string()), list_style_type.unwrap_or("disc".to_string())]);
+}
+
+// https://drafts.csswg.org/css-backgrounds-3/#propdef-border
+// <line-width> || <line-style> || <color>, in any order and each at most once, those left out are set to their initial values
+// The values of longhands, which are the width, style and color longhands of one or more sides
+fn border_values(longhands: &[&str], value: &str) -> Option<Vec<String>> {
+    let (mut width, mut style, mut color) = (None, None, None);
+    for component in component_texts(value) {
+        let slot = if BorderStyle::parse(&component).is_some() {
+            &mut style
+        } else if parse_color(&component).is_some() {
+            &mut color
+        } else if parse_border_width(&component).is_some() {
+            &mut width
+        } else {
+            return None;
+        };
+        if slot.replace(component).is_some() {
+            return None;
+        }
+    }
+
+    let width = width.unwrap_or_else(|| String::from("medium"));
+    let style = style.unwrap_or_else(|| String::from("none"));
+    let color = color.unwrap_or_else(|| String::from("currentcolor"));
+    let values = longhands.iter().map(|longhand| {
+        if longhand.ends_with("-width") {
+            return width.clone();
+        }
+        if longhand.ends_with("-style") {
+            return style.clone();
+        }
+        return color.clone();
+    });
+    return Some(values.collect());
+}
+
+// https://drafts.csswg.org/css-transitions-1/#transition-shorthand-property
+// A comma separated list of [ none | <single-transition-property> ] || <time> || <easing-function> || <time>, the first time is the duration and the second the delay
+// The values of the property, duration, timing function and delay longhands, each a list with an entry for every transition
+fn transition_values(value: &str) -> Option<Vec<String>> {
+    let transitions = split_on_commas(value);
+    let (mut properties, mut durations, mut timing_functions, mut delays) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    for transition in &transitions {
+        let (mut property, mut duration, mut timing_function, mut delay) = (None, None, None, None);
+        for component in transition {
+            let text = serialize_component_values(std::slice::from_ref(component));
+            if parse_time(&text).is_some() {
+                let slot = if duration.is_none() { &mut duration } else { &mut delay };
+                if slot.replace(text).is_some() {
+                    return None;
+                }
+            } else if EasingFunction::parse(&text).is_some() {
+                if timing_function.replace(text).is_some() {
+                    return None;
+                }
+            } else if let ComponentValue::Token(CSSToken::Ident(_)) = component {
+                if property.replace(text.to_ascii_lowercase()).is_some() {
+                    return None;
+                }
+            } else {
+                return None;
+            }
+        }
+
+        // none is only valid as the property of the only transition in the list
+        if property.as_deref() == Some("none") && transitions.len() > 1 {
+            return None;
+        }
+        if duration.as_ref().is_some_and(|duration| parse_time(duration).is_some_and(|time| time < 0.0)) {
+            return None;
+        }
+        properties.push(property.unwrap_or_else(|| String::from("all")));
+        durations.push(duration.unwrap_or_else(|| String::from("0s")));
+        timing_functions.push(timing_function.unwrap_or_else(|| String::from("ease")));
+        delays.push(delay.unwrap_or_else(|| String::from("0s")));
+    }
+    return Some(vec![properties.join(", "), durations.join(", "), timing_functions.join(", "), delays.join(", ")]);
+}
+
+// https://drafts.csswg.org/css-transitions-1/#transition-property-property
+// none | <single-transition-property>#, where a single transition property is all or the name of a property
+fn parse_transition_properties(value: &str) -> Option<Vec<String>> {
+    let properties = parse_list(value, |value| match single_component(value)? {
+        ComponentValue::Token(CSSToken::Ident(ident)) => Some(ident.to_ascii_lowercase()),
+        _ => None
+    })?;
+    match properties.as_slice() {
+        [none] if none == "none" => return Some(Vec::new()),
+        _ if properties.iter().any(|property| property == "none" || parse_css_wide_keyword(property).is_some()) => return None,
+        _ => return Some(properties)
+    }
+}
+
+// https://drafts.csswg.org/css-values-4/#comb-comma
+// A comma separated list of values that are each parsed by parse, None when any of them is invalid
+fn parse_list<T>(value: &str, parse: impl Fn(&str) -> Option<T>) -> Option<Vec<T>> {
+    return split_on_commas(value).iter().map(|components| parse(&serialize_component_values(components))).collect();
+}
+
+// The component values of value split at its top level commas, without the whitespace
+fn split_on_commas(value: &str) -> Vec<Vec<ComponentValue>> {
+    let mut lists = vec![Vec::new()];
+    for component in components(value) {
+        match component {
+            ComponentValue::Token(CSSToken::Comma) => lists.push(Vec::new()),
+            component => lists.last_mut().unwrap().push(component)
+        }
+    }
+    return lists;
+}
+
+// https://drafts.csswg.org/css-values-4/#time
+// A time in milliseconds, given in s or ms, e.g. 0.5s or 500ms
+pub fn parse_time(value: &str) -> Option<f32> {
+    match single_component(value)? {
+        ComponentValue::Token(CSSToken::Dimension(number, unit)) => {
+            match unit.to_ascii_lowercase().as_str() {
+                "s" => return Some(number.value as f32 * 1000.0),
+                "ms" => return Some(number.value as f32),
+                _ => return None
+            }
+        },
+        _ => return None
+    }
+}
+
+// https://drafts.csswg.org/css-color-4/#typedef-alpha-value
+// A number or a percentage, values out of the range of 0 to 1 are clamped when they are used
+fn parse_alpha_value(value: &str) -> Option<f32> {
+    match single_component(value)? {
+        ComponentValue::Token(CSSToken::Number(number)) => return Some(number.value as f32),
+        ComponentValue::Token(CSSToken::Percentage(number)) => return Some(number.value as f32 / 100.0),
+        _ => return None
+    }
+}
+
+// https://drafts.csswg.org/css2/#z-index
+// auto | <integer>
+fn parse_z_index(value: &str) -> Option<Option<i32>> {
+    match single_component(value)? {
+        ComponentValue::Token(CSSToken::Ident(ident)) if ident.eq_ignore_ascii_case("auto") => return Some(None),
+        ComponentValue::Token(CSSToken::Number(number)) if number.is_integer => return Some(Some(number.value as i32)),
+        _ => return None
+    }
+}
+
+fn parse_css_wide_keyword(value: &str) -> Option<CssWideKeyword> {
+    match value.to_ascii_lowercase().as_str() {
+        "initial" => Some(CssWideKeyword::Initial),
+        "inherit" => Some(CssWideKeyword::Inherit),
+        "unset" => Some(CssWideKeyword::Unset),
+        _ => None
+    }
+}
+
+// The component values of value without the whitespace between them
+pub(crate) fn components(value: &str) -> Vec<ComponentValue> {
+    return parse_a_list_of_component_values(value).into_iter().filter(|component| !matches!(component, ComponentValue::Token(CSSToken::Whitespace))).collect();
+}
+
+fn component_texts(value: &str) -> Vec<String> {
+    return components(value).iter().map(|component| serialize_component_values(std::slice::from_ref(component))).collect();
+}
+
+// The only component value of value, None when there is not exactly one
+pub(crate) fn single_component(value: &str) -> Option<ComponentValue> {
+    let mut components = components(value);
+    if components.len() != 1 {
+        return None;
+    }
+    return components.pop();
+}
+
+// https://drafts.csswg.org/css-values-4/#lengths
+// A number other than 0 needs a unit, auto is only a length where allow_auto is true and negative lengths where allow_negative is
+pub fn parse_length(value: &str, allow_auto: bool, allow_negative: bool) -> Option<Length> {
+    let length = match single_component(value)? {
+        ComponentValue::Token(CSSToken::Ident(ident)) if allow_auto && ident.eq_ignore_ascii_case("auto") => return Some(Length::Auto),
+        ComponentValue::Token(CSSToken::Number(number)) if number.value == 0.0 => Length::Px(0.0),
+        ComponentValue::Token(CSSToken::Dimension(number, unit)) => {
+            match unit.to_ascii_lowercase().as_str() {
+                "px" => Length::Px(number.value as f32),
+                "em" => Length::Em(number.value as f32),
+                _ => return None
+            }
+        },
+        ComponentValue::Token(CSSToken::Percentage(number)) => Length::Percentage(number.value as f32),
+        _ => return None
+    };
+
+    let negative = match length {
+        Length::Px(value) | Length::Em(value) | Length::Percentage(value) => value < 0.0,
+        Length::Auto => false
+    };
+    if negative && !allow_negative {
+        return None;
+    }
+    return Some(length);
+}
+
+// https://drafts.csswg.org/css-backgrounds-3/#typedef-line-width
+// <length [0,∞]> | thin | medium | thick, the keywords are 1px, 3px and 5px wide
+pub fn parse_border_width(value: &str) -> Option<Length> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "thin" => return Some(Length::Px(1.0)),
+        "medium" => return Some(Length::Px(3.0)),
+        "thick" => return Some(Length::Px(5.0)),
+        _ => {}
+    }
+    if let Some(ComponentValue::Token(CSSToken::Percentage(_))) = single_component(value) {
+        return None;
+    }
+    return parse_length(value, false, false);
+}
+
+// https://drafts.csswg.org/css-fonts-4/#font-size-prop
+// <absolute-size> | <relative-size> | <length-percentage [0,∞]>
+pub fn parse_font_size(value: &str) -> Option<FontSize> {
+    let keyword = match single_component(value)? {
+        ComponentValue::Token(CSSToken::Ident(ident)) => ident.to_ascii_lowercase(),
+        _ => return parse_length(value, false, false).map(FontSize::Length)
+    };
+    let font_size = match keyword.as_str() {
+        "xx-small" => FontSize::Absolute(AbsoluteSize::XxSmall),
+        "x-small" => FontSize::Absolute(AbsoluteSize::XSmall),
+        "small" => FontSize::Absolute(AbsoluteSize::Small),
+        "medium" => FontSize::Absolute(AbsoluteSize::Medium),
+        "large" => FontSize::Absolute(AbsoluteSize::Large),
+        "x-large" => FontSize::Absolute(AbsoluteSize::XLarge),
+        "xx-large" => FontSize::Absolute(AbsoluteSize::XxLarge),
+        "xxx-large" => FontSize::Absolute(AbsoluteSize::XxxLarge),
+        "larger" => FontSize::Larger,
+        "smaller" => FontSize::Smaller,
+        _ => return None
+    };
+    return Some(font_size);
+}
+
+// https://drafts.csswg.org/css-color-4/#typedef-color
+// A named color, a hex color, rgb() or rgba(), transparent or currentcolor
+// TODO: hsl(), hwb() and the other color functions are not supported
+pub fn parse_color(value: &str) -> Option<Color> {
+    match single_component(value)? {
+        ComponentValue::Token(CSSToken::Ident(ident)) => {
+            let ident = ident.to_ascii_lowercase();
+            match ident.as_str() {
+                "currentcolor" => return Some(Color::CurrentColor),
+                // https://drafts.csswg.org/css-color-4/#transparent-color
+                "transparent" => return Some(Color::Rgba { red: 0, green: 0, blue: 0, alpha: 0.0 }),
+                _ => {
+                    let (red, green, blue) = named_color(&ident)?;
+                    return Some(Color::Rgba { red, green, blue, alpha: 1.0 });
+                }
+            }
+        },
+        ComponentValue::Token(CSSToken::Hash(hex, _)) => return parse_hex_color(&hex),
+        ComponentValue::Function(function) if function.name.eq_ignore_ascii_case("rgb") || function.name.eq_ignore_ascii_case("rgba") => return parse_rgb_function(&function.value),
+        _ => return None
+    }
+}
+
+// https://drafts.csswg.org/css-color-4/#hex-notation
+// 3, 4, 6 or 8 hexadecimal digits, a digit of the 3 and 4 digit forms is repeated, e.g. #f80 is #ff8800
+fn parse_hex_color(hex: &str) -> Option<Color> {
+    if !hex.chars().all(|character| character.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digits: Vec<u8> = hex.chars().filter_map(|character| character.to_digit(16)).map(|digit| digit as u8).collect();
+    let channels: Vec<u8> = match digits.len() {
+        3 | 4 => digits.iter().map(|digit| digit * 17).collect(),
+        6 | 8 => digits.chunks(2).map(|pair| pair[0] * 16 + pair[1]).collect(),
+        _ => return None
+    };
+    let alpha = channels.get(3).map_or(1.0, |alpha| *alpha as f32 / 255.0);
+    return Some(Color::Rgba { red: channels[0], green: channels[1], blue: channels[2], alpha });
+}
+
+// https://drafts.csswg.org/css-color-4/#rgb-functions
+// The legacy syntax separates the values with commas, e.g. rgb(255, 0, 0, 0.5), the modern one with spaces and the alpha after a /, e.g. rgb(255 0 0 / 50%)
+// The red, green and blue are all numbers or all percentages, and values out of range are clamped
+fn parse_rgb_function(arguments: &[ComponentValue]) -> Option<Color> {
+    let arguments: Vec<&ComponentValue> = arguments.iter().filter(|argument| !matches!(argument, ComponentValue::Token(CSSToken::Whitespace))).collect();
+    let legacy = arguments.iter().any(|argument| matches!(argument, ComponentValue::Token(CSSToken::Comma)));
+
+    let mut channels = Vec::new();
+    let mut alpha = None;
+    for (index, argument) in arguments.iter().enumerate() {
+        let separator = if legacy { index % 2 == 1 } else { false };
+        match argument {
+            ComponentValue::Token(CSSToken::Comma) if separator => continue,
+            ComponentValue::Token(CSSToken::Delim('/')) if !legacy && channels.len() == 3 && alpha.is_none() => {
+                alpha = Some(arguments.get(index + 1).copied()?);
+                if index + 2 != arguments.len() {
+                    return None;
+                }
+                break;
+            },
+            _ if separator => return None,
+            ComponentValue::Token(token) if channels.len() < 3 => channels.push(token.clone()),
+            ComponentValue::Token(_) if legacy && channels.len() == 3 && alpha.is_none() => alpha = Some(*argument),
+            _ => return None
+        }
+    }
+    if channels.len() != 3 {
+        return None;
+    }
+
+    let percentages = matches!(channels[0], CSSToken::Percentage(_));
+    let mut rgb = [0; 3];
+    for (channel, token) in rgb.iter_mut().zip(&channels) {
+        let value = match token {
+            CSSToken::Number(number) if !percentages => number.value,
+            CSSToken::Percentage(number) if percentages => number.value * 2.55,
+            _ => return None
+        };
+        *channel = value.round().clamp(0.0, 255.0) as u8;
+    }
+
+    let alpha = match alpha {
+        None => 1.0,
+        Some(ComponentValue::Token(CSSToken::Number(number))) => number.value,
+        Some(ComponentValue::Token(CSSToken::Percentage(number))) => number.value / 100.0,
+        Some(_) => return None
+    };
+    return Some(Color::Rgba { red: rgb[0], green: rgb[1], blue: rgb[2], alpha: alpha.clamp(0.0, 1.0) as f32 });
+}
+
+// https://drafts.csswg.org/css-color-4/#named-colors
+fn named_color(name: &str) -> Option<(u8, u8, u8)> {
+    let rgb = match name {
+        "aliceblue" => (0xf0, 0xf8, 0xff),
+        "antiquewhite" => (0xfa, 0xeb, 0xd7),
+        "aqua" => (0x00, 0xff, 0xff),
+        "aquamarine" => (0x7f, 0xff, 0xd4),
+        "azure" => (0xf0, 0xff, 0xff),
+        "beige" => (0xf5, 0xf5, 0xdc),
+        "bisque" => (0xff, 0xe4, 0xc4),
+        "black" => (0x00, 0x00, 0x00),
+        "blanchedalmond" => (0xff, 0xeb, 0xcd),
+        "blue" => (0x00, 0x00, 0xff),
+        "blueviolet" => (0x8a, 0x2b, 0xe2),
+        "brown" => (0xa5, 0x2a, 0x2a),
+        "burlywood" => (0xde, 0xb8, 0x87),
+        "cadetblue" => (0x5f, 0x9e, 0xa0),
+        "chartreuse" => (0x7f, 0xff, 0x00),
+        "chocolate" => (0xd2, 0x69, 0x1e),
+        "coral" => (0xff, 0x7f, 0x50),
+        "cornflowerblue" => (0x64, 0x95, 0xed),
+        "cornsilk" => (0xff, 0xf8, 0xdc),
+        "crimson" => (0xdc, 0x14, 0x3c),
+        "cyan" => (0x00, 0xff, 0xff),
+        "darkblue" => (0x00, 0x00, 0x8b),
+        "darkcyan" => (0x00, 0x8b, 0x8b),
+        "darkgoldenrod" => (0xb8, 0x86, 0x0b),
+        "darkgray" => (0xa9, 0xa9, 0xa9),
+        "darkgreen" => (0x00, 0x64, 0x00),
+        "darkgrey" => (0xa9, 0xa9, 0xa9),
+        "darkkhaki" => (0xbd, 0xb7, 0x6b),
+        "darkmagenta" => (0x8b, 0x00, 0x8b),
+        "darkolivegreen" => (0x55, 0x6b, 0x2f),
+        "darkorange" => (0xff, 0x8c, 0x00),
+        "darkorchid" => (0x99, 0x32, 0xcc),
+        "darkred" => (0x8b, 0x00, 0x00),
+        "darksalmon" => (0xe9, 0x96, 0x7a),
+        "darkseagreen" => (0x8f, 0xbc, 0x8f),
+        "darkslateblue" => (0x48, 0x3d, 0x8b),
+        "darkslategray" => (0x2f, 0x4f, 0x4f),
+        "darkslategrey" => (0x2f, 0x4f, 0x4f),
+        "darkturquoise" => (0x00, 0xce, 0xd1),
+        "darkviolet" => (0x94, 0x00, 0xd3),
+        "deeppink" => (0xff, 0x14, 0x93),
+        "deepskyblue" => (0x00, 0xbf, 0xff),
+        "dimgray" => (0x69, 0x69, 0x69),
+        "dimgrey" => (0x69, 0x69, 0x69),
+        "dodgerblue" => (0x1e, 0x90, 0xff),
+        "firebrick" => (0xb2, 0x22, 0x22),
+        "floralwhite" => (0xff, 0xfa, 0xf0),
+        "forestgreen" => (0x22, 0x8b, 0x22),
+        "fuchsia" => (0xff, 0x00, 0xff),
+        "gainsboro" => (0xdc, 0xdc, 0xdc),
+        "ghostwhite" => (0xf8, 0xf8, 0xff),
+        "gold" => (0xff, 0xd7, 0x00),
+        "goldenrod" => (0xda, 0xa5, 0x20),
+        "gray" => (0x80, 0x80, 0x80),
+        "green" => (0x00, 0x80, 0x00),
+        "greenyellow" => (0xad, 0xff, 0x2f),
+        "grey" => (0x80, 0x80, 0x80),
+        "honeydew" => (0xf0, 0xff, 0xf0),
+        "hotpink" => (0xff, 0x69, 0xb4),
+        "indianred" => (0xcd, 0x5c, 0x5c),
+        "indigo" => (0x4b, 0x00, 0x82),
+        "ivory" => (0xff, 0xff, 0xf0),
+        "khaki" => (0xf0, 0xe6, 0x8c),
+        "lavender" => (0xe6, 0xe6, 0xfa),
+        "lavenderblush" => (0xff, 0xf0, 0xf5),
+        "lawngreen" => (0x7c, 0xfc, 0x00),
+        "lemonchiffon" => (0xff, 0xfa, 0xcd),
+        "lightblue" => (0xad, 0xd8, 0xe6),
+        "lightcoral" => (0xf0, 0x80, 0x80),
+        "lightcyan" => (0xe0, 0xff, 0xff),
+        "lightgoldenrodyellow" => (0xfa, 0xfa, 0xd2),
+        "lightgray" => (0xd3, 0xd3, 0xd3),
+        "lightgreen" => (0x90, 0xee, 0x90),
+        "lightgrey" => (0xd3, 0xd3, 0xd3),
+        "lightpink" => (0xff, 0xb6, 0xc1),
+        "lightsalmon" => (0xff, 0xa0, 0x7a),
+        "lightseagreen" => (0x20, 0xb2, 0xaa),
+        "lightskyblue" => (0x87, 0xce, 0xfa),
+        "lightslategray" => (0x77, 0x88, 0x99),
+        "lightslategrey" => (0x77, 0x88, 0x99),
+        "lightsteelblue" => (0xb0, 0xc4, 0xde),
+        "lightyellow" => (0xff, 0xff, 0xe0),
+        "lime" => (0x00, 0xff, 0x00),
+        "limegreen" => (0x32, 0xcd, 0x32),
+        "linen" => (0xfa, 0xf0, 0xe6),
+        "magenta" => (0xff, 0x00, 0xff),
+        "maroon" => (0x80, 0x00, 0x00),
+        "mediumaquamarine" => (0x66, 0xcd, 0xaa),
+        "mediumblue" => (0x00, 0x00, 0xcd),
+        "mediumorchid" => (0xba, 0x55, 0xd3),
+        "mediumpurple" => (0x93, 0x70, 0xdb),
+        "mediumseagreen" => (0x3c, 0xb3, 0x71),
+        "mediumslateblue" => (0x7b, 0x68, 0xee),
+        "mediumspringgreen" => (0x00, 0xfa, 0x9a),
+        "mediumturquoise" => (0x48, 0xd1, 0xcc),
+        "mediumvioletred" => (0xc7, 0x15, 0x85),
+        "midnightblue" => (0x19, 0x19, 0x70),
+        "mintcream" => (0xf5, 0xff, 0xfa),
+        "mistyrose" => (0xff, 0xe4, 0xe1),
+        "moccasin" => (0xff, 0xe4, 0xb5),
+        "navajowhite" => (0xff, 0xde, 0xad),
+        "navy" => (0x00, 0x00, 0x80),
+        "oldlace" => (0xfd, 0xf5, 0xe6),
+        "olive" => (0x80, 0x80, 0x00),
+        "olivedrab" => (0x6b, 0x8e, 0x23),
+        "orange" => (0xff, 0xa5, 0x00),
+        "orangered" => (0xff, 0x45, 0x00),
+        "orchid" => (0xda, 0x70, 0xd6),
+        "palegoldenrod" => (0xee, 0xe8, 0xaa),
+        "palegreen" => (0x98, 0xfb, 0x98),
+        "paleturquoise" => (0xaf, 0xee, 0xee),
+        "palevioletred" => (0xdb, 0x70, 0x93),
+        "papayawhip" => (0xff, 0xef, 0xd5),
+        "peachpuff" => (0xff, 0xda, 0xb9),
+        "peru" => (0xcd, 0x85, 0x3f),
+        "pink" => (0xff, 0xc0, 0xcb),
+        "plum" => (0xdd, 0xa0, 0xdd),
+        "powderblue" => (0xb0, 0xe0, 0xe6),
+        "purple" => (0x80, 0x00, 0x80),
+        "rebeccapurple" => (0x66, 0x33, 0x99),
+        "red" => (0xff, 0x00, 0x00),
+        "rosybrown" => (0xbc, 0x8f, 0x8f),
+        "royalblue" => (0x41, 0x69, 0xe1),
+        "saddlebrown" => (0x8b, 0x45, 0x13),
+        "salmon" => (0xfa, 0x80, 0x72),
+        "sandybrown" => (0xf4, 0xa4, 0x60),
+        "seagreen" => (0x2e, 0x8b, 0x57),
+        "seashell" => (0xff, 0xf5, 0xee),
+        "sienna" => (0xa0, 0x52, 0x2d),
+        "silver" => (0xc0, 0xc0, 0xc0),
+        "skyblue" => (0x87, 0xce, 0xeb),
+        "slateblue" => (0x6a, 0x5a, 0xcd),
+        "slategray" => (0x70, 0x80, 0x90),
+        "slategrey" => (0x70, 0x80, 0x90),
+        "snow" => (0xff, 0xfa, 0xfa),
+        "springgreen" => (0x00, 0xff, 0x7f),
+        "steelblue" => (0x46, 0x82, 0xb4),
+        "tan" => (0xd2, 0xb4, 0x8c),
+        "teal" => (0x00, 0x80, 0x80),
+        "thistle" => (0xd8, 0xbf, 0xd8),
+        "tomato" => (0xff, 0x63, 0x47),
+        "turquoise" => (0x40, 0xe0, 0xd0),
+        "violet" => (0xee, 0x82, 0xee),
+        "wheat" => (0xf5, 0xde, 0xb3),
+        "white" => (0xff, 0xff, 0xff),
+        "whitesmoke" => (0xf5, 0xf5, 0xf5),
+        "yellow" => (0xff, 0xff, 0x00),
+        "yellowgreen" => (0x9a, 0xcd, 0x32),
+        _ => return None
+    };
+    return Some(rgb);
+}
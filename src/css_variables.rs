@@ -0,0 +1,176 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use indexmap::IndexMap;
+use crate::css_parser::{parse_a_list_of_component_values, serialize_component_values, CSSToken, ComponentValue};
+use crate::cssom::{is_custom_property, CSSStyleSheet};
+use crate::node::{NodeData, RefNode};
+use crate::style::cascade;
+
+// https://drafts.csswg.org/css-variables/
+// Custom properties (e.g. --accent: #f80) and the var() functions that refer to them (e.g. color: var(--accent, black))
+// Custom properties are inherited, and var() is substituted when values are computed, so a declaration with var() in it is only checked against the grammar of its property then
+// TODO: The custom properties of an element are computed again from the root of its tree each time they are needed, rather than once for every element of the document
+
+// https://drafts.csswg.org/css-variables/#using-variables
+pub fn contains_var(value: &str) -> bool {
+    return !var_references(&parse_a_list_of_component_values(value)).is_empty();
+}
+
+// https://drafts.csswg.org/css-variables/#defining-variables
+// The computed values of the custom properties of element, the ones it declares and the ones it inherits from its ancestors, keyed by name
+pub fn custom_properties(element: &RefNode, style_sheets: &[Rc<RefCell<CSSStyleSheet>>]) -> IndexMap<String, String> {
+    let parent = element.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+    let inherited = match parent {
+        Some(parent) if matches!(parent.borrow().data, NodeData::Element(_)) => custom_properties(&parent, style_sheets),
+        _ => IndexMap::new()
+    };
+
+    let declared: IndexMap<String, String> = cascade(element, style_sheets).into_iter()
+        .filter(|(property_name, _)| is_custom_property(property_name))
+        .map(|(property_name, declaration)| (property_name, declaration.value))
+        .collect();
+    let mut resolver = CustomPropertyResolver { declared: &declared, inherited: &inherited, resolved: HashMap::new(), in_progress: Vec::new(), cyclic: HashSet::new() };
+
+    let mut computed = inherited.clone();
+    for property_name in declared.keys() {
+        match resolver.resolve(property_name) {
+            Some(value) => {
+                computed.insert(property_name.to_string(), value);
+            },
+            // The guaranteed-invalid value, which is not inherited either
+            None => {
+                computed.shift_remove(property_name);
+            }
+        }
+    }
+    return computed;
+}
+
+// https://drafts.csswg.org/css-variables/#substitute-a-var
+// The value with each var() in it replaced by the value of the custom property it refers to, or by its fallback when there is no such property
+// None when a var() has neither, which makes the declaration invalid at computed-value time
+pub fn substitute_var(value: &str, custom_properties: &IndexMap<String, String>) -> Option<String> {
+    let lookup = |name: &str| custom_properties.get(name).cloned();
+    return substitute(&parse_a_list_of_component_values(value), &lookup).map(|value| value.trim().to_string());
+}
+
+// Resolves the var() functions in the values of the custom properties an element declares, which can refer to each other
+struct CustomPropertyResolver<'a> {
+    declared: &'a IndexMap<String, String>,
+    inherited: &'a IndexMap<String, String>,
+    // The computed value of each declared custom property resolved so far, None for the guaranteed-invalid value
+    resolved: HashMap<String, Option<String>>,
+    // The custom properties whose references are being resolved, each refers to the one after it
+    in_progress: Vec<String>,
+    cyclic: HashSet<String>,
+}
+
+impl CustomPropertyResolver<'_> {
+    fn resolve(&mut self, name: &str) -> Option<String> {
+        if let Some(value) = self.resolved.get(name) {
+            return value.clone();
+        }
+        let value = match self.declared.get(name) {
+            Some(value) => value,
+            None => return self.inherited.get(name).cloned()
+        };
+
+        // https://drafts.csswg.org/css-variables/#cycles
+        // If there is a cycle in the dependency graph, all the custom properties in the cycle are invalid at computed-value time.
+        if let Some(index) = self.in_progress.iter().position(|in_progress| in_progress == name) {
+            self.cyclic.extend(self.in_progress[index..].iter().cloned());
+            return None;
+        }
+
+        // https://drafts.csswg.org/css-cascade-4/#defaulting-keywords
+        // The initial value of a custom property is the guaranteed-invalid value, and custom properties are inherited
+        let resolved = match value.to_ascii_lowercase().as_str() {
+            "initial" => None,
+            "inherit" | "unset" => self.inherited.get(name).cloned(),
+            _ => {
+                self.in_progress.push(name.to_string());
+                for reference in var_references(&parse_a_list_of_component_values(value)) {
+                    self.resolve(&reference);
+                }
+                self.in_progress.pop();
+
+                match self.cyclic.contains(name) {
+                    true => None,
+                    false => {
+                        let lookup = |reference: &str| match self.declared.contains_key(reference) {
+                            true => self.resolved.get(reference).cloned().flatten(),
+                            false => self.inherited.get(reference).cloned()
+                        };
+                        substitute(&parse_a_list_of_component_values(value), &lookup).map(|value| value.trim().to_string())
+                    }
+                }
+            }
+        };
+        self.resolved.insert(name.to_string(), resolved.clone());
+        return resolved;
+    }
+}
+
+// https://drafts.csswg.org/css-variables/#funcdef-var
+// var( <custom-property-name> , <declaration-value>? )
+// The name of the custom property a var() refers to and its fallback, which is there (though it can be empty) when there is a comma after the name
+fn parse_var_arguments(arguments: &[ComponentValue]) -> Option<(String, Option<&[ComponentValue]>)> {
+    let mut tokens = arguments.iter().enumerate().filter(|(_, argument)| !matches!(argument, ComponentValue::Token(CSSToken::Whitespace)));
+    let name = match tokens.next() {
+        Some((_, ComponentValue::Token(CSSToken::Ident(name)))) if is_custom_property(name) => name.to_string(),
+        _ => return None
+    };
+    match tokens.next() {
+        None => return Some((name, None)),
+        Some((index, ComponentValue::Token(CSSToken::Comma))) => return Some((name, Some(&arguments[index + 1..]))),
+        Some(_) => return None
+    }
+}
+
+// The names of the custom properties the var() functions in values refer to, including those in fallbacks
+fn var_references(values: &[ComponentValue]) -> Vec<String> {
+    let mut references = Vec::new();
+    for value in values {
+        match value {
+            ComponentValue::Function(function) if function.name.eq_ignore_ascii_case("var") => {
+                if let Some((name, fallback)) = parse_var_arguments(&function.value) {
+                    references.push(name);
+                    references.extend(var_references(fallback.unwrap_or_default()));
+                }
+            },
+            ComponentValue::Function(function) => references.extend(var_references(&function.value)),
+            ComponentValue::SimpleBlock(block) => references.extend(var_references(&block.value)),
+            ComponentValue::Token(_) => {}
+        }
+    }
+    return references;
+}
+
+fn substitute(values: &[ComponentValue], lookup: &dyn Fn(&str) -> Option<String>) -> Option<String> {
+    let mut substituted = String::new();
+    for value in values {
+        match value {
+            ComponentValue::Function(function) if function.name.eq_ignore_ascii_case("var") => {
+                let (name, fallback) = parse_var_arguments(&function.value)?;
+                match (lookup(&name), fallback) {
+                    (Some(value), _) => substituted.push_str(&value),
+                    (None, Some(fallback)) => substituted.push_str(substitute(fallback, lookup)?.trim()),
+                    (None, None) => return None
+                }
+            },
+            ComponentValue::Function(function) => {
+                let mut function = function.clone();
+                function.value = parse_a_list_of_component_values(&substitute(&function.value, lookup)?);
+                substituted.push_str(&serialize_component_values(&[ComponentValue::Function(function)]));
+            },
+            ComponentValue::SimpleBlock(block) => {
+                let mut block = block.clone();
+                block.value = parse_a_list_of_component_values(&substitute(&block.value, lookup)?);
+                substituted.push_str(&serialize_component_values(&[ComponentValue::SimpleBlock(block)]));
+            },
+            ComponentValue::Token(_) => substituted.push_str(&serialize_component_values(std::slice::from_ref(value)))
+        }
+    }
+    return Some(substituted);
+}
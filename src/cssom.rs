@@ -0,0 +1,828 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::css_parser::{parse_a_list_of_component_values, parse_a_list_of_declarations, parse_a_list_of_declarations_from_values, parse_a_rule, parse_a_stylesheet, serialize_component_values, Declaration, QualifiedRule, Rule};
+use crate::css_values::{longhand_declarations, shorthand_longhands, shorthand_value, SHORTHANDS};
+use crate::css_variables::contains_var;
+use crate::dom_bindings::{argument_to_dom_string, create_interface_prototype_object, install_interface_object, wrap_optional_node};
+use crate::webidl::create_platform_object;
+use crate::interpreter::{completion_value, create_normal_completion, create_value_completion, CompletionRecord, Interpreter, JSValue, NativeFunction, PlatformObject, ReferenceRecordOrJsValue};
+use crate::node::{descendants, set_attribute_value, text_content, NodeData, RefNode, WeakNode};
+
+// https://drafts.csswg.org/cssom/
+// TODO: Only style rules are kept, at-rules such as @media and @import are dropped and link elements do not load style sheets
+// Changes to the rules of a style sheet are noticed by layout comparing the rules with those it last laid the document out with, changes to a style attribute invalidate the style of its element
+
+// https://drafts.csswg.org/cssom/#css-style-sheet
+pub struct CSSStyleSheet {
+    // https://drafts.csswg.org/cssom/#concept-css-style-sheet-owner-node
+    pub owner_node: Option<WeakNode>,
+    // https://drafts.csswg.org/cssom/#concept-css-style-sheet-css-rules
+    pub css_rules: Vec<Rc<RefCell<CSSStyleRule>>>,
+    // The text of the style block the sheet was created from, the sheet is only created again when it changes
+    source_text: String,
+}
+
+impl CSSStyleSheet {
+    // https://drafts.csswg.org/cssom/#create-a-css-style-sheet
+    pub fn new(text: &str, owner_node: Option<WeakNode>) -> CSSStyleSheet {
+        // https://drafts.csswg.org/cssom/#parse-a-css-stylesheet
+        // Qualified rules without a selector are invalid and dropped
+        let css_rules = parse_a_stylesheet(text).into_iter().filter_map(|rule| {
+            match rule {
+                Rule::Qualified(qualified_rule) => CSSStyleRule::from_qualified_rule(qualified_rule).map(|rule| Rc::new(RefCell::new(rule))),
+                Rule::At(_) => None
+            }
+        }).collect();
+
+        return CSSStyleSheet { owner_node, css_rules, source_text: text.to_string() };
+    }
+}
+
+// https://drafts.csswg.org/cssom/#the-cssstylerule-interface
+pub struct CSSStyleRule {
+    pub selector_text: String,
+    pub style: Rc<RefCell<CSSStyleDeclaration>>,
+}
+
+impl CSSStyleRule {
+    // A rule whose selectors the selector engine does not support (e.g. a:hover) is kept, so scripts see every rule of the sheet, and it matches no element (see style.rs)
+    // TODO: A selector list that is not valid CSS is kept too, as it can not be told apart from one that is only not supported
+    fn from_qualified_rule(qualified_rule: QualifiedRule) -> Option<CSSStyleRule> {
+        let selector_text = serialize_component_values(&qualified_rule.prelude).trim().to_string();
+        if selector_text.is_empty() {
+            return None;
+        }
+
+        let declarations = parse_a_list_of_declarations_from_values(qualified_rule.block.value);
+        let style = CSSStyleDeclaration::from_declarations(declarations);
+        return Some(CSSStyleRule { selector_text, style: Rc::new(RefCell::new(style)) });
+    }
+
+    // https://drafts.csswg.org/cssom/#parse-a-css-rule
+    // Returns None for a syntax error, or a rule that is not a style rule
+    pub fn parse(text: &str) -> Option<CSSStyleRule> {
+        match parse_a_rule(text)? {
+            Rule::Qualified(qualified_rule) => CSSStyleRule::from_qualified_rule(qualified_rule),
+            Rule::At(_) => None
+        }
+    }
+
+    // https://drafts.csswg.org/cssom/#serialize-a-css-rule
+    pub fn css_text(&self) -> String {
+        // 1. Let s initially be the result of performing serialize a group of selectors on the rule's associated selectors, followed by the string " {", i.e., a single SPACE (U+0020), followed by LEFT CURLY BRACKET (U+007B).
+        let mut serialization = format!("{} {{", self.selector_text);
+
+        // 2. Let decls be the result of performing serialize a CSS declaration block on the rule's associated declarations, or null if there are no such declarations.
+        // 5. If decls is not null, prepend " " (a single SPACE (U+0020)) to decls and append it to s.
+        let declarations = self.style.borrow().css_text();
+        if !declarations.is_empty() {
+            serialization.push(' ');
+            serialization.push_str(&declarations);
+        }
+
+        // 7. Append " }" to s (i.e. a single SPACE (U+0020) followed by RIGHT CURLY BRACKET (U+007D)).
+        serialization.push_str(" }");
+        return serialization;
+    }
+}
+
+// https://drafts.csswg.org/cssom/#css-declaration
+#[derive(Debug, Clone)]
+pub struct CSSDeclaration {
+    pub property_name: String,
+    pub value: String,
+    pub important: bool,
+    // https://drafts.csswg.org/css-variables/#pending-substitution-value
+    // The shorthand a longhand was expanded from when the shorthand has var() in it, its value is then the shorthand's until var() is substituted and the shorthand can be expanded
+    pub pending_substitution: Option<String>,
+}
+
+// https://drafts.csswg.org/cssom/#css-declaration-blocks
+// A shorthand is stored as its longhands, which are put back together into the shorthand when the block is serialized
+// TODO: Values are kept as the text they were written as, only a shorthand is checked against its grammar as it is expanded and longhands are only checked when they are cascaded (see css_values)
+#[derive(Debug, Default)]
+pub struct CSSStyleDeclaration {
+    pub declarations: Vec<CSSDeclaration>,
+    // https://drafts.csswg.org/cssom/#cssstyledeclaration-owner-node
+    // The element whose style attribute the block is parsed from, None for the block of a style rule
+    pub owner_node: Option<WeakNode>,
+    // https://drafts.csswg.org/cssom/#cssstyledeclaration-updating-flag
+    pub(crate) updating: bool,
+}
+
+// https://drafts.csswg.org/css-variables/#custom-property
+pub(crate) fn is_custom_property(property: &str) -> bool {
+    return property.starts_with("--");
+}
+
+// Property names are ASCII case-insensitive, apart from custom properties
+fn normalize_property_name(property: &str) -> String {
+    match is_custom_property(property) {
+        true => property.to_string(),
+        false => property.to_ascii_lowercase()
+    }
+}
+
+// https://drafts.csswg.org/cssom/#set-a-css-declaration
+// The declarations a declaration is stored as, the longhands of a shorthand and none when the value of a shorthand is invalid
+// https://drafts.csswg.org/css-variables/#variables-in-shorthands
+// A shorthand with var() in it can only be expanded once var() is substituted, so its longhands are pending substitution until then
+fn expand_shorthand(property_name: &str, value: &str, important: bool) -> Vec<CSSDeclaration> {
+    let longhands = match shorthand_longhands(property_name) {
+        Some(longhands) => longhands,
+        None => return vec![CSSDeclaration { property_name: property_name.to_string(), value: value.to_string(), important, pending_substitution: None }]
+    };
+    if contains_var(value) {
+        return longhands.iter()
+            .map(|longhand| CSSDeclaration { property_name: longhand.to_string(), value: value.to_string(), important, pending_substitution: Some(property_name.to_string()) })
+            .collect();
+    }
+    return longhand_declarations(property_name, value).into_iter()
+        .map(|(property_name, value)| CSSDeclaration { property_name, value, important, pending_substitution: None })
+        .collect();
+}
+
+impl CSSStyleDeclaration {
+    // https://drafts.csswg.org/cssom/#parse-a-css-declaration-block
+    pub fn from_declarations(declarations: Vec<Declaration>) -> CSSStyleDeclaration {
+        let mut declaration_block = CSSStyleDeclaration::default();
+        for declaration in declarations {
+            let property_name = normalize_property_name(&declaration.name);
+            let value = serialize_component_values(&declaration.value).trim().to_string();
+
+            // A declaration without a value is invalid, apart from a custom property
+            if value.is_empty() && !is_custom_property(&property_name) {
+                continue;
+            }
+
+            for longhand in expand_shorthand(&property_name, &value, declaration.important) {
+                // A later declaration of the same property wins, unless only the earlier one is important
+                if let Some(index) = declaration_block.declarations.iter().position(|existing| existing.property_name == longhand.property_name) {
+                    if declaration_block.declarations[index].important && !longhand.important {
+                        continue;
+                    }
+                    declaration_block.declarations.remove(index);
+                }
+                declaration_block.declarations.push(longhand);
+            }
+        }
+        return declaration_block;
+    }
+
+    // The declarations of the longhands of shorthand in the order of shorthand_longhands, None when one of them is not in the block or they are not all of the same importance
+    fn longhand_declarations(&self, shorthand: &str) -> Option<Vec<&CSSDeclaration>> {
+        let declarations = shorthand_longhands(shorthand)?.iter().map(|longhand| self.declaration(longhand)).collect::<Option<Vec<&CSSDeclaration>>>()?;
+        if declarations.iter().any(|declaration| declaration.important != declarations[0].important) {
+            return None;
+        }
+        return Some(declarations);
+    }
+
+    // https://drafts.csswg.org/cssom/#serialize-a-css-value
+    // The value of shorthand put back together from its longhands, None when they are not all in the block or the shorthand can not represent them
+    fn shorthand_value(&self, shorthand: &str) -> Option<String> {
+        let declarations = self.longhand_declarations(shorthand)?;
+
+        // Longhands that are pending substitution are only represented by the shorthand they were expanded from, with the value it was given
+        if declarations.iter().any(|declaration| declaration.pending_substitution.is_some()) {
+            let all_from_shorthand = declarations.iter().all(|declaration| declaration.pending_substitution.as_deref() == Some(shorthand) && declaration.value == declarations[0].value);
+            return all_from_shorthand.then(|| declarations[0].value.clone());
+        }
+
+        let values: Vec<&str> = declarations.iter().map(|declaration| declaration.value.as_str()).collect();
+        return shorthand_value(shorthand, &values);
+    }
+
+    // https://drafts.csswg.org/cssom/#dom-cssstyledeclaration-getpropertyvalue
+    pub fn get_property_value(&self, property: &str) -> String {
+        // 1. If property is not a custom property, follow these substeps: Let property be property converted to ASCII lowercase.
+        let property = normalize_property_name(property);
+
+        // 2. If property is a shorthand property, then follow these substeps: return the serialization of the shorthand that represents its longhands, or the empty string when they can not be represented
+        if shorthand_longhands(&property).is_some() {
+            return self.shorthand_value(&property).unwrap_or_default();
+        }
+
+        // 3. If property is a case-sensitive match for a property name of a CSS declaration in the declarations, then return the result of invoking serialize a CSS value of that declaration.
+        // A longhand that is pending substitution serializes as the empty string
+        // 4. Return the empty string.
+        return self.declaration(&property).filter(|declaration| declaration.pending_substitution.is_none()).map(|declaration| declaration.value.clone()).unwrap_or_default();
+    }
+
+    // https://drafts.csswg.org/cssom/#dom-cssstyledeclaration-getpropertypriority
+    pub fn get_property_priority(&self, property: &str) -> String {
+        // 2. If property is a shorthand property, then: if all the longhands it maps to are in the declarations with the important flag set, return the string "important"
+        // 3. If property is a case-sensitive match for a property name of a CSS declaration in the declarations that has the important flag set, return the string "important".
+        // 4. Return the empty string.
+        let property = normalize_property_name(property);
+        if shorthand_longhands(&property).is_some() {
+            return match self.longhand_declarations(&property) {
+                Some(declarations) if declarations[0].important => String::from("important"),
+                _ => String::new()
+            };
+        }
+        match self.declaration(&property) {
+            Some(declaration) if declaration.important => String::from("important"),
+            _ => String::new()
+        }
+    }
+
+    // https://drafts.csswg.org/cssom/#dom-cssstyledeclaration-setproperty
+    pub fn set_property(&mut self, property: &str, value: &str, priority: &str) {
+        // 2. If property is not a custom property, follow these substeps: Let property be property converted to ASCII lowercase.
+        let property = normalize_property_name(property);
+
+        // 3. If value is the empty string, invoke removeProperty() with property as argument and return.
+        if value.is_empty() {
+            self.remove_property(&property);
+            return;
+        }
+
+        // 4. If priority is not the empty string and is not an ASCII case-insensitive match for the string "important", then return.
+        if !priority.is_empty() && !priority.eq_ignore_ascii_case("important") {
+            return;
+        }
+
+        // 5. Let component value list be the result of parsing value for property property.
+        // 6. If component value list is null, then return.
+        // FIXME: The value only has to be a list of component values, it is not checked against the property's grammar
+        let component_values = parse_a_list_of_component_values(value);
+        let value = serialize_component_values(&component_values).trim().to_string();
+        if value.is_empty() && !is_custom_property(&property) {
+            return;
+        }
+
+        // 7. If property is a shorthand property, then for each longhand property longhand that property maps to, in canonical order, set the CSS declaration longhand with the appropriate value(s) from component value list
+        // 8. Otherwise, let updated be the result of set the CSS declaration property with value component value list
+        // A shorthand whose value is invalid for it has no longhands and sets nothing
+        let important = !priority.is_empty();
+        for longhand in expand_shorthand(&property, &value, important) {
+            match self.declarations.iter_mut().find(|declaration| declaration.property_name == longhand.property_name) {
+                Some(declaration) => *declaration = longhand,
+                None => self.declarations.push(longhand)
+            }
+        }
+    }
+
+    // https://drafts.csswg.org/cssom/#dom-cssstyledeclaration-removeproperty
+    pub fn remove_property(&mut self, property: &str) -> String {
+        // 2. If property is not a custom property, let property be property converted to ASCII lowercase.
+        let property = normalize_property_name(property);
+
+        // 3. Let value be the return value of invoking getPropertyValue() with property as argument.
+        let value = self.get_property_value(&property);
+
+        // 5. If property is a shorthand property, for each longhand property longhand that property maps to: if longhand is a case-sensitive match for a property name of a CSS declaration in the declarations, remove that CSS declaration and let removed be true.
+        // 6. Otherwise, if property is a case-sensitive match for a property name of a CSS declaration in the declarations, remove that CSS declaration and let removed be true.
+        let longhands = shorthand_longhands(&property).unwrap_or_default();
+        self.declarations.retain(|declaration| declaration.property_name != property && !longhands.contains(&declaration.property_name.as_str()));
+
+        // 7. Return value.
+        return value;
+    }
+
+    // https://drafts.csswg.org/cssom/#serialize-a-css-declaration-block
+    pub fn css_text(&self) -> String {
+        // https://drafts.csswg.org/cssom/#serialize-a-css-declaration
+        // The declarations are serialized as "property: value;", with " !important" before the semicolon if they are important, and joined with a single SPACE (U+0020)
+        let serialize = |property_name: &str, value: &str, important: bool| match important {
+            true => format!("{}: {} !important;", property_name, value),
+            false => format!("{}: {};", property_name, value)
+        };
+
+        // 1. Let list be an empty array.
+        // 2. Let already serialized be an empty array.
+        let mut list = Vec::new();
+        let mut already_serialized: Vec<&str> = Vec::new();
+
+        // 3. Declaration loop: For each CSS declaration declaration in declaration block's declarations, follow these substeps:
+        'declaration_loop: for declaration in &self.declarations {
+            // 2. If property is in already serialized, continue with the steps labeled declaration loop.
+            let property = declaration.property_name.as_str();
+            if already_serialized.contains(&property) {
+                continue;
+            }
+
+            // 3. If property maps to one or more shorthand properties, let shorthands be an array of those shorthand properties, in preferred order.
+            // 4. Shorthand loop: For each shorthand in shorthands, follow these substeps:
+            for shorthand in SHORTHANDS.iter().filter(|shorthand| shorthand_longhands(shorthand).is_some_and(|longhands| longhands.contains(&property))) {
+                // 1-6. If all the longhands of shorthand are in the declarations with the same importance, and shorthand can represent them, serialize shorthand instead of them
+                let value = match self.shorthand_value(shorthand) {
+                    Some(value) => value,
+                    None => continue
+                };
+                let longhands = shorthand_longhands(shorthand).unwrap_or_default();
+                if longhands.iter().any(|longhand| already_serialized.contains(longhand)) {
+                    continue;
+                }
+                let important = self.declaration(longhands[0]).is_some_and(|declaration| declaration.important);
+                list.push(serialize(shorthand, &value, important));
+                already_serialized.extend_from_slice(longhands);
+                continue 'declaration_loop;
+            }
+
+            // 5-9. Serialize the declaration on its own, and append property to already serialized.
+            list.push(serialize(property, &declaration.value, declaration.important));
+            already_serialized.push(property);
+        }
+
+        // 4. Return list joined with " " (a single SPACE (U+0020)).
+        return list.join(" ");
+    }
+
+    // https://drafts.csswg.org/cssom/#dom-cssstyledeclaration-csstext
+    pub fn set_css_text(&mut self, text: &str) {
+        // 2. Empty the declarations.
+        // 3. Parse the given value and, if the return value is not the empty list, insert the items in the list into the declarations, in specified order.
+        self.declarations = CSSStyleDeclaration::from_declarations(parse_a_list_of_declarations(text)).declarations;
+    }
+
+    fn declaration(&self, property: &str) -> Option<&CSSDeclaration> {
+        return self.declarations.iter().find(|declaration| declaration.property_name == property);
+    }
+}
+
+// https://drafts.csswg.org/cssom/#dom-elementcssinlinestyle-style
+// The declaration block of the element's style attribute, it is parsed the first time it is asked for and kept up to date with the attribute after that
+pub fn inline_style(element: &RefNode) -> Option<Rc<RefCell<CSSStyleDeclaration>>> {
+    let style_attribute = match &element.borrow().data {
+        NodeData::Element(element) => {
+            if let Some(declaration_block) = &element.style_declaration_block {
+                return Some(Rc::clone(declaration_block));
+            }
+            element.get_attribute("style").cloned().unwrap_or_default()
+        },
+        _ => return None
+    };
+
+    let mut declaration_block = CSSStyleDeclaration::from_declarations(parse_a_list_of_declarations(&style_attribute));
+    declaration_block.owner_node = Some(Rc::downgrade(element));
+    let declaration_block = Rc::new(RefCell::new(declaration_block));
+    if let NodeData::Element(element) = &mut element.borrow_mut().data {
+        element.style_declaration_block = Some(Rc::clone(&declaration_block));
+    }
+    return Some(declaration_block);
+}
+
+// https://drafts.csswg.org/cssom/#update-style-attribute-for
+pub(crate) fn update_style_attribute_for(declaration_block: &Rc<RefCell<CSSStyleDeclaration>>) {
+    // 1. Let owner node be declaration block's owner node.
+    // 2. If owner node is null, then return.
+    let owner_node = declaration_block.borrow().owner_node.as_ref().and_then(|owner_node| owner_node.upgrade());
+    let owner_node = match owner_node {
+        Some(owner_node) => owner_node,
+        None => return
+    };
+
+    // 3. Set declaration block's updating flag.
+    // 4. Set an attribute value for owner node using "style" and the result of serializing declaration block.
+    // 5. Unset declaration block's updating flag.
+    let css_text = declaration_block.borrow().css_text();
+    declaration_block.borrow_mut().updating = true;
+    set_attribute_value(&owner_node, "style", css_text);
+    declaration_block.borrow_mut().updating = false;
+}
+
+// https://html.spec.whatwg.org/multipage/semantics.html#update-a-style-block
+// The style block is brought up to date when the document's style sheets are asked for, rather than whenever the style element's children change
+fn update_a_style_block(element: &RefNode) -> Option<Rc<RefCell<CSSStyleSheet>>> {
+    let (type_attribute, associated_css_style_sheet) = match &element.borrow().data {
+        NodeData::Element(element) => (element.get_attribute("type").cloned(), element.css_style_sheet.clone()),
+        _ => return None
+    };
+    let text = text_content(element).unwrap_or_default();
+
+    // 4. If element's type attribute is present and its value is neither the empty string nor an ASCII case-insensitive match for "text/css", then return.
+    let is_css = type_attribute.is_none_or(|type_attribute| type_attribute.is_empty() || type_attribute.eq_ignore_ascii_case("text/css"));
+
+    let sheet = match associated_css_style_sheet {
+        Some(sheet) if is_css && sheet.borrow().source_text == text => return Some(sheet),
+        // 2. If element has an associated CSS style sheet, remove the CSS style sheet in question.
+        _ if !is_css => None,
+        // 6. Create a CSS style sheet with the following properties: owner node: element
+        _ => Some(Rc::new(RefCell::new(CSSStyleSheet::new(&text, Some(Rc::downgrade(element))))))
+    };
+
+    if let NodeData::Element(element) = &mut element.borrow_mut().data {
+        element.css_style_sheet = sheet.clone();
+    }
+    return sheet;
+}
+
+// https://drafts.csswg.org/cssom/#documentorshadowroot-document-or-shadow-root-css-style-sheets
+// The style sheets of the document's style elements, in tree order
+pub fn document_css_style_sheets(document: &RefNode) -> Vec<Rc<RefCell<CSSStyleSheet>>> {
+    return descendants(document).iter().filter(|node| {
+        return matches!(node.borrow().data, NodeData::Element(ref element) if element.local_name() == "style");
+    }).filter_map(update_a_style_block).collect();
+}
+
+pub(crate) fn install_cssom(interpreter: &mut Interpreter) {
+    // https://drafts.csswg.org/cssom/#the-cssstylesheet-interface
+    // TODO: CSSStyleSheet should inherit from StyleSheet
+    let css_style_sheet_prototype = create_interface_prototype_object(interpreter.intrinsic("Object.prototype"));
+    let css_style_sheet_attributes: [(&str, NativeFunction); 3] = [
+        ("type", css_style_sheet_type),
+        ("ownerNode", css_style_sheet_owner_node),
+        ("cssRules", css_style_sheet_css_rules),
+    ];
+    for (name, getter) in css_style_sheet_attributes {
+        Interpreter::define_accessor_property(&css_style_sheet_prototype, name, getter, None);
+    }
+    Interpreter::define_builtin_function(&css_style_sheet_prototype, "insertRule", css_style_sheet_insert_rule);
+    Interpreter::define_builtin_function(&css_style_sheet_prototype, "deleteRule", css_style_sheet_delete_rule);
+    interpreter.set_intrinsic("CSSStyleSheet.prototype", css_style_sheet_prototype);
+
+    // https://drafts.csswg.org/cssom/#the-cssrule-interface
+    let css_rule_prototype = create_interface_prototype_object(interpreter.intrinsic("Object.prototype"));
+    Interpreter::define_accessor_property(&css_rule_prototype, "type", css_rule_type, None);
+    Interpreter::define_accessor_property(&css_rule_prototype, "cssText", css_rule_css_text, None);
+
+    // https://drafts.csswg.org/cssom/#the-cssstylerule-interface
+    let css_style_rule_prototype = create_interface_prototype_object(Some(Rc::clone(&css_rule_prototype)));
+    Interpreter::define_accessor_property(&css_style_rule_prototype, "selectorText", css_style_rule_selector_text, Some(css_style_rule_set_selector_text));
+    Interpreter::define_accessor_property(&css_style_rule_prototype, "style", css_style_rule_style, None);
+    interpreter.set_intrinsic("CSSRule.prototype", css_rule_prototype);
+    interpreter.set_intrinsic("CSSStyleRule.prototype", css_style_rule_prototype);
+
+    // https://drafts.csswg.org/cssom/#the-cssstyledeclaration-interface
+    let css_style_declaration_prototype = create_interface_prototype_object(interpreter.intrinsic("Object.prototype"));
+    Interpreter::define_accessor_property(&css_style_declaration_prototype, "cssText", css_style_declaration_css_text, Some(css_style_declaration_set_css_text));
+    Interpreter::define_accessor_property(&css_style_declaration_prototype, "length", css_style_declaration_length, None);
+    let css_style_declaration_methods: [(&str, NativeFunction); 5] = [
+        ("item", css_style_declaration_item),
+        ("getPropertyValue", css_style_declaration_get_property_value),
+        ("getPropertyPriority", css_style_declaration_get_property_priority),
+        ("setProperty", css_style_declaration_set_property),
+        ("removeProperty", css_style_declaration_remove_property),
+    ];
+    for (name, method) in css_style_declaration_methods {
+        Interpreter::define_builtin_function(&css_style_declaration_prototype, name, method);
+    }
+    interpreter.set_intrinsic("CSSStyleDeclaration.prototype", css_style_declaration_prototype);
+
+    install_interface_object(interpreter, "CSSStyleSheet");
+    install_interface_object(interpreter, "CSSRule");
+    install_interface_object(interpreter, "CSSStyleRule");
+    install_interface_object(interpreter, "CSSStyleDeclaration");
+}
+
+// https://webidl.spec.whatwg.org/#es-unsigned-long
+// A missing or undefined argument is 0, which is the default of the optional index of insertRule
+fn argument_to_unsigned_long(interpreter: &mut Interpreter, arguments: &[Rc<RefCell<JSValue>>], index: usize) -> Result<u32, CompletionRecord> {
+    let value = match arguments.get(index) {
+        Some(value) if !matches!(*value.borrow(), JSValue::Undefined) => Rc::clone(value),
+        _ => return Ok(0)
+    };
+
+    // FIXME: ToNumber is not implemented for objects yet
+    if matches!(*value.borrow(), JSValue::Object(_)) {
+        let primitive = completion_value(interpreter.to_string_coercing_objects(value))?;
+        return argument_to_unsigned_long(interpreter, &[primitive], 0);
+    }
+
+    // 1. Let x be ? ToNumber(V).
+    // 2-6. The conversion for unsigned long without [EnforceRange] or [Clamp] is the same as ToUint32.
+    let number = match &*completion_value(Interpreter::to_number(value))?.borrow() {
+        JSValue::Numeric(number) => *number,
+        _ => unreachable!()
+    };
+    return Ok(Interpreter::to_uint32(number));
+}
+
+// https://webidl.spec.whatwg.org/#es-platform-objects
+pub(crate) fn wrap_css_style_sheet(interpreter: &Interpreter, sheet: &Rc<RefCell<CSSStyleSheet>>) -> Rc<RefCell<JSValue>> {
+    return create_platform_object(interpreter, "CSSStyleSheet.prototype", PlatformObject::CSSStyleSheet(Rc::clone(sheet)));
+}
+
+fn wrap_css_style_rule(interpreter: &Interpreter, rule: &Rc<RefCell<CSSStyleRule>>) -> Rc<RefCell<JSValue>> {
+    return create_platform_object(interpreter, "CSSStyleRule.prototype", PlatformObject::CSSStyleRule(Rc::clone(rule)));
+}
+
+pub(crate) fn wrap_css_style_declaration(interpreter: &Interpreter, declaration: &Rc<RefCell<CSSStyleDeclaration>>) -> Rc<RefCell<JSValue>> {
+    return create_platform_object(interpreter, "CSSStyleDeclaration.prototype", PlatformObject::CSSStyleDeclaration(Rc::clone(declaration)));
+}
+
+fn this_css_style_sheet(interpreter: &mut Interpreter, this_value: &Rc<RefCell<JSValue>>) -> Result<Rc<RefCell<CSSStyleSheet>>, CompletionRecord> {
+    if let JSValue::Object(object) = &*this_value.borrow() {
+        if let Some(PlatformObject::CSSStyleSheet(sheet)) = &object.borrow().platform_object {
+            return Ok(Rc::clone(sheet));
+        }
+    }
+
+    return Err(interpreter.throw_type_error(String::from("Illegal invocation")));
+}
+
+fn this_css_style_rule(interpreter: &mut Interpreter, this_value: &Rc<RefCell<JSValue>>) -> Result<Rc<RefCell<CSSStyleRule>>, CompletionRecord> {
+    if let JSValue::Object(object) = &*this_value.borrow() {
+        if let Some(PlatformObject::CSSStyleRule(rule)) = &object.borrow().platform_object {
+            return Ok(Rc::clone(rule));
+        }
+    }
+
+    return Err(interpreter.throw_type_error(String::from("Illegal invocation")));
+}
+
+fn this_css_style_declaration(interpreter: &mut Interpreter, this_value: &Rc<RefCell<JSValue>>) -> Result<Rc<RefCell<CSSStyleDeclaration>>, CompletionRecord> {
+    if let JSValue::Object(object) = &*this_value.borrow() {
+        if let Some(PlatformObject::CSSStyleDeclaration(declaration)) = &object.borrow().platform_object {
+            return Ok(Rc::clone(declaration));
+        }
+    }
+
+    return Err(interpreter.throw_type_error(String::from("Illegal invocation")));
+}
+
+// https://drafts.csswg.org/cssom/#dom-stylesheet-type
+fn css_style_sheet_type(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    if let Err(completion_record) = this_css_style_sheet(interpreter, &this_value) {
+        return completion_record;
+    }
+
+    return create_value_completion(JSValue::String(String::from("text/css")));
+}
+
+// https://drafts.csswg.org/cssom/#dom-stylesheet-ownernode
+fn css_style_sheet_owner_node(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let sheet = match this_css_style_sheet(interpreter, &this_value) {
+        Ok(sheet) => sheet,
+        Err(completion_record) => return completion_record
+    };
+
+    let owner_node = sheet.borrow().owner_node.as_ref().and_then(|owner_node| owner_node.upgrade());
+    return wrap_optional_node(interpreter, owner_node);
+}
+
+// https://drafts.csswg.org/cssom/#dom-cssstylesheet-cssrules
+// FIXME: This is an array of the rules at the time it is read rather than a live CSSRuleList
+fn css_style_sheet_css_rules(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let sheet = match this_css_style_sheet(interpreter, &this_value) {
+        Ok(sheet) => sheet,
+        Err(completion_record) => return completion_record
+    };
+
+    let rules = sheet.borrow().css_rules.iter().map(|rule| wrap_css_style_rule(interpreter, rule)).collect();
+    return create_value_completion(JSValue::Object(interpreter.create_array_from_list(rules)));
+}
+
+// https://drafts.csswg.org/cssom/#dom-cssstylesheet-insertrule
+fn css_style_sheet_insert_rule(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let sheet = match this_css_style_sheet(interpreter, &this_value) {
+        Ok(sheet) => sheet,
+        Err(completion_record) => return completion_record
+    };
+
+    let rule = match argument_to_dom_string(interpreter, &arguments, 0) {
+        Ok(rule) => rule,
+        Err(completion_record) => return completion_record
+    };
+    let index = match argument_to_unsigned_long(interpreter, &arguments, 1) {
+        Ok(index) => index as usize,
+        Err(completion_record) => return completion_record
+    };
+
+    // https://drafts.csswg.org/cssom/#insert-a-css-rule
+    // 1. Set length to the number of items in list.
+    // 2. If index is greater than length, then throw an IndexSizeError exception.
+    let length = sheet.borrow().css_rules.len();
+    if index > length {
+        return interpreter.throw_error("IndexSizeError", format!("The index provided ({}) is larger than the maximum index ({}).", index, length));
+    }
+
+    // 3. Set new rule to the results of performing parse a CSS rule on argument rule.
+    // 4. If new rule is a syntax error, throw a SyntaxError exception.
+    let new_rule = match CSSStyleRule::parse(&rule) {
+        Some(new_rule) => new_rule,
+        None => return interpreter.throw_error("SyntaxError", format!("Failed to parse the rule '{}'.", rule))
+    };
+
+    // 8. Insert new rule into list at the zero-indexed position index.
+    sheet.borrow_mut().css_rules.insert(index, Rc::new(RefCell::new(new_rule)));
+
+    // 9. Return index.
+    return create_value_completion(JSValue::Numeric(index as f64));
+}
+
+// https://drafts.csswg.org/cssom/#dom-cssstylesheet-deleterule
+fn css_style_sheet_delete_rule(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let sheet = match this_css_style_sheet(interpreter, &this_value) {
+        Ok(sheet) => sheet,
+        Err(completion_record) => return completion_record
+    };
+
+    let index = match argument_to_unsigned_long(interpreter, &arguments, 0) {
+        Ok(index) => index as usize,
+        Err(completion_record) => return completion_record
+    };
+
+    // https://drafts.csswg.org/cssom/#remove-a-css-rule
+    // 2. If index is greater than or equal to length, then throw an IndexSizeError exception.
+    let length = sheet.borrow().css_rules.len();
+    if index >= length {
+        return interpreter.throw_error("IndexSizeError", format!("The index provided ({}) is outside the range [0, {}).", index, length));
+    }
+
+    // 6. Remove rule old rule from list at the zero-indexed position index.
+    sheet.borrow_mut().css_rules.remove(index);
+    return create_value_completion(JSValue::Undefined);
+}
+
+// https://drafts.csswg.org/cssom/#dom-cssrule-type
+// Every rule is a style rule, whose type is STYLE_RULE
+fn css_rule_type(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    if let Err(completion_record) = this_css_style_rule(interpreter, &this_value) {
+        return completion_record;
+    }
+
+    return create_value_completion(JSValue::Numeric(1.0));
+}
+
+// https://drafts.csswg.org/cssom/#dom-cssrule-csstext
+fn css_rule_css_text(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let rule = match this_css_style_rule(interpreter, &this_value) {
+        Ok(rule) => rule,
+        Err(completion_record) => return completion_record
+    };
+
+    let css_text = rule.borrow().css_text();
+    return create_value_completion(JSValue::String(css_text));
+}
+
+// https://drafts.csswg.org/cssom/#dom-cssstylerule-selectortext
+fn css_style_rule_selector_text(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let rule = match this_css_style_rule(interpreter, &this_value) {
+        Ok(rule) => rule,
+        Err(completion_record) => return completion_record
+    };
+
+    let selector_text = rule.borrow().selector_text.clone();
+    return create_value_completion(JSValue::String(selector_text));
+}
+
+fn css_style_rule_set_selector_text(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let rule = match this_css_style_rule(interpreter, &this_value) {
+        Ok(rule) => rule,
+        Err(completion_record) => return completion_record
+    };
+
+    let selector_text = match argument_to_dom_string(interpreter, &arguments, 0) {
+        Ok(selector_text) => selector_text,
+        Err(completion_record) => return completion_record
+    };
+
+    // 1. Run the parse a group of selectors algorithm on the given value.
+    // 2. If the algorithm returns a non-null value replace the associated group of selectors with the returned value.
+    // 3. Otherwise, if the algorithm returns a null value, do nothing.
+    // Selectors that are not supported are kept as they are in a style sheet, see CSSStyleRule::from_qualified_rule
+    let selector_text = selector_text.trim();
+    if !selector_text.is_empty() {
+        rule.borrow_mut().selector_text = selector_text.to_string();
+    }
+    return create_value_completion(JSValue::Undefined);
+}
+
+// https://drafts.csswg.org/cssom/#dom-cssstylerule-style
+fn css_style_rule_style(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let rule = match this_css_style_rule(interpreter, &this_value) {
+        Ok(rule) => rule,
+        Err(completion_record) => return completion_record
+    };
+
+    let style = Rc::clone(&rule.borrow().style);
+    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(wrap_css_style_declaration(interpreter, &style))));
+}
+
+// https://drafts.csswg.org/cssom/#dom-cssstyledeclaration-csstext
+fn css_style_declaration_css_text(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let declaration = match this_css_style_declaration(interpreter, &this_value) {
+        Ok(declaration) => declaration,
+        Err(completion_record) => return completion_record
+    };
+
+    let css_text = declaration.borrow().css_text();
+    return create_value_completion(JSValue::String(css_text));
+}
+
+fn css_style_declaration_set_css_text(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let declaration = match this_css_style_declaration(interpreter, &this_value) {
+        Ok(declaration) => declaration,
+        Err(completion_record) => return completion_record
+    };
+
+    let css_text = match argument_to_dom_string(interpreter, &arguments, 0) {
+        Ok(css_text) => css_text,
+        Err(completion_record) => return completion_record
+    };
+
+    declaration.borrow_mut().set_css_text(&css_text);
+    update_style_attribute_for(&declaration);
+    return create_value_completion(JSValue::Undefined);
+}
+
+// https://drafts.csswg.org/cssom/#dom-cssstyledeclaration-length
+fn css_style_declaration_length(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let declaration = match this_css_style_declaration(interpreter, &this_value) {
+        Ok(declaration) => declaration,
+        Err(completion_record) => return completion_record
+    };
+
+    let length = declaration.borrow().declarations.len();
+    return create_value_completion(JSValue::Numeric(length as f64));
+}
+
+// https://drafts.csswg.org/cssom/#dom-cssstyledeclaration-item
+fn css_style_declaration_item(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let declaration = match this_css_style_declaration(interpreter, &this_value) {
+        Ok(declaration) => declaration,
+        Err(completion_record) => return completion_record
+    };
+
+    let index = match argument_to_unsigned_long(interpreter, &arguments, 0) {
+        Ok(index) => index as usize,
+        Err(completion_record) => return completion_record
+    };
+
+    // 1. If index is greater than or equal to the number of CSS declarations in the declarations, then return the empty string.
+    // 2. Let declaration be the indexth CSS declaration in the declarations.
+    // 3. Return declaration's property name.
+    let property_name = declaration.borrow().declarations.get(index).map(|declaration| declaration.property_name.clone()).unwrap_or_default();
+    return create_value_completion(JSValue::String(property_name));
+}
+
+// https://drafts.csswg.org/cssom/#dom-cssstyledeclaration-getpropertyvalue
+fn css_style_declaration_get_property_value(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let declaration = match this_css_style_declaration(interpreter, &this_value) {
+        Ok(declaration) => declaration,
+        Err(completion_record) => return completion_record
+    };
+
+    let property = match argument_to_dom_string(interpreter, &arguments, 0) {
+        Ok(property) => property,
+        Err(completion_record) => return completion_record
+    };
+
+    let value = declaration.borrow().get_property_value(&property);
+    return create_value_completion(JSValue::String(value));
+}
+
+// https://drafts.csswg.org/cssom/#dom-cssstyledeclaration-getpropertypriority
+fn css_style_declaration_get_property_priority(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let declaration = match this_css_style_declaration(interpreter, &this_value) {
+        Ok(declaration) => declaration,
+        Err(completion_record) => return completion_record
+    };
+
+    let property = match argument_to_dom_string(interpreter, &arguments, 0) {
+        Ok(property) => property,
+        Err(completion_record) => return completion_record
+    };
+
+    let priority = declaration.borrow().get_property_priority(&property);
+    return create_value_completion(JSValue::String(priority));
+}
+
+// https://drafts.csswg.org/cssom/#dom-cssstyledeclaration-setproperty
+fn css_style_declaration_set_property(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let declaration = match this_css_style_declaration(interpreter, &this_value) {
+        Ok(declaration) => declaration,
+        Err(completion_record) => return completion_record
+    };
+
+    let property = match argument_to_dom_string(interpreter, &arguments, 0) {
+        Ok(property) => property,
+        Err(completion_record) => return completion_record
+    };
+    let value = match argument_to_dom_string(interpreter, &arguments, 1) {
+        Ok(value) => value,
+        Err(completion_record) => return completion_record
+    };
+    // The priority argument is optional and defaults to the empty string
+    let priority = match arguments.get(2) {
+        Some(priority) if !matches!(*priority.borrow(), JSValue::Undefined) => match argument_to_dom_string(interpreter, &arguments, 2) {
+            Ok(priority) => priority,
+            Err(completion_record) => return completion_record
+        },
+        _ => String::new()
+    };
+
+    declaration.borrow_mut().set_property(&property, &value, &priority);
+    update_style_attribute_for(&declaration);
+    return create_value_completion(JSValue::Undefined);
+}
+
+// https://drafts.csswg.org/cssom/#dom-cssstyledeclaration-removeproperty
+fn css_style_declaration_remove_property(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let declaration = match this_css_style_declaration(interpreter, &this_value) {
+        Ok(declaration) => declaration,
+        Err(completion_record) => return completion_record
+    };
+
+    let property = match argument_to_dom_string(interpreter, &arguments, 0) {
+        Ok(property) => property,
+        Err(completion_record) => return completion_record
+    };
+
+    let value = declaration.borrow_mut().remove_property(&property);
+    update_style_attribute_for(&declaration);
+    return create_value_completion(JSValue::String(value));
+}
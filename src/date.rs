@@ -0,0 +1,744 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::interpreter::{argument, completion_value, create_normal_completion, create_value_completion, return_if_abrupt, to_number, CompletionRecord, Interpreter, JSObject, JSValue, PropertyKey, ReferenceRecordOrJsValue};
+
+// https://tc39.es/ecma262/#sec-date-objects
+// Dates keep their time value, the milliseconds since the epoch in UTC, in the [[DateValue]] internal slot, and the getters take it apart with the
+// spec's day, year and month arithmetic
+// TODO: Local time is UTC, there is no time zone database, and the setters other than setTime are not implemented
+
+// https://tc39.es/ecma262/#sec-time-values-and-time-range
+const MS_PER_DAY: f64 = 86400000.0;
+const MS_PER_HOUR: f64 = 3600000.0;
+const MS_PER_MINUTE: f64 = 60000.0;
+const MS_PER_SECOND: f64 = 1000.0;
+
+const WEEK_DAY_NAMES: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const MONTH_NAMES: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+// A getter of one component of a date's local time, e.g. getMonth, which is NaN for an invalid date
+macro_rules! date_getter {
+    ($getter:ident, $component:expr) => {
+        fn $getter(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+            // 1. Let dateObject be the this value.
+            // 2. Perform ? RequireInternalSlot(dateObject, [[DateValue]]).
+            // 3. Let t be dateObject.[[DateValue]].
+            let time = return_if_abrupt!(this_time_value(interpreter, &this_value));
+
+            // 4. If t is NaN, return NaN.
+            if time.is_nan() {
+                return create_value_completion(JSValue::Numeric(f64::NAN));
+            }
+
+            // 5. Return the component of LocalTime(t).
+            let component: fn(f64) -> f64 = $component;
+            return create_value_completion(JSValue::Numeric(component(local_time(time))));
+        }
+    };
+}
+
+date_getter!(date_prototype_get_full_year, year_from_time);
+date_getter!(date_prototype_get_month, month_from_time);
+date_getter!(date_prototype_get_date, date_from_time);
+date_getter!(date_prototype_get_day, week_day);
+date_getter!(date_prototype_get_hours, hour_from_time);
+date_getter!(date_prototype_get_minutes, min_from_time);
+date_getter!(date_prototype_get_seconds, sec_from_time);
+date_getter!(date_prototype_get_milliseconds, ms_from_time);
+
+// https://tc39.es/ecma262/#sec-properties-of-the-date-constructor
+// https://tc39.es/ecma262/#sec-properties-of-the-date-prototype-object
+pub(crate) fn install_date(interpreter: &mut Interpreter) {
+    // The Date prototype object is an ordinary object, it is not a Date instance and does not have a [[DateValue]] internal slot
+    let date_prototype = interpreter.create_ordinary_object();
+    Interpreter::define_builtin_function(&date_prototype, "getTime", date_prototype_get_time);
+    Interpreter::define_builtin_function(&date_prototype, "valueOf", date_prototype_get_time);
+    Interpreter::define_builtin_function(&date_prototype, "setTime", date_prototype_set_time);
+    Interpreter::define_builtin_function(&date_prototype, "getTimezoneOffset", date_prototype_get_timezone_offset);
+    Interpreter::define_builtin_function(&date_prototype, "toISOString", date_prototype_to_iso_string);
+    Interpreter::define_builtin_function(&date_prototype, "toJSON", date_prototype_to_json);
+    Interpreter::define_builtin_function(&date_prototype, "toString", date_prototype_to_string);
+    Interpreter::define_builtin_function(&date_prototype, "toDateString", date_prototype_to_date_string);
+    Interpreter::define_builtin_function(&date_prototype, "toTimeString", date_prototype_to_time_string);
+    Interpreter::define_builtin_function(&date_prototype, "toUTCString", date_prototype_to_utc_string);
+
+    // Local time is UTC, so each getUTC method is the same as its local time one
+    let getters: [(&str, &str, fn(&mut Interpreter, Rc<RefCell<JSValue>>, Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord); 8] = [
+        ("getFullYear", "getUTCFullYear", date_prototype_get_full_year),
+        ("getMonth", "getUTCMonth", date_prototype_get_month),
+        ("getDate", "getUTCDate", date_prototype_get_date),
+        ("getDay", "getUTCDay", date_prototype_get_day),
+        ("getHours", "getUTCHours", date_prototype_get_hours),
+        ("getMinutes", "getUTCMinutes", date_prototype_get_minutes),
+        ("getSeconds", "getUTCSeconds", date_prototype_get_seconds),
+        ("getMilliseconds", "getUTCMilliseconds", date_prototype_get_milliseconds),
+    ];
+    for (local_name, utc_name, getter) in getters {
+        Interpreter::define_builtin_function(&date_prototype, local_name, getter);
+        Interpreter::define_builtin_function(&date_prototype, utc_name, getter);
+    }
+
+    let date_constructor = Interpreter::create_builtin_function(date_call);
+    date_constructor.borrow_mut().construct = Some(date_construct);
+    date_constructor.borrow_mut().prototype = interpreter.intrinsic("Function.prototype");
+    Interpreter::define_property_or_throw(&date_constructor, "length", Rc::new(RefCell::new(JSValue::Numeric(7.0))), false, false, true);
+    Interpreter::set_function_name(&date_constructor, "Date");
+    Interpreter::define_builtin_function(&date_constructor, "now", date_now);
+    Interpreter::define_builtin_function(&date_constructor, "parse", date_parse);
+    Interpreter::define_builtin_function(&date_constructor, "UTC", date_utc);
+
+    // https://tc39.es/ecma262/#sec-date.prototype
+    // This property has the attributes { [[Writable]]: false, [[Enumerable]]: false, [[Configurable]]: false }.
+    Interpreter::define_property_or_throw(&date_constructor, "prototype", Rc::new(RefCell::new(JSValue::Object(Rc::clone(&date_prototype)))), false, false, false);
+
+    // https://tc39.es/ecma262/#sec-date.prototype.constructor
+    Interpreter::define_property_or_throw(&date_prototype, "constructor", Rc::new(RefCell::new(JSValue::Object(Rc::clone(&date_constructor)))), true, false, true);
+
+    interpreter.set_intrinsic("Date", Rc::clone(&date_constructor));
+    interpreter.set_intrinsic("Date.prototype", date_prototype);
+    interpreter.define_global_property("Date", Rc::new(RefCell::new(JSValue::Object(date_constructor))));
+}
+
+// https://tc39.es/ecma262/#sec-day
+fn day(time: f64) -> f64 {
+    return (time / MS_PER_DAY).floor();
+}
+
+// https://tc39.es/ecma262/#sec-daysinyear
+fn days_in_year(year: f64) -> f64 {
+    if year % 4.0 != 0.0 || (year % 100.0 == 0.0 && year % 400.0 != 0.0) {
+        return 365.0;
+    }
+    return 366.0;
+}
+
+// https://tc39.es/ecma262/#sec-dayfromyear
+fn day_from_year(year: f64) -> f64 {
+    // 2. Define the following mathematical values:
+    //        a. Let numYears1 be (ry - 1970).
+    //        b. Let numYears4 be floor((ry - 1969) / 4).
+    //        c. Let numYears100 be floor((ry - 1901) / 100).
+    //        d. Let numYears400 be floor((ry - 1601) / 400).
+    // 3. Return 𝔽(365 × numYears1 + numYears4 - numYears100 + numYears400).
+    return 365.0 * (year - 1970.0) + ((year - 1969.0) / 4.0).floor() - ((year - 1901.0) / 100.0).floor() + ((year - 1601.0) / 400.0).floor();
+}
+
+// https://tc39.es/ecma262/#sec-timefromyear
+fn time_from_year(year: f64) -> f64 {
+    return MS_PER_DAY * day_from_year(year);
+}
+
+// https://tc39.es/ecma262/#sec-yearfromtime
+// The largest integral Number y (closest to +∞) such that TimeFromYear(y) ≤ t, found from an estimate by the average length of a year
+fn year_from_time(time: f64) -> f64 {
+    let mut year = (time / (MS_PER_DAY * 365.2425)).floor() + 1970.0;
+    while time_from_year(year) > time {
+        year -= 1.0;
+    }
+    while time_from_year(year + 1.0) <= time {
+        year += 1.0;
+    }
+    return year;
+}
+
+// https://tc39.es/ecma262/#sec-inleapyear
+fn in_leap_year(time: f64) -> bool {
+    return days_in_year(year_from_time(time)) == 366.0;
+}
+
+// https://tc39.es/ecma262/#sec-daywithinyear
+fn day_within_year(time: f64) -> f64 {
+    return day(time) - day_from_year(year_from_time(time));
+}
+
+// The day within the year that each month starts on
+fn month_start_days(leap_year: bool) -> [f64; 13] {
+    let leap = if leap_year { 1.0 } else { 0.0 };
+    return [0.0, 31.0, 59.0 + leap, 90.0 + leap, 120.0 + leap, 151.0 + leap, 181.0 + leap, 212.0 + leap, 243.0 + leap, 273.0 + leap, 304.0 + leap, 334.0 + leap, 365.0 + leap];
+}
+
+// https://tc39.es/ecma262/#sec-monthfromtime
+fn month_from_time(time: f64) -> f64 {
+    let day_within_year = day_within_year(time);
+    let month_start_days = month_start_days(in_leap_year(time));
+    return (0..12).find(|month| day_within_year < month_start_days[month + 1]).unwrap_or(11) as f64;
+}
+
+// https://tc39.es/ecma262/#sec-datefromtime
+fn date_from_time(time: f64) -> f64 {
+    let month = month_from_time(time) as usize;
+    return day_within_year(time) - month_start_days(in_leap_year(time))[month] + 1.0;
+}
+
+// https://tc39.es/ecma262/#sec-weekday
+fn week_day(time: f64) -> f64 {
+    // 1. Return 𝔽(ℝ(Day(t) + 4𝔽) modulo 7).
+    return (day(time) + 4.0).rem_euclid(7.0);
+}
+
+// https://tc39.es/ecma262/#sec-hourfromtime
+fn hour_from_time(time: f64) -> f64 {
+    return (time / MS_PER_HOUR).floor().rem_euclid(24.0);
+}
+
+// https://tc39.es/ecma262/#sec-minfromtime
+fn min_from_time(time: f64) -> f64 {
+    return (time / MS_PER_MINUTE).floor().rem_euclid(60.0);
+}
+
+// https://tc39.es/ecma262/#sec-secfromtime
+fn sec_from_time(time: f64) -> f64 {
+    return (time / MS_PER_SECOND).floor().rem_euclid(60.0);
+}
+
+// https://tc39.es/ecma262/#sec-msfromtime
+fn ms_from_time(time: f64) -> f64 {
+    return time.rem_euclid(MS_PER_SECOND);
+}
+
+// https://tc39.es/ecma262/#sec-maketime
+fn make_time(hour: f64, min: f64, sec: f64, ms: f64) -> f64 {
+    // 1. If hour is not finite, min is not finite, sec is not finite, or ms is not finite, return NaN.
+    if !hour.is_finite() || !min.is_finite() || !sec.is_finite() || !ms.is_finite() {
+        return f64::NAN;
+    }
+
+    // 2-5. Let h, m, s and milli be 𝔽(! ToIntegerOrInfinity(hour)), and so on.
+    // 6. Return ((h × msPerHour + m × msPerMinute) + s × msPerSecond) + milli.
+    return hour.trunc() * MS_PER_HOUR + min.trunc() * MS_PER_MINUTE + sec.trunc() * MS_PER_SECOND + ms.trunc();
+}
+
+// https://tc39.es/ecma262/#sec-makeday
+fn make_day(year: f64, month: f64, date: f64) -> f64 {
+    // 1. If year is not finite or month is not finite or date is not finite, return NaN.
+    if !year.is_finite() || !month.is_finite() || !date.is_finite() {
+        return f64::NAN;
+    }
+
+    // 2. Let y be 𝔽(! ToIntegerOrInfinity(year)).
+    // 3. Let m be 𝔽(! ToIntegerOrInfinity(month)).
+    // 4. Let dt be 𝔽(! ToIntegerOrInfinity(date)).
+    let (year, month, date) = (year.trunc(), month.trunc(), date.trunc());
+
+    // 5. Let ym be y + 𝔽(floor(ℝ(m) / 12)).
+    // 6. If ym is not finite, return NaN.
+    // Years this far out are beyond the time range, and would take long to find
+    let year_month = year + (month / 12.0).floor();
+    if !year_month.is_finite() || year_month.abs() > 400000.0 {
+        return f64::NAN;
+    }
+
+    // 7. Let mn be 𝔽(ℝ(m) modulo 12).
+    let month = month.rem_euclid(12.0) as usize;
+
+    // 8. Find a finite time value t such that YearFromTime(t) is ym, MonthFromTime(t) is mn, and DateFromTime(t) is 1𝔽;
+    //    but if this is not possible (because some argument is out of range), return NaN.
+    // 9. Return Day(t) + dt - 1𝔽.
+    return day_from_year(year_month) + month_start_days(days_in_year(year_month) == 366.0)[month] + date - 1.0;
+}
+
+// https://tc39.es/ecma262/#sec-makedate
+fn make_date(day: f64, time: f64) -> f64 {
+    // 1. If day is not finite or time is not finite, return NaN.
+    if !day.is_finite() || !time.is_finite() {
+        return f64::NAN;
+    }
+
+    // 2. Let tv be day × msPerDay + time.
+    // 3. If tv is not finite, return NaN.
+    // 4. Return tv.
+    return day * MS_PER_DAY + time;
+}
+
+// https://tc39.es/ecma262/#sec-makefullyear
+fn make_full_year(year: f64) -> f64 {
+    // 1. If year is NaN, return NaN.
+    // 2. Let truncated be ! ToIntegerOrInfinity(year).
+    // 3. If truncated is in the inclusive interval from 0 to 99, return 1900𝔽 + 𝔽(truncated).
+    // 4. Return 𝔽(truncated).
+    if (0.0..100.0).contains(&year.trunc()) {
+        return 1900.0 + year.trunc();
+    }
+    return year;
+}
+
+// https://tc39.es/ecma262/#sec-timeclip
+fn time_clip(time: f64) -> f64 {
+    // 1. If time is not finite, return NaN.
+    // 2. If abs(ℝ(time)) > 8.64 × 10^15, return NaN.
+    if !time.is_finite() || time.abs() > 8.64e15 {
+        return f64::NAN;
+    }
+
+    // 3. Return 𝔽(! ToIntegerOrInfinity(time)).
+    return time.trunc() + 0.0;
+}
+
+// https://tc39.es/ecma262/#sec-localtime
+// TODO: The offset of the local time zone is always 0
+fn local_time(time: f64) -> f64 {
+    return time;
+}
+
+// https://tc39.es/ecma262/#sec-utc-t
+fn utc(time: f64) -> f64 {
+    return time;
+}
+
+// The time value of the current time
+fn now() -> f64 {
+    return SystemTime::now().duration_since(UNIX_EPOCH).map_or(0.0, |duration| duration.as_millis() as f64);
+}
+
+// https://tc39.es/ecma262/#thistimevalue
+fn this_time_value(interpreter: &mut Interpreter, this_value: &Rc<RefCell<JSValue>>) -> Result<f64, CompletionRecord> {
+    if let JSValue::Object(object) = &*this_value.borrow() {
+        if let Some(date_value) = object.borrow().date_value {
+            return Ok(date_value);
+        }
+    }
+    return Err(interpreter.throw_type_error(String::from("this is not a Date object.")));
+}
+
+// https://tc39.es/ecma262/#sec-date-constructor-date
+fn date_call(_interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. If NewTarget is undefined, then
+    //        a. Let now be the time value (UTC) identifying the current time.
+    //        b. Return ToDateString(now).
+    return create_value_completion(JSValue::String(to_date_string(now())));
+}
+
+// https://tc39.es/ecma262/#sec-date
+fn date_construct(interpreter: &mut Interpreter, arguments: Vec<Rc<RefCell<JSValue>>>, new_target: Rc<RefCell<JSObject>>) -> CompletionRecord {
+    // 2. Let numberOfArgs be the number of elements in values.
+    let time_value = match arguments.len() {
+        // 3. If numberOfArgs = 0, then
+        //        a. Let dv be the time value (UTC) identifying the current time.
+        0 => now(),
+        // 4. Else if numberOfArgs = 1, then
+        1 => {
+            // a. Let value be values[0].
+            let value = Rc::clone(&arguments[0]);
+
+            // b. If value is an Object and value has a [[DateValue]] internal slot, then
+            //        i. Let tv be value.[[DateValue]].
+            let date_value = match &*value.borrow() {
+                JSValue::Object(object) => object.borrow().date_value,
+                _ => None
+            };
+            match date_value {
+                Some(date_value) => date_value,
+                None => {
+                    // c. Else,
+                    //        i. Let v be ? ToPrimitive(value).
+                    let value = return_if_abrupt!(to_primitive(interpreter, value));
+
+                    //        ii. If v is a String, then
+                    //                2. Let tv be the result of parsing v as a date, in exactly the same manner as for the parse method.
+                    //        iii. Else,
+                    //                1. Let tv be ? ToNumber(v).
+                    let string = match &*value.borrow() {
+                        JSValue::String(string) => Some(string.clone()),
+                        _ => None
+                    };
+                    match string {
+                        Some(string) => parse_date(&string),
+                        None => return_if_abrupt!(to_number(interpreter, value))
+                    }
+                }
+            }
+        },
+        // 5. Else,
+        _ => {
+            // a. Assert: numberOfArgs ≥ 2.
+            // b-h. Let y, m, dt, h, min, s and milli be ? ToNumber of each argument, with the defaults 1 for dt and 0 for the others.
+            // i. Let yr be MakeFullYear(y).
+            // j. Let finalDate be MakeDate(MakeDay(yr, m, dt), MakeTime(h, min, s, milli)).
+            // k. Let dv be TimeClip(UTC(finalDate)).
+            let final_date = return_if_abrupt!(date_from_components(interpreter, &arguments));
+            utc(final_date)
+        }
+    };
+
+    // 6. Let O be ? OrdinaryCreateFromConstructor(NewTarget, "%Date.prototype%", « [[DateValue]] »).
+    let object = return_if_abrupt!(completion_value(interpreter.ordinary_create_from_constructor(&new_target, "Date.prototype")));
+
+    // 7. Set O.[[DateValue]] to dv.
+    if let JSValue::Object(date) = &*object.borrow() {
+        date.borrow_mut().date_value = Some(time_clip(time_value));
+    }
+
+    // 8. Return O.
+    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(object)));
+}
+
+// The time value of a year, month, date, hours, minutes, seconds and milliseconds, as given to the Date constructor and Date.UTC
+fn date_from_components(interpreter: &mut Interpreter, arguments: &[Rc<RefCell<JSValue>>]) -> Result<f64, CompletionRecord> {
+    let mut components = [f64::NAN, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0];
+    for (index, value) in arguments.iter().take(7).enumerate() {
+        components[index] = to_number(interpreter, Rc::clone(value))?;
+    }
+    let [year, month, date, hours, minutes, seconds, milliseconds] = components;
+    return Ok(make_date(make_day(make_full_year(year), month, date), make_time(hours, minutes, seconds, milliseconds)));
+}
+
+// https://tc39.es/ecma262/#sec-date.now
+fn date_now(_interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return create_value_completion(JSValue::Numeric(now()));
+}
+
+// https://tc39.es/ecma262/#sec-date.parse
+fn date_parse(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let string = return_if_abrupt!(to_string(interpreter, argument(&arguments, 0)));
+    return create_value_completion(JSValue::Numeric(parse_date(&string)));
+}
+
+// https://tc39.es/ecma262/#sec-date.utc
+fn date_utc(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1-8. Let y, m, dt, h, min, s and milli be ? ToNumber of each argument, with the defaults 1 for dt and 0 for the others.
+    // 9. Let yr be MakeFullYear(y).
+    // 10. Return TimeClip(MakeDate(MakeDay(yr, m, dt), MakeTime(h, min, s, milli))).
+    let time = return_if_abrupt!(date_from_components(interpreter, &arguments));
+    return create_value_completion(JSValue::Numeric(time_clip(time)));
+}
+
+// https://tc39.es/ecma262/#sec-date.parse
+// The time value of a string in the Date Time String Format, or in the format of toString or toUTCString, NaN for any other string
+fn parse_date(string: &str) -> f64 {
+    return parse_date_time_string(string).or_else(|| parse_date_string(string)).map_or(f64::NAN, time_clip);
+}
+
+// https://tc39.es/ecma262/#sec-date-time-string-format
+// YYYY-MM-DDTHH:mm:ss.sssZ, where everything after the year is optional and the year can be an expanded year of six digits with a sign,
+// a date-only form is UTC and a date-time form without an offset is local time
+fn parse_date_time_string(string: &str) -> Option<f64> {
+    let bytes = string.as_bytes();
+    let mut position = 0;
+    let number = |position: &mut usize, digits: usize| -> Option<f64> {
+        let slice = bytes.get(*position..*position + digits)?;
+        if !slice.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        *position += digits;
+        return std::str::from_utf8(slice).ok()?.parse::<f64>().ok();
+    };
+    let expect = |position: &mut usize, byte: u8| -> bool {
+        if bytes.get(*position) == Some(&byte) {
+            *position += 1;
+            return true;
+        }
+        return false;
+    };
+
+    let year = match bytes.first() {
+        Some(b'+') | Some(b'-') => {
+            let negative = bytes[0] == b'-';
+            position += 1;
+            let year = number(&mut position, 6)?;
+            // -000000 is not a valid year
+            if negative && year == 0.0 {
+                return None;
+            }
+            if negative { -year } else { year }
+        },
+        _ => number(&mut position, 4)?
+    };
+    let has_month = expect(&mut position, b'-');
+    let month = if has_month { number(&mut position, 2)? } else { 1.0 };
+    let date = if has_month && expect(&mut position, b'-') { number(&mut position, 2)? } else { 1.0 };
+    if !(1.0..=12.0).contains(&month) || !(1.0..=31.0).contains(&date) {
+        return None;
+    }
+
+    let (mut hours, mut minutes, mut seconds, mut milliseconds) = (0.0, 0.0, 0.0, 0.0);
+    let mut offset = Some(0.0);
+    if expect(&mut position, b'T') {
+        hours = number(&mut position, 2)?;
+        if !expect(&mut position, b':') {
+            return None;
+        }
+        minutes = number(&mut position, 2)?;
+        if expect(&mut position, b':') {
+            seconds = number(&mut position, 2)?;
+            if expect(&mut position, b'.') {
+                let digits = bytes[position..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+                if digits == 0 {
+                    return None;
+                }
+                // Only the first three digits are milliseconds
+                milliseconds = number(&mut position, digits.min(3))?;
+                milliseconds *= 10f64.powi(3 - digits.min(3) as i32);
+                position += digits.saturating_sub(3);
+            }
+        }
+        if hours > 24.0 || minutes > 59.0 || seconds > 59.0 || (hours == 24.0 && (minutes > 0.0 || seconds > 0.0 || milliseconds > 0.0)) {
+            return None;
+        }
+
+        // A date-time form without an offset is local time
+        offset = None;
+        if expect(&mut position, b'Z') {
+            offset = Some(0.0);
+        } else if let Some(&sign) = bytes.get(position).filter(|byte| **byte == b'+' || **byte == b'-') {
+            position += 1;
+            let offset_hours = number(&mut position, 2)?;
+            if !expect(&mut position, b':') {
+                return None;
+            }
+            let offset_minutes = number(&mut position, 2)?;
+            let magnitude = offset_hours * MS_PER_HOUR + offset_minutes * MS_PER_MINUTE;
+            offset = Some(if sign == b'-' { -magnitude } else { magnitude });
+        }
+    }
+    if position != bytes.len() {
+        return None;
+    }
+
+    let time = make_date(make_day(year, month - 1.0, date), make_time(hours, minutes, seconds, milliseconds));
+    return Some(match offset {
+        Some(offset) => time - offset,
+        None => utc(time)
+    });
+}
+
+// The formats toString and toUTCString produce, e.g. "Tue Jan 02 2024 03:04:05 GMT+0000 (Coordinated Universal Time)" and "Tue, 02 Jan 2024 03:04:05 GMT",
+// the week day and anything after the offset are ignored
+fn parse_date_string(string: &str) -> Option<f64> {
+    let mut month = None;
+    let mut date = None;
+    let mut year = None;
+    let mut time = (0.0, 0.0, 0.0);
+    let mut offset = 0.0;
+    for word in string.split(|character: char| character.is_ascii_whitespace() || character == ',').filter(|word| !word.is_empty()) {
+        if word.starts_with('(') {
+            break;
+        }
+        if let Some(index) = MONTH_NAMES.iter().position(|name| name.eq_ignore_ascii_case(word)) {
+            month = Some(index as f64);
+        } else if word.contains(':') {
+            let parts: Vec<f64> = word.split(':').map(|part| part.parse::<f64>()).collect::<Result<_, _>>().ok()?;
+            time = (*parts.first()?, *parts.get(1)?, parts.get(2).copied().unwrap_or(0.0));
+        } else if let Some(zone) = word.strip_prefix("GMT").or_else(|| word.strip_prefix("UTC")) {
+            if !zone.is_empty() {
+                let sign = if zone.starts_with('-') { -1.0 } else { 1.0 };
+                let digits = zone.get(1..5).filter(|digits| digits.bytes().all(|byte| byte.is_ascii_digit()))?;
+                offset = sign * (digits[..2].parse::<f64>().ok()? * MS_PER_HOUR + digits[2..].parse::<f64>().ok()? * MS_PER_MINUTE);
+            }
+        } else if let Ok(number) = word.parse::<f64>() {
+            if date.is_none() && word.len() <= 2 {
+                date = Some(number);
+            } else {
+                year = Some(number);
+            }
+        } else if !WEEK_DAY_NAMES.iter().any(|name| word.len() >= 3 && name.eq_ignore_ascii_case(&word[..3])) {
+            return None;
+        }
+    }
+    let day = make_day(year?, month?, date?);
+    return Some(make_date(day, make_time(time.0, time.1, time.2, 0.0)) - offset);
+}
+
+// https://tc39.es/ecma262/#sec-date.prototype.gettime
+fn date_prototype_get_time(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let dateObject be the this value.
+    // 2. Perform ? RequireInternalSlot(dateObject, [[DateValue]]).
+    // 3. Return dateObject.[[DateValue]].
+    let time = return_if_abrupt!(this_time_value(interpreter, &this_value));
+    return create_value_completion(JSValue::Numeric(time));
+}
+
+// https://tc39.es/ecma262/#sec-date.prototype.settime
+fn date_prototype_set_time(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let dateObject be the this value.
+    // 2. Perform ? RequireInternalSlot(dateObject, [[DateValue]]).
+    return_if_abrupt!(this_time_value(interpreter, &this_value));
+
+    // 3. Let t be ? ToNumber(time).
+    let time = return_if_abrupt!(to_number(interpreter, argument(&arguments, 0)));
+
+    // 4. Let v be TimeClip(t).
+    let value = time_clip(time);
+
+    // 5. Set dateObject.[[DateValue]] to v.
+    if let JSValue::Object(date) = &*this_value.borrow() {
+        date.borrow_mut().date_value = Some(value);
+    }
+
+    // 6. Return v.
+    return create_value_completion(JSValue::Numeric(value));
+}
+
+// https://tc39.es/ecma262/#sec-date.prototype.gettimezoneoffset
+fn date_prototype_get_timezone_offset(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 3. Let t be dateObject.[[DateValue]].
+    let time = return_if_abrupt!(this_time_value(interpreter, &this_value));
+
+    // 4. If t is NaN, return NaN.
+    if time.is_nan() {
+        return create_value_completion(JSValue::Numeric(f64::NAN));
+    }
+
+    // 5. Return (t - LocalTime(t)) / msPerMinute.
+    return create_value_completion(JSValue::Numeric((time - local_time(time)) / MS_PER_MINUTE + 0.0));
+}
+
+// https://tc39.es/ecma262/#sec-date.prototype.toisostring
+fn date_prototype_to_iso_string(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 3. Let tv be dateObject.[[DateValue]].
+    let time = return_if_abrupt!(this_time_value(interpreter, &this_value));
+
+    // 4. If tv is NaN, throw a RangeError exception.
+    // 5. Assert: tv is an integral Number.
+    // 6. If tv corresponds with a year that cannot be represented in the Date Time String Format, throw a RangeError exception.
+    // 7. Return a String representation of tv in the Date Time String Format on the UTC time scale, including all format elements and the UTC offset representation "Z".
+    return match date_to_iso_string(time) {
+        Some(string) => create_value_completion(JSValue::String(string)),
+        None => interpreter.throw_error("RangeError", String::from("Invalid time value"))
+    };
+}
+
+// https://tc39.es/ecma262/#sec-date-time-string-format
+// The Date Time String Format of a time value, None when the date is invalid
+pub(crate) fn date_to_iso_string(time: f64) -> Option<String> {
+    if time.is_nan() {
+        return None;
+    }
+    let year = year_from_time(time);
+    let year = if (0.0..=9999.0).contains(&year) {
+        format!("{:04}", year)
+    } else if year < 0.0 {
+        format!("-{:06}", -year)
+    } else {
+        format!("+{:06}", year)
+    };
+    return Some(format!("{}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z", year, month_from_time(time) + 1.0, date_from_time(time), hour_from_time(time), min_from_time(time), sec_from_time(time), ms_from_time(time)));
+}
+
+// https://tc39.es/ecma262/#sec-date.prototype.tojson
+fn date_prototype_to_json(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let O be ? ToObject(this value).
+    let object = match &*this_value.borrow() {
+        JSValue::Object(object) => Rc::clone(object),
+        _ => return interpreter.throw_type_error(String::from("Date.prototype.toJSON called on non-object"))
+    };
+
+    // 2. Let tv be ? ToPrimitive(O, number).
+    let time_value = return_if_abrupt!(completion_value(interpreter.ordinary_to_primitive(&object, "number")));
+
+    // 3. If tv is a Number and tv is not finite, return null.
+    if matches!(*time_value.borrow(), JSValue::Numeric(number) if !number.is_finite()) {
+        return create_value_completion(JSValue::Null);
+    }
+
+    // 4. Return ? Invoke(O, "toISOString").
+    let receiver = Rc::new(RefCell::new(JSValue::Object(Rc::clone(&object))));
+    let to_iso_string = return_if_abrupt!(completion_value(interpreter.get(&object, &PropertyKey::String(String::from("toISOString")), Rc::clone(&receiver))));
+    return interpreter.call(to_iso_string, receiver, Vec::new());
+}
+
+// https://tc39.es/ecma262/#sec-date.prototype.tostring
+fn date_prototype_to_string(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 3. Let tv be dateObject.[[DateValue]].
+    // 4. Return ToDateString(tv).
+    let time = return_if_abrupt!(this_time_value(interpreter, &this_value));
+    return create_value_completion(JSValue::String(to_date_string(time)));
+}
+
+// https://tc39.es/ecma262/#sec-date.prototype.todatestring
+fn date_prototype_to_date_string(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 3. Let tv be dateObject.[[DateValue]].
+    // 4. If tv is NaN, return "Invalid Date".
+    // 5. Let t be LocalTime(tv).
+    // 6. Return DateString(t).
+    let time = return_if_abrupt!(this_time_value(interpreter, &this_value));
+    if time.is_nan() {
+        return create_value_completion(JSValue::String(String::from("Invalid Date")));
+    }
+    return create_value_completion(JSValue::String(date_string(local_time(time))));
+}
+
+// https://tc39.es/ecma262/#sec-date.prototype.totimestring
+fn date_prototype_to_time_string(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 3. Let tv be dateObject.[[DateValue]].
+    // 4. If tv is NaN, return "Invalid Date".
+    // 5. Let t be LocalTime(tv).
+    // 6. Return the string-concatenation of TimeString(t) and TimeZoneString(tv).
+    let time = return_if_abrupt!(this_time_value(interpreter, &this_value));
+    if time.is_nan() {
+        return create_value_completion(JSValue::String(String::from("Invalid Date")));
+    }
+    return create_value_completion(JSValue::String(format!("{}{}", time_string(local_time(time)), time_zone_string())));
+}
+
+// https://tc39.es/ecma262/#sec-date.prototype.toutcstring
+fn date_prototype_to_utc_string(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 3. Let tv be dateObject.[[DateValue]].
+    // 4. If tv is NaN, return "Invalid Date".
+    let time = return_if_abrupt!(this_time_value(interpreter, &this_value));
+    if time.is_nan() {
+        return create_value_completion(JSValue::String(String::from("Invalid Date")));
+    }
+
+    // 5. Let weekday be the Name of the entry in Table 62 with the Number WeekDay(tv).
+    // 6. Let month be the Name of the entry in Table 63 with the Number MonthFromTime(tv).
+    // 7. Let day be ToZeroPaddedDecimalString(ℝ(DateFromTime(tv)), 2).
+    // 8. Let yv be YearFromTime(tv).
+    // 9. If yv is +0𝔽 or yv > +0𝔽, let yearSign be the empty String; otherwise, let yearSign be "-".
+    // 10. Let paddedYear be ToZeroPaddedDecimalString(abs(ℝ(yv)), 4).
+    // 11. Return the string-concatenation of weekday, ",", the code unit 0x0020 (SPACE), day, the code unit 0x0020 (SPACE), month, the code unit 0x0020 (SPACE), yearSign, paddedYear,
+    //     the code unit 0x0020 (SPACE), and TimeString(tv).
+    let year = year_from_time(time);
+    let string = format!("{}, {:02} {} {}{:04} {}", WEEK_DAY_NAMES[week_day(time) as usize], date_from_time(time), MONTH_NAMES[month_from_time(time) as usize], if year < 0.0 { "-" } else { "" }, year.abs(), time_string(time));
+    return create_value_completion(JSValue::String(string));
+}
+
+// https://tc39.es/ecma262/#sec-todatestring
+fn to_date_string(time: f64) -> String {
+    // 1. If tv is NaN, return "Invalid Date".
+    if time.is_nan() {
+        return String::from("Invalid Date");
+    }
+
+    // 2. Let t be LocalTime(tv).
+    // 3. Return the string-concatenation of DateString(t), the code unit 0x0020 (SPACE), TimeString(t), and TimeZoneString(tv).
+    let local = local_time(time);
+    return format!("{} {}{}", date_string(local), time_string(local), time_zone_string());
+}
+
+// https://tc39.es/ecma262/#sec-datestring
+// e.g. "Tue Jan 02 2024"
+fn date_string(time: f64) -> String {
+    let year = year_from_time(time);
+    return format!("{} {} {:02} {}{:04}", WEEK_DAY_NAMES[week_day(time) as usize], MONTH_NAMES[month_from_time(time) as usize], date_from_time(time), if year < 0.0 { "-" } else { "" }, year.abs());
+}
+
+// https://tc39.es/ecma262/#sec-timestring
+// e.g. "03:04:05 GMT"
+fn time_string(time: f64) -> String {
+    return format!("{:02}:{:02}:{:02} GMT", hour_from_time(time), min_from_time(time), sec_from_time(time));
+}
+
+// https://tc39.es/ecma262/#sec-timezoneestring
+// The offset and name of the local time zone, which is always UTC
+fn time_zone_string() -> String {
+    return String::from("+0000 (Coordinated Universal Time)");
+}
+
+// https://tc39.es/ecma262/#sec-toprimitive
+// A Date converted without a hint is converted to a string, any other object to a number
+fn to_primitive(interpreter: &mut Interpreter, value: Rc<RefCell<JSValue>>) -> Result<Rc<RefCell<JSValue>>, CompletionRecord> {
+    let object = match &*value.borrow() {
+        JSValue::Object(object) => Rc::clone(object),
+        _ => return Ok(Rc::clone(&value))
+    };
+    let hint = if object.borrow().date_value.is_some() { "string" } else { "number" };
+    return completion_value(interpreter.ordinary_to_primitive(&object, hint));
+}
+
+fn to_string(interpreter: &mut Interpreter, value: Rc<RefCell<JSValue>>) -> Result<String, CompletionRecord> {
+    match &*completion_value(interpreter.to_string_coercing_objects(value))?.borrow() {
+        JSValue::String(string) => Ok(string.clone()),
+        _ => unreachable!()
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use html5ever::tendril::TendrilSink;
+use html5ever::{namespace_url, ns, parse_document, ParseOpts};
+use markup5ever_rcdom::{Handle, NodeData, RcDom};
+use crate::html_document_parser::ParserOptions;
+use crate::serializer::serialize_as_tree;
+use crate::tokenizer::Tokenizer;
+
+// Differential testing of the tree builder against html5ever, which follows the spec closely
+// Each document of a corpus is parsed by both, and the trees are written in the format of the html5lib tree construction tests and compared line by line
+// The first line where the trees differ is where the tree builder deviates, and counting the files by their first difference shows which deviations are the most common
+// Both parse with scripting disabled, and bytes that are not UTF-8 are decoded by html5ever as replacement characters
+// TODO: Template contents are not parsed here, and the tag names and attributes of SVG and MathML elements are not adjusted, so they can differ from html5ever's
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    // The index of the first line of the trees that differs
+    pub line: usize,
+    pub engine_line: String,
+    pub html5ever_line: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileComparison {
+    pub path: PathBuf,
+    // None when the trees are the same
+    pub difference: Option<Difference>,
+    // The file could not be read
+    pub failure: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CorpusReport {
+    pub files: Vec<FileComparison>,
+}
+
+impl CorpusReport {
+    pub fn differing_files(&self) -> usize {
+        return self.files.iter().filter(|file| file.difference.is_some()).count();
+    }
+
+    pub fn failures(&self) -> usize {
+        return self.files.iter().filter(|file| file.failure.is_some()).count();
+    }
+
+    // The first differences of the files without their indentation, with how many files they are found in, most common first
+    pub fn common_differences(&self) -> Vec<((String, String), usize)> {
+        let mut counts: HashMap<(String, String), usize> = HashMap::new();
+        for difference in self.files.iter().filter_map(|file| file.difference.as_ref()) {
+            let key = (tree_line_content(&difference.engine_line), tree_line_content(&difference.html5ever_line));
+            *counts.entry(key).or_insert(0) += 1;
+        }
+        let mut counts: Vec<((String, String), usize)> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        return counts;
+    }
+}
+
+// The tree this crate's parser builds for html
+pub fn engine_tree(html: &[u8]) -> String {
+    let mut tokenizer = Tokenizer::from_bytes(html.to_vec(), ParserOptions { scripting_enabled: false, print_parse_errors: false, ..ParserOptions::default() });
+    tokenizer.run();
+    return serialize_as_tree(&tokenizer.document());
+}
+
+// The tree html5ever builds for html
+pub fn html5ever_tree(html: &[u8]) -> String {
+    let mut options = ParseOpts::default();
+    options.tree_builder.scripting_enabled = false;
+    let dom = parse_document(RcDom::default(), options).from_utf8().one(html);
+
+    let mut tree = String::new();
+    for child in dom.document.children.borrow().iter() {
+        write_html5ever_node(child, 0, &mut tree);
+    }
+    return tree;
+}
+
+// The same format as serialize_as_tree, with the namespace of elements and attributes that are not HTML ones in front of their names
+fn write_html5ever_node(node: &Handle, depth: usize, tree: &mut String) {
+    let indent = format!("| {}", "  ".repeat(depth));
+    match &node.data {
+        NodeData::Element { name, attrs, template_contents, .. } => {
+            let prefix = match name.ns {
+                ns!(svg) => "svg ",
+                ns!(mathml) => "math ",
+                _ => ""
+            };
+            tree.push_str(&format!("{}<{}{}>\n", indent, prefix, name.local));
+            let mut attributes: Vec<(String, String)> = attrs.borrow().iter().map(|attribute| {
+                let prefix = match attribute.name.ns {
+                    ns!(xlink) => "xlink ",
+                    ns!(xml) => "xml ",
+                    ns!(xmlns) => "xmlns ",
+                    _ => ""
+                };
+                (format!("{}{}", prefix, attribute.name.local), attribute.value.to_string())
+            }).collect();
+            attributes.sort();
+            for (name, value) in attributes {
+                tree.push_str(&format!("{}  {}=\"{}\"\n", indent, name, value));
+            }
+            if let Some(contents) = template_contents.borrow().as_ref() {
+                tree.push_str(&format!("{}  content\n", indent));
+                for child in contents.children.borrow().iter() {
+                    write_html5ever_node(child, depth + 2, tree);
+                }
+            }
+        },
+        NodeData::Text { contents } => tree.push_str(&format!("{}\"{}\"\n", indent, contents.borrow())),
+        NodeData::Comment { contents } => tree.push_str(&format!("{}<!-- {} -->\n", indent, contents)),
+        NodeData::Doctype { name, public_id, system_id } if public_id.is_empty() && system_id.is_empty() => tree.push_str(&format!("{}<!DOCTYPE {}>\n", indent, name)),
+        NodeData::Doctype { name, public_id, system_id } => tree.push_str(&format!("{}<!DOCTYPE {} \"{}\" \"{}\">\n", indent, name, public_id, system_id)),
+        NodeData::Document | NodeData::ProcessingInstruction { .. } => {}
+    }
+    for child in node.children.borrow().iter() {
+        write_html5ever_node(child, depth + 1, tree);
+    }
+}
+
+// Where the trees of html first differ, None when they are the same
+pub fn compare(html: &[u8]) -> Option<Difference> {
+    let (engine_tree, html5ever_tree) = (engine_tree(html), html5ever_tree(html));
+    let (engine_lines, html5ever_lines): (Vec<&str>, Vec<&str>) = (engine_tree.lines().collect(), html5ever_tree.lines().collect());
+    let line = (0..engine_lines.len().max(html5ever_lines.len())).find(|line| engine_lines.get(*line) != html5ever_lines.get(*line))?;
+    let text = |lines: &[&str]| lines.get(line).map_or("(no line)".to_string(), |line| line.to_string());
+    return Some(Difference { line, engine_line: text(&engine_lines), html5ever_line: text(&html5ever_lines) });
+}
+
+pub fn compare_file(path: &Path) -> FileComparison {
+    return match fs::read(path) {
+        Ok(html) => FileComparison { path: path.to_path_buf(), difference: compare(&html), failure: None },
+        Err(error) => FileComparison { path: path.to_path_buf(), difference: None, failure: Some(error.to_string()) }
+    };
+}
+
+pub fn compare_files(files: &[PathBuf]) -> CorpusReport {
+    return CorpusReport { files: files.iter().map(|path| compare_file(path)).collect() };
+}
+
+// A line of a tree without the "| " and indentation in front of it
+fn tree_line_content(line: &str) -> String {
+    return line.trim_start_matches('|').trim_start().to_string();
+}
@@ -0,0 +1,1169 @@
+use std::cell::RefCell;
+use std::ops::Range;
+use std::rc::Rc;
+use indexmap::IndexMap;
+use serde::Serialize;
+use crate::counters::ListStylePosition;
+use crate::css_values::{BorderStyle, Color, CssWideKeyword, FontSize, Length, Overflow, Position, PropertyValue};
+use crate::bidi::{needs_reordering, visual_runs};
+use crate::cssom::{document_css_style_sheets, CSSStyleSheet};
+use crate::language::{directionality, Direction};
+use crate::layout::{build_box_tree, BoxType, LayoutBox, WhiteSpace};
+use crate::node::{encoding_parse_url, RefNode};
+use crate::scrolling::{clamp_scroll_position, element_scroll_position, viewport_scroll_position, ScrollPosition};
+use crate::selector::PseudoElement;
+use crate::srcset::select_source;
+use crate::style::{pseudo_element_specified_values, specified_values};
+use crate::text::FontMetrics;
+use crate::window::Viewport;
+
+// https://drafts.csswg.org/css2/#painting
+// Painting a document is building its display list, the items to draw in the order they are drawn, in CSS pixels from the top left of the document
+// A backend (e.g. an SVG or a PNG renderer) only has to know how to draw each kind of item, and painting can be tested by looking at the items
+// Blocks are stacked down the page as wide as their containing block, and the inline content of each is broken into lines of text runs
+// TODO: Margins do not collapse, inline boxes have no backgrounds or borders, opacity fades the color of each item rather than the element as a group and does not apply to images, floats and tables are laid out as blocks, inline boxes can not be positioned, list markers are text (list-style-image is not drawn) and all text is in the one font of the metrics
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+// The colors are never currentcolor, it is resolved to the color of the element
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum DisplayItem {
+    // A rectangle filled with a color, the background of a box
+    Rect { rect: Rect, color: Color },
+    // The border of a box, rect is its outer edge and each side is given as top, right, bottom and left
+    Border { rect: Rect, widths: [f32; 4], styles: [BorderStyle; 4], colors: [Color; 4] },
+    // A run of text on one line in one font size and color, starting at x on the baseline, its characters are in the order they are displayed from left to right
+    Text { x: f32, baseline: f32, text: String, font_size: f32, color: Color },
+    // An image to draw into rect, loading it is left to the backend
+    Image { rect: Rect, url: String },
+    // https://drafts.csswg.org/css-overflow-3/#overflow-clip-edge
+    // The items up to the matching EndClip are only drawn inside rect, clips nest
+    Clip { rect: Rect },
+    EndClip,
+}
+
+impl DisplayItem {
+    // Moves the item dx pixels to the right and dy pixels down
+    fn translate(&mut self, dx: f32, dy: f32) {
+        match self {
+            DisplayItem::Rect { rect, .. } | DisplayItem::Border { rect, .. } | DisplayItem::Image { rect, .. } | DisplayItem::Clip { rect } => *rect = rect.translate(dx, dy),
+            DisplayItem::Text { x, baseline, .. } => {
+                *x += dx;
+                *baseline += dy;
+            },
+            DisplayItem::EndClip => ()
+        }
+    }
+}
+
+impl Rect {
+    pub fn translate(&self, dx: f32, dy: f32) -> Rect {
+        return Rect { x: self.x + dx, y: self.y + dy, ..*self };
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        return self.x < other.x + other.width && other.x < self.x + self.width && self.y < other.y + other.height && other.y < self.y + self.height;
+    }
+
+    // The part of the rect that is inside other, which is empty when they do not meet
+    pub fn intersection(&self, other: &Rect) -> Rect {
+        let (x, y) = (self.x.max(other.x), self.y.max(other.y));
+        let (right, bottom) = ((self.x + self.width).min(other.x + other.width), (self.y + self.height).min(other.y + other.height));
+        return Rect { x, y, width: (right - x).max(0.0), height: (bottom - y).max(0.0) };
+    }
+
+    // The smallest rect that contains both rects
+    fn union(&self, other: &Rect) -> Rect {
+        let (x, y) = (self.x.min(other.x), self.y.min(other.y));
+        let (right, bottom) = ((self.x + self.width).max(other.x + other.width), (self.y + self.height).max(other.y + other.height));
+        return Rect { x, y, width: right - x, height: bottom - y };
+    }
+}
+
+// https://drafts.csswg.org/cssom-view/#css-layout-box
+// Where the box of an element was laid out, in CSS pixels from the top left of the document as it is painted, so the boxes in a scroll container are moved by its scroll position
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoxGeometry {
+    pub border_box: Rect,
+    // https://drafts.csswg.org/css-box-4/#padding-box
+    // The area the content of a scroll container is seen through, scrollbars take no room
+    pub padding_box: Rect,
+    // https://drafts.csswg.org/css-overflow-3/#scrollable-overflow-region
+    // How big the content of the box is, at least as big as its padding box
+    pub scroll_width: f32,
+    pub scroll_height: f32,
+    // Whether the box clips its content and can be scrolled
+    pub scrollable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DisplayList {
+    // The size of the document, which is as wide as the viewport and as tall as its content
+    pub width: f32,
+    pub height: f32,
+    pub items: Vec<DisplayItem>,
+}
+
+impl DisplayList {
+    pub fn to_json(&self) -> String {
+        return serde_json::to_string_pretty(self).unwrap_or_default();
+    }
+}
+
+// The display list of document laid out in a viewport width pixels wide, with text measured by metrics
+pub fn build_display_list(document: &RefNode, width: f32, metrics: &dyn FontMetrics) -> DisplayList {
+    return paint_document(document, Viewport { width: width as u32, ..Viewport::default() }, metrics).0;
+}
+
+// https://drafts.csswg.org/cssom-view/#viewport
+// The display list of what is seen of document in the viewport at its scroll position, as big as the viewport
+// Long documents can be drawn one viewport at a time by scrolling the viewport down by its height in between
+pub fn build_viewport_display_list(document: &RefNode, viewport: Viewport, metrics: &dyn FontMetrics) -> DisplayList {
+    let (width, height) = (viewport.width as f32, viewport.height as f32);
+    let (display_list, _) = paint_document(document, viewport, metrics);
+    let position = clamp_scroll_position(viewport_scroll_position(document), document_scroll_size(&display_list, metrics), (width, height));
+
+    // The items that are not in the viewport at all are left out, and so are the clips that are left with nothing in them
+    let visible = Rect { x: 0.0, y: 0.0, width, height };
+    let mut items = Vec::new();
+    for mut item in display_list.items {
+        item.translate(-position.x, -position.y);
+        match item {
+            DisplayItem::Clip { .. } => items.push(item),
+            DisplayItem::EndClip if matches!(items.last(), Some(DisplayItem::Clip { .. })) => {
+                items.pop();
+            },
+            DisplayItem::EndClip => items.push(item),
+            _ if item_bounds(&item, metrics).is_some_and(|bounds| bounds.intersects(&visible)) => items.push(item),
+            _ => ()
+        }
+    }
+    return DisplayList { width, height, items };
+}
+
+// The display list of document and the geometry of the boxes of its elements, in the order they were laid out
+pub(crate) fn paint_document(document: &RefNode, viewport: Viewport, metrics: &dyn FontMetrics) -> (DisplayList, Vec<(RefNode, BoxGeometry)>) {
+    let width = viewport.width as f32;
+    let mut painter = Painter {
+        metrics,
+        style_sheets: document_css_style_sheets(document),
+        items: Vec::new(),
+        geometry: Vec::new(),
+        viewport,
+        viewport_scroll_position: viewport_scroll_position(document),
+        direction: Direction::Ltr,
+        stacking_contexts: Vec::new(),
+        layer_count: 0,
+        out_of_flow_boxes: Vec::new(),
+        measuring: false,
+        pending_markers: Vec::new(),
+    };
+    let height = match build_box_tree(document) {
+        Some(root) => painter.paint_block(&root, 0.0, width, 0.0, &InheritedStyle::default()),
+        None => 0.0
+    };
+    return (DisplayList { width, height, items: painter.items }, painter.geometry);
+}
+
+// https://drafts.csswg.org/cssom-view/#scrolling-area
+// The width and height of what the viewport can be scrolled over, the document and anything that sticks out of it to the right
+pub(crate) fn document_scroll_size(display_list: &DisplayList, metrics: &dyn FontMetrics) -> (f32, f32) {
+    let right = content_bounds(&display_list.items, metrics).map_or(0.0, |bounds| bounds.x + bounds.width);
+    return (display_list.width.max(right), display_list.height);
+}
+
+// The area an item draws over, None for the end of a clip
+fn item_bounds(item: &DisplayItem, metrics: &dyn FontMetrics) -> Option<Rect> {
+    match item {
+        DisplayItem::Rect { rect, .. } | DisplayItem::Border { rect, .. } | DisplayItem::Image { rect, .. } | DisplayItem::Clip { rect } => return Some(*rect),
+        DisplayItem::Text { x, baseline, text, font_size, .. } => {
+            let ascent = metrics.ascent(*font_size);
+            return Some(Rect { x: *x, y: baseline - ascent, width: metrics.measure(text, *font_size), height: ascent + metrics.descent(*font_size) });
+        },
+        DisplayItem::EndClip => return None
+    }
+}
+
+// The area the items draw over, what is inside a clip only counts as far as the clip goes
+fn content_bounds(items: &[DisplayItem], metrics: &dyn FontMetrics) -> Option<Rect> {
+    let mut bounds: Option<Rect> = None;
+    let mut depth = 0;
+    for item in items {
+        if depth == 0 {
+            if let Some(item_bounds) = item_bounds(item, metrics) {
+                bounds = Some(bounds.map_or(item_bounds, |bounds| bounds.union(&item_bounds)));
+            }
+        }
+        match item {
+            DisplayItem::Clip { .. } => depth += 1,
+            DisplayItem::EndClip => depth -= 1,
+            _ => ()
+        }
+    }
+    return bounds;
+}
+
+// The values of the inherited properties that painting uses
+#[derive(Debug, Clone, Copy)]
+struct InheritedStyle {
+    color: Color,
+    font_size: f32,
+    // https://drafts.csswg.org/css-color-4/#transparency
+    // opacity is not inherited, but the content of an element is as transparent as the element and its ancestors together
+    opacity: f32,
+    list_style_position: ListStylePosition,
+}
+
+impl Default for InheritedStyle {
+    // https://drafts.csswg.org/css-color-4/#the-color-property
+    // The initial color is canvastext, black on a light canvas, and the initial font size is medium
+    fn default() -> Self {
+        return InheritedStyle { color: Color::Rgba { red: 0, green: 0, blue: 0, alpha: 1.0 }, font_size: 16.0, opacity: 1.0, list_style_position: ListStylePosition::Outside };
+    }
+}
+
+// The box model of a box in pixels, each side as top, right, bottom and left
+struct BoxModel {
+    margin: [f32; 4],
+    padding: [f32; 4],
+    border_widths: [f32; 4],
+    border_styles: [BorderStyle; 4],
+    border_colors: [Color; 4],
+    background_color: Option<Color>,
+    // The size of the content box, None for auto
+    width: Option<f32>,
+    height: Option<f32>,
+}
+
+// A piece of inline content, in the style of the box it came from
+enum InlineItem {
+    Text { text: String, white_space: WhiteSpace, style: InheritedStyle },
+    LineBreak { style: InheritedStyle },
+    Image { width: f32, height: f32, url: String },
+}
+
+// A run of text or an image of a line, see Painter::reorder_line
+enum LinePiece {
+    Run(String, InheritedStyle),
+    Image(f32, f32, String),
+}
+
+// A line being filled with text runs and images, the x of each is from the start of the line
+#[derive(Default)]
+struct Line {
+    width: f32,
+    // The ascent above and the descent below the baseline of the tallest piece so far
+    ascent: f32,
+    descent: f32,
+    runs: Vec<(f32, String, InheritedStyle)>,
+    images: Vec<(f32, f32, f32, String)>,
+    // Whether a collapsible space was left between the last piece and the next one
+    pending_space: bool,
+}
+
+// https://drafts.csswg.org/css2/#painting-order
+// The items of a positioned box and its content, which are painted at the z-index of the box in the stacking context it is in
+struct Layer {
+    z_index: i32,
+    // The order of the box in the tree, which layers with the same z-index are painted in
+    order: usize,
+    // Whether the box is fixed, which scroll containers do not move or clip
+    fixed: bool,
+    items: Vec<DisplayItem>,
+}
+
+// An absolutely positioned box waiting for the size of its containing block, with where it would have been in the flow
+struct OutOfFlowBox {
+    layout_box: LayoutBox,
+    static_x: f32,
+    static_y: f32,
+    parent_style: InheritedStyle,
+    fixed: bool,
+}
+
+// https://drafts.csswg.org/css-lists-3/#list-style-position-outside
+// The marker of a list item whose position is outside, waiting for the first line of the list item, x and width are those of its content box
+struct OutsideMarker {
+    text: String,
+    style: InheritedStyle,
+    x: f32,
+    width: f32,
+    direction: Direction,
+}
+
+// The width boxes are laid out in to find how wide their content is when none of its lines are broken
+const UNBOUNDED_WIDTH: f32 = 1.0e7;
+
+struct Painter<'a> {
+    metrics: &'a dyn FontMetrics,
+    style_sheets: Vec<Rc<RefCell<CSSStyleSheet>>>,
+    items: Vec<DisplayItem>,
+    geometry: Vec<(RefNode, BoxGeometry)>,
+    // The viewport the srcset of an image is chosen for, and which is the containing block of fixed boxes at its scroll position
+    viewport: Viewport,
+    viewport_scroll_position: ScrollPosition,
+    // The directionality of the block being painted, which its lines are ordered and aligned by
+    direction: Direction,
+    // The layers of the stacking contexts the box being painted is in, the innermost last
+    stacking_contexts: Vec<Vec<Layer>>,
+    // How many boxes were painted so far, which orders the layers as the tree is
+    layer_count: usize,
+    // The absolutely positioned boxes of each positioned box being painted, the root first, which are laid out at its end
+    out_of_flow_boxes: Vec<Vec<OutOfFlowBox>>,
+    // Whether boxes are only laid out to be measured, so their absolutely positioned descendants are left out
+    measuring: bool,
+    // The outside markers of the list items whose first line has not been painted yet, the outermost first
+    pending_markers: Vec<OutsideMarker>,
+}
+
+impl Painter<'_> {
+    // The specified values of the element or pseudo-element that generated a box
+    fn values(&self, layout_box: &LayoutBox) -> IndexMap<String, PropertyValue> {
+        match layout_box.pseudo_element {
+            Some(pseudo_element) => return pseudo_element_specified_values(&layout_box.node, pseudo_element, &self.style_sheets),
+            None => return specified_values(&layout_box.node, &self.style_sheets)
+        }
+    }
+
+    // Paints a block box whose margin box starts at x and y in a containing block width wide, and returns the y its margin box ends at
+    fn paint_block(&mut self, layout_box: &LayoutBox, x: f32, width: f32, y: f32, parent_style: &InheritedStyle) -> f32 {
+        return self.paint_sized_block(layout_box, x, width, y, parent_style, (None, None));
+    }
+
+    // paint_block with the width and height of the content box given, rather than from the box model, when they are Some
+    fn paint_sized_block(&mut self, layout_box: &LayoutBox, x: f32, width: f32, y: f32, parent_style: &InheritedStyle, used_size: (Option<f32>, Option<f32>)) -> f32 {
+        let values = self.values(layout_box);
+        let local_name = layout_box.local_name();
+        let style = inherited_style(&values, &local_name, layout_box, parent_style);
+        let box_model = box_model(&values, &local_name, &style, width);
+
+        let [margin_top, margin_right, margin_bottom, margin_left] = box_model.margin;
+        let [padding_top, padding_right, padding_bottom, padding_left] = box_model.padding;
+        let [border_top, border_right, border_bottom, border_left] = box_model.border_widths;
+        let horizontal_edges = padding_left + padding_right + border_left + border_right;
+
+        // https://drafts.csswg.org/css2/#relative-positioning
+        // A relatively positioned box is moved from where it is laid out, the boxes after it are laid out as if it were not
+        // TODO: Sticky positioning is treated as relative
+        let position = position(&values);
+        let (offset_x, offset_y) = match position {
+            Position::Relative | Position::Sticky => relative_offset(&values, style.font_size, width),
+            _ => (0.0, 0.0)
+        };
+        let (x, y) = (x + offset_x, y + offset_y);
+
+        // https://drafts.csswg.org/css2/#stacking-context
+        // The root element, a positioned box with a z-index other than auto, a fixed box and a box with an opacity less than 1 form stacking contexts
+        // A positioned box is painted on a layer of the stacking context it is in, the other boxes are painted in the order of the tree
+        let is_root = self.stacking_contexts.is_empty();
+        let positioned = position != Position::Static;
+        let z_index = match values.get("z-index") {
+            Some(PropertyValue::ZIndex(z_index)) if positioned => *z_index,
+            _ => None
+        };
+        let transparent = matches!(values.get("opacity"), Some(PropertyValue::Number(opacity)) if *opacity < 1.0);
+        let forms_stacking_context = is_root || z_index.is_some() || position == Position::Fixed || transparent;
+        let layer_order = self.layer_count;
+        self.layer_count += 1;
+        let parent_items = match positioned || forms_stacking_context {
+            true => Some(std::mem::take(&mut self.items)),
+            false => None
+        };
+        if forms_stacking_context {
+            self.stacking_contexts.push(Vec::new());
+        }
+        // The absolutely positioned descendants of a positioned box are laid out once its size is known, as it is their containing block
+        if positioned || is_root {
+            self.out_of_flow_boxes.push(Vec::new());
+        }
+
+        // https://drafts.csswg.org/css2/#blockwidth
+        // A block with an auto width fills its containing block
+        let content_width = used_size.0.or(box_model.width).unwrap_or(width - margin_left - margin_right - horizontal_edges).max(0.0);
+        let border_box = Rect { x: x + margin_left, y: y + margin_top, width: content_width + horizontal_edges, height: 0.0 };
+        let content_x = border_box.x + border_left + padding_left;
+        let content_y = border_box.y + border_top + padding_top;
+
+        // The background and border are painted under the content, they are put before its items once the height of the box is known
+        let first_content_item = self.items.len();
+        let first_descendant_geometry = self.geometry.len();
+        let previous_direction = self.direction;
+        self.direction = directionality(&layout_box.node);
+        let content_bottom = self.paint_block_children(layout_box, content_x, content_width, content_y, &style);
+        self.direction = previous_direction;
+        let content_height = used_size.1.or(box_model.height).unwrap_or(content_bottom - content_y);
+        let border_box = Rect { height: content_height + padding_top + padding_bottom + border_top + border_bottom, ..border_box };
+        let padding_box = Rect {
+            x: border_box.x + border_left,
+            y: border_box.y + border_top,
+            width: border_box.width - border_left - border_right,
+            height: border_box.height - border_top - border_bottom
+        };
+
+        // https://drafts.csswg.org/css-position-3/#abspos-layout
+        // The initial containing block of the root is the size of the viewport
+        if positioned || is_root {
+            let containing_block = match is_root {
+                true => Rect { x: 0.0, y: 0.0, width: self.viewport.width as f32, height: self.viewport.height as f32 },
+                false => padding_box
+            };
+            for out_of_flow_box in self.out_of_flow_boxes.pop().unwrap_or_default() {
+                self.paint_out_of_flow_box(out_of_flow_box, containing_block);
+            }
+        }
+
+        // The layers that were put on the stacking context the box is in while its content was painted, which are of its positioned descendants
+        let descendant_layers = match self.stacking_contexts.last_mut() {
+            Some(layers) if !forms_stacking_context => layers.iter_mut().filter(|layer| layer.order > layer_order && !layer.fixed).collect(),
+            _ => Vec::new()
+        };
+
+        // https://drafts.csswg.org/css-overflow-3/#scrollable
+        // The content that sticks out past the end of the padding box, with the padding at the end of the box after it
+        let mut bounds = content_bounds(&self.items[first_content_item..], self.metrics);
+        for layer in &descendant_layers {
+            if let Some(layer_bounds) = content_bounds(&layer.items, self.metrics) {
+                bounds = Some(bounds.map_or(layer_bounds, |bounds| bounds.union(&layer_bounds)));
+            }
+        }
+        let content_right = bounds.map_or(content_x, |bounds| bounds.x + bounds.width);
+        let content_bottom = bounds.map_or(content_bottom, |bounds| content_bottom.max(bounds.y + bounds.height));
+        let scroll_width = padding_box.width.max(content_right + padding_right - padding_box.x);
+        let scroll_height = padding_box.height.max(content_bottom + padding_bottom - padding_box.y);
+
+        // https://drafts.csswg.org/css-overflow-3/#overflow-properties
+        // The content of a box whose overflow is not visible is clipped to its padding box, and moved by the scroll position of a scroll container
+        // The layers of positioned descendants are clipped and moved as well, apart from fixed ones
+        // TODO: There are no scrollbars, a box whose overflow is clip in only one axis is clipped in both, the overflow of the root element and body is not propagated to the viewport and an absolutely positioned descendant is clipped even when its containing block is outside the box
+        let (overflow_x, overflow_y) = overflow(&values);
+        let scrollable = overflow_x.is_scrollable() || overflow_y.is_scrollable();
+        let clipped = overflow_x != Overflow::Visible || overflow_y != Overflow::Visible;
+        if clipped {
+            let scroll_position = match scrollable && layout_box.pseudo_element.is_none() {
+                true => clamp_scroll_position(element_scroll_position(&layout_box.node), (scroll_width, scroll_height), (padding_box.width, padding_box.height)),
+                false => Default::default()
+            };
+            for item in &mut self.items[first_content_item..] {
+                item.translate(-scroll_position.x, -scroll_position.y);
+            }
+            for layer in descendant_layers {
+                for item in &mut layer.items {
+                    item.translate(-scroll_position.x, -scroll_position.y);
+                }
+                layer.items.insert(0, DisplayItem::Clip { rect: padding_box });
+                layer.items.push(DisplayItem::EndClip);
+            }
+            for (_, geometry) in &mut self.geometry[first_descendant_geometry..] {
+                geometry.border_box = geometry.border_box.translate(-scroll_position.x, -scroll_position.y);
+                geometry.padding_box = geometry.padding_box.translate(-scroll_position.x, -scroll_position.y);
+            }
+            self.items.insert(first_content_item, DisplayItem::Clip { rect: padding_box });
+            self.items.push(DisplayItem::EndClip);
+        }
+        // The box of a pseudo-element is not the box of its element
+        if layout_box.pseudo_element.is_none() {
+            self.geometry.push((Rc::clone(&layout_box.node), BoxGeometry { border_box, padding_box, scroll_width, scroll_height, scrollable }));
+        }
+
+        let mut box_items = Vec::new();
+        if let Some(color) = box_model.background_color.map(|color| with_opacity(color, style.opacity)).filter(|color| !is_transparent(color)) {
+            box_items.push(DisplayItem::Rect { rect: border_box, color });
+        }
+        if box_model.border_widths.iter().any(|width| *width > 0.0) {
+            box_items.push(DisplayItem::Border { rect: border_box, widths: box_model.border_widths, styles: box_model.border_styles, colors: box_model.border_colors.map(|color| with_opacity(color, style.opacity)) });
+        }
+        let content_start = first_content_item + box_items.len() + if clipped { 1 } else { 0 };
+        self.items.splice(first_content_item..first_content_item, box_items);
+
+        // https://drafts.csswg.org/css2/#painting-order
+        // The layers of a stacking context with a negative z-index are painted over its background and border and under its content, the others over its content, from the lowest z-index to the highest and in the order of the tree
+        if forms_stacking_context {
+            let mut layers = self.stacking_contexts.pop().unwrap_or_default();
+            layers.sort_by_key(|layer| (layer.z_index, layer.order));
+            let content_end = if clipped { self.items.len() - 1 } else { self.items.len() };
+            let mut items: Vec<DisplayItem> = self.items.drain(..content_start).collect();
+            let mut content: Vec<DisplayItem> = self.items.drain(..content_end - content_start).collect();
+            let (negative, others): (Vec<Layer>, Vec<Layer>) = layers.into_iter().partition(|layer| layer.z_index < 0);
+            items.extend(negative.into_iter().flat_map(|layer| layer.items));
+            items.append(&mut content);
+            items.extend(others.into_iter().flat_map(|layer| layer.items));
+            items.append(&mut self.items);
+            self.items = items;
+        }
+        if let Some(parent_items) = parent_items {
+            let items = std::mem::replace(&mut self.items, parent_items);
+            match self.stacking_contexts.last_mut() {
+                Some(layers) => layers.push(Layer { z_index: z_index.unwrap_or(0), order: layer_order, fixed: position == Position::Fixed, items }),
+                None => self.items.extend(items)
+            }
+        }
+
+        return border_box.y + border_box.height + margin_bottom - offset_y;
+    }
+
+    // https://drafts.csswg.org/css2/#abs-non-replaced-width
+    // https://drafts.csswg.org/css2/#abs-non-replaced-height
+    // Paints an absolutely positioned box in its containing block, a box with auto insets is where it would have been if it were not positioned
+    // TODO: Auto margins are 0, and the width of a box with an auto width and an auto left or right is only as wide as its text and images
+    fn paint_out_of_flow_box(&mut self, out_of_flow_box: OutOfFlowBox, containing_block: Rect) {
+        let OutOfFlowBox { layout_box, static_x, static_y, parent_style, fixed } = out_of_flow_box;
+        // https://drafts.csswg.org/css-position-3/#fixed-cb
+        // The containing block of a fixed box is the viewport, wherever it is scrolled to
+        let containing_block = match fixed {
+            true => Rect { x: self.viewport_scroll_position.x, y: self.viewport_scroll_position.y, width: self.viewport.width as f32, height: self.viewport.height as f32 },
+            false => containing_block
+        };
+        let values = self.values(&layout_box);
+        let local_name = layout_box.local_name();
+        let style = inherited_style(&values, &local_name, &layout_box, &parent_style);
+        let box_model = box_model(&values, &local_name, &style, containing_block.width);
+        let inset = |property: &str, percentage_basis: f32| match values.get(property) {
+            Some(PropertyValue::Length(length)) => resolve_length(length, style.font_size, percentage_basis),
+            _ => None
+        };
+        let (top, right, bottom, left) = (inset("top", containing_block.height), inset("right", containing_block.width), inset("bottom", containing_block.height), inset("left", containing_block.width));
+        let [margin_top, margin_right, margin_bottom, margin_left] = box_model.margin;
+        let [padding_top, padding_right, padding_bottom, padding_left] = box_model.padding;
+        let [border_top, border_right, border_bottom, border_left] = box_model.border_widths;
+        let horizontal_edges = margin_left + margin_right + padding_left + padding_right + border_left + border_right;
+        let vertical_edges = margin_top + margin_bottom + padding_top + padding_bottom + border_top + border_bottom;
+
+        // The width is what is left between left and right when both are given, and shrinks to fit the content otherwise
+        let content_width = match (box_model.width, left, right) {
+            (Some(width), _, _) => width,
+            (None, Some(left), Some(right)) => containing_block.width - left - right - horizontal_edges,
+            (None, _, _) => {
+                let available_width = containing_block.width - left.unwrap_or(0.0) - right.unwrap_or(0.0) - horizontal_edges;
+                self.preferred_width(&layout_box, containing_block.width, &parent_style).min(available_width)
+            }
+        }.max(0.0);
+        let x = match (left, right) {
+            (Some(left), _) => containing_block.x + left,
+            (None, Some(right)) => containing_block.x + containing_block.width - right - content_width - horizontal_edges,
+            (None, None) => static_x
+        };
+
+        let content_height = match (box_model.height, top, bottom) {
+            (Some(height), _, _) => Some(height),
+            (None, Some(top), Some(bottom)) => Some((containing_block.height - top - bottom - vertical_edges).max(0.0)),
+            _ => None
+        };
+        let y = match (top, bottom) {
+            (Some(top), _) => containing_block.y + top,
+            (None, Some(bottom)) => {
+                let height = match content_height {
+                    Some(content_height) => content_height + vertical_edges,
+                    None => self.measuring_painter().paint_sized_block(&layout_box, 0.0, containing_block.width, 0.0, &parent_style, (Some(content_width), None))
+                };
+                containing_block.y + containing_block.height - bottom - height
+            },
+            (None, None) => static_y
+        };
+        self.paint_sized_block(&layout_box, x, containing_block.width, y, &parent_style, (Some(content_width), content_height));
+    }
+
+    // https://drafts.csswg.org/css-sizing-3/#max-content-inline-size
+    // How wide the content box of a box would be if none of its lines were broken
+    fn preferred_width(&self, layout_box: &LayoutBox, containing_width: f32, parent_style: &InheritedStyle) -> f32 {
+        let mut painter = self.measuring_painter();
+        // The content box starts at 0, as the margins, borders and padding are left out of the width
+        let values = self.values(layout_box);
+        let local_name = layout_box.local_name();
+        let box_model = box_model(&values, &local_name, &inherited_style(&values, &local_name, layout_box, parent_style), containing_width);
+        let content_x = box_model.margin[3] + box_model.border_widths[3] + box_model.padding[3];
+        painter.paint_sized_block(layout_box, -content_x, containing_width, 0.0, parent_style, (Some(UNBOUNDED_WIDTH), None));
+        let right = painter.items.iter()
+            .filter(|item| matches!(item, DisplayItem::Text { .. } | DisplayItem::Image { .. }))
+            .filter_map(|item| item_bounds(item, self.metrics))
+            .map(|bounds| bounds.x + bounds.width)
+            .fold(0.0, f32::max);
+        return right;
+    }
+
+    // A painter to lay boxes out with to measure them, it leaves out their absolutely positioned descendants
+    fn measuring_painter(&self) -> Painter<'_> {
+        return Painter {
+            metrics: self.metrics,
+            style_sheets: self.style_sheets.clone(),
+            items: Vec::new(),
+            geometry: Vec::new(),
+            viewport: self.viewport,
+            viewport_scroll_position: self.viewport_scroll_position,
+            direction: self.direction,
+            stacking_contexts: Vec::new(),
+            layer_count: 0,
+            out_of_flow_boxes: Vec::new(),
+            measuring: true,
+            pending_markers: Vec::new(),
+        };
+    }
+
+    // https://drafts.csswg.org/css2/#anonymous-block-level
+    // The children of a block, the inline content between its block children is laid out as if it were in a block of its own
+    fn paint_block_children(&mut self, layout_box: &LayoutBox, x: f32, width: f32, y: f32, style: &InheritedStyle) -> f32 {
+        let mut inline_items = Vec::new();
+        let mut y = y;
+        let pending_marker_count = self.pending_markers.len();
+        for child in &layout_box.children {
+            // https://drafts.csswg.org/css-lists-3/#list-style-position-property
+            // An outside marker is painted by the first line of the list item, an inside one is inline content like any other
+            if child.pseudo_element == Some(PseudoElement::Marker) && style.list_style_position == ListStylePosition::Outside {
+                let marker_style = inherited_style(&self.values(child), "", child, style);
+                let text: String = child.children.iter().filter_map(|text_box| match &text_box.box_type {
+                    BoxType::Text(text) => Some(text.as_str()),
+                    _ => None
+                }).collect();
+                if !text.is_empty() {
+                    self.pending_markers.push(OutsideMarker { text, style: marker_style, x, width, direction: self.direction });
+                }
+                continue;
+            }
+            match child.box_type {
+                BoxType::Block | BoxType::ListItem => {
+                    y = self.paint_inline_content(std::mem::take(&mut inline_items), x, width, y);
+                    let child_position = position(&self.values(child));
+                    if !child_position.is_out_of_flow() {
+                        y = self.paint_block(child, x, width, y, style);
+                        continue;
+                    }
+                    // An absolutely positioned box is laid out once its containing block is, at the position it has in the flow now
+                    // The containing block of a fixed box is the viewport, so it is laid out with the boxes of the root
+                    let fixed = child_position == Position::Fixed;
+                    let out_of_flow_box = OutOfFlowBox { layout_box: child.clone(), static_x: x, static_y: y, parent_style: *style, fixed };
+                    let out_of_flow_boxes = if fixed { self.out_of_flow_boxes.first_mut() } else { self.out_of_flow_boxes.last_mut() };
+                    if let Some(out_of_flow_boxes) = out_of_flow_boxes.filter(|_| !self.measuring) {
+                        out_of_flow_boxes.push(out_of_flow_box);
+                    }
+                },
+                BoxType::Inline | BoxType::Text(_) => self.collect_inline_items(child, style, &mut inline_items)
+            }
+        }
+        let mut y = self.paint_inline_content(inline_items, x, width, y);
+
+        // A list item with no lines still gets its marker, on a line of its own
+        if self.pending_markers.len() > pending_marker_count {
+            let font_size = self.pending_markers.iter().map(|marker| marker.style.font_size).fold(0.0, f32::max);
+            self.paint_pending_markers(y + half_leading(self.metrics, font_size) + self.metrics.ascent(font_size));
+            y += self.metrics.line_height(font_size);
+        }
+        return y;
+    }
+
+    // Paints the outside markers waiting for a line on the line whose baseline is at baseline, each before the start of the line of its list item
+    // The space after a marker is left between it and the line rather than painted
+    fn paint_pending_markers(&mut self, baseline: f32) {
+        for marker in std::mem::take(&mut self.pending_markers) {
+            let marker_width = self.metrics.measure(&marker.text, marker.style.font_size);
+            let text = marker.text.trim_end().to_string();
+            let marker_x = match marker.direction {
+                Direction::Rtl => marker.x + marker.width + marker_width - self.metrics.measure(&text, marker.style.font_size),
+                Direction::Ltr => marker.x - marker_width
+            };
+            self.items.push(DisplayItem::Text { x: marker_x, baseline, text, font_size: marker.style.font_size, color: with_opacity(marker.style.color, marker.style.opacity) });
+        }
+    }
+
+    fn collect_inline_items(&self, layout_box: &LayoutBox, parent_style: &InheritedStyle, inline_items: &mut Vec<InlineItem>) {
+        if let BoxType::Text(text) = &layout_box.box_type {
+            inline_items.push(InlineItem::Text { text: text.to_string(), white_space: layout_box.white_space, style: *parent_style });
+            return;
+        }
+
+        let values = self.values(layout_box);
+        let local_name = layout_box.local_name();
+        let style = inherited_style(&values, &local_name, layout_box, parent_style);
+        match local_name.as_str() {
+            // https://html.spec.whatwg.org/multipage/rendering.html#phrasing-content-3
+            "br" => inline_items.push(InlineItem::LineBreak { style }),
+            // https://html.spec.whatwg.org/multipage/rendering.html#images-3
+            // TODO: Images are not loaded, so one is only as big as its width and height attributes and is replaced by its alternative text without them
+            "img" => {
+                let dimension = |name: &str| layout_box.get_attribute(name).and_then(|value| value.trim().parse::<f32>().ok()).filter(|value| *value > 0.0);
+                // The display list is drawn one pixel for each CSS pixel, so the candidate for a device pixel ratio of 1 is chosen
+                let url = select_source(&layout_box.node, &self.viewport, 1.0).map(|src| encoding_parse_url(&layout_box.node, &src).map(|url| url.href()).unwrap_or(src));
+                match (dimension("width"), dimension("height"), url) {
+                    (Some(width), Some(height), Some(url)) => inline_items.push(InlineItem::Image { width, height, url }),
+                    _ => {
+                        if let Some(alt) = layout_box.get_attribute("alt").filter(|alt| !alt.trim().is_empty()) {
+                            inline_items.push(InlineItem::Text { text: alt.trim().to_string(), white_space: layout_box.white_space, style });
+                        }
+                    }
+                }
+            },
+            _ => {
+                for child in &layout_box.children {
+                    self.collect_inline_items(child, &style, inline_items);
+                }
+            }
+        }
+    }
+
+    // https://drafts.csswg.org/css-text-3/#line-breaking
+    // Breaks inline content into lines width wide starting at y, paints them, and returns the y the last line ends at
+    fn paint_inline_content(&mut self, inline_items: Vec<InlineItem>, x: f32, width: f32, y: f32) -> f32 {
+        let mut y = y;
+        let mut line = Line::default();
+        for inline_item in inline_items {
+            match inline_item {
+                InlineItem::Text { text, white_space, style } => {
+                    // A preserved segment break is a forced line break, and lines only wrap at spaces where white-space lets them
+                    let segment_breaks_preserved = white_space.preserves_spaces() || white_space == WhiteSpace::PreLine;
+                    let mut segments = text.split('\n').peekable();
+                    while let Some(segment) = segments.next() {
+                        if white_space.preserves_spaces() {
+                            self.place_word(&mut line, segment, &style, false, width, x, &mut y);
+                        } else {
+                            let words = segment.split(' ');
+                            let word_count = segment.split(' ').count();
+                            for (index, word) in words.enumerate() {
+                                if !word.is_empty() {
+                                    self.place_word(&mut line, word, &style, white_space.wraps(), width, x, &mut y);
+                                }
+                                // The spaces at the start of a line are removed
+                                if index + 1 < word_count && (!line.runs.is_empty() || !line.images.is_empty()) {
+                                    line.pending_space = true;
+                                }
+                            }
+                        }
+                        if segments.peek().is_some() && segment_breaks_preserved {
+                            self.end_line(&mut line, &style, x, width, &mut y);
+                        }
+                    }
+                },
+                InlineItem::LineBreak { style } => self.end_line(&mut line, &style, x, width, &mut y),
+                InlineItem::Image { width: image_width, height, url } => {
+                    let space = if line.pending_space { self.metrics.advance_width(' ', InheritedStyle::default().font_size) } else { 0.0 };
+                    if line.width > 0.0 && line.width + space + image_width > width {
+                        self.end_line(&mut line, &InheritedStyle::default(), x, width, &mut y);
+                    }
+                    let image_x = if line.width > 0.0 { line.width + space } else { 0.0 };
+                    line.images.push((image_x, image_width, height, url));
+                    line.width = image_x + image_width;
+                    line.ascent = line.ascent.max(height);
+                    line.pending_space = false;
+                }
+            }
+        }
+        if !line.runs.is_empty() || !line.images.is_empty() {
+            self.end_line(&mut line, &InheritedStyle::default(), x, width, &mut y);
+        }
+        return y;
+    }
+
+    // Puts word on the line after a space if one was left before it, or on a new line if it does not fit and the line can wrap
+    #[allow(clippy::too_many_arguments)]
+    fn place_word(&mut self, line: &mut Line, word: &str, style: &InheritedStyle, wraps: bool, width: f32, x: f32, y: &mut f32) {
+        let space_width = self.metrics.advance_width(' ', style.font_size);
+        let word_width = self.metrics.measure(word, style.font_size);
+        let line_is_empty = line.runs.is_empty() && line.images.is_empty();
+        if wraps && !line_is_empty && line.width + if line.pending_space { space_width } else { 0.0 } + word_width > width {
+            self.end_line(line, style, x, width, y);
+        }
+
+        let line_is_empty = line.runs.is_empty() && line.images.is_empty();
+        let space = if line.pending_space && !line_is_empty { space_width } else { 0.0 };
+        let same_style = |(_, _, run_style): &(f32, String, InheritedStyle)| run_style.font_size == style.font_size && run_style.color == style.color;
+        match line.runs.last_mut() {
+            // A word in the same style as the run before it, with nothing in between, goes on the end of the run
+            Some(run) if same_style(run) && line.images.last().is_none_or(|image| image.0 < run.0) => {
+                if space > 0.0 {
+                    run.1.push(' ');
+                }
+                run.1.push_str(word);
+            },
+            _ => line.runs.push((line.width + space, word.to_string(), *style))
+        }
+        line.width += space + word_width;
+        line.ascent = line.ascent.max(half_leading(self.metrics, style.font_size) + self.metrics.ascent(style.font_size));
+        line.descent = line.descent.max(half_leading(self.metrics, style.font_size) + self.metrics.descent(style.font_size));
+        line.pending_space = false;
+    }
+
+    // Paints the line at y and moves y to below it, an empty line (e.g. of two br in a row) is as tall as the font of style
+    // The line is width wide, and the line of a right-to-left block starts at its right
+    fn end_line(&mut self, line: &mut Line, style: &InheritedStyle, x: f32, width: f32, y: &mut f32) {
+        let line = std::mem::take(line);
+        if line.runs.is_empty() && line.images.is_empty() {
+            *y += self.metrics.line_height(style.font_size);
+            return;
+        }
+        let line = self.reorder_line(line, width);
+
+        let baseline = *y + line.ascent;
+        self.paint_pending_markers(baseline);
+        for (run_x, text, run_style) in line.runs {
+            self.items.push(DisplayItem::Text { x: x + run_x, baseline, text, font_size: run_style.font_size, color: with_opacity(run_style.color, run_style.opacity) });
+        }
+        // Images sit on the baseline
+        for (image_x, width, height, url) in line.images {
+            self.items.push(DisplayItem::Image { rect: Rect { x: x + image_x, y: baseline - height, width, height }, url });
+        }
+        *y += line.ascent + line.descent;
+    }
+
+    // https://www.unicode.org/reports/tr9/#Reordering_Resolved_Levels
+    // The runs and images of the line in the order they are displayed and placed from its start, a right-to-left run has its characters reversed so a backend draws each run from left to right
+    fn reorder_line(&self, line: Line, width: f32) -> Line {
+        let start_x = match self.direction {
+            Direction::Rtl => (width - line.width).max(0.0),
+            Direction::Ltr => 0.0
+        };
+
+        // The pieces of the line in the order they were placed, which is the order they are written in
+        let mut pieces: Vec<(f32, LinePiece)> = Vec::new();
+        pieces.extend(line.runs.iter().map(|(run_x, text, style)| (*run_x, LinePiece::Run(text.clone(), *style))));
+        pieces.extend(line.images.iter().map(|(image_x, image_width, height, url)| (*image_x, LinePiece::Image(*image_width, *height, url.clone()))));
+        pieces.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // The text of the line with the spaces between its pieces, and an object replacement character for each image, which is neutral
+        let mut text = String::new();
+        let mut ranges = Vec::new();
+        let mut end = 0.0;
+        for (piece_x, piece) in &pieces {
+            if *piece_x > end + 0.01 {
+                text.push(' ');
+            }
+            let start = text.len();
+            match piece {
+                LinePiece::Run(run_text, style) => {
+                    text.push_str(run_text);
+                    end = piece_x + self.metrics.measure(run_text, style.font_size);
+                },
+                LinePiece::Image(image_width, _, _) => {
+                    text.push('\u{FFFC}');
+                    end = piece_x + image_width;
+                }
+            }
+            ranges.push(start..text.len());
+        }
+
+        if !needs_reordering(&text, self.direction) {
+            let runs = line.runs.into_iter().map(|(run_x, text, style)| (start_x + run_x, text, style)).collect();
+            let images = line.images.into_iter().map(|(image_x, image_width, height, url)| (start_x + image_x, image_width, height, url)).collect();
+            return Line { runs, images, ..line };
+        }
+
+        // Each visual run is made of the parts of the pieces it covers and the spaces between them, in reverse in a right-to-left run
+        let mut reordered = Line { width: line.width, ascent: line.ascent, descent: line.descent, ..Line::default() };
+        let mut cursor = start_x;
+        for (run, rtl) in visual_runs(&text, self.direction) {
+            let mut parts: Vec<(Option<&LinePiece>, Range<usize>)> = Vec::new();
+            let mut position = run.start;
+            for ((_, piece), range) in pieces.iter().zip(&ranges) {
+                let (part_start, part_end) = (range.start.max(run.start), range.end.min(run.end));
+                if part_start >= part_end {
+                    continue;
+                }
+                if part_start > position {
+                    parts.push((None, position..part_start));
+                }
+                parts.push((Some(piece), part_start..part_end));
+                position = part_end;
+            }
+            if position < run.end {
+                parts.push((None, position..run.end));
+            }
+            if rtl {
+                parts.reverse();
+            }
+
+            for (piece, part) in parts {
+                match piece {
+                    Some(LinePiece::Run(_, style)) => {
+                        let part_text: String = if rtl { text[part].chars().rev().collect() } else { text[part].to_string() };
+                        let part_width = self.metrics.measure(&part_text, style.font_size);
+                        reordered.runs.push((cursor, part_text, *style));
+                        cursor += part_width;
+                    },
+                    Some(LinePiece::Image(image_width, height, url)) => {
+                        reordered.images.push((cursor, *image_width, *height, url.clone()));
+                        cursor += image_width;
+                    },
+                    None => cursor += self.metrics.advance_width(' ', InheritedStyle::default().font_size) * text[part].chars().count() as f32
+                }
+            }
+        }
+        return reordered;
+    }
+}
+
+// https://drafts.csswg.org/css-inline-3/#half-leading
+// Half the space between lines, which is the line gap as line-height is normal
+fn half_leading(metrics: &dyn FontMetrics, font_size: f32) -> f32 {
+    return metrics.line_gap(font_size) / 2.0;
+}
+
+fn is_transparent(color: &Color) -> bool {
+    return matches!(color, Color::Rgba { alpha, .. } if *alpha <= 0.0);
+}
+
+// The color as it is painted in an element whose opacity and its ancestors' together is opacity
+fn with_opacity(color: Color, opacity: f32) -> Color {
+    match color {
+        Color::Rgba { red, green, blue, alpha } if opacity < 1.0 => Color::Rgba { red, green, blue, alpha: alpha * opacity },
+        color => color
+    }
+}
+
+// https://drafts.csswg.org/css-cascade-4/#computed
+// The color and font size of a box, from its specified values or the user agent's, and inherited from parent_style otherwise
+fn inherited_style(values: &IndexMap<String, PropertyValue>, local_name: &str, layout_box: &LayoutBox, parent_style: &InheritedStyle) -> InheritedStyle {
+    // https://drafts.csswg.org/css-fonts-4/#font-size-prop
+    // Relative sizes and ems are of the parent's font size
+    let parent_font_size = parent_style.font_size;
+    let font_size = match values.get("font-size") {
+        Some(PropertyValue::FontSize(FontSize::Absolute(absolute_size))) => absolute_size.px(),
+        Some(PropertyValue::FontSize(FontSize::Larger)) => parent_font_size * 1.2,
+        Some(PropertyValue::FontSize(FontSize::Smaller)) => parent_font_size / 1.2,
+        Some(PropertyValue::FontSize(FontSize::Length(length))) => resolve_length(length, parent_font_size, parent_font_size).unwrap_or(parent_font_size),
+        Some(PropertyValue::CssWideKeyword(CssWideKeyword::Initial)) => 16.0,
+        Some(_) => parent_font_size,
+        None => default_font_size_scale(local_name).map_or(parent_font_size, |scale| parent_font_size * scale)
+    };
+
+    // https://drafts.csswg.org/css-color-4/#resolving-other-colors
+    // currentcolor as the value of color is the parent's color
+    let color = match values.get("color") {
+        Some(PropertyValue::Color(Color::CurrentColor)) => parent_style.color,
+        Some(PropertyValue::Color(color)) => *color,
+        Some(PropertyValue::CssWideKeyword(CssWideKeyword::Initial)) => InheritedStyle::default().color,
+        Some(_) => parent_style.color,
+        // https://html.spec.whatwg.org/multipage/rendering.html#phrasing-content-3
+        // :link { color: #0000EE; }
+        None if local_name == "a" && layout_box.get_attribute("href").is_some() => Color::Rgba { red: 0, green: 0, blue: 0xEE, alpha: 1.0 },
+        None => parent_style.color
+    };
+
+    // Values out of the range of 0 to 1 are clamped
+    let opacity = match values.get("opacity") {
+        Some(PropertyValue::Number(opacity)) => opacity.clamp(0.0, 1.0),
+        _ => 1.0
+    };
+
+    // https://drafts.csswg.org/css-lists-3/#list-style-position-property
+    let list_style_position = match values.get("list-style-position") {
+        Some(PropertyValue::ListStylePosition(list_style_position)) => *list_style_position,
+        Some(PropertyValue::CssWideKeyword(CssWideKeyword::Initial)) => ListStylePosition::Outside,
+        _ => parent_style.list_style_position
+    };
+    return InheritedStyle { color, font_size, opacity: parent_style.opacity * opacity, list_style_position };
+}
+
+// https://drafts.csswg.org/css-position-3/#position-property
+fn position(values: &IndexMap<String, PropertyValue>) -> Position {
+    match values.get("position") {
+        Some(PropertyValue::Position(position)) => *position,
+        _ => Position::Static
+    }
+}
+
+// https://drafts.csswg.org/css-position-3/#relpos-insets
+// How far a relatively positioned box is moved right and down, left wins over right and top over bottom when both are given
+// TODO: Percentages of top and bottom are of the height of the containing block, which is not known, so they are 0
+fn relative_offset(values: &IndexMap<String, PropertyValue>, font_size: f32, containing_width: f32) -> (f32, f32) {
+    let inset = |property: &str, percentage_basis: f32| match values.get(property) {
+        Some(PropertyValue::Length(length)) => resolve_length(length, font_size, percentage_basis),
+        _ => None
+    };
+    let x = match (inset("left", containing_width), inset("right", containing_width)) {
+        (Some(left), _) => left,
+        (None, Some(right)) => -right,
+        (None, None) => 0.0
+    };
+    let y = match (inset("top", 0.0), inset("bottom", 0.0)) {
+        (Some(top), _) => top,
+        (None, Some(bottom)) => -bottom,
+        (None, None) => 0.0
+    };
+    return (x, y);
+}
+
+// https://drafts.csswg.org/css-overflow-3/#overflow-control
+// The overflow in the x and y axis, visible or clip becomes auto or hidden when the other axis can be scrolled
+fn overflow(values: &IndexMap<String, PropertyValue>) -> (Overflow, Overflow) {
+    let value = |property: &str| match values.get(property) {
+        Some(PropertyValue::Overflow(overflow)) => *overflow,
+        _ => Overflow::Visible
+    };
+    let (overflow_x, overflow_y) = (value("overflow-x"), value("overflow-y"));
+    if !overflow_x.is_scrollable() && !overflow_y.is_scrollable() {
+        return (overflow_x, overflow_y);
+    }
+    let computed = |overflow: Overflow| match overflow {
+        Overflow::Visible => Overflow::Auto,
+        Overflow::Clip => Overflow::Hidden,
+        overflow => overflow
+    };
+    return (computed(overflow_x), computed(overflow_y));
+}
+
+// https://html.spec.whatwg.org/multipage/rendering.html#sections-and-headings
+// The font size of a heading relative to its parent's in the user agent style sheet
+fn default_font_size_scale(local_name: &str) -> Option<f32> {
+    match local_name {
+        "h1" => Some(2.0),
+        "h2" => Some(1.5),
+        "h3" => Some(1.17),
+        "h5" => Some(0.83),
+        "h6" => Some(0.67),
+        _ => None
+    }
+}
+
+// The margins, padding, borders, background and size of a box, percentages are of the width of its containing block
+fn box_model(values: &IndexMap<String, PropertyValue>, local_name: &str, style: &InheritedStyle, containing_width: f32) -> BoxModel {
+    let (default_margin, default_padding, default_border) = default_box_model(local_name);
+    let length = |property: &str, default: Length| -> Option<f32> {
+        let length = match values.get(property) {
+            Some(PropertyValue::Length(length)) => *length,
+            // TODO: inherit is treated as initial for the properties that are not inherited
+            Some(_) => Length::Px(0.0),
+            None => default
+        };
+        return resolve_length(&length, style.font_size, containing_width);
+    };
+    let sides = ["top", "right", "bottom", "left"];
+
+    // https://drafts.csswg.org/css2/#margin-properties
+    // TODO: An auto margin is 0 rather than taking up the width left over, so blocks can not be centered
+    let margin = std::array::from_fn(|index| length(&format!("margin-{}", sides[index]), default_margin[index]).unwrap_or(0.0));
+    let padding = std::array::from_fn(|index| length(&format!("padding-{}", sides[index]), default_padding[index]).unwrap_or(0.0));
+
+    // https://drafts.csswg.org/css-backgrounds-3/#border-style
+    // A side whose style is none or hidden has no border, whatever its width
+    let border_styles: [BorderStyle; 4] = std::array::from_fn(|index| match values.get(&format!("border-{}-style", sides[index])) {
+        Some(PropertyValue::BorderStyle(border_style)) => *border_style,
+        Some(_) => BorderStyle::None,
+        None => default_border.map_or(BorderStyle::None, |(border_style, _)| border_style)
+    });
+    let border_widths = std::array::from_fn(|index| match border_styles[index] {
+        BorderStyle::None | BorderStyle::Hidden => 0.0,
+        _ => length(&format!("border-{}-width", sides[index]), default_border.map_or(Length::Px(3.0), |(_, width)| width)).unwrap_or(0.0)
+    });
+    // The initial border color is currentcolor
+    let border_colors = std::array::from_fn(|index| match values.get(&format!("border-{}-color", sides[index])) {
+        Some(PropertyValue::Color(Color::CurrentColor)) | Some(PropertyValue::CssWideKeyword(_)) => style.color,
+        Some(PropertyValue::Color(color)) => *color,
+        // https://html.spec.whatwg.org/multipage/rendering.html#the-hr-element-2
+        None if local_name == "hr" => Color::Rgba { red: 0x80, green: 0x80, blue: 0x80, alpha: 1.0 },
+        _ => style.color
+    });
+
+    let background_color = match values.get("background-color") {
+        Some(PropertyValue::Color(Color::CurrentColor)) => Some(style.color),
+        Some(PropertyValue::Color(color)) => Some(*color),
+        _ => None
+    };
+    return BoxModel {
+        margin,
+        padding,
+        border_widths,
+        border_styles,
+        border_colors,
+        background_color,
+        width: length("width", Length::Auto),
+        height: length("height", Length::Auto),
+    };
+}
+
+// https://html.spec.whatwg.org/multipage/rendering.html#the-page
+// https://html.spec.whatwg.org/multipage/rendering.html#flow-content-3
+// https://html.spec.whatwg.org/multipage/rendering.html#sections-and-headings
+// https://html.spec.whatwg.org/multipage/rendering.html#lists
+// The margins, padding and border (style and width) of the user agent style sheet
+fn default_box_model(local_name: &str) -> ([Length; 4], [Length; 4], Option<(BorderStyle, Length)>) {
+    let none = [Length::Px(0.0); 4];
+    let vertical = |em: f32| [Length::Em(em), Length::Px(0.0), Length::Em(em), Length::Px(0.0)];
+    match local_name {
+        "body" => return ([Length::Px(8.0); 4], none, None),
+        "p" | "dl" | "pre" | "listing" | "xmp" | "plaintext" => return (vertical(1.0), none, None),
+        "blockquote" | "figure" => return ([Length::Em(1.0), Length::Px(40.0), Length::Em(1.0), Length::Px(40.0)], none, None),
+        "dd" => return ([Length::Px(0.0), Length::Px(0.0), Length::Px(0.0), Length::Px(40.0)], none, None),
+        "ul" | "ol" | "menu" | "dir" => return (vertical(1.0), [Length::Px(0.0), Length::Px(0.0), Length::Px(0.0), Length::Px(40.0)], None),
+        "h1" => return (vertical(0.67), none, None),
+        "h2" => return (vertical(0.83), none, None),
+        "h3" => return (vertical(1.0), none, None),
+        "h4" => return (vertical(1.33), none, None),
+        "h5" => return (vertical(1.67), none, None),
+        "h6" => return (vertical(2.33), none, None),
+        // https://html.spec.whatwg.org/multipage/rendering.html#the-hr-element-2
+        "hr" => return (vertical(0.5), none, Some((BorderStyle::Inset, Length::Px(1.0)))),
+        _ => return (none, none, None)
+    }
+}
+
+// The length in pixels, ems are of font_size and percentages of percentage_basis, None for auto
+fn resolve_length(length: &Length, font_size: f32, percentage_basis: f32) -> Option<f32> {
+    match length {
+        Length::Px(px) => return Some(*px),
+        Length::Em(em) => return Some(em * font_size),
+        Length::Percentage(percentage) => return Some(percentage / 100.0 * percentage_basis),
+        Length::Auto => return None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::css_values::{BorderStyle, Color};
+    use crate::html_document_parser::ParserOptions;
+    use crate::text::BuiltinFontMetrics;
+    use crate::tokenizer::Tokenizer;
+    use super::{build_display_list, DisplayItem, Rect};
+
+    // The items of html painted 800 pixels wide, without the default margin of the body so boxes start at the top left
+    fn display_items(html: &str) -> Vec<DisplayItem> {
+        let html = format!("<!DOCTYPE html><style>body {{ margin: 0 }}</style>{}", html);
+        let mut tokenizer = Tokenizer::from_bytes(html.into_bytes(), ParserOptions { scripting_enabled: false, print_parse_errors: false, ..ParserOptions::default() });
+        tokenizer.run();
+        return build_display_list(&tokenizer.document(), 800.0, &BuiltinFontMetrics::default()).items;
+    }
+
+    fn rgb(red: u8, green: u8, blue: u8) -> Color {
+        return Color::Rgba { red, green, blue, alpha: 1.0 };
+    }
+
+    #[test]
+    fn a_background_is_a_rect_of_the_border_box() {
+        let items = display_items("<div style=\"width: 100px; height: 50px; padding: 5px; background-color: rgb(0, 128, 0)\"></div>");
+        assert_eq!(items, vec![DisplayItem::Rect { rect: Rect { x: 0.0, y: 0.0, width: 110.0, height: 60.0 }, color: rgb(0, 128, 0) }]);
+    }
+
+    #[test]
+    fn a_border_is_painted_over_the_background() {
+        let items = display_items("<div style=\"width: 100px; height: 50px; background-color: rgb(0, 128, 0); border: 2px solid rgb(255, 0, 0); border-left: 4px dashed rgb(0, 0, 255)\"></div>");
+        let rect = Rect { x: 0.0, y: 0.0, width: 106.0, height: 54.0 };
+        assert_eq!(items, vec![
+            DisplayItem::Rect { rect, color: rgb(0, 128, 0) },
+            DisplayItem::Border {
+                rect,
+                widths: [2.0, 2.0, 2.0, 4.0],
+                styles: [BorderStyle::Solid, BorderStyle::Solid, BorderStyle::Solid, BorderStyle::Dashed],
+                colors: [rgb(255, 0, 0), rgb(255, 0, 0), rgb(255, 0, 0), rgb(0, 0, 255)]
+            }
+        ]);
+    }
+
+    #[test]
+    fn text_is_a_run_in_the_font_size_and_color_of_its_element() {
+        let items = display_items("<p style=\"margin: 0; font-size: 20px; color: rgb(0, 0, 255)\">Hello world</p>");
+        match items.as_slice() {
+            [DisplayItem::Text { x, baseline, text, font_size, color }] => {
+                assert_eq!((*x, text.as_str(), *font_size, color), (0.0, "Hello world", 20.0, &rgb(0, 0, 255)));
+                assert!(*baseline > 0.0 && *baseline < 40.0, "the baseline is at {}", baseline);
+            },
+            items => panic!("expected one text run, the items are {:?}", items)
+        }
+    }
+
+    #[test]
+    fn boxes_that_are_not_displayed_paint_nothing() {
+        assert_eq!(display_items("<div style=\"display: none; background-color: red\">Hidden</div>"), vec![]);
+    }
+}
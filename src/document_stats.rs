@@ -0,0 +1,131 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::mem::size_of;
+use std::rc::Rc;
+use serde_json::{json, Value};
+use crate::node::{Node, NodeData, RefNode};
+
+// How big a document is, to measure the memory of huge inputs and what changes to the node representation save
+// The heap estimate adds up the allocation of each node (an Rc of a RefCell of a Node), the strings and lists each node allocated, and the interned names once each
+// TODO: Style sheets, the box tree and script objects are not counted, so the estimate is of the tree the parser built and not of everything a page holds on to
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentStats {
+    // Every node, including the document
+    pub nodes: usize,
+    pub elements: usize,
+    pub text_nodes: usize,
+    pub comments: usize,
+    pub doctypes: usize,
+    pub attributes: usize,
+    // The bytes of the attribute values, in UTF-8
+    pub attribute_value_bytes: usize,
+    // The bytes of the data of text nodes
+    pub text_bytes: usize,
+    // The bytes of the data of comments
+    pub comment_bytes: usize,
+    // The number of ancestors of the deepest node, the children of the document are at depth 1
+    pub max_depth: usize,
+    // The different element and attribute names, each of which is interned once however many nodes use it
+    pub distinct_names: usize,
+    pub estimated_heap_bytes: usize,
+}
+
+impl DocumentStats {
+    pub fn to_json(&self) -> Value {
+        return json!({
+            "nodes": self.nodes,
+            "elements": self.elements,
+            "textNodes": self.text_nodes,
+            "comments": self.comments,
+            "doctypes": self.doctypes,
+            "attributes": self.attributes,
+            "attributeValueBytes": self.attribute_value_bytes,
+            "textBytes": self.text_bytes,
+            "commentBytes": self.comment_bytes,
+            "maxDepth": self.max_depth,
+            "distinctNames": self.distinct_names,
+            "estimatedHeapBytes": self.estimated_heap_bytes,
+        });
+    }
+
+    // The counts on one line each, e.g. for the parse command's --stats
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        text.push_str(&format!("nodes: {} ({} elements, {} text, {} comments, {} doctypes)\n", self.nodes, self.elements, self.text_nodes, self.comments, self.doctypes));
+        text.push_str(&format!("attributes: {} ({} bytes of values)\n", self.attributes, self.attribute_value_bytes));
+        text.push_str(&format!("text: {} bytes, comments: {} bytes\n", self.text_bytes, self.comment_bytes));
+        text.push_str(&format!("max depth: {}, distinct names: {}\n", self.max_depth, self.distinct_names));
+        text.push_str(&format!("estimated heap: {} bytes\n", self.estimated_heap_bytes));
+        return text;
+    }
+
+    // The sums of the counts of documents, and the deepest of their depths
+    // Distinct names are added up as well, as the documents of a batch are parsed on threads that each intern their own names
+    pub fn add(&mut self, other: &DocumentStats) {
+        self.nodes += other.nodes;
+        self.elements += other.elements;
+        self.text_nodes += other.text_nodes;
+        self.comments += other.comments;
+        self.doctypes += other.doctypes;
+        self.attributes += other.attributes;
+        self.attribute_value_bytes += other.attribute_value_bytes;
+        self.text_bytes += other.text_bytes;
+        self.comment_bytes += other.comment_bytes;
+        self.max_depth = self.max_depth.max(other.max_depth);
+        self.distinct_names += other.distinct_names;
+        self.estimated_heap_bytes += other.estimated_heap_bytes;
+    }
+}
+
+// The counts of the nodes of document and its descendants
+pub fn document_stats(document: &RefNode) -> DocumentStats {
+    let mut stats = DocumentStats::default();
+    let mut names = HashSet::new();
+    // The nodes still to count with their depths, a stack rather than recursion so a deeply nested document cannot overflow the call stack
+    let mut nodes = vec![(Rc::clone(document), 0)];
+    while let Some((node, depth)) = nodes.pop() {
+        count_node(&node, depth, &mut stats, &mut names);
+        nodes.extend(node.borrow().childNodes.iter().rev().map(|child| (Rc::clone(child), depth + 1)));
+    }
+
+    stats.distinct_names = names.len();
+    // An atom is an Rc<str>, its text follows the strong and weak counts, and the atom table holds a pointer to it
+    stats.estimated_heap_bytes += names.iter().map(|name: &String| 2 * size_of::<usize>() + name.len() + size_of::<(usize, usize)>()).sum::<usize>();
+    return stats;
+}
+
+// Adds node itself to the counts, without its children
+fn count_node(node: &RefNode, depth: usize, stats: &mut DocumentStats, names: &mut HashSet<String>) {
+    let node = node.borrow();
+    stats.nodes += 1;
+    stats.max_depth = stats.max_depth.max(depth);
+    // The Rc's strong and weak counts come before the RefCell
+    stats.estimated_heap_bytes += 2 * size_of::<usize>() + size_of::<RefCell<Node>>() + node.heap_size();
+
+    match &node.data {
+        NodeData::Element(element) => {
+            stats.elements += 1;
+            names.insert(element.local_name().to_string());
+            for attribute in element.attribute_list() {
+                stats.attributes += 1;
+                stats.attribute_value_bytes += attribute.value.len();
+                names.insert(attribute.local_name.to_string());
+            }
+        },
+        NodeData::Text(text) => {
+            stats.text_nodes += 1;
+            stats.text_bytes += text.character_data.data.len();
+        },
+        NodeData::CharacterData(character_data) => {
+            stats.text_nodes += 1;
+            stats.text_bytes += character_data.data.len();
+        },
+        NodeData::Comment(comment) => {
+            stats.comments += 1;
+            stats.comment_bytes += comment.character_data.data.len();
+        },
+        NodeData::DocumentType(_) => stats.doctypes += 1,
+        NodeData::Document(_) => {}
+    }
+}
@@ -0,0 +1,436 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::cssom::{document_css_style_sheets, inline_style, install_cssom, CSSStyleDeclaration, CSSStyleSheet};
+use crate::events::install_events;
+use crate::forms::install_forms;
+use crate::html_document_parser::create_element_node;
+use crate::interpreter::{create_normal_completion, create_value_completion, CompletionRecord, Interpreter, JSObject, JSValue, PlatformObject, ReferenceRecordOrJsValue};
+use crate::mutation_observer::install_mutation_observer;
+use crate::metadata::document_title;
+use crate::node::{append, create_ref_node, descendants, document_base_url, encoding_parse_url, node_document, remove_attribute_by_name, set_attribute_value, set_text_content, text_content, DOMString, NodeData, NodeType, RefNode, Text};
+use crate::scrolling::install_scrolling;
+use crate::selector::parse_selector_list;
+use crate::text_renderer::inner_text;
+use crate::unicode::ascii_lowercase;
+use crate::url::Url;
+use crate::webidl::{attribute_getter, attribute_setter, convert_argument, define_attributes, define_operations, operation, ElementNode, Exception};
+
+// https://webidl.spec.whatwg.org/#javascript-binding
+// TODO: Only the parts of Node, Document and Element that scripts commonly use are exposed
+
+// https://webidl.spec.whatwg.org/#interface-prototype-object
+// Creates the interface prototype objects and exposes document as a property of the global object
+pub(crate) fn install_document(interpreter: &mut Interpreter, document: &RefNode) {
+    // https://dom.spec.whatwg.org/#interface-node
+    // Node inherits from EventTarget
+    install_events(interpreter);
+    install_cssom(interpreter);
+    install_mutation_observer(interpreter);
+    let node_prototype = create_interface_prototype_object(interpreter.intrinsic("EventTarget.prototype"));
+    define_operations(&node_prototype, &[
+        ("appendChild", operation!("Node", "appendChild", node_append_child(parent: RefNode, child: RefNode))),
+    ]);
+    define_attributes(&node_prototype, &[
+        ("textContent", attribute_getter!("Node", "textContent", node_text_content(node: RefNode)), Some(attribute_setter!("Node", "textContent", node_set_text_content(node: RefNode, value: Option<DOMString>)))),
+        ("baseURI", attribute_getter!("Node", "baseURI", node_base_uri(node: RefNode)), None),
+    ]);
+
+    let document_prototype = create_interface_prototype_object(Some(Rc::clone(&node_prototype)));
+    define_operations(&document_prototype, &[
+        ("getElementById", operation!("Document", "getElementById", document_get_element_by_id(document: RefNode, element_id: DOMString))),
+        ("querySelector", operation!("Document", "querySelector", parent_node_query_selector(node: RefNode, selectors: DOMString))),
+        ("createElement", operation!("Document", "createElement", document_create_element(document: RefNode, local_name: DOMString))),
+    ]);
+    define_attributes(&document_prototype, &[
+        ("documentElement", attribute_getter!("Document", "documentElement", document_document_element(document: RefNode)), None),
+        ("body", attribute_getter!("Document", "body", document_body(document: RefNode)), None),
+        ("URL", attribute_getter!("Document", "URL", document_url(document: RefNode)), None),
+        ("documentURI", attribute_getter!("Document", "documentURI", document_url(document: RefNode)), None),
+        ("styleSheets", attribute_getter!("Document", "styleSheets", document_style_sheets(document: RefNode)), None),
+        ("title", attribute_getter!("Document", "title", document_get_title(document: RefNode)), Some(attribute_setter!("Document", "title", document_set_title(document: RefNode, value: DOMString)))),
+    ]);
+
+    let element_prototype = create_interface_prototype_object(Some(Rc::clone(&node_prototype)));
+    define_operations(&element_prototype, &[
+        ("getAttribute", operation!("Element", "getAttribute", element_get_attribute(element: ElementNode, qualified_name: DOMString))),
+        ("setAttribute", operation!("Element", "setAttribute", element_set_attribute(element: ElementNode, qualified_name: DOMString, value: DOMString))),
+        ("removeAttribute", operation!("Element", "removeAttribute", element_remove_attribute(element: ElementNode, qualified_name: DOMString))),
+        ("hasAttribute", operation!("Element", "hasAttribute", element_has_attribute(element: ElementNode, qualified_name: DOMString))),
+        ("querySelector", operation!("Element", "querySelector", parent_node_query_selector(node: RefNode, selectors: DOMString))),
+    ]);
+    // TODO: There are no HTMLElement interfaces yet, so every element reflects href and src and has an inline style
+    define_attributes(&element_prototype, &[
+        ("tagName", attribute_getter!("Element", "tagName", element_tag_name(element: ElementNode)), None),
+        ("id", attribute_getter!("Element", "id", element_id(element: ElementNode)), Some(attribute_setter!("Element", "id", element_set_id(element: ElementNode, id: DOMString)))),
+        ("href", attribute_getter!("Element", "href", element_href(element: ElementNode)), Some(attribute_setter!("Element", "href", element_set_href(element: ElementNode, value: DOMString)))),
+        ("src", attribute_getter!("Element", "src", element_src(element: ElementNode)), Some(attribute_setter!("Element", "src", element_set_src(element: ElementNode, value: DOMString)))),
+        ("style", attribute_getter!("Element", "style", element_style(element: ElementNode)), None),
+        ("innerText", attribute_getter!("Element", "innerText", element_inner_text(element: ElementNode)), Some(attribute_setter!("Element", "innerText", element_set_inner_text(element: ElementNode, value: DOMString)))),
+    ]);
+    install_forms(&element_prototype);
+    install_scrolling(&document_prototype, &element_prototype);
+
+    interpreter.set_intrinsic("Node.prototype", node_prototype);
+    interpreter.set_intrinsic("Document.prototype", document_prototype);
+    interpreter.set_intrinsic("Element.prototype", element_prototype);
+
+    install_interface_object(interpreter, "Node");
+    install_interface_object(interpreter, "Document");
+    install_interface_object(interpreter, "Element");
+
+    let document = wrap_node(interpreter, document);
+    interpreter.define_global_property("document", document);
+}
+
+pub(crate) fn create_interface_prototype_object(prototype: Option<Rc<RefCell<JSObject>>>) -> Rc<RefCell<JSObject>> {
+    let mut interface_prototype_object = JSObject::new();
+    interface_prototype_object.extensible = true;
+    interface_prototype_object.prototype = prototype;
+    return Rc::new(RefCell::new(interface_prototype_object));
+}
+
+// https://webidl.spec.whatwg.org/#interface-object
+// Exposes the interface object on the global object so scripts can use instanceof with it
+pub(crate) fn install_interface_object(interpreter: &mut Interpreter, identifier: &str) -> Rc<RefCell<JSObject>> {
+    let interface_object = Interpreter::create_builtin_function(interface_object_call);
+    interface_object.borrow_mut().prototype = interpreter.intrinsic("Function.prototype");
+    Interpreter::set_function_name(&interface_object, identifier);
+
+    // https://webidl.spec.whatwg.org/#interface-prototype-object
+    // The interface object has a "prototype" property which is not writable, enumerable or configurable
+    let interface_prototype_object = interpreter.intrinsic(&format!("{}.prototype", identifier)).unwrap();
+    Interpreter::define_property_or_throw(&interface_object, "prototype", Rc::new(RefCell::new(JSValue::Object(Rc::clone(&interface_prototype_object)))), false, false, false);
+
+    // The interface prototype object has a "constructor" property which is writable and configurable but not enumerable
+    Interpreter::define_property_or_throw(&interface_prototype_object, "constructor", Rc::new(RefCell::new(JSValue::Object(Rc::clone(&interface_object)))), true, false, true);
+
+    interpreter.define_global_property(identifier, Rc::new(RefCell::new(JSValue::Object(Rc::clone(&interface_object)))));
+    return interface_object;
+}
+
+// https://webidl.spec.whatwg.org/#create-an-interface-object
+// Interfaces without a constructor operation throw when they are called or constructed
+fn interface_object_call(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return interpreter.throw_type_error(String::from("Illegal constructor"));
+}
+
+// https://webidl.spec.whatwg.org/#es-platform-objects
+// TODO: The same node gets a new wrapper every time it is exposed, so wrappers can not be compared by identity
+pub(crate) fn wrap_node(interpreter: &mut Interpreter, node: &RefNode) -> Rc<RefCell<JSValue>> {
+    let interface_name = match node.borrow().data {
+        NodeData::Document(_) => "Document.prototype",
+        NodeData::Element(_) => "Element.prototype",
+        _ => "Node.prototype"
+    };
+
+    let mut wrapper = JSObject::new();
+    wrapper.extensible = true;
+    wrapper.prototype = interpreter.intrinsic(interface_name);
+    wrapper.platform_object = Some(PlatformObject::Node(Rc::clone(node)));
+    return Rc::new(RefCell::new(JSValue::Object(Rc::new(RefCell::new(wrapper)))));
+}
+
+pub(crate) fn wrap_optional_node(interpreter: &mut Interpreter, node: Option<RefNode>) -> CompletionRecord {
+    match node {
+        Some(node) => {
+            let wrapper = wrap_node(interpreter, &node);
+            return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(wrapper)));
+        },
+        None => {
+            return create_value_completion(JSValue::Null);
+        }
+    }
+}
+
+pub(crate) fn unwrap_node(value: &Rc<RefCell<JSValue>>) -> Option<RefNode> {
+    match &*value.borrow() {
+        JSValue::Object(object) => {
+            match &object.borrow().platform_object {
+                Some(PlatformObject::Node(node)) => Some(Rc::clone(node)),
+                _ => None
+            }
+        },
+        _ => None
+    }
+}
+
+// https://webidl.spec.whatwg.org/#ref-for-dfn-perform-a-security-check
+// Methods called with a this value that is not a node throw a TypeError
+pub(crate) fn this_node(interpreter: &mut Interpreter, this_value: &Rc<RefCell<JSValue>>) -> Result<RefNode, CompletionRecord> {
+    match unwrap_node(this_value) {
+        Some(node) => Ok(node),
+        None => Err(interpreter.throw_type_error(String::from("Illegal invocation")))
+    }
+}
+
+// https://webidl.spec.whatwg.org/#es-DOMString
+pub(crate) fn argument_to_dom_string(interpreter: &mut Interpreter, arguments: &[Rc<RefCell<JSValue>>], index: usize) -> Result<DOMString, CompletionRecord> {
+    // Every value converts to a string, so there is no TypeError whose message needs the context
+    return convert_argument(interpreter, arguments, index, "");
+}
+
+fn element_attribute(node: &RefNode, name: &str) -> Option<DOMString> {
+    match &node.borrow().data {
+        NodeData::Element(element) => element.get_attribute(name).cloned(),
+        _ => None
+    }
+}
+
+// https://dom.spec.whatwg.org/#dom-node-appendchild
+fn node_append_child(parent: RefNode, child: RefNode) -> Result<RefNode, Exception> {
+    // https://dom.spec.whatwg.org/#concept-node-ensure-pre-insertion-validity
+    // 2. If node is a host-including inclusive ancestor of parent, then throw a "HierarchyRequestError" DOMException.
+    let mut ancestor = Some(Rc::clone(&parent));
+    while let Some(current) = ancestor {
+        if Rc::ptr_eq(&current, &child) {
+            return Err(Exception::DOMException("HierarchyRequestError", String::from("The new child element contains the parent.")));
+        }
+        ancestor = current.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+    }
+
+    append(&parent, &child);
+    return Ok(child);
+}
+
+// https://dom.spec.whatwg.org/#dom-node-textcontent
+fn node_text_content(node: RefNode) -> Option<DOMString> {
+    return text_content(&node);
+}
+
+// The textContent attribute is a nullable DOMString, setting it to null is the same as the empty string
+fn node_set_text_content(node: RefNode, value: Option<DOMString>) {
+    // The Document's textContent setter does nothing
+    let is_document = matches!(node.borrow().data, NodeData::Document(_));
+    if !is_document {
+        set_text_content(&node, value.unwrap_or_default());
+    }
+}
+
+// https://dom.spec.whatwg.org/#dom-nonelementparentnode-getelementbyid
+fn document_get_element_by_id(document: RefNode, element_id: DOMString) -> Option<RefNode> {
+    // The getElementById(elementId) method steps are to return the first element, in tree order, within this's descendants, whose ID is elementId; otherwise, if there is no such element, null.
+    return descendants(&document).into_iter().find(|descendant| element_attribute(descendant, "id").as_ref() == Some(&element_id));
+}
+
+// https://dom.spec.whatwg.org/#dom-parentnode-queryselector
+fn parent_node_query_selector(node: RefNode, selectors: DOMString) -> Result<Option<RefNode>, Exception> {
+    // https://dom.spec.whatwg.org/#scope-match-a-selectors-string
+    // 1. Let s be the result of parse a selector selectors.
+    // 2. If s is failure, then throw a "SyntaxError" DOMException.
+    let selector_list = match parse_selector_list(&selectors) {
+        Some(selector_list) => selector_list,
+        None => return Err(Exception::DOMException("SyntaxError", format!("'{}' is not a valid selector.", selectors)))
+    };
+
+    // 3. Return the result of match a selector against a tree with s and node's root using scoping root node.
+    // The querySelector(selectors) method steps are to return the first result of running scope-match a selectors string selectors against this, if the result is not an empty list; otherwise null.
+    return Ok(descendants(&node).into_iter().find(|descendant| selector_list.matches(descendant)));
+}
+
+// https://dom.spec.whatwg.org/#dom-document-createelement
+fn document_create_element(document: RefNode, local_name: DOMString) -> Result<RefNode, Exception> {
+    // 1. If localName does not match the Name production, then throw an "InvalidCharacterError" DOMException.
+    // TODO: Only the simplest checks of the Name production are done
+    if local_name.is_empty() || local_name.chars().any(|character| character.is_whitespace() || character == '<' || character == '>') {
+        return Err(Exception::DOMException("InvalidCharacterError", format!("The tag name provided ('{}') is not a valid name.", local_name)));
+    }
+
+    // 2. If this is an HTML document, then set localName to localName in ASCII lowercase.
+    let local_name = ascii_lowercase(&local_name).into_owned();
+
+    // 7. Return the result of creating an element given this, localName, namespace, null, is, and with the synchronous custom elements flag set.
+    return Ok(create_element_node(local_name, &document));
+}
+
+// https://dom.spec.whatwg.org/#dom-document-documentelement
+pub(crate) fn document_document_element(document: RefNode) -> Option<RefNode> {
+    // The documentElement getter steps are to return this's document element.
+    return document.borrow().childNodes.iter().find(|child| matches!(child.borrow().data, NodeData::Element(_))).cloned();
+}
+
+// https://html.spec.whatwg.org/multipage/dom.html#dom-document-body
+pub(crate) fn document_body(document: RefNode) -> Option<RefNode> {
+    // The body element of a document is the first of the html element's children that is either a body element or a frameset element, or null if there is no such element.
+    let html_element = document.borrow().childNodes.iter().find(|child| matches!(child.borrow().data, NodeData::Element(ref element) if element.local_name() == "html")).cloned();
+    return html_element.and_then(|html_element| {
+        return html_element.borrow().childNodes.iter().find(|child| matches!(child.borrow().data, NodeData::Element(ref element) if element.local_name() == "body" || element.local_name() == "frameset")).cloned();
+    });
+}
+
+// https://html.spec.whatwg.org/multipage/dom.html#document.title
+fn document_get_title(document: RefNode) -> DOMString {
+    return document_title(&document);
+}
+
+fn document_set_title(document: RefNode, value: DOMString) {
+    // 1. If the document element is an SVG svg element, then set the child text content of the first SVG title element that is a child of the document element
+    // TODO: SVG is not supported, so the document element is always treated as an html element
+    // 2. Otherwise, if the document element is in the HTML namespace:
+    let document_element = document.borrow().childNodes.iter().find(|child| matches!(child.borrow().data, NodeData::Element(_))).cloned();
+    let document_element = match document_element {
+        Some(document_element) => document_element,
+        // 3. Otherwise, do nothing.
+        None => return
+    };
+    let title_element = descendants(&document).into_iter().find(|descendant| matches!(descendant.borrow().data, NodeData::Element(ref element) if element.local_name() == "title"));
+    let head_element = document_element.borrow().childNodes.iter().find(|child| matches!(child.borrow().data, NodeData::Element(ref element) if element.local_name() == "head")).cloned();
+    let element = match (title_element, head_element) {
+        // 2. If the title element is non-null, let element be the title element.
+        (Some(title_element), _) => title_element,
+        // 3. Otherwise, let element be the result of creating an element given the document element's node document, "title", and the HTML namespace, and append element to the head element.
+        (None, Some(head_element)) => {
+            let title_element = create_element_node(String::from("title"), &document);
+            append(&head_element, &title_element);
+            title_element
+        },
+        // 1. If the title element is null and the head element is null, then return.
+        (None, None) => return
+    };
+    // 4. String replace all with the given value within element.
+    set_text_content(&element, value);
+}
+
+// https://html.spec.whatwg.org/multipage/dom.html#dom-innertext
+fn element_inner_text(ElementNode(element): ElementNode) -> DOMString {
+    return inner_text(&element);
+}
+
+// https://html.spec.whatwg.org/multipage/dom.html#set-the-inner-text-steps
+// The lines of the value become text nodes, with a br element between each of them
+fn element_set_inner_text(ElementNode(element): ElementNode, value: DOMString) {
+    // 1. Let fragment be the rendered text fragment for value and element's node document.
+    // 2. Replace all with fragment within element.
+    set_text_content(&element, String::new());
+    let document = match node_document(&element) {
+        Some(document) => document,
+        None => return
+    };
+    // https://html.spec.whatwg.org/multipage/dom.html#rendered-text-fragment
+    // A CR LF, a lone CR and a LF are each a line break
+    let value = value.replace("\r\n", "\n").replace('\r', "\n");
+    for (index, line) in value.split('\n').enumerate() {
+        if index > 0 {
+            append(&element, &create_element_node(String::from("br"), &document));
+        }
+        if !line.is_empty() {
+            let text_node = create_ref_node(NodeData::Text(Text::new(Some(line.to_string()))), NodeType::TEXT_NODE);
+            text_node.borrow_mut().ownerDocument = Some(Rc::downgrade(&document));
+            append(&element, &text_node);
+        }
+    }
+}
+
+// https://dom.spec.whatwg.org/#dom-element-getattribute
+fn element_get_attribute(ElementNode(element): ElementNode, qualified_name: DOMString) -> Option<DOMString> {
+    // 2. If attr is null, return null.
+    // 3. Return attr's value.
+    return element_attribute(&element, &ascii_lowercase(&qualified_name));
+}
+
+// https://dom.spec.whatwg.org/#dom-element-setattribute
+fn element_set_attribute(ElementNode(element): ElementNode, qualified_name: DOMString, value: DOMString) -> Result<(), Exception> {
+    // 1. If qualifiedName does not match the Name production in XML, then throw an "InvalidCharacterError" DOMException.
+    if qualified_name.is_empty() || qualified_name.chars().any(|character| character.is_whitespace() || character == '=' || character == '"' || character == '\'') {
+        return Err(Exception::DOMException("InvalidCharacterError", format!("'{}' is not a valid attribute name.", qualified_name)));
+    }
+
+    // 2. If this is in the HTML namespace and its node document is an HTML document, then set qualifiedName to qualifiedName in ASCII lowercase.
+    set_attribute_value(&element, &ascii_lowercase(&qualified_name), value);
+    return Ok(());
+}
+
+// https://dom.spec.whatwg.org/#dom-element-removeattribute
+fn element_remove_attribute(ElementNode(element): ElementNode, qualified_name: DOMString) {
+    remove_attribute_by_name(&element, &ascii_lowercase(&qualified_name));
+}
+
+// https://dom.spec.whatwg.org/#dom-element-hasattribute
+fn element_has_attribute(ElementNode(element): ElementNode, qualified_name: DOMString) -> bool {
+    return element_attribute(&element, &ascii_lowercase(&qualified_name)).is_some();
+}
+
+// https://dom.spec.whatwg.org/#dom-element-tagname
+fn element_tag_name(ElementNode(element): ElementNode) -> DOMString {
+    // 2. If this is in the HTML namespace and its node document is an HTML document, then set qualifiedName to qualifiedName in ASCII uppercase.
+    match &element.borrow().data {
+        NodeData::Element(element) => element.local_name().to_ascii_uppercase(),
+        _ => unreachable!()
+    }
+}
+
+// https://dom.spec.whatwg.org/#dom-element-id
+// The id attribute must reflect the "id" content attribute
+fn element_id(ElementNode(element): ElementNode) -> DOMString {
+    return element_attribute(&element, "id").unwrap_or_default();
+}
+
+fn element_set_id(ElementNode(element): ElementNode, id: DOMString) {
+    set_attribute_value(&element, "id", id);
+}
+
+// https://dom.spec.whatwg.org/#dom-node-baseuri
+fn node_base_uri(node: RefNode) -> DOMString {
+    // The baseURI getter steps are to return this's node document's document base URL, serialized.
+    let base_url = match node_document(&node) {
+        Some(document) => document_base_url(&document),
+        None => Url::about_blank()
+    };
+    return base_url.href();
+}
+
+// https://dom.spec.whatwg.org/#dom-document-url
+fn document_url(document: RefNode) -> Result<DOMString, Exception> {
+    // The URL and documentURI getter steps are to return this's URL, serialized.
+    match &document.borrow().data {
+        NodeData::Document(document) => Ok(document.url.href()),
+        _ => Err(Exception::TypeError(String::from("Illegal invocation")))
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/common-dom-interfaces.html#reflecting-content-attributes-in-idl-attributes
+// A USVString attribute reflecting a URL resolves the content attribute against the document base URL
+fn reflect_url_attribute(element: &RefNode, name: &str) -> DOMString {
+    // 2. If contentAttributeValue is null, then return the empty string.
+    let content_attribute_value = match element_attribute(element, name) {
+        Some(content_attribute_value) => content_attribute_value,
+        None => return String::new()
+    };
+
+    // 3. Let urlString be the result of encoding-parsing-and-serializing a URL given contentAttributeValue, relative to the element's node document.
+    // 4. If urlString is not failure, then return urlString.
+    // 5. Return contentAttributeValue, converted to a scalar value string.
+    match encoding_parse_url(element, &content_attribute_value) {
+        Ok(url) => url.href(),
+        Err(_) => content_attribute_value
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/links.html#dom-a-href
+fn element_href(ElementNode(element): ElementNode) -> DOMString {
+    return reflect_url_attribute(&element, "href");
+}
+
+// The setter steps are to set the content attribute to the given value
+fn element_set_href(ElementNode(element): ElementNode, value: DOMString) {
+    set_attribute_value(&element, "href", value);
+}
+
+// https://html.spec.whatwg.org/multipage/embedded-content.html#dom-img-src
+fn element_src(ElementNode(element): ElementNode) -> DOMString {
+    return reflect_url_attribute(&element, "src");
+}
+
+fn element_set_src(ElementNode(element): ElementNode, value: DOMString) {
+    set_attribute_value(&element, "src", value);
+}
+
+// https://drafts.csswg.org/cssom/#dom-documentorshadowroot-stylesheets
+// FIXME: This is an array of the style sheets at the time it is read rather than a live StyleSheetList
+fn document_style_sheets(document: RefNode) -> Vec<Rc<RefCell<CSSStyleSheet>>> {
+    return document_css_style_sheets(&document);
+}
+
+// https://drafts.csswg.org/cssom/#dom-elementcssinlinestyle-style
+fn element_style(ElementNode(element): ElementNode) -> Rc<RefCell<CSSStyleDeclaration>> {
+    return inline_style(&element).unwrap();
+}
@@ -0,0 +1,413 @@
+use std::fmt;
+use std::rc::Rc;
+use crate::node::{descendants, node_document, remove_attribute_by_name, set_attribute_value, set_text_content, NodeData, RefNode};
+use crate::style::{invalidate_children, invalidate_style};
+
+// Compares two documents and describes how to turn the first into the second as a list of edits
+// Children are matched by their subtrees first, so a child that is the same but in another place among its siblings is a move,
+// then by the longest common subsequence of their kinds (the local name and id of an element, or whether a node is text or a comment), and those are compared in turn
+// A node removed in one place and inserted unchanged under another parent is a move as well
+// Nodes are identified by a path from the document, e.g. /html/body/p[2]/text()[1], where the index counts the siblings of the same kind
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit {
+    // The path is that of the node in the second document
+    NodeInserted { path: String, node: String },
+    // The path is that of the node in the first document
+    NodeRemoved { path: String, node: String },
+    NodeMoved { from: String, to: String, node: String },
+    // A value of None is an attribute that is not there, so it was added or removed
+    AttributeChanged { path: String, name: String, old_value: Option<String>, new_value: Option<String> },
+    // The data of a text or comment node changed
+    TextChanged { path: String, old_data: String, new_data: String },
+}
+
+impl fmt::Display for Edit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Edit::NodeInserted { path, node } => write!(f, "+ {} {}", path, node),
+            Edit::NodeRemoved { path, node } => write!(f, "- {} {}", path, node),
+            Edit::NodeMoved { from, to, node } => write!(f, "> {} -> {} {}", from, to, node),
+            Edit::AttributeChanged { path, name, old_value, new_value } => {
+                let describe = |value: &Option<String>| value.as_ref().map(|value| format!("{:?}", value)).unwrap_or_else(|| "(none)".to_string());
+                write!(f, "~ {} @{} {} -> {}", path, name, describe(old_value), describe(new_value))
+            },
+            Edit::TextChanged { path, old_data, new_data } => write!(f, "~ {} {:?} -> {:?}", path, old_data, new_data)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DiffOptions {
+    // Text nodes that are only whitespace are left out, and runs of whitespace in text compare equal to a single space
+    pub ignore_whitespace: bool,
+}
+
+// The edits that turn document a into document b, in tree order
+pub fn diff(a: &RefNode, b: &RefNode) -> Vec<Edit> {
+    return diff_with_options(a, b, DiffOptions::default());
+}
+
+pub fn diff_with_options(a: &RefNode, b: &RefNode, options: DiffOptions) -> Vec<Edit> {
+    let mut differ = Differ { options, edits: Vec::new(), fingerprints: Vec::new() };
+    differ.diff_children(a, b, "", "");
+    differ.find_moves();
+    return differ.edits;
+}
+
+struct Differ {
+    options: DiffOptions,
+    edits: Vec<Edit>,
+    // The fingerprint of the subtree of each edit that inserts or removes a node, to find the nodes that were moved
+    fingerprints: Vec<Option<String>>,
+}
+
+impl Differ {
+    fn push_edit(&mut self, edit: Edit, fingerprint: Option<String>) {
+        self.edits.push(edit);
+        self.fingerprints.push(fingerprint);
+    }
+
+    // Compares two nodes of the same kind
+    fn diff_nodes(&mut self, a: &RefNode, b: &RefNode, path_a: &str, path_b: &str) {
+        let mut attribute_changes = Vec::new();
+        let mut text_change = None;
+        match (&a.borrow().data, &b.borrow().data) {
+            (NodeData::Element(element_a), NodeData::Element(element_b)) => {
+                for attribute in element_a.attribute_list() {
+                    let new_value = element_b.get_attribute(&attribute.local_name);
+                    if new_value != Some(&attribute.value) {
+                        attribute_changes.push((attribute.local_name.clone(), Some(attribute.value.clone()), new_value.cloned()));
+                    }
+                }
+                for attribute in element_b.attribute_list() {
+                    if !element_a.has_attribute(&attribute.local_name) {
+                        attribute_changes.push((attribute.local_name.clone(), None, Some(attribute.value.clone())));
+                    }
+                }
+            },
+            (NodeData::Text(_), NodeData::Text(_)) | (NodeData::Comment(_), NodeData::Comment(_)) => {
+                let (old_data, new_data) = (self.node_data(a), self.node_data(b));
+                if old_data != new_data {
+                    text_change = Some((old_data, new_data));
+                }
+            },
+            (NodeData::DocumentType(doctype_a), NodeData::DocumentType(doctype_b))
+                if doctype_a.name != doctype_b.name || doctype_a.public_id != doctype_b.public_id || doctype_a.system_id != doctype_b.system_id => {
+                text_change = Some((describe_node(a), describe_node(b)));
+            },
+            _ => {}
+        }
+
+        for (name, old_value, new_value) in attribute_changes {
+            self.push_edit(Edit::AttributeChanged { path: path_b.to_string(), name: name.to_string(), old_value, new_value }, None);
+        }
+        if let Some((old_data, new_data)) = text_change {
+            self.push_edit(Edit::TextChanged { path: path_b.to_string(), old_data, new_data }, None);
+        }
+        self.diff_children(a, b, path_a, path_b);
+    }
+
+    // https://en.wikipedia.org/wiki/Longest_common_subsequence
+    // The children with the same subtrees in the same order are matched first, and are left as they are
+    // Of the others, a child whose subtree is the same as one in a was moved, and the rest are matched by the longest common subsequence of their kinds
+    // The children that are still not matched were removed from a or inserted into b
+    fn diff_children(&mut self, a: &RefNode, b: &RefNode, path_a: &str, path_b: &str) {
+        let children_a = self.children_with_paths(a, path_a);
+        let children_b = self.children_with_paths(b, path_b);
+        let fingerprints_a: Vec<String> = children_a.iter().map(|(child, _)| self.fingerprint(child)).collect();
+        let fingerprints_b: Vec<String> = children_b.iter().map(|(child, _)| self.fingerprint(child)).collect();
+        let mut partners_a: Vec<Option<(usize, Match)>> = vec![None; children_a.len()];
+        let mut partners_b: Vec<Option<(usize, Match)>> = vec![None; children_b.len()];
+
+        let all_a: Vec<usize> = (0..children_a.len()).collect();
+        let all_b: Vec<usize> = (0..children_b.len()).collect();
+        for (i, j) in match_in_order(&fingerprints_a, &fingerprints_b, &all_a, &all_b) {
+            partners_a[i] = Some((j, Match::Same));
+            partners_b[j] = Some((i, Match::Same));
+        }
+        for j in 0..children_b.len() {
+            if partners_b[j].is_some() {
+                continue;
+            }
+            if let Some(i) = (0..children_a.len()).find(|&i| partners_a[i].is_none() && fingerprints_a[i] == fingerprints_b[j]) {
+                partners_a[i] = Some((j, Match::Moved));
+                partners_b[j] = Some((i, Match::Moved));
+            }
+        }
+        let keys_a: Vec<String> = children_a.iter().map(|(child, _)| node_key(child)).collect();
+        let keys_b: Vec<String> = children_b.iter().map(|(child, _)| node_key(child)).collect();
+        let unmatched_a: Vec<usize> = all_a.into_iter().filter(|&i| partners_a[i].is_none()).collect();
+        let unmatched_b: Vec<usize> = all_b.into_iter().filter(|&j| partners_b[j].is_none()).collect();
+        for (i, j) in match_in_order(&keys_a, &keys_b, &unmatched_a, &unmatched_b) {
+            partners_a[i] = Some((j, Match::SameKind));
+            partners_b[j] = Some((i, Match::SameKind));
+        }
+
+        // The edits are in tree order, with the children removed before a child of b those inserted or matched at it
+        let (mut i, mut j) = (0, 0);
+        while i < children_a.len() || j < children_b.len() {
+            if i < children_a.len() && partners_a[i].is_none() {
+                let (child, path) = &children_a[i];
+                self.push_edit(Edit::NodeRemoved { path: path.clone(), node: describe_node(child) }, Some(fingerprints_a[i].clone()));
+                i += 1;
+            } else if i < children_a.len() && partners_a[i].is_some_and(|(partner, _)| partner < j) {
+                // Matched with a child of b that came earlier
+                i += 1;
+            } else if j < children_b.len() {
+                let (child, path) = &children_b[j];
+                match partners_b[j] {
+                    None => self.push_edit(Edit::NodeInserted { path: path.clone(), node: describe_node(child) }, Some(fingerprints_b[j].clone())),
+                    Some((_, Match::Same)) => {},
+                    Some((partner, Match::Moved)) => {
+                        self.push_edit(Edit::NodeMoved { from: children_a[partner].1.clone(), to: path.clone(), node: describe_node(child) }, None);
+                    },
+                    Some((partner, Match::SameKind)) => self.diff_nodes(&children_a[partner].0, child, &children_a[partner].1, path)
+                }
+                j += 1;
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    // A removed node that is inserted somewhere else with the same attributes and descendants was moved
+    // The move takes the place of the removal, and the insertion is dropped
+    fn find_moves(&mut self) {
+        let mut dropped = vec![false; self.edits.len()];
+        for removal in 0..self.edits.len() {
+            let (from, node) = match &self.edits[removal] {
+                Edit::NodeRemoved { path, node } => (path.clone(), node.clone()),
+                _ => continue
+            };
+            let insertion = (0..self.edits.len()).find(|&insertion| {
+                !dropped[insertion] && matches!(self.edits[insertion], Edit::NodeInserted { .. }) && self.fingerprints[insertion] == self.fingerprints[removal]
+            });
+            if let Some(insertion) = insertion {
+                if let Edit::NodeInserted { path: to, .. } = &self.edits[insertion] {
+                    self.edits[removal] = Edit::NodeMoved { from, to: to.clone(), node };
+                }
+                dropped[insertion] = true;
+            }
+        }
+
+        let mut index = 0;
+        self.edits.retain(|_| {
+            index += 1;
+            !dropped[index - 1]
+        });
+    }
+
+    // The children of node that are compared, each with its path
+    fn children_with_paths(&self, node: &RefNode, path: &str) -> Vec<(RefNode, String)> {
+        let children: Vec<RefNode> = node.borrow().childNodes.iter().filter(|child| !self.is_ignored(child)).cloned().collect();
+        let mut children_with_paths = Vec::new();
+        for (index, child) in children.iter().enumerate() {
+            let step = path_step(child);
+            let same_kind = children.iter().filter(|sibling| path_step(sibling) == step).count();
+            let position = children[..index].iter().filter(|sibling| path_step(sibling) == step).count() + 1;
+            // The index is left out for an element that is the only child with its local name, as in /html/body
+            let child_path = if same_kind == 1 && !step.ends_with("()") { format!("{}/{}", path, step) } else { format!("{}/{}[{}]", path, step, position) };
+            children_with_paths.push((Rc::clone(child), child_path));
+        }
+        return children_with_paths;
+    }
+
+    fn is_ignored(&self, node: &RefNode) -> bool {
+        return self.options.ignore_whitespace && matches!(&node.borrow().data, NodeData::Text(text) if text.character_data.data.trim().is_empty());
+    }
+
+    // The data of a text or comment node, with its whitespace collapsed when whitespace is ignored
+    fn node_data(&self, node: &RefNode) -> String {
+        let data = match &node.borrow().data {
+            NodeData::Text(text) => text.character_data.data.clone(),
+            NodeData::Comment(comment) => comment.character_data.data.clone(),
+            _ => String::new()
+        };
+        if self.options.ignore_whitespace {
+            return data.split_ascii_whitespace().collect::<Vec<&str>>().join(" ");
+        }
+        return data;
+    }
+
+    // A string that is the same for two subtrees when they are the same, given the options
+    fn fingerprint(&self, node: &RefNode) -> String {
+        let mut fingerprint = match &node.borrow().data {
+            NodeData::Text(_) | NodeData::Comment(_) => format!("{}{:?}", node_key(node), self.node_data(node)),
+            _ => describe_node(node)
+        };
+        for child in node.borrow().childNodes.iter().filter(|child| !self.is_ignored(child)) {
+            fingerprint.push_str(&self.fingerprint(child));
+        }
+        fingerprint.push_str("</>");
+        return fingerprint;
+    }
+}
+
+// https://en.wikipedia.org/wiki/Longest_common_subsequence
+// lengths[i][j] is the length of the longest common subsequence of keys_a[i..] and keys_b[j..]
+fn common_subsequence_lengths(keys_a: &[String], keys_b: &[String]) -> Vec<Vec<usize>> {
+    let mut lengths = vec![vec![0usize; keys_b.len() + 1]; keys_a.len() + 1];
+    for i in (0..keys_a.len()).rev() {
+        for j in (0..keys_b.len()).rev() {
+            lengths[i][j] = if keys_a[i] == keys_b[j] { lengths[i + 1][j + 1] + 1 } else { lengths[i + 1][j].max(lengths[i][j + 1]) };
+        }
+    }
+    return lengths;
+}
+
+// How a child of a was matched with a child of b
+#[derive(Debug, Clone, Copy)]
+enum Match {
+    // The subtrees are the same, and so is their order among the other children that are the same
+    Same,
+    // The subtrees are the same, but the child is somewhere else among its siblings
+    Moved,
+    // The children have the same kind, and are compared in turn
+    SameKind,
+}
+
+// The pairs of indices in candidates_a and candidates_b whose keys make up the longest common subsequence, in order
+fn match_in_order(keys_a: &[String], keys_b: &[String], candidates_a: &[usize], candidates_b: &[usize]) -> Vec<(usize, usize)> {
+    let keys_a: Vec<String> = candidates_a.iter().map(|&i| keys_a[i].clone()).collect();
+    let keys_b: Vec<String> = candidates_b.iter().map(|&j| keys_b[j].clone()).collect();
+    let lengths = common_subsequence_lengths(&keys_a, &keys_b);
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < keys_a.len() && j < keys_b.len() {
+        if keys_a[i] == keys_b[j] {
+            pairs.push((candidates_a[i], candidates_b[j]));
+            i += 1;
+            j += 1;
+        } else if lengths[i][j + 1] >= lengths[i + 1][j] {
+            j += 1;
+        } else {
+            i += 1;
+        }
+    }
+    return pairs;
+}
+
+// Changes document a in place into document b, e.g. when the file a document was parsed from changed and was parsed again
+// The children of a that match children of b as they do in a diff are kept and patched in turn, so only the nodes that changed have to be styled and laid out again
+// The others are removed, and the nodes of b that have no match in a are moved into a
+// TODO: Mutation records are not queued, and elements are not told they were inserted (e.g. scripts that are moved in are not run)
+pub fn patch(a: &RefNode, b: &RefNode) {
+    let (mut removed_attributes, mut changed_attributes, mut new_data) = (Vec::new(), Vec::new(), None);
+    match (&a.borrow().data, &b.borrow().data) {
+        (NodeData::Element(element_a), NodeData::Element(element_b)) => {
+            removed_attributes = element_a.attribute_list().iter().filter(|attribute| !element_b.has_attribute(&attribute.local_name))
+                .map(|attribute| attribute.local_name.to_string()).collect();
+            changed_attributes = element_b.attribute_list().iter().filter(|attribute| element_a.get_attribute(&attribute.local_name) != Some(&attribute.value))
+                .map(|attribute| (attribute.local_name.to_string(), attribute.value.clone())).collect();
+        },
+        (NodeData::Text(text_a), NodeData::Text(text_b)) if text_a.character_data.data != text_b.character_data.data => new_data = Some(text_b.character_data.data.clone()),
+        (NodeData::Comment(comment_a), NodeData::Comment(comment_b)) if comment_a.character_data.data != comment_b.character_data.data => new_data = Some(comment_b.character_data.data.clone()),
+        _ => {}
+    }
+    for name in removed_attributes {
+        remove_attribute_by_name(a, &name);
+    }
+    for (name, value) in changed_attributes {
+        set_attribute_value(a, &name, value);
+    }
+    if let Some(new_data) = new_data {
+        set_text_content(a, new_data);
+    }
+
+    let children_a = a.borrow().childNodes.clone();
+    let children_b = b.borrow().childNodes.clone();
+    // A doctype is matched by its name and identifiers, as there is nothing in it to patch
+    let key = |node: &RefNode| if matches!(node.borrow().data, NodeData::DocumentType(_)) { describe_node(node) } else { node_key(node) };
+    let keys_a: Vec<String> = children_a.iter().map(key).collect();
+    let keys_b: Vec<String> = children_b.iter().map(key).collect();
+    let lengths = common_subsequence_lengths(&keys_a, &keys_b);
+
+    let document = node_document(a);
+    let mut children = Vec::with_capacity(children_b.len());
+    let mut changed = false;
+    let (mut i, mut j) = (0, 0);
+    while i < keys_a.len() || j < keys_b.len() {
+        if i < keys_a.len() && j < keys_b.len() && keys_a[i] == keys_b[j] {
+            patch(&children_a[i], &children_b[j]);
+            children.push(Rc::clone(&children_a[i]));
+            i += 1;
+            j += 1;
+        } else if j < keys_b.len() && (i == keys_a.len() || lengths[i][j + 1] >= lengths[i + 1][j]) {
+            let child = &children_b[j];
+            child.borrow_mut().parentNode = Some(Rc::downgrade(a));
+            for node in std::iter::once(Rc::clone(child)).chain(descendants(child)) {
+                node.borrow_mut().ownerDocument = document.as_ref().map(Rc::downgrade);
+            }
+            children.push(Rc::clone(child));
+            changed = true;
+            j += 1;
+        } else {
+            children_a[i].borrow_mut().parentNode = None;
+            changed = true;
+            i += 1;
+        }
+    }
+    if changed {
+        b.borrow_mut().childNodes.clear();
+        a.borrow_mut().childNodes = children;
+        invalidate_children(a);
+        // The inserted nodes are styled for the first time, the ones that were kept are styled as they were
+        let inserted: Vec<RefNode> = a.borrow().childNodes.iter().filter(|child| !children_a.iter().any(|kept| Rc::ptr_eq(kept, child))).cloned().collect();
+        for child in &inserted {
+            invalidate_style(child);
+        }
+    }
+}
+
+// Children with the same key are matched with each other, an element's id is part of its key so elements that were reordered are not compared with each other
+fn node_key(node: &RefNode) -> String {
+    match &node.borrow().data {
+        NodeData::Element(element) => {
+            match element.get_attribute("id") {
+                Some(id) => format!("{}#{}", element.local_name(), id),
+                None => element.local_name().to_string()
+            }
+        },
+        _ => path_step(node)
+    }
+}
+
+// The step of the path of node from its parent, before its index among the siblings of the same kind
+fn path_step(node: &RefNode) -> String {
+    match &node.borrow().data {
+        NodeData::Element(element) => element.local_name().to_string(),
+        NodeData::Text(_) | NodeData::CharacterData(_) => "text()".to_string(),
+        NodeData::Comment(_) => "comment()".to_string(),
+        NodeData::DocumentType(_) => "doctype()".to_string(),
+        NodeData::Document(_) => String::new()
+    }
+}
+
+// A short description of node, the start tag of an element or the first characters of a text or comment
+fn describe_node(node: &RefNode) -> String {
+    // Long text is cut short, so an edit fits on a line
+    let truncate = |data: &str| {
+        let mut truncated: String = data.chars().take(40).collect();
+        if data.chars().count() > 40 {
+            truncated.push_str("...");
+        }
+        truncated
+    };
+
+    match &node.borrow().data {
+        NodeData::Element(element) => {
+            let mut start_tag = format!("<{}", element.local_name());
+            for attribute in element.attribute_list() {
+                start_tag.push_str(&format!(" {}={:?}", attribute.local_name, attribute.value));
+            }
+            start_tag.push('>');
+            start_tag
+        },
+        NodeData::Text(text) => format!("{:?}", truncate(&text.character_data.data)),
+        NodeData::CharacterData(character_data) => format!("{:?}", truncate(&character_data.data)),
+        NodeData::Comment(comment) => format!("<!--{}-->", truncate(&comment.character_data.data)),
+        NodeData::DocumentType(doctype) => format!("<!DOCTYPE {}>", doctype.name),
+        NodeData::Document(_) => "#document".to_string()
+    }
+}
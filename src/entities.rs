@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use crate::named_character_references;
+use crate::serializer::{escape_attribute_value, escape_text};
+
+// https://html.spec.whatwg.org/multipage/syntax.html#character-references
+// Character references on their own, for text that is not parsed as a document, e.g. the title of a feed item or a value scraped from markup:
+//     decode_entities("Fish &amp; Chips &#8212; &pound;5") == "Fish & Chips — £5"
+//     encode_entities("Fish & Chips — £5", EncodeMode::Named) == "Fish &amp; Chips &mdash; &pound;5"
+// References are decoded as the tokenizer decodes them in text, with the same table of names, so the result is the text a parsed document would have
+// TODO: References are decoded as in text rather than in an attribute value, where a legacy reference followed by = or an alphanumeric is left as it is
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodeMode {
+    // &, <, > and no-break spaces, as text is serialized
+    Text,
+    // &, <, >, no-break spaces and ", as attribute values are serialized
+    Attribute,
+    // &, <, >, " and every character outside ASCII that has a named reference, e.g. © as &copy;
+    Named,
+    // &, <, > and " by name and every character outside ASCII as a numeric reference, for output that has to be ASCII
+    Ascii,
+}
+
+// The text with every character reference replaced by the characters it stands for, an & that does not start one is left as it is
+pub fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(index) = rest.find('&') {
+        decoded.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+        let consumed = match rest.strip_prefix('#') {
+            Some(number) => decode_numeric_reference(number, &mut decoded).map(|length| length + 1),
+            None => decode_named_reference(rest, &mut decoded)
+        };
+        match consumed {
+            Some(length) => rest = &rest[length..],
+            None => decoded.push('&')
+        }
+    }
+    decoded.push_str(rest);
+    return decoded;
+}
+
+pub fn encode_entities(text: &str, mode: EncodeMode) -> String {
+    match mode {
+        EncodeMode::Text => return escape_text(text),
+        EncodeMode::Attribute => return escape_attribute_value(text),
+        EncodeMode::Named | EncodeMode::Ascii => ()
+    }
+    let mut encoded = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => encoded.push_str("&amp;"),
+            '<' => encoded.push_str("&lt;"),
+            '>' => encoded.push_str("&gt;"),
+            '"' => encoded.push_str("&quot;"),
+            _ if character.is_ascii() => encoded.push(character),
+            _ => match (mode, reference_names().get(&character)) {
+                (EncodeMode::Named, Some(name)) => {
+                    encoded.push('&');
+                    encoded.push_str(name);
+                },
+                (EncodeMode::Named, None) => encoded.push(character),
+                _ => encoded.push_str(&format!("&#x{:X};", character as u32))
+            }
+        }
+    }
+    return encoded;
+}
+
+// https://html.spec.whatwg.org/multipage/parsing.html#named-character-reference-state
+// The longest name in the table the text starts with, as the tokenizer matches them, the length of the name when there is one
+fn decode_named_reference(text: &str, decoded: &mut String) -> Option<usize> {
+    let mut longest = None;
+    for (index, character) in text.char_indices() {
+        let candidate = &text[..index + character.len_utf8()];
+        if !named_character_references::has_prefix(candidate) {
+            break;
+        }
+        if let Some(characters) = named_character_references::lookup(candidate) {
+            longest = Some((candidate.len(), characters));
+        }
+    }
+    let (length, characters) = longest?;
+    decoded.push_str(characters);
+    return Some(length);
+}
+
+// https://html.spec.whatwg.org/multipage/parsing.html#numeric-character-reference-state
+// The digits of a decimal or x followed by hexadecimal digits, and an optional ;, the length of the reference after the # when it has digits
+fn decode_numeric_reference(text: &str, decoded: &mut String) -> Option<usize> {
+    let (radix, prefix_length) = match text.as_bytes().first() {
+        Some(b'x') | Some(b'X') => (16, 1),
+        _ => (10, 0)
+    };
+    let digits = &text[prefix_length..];
+    let digit_count = digits.bytes().take_while(|byte| (*byte as char).is_digit(radix)).count();
+    if digit_count == 0 {
+        return None;
+    }
+    // A number too big for a u32 is outside the Unicode range all the same
+    let code = u32::from_str_radix(&digits[..digit_count], radix).unwrap_or(u32::MAX);
+    decoded.push(numeric_reference_character(code));
+    let semicolon = if digits[digit_count..].starts_with(';') { 1 } else { 0 };
+    return Some(prefix_length + digit_count + semicolon);
+}
+
+// https://html.spec.whatwg.org/multipage/parsing.html#numeric-character-reference-end-state
+// Null, surrogates and codes outside Unicode are a replacement character, and the C1 controls that windows-1252 has characters for are those characters
+fn numeric_reference_character(code: u32) -> char {
+    if code == 0 || code > 0x10FFFF || (0xD800..=0xDFFF).contains(&code) {
+        return '\u{FFFD}';
+    }
+    let code = control_character_replacement(code).unwrap_or(code);
+    return char::from_u32(code).unwrap_or('\u{FFFD}');
+}
+
+// https://html.spec.whatwg.org/multipage/parsing.html#numeric-character-reference-end-state
+// The code point a numeric reference to one of the controls in the first column of the table stands for, the tokenizer uses the same table
+pub(crate) fn control_character_replacement(code: u32) -> Option<u32> {
+    return match code {
+        0x80 => Some(0x20AC),
+        0x82 => Some(0x201A),
+        0x83 => Some(0x0192),
+        0x84 => Some(0x201E),
+        0x85 => Some(0x2026),
+        0x86 => Some(0x2020),
+        0x87 => Some(0x2021),
+        0x88 => Some(0x02C6),
+        0x89 => Some(0x2030),
+        0x8A => Some(0x0160),
+        0x8B => Some(0x2039),
+        0x8C => Some(0x0152),
+        0x8E => Some(0x017D),
+        0x91 => Some(0x2018),
+        0x92 => Some(0x2019),
+        0x93 => Some(0x201C),
+        0x94 => Some(0x201D),
+        0x95 => Some(0x2022),
+        0x96 => Some(0x2013),
+        0x97 => Some(0x2014),
+        0x98 => Some(0x02DC),
+        0x99 => Some(0x2122),
+        0x9A => Some(0x0161),
+        0x9B => Some(0x203A),
+        0x9C => Some(0x0153),
+        0x9E => Some(0x017E),
+        0x9F => Some(0x0178),
+        _ => None
+    };
+}
+
+// The name each character is encoded with, the shortest name that ends in ; and, of those, a lowercase one (e.g. "amp;" rather than "AMP;")
+// Names that stand for two characters are not used
+fn reference_names() -> &'static HashMap<char, &'static str> {
+    static REFERENCE_NAMES: OnceLock<HashMap<char, &'static str>> = OnceLock::new();
+    return REFERENCE_NAMES.get_or_init(|| {
+        let mut reference_names: HashMap<char, &'static str> = HashMap::new();
+        for name in named_character_references::names().filter(|name| name.ends_with(';')) {
+            let mut characters = named_character_references::lookup(name).unwrap_or_default().chars();
+            let character = match (characters.next(), characters.next()) {
+                (Some(character), None) => character,
+                _ => continue
+            };
+            let preference = |name: &str| (name.len(), name.chars().any(|character| character.is_ascii_uppercase()), name.to_string());
+            match reference_names.get(&character) {
+                Some(existing) if preference(existing) <= preference(name) => (),
+                _ => { reference_names.insert(character, name); }
+            }
+        }
+        return reference_names;
+    });
+}
@@ -0,0 +1,263 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::interpreter::{argument, completion_value, create_normal_completion, create_value_completion, return_if_abrupt, CompletionRecord, Interpreter, JSObject, JSValue, NativeConstructor, NativeFunction, PropertyKey, PropertyType, ReferenceRecordOrJsValue};
+
+// https://tc39.es/ecma262/#sec-error-objects
+// TODO: AggregateError is not implemented
+
+// https://tc39.es/ecma262/#sec-native-error-types-used-in-this-standard
+// Each error constructor is called with the intrinsic default prototype it creates instances from
+macro_rules! error_constructor {
+    ($call:ident, $construct:ident, $name:literal) => {
+        // 1. If NewTarget is undefined, let newTarget be the active function object; else let newTarget be NewTarget.
+        fn $call(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+            let new_target = interpreter.intrinsic($name).unwrap();
+            return construct_error(interpreter, &new_target, concat!($name, ".prototype"), &arguments);
+        }
+
+        fn $construct(interpreter: &mut Interpreter, arguments: Vec<Rc<RefCell<JSValue>>>, new_target: Rc<RefCell<JSObject>>) -> CompletionRecord {
+            return construct_error(interpreter, &new_target, concat!($name, ".prototype"), &arguments);
+        }
+    };
+}
+
+error_constructor!(error_call, error_construct, "Error");
+error_constructor!(eval_error_call, eval_error_construct, "EvalError");
+error_constructor!(range_error_call, range_error_construct, "RangeError");
+error_constructor!(reference_error_call, reference_error_construct, "ReferenceError");
+error_constructor!(syntax_error_call, syntax_error_construct, "SyntaxError");
+error_constructor!(type_error_call, type_error_construct, "TypeError");
+error_constructor!(uri_error_call, uri_error_construct, "URIError");
+
+// https://tc39.es/ecma262/#sec-properties-of-the-error-constructor
+// https://tc39.es/ecma262/#sec-properties-of-the-nativeerror-constructors
+pub(crate) fn install_error(interpreter: &mut Interpreter) {
+    // The Error prototype object is not an Error instance, it has no [[ErrorData]] internal slot
+    let object_prototype = interpreter.intrinsic("Object.prototype");
+    let function_prototype = interpreter.intrinsic("Function.prototype");
+    let error_prototype = install_error_constructor(interpreter, "Error", error_call, error_construct, object_prototype, function_prototype);
+    Interpreter::define_builtin_function(&error_prototype, "toString", error_prototype_to_string);
+
+    // Each NativeError constructor has a [[Prototype]] of %Error% and each NativeError prototype a [[Prototype]] of %Error.prototype%
+    let native_errors: [(&str, NativeFunction, NativeConstructor); 6] = [
+        ("EvalError", eval_error_call, eval_error_construct),
+        ("RangeError", range_error_call, range_error_construct),
+        ("ReferenceError", reference_error_call, reference_error_construct),
+        ("SyntaxError", syntax_error_call, syntax_error_construct),
+        ("TypeError", type_error_call, type_error_construct),
+        ("URIError", uri_error_call, uri_error_construct),
+    ];
+    let error_constructor = interpreter.intrinsic("Error");
+    for (name, call, construct) in native_errors {
+        install_error_constructor(interpreter, name, call, construct, Some(Rc::clone(&error_prototype)), error_constructor.clone());
+    }
+}
+
+// Creates the constructor and its prototype object, then exposes the constructor on the global object
+fn install_error_constructor(interpreter: &mut Interpreter, name: &str, call: NativeFunction, construct: NativeConstructor, prototype_parent: Option<Rc<RefCell<JSObject>>>, constructor_parent: Option<Rc<RefCell<JSObject>>>) -> Rc<RefCell<JSObject>> {
+    let constructor = Interpreter::create_builtin_function(call);
+    constructor.borrow_mut().construct = Some(construct);
+    constructor.borrow_mut().prototype = constructor_parent;
+    Interpreter::define_property_or_throw(&constructor, "length", Rc::new(RefCell::new(JSValue::Numeric(1.0))), false, false, true);
+    Interpreter::set_function_name(&constructor, name);
+
+    let mut prototype = JSObject::new();
+    prototype.extensible = true;
+    prototype.prototype = prototype_parent;
+    let prototype = Rc::new(RefCell::new(prototype));
+
+    // https://tc39.es/ecma262/#sec-error.prototype
+    // This property has the attributes { [[Writable]]: false, [[Enumerable]]: false, [[Configurable]]: false }.
+    Interpreter::define_property_or_throw(&constructor, "prototype", Rc::new(RefCell::new(JSValue::Object(Rc::clone(&prototype)))), false, false, false);
+
+    // https://tc39.es/ecma262/#sec-error.prototype.constructor
+    // https://tc39.es/ecma262/#sec-error.prototype.message
+    // https://tc39.es/ecma262/#sec-error.prototype.name
+    Interpreter::define_property_or_throw(&prototype, "constructor", Rc::new(RefCell::new(JSValue::Object(Rc::clone(&constructor)))), true, false, true);
+    Interpreter::define_property_or_throw(&prototype, "message", Rc::new(RefCell::new(JSValue::String(String::new()))), true, false, true);
+    Interpreter::define_property_or_throw(&prototype, "name", Rc::new(RefCell::new(JSValue::String(name.to_string()))), true, false, true);
+
+    interpreter.set_intrinsic(name, Rc::clone(&constructor));
+    interpreter.set_intrinsic(&format!("{}.prototype", name), Rc::clone(&prototype));
+    interpreter.define_global_property(name, Rc::new(RefCell::new(JSValue::Object(constructor))));
+
+    return prototype;
+}
+
+// https://tc39.es/ecma262/#sec-error-message
+// https://tc39.es/ecma262/#sec-nativeerror
+fn construct_error(interpreter: &mut Interpreter, new_target: &Rc<RefCell<JSObject>>, intrinsic_default_proto: &str, arguments: &[Rc<RefCell<JSValue>>]) -> CompletionRecord {
+    // 2. Let O be ? OrdinaryCreateFromConstructor(newTarget, "%Error.prototype%", « [[ErrorData]] »).
+    let object = return_if_abrupt!(completion_value(interpreter.ordinary_create_from_constructor(new_target, intrinsic_default_proto)));
+    let error = match &*object.borrow() {
+        JSValue::Object(error) => Rc::clone(error),
+        _ => { unreachable!() }
+    };
+    error.borrow_mut().error_data = true;
+
+    // 3. If message is not undefined, then
+    let message = argument(arguments, 0);
+    if !matches!(*message.borrow(), JSValue::Undefined) {
+        // a. Let msg be ? ToString(message).
+        let message = return_if_abrupt!(completion_value(interpreter.to_string_coercing_objects(message)));
+
+        // b. Perform CreateNonEnumerableDataPropertyOrThrow(O, "message", msg).
+        Interpreter::define_property_or_throw(&error, "message", message, true, false, true);
+    }
+
+    // 4. Perform ? InstallErrorCause(O, options).
+    // https://tc39.es/ecma262/#sec-installerrorcause
+    // 1. If options is an Object and ? HasProperty(options, "cause") is true, then
+    if let JSValue::Object(options) = &*argument(arguments, 1).borrow() {
+        let has_cause = return_if_abrupt!(completion_value(options.borrow().has_property(PropertyKey::String(String::from("cause")))));
+        let has_cause = matches!(*has_cause.borrow(), JSValue::Boolean(true));
+        if has_cause {
+            // a. Let cause be ? Get(options, "cause").
+            let cause = return_if_abrupt!(completion_value(interpreter.get(options, &PropertyKey::String(String::from("cause")), Rc::new(RefCell::new(JSValue::Object(Rc::clone(options)))))));
+
+            // b. Perform CreateNonEnumerableDataPropertyOrThrow(O, "cause", cause).
+            Interpreter::define_property_or_throw(&error, "cause", cause, true, false, true);
+        }
+    }
+
+    // Not part of the spec, engines record where the error was created in a stack property
+    capture_stack(interpreter, &error);
+
+    // 5. Return O.
+    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(object)));
+}
+
+// Creates an error object for errors thrown by the engine itself, such as a TypeError for calling a value that is not a function
+// Environment records have no access to the realm so the prototype and stack are filled in later by complete_error_object
+pub(crate) fn create_error_object(name: &str, message: String) -> Rc<RefCell<JSObject>> {
+    let mut error = JSObject::new();
+    error.extensible = true;
+    error.error_data = true;
+    let error = Rc::new(RefCell::new(error));
+
+    Interpreter::define_property_or_throw(&error, "name", Rc::new(RefCell::new(JSValue::String(name.to_string()))), true, false, true);
+    Interpreter::define_property_or_throw(&error, "message", Rc::new(RefCell::new(JSValue::String(message))), true, false, true);
+    return error;
+}
+
+// Gives an error created by create_error_object the prototype of the error type it is named after and captures its stack
+// Host errors such as DOMExceptions have no intrinsic of their own, they inherit from Error.prototype and keep their name
+pub(crate) fn complete_error_object(interpreter: &mut Interpreter, error: &Rc<RefCell<JSObject>>) {
+    if !error.borrow().error_data || error.borrow().prototype.is_some() {
+        return;
+    }
+
+    let name = match own_string_property(&error.borrow(), "name") {
+        Some(name) => name,
+        None => String::from("Error")
+    };
+
+    match interpreter.intrinsic(&format!("{}.prototype", name)) {
+        Some(prototype) => {
+            error.borrow_mut().delete(&PropertyKey::String(String::from("name")));
+            error.borrow_mut().prototype = Some(prototype);
+        },
+        None => {
+            error.borrow_mut().prototype = interpreter.intrinsic("Error.prototype");
+        }
+    }
+
+    capture_stack(interpreter, error);
+}
+
+// Defines the non-standard stack property, the error followed by a line for each execution context on the stack
+fn capture_stack(interpreter: &mut Interpreter, error: &Rc<RefCell<JSObject>>) {
+    let mut stack = error_to_string(error);
+    for frame in interpreter.stack_trace() {
+        stack.push_str("\n    at ");
+        stack.push_str(&frame);
+    }
+
+    Interpreter::define_property_or_throw(error, "stack", Rc::new(RefCell::new(JSValue::String(stack))), true, false, true);
+}
+
+// https://tc39.es/ecma262/#sec-error.prototype.tostring
+fn error_prototype_to_string(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let O be the this value.
+    // 2. If O is not an Object, throw a TypeError exception.
+    let object = match &*this_value.borrow() {
+        JSValue::Object(object) => Rc::clone(object),
+        _ => {
+            return interpreter.throw_type_error(String::from("Error.prototype.toString called on non-object"));
+        }
+    };
+
+    // 3. Let name be ? Get(O, "name").
+    let name = return_if_abrupt!(completion_value(interpreter.get(&object, &PropertyKey::String(String::from("name")), Rc::clone(&this_value))));
+
+    // 4. If name is undefined, set name to "Error"; otherwise set name to ? ToString(name).
+    let name = match *name.borrow() {
+        JSValue::Undefined => String::from("Error"),
+        _ => return_if_abrupt!(string_value(interpreter.to_string_coercing_objects(Rc::clone(&name))))
+    };
+
+    // 5. Let msg be ? Get(O, "message").
+    let message = return_if_abrupt!(completion_value(interpreter.get(&object, &PropertyKey::String(String::from("message")), Rc::clone(&this_value))));
+
+    // 6. If msg is undefined, set msg to the empty String; otherwise set msg to ? ToString(msg).
+    let message = match *message.borrow() {
+        JSValue::Undefined => String::new(),
+        _ => return_if_abrupt!(string_value(interpreter.to_string_coercing_objects(Rc::clone(&message))))
+    };
+
+    return create_value_completion(JSValue::String(join_name_and_message(name, message)));
+}
+
+// Error.prototype.toString without calling into script, the name and message are read as data properties along the prototype chain
+pub(crate) fn error_to_string(error: &Rc<RefCell<JSObject>>) -> String {
+    let name = string_property(error, "name").unwrap_or_else(|| String::from("Error"));
+    let message = string_property(error, "message").unwrap_or_default();
+    return join_name_and_message(name, message);
+}
+
+fn join_name_and_message(name: String, message: String) -> String {
+    // 7. If name is the empty String, return msg.
+    if name.is_empty() {
+        return message;
+    }
+
+    // 8. If msg is the empty String, return name.
+    if message.is_empty() {
+        return name;
+    }
+
+    // 9. Return the string-concatenation of name, the code unit 0x003A (COLON), the code unit 0x0020 (SPACE), and msg.
+    return format!("{}: {}", name, message);
+}
+
+fn string_property(object: &Rc<RefCell<JSObject>>, key: &str) -> Option<String> {
+    let mut current = Some(Rc::clone(object));
+    while let Some(object) = current {
+        if object.borrow().own_property(&PropertyKey::String(key.to_string())).is_some() {
+            return own_string_property(&object.borrow(), key);
+        }
+        current = object.borrow().prototype.clone();
+    }
+    return None;
+}
+
+fn own_string_property(object: &JSObject, key: &str) -> Option<String> {
+    match object.own_property(&PropertyKey::String(key.to_string())).as_deref() {
+        Some(PropertyType::DataProperty(data_property)) => {
+            match &*data_property.value.borrow() {
+                JSValue::String(string) => Some(string.clone()),
+                _ => None
+            }
+        },
+        _ => None
+    }
+}
+
+fn string_value(completion_record: CompletionRecord) -> Result<String, CompletionRecord> {
+    let value = completion_value(completion_record)?;
+    let string = match &*value.borrow() {
+        JSValue::String(string) => string.clone(),
+        _ => unreachable!()
+    };
+    return Ok(string);
+}
@@ -0,0 +1,229 @@
+use std::cell::RefCell;
+use std::collections::{HashSet, VecDeque};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use crate::console::{format_error, LogLevel};
+use crate::interpreter::{Interpreter, JSObject};
+use crate::mutation_observer::take_mutation_observer_microtask;
+use crate::node::WeakNode;
+use crate::transitions::run_animation_frame;
+
+// https://html.spec.whatwg.org/multipage/webappapis.html#event-loops
+// TODO: There is a single task queue, and updating the rendering only runs a frame of the documents' animations
+
+// https://html.spec.whatwg.org/multipage/webappapis.html#concept-task
+pub(crate) type Task = Box<dyn FnOnce(&mut Interpreter)>;
+
+// https://html.spec.whatwg.org/multipage/timers-and-user-prompts.html#run-steps-after-a-timeout
+struct Timeout {
+    timer_id: i32,
+    due: Instant,
+    steps: Task,
+}
+
+#[derive(Default)]
+pub(crate) struct EventLoop {
+    // https://html.spec.whatwg.org/multipage/webappapis.html#task-queue
+    task_queue: VecDeque<Task>,
+    // https://html.spec.whatwg.org/multipage/webappapis.html#microtask-queue
+    microtask_queue: VecDeque<Task>,
+    // https://html.spec.whatwg.org/multipage/webappapis.html#performing-a-microtask-checkpoint
+    performing_a_microtask_checkpoint: bool,
+    // https://html.spec.whatwg.org/multipage/webappapis.html#about-to-be-notified-rejected-promises-list
+    about_to_be_notified_rejected_promises: Vec<Rc<RefCell<JSObject>>>,
+    // Steps waiting for their timeout to elapse, in the order they were started
+    timeouts: Vec<Timeout>,
+    // https://html.spec.whatwg.org/multipage/timers-and-user-prompts.html#map-of-active-timers
+    map_of_active_timers: HashSet<i32>,
+    last_timer_id: i32,
+    // https://html.spec.whatwg.org/multipage/timers-and-user-prompts.html#timer-nesting-level
+    // The timer nesting level of the currently running task, 0 when it was not queued by a timer
+    pub(crate) timer_nesting_level: u32,
+    // The documents whose rendering is updated after each task
+    pub(crate) documents: Vec<WeakNode>,
+}
+
+impl EventLoop {
+    // https://html.spec.whatwg.org/multipage/webappapis.html#queue-a-task
+    pub(crate) fn queue_task(&mut self, task: Task) {
+        self.task_queue.push_back(task);
+    }
+
+    // https://html.spec.whatwg.org/multipage/webappapis.html#queue-a-microtask
+    pub(crate) fn queue_microtask(&mut self, microtask: Task) {
+        self.microtask_queue.push_back(microtask);
+    }
+
+    // https://html.spec.whatwg.org/multipage/webappapis.html#the-hostpromiserejectiontracker-implementation
+    // The "reject" operation, the promise is reported at the next microtask checkpoint unless a handler is added before then
+    pub(crate) fn track_rejected_promise(&mut self, promise: &Rc<RefCell<JSObject>>) {
+        // 6. If operation is "reject", then
+        //        1. Append promise to settings object's about-to-be-notified rejected promises list.
+        self.about_to_be_notified_rejected_promises.push(Rc::clone(promise));
+    }
+
+    // The "handle" operation
+    // TODO: rejectionhandled is not fired for promises that have already been reported
+    pub(crate) fn untrack_rejected_promise(&mut self, promise: &Rc<RefCell<JSObject>>) {
+        // 7. If operation is "handle", then
+        //        1. If settings object's about-to-be-notified rejected promises list contains promise, then remove promise from that list and return.
+        self.about_to_be_notified_rejected_promises.retain(|other| !Rc::ptr_eq(other, promise));
+    }
+
+    // Ids are greater than zero and unique within the map of active timers
+    pub(crate) fn create_timer_id(&mut self) -> i32 {
+        loop {
+            self.last_timer_id = self.last_timer_id.checked_add(1).unwrap_or(1);
+            if !self.map_of_active_timers.contains(&self.last_timer_id) {
+                return self.last_timer_id;
+            }
+        }
+    }
+
+    pub(crate) fn is_active_timer(&self, timer_id: i32) -> bool {
+        return self.map_of_active_timers.contains(&timer_id);
+    }
+
+    pub(crate) fn activate_timer(&mut self, timer_id: i32) {
+        self.map_of_active_timers.insert(timer_id);
+    }
+
+    // Removing the timer is enough to stop its task from running, its timeout is dropped too so the event loop does not wait for it
+    pub(crate) fn remove_timer(&mut self, timer_id: i32) {
+        self.map_of_active_timers.remove(&timer_id);
+        self.timeouts.retain(|timeout| timeout.timer_id != timer_id);
+    }
+
+    // https://html.spec.whatwg.org/multipage/timers-and-user-prompts.html#run-steps-after-a-timeout
+    pub(crate) fn run_steps_after_a_timeout(&mut self, timer_id: i32, milliseconds: u32, completion_steps: Task) {
+        // 6. Let startTime be the current high resolution time given global.
+        // 10. Wait until any invocations of this algorithm that had the same global and orderingIdentifier, that started before this one, and whose milliseconds is less than or equal to this one's, have completed.
+        // Timeouts that are due at the same time keep the order they were started in
+        let due = Instant::now() + Duration::from_millis(milliseconds as u64);
+        self.timeouts.push(Timeout { timer_id, due, steps: completion_steps });
+    }
+
+    // The index of the timeout that elapses first, the earliest started of them if several elapse together
+    fn next_timeout(&self) -> Option<usize> {
+        let mut next: Option<usize> = None;
+        for (index, timeout) in self.timeouts.iter().enumerate() {
+            if next.map(|next| timeout.due < self.timeouts[next].due).unwrap_or(true) {
+                next = Some(index);
+            }
+        }
+        return next;
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/webappapis.html#perform-a-microtask-checkpoint
+pub(crate) fn perform_a_microtask_checkpoint(interpreter: &mut Interpreter) {
+    // 1. If the event loop's performing a microtask checkpoint is true, then return.
+    if interpreter.event_loop.performing_a_microtask_checkpoint {
+        return;
+    }
+
+    // 2. Set the event loop's performing a microtask checkpoint to true.
+    interpreter.event_loop.performing_a_microtask_checkpoint = true;
+
+    // 3. While the event loop's microtask queue is not empty:
+    //        1. Let oldestMicrotask be the result of dequeuing from the event loop's microtask queue.
+    //        3. Run oldestMicrotask.
+    loop {
+        // FIXME: The DOM can not reach the event loop, so the microtask to notify mutation observers is added here, behind the microtasks queued before it
+        if let Some(microtask) = take_mutation_observer_microtask() {
+            interpreter.event_loop.queue_microtask(microtask);
+        }
+        let microtask = match interpreter.event_loop.microtask_queue.pop_front() {
+            Some(microtask) => microtask,
+            None => break
+        };
+        microtask(interpreter);
+    }
+
+    // 5. For each environment settings object settingsObject whose responsible event loop is this event loop, notify about rejected promises given settingsObject's global object.
+    notify_about_rejected_promises(interpreter);
+
+    // 8. Set the event loop's performing a microtask checkpoint to false.
+    interpreter.event_loop.performing_a_microtask_checkpoint = false;
+}
+
+// https://html.spec.whatwg.org/multipage/webappapis.html#notify-about-rejected-promises
+// TODO: There is no Window to fire unhandledrejection at, so the rejections are reported straight away
+fn notify_about_rejected_promises(interpreter: &mut Interpreter) {
+    // 2. Let list be a clone of global's about-to-be-notified rejected promises list.
+    // 4. Clear global's about-to-be-notified rejected promises list.
+    let list = std::mem::take(&mut interpreter.event_loop.about_to_be_notified_rejected_promises);
+
+    // 5.1. For each promise p of list:
+    for promise in list {
+        let message = {
+            let promise = promise.borrow();
+            let promise_data = promise.promise_data.as_ref().unwrap();
+
+            // 1. If p.[[PromiseIsHandled]] is true, then continue.
+            if promise_data.is_handled {
+                continue;
+            }
+
+            // 5. If notCanceled is true, then the user agent may report p.[[PromiseResult]] to a developer console.
+            let reason = promise_data.result.borrow();
+            format!("Uncaught (in promise) {}", format_error(&reason))
+        };
+        interpreter.print(LogLevel::Error, &message);
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/webappapis.html#event-loop-processing-model
+// Runs tasks until none are left, or until the next timeout would only elapse after the deadline
+pub(crate) fn run(interpreter: &mut Interpreter, deadline: Option<Instant>) {
+    // The microtasks queued by the script that ran before the event loop are run first
+    perform_a_microtask_checkpoint(interpreter);
+    update_the_rendering(interpreter);
+
+    loop {
+        // 2. Let taskQueue be one of the event loop's task queues, chosen in an implementation-defined manner, with the constraint that the chosen task queue must contain at least one runnable task.
+        // 2.4. Let oldestTask be the first runnable task in taskQueue, and remove it from taskQueue.
+        if let Some(task) = interpreter.event_loop.task_queue.pop_front() {
+            // 2.7. Perform oldestTask's steps.
+            interpreter.reset_script_budget();
+            task(interpreter);
+
+            // 2.9. Perform a microtask checkpoint.
+            perform_a_microtask_checkpoint(interpreter);
+            interpreter.collect_garbage_if_needed();
+
+            // 2.11. Update the rendering
+            update_the_rendering(interpreter);
+            continue;
+        }
+
+        // There are no runnable tasks, so wait for the next timeout to elapse and queue its steps
+        let index = match interpreter.event_loop.next_timeout() {
+            Some(index) => index,
+            None => return
+        };
+
+        let due = interpreter.event_loop.timeouts[index].due;
+        if deadline.is_some_and(|deadline| due > deadline) {
+            return;
+        }
+
+        let now = Instant::now();
+        if due > now {
+            std::thread::sleep(due - now);
+        }
+
+        let timeout = interpreter.event_loop.timeouts.remove(index);
+        interpreter.event_loop.queue_task(timeout.steps);
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/webappapis.html#update-the-rendering
+// TODO: There are no animation frame callbacks, and nothing is painted
+fn update_the_rendering(interpreter: &mut Interpreter) {
+    let documents: Vec<_> = interpreter.event_loop.documents.iter().filter_map(|document| document.upgrade()).collect();
+    // 14. For each doc of docs, update animations and send events for doc
+    for document in documents {
+        run_animation_frame(&document);
+    }
+}
@@ -0,0 +1,696 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use crate::dom_bindings::{argument_to_dom_string, create_interface_prototype_object, install_interface_object, this_node, wrap_node, wrap_optional_node};
+use crate::gc::{HeapCell, Trace, Tracer};
+use crate::interpreter::{completion_value, create_normal_completion, create_value_completion, CompletionRecord, CompletionRecordType, Interpreter, JSObject, JSValue, NativeFunction, PlatformObject, PropertyKey, ReferenceRecordOrJsValue};
+use crate::node::{DOMString, RefNode};
+
+// https://dom.spec.whatwg.org/#events
+// TODO: Nodes are the only event targets as there is no Window, and CustomEvent, AbortSignal and shadow trees are not supported
+
+// https://dom.spec.whatwg.org/#dom-event-eventphase
+const NONE: u16 = 0;
+const CAPTURING_PHASE: u16 = 1;
+const AT_TARGET: u16 = 2;
+const BUBBLING_PHASE: u16 = 3;
+
+// https://dom.spec.whatwg.org/#concept-event
+pub(crate) struct Event {
+    pub(crate) type_: DOMString,
+    target: Option<RefNode>,
+    current_target: Option<RefNode>,
+    event_phase: u16,
+    // https://dom.spec.whatwg.org/#stop-propagation-flag
+    stop_propagation_flag: bool,
+    stop_immediate_propagation_flag: bool,
+    canceled_flag: bool,
+    in_passive_listener_flag: bool,
+    initialized_flag: bool,
+    dispatch_flag: bool,
+    bubbles: bool,
+    cancelable: bool,
+    is_trusted: bool,
+}
+
+impl Event {
+    // https://dom.spec.whatwg.org/#inner-event-creation-steps
+    fn new(type_: DOMString, bubbles: bool, cancelable: bool) -> Event {
+        // 2. Set event's initialized flag.
+        Event { type_, target: None, current_target: None, event_phase: NONE, stop_propagation_flag: false, stop_immediate_propagation_flag: false, canceled_flag: false, in_passive_listener_flag: false, initialized_flag: true, dispatch_flag: false, bubbles, cancelable, is_trusted: false }
+    }
+}
+
+// https://dom.spec.whatwg.org/#concept-event-listener
+pub(crate) struct EventListener {
+    type_: DOMString,
+    // The callback is an EventListener callback interface, either a function or an object with a handleEvent method
+    callback: Rc<RefCell<JSObject>>,
+    capture: bool,
+    passive: bool,
+    once: bool,
+    // Dispatching iterates over a clone of the event listener list, so listeners removed during it are marked instead
+    removed: Cell<bool>,
+}
+
+impl Trace for EventListener {
+    fn trace(&self, tracer: &mut Tracer) {
+        tracer.visit(&self.callback);
+    }
+}
+
+// Event listener lists share their listeners with the clones dispatching makes, so each listener is a cell of its own
+impl HeapCell for EventListener {
+    fn trace_cell(&self, tracer: &mut Tracer) {
+        self.trace(tracer);
+    }
+
+    fn unlink_cell(&self) {}
+
+    fn cell_size(&self) -> usize {
+        return std::mem::size_of::<Self>() + self.type_.capacity();
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Phase {
+    Capturing,
+    Bubbling,
+}
+
+// https://dom.spec.whatwg.org/#interface-eventtarget
+// https://dom.spec.whatwg.org/#interface-event
+pub(crate) fn install_events(interpreter: &mut Interpreter) {
+    let event_target_prototype = create_interface_prototype_object(interpreter.intrinsic("Object.prototype"));
+    Interpreter::define_builtin_function(&event_target_prototype, "addEventListener", event_target_add_event_listener);
+    Interpreter::define_builtin_function(&event_target_prototype, "removeEventListener", event_target_remove_event_listener);
+    Interpreter::define_builtin_function(&event_target_prototype, "dispatchEvent", event_target_dispatch_event);
+    interpreter.set_intrinsic("EventTarget.prototype", event_target_prototype);
+    install_interface_object(interpreter, "EventTarget");
+
+    let event_prototype = create_interface_prototype_object(interpreter.intrinsic("Object.prototype"));
+    let attributes: [(&str, NativeFunction); 8] = [
+        ("type", event_type),
+        ("target", event_target),
+        ("currentTarget", event_current_target),
+        ("eventPhase", event_event_phase),
+        ("bubbles", event_bubbles),
+        ("cancelable", event_cancelable),
+        ("defaultPrevented", event_default_prevented),
+        ("isTrusted", event_is_trusted),
+    ];
+    for (name, getter) in attributes {
+        Interpreter::define_accessor_property(&event_prototype, name, getter, None);
+    }
+    Interpreter::define_builtin_function(&event_prototype, "stopPropagation", event_stop_propagation);
+    Interpreter::define_builtin_function(&event_prototype, "stopImmediatePropagation", event_stop_immediate_propagation);
+    Interpreter::define_builtin_function(&event_prototype, "preventDefault", event_prevent_default);
+    define_event_phase_constants(&event_prototype);
+    interpreter.set_intrinsic("Event.prototype", event_prototype);
+
+    let event_interface_object = install_interface_object(interpreter, "Event");
+    event_interface_object.borrow_mut().call = Some(event_call);
+    event_interface_object.borrow_mut().construct = Some(event_construct);
+    Interpreter::define_property_or_throw(&event_interface_object, "length", Rc::new(RefCell::new(JSValue::Numeric(1.0))), false, false, true);
+    define_event_phase_constants(&event_interface_object);
+}
+
+// https://webidl.spec.whatwg.org/#es-constants
+// Constants are exposed on both the interface object and the interface prototype object
+fn define_event_phase_constants(object: &Rc<RefCell<JSObject>>) {
+    for (name, value) in [("NONE", NONE), ("CAPTURING_PHASE", CAPTURING_PHASE), ("AT_TARGET", AT_TARGET), ("BUBBLING_PHASE", BUBBLING_PHASE)] {
+        Interpreter::define_property_or_throw(object, name, Rc::new(RefCell::new(JSValue::Numeric(value as f64))), false, true, false);
+    }
+}
+
+// https://dom.spec.whatwg.org/#concept-event-create
+// Events created by the user agent rather than by a script are trusted
+fn create_an_event(interpreter: &Interpreter, type_: &str, bubbles: bool, cancelable: bool) -> Rc<RefCell<JSObject>> {
+    let mut event = Event::new(type_.to_string(), bubbles, cancelable);
+    // 3. Initialize event's isTrusted attribute to true.
+    event.is_trusted = true;
+
+    let mut object = JSObject::new();
+    object.extensible = true;
+    object.prototype = interpreter.intrinsic("Event.prototype");
+    object.platform_object = Some(PlatformObject::Event(Rc::new(RefCell::new(event))));
+    return Rc::new(RefCell::new(object));
+}
+
+// https://dom.spec.whatwg.org/#concept-event-fire
+// Returns false if a listener canceled the event
+pub(crate) fn fire_an_event(interpreter: &mut Interpreter, type_: &str, target: &RefNode, bubbles: bool, cancelable: bool) -> bool {
+    // 2. Let event be the result of creating an event given eventConstructor, in the relevant realm of target.
+    // 3. Initialize event's type attribute to e.
+    // 4. Initialize any other IDL attributes of event as described in the invocation of this algorithm.
+    let event_object = create_an_event(interpreter, type_, bubbles, cancelable);
+
+    // 5. Return the result of dispatching event at target, with legacy target override flag set if set.
+    return dispatch(interpreter, &event_object, target);
+}
+
+fn unwrap_event(value: &Rc<RefCell<JSValue>>) -> Option<Rc<RefCell<Event>>> {
+    match &*value.borrow() {
+        JSValue::Object(object) => {
+            match &object.borrow().platform_object {
+                Some(PlatformObject::Event(event)) => Some(Rc::clone(event)),
+                _ => None
+            }
+        },
+        _ => None
+    }
+}
+
+fn this_event(interpreter: &mut Interpreter, this_value: &Rc<RefCell<JSValue>>) -> Result<Rc<RefCell<Event>>, CompletionRecord> {
+    match unwrap_event(this_value) {
+        Some(event) => Ok(event),
+        None => Err(interpreter.throw_type_error(String::from("Illegal invocation")))
+    }
+}
+
+// https://webidl.spec.whatwg.org/#es-dictionary
+// Returns None when the dictionary is undefined or null, in which case every member has its default value
+fn dictionary_object(interpreter: &mut Interpreter, value: Option<&Rc<RefCell<JSValue>>>, message: &str) -> Result<Option<Rc<RefCell<JSObject>>>, CompletionRecord> {
+    let value = match value {
+        Some(value) => value,
+        None => return Ok(None)
+    };
+
+    match &*value.borrow() {
+        // 1. If jsDict is not an Object and jsDict is neither undefined nor null, then throw a TypeError.
+        JSValue::Undefined | JSValue::Null => Ok(None),
+        JSValue::Object(object) => Ok(Some(Rc::clone(object))),
+        _ => Err(interpreter.throw_type_error(message.to_string()))
+    }
+}
+
+// https://webidl.spec.whatwg.org/#es-dictionary
+// Boolean members that are not present default to false
+pub(crate) fn dictionary_boolean_member(interpreter: &mut Interpreter, dictionary: &Option<Rc<RefCell<JSObject>>>, key: &str) -> Result<bool, CompletionRecord> {
+    let dictionary = match dictionary {
+        Some(dictionary) => dictionary,
+        None => return Ok(false)
+    };
+
+    // 1. Let jsMemberValue be ? Get(jsDict, key).
+    let receiver = Rc::new(RefCell::new(JSValue::Object(Rc::clone(dictionary))));
+    let value = completion_value(interpreter.get(dictionary, &PropertyKey::String(key.to_string()), receiver))?;
+
+    // 4. If jsMemberValue is not undefined, then let idlMemberValue be the result of converting jsMemberValue to an IDL value whose type is the type member is declared to be of.
+    let value = Interpreter::to_boolean(value);
+    let value = matches!(*value.borrow(), JSValue::Boolean(true));
+    return Ok(value);
+}
+
+// https://dom.spec.whatwg.org/#concept-flatten-options
+// https://dom.spec.whatwg.org/#event-flatten-more
+// The options are either a boolean for capture or an AddEventListenerOptions dictionary
+fn flatten_more_options(interpreter: &mut Interpreter, options: Option<&Rc<RefCell<JSValue>>>) -> Result<(bool, bool, bool), CompletionRecord> {
+    let is_object = options.map(|options| matches!(*options.borrow(), JSValue::Object(_))).unwrap_or(false);
+
+    // 1. If options is a boolean, then return options.
+    if !is_object {
+        let capture = match options {
+            Some(options) => matches!(*Interpreter::to_boolean(Rc::clone(options)).borrow(), JSValue::Boolean(true)),
+            None => false
+        };
+        return Ok((capture, false, false));
+    }
+
+    // 2. Return options["capture"].
+    let options = dictionary_object(interpreter, options, "The provided value is not of type 'AddEventListenerOptions'.")?;
+    let capture = dictionary_boolean_member(interpreter, &options, "capture")?;
+
+    // 3. If options is a dictionary, then set passive to options["passive"] and once to options["once"].
+    let once = dictionary_boolean_member(interpreter, &options, "once")?;
+    let passive = dictionary_boolean_member(interpreter, &options, "passive")?;
+    return Ok((capture, passive, once));
+}
+
+// The callback of addEventListener and removeEventListener is a nullable callback interface, any object is accepted
+fn argument_to_event_listener(interpreter: &mut Interpreter, arguments: &[Rc<RefCell<JSValue>>], operation: &str) -> Result<Option<Rc<RefCell<JSObject>>>, CompletionRecord> {
+    if arguments.len() < 2 {
+        return Err(interpreter.throw_type_error(format!("Failed to execute '{}' on 'EventTarget': 2 arguments required, but only {} present.", operation, arguments.len())));
+    }
+
+    match &*arguments[1].borrow() {
+        JSValue::Object(callback) => Ok(Some(Rc::clone(callback))),
+        JSValue::Undefined | JSValue::Null => Ok(None),
+        _ => Err(interpreter.throw_type_error(format!("Failed to execute '{}' on 'EventTarget': parameter 2 is not of type 'Object'.", operation)))
+    }
+}
+
+// https://dom.spec.whatwg.org/#dom-eventtarget-addeventlistener
+fn event_target_add_event_listener(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let event_target = match this_node(interpreter, &this_value) {
+        Ok(event_target) => event_target,
+        Err(completion_record) => return completion_record
+    };
+
+    let callback = match argument_to_event_listener(interpreter, &arguments, "addEventListener") {
+        Ok(callback) => callback,
+        Err(completion_record) => return completion_record
+    };
+
+    let type_ = match argument_to_dom_string(interpreter, &arguments, 0) {
+        Ok(type_) => type_,
+        Err(completion_record) => return completion_record
+    };
+
+    // 1. Let capture, passive, once, and signal be the result of flattening more options.
+    let (capture, passive, once) = match flatten_more_options(interpreter, arguments.get(2)) {
+        Ok(options) => options,
+        Err(completion_record) => return completion_record
+    };
+
+    // 2. Add an event listener with this and an event listener whose type is type, callback is callback, capture is capture, passive is passive, once is once, and signal is signal.
+    // https://dom.spec.whatwg.org/#add-an-event-listener
+    // 2. If listener's callback is null, then return.
+    let callback = match callback {
+        Some(callback) => callback,
+        None => return create_value_completion(JSValue::Undefined)
+    };
+
+    // 4. If eventTarget's event listener list does not contain an event listener whose type is listener's type, callback is listener's callback, and capture is listener's capture, then append listener to eventTarget's event listener list.
+    let contains_listener = event_target.borrow().event_listener_list.iter().any(|listener| listener.type_ == type_ && Rc::ptr_eq(&listener.callback, &callback) && listener.capture == capture);
+    if !contains_listener {
+        event_target.borrow_mut().event_listener_list.push(Rc::new(EventListener { type_, callback, capture, passive, once, removed: Cell::new(false) }));
+    }
+
+    return create_value_completion(JSValue::Undefined);
+}
+
+// https://dom.spec.whatwg.org/#dom-eventtarget-removeeventlistener
+fn event_target_remove_event_listener(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let event_target = match this_node(interpreter, &this_value) {
+        Ok(event_target) => event_target,
+        Err(completion_record) => return completion_record
+    };
+
+    let callback = match argument_to_event_listener(interpreter, &arguments, "removeEventListener") {
+        Ok(callback) => callback,
+        Err(completion_record) => return completion_record
+    };
+
+    let type_ = match argument_to_dom_string(interpreter, &arguments, 0) {
+        Ok(type_) => type_,
+        Err(completion_record) => return completion_record
+    };
+
+    // 1. Let capture be the result of flattening options.
+    let (capture, _, _) = match flatten_more_options(interpreter, arguments.get(2)) {
+        Ok(options) => options,
+        Err(completion_record) => return completion_record
+    };
+
+    // 2. If this's event listener list contains an event listener whose type is type, callback is callback, and capture is capture, then remove an event listener with this and that event listener.
+    if let Some(callback) = callback {
+        let listener = event_target.borrow().event_listener_list.iter().find(|listener| listener.type_ == type_ && Rc::ptr_eq(&listener.callback, &callback) && listener.capture == capture).cloned();
+        if let Some(listener) = listener {
+            remove_an_event_listener(&event_target, &listener);
+        }
+    }
+
+    return create_value_completion(JSValue::Undefined);
+}
+
+// https://dom.spec.whatwg.org/#remove-an-event-listener
+fn remove_an_event_listener(event_target: &RefNode, listener: &Rc<EventListener>) {
+    // 2. Set listener's removed to true and remove listener from eventTarget's event listener list.
+    listener.removed.set(true);
+    event_target.borrow_mut().event_listener_list.retain(|other| !Rc::ptr_eq(other, listener));
+}
+
+// https://dom.spec.whatwg.org/#dom-eventtarget-dispatchevent
+fn event_target_dispatch_event(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let event_target = match this_node(interpreter, &this_value) {
+        Ok(event_target) => event_target,
+        Err(completion_record) => return completion_record
+    };
+
+    let event_value = match arguments.first() {
+        Some(event_value) => Rc::clone(event_value),
+        None => return interpreter.throw_type_error(String::from("Failed to execute 'dispatchEvent' on 'EventTarget': 1 argument required, but only 0 present."))
+    };
+
+    let event = match unwrap_event(&event_value) {
+        Some(event) => event,
+        None => return interpreter.throw_type_error(String::from("Failed to execute 'dispatchEvent' on 'EventTarget': parameter 1 is not of type 'Event'."))
+    };
+
+    // 1. If event's dispatch flag is set, or if its initialized flag is not set, then throw an "InvalidStateError" DOMException.
+    if event.borrow().dispatch_flag || !event.borrow().initialized_flag {
+        return interpreter.throw_error("InvalidStateError", String::from("Failed to execute 'dispatchEvent' on 'EventTarget': The event is already being dispatched."));
+    }
+
+    // 2. Initialize event's isTrusted attribute to false.
+    event.borrow_mut().is_trusted = false;
+
+    // 3. Return the result of dispatching event to this.
+    let event_object = match &*event_value.borrow() {
+        JSValue::Object(event_object) => Rc::clone(event_object),
+        _ => unreachable!()
+    };
+    let not_canceled = dispatch(interpreter, &event_object, &event_target);
+    return create_value_completion(JSValue::Boolean(not_canceled));
+}
+
+// https://dom.spec.whatwg.org/#get-the-parent
+// A node's get the parent returns its parent, a document's would return its Window
+fn get_the_parent(node: &RefNode) -> Option<RefNode> {
+    return node.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+}
+
+// https://dom.spec.whatwg.org/#concept-event-dispatch
+fn dispatch(interpreter: &mut Interpreter, event_object: &Rc<RefCell<JSObject>>, target: &RefNode) -> bool {
+    let event = match &event_object.borrow().platform_object {
+        Some(PlatformObject::Event(event)) => Rc::clone(event),
+        _ => unreachable!()
+    };
+
+    // 1. Set event's dispatch flag.
+    event.borrow_mut().dispatch_flag = true;
+    event.borrow_mut().target = Some(Rc::clone(target));
+
+    // 5.4. Append to an event path with event, target, targetOverride, relatedTarget, touchTargets, and false.
+    // 5.9. Let parent be the result of invoking target's get the parent with event.
+    // 5.10. While parent is non-null:
+    //        1. Append to an event path with event, parent, null, relatedTarget, touchTargets, and slot-in-closed-tree.
+    //        2. Set parent to the result of invoking parent's get the parent with event.
+    let mut path = vec![Rc::clone(target)];
+    let mut parent = get_the_parent(target);
+    while let Some(node) = parent {
+        parent = get_the_parent(&node);
+        path.push(node);
+    }
+
+    // 5.13. For each struct in event's path, in reverse order:
+    for node in path.iter().rev() {
+        // 1. If struct's shadow-adjusted target is non-null, then set event's eventPhase attribute to AT_TARGET.
+        // 2. Otherwise, set event's eventPhase attribute to CAPTURING_PHASE.
+        event.borrow_mut().event_phase = if Rc::ptr_eq(node, target) { AT_TARGET } else { CAPTURING_PHASE };
+
+        // 3. Invoke with struct, event, "capturing", and legacyOutputDidListenersThrowFlag if given.
+        invoke(interpreter, event_object, &event, node, Phase::Capturing);
+    }
+
+    // 5.14. For each struct in event's path:
+    for node in path.iter() {
+        // 1. If struct's shadow-adjusted target is non-null, then set event's eventPhase attribute to AT_TARGET.
+        if Rc::ptr_eq(node, target) {
+            event.borrow_mut().event_phase = AT_TARGET;
+        } else {
+            // 2. Otherwise:
+            //        1. If event's bubbles attribute is false, then continue.
+            if !event.borrow().bubbles {
+                continue;
+            }
+
+            //        2. Set event's eventPhase attribute to BUBBLING_PHASE.
+            event.borrow_mut().event_phase = BUBBLING_PHASE;
+        }
+
+        // 3. Invoke with struct, event, "bubbling", and legacyOutputDidListenersThrowFlag if given.
+        invoke(interpreter, event_object, &event, node, Phase::Bubbling);
+    }
+
+    let mut event = event.borrow_mut();
+
+    // 6. Set event's eventPhase attribute to NONE.
+    event.event_phase = NONE;
+
+    // 7. Set event's currentTarget attribute to null.
+    event.current_target = None;
+
+    // 9. Unset event's dispatch flag, stop propagation flag, and stop immediate propagation flag.
+    event.dispatch_flag = false;
+    event.stop_propagation_flag = false;
+    event.stop_immediate_propagation_flag = false;
+
+    // 12. Return false if event's canceled flag is set; otherwise true.
+    return !event.canceled_flag;
+}
+
+// https://dom.spec.whatwg.org/#concept-event-listener-invoke
+fn invoke(interpreter: &mut Interpreter, event_object: &Rc<RefCell<JSObject>>, event: &Rc<RefCell<Event>>, node: &RefNode, phase: Phase) {
+    // 3. If event's stop propagation flag is set, then return.
+    if event.borrow().stop_propagation_flag {
+        return;
+    }
+
+    // 4. Initialize event's currentTarget attribute to struct's invocation target.
+    event.borrow_mut().current_target = Some(Rc::clone(node));
+
+    // 5. Let listeners be a clone of event's currentTarget attribute value's event listener list.
+    let listeners = node.borrow().event_listener_list.clone();
+
+    // 6. Let found be the result of running inner invoke with event, listeners, phase, struct's invocation-target-in-shadow-tree, and legacyOutputDidListenersThrowFlag if given.
+    inner_invoke(interpreter, event_object, event, node, &listeners, phase);
+}
+
+// https://dom.spec.whatwg.org/#concept-event-listener-inner-invoke
+fn inner_invoke(interpreter: &mut Interpreter, event_object: &Rc<RefCell<JSObject>>, event: &Rc<RefCell<Event>>, node: &RefNode, listeners: &[Rc<EventListener>], phase: Phase) {
+    // 2. For each listener of listeners, whose removed is false:
+    for listener in listeners.iter().filter(|listener| !listener.removed.get()) {
+        // 1. If event's type attribute value is not listener's type, then continue.
+        if event.borrow().type_ != listener.type_ {
+            continue;
+        }
+
+        // 3. If phase is "capturing" and listener's capture is false, then continue.
+        // 4. If phase is "bubbling" and listener's capture is true, then continue.
+        if (phase == Phase::Capturing) != listener.capture {
+            continue;
+        }
+
+        // 5. If listener's once is true, then remove an event listener given event's currentTarget attribute value and listener.
+        if listener.once {
+            remove_an_event_listener(node, listener);
+        }
+
+        // 9. If listener's passive is true, then set event's in passive listener flag.
+        if listener.passive {
+            event.borrow_mut().in_passive_listener_flag = true;
+        }
+
+        // 11. Call a user object's operation with listener's callback, "handleEvent", « event », and event's currentTarget attribute value.
+        //     If this throws an exception exception, report exception for listener's callback's corresponding JavaScript object's associated realm's global object.
+        let this_value = wrap_node(interpreter, node);
+        let event_value = Rc::new(RefCell::new(JSValue::Object(Rc::clone(event_object))));
+        let completion_record = call_user_object_operation(interpreter, &listener.callback, "handleEvent", vec![event_value], this_value);
+        if let (CompletionRecordType::Throw, ReferenceRecordOrJsValue::JSValue(exception)) = (&completion_record.type_, &*completion_record.value) {
+            interpreter.report_exception(exception);
+        }
+
+        // 12. Unset event's in passive listener flag.
+        event.borrow_mut().in_passive_listener_flag = false;
+
+        // 14. If event's stop immediate propagation flag is set, then break.
+        if event.borrow().stop_immediate_propagation_flag {
+            break;
+        }
+    }
+}
+
+// https://webidl.spec.whatwg.org/#call-a-user-objects-operation
+fn call_user_object_operation(interpreter: &mut Interpreter, object: &Rc<RefCell<JSObject>>, operation_name: &str, arguments: Vec<Rc<RefCell<JSValue>>>, callback_this_value: Rc<RefCell<JSValue>>) -> CompletionRecord {
+    let object_value = Rc::new(RefCell::new(JSValue::Object(Rc::clone(object))));
+
+    // 6. Let X be O.
+    // 7. If IsCallable(O) is false, then:
+    if !Interpreter::is_callable(&object_value.borrow()) {
+        // 1. Let getResult be Completion(Get(O, opName)).
+        // 2. If getResult is an abrupt completion, set completion to getResult and jump to the step labeled return.
+        let operation = match completion_value(interpreter.get(object, &PropertyKey::String(operation_name.to_string()), Rc::clone(&object_value))) {
+            Ok(operation) => operation,
+            Err(completion_record) => return completion_record
+        };
+
+        // 4. If IsCallable(X) is false, then set completion to a new ThrowCompletion(a newly created TypeError object) and jump to the step labeled return.
+        if !Interpreter::is_callable(&operation.borrow()) {
+            return interpreter.throw_type_error(format!("The provided callback is not a function and has no '{}' method.", operation_name));
+        }
+
+        // 5. Set thisArg to O (overriding the provided value).
+        return interpreter.call(operation, object_value, arguments);
+    }
+
+    // 10. Let callResult be Completion(Call(X, thisArg, jsArgs)).
+    return interpreter.call(object_value, callback_this_value, arguments);
+}
+
+// https://dom.spec.whatwg.org/#dom-event-event
+// Constructing an Event without new throws like other constructors of platform objects
+fn event_call(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return interpreter.throw_type_error(String::from("Failed to construct 'Event': Please use the 'new' operator, this DOM object constructor cannot be called as a function."));
+}
+
+// https://dom.spec.whatwg.org/#concept-event-constructor
+fn event_construct(interpreter: &mut Interpreter, arguments: Vec<Rc<RefCell<JSValue>>>, new_target: Rc<RefCell<JSObject>>) -> CompletionRecord {
+    if arguments.is_empty() {
+        return interpreter.throw_type_error(String::from("Failed to construct 'Event': 1 argument required, but only 0 present."));
+    }
+
+    let type_ = match argument_to_dom_string(interpreter, &arguments, 0) {
+        Ok(type_) => type_,
+        Err(completion_record) => return completion_record
+    };
+
+    // https://dom.spec.whatwg.org/#dictdef-eventinit
+    let event_init_dict = match dictionary_object(interpreter, arguments.get(1), "Failed to construct 'Event': The provided value is not of type 'EventInit'.") {
+        Ok(event_init_dict) => event_init_dict,
+        Err(completion_record) => return completion_record
+    };
+    let bubbles = match dictionary_boolean_member(interpreter, &event_init_dict, "bubbles") {
+        Ok(bubbles) => bubbles,
+        Err(completion_record) => return completion_record
+    };
+    let cancelable = match dictionary_boolean_member(interpreter, &event_init_dict, "cancelable") {
+        Ok(cancelable) => cancelable,
+        Err(completion_record) => return completion_record
+    };
+
+    // 1. Let event be the result of running the inner event creation steps with this interface, null, now, and eventInitDict.
+    let object = match completion_value(interpreter.ordinary_create_from_constructor(&new_target, "Event.prototype")) {
+        Ok(object) => object,
+        Err(completion_record) => return completion_record
+    };
+
+    // 2. Initialize event's type attribute to type.
+    if let JSValue::Object(event_object) = &*object.borrow() {
+        event_object.borrow_mut().platform_object = Some(PlatformObject::Event(Rc::new(RefCell::new(Event::new(type_, bubbles, cancelable)))));
+    }
+
+    // 3. Return event.
+    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(object)));
+}
+
+// https://dom.spec.whatwg.org/#dom-event-type
+fn event_type(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let event = match this_event(interpreter, &this_value) {
+        Ok(event) => event,
+        Err(completion_record) => return completion_record
+    };
+
+    let type_ = event.borrow().type_.clone();
+    return create_value_completion(JSValue::String(type_));
+}
+
+// https://dom.spec.whatwg.org/#dom-event-target
+fn event_target(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let event = match this_event(interpreter, &this_value) {
+        Ok(event) => event,
+        Err(completion_record) => return completion_record
+    };
+
+    let target = event.borrow().target.clone();
+    return wrap_optional_node(interpreter, target);
+}
+
+// https://dom.spec.whatwg.org/#dom-event-currenttarget
+fn event_current_target(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let event = match this_event(interpreter, &this_value) {
+        Ok(event) => event,
+        Err(completion_record) => return completion_record
+    };
+
+    let current_target = event.borrow().current_target.clone();
+    return wrap_optional_node(interpreter, current_target);
+}
+
+// https://dom.spec.whatwg.org/#dom-event-eventphase
+fn event_event_phase(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let event = match this_event(interpreter, &this_value) {
+        Ok(event) => event,
+        Err(completion_record) => return completion_record
+    };
+
+    let event_phase = event.borrow().event_phase;
+    return create_value_completion(JSValue::Numeric(event_phase as f64));
+}
+
+// https://dom.spec.whatwg.org/#dom-event-bubbles
+fn event_bubbles(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let event = match this_event(interpreter, &this_value) {
+        Ok(event) => event,
+        Err(completion_record) => return completion_record
+    };
+
+    let bubbles = event.borrow().bubbles;
+    return create_value_completion(JSValue::Boolean(bubbles));
+}
+
+// https://dom.spec.whatwg.org/#dom-event-cancelable
+fn event_cancelable(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let event = match this_event(interpreter, &this_value) {
+        Ok(event) => event,
+        Err(completion_record) => return completion_record
+    };
+
+    let cancelable = event.borrow().cancelable;
+    return create_value_completion(JSValue::Boolean(cancelable));
+}
+
+// https://dom.spec.whatwg.org/#dom-event-defaultprevented
+// The defaultPrevented getter steps are to return true if this's canceled flag is set; otherwise false.
+fn event_default_prevented(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let event = match this_event(interpreter, &this_value) {
+        Ok(event) => event,
+        Err(completion_record) => return completion_record
+    };
+
+    let canceled = event.borrow().canceled_flag;
+    return create_value_completion(JSValue::Boolean(canceled));
+}
+
+// https://dom.spec.whatwg.org/#dom-event-istrusted
+// TODO: isTrusted should be an unforgeable own property of each event rather than an accessor on the prototype
+fn event_is_trusted(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let event = match this_event(interpreter, &this_value) {
+        Ok(event) => event,
+        Err(completion_record) => return completion_record
+    };
+
+    let is_trusted = event.borrow().is_trusted;
+    return create_value_completion(JSValue::Boolean(is_trusted));
+}
+
+// https://dom.spec.whatwg.org/#dom-event-stoppropagation
+fn event_stop_propagation(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let event = match this_event(interpreter, &this_value) {
+        Ok(event) => event,
+        Err(completion_record) => return completion_record
+    };
+
+    // The stopPropagation() method steps are to set this's stop propagation flag.
+    event.borrow_mut().stop_propagation_flag = true;
+    return create_value_completion(JSValue::Undefined);
+}
+
+// https://dom.spec.whatwg.org/#dom-event-stopimmediatepropagation
+fn event_stop_immediate_propagation(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let event = match this_event(interpreter, &this_value) {
+        Ok(event) => event,
+        Err(completion_record) => return completion_record
+    };
+
+    // The stopImmediatePropagation() method steps are to set this's stop propagation flag and this's stop immediate propagation flag.
+    event.borrow_mut().stop_propagation_flag = true;
+    event.borrow_mut().stop_immediate_propagation_flag = true;
+    return create_value_completion(JSValue::Undefined);
+}
+
+// https://dom.spec.whatwg.org/#dom-event-preventdefault
+fn event_prevent_default(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let event = match this_event(interpreter, &this_value) {
+        Ok(event) => event,
+        Err(completion_record) => return completion_record
+    };
+
+    // https://dom.spec.whatwg.org/#set-the-canceled-flag
+    // To set the canceled flag given an event event, if event's cancelable attribute value is true and event's in passive listener flag is unset, set event's canceled flag, and do nothing otherwise.
+    let mut event = event.borrow_mut();
+    if event.cancelable && !event.in_passive_listener_flag {
+        event.canceled_flag = true;
+    }
+    return create_value_completion(JSValue::Undefined);
+}
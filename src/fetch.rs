@@ -0,0 +1,199 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::dom_bindings::{create_interface_prototype_object, install_interface_object};
+use crate::interpreter::{create_normal_completion, create_value_completion, CompletionRecord, CompletionRecordType, Interpreter, JSObject, JSValue, NativeFunction, PlatformObject, ReferenceRecordOrJsValue};
+use crate::promise::{a_new_promise, reject_a_promise, resolve_a_promise};
+use crate::resource_loader::Response;
+
+// https://fetch.spec.whatwg.org/#fetch-method
+// TODO: There is no Request or Headers interface and no init dictionary, every fetch is a GET of the URL it is given, and XMLHttpRequest is not implemented
+
+// https://fetch.spec.whatwg.org/#response-class
+pub(crate) struct FetchResponse {
+    pub(crate) response: Response,
+    // https://fetch.spec.whatwg.org/#dom-body-bodyused
+    body_used: bool,
+}
+
+pub(crate) fn install_fetch(interpreter: &mut Interpreter) {
+    let response_prototype = create_interface_prototype_object(interpreter.intrinsic("Object.prototype"));
+    let attributes: [(&str, NativeFunction); 5] = [
+        ("url", response_url),
+        ("status", response_status),
+        ("ok", response_ok),
+        ("statusText", response_status_text),
+        ("bodyUsed", response_body_used),
+    ];
+    for (name, getter) in attributes {
+        Interpreter::define_accessor_property(&response_prototype, name, getter, None);
+    }
+    Interpreter::define_builtin_function(&response_prototype, "text", response_text);
+    interpreter.set_intrinsic("Response.prototype", response_prototype);
+    install_interface_object(interpreter, "Response");
+
+    let global_object = interpreter.global_object();
+    Interpreter::define_builtin_function(&global_object, "fetch", fetch);
+}
+
+// The value of a normal or throw completion
+fn completion_record_value(completion_record: &CompletionRecord) -> Rc<RefCell<JSValue>> {
+    match &*completion_record.value {
+        ReferenceRecordOrJsValue::JSValue(value) => Rc::clone(value),
+        _ => unreachable!()
+    }
+}
+
+fn this_response(interpreter: &mut Interpreter, this_value: &Rc<RefCell<JSValue>>) -> Result<Rc<RefCell<FetchResponse>>, CompletionRecord> {
+    if let JSValue::Object(object) = &*this_value.borrow() {
+        if let Some(PlatformObject::Response(response)) = &object.borrow().platform_object {
+            return Ok(Rc::clone(response));
+        }
+    }
+
+    return Err(interpreter.throw_type_error(String::from("Illegal invocation")));
+}
+
+// https://fetch.spec.whatwg.org/#concept-response-create
+fn create_a_response(interpreter: &Interpreter, response: Response) -> Rc<RefCell<JSValue>> {
+    let mut object = JSObject::new();
+    object.extensible = true;
+    object.prototype = interpreter.intrinsic("Response.prototype");
+    object.platform_object = Some(PlatformObject::Response(Rc::new(RefCell::new(FetchResponse { response, body_used: false }))));
+    return Rc::new(RefCell::new(JSValue::Object(Rc::new(RefCell::new(object)))));
+}
+
+// https://fetch.spec.whatwg.org/#dom-global-fetch
+fn fetch(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let input = match arguments.first() {
+        Some(input) => Rc::clone(input),
+        None => return interpreter.throw_type_error(String::from("fetch requires at least 1 argument"))
+    };
+
+    let completion_record = interpreter.to_string_coercing_objects(input);
+    if let CompletionRecordType::Throw = completion_record.type_ {
+        return completion_record;
+    }
+    let input = match &*completion_record_value(&completion_record).borrow() {
+        JSValue::String(input) => input.clone(),
+        _ => unreachable!()
+    };
+
+    // 1. Let p be a new promise.
+    let promise_capability = a_new_promise(interpreter);
+
+    // 2. Let requestObject be the result of invoking the initial value of Request as constructor with input and init as arguments. If this throws an exception, reject p with it and return p.
+    // https://fetch.spec.whatwg.org/#dom-request
+    // 5.1. Let parsedURL be the result of parsing input with baseURL.
+    // 5.2. If parsedURL is failure, then throw a TypeError.
+    let url = match interpreter.document_url().join(&input) {
+        Ok(url) => url,
+        Err(error) => {
+            let completion_record = interpreter.throw_type_error(format!("Failed to parse URL from {}: {}", input, error));
+            let promise = reject_a_promise(interpreter, &promise_capability, completion_record_value(&completion_record));
+            return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(promise)));
+        }
+    };
+
+    // 12. Set controller to the result of calling fetch given request and processResponse given response being these steps:
+    // The resource is loaded by a task on the networking task source, so fetch returns before the response arrives
+    let promise = Rc::clone(&promise_capability.promise);
+    interpreter.event_loop.queue_task(Box::new(move |interpreter: &mut Interpreter| {
+        match interpreter.load_resource(&url) {
+            // 3. If response is a network error, then reject p with a TypeError and abort these steps.
+            Err(message) => {
+                let completion_record = interpreter.throw_type_error(format!("Failed to fetch {}: {}", url, message));
+                reject_a_promise(interpreter, &promise_capability, completion_record_value(&completion_record));
+            },
+            // 4. Set responseObject to the result of creating a Response object, given response, "immutable", and relevantRealm.
+            // 5. Resolve p with responseObject.
+            Ok(response) => {
+                let response_object = create_a_response(interpreter, response);
+                resolve_a_promise(interpreter, &promise_capability, response_object);
+            }
+        }
+    }));
+
+    // 13. Return p.
+    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(promise)));
+}
+
+// https://fetch.spec.whatwg.org/#dom-response-url
+fn response_url(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let response = match this_response(interpreter, &this_value) {
+        Ok(response) => response,
+        Err(completion_record) => return completion_record
+    };
+
+    let url = response.borrow().response.url.clone();
+    return create_value_completion(JSValue::String(url));
+}
+
+// https://fetch.spec.whatwg.org/#dom-response-status
+fn response_status(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let response = match this_response(interpreter, &this_value) {
+        Ok(response) => response,
+        Err(completion_record) => return completion_record
+    };
+
+    let status = response.borrow().response.status;
+    return create_value_completion(JSValue::Numeric(status as f64));
+}
+
+// https://fetch.spec.whatwg.org/#dom-response-ok
+fn response_ok(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let response = match this_response(interpreter, &this_value) {
+        Ok(response) => response,
+        Err(completion_record) => return completion_record
+    };
+
+    // The ok getter steps are to return true if this's response's status is an ok status; otherwise false.
+    let status = response.borrow().response.status;
+    return create_value_completion(JSValue::Boolean((200..=299).contains(&status)));
+}
+
+// https://fetch.spec.whatwg.org/#dom-response-statustext
+fn response_status_text(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let response = match this_response(interpreter, &this_value) {
+        Ok(response) => response,
+        Err(completion_record) => return completion_record
+    };
+
+    let status_message = response.borrow().response.status_message.clone();
+    return create_value_completion(JSValue::String(status_message));
+}
+
+// https://fetch.spec.whatwg.org/#dom-body-bodyused
+fn response_body_used(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let response = match this_response(interpreter, &this_value) {
+        Ok(response) => response,
+        Err(completion_record) => return completion_record
+    };
+
+    let body_used = response.borrow().body_used;
+    return create_value_completion(JSValue::Boolean(body_used));
+}
+
+// https://fetch.spec.whatwg.org/#dom-body-text
+fn response_text(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let response = match this_response(interpreter, &this_value) {
+        Ok(response) => response,
+        Err(completion_record) => return completion_record
+    };
+
+    // https://fetch.spec.whatwg.org/#concept-body-consume-body
+    let promise_capability = a_new_promise(interpreter);
+
+    // 1. If object is unusable, then return a promise rejected with a TypeError.
+    if response.borrow().body_used {
+        let completion_record = interpreter.throw_type_error(String::from("Body has already been consumed"));
+        let promise = reject_a_promise(interpreter, &promise_capability, completion_record_value(&completion_record));
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(promise)));
+    }
+    response.borrow_mut().body_used = true;
+
+    // The text() method steps are to return the result of running consume body with this and UTF-8 decode.
+    // The whole body has already been loaded, so the promise is resolved straight away
+    let text = String::from_utf8_lossy(&response.borrow().response.body).into_owned();
+    let promise = resolve_a_promise(interpreter, &promise_capability, Rc::new(RefCell::new(JSValue::String(text))));
+    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(promise)));
+}
@@ -0,0 +1,305 @@
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::shared_document::SharedDocument;
+
+// The C interface of the parser, for embedding it in programs that are not written in Rust
+// A document is parsed from a buffer into an opaque handle, and its nodes are named by their index in tree order, the document itself is node 0
+// Every function that can fail returns a WebEngineStatus and writes its result through an out pointer, which is only written on success
+// Strings and node lists the library returns are owned by the caller, who frees them with web_engine_string_free and web_engine_node_list_free
+// The document is a SharedDocument, so a handle can be used from any thread once it is parsed, as long as it is not freed while in use
+// The declarations for C are in include/web_engine.h, the types are plain repr(C) enums and opaque pointers so cbindgen can also generate them
+
+// The result of a call, the values are part of the interface and never change meaning
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebEngineStatus {
+    Ok = 0,
+    // A pointer that has to point to something was null
+    NullArgument = 1,
+    // A string argument is not UTF-8
+    InvalidUtf8 = 2,
+    InvalidSelector = 3,
+    // The node index is past the last node of the document
+    NoSuchNode = 4,
+    // The node is not an element, or the element does not have the attribute
+    NotFound = 5,
+    // The library panicked, which is a bug in it, the document is still safe to use and free
+    InternalError = 6,
+}
+
+// A parsed document
+pub struct WebEngineDocument {
+    document: SharedDocument,
+}
+
+// The indices of the nodes a query found, in tree order
+pub struct WebEngineNodeList {
+    nodes: Vec<usize>,
+}
+
+// Runs the body of a function, a panic is returned as an error rather than unwinding into C, which is undefined behavior
+// A stack overflow is not a panic and aborts the whole program, so nothing a document is parsed, copied or queried with may recurse as deep as its tree is
+fn guard(body: impl FnOnce() -> WebEngineStatus) -> WebEngineStatus {
+    return panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or(WebEngineStatus::InternalError);
+}
+
+// A string the caller passed in
+unsafe fn argument_str<'a>(string: *const c_char) -> Result<&'a str, WebEngineStatus> {
+    if string.is_null() {
+        return Err(WebEngineStatus::NullArgument);
+    }
+    return CStr::from_ptr(string).to_str().map_err(|_| WebEngineStatus::InvalidUtf8);
+}
+
+// A string for the caller, a NUL in the text would end the string early in C, so it is replaced like the parser replaces one in the input
+fn to_c_string(text: &str) -> *mut c_char {
+    let text = text.replace('\0', "\u{FFFD}");
+    return CString::new(text).unwrap_or_default().into_raw();
+}
+
+/// Parses length bytes of HTML, the encoding is sniffed from the bytes as for a response body, and scripts are not run
+/// On success *document_out is a document that is freed with web_engine_document_free
+///
+/// # Safety
+/// html points to length readable bytes, and can only be null when length is 0, document_out points to memory a document pointer can be written to
+#[no_mangle]
+pub unsafe extern "C" fn web_engine_parse(html: *const u8, length: usize, document_out: *mut *mut WebEngineDocument) -> WebEngineStatus {
+    if (html.is_null() && length > 0) || document_out.is_null() {
+        return WebEngineStatus::NullArgument;
+    }
+    let bytes = if length == 0 { Vec::new() } else { std::slice::from_raw_parts(html, length).to_vec() };
+
+    return guard(|| {
+        let document = Box::new(WebEngineDocument { document: SharedDocument::parse_bytes(bytes) });
+        *document_out = Box::into_raw(document);
+        return WebEngineStatus::Ok;
+    });
+}
+
+/// Frees a document, a null document is ignored
+///
+/// # Safety
+/// document is null or a document from web_engine_parse that has not been freed, and is not used again afterwards
+#[no_mangle]
+pub unsafe extern "C" fn web_engine_document_free(document: *mut WebEngineDocument) {
+    if !document.is_null() {
+        drop(Box::from_raw(document));
+    }
+}
+
+/// The number of nodes in the document, including the document itself, 0 for a null document
+///
+/// # Safety
+/// document is null or a document from web_engine_parse that has not been freed
+#[no_mangle]
+pub unsafe extern "C" fn web_engine_document_node_count(document: *const WebEngineDocument) -> usize {
+    return match document.as_ref() {
+        Some(document) => document.document.len(),
+        None => 0
+    };
+}
+
+/// The number of parse errors found in the document, 0 for a null document
+///
+/// # Safety
+/// document is null or a document from web_engine_parse that has not been freed
+#[no_mangle]
+pub unsafe extern "C" fn web_engine_document_parse_error_count(document: *const WebEngineDocument) -> usize {
+    return match document.as_ref() {
+        Some(document) => document.document.parse_errors.len(),
+        None => 0
+    };
+}
+
+/// https://dom.spec.whatwg.org/#dom-parentnode-queryselectorall
+/// On success *list_out is the elements that match the selectors, which can be none, and is freed with web_engine_node_list_free
+///
+/// # Safety
+/// document is null or a document that has not been freed, selectors is null or a NUL terminated string, and list_out points to memory a list pointer can be written to
+#[no_mangle]
+pub unsafe extern "C" fn web_engine_query_selector_all(document: *const WebEngineDocument, selectors: *const c_char, list_out: *mut *mut WebEngineNodeList) -> WebEngineStatus {
+    let document = match document.as_ref() {
+        Some(document) => &document.document,
+        None => return WebEngineStatus::NullArgument
+    };
+    if list_out.is_null() {
+        return WebEngineStatus::NullArgument;
+    }
+    let selectors = match argument_str(selectors) {
+        Ok(selectors) => selectors,
+        Err(status) => return status
+    };
+
+    return guard(|| {
+        let nodes = match document.query_selector_all(selectors) {
+            Ok(nodes) => nodes,
+            Err(_) => return WebEngineStatus::InvalidSelector
+        };
+        let list = Box::new(WebEngineNodeList { nodes: nodes.iter().map(|node| node.id().index()).collect() });
+        *list_out = Box::into_raw(list);
+        return WebEngineStatus::Ok;
+    });
+}
+
+/// The number of nodes in a list, 0 for a null list
+///
+/// # Safety
+/// list is null or a list from web_engine_query_selector_all that has not been freed
+#[no_mangle]
+pub unsafe extern "C" fn web_engine_node_list_length(list: *const WebEngineNodeList) -> usize {
+    return match list.as_ref() {
+        Some(list) => list.nodes.len(),
+        None => 0
+    };
+}
+
+/// On success *node_out is the index of the node at position index of the list
+///
+/// # Safety
+/// list is null or a list that has not been freed, and node_out points to memory an index can be written to
+#[no_mangle]
+pub unsafe extern "C" fn web_engine_node_list_get(list: *const WebEngineNodeList, index: usize, node_out: *mut usize) -> WebEngineStatus {
+    let list = match list.as_ref() {
+        Some(list) => list,
+        None => return WebEngineStatus::NullArgument
+    };
+    if node_out.is_null() {
+        return WebEngineStatus::NullArgument;
+    }
+    return match list.nodes.get(index) {
+        Some(node) => {
+            *node_out = *node;
+            WebEngineStatus::Ok
+        },
+        None => WebEngineStatus::NoSuchNode
+    };
+}
+
+/// Frees a node list, a null list is ignored
+///
+/// # Safety
+/// list is null or a list from web_engine_query_selector_all that has not been freed, and is not used again afterwards
+#[no_mangle]
+pub unsafe extern "C" fn web_engine_node_list_free(list: *mut WebEngineNodeList) {
+    if !list.is_null() {
+        drop(Box::from_raw(list));
+    }
+}
+
+/// https://dom.spec.whatwg.org/#dom-node-textcontent
+/// On success *text_out is the text of the node, an empty string for the document and doctype, which is freed with web_engine_string_free
+///
+/// # Safety
+/// document is null or a document that has not been freed, and text_out points to memory a string pointer can be written to
+#[no_mangle]
+pub unsafe extern "C" fn web_engine_node_text_content(document: *const WebEngineDocument, node: usize, text_out: *mut *mut c_char) -> WebEngineStatus {
+    let document = match document.as_ref() {
+        Some(document) => &document.document,
+        None => return WebEngineStatus::NullArgument
+    };
+    if text_out.is_null() {
+        return WebEngineStatus::NullArgument;
+    }
+
+    return guard(|| {
+        let node = match document.node_at(node) {
+            Some(node) => node,
+            None => return WebEngineStatus::NoSuchNode
+        };
+        *text_out = to_c_string(&node.text_content().unwrap_or_default());
+        return WebEngineStatus::Ok;
+    });
+}
+
+/// https://dom.spec.whatwg.org/#dom-element-getattribute
+/// On success *value_out is the value of the attribute, which is freed with web_engine_string_free
+///
+/// # Safety
+/// document is null or a document that has not been freed, name is null or a NUL terminated string, and value_out points to memory a string pointer can be written to
+#[no_mangle]
+pub unsafe extern "C" fn web_engine_node_get_attribute(document: *const WebEngineDocument, node: usize, name: *const c_char, value_out: *mut *mut c_char) -> WebEngineStatus {
+    let document = match document.as_ref() {
+        Some(document) => &document.document,
+        None => return WebEngineStatus::NullArgument
+    };
+    if value_out.is_null() {
+        return WebEngineStatus::NullArgument;
+    }
+    let name = match argument_str(name) {
+        Ok(name) => name,
+        Err(status) => return status
+    };
+
+    return guard(|| {
+        let node = match document.node_at(node) {
+            Some(node) => node,
+            None => return WebEngineStatus::NoSuchNode
+        };
+        return match node.get_attribute(name) {
+            Some(value) => {
+                *value_out = to_c_string(value);
+                WebEngineStatus::Ok
+            },
+            None => WebEngineStatus::NotFound
+        };
+    });
+}
+
+/// https://dom.spec.whatwg.org/#dom-element-localname
+/// On success *name_out is the local name of the element, which is freed with web_engine_string_free
+///
+/// # Safety
+/// document is null or a document that has not been freed, and name_out points to memory a string pointer can be written to
+#[no_mangle]
+pub unsafe extern "C" fn web_engine_node_local_name(document: *const WebEngineDocument, node: usize, name_out: *mut *mut c_char) -> WebEngineStatus {
+    let document = match document.as_ref() {
+        Some(document) => &document.document,
+        None => return WebEngineStatus::NullArgument
+    };
+    if name_out.is_null() {
+        return WebEngineStatus::NullArgument;
+    }
+
+    return guard(|| {
+        let node = match document.node_at(node) {
+            Some(node) => node,
+            None => return WebEngineStatus::NoSuchNode
+        };
+        return match node.local_name() {
+            Some(local_name) => {
+                *name_out = to_c_string(local_name);
+                WebEngineStatus::Ok
+            },
+            None => WebEngineStatus::NotFound
+        };
+    });
+}
+
+/// Frees a string the library returned, a null string is ignored
+///
+/// # Safety
+/// string is null or a string the library returned that has not been freed, and is not used again afterwards
+#[no_mangle]
+pub unsafe extern "C" fn web_engine_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// A description of a status, the string is static and is not freed
+/// The status is taken as an int, as C can pass any value for an enum and one that is not a status would be undefined behavior in Rust
+#[no_mangle]
+pub extern "C" fn web_engine_status_message(status: c_int) -> *const c_char {
+    let message: &'static CStr = match status {
+        0 => c"ok",
+        1 => c"a required pointer argument was null",
+        2 => c"a string argument is not valid UTF-8",
+        3 => c"the selectors are not valid",
+        4 => c"there is no node with that index",
+        5 => c"the node does not have what was asked for",
+        6 => c"the library failed unexpectedly",
+        _ => c"unknown status"
+    };
+    return message.as_ptr();
+}
@@ -0,0 +1,401 @@
+use std::rc::Rc;
+use crate::node::{Attr, NodeData, RefNode};
+use crate::serializer::{escape_text, is_raw_text_element, is_void_element, serialize_attribute, serialize_children};
+
+// Serializes a document again as HTML laid out to be read: block elements on lines of their own indented by how deeply they are nested, and text wrapped to a line width
+// Only whitespace that does not change how the document renders is changed, runs of whitespace in text become a single space or a line break,
+// and the contents of pre, textarea, script and style elements are kept exactly as they are
+// The source locations of the nodes are used to keep the attributes in the order they were written in,
+// and to leave out the tags the parser implied (e.g. the html, head and body of a page that has none), so a fragment is formatted as a fragment
+// TODO: Whitespace that CSS makes significant (e.g. white-space: pre on an element that is not a pre) is collapsed too
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeOrder {
+    // The order the attributes are in in the input, attributes a script added come last
+    Source,
+    Alphabetical,
+}
+
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+    // What each level of nesting is indented by, e.g. two spaces or a tab
+    pub indent: String,
+    // Lines are broken at whitespace to be at most this many characters long where they can be, 0 for no wrapping
+    // A start tag that is too long has each of its attributes on a line of its own
+    pub line_width: usize,
+    pub attribute_order: AttributeOrder,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        return FormatOptions { indent: "  ".to_string(), line_width: 80, attribute_order: AttributeOrder::Source };
+    }
+}
+
+// The inline content of a block is broken into words, which are only ever separated where there was whitespace
+enum Piece {
+    // Text and tags with no whitespace between them
+    Word(String),
+    // Whitespace, a blank line in it is kept
+    Space { blank_line: bool },
+    // The line ends after a br element
+    LineBreak,
+    // A block element inside inline content, which is formatted on lines of its own
+    Block(RefNode),
+}
+
+struct Formatter<'a> {
+    options: &'a FormatOptions,
+    lines: Vec<String>,
+    // The line a plaintext element starts on, the input ends with it as there is no end tag for it
+    plaintext_line: Option<usize>,
+}
+
+pub fn format_document(document: &RefNode, options: &FormatOptions) -> String {
+    let mut formatter = Formatter { options, lines: Vec::new(), plaintext_line: None };
+    formatter.format_children(document, 0);
+    if let Some(plaintext_line) = formatter.plaintext_line {
+        formatter.lines.truncate(plaintext_line + 1);
+    }
+
+    // The text of a plaintext element runs to the end of the input, so nothing is added after it
+    let mut html = formatter.lines.join("\n");
+    if !html.is_empty() && formatter.plaintext_line.is_none() {
+        html.push('\n');
+    }
+    return html;
+}
+
+impl Formatter<'_> {
+    // The children of node, a line or more for each of them when they are all blocks, otherwise their inline content wrapped
+    // A blank line between two blocks in the input is kept, more than one becomes one
+    fn format_children(&mut self, node: &RefNode, depth: usize) {
+        let children: Vec<RefNode> = node.borrow().childNodes.to_vec();
+        if has_inline_content(&children) {
+            let mut pieces = Vec::new();
+            for child in &children {
+                self.inline_pieces(child, &mut pieces);
+            }
+            self.fill(pieces, depth);
+            return;
+        }
+
+        let mut blank_line = false;
+        let mut first_child = true;
+        for child in &children {
+            if let NodeData::Text(text) = &child.borrow().data {
+                blank_line = !first_child && text.character_data.data.matches('\n').count() >= 2;
+                continue;
+            }
+            if blank_line && self.lines.last().is_some_and(|line| !line.is_empty()) {
+                self.lines.push(String::new());
+            }
+            blank_line = false;
+            first_child = false;
+            self.format_block(child, depth);
+        }
+    }
+
+    fn format_block(&mut self, node: &RefNode, depth: usize) {
+        let indentation = self.options.indent.repeat(depth);
+        let (local_name, attributes) = match &node.borrow().data {
+            NodeData::Element(element) => (element.local_name().to_string(), self.serialized_attributes(element.attribute_list())),
+            NodeData::Comment(comment) => {
+                self.lines.push(format!("{}<!--{}-->", indentation, comment.character_data.data));
+                return;
+            },
+            NodeData::DocumentType(doctype) => {
+                self.lines.push(format!("{}<!DOCTYPE {}>", indentation, doctype.name));
+                return;
+            },
+            NodeData::Document(_) => {
+                self.format_children(node, depth);
+                return;
+            },
+            // Text is only formatted as a block when it is whitespace between blocks, which is left out
+            NodeData::Text(_) | NodeData::CharacterData(_) => return
+        };
+
+        if is_implied(node) {
+            self.format_children(node, depth);
+            return;
+        }
+
+        let mut start_tag_lines = self.start_tag_lines(&local_name, &attributes, depth);
+        let end_tag = format!("</{}>", local_name);
+        if is_void_element(&local_name) {
+            self.lines.append(&mut start_tag_lines);
+            return;
+        }
+
+        // The contents of an element whose whitespace is significant are serialized as they are, right after the start tag
+        if is_preformatted(&local_name) {
+            if let Some(last_line) = start_tag_lines.last_mut() {
+                last_line.push_str(&serialize_children(node));
+                if local_name == "plaintext" {
+                    self.plaintext_line = self.plaintext_line.or(Some(self.lines.len() + start_tag_lines.len() - 1));
+                } else {
+                    last_line.push_str(&end_tag);
+                }
+            }
+            self.lines.append(&mut start_tag_lines);
+            return;
+        }
+
+        let children: Vec<RefNode> = node.borrow().childNodes.to_vec();
+        let empty = children.iter().all(|child| matches!(&child.borrow().data, NodeData::Text(text) if text.character_data.data.trim_matches(is_ascii_whitespace).is_empty()));
+        if empty {
+            if let Some(last_line) = start_tag_lines.last_mut() {
+                last_line.push_str(&end_tag);
+            }
+            self.lines.append(&mut start_tag_lines);
+            return;
+        }
+
+        // An element with a line of text that fits is kept on one line, e.g. <p>Hello</p>
+        if start_tag_lines.len() == 1 && has_inline_content(&children) {
+            let mut pieces = Vec::new();
+            for child in &children {
+                self.inline_pieces(child, &mut pieces);
+            }
+            if let Some(words) = single_line(&pieces) {
+                let line = format!("{}{}{}", start_tag_lines[0], words, end_tag);
+                if self.options.line_width == 0 || line.chars().count() <= self.options.line_width {
+                    self.lines.push(line);
+                    return;
+                }
+            }
+        }
+
+        self.lines.append(&mut start_tag_lines);
+        self.format_children(node, depth + 1);
+        self.lines.push(format!("{}{}", indentation, end_tag));
+    }
+
+    // The start tag on one line, or on a line for the tag name and a line for each attribute when it is too long for one
+    fn start_tag_lines(&self, local_name: &str, attributes: &[String], depth: usize) -> Vec<String> {
+        let indentation = self.options.indent.repeat(depth);
+        let line = format!("{}{}", indentation, start_tag(local_name, attributes));
+        if self.options.line_width == 0 || line.chars().count() <= self.options.line_width || attributes.len() < 2 {
+            return vec![line];
+        }
+
+        let mut lines = vec![format!("{}<{}", indentation, local_name)];
+        for attribute in attributes {
+            lines.push(format!("{}{}{}", indentation, self.options.indent, attribute));
+        }
+        if let Some(last_line) = lines.last_mut() {
+            last_line.push('>');
+        }
+        return lines;
+    }
+
+    // The attributes serialized as name="value" in the order they are formatted in
+    fn serialized_attributes(&self, attributes: &[Attr]) -> Vec<String> {
+        let mut ordered_attributes: Vec<&Attr> = attributes.iter().collect();
+        match self.options.attribute_order {
+            AttributeOrder::Source => ordered_attributes.sort_by_key(|attribute| attribute.source_location().map(|source_location| source_location.start.offset).unwrap_or(usize::MAX)),
+            AttributeOrder::Alphabetical => ordered_attributes.sort_by(|a, b| a.local_name.cmp(&b.local_name))
+        }
+        return ordered_attributes.into_iter().map(serialize_attribute).collect();
+    }
+
+    // Breaks node into the words of the inline content it is part of
+    fn inline_pieces(&self, node: &RefNode, pieces: &mut Vec<Piece>) {
+        let (local_name, attributes) = match &node.borrow().data {
+            NodeData::Text(text) => {
+                let escaped_text = escape_text(&text.character_data.data);
+                let mut rest = escaped_text.as_str();
+                while !rest.is_empty() {
+                    let word_end = rest.find(is_ascii_whitespace).unwrap_or(rest.len());
+                    push_word(pieces, &rest[..word_end]);
+                    rest = &rest[word_end..];
+
+                    let whitespace_end = rest.find(|character| !is_ascii_whitespace(character)).unwrap_or(rest.len());
+                    if whitespace_end > 0 {
+                        push_space(pieces, rest[..whitespace_end].matches('\n').count() >= 2);
+                    }
+                    rest = &rest[whitespace_end..];
+                }
+                return;
+            },
+            NodeData::Comment(comment) => {
+                push_word(pieces, &format!("<!--{}-->", comment.character_data.data));
+                return;
+            },
+            NodeData::Element(element) => (element.local_name().to_string(), self.serialized_attributes(element.attribute_list())),
+            _ => return
+        };
+
+        if is_implied(node) {
+            for child in node.borrow().childNodes.iter() {
+                self.inline_pieces(child, pieces);
+            }
+            return;
+        }
+        if !is_phrasing_element(&local_name) && !is_hidden_element(&local_name) {
+            pieces.push(Piece::Block(Rc::clone(node)));
+            return;
+        }
+
+        push_word(pieces, &start_tag(&local_name, &attributes));
+        if is_void_element(&local_name) {
+            if local_name == "br" {
+                pieces.push(Piece::LineBreak);
+            }
+            return;
+        }
+        if is_preformatted(&local_name) {
+            push_word(pieces, &serialize_children(node));
+        } else {
+            for child in node.borrow().childNodes.iter() {
+                self.inline_pieces(child, pieces);
+            }
+        }
+        push_word(pieces, &format!("</{}>", local_name));
+    }
+
+    // Writes the words on as many lines as it takes for them to fit in the line width
+    fn fill(&mut self, pieces: Vec<Piece>, depth: usize) {
+        let indentation = self.options.indent.repeat(depth);
+        let first_line = self.lines.len();
+        let mut line = String::new();
+        let mut space = false;
+        let mut blank_line = false;
+        for piece in pieces {
+            // A blank line is only kept between lines of this content, not before or after them
+            if blank_line && matches!(piece, Piece::Word(_) | Piece::Block(_)) {
+                if !line.is_empty() {
+                    self.lines.push(format!("{}{}", indentation, line));
+                    line.clear();
+                }
+                if self.lines.len() > first_line {
+                    self.lines.push(String::new());
+                }
+                blank_line = false;
+                space = false;
+            }
+
+            match piece {
+                Piece::Word(word) => {
+                    let too_long = self.options.line_width > 0 && indentation.chars().count() + line.chars().count() + 1 + word.chars().count() > self.options.line_width;
+                    if !line.is_empty() && space && too_long {
+                        self.lines.push(format!("{}{}", indentation, line));
+                        line.clear();
+                    } else if !line.is_empty() && space {
+                        line.push(' ');
+                    }
+                    line.push_str(&word);
+                    space = false;
+                },
+                // Whitespace at the start of a line is left out
+                Piece::Space { blank_line: blank } => {
+                    space = !line.is_empty();
+                    blank_line = blank && (!line.is_empty() || self.lines.len() > first_line);
+                },
+                Piece::LineBreak | Piece::Block(_) => {
+                    if !line.is_empty() {
+                        self.lines.push(format!("{}{}", indentation, line));
+                        line.clear();
+                    }
+                    space = false;
+                    if let Piece::Block(node) = piece {
+                        self.format_block(&node, depth);
+                    }
+                }
+            }
+        }
+        if !line.is_empty() {
+            self.lines.push(format!("{}{}", indentation, line));
+        }
+    }
+}
+
+fn start_tag(local_name: &str, attributes: &[String]) -> String {
+    let attributes: String = attributes.iter().map(|attribute| format!(" {}", attribute)).collect();
+    return format!("<{}{}>", local_name, attributes);
+}
+
+// Whitespace next to whitespace is one run of it
+fn push_space(pieces: &mut Vec<Piece>, blank_line: bool) {
+    match pieces.last_mut() {
+        Some(Piece::Space { blank_line: last_blank_line }) => *last_blank_line |= blank_line,
+        _ => pieces.push(Piece::Space { blank_line })
+    }
+}
+
+// Appends word to the word before it when there is no whitespace between them, e.g. a start tag and the text after it
+fn push_word(pieces: &mut Vec<Piece>, word: &str) {
+    if word.is_empty() {
+        return;
+    }
+    match pieces.last_mut() {
+        Some(Piece::Word(last_word)) => last_word.push_str(word),
+        _ => pieces.push(Piece::Word(word.to_string()))
+    }
+}
+
+// The words on one line, None when the pieces have to be on lines of their own
+fn single_line(pieces: &[Piece]) -> Option<String> {
+    let mut line = String::new();
+    let mut space = false;
+    for piece in pieces {
+        match piece {
+            Piece::Word(word) => {
+                if space && !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(word);
+                space = false;
+            },
+            Piece::Space { .. } => space = true,
+            Piece::LineBreak | Piece::Block(_) => return None
+        }
+    }
+    return Some(line);
+}
+
+// Content is inline when there is text or a phrasing element in it, the whitespace between its children then matters
+fn has_inline_content(children: &[RefNode]) -> bool {
+    return children.iter().any(|child| {
+        match &child.borrow().data {
+            NodeData::Text(text) => !text.character_data.data.trim_matches(is_ascii_whitespace).is_empty(),
+            NodeData::Element(element) if is_implied(child) => has_inline_content(&child.borrow().childNodes),
+            NodeData::Element(element) => is_phrasing_element(element.local_name()),
+            _ => false
+        }
+    });
+}
+
+// An html, head or body element the parser created without a tag for it in the input
+fn is_implied(node: &RefNode) -> bool {
+    let node = node.borrow();
+    return match &node.data {
+        NodeData::Element(element) => node.source_location().is_none() && element.attribute_list().is_empty() && matches!(element.local_name().as_str(), "html" | "head" | "body"),
+        _ => false
+    };
+}
+
+// https://html.spec.whatwg.org/multipage/dom.html#phrasing-content
+// The elements that are laid out in the line of the text around them
+fn is_phrasing_element(local_name: &str) -> bool {
+    return matches!(local_name,
+        "a" | "abbr" | "area" | "audio" | "b" | "bdi" | "bdo" | "br" | "button" | "canvas" | "cite" | "code" | "data" | "del" | "dfn" | "em" | "embed" | "i" | "iframe" | "img" | "input" | "ins" | "kbd" | "label"
+        | "map" | "mark" | "math" | "meter" | "object" | "output" | "picture" | "progress" | "q" | "s" | "samp" | "select" | "slot" | "small" | "span" | "strong" | "sub" | "sup" | "svg" | "textarea" | "time"
+        | "u" | "var" | "video" | "wbr" | "big" | "font" | "nobr" | "strike" | "tt");
+}
+
+// Elements that are not rendered, so the whitespace around them in inline content is kept as it is rather than putting them on lines of their own
+fn is_hidden_element(local_name: &str) -> bool {
+    return matches!(local_name, "script" | "style" | "template" | "link" | "meta" | "base");
+}
+
+// The elements whose contents are kept as they are
+fn is_preformatted(local_name: &str) -> bool {
+    return is_raw_text_element(local_name) || matches!(local_name, "pre" | "textarea" | "listing");
+}
+
+// https://infra.spec.whatwg.org/#ascii-whitespace
+fn is_ascii_whitespace(character: char) -> bool {
+    return matches!(character, '\t' | '\n' | '\u{000C}' | '\r' | ' ');
+}
@@ -0,0 +1,614 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::interpreter::JSObject;
+use crate::node::{descendants, document_base_url, node_document, set_attribute_value, DOMString, NodeData, RefNode};
+use crate::unicode::{ascii_lowercase, is_ascii_case_insensitive_match};
+use crate::url::{form_urlencoded_serialize, Url};
+use crate::webidl::{attribute_getter, attribute_setter, define_attributes, define_operations, operation, ElementNode, Exception, OrUndefined};
+
+// https://html.spec.whatwg.org/multipage/forms.html
+// The state of form controls (the value of inputs and textareas, the checkedness of checkboxes and radio buttons and the selectedness of options),
+// which starts out as what the content attributes say and is kept apart from them once it is changed, and submitting forms
+// TODO: There is no navigation, so a submitted form is only kept on its document as a FormSubmission for the embedder to act on
+// TODO: Only application/x-www-form-urlencoded is supported, forms with another enctype are submitted as if it were that, and there are no files to submit
+
+// https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#concept-fe-dirty
+// The state of a form control that was changed by a script or the user, None while it still follows the content attributes
+#[derive(Debug, Clone, Default)]
+pub struct FormControlState {
+    // The value of an input or textarea once its dirty value flag is set
+    pub(crate) value: Option<DOMString>,
+    // The checkedness of a checkbox or radio button once its dirty checkedness flag is set
+    pub(crate) checkedness: Option<bool>,
+    // The selectedness of an option once its dirtiness is set
+    pub(crate) selectedness: Option<bool>,
+}
+
+// https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#form-submission-2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormMethod {
+    Get,
+    Post,
+}
+
+// Where a form would navigate to when it is submitted, with the entity body of a POST
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormSubmission {
+    pub method: FormMethod,
+    pub url: Url,
+    pub body: Option<String>,
+}
+
+fn local_name(node: &RefNode) -> String {
+    match &node.borrow().data {
+        NodeData::Element(element) => element.local_name().to_string(),
+        _ => String::new()
+    }
+}
+
+fn attribute(node: &RefNode, name: &str) -> Option<DOMString> {
+    match &node.borrow().data {
+        NodeData::Element(element) => element.get_attribute(name).cloned(),
+        _ => None
+    }
+}
+
+fn form_control_state(node: &RefNode) -> FormControlState {
+    match &node.borrow().data {
+        NodeData::Element(element) => element.form_control_state.clone(),
+        _ => FormControlState::default()
+    }
+}
+
+fn update_form_control_state(node: &RefNode, update: impl FnOnce(&mut FormControlState)) {
+    if let NodeData::Element(element) = &mut node.borrow_mut().data {
+        update(&mut element.form_control_state);
+    }
+}
+
+// https://dom.spec.whatwg.org/#concept-tree-root
+fn root(node: &RefNode) -> RefNode {
+    let mut root = Rc::clone(node);
+    loop {
+        let parent = root.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+        match parent {
+            Some(parent) => root = parent,
+            None => return root
+        }
+    }
+}
+
+fn ancestors(node: &RefNode) -> Vec<RefNode> {
+    let mut ancestors = Vec::new();
+    let mut parent = node.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+    while let Some(node) = parent {
+        parent = node.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+        ancestors.push(node);
+    }
+    return ancestors;
+}
+
+// https://html.spec.whatwg.org/multipage/input.html#attr-input-type
+// The state of the type attribute of an input element, the Text state when it is missing or not a known keyword
+pub fn input_type(element: &RefNode) -> String {
+    let input_type = ascii_lowercase(&attribute(element, "type").unwrap_or_default()).into_owned();
+    match input_type.as_str() {
+        "hidden" | "search" | "tel" | "url" | "email" | "password" | "date" | "month" | "week" | "time" | "datetime-local" | "number" | "range" | "color"
+            | "checkbox" | "radio" | "file" | "submit" | "image" | "reset" | "button" => return input_type,
+        _ => return String::from("text")
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/input.html#dom-input-value
+// The mode the value IDL attribute of an input element of the given type is in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueMode {
+    Value,
+    Default,
+    DefaultOn,
+    Filename,
+}
+
+fn value_mode(input_type: &str) -> ValueMode {
+    match input_type {
+        "hidden" | "submit" | "image" | "reset" | "button" => return ValueMode::Default,
+        "checkbox" | "radio" => return ValueMode::DefaultOn,
+        "file" => return ValueMode::Filename,
+        _ => return ValueMode::Value
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/input.html#value-sanitization-algorithm
+// TODO: Only the text-like types are sanitized, e.g. the value of a number input is not checked to be a number
+fn sanitize_value(input_type: &str, value: &str) -> DOMString {
+    let strip_newlines = |value: &str| value.chars().filter(|character| *character != '\r' && *character != '\n').collect::<String>();
+    match input_type {
+        // Strip newlines from the value.
+        "text" | "search" | "tel" | "password" => return strip_newlines(value),
+        // Strip newlines from the value, then strip leading and trailing ASCII whitespace from the value.
+        "url" | "email" => return strip_newlines(value).trim_matches(|character: char| character.is_ascii_whitespace()).to_string(),
+        _ => return value.to_string()
+    }
+}
+
+// https://infra.spec.whatwg.org/#normalize-newlines
+fn normalize_newlines(value: &str) -> String {
+    return value.replace("\r\n", "\n").replace('\r', "\n");
+}
+
+// https://infra.spec.whatwg.org/#strip-and-collapse-ascii-whitespace
+fn strip_and_collapse_ascii_whitespace(value: &str) -> String {
+    return value.split_ascii_whitespace().collect::<Vec<&str>>().join(" ");
+}
+
+// The text of the Text descendants of node that are not in a script element
+fn descendant_text(node: &RefNode) -> String {
+    let mut text = String::new();
+    for child in &node.borrow().childNodes {
+        match &child.borrow().data {
+            NodeData::Text(text_node) => text.push_str(&text_node.character_data.data),
+            NodeData::Element(element) if element.local_name() == "script" => (),
+            NodeData::Element(_) => text.push_str(&descendant_text(child)),
+            _ => ()
+        }
+    }
+    return text;
+}
+
+// https://html.spec.whatwg.org/multipage/forms.html#dom-textarea-value
+// The value of an input, textarea, select, option or button element, the empty string for other elements
+pub fn value(element: &RefNode) -> DOMString {
+    match local_name(element).as_str() {
+        "input" => {
+            let input_type = input_type(element);
+            match value_mode(&input_type) {
+                // On getting, return the current value of the element.
+                ValueMode::Value => return form_control_state(element).value.unwrap_or_else(|| sanitize_value(&input_type, &attribute(element, "value").unwrap_or_default())),
+                // On getting, if the element has a value content attribute, return that attribute's value; otherwise, return the empty string.
+                ValueMode::Default => return attribute(element, "value").unwrap_or_default(),
+                // On getting, if the element has a value content attribute, return that attribute's value; otherwise, return the string "on".
+                ValueMode::DefaultOn => return attribute(element, "value").unwrap_or_else(|| String::from("on")),
+                // On getting, return the string "C:\fakepath\" followed by the name of the first file in the list of selected files, if any, or the empty string if the list is empty.
+                ValueMode::Filename => return String::new()
+            }
+        },
+        // The value IDL attribute must, on getting, return the element's API value.
+        // The API value is the raw value with newlines normalized, and the raw value starts out as the child text content
+        "textarea" => return normalize_newlines(&form_control_state(element).value.unwrap_or_else(|| descendant_text(element))),
+        // The value IDL attribute, on getting, must return the value of the first option element in the list of options in tree order that has its selectedness set to true, if any.
+        // If there isn't one, then it must return the empty string.
+        "select" => return selected_options(element).first().map(value).unwrap_or_default(),
+        // The value of an option element is the value of the value content attribute, if there is one, or, if there is not, the value of the element's text IDL attribute.
+        "option" => return attribute(element, "value").unwrap_or_else(|| strip_and_collapse_ascii_whitespace(&descendant_text(element))),
+        "button" => return attribute(element, "value").unwrap_or_default(),
+        _ => return String::new()
+    }
+}
+
+// Sets the value of an input, textarea, select, option or button element, false when it can not be set (a file input can only be set to the empty string)
+pub fn set_value(element: &RefNode, value: DOMString) -> bool {
+    match local_name(element).as_str() {
+        "input" => {
+            let input_type = input_type(element);
+            match value_mode(&input_type) {
+                // On setting, set the value of the element to the new value, set the element's dirty value flag to true, invoke the value sanitization algorithm.
+                ValueMode::Value => update_form_control_state(element, |state| state.value = Some(sanitize_value(&input_type, &value))),
+                // On setting, set the value of the element's value content attribute to the new value.
+                ValueMode::Default | ValueMode::DefaultOn => set_attribute_value(element, "value", value),
+                // On setting, if the new value is the empty string, empty the list of selected files; otherwise, throw an "InvalidStateError" DOMException.
+                ValueMode::Filename => return value.is_empty()
+            }
+        },
+        // On setting, the raw value is set to the given value and the dirty value flag to true
+        "textarea" => update_form_control_state(element, |state| state.value = Some(value)),
+        // On setting, the value attribute must set the selectedness of all the option elements in the list of options to false,
+        // and then the first option element in the list of options, in tree order, whose value is equal to the given new value, if any, must have its selectedness set to true and its dirtiness set to true.
+        "select" => {
+            let mut found = false;
+            for option in list_of_options(element) {
+                let selected = !found && self::value(&option) == value;
+                found |= selected;
+                update_form_control_state(&option, |state| state.selectedness = Some(selected));
+            }
+        },
+        "option" | "button" => set_attribute_value(element, "value", value),
+        _ => ()
+    }
+    return true;
+}
+
+// https://html.spec.whatwg.org/multipage/input.html#concept-input-checked-dirty
+// The checkedness of an input element, which follows its checked content attribute until it is changed
+// TODO: Two radio buttons in a group can both be checked when both have a checked content attribute and neither was changed
+pub fn checked(element: &RefNode) -> bool {
+    return form_control_state(element).checkedness.unwrap_or_else(|| attribute(element, "checked").is_some());
+}
+
+// https://html.spec.whatwg.org/multipage/input.html#dom-input-checked
+// On setting, it must set the element's checkedness to the new value and set the element's dirty checkedness flag to true
+pub fn set_checked(element: &RefNode, checked: bool) {
+    update_form_control_state(element, |state| state.checkedness = Some(checked));
+
+    // https://html.spec.whatwg.org/multipage/input.html#radio-button-state-(type=radio)
+    // When any of the following phenomena occur, if the element's checkedness state is true after the occurrence, the checkedness state of all the other elements in the same radio button group must be set to false
+    if checked && local_name(element) == "input" && input_type(element) == "radio" {
+        for other in radio_button_group(element) {
+            if !Rc::ptr_eq(&other, element) {
+                update_form_control_state(&other, |state| state.checkedness = Some(false));
+            }
+        }
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/input.html#radio-button-group
+// The radio buttons in the same tree with the same form owner and the same name as element, element included
+fn radio_button_group(element: &RefNode) -> Vec<RefNode> {
+    // The input elements a and b are both in the same radio button group if all of the following conditions are true:
+    // They both have a name attribute, their name attributes are not empty, and the value of a's name attribute equals the value of b's name attribute.
+    let name = match attribute(element, "name").filter(|name| !name.is_empty()) {
+        Some(name) => name,
+        None => return vec![Rc::clone(element)]
+    };
+    let owner = form_owner(element);
+    return descendants(&root(element)).into_iter().filter(|other| {
+        // Both a and b are in the same tree, the input element b's type attribute is in the Radio Button state, and either a and b have the same form owner, or they both have no form owner.
+        return local_name(other) == "input" && input_type(other) == "radio" && attribute(other, "name").as_ref() == Some(&name)
+            && match (&owner, form_owner(other)) {
+                (Some(owner), Some(other_owner)) => Rc::ptr_eq(owner, &other_owner),
+                (None, None) => true,
+                _ => false
+            };
+    }).collect();
+}
+
+// https://html.spec.whatwg.org/multipage/form-elements.html#concept-select-option-list
+// The option elements that are children of the select element or of an optgroup child of it, in tree order
+pub fn list_of_options(select: &RefNode) -> Vec<RefNode> {
+    let mut options = Vec::new();
+    for child in &select.borrow().childNodes {
+        match local_name(child).as_str() {
+            "option" => options.push(Rc::clone(child)),
+            "optgroup" => options.extend(child.borrow().childNodes.iter().filter(|option| local_name(option) == "option").cloned()),
+            _ => ()
+        }
+    }
+    return options;
+}
+
+// https://html.spec.whatwg.org/multipage/form-elements.html#selectedness-setting-algorithm
+// The options of a select element whose selectedness is true
+// A select element that does not allow more than one option to be selected always has one selected unless it has no options to select:
+// the last option whose selectedness is true, or the first option that is not disabled when there is none
+pub fn selected_options(select: &RefNode) -> Vec<RefNode> {
+    let options = list_of_options(select);
+    let option_selectedness = |option: &RefNode| form_control_state(option).selectedness.unwrap_or_else(|| attribute(option, "selected").is_some());
+    let selected: Vec<RefNode> = options.iter().filter(|option| option_selectedness(option)).cloned().collect();
+    if attribute(select, "multiple").is_some() {
+        return selected;
+    }
+
+    // If nodes are inserted or nodes are removed causing the list of options to gain or lose one or more option elements, or if an option element in the list of options asks for a reset,
+    // then, if the select element's multiple attribute is absent, the user agent must run the selectedness setting algorithm
+    // TODO: A select with a display size greater than 1 can have no option selected, but the size attribute is not supported
+    match selected.last() {
+        Some(last) => return vec![Rc::clone(last)],
+        None => return options.into_iter().find(|option| !is_disabled(option)).into_iter().collect()
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/form-elements.html#dom-option-selected
+pub fn selected(option: &RefNode) -> bool {
+    match option.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade()).and_then(|parent| owning_select(&parent)) {
+        Some(select) => return selected_options(&select).iter().any(|selected| Rc::ptr_eq(selected, option)),
+        None => return form_control_state(option).selectedness.unwrap_or_else(|| attribute(option, "selected").is_some())
+    }
+}
+
+// On setting, it must set the element's selectedness to the new value, set its dirtiness to true, and then cause the element to ask for a reset.
+pub fn set_selected(option: &RefNode, selected: bool) {
+    let select = option.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade()).and_then(|parent| owning_select(&parent));
+    if let Some(select) = select.filter(|select| selected && attribute(select, "multiple").is_none()) {
+        for other in list_of_options(&select) {
+            update_form_control_state(&other, |state| state.selectedness = Some(false));
+        }
+    }
+    update_form_control_state(option, |state| state.selectedness = Some(selected));
+}
+
+// The select element whose list of options an option with the given parent is in
+fn owning_select(parent: &RefNode) -> Option<RefNode> {
+    match local_name(parent).as_str() {
+        "select" => return Some(Rc::clone(parent)),
+        "optgroup" => return parent.borrow().parentNode.as_ref().and_then(|grandparent| grandparent.upgrade()).filter(|grandparent| local_name(grandparent) == "select"),
+        _ => return None
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#concept-fe-disabled
+// A form control is disabled if it has a disabled attribute or is a descendant of a disabled fieldset that is not in the first legend of that fieldset
+// https://html.spec.whatwg.org/multipage/form-elements.html#concept-option-disabled
+// An option element is disabled if its disabled attribute is present or if it is a child of an optgroup element whose disabled attribute is present
+pub fn is_disabled(element: &RefNode) -> bool {
+    if attribute(element, "disabled").is_some() {
+        return true;
+    }
+    let parent = element.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+    if local_name(element) == "option" {
+        return parent.is_some_and(|parent| local_name(&parent) == "optgroup" && attribute(&parent, "disabled").is_some());
+    }
+
+    let ancestors = ancestors(element);
+    for (index, ancestor) in ancestors.iter().enumerate() {
+        if local_name(ancestor) == "fieldset" && attribute(ancestor, "disabled").is_some() {
+            let first_legend = ancestor.borrow().childNodes.iter().find(|child| local_name(child) == "legend").cloned();
+            let in_first_legend = index > 0 && first_legend.is_some_and(|legend| Rc::ptr_eq(&legend, &ancestors[index - 1]));
+            if !in_first_legend {
+                return true;
+            }
+        }
+    }
+    return false;
+}
+
+// https://html.spec.whatwg.org/multipage/forms.html#category-listed
+fn is_listed_element(element: &RefNode) -> bool {
+    return matches!(local_name(element).as_str(), "button" | "fieldset" | "input" | "object" | "output" | "select" | "textarea");
+}
+
+// https://html.spec.whatwg.org/multipage/forms.html#category-submit
+fn is_submittable_element(element: &RefNode) -> bool {
+    return matches!(local_name(element).as_str(), "button" | "input" | "select" | "textarea");
+}
+
+// https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#form-owner
+// The form a listed element is associated with, the form its form attribute names or else its nearest form ancestor
+pub fn form_owner(element: &RefNode) -> Option<RefNode> {
+    if !is_listed_element(element) {
+        return None;
+    }
+    // If a listed form-associated element has a form attribute, then that attribute's value must be the ID of a form element in the element's tree.
+    if let Some(form_id) = attribute(element, "form") {
+        return descendants(&root(element)).into_iter().find(|candidate| attribute(candidate, "id").as_ref() == Some(&form_id)).filter(|form| local_name(form) == "form");
+    }
+    return ancestors(element).into_iter().find(|ancestor| local_name(ancestor) == "form");
+}
+
+// https://html.spec.whatwg.org/multipage/forms.html#dom-form-elements
+// The listed elements whose form owner is form, in tree order, except for image buttons
+pub fn form_elements(form: &RefNode) -> Vec<RefNode> {
+    return descendants(&root(form)).into_iter().filter(|element| {
+        let is_image_button = local_name(element) == "input" && input_type(element) == "image";
+        return !is_image_button && form_owner(element).is_some_and(|owner| Rc::ptr_eq(&owner, form));
+    }).collect();
+}
+
+// https://html.spec.whatwg.org/multipage/forms.html#concept-submit-button
+fn is_button(element: &RefNode) -> bool {
+    match local_name(element).as_str() {
+        "button" => return !matches!(attribute(element, "type").map(|button_type| ascii_lowercase(&button_type).into_owned()).as_deref(), Some("reset") | Some("button")),
+        "input" => return matches!(input_type(element).as_str(), "submit" | "image" | "reset" | "button"),
+        _ => return false
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#constructing-the-form-data-set
+// The names and values a form submits, with the button that submitted it if any
+pub fn construct_entry_list(form: &RefNode, submitter: Option<&RefNode>) -> Vec<(String, String)> {
+    // 4. Let controls be a list of all the submittable elements whose form owner is form, in tree order.
+    let controls = descendants(&root(form)).into_iter().filter(|element| is_submittable_element(element) && form_owner(element).is_some_and(|owner| Rc::ptr_eq(&owner, form)));
+
+    // 3. Let entry list be a new empty entry list.
+    let mut entry_list = Vec::new();
+    // 5. For each element field in controls, in tree order:
+    for field in controls {
+        // 5.1. If any of the following are true, then continue:
+        // The field element has a datalist element ancestor.
+        // The field element is disabled.
+        // The field element is a button but it is not submitter.
+        // The field element is an input element whose type attribute is in the Checkbox state and whose checkedness is false.
+        // The field element is an input element whose type attribute is in the Radio Button state and whose checkedness is false.
+        let is_input = local_name(&field) == "input";
+        let input_type = if is_input { input_type(&field) } else { String::new() };
+        let is_submitter = submitter.is_some_and(|submitter| Rc::ptr_eq(submitter, &field));
+        if ancestors(&field).iter().any(|ancestor| local_name(ancestor) == "datalist") || is_disabled(&field)
+            || (is_button(&field) && !is_submitter)
+            || (matches!(input_type.as_str(), "checkbox" | "radio") && !checked(&field)) {
+            continue;
+        }
+
+        // 5.2. If the field element is an input element whose type attribute is in the Image Button state, then:
+        if input_type == "image" {
+            // 5.2.2. Let name be the value of the field element's name attribute, if it has one, or the empty string otherwise.
+            // 5.2.3. If name is not the empty string, then let namex be the concatenation of name and U+002E (.) followed by a single U+0078 (x). Let namey likewise.
+            // TODO: There is no selected coordinate, as buttons are only clicked by scripts, so it is always 0,0
+            let prefix = attribute(&field, "name").filter(|name| !name.is_empty()).map(|name| format!("{}.", name)).unwrap_or_default();
+            entry_list.push((format!("{}x", prefix), String::from("0")));
+            entry_list.push((format!("{}y", prefix), String::from("0")));
+            continue;
+        }
+
+        // 5.4. If either the field element does not have a name attribute specified, or its name attribute's value is the empty string, then continue.
+        // 5.5. Let name be the value of the field element's name attribute.
+        let name = match attribute(&field, "name").filter(|name| !name.is_empty()) {
+            Some(name) => name,
+            None => continue
+        };
+
+        match local_name(&field).as_str() {
+            // 5.6. If the field element is a select element, then for each option element in the select element's list of options whose selectedness is true and that is not disabled, create an entry with name and the value of the option element, and append it to entry list.
+            "select" => {
+                for option in selected_options(&field).iter().filter(|option| !is_disabled(option)) {
+                    entry_list.push((name.to_string(), value(option)));
+                }
+            },
+            // 5.7. Otherwise, if the field element is an input element whose type attribute is in the Checkbox state or the Radio Button state, then:
+            // If the field element has a value attribute specified, then let value be the value of that attribute; otherwise, let value be the string "on".
+            "input" if matches!(input_type.as_str(), "checkbox" | "radio") => entry_list.push((name, attribute(&field, "value").unwrap_or_else(|| String::from("on")))),
+            // 5.8. Otherwise, if the field element is an input element whose type attribute is in the File Upload state, then, if there are no selected files, then create an entry with name and a new File object with an empty name.
+            // A file is serialized as its name in application/x-www-form-urlencoded
+            "input" if input_type == "file" => entry_list.push((name, String::new())),
+            // 5.10. Otherwise, if the field element is an input element whose type attribute is in the Hidden state and name is an ASCII case-insensitive match for "_charset_":
+            // Let charset be the name of encoding. Create an entry with name and charset, and append it to entry list.
+            "input" if input_type == "hidden" && is_ascii_case_insensitive_match(&name, "_charset_") => entry_list.push((name, String::from("UTF-8"))),
+            // 5.11. Otherwise, create an entry with name and the value of the field element, and append it to entry list.
+            _ => entry_list.push((name, value(&field)))
+        }
+    }
+    return entry_list;
+}
+
+// https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#converting-an-entry-list-to-a-list-of-name-value-pairs
+// The names and values with every newline as a CRLF pair
+fn normalize_entry_list(entry_list: Vec<(String, String)>) -> Vec<(String, String)> {
+    let crlf = |value: &str| normalize_newlines(value).replace('\n', "\r\n");
+    return entry_list.iter().map(|(name, value)| (crlf(name), crlf(value))).collect();
+}
+
+// https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#form-submission-algorithm
+// Where submitting form with submitter would navigate to, None when its action URL can not be parsed
+pub fn form_submission(form: &RefNode, submitter: Option<&RefNode>) -> Option<FormSubmission> {
+    // The submitter's formaction, formmethod and formenctype attributes override the form's
+    let form_attribute = |name: &str| submitter.and_then(|submitter| attribute(submitter, &format!("form{}", name))).or_else(|| attribute(form, name));
+
+    // 12. Let entry list be the result of constructing the entry list with form, submitter, and encoding.
+    let entry_list = construct_entry_list(form, submitter);
+
+    // 14. If the element's action is the empty string, let action be the URL of the form document; otherwise, let action be the result of encoding-parsing a URL given the element's action, relative to the submitter element's node document.
+    let document = node_document(form)?;
+    let base_url = document_base_url(&document);
+    let mut url = match form_attribute("action").filter(|action| !action.is_empty()) {
+        Some(action) => Url::parse(&action, Some(&base_url)).ok()?,
+        None => match &document.borrow().data {
+            NodeData::Document(document) => document.url.clone(),
+            _ => return None
+        }
+    };
+
+    // 21. Let method be the submitter element's method.
+    let method = match form_attribute("method").map(|method| ascii_lowercase(&method).into_owned()).as_deref() {
+        Some("post") => FormMethod::Post,
+        _ => FormMethod::Get
+    };
+
+    // https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#submit-mutate-action
+    // Mutate action URL: Let pairs be the result of converting to a list of name-value pairs with entry list. Let query be the result of running the application/x-www-form-urlencoded serializer with pairs and encoding. Set parsed action's query component to query.
+    // https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#submit-body
+    // Submit as entity body: Let body be the result of running the application/x-www-form-urlencoded serializer with pairs and encoding.
+    let serialized = form_urlencoded_serialize(&normalize_entry_list(entry_list));
+    match method {
+        FormMethod::Get => {
+            url.query = Some(serialized);
+            return Some(FormSubmission { method, url, body: None });
+        },
+        FormMethod::Post => return Some(FormSubmission { method, url, body: Some(serialized) })
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/forms.html#dom-form-submit
+// The submit() method steps are to submit this from this, with ignore validation and no submit event, and the submission is kept on the document
+pub fn submit(form: &RefNode) {
+    let Some(submission) = form_submission(form, None) else { return };
+    if let Some(document) = node_document(form) {
+        if let NodeData::Document(document) = &mut document.borrow_mut().data {
+            document.form_submissions.push(submission);
+        }
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/input.html#htmlinputelement
+// TODO: There are no HTMLElement interfaces yet, so every element has the form control attributes, which are undefined on elements they do not apply to
+pub(crate) fn install_forms(element_prototype: &Rc<RefCell<JSObject>>) {
+    define_operations(element_prototype, &[
+        ("submit", operation!("HTMLFormElement", "submit", form_submit(element: ElementNode))),
+    ]);
+    define_attributes(element_prototype, &[
+        ("value", attribute_getter!("HTMLInputElement", "value", element_value(element: ElementNode)), Some(attribute_setter!("HTMLInputElement", "value", element_set_value(element: ElementNode, new_value: DOMString)))),
+        ("checked", attribute_getter!("HTMLInputElement", "checked", element_checked(element: ElementNode)), Some(attribute_setter!("HTMLInputElement", "checked", element_set_checked(element: ElementNode, checkedness: bool)))),
+        ("selected", attribute_getter!("HTMLOptionElement", "selected", element_selected(element: ElementNode)), Some(attribute_setter!("HTMLOptionElement", "selected", element_set_selected(element: ElementNode, selectedness: bool)))),
+        ("form", attribute_getter!("HTMLInputElement", "form", element_form(element: ElementNode)), None),
+        ("elements", attribute_getter!("HTMLFormElement", "elements", form_elements_getter(element: ElementNode)), None),
+    ]);
+}
+
+// The element when it is one of local_names, None for other elements
+fn form_element(ElementNode(element): ElementNode, local_names: &[&str]) -> Option<RefNode> {
+    let applies = local_names.contains(&local_name(&element).as_str());
+    return if applies { Some(element) } else { None };
+}
+
+// https://html.spec.whatwg.org/multipage/input.html#dom-input-value
+fn element_value(element: ElementNode) -> OrUndefined<DOMString> {
+    match form_element(element, &["input", "textarea", "select", "option", "button"]) {
+        Some(element) => OrUndefined::Value(value(&element)),
+        None => OrUndefined::Undefined
+    }
+}
+
+fn element_set_value(element: ElementNode, new_value: DOMString) -> Result<(), Exception> {
+    let element = match form_element(element, &["input", "textarea", "select", "option", "button"]) {
+        Some(element) => element,
+        None => return Ok(())
+    };
+
+    if !set_value(&element, new_value) {
+        return Err(Exception::DOMException("InvalidStateError", String::from("This input element accepts a filename, which may only be programmatically set to the empty string.")));
+    }
+    return Ok(());
+}
+
+// https://html.spec.whatwg.org/multipage/input.html#dom-input-checked
+fn element_checked(element: ElementNode) -> OrUndefined<bool> {
+    match form_element(element, &["input"]) {
+        Some(element) => OrUndefined::Value(checked(&element)),
+        None => OrUndefined::Undefined
+    }
+}
+
+fn element_set_checked(element: ElementNode, checkedness: bool) {
+    if let Some(element) = form_element(element, &["input"]) {
+        set_checked(&element, checkedness);
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/form-elements.html#dom-option-selected
+fn element_selected(element: ElementNode) -> OrUndefined<bool> {
+    match form_element(element, &["option"]) {
+        Some(option) => OrUndefined::Value(selected(&option)),
+        None => OrUndefined::Undefined
+    }
+}
+
+fn element_set_selected(element: ElementNode, selectedness: bool) {
+    if let Some(option) = form_element(element, &["option"]) {
+        set_selected(&option, selectedness);
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#dom-fae-form
+// The form IDL attribute's behavior depends on whether the element is a form-associated element: return the element's form owner, or null if there isn't one
+fn element_form(element: ElementNode) -> OrUndefined<Option<RefNode>> {
+    match form_element(element, &["button", "fieldset", "input", "object", "output", "select", "textarea"]) {
+        Some(element) => OrUndefined::Value(form_owner(&element)),
+        None => OrUndefined::Undefined
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/forms.html#dom-form-elements
+// FIXME: This is an array of the elements at the time it is read rather than a live HTMLFormControlsCollection
+fn form_elements_getter(element: ElementNode) -> OrUndefined<Vec<RefNode>> {
+    match form_element(element, &["form"]) {
+        Some(form) => OrUndefined::Value(form_elements(&form)),
+        None => OrUndefined::Undefined
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/forms.html#dom-form-submit
+fn form_submit(element: ElementNode) -> Result<(), Exception> {
+    match form_element(element, &["form"]) {
+        Some(form) => {
+            submit(&form);
+            return Ok(());
+        },
+        None => return Err(Exception::TypeError(String::from("Illegal invocation")))
+    }
+}
@@ -0,0 +1,241 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+use crate::interpreter::{JSObject, PropertyType};
+
+// The ownership model of the DOM and the interpreter
+// Everything is reference counted, a strong reference (Rc) owns and a weak reference (Weak) does not:
+// - A node owns its children, and refers to its parent and node document weakly, so the tree on its own has no cycles
+// - A node owns its event listeners and registered observers, which own their callback function objects
+// - A platform object (the wrapper of a node, event, style sheet, ...) owns what it wraps, the wrapped object never owns a wrapper
+// - A function object owns its [[Environment]], an environment owns its outer environment and the values of its bindings
+// - An object owns its prototype and the values of its properties
+// Script can close all of these into cycles, e.g. a listener on a node whose function closes over a variable holding the node's wrapper,
+// and so can the realm itself, e.g. F.prototype.constructor is F, so reference counting alone never frees them
+//
+// The cycles are broken by a cycle collector, which finds the groups of cells that are only referenced from inside the group,
+// the same trial deletion as CPython: a cell whose strong count is higher than the number of references to it the collector can see
+// is referenced from somewhere else (a Rust local, a task, a closure it can not look into), so it and everything it refers to is kept
+// Only objects are unlinked, every cycle goes through one, and dropping their references lets reference counting free the rest
+// When the interpreter is dropped its realm is torn down, and every object reachable from it is unlinked
+// TODO: The objects the engine creates for itself, e.g. errors and promises, are not tracked, so their cycles are only freed when the realm is torn down
+
+// The number of objects that are allocated before the cycle collector runs again
+const COLLECTION_THRESHOLD: usize = 10000;
+
+thread_local! {
+    // The bytes objects have grown by since the heap was last measured, objects can not reach the interpreter to count it themselves
+    static GROWTH: Cell<usize> = const { Cell::new(0) };
+}
+
+// Counts bytes an object grew by, e.g. a property that was added, against the maximum heap size
+pub(crate) fn record_growth(bytes: usize) {
+    GROWTH.with(|growth| growth.set(growth.get().saturating_add(bytes)));
+}
+
+// The bytes objects have grown by since this was last called
+pub(crate) fn take_growth() -> usize {
+    return GROWTH.with(|growth| growth.replace(0));
+}
+
+// A value that holds strong references to cells of the heap
+pub(crate) trait Trace {
+    // Reports every strong reference the value holds to the tracer, each one once
+    fn trace(&self, tracer: &mut Tracer);
+
+    // Drops the references the value holds, only called on values that are garbage or belong to a realm that is torn down
+    fn unlink(&mut self) {}
+
+    // The bytes the value takes up on the heap besides itself and the cells it refers to, e.g. the characters of a string
+    fn heap_size(&self) -> usize {
+        return 0;
+    }
+}
+
+// An allocation reference counting manages, which the cycle collector can look into
+pub(crate) trait HeapCell {
+    fn trace_cell(&self, tracer: &mut Tracer);
+    fn unlink_cell(&self);
+    fn is_object(&self) -> bool {
+        return false;
+    }
+
+    // The bytes the cell takes up, not counting the cells it refers to
+    fn cell_size(&self) -> usize;
+}
+
+// A cell that is borrowed while the collector runs is not looked into, so the cells it refers to are kept
+impl<T: Trace + 'static> HeapCell for RefCell<T> {
+    fn trace_cell(&self, tracer: &mut Tracer) {
+        if let Ok(value) = self.try_borrow() {
+            value.trace(tracer);
+        }
+    }
+
+    fn unlink_cell(&self) {
+        if let Ok(mut value) = self.try_borrow_mut() {
+            value.unlink();
+        }
+    }
+
+    fn is_object(&self) -> bool {
+        return std::any::TypeId::of::<T>() == std::any::TypeId::of::<JSObject>();
+    }
+
+    fn cell_size(&self) -> usize {
+        return std::mem::size_of::<Self>() + self.try_borrow().map_or(0, |value| value.heap_size());
+    }
+}
+
+impl HeapCell for PropertyType {
+    fn trace_cell(&self, tracer: &mut Tracer) {
+        self.trace(tracer);
+    }
+
+    fn unlink_cell(&self) {}
+
+    fn cell_size(&self) -> usize {
+        return std::mem::size_of::<Self>() + self.heap_size();
+    }
+}
+
+// Collects the strong references a cell holds
+#[derive(Default)]
+pub(crate) struct Tracer {
+    references: Vec<Rc<dyn HeapCell>>,
+}
+
+impl Tracer {
+    pub(crate) fn visit<T: HeapCell + 'static>(&mut self, cell: &Rc<T>) {
+        self.references.push(Rc::clone(cell) as Rc<dyn HeapCell>);
+    }
+}
+
+// The objects of a realm the cycle collector starts looking for garbage from
+#[derive(Default)]
+pub(crate) struct Heap {
+    // The objects script allocated, e.g. with object and array literals, new and function expressions
+    objects: RefCell<Vec<Weak<RefCell<JSObject>>>>,
+    allocations_since_collection: Cell<usize>,
+}
+
+impl Heap {
+    pub(crate) fn allocate(&self, object: JSObject) -> Rc<RefCell<JSObject>> {
+        let object = Rc::new(RefCell::new(object));
+        self.objects.borrow_mut().push(Rc::downgrade(&object));
+        self.allocations_since_collection.set(self.allocations_since_collection.get() + 1);
+        return object;
+    }
+
+    pub(crate) fn should_collect(&self) -> bool {
+        return self.allocations_since_collection.get() >= COLLECTION_THRESHOLD;
+    }
+
+    // The tracked objects that are still alive, the ones that were freed are forgotten
+    fn live_objects(&self) -> Vec<Rc<dyn HeapCell>> {
+        let mut objects = self.objects.borrow_mut();
+        objects.retain(|object| object.strong_count() > 0);
+        return objects.iter().filter_map(|object| object.upgrade()).map(|object| object as Rc<dyn HeapCell>).collect();
+    }
+
+    // The number of tracked objects that are still alive, including the garbage the cycle collector has not freed yet
+    pub(crate) fn live_object_count(&self) -> usize {
+        let mut objects = self.objects.borrow_mut();
+        objects.retain(|object| object.strong_count() > 0);
+        return objects.len();
+    }
+
+    // The bytes taken up by the cells reachable from roots and from the tracked objects that are still alive
+    // This walks every cell, so it is only done when the heap may have grown over its maximum size
+    pub(crate) fn live_size(&self, mut roots: Vec<Rc<dyn HeapCell>>) -> usize {
+        roots.extend(self.live_objects());
+        let graph = HeapGraph::new(roots);
+        return graph.cells.iter().map(|cell| cell.cell_size()).sum();
+    }
+
+    // Frees the cycles of objects that can no longer be reached, and returns how many objects were unlinked
+    pub(crate) fn collect_cycles(&self) -> usize {
+        self.allocations_since_collection.set(0);
+        let graph = HeapGraph::new(self.live_objects());
+
+        // Every cell starts out with its strong count, minus the reference the graph holds
+        let mut external_references: Vec<isize> = graph.cells.iter().map(|cell| Rc::strong_count(cell) as isize - 1).collect();
+
+        // Subtract the references from inside the graph, what is left are the references from outside of it
+        for references in &graph.references {
+            for reference in references {
+                external_references[*reference] -= 1;
+            }
+        }
+
+        // The cells referenced from outside of the graph, and everything they refer to, are reachable
+        let mut reachable = vec![false; graph.cells.len()];
+        let mut stack: Vec<usize> = (0..graph.cells.len()).filter(|index| external_references[*index] > 0).collect();
+        while let Some(index) = stack.pop() {
+            if reachable[index] {
+                continue;
+            }
+            reachable[index] = true;
+            stack.extend(graph.references[index].iter().filter(|reference| !reachable[**reference]));
+        }
+
+        let garbage: Vec<&Rc<dyn HeapCell>> = graph.cells.iter().enumerate().filter(|(index, _)| !reachable[*index]).map(|(_, cell)| cell).collect();
+        return unlink_objects(&garbage);
+    }
+
+    // Unlinks every object reachable from the roots of a realm and the objects it allocated, as none of them can be used once the realm is gone
+    pub(crate) fn tear_down(&self, mut roots: Vec<Rc<dyn HeapCell>>) -> usize {
+        roots.extend(self.live_objects());
+        let graph = HeapGraph::new(roots);
+        return unlink_objects(&graph.cells.iter().collect::<Vec<_>>());
+    }
+}
+
+fn unlink_objects(cells: &[&Rc<dyn HeapCell>]) -> usize {
+    let mut count = 0;
+    for cell in cells.iter().filter(|cell| cell.is_object()) {
+        cell.unlink_cell();
+        count += 1;
+    }
+    return count;
+}
+
+// The cells reachable from a set of cells, and the strong references between them
+// The graph holds one strong reference to each cell, which keeps them alive while they are unlinked
+struct HeapGraph {
+    cells: Vec<Rc<dyn HeapCell>>,
+    references: Vec<Vec<usize>>,
+}
+
+impl HeapGraph {
+    fn new(roots: Vec<Rc<dyn HeapCell>>) -> HeapGraph {
+        let mut graph = HeapGraph { cells: Vec::new(), references: Vec::new() };
+        let mut indices: HashMap<*const (), usize> = HashMap::new();
+        for root in roots {
+            graph.add(&mut indices, root);
+        }
+
+        let mut index = 0;
+        while index < graph.cells.len() {
+            let mut tracer = Tracer::default();
+            graph.cells[index].trace_cell(&mut tracer);
+
+            // The tracer is emptied so the strong counts only include the references of the graph
+            let references: Vec<usize> = tracer.references.drain(..).map(|cell| graph.add(&mut indices, cell)).collect();
+            graph.references[index] = references;
+            index += 1;
+        }
+        return graph;
+    }
+
+    fn add(&mut self, indices: &mut HashMap<*const (), usize>, cell: Rc<dyn HeapCell>) -> usize {
+        let address = Rc::as_ptr(&cell) as *const ();
+        if let Some(index) = indices.get(&address) {
+            return *index;
+        }
+        self.cells.push(cell);
+        self.references.push(Vec::new());
+        indices.insert(address, self.cells.len() - 1);
+        return self.cells.len() - 1;
+    }
+}
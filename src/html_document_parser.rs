@@ -1,16 +1,42 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::process::abort;
-use std::rc::Rc;
-use web_engine::node::{Node};
-use crate::node::{DOMString, Document, DocumentType, Element, NodeType, Text, WeakNode};
+use std::rc::{Rc, Weak};
+use std::time::Instant;
+use crate::atom::Atom;
+use crate::node::{DOMString, Document, DocumentMode, DocumentType, Element, NodeType, Text, WeakNode};
 use crate::node::NodeData;
 use crate::comment::Comment;
-use crate::html_token::{HtmlToken, HtmlTokenType};
+use crate::html_token::{HtmlToken, HtmlTokenType, TokenAttribute};
+use crate::source_location::{SourceLocation, SourcePosition};
+#[cfg(feature = "trace")]
+use crate::trace::{SharedTrace, TraceEvent};
 use crate::node;
 use crate::node::create_ref_node;
 use crate::node::RefNode;
-
-enum InsertionMode {
+use crate::interpreter::Interpreter;
+use crate::tokenizer::HTMLTokenizerState;
+use crate::token_sink::{TokenSink, TokenSinkResult};
+use crate::parse_error::ParseError;
+use crate::dom_bindings::install_document;
+use crate::events::fire_an_event;
+use crate::metadata;
+use crate::microsyntaxes::is_ascii_whitespace;
+use crate::selector::parse_selector_list;
+use crate::style::invalidate_style;
+use crate::unicode::{ascii_lowercase, is_ascii_case_insensitive_match};
+use crate::window::Viewport;
+use crate::url::Url;
+use crate::resource_loader::ResourceLoader;
+use crate::script_limits::ScriptLimits;
+use crate::tree_limits::{TreeLimit, TreeLimitError, TreeLimits};
+
+// https://infra.spec.whatwg.org/#namespaces
+pub const MATHML_NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+pub const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+// https://html.spec.whatwg.org/multipage/parsing.html#insertion-mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InsertionMode {
     Initial,
     BeforeHtml,
     BeforeHead,
@@ -36,56 +62,266 @@ enum InsertionMode {
     AfterAfterFrameset,
 }
 
+// https://html.spec.whatwg.org/multipage/scripting.html#the-script-element
+// A script element that has been prepared, it is ready to be executed as fetching is synchronous
+struct PreparedScript {
+    element: RefNode,
+    // https://html.spec.whatwg.org/multipage/scripting.html#concept-script-result
+    // None when the script could not be fetched, executing it fires error at the element instead
+    source_text: Option<String>,
+    // https://html.spec.whatwg.org/multipage/scripting.html#concept-script-external
+    from_an_external_file: bool,
+    // The URL an external script was fetched from, which errors it throws are reported at
+    url: Option<Url>,
+}
+
+// The flags the parsing algorithm depends on that come from where the HTML is from rather than from the HTML itself
+#[derive(Clone)]
+pub struct ParserOptions {
+    // https://html.spec.whatwg.org/multipage/parsing.html#scripting-flag
+    // When scripting is disabled scripts are not run, and noscript elements are parsed as markup instead of raw text
+    pub scripting_enabled: bool,
+    // https://html.spec.whatwg.org/multipage/iframe-embed-object.html#an-iframe-srcdoc-document
+    // The HTML is the srcdoc attribute of an iframe, which is never in quirks mode and does not need a DOCTYPE
+    pub iframe_srcdoc_document: bool,
+    // https://html.spec.whatwg.org/multipage/parsing.html#concept-frag-parse-context
+    // The local name of the context element when the HTML is a fragment (e.g. the value given to innerHTML), None when it is a whole document
+    pub fragment_context: Option<String>,
+    // https://html.spec.whatwg.org/multipage/parsing.html#parser-cannot-change-the-mode-flag
+    // The mode of the document whatever its DOCTYPE says
+    pub quirks_mode_override: Option<DocumentMode>,
+    // https://html.spec.whatwg.org/multipage/parsing.html#parse-errors
+    // Whether parse errors are printed as they are found, they are recorded either way
+    pub print_parse_errors: bool,
+    // Normalizes the input to NFC before it is tokenized (see Lexer::normalize_to_nfc), which the HTML standard does not do, for consumers that compare the text of documents
+    pub normalize_unicode: bool,
+    // The depth, nodes and attributes the tree may have, see tree_limits.rs
+    pub tree_limits: TreeLimits,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        return ParserOptions { scripting_enabled: true, iframe_srcdoc_document: false, fragment_context: None, quirks_mode_override: None, print_parse_errors: true, normalize_unicode: false, tree_limits: TreeLimits::default() };
+    }
+}
+
 pub struct HTMLDocumentParser {
     insertion_mode: InsertionMode,
     document: RefNode,
     stack_of_open_elements: Vec<WeakNode>,
     head_element: Option<WeakNode>,
+    original_insertion_mode: InsertionMode,
+    // https://html.spec.whatwg.org/multipage/parsing.html#scripting-flag
+    scripting_flag: bool,
+    // https://html.spec.whatwg.org/multipage/iframe-embed-object.html#an-iframe-srcdoc-document
+    iframe_srcdoc_document: bool,
+    // https://html.spec.whatwg.org/multipage/parsing.html#parser-cannot-change-the-mode-flag
+    parser_cannot_change_the_mode_flag: bool,
+    // https://html.spec.whatwg.org/multipage/parsing.html#concept-frag-parse-context
+    // The root html element the nodes of a fragment are parsed into, None when a whole document is parsed
+    fragment_root: Option<RefNode>,
+    // Every script in the document runs against the same global environment
+    interpreter: Interpreter,
+    // https://html.spec.whatwg.org/multipage/parsing.html#parse-errors
+    // The parse errors of the tokenizer and the tree builder in the order they were found, kept per parser so documents parsed side by side do not mix their errors
+    parse_errors: Vec<String>,
+    // Where in the input each of the parse errors was found, None when it was not found at a position of the input (e.g. at the end of the input)
+    parse_error_positions: Vec<Option<SourcePosition>>,
+    // The start of the token the tree construction stage is processing, the position of the parse errors it finds
+    current_token_position: Option<SourcePosition>,
+    print_parse_errors: bool,
+    // https://html.spec.whatwg.org/multipage/parsing.html#tokenization
+    // The state the tree construction stage switched the tokenizer to while handling the current token, taken by the tokenizer once the token is handled
+    pending_tokenizer_state: Option<HTMLTokenizerState>,
+    // Set by a pre or listing start tag, whose first newline is not part of its contents
+    ignore_next_line_feed: bool,
+    // https://html.spec.whatwg.org/multipage/parsing.html#acknowledge-self-closing-flag
+    // Set when the tree construction stage acknowledges the self-closing flag of the token it is processing, e.g. for a void element like br
+    self_closing_flag_acknowledged: bool,
+    // https://html.spec.whatwg.org/multipage/parsing.html#script-nesting-level
+    script_nesting_level: u32,
+    // https://html.spec.whatwg.org/multipage/parsing.html#parser-pause-flag
+    // The tokenizer does not tokenize while the parser is paused
+    parser_pause_flag: bool,
+    // https://html.spec.whatwg.org/multipage/scripting.html#pending-parsing-blocking-script
+    pending_parsing_blocking_script: Option<PreparedScript>,
+    // https://html.spec.whatwg.org/multipage/scripting.html#list-of-scripts-that-will-execute-when-the-document-has-finished-parsing
+    // The defer scripts, in the order they were prepared
+    list_of_scripts_that_will_execute_when_the_document_has_finished_parsing: Vec<PreparedScript>,
+    tree_limits: TreeLimits,
+    // The elements and text nodes the parser has created, and the comments and doctypes it was given, for the node limit
+    created_nodes: Cell<usize>,
+    // The limit the input went over, after which the tree builder ignores the rest of the tokens
+    tree_limit_error: Option<TreeLimitError>,
+    // Where insertion mode switches are recorded, see trace.rs
+    #[cfg(feature = "trace")]
+    trace: Option<SharedTrace>,
 }
 
 impl HTMLDocumentParser {
     pub fn new() -> HTMLDocumentParser {
+        return HTMLDocumentParser::with_options(ParserOptions::default());
+    }
+
+    pub fn with_options(options: ParserOptions) -> HTMLDocumentParser {
         let document = create_document_node();
         let mut stack_of_open_elements: Vec<WeakNode> = Vec::new();
         stack_of_open_elements.push(Rc::downgrade(&document));
-        
-        return HTMLDocumentParser {
+
+        let mut interpreter = Interpreter::new();
+        install_document(&mut interpreter, &document);
+        interpreter.event_loop.documents.push(Rc::downgrade(&document));
+
+        let mut parser = HTMLDocumentParser {
             insertion_mode: InsertionMode::Initial,
-            document: create_document_node(),
+            document,
             stack_of_open_elements,
             head_element: None,
+            original_insertion_mode: InsertionMode::Initial,
+            scripting_flag: true,
+            iframe_srcdoc_document: false,
+            parser_cannot_change_the_mode_flag: false,
+            fragment_root: None,
+            interpreter,
+            parse_errors: Vec::new(),
+            parse_error_positions: Vec::new(),
+            current_token_position: None,
+            print_parse_errors: true,
+            pending_tokenizer_state: None,
+            ignore_next_line_feed: false,
+            self_closing_flag_acknowledged: false,
+            script_nesting_level: 0,
+            parser_pause_flag: false,
+            pending_parsing_blocking_script: None,
+            list_of_scripts_that_will_execute_when_the_document_has_finished_parsing: Vec::new(),
+            tree_limits: options.tree_limits,
+            created_nodes: Cell::new(0),
+            tree_limit_error: None,
+            #[cfg(feature = "trace")]
+            trace: None,
+        };
+
+        parser.scripting_flag = options.scripting_enabled;
+        parser.iframe_srcdoc_document = options.iframe_srcdoc_document;
+        parser.print_parse_errors = options.print_parse_errors;
+        if let Some(mode) = options.quirks_mode_override {
+            parser.set_document_mode(mode);
+            parser.parser_cannot_change_the_mode_flag = true;
+        }
+        if let Some(context_element) = &options.fragment_context {
+            parser.start_parsing_a_fragment(context_element);
         }
+
+        return parser;
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#parsing-html-fragments
+    // The steps of the HTML fragment parsing algorithm that come before the input is tokenized, the nodes of the fragment are then the children of the root html element
+    // TODO: The context element is only known by its local name, so there is no form element pointer and no template contents to parse into
+    fn start_parsing_a_fragment(&mut self, context_element: &str) {
+        // 2. If the node document of the context element is in quirks mode, then let the Document be in quirks mode. Otherwise, the node document of the context element is in limited-quirks mode, then let the Document be in limited-quirks mode. Otherwise, leave the Document in no-quirks mode.
+        // The context element has no node document, so the Document is left in the mode it is in
+
+        // 4. Set the state of the HTML parser's tokenization stage as follows, switching on the context element:
+        // Any other element: Leave the tokenizer in the data state.
+        if let Some(tokenizer_state) = tokenizer_state_for_text_element(context_element, self.scripting_flag) {
+            self.switch_the_tokenizer_to(tokenizer_state);
+        }
+
+        // 5. Let root be the result of creating an element given document, "html", and the HTML namespace.
+        // 6. Append the element root to the Document node created above.
+        let root = self.create_element_node_for_token(Atom::from("html"), &[], None);
+        self.document.borrow_mut().append_child(Rc::clone(&root));
+
+        // 7. Set up the HTML parser's stack of open elements so that it contains just the single element root.
+        self.stack_of_open_elements.push(Rc::downgrade(&root));
+        self.fragment_root = Some(root);
+
+        // 9. Reset the parser's insertion mode appropriately.
+        // https://html.spec.whatwg.org/multipage/parsing.html#reset-the-insertion-mode-appropriately
+        // The node is the context element and last is true, so an html context element is before head (there is no head element pointer) and everything else is in body
+        // TODO: The table, select, template and frameset insertion modes are not implemented
+        self.original_insertion_mode = self.insertion_mode;
+        if context_element == "html" {
+            self.switch_to_insertion_mode(InsertionMode::BeforeHead);
+        } else {
+            self.switch_to_insertion_mode(InsertionMode::InBody);
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#parsing-html-fragments
+    // 16. Return root's children, in tree order. Empty when a whole document was parsed.
+    pub fn fragment_nodes(&self) -> Vec<RefNode> {
+        return match &self.fragment_root {
+            Some(root) => root.borrow().childNodes.to_vec(),
+            None => Vec::new()
+        };
     }
 
     pub fn parse_html_token(&mut self, html_token: &HtmlToken) {
+            // The tree builder stops once the input has gone over a limit, this also stops a token that is being reprocessed
+            if self.tree_limit_error.is_some() {
+                return;
+            }
+            if let Some((whitespace_token, rest_token)) = self.split_leading_whitespace(html_token) {
+                self.parse_html_token(&whitespace_token);
+                self.parse_html_token(&rest_token);
+                return;
+            }
+            let ignore_line_feed = std::mem::take(&mut self.ignore_next_line_feed);
+            self.current_token_position = html_token.source_location.map(|source_location| source_location.start);
+            self.trace_tree_builder_step(html_token);
+            // https://html.spec.whatwg.org/multipage/parsing.html#tree-construction-dispatcher
+            // TODO: Only start tags are processed using the rules for parsing tokens in foreign content, other tokens are processed using the insertion mode
+            if matches!(html_token.token_type, HtmlTokenType::StartTag) && self.is_in_foreign_content(html_token) {
+                self.parse_start_tag_in_foreign_content(html_token);
+                return;
+            }
             // https://html.spec.whatwg.org/multipage/parsing.html#the-initial-insertion-mode
             match self.insertion_mode {
                 InsertionMode::Initial => {
                     match html_token.token_type {
-                        HtmlTokenType::Character => {
-                            if (html_token.data == "\u{0009}" || html_token.data == "\u{000A}" || html_token.data == "\u{000C}" || html_token.data == "\u{000D}" || html_token.data == "\u{0020}") {
-                                // Ignore the token.
-                            }
+                        HtmlTokenType::Character if is_whitespace_character_token(html_token) => {
+                            // Ignore the token.
                         },
                         HtmlTokenType::Comment => {
-                            self.document.borrow_mut().append_child(create_comment_node(Some(html_token.data.to_owned()), &self.document, &self.document));
+                            self.document.borrow_mut().append_child(create_comment_node_for_token(html_token, &self.document, &self.document));
                         },
                         HtmlTokenType::DocType => {
-                            if (html_token.name != "html"
-                                || html_token.public_identifier.len() != 0
-                                || (html_token.system_identifier.len() != 0 && html_token.system_identifier != "about:legacy-compat")) {
-                                panic!("Parse Error: Invalid DOCTYPE");
-                            } else {
-                                self.document.borrow_mut().append_child(create_document_type_node(html_token.name.to_owned(), html_token.public_identifier.to_owned(), html_token.system_identifier.to_owned()));
+                            // If the DOCTYPE token's name is not "html", or the token's public identifier is not missing, or the token's system identifier is neither missing nor "about:legacy-compat", then there is a parse error.
+                            if html_token.name != "html"
+                                || !html_token.public_identifier.is_empty()
+                                || (!html_token.system_identifier.is_empty() && html_token.system_identifier != "about:legacy-compat") {
+                                self.parse_error("Invalid DOCTYPE.");
                             }
 
-                            // TODO: Support quirks mode for document
+                            // Append a DocumentType node to the Document node, with its name set to the name given in the DOCTYPE token, or the empty string if the name was missing;
+                            // its public ID set to the public identifier given in the DOCTYPE token, or the empty string if the public identifier was missing; and its system ID set to the system identifier given in the DOCTYPE token, or the empty string if the system identifier was missing.
+                            let document_type_node = create_document_type_node(html_token.name.to_owned(), html_token.public_identifier.to_owned(), html_token.system_identifier.to_owned());
+                            document_type_node.borrow_mut().source_location = html_token.source_location;
+                            self.document.borrow_mut().append_child(document_type_node);
 
+                            // Then, if the document is not an iframe srcdoc document, and the parser cannot change the mode flag is false, set the Document to quirks mode or limited-quirks mode when the DOCTYPE token matches one of the conditions.
+                            if !self.iframe_srcdoc_document && !self.parser_cannot_change_the_mode_flag {
+                                let mode = document_mode_for_doctype(html_token);
+                                self.set_document_mode(mode);
+                            }
+
+                            // Then, switch the insertion mode to "before html".
                             self.switch_to_insertion_mode(InsertionMode::BeforeHtml);
                         }
                         _ => {
-                            // TODO: If the document is not an iframe srcdoc document, then this is a parse error; if the parser cannot change the mode flag is false, set the Document to quirks mode.
-                            self.switch_to_insertion_mode(InsertionMode::BeforeHtml)
+                            // If the document is not an iframe srcdoc document, then this is a parse error; if the parser cannot change the mode flag is false, set the Document to quirks mode.
+                            if !self.iframe_srcdoc_document {
+                                self.parse_error("Missing DOCTYPE.");
+                                if !self.parser_cannot_change_the_mode_flag {
+                                    self.set_document_mode(DocumentMode::Quirks);
+                                }
+                            }
+
+                            // In any case, switch the insertion mode to "before html", then reprocess the token.
+                            self.switch_to_insertion_mode(InsertionMode::BeforeHtml);
+                            self.parse_html_token(html_token);
                         }
                     }
                 },
@@ -93,161 +329,1061 @@ impl HTMLDocumentParser {
                 InsertionMode::BeforeHtml => {
                     match html_token.token_type {
                         HtmlTokenType::DocType => {
-                            panic!("Parse Error: Unexpected DOCTYPE");
+                            self.parse_error("Unexpected DOCTYPE. Ignore the token.");
                         },
                         HtmlTokenType::Comment => {
-                            self.document.borrow_mut().append_child(create_comment_node(Some(html_token.data.to_owned()), &self.document, &self.document));
+                            self.document.borrow_mut().append_child(create_comment_node_for_token(html_token, &self.document, &self.document));
                         },
-                        HtmlTokenType::Character => {
-                            if (html_token.data == "\u{0009}" || html_token.data == "\u{000A}" || html_token.data == "\u{000C}" || html_token.data == "\u{000D}" || html_token.data == "\u{0020}") {
-                                // Ignore the token.
-                            }
+                        HtmlTokenType::Character if is_whitespace_character_token(html_token) => {
+                            // Ignore the token.
                         },
-                        HtmlTokenType::StartTag => {
-                            if (html_token.tag_name == "html") {
-                                let element_node = self.create_element_node_for_token(html_token.tag_name.to_owned());
-                                let element_node_clone = Rc::clone(&element_node);
+                        HtmlTokenType::StartTag if html_token.tag_name == "html" => {
+                            let element_node = self.create_element_node_for_token(html_token.tag_name.to_atom(), &html_token.attributes, None);
+                            set_source_locations_from_token(&element_node, html_token);
+                            let element_node_clone = Rc::clone(&element_node);
+
+                            self.document.borrow_mut().append_child(element_node);
+                            self.stack_of_open_elements.push(Rc::downgrade(&element_node_clone));
+
+                            self.switch_to_insertion_mode(InsertionMode::BeforeHead);
+                        },
+                        HtmlTokenType::EndTag if !matches!(html_token.tag_name.as_str(), "head" | "body" | "html" | "br") => {
+                            self.parse_error("Unexpected end tag. Ignore the token.");
+                        },
+                        _ => {
+                            // Anything else
+                            // Create an html element whose node document is the Document object. Append it to the Document object. Put this element in the stack of open elements.
+                            let element_node = self.create_element_node_for_token(Atom::from("html"), &[], None);
+                            let element_node_clone = Rc::clone(&element_node);
 
-                                self.document.borrow_mut().append_child(element_node);
-                                self.stack_of_open_elements.push(Rc::downgrade(&element_node_clone));
+                            self.document.borrow_mut().append_child(element_node);
+                            self.stack_of_open_elements.push(Rc::downgrade(&element_node_clone));
 
-                                self.switch_to_insertion_mode(InsertionMode::BeforeHead);
+                            // Switch the insertion mode to "before head", then reprocess the token.
+                            self.switch_to_insertion_mode(InsertionMode::BeforeHead);
+                            self.parse_html_token(html_token);
+                        }
+                    }
+                },
+                // https://html.spec.whatwg.org/multipage/parsing.html#the-before-head-insertion-mode
+                InsertionMode::BeforeHead => {
+                    match html_token.token_type {
+                        HtmlTokenType::Character if is_whitespace_character_token(html_token) => {
+                            // Ignore the token.
+                        },
+                        HtmlTokenType::Comment => {
+                            self.insert_a_comment(&html_token.data, html_token.source_location);
+                        },
+                        HtmlTokenType::DocType => {
+                            self.parse_error("Unexpected DOCTYPE. Ignore the token.");
+                        },
+                        HtmlTokenType::StartTag => {
+                            // Process the token using the rules for the "in body" insertion mode.
+                            // https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inbody
+                            match html_token.tag_name.as_str() {
+                                "html" => {
+                                    self.in_body_html_start_tag(html_token);
+                                },
+                                "head" => {
+                                    let head_element_node = self.insert_an_html_element_for_token(html_token);
+                                    self.head_element = Some(head_element_node);
+
+                                    self.switch_to_insertion_mode(InsertionMode::InHead);
+                                },
+                                _ => {
+                                    self.before_head_anything_else(html_token);
+                                }
                             }
                         },
                         HtmlTokenType::EndTag => {
                             match html_token.tag_name.as_str() {
                                 "head" | "body" | "html" | "br" => {
-                                    let element_node = self.create_element_node_for_token(html_token.tag_name.to_owned());
-                                    let element_node_clone = Rc::clone(&element_node);
-
-                                    self.document.borrow_mut().append_child(element_node);
-                                    self.stack_of_open_elements.push(Rc::downgrade(&element_node_clone));
-
-                                    self.switch_to_insertion_mode(InsertionMode::BeforeHead);
+                                    self.before_head_anything_else(html_token);
                                 },
                                 _ => {
-                                    panic!("Parse Error: Unexpected end tag. Ignore the token.");
+                                    self.parse_error("Unexpected end tag. Ignore the token.");
                                 }
                             }
                         }
-                        _ => { }
+                        _ => {
+                            self.before_head_anything_else(html_token);
+                        }
                     }
                 },
-                // https://html.spec.whatwg.org/multipage/parsing.html#the-before-head-insertion-mode
-                InsertionMode::BeforeHead => {
+                // https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inhead
+                InsertionMode::InHead => {
+                    match html_token.token_type {
+                        HtmlTokenType::Character if is_whitespace_character_token(html_token) => {
+                            self.insert_a_character(&html_token.data, html_token.source_location);
+                        },
+                        HtmlTokenType::Comment => {
+                            self.insert_a_comment(&html_token.data, html_token.source_location);
+                        },
+                        HtmlTokenType::DocType => {
+                            self.parse_error("Unexpected DOCTYPE. Ignore the token.");
+                        },
+                        HtmlTokenType::StartTag if html_token.tag_name == "script" => {
+                            self.insert_a_script_element(html_token);
+                        },
+                        HtmlTokenType::StartTag if matches!(html_token.tag_name.as_str(), "base" | "basefont" | "bgsound" | "link" | "meta") => {
+                            // TODO: A meta element's charset and http-equiv attributes can change the encoding, which is always UTF-8
+                            self.insert_a_void_html_element(html_token);
+                        },
+                        HtmlTokenType::StartTag if html_token.tag_name == "title" => {
+                            // Follow the generic RCDATA element parsing algorithm.
+                            self.generic_rcdata_element_parsing(html_token);
+                        },
+                        HtmlTokenType::StartTag if html_token.tag_name == "noframes" || html_token.tag_name == "style" || (html_token.tag_name == "noscript" && self.scripting_flag) => {
+                            // Follow the generic raw text element parsing algorithm.
+                            self.generic_raw_text_element_parsing(html_token);
+                        },
+                        // TODO: A noscript start tag when the scripting flag is disabled switches to the "in head noscript" insertion mode, which is not implemented
+                        HtmlTokenType::StartTag if html_token.tag_name == "head" => {
+                            self.parse_error("Unexpected head start tag. Ignore the token.");
+                        },
+                        HtmlTokenType::EndTag if html_token.tag_name == "head" => {
+                            // Pop the current node (which will be the head element) off the stack of open elements.
+                            self.stack_of_open_elements.pop();
+
+                            self.switch_to_insertion_mode(InsertionMode::AfterHead);
+                        },
+                        HtmlTokenType::EndTag if !matches!(html_token.tag_name.as_str(), "body" | "html" | "br") => {
+                            self.parse_error("Unexpected end tag. Ignore the token.");
+                        },
+                        _ => {
+                            // Anything else
+                            // Pop the current node (which will be the head element) off the stack of open elements.
+                            self.stack_of_open_elements.pop();
+
+                            self.switch_to_insertion_mode(InsertionMode::AfterHead);
+
+                            // Reprocess the token.
+                            self.parse_html_token(html_token);
+                        }
+                    }
+                },
+                // https://html.spec.whatwg.org/multipage/parsing.html#the-after-head-insertion-mode
+                InsertionMode::AfterHead => {
+                    match html_token.token_type {
+                        HtmlTokenType::Character if is_whitespace_character_token(html_token) => {
+                            self.insert_a_character(&html_token.data, html_token.source_location);
+                        },
+                        HtmlTokenType::Comment => {
+                            self.insert_a_comment(&html_token.data, html_token.source_location);
+                        },
+                        HtmlTokenType::DocType => {
+                            self.parse_error("Unexpected DOCTYPE. Ignore the token.");
+                        },
+                        HtmlTokenType::StartTag if html_token.tag_name == "body" => {
+                            self.insert_an_html_element_for_token(html_token);
+
+                            // TODO: Set the frameset-ok flag to "not ok".
+
+                            self.switch_to_insertion_mode(InsertionMode::InBody);
+                        },
+                        // TODO: A template start tag needs the "in head" rules for it first
+                        HtmlTokenType::StartTag if matches!(html_token.tag_name.as_str(), "base" | "basefont" | "bgsound" | "link" | "meta" | "noframes" | "script" | "style" | "title") => {
+                            self.parse_error(&format!("Unexpected {} start tag after head.", html_token.tag_name));
+
+                            // Push the node pointed to by the head element pointer onto the stack of open elements.
+                            // The head element pointer is always set in this insertion mode, the document element stands in for it otherwise
+                            let head_element = self.head_element.clone().unwrap_or_else(|| Rc::downgrade(&self.document));
+                            self.stack_of_open_elements.push(head_element.clone());
+
+                            // Process the token using the rules for the "in head" insertion mode.
+                            match html_token.tag_name.as_str() {
+                                "script" => self.insert_a_script_element(html_token),
+                                "title" => self.generic_rcdata_element_parsing(html_token),
+                                "noframes" | "style" => self.generic_raw_text_element_parsing(html_token),
+                                _ => self.insert_a_void_html_element(html_token)
+                            }
+
+                            // Remove the node pointed to by the head element pointer from the stack of open elements. (It might not be the current node at this point.)
+                            self.stack_of_open_elements.retain(|node| !node.ptr_eq(&head_element));
+                        },
+                        HtmlTokenType::StartTag if html_token.tag_name == "head" => {
+                            self.parse_error("Unexpected head start tag. Ignore the token.");
+                        },
+                        HtmlTokenType::EndTag if !matches!(html_token.tag_name.as_str(), "body" | "html" | "br") => {
+                            self.parse_error("Unexpected end tag. Ignore the token.");
+                        },
+                        _ => {
+                            // Anything else
+                            // Insert an HTML element for a "body" start tag token with no attributes.
+                            self.insert_an_html_element(Atom::from("body"), &[]);
+
+                            self.switch_to_insertion_mode(InsertionMode::InBody);
+
+                            // Reprocess the current token.
+                            self.parse_html_token(html_token);
+                        }
+                    }
+                },
+                // https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inbody
+                // TODO: Only character, comment, script, void element and generic start and end tag handling is done.
+                InsertionMode::InBody => {
                     match html_token.token_type {
                         HtmlTokenType::Character => {
-                            if (html_token.data == "\u{0009}" || html_token.data == "\u{000A}" || html_token.data == "\u{000C}" || html_token.data == "\u{000D}" || html_token.data == "\u{0020}") {
-                                // Ignore the token.
+                            // If the next token after a pre or listing start tag is a U+000A LINE FEED (LF) character token, then ignore that token and move on to the next one.
+                            // A run of characters is a single token here, so only its first character is ignored
+                            let (data, source_location) = match html_token.data.strip_prefix('\n') {
+                                Some(data) if ignore_line_feed => (data, html_token.source_location.map(|source_location| source_location.after_leading_line_feed())),
+                                _ => (html_token.data.as_str(), html_token.source_location)
+                            };
+                            if data.is_empty() {
+                                return;
                             }
+
+                            // TODO: Reconstruct the active formatting elements, if any.
+                            self.insert_a_character(data, source_location);
                         },
                         HtmlTokenType::Comment => {
-                            let appropriate_place_for_inserting_a_node = self.appropriate_place_for_inserting_a_node(None).upgrade().unwrap();
-                            appropriate_place_for_inserting_a_node.borrow_mut().append_child(create_comment_node(Some(html_token.data.to_owned()), &appropriate_place_for_inserting_a_node, &self.document));
+                            self.insert_a_comment(&html_token.data, html_token.source_location);
                         },
                         HtmlTokenType::DocType => {
-                            panic!("Parse Error: Unexpected DOCTYPE. Ignore the token.");
+                            self.parse_error("Unexpected DOCTYPE. Ignore the token.");
                         },
                         HtmlTokenType::StartTag => {
-                            // Process the token using the rules for the "in body" insertion mode.
-                            // https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inbody
                             match html_token.tag_name.as_str() {
                                 "html" => {
-                                    println!("Parse Error: Unexpected html start tag.");
+                                    self.in_body_html_start_tag(html_token);
+                                },
+                                "script" => {
+                                    // Process the token using the rules for the "in head" insertion mode.
+                                    self.insert_a_script_element(html_token);
+                                },
+                                "noframes" | "style" => {
+                                    // Process the token using the rules for the "in head" insertion mode.
+                                    self.generic_raw_text_element_parsing(html_token);
+                                },
+                                "title" => {
+                                    // Process the token using the rules for the "in head" insertion mode.
+                                    self.generic_rcdata_element_parsing(html_token);
+                                },
+                                "textarea" => {
+                                    // 1. Insert an HTML element for the token.
+                                    self.insert_an_html_element_for_token(html_token);
 
-                                    todo!()
-                                    /*
-                                    TODO:
-                                    If there is a template element on the stack of open elements, then ignore the token.
+                                    // 2. If the next token is a U+000A LINE FEED (LF) character token, then ignore that token and move on to the next one. (Newlines at the start of textarea elements are ignored as an authoring convenience.)
+                                    self.ignore_next_line_feed = true;
 
-                                    Otherwise, for each attribute on the token,
-                                    check to see if the attribute is already present on the top element of the stack of open elements.
-                                    If it is not, add the attribute and its corresponding value to that element.
-                                     */
-                                },
-                                "head" => {
-                                    let head_element_node = self.create_element_node_for_token(html_token.tag_name.to_owned());
-                                    self.head_element = Some(Rc::downgrade(&head_element_node));
-                                    
-                                    self.appropriate_place_for_inserting_a_node(None).upgrade().unwrap().borrow_mut().append_child(head_element_node);
+                                    // 3. Switch the tokenizer to the RCDATA state.
+                                    self.switch_the_tokenizer_to(HTMLTokenizerState::RCData);
 
-                                    self.switch_to_insertion_mode(InsertionMode::InHead);
+                                    // 4. Let the original insertion mode be the current insertion mode.
+                                    self.original_insertion_mode = self.insertion_mode;
+
+                                    // TODO: 5. Set the frameset-ok flag to "not ok".
+
+                                    // 6. Switch the insertion mode to "text".
+                                    self.switch_to_insertion_mode(InsertionMode::Text);
+                                },
+                                "xmp" => {
+                                    // TODO: If the stack of open elements has a p element in button scope, then close a p element.
+                                    // TODO: Reconstruct the active formatting elements, if any.
+                                    // TODO: Set the frameset-ok flag to "not ok".
+                                    // Follow the generic raw text element parsing algorithm.
+                                    self.generic_raw_text_element_parsing(html_token);
+                                },
+                                "iframe" => {
+                                    // TODO: Set the frameset-ok flag to "not ok".
+                                    // Follow the generic raw text element parsing algorithm.
+                                    self.generic_raw_text_element_parsing(html_token);
                                 },
-                                _ => {}
+                                "noembed" => {
+                                    // Follow the generic raw text element parsing algorithm.
+                                    self.generic_raw_text_element_parsing(html_token);
+                                },
+                                "noscript" if self.scripting_flag => {
+                                    // Follow the generic raw text element parsing algorithm.
+                                    self.generic_raw_text_element_parsing(html_token);
+                                },
+                                "plaintext" => {
+                                    // TODO: If the stack of open elements has a p element in button scope, then close a p element.
+                                    // Insert an HTML element for the token.
+                                    self.insert_an_html_element_for_token(html_token);
+
+                                    // Switch the tokenizer to the PLAINTEXT state.
+                                    // Once a start tag with the tag name "plaintext" has been seen, that will be the last token ever seen other than character tokens (and the end-of-file token), because there is no way to switch out of the PLAINTEXT state.
+                                    self.switch_the_tokenizer_to(HTMLTokenizerState::PlainText);
+                                },
+                                "pre" | "listing" => {
+                                    // TODO: If the stack of open elements has a p element in button scope, then close a p element.
+                                    // Insert an HTML element for the token.
+                                    self.insert_an_html_element_for_token(html_token);
 
+                                    // If the next token is a U+000A LINE FEED (LF) character token, then ignore that token and move on to the next one. (Newlines at the start of pre blocks are ignored as an authoring convenience.)
+                                    self.ignore_next_line_feed = true;
+
+                                    // TODO: Set the frameset-ok flag to "not ok".
+                                },
+                                "area" | "br" | "embed" | "img" | "keygen" | "wbr" | "input" | "hr" | "base" | "basefont" | "bgsound" | "link" | "meta" | "param" | "source" | "track" => {
+                                    self.insert_a_void_html_element(html_token);
+                                },
+                                "math" | "svg" => {
+                                    // TODO: Reconstruct the active formatting elements, if any.
+                                    // TODO: Adjust MathML attributes or adjust SVG attributes for the token, and adjust foreign attributes for the token.
+                                    // Insert a foreign element for the token, with the MathML namespace or the SVG namespace and false.
+                                    let namespace = if html_token.tag_name == "math" { MATHML_NAMESPACE } else { SVG_NAMESPACE };
+                                    self.insert_a_foreign_element_for_token(html_token, Some(namespace));
+
+                                    // If the token has its self-closing flag set, pop the current node off the stack of open elements and acknowledge the token's self-closing flag.
+                                    if html_token.self_closing {
+                                        self.stack_of_open_elements.pop();
+                                        self.acknowledge_the_self_closing_flag(html_token);
+                                    }
+                                },
+                                _ => {
+                                    // Any other start tag
+                                    // TODO: Reconstruct the active formatting elements, if any.
+                                    self.insert_an_html_element_for_token(html_token);
+                                }
                             }
                         },
                         HtmlTokenType::EndTag => {
                             match html_token.tag_name.as_str() {
-                                "head" | "body" | "html" | "br" => {
-                                    todo!()
-                                    // Anything else
-                                    /*
-                                        Insert an HTML element for a "head" start tag token with no attributes.
-
-                                        Set the head element pointer to the newly created head element.
-
-                                        Switch the insertion mode to "in head".
+                                "body" => {
+                                    // TODO: If the stack of open elements does not have a body element in scope, this is a parse error; ignore the token.
+                                    self.switch_to_insertion_mode(InsertionMode::AfterBody);
+                                },
+                                "html" => {
+                                    self.switch_to_insertion_mode(InsertionMode::AfterBody);
 
-                                        Reprocess the current token.
-                                     */
+                                    // Reprocess the token.
+                                    self.parse_html_token(html_token);
                                 },
                                 _ => {
-                                    panic!("Parse Error: Unexpected end tag. Ignore the token.");
+                                    // Any other end tag
+                                    // TODO: Generate implied end tags and stop at special elements
+                                    match self.stack_of_open_elements.iter().rposition(|node| HTMLDocumentParser::is_element_with_local_name(node, &html_token.tag_name)) {
+                                        Some(index) => {
+                                            // Pop all the nodes from the current node up to node, including node
+                                            self.stack_of_open_elements.truncate(index);
+                                        },
+                                        None => {
+                                            self.parse_error("Unexpected end tag. Ignore the token.");
+                                        }
+                                    }
                                 }
                             }
+                        },
+                        HtmlTokenType::EndOfFile => {
+                            // TODO: Stop parsing.
                         }
-                        _ => {}
                     }
-
-
                 },
-                InsertionMode::InHead => {
+                // https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-incdata
+                InsertionMode::Text => {
                     match html_token.token_type {
                         HtmlTokenType::Character => {
-                            if (html_token.data == "\u{0009}" || html_token.data == "\u{000A}" || html_token.data == "\u{000C}" || html_token.data == "\u{000D}" || html_token.data == "\u{0020}") {
-                                // https://html.spec.whatwg.org/multipage/parsing.html#insert-a-character
+                            // The newline after a textarea start tag is ignored, see the "in body" insertion mode
+                            let (data, source_location) = match html_token.data.strip_prefix('\n') {
+                                Some(data) if ignore_line_feed => (data, html_token.source_location.map(|source_location| source_location.after_leading_line_feed())),
+                                _ => (html_token.data.as_str(), html_token.source_location)
+                            };
+                            if data.is_empty() {
+                                return;
+                            }
+
+                            self.insert_a_character(data, source_location);
+                        },
+                        HtmlTokenType::EndOfFile => {
+                            self.parse_error("Unexpected end of file in text.");
+
+                            // TODO: If the current node is a script element, then set its already started to true.
 
-                                // 1. Let data be the characters passed to the algorithm, or, if no characters were explicitly specified, the character of the character token being processed
-                                let character = &html_token.data;
+                            self.stack_of_open_elements.pop();
 
-                                // 2. Let the adjusted insertion location be the appropriate place for inserting a node.
-                                let adjusted_insertion_location = &self.appropriate_place_for_inserting_a_node(None);
+                            self.switch_to_insertion_mode(self.original_insertion_mode);
 
-                                // 3. If the adjusted insertion location is in a Document node, then return.
-                                match adjusted_insertion_location.upgrade().unwrap().borrow().nodeType {
-                                    NodeType::DOCUMENT_NODE => {
-                                        return;
-                                    },
-                                    _ => {}
+                            // Reprocess the token.
+                            self.parse_html_token(html_token);
+                        },
+                        HtmlTokenType::EndTag if html_token.tag_name == "script" => {
+                            // TODO: If the active speculative HTML parser is null and the JavaScript execution context stack is empty, then perform a microtask checkpoint.
+
+                            // Let script be the current node (which will be a script element).
+                            // A script that removed itself from the document is no longer the current node, so there is nothing to prepare
+                            let script = match self.current_node().upgrade() {
+                                Some(script) => script,
+                                None => {
+                                    self.stack_of_open_elements.pop();
+                                    self.switch_to_insertion_mode(self.original_insertion_mode);
+                                    return;
                                 }
+                            };
 
-                                match &mut self.stack_of_open_elements[self.stack_of_open_elements.len() - 2].upgrade().unwrap().borrow_mut().data {
-                                    // 4. If there is a Text node immediately before the adjusted insertion location, then append data to that Text node's data.
-                                    node::NodeData::Text(ref mut text) => {
-                                        text.character_data.data.push_str(&character);
-                                    }
-                                    // Otherwise, create a new Text node whose data is data and whose node document is the same as that of the element in which the adjusted insertion location finds itself,
-                                    // and insert the newly created node at the adjusted insertion location.
-                                    _ => {
-                                        let text_node = self.create_text_node(character.clone());
-                                        self.stack_of_open_elements.push(Rc::downgrade(&text_node));
-                                        adjusted_insertion_location.upgrade().unwrap().borrow_mut().append_child(text_node);
-                                    }
+                            // Pop the current node off the stack of open elements.
+                            self.stack_of_open_elements.pop();
+
+                            // Switch the insertion mode to the original insertion mode.
+                            self.switch_to_insertion_mode(self.original_insertion_mode);
+
+                            // TODO: Let the old insertion point have the same value as the current insertion point. Let the insertion point be just before the next input character.
+
+                            // Increment the parser's script nesting level by one.
+                            self.script_nesting_level += 1;
+
+                            // If the active speculative HTML parser is null, then prepare the script element script.
+                            self.prepare_the_script_element(&script);
+
+                            // Decrement the parser's script nesting level by one. If the parser's script nesting level is zero, then set the parser pause flag to false.
+                            self.script_nesting_level -= 1;
+                            if self.script_nesting_level == 0 {
+                                self.parser_pause_flag = false;
+                            }
+
+                            // TODO: Let the insertion point have the value of the old insertion point.
+
+                            // At this stage, if the pending parsing-blocking script is not null, then:
+                            if self.pending_parsing_blocking_script.is_some() {
+                                // If the script nesting level is not zero:
+                                if self.script_nesting_level != 0 {
+                                    // Set the parser pause flag to true, and abort the processing of any nested invocations of the tokenizer, yielding control back to the caller.
+                                    // (Tokenization will resume when the caller returns to the "outer" tree construction stage.)
+                                    self.parser_pause_flag = true;
+                                    return;
                                 }
 
+                                // Otherwise:
+                                // While the pending parsing-blocking script is not null:
+                                while let Some(pending_parsing_blocking_script) = self.pending_parsing_blocking_script.take() {
+                                    // 1. Let the script be the pending parsing-blocking script.
+                                    // 2. Set the pending parsing-blocking script to null.
+                                    // TODO: 3. Start the speculative HTML parser for this instance of the HTML parser.
+                                    // 4. Block the tokenizer for this instance of the HTML parser, such that the event loop will not run tasks that invoke the tokenizer.
+                                    // 5. If the parser's Document has a style sheet that is blocking scripts or the script's ready to be parser-executed is false:
+                                    //    spin the event loop until the parser's Document has no style sheet that is blocking scripts and the script's ready to be parser-executed becomes true.
+                                    // Fetching is synchronous and style sheets are never blocking, so the script is always ready by now
+                                    // 6. If this parser has been aborted in the meantime, return.
+                                    // 7. Stop the speculative HTML parser for this instance of the HTML parser.
+                                    // 8. Unblock the tokenizer for this instance of the HTML parser, such that tasks that invoke the tokenizer can again be run.
+                                    // TODO: 9. Let the insertion point be just before the next input character.
+
+                                    // 10. Increment the parser's script nesting level by one (it should be zero before this step, so this sets it to one).
+                                    self.script_nesting_level += 1;
+
+                                    // 11. Execute the script element the script.
+                                    execute_the_script_element(&mut self.interpreter, &pending_parsing_blocking_script);
+
+                                    // 12. Decrement the parser's script nesting level by one. If the parser's script nesting level is zero (which it always should be at this point), then set the parser pause flag to false.
+                                    self.script_nesting_level -= 1;
+                                    if self.script_nesting_level == 0 {
+                                        self.parser_pause_flag = false;
+                                    }
+
+                                    // TODO: 13. Let the insertion point be undefined again.
+                                }
                             }
                         },
-                        _ => {}
+                        _ => {
+                            // Any other end tag
+                            self.stack_of_open_elements.pop();
+
+                            self.switch_to_insertion_mode(self.original_insertion_mode);
+                        }
                     }
-                }
+                },
+                // https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-afterbody
+                InsertionMode::AfterBody => {
+                    match html_token.token_type {
+                        HtmlTokenType::Character if is_whitespace_character_token(html_token) => {
+                            // Process the token using the rules for the "in body" insertion mode.
+                            self.switch_to_insertion_mode(InsertionMode::InBody);
+                            self.parse_html_token(html_token);
+                            self.switch_to_insertion_mode(InsertionMode::AfterBody);
+                        },
+                        HtmlTokenType::Comment => {
+                            // Insert a comment as the last child of the first element in the stack of open elements (the html element).
+                            let html_element = self.stack_of_open_elements.get(1).and_then(|html_element| html_element.upgrade()).unwrap_or_else(|| Rc::clone(&self.document));
+                            html_element.borrow_mut().append_child(create_comment_node_for_token(html_token, &html_element, &self.document));
+                        },
+                        HtmlTokenType::DocType => {
+                            self.parse_error("Unexpected DOCTYPE. Ignore the token.");
+                        },
+                        HtmlTokenType::EndTag if html_token.tag_name == "html" => {
+                            self.switch_to_insertion_mode(InsertionMode::AfterAfterBody);
+                        },
+                        HtmlTokenType::EndOfFile => {
+                            // TODO: Stop parsing.
+                        },
+                        _ => {
+                            self.parse_error("Unexpected token after body.");
+
+                            self.switch_to_insertion_mode(InsertionMode::InBody);
+
+                            // Reprocess the token.
+                            self.parse_html_token(html_token);
+                        }
+                    }
+                },
+                // https://html.spec.whatwg.org/multipage/parsing.html#the-after-after-body-insertion-mode
+                InsertionMode::AfterAfterBody => {
+                    match html_token.token_type {
+                        HtmlTokenType::Comment => {
+                            self.document.borrow_mut().append_child(create_comment_node_for_token(html_token, &self.document, &self.document));
+                        },
+                        HtmlTokenType::EndOfFile => {
+                            // TODO: Stop parsing.
+                        },
+                        HtmlTokenType::Character if is_whitespace_character_token(html_token) => {
+                            // Process the token using the rules for the "in body" insertion mode.
+                            self.switch_to_insertion_mode(InsertionMode::InBody);
+                            self.parse_html_token(html_token);
+                            self.switch_to_insertion_mode(InsertionMode::AfterAfterBody);
+                        },
+                        _ => {
+                            self.parse_error("Unexpected token after body.");
+
+                            self.switch_to_insertion_mode(InsertionMode::InBody);
+
+                            // Reprocess the token.
+                            self.parse_html_token(html_token);
+                        }
+                    }
+                },
                 _ => {}
             }
 
     }
 
+    // The spec gives the tree builder one character token per character, the tokenizer gives it a run of them
+    // In the insertion modes where whitespace is processed differently from other characters (e.g. ignored before the head or inserted after it),
+    // a run that starts with whitespace is split so the whitespace is processed on its own and the rest of the run is reprocessed after it
+    fn split_leading_whitespace(&self, html_token: &HtmlToken) -> Option<(HtmlToken, HtmlToken)> {
+        if !matches!(html_token.token_type, HtmlTokenType::Character) {
+            return None;
+        }
+        if !matches!(self.insertion_mode, InsertionMode::Initial | InsertionMode::BeforeHtml | InsertionMode::BeforeHead | InsertionMode::InHead | InsertionMode::InHeadNoScript
+            | InsertionMode::AfterHead | InsertionMode::InColumnGroup | InsertionMode::InFrameset | InsertionMode::AfterFrameset
+            | InsertionMode::AfterBody | InsertionMode::AfterAfterBody | InsertionMode::AfterAfterFrameset) {
+            return None;
+        }
+
+        let whitespace_length = html_token.data.len() - html_token.data.trim_start_matches(is_ascii_whitespace).len();
+        if whitespace_length == 0 || whitespace_length == html_token.data.len() {
+            return None;
+        }
+
+        let (whitespace, rest) = html_token.data.split_at(whitespace_length);
+        let (whitespace_source_location, rest_source_location) = match html_token.source_location {
+            Some(source_location) => {
+                let (whitespace_source_location, rest_source_location) = source_location.split_after(whitespace);
+                (Some(whitespace_source_location), Some(rest_source_location))
+            },
+            None => (None, None)
+        };
+        let mut whitespace_token = html_token.clone();
+        whitespace_token.data = whitespace.to_string().into();
+        whitespace_token.source_location = whitespace_source_location;
+        let mut rest_token = html_token.clone();
+        rest_token.data = rest.to_string().into();
+        rest_token.source_location = rest_source_location;
+        return Some((whitespace_token, rest_token));
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inbody
+    // A start tag whose tag name is "html"
+    fn in_body_html_start_tag(&mut self, html_token: &HtmlToken) {
+        // Parse error.
+        self.parse_error("Unexpected html start tag.");
+
+        // If there is a template element on the stack of open elements, then ignore the token.
+        if self.stack_of_open_elements.iter().any(|node| HTMLDocumentParser::is_element_with_local_name(node, "template")) {
+            return;
+        }
+
+        // Otherwise, for each attribute on the token, check to see if the attribute is already present on the top element of the stack of open elements. If it is not, add the attribute and its corresponding value to that element.
+        // The first entry of the stack is the document, so the top element is the one after it
+        let top_element = match self.stack_of_open_elements.get(1).and_then(|node| node.upgrade()) {
+            Some(top_element) => top_element,
+            None => return
+        };
+        if let NodeData::Element(ref mut element) = top_element.borrow_mut().data {
+            for attribute in &html_token.attributes {
+                if !element.has_attribute(&attribute.name) {
+                    element.set_attribute(&attribute.name, attribute.value.to_string());
+                    element.set_attribute_source_location(&attribute.name, attribute.source_location);
+                }
+            }
+        };
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#the-before-head-insertion-mode
+    fn before_head_anything_else(&mut self, html_token: &HtmlToken) {
+        // Insert an HTML element for a "head" start tag token with no attributes.
+        let head_element_node = self.insert_an_html_element(Atom::from("head"), &[]);
+
+        // Set the head element pointer to the newly created head element.
+        self.head_element = Some(head_element_node);
+
+        // Switch the insertion mode to "in head".
+        self.switch_to_insertion_mode(InsertionMode::InHead);
+
+        // Reprocess the current token.
+        self.parse_html_token(html_token);
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inhead
+    // A start tag whose tag name is "script"
+    fn insert_a_script_element(&mut self, html_token: &HtmlToken) {
+        // 1. Let the adjusted insertion location be the appropriate place for inserting a node.
+        // 2. Let element be the result of creating an element for the token in the given namespace, with the intended parent being the element in which the adjusted insertion location finds itself.
+        // TODO: 3. Set the element's parser document to the Document, and set the element's force async to false.
+        // 4. If the parser was created as part of the HTML fragment parsing algorithm, then set the script element's already started to true.
+        // There is no already started flag, a script of a fragment is never prepared instead
+        // TODO: 5. If the parser was invoked via the document.write() or document.writeln() methods, then optionally set the script element's already started to true.
+        // 6. Insert the newly created element at the adjusted insertion location.
+        // 7. Push the element onto the stack of open elements so that it is the new current node.
+        self.insert_an_html_element_for_token(html_token);
+
+        // 8. Switch the tokenizer to the script data state.
+        self.switch_the_tokenizer_to(HTMLTokenizerState::ScriptData);
+
+        // 9. Set the original insertion mode to the current insertion mode.
+        self.original_insertion_mode = self.insertion_mode;
+
+        // 10. Switch the insertion mode to "text".
+        self.switch_to_insertion_mode(InsertionMode::Text);
+    }
+
+    // A start tag for a void element, e.g. br or meta, which can not have any contents
+    fn insert_a_void_html_element(&mut self, html_token: &HtmlToken) {
+        // Insert an HTML element for the token. Immediately pop the current node off the stack of open elements.
+        let element = self.insert_an_html_element_for_token(html_token);
+        self.stack_of_open_elements.pop();
+
+        // https://html.spec.whatwg.org/multipage/semantics.html#attr-meta-http-equiv
+        // When a meta element is inserted into the document, if its http-equiv attribute is present and represents one of the above states, then the user agent must run the algorithm appropriate for that state
+        if html_token.tag_name == "meta" {
+            if let Some(element) = element.upgrade() {
+                metadata::process_http_equiv(&element);
+            }
+        }
+
+        // Acknowledge the token's self-closing flag, if it is set.
+        self.acknowledge_the_self_closing_flag(html_token);
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#generic-raw-text-element-parsing-algorithm
+    fn generic_raw_text_element_parsing(&mut self, html_token: &HtmlToken) {
+        self.generic_text_element_parsing(html_token, HTMLTokenizerState::RawText);
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#generic-rcdata-element-parsing-algorithm
+    fn generic_rcdata_element_parsing(&mut self, html_token: &HtmlToken) {
+        self.generic_text_element_parsing(html_token, HTMLTokenizerState::RCData);
+    }
+
+    // The steps shared by the generic raw text and generic RCDATA element parsing algorithms, which only differ in the state the tokenizer is switched to
+    fn generic_text_element_parsing(&mut self, html_token: &HtmlToken, tokenizer_state: HTMLTokenizerState) {
+        // 1. Insert an HTML element for the token.
+        self.insert_an_html_element_for_token(html_token);
+
+        // 2. If the algorithm that was invoked is the generic raw text element parsing algorithm, switch the tokenizer to the RAWTEXT state; otherwise the algorithm invoked was the generic RCDATA element parsing algorithm, switch the tokenizer to the RCDATA state.
+        self.switch_the_tokenizer_to(tokenizer_state);
+
+        // 3. Set the original insertion mode to the current insertion mode.
+        self.original_insertion_mode = self.insertion_mode;
+
+        // 4. Then, switch the insertion mode to "text".
+        self.switch_to_insertion_mode(InsertionMode::Text);
+    }
+
+    // https://html.spec.whatwg.org/multipage/scripting.html#prepare-the-script-element
+    // Only parser-inserted scripts are prepared, so every script here is parser-inserted
+    // TODO: Only classic scripts are run, module scripts and import maps are not supported and are skipped
+    fn prepare_the_script_element(&mut self, script: &RefNode) {
+        let (src, has_async, has_defer) = match &script.borrow().data {
+            NodeData::Element(element) => (element.get_attribute("src").cloned(), element.has_attribute("async"), element.has_attribute("defer")),
+            _ => return
+        };
+
+        // 1. If el's already started is true, then return.
+        if self.fragment_root.is_some() {
+            return;
+        }
+
+        // 5. Let source text be el's child text content.
+        let source_text = child_text_content(script);
+
+        // 7. If el has no src attribute, and source text is the empty string, then return.
+        if src.is_none() && source_text.is_empty() {
+            return;
+        }
+
+        // 12. Otherwise, return. (No script is executed, and el's type is left as null.)
+        // A script of a type that is not a script (e.g. application/json or text/template) is a data block, its text is read with metadata::data_blocks instead
+        match script_type(script) {
+            Some(ScriptType::Classic) => (),
+            Some(ScriptType::Module) | Some(ScriptType::ImportMap) | None => return
+        }
+
+        // 30. If el has a nomodule content attribute and its type is "classic", then return.
+        // Module scripts are not supported, so a nomodule script runs as the fallback it is for, the way a browser without them runs it
+
+        // 21. If el's node document's scripting is disabled, then return. Scripting is disabled when the parser's scripting flag is false.
+        if !self.scripting_flag {
+            return;
+        }
+
+        // 31. If el has a src content attribute, then:
+        let prepared_script = match src {
+            Some(src) => {
+                // 2. Let src be the value of el's src attribute.
+                // 3. If src is the empty string, then queue an element task on the DOM manipulation task source given el to fire an event named error at el, and return.
+                // 5. Let url be the result of encoding-parsing a URL given src, relative to el's node document.
+                // 6. If url is failure, then queue an element task on the DOM manipulation task source given el to fire an event named error at el, and return.
+                let url = match node::encoding_parse_url(script, &src) {
+                    Ok(url) if !src.is_empty() => url,
+                    _ => {
+                        let script = Rc::clone(script);
+                        self.interpreter.event_loop.queue_task(Box::new(move |interpreter| {
+                            fire_an_event(interpreter, "error", &script, false, false);
+                        }));
+                        return;
+                    }
+                };
+
+                // 7. If el is potentially render-blocking, then block rendering on el.
+                // 8. Set el's delaying the load event to true.
+                // 11. Switch on el's type: "classic": Fetch a classic script given url, settings object, options, classic script CORS setting, encoding, and onComplete.
+                // https://html.spec.whatwg.org/multipage/webappapis.html#fetch-a-classic-script
+                // If response's type is "error", or response's status is not an ok status, then run onComplete given null
+                // TODO: The response is always decoded as UTF-8, the charset of its Content-Type and the script's charset attribute are not used
+                let source_text = match self.interpreter.load_resource(&url) {
+                    Ok(response) if (200..=299).contains(&response.status) => Some(String::from_utf8_lossy(&response.body).to_string()),
+                    _ => None
+                };
+                PreparedScript { element: Rc::clone(script), source_text, from_an_external_file: true, url: Some(url) }
+            },
+            // 32. If el does not have a src content attribute: let script be the result of creating a classic script using source text, settings object, base URL, and options.
+            None => PreparedScript { element: Rc::clone(script), source_text: Some(source_text), from_an_external_file: false, url: None }
+        };
+
+        // 33. If el's type is "classic" and el has a src attribute, or el's type is "module":
+        if prepared_script.from_an_external_file {
+            if has_defer && !has_async {
+                // 3. If el has a defer attribute and does not have an async attribute, then add el to the end of the list of scripts that will execute when the document has finished parsing associated with el's parser document.
+                self.list_of_scripts_that_will_execute_when_the_document_has_finished_parsing.push(prepared_script);
+            } else if !has_async {
+                // 4. Otherwise, if el is not parser-inserted, ...
+                // 5. Otherwise, set el's parser document's pending parsing-blocking script to el.
+                self.pending_parsing_blocking_script = Some(prepared_script);
+            } else {
+                // 2. If el has an async attribute, then add el to the set of scripts that will execute as soon as possible, and when the result is ready, execute the script element el.
+                self.interpreter.event_loop.queue_task(Box::new(move |interpreter| {
+                    execute_the_script_element(interpreter, &prepared_script);
+                }));
+            }
+            return;
+        }
+
+        // 34. If el does not have a src attribute:
+        // 2. Otherwise, immediately execute the script element el, even if other scripts are already executing.
+        // TODO: 1. If el is parser-inserted, and either the parser that created el is an XML parser or it's an HTML parser whose script nesting level is not greater than one,
+        //          and el's parser document has a style sheet that is blocking scripts, then set el's parser document's pending parsing-blocking script to el.
+        execute_the_script_element(&mut self.interpreter, &prepared_script);
+    }
+
+    // Whether the tokenizer has to stop tokenizing, e.g. while a nested invocation of the tokenizer is waiting for a script
+    pub(crate) fn is_paused(&self) -> bool {
+        return self.parser_pause_flag;
+    }
+
+    // The tree construction stage switches the tokenizer to the RCDATA, RAWTEXT, script data or PLAINTEXT state for the contents of elements like textarea, style, script and plaintext
+    // The tokenizer is in the middle of emitting the start tag, so the switch is held until it takes it
+    fn switch_the_tokenizer_to(&mut self, tokenizer_state: HTMLTokenizerState) {
+        self.pending_tokenizer_state = Some(tokenizer_state);
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#acknowledge-self-closing-flag
+    fn acknowledge_the_self_closing_flag(&mut self, html_token: &HtmlToken) {
+        if html_token.self_closing {
+            self.self_closing_flag_acknowledged = true;
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#tree-construction-dispatcher
+    // Whether a start tag is processed using the rules for parsing tokens in foreign content rather than the insertion mode
+    // TODO: The adjusted current node is the current node, as the context element of the fragment parsing algorithm is never foreign
+    fn is_in_foreign_content(&self, html_token: &HtmlToken) -> bool {
+        let adjusted_current_node = match self.stack_of_open_elements.last().and_then(|node| node.upgrade()) {
+            Some(adjusted_current_node) => adjusted_current_node,
+            None => return false
+        };
+        let (namespace, local_name) = match &adjusted_current_node.borrow().data {
+            NodeData::Element(element) => (element.namespace_uri().cloned(), element.local_name().to_string()),
+            _ => return false
+        };
+        // If the adjusted current node is a MathML text integration point and the token is a start tag whose tag name is neither "mglyph" nor "malignmark"
+        if namespace.as_deref() == Some(MATHML_NAMESPACE) && is_mathml_text_integration_point(&local_name) {
+            return matches!(html_token.tag_name.as_str(), "mglyph" | "malignmark");
+        }
+        // If the adjusted current node is a MathML annotation-xml element and the token is a start tag whose tag name is "svg"
+        if namespace.as_deref() == Some(MATHML_NAMESPACE) && local_name == "annotation-xml" && html_token.tag_name == "svg" {
+            return false;
+        }
+        return is_foreign_content_element(&adjusted_current_node);
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inforeign
+    fn parse_start_tag_in_foreign_content(&mut self, html_token: &HtmlToken) {
+        let tag_name = html_token.tag_name.as_str();
+        let is_breakout = matches!(tag_name, "b" | "big" | "blockquote" | "body" | "br" | "center" | "code" | "dd" | "div" | "dl" | "dt" | "em" | "embed"
+            | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "head" | "hr" | "i" | "img" | "li" | "listing" | "menu" | "meta" | "nobr" | "ol" | "p" | "pre"
+            | "ruby" | "s" | "small" | "span" | "strong" | "strike" | "sub" | "sup" | "table" | "tt" | "u" | "ul" | "var")
+            || (tag_name == "font" && html_token.attributes.iter().any(|attribute| matches!(attribute.name.as_str(), "color" | "face" | "size")));
+        if is_breakout {
+            // Parse error.
+            self.parse_error("HTML start tag in foreign content.");
+
+            // While the current node is not a MathML text integration point, an HTML integration point, or an element in the HTML namespace, pop elements from the stack of open elements.
+            while self.stack_of_open_elements.last().and_then(|node| node.upgrade()).is_some_and(|node| is_foreign_content_element(&node)) {
+                self.stack_of_open_elements.pop();
+            }
+
+            // Reprocess the token according to the rules given in the section corresponding to the current insertion mode in HTML content.
+            self.parse_html_token(html_token);
+            return;
+        }
+
+        // Any other start tag
+        // TODO: Adjust MathML attributes, adjust the tag names and attributes of SVG elements, and adjust foreign attributes for the token.
+        // Insert a foreign element for the token, with adjusted current node's namespace and false.
+        let namespace = self.stack_of_open_elements.last().and_then(|node| node.upgrade()).and_then(|node| match &node.borrow().data {
+            NodeData::Element(element) => element.namespace_uri().cloned(),
+            _ => None
+        });
+        self.insert_a_foreign_element_for_token(html_token, namespace.as_deref());
+
+        // If the token has its self-closing flag set, then run the appropriate steps from the following list:
+        // TODO: If the token's tag name is "script", and the new current node is in the SVG namespace, the script is not processed
+        // Otherwise: Pop the current node off the stack of open elements and acknowledge the token's self-closing flag.
+        if html_token.self_closing {
+            self.stack_of_open_elements.pop();
+            self.acknowledge_the_self_closing_flag(html_token);
+        }
+    }
+
+    // Called by the tokenizer after each token to find out whether the tree builder acknowledged its self-closing flag, and cleared for the next token
+    pub(crate) fn take_self_closing_flag_acknowledged(&mut self) -> bool {
+        return std::mem::take(&mut self.self_closing_flag_acknowledged);
+    }
+
+    // Called by the tokenizer after each token, the tree builder can ask it to switch state (e.g. to script data after a script start tag)
+    pub(crate) fn take_tokenizer_state(&mut self) -> Option<HTMLTokenizerState> {
+        return self.pending_tokenizer_state.take();
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#stop-parsing
+    // TODO: There is no event loop, so the queued tasks run right away, and there is no Window to fire load at
+    pub fn stop_parsing(&mut self) {
+        // 5. While the list of scripts that will execute when the document has finished parsing is not empty:
+        //        1. Spin the event loop until the first script in the list of scripts that will execute when the document has finished parsing has its ready to be parser-executed set to true
+        //           and the parser's Document has no style sheet that is blocking scripts.
+        //        2. Execute the script element given by the first script in the list of scripts that will execute when the document has finished parsing.
+        //        3. Remove the first script element from the list of scripts that will execute when the document has finished parsing (i.e. shift out the first entry in the list).
+        for script in std::mem::take(&mut self.list_of_scripts_that_will_execute_when_the_document_has_finished_parsing) {
+            execute_the_script_element(&mut self.interpreter, &script);
+        }
+
+        // 6. Queue a global task on the DOM manipulation task source given the Document's relevant global object to run the following substeps:
+        //        2. Fire an event named DOMContentLoaded at the Document object, with its bubbles attribute initialized to true.
+        fire_an_event(&mut self.interpreter, "DOMContentLoaded", &self.document, true, false);
+    }
+
+    // Runs the tasks the document's scripts have queued, until there are none left or the deadline is reached
+    pub fn run_event_loop(&mut self, deadline: Option<Instant>) {
+        self.interpreter.run_event_loop(deadline);
+    }
+
+    // Fires an event as if the user had caused it, at the first element matching the selector or at the document when there is none
+    pub fn fire_synthetic_event(&mut self, event_type: &str, selector: Option<&str>) -> Result<(), String> {
+        let target = match selector {
+            Some(selector) => {
+                let selector_list = match parse_selector_list(selector) {
+                    Some(selector_list) => selector_list,
+                    None => return Err(format!("'{}' is not a valid selector", selector))
+                };
+                match node::descendants(&self.document).into_iter().find(|descendant| selector_list.matches(descendant)) {
+                    Some(target) => target,
+                    None => return Err(format!("No element matches '{}'", selector))
+                }
+            },
+            None => Rc::clone(&self.document)
+        };
+
+        // Events caused by the user, such as click, bubble and can be canceled
+        self.interpreter.reset_script_budget();
+        fire_an_event(&mut self.interpreter, event_type, &target, true, true);
+        return Ok(());
+    }
+
+    // The size of the viewport the document's scripts see
+    pub fn set_viewport(&mut self, viewport: Viewport) {
+        self.interpreter.set_viewport(viewport);
+    }
+
+    // How much the document's scripts may do before they are stopped
+    pub fn set_script_limits(&mut self, script_limits: ScriptLimits) {
+        self.interpreter.set_script_limits(script_limits);
+    }
+
+    // Where the document's scripts and the resources they fetch are loaded from
+    pub fn set_resource_loader(&mut self, resource_loader: Box<dyn ResourceLoader>) {
+        self.interpreter.set_resource_loader(resource_loader);
+    }
+
+    // https://dom.spec.whatwg.org/#concept-document-url
+    pub fn set_document_url(&mut self, url: Url) {
+        if let NodeData::Document(document) = &mut self.document.borrow_mut().data {
+            document.url = url.clone();
+        }
+        self.interpreter.set_document_url(url);
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#scripting-flag
+    pub fn set_scripting_flag(&mut self, scripting_flag: bool) {
+        self.scripting_flag = scripting_flag;
+    }
+
+    pub(crate) fn scripting_flag(&self) -> bool {
+        return self.scripting_flag;
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#parse-errors
+    fn parse_error(&mut self, message: &str) {
+        if self.print_parse_errors {
+            println!("Parse Error: {}", message);
+        }
+        self.parse_errors.push(message.to_string());
+        self.parse_error_positions.push(self.current_token_position);
+    }
+
+    // The limit the input went over, None when the whole input was parsed
+    pub fn tree_limit_error(&self) -> Option<&TreeLimitError> {
+        return self.tree_limit_error.as_ref();
+    }
+
+    pub fn parse_errors(&self) -> &[String] {
+        return &self.parse_errors;
+    }
+
+    // The position of each of the parse errors, in the same order as the parse errors
+    pub fn parse_error_positions(&self) -> &[Option<SourcePosition>] {
+        return &self.parse_error_positions;
+    }
+
+    // https://dom.spec.whatwg.org/#concept-document-mode
+    fn set_document_mode(&mut self, mode: DocumentMode) {
+        if let NodeData::Document(document) = &mut self.document.borrow_mut().data {
+            document.mode = mode;
+        }
+    }
+
+    fn is_element_with_local_name(node: &WeakNode, local_name: &str) -> bool {
+        match node.upgrade() {
+            Some(node) => {
+                match &node.borrow().data {
+                    NodeData::Element(element) => element.local_name() == local_name,
+                    _ => false
+                }
+            },
+            None => false
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#insert-a-character
+    // The source location is that of the characters, the location of a Text node they are appended to is extended to the end of them
+    fn insert_a_character(&mut self, data: &str, source_location: Option<SourceLocation>) {
+        // 1. Let data be the characters passed to the algorithm, or, if no characters were explicitly specified, the character of the character token being processed
+        let character = data;
+
+        // 2. Let the adjusted insertion location be the appropriate place for inserting a node.
+        let adjusted_insertion_location = &self.appropriate_place_for_inserting_a_node(None);
+
+        // 3. If the adjusted insertion location is in a Document node, then return.
+        let insertion_parent = match adjusted_insertion_location.upgrade() {
+            Some(insertion_parent) => insertion_parent,
+            None => return
+        };
+        if let NodeType::DOCUMENT_NODE = insertion_parent.borrow().nodeType {
+            return;
+        }
+
+        // 4. If there is a Text node immediately before the adjusted insertion location, then append data to that Text node's data.
+        let last_child = insertion_parent.borrow().childNodes.last().cloned();
+        if let Some(last_child_node) = last_child {
+            let mut last_child = last_child_node.borrow_mut();
+            if let node::NodeData::Text(ref mut text) = last_child.data {
+                text.character_data.data.push_str(character);
+                last_child.source_location = match (last_child.source_location, source_location) {
+                    (Some(text_source_location), Some(source_location)) => Some(SourceLocation { start: text_source_location.start, end: source_location.end }),
+                    (text_source_location, source_location) => text_source_location.or(source_location)
+                };
+                drop(last_child);
+                invalidate_style(&last_child_node);
+                return;
+            }
+        }
+
+        // Otherwise, create a new Text node whose data is data and whose node document is the same as that of the element in which the adjusted insertion location finds itself,
+        // and insert the newly created node at the adjusted insertion location.
+        let text_node = self.create_text_node(character.to_string());
+        text_node.borrow_mut().source_location = source_location;
+        insertion_parent.borrow_mut().append_child(Rc::clone(&text_node));
+        invalidate_style(&text_node);
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#insert-a-comment
+    fn insert_a_comment(&mut self, data: &str, source_location: Option<SourceLocation>) {
+        // 2. Let adjusted insertion location be the appropriate place for inserting a node.
+        let adjusted_insertion_location = match self.appropriate_place_for_inserting_a_node(None).upgrade() {
+            Some(adjusted_insertion_location) => adjusted_insertion_location,
+            None => return
+        };
+
+        // 3. Create a Comment node whose data attribute is set to data and whose node document is the same as that of the node in which the adjusted insertion location finds itself.
+        // 4. Insert the newly created node at the adjusted insertion location.
+        let comment_node = create_comment_node(Some(data.to_string()), &adjusted_insertion_location, &self.document);
+        comment_node.borrow_mut().source_location = source_location;
+        adjusted_insertion_location.borrow_mut().append_child(comment_node);
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#insert-an-html-element
+    fn insert_an_html_element(&mut self, tag_name: Atom, attributes: &[TokenAttribute]) -> WeakNode {
+        // Insert a foreign element for the token, with the HTML namespace and false.
+        // HTML elements are created with no namespace
+        return self.insert_a_foreign_element(tag_name, attributes, None);
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#insert-a-foreign-element
+    fn insert_a_foreign_element_for_token(&mut self, html_token: &HtmlToken, namespace: Option<&str>) -> WeakNode {
+        let element = self.insert_a_foreign_element(html_token.tag_name.to_atom(), &html_token.attributes, namespace);
+        if let Some(element) = element.upgrade() {
+            set_source_locations_from_token(&element, html_token);
+        }
+        return element;
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#insert-an-html-element
+    // Inserts an element for a token from the input, which is given the places in the input of the token and of its attributes
+    fn insert_an_html_element_for_token(&mut self, html_token: &HtmlToken) -> WeakNode {
+        let element = self.insert_an_html_element(html_token.tag_name.to_atom(), &html_token.attributes);
+        if let Some(element) = element.upgrade() {
+            set_source_locations_from_token(&element, html_token);
+        }
+        return element;
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#current-node
+    // The document is at the bottom of the stack and is never popped, it stands in for the current node should the stack ever be empty
     fn current_node(&self) -> WeakNode {
-        return self.stack_of_open_elements[self.stack_of_open_elements.len() - 1].clone();
+        return match self.stack_of_open_elements.last() {
+            Some(current_node) => current_node.clone(),
+            None => Rc::downgrade(&self.document)
+        };
     }
 
     // https://html.spec.whatwg.org/multipage/parsing.html#appropriate-place-for-inserting-a-node
@@ -255,8 +1391,8 @@ impl HTMLDocumentParser {
         let mut target = self.current_node();
 
         // 1. If there was an override target specified, then let target be the override target.
-        if override_target.is_some() {
-            target = Rc::downgrade(override_target.unwrap());
+        if let Some(override_target) = override_target {
+            target = Rc::downgrade(override_target);
         }
 
         // TODO: 2. Determine the adjusted insertion location using the first matching steps from the following list:
@@ -268,14 +1404,28 @@ impl HTMLDocumentParser {
 
     // This can be used for non-foreign elements but I think the spec implies that the logic is shared for both foreign and non-foreign
     // https://html.spec.whatwg.org/multipage/parsing.html#insert-a-foreign-element
-    fn insert_a_foreign_element(&mut self, tag_name: String) -> WeakNode {
+    fn insert_a_foreign_element(&mut self, tag_name: Atom, attributes: &[TokenAttribute], namespace: Option<&str>) -> WeakNode {
+        // The document is at the bottom of the stack of open elements, so the element would be as deep as the stack is tall
+        // An element deeper than the maximum depth is not created at all, and the tree builder stops
+        if let Some(max_depth) = self.tree_limits.max_depth {
+            if self.stack_of_open_elements.len() > max_depth {
+                self.tree_limit_error = Some(TreeLimitError { limit: TreeLimit::Depth, maximum: max_depth, tag_name: Some(tag_name.to_string()), position: self.current_token_position });
+                return Weak::new();
+            }
+        }
+
         // 1. Let the adjustedInsertionLocation be the appropriate place for inserting a node.
         let adjusted_insertion_location = &self.appropriate_place_for_inserting_a_node(None);
 
         // 2. Let element be the result of creating an element for the token given token, namespace, and the element in which the adjustedInsertionLocation finds itself.
-        let element = self.create_element_node_for_token(tag_name);
+        let element = self.create_element_node_for_token(tag_name, attributes, namespace);
 
-        // TODO: 3. If onlyAddToElementStack is false, then run insert an element at the adjusted insertion location with element.
+        // 3. If onlyAddToElementStack is false, then run insert an element at the adjusted insertion location with element.
+        // A node that was removed from the document while it was on the stack of open elements (e.g. by a script) can be gone, the element is then not inserted anywhere
+        if let Some(adjusted_insertion_location) = adjusted_insertion_location.upgrade() {
+            adjusted_insertion_location.borrow_mut().append_child(Rc::clone(&element));
+            invalidate_style(&element);
+        }
 
         // 4. Push element onto the stack of open elements so that it is the new current node.
         self.stack_of_open_elements.push(Rc::downgrade(&element));
@@ -285,43 +1435,112 @@ impl HTMLDocumentParser {
     }
 
     fn switch_to_insertion_mode(&mut self, new_insertion_mode: InsertionMode) {
+        // The switch is recorded at the start of the token that caused it
+        #[cfg(feature = "trace")]
+        if let Some(trace) = &self.trace {
+            let event = TraceEvent::InsertionMode { from: format!("{:?}", self.insertion_mode), to: format!("{:?}", new_insertion_mode) };
+            trace.borrow_mut().record(event, self.current_token_position);
+        }
         self.insertion_mode = new_insertion_mode;
     }
 
+    // Records the insertion mode switches of the tree builder in trace from now on
+    #[cfg(feature = "trace")]
+    pub fn set_trace(&mut self, trace: SharedTrace) {
+        self.trace = Some(trace);
+    }
+
+    // Records the insertion mode a token is processed in and the stack of open elements it is processed with, a reprocessed token is recorded again in its new mode
+    #[cfg(feature = "trace")]
+    fn trace_tree_builder_step(&mut self, html_token: &HtmlToken) {
+        if let Some(trace) = &self.trace {
+            let open_elements = self.open_elements().iter().filter_map(|element| match &element.borrow().data {
+                NodeData::Element(element) => Some(element.local_name().to_string()),
+                _ => None
+            }).collect();
+            let event = TraceEvent::TreeBuilderStep { insertion_mode: format!("{:?}", self.insertion_mode), token: html_token.describe(), open_elements };
+            trace.borrow_mut().record(event, self.current_token_position);
+        }
+    }
+
+    #[cfg(not(feature = "trace"))]
+    fn trace_tree_builder_step(&mut self, _html_token: &HtmlToken) {}
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#insertion-mode
+    // The mode the next token is processed in, for debugging why a tree was built the way it was
+    pub fn insertion_mode(&self) -> InsertionMode {
+        return self.insertion_mode;
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#stack-of-open-elements
+    // The elements on the stack of open elements, the first one pushed first, without the document that stands in for the bottom of the stack
+    pub fn open_elements(&self) -> Vec<RefNode> {
+        return self.stack_of_open_elements.iter().filter_map(|element| element.upgrade()).filter(|element| !Rc::ptr_eq(element, &self.document)).collect();
+    }
+
+    // The document being parsed, e.g. to read its metadata once parsing has finished
+    pub fn document(&self) -> RefNode {
+        return Rc::clone(&self.document);
+    }
+
     pub fn print_document(&self) {
-        self.print_node(&self.document, 0);
+        print!("{}", self.document_tree());
+    }
+
+    // The nodes of the document as an indented outline, as print_document prints them
+    pub fn document_tree(&self) -> String {
+        let mut tree = String::new();
+        // The nodes still to write with their depths, a stack rather than recursion so a deeply nested document cannot overflow the call stack
+        let mut nodes = vec![(Rc::clone(&self.document), 0)];
+        while let Some((node, depth)) = nodes.pop() {
+            self.write_node(&node, depth, &mut tree);
+            nodes.extend(node.borrow().childNodes.iter().rev().map(|child| (Rc::clone(child), depth + 1)));
+        }
+        return tree;
+    }
+
+    pub fn render_as_text(&self, columns: usize) -> String {
+        return crate::text_renderer::render_as_text(&self.document, columns);
+    }
+
+    pub fn extract_text(&self, options: &crate::text_renderer::ExtractTextOptions) -> String {
+        return crate::text_renderer::extract_text(&self.document, options);
+    }
+
+    pub fn accessibility_tree_json(&self) -> String {
+        return crate::a11y::build_accessibility_tree(&self.document).map(|tree| tree.to_json()).unwrap_or_else(|| "null".to_string());
+    }
+
+    pub fn document_stats(&self) -> crate::document_stats::DocumentStats {
+        return crate::document_stats::document_stats(&self.document);
     }
 
-    fn print_node(&self, node: &RefNode, depth: usize) {
+    // The lines of node itself, without its children
+    fn write_node(&self, node: &RefNode, depth: usize, tree: &mut String) {
         let indent = "  ".repeat(depth);
 
         let node_ref = node.borrow();
 
-        println!("{}- {:?}", indent, node_ref.nodeType);
+        tree.push_str(&format!("{}- {:?}\n", indent, node_ref.nodeType));
 
         if let Some(parent_weak) = &node_ref.parentNode {
             if let Some(parent) = parent_weak.upgrade() {
                 let parent_ref = parent.borrow();
-                println!("{}    Parent Node Type: {:?}", indent, parent_ref.nodeType);
+                tree.push_str(&format!("{}    Parent Node Type: {:?}\n", indent, parent_ref.nodeType));
             }
         }
         
         if let Some(owner_weak) = &node_ref.ownerDocument {
             if let Some(owner) = owner_weak.upgrade() {
                 let owner_ref = owner.borrow();
-                println!("{}    Owner Document Node Type: {:?}", indent, owner_ref.nodeType);
+                tree.push_str(&format!("{}    Owner Document Node Type: {:?}\n", indent, owner_ref.nodeType));
             }
         }
-
-        // Recursively print all child nodes
-        for child in &node_ref.childNodes {
-            self.print_node(child, depth + 1);
-        }
     }
 
     // https://html.spec.whatwg.org/multipage/parsing.html#create-an-element-for-the-token
-    pub fn create_element_node_for_token(&self, tag_name: DOMString) -> RefNode {
-        // TODO: Only steps 3, 4 and 10 are done.
+    pub fn create_element_node_for_token(&self, tag_name: Atom, attributes: &[TokenAttribute], namespace: Option<&str>) -> RefNode {
+        // TODO: Only steps 3, 4, 10 and 12 are done.
 
         // 3. Let document be intendedParent's node document.
         let document = Rc::downgrade(&self.document);
@@ -331,13 +1550,23 @@ impl HTMLDocumentParser {
 
 
         // 10. Let element be the result of creating an element given document, localName, namespace, null, is, willExecuteScript, and registry.
-        let element_node = self.create_element(document, localName, None, None, None, false);
+        let element_node = self.create_element(document, localName, namespace.map(str::to_string), None, None, false);
+
+        // 12. Append each attribute in the given token to element.
+        // The attributes are appended in the order they are in the input, into a list that is allocated once for all of them
+        if let NodeData::Element(ref mut element) = element_node.borrow_mut().data {
+            element.reserve_attributes(attributes.len());
+            for attribute in attributes {
+                element.append_attribute(attribute.name.to_atom(), attribute.value.to_string());
+            }
+        }
+
         return element_node;
     }
 
     // https://dom.spec.whatwg.org/#concept-create-element
     // TODO: Add 'registry' param for CustomElementRegistry object
-    pub fn create_element(&self, document: WeakNode, local_name: DOMString, namespace: Option<String>, prefix: Option<String>, is: Option<String>, synchronous_custom_elements : bool) -> RefNode {
+    pub fn create_element(&self, document: WeakNode, local_name: Atom, namespace: Option<String>, prefix: Option<String>, is: Option<String>, synchronous_custom_elements : bool) -> RefNode {
         // 1. Let result be null
 
         // TODO: 2. If registry is "default", then set registry to the result of looking up a custom element registry given document.
@@ -353,7 +1582,8 @@ impl HTMLDocumentParser {
         // 1. Let interface be the element interface for localName and namespace.
 
         // Partial TODO: 2. Set result to the result of creating an element internal given document, interface, localName, namespace, prefix, "uncustomized", is, and registry.
-        let element_node = create_ref_node(NodeData::Element(Element::new(local_name)), NodeType::ELEMENT_NODE);
+        let element_node = create_ref_node(NodeData::Element(Element::new_in_namespace(local_name, namespace)), NodeType::ELEMENT_NODE);
+        self.created_nodes.set(self.created_nodes.get() + 1);
         element_node.borrow_mut().ownerDocument = Some(document);
         element_node.borrow_mut().parentNode = Some(self.appropriate_place_for_inserting_a_node(None));
 
@@ -363,6 +1593,7 @@ impl HTMLDocumentParser {
 
     pub fn create_text_node(&self, data: DOMString) -> RefNode {
         let text_node =  create_ref_node(NodeData::Text(Text::new(Some(data))), NodeType::TEXT_NODE);
+        self.created_nodes.set(self.created_nodes.get() + 1);
 
         let document = Rc::downgrade(&self.document);
         text_node.borrow_mut().ownerDocument = Some(document);
@@ -382,6 +1613,181 @@ pub fn create_comment_node(data: Option<DOMString>, parent_node: &RefNode, owner
     return comment_node;
 }
 
+// A comment node for a comment token, which is where the token is in the input
+fn create_comment_node_for_token(html_token: &HtmlToken, parent_node: &RefNode, owner_document: &RefNode) -> RefNode {
+    let comment_node = create_comment_node(Some(html_token.data.to_string()), parent_node, owner_document);
+    comment_node.borrow_mut().source_location = html_token.source_location;
+
+    return comment_node;
+}
+
+// Gives an element created for a start tag token the places in the input of the token and of each of its attributes
+fn set_source_locations_from_token(element_node: &RefNode, html_token: &HtmlToken) {
+    let mut element_node = element_node.borrow_mut();
+    element_node.source_location = html_token.source_location;
+    if let NodeData::Element(ref mut element) = element_node.data {
+        for attribute in &html_token.attributes {
+            element.set_attribute_source_location(&attribute.name, attribute.source_location);
+        }
+    }
+}
+
+// The tree construction stage is the sink of the tokenizer unless it is given another one
+impl TokenSink for HTMLDocumentParser {
+    fn handle_token(&mut self, html_token: &HtmlToken) -> TokenSinkResult {
+        if self.tree_limit_error.is_some() {
+            return TokenSinkResult::Continue;
+        }
+        // The attributes of a start tag are checked before its element is created
+        let position = html_token.source_location.map(|source_location| source_location.start);
+        if let Some(error) = self.tree_limits.check_attributes(html_token, position) {
+            self.tree_limit_error = Some(error);
+            return TokenSinkResult::Continue;
+        }
+        // A comment token always becomes a node, and a DOCTYPE token only does in the initial insertion mode
+        if matches!((&html_token.token_type, self.insertion_mode), (HtmlTokenType::Comment, _) | (HtmlTokenType::DocType, InsertionMode::Initial)) {
+            self.created_nodes.set(self.created_nodes.get() + 1);
+        }
+
+        self.parse_html_token(html_token);
+
+        if let Some(max_nodes) = self.tree_limits.max_nodes {
+            if self.created_nodes.get() > max_nodes && self.tree_limit_error.is_none() {
+                self.tree_limit_error = Some(TreeLimitError { limit: TreeLimit::Nodes, maximum: max_nodes, tag_name: None, position });
+            }
+        }
+        return TokenSinkResult::from_tokenizer_state(self.take_tokenizer_state());
+    }
+
+    fn end(&mut self) {
+        self.stop_parsing();
+    }
+
+    fn parse_error(&mut self, parse_error: &ParseError, position: SourcePosition) {
+        if self.print_parse_errors {
+            println!("[HTML::Tokenizer] Parse error found '{}'", parse_error);
+        }
+        self.parse_errors.push(parse_error.to_string());
+        self.parse_error_positions.push(Some(position));
+    }
+
+    fn is_paused(&self) -> bool {
+        return HTMLDocumentParser::is_paused(self);
+    }
+
+    fn take_self_closing_flag_acknowledged(&mut self) -> bool {
+        return HTMLDocumentParser::take_self_closing_flag_acknowledged(self);
+    }
+}
+
+// https://dom.spec.whatwg.org/#dom-document-createelement
+// Creates an element with no parent, used when scripts create elements
+pub fn create_element_node(local_name: DOMString, owner_document: &RefNode) -> RefNode {
+    let element_node = create_ref_node(NodeData::Element(Element::new(Atom::from(local_name))), NodeType::ELEMENT_NODE);
+    element_node.borrow_mut().ownerDocument = Some(Rc::downgrade(owner_document));
+
+    return element_node;
+}
+
+// https://html.spec.whatwg.org/multipage/parsing.html#parsing-html-fragments
+// The state the tokenizer is in for the contents of an element whose contents are text, e.g. RCDATA for a textarea, None for an element whose contents are markup
+pub(crate) fn tokenizer_state_for_text_element(local_name: &str, scripting_flag: bool) -> Option<HTMLTokenizerState> {
+    return match local_name {
+        "title" | "textarea" => Some(HTMLTokenizerState::RCData),
+        "style" | "xmp" | "iframe" | "noembed" | "noframes" => Some(HTMLTokenizerState::RawText),
+        "script" => Some(HTMLTokenizerState::ScriptData),
+        "noscript" if scripting_flag => Some(HTMLTokenizerState::RawText),
+        "plaintext" => Some(HTMLTokenizerState::PlainText),
+        _ => None
+    };
+}
+
+// https://html.spec.whatwg.org/multipage/parsing.html#the-initial-insertion-mode
+// The mode a DOCTYPE token puts the Document in, the public and system identifiers are compared ASCII case-insensitively
+fn document_mode_for_doctype(html_token: &HtmlToken) -> DocumentMode {
+    const QUIRKS_PUBLIC_IDENTIFIER_PREFIXES: [&str; 55] = [
+        "+//silmaril//dtd html pro v0r11 19970101//",
+        "-//as//dtd html 3.0 aswedit + extensions//",
+        "-//advasoft ltd//dtd html 3.0 aswedit + extensions//",
+        "-//ietf//dtd html 2.0 level 1//",
+        "-//ietf//dtd html 2.0 level 2//",
+        "-//ietf//dtd html 2.0 strict level 1//",
+        "-//ietf//dtd html 2.0 strict level 2//",
+        "-//ietf//dtd html 2.0 strict//",
+        "-//ietf//dtd html 2.0//",
+        "-//ietf//dtd html 2.1e//",
+        "-//ietf//dtd html 3.0//",
+        "-//ietf//dtd html 3.2 final//",
+        "-//ietf//dtd html 3.2//",
+        "-//ietf//dtd html 3//",
+        "-//ietf//dtd html level 0//",
+        "-//ietf//dtd html level 1//",
+        "-//ietf//dtd html level 2//",
+        "-//ietf//dtd html level 3//",
+        "-//ietf//dtd html strict level 0//",
+        "-//ietf//dtd html strict level 1//",
+        "-//ietf//dtd html strict level 2//",
+        "-//ietf//dtd html strict level 3//",
+        "-//ietf//dtd html strict//",
+        "-//ietf//dtd html//",
+        "-//metrius//dtd metrius presentational//",
+        "-//microsoft//dtd internet explorer 2.0 html strict//",
+        "-//microsoft//dtd internet explorer 2.0 html//",
+        "-//microsoft//dtd internet explorer 2.0 tables//",
+        "-//microsoft//dtd internet explorer 3.0 html strict//",
+        "-//microsoft//dtd internet explorer 3.0 html//",
+        "-//microsoft//dtd internet explorer 3.0 tables//",
+        "-//netscape comm. corp.//dtd html//",
+        "-//netscape comm. corp.//dtd strict html//",
+        "-//o'reilly and associates//dtd html 2.0//",
+        "-//o'reilly and associates//dtd html extended 1.0//",
+        "-//o'reilly and associates//dtd html extended relaxed 1.0//",
+        "-//sq//dtd html 2.0 hotmetal + extensions//",
+        "-//softquad software//dtd hotmetal pro 6.0::19990601::extensions to html 4.0//",
+        "-//softquad//dtd hotmetal pro 4.0::19971010::extensions to html 4.0//",
+        "-//spyglass//dtd html 2.0 extended//",
+        "-//sun microsystems corp.//dtd hotjava html//",
+        "-//sun microsystems corp.//dtd hotjava strict html//",
+        "-//w3c//dtd html 3 1995-03-24//",
+        "-//w3c//dtd html 3.2 draft//",
+        "-//w3c//dtd html 3.2 final//",
+        "-//w3c//dtd html 3.2//",
+        "-//w3c//dtd html 3.2s draft//",
+        "-//w3c//dtd html 4.0 frameset//",
+        "-//w3c//dtd html 4.0 transitional//",
+        "-//w3c//dtd html experimental 19960712//",
+        "-//w3c//dtd html experimental 970421//",
+        "-//w3c//dtd w3 html//",
+        "-//w3o//dtd w3 html 3.0//",
+        "-//webtechs//dtd mozilla html 2.0//",
+        "-//webtechs//dtd mozilla html//",
+    ];
+
+    let public_identifier = ascii_lowercase(&html_token.public_identifier);
+    let system_identifier = ascii_lowercase(&html_token.system_identifier);
+    // TODO: A missing identifier and an empty one are the same in the token, so a DOCTYPE with an empty system identifier is treated as if it had none
+    let system_identifier_is_missing = html_token.system_identifier.is_empty();
+
+    // The force-quirks flag is set to on, or the name is not "html", or the public identifier is set to one of the quirky ones, or the system identifier is set to the IBM one, or the public identifier starts with one of the quirky prefixes
+    if html_token.force_quirks
+        || html_token.name != "html"
+        || matches!(public_identifier.as_ref(), "-//w3o//dtd w3 html strict 3.0//en//" | "-/w3c/dtd html 4.0 transitional/en" | "html")
+        || system_identifier == "http://www.ibm.com/data/dtd/v11/ibmxhtml1-transitional.dtd"
+        || QUIRKS_PUBLIC_IDENTIFIER_PREFIXES.iter().any(|prefix| public_identifier.starts_with(prefix))
+        || (system_identifier_is_missing && (public_identifier.starts_with("-//w3c//dtd html 4.01 frameset//") || public_identifier.starts_with("-//w3c//dtd html 4.01 transitional//"))) {
+        return DocumentMode::Quirks;
+    }
+
+    // The public identifier starts with one of the XHTML 1.0 prefixes, or with one of the HTML 4.01 ones when the system identifier is not missing
+    if public_identifier.starts_with("-//w3c//dtd xhtml 1.0 frameset//")
+        || public_identifier.starts_with("-//w3c//dtd xhtml 1.0 transitional//")
+        || (!system_identifier_is_missing && (public_identifier.starts_with("-//w3c//dtd html 4.01 frameset//") || public_identifier.starts_with("-//w3c//dtd html 4.01 transitional//"))) {
+        return DocumentMode::LimitedQuirks;
+    }
+
+    return DocumentMode::NoQuirks;
+}
+
 pub fn create_document_node() -> RefNode {
     return create_ref_node(NodeData::Document(Document::new()), NodeType::DOCUMENT_NODE)
 }
@@ -390,3 +1796,122 @@ pub fn create_document_type_node(name: DOMString, public_id: DOMString, system_i
     return create_ref_node(NodeData::DocumentType(DocumentType::new(name, public_id, system_id)), NodeType::DOCUMENT_TYPE_NODE)
 }
 
+// https://dom.spec.whatwg.org/#concept-child-text-content
+fn child_text_content(node: &RefNode) -> DOMString {
+    let mut text_content = String::from("");
+
+    for child in &node.borrow().childNodes {
+        if let NodeData::Text(ref text) = child.borrow().data {
+            text_content.push_str(&text.character_data.data);
+        }
+    }
+
+    return text_content;
+}
+
+// Character tokens carry a run of characters, so a run is only whitespace if every character in it is
+// A run that starts with whitespace is split by the parser in the insertion modes where whitespace is processed differently, see split_leading_whitespace
+// https://html.spec.whatwg.org/multipage/parsing.html#mathml-text-integration-point
+fn is_mathml_text_integration_point(local_name: &str) -> bool {
+    return matches!(local_name, "mi" | "mo" | "mn" | "ms" | "mtext");
+}
+
+// An element in the MathML or SVG namespace that is neither a MathML text integration point nor an HTML integration point, whose children are foreign content
+// https://html.spec.whatwg.org/multipage/parsing.html#html-integration-point
+fn is_foreign_content_element(node: &RefNode) -> bool {
+    return match &node.borrow().data {
+        NodeData::Element(element) => {
+            let local_name = element.local_name().to_string();
+            match element.namespace_uri().map(String::as_str) {
+                // A MathML annotation-xml element whose encoding attribute is text/html or application/xhtml+xml is an HTML integration point
+                Some(MATHML_NAMESPACE) if local_name == "annotation-xml" => !element.get_attribute("encoding")
+                    .is_some_and(|encoding| is_ascii_case_insensitive_match(encoding, "text/html") || is_ascii_case_insensitive_match(encoding, "application/xhtml+xml")),
+                Some(MATHML_NAMESPACE) => !is_mathml_text_integration_point(&local_name),
+                // An SVG foreignObject, desc or title element is an HTML integration point, its tag name was lowercased by the tokenizer and is not adjusted
+                Some(SVG_NAMESPACE) => !["foreignObject", "desc", "title"].iter().any(|name| name.eq_ignore_ascii_case(&local_name)),
+                _ => false
+            }
+        },
+        _ => false
+    };
+}
+
+fn is_whitespace_character_token(html_token: &HtmlToken) -> bool {
+    html_token.data.chars().all(|character| matches!(character, '\u{0009}' | '\u{000A}' | '\u{000C}' | '\u{000D}' | '\u{0020}'))
+}
+
+// https://html.spec.whatwg.org/multipage/scripting.html#concept-script-type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptType {
+    Classic,
+    Module,
+    ImportMap,
+}
+
+// https://html.spec.whatwg.org/multipage/scripting.html#prepare-the-script-element
+// The type of a script element from its type and language attributes, None for a data block, which is never run
+pub fn script_type(script: &RefNode) -> Option<ScriptType> {
+    let (type_attribute, language_attribute) = match &script.borrow().data {
+        NodeData::Element(element) => (element.get_attribute("type").cloned(), element.get_attribute("language").cloned()),
+        _ => return None
+    };
+
+    // 8. Determine the script's type as follows:
+    let the_script_block_type_string = match (type_attribute, language_attribute) {
+        // If el has a type attribute whose value is the empty string, or el has no type attribute but it has a language attribute and that attribute's value is the empty string,
+        // or el has neither a type attribute nor a language attribute, then let the script block's type string for this script element be "text/javascript".
+        (Some(type_attribute), _) if type_attribute.is_empty() => String::from("text/javascript"),
+        (None, Some(language_attribute)) if language_attribute.is_empty() => String::from("text/javascript"),
+        (None, None) => String::from("text/javascript"),
+        // If el has a type attribute, then let the script block's type string be the value of that attribute with leading and trailing ASCII whitespace stripped.
+        (Some(type_attribute), _) => type_attribute.trim_matches(is_ascii_whitespace).to_string(),
+        // Otherwise, el has a non-empty language attribute; let the script block's type string be the concatenation of "text/" and the value of el's language attribute.
+        (None, Some(language_attribute)) => format!("text/{}", language_attribute)
+    };
+
+    // 9. If the script block's type string is a JavaScript MIME type essence match, then set el's type to "classic".
+    if is_javascript_mime_type_essence_match(&the_script_block_type_string) {
+        return Some(ScriptType::Classic);
+    }
+    // 10. Otherwise, if the script block's type string is an ASCII case-insensitive match for the string "module", then set el's type to "module".
+    if is_ascii_case_insensitive_match(&the_script_block_type_string, "module") {
+        return Some(ScriptType::Module);
+    }
+    // 11. Otherwise, if the script block's type string is an ASCII case-insensitive match for the string "importmap", then set el's type to "importmap".
+    if is_ascii_case_insensitive_match(&the_script_block_type_string, "importmap") {
+        return Some(ScriptType::ImportMap);
+    }
+    return None;
+}
+
+// https://mimesniff.spec.whatwg.org/#javascript-mime-type-essence-match
+// Parameters are not allowed, e.g. text/javascript;charset=utf-8 is not a match
+fn is_javascript_mime_type_essence_match(string: &str) -> bool {
+    return [
+        "application/ecmascript", "application/javascript", "application/x-ecmascript", "application/x-javascript", "text/ecmascript", "text/javascript",
+        "text/javascript1.0", "text/javascript1.1", "text/javascript1.2", "text/javascript1.3", "text/javascript1.4", "text/javascript1.5", "text/jscript",
+        "text/livescript", "text/x-ecmascript", "text/x-javascript"
+    ].iter().any(|essence| is_ascii_case_insensitive_match(string, essence));
+}
+
+// https://html.spec.whatwg.org/multipage/scripting.html#execute-the-script-element
+// TODO: document.currentScript is not set while the script runs
+fn execute_the_script_element(interpreter: &mut Interpreter, script: &PreparedScript) {
+    // 4. If el's result is null, then fire an event named error at el, and return.
+    let source_text = match &script.source_text {
+        Some(source_text) => source_text.clone(),
+        None => {
+            fire_an_event(interpreter, "error", &script.element, false, false);
+            return;
+        }
+    };
+
+    // 6. Switch on el's type: "classic": Run the classic script given by el's result.
+    // https://html.spec.whatwg.org/multipage/webappapis.html#run-a-classic-script
+    interpreter.run_script(source_text, script.url.as_ref());
+
+    // 8. If el's from an external file is true, then fire an event named load at el.
+    if script.from_an_external_file {
+        fire_an_event(interpreter, "load", &script.element, false, false);
+    }
+}
@@ -1,6 +1,8 @@
-use std::collections::HashMap;
 use std::fmt;
 
+use crate::source_location::SourceLocation;
+use crate::token_text::TokenText;
+
 #[derive(Clone)]
 pub enum HtmlTokenType { 
     DocType,
@@ -20,18 +22,58 @@ pub struct HtmlToken {
     pub system_identifier: String,
     pub force_quirks: bool,
 
-    pub tag_name: String,
+    // The tag name, attributes and data are mostly ranges of the input rather than copies of it
+    pub tag_name: TokenText,
     pub self_closing: bool,
-    pub attributes: HashMap<String, String>,
+    // The attributes in the order they are in the input, a tag has few enough of them that finding one by name is a linear search
+    pub attributes: Vec<TokenAttribute>,
+
+    pub data: TokenText,
 
-    pub data: String
+    // Where the token is in the input, None for the end of file token
+    pub source_location: Option<SourceLocation>,
+}
+
+// https://html.spec.whatwg.org/multipage/parsing.html#tokenization
+// Start and end tag tokens have a list of attributes, each of which has a name and a value
+#[derive(Clone)]
+pub struct TokenAttribute {
+    pub name: TokenText,
+    pub value: TokenText,
+    // Where the attribute is in the input, from the start of its name to the end of its value
+    pub source_location: SourceLocation,
 }
 
 impl HtmlToken { 
+    pub fn attribute(&self, name: &str) -> Option<&TokenAttribute> {
+        return self.attributes.iter().find(|attribute| attribute.name == name);
+    }
+
+    // A line describing the token, as the tokens command prints it, e.g. StartTag a href="/"
+    pub fn describe(&self) -> String {
+        return match self.token_type {
+            HtmlTokenType::DocType => format!("DOCTYPE {}", self.name),
+            HtmlTokenType::StartTag => {
+                let mut description = format!("StartTag {}", self.tag_name);
+                for attribute in &self.attributes {
+                    description.push_str(&format!(" {}={:?}", attribute.name, attribute.value.as_str()));
+                }
+                if self.self_closing {
+                    description.push_str(" /");
+                }
+                description
+            },
+            HtmlTokenType::EndTag => format!("EndTag {}", self.tag_name),
+            HtmlTokenType::Comment => format!("Comment {:?}", self.data.as_str()),
+            HtmlTokenType::Character => format!("Character {:?}", self.data.as_str()),
+            HtmlTokenType::EndOfFile => "EndOfFile".to_string()
+        };
+    }
+
     fn attributes_to_string(&self) -> String { 
         let mut attributes_string = String::from("");
-        for (name, value) in self.attributes.iter() { 
-            let s = format!("  Name: {}\n    Value: {}\n", name, value);
+        for attribute in self.attributes.iter() { 
+            let s = format!("  Name: {}\n    Value: {}\n", attribute.name, attribute.value);
             attributes_string.push_str(&s);
         }
 
@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use crate::resource_loader::{ResourceLoader, Response};
+use crate::url::Url;
+
+// https://httpwg.org/specs/rfc9111.html
+// A private HTTP cache in front of a resource loader, so a resource that has not changed is not downloaded again
+// Fresh responses are answered from the cache, stale ones are revalidated with If-None-Match and If-Modified-Since
+// TODO: Vary is ignored, as every request is sent with the same headers, and stale responses are never served when the origin can not be reached
+
+// A response as it was stored, with when it was stored in seconds since the Unix epoch
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub response: Response,
+    pub stored_at: u64,
+}
+
+// Where the cache keeps its entries, keyed by the URL of the response
+pub trait CacheStorage {
+    fn get(&mut self, key: &str) -> Option<CacheEntry>;
+    fn put(&mut self, key: &str, entry: &CacheEntry);
+    fn remove(&mut self, key: &str);
+}
+
+// Keeps the entries for as long as the storage lives, e.g. the subresources of one document
+#[derive(Default)]
+pub struct MemoryCacheStorage {
+    entries: HashMap<String, CacheEntry>,
+}
+
+impl MemoryCacheStorage {
+    pub fn new() -> MemoryCacheStorage {
+        MemoryCacheStorage { entries: HashMap::new() }
+    }
+}
+
+impl CacheStorage for MemoryCacheStorage {
+    fn get(&mut self, key: &str) -> Option<CacheEntry> {
+        return self.entries.get(key).cloned();
+    }
+
+    fn put(&mut self, key: &str, entry: &CacheEntry) {
+        self.entries.insert(key.to_string(), entry.clone());
+    }
+
+    fn remove(&mut self, key: &str) {
+        self.entries.remove(key);
+    }
+}
+
+// The stored response without its body, which is kept in a file of its own
+#[derive(Serialize, Deserialize)]
+struct StoredHead {
+    key: String,
+    url: String,
+    status: u16,
+    status_message: String,
+    header_list: Vec<(String, String)>,
+    stored_at: u64,
+}
+
+// Keeps the entries in a directory so they outlive the process, e.g. across runs of the CLI
+// Each entry is a .json file with the status and headers and a .body file with the body, named after a hash of the key
+// A file that can not be read or written is treated as a missing entry, the cache is never the reason a load fails
+pub struct DirectoryCacheStorage {
+    directory: PathBuf,
+}
+
+impl DirectoryCacheStorage {
+    // The directory is created when the first entry is stored
+    pub fn new(directory: impl Into<PathBuf>) -> DirectoryCacheStorage {
+        DirectoryCacheStorage { directory: directory.into() }
+    }
+
+    fn paths(&self, key: &str) -> (PathBuf, PathBuf) {
+        let name = format!("{:016x}", fnv1a(key.as_bytes()));
+        return (self.directory.join(format!("{}.json", name)), self.directory.join(format!("{}.body", name)));
+    }
+}
+
+// The 64 bit FNV-1a hash, which unlike DefaultHasher is the same in every build, so the file names stay the same
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    return hash;
+}
+
+impl CacheStorage for DirectoryCacheStorage {
+    fn get(&mut self, key: &str) -> Option<CacheEntry> {
+        let (head_path, body_path) = self.paths(key);
+        let head: StoredHead = serde_json::from_slice(&std::fs::read(head_path).ok()?).ok()?;
+        // Two keys with the same hash share the files, the key in the head says whose they are
+        if head.key != key {
+            return None;
+        }
+        let body = std::fs::read(body_path).ok()?;
+        let response = Response { url: head.url, status: head.status, status_message: head.status_message, header_list: head.header_list, body };
+        return Some(CacheEntry { response, stored_at: head.stored_at });
+    }
+
+    fn put(&mut self, key: &str, entry: &CacheEntry) {
+        if std::fs::create_dir_all(&self.directory).is_err() {
+            return;
+        }
+        let (head_path, body_path) = self.paths(key);
+        let response = &entry.response;
+        let head = StoredHead {
+            key: key.to_string(),
+            url: response.url.clone(),
+            status: response.status,
+            status_message: response.status_message.clone(),
+            header_list: response.header_list.clone(),
+            stored_at: entry.stored_at,
+        };
+        // The body is written first, so a head is never read with the body of an older response
+        if std::fs::write(body_path, &response.body).is_ok() {
+            if let Ok(json) = serde_json::to_vec(&head) {
+                let _ = std::fs::write(head_path, json);
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        let (head_path, body_path) = self.paths(key);
+        let _ = std::fs::remove_file(head_path);
+        let _ = std::fs::remove_file(body_path);
+    }
+}
+
+// https://httpwg.org/specs/rfc9111.html#cache-response-directive
+// The directives of a Cache-Control header that the cache acts on
+#[derive(Debug, Default, PartialEq)]
+struct CacheControl {
+    no_store: bool,
+    no_cache: bool,
+    max_age: Option<u64>,
+}
+
+fn cache_control(response: &Response) -> CacheControl {
+    let mut cache_control = CacheControl::default();
+    for value in response.header_list.iter().filter(|(name, _)| name == "cache-control").map(|(_, value)| value) {
+        for directive in value.split(',') {
+            let (name, argument) = match directive.split_once('=') {
+                Some((name, argument)) => (name.trim(), Some(argument.trim().trim_matches('"'))),
+                None => (directive.trim(), None)
+            };
+            match name.to_ascii_lowercase().as_str() {
+                "no-store" => cache_control.no_store = true,
+                // A no-cache with field names only applies to those fields, but the whole response is revalidated here
+                "no-cache" => cache_control.no_cache = true,
+                // When there is more than one max-age, the first one is used
+                "max-age" => cache_control.max_age = cache_control.max_age.or_else(|| argument.and_then(|argument| argument.parse::<u64>().ok())),
+                _ => ()
+            }
+        }
+    }
+    return cache_control;
+}
+
+fn header<'a>(response: &'a Response, name: &str) -> Option<&'a str> {
+    return response.header_list.iter().find(|(header_name, _)| header_name == name).map(|(_, value)| value.as_str());
+}
+
+// https://httpwg.org/specs/rfc9110.html#http.date
+// The seconds since the Unix epoch of an IMF-fixdate, e.g. Sun, 06 Nov 1994 08:49:37 GMT
+// TODO: The obsolete RFC 850 and asctime formats are not parsed, such dates are treated as invalid
+pub fn parse_http_date(date: &str) -> Option<u64> {
+    let mut parts = date.trim().split_ascii_whitespace();
+    let (_day_name, day, month, year, time, zone) = (parts.next()?, parts.next()?, parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+    if zone != "GMT" || parts.next().is_some() {
+        return None;
+    }
+    let day = day.parse::<u64>().ok().filter(|day| (1..=31).contains(day))?;
+    let month = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"].iter().position(|name| *name == month)? as u64 + 1;
+    let year = year.parse::<u64>().ok().filter(|year| *year >= 1970)?;
+    let mut time = time.split(':').map(|part| part.parse::<u64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    // The days since the epoch of a date in the proleptic Gregorian calendar, counted from March so the leap day is at the end of the year
+    let (year, month) = if month <= 2 { (year - 1, month + 9) } else { (year, month - 3) };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146097 + day_of_era).checked_sub(719468)?;
+    return Some(days * 86400 + hour * 3600 + minute * 60 + second);
+}
+
+// https://httpwg.org/specs/rfc9111.html#heuristic.freshness
+// The statuses whose responses can be cached without explicit freshness information
+fn is_heuristically_cacheable(status: u16) -> bool {
+    return matches!(status, 200 | 203 | 204 | 206 | 300 | 301 | 308 | 404 | 405 | 410 | 414 | 501);
+}
+
+// https://httpwg.org/specs/rfc9111.html#calculating.freshness.lifetime
+// How many seconds the response is fresh for after it was generated, 0 when it has to be revalidated every time
+fn freshness_lifetime(response: &Response) -> u64 {
+    // If the cache is private and the max-age response directive is present, use its value
+    if let Some(max_age) = cache_control(response).max_age {
+        return max_age;
+    }
+    // If the Expires response header field is present, use its value minus the value of the Date response header field
+    // A cache recipient must interpret invalid date formats, especially the value "0", as representing a time in the past
+    let date = header(response, "date").and_then(parse_http_date);
+    if let Some(expires) = header(response, "expires") {
+        return match (parse_http_date(expires), date) {
+            (Some(expires), Some(date)) => expires.saturating_sub(date),
+            _ => 0
+        };
+    }
+    // If the response has a Last-Modified header field, caches are encouraged to use a heuristic expiration value that is no more than some fraction of the interval since that time, a typical setting of this fraction might be 10%
+    if is_heuristically_cacheable(response.status) {
+        if let (Some(date), Some(last_modified)) = (date, header(response, "last-modified").and_then(parse_http_date)) {
+            return date.saturating_sub(last_modified) / 10;
+        }
+    }
+    return 0;
+}
+
+// https://httpwg.org/specs/rfc9111.html#age.calculations
+// How many seconds old the stored response is at now
+fn current_age(entry: &CacheEntry, now: u64) -> u64 {
+    let age = header(&entry.response, "age").and_then(|age| age.trim().parse::<u64>().ok()).unwrap_or(0);
+    let date = header(&entry.response, "date").and_then(parse_http_date);
+    let apparent_age = date.map(|date| entry.stored_at.saturating_sub(date)).unwrap_or(0);
+    let corrected_initial_age = apparent_age.max(age);
+    let resident_time = now.saturating_sub(entry.stored_at);
+    return corrected_initial_age + resident_time;
+}
+
+// https://httpwg.org/specs/rfc9111.html#response.cacheability
+// A cache must not store a response to a request unless the response status code is final, the no-store cache directive is not present,
+// and the response contains an explicit expiration time, a validator or a status code that is defined as heuristically cacheable
+// The requests are all GETs without Authorization, so only the response decides
+fn is_storable(response: &Response) -> bool {
+    let cache_control = cache_control(response);
+    if response.status < 200 || response.status == 206 || cache_control.no_store {
+        return false;
+    }
+    return cache_control.max_age.is_some() || header(response, "expires").is_some() || validators(response).is_some() || is_heuristically_cacheable(response.status);
+}
+
+// https://httpwg.org/specs/rfc9111.html#validation.sent
+// The headers of a conditional request that validates the stored response, None when it has no validator
+fn validators(response: &Response) -> Option<Vec<(String, String)>> {
+    let mut header_list = Vec::new();
+    if let Some(entity_tag) = header(response, "etag") {
+        header_list.push((String::from("If-None-Match"), entity_tag.to_string()));
+    }
+    // A client should send the Last-Modified value as it was received rather than a date of its own
+    if let Some(last_modified) = header(response, "last-modified") {
+        header_list.push((String::from("If-Modified-Since"), last_modified.to_string()));
+    }
+    return if header_list.is_empty() { None } else { Some(header_list) };
+}
+
+// https://httpwg.org/specs/rfc9111.html#freshening.responses
+// The stored response with the headers of a 304 (Not Modified) response, except those describing the content, which did not change
+fn freshen(stored: &Response, not_modified: &Response) -> Response {
+    let mut response = stored.clone();
+    let updated: Vec<&(String, String)> = not_modified.header_list.iter()
+        .filter(|(name, _)| !matches!(name.as_str(), "content-length" | "content-encoding" | "transfer-encoding" | "content-range"))
+        .collect();
+    response.header_list.retain(|(stored_name, _)| !updated.iter().any(|(name, _)| name == stored_name));
+    response.header_list.extend(updated.into_iter().cloned());
+    return response;
+}
+
+fn now() -> u64 {
+    return SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or(0);
+}
+
+// A resource loader that answers from storage when it can and loads the rest with loader
+// Only http: and https: URLs are cached, other URLs, e.g. file: URLs, are always loaded
+pub struct CachingResourceLoader {
+    loader: Box<dyn ResourceLoader>,
+    storage: Box<dyn CacheStorage>,
+}
+
+impl CachingResourceLoader {
+    pub fn new(loader: Box<dyn ResourceLoader>, storage: Box<dyn CacheStorage>) -> CachingResourceLoader {
+        CachingResourceLoader { loader, storage }
+    }
+
+    // Stores response when it is storable, and drops what was stored for key when it is not
+    fn store(&mut self, key: &str, response: &Response, now: u64) {
+        if is_storable(response) {
+            self.storage.put(key, &CacheEntry { response: response.clone(), stored_at: now });
+        } else {
+            self.storage.remove(key);
+        }
+    }
+}
+
+impl ResourceLoader for CachingResourceLoader {
+    fn load(&mut self, url: &Url) -> Result<Response, String> {
+        return self.load_with_headers(url, &[]);
+    }
+
+    fn load_with_headers(&mut self, url: &Url, header_list: &[(String, String)]) -> Result<Response, String> {
+        // A request that already has headers of its own, e.g. a conditional request, goes to the loader as it is
+        if !matches!(url.scheme.as_str(), "http" | "https") || !header_list.is_empty() {
+            return self.loader.load_with_headers(url, header_list);
+        }
+
+        // https://httpwg.org/specs/rfc9111.html#constructing.responses.from.caches
+        // The primary cache key consists of the request method and target URI, the fragment is not part of it
+        let mut key_url = url.clone();
+        key_url.fragment = None;
+        let key = key_url.href();
+        let now = now();
+
+        let entry = match self.storage.get(&key) {
+            Some(entry) => entry,
+            None => {
+                let response = self.loader.load(url)?;
+                self.store(&key, &response, now);
+                return Ok(response);
+            }
+        };
+
+        // A fresh response is used as it is unless the no-cache directive says it must be validated first
+        let cache_control = cache_control(&entry.response);
+        let is_fresh = current_age(&entry, now) < freshness_lifetime(&entry.response);
+        if is_fresh && !cache_control.no_cache {
+            return Ok(entry.response);
+        }
+
+        // A stale response without a validator can only be replaced
+        let validators = match validators(&entry.response) {
+            Some(validators) => validators,
+            None => {
+                let response = self.loader.load(url)?;
+                self.store(&key, &response, now);
+                return Ok(response);
+            }
+        };
+
+        // https://httpwg.org/specs/rfc9111.html#validation.response
+        // A 304 (Not Modified) response status code indicates that the stored response can be updated and reused
+        // Any other response is used instead of the stored one, as a full response to the request
+        let response = self.loader.load_with_headers(url, &validators)?;
+        if response.status == 304 {
+            let freshened = freshen(&entry.response, &response);
+            self.store(&key, &freshened, now);
+            return Ok(freshened);
+        }
+        self.store(&key, &response, now);
+        return Ok(response);
+    }
+}
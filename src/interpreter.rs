@@ -1,27 +1,99 @@
 use std::any::Any;
 use std::cell::{Ref, RefCell};
 use std::collections::HashMap;
+use indexmap::IndexMap;
 use std::fs::File;
 use std::io::{BufReader, Read, Write};
 use std::ops::Deref;
 use std::process::exit;
 use std::rc::{Rc, Weak};
-use crate::token::{Token, TokenType, Literal};
+use std::time::Instant;
+use crate::atom::Atom;
+use crate::token::{Token, TokenType, Literal, SyntaxError};
 use crate::scanner::Scanner;
 use crate::parser::Parser;
-use crate::ast::{Statement, ExpressionStatement, BinaryExpression, LiteralExpression, ParenthesizedExpression, UnaryExpression, IdentifierExpression, VariableDeclarationStatement, AstVisitor, Accept, Callable, CallExpression, BlockStatement, ObjectLiteralExpression, AssignmentExpression};
-use crate::ast_printer::ASTPrettyPrinter;
+use crate::ast::{Statement, ExpressionStatement, BinaryExpression, LiteralExpression, ParenthesizedExpression, UnaryExpression, IdentifierExpression, VariableDeclarationStatement, AstVisitor, Accept, Callable, CallExpression, BlockStatement, ObjectLiteralExpression, ArrayLiteralExpression, AssignmentExpression, MemberExpression, MemberProperty, PropertyName, FunctionDeclaration, FunctionExpression, ArrowFunction, ReturnStatement, FormalParameters, FunctionBody, ThisExpression, NewExpression, ThrowStatement, TryStatement, TemplateLiteral, LexicalDeclaration, Script};
+use crate::ast_printer::{source_text, ASTPrettyPrinter};
+use crate::optimizer;
+use crate::line_editor::{LineEditor, ReadLine};
+use crate::array::install_array;
+use crate::string::install_string;
+use crate::json::install_json;
+use crate::math::install_math;
+use crate::date::install_date;
+use crate::regexp::{install_regexp, regexp_create, RegExpData};
+use crate::console::{format_error, inspect_value, install_console, LogLevel, Logger, StandardStreamsLogger};
+use crate::error::{complete_error_object, create_error_object, install_error};
+use crate::node::RefNode;
+use crate::events::Event;
+use crate::cssom::{CSSStyleDeclaration, CSSStyleRule, CSSStyleSheet};
+use crate::mutation_observer::{MutationObserver, MutationRecord};
+use crate::event_loop::{self, EventLoop};
+use crate::gc::{self, Heap, HeapCell, Trace, Tracer};
+use crate::script_limits::{ScriptBudget, ScriptLimits};
+use crate::timers::install_timers;
+use crate::promise::{install_promise, PromiseData};
+use crate::fetch::{install_fetch, FetchResponse};
+use crate::resource_loader::{DefaultResourceLoader, ResourceLoader, Response};
+use crate::url::Url;
+use crate::window::{define_viewport_properties, install_window, Viewport};
 
 pub struct Interpreter {
     had_error: bool,
     //https://tc39.es/ecma262/#sec-execution-contexts
     execution_contexts: Vec<ExecutionContext>,
+    // https://tc39.es/ecma262/#sec-code-realms
+    // The realm's [[Intrinsics]]
+    intrinsics: HashMap<String, Rc<RefCell<JSObject>>>,
+    // https://console.spec.whatwg.org/#printer
+    logger: Box<dyn Logger>,
+    // Where fetched resources are loaded from
+    resource_loader: Box<dyn ResourceLoader>,
+    // https://drafts.csswg.org/cssom-view/#viewport
+    viewport: Viewport,
+    // https://dom.spec.whatwg.org/#concept-document-url
+    document_url: Url,
+    // https://html.spec.whatwg.org/multipage/webappapis.html#event-loop
+    pub(crate) event_loop: EventLoop,
+    // The objects script allocated, which the cycle collector looks for garbage from
+    heap: Heap,
+    // Whether scripts go through the optimizer before they run, see optimizer.rs
+    optimize_scripts: bool,
+    // The number of scripts run without a URL, they are named inline-script-1, inline-script-2 and so on in stack traces
+    inline_script_count: usize,
+    // How much script may do before it is stopped, see script_limits.rs
+    script_limits: ScriptLimits,
+    script_budget: ScriptBudget,
+    // The bytes the heap took up when it was last measured, plus what script has allocated since, only kept when there is a maximum heap size
+    heap_size: usize,
 }
 
+// The realm is torn down with the interpreter, so the cycles its objects are part of are freed, see gc.rs
+impl Drop for Interpreter {
+    fn drop(&mut self) {
+        // The tasks that never ran are dropped first, as the cycle collector can not look into them
+        self.event_loop = EventLoop::default();
+        let roots = self.heap_roots();
+        self.heap.tear_down(roots);
+    }
+}
+
+// The number of frames recorded in the stack of an error
+const STACK_TRACE_LIMIT: usize = 10;
+
 // https://tc39.es/ecma262/#sec-execution-contexts
 struct ExecutionContext {
     lexical_environment_record: Rc<RefCell<EnvironmentRecord>>,
-    variable_environment_record:  Rc<RefCell<EnvironmentRecord>>
+    variable_environment_record:  Rc<RefCell<EnvironmentRecord>>,
+    // The function object whose code is being evaluated, None for script code
+    function: Option<Rc<RefCell<JSObject>>>,
+    // The line and column of the last token evaluated in this context, used for the stack of errors
+    line: usize,
+    column: usize,
+    // [[ScriptOrModule]], only the name the script is reported by in stack traces is kept, e.g. its URL or inline-script-2
+    script_name: Rc<str>,
+    // Whether the code being evaluated is strict mode code, which is what IsStrict of the syntactic production being evaluated returns
+    strict: bool,
 }
 
 // https://tc39.es/ecma262/#sec-ecmascript-language-types-symbol-type
@@ -29,78 +101,230 @@ struct ExecutionContext {
 #[derive(PartialEq)]
 #[derive(Eq)]
 #[derive(Hash)]
-struct JSSymbol {
+pub(crate) struct JSSymbol {
     description: String,
 }
 
 macro_rules! completion {
     ($expr:expr) => {
-        match $expr.type_ {
-            CompletionRecordType::Normal => {
-                $expr
-            },
-            CompletionRecordType::Throw => return $expr,
-            _ => unimplemented!()
+        // Evaluate the expression once, matching on it twice would run its side effects twice
+        match $expr {
+            completion_record => match completion_record.type_ {
+                CompletionRecordType::Normal => {
+                    completion_record
+                },
+                // Throw, return, break and continue completions propagate to the nearest statement that handles them
+                _ => return completion_record
+            }
+        }
+    };
+}
+
+// Returns early with the abrupt completion, the same as ? in the spec, for the built-ins that pass Results around
+macro_rules! return_if_abrupt {
+    ($expr:expr) => {
+        match $expr {
+            Ok(value) => value,
+            Err(completion_record) => return completion_record
         }
     };
 }
+pub(crate) use return_if_abrupt;
 
 impl JSSymbol {
     pub fn new(description: String) -> JSSymbol {
         JSSymbol { description: description }
     }
+
+    pub(crate) fn description(&self) -> &String {
+        return &self.description;
+    }
 }
 
 // https://tc39.es/ecma262/#property-key
 #[derive(Debug)]
 #[derive(Eq, Hash, PartialEq)]
-enum PropertyKey {
+pub(crate) enum PropertyKey {
     String(String),
     Symbol(JSSymbol),
 }
 
 #[derive(Debug)]
-struct DataProperty {
-    value: Rc<RefCell<JSValue>>,
-    writable: bool,
-    enumerable: bool,
-    configurable: bool,
+pub(crate) struct DataProperty {
+    pub(crate) value: Rc<RefCell<JSValue>>,
+    pub(crate) writable: bool,
+    pub(crate) enumerable: bool,
+    pub(crate) configurable: bool,
 }
 #[derive(Debug)]
-struct AccessorProperty {
-    get: Option<fn(key: PropertyKey, receiver: &JSValue) -> JSObject>,
-    set: Option<fn(key: PropertyKey, value: JSValue, receiver: &JSValue) -> JSObject>
+pub(crate) struct AccessorProperty {
+    // The getter and setter are function objects
+    pub(crate) get: Option<Rc<RefCell<JSValue>>>,
+    pub(crate) set: Option<Rc<RefCell<JSValue>>>
 }
 
 #[derive(Debug)]
-enum PropertyType {
+pub(crate) enum PropertyType {
     DataProperty(DataProperty),
     AccessorProperty(AccessorProperty),
 }
 
 
+// https://tc39.es/ecma262/#sec-built-in-function-objects
+// Built-in functions are implemented in Rust and receive the this value and the argument list
+pub(crate) type NativeFunction = fn(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord;
+
+// https://tc39.es/ecma262/#sec-built-in-function-objects-construct-argumentslist-newtarget
+// Built-in constructors receive the argument list and the constructor new was applied to
+pub(crate) type NativeConstructor = fn(interpreter: &mut Interpreter, arguments: Vec<Rc<RefCell<JSValue>>>, new_target: Rc<RefCell<JSObject>>) -> CompletionRecord;
+
+// https://tc39.es/ecma262/#sec-createbuiltinfunction
+// Built-in functions created at runtime have an abstract closure as their behaviour, which captures values such as the promise a resolving function settles
+pub(crate) type ClosureBehaviour = dyn Fn(&mut Interpreter, Rc<RefCell<JSValue>>, Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord;
+
+#[derive(Clone)]
+pub(crate) struct AbstractClosure(Rc<ClosureBehaviour>);
+
+impl std::fmt::Debug for AbstractClosure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AbstractClosure")
+    }
+}
+
+// https://webidl.spec.whatwg.org/#dfn-platform-object
+// The implementation object a platform object is a wrapper for
+pub(crate) enum PlatformObject {
+    Node(RefNode),
+    Event(Rc<RefCell<Event>>),
+    Response(Rc<RefCell<FetchResponse>>),
+    CSSStyleSheet(Rc<RefCell<CSSStyleSheet>>),
+    CSSStyleRule(Rc<RefCell<CSSStyleRule>>),
+    CSSStyleDeclaration(Rc<RefCell<CSSStyleDeclaration>>),
+    MutationObserver(Rc<RefCell<MutationObserver>>),
+    MutationRecord(Rc<MutationRecord>),
+}
+
+impl std::fmt::Debug for PlatformObject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlatformObject::Node(node) => write!(f, "Node({:?})", node.borrow().nodeType),
+            PlatformObject::Event(event) => write!(f, "Event({:?})", event.borrow().type_),
+            PlatformObject::Response(response) => write!(f, "Response({:?})", response.borrow().response.url),
+            PlatformObject::CSSStyleSheet(sheet) => write!(f, "CSSStyleSheet({} rules)", sheet.borrow().css_rules.len()),
+            PlatformObject::CSSStyleRule(rule) => write!(f, "CSSStyleRule({:?})", rule.borrow().selector_text),
+            PlatformObject::CSSStyleDeclaration(declaration) => write!(f, "CSSStyleDeclaration({:?})", declaration.borrow().css_text()),
+            PlatformObject::MutationObserver(_) => write!(f, "MutationObserver"),
+            PlatformObject::MutationRecord(record) => write!(f, "MutationRecord({:?})", record.type_),
+        }
+    }
+}
+
 // https://tc39.es/ecma262/#sec-object-type
 #[derive(Debug)]
-struct JSObject {
+pub(crate) struct JSObject {
     // https://tc39.es/ecma262/#table-object-property-attributes
-    values: HashMap<PropertyKey, Rc<PropertyType>>,
-    pub prototype: Option<Rc<JSObject>>,
+    // Kept in creation order, which OrdinaryOwnPropertyKeys depends on
+    values: IndexMap<PropertyKey, Rc<PropertyType>>,
+    pub prototype: Option<Rc<RefCell<JSObject>>>,
     pub extensible: bool,
+    // https://tc39.es/ecma262/#table-additional-essential-internal-methods-of-function-objects
+    pub(crate) call: Option<NativeFunction>,
+    pub(crate) call_closure: Option<AbstractClosure>,
+    pub(crate) construct: Option<NativeConstructor>,
+    pub(crate) platform_object: Option<PlatformObject>,
+    // https://tc39.es/ecma262/#sec-array-exotic-objects
+    // Array exotic objects keep their length property in sync with their array index properties
+    pub(crate) array: bool,
+    // https://tc39.es/ecma262/#sec-ecmascript-function-objects
+    // Set for functions defined in script, built-in functions use call instead
+    ecmascript_function: Option<ECMAScriptFunction>,
+    // https://tc39.es/ecma262/#sec-properties-of-error-instances
+    // Error instances have an [[ErrorData]] internal slot
+    pub(crate) error_data: bool,
+    // https://tc39.es/ecma262/#table-internal-slots-of-promise-instances
+    pub(crate) promise_data: Option<PromiseData>,
+    // https://tc39.es/ecma262/#sec-properties-of-date-instances
+    // [[DateValue]], the time value of a Date instance
+    pub(crate) date_value: Option<f64>,
+    // https://tc39.es/ecma262/#sec-properties-of-regexp-instances
+    // [[OriginalSource]], [[OriginalFlags]] and [[RegExpMatcher]] of a RegExp instance
+    pub(crate) regexp_data: Option<Rc<RegExpData>>,
+}
+
+// https://tc39.es/ecma262/#table-internal-slots-of-ecmascript-function-objects
+struct ECMAScriptFunction {
+    // [[Environment]], the environment the function closes over
+    environment: Rc<RefCell<EnvironmentRecord>>,
+    // [[FormalParameters]]
+    formal_parameters: Rc<FormalParameters>,
+    // [[ECMAScriptCode]]
+    ecmascript_code: Rc<FunctionBody>,
+    // [[ThisMode]]
+    this_mode: ThisMode,
+    // [[Strict]]
+    strict: bool,
+    // [[ScriptOrModule]], the name of the script the function was created by
+    script_name: Rc<str>,
+    // Whether MakeConstructor has given F a [[Construct]] internal method
+    // TODO: [[ConstructorKind]] is always base until classes are supported
+    constructor: bool,
+}
+
+// The environment usually contains the function itself, so it is left out to avoid printing cycles
+impl std::fmt::Debug for ECMAScriptFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ECMAScriptFunction({:?})", self.this_mode)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ThisMode {
+    Lexical,
+    Strict,
+    Global,
 }
 // https://tc39.es/ecma262/#sec-property-descriptor-specification-type
 #[derive(Debug)]
-struct PropertyDescriptor {
+pub(crate) struct PropertyDescriptor {
    property: Option<PropertyType>
 }
 
 #[derive(Debug)]
-enum PropertyDescriptorType {
+pub(crate) enum PropertyDescriptorType {
     PropertyDescriptor(PropertyDescriptor),
     Undefined(JSValue)
 }
 impl JSObject {
     pub fn new() -> JSObject {
-        JSObject { values: HashMap::new(), prototype: None, extensible: false }
+        JSObject { values: IndexMap::new(), prototype: None, extensible: false, call: None, call_closure: None, construct: None, platform_object: None, array: false, ecmascript_function: None, error_data: false, promise_data: None, date_value: None, regexp_data: None }
+    }
+
+    // https://tc39.es/ecma262/#sec-ordinaryownpropertykeys
+    pub(crate) fn own_property_keys(&self) -> Vec<&PropertyKey> {
+        // 1. Let keys be a new empty List.
+        // 2. For each own property key P of O such that P is an array index, in ascending numeric index order, do
+        //     a. Append P to keys.
+        let mut array_indices: Vec<(u32, &PropertyKey)> = self.values.keys().filter_map(|key| match key {
+            PropertyKey::String(name) => Interpreter::array_index(name).map(|index| (index, key)),
+            PropertyKey::Symbol(_) => None
+        }).collect();
+        array_indices.sort_by_key(|(index, _)| *index);
+        let mut keys: Vec<&PropertyKey> = array_indices.into_iter().map(|(_, key)| key).collect();
+
+        // 3. For each own property key P of O such that P is a String and P is not an array index, in ascending chronological order of property creation, do
+        //     a. Append P to keys.
+        keys.extend(self.values.keys().filter(|key| matches!(key, PropertyKey::String(name) if Interpreter::array_index(name).is_none())));
+
+        // 4. For each own property key P of O such that P is a Symbol, in ascending chronological order of property creation, do
+        //     a. Append P to keys.
+        keys.extend(self.values.keys().filter(|key| matches!(key, PropertyKey::Symbol(_))));
+
+        // 5. Return keys.
+        return keys;
+    }
+
+    pub(crate) fn own_property(&self, key: &PropertyKey) -> Option<Rc<PropertyType>> {
+        return self.values.get(key).cloned();
     }
 
     // https://tc39.es/ecma262/#sec-ordinary-object-internal-methods-and-internal-slots-get-p-receiver
@@ -223,10 +447,178 @@ impl JSObject {
 
     // https://tc39.es/ecma262/#sec-ordinary-object-internal-methods-and-internal-slots-defineownproperty-p-desc
     fn define_own_property(&mut self, property_key: &PropertyKey, property_descriptor: &PropertyDescriptor) -> CompletionRecord {
+        if self.array {
+            return completion!(self.array_define_own_property(property_key, property_descriptor));
+        }
+
         //1. Return ? OrdinaryDefineOwnProperty(O, P, Desc).
         return completion!(self.ordinary_define_own_property(property_key, property_descriptor));
     }
 
+    // https://tc39.es/ecma262/#sec-array-exotic-objects-defineownproperty-p-desc
+    fn array_define_own_property(&mut self, property_key: &PropertyKey, property_descriptor: &PropertyDescriptor) -> CompletionRecord {
+        if let PropertyKey::String(key) = property_key {
+            // 1. If P is "length", then
+            if key == "length" {
+                // a. Return ? ArraySetLength(A, Desc).
+                return completion!(self.array_set_length(property_descriptor));
+            }
+
+            // 2. Else if P is an array index, then
+            if let Some(index) = Interpreter::array_index(key) {
+                // a. Let lengthDesc be OrdinaryGetOwnProperty(A, "length").
+                // b. Assert: IsDataDescriptor(lengthDesc) is true.
+                // c. Assert: lengthDesc.[[Configurable]] is false.
+                // d. Let length be lengthDesc.[[Value]].
+                // e. Assert: length is a non-negative integral Number.
+                // f. Let index be ! ToUint32(P).
+                let (length, length_writable) = self.array_length();
+
+                // g. If index ≥ length and lengthDesc.[[Writable]] is false, return false.
+                if index >= length && !length_writable {
+                    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(false))))));
+                }
+
+                // h. Let succeeded be ! OrdinaryDefineOwnProperty(A, P, Desc).
+                let succeeded = completion!(self.ordinary_define_own_property(property_key, property_descriptor));
+
+                // i. If succeeded is false, return false.
+                if matches!(&*succeeded.value, ReferenceRecordOrJsValue::JSValue(value) if matches!(*value.borrow(), JSValue::Boolean(false))) {
+                    return succeeded;
+                }
+
+                // j. If index ≥ length, then
+                if index >= length {
+                    // i. Set lengthDesc.[[Value]] to index + 1𝔽.
+                    // ii. Set succeeded to ! OrdinaryDefineOwnProperty(A, "length", lengthDesc).
+                    // iii. Assert: succeeded is true.
+                    self.set_array_length(index + 1, length_writable);
+                }
+
+                // k. Return true.
+                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(true))))));
+            }
+        }
+
+        // 3. Return ? OrdinaryDefineOwnProperty(A, P, Desc).
+        return completion!(self.ordinary_define_own_property(property_key, property_descriptor));
+    }
+
+    // https://tc39.es/ecma262/#sec-arraysetlength
+    // TODO: Property descriptors are always fully populated, so only [[Value]] and [[Writable]] of Desc are applied to length
+    fn array_set_length(&mut self, property_descriptor: &PropertyDescriptor) -> CompletionRecord {
+        // 1. If Desc does not have a [[Value]] field, then
+        //     a. Return ! OrdinaryDefineOwnProperty(A, "length", Desc).
+        let (value, new_writable) = match &property_descriptor.property {
+            Some(PropertyType::DataProperty(data_property)) => (Rc::clone(&data_property.value), data_property.writable),
+            _ => {
+                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(false))))));
+            }
+        };
+
+        // 2. Let newLenDesc be a copy of Desc.
+        // 3. Let newLen be ? ToUint32(Desc.[[Value]]).
+        // 4. Let numberLen be ? ToNumber(Desc.[[Value]]).
+        let number_length = match &*completion!(Interpreter::to_number(value)).value {
+            ReferenceRecordOrJsValue::JSValue(number) => match *number.borrow() {
+                JSValue::Numeric(number) => number,
+                _ => { unreachable!() }
+            },
+            _ => { unreachable!() }
+        };
+        let new_length = Interpreter::to_uint32(number_length);
+
+        // 5. If SameValueZero(newLen, numberLen) is false, throw a RangeError exception.
+        if new_length as Number != number_length {
+            return create_error_throw_completion("RangeError", String::from("Invalid array length"));
+        }
+
+        // 6. Set newLenDesc.[[Value]] to newLen.
+        // 7. Let oldLenDesc be OrdinaryGetOwnProperty(A, "length").
+        // 8. Assert: IsDataDescriptor(oldLenDesc) is true.
+        // 9. Assert: oldLenDesc.[[Configurable]] is false.
+        // 10. Let oldLen be oldLenDesc.[[Value]].
+        let (old_length, old_length_writable) = self.array_length();
+
+        // 11. If newLen ≥ oldLen, then
+        if new_length >= old_length {
+            // a. Return ! OrdinaryDefineOwnProperty(A, "length", newLenDesc).
+            // A non-writable length can only be set to the value it already has
+            if !old_length_writable && new_length != old_length {
+                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(false))))));
+            }
+            self.set_array_length(new_length, old_length_writable && new_writable);
+            return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(true))))));
+        }
+
+        // 12. If oldLenDesc.[[Writable]] is false, return false.
+        if !old_length_writable {
+            return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(false))))));
+        }
+
+        // 13. If newLenDesc does not have a [[Writable]] field or newLenDesc.[[Writable]] is true, then
+        //     a. Let newWritable be true.
+        // 14. Else,
+        //     a. NOTE: Setting the [[Writable]] attribute to false is deferred in case any elements cannot be deleted.
+        //     b. Let newWritable be false.
+        //     c. Set newLenDesc.[[Writable]] to true.
+        // 15. Let succeeded be ! OrdinaryDefineOwnProperty(A, "length", newLenDesc).
+        // 16. If succeeded is false, return false.
+        self.set_array_length(new_length, true);
+
+        // 17. For each own property key P of A such that P is an array index and ! ToUint32(P) ≥ newLen, in descending numeric index order, do
+        let mut deleted_indices: Vec<u32> = self.values.keys().filter_map(|key| match key {
+            PropertyKey::String(key) => Interpreter::array_index(key).filter(|index| *index >= new_length),
+            PropertyKey::Symbol(_) => None
+        }).collect();
+        deleted_indices.sort_unstable_by(|a, b| b.cmp(a));
+
+        for index in deleted_indices {
+            // a. Let deleteSucceeded be ! A.[[Delete]](P).
+            let delete_succeeded = self.delete(&PropertyKey::String(index.to_string()));
+
+            // b. If deleteSucceeded is false, then
+            if !delete_succeeded {
+                // i. Set newLenDesc.[[Value]] to ! ToUint32(P) + 1𝔽.
+                // ii. If newWritable is false, set newLenDesc.[[Writable]] to false.
+                // iii. Perform ! OrdinaryDefineOwnProperty(A, "length", newLenDesc).
+                self.set_array_length(index + 1, new_writable);
+
+                // iv. Return false.
+                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(false))))));
+            }
+        }
+
+        // 18. If newWritable is false, then
+        //     a. Set succeeded to ! OrdinaryDefineOwnProperty(A, "length", PropertyDescriptor { [[Writable]]: false }).
+        //     b. Assert: succeeded is true.
+        if !new_writable {
+            self.set_array_length(new_length, false);
+        }
+
+        // 19. Return true.
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(true))))));
+    }
+
+    // The value and [[Writable]] attribute of an array's length property
+    fn array_length(&self) -> (u32, bool) {
+        match self.values.get(&PropertyKey::String(String::from("length"))).map(|property| &**property) {
+            Some(PropertyType::DataProperty(data_property)) => {
+                match *data_property.value.borrow() {
+                    JSValue::Numeric(length) => (length as u32, data_property.writable),
+                    _ => { unreachable!() }
+                }
+            },
+            _ => { unreachable!() }
+        }
+    }
+
+    // Replaces the length property of an array, it is never enumerable or configurable
+    fn set_array_length(&mut self, length: u32, writable: bool) {
+        let length = DataProperty { value: Rc::new(RefCell::new(JSValue::Numeric(length as Number))), writable, enumerable: false, configurable: false };
+        self.values.insert(PropertyKey::String(String::from("length")), Rc::new(PropertyType::DataProperty(length)));
+    }
+
     // https://tc39.es/ecma262/#sec-ordinarydefineownproperty
     fn ordinary_define_own_property(&mut self, property_key: &PropertyKey, property_descriptor: &PropertyDescriptor) -> CompletionRecord  {
         // Let current be ? O.[[GetOwnProperty]](P).
@@ -256,10 +648,20 @@ impl JSObject {
                 //        b. If O is undefined, return true. TODO
                 //        c. If IsAccessorDescriptor(Desc) is true, then
                 match &property_descriptor.property {
-                    Some(PropertyType::AccessorProperty(data_property)) => {
+                    Some(PropertyType::AccessorProperty(accessor_property)) => {
                         //               i. Create an own accessor property named P of object O whose [[Get]], [[Set]], [[Enumerable]], and [[Configurable]] attributes
                         //                  are set to the value of the corresponding field in Desc if Desc has that field, or to the attribute's default value otherwise.
-                        unimplemented!();
+                        let new_accessor_property = AccessorProperty { get: accessor_property.get.clone(), set: accessor_property.set.clone() };
+
+                        match property_key {
+                            PropertyKey::String(s) => {
+                                self.values.insert(PropertyKey::String(s.clone()), Rc::new(PropertyType::AccessorProperty(new_accessor_property)));
+                                gc::record_growth(new_property_size(s));
+                            },
+                            _ => { unimplemented!() }
+                        }
+                        //        e. Return true.
+                        return true;
                     },
                     //        d. Else,
                     Some(PropertyType::DataProperty(data_prop)) => {
@@ -270,6 +672,7 @@ impl JSObject {
                         match property_key {
                             PropertyKey::String(s) => {
                                 self.values.insert(PropertyKey::String(s.clone()), Rc::new(PropertyType::DataProperty(new_data_property)));
+                                gc::record_growth(new_property_size(s));
                             },
                             _ => { unimplemented!() }
                         }
@@ -283,7 +686,7 @@ impl JSObject {
 
 
             },
-            PropertyDescriptorType::PropertyDescriptor(property_descriptor) => {
+            PropertyDescriptorType::PropertyDescriptor(current_property_descriptor) => {
                 // 3. Assert: current is a fully populated Property Descriptor. TODO
                 // 4. If Desc does not have any fields, return true. TODO
 /*                match property_descriptor.property {
@@ -324,7 +727,30 @@ impl JSObject {
                 //               i. For each field of Desc, set the corresponding attribute of the property named P of object O to the value of the field.
                 //
                 // 7. Return true.
-                unimplemented!();
+                // TODO: Only steps 5.e and 6 are done, and a descriptor always replaces the whole property
+                if let Some(PropertyType::DataProperty(current_data_property)) = &current_property_descriptor.property {
+                    if !current_data_property.configurable && !current_data_property.writable {
+                        return false;
+                    }
+                }
+
+                let new_property = match &property_descriptor.property {
+                    Some(PropertyType::DataProperty(data_prop)) => {
+                        PropertyType::DataProperty(DataProperty { value: data_prop.value.clone(), writable: data_prop.writable, configurable: data_prop.configurable, enumerable: data_prop.enumerable })
+                    },
+                    Some(PropertyType::AccessorProperty(accessor_property)) => {
+                        PropertyType::AccessorProperty(AccessorProperty { get: accessor_property.get.clone(), set: accessor_property.set.clone() })
+                    },
+                    None => { return true; }
+                };
+
+                match property_key {
+                    PropertyKey::String(s) => {
+                        self.values.insert(PropertyKey::String(s.clone()), Rc::new(new_property));
+                    },
+                    _ => { unimplemented!() }
+                }
+                return true;
             }
         }
 
@@ -356,7 +782,7 @@ impl JSObject {
                             //     4. Assert: IsAccessorDescriptor(desc) is true.
                             Some(PropertyType::AccessorProperty(accessor_property)) => {
                                 //     5. Let getter be desc.[[Get]].
-                                let getter = accessor_property.get;
+                                let getter = &accessor_property.get;
                                 //     6. If getter is undefined, return undefined.
                                 if getter.is_none() {
                                     return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
@@ -383,14 +809,14 @@ impl JSObject {
     // https://tc39.es/ecma262/#sec-ordinarygetownproperty
     fn ordinary_get_own_property(&self, key: &PropertyKey) -> PropertyDescriptorType {
         // 1. If O does not have an own property with key P, return undefined.
-        if !self.values.contains_key(&key) {
+        if !self.values.contains_key(key) {
             return PropertyDescriptorType::Undefined(JSValue::Undefined);
         }
         //     2. Let D be a newly created Property Descriptor with no fields.
         let mut property_descriptor: PropertyDescriptor = PropertyDescriptor { property: None };
 
         //     3. Let X be O's own property whose key is P.
-        let property_data = self.values.get(&key).unwrap().clone();
+        let property_data = self.values.get(key).unwrap().clone();
 
         match &*property_data {
             //     4. If X is a data property, then
@@ -407,16 +833,51 @@ impl JSObject {
             PropertyType::AccessorProperty(accessor_prop) => {
                 //     b. Set D.[[Get]] to the value of X's [[Get]] attribute.
                 //     c. Set D.[[Set]] to the value of X's [[Set]] attribute.
+                property_descriptor.property = Some(PropertyType::AccessorProperty(AccessorProperty { get: accessor_prop.get.clone(), set: accessor_prop.set.clone() }));
+
                 //     8. Return D.
-                unimplemented!();
+                return PropertyDescriptorType::PropertyDescriptor(property_descriptor);
             },
         }
         //     TODO: 6. Set D.[[Enumerable]] to the value of X's [[Enumerable]] attribute.
         //     TODO: 7. Set D.[[Configurable]] to the value of X's [[Configurable]] attribute.
     }
 
+    // https://tc39.es/ecma262/#sec-ordinary-object-internal-methods-and-internal-slots-delete-p
+    pub(crate) fn delete(&mut self, property_key: &PropertyKey) -> bool {
+        // 1. Return ? OrdinaryDelete(O, P).
+        return self.ordinary_delete(property_key);
+    }
+
+    // https://tc39.es/ecma262/#sec-ordinarydelete
+    fn ordinary_delete(&mut self, property_key: &PropertyKey) -> bool {
+        // 1. Let desc be ? O.[[GetOwnProperty]](P).
+        // 2. If desc is undefined, return true.
+        let configurable = match self.values.get(property_key).map(Rc::clone) {
+            None => return true,
+            Some(property) => match &*property {
+                PropertyType::DataProperty(data_property) => data_property.configurable,
+                // TODO: Accessor properties do not track [[Configurable]] yet
+                PropertyType::AccessorProperty(_) => true
+            }
+        };
+
+        // 3. If desc.[[Configurable]] is true, then
+        if configurable {
+            // a. Remove the own property with name P from O.
+            // Shifting keeps the remaining properties in creation order
+            self.values.shift_remove(property_key);
+
+            // b. Return true.
+            return true;
+        }
+
+        // 4. Return false.
+        return false;
+    }
+
     // https://tc39.es/ecma262/#sec-ordinary-object-internal-methods-and-internal-slots-hasproperty-p
-    fn has_property(&self, property_key: PropertyKey) -> CompletionRecord {
+    pub(crate) fn has_property(&self, property_key: PropertyKey) -> CompletionRecord {
         //1. Return ? OrdinaryHasProperty(O, P).
         return self.ordinary_has_property(property_key);
     }
@@ -435,9 +896,10 @@ impl JSObject {
                     _ => {
                         // 3. Let parent be ? O.[[GetPrototypeOf]]().
                         // 4. If parent is not null, then
-                        //
-                        //        a. Return ? parent.[[HasProperty]](P).
-                        //
+                        if let Some(parent) = &self.prototype {
+                            // a. Return ? parent.[[HasProperty]](P).
+                            return completion!(parent.borrow().has_property(property_key));
+                        }
                     }
                 }
             },
@@ -451,19 +913,25 @@ impl JSObject {
 impl Callable for JSObject {
 }
 
+// The bytes a new property adds to an object, counted against the maximum heap size
+fn new_property_size(name: &str) -> usize {
+    return std::mem::size_of::<(PropertyKey, Rc<PropertyType>)>() + std::mem::size_of::<PropertyType>() + name.len();
+}
+
 // https://tc39.es/ecma262/#sec-ecmascript-language-types-number-type
 // TODO: Support BigInt https://tc39.es/ecma262/#sec-ecmascript-language-types-bigint-type
 type Number = f64;
 
 // https://tc39.es/ecma262/#sec-ecmascript-language-types
 #[derive(Debug)]
-enum JSValue {
+pub(crate) enum JSValue {
     Undefined,
     Boolean(bool),
     String(String),
     Symbol(JSSymbol),
     Numeric(Number),
-    Object(JSObject),
+    // Objects are shared, every value referring to an object refers to the same one
+    Object(Rc<RefCell<JSObject>>),
     Null
 }
 
@@ -476,7 +944,7 @@ enum EnvironmentRecordType {
     GlobalEnvironmentRecord(Rc<RefCell<GlobalEnvironmentRecord>>),
 }
 
-fn create_normal_completion(value: Rc<ReferenceRecordOrJsValue>) -> CompletionRecord {
+pub(crate) fn create_normal_completion(value: Rc<ReferenceRecordOrJsValue>) -> CompletionRecord {
     return CompletionRecord {
         type_: CompletionRecordType::Normal,
         value: Rc::clone(&value),
@@ -484,7 +952,20 @@ fn create_normal_completion(value: Rc<ReferenceRecordOrJsValue>) -> CompletionRe
     }
 }
 
-fn create_throw_completion(value: Rc<ReferenceRecordOrJsValue>) -> CompletionRecord {
+// Unwraps the String held by a normal completion, such as the result of ToString or ToPropertyKey
+fn completion_to_string(completion_record: CompletionRecord) -> String {
+    match completion_record.value.deref() {
+        ReferenceRecordOrJsValue::JSValue(value) => {
+            match &*value.borrow() {
+                JSValue::String(string) => string.clone(),
+                _ => { unreachable!() }
+            }
+        },
+        _ => { unreachable!() }
+    }
+}
+
+pub(crate) fn create_throw_completion(value: Rc<ReferenceRecordOrJsValue>) -> CompletionRecord {
     return CompletionRecord {
         type_: CompletionRecordType::Throw,
         value: Rc::clone(&value),
@@ -492,11 +973,62 @@ fn create_throw_completion(value: Rc<ReferenceRecordOrJsValue>) -> CompletionRec
     }
 }
 
+pub(crate) fn create_value_completion(value: JSValue) -> CompletionRecord {
+    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(value)))));
+}
+
+// The value of a normal completion, or the completion itself when it is a throw completion
+pub(crate) fn completion_value(completion_record: CompletionRecord) -> Result<Rc<RefCell<JSValue>>, CompletionRecord> {
+    if let CompletionRecordType::Throw = completion_record.type_ {
+        return Err(completion_record);
+    }
+
+    match &*completion_record.value {
+        ReferenceRecordOrJsValue::JSValue(value) => Ok(Rc::clone(value)),
+        _ => unreachable!()
+    }
+}
+
+// Missing arguments are undefined
+pub(crate) fn argument(arguments: &[Rc<RefCell<JSValue>>], index: usize) -> Rc<RefCell<JSValue>> {
+    match arguments.get(index) {
+        Some(value) => Rc::clone(value),
+        None => Rc::new(RefCell::new(JSValue::Undefined))
+    }
+}
+
+// https://tc39.es/ecma262/#sec-tonumber
+// Objects are converted to a primitive by their valueOf or toString method first
+pub(crate) fn to_number(interpreter: &mut Interpreter, value: Rc<RefCell<JSValue>>) -> Result<f64, CompletionRecord> {
+    let object = match &*value.borrow() {
+        JSValue::Object(object) => Some(Rc::clone(object)),
+        _ => None
+    };
+    let value = match object {
+        Some(object) => completion_value(interpreter.ordinary_to_primitive(&object, "number"))?,
+        None => value
+    };
+    if matches!(*value.borrow(), JSValue::Symbol(_)) {
+        return Err(interpreter.throw_type_error(String::from("Cannot convert a Symbol value to a number")));
+    }
+    match *completion_value(Interpreter::to_number(value))?.borrow() {
+        JSValue::Numeric(number) => Ok(number),
+        _ => unreachable!()
+    }
+}
+
+// Throws a new error object, for places such as environment records that have no access to the interpreter
+// The error gets its prototype and stack once the statement that threw it completes, see Interpreter::execute
+fn create_error_throw_completion(name: &str, message: String) -> CompletionRecord {
+    let error = create_error_object(name, message);
+    return create_throw_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Object(error))))));
+}
+
 // https://tc39.es/ecma262/#sec-declarative-environment-records
 impl DeclarativeEnvironmentRecord {
 
     // https://tc39.es/ecma262/#sec-declarative-environment-records-hasbinding-n
-    fn has_binding(&self, binding_id: String) -> CompletionRecord {
+    fn has_binding(&self, binding_id: Atom) -> CompletionRecord {
         // If envRec has a binding for N, return true.
         if self.variable_bindings.contains_key(&binding_id) {
             return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(true))))));
@@ -505,17 +1037,16 @@ impl DeclarativeEnvironmentRecord {
             return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(false))))));;
         }
     }
-    // tc39.es/ecma262/#sec-declarative-environment-records-setmutablebinding-n-v-s
-    pub fn set_mutable_binding(&mut self, binding_id: String, value: Rc<RefCell<JSValue>>, strict: bool) -> CompletionRecord {
+    // https://tc39.es/ecma262/#sec-declarative-environment-records-setmutablebinding-n-v-s
+    pub fn set_mutable_binding(&mut self, binding_id: Atom, value: Rc<RefCell<JSValue>>, strict: bool) -> CompletionRecord {
         // 1. If envRec does not have a binding for N, then
         if !self.variable_bindings.contains_key(&binding_id) {
             // a. If S is true, throw a ReferenceError exception.
             if strict {
-                // FIXME: The value here in throw and everywhere else we throw should be a Error object and and not just the base object
-                return create_throw_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Object(JSObject { values: HashMap::new(), prototype: None, extensible: false }))))));
+                return create_error_throw_completion("ReferenceError", format!("{} is not defined", binding_id));
             } else {
                 //     b. Perform ! envRec.CreateMutableBinding(N, true).
-                self.create_mutable_binding(binding_id.clone(), strict);
+                self.create_mutable_binding(binding_id.clone(), true);
 
                 //     c. Perform ! envRec.InitializeBinding(N, V).
                 self.initialize_binding(binding_id, value);
@@ -525,119 +1056,115 @@ impl DeclarativeEnvironmentRecord {
             }
         }
 
-        //     2. If the binding for N in envRec is a strict binding, set S to true. TODO: This occurs in CreateImmutableBinding which is not implemented yet
-        // Check the initialization state before trying to insert
-        let should_insert;
-        let initialized;
-
-        match self.variable_bindings.get(&binding_id) {
-            Some(binding_ref) => {
-                match binding_ref {
-                    Binding::MutableBinding(mut_binding) => {
-                        match &*mut_binding.borrow() {
-                            //     3. If the binding for N in envRec has not yet been initialized, then
-                            JSValue::Undefined => {
-                                // a. Throw a ReferenceError exception.
-                                // FIXME: The value here in throw and everywhere else we throw should be a Error object and and not just the base object
-                                return create_throw_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Object(JSObject { values: HashMap::new(), prototype: None, extensible: false }))))));
-                            },
-                            _ => {
-                                //     4. Else if the binding for N in envRec is a mutable binding, then
-                                // a. Change its bound value to V.
-                                should_insert = true;
-                                initialized = true;
-                            }
-                        }
+        match self.variable_bindings.get_mut(&binding_id).unwrap() {
+            Binding::MutableBinding(bound_value) => {
+                match bound_value {
+                    // 3. If the binding for N in envRec has not yet been initialized, then
+                    None => {
+                        // a. Throw a ReferenceError exception.
+                        return create_error_throw_completion("ReferenceError", format!("Cannot access '{}' before initialization", binding_id));
                     },
-                    //     5. Else,
-                    // a. Assert: This is an attempt to change the value of an immutable binding.
-                   Binding::ImmutableBinding(_) => {
-                       //     b. If S is true, throw a TypeError exception.
-                       if strict {
-                           // FIXME: The value here in throw and everywhere else we throw should be a Error object and and not just the base object
-                           return create_throw_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Object(JSObject { values: HashMap::new(), prototype: None, extensible: false }))))));
-                       }
-                       should_insert = false;
-                       initialized = false;
-                   }
+                    // 4. Else if the binding for N in envRec is a mutable binding, then
+                    Some(_) => {
+                        // a. Change its bound value to V.
+                        *bound_value = Some(value);
+                    }
                 }
             },
-            None => {
-                should_insert = false;
-                initialized = false;
+            // 5. Else,
+            // a. Assert: This is an attempt to change the value of an immutable binding.
+            Binding::ImmutableBinding { value: bound_value, strict: strict_binding } => {
+                if bound_value.is_none() {
+                    return create_error_throw_completion("ReferenceError", format!("Cannot access '{}' before initialization", binding_id));
+                }
+
+                // 2. If the binding for N in envRec is a strict binding, set S to true.
+                // b. If S is true, throw a TypeError exception.
+                if strict || *strict_binding {
+                    return create_error_throw_completion("TypeError", String::from("Assignment to constant variable."));
+                }
             }
         }
 
-        // Now perform the insertion if needed
-        if should_insert && initialized {
-            let new_binding = Binding::MutableBinding(value);
-            self.variable_bindings.insert(binding_id.to_string(), new_binding);
-        }
         //     6. Return unused.
         return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
     }
 
-    // https://tc39.es/ecma262/#sec-declarative-environment-records-setmutablebinding-n-v-s
-    fn create_mutable_binding(&mut self, binding_id: String, marked_for_deletion: bool) -> CompletionRecord {
+    // https://tc39.es/ecma262/#sec-declarative-environment-records-createmutablebinding-n-d
+    fn create_mutable_binding(&mut self, binding_id: Atom, marked_for_deletion: bool) -> CompletionRecord {
         // 1. Assert: envRec does not already have a binding for N.
         if !self.variable_bindings.contains_key(&binding_id) {
-            // 2. Create a mutable binding in envRec for N and record that it is uninitialized (Setting value of mut binding to Undefined which means uninitialized)
+            // 2. Create a mutable binding in envRec for N and record that it is uninitialized.
             // TODO: If D is true, record that the newly created binding may be deleted by a subsequent DeleteBinding call.
-            let new_mutable_binding: Binding = Binding::MutableBinding(Rc::new(RefCell::new(JSValue::Undefined)));
-            self.variable_bindings.insert(binding_id, new_mutable_binding);
+            self.variable_bindings.insert(binding_id, Binding::MutableBinding(None));
         }
 
         // 3. Return unused.
         return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
     }
 
-    // https://tc39.es/ecma262/#sec-declarative-environment-records-initializebinding-n-v
-    fn initialize_binding(&mut self, binding_id: String, value: Rc<RefCell<JSValue>>) -> CompletionRecord {
-        match self.variable_bindings.get(&binding_id) {
-            // 1. Assert: envRec must have an uninitialized binding for N.
-            Some(binding) => {
-                match &binding {
-                    Binding::MutableBinding(mut_binding) => {
-                        // 2. Set the bound value for N in envRec to V.
-                        // 3. Record that the binding for N in envRec has been initialized. (Presence here determines if it is initialized)
-                        let new_binding = Binding::MutableBinding(value);
-                        self.variable_bindings.insert(binding_id.to_string(), new_binding);
-                    }
-                    Binding::ImmutableBinding(_) => {
-                        // 2. Set the bound value for N in envRec to V.
-                        // 3. Record that the binding for N in envRec has been initialized. (Presense here determine if it is initialized)
-                        unreachable!("[js::initialize_binding] Trying to initialize an immutable binding after creation!")
-                    }
-                }
+    // https://tc39.es/ecma262/#sec-declarative-environment-records-createimmutablebinding-n-s
+    fn create_immutable_binding(&mut self, binding_id: Atom, strict: bool) -> CompletionRecord {
+        // 1. Assert: envRec does not already have a binding for N.
+        // 2. Create an immutable binding in envRec for N and record that it is uninitialized.
+        //    If S is true, record that the newly created binding is a strict binding.
+        self.variable_bindings.insert(binding_id, Binding::ImmutableBinding { value: None, strict });
 
-            }
+        // 3. Return unused.
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+    }
+
+    // https://tc39.es/ecma262/#sec-declarative-environment-records-initializebinding-n-v
+    fn initialize_binding(&mut self, binding_id: Atom, value: Rc<RefCell<JSValue>>) -> CompletionRecord {
+        // 1. Assert: envRec must have an uninitialized binding for N.
+        match self.variable_bindings.get_mut(&binding_id) {
+            // 2. Set the bound value for N in envRec to V.
+            // 3. Record that the binding for N in envRec has been initialized.
+            Some(Binding::MutableBinding(bound_value)) => *bound_value = Some(value),
+            Some(Binding::ImmutableBinding { value: bound_value, .. }) => *bound_value = Some(value),
             None => { unreachable!() }
         }
+
         // 4. Return unused.
         return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
     }
 
     // https://tc39.es/ecma262/#sec-declarative-environment-records-getbindingvalue-n-s
-    fn get_binding_value(&self, binding_id: String, is_strict: bool) -> CompletionRecord {
+    fn get_binding_value(&self, binding_id: Atom, is_strict: bool) -> CompletionRecord {
         // 1. Assert: envRec has a binding for N.
-        if self.variable_bindings.contains_key(&binding_id) {
+        let bound_value = match self.variable_bindings.get(&binding_id).unwrap() {
+            Binding::MutableBinding(bound_value) => bound_value,
+            Binding::ImmutableBinding { value: bound_value, .. } => bound_value
+        };
+
+        match bound_value {
             // 2. If the binding for N in envRec is an uninitialized binding, throw a ReferenceError exception.
-            if self.variable_bindings.get(&binding_id).is_none() {
-                // FIXME: value should of a ReferenceError JS object
-                return CompletionRecord { type_: CompletionRecordType::Throw, value: Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Object(JSObject { values: HashMap::new(), prototype: None, extensible: false }))))), target: None }
+            None => {
+                return create_error_throw_completion("ReferenceError", format!("Cannot access '{}' before initialization", binding_id));
+            },
+            // 3. Return the value currently bound to N in envRec.
+            Some(value) => {
+                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::clone(value))));
             }
         }
+    }
 
-        // 3. Return the value currently bound to N in envRec.
-        let binding = self.variable_bindings.get(&binding_id).unwrap();
-        match binding {
-            Binding::MutableBinding(js_value) => {
-                return CompletionRecord { type_: CompletionRecordType::Normal, value: Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::clone(js_value))), target: None }
-            },
-            // Binding::ImmutableBinding(js_value) => {
-            //     return CompletionRecord { type_: CompletionRecordType::Normal, value: Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::clone(js_value))), target: None }
-            // }
-            _ => { todo!("Implement returning immutable binding value") }
+    // Creates the uninitialized bindings of let and const declarations, they stay in the temporal dead zone until their declaration is evaluated
+    fn create_lexical_bindings(&mut self, declarations: &[&LexicalDeclaration]) {
+        // For each element d of declarations, do
+        for declaration in declarations {
+            // a. For each element dn of the BoundNames of d, do
+            let name = declaration.binding_identifier.lexeme.clone();
+
+            // i. If IsConstantDeclaration of d is true, then
+            //        1. Perform ! env.CreateImmutableBinding(dn, true).
+            // ii. Else,
+            //        1. Perform ! env.CreateMutableBinding(dn, false).
+            if declaration.is_constant_declaration() {
+                self.create_immutable_binding(name, true);
+            } else {
+                self.create_mutable_binding(name, false);
+            }
         }
     }
 }
@@ -651,13 +1178,13 @@ struct EnvironmentRecord {
 impl ObjectEnvironmentRecord {
 
     // https://tc39.es/ecma262/#sec-object-environment-records-getbindingvalue-n-s
-    fn get_binding_value(&self, binding_id: String, is_strict: bool) -> CompletionRecord {
+    fn get_binding_value(&self, binding_id: Atom, is_strict: bool) -> CompletionRecord {
         // 1. Let bindingObject be envRec.[[BindingObject]].
         let bindingObject = &self.binding_object;
 
         //     2. Let value be ? HasProperty(bindingObject, N).
         //      https://tc39.es/ecma262/#sec-hasproperty
-        let value = completion!(bindingObject.borrow().has_property(PropertyKey::String(binding_id.clone())));
+        let value = completion!(bindingObject.borrow().has_property(PropertyKey::String(binding_id.to_string())));
 
         match &*value.value {
             ReferenceRecordOrJsValue::JSValue(ref value) => {
@@ -665,12 +1192,15 @@ impl ObjectEnvironmentRecord {
                     JSValue::Boolean(bool_value) => {
                         //     3. If value is false, then
                         if !bool_value {
-                            todo!()
                             // a. If S is false, return undefined; otherwise throw a ReferenceError exception.
+                            if is_strict {
+                                return create_error_throw_completion("ReferenceError", format!("{} is not defined", binding_id));
+                            }
+                            return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
                         } else {
                             //     4. Return ? Get(bindingObject, N).
                             // https://tc39.es/ecma262/#sec-get-o-p
-                            return completion!(bindingObject.borrow().get(&*Rc::new(PropertyKey::String(binding_id.clone())), bindingObject));
+                            return completion!(bindingObject.borrow().get(&*Rc::new(PropertyKey::String(binding_id.to_string())), bindingObject));
                         }
                     },
                     _ => { unreachable!() }
@@ -682,12 +1212,12 @@ impl ObjectEnvironmentRecord {
     }
 
     // https://tc39.es/ecma262/#sec-object-environment-records-hasbinding-n
-    fn has_binding(&self, binding_name: String) -> CompletionRecord {
+    fn has_binding(&self, binding_name: Atom) -> CompletionRecord {
         // 1. Let bindingObject be envRec.[[BindingObject]].
         let bindingObject = &self.binding_object;
         // 2. Let foundBinding be ? HasProperty(bindingObject, N).
         // TODO: PropertyKey could also be a Symbol?
-        let found_binding = completion!(ObjectEnvironmentRecord::has_property(bindingObject, PropertyKey::String(binding_name.clone())));
+        let found_binding = completion!(ObjectEnvironmentRecord::has_property(bindingObject, PropertyKey::String(binding_name.to_string())));
         // 3. If foundBinding is false, return false.
         match found_binding.value.deref() {
             ReferenceRecordOrJsValue::JSValue(js_value) => {
@@ -715,6 +1245,25 @@ impl ObjectEnvironmentRecord {
         return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(true))))));
     }
 
+    // https://tc39.es/ecma262/#sec-object-environment-records-setmutablebinding-n-v-s
+    fn set_mutable_binding(&self, binding_id: Atom, value: Rc<RefCell<JSValue>>, strict: bool) -> CompletionRecord {
+        // 1. Let stillExists be ? HasProperty(envRec.[[BindingObject]], N).
+        let still_exists = completion!(ObjectEnvironmentRecord::has_property(&self.binding_object, PropertyKey::String(binding_id.to_string())));
+
+        // 2. If stillExists is false and S is true, throw a ReferenceError exception.
+        if let ReferenceRecordOrJsValue::JSValue(still_exists) = still_exists.value.deref() {
+            if matches!(*still_exists.borrow(), JSValue::Boolean(false)) && strict {
+                return create_error_throw_completion("ReferenceError", format!("{} is not defined", binding_id));
+            }
+        }
+
+        // 3. Perform ? Set(envRec.[[BindingObject]], N, V, S).
+        completion!(Interpreter::set(&self.binding_object, Rc::new(PropertyKey::String(binding_id.to_string())), value, strict));
+
+        // 4. Return unused.
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+    }
+
     // https://tc39.es/ecma262/#sec-hasproperty
     fn has_property(object: &Rc<RefCell<JSObject>>, property_key: PropertyKey) -> CompletionRecord {
         return object.borrow().has_property(property_key);
@@ -723,7 +1272,7 @@ impl ObjectEnvironmentRecord {
 
 impl GlobalEnvironmentRecord {
     // https://tc39.es/ecma262/#sec-global-environment-records-getbindingvalue-n-s
-    fn get_binding_value(&self, binding_id: String, is_strict: bool) -> CompletionRecord {
+    fn get_binding_value(&self, binding_id: Atom, is_strict: bool) -> CompletionRecord {
         // 1. Let DclRec be envRec.[[DeclarativeRecord]].
         let declarative_record = &self.declarative_environment_record;
         // 2. If ! DclRec.HasBinding(N) is true, then
@@ -748,6 +1297,114 @@ impl GlobalEnvironmentRecord {
         }
 
     }
+
+    // https://tc39.es/ecma262/#sec-global-environment-records-setmutablebinding-n-v-s
+    fn set_mutable_binding(&self, binding_id: Atom, value: Rc<RefCell<JSValue>>, strict: bool) -> CompletionRecord {
+        // 1. Let DclRec be envRec.[[DeclarativeRecord]].
+        // 2. If ! DclRec.HasBinding(N) is true, then
+        if self.declarative_environment_record.borrow().variable_bindings.contains_key(&binding_id) {
+            // a. Return ? DclRec.SetMutableBinding(N, V, S).
+            return self.declarative_environment_record.borrow_mut().set_mutable_binding(binding_id, value, strict);
+        }
+
+        // 3. Let ObjRec be envRec.[[ObjectRecord]].
+        // 4. Return ? ObjRec.SetMutableBinding(N, V, S).
+        return self.object_environment_record.as_ref().unwrap().borrow().set_mutable_binding(binding_id, value, strict);
+    }
+
+    // https://tc39.es/ecma262/#sec-hasvardeclaration
+    fn has_var_declaration(&self, name: &Atom) -> bool {
+        // 1. Let varDeclaredNames be envRec.[[VarNames]].
+        // 2. If varDeclaredNames contains N, return true.
+        // 3. Return false.
+        return self.var_names.borrow().contains(name);
+    }
+
+    // https://tc39.es/ecma262/#sec-haslexicaldeclaration
+    fn has_lexical_declaration(&self, name: &Atom) -> bool {
+        // 1. Let DclRec be envRec.[[DeclarativeRecord]].
+        // 2. Return ! DclRec.HasBinding(N).
+        return self.declarative_environment_record.borrow().variable_bindings.contains_key(name);
+    }
+
+    // https://tc39.es/ecma262/#sec-hasrestrictedglobalproperty
+    fn has_restricted_global_property(&self, name: &str) -> bool {
+        // 1. Let ObjRec be envRec.[[ObjectRecord]].
+        // 2. Let globalObject be ObjRec.[[BindingObject]].
+        let global_object = Rc::clone(&self.object_environment_record.as_ref().unwrap().borrow().binding_object);
+
+        // 3. Let existingProp be ? globalObject.[[GetOwnProperty]](N).
+        let existing_property = global_object.borrow().own_property(&PropertyKey::String(name.to_string()));
+        match existing_property.as_deref() {
+            // 4. If existingProp is undefined, return false.
+            None => false,
+            // 5. If existingProp.[[Configurable]] is true, return false.
+            // 6. Return true.
+            Some(PropertyType::DataProperty(data_property)) => !data_property.configurable,
+            // TODO: Accessor properties do not record whether they are configurable, they are always treated as configurable
+            Some(PropertyType::AccessorProperty(_)) => false
+        }
+    }
+
+    // https://tc39.es/ecma262/#sec-createglobalvarbinding
+    fn create_global_var_binding(&self, binding_id: Atom, deletable: bool) -> CompletionRecord {
+        // 1. Let ObjRec be envRec.[[ObjectRecord]].
+        // 2. Let globalObject be ObjRec.[[BindingObject]].
+        let global_object = Rc::clone(&self.object_environment_record.as_ref().unwrap().borrow().binding_object);
+
+        // 3. Let hasProperty be ? HasOwnProperty(globalObject, N).
+        let has_property = global_object.borrow().own_property(&PropertyKey::String(binding_id.to_string())).is_some();
+
+        // 4. Let extensible be ? IsExtensible(globalObject).
+        let extensible = global_object.borrow().extensible;
+
+        // 5. If hasProperty is false and extensible is true, then
+        if !has_property && extensible {
+            // a. Perform ? ObjRec.CreateMutableBinding(N, D).
+            // b. Perform ? ObjRec.InitializeBinding(N, undefined).
+            let property_descriptor = PropertyDescriptor { property: Some(PropertyType::DataProperty(DataProperty { value: Rc::new(RefCell::new(JSValue::Undefined)), writable: true, enumerable: true, configurable: deletable })) };
+            completion!(global_object.borrow_mut().define_own_property(&PropertyKey::String(binding_id.to_string()), &property_descriptor));
+        }
+
+        // 6. If envRec.[[VarNames]] does not contain N, then
+        //        a. Append N to envRec.[[VarNames]].
+        if !self.has_var_declaration(&binding_id) {
+            self.var_names.borrow_mut().push(binding_id);
+        }
+
+        // 7. Return unused.
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+    }
+
+    // https://tc39.es/ecma262/#sec-createglobalfunctionbinding
+    fn create_global_function_binding(&self, binding_id: Atom, value: Rc<RefCell<JSValue>>, deletable: bool) -> CompletionRecord {
+        // 1. Let ObjRec be envRec.[[ObjectRecord]].
+        // 2. Let globalObject be ObjRec.[[BindingObject]].
+        let global_object = Rc::clone(&self.object_environment_record.as_ref().unwrap().borrow().binding_object);
+
+        // 3. Let existingProp be ? globalObject.[[GetOwnProperty]](N).
+        // 4. If existingProp is undefined or existingProp.[[Configurable]] is true, then
+        //        a. Let desc be the PropertyDescriptor { [[Value]]: V, [[Writable]]: true, [[Enumerable]]: true, [[Configurable]]: D }.
+        // 5. Else,
+        //        a. Let desc be the PropertyDescriptor { [[Value]]: V }.
+        // TODO: Partial descriptors are not supported, so the property is always replaced as in step 4
+        let property_descriptor = PropertyDescriptor { property: Some(PropertyType::DataProperty(DataProperty { value: Rc::clone(&value), writable: true, enumerable: true, configurable: deletable })) };
+
+        // 6. Perform ? DefinePropertyOrThrow(globalObject, N, desc).
+        completion!(global_object.borrow_mut().define_own_property(&PropertyKey::String(binding_id.to_string()), &property_descriptor));
+
+        // 7. Perform ? Set(globalObject, N, V, false).
+        completion!(Interpreter::set(&global_object, Rc::new(PropertyKey::String(binding_id.to_string())), value, false));
+
+        // 8. If envRec.[[VarNames]] does not contain N, then
+        //        a. Append N to envRec.[[VarNames]].
+        if !self.has_var_declaration(&binding_id) {
+            self.var_names.borrow_mut().push(binding_id);
+        }
+
+        // 9. Return unused.
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+    }
 }
 impl EnvironmentRecord {
     pub fn new(type_: EnvironmentRecordType) -> EnvironmentRecord {
@@ -757,39 +1414,129 @@ impl EnvironmentRecord {
         }
     }
 
-    // https://tc39.es/ecma262/#table-abstract-methods-of-environment-records
-    fn has_binding(&self, binding_name: String) -> CompletionRecord {
+    // https://tc39.es/ecma262/#sec-newdeclarativeenvironment
+    fn new_declarative_environment(outer_environment_record: Option<Rc<RefCell<EnvironmentRecord>>>) -> Rc<RefCell<EnvironmentRecord>> {
+        // 1. Let env be a new Declarative Environment Record containing no bindings.
+        // 2. Set env.[[OuterEnv]] to E.
+        // 3. Return env.
+        return Rc::new(RefCell::new(EnvironmentRecord {
+            environment_record_type: EnvironmentRecordType::DeclarativeEnvironmentRecord(Rc::new(RefCell::new(DeclarativeEnvironmentRecord { variable_bindings: HashMap::new(), function_environment_record: None }))),
+            outer_environment_record,
+        }));
+    }
+
+    // The declarative record of a declarative or function environment, which is where their bindings live
+    fn declarative_record(&self) -> Rc<RefCell<DeclarativeEnvironmentRecord>> {
         match &self.environment_record_type {
-            // TODO: https://tc39.es/ecma262/#sec-global-environment-records-hasbinding-n
-            EnvironmentRecordType::GlobalEnvironmentRecord(global_environment_record) => {
-                // 1. Let DclRec be envRec.[[DeclarativeRecord]].
-                let declarative_record = &global_environment_record.borrow().declarative_environment_record;
-                // TODO: 2. If ! DclRec.HasBinding(N) is true, return true.
+            EnvironmentRecordType::DeclarativeEnvironmentRecord(declarative_record) => Rc::clone(declarative_record),
+            _ => { unreachable!() }
+        }
+    }
 
-                // 3. Let ObjRec be envRec.[[ObjectRecord]].
-                let object_record = &global_environment_record.borrow().object_environment_record;
-                // 4. Return ? ObjRec.HasBinding(N).
-                return completion!(object_record.clone().unwrap().borrow().has_binding(binding_name.clone()));
+    // https://tc39.es/ecma262/#table-abstract-methods-of-environment-records
+    // HasThisBinding, only function environments that are not for arrow functions and the global environment have a this binding
+    fn has_this_binding(&self) -> bool {
+        match &self.environment_record_type {
+            // https://tc39.es/ecma262/#sec-function-environment-records-hasthisbinding
+            EnvironmentRecordType::DeclarativeEnvironmentRecord(declarative_record) => {
+                match &declarative_record.borrow().function_environment_record {
+                    // 1. If envRec.[[ThisBindingStatus]] is lexical, return false; otherwise, return true.
+                    Some(function_environment_record) => !matches!(function_environment_record.this_binding_status, ThisBindingStatus::Lexical),
+                    // https://tc39.es/ecma262/#sec-declarative-environment-records-hasthisbinding
+                    // 1. Return false.
+                    None => false
+                }
             },
-            _ => { todo!("has_binding: Support other environment record types") }
+            // https://tc39.es/ecma262/#sec-global-environment-records-hasthisbinding
+            // 1. Return true.
+            EnvironmentRecordType::GlobalEnvironmentRecord(_) => true,
+            // https://tc39.es/ecma262/#sec-object-environment-records-hasthisbinding
+            // 1. Return false.
+            _ => false
         }
     }
-}
 
-type MutableBinding = Rc<RefCell<JSValue>>;
-type ImmutableBinding = Rc<JSValue>;
+    // https://tc39.es/ecma262/#table-abstract-methods-of-environment-records
+    // GetThisBinding, only called on environments for which HasThisBinding is true
+    fn get_this_binding(&self) -> CompletionRecord {
+        match &self.environment_record_type {
+            EnvironmentRecordType::DeclarativeEnvironmentRecord(declarative_record) => {
+                return declarative_record.borrow().function_environment_record.as_ref().unwrap().get_this_binding();
+            },
+            // https://tc39.es/ecma262/#sec-global-environment-records-getthisbinding
+            EnvironmentRecordType::GlobalEnvironmentRecord(global_record) => {
+                // 1. Return envRec.[[GlobalThisValue]].
+                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Object(Rc::clone(&global_record.borrow().global_this_value)))))));
+            },
+            _ => { unreachable!() }
+        }
+    }
 
-#[derive(Debug)]
-enum Binding {
-    MutableBinding(MutableBinding),
-    ImmutableBinding(ImmutableBinding)
-}
+    // https://tc39.es/ecma262/#table-abstract-methods-of-environment-records
+    fn initialize_binding(&self, binding_name: Atom, value: Rc<RefCell<JSValue>>) -> CompletionRecord {
+        match &self.environment_record_type {
+            // https://tc39.es/ecma262/#sec-declarative-environment-records-initializebinding-n-v
+            EnvironmentRecordType::DeclarativeEnvironmentRecord(declarative_record) => {
+                return declarative_record.borrow_mut().initialize_binding(binding_name, value);
+            },
+            // https://tc39.es/ecma262/#sec-global-environment-records-initializebinding-n-v
+            EnvironmentRecordType::GlobalEnvironmentRecord(global_environment_record) => {
+                // 1. Let DclRec be envRec.[[DeclarativeRecord]].
+                // 2. If ! DclRec.HasBinding(N) is true, then
+                //        a. Return ! DclRec.InitializeBinding(N, V).
+                // 3. Assert: If the binding exists, it must be in the Object Environment Record.
+                // NOTE: Var bindings on the global object are initialized when they are created, so only lexical bindings get here
+                return global_environment_record.borrow().declarative_environment_record.borrow_mut().initialize_binding(binding_name, value);
+            },
+            _ => { todo!("initialize_binding: Support other environment record types") }
+        }
+    }
+
+    // https://tc39.es/ecma262/#table-abstract-methods-of-environment-records
+    fn has_binding(&self, binding_name: Atom) -> CompletionRecord {
+        match &self.environment_record_type {
+            // https://tc39.es/ecma262/#sec-declarative-environment-records-hasbinding-n
+            EnvironmentRecordType::DeclarativeEnvironmentRecord(declarative_record) => {
+                return declarative_record.borrow().has_binding(binding_name);
+            },
+            // https://tc39.es/ecma262/#sec-object-environment-records-hasbinding-n
+            EnvironmentRecordType::ObjectEnvironmentRecord(object_record) => {
+                return object_record.borrow().has_binding(binding_name);
+            },
+            // https://tc39.es/ecma262/#sec-global-environment-records-hasbinding-n
+            EnvironmentRecordType::GlobalEnvironmentRecord(global_environment_record) => {
+                // 1. Let DclRec be envRec.[[DeclarativeRecord]].
+                let declarative_record = &global_environment_record.borrow().declarative_environment_record;
+                // 2. If ! DclRec.HasBinding(N) is true, return true.
+                if declarative_record.borrow().variable_bindings.contains_key(&binding_name) {
+                    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(true))))));
+                }
+
+                // 3. Let ObjRec be envRec.[[ObjectRecord]].
+                let object_record = &global_environment_record.borrow().object_environment_record;
+                // 4. Return ? ObjRec.HasBinding(N).
+                return completion!(object_record.clone().unwrap().borrow().has_binding(binding_name.clone()));
+            },
+            _ => { todo!("has_binding: Support other environment record types") }
+        }
+    }
+}
+
+type MutableBinding = Rc<RefCell<JSValue>>;
+type ImmutableBinding = Rc<RefCell<JSValue>>;
+
+// A binding without a value has been created but not yet initialized
+#[derive(Debug)]
+enum Binding {
+    MutableBinding(Option<MutableBinding>),
+    ImmutableBinding { value: Option<ImmutableBinding>, strict: bool }
+}
 
 #[derive(Debug)]
 struct DeclarativeEnvironmentRecord {
     // TODO: Should not be of an option type
     function_environment_record: Option<FunctionEnvironmentRecord>,
-    variable_bindings: HashMap<String, Binding>,
+    variable_bindings: HashMap<Atom, Binding>,
 }
 
 #[derive(Debug)]
@@ -802,10 +1549,42 @@ enum ThisBindingStatus {
 #[derive(Debug)]
 // https://tc39.es/ecma262/#table-additional-fields-of-function-environment-records
 struct FunctionEnvironmentRecord {
-    this_value: Box<JSValue>,
+    this_value: Rc<RefCell<JSValue>>,
     this_binding_status: ThisBindingStatus,
-    function_object: JSObject,
-    new_target: Option<JSObject>,
+    function_object: Rc<RefCell<JSObject>>,
+    new_target: Option<Rc<RefCell<JSObject>>>,
+}
+
+impl FunctionEnvironmentRecord {
+    // https://tc39.es/ecma262/#sec-bindthisvalue
+    fn bind_this_value(&mut self, value: Rc<RefCell<JSValue>>) -> CompletionRecord {
+        // 1. Assert: envRec.[[ThisBindingStatus]] is not lexical.
+        // 2. If envRec.[[ThisBindingStatus]] is initialized, throw a ReferenceError exception.
+        if let ThisBindingStatus::Initialized = self.this_binding_status {
+            return create_error_throw_completion("ReferenceError", String::from("Super constructor may only be called once"));
+        }
+
+        // 3. Set envRec.[[ThisValue]] to V.
+        self.this_value = value;
+
+        // 4. Set envRec.[[ThisBindingStatus]] to initialized.
+        self.this_binding_status = ThisBindingStatus::Initialized;
+
+        // 5. Return unused.
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+    }
+
+    // https://tc39.es/ecma262/#sec-function-environment-records-getthisbinding
+    fn get_this_binding(&self) -> CompletionRecord {
+        // 1. Assert: envRec.[[ThisBindingStatus]] is not lexical.
+        // 2. If envRec.[[ThisBindingStatus]] is uninitialized, throw a ReferenceError exception.
+        if let ThisBindingStatus::Uninitialized = self.this_binding_status {
+            return create_error_throw_completion("ReferenceError", String::from("Must call super constructor in derived class before accessing 'this'"));
+        }
+
+        // 3. Return envRec.[[ThisValue]].
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::clone(&self.this_value))));
+    }
 }
 
 #[derive(Debug)]
@@ -818,13 +1597,147 @@ struct ObjectEnvironmentRecord {
 // https://tc39.es/ecma262/#table-additional-fields-of-global-environment-records
 struct GlobalEnvironmentRecord {
     object_environment_record: Option<Rc<RefCell<ObjectEnvironmentRecord>>>,
-    global_this_value: Option<Box<JSObject>>,
-    declarative_environment_record: RefCell<DeclarativeEnvironmentRecord>
+    global_this_value: Rc<RefCell<JSObject>>,
+    declarative_environment_record: RefCell<DeclarativeEnvironmentRecord>,
+    // The names bound by var and function declarations in global code
+    var_names: RefCell<Vec<Atom>>,
+}
+
+// The references the cycle collector follows, see gc.rs for the ownership model
+impl Trace for JSObject {
+    // TODO: The values an abstract closure captures and the reactions of a promise are not looked into, so they are always kept
+    fn trace(&self, tracer: &mut Tracer) {
+        for property in self.values.values() {
+            tracer.visit(property);
+        }
+        if let Some(prototype) = &self.prototype {
+            tracer.visit(prototype);
+        }
+        if let Some(ecmascript_function) = &self.ecmascript_function {
+            tracer.visit(&ecmascript_function.environment);
+        }
+        match &self.platform_object {
+            Some(PlatformObject::Node(node)) => tracer.visit(node),
+            Some(PlatformObject::MutationObserver(observer)) => tracer.visit(observer),
+            _ => {}
+        }
+    }
+
+    // The table of properties and the names of their keys, the values are cells of their own
+    fn heap_size(&self) -> usize {
+        let keys: usize = self.values.keys().map(|key| match key {
+            PropertyKey::String(name) => name.capacity(),
+            PropertyKey::Symbol(_) => 0
+        }).sum();
+        return self.values.capacity() * std::mem::size_of::<(PropertyKey, Rc<PropertyType>)>() + keys;
+    }
+
+    fn unlink(&mut self) {
+        self.values.clear();
+        self.prototype = None;
+        self.call_closure = None;
+        self.platform_object = None;
+        self.ecmascript_function = None;
+        self.promise_data = None;
+    }
+}
+
+impl Trace for PropertyType {
+    fn trace(&self, tracer: &mut Tracer) {
+        match self {
+            PropertyType::DataProperty(data_property) => tracer.visit(&data_property.value),
+            PropertyType::AccessorProperty(accessor_property) => {
+                for function in accessor_property.get.iter().chain(accessor_property.set.iter()) {
+                    tracer.visit(function);
+                }
+            }
+        }
+    }
+}
+
+impl Trace for JSValue {
+    fn trace(&self, tracer: &mut Tracer) {
+        if let JSValue::Object(object) = self {
+            tracer.visit(object);
+        }
+    }
+
+    fn heap_size(&self) -> usize {
+        return match self {
+            JSValue::String(string) => string.capacity(),
+            _ => 0
+        };
+    }
+}
+
+impl Trace for EnvironmentRecord {
+    fn trace(&self, tracer: &mut Tracer) {
+        if let Some(outer_environment_record) = &self.outer_environment_record {
+            tracer.visit(outer_environment_record);
+        }
+        match &self.environment_record_type {
+            EnvironmentRecordType::DeclarativeEnvironmentRecord(declarative_record) => tracer.visit(declarative_record),
+            EnvironmentRecordType::FunctionEnvironmentRecord(function_record) => function_record.trace(tracer),
+            EnvironmentRecordType::ObjectEnvironmentRecord(object_record) => tracer.visit(object_record),
+            EnvironmentRecordType::GlobalEnvironmentRecord(global_record) => tracer.visit(global_record),
+        }
+    }
+}
+
+impl Trace for DeclarativeEnvironmentRecord {
+    fn heap_size(&self) -> usize {
+        return self.variable_bindings.capacity() * std::mem::size_of::<(Atom, Binding)>();
+    }
+
+    fn trace(&self, tracer: &mut Tracer) {
+        for binding in self.variable_bindings.values() {
+            match binding {
+                Binding::MutableBinding(Some(value)) | Binding::ImmutableBinding { value: Some(value), .. } => tracer.visit(value),
+                _ => {}
+            }
+        }
+        if let Some(function_record) = &self.function_environment_record {
+            function_record.trace(tracer);
+        }
+    }
+}
+
+impl Trace for FunctionEnvironmentRecord {
+    fn trace(&self, tracer: &mut Tracer) {
+        tracer.visit(&self.this_value);
+        tracer.visit(&self.function_object);
+        if let Some(new_target) = &self.new_target {
+            tracer.visit(new_target);
+        }
+    }
+}
+
+impl Trace for ObjectEnvironmentRecord {
+    fn trace(&self, tracer: &mut Tracer) {
+        tracer.visit(&self.binding_object);
+    }
+}
+
+impl Trace for GlobalEnvironmentRecord {
+    fn trace(&self, tracer: &mut Tracer) {
+        if let Some(object_record) = &self.object_environment_record {
+            tracer.visit(object_record);
+        }
+        tracer.visit(&self.global_this_value);
+        if let Ok(declarative_record) = self.declarative_environment_record.try_borrow() {
+            declarative_record.trace(tracer);
+        }
+    }
 }
 
 impl AstVisitor<CompletionRecord> for Interpreter {
+    // https://tc39.es/ecma262/#sec-expression-statement-runtime-semantics-evaluation
     fn visit_expression_statement(&mut self, expression: &ExpressionStatement) -> CompletionRecord {
-        return self.evaluate(expression);
+        // 1. Let exprRef be ? Evaluation of Expression.
+        let expression_reference = completion!(self.evaluate(expression));
+
+        // 2. Return ? GetValue(exprRef).
+        return self.get_value(expression_reference.value.clone());
     }
 
     // https://tc39.es/ecma262/#sec-evaluatestringornumericbinaryexpression
@@ -833,16 +1746,38 @@ impl AstVisitor<CompletionRecord> for Interpreter {
         let left_expression = completion!(self.evaluate(&*expression.left));
 
         // 2. Let lVal be ? GetValue(lRef).
-        let left_value =  completion!(Interpreter::get_value(left_expression.value.clone()));
+        let left_value =  completion!(self.get_value(left_expression.value.clone()));
 
         // 3. Let rRef be ? Evaluation of rightOperand.
         let right_expression = completion!(self.evaluate(&*expression.right));
 
         // 4. Let rVal be ? GetValue(rRef).
-        let right_value = completion!(Interpreter::get_value(right_expression.value.clone()));
+        let right_value = completion!(self.get_value(right_expression.value.clone()));
+
+        self.set_position(&expression.operator);
 
         match (&*left_value.value, &*right_value.value) {
+            // https://tc39.es/ecma262/#sec-relational-operators-runtime-semantics-evaluation
+            // RelationalExpression : RelationalExpression instanceof ShiftExpression
+            (ReferenceRecordOrJsValue::JSValue(l_value), ReferenceRecordOrJsValue::JSValue(r_value)) if expression.operator.token_type == TokenType::INSTANCEOF => {
+                // 5. Return ? InstanceofOperator(lVal, rVal).
+                return self.instanceof_operator(Rc::clone(l_value), Rc::clone(r_value));
+            },
             (ReferenceRecordOrJsValue::JSValue(l_value), ReferenceRecordOrJsValue::JSValue(r_value)) => {
+                // The strings a + concatenates are counted before they are, an operand that is converted to a string counts when that string is made
+                if expression.operator.token_type == TokenType::PLUS {
+                    let string_length = |value: &Rc<RefCell<JSValue>>| match &*value.borrow() {
+                        JSValue::String(string) => string.len(),
+                        _ => 0
+                    };
+                    let length = string_length(l_value) + string_length(r_value);
+                    if length > 0 {
+                        if let Some(range_error) = self.reserve_heap(length, 0) {
+                            return range_error;
+                        }
+                    }
+                }
+
                 // 5. Return ? ApplyStringOrNumericBinaryOperator(lVal, opText, rVal).
                 return completion!(Interpreter::apply_string_or_numeric_binary_operator(l_value.clone(), r_value.clone(), &expression.operator.token_type));
             }
@@ -870,7 +1805,16 @@ impl AstVisitor<CompletionRecord> for Interpreter {
                 let js_value = JSValue::Null;
                 return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(js_value)))));
             }
-
+            // https://tc39.es/ecma262/#sec-regular-expression-literals-runtime-semantics-evaluation
+            // 1. Let pattern be CodePointsToString(BodyText of RegularExpressionLiteral).
+            // 2. Let flags be CodePointsToString(FlagText of RegularExpressionLiteral).
+            // 3. Return ! RegExpCreate(pattern, flags).
+            // TODO: An invalid pattern or flags should be an early SyntaxError rather than one thrown when the literal is evaluated
+            Literal::RegExp { pattern, flags } => {
+                let pattern = Rc::new(RefCell::new(JSValue::String(pattern.clone())));
+                let flags = Rc::new(RefCell::new(JSValue::String(flags.clone())));
+                return regexp_create(self, pattern, flags);
+            }
         }
     }
 
@@ -887,7 +1831,7 @@ impl AstVisitor<CompletionRecord> for Interpreter {
             // httÓps://tc39.es/ecma262/#sec-unary-plus-operator-runtime-semantics-evaluation
             TokenType::PLUS => {
                 // 2. Return ? ToNumber(? GetValue(expr)).
-                match completion!(Interpreter::get_value(right.value.clone())).value.deref() {
+                match completion!(self.get_value(right.value.clone())).value.deref() {
                     ReferenceRecordOrJsValue::JSValue(js_value) => {
                         return completion!(Interpreter::to_number(js_value.clone()));
                     },
@@ -897,7 +1841,7 @@ impl AstVisitor<CompletionRecord> for Interpreter {
             // https://tc39.es/ecma262/#sec-unary-minus-operator-runtime-semantics-evaluation
             TokenType::MINUS => {
                 // 2. Let oldValue be ? ToNumeric(? GetValue(expr)).
-                let right_value_js = completion!(Interpreter::get_value(right.value.clone()));
+                let right_value_js = completion!(self.get_value(right.value.clone()));
                 let old_value: CompletionRecord = match right_value_js.value.deref() {
                     ReferenceRecordOrJsValue::JSValue(value) => {
                         completion!(Interpreter::to_numeric(value.clone()))
@@ -926,6 +1870,67 @@ impl AstVisitor<CompletionRecord> for Interpreter {
                     _ => { unreachable!("Expected JSValue") }
                 }
             },
+            // https://tc39.es/ecma262/#sec-delete-operator-runtime-semantics-evaluation
+            TokenType::DELETE => {
+                match right.value.deref() {
+                    // 2. If ref is not a Reference Record, return true.
+                    ReferenceRecordOrJsValue::ReferenceRecord(reference) => {
+                        match reference.base.deref() {
+                            // 3. If IsUnresolvableReference(ref) is true, then
+                            BaseValue::Unresolvable => {
+                                // a. Assert: ref.[[Strict]] is false.
+                                // b. Return true.
+                                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(true))))));
+                            },
+                            // 4. If IsPropertyReference(ref) is true, then
+                            BaseValue::JSValue(base) => {
+                                // a. Assert: IsPrivateReference(ref) is false.
+                                // b. TODO: If IsSuperReference(ref) is true, throw a ReferenceError exception.
+                                // c. Let baseObj be ? ToObject(ref.[[Base]]).
+                                // TODO: Primitives should be wrapped in their wrapper objects, their properties can not be deleted
+                                let base_object = match &*base.borrow() {
+                                    JSValue::Object(object) => Rc::clone(object),
+                                    JSValue::Undefined | JSValue::Null => {
+                                        return self.throw_type_error(format!("Cannot convert {} to object", Interpreter::type_name(&base.borrow())));
+                                    },
+                                    _ => {
+                                        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(true))))));
+                                    }
+                                };
+
+                                // d. If ref.[[ReferencedName]] is not a property key, then
+                                // i. Set ref.[[ReferencedName]] to ? ToPropertyKey(ref.[[ReferencedName]]).
+                                let property_key = match &reference.referenced_name {
+                                    JSValue::String(name) => PropertyKey::String(name.clone()),
+                                    _ => { todo!("Support Symbols Properly") }
+                                };
+
+                                // e. Let deleteStatus be ? baseObj.[[Delete]](ref.[[ReferencedName]]).
+                                let delete_status = base_object.borrow_mut().delete(&property_key);
+
+                                // f. If deleteStatus is false and ref.[[Strict]] is true, throw a TypeError exception.
+                                if !delete_status && reference.strict {
+                                    return self.throw_type_error(format!("Cannot delete property '{}'", Interpreter::referenced_name_to_string(&reference.referenced_name)));
+                                }
+
+                                // g. Return deleteStatus.
+                                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(delete_status))))));
+                            },
+                            // 5. Else,
+                            BaseValue::EnvironmentRecord(_) => {
+                                // a. Let base be ref.[[Base]].
+                                // b. Assert: base is an Environment Record.
+                                // c. TODO: Return ? base.DeleteBinding(ref.[[ReferencedName]]).
+                                // Bindings created by declarations can not be deleted
+                                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(false))))));
+                            }
+                        }
+                    },
+                    _ => {
+                        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(true))))));
+                    }
+                }
+            },
             // https://tc39.es/ecma262/#sec-bitwise-not-operator-runtime-semantics-evaluation
             TokenType::BITWISE_NOT => {
                 todo!();
@@ -933,7 +1938,7 @@ impl AstVisitor<CompletionRecord> for Interpreter {
             // https://tc39.es/ecma262/#sec-logical-not-operator-runtime-semantics-evaluation
             TokenType::BANG => {
                 // 2. Let oldValue be ToBoolean(? GetValue(expr)).
-                let old_value: Rc<RefCell<JSValue>> = match completion!(Interpreter::get_value(right.value.clone())).value.deref() {
+                let old_value: Rc<RefCell<JSValue>> = match completion!(self.get_value(right.value.clone())).value.deref() {
                     ReferenceRecordOrJsValue::JSValue(js_value) => {
                         Interpreter::to_boolean(js_value.clone())
                     },
@@ -973,8 +1978,9 @@ impl AstVisitor<CompletionRecord> for Interpreter {
         // 2. Let lhs be ? ResolveBinding(bindingId).
         let left_hand_side =  completion!(self.resolve_binding(binding_id.clone(), None));
 
-        // 3. TODO: If IsAnonymousFunctionDefinition(Initializer) is true, then
-
+        // 3. If IsAnonymousFunctionDefinition(Initializer) is true, then
+        //        a. Let value be ? NamedEvaluation of Initializer with argument bindingId.
+        // NOTE: The initializer is evaluated as an assignment to bindingId, which performs the NamedEvaluation
         // 4. Else
         // a. Let rhs be ? Evaluation of Initializer.
         let right_hand_side = match &expression.initializer {
@@ -988,7 +1994,7 @@ impl AstVisitor<CompletionRecord> for Interpreter {
          };
 
         // b. Let value be ? GetValue(rhs).
-        let value =  completion!(Interpreter::get_value(right_hand_side.value.clone()));
+        let value =  completion!(self.get_value(right_hand_side.value.clone()));
 
         // 5. Perform ? PutValue(lhs, value).
         match &*value.value {
@@ -998,26 +2004,180 @@ impl AstVisitor<CompletionRecord> for Interpreter {
             _ => { }
         }
 
-        //print the global obj
-
-        println!("\nGlobal Object {:?}\n", self.global_object());
-
         // Return empty.
         return CompletionRecord { type_: CompletionRecordType::Normal, value: Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))), target: None };
     }
 
+    // https://tc39.es/ecma262/#sec-let-and-const-declarations-runtime-semantics-evaluation
+    fn visit_lexical_declaration(&mut self, declaration: &LexicalDeclaration) -> CompletionRecord {
+        // LexicalBinding : BindingIdentifier Initializer
+        // 1. Let bindingId be the StringValue of BindingIdentifier.
+        let binding_id = declaration.binding_identifier.lexeme.clone();
+
+        // 2. Let lhs be ! ResolveBinding(bindingId).
+        let left_hand_side = completion!(self.resolve_binding(binding_id.clone(), None));
+
+        let value = match &declaration.initializer {
+            Some(initializer) => {
+                // 3. If IsAnonymousFunctionDefinition(Initializer) is true, then
+                //        a. Let value be ? NamedEvaluation of Initializer with argument bindingId.
+                // 4. Else,
+                //        a. Let rhs be ? Evaluation of Initializer.
+                let right_hand_side = if initializer.is_anonymous_function_definition() {
+                    completion!(self.named_evaluation(initializer, &binding_id))
+                } else {
+                    completion!(self.evaluate(initializer))
+                };
+
+                //        b. Let value be ? GetValue(rhs).
+                match completion!(self.get_value(right_hand_side.value)).value.deref() {
+                    ReferenceRecordOrJsValue::JSValue(value) => Rc::clone(value),
+                    _ => { unreachable!() }
+                }
+            },
+            // LexicalBinding : BindingIdentifier
+            // 2. Perform ! InitializeReferencedBinding(lhs, undefined).
+            None => Rc::new(RefCell::new(JSValue::Undefined))
+        };
+
+        // 5. Perform ! InitializeReferencedBinding(lhs, value).
+        Interpreter::initialize_referenced_binding(&left_hand_side.value, value);
+
+        // 6. Return empty.
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+    }
+
     // https://tc39.es/ecma262/#sec-identifiers-runtime-semantics-evaluation
     fn visit_identifier_expression(&mut self, expression: &IdentifierExpression) -> CompletionRecord {
+        self.set_position(&expression.binding_identifier);
         return self.resolve_binding(expression.binding_identifier.lexeme.clone(), None);
     }
 
+    // https://tc39.es/ecma262/#sec-function-calls-runtime-semantics-evaluation
     fn visit_call_expression(&mut self, expression: &CallExpression) -> CompletionRecord {
-        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))))
+        // 1. Let memberExpr be the MemberExpression of expr.
+        // 3. Let ref be ? Evaluation of memberExpr.
+        let reference = completion!(self.evaluate(&expression.callee));
+
+        // 4. Let func be ? GetValue(ref).
+        let function = match completion!(self.get_value(reference.value.clone())).value.deref() {
+            ReferenceRecordOrJsValue::JSValue(function) => Rc::clone(function),
+            _ => { unreachable!() }
+        };
+
+        // TODO: 5. If ref is a Reference Record, IsPropertyReference(ref) is false, and ref.[[ReferencedName]] is "eval", then
+
+        // 7. Return ? EvaluateCall(func, ref, arguments, tailCall).
+        // https://tc39.es/ecma262/#sec-evaluatecall
+        // 1. If ref is a Reference Record, then
+        let this_value = match reference.value.deref() {
+            ReferenceRecordOrJsValue::ReferenceRecord(reference_record) => {
+                match reference_record.base.as_ref() {
+                    // a. If IsPropertyReference(ref) is true, then
+                    //    i. Let thisValue be GetThisValue(ref).
+                    BaseValue::JSValue(base) => Rc::clone(base),
+                    // b. Else,
+                    //    i. Let refEnv be ref.[[Base]].
+                    //    iii. Let thisValue be refEnv.WithBaseObject().
+                    _ => Rc::new(RefCell::new(JSValue::Undefined))
+                }
+            },
+            // 2. Else,
+            //    a. Let thisValue be undefined.
+            _ => Rc::new(RefCell::new(JSValue::Undefined))
+        };
+
+        // 3. Let argList be ? ArgumentListEvaluation of arguments.
+        let mut arguments = Vec::new();
+        for argument in &expression.arguments {
+            let argument_reference = completion!(self.evaluate(argument));
+            match completion!(self.get_value(argument_reference.value.clone())).value.deref() {
+                ReferenceRecordOrJsValue::JSValue(argument_value) => arguments.push(Rc::clone(argument_value)),
+                _ => { unreachable!() }
+            }
+        }
+
+        // The arguments may have moved the position on, the frame of the caller is at the call itself
+        self.set_position(expression.position());
+
+        // 4. If func is not an Object, throw a TypeError exception.
+        // 5. If IsCallable(func) is false, throw a TypeError exception.
+        if !Interpreter::is_callable(&function.borrow()) {
+            let callee = source_text(&expression.callee);
+            return self.throw_type_error(format!("{} is not a function", callee));
+        }
+
+        // 7. Return ? Call(func, thisValue, argList).
+        return completion!(self.call(function, this_value, arguments));
+    }
+
+    // https://tc39.es/ecma262/#sec-property-accessors-runtime-semantics-evaluation
+    // MemberExpression : MemberExpression . IdentifierName
+    fn visit_member_expression(&mut self, expression: &MemberExpression) -> CompletionRecord {
+        // 1. Let baseReference be ? Evaluation of MemberExpression.
+        let base_reference = completion!(self.evaluate(&expression.object));
+
+        // 2. Let baseValue be ? GetValue(baseReference).
+        let base_value = match completion!(self.get_value(base_reference.value.clone())).value.deref() {
+            ReferenceRecordOrJsValue::JSValue(base_value) => Rc::clone(base_value),
+            _ => { unreachable!() }
+        };
+
+        // 3. If the source text matched by this MemberExpression is strict mode code, let strict be true; else let strict be false.
+        let strict = self.running_execution_context().strict;
+        let referenced_name = match &expression.property {
+            // MemberExpression : MemberExpression . IdentifierName
+            // 4. Return EvaluatePropertyAccessWithIdentifierKey(baseValue, IdentifierName, strict).
+            // https://tc39.es/ecma262/#sec-evaluate-property-access-with-identifier-key
+            MemberProperty::IdentifierName(property_name) => {
+                // 1. Let propertyNameString be the StringValue of IdentifierName.
+                JSValue::String(property_name.lexeme.to_string())
+            },
+            // MemberExpression : MemberExpression [ Expression ]
+            // 4. Return ? EvaluatePropertyAccessWithExpressionKey(baseValue, Expression, strict).
+            // https://tc39.es/ecma262/#sec-evaluate-property-access-with-expression-key
+            MemberProperty::Expression(property_expression) => {
+                // 1. Let propertyNameReference be ? Evaluation of expression.
+                let property_name_reference = completion!(self.evaluate(property_expression));
+
+                // 2. Let propertyNameValue be ? GetValue(propertyNameReference).
+                let property_name_value = match completion!(self.get_value(property_name_reference.value.clone())).value.deref() {
+                    ReferenceRecordOrJsValue::JSValue(property_name_value) => Rc::clone(property_name_value),
+                    _ => { unreachable!() }
+                };
+
+                // 3. NOTE: In most cases, ToPropertyKey will be performed on propertyNameValue immediately after this step.
+                // The property key is computed here so the Reference Record always holds a String
+                JSValue::String(completion_to_string(completion!(self.to_property_key(property_name_value))))
+            }
+        };
+
+        // Return the Reference Record { [[Base]]: baseValue, [[ReferencedName]]: propertyNameString, [[Strict]]: strict, [[ThisValue]]: empty }.
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::ReferenceRecord(ReferenceRecord {
+            base: Rc::new(BaseValue::JSValue(base_value)),
+            referenced_name,
+            strict,
+            this_value: None,
+        })));
     }
 
+    // https://tc39.es/ecma262/#sec-block-runtime-semantics-evaluation
     // https://tc39.es/ecma262/#sec-block-runtime-semantics-evaluation
     fn visit_block_statement(&mut self, expression: &BlockStatement) -> CompletionRecord {
-       // TODO: Ensure the correct environment record is used and scoped to the block
+        // Block : { StatementList }
+        // 1. Let oldEnv be the running execution context's LexicalEnvironment.
+        let old_environment = Rc::clone(&self.running_execution_context().lexical_environment_record);
+
+        // 2. Let blockEnv be NewDeclarativeEnvironment(oldEnv).
+        let block_environment = EnvironmentRecord::new_declarative_environment(Some(Rc::clone(&old_environment)));
+
+        // 3. Perform BlockDeclarationInstantiation(StatementList, blockEnv).
+        completion!(self.block_declaration_instantiation(&expression.statements, &block_environment));
+
+        // 4. Set the running execution context's LexicalEnvironment to blockEnv.
+        self.execution_contexts.last_mut().unwrap().lexical_environment_record = block_environment;
+
+        // 5. Let blockValue be Completion(Evaluation of StatementList).
         let mut value: CompletionRecord = CompletionRecord {
             type_: CompletionRecordType::Normal,
             value: Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))),
@@ -1026,40 +2186,148 @@ impl AstVisitor<CompletionRecord> for Interpreter {
 
         for statement in expression.statements.iter() {
             value = self.execute(statement);
+
+            // An abrupt completion such as a return ends the block early
+            if !matches!(value.type_, CompletionRecordType::Normal) {
+                break;
+            }
         }
 
+        // 6. Set the running execution context's LexicalEnvironment to oldEnv.
+        self.execution_contexts.last_mut().unwrap().lexical_environment_record = old_environment;
+
+        // 7. Return ? blockValue.
         // The value of a StatementList is the value of the last value-producing item in the StatementList.
-        return value; // TODO: Remove
+        return value;
     }
 
     // https://tc39.es/ecma262/#sec-object-initializer-runtime-semantics-evaluation
     fn visit_object_literal_expression(&mut self, object_literal_expression: &ObjectLiteralExpression) -> CompletionRecord {
-        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+        // 1. Let obj be OrdinaryObjectCreate(%Object.prototype%).
+        let object = self.create_ordinary_object();
+
+        // 2. Perform ? PropertyDefinitionEvaluation of PropertyDefinitionList with argument obj.
+        // https://tc39.es/ecma262/#sec-runtime-semantics-propertydefinitionevaluation
+        // TODO: __proto__: value should set the prototype instead of defining a property
+        for property_definition in &object_literal_expression.property_definitions {
+            // 1. Let propKey be ? Evaluation of PropertyName.
+            let property_key = match &property_definition.property_name {
+                PropertyName::IdentifierName(identifier_name) => identifier_name.lexeme.to_string(),
+                PropertyName::LiteralPropertyName(Literal::String(value)) => value.clone(),
+                PropertyName::LiteralPropertyName(Literal::Numeric(value)) => Interpreter::number_to_string(*value),
+                PropertyName::LiteralPropertyName(_) => { unreachable!() },
+                // https://tc39.es/ecma262/#sec-object-initializer-runtime-semantics-evaluation
+                PropertyName::ComputedPropertyName(expression) => {
+                    // 1. Let exprValue be ? Evaluation of AssignmentExpression.
+                    let expression_value = completion!(self.evaluate(expression));
+
+                    // 2. Let propName be ? GetValue(exprValue).
+                    let property_name = match completion!(self.get_value(expression_value.value.clone())).value.deref() {
+                        ReferenceRecordOrJsValue::JSValue(property_name) => Rc::clone(property_name),
+                        _ => { unreachable!() }
+                    };
+
+                    // 3. Return ? ToPropertyKey(propName).
+                    completion_to_string(completion!(self.to_property_key(property_name)))
+                }
+            };
+
+            // 4. If IsAnonymousFunctionDefinition(AssignmentExpression) is true and isProtoSetter is false, then
+            //        a. Let propValue be ? NamedEvaluation of AssignmentExpression with argument propKey.
+            // 5. Else,
+            // a. Let exprValueRef be ? Evaluation of AssignmentExpression.
+            let expression_value_reference = if property_definition.assignment_expression.expression.is_anonymous_function_definition() {
+                completion!(self.named_evaluation(&property_definition.assignment_expression.expression, &property_key))
+            } else {
+                completion!(self.evaluate(&property_definition.assignment_expression.expression))
+            };
+
+            // b. Let propValue be ? GetValue(exprValueRef).
+            let property_value = match completion!(self.get_value(expression_value_reference.value.clone())).value.deref() {
+                ReferenceRecordOrJsValue::JSValue(property_value) => Rc::clone(property_value),
+                _ => { unreachable!() }
+            };
+
+            // 7. Assert: enumerable is true.
+            // 8. Assert: object is an ordinary, extensible object with no non-configurable properties.
+            // 9. Perform ! CreateDataPropertyOrThrow(object, propKey, propValue).
+            Interpreter::create_data_property(&object, &property_key, property_value);
+        }
+
+        // 3. Return obj.
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Object(object))))));
+    }
+
+    // https://tc39.es/ecma262/#sec-array-initializer-runtime-semantics-evaluation
+    fn visit_array_literal_expression(&mut self, array_literal_expression: &ArrayLiteralExpression) -> CompletionRecord {
+        // 1. Let array be ! ArrayCreate(0).
+        let array = self.array_create(0);
+
+        // 2. Let len be ? ArrayAccumulation of ElementList with arguments array and 0.
+        // https://tc39.es/ecma262/#sec-runtime-semantics-arrayaccumulation
+        let mut next_index: u32 = 0;
+        for element in &array_literal_expression.element_list {
+            match element {
+                // Elision : ,
+                // 1. Let len be nextIndex + 1.
+                // 2. Perform ? Set(array, "length", 𝔽(len), true).
+                None => {},
+                // ElementList : Elision(opt) AssignmentExpression
+                Some(expression) => {
+                    // 2. Let initResult be ? Evaluation of AssignmentExpression.
+                    let init_result = completion!(self.evaluate(expression));
+
+                    // 3. Let initValue be ? GetValue(initResult).
+                    let init_value = match completion!(self.get_value(init_result.value.clone())).value.deref() {
+                        ReferenceRecordOrJsValue::JSValue(init_value) => Rc::clone(init_value),
+                        _ => { unreachable!() }
+                    };
+
+                    // 4. Perform ! CreateDataPropertyOrThrow(array, ! ToString(𝔽(nextIndex)), initValue).
+                    Interpreter::create_data_property(&array, &next_index.to_string(), init_value);
+                }
+            }
+
+            // 5. Return nextIndex + 1.
+            next_index += 1;
+        }
+
+        // 3. Perform ! Set(array, "length", 𝔽(len), true).
+        // NOTE: This step is needed when the last element is an elision
+        completion!(Interpreter::set(&array, Rc::new(PropertyKey::String(String::from("length"))), Rc::new(RefCell::new(JSValue::Numeric(next_index as Number))), true));
+
+        // 4. Return array.
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Object(array))))));
     }
 
     // https://tc39.es/ecma262/#sec-assignment-operators-runtime-semantics-evaluation
     fn visit_assignment_expression(&mut self, expression: &AssignmentExpression) -> CompletionRecord {
 
         // 1. If LeftHandSideExpression is neither an ObjectLiteral nor an ArrayLiteral, then
-        match &*expression.expression {
+        match &*expression.left_hand_side_expression {
+            // TODO: Destructuring assignment
             ExpressionStatement::ObjectLiteralExpression(_) => { unimplemented!() },
             _ => {
                 // a. Let lRef be ? Evaluation of LeftHandSideExpression.
                 let left_reference =  completion!(self.evaluate(&*expression.left_hand_side_expression));
-                println!("Left Hand Side Expression: {:?}\n", self.evaluate(&*expression.left_hand_side_expression));
 
-                //        b. If IsAnonymousFunctionDefinition(AssignmentExpression) is true and IsIdentifierRef of LeftHandSideExpression is true, then TODO
+                //        b. If IsAnonymousFunctionDefinition(AssignmentExpression) is true and IsIdentifierRef of LeftHandSideExpression is true, then
                 //               i. Let lhs be the StringValue of LeftHandSideExpression.
                 //               ii. Let rVal be ? NamedEvaluation of AssignmentExpression with argument lhs.
                 //        c. Else,
                 //               i. Let rRef be ? Evaluation of AssignmentExpression.
-                let right_reference =  completion!(self.evaluate(&*expression.expression));
+                let right_reference = match &*expression.left_hand_side_expression {
+                    ExpressionStatement::IdentifierExpression(identifier) if expression.expression.is_anonymous_function_definition() => {
+                        completion!(self.named_evaluation(&expression.expression, &identifier.binding_identifier.lexeme))
+                    },
+                    _ => completion!(self.evaluate(&expression.expression))
+                };
                 //               ii. Let rVal be ? GetValue(rRef).
-                let right_value =  completion!(Interpreter::get_value(right_reference.value.clone()));
+                let right_value =  completion!(self.get_value(right_reference.value.clone()));
                 match right_value.value.deref() {
                     ReferenceRecordOrJsValue::JSValue(value) => {
                         //        d. Perform ? PutValue(lRef, rVal).
-                       //  completion!(self.put_value(left_reference.value, value.clone()));
+                        completion!(self.put_value(left_reference.value.clone(), value.clone()));
                         //        e. Return rVal.
                         return right_value;
                     },
@@ -1074,18 +2342,210 @@ impl AstVisitor<CompletionRecord> for Interpreter {
         // 5. Perform ? DestructuringAssignmentEvaluation of assignmentPattern with argument rVal.
         // 6. Return rVal.
     }
+
+    // https://tc39.es/ecma262/#sec-function-definitions-runtime-semantics-evaluation
+    fn visit_function_declaration(&mut self, _declaration: &FunctionDeclaration) -> CompletionRecord {
+        // 1. Return empty.
+        // NOTE: The function object was created when the enclosing script or function was instantiated
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+    }
+
+    // https://tc39.es/ecma262/#sec-function-definitions-runtime-semantics-evaluation
+    fn visit_function_expression(&mut self, expression: &FunctionExpression) -> CompletionRecord {
+        // 1. Return InstantiateOrdinaryFunctionExpression of FunctionExpression.
+        let closure = self.instantiate_ordinary_function_expression(expression, None);
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Object(closure))))));
+    }
+
+    // https://tc39.es/ecma262/#sec-arrow-function-definitions-runtime-semantics-evaluation
+    fn visit_arrow_function(&mut self, expression: &ArrowFunction) -> CompletionRecord {
+        // 1. Return InstantiateArrowFunctionExpression of ArrowFunction.
+        let closure = self.instantiate_arrow_function_expression(expression, None);
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Object(closure))))));
+    }
+
+    // https://tc39.es/ecma262/#sec-this-keyword-runtime-semantics-evaluation
+    fn visit_this_expression(&mut self, _expression: &ThisExpression) -> CompletionRecord {
+        // 1. Return ? ResolveThisBinding().
+        return self.resolve_this_binding();
+    }
+
+    // https://tc39.es/ecma262/#sec-new-operator-runtime-semantics-evaluation
+    // https://tc39.es/ecma262/#sec-evaluatenew
+    fn visit_new_expression(&mut self, expression: &NewExpression) -> CompletionRecord {
+        // 1. Let ref be ? Evaluation of constructExpr.
+        let reference = completion!(self.evaluate(&expression.callee));
+
+        // 2. Let constructor be ? GetValue(ref).
+        let constructor = match completion!(self.get_value(reference.value.clone())).value.deref() {
+            ReferenceRecordOrJsValue::JSValue(constructor) => Rc::clone(constructor),
+            _ => { unreachable!() }
+        };
+
+        // 3. If arguments is empty, then
+        //        a. Let argList be a new empty List.
+        // 4. Else,
+        //        a. Let argList be ? ArgumentListEvaluation of arguments.
+        let mut arguments = Vec::new();
+        for argument in &expression.arguments {
+            let argument_reference = completion!(self.evaluate(argument));
+            match completion!(self.get_value(argument_reference.value.clone())).value.deref() {
+                ReferenceRecordOrJsValue::JSValue(argument_value) => arguments.push(Rc::clone(argument_value)),
+                _ => { unreachable!() }
+            }
+        }
+
+        self.set_position(&expression.keyword);
+
+        // 5. If IsConstructor(constructor) is false, throw a TypeError exception.
+        let constructor = match &*constructor.borrow() {
+            JSValue::Object(constructor) if Interpreter::is_constructor(&constructor.borrow()) => Rc::clone(constructor),
+            _ => {
+                let callee = source_text(&expression.callee);
+                return self.throw_type_error(format!("{} is not a constructor", callee));
+            }
+        };
+
+        // 6. Return ? Construct(constructor, argList).
+        return self.construct(&constructor, arguments, None);
+    }
+
+    // https://tc39.es/ecma262/#sec-template-literals-runtime-semantics-evaluation
+    fn visit_template_literal(&mut self, expression: &TemplateLiteral) -> CompletionRecord {
+        // TemplateLiteral : NoSubstitutionTemplate
+        // 1. Return the TV of NoSubstitutionTemplate.
+        // SubstitutionTemplate : TemplateHead Expression TemplateSpans
+        // 1. Let head be the TV of TemplateHead.
+        let mut result = expression.strings[0].clone();
+
+        for (index, substitution) in expression.substitutions.iter().enumerate() {
+            // 2. Let subRef be ? Evaluation of Expression.
+            let substitution_reference = completion!(self.evaluate(substitution));
+
+            // 3. Let sub be ? GetValue(subRef).
+            let substitution_value = match completion!(self.get_value(substitution_reference.value.clone())).value.deref() {
+                ReferenceRecordOrJsValue::JSValue(value) => Rc::clone(value),
+                _ => { unreachable!() }
+            };
+
+            // 4. Let middle be ? ToString(sub).
+            let middle = match completion!(self.to_string_coercing_objects(substitution_value)).value.deref() {
+                ReferenceRecordOrJsValue::JSValue(middle) => match &*middle.borrow() {
+                    JSValue::String(middle) => middle.clone(),
+                    _ => String::new()
+                },
+                _ => { unreachable!() }
+            };
+            if let Some(range_error) = self.reserve_heap(middle.len() + expression.strings[index + 1].len(), result.len()) {
+                return range_error;
+            }
+            result.push_str(&middle);
+
+            // 5. Let tail be ? Evaluation of TemplateSpans.
+            result.push_str(&expression.strings[index + 1]);
+        }
+
+        // 6. Return the string-concatenation of head, middle, and tail.
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::String(result))))));
+    }
+
+    // https://tc39.es/ecma262/#sec-return-statement-runtime-semantics-evaluation
+    fn visit_return_statement(&mut self, statement: &ReturnStatement) -> CompletionRecord {
+        let value = match &statement.expression {
+            // ReturnStatement : return ;
+            // 1. Return ReturnCompletion(undefined).
+            None => Rc::new(RefCell::new(JSValue::Undefined)),
+            // ReturnStatement : return Expression ;
+            Some(expression) => {
+                // 1. Let exprRef be ? Evaluation of Expression.
+                let expression_reference = completion!(self.evaluate(expression));
+
+                // 2. Let exprValue be ? GetValue(exprRef).
+                match completion!(self.get_value(expression_reference.value.clone())).value.deref() {
+                    ReferenceRecordOrJsValue::JSValue(value) => Rc::clone(value),
+                    _ => { unreachable!() }
+                }
+
+                // 3. TODO: If GetGeneratorKind() is async, set exprValue to ? Await(exprValue).
+            }
+        };
+
+        // 4. Return ReturnCompletion(exprValue).
+        return CompletionRecord { type_: CompletionRecordType::Return, value: Rc::new(ReferenceRecordOrJsValue::JSValue(value)), target: None };
+    }
+
+    // https://tc39.es/ecma262/#sec-throw-statement-runtime-semantics-evaluation
+    fn visit_throw_statement(&mut self, statement: &ThrowStatement) -> CompletionRecord {
+        // 1. Let exprRef be ? Evaluation of Expression.
+        let expression_reference = completion!(self.evaluate(&statement.expression));
+
+        // 2. Let exprValue be ? GetValue(exprRef).
+        let value = match completion!(self.get_value(expression_reference.value.clone())).value.deref() {
+            ReferenceRecordOrJsValue::JSValue(value) => Rc::clone(value),
+            _ => { unreachable!() }
+        };
+
+        self.set_position(&statement.keyword);
+
+        // 3. Return ThrowCompletion(exprValue).
+        return create_throw_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(value)));
+    }
+
+    // https://tc39.es/ecma262/#sec-try-statement-runtime-semantics-evaluation
+    // TODO: The completion value is not updated from empty to undefined, statements do not produce empty completions yet
+    fn visit_try_statement(&mut self, statement: &TryStatement) -> CompletionRecord {
+        // 1. Let B be Completion(Evaluation of Block).
+        let block_completion = self.visit_block_statement(&statement.block);
+
+        // 2. If B is a throw completion, let C be Completion(CatchClauseEvaluation of Catch with argument B.[[Value]]).
+        // 3. Else, let C be B.
+        let catch_completion = match (&statement.catch, &block_completion.type_, &*block_completion.value) {
+            (Some(catch), CompletionRecordType::Throw, ReferenceRecordOrJsValue::JSValue(thrown_value)) => {
+                let thrown_value = Rc::clone(thrown_value);
+                self.catch_clause_evaluation(&catch.parameter, &catch.block, thrown_value)
+            },
+            _ => block_completion
+        };
+
+        match &statement.finally {
+            // TryStatement : try Block Finally
+            // TryStatement : try Block Catch Finally
+            Some(finally) => {
+                // 3. Let F be Completion(Evaluation of Finally).
+                let finally_completion = self.visit_block_statement(finally);
+
+                // 4. If F is a normal completion, set F to C.
+                if let CompletionRecordType::Normal = finally_completion.type_ {
+                    return catch_completion;
+                }
+
+                // 5. Return ? UpdateEmpty(F, undefined).
+                return finally_completion;
+            },
+            // TryStatement : try Block Catch
+            // 4. Return ? UpdateEmpty(C, undefined).
+            None => {
+                return catch_completion;
+            }
+        }
+    }
+}
+
+// https://tc39.es/ecma262/#sec-properties-of-the-function-prototype-object
+fn function_prototype_call(_interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
 }
 
 #[derive(Debug)]
-enum BaseValue {
-    JSValue(Box<JSValue>),
+pub(crate) enum BaseValue {
+    JSValue(Rc<RefCell<JSValue>>),
     EnvironmentRecord(Rc<RefCell<EnvironmentRecord>>),
     Unresolvable
 }
 
 // https://tc39.es/ecma262/#sec-reference-record-specification-type
 #[derive(Debug)]
-struct ReferenceRecord {
+pub(crate) struct ReferenceRecord {
     // https://tc39.es/ecma262/#table-reference-record-fields
     base: Rc<BaseValue>,
     referenced_name: JSValue, // TODO: Or a Private Name
@@ -1094,92 +2554,1564 @@ struct ReferenceRecord {
 }
 
 #[derive(Debug)]
-enum ReferenceRecordOrJsValue {
+pub(crate) enum ReferenceRecordOrJsValue {
     ReferenceRecord(ReferenceRecord),
     JSValue(Rc<RefCell<JSValue>>),
     PropertyDescriptor(PropertyDescriptorType),
 }
 
-#[derive(Debug)]
-enum CompletionRecordType {
-    Normal,
-    Break,
-    Continue,
-    Return,
-    Throw
-}
+#[derive(Debug)]
+pub(crate) enum CompletionRecordType {
+    Normal,
+    Break,
+    Continue,
+    Return,
+    Throw
+}
+
+// https://tc39.es/ecma262/#sec-completion-record-specification-type
+#[derive(Debug)]
+pub(crate) struct CompletionRecord {
+    // https://tc39.es/ecma262/#table-completion-record-fields
+    pub(crate) type_: CompletionRecordType,
+    pub(crate) value: Rc<ReferenceRecordOrJsValue>,
+    pub(crate) target: Option<String>
+}
+
+#[derive(PartialEq)]
+enum ObjectInternalSlot {
+    Prototype,
+    Extensible,
+    PrivateElements
+}
+
+impl Interpreter {
+    pub fn new() -> Interpreter {
+        // https://tc39.es/ecma262/#sec-setrealmglobalobject
+        // The global object is both the binding object of the global environment and its this value
+        let mut global_object = JSObject::new();
+        global_object.extensible = true;
+        let global_object = Rc::new(RefCell::new(global_object));
+
+        let mut interpreter = Interpreter {
+            had_error: false,
+            execution_contexts: vec![
+                ExecutionContext {
+                    lexical_environment_record: Rc::new(RefCell::new(EnvironmentRecord::new(EnvironmentRecordType::GlobalEnvironmentRecord(Rc::new(RefCell::new(GlobalEnvironmentRecord {
+                        global_this_value: Rc::clone(&global_object),
+                        object_environment_record: Option::from(Rc::new(RefCell::new(ObjectEnvironmentRecord {
+                            binding_object: global_object,
+                            is_with_environment: false
+                        }))), // Should not be none, temporary
+                        declarative_environment_record: RefCell::new(DeclarativeEnvironmentRecord { variable_bindings: HashMap::new(), function_environment_record: None }),
+                        var_names: RefCell::new(Vec::new()),
+                    })))))),
+                    variable_environment_record: Rc::new(RefCell::new(EnvironmentRecord {
+                        outer_environment_record: None,
+                        environment_record_type: EnvironmentRecordType::DeclarativeEnvironmentRecord(
+                            Rc::new(RefCell::new(DeclarativeEnvironmentRecord { variable_bindings: HashMap::new(), function_environment_record: None }))
+                        )
+                    })),
+                    function: None,
+                    line: 0,
+                    column: 0,
+                    script_name: Rc::from(""),
+                    strict: false,
+                }
+            ],
+            intrinsics: HashMap::new(),
+            logger: Box::new(StandardStreamsLogger),
+            resource_loader: Box::new(DefaultResourceLoader::default()),
+            viewport: Viewport::default(),
+            document_url: Url::about_blank(),
+            event_loop: EventLoop::default(),
+            heap: Heap::default(),
+            optimize_scripts: true,
+            inline_script_count: 0,
+            script_limits: ScriptLimits::default(),
+            script_budget: ScriptBudget::new(),
+            heap_size: 0,
+        };
+
+        interpreter.create_intrinsics();
+        install_error(&mut interpreter);
+        install_array(&mut interpreter);
+        install_string(&mut interpreter);
+        install_json(&mut interpreter);
+        install_math(&mut interpreter);
+        install_date(&mut interpreter);
+        install_regexp(&mut interpreter);
+        install_promise(&mut interpreter);
+        install_console(&mut interpreter);
+        install_timers(&mut interpreter);
+        install_fetch(&mut interpreter);
+        install_window(&mut interpreter);
+
+        return interpreter;
+    }
+
+    // Replaces where console output and uncaught exceptions are printed
+    pub fn set_logger(&mut self, logger: Box<dyn Logger>) {
+        self.logger = logger;
+    }
+
+    pub(crate) fn print(&mut self, log_level: LogLevel, message: &str) {
+        self.logger.print(log_level, message);
+    }
+
+    // Replaces where fetch() loads resources from, e.g. with a StubResourceLoader
+    pub fn set_resource_loader(&mut self, resource_loader: Box<dyn ResourceLoader>) {
+        self.resource_loader = resource_loader;
+    }
+
+    pub(crate) fn load_resource(&mut self, url: &Url) -> Result<Response, String> {
+        return self.resource_loader.load(url);
+    }
+
+    // Runs scripts as they were parsed, e.g. to compare what they do with and without the optimizer
+    pub fn set_optimize_scripts(&mut self, optimize_scripts: bool) {
+        self.optimize_scripts = optimize_scripts;
+    }
+
+    // The size reported by innerWidth and innerHeight
+    pub fn set_viewport(&mut self, viewport: Viewport) {
+        self.viewport = viewport;
+        define_viewport_properties(self);
+    }
+
+    // The steps, time, heap objects and call depth scripts may use, see script_limits.rs
+    pub fn set_script_limits(&mut self, script_limits: ScriptLimits) {
+        self.script_limits = script_limits;
+    }
+
+    // Gives the next script or task a fresh budget, what the last one used does not count against it
+    pub(crate) fn reset_script_budget(&mut self) {
+        self.script_budget = ScriptBudget::new();
+    }
+
+    // Counts a step against the budget of the running script, and returns the RangeError that stops it once it went over a limit
+    fn check_script_limits(&mut self) -> Option<CompletionRecord> {
+        if let Some(message) = self.script_budget.step(&self.script_limits) {
+            return Some(self.throw_error("RangeError", message));
+        }
+
+        if let Some(range_error) = self.reserve_heap(0, 0) {
+            return Some(range_error);
+        }
+
+        if let Some(max_heap_objects) = self.script_limits.max_heap_objects {
+            // Garbage only counts until the cycle collector had a chance to free it
+            if self.script_budget.is_check_step() && self.heap.live_object_count() > max_heap_objects {
+                self.heap.collect_cycles();
+                if self.heap.live_object_count() > max_heap_objects {
+                    let message = self.script_budget.exceed(format!("Script exceeded the maximum of {} heap objects", max_heap_objects)).unwrap();
+                    return Some(self.throw_error("RangeError", message));
+                }
+            }
+        }
+
+        return None;
+    }
+
+    // Counts bytes script is about to allocate, e.g. for a string it concatenates, and what objects grew by, against the maximum heap size
+    // Returns the RangeError that stops the script when they do not fit, and then the bytes are not to be allocated
+    // The heap is only measured when the count goes over the maximum, as that walks every cell
+    // unmeasured is what was already counted into a buffer still being built, which measuring the heap cannot see
+    pub(crate) fn reserve_heap(&mut self, bytes: usize, unmeasured: usize) -> Option<CompletionRecord> {
+        let max_heap_size = self.script_limits.max_heap_size?;
+        if let Some(message) = self.script_budget.exceeded() {
+            return Some(self.throw_error("RangeError", message));
+        }
+
+        self.heap_size = self.heap_size.saturating_add(gc::take_growth());
+        if self.heap_size.saturating_add(bytes) <= max_heap_size {
+            self.heap_size += bytes;
+            return None;
+        }
+
+        // Garbage only counts until the cycle collector had a chance to free it
+        self.heap.collect_cycles();
+        let roots = self.heap_roots();
+        self.heap_size = self.heap.live_size(roots).saturating_add(unmeasured);
+        if self.heap_size.saturating_add(bytes) <= max_heap_size {
+            self.heap_size += bytes;
+            return None;
+        }
+        let message = self.script_budget.exceed(format!("Script exceeded the maximum heap size of {} bytes", max_heap_size)).unwrap();
+        return Some(self.throw_error("RangeError", message));
+    }
+
+    // The cells of the realm and of the running code the heap is measured and torn down from
+    fn heap_roots(&self) -> Vec<Rc<dyn HeapCell>> {
+        let mut roots: Vec<Rc<dyn HeapCell>> = self.intrinsics.values().map(|intrinsic| Rc::clone(intrinsic) as Rc<dyn HeapCell>).collect();
+        for execution_context in &self.execution_contexts {
+            roots.push(Rc::clone(&execution_context.lexical_environment_record) as Rc<dyn HeapCell>);
+            roots.push(Rc::clone(&execution_context.variable_environment_record) as Rc<dyn HeapCell>);
+            if let Some(function) = &execution_context.function {
+                roots.push(Rc::clone(function) as Rc<dyn HeapCell>);
+            }
+        }
+        return roots;
+    }
+
+    pub(crate) fn viewport(&self) -> Viewport {
+        return self.viewport;
+    }
+
+    // The URL reported by location, about:blank unless a document was loaded from somewhere
+    pub fn set_document_url(&mut self, url: Url) {
+        self.document_url = url;
+    }
+
+    pub(crate) fn document_url(&self) -> &Url {
+        return &self.document_url;
+    }
+
+    // https://tc39.es/ecma262/#sec-ordinaryobjectcreate
+    fn ordinary_object_create(&mut self, proto: Option<JSObject>, mut additional_internal_slots: Vec<ObjectInternalSlot>) -> JSObject {
+        // 1. Let internalSlotsList be « [[Prototype]], [[Extensible]] ».
+        let mut internal_slots = vec![ObjectInternalSlot::Prototype, ObjectInternalSlot::Extensible];
+
+        // 2. If additionalInternalSlotsList is present, set internalSlotsList to the list-concatenation of internalSlotsList and additionalInternalSlotsList.
+        if additional_internal_slots.len() > 0 {
+            internal_slots.append(&mut additional_internal_slots);
+        }
+
+        // 3. Let O be MakeBasicObject(internalSlotsList).
+        let mut object = self.make_basic_object(internal_slots);
+
+        // 4. Set O.[[Prototype]] to proto.
+        object.prototype = Some(Rc::new(RefCell::new(proto.unwrap())));
+
+        // 5. Return O.
+        return object;
+    }
+
+    // https://tc39.es/ecma262/#sec-set-o-p-v-throw
+    pub fn set(object: &Rc<RefCell<JSObject>>, key: Rc<PropertyKey>, value: Rc<RefCell<JSValue>>, throw: bool) -> CompletionRecord {
+        // 1. Let success be ? O.[[Set]](P, V, O).
+        let success =  completion!(object.borrow_mut().set(key.clone(), value.clone(), object));
+        // 2. If success is false and Throw is true, throw a TypeError exception. TODO
+        // 3. Return unused.
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+    }
+
+    // https://tc39.es/ecma262/#sec-ordinaryget
+    // Unlike JSObject::get this can follow the prototype chain and call getters
+    pub(crate) fn get(&mut self, object: &Rc<RefCell<JSObject>>, key: &PropertyKey, receiver: Rc<RefCell<JSValue>>) -> CompletionRecord {
+        // 1. Let desc be ? O.[[GetOwnProperty]](P).
+        let desc = object.borrow().ordinary_get_own_property(key);
+
+        match desc {
+            // 2. If desc is undefined, then
+            PropertyDescriptorType::Undefined(_) => {
+                // a. Let parent be ? O.[[GetPrototypeOf]]().
+                let parent = object.borrow().prototype.clone();
+                match parent {
+                    // b. If parent is null, return undefined.
+                    None => {
+                        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+                    },
+                    // c. Return ? parent.[[Get]](P, Receiver).
+                    Some(parent) => {
+                        return completion!(self.get(&parent, key, receiver));
+                    }
+                }
+            },
+            PropertyDescriptorType::PropertyDescriptor(property_descriptor) => {
+                match property_descriptor.property {
+                    // 3. If IsDataDescriptor(desc) is true, return desc.[[Value]].
+                    Some(PropertyType::DataProperty(data_property)) => {
+                        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(data_property.value)));
+                    },
+                    // 4. Assert: IsAccessorDescriptor(desc) is true.
+                    // 5. Let getter be desc.[[Get]].
+                    Some(PropertyType::AccessorProperty(accessor_property)) => {
+                        match accessor_property.get {
+                            // 6. If getter is undefined, return undefined.
+                            None => {
+                                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+                            },
+                            // 7. Return ? Call(getter, Receiver).
+                            Some(getter) => {
+                                return completion!(self.call(getter, receiver, Vec::new()));
+                            }
+                        }
+                    },
+                    None => { unreachable!() }
+                }
+            }
+        }
+    }
+
+    // https://tc39.es/ecma262/#sec-ordinaryset
+    pub(crate) fn set_property(&mut self, object: &Rc<RefCell<JSObject>>, key: PropertyKey, value: Rc<RefCell<JSValue>>, receiver: Rc<RefCell<JSValue>>) -> CompletionRecord {
+        // 1. Let ownDesc be ? O.[[GetOwnProperty]](P).
+        let own_descriptor = object.borrow().ordinary_get_own_property(&key);
+
+        // 2. Return ? OrdinarySetWithOwnDescriptor(O, P, V, Receiver, ownDesc).
+        // https://tc39.es/ecma262/#sec-ordinarysetwithowndescriptor
+        match own_descriptor {
+            // 1. If ownDesc is undefined, then
+            PropertyDescriptorType::Undefined(_) => {
+                // a. Let parent be ? O.[[GetPrototypeOf]]().
+                let parent = object.borrow().prototype.clone();
+                match parent {
+                    // b. If parent is not null, then
+                    //    i. Return ? parent.[[Set]](P, V, Receiver).
+                    Some(parent) => {
+                        return completion!(self.set_property(&parent, key, value, receiver));
+                    },
+                    // c. Else,
+                    //    i. Set ownDesc to the PropertyDescriptor { [[Value]]: undefined, [[Writable]]: true, [[Enumerable]]: true, [[Configurable]]: true }.
+                    None => {
+                        return completion!(self.create_data_property_on_receiver(key, value, receiver));
+                    }
+                }
+            },
+            PropertyDescriptorType::PropertyDescriptor(property_descriptor) => {
+                match property_descriptor.property {
+                    // 2. If IsDataDescriptor(ownDesc) is true, then
+                    Some(PropertyType::DataProperty(data_property)) => {
+                        // a. If ownDesc.[[Writable]] is false, return false.
+                        if !data_property.writable {
+                            return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(false))))));
+                        }
+                        return completion!(self.create_data_property_on_receiver(key, value, receiver));
+                    },
+                    // 3. Assert: IsAccessorDescriptor(ownDesc) is true.
+                    // 4. Let setter be ownDesc.[[Set]].
+                    Some(PropertyType::AccessorProperty(accessor_property)) => {
+                        match accessor_property.set {
+                            // 5. If setter is undefined, return false.
+                            None => {
+                                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(false))))));
+                            },
+                            // 6. Perform ? Call(setter, Receiver, « V »).
+                            Some(setter) => {
+                                completion!(self.call(setter, receiver, vec![value]));
+
+                                // 7. Return true.
+                                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(true))))));
+                            }
+                        }
+                    },
+                    None => { unreachable!() }
+                }
+            }
+        }
+    }
+
+    // https://tc39.es/ecma262/#sec-ordinarysetwithowndescriptor
+    // Steps 2.b to 2.e, the data property ends up on the receiver rather than the object it was found on
+    fn create_data_property_on_receiver(&mut self, key: PropertyKey, value: Rc<RefCell<JSValue>>, receiver: Rc<RefCell<JSValue>>) -> CompletionRecord {
+        // b. If Receiver is not an Object, return false.
+        let receiver_object = match &*receiver.borrow() {
+            JSValue::Object(receiver_object) => Rc::clone(receiver_object),
+            _ => {
+                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(false))))));
+            }
+        };
+
+        // c. Let existingDescriptor be ? Receiver.[[GetOwnProperty]](P).
+        // d. If existingDescriptor is not undefined, then
+        //    iii. Let valueDesc be the PropertyDescriptor { [[Value]]: V }.
+        //    iv. Return ? Receiver.[[DefineOwnProperty]](P, valueDesc).
+        // e. Else,
+        //    ii. Return ? CreateDataProperty(Receiver, P, V).
+        return completion!(receiver_object.borrow_mut().define_own_property(&key, &PropertyDescriptor { property: Some(PropertyType::DataProperty(DataProperty { value, writable: true, enumerable: true, configurable: true })) }));
+    }
+
+    // https://tc39.es/ecma262/#sec-createdataproperty
+    pub(crate) fn create_data_property(object: &Rc<RefCell<JSObject>>, key: &str, value: Rc<RefCell<JSValue>>) {
+        // 1. Let newDesc be the PropertyDescriptor { [[Value]]: V, [[Writable]]: true, [[Enumerable]]: true, [[Configurable]]: true }.
+        // 2. Return ? O.[[DefineOwnProperty]](P, newDesc).
+        object.borrow_mut().define_own_property(&PropertyKey::String(key.to_string()), &PropertyDescriptor { property: Some(PropertyType::DataProperty(DataProperty { value, writable: true, enumerable: true, configurable: true })) });
+    }
+
+    // https://tc39.es/ecma262/#sec-definepropertyorthrow
+    // Defines a data property with the given attributes, such as the non-enumerable prototype and constructor properties
+    // TODO: Only string keys are supported and a failure to define the property is not reported
+    pub(crate) fn define_property_or_throw(object: &Rc<RefCell<JSObject>>, key: &str, value: Rc<RefCell<JSValue>>, writable: bool, enumerable: bool, configurable: bool) {
+        // 1. Let success be ? O.[[DefineOwnProperty]](P, desc).
+        // 2. If success is false, throw a TypeError exception.
+        object.borrow_mut().define_own_property(&PropertyKey::String(key.to_string()), &PropertyDescriptor { property: Some(PropertyType::DataProperty(DataProperty { value, writable, enumerable, configurable })) });
+
+        // 3. Return unused.
+    }
+
+    // https://webidl.spec.whatwg.org/#es-attributes
+    // Defines an accessor property whose getter and setter are built-in functions
+    pub(crate) fn define_accessor_property(object: &Rc<RefCell<JSObject>>, key: &str, getter: NativeFunction, setter: Option<NativeFunction>) {
+        let get = Some(Rc::new(RefCell::new(JSValue::Object(Interpreter::create_builtin_function(getter)))));
+        let set = setter.map(|setter| Rc::new(RefCell::new(JSValue::Object(Interpreter::create_builtin_function(setter)))));
+        object.borrow_mut().define_own_property(&PropertyKey::String(key.to_string()), &PropertyDescriptor { property: Some(PropertyType::AccessorProperty(AccessorProperty { get, set })) });
+    }
+
+    // https://tc39.es/ecma262/#sec-createbuiltinfunction
+    // TODO: The length and name properties are not set
+    pub(crate) fn create_builtin_function(behaviour: NativeFunction) -> Rc<RefCell<JSObject>> {
+        let mut function = JSObject::new();
+        function.extensible = true;
+        function.call = Some(behaviour);
+        return Rc::new(RefCell::new(function));
+    }
+
+    // Defines a built-in function as a method property of object
+    // https://tc39.es/ecma262/#sec-createbuiltinfunction
+    pub(crate) fn create_builtin_closure(behaviour: impl Fn(&mut Interpreter, Rc<RefCell<JSValue>>, Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord + 'static) -> Rc<RefCell<JSObject>> {
+        let mut function = JSObject::new();
+        function.extensible = true;
+        function.call_closure = Some(AbstractClosure(Rc::new(behaviour)));
+        return Rc::new(RefCell::new(function));
+    }
+
+    pub(crate) fn define_builtin_function(object: &Rc<RefCell<JSObject>>, key: &str, behaviour: NativeFunction) {
+        let function = Interpreter::create_builtin_function(behaviour);
+        Interpreter::set_function_name(&function, key);
+        Interpreter::create_data_property(object, key, Rc::new(RefCell::new(JSValue::Object(function))));
+    }
+
+    // https://tc39.es/ecma262/#sec-iscallable
+    pub(crate) fn is_callable(value: &JSValue) -> bool {
+        match value {
+            // 2. If argument has a [[Call]] internal method, return true.
+            JSValue::Object(object) => object.borrow().call.is_some() || object.borrow().call_closure.is_some() || object.borrow().ecmascript_function.is_some(),
+            // 1. If argument is not an Object, return false.
+            // 3. Return false.
+            _ => false
+        }
+    }
+
+    // https://tc39.es/ecma262/#sec-call
+    pub(crate) fn call(&mut self, function: Rc<RefCell<JSValue>>, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+        // 1. If argumentsList is not present, set argumentsList to a new empty List.
+        // 2. If IsCallable(F) is false, throw a TypeError exception.
+        let function_object = match &*function.borrow() {
+            JSValue::Object(object) if Interpreter::is_callable(&function.borrow()) => Rc::clone(object),
+            _ => {
+                return self.throw_type_error(String::from("value is not a function"));
+            }
+        };
+
+        // 3. Return ? F.[[Call]](V, argumentsList).
+        let behaviour = function_object.borrow().call;
+        let closure = function_object.borrow().call_closure.clone();
+        match (behaviour, closure) {
+            // https://tc39.es/ecma262/#sec-built-in-function-objects-call-thisargument-argumentslist
+            (Some(behaviour), _) => {
+                return behaviour(self, this_value, arguments);
+            },
+            (None, Some(AbstractClosure(closure))) => {
+                return closure(self, this_value, arguments);
+            },
+            (None, None) => {
+                return self.call_ecmascript_function(&function_object, this_value, arguments);
+            }
+        }
+    }
+
+    // https://tc39.es/ecma262/#sec-blockdeclarationinstantiation
+    // TODO: Function declarations in blocks are hoisted like var, see Statement::function_declarations
+    fn block_declaration_instantiation(&mut self, statements: &[Statement], environment: &Rc<RefCell<EnvironmentRecord>>) -> CompletionRecord {
+        // https://tc39.es/ecma262/#sec-block-static-semantics-early-errors
+        if let Some(binding_identifier) = Statement::redeclared_lexical_name(statements) {
+            return self.throw_redeclaration_error(binding_identifier);
+        }
+
+        // 1. Let declarations be the LexicallyScopedDeclarations of code.
+        // 2. Let privateEnv be the running execution context's PrivateEnvironment.
+        // 3. For each element d of declarations, do
+        let declarations = Statement::lexically_scoped_declarations(statements);
+        environment.borrow().declarative_record().borrow_mut().create_lexical_bindings(&declarations);
+
+        // 4. Return unused.
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+    }
+
+    // https://tc39.es/ecma262/#sec-initializereferencedbinding
+    fn initialize_referenced_binding(reference: &ReferenceRecordOrJsValue, value: Rc<RefCell<JSValue>>) -> CompletionRecord {
+        match reference {
+            ReferenceRecordOrJsValue::ReferenceRecord(reference_record) => {
+                // 1. Assert: IsUnresolvableReference(V) is false.
+                // 2. Let base be V.[[Base]].
+                // 3. Assert: base is an Environment Record.
+                match (reference_record.base.as_ref(), &reference_record.referenced_name) {
+                    // 4. Return ? base.InitializeBinding(V.[[ReferencedName]], W).
+                    (BaseValue::EnvironmentRecord(environment), JSValue::String(name)) => {
+                        return environment.borrow().initialize_binding(Atom::from(name), value);
+                    },
+                    _ => { unreachable!() }
+                }
+            },
+            _ => { unreachable!() }
+        }
+    }
+
+    // https://tc39.es/ecma262/#sec-runtime-semantics-catchclauseevaluation
+    fn catch_clause_evaluation(&mut self, parameter: &Option<Token>, block: &BlockStatement, thrown_value: Rc<RefCell<JSValue>>) -> CompletionRecord {
+        let parameter = match parameter {
+            Some(parameter) => parameter,
+            // Catch : catch Block
+            // 1. Return ? Evaluation of Block.
+            None => {
+                return self.visit_block_statement(block);
+            }
+        };
+
+        // Catch : catch ( CatchParameter ) Block
+        // 1. Let oldEnv be the running execution context's LexicalEnvironment.
+        let old_environment = Rc::clone(&self.running_execution_context().lexical_environment_record);
+
+        // 2. Let catchEnv be NewDeclarativeEnvironment(oldEnv).
+        let catch_environment = EnvironmentRecord::new_declarative_environment(Some(Rc::clone(&old_environment)));
+        let declarative_record = catch_environment.borrow().declarative_record();
+
+        // 3. For each element argName of the BoundNames of CatchParameter, do
+        //        a. Perform ! catchEnv.CreateMutableBinding(argName, false).
+        declarative_record.borrow_mut().create_mutable_binding(parameter.lexeme.clone(), false);
+
+        // 4. Set the running execution context's LexicalEnvironment to catchEnv.
+        self.execution_contexts.last_mut().unwrap().lexical_environment_record = catch_environment;
+
+        // 5. Let status be Completion(BindingInitialization of CatchParameter with arguments thrownValue and catchEnv).
+        // 6. If status is an abrupt completion, then
+        //        a. Set the running execution context's LexicalEnvironment to oldEnv.
+        //        b. Return ? status.
+        declarative_record.borrow_mut().initialize_binding(parameter.lexeme.clone(), thrown_value);
+
+        // 7. Let B be Completion(Evaluation of Block).
+        let block_completion = self.visit_block_statement(block);
+
+        // 8. Set the running execution context's LexicalEnvironment to oldEnv.
+        self.execution_contexts.last_mut().unwrap().lexical_environment_record = old_environment;
+
+        // 9. Return ? B.
+        return block_completion;
+    }
+
+    // https://tc39.es/ecma262/#sec-isconstructor
+    pub(crate) fn is_constructor(object: &JSObject) -> bool {
+        // 1. If argument is not an Object, return false.
+        // 2. If argument has a [[Construct]] internal method, return true.
+        // 3. Return false.
+        return object.construct.is_some() || matches!(&object.ecmascript_function, Some(ecmascript_function) if ecmascript_function.constructor);
+    }
+
+    // https://tc39.es/ecma262/#sec-construct
+    pub(crate) fn construct(&mut self, constructor: &Rc<RefCell<JSObject>>, arguments: Vec<Rc<RefCell<JSValue>>>, new_target: Option<Rc<RefCell<JSObject>>>) -> CompletionRecord {
+        // 1. If newTarget is not present, set newTarget to F.
+        let new_target = new_target.unwrap_or_else(|| Rc::clone(constructor));
+
+        // 2. If argumentsList is not present, set argumentsList to a new empty List.
+        // 3. Return ? F.[[Construct]](argumentsList, newTarget).
+        let behaviour = constructor.borrow().construct;
+        match behaviour {
+            // https://tc39.es/ecma262/#sec-built-in-function-objects-construct-argumentslist-newtarget
+            Some(behaviour) => {
+                return behaviour(self, arguments, new_target);
+            },
+            None => {
+                return self.construct_ecmascript_function(constructor, arguments, new_target);
+            }
+        }
+    }
+
+    // https://tc39.es/ecma262/#sec-ecmascript-function-objects-construct-argumentslist-newtarget
+    fn construct_ecmascript_function(&mut self, function: &Rc<RefCell<JSObject>>, arguments: Vec<Rc<RefCell<JSValue>>>, new_target: Rc<RefCell<JSObject>>) -> CompletionRecord {
+        if self.execution_contexts.len() > self.script_limits.max_call_depth {
+            return self.throw_error("RangeError", String::from("Maximum call stack size exceeded"));
+        }
+
+        // 1. Let callerContext be the running execution context.
+        // 2. Let kind be F.[[ConstructorKind]].
+        // 3. If kind is base, then
+        //        a. Let thisArgument be ? OrdinaryCreateFromConstructor(newTarget, "%Object.prototype%").
+        let this_argument = match completion!(self.ordinary_create_from_constructor(&new_target, "Object.prototype")).value.deref() {
+            ReferenceRecordOrJsValue::JSValue(this_argument) => Rc::clone(this_argument),
+            _ => { unreachable!() }
+        };
+
+        // 4. Let calleeContext be PrepareForOrdinaryCall(F, newTarget).
+        self.prepare_for_ordinary_call(function, Some(new_target));
+
+        // 5. Assert: calleeContext is now the running execution context.
+        // 6. If kind is base, then
+        //        a. Perform OrdinaryCallBindThis(F, calleeContext, thisArgument).
+        //        b. TODO: Let initializeResult be Completion(InitializeInstanceElements(thisArgument, F)).
+        self.ordinary_call_bind_this(function, Rc::clone(&this_argument));
+
+        // 7. Let constructorEnv be the LexicalEnvironment of calleeContext.
+        let constructor_environment = Rc::clone(&self.running_execution_context().lexical_environment_record);
+
+        // 8. Let result be Completion(OrdinaryCallEvaluateBody(F, argumentsList)).
+        let result = self.ordinary_call_evaluate_body(function, arguments);
+
+        // 9. Remove calleeContext from the execution context stack and restore callerContext as the running execution context.
+        self.execution_contexts.pop();
+
+        match result.type_ {
+            // 10. If result is a return completion, then
+            CompletionRecordType::Return => {
+                // a. If result.[[Value]] is an Object, return result.[[Value]].
+                if let ReferenceRecordOrJsValue::JSValue(value) = &*result.value {
+                    if let JSValue::Object(_) = &*value.borrow() {
+                        return create_normal_completion(Rc::clone(&result.value));
+                    }
+                }
+
+                // b. If kind is base, return thisArgument.
+                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(this_argument)));
+            },
+            // 11. Else, ReturnIfAbrupt(result).
+            CompletionRecordType::Throw => {
+                return result;
+            },
+            _ => {}
+        }
+
+        // 12. Let thisBinding be ? constructorEnv.GetThisBinding().
+        // 13. Assert: thisBinding is an Object.
+        // 14. Return thisBinding.
+        return constructor_environment.borrow().get_this_binding();
+    }
+
+    // https://tc39.es/ecma262/#sec-ordinarycreatefromconstructor
+    pub(crate) fn ordinary_create_from_constructor(&mut self, constructor: &Rc<RefCell<JSObject>>, intrinsic_default_proto: &str) -> CompletionRecord {
+        // 1. Assert: intrinsicDefaultProto is this specification's name of an intrinsic object.
+        // 2. Let proto be ? GetPrototypeFromConstructor(constructor, intrinsicDefaultProto).
+        // https://tc39.es/ecma262/#sec-getprototypefromconstructor
+        // 2. Let proto be ? Get(constructor, "prototype").
+        let prototype = match completion!(self.get(constructor, &PropertyKey::String(String::from("prototype")), Rc::new(RefCell::new(JSValue::Object(Rc::clone(constructor)))))).value.deref() {
+            ReferenceRecordOrJsValue::JSValue(prototype) => match &*prototype.borrow() {
+                JSValue::Object(prototype) => Some(Rc::clone(prototype)),
+                // 3. If proto is not an Object, then
+                //        a. Let realm be ? GetFunctionRealm(constructor).
+                //        b. Set proto to realm's intrinsic object named intrinsicDefaultProto.
+                _ => self.intrinsic(intrinsic_default_proto)
+            },
+            _ => { unreachable!() }
+        };
+
+        // 3. If internalSlotsList is present, let slotsList be internalSlotsList.
+        // 5. Return OrdinaryObjectCreate(proto, slotsList).
+        let mut object = JSObject::new();
+        object.extensible = true;
+        object.prototype = prototype;
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Object(self.heap.allocate(object)))))));
+    }
+
+    // https://tc39.es/ecma262/#sec-makeconstructor
+    // TODO: The writablePrototype and prototype arguments are not supported
+    fn make_constructor(&mut self, function: &Rc<RefCell<JSObject>>) {
+        // 1. If F is an ECMAScript function object, then
+        //        a. Assert: IsConstructor(F) is false.
+        //        b. Assert: F is an extensible object that does not have a "prototype" own property.
+        //        c. Set F.[[Construct]] to the definition specified in 10.2.2.
+        // 3. Set F.[[ConstructorKind]] to base.
+        function.borrow_mut().ecmascript_function.as_mut().unwrap().constructor = true;
+
+        // 4. If writablePrototype is not present, set writablePrototype to true.
+        // 5. If prototype is not present, then
+        //        a. Set prototype to OrdinaryObjectCreate(%Object.prototype%).
+        let mut prototype = JSObject::new();
+        prototype.extensible = true;
+        prototype.prototype = self.intrinsic("Object.prototype");
+        let prototype = Rc::new(RefCell::new(prototype));
+
+        //        b. Perform ! DefinePropertyOrThrow(prototype, "constructor", PropertyDescriptor { [[Value]]: F, [[Writable]]: writablePrototype, [[Enumerable]]: false, [[Configurable]]: true }).
+        Interpreter::define_property_or_throw(&prototype, "constructor", Rc::new(RefCell::new(JSValue::Object(Rc::clone(function)))), true, false, true);
+
+        // 6. Perform ! DefinePropertyOrThrow(F, "prototype", PropertyDescriptor { [[Value]]: prototype, [[Writable]]: writablePrototype, [[Enumerable]]: false, [[Configurable]]: false }).
+        Interpreter::define_property_or_throw(function, "prototype", Rc::new(RefCell::new(JSValue::Object(prototype))), true, false, false);
+
+        // 7. Return unused.
+    }
+
+    // https://tc39.es/ecma262/#sec-instanceofoperator
+    fn instanceof_operator(&mut self, value: Rc<RefCell<JSValue>>, target: Rc<RefCell<JSValue>>) -> CompletionRecord {
+        // 1. If target is not an Object, throw a TypeError exception.
+        let target_object = match &*target.borrow() {
+            JSValue::Object(target_object) => Rc::clone(target_object),
+            _ => {
+                return self.throw_type_error(String::from("Right-hand side of 'instanceof' is not an object"));
+            }
+        };
+
+        // 2. TODO: Let instOfHandler be ? GetMethod(target, %Symbol.hasInstance%).
+        // 3. If instOfHandler is not undefined, then
+        //        a. Return ToBoolean(? Call(instOfHandler, target, « V »)).
+        // 4. If IsCallable(target) is false, throw a TypeError exception.
+        if !Interpreter::is_callable(&target.borrow()) {
+            return self.throw_type_error(String::from("Right-hand side of 'instanceof' is not callable"));
+        }
+
+        // 5. Return ? OrdinaryHasInstance(target, V).
+        return self.ordinary_has_instance(&target_object, value);
+    }
+
+    // https://tc39.es/ecma262/#sec-ordinaryhasinstance
+    fn ordinary_has_instance(&mut self, constructor: &Rc<RefCell<JSObject>>, value: Rc<RefCell<JSValue>>) -> CompletionRecord {
+        // 1. If IsCallable(C) is false, return false.
+        // 2. TODO: If C has a [[BoundTargetFunction]] internal slot, then
+        // 3. If O is not an Object, return false.
+        let mut object = match &*value.borrow() {
+            JSValue::Object(object) => Rc::clone(object),
+            _ => {
+                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(false))))));
+            }
+        };
+
+        // 4. Let P be ? Get(C, "prototype").
+        let prototype = match completion!(self.get(constructor, &PropertyKey::String(String::from("prototype")), Rc::new(RefCell::new(JSValue::Object(Rc::clone(constructor)))))).value.deref() {
+            ReferenceRecordOrJsValue::JSValue(prototype) => match &*prototype.borrow() {
+                JSValue::Object(prototype) => Rc::clone(prototype),
+                // 5. If P is not an Object, throw a TypeError exception.
+                _ => {
+                    return self.throw_type_error(String::from("Function has non-object prototype in instanceof check"));
+                }
+            },
+            _ => { unreachable!() }
+        };
+
+        // 6. Repeat,
+        loop {
+            // a. Set O to ? O.[[GetPrototypeOf]]().
+            let parent = object.borrow().prototype.clone();
+            match parent {
+                // b. If O is null, return false.
+                None => {
+                    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(false))))));
+                },
+                // c. If SameValue(P, O) is true, return true.
+                Some(parent) if Rc::ptr_eq(&parent, &prototype) => {
+                    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Boolean(true))))));
+                },
+                Some(parent) => object = parent
+            }
+        }
+    }
+
+    // https://tc39.es/ecma262/#sec-resolvethisbinding
+    fn resolve_this_binding(&mut self) -> CompletionRecord {
+        // 1. Let envRec be GetThisEnvironment().
+        // https://tc39.es/ecma262/#sec-getthisenvironment
+        // 1. Let env be the running execution context's LexicalEnvironment.
+        let mut environment = Rc::clone(&self.running_execution_context().lexical_environment_record);
+
+        // 2. Repeat,
+        loop {
+            // a. Let exists be env.HasThisBinding().
+            // b. If exists is true, return env.
+            if environment.borrow().has_this_binding() {
+                break;
+            }
+
+            // c. Let outer be env.[[OuterEnv]].
+            // d. Assert: outer is not null.
+            // e. Set env to outer.
+            let outer = environment.borrow().outer_environment_record.clone().unwrap();
+            environment = outer;
+        }
+
+        // 2. Return ? envRec.GetThisBinding().
+        return environment.borrow().get_this_binding();
+    }
+
+    // https://tc39.es/ecma262/#sec-ecmascript-function-objects-call-thisargument-argumentslist
+    fn call_ecmascript_function(&mut self, function: &Rc<RefCell<JSObject>>, this_argument: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+        // Every call is evaluated recursively, so runaway recursion in a script is stopped before it overflows the native stack
+        if self.execution_contexts.len() > self.script_limits.max_call_depth {
+            return self.throw_error("RangeError", String::from("Maximum call stack size exceeded"));
+        }
+
+        // 1. Let callerContext be the running execution context.
+        // 2. Let calleeContext be PrepareForOrdinaryCall(F, undefined).
+        self.prepare_for_ordinary_call(function, None);
+
+        // 3. Assert: calleeContext is now the running execution context.
+        // 4. TODO: If F.[[IsClassConstructor]] is true, then throw a TypeError exception.
+
+        // 5. Perform OrdinaryCallBindThis(F, calleeContext, thisArgument).
+        self.ordinary_call_bind_this(function, this_argument);
+
+        // 6. Let result be Completion(OrdinaryCallEvaluateBody(F, argumentsList)).
+        let result = self.ordinary_call_evaluate_body(function, arguments);
+
+        // 7. Remove calleeContext from the execution context stack and restore callerContext as the running execution context.
+        self.execution_contexts.pop();
+
+        match result.type_ {
+            // 8. If result is a return completion, return result.[[Value]].
+            CompletionRecordType::Return => {
+                return create_normal_completion(result.value);
+            },
+            // 9. ReturnIfAbrupt(result).
+            CompletionRecordType::Throw => {
+                return result;
+            },
+            // 10. Return undefined.
+            _ => {
+                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+            }
+        }
+    }
+
+    // https://tc39.es/ecma262/#sec-prepareforordinarycall
+    fn prepare_for_ordinary_call(&mut self, function: &Rc<RefCell<JSObject>>, new_target: Option<Rc<RefCell<JSObject>>>) {
+        // 1. Let callerContext be the running execution context.
+        // 2. Let calleeContext be a new ECMAScript code execution context.
+        // 3. Set the Function of calleeContext to F.
+        // 4. TODO: Set the Realm of calleeContext to F.[[Realm]].
+        // 5. Set the ScriptOrModule of calleeContext to F.[[ScriptOrModule]].
+        // 6. Let localEnv be NewFunctionEnvironment(F, newTarget).
+        let local_environment = Interpreter::new_function_environment(function, new_target);
+
+        // 7. Set the LexicalEnvironment of calleeContext to localEnv.
+        // 8. Set the VariableEnvironment of calleeContext to localEnv.
+        // 9. TODO: Set the PrivateEnvironment of calleeContext to F.[[PrivateEnvironment]].
+        let callee_context = ExecutionContext {
+            lexical_environment_record: Rc::clone(&local_environment),
+            variable_environment_record: local_environment,
+            function: Some(Rc::clone(function)),
+            line: self.running_execution_context().line,
+            column: self.running_execution_context().column,
+            script_name: Rc::clone(&function.borrow().ecmascript_function.as_ref().unwrap().script_name),
+            strict: function.borrow().ecmascript_function.as_ref().unwrap().strict,
+        };
+
+        // 10. If callerContext is not already suspended, suspend callerContext.
+        // 11. Push calleeContext onto the execution context stack; calleeContext is now the running execution context.
+        self.execution_contexts.push(callee_context);
+
+        // 12. NOTE: Any exception objects produced after this point are associated with calleeRealm.
+        // 13. Return calleeContext.
+    }
+
+    // https://tc39.es/ecma262/#sec-newfunctionenvironment
+    fn new_function_environment(function: &Rc<RefCell<JSObject>>, new_target: Option<Rc<RefCell<JSObject>>>) -> Rc<RefCell<EnvironmentRecord>> {
+        let function_ref = function.borrow();
+        let ecmascript_function = function_ref.ecmascript_function.as_ref().unwrap();
+
+        // 1. Let env be a new Function Environment Record containing no bindings.
+        // 2. Set env.[[FunctionObject]] to F.
+        // 3. If F.[[ThisMode]] is lexical, set env.[[ThisBindingStatus]] to lexical.
+        // 4. Else, set env.[[ThisBindingStatus]] to uninitialized.
+        let this_binding_status = if ecmascript_function.this_mode == ThisMode::Lexical {
+            ThisBindingStatus::Lexical
+        } else {
+            ThisBindingStatus::Uninitialized
+        };
+
+        // 5. Set env.[[NewTarget]] to newTarget.
+        let function_environment_record = FunctionEnvironmentRecord {
+            this_value: Rc::new(RefCell::new(JSValue::Undefined)),
+            this_binding_status,
+            function_object: Rc::clone(function),
+            new_target,
+        };
+
+        // 6. Set env.[[OuterEnv]] to F.[[Environment]].
+        // 7. Return env.
+        return Rc::new(RefCell::new(EnvironmentRecord {
+            environment_record_type: EnvironmentRecordType::DeclarativeEnvironmentRecord(Rc::new(RefCell::new(DeclarativeEnvironmentRecord {
+                variable_bindings: HashMap::new(),
+                function_environment_record: Some(function_environment_record)
+            }))),
+            outer_environment_record: Some(Rc::clone(&ecmascript_function.environment)),
+        }));
+    }
+
+    // https://tc39.es/ecma262/#sec-ordinarycallbindthis
+    fn ordinary_call_bind_this(&mut self, function: &Rc<RefCell<JSObject>>, this_argument: Rc<RefCell<JSValue>>) {
+        // 1. Let thisMode be F.[[ThisMode]].
+        let this_mode = function.borrow().ecmascript_function.as_ref().unwrap().this_mode;
+
+        // 2. If thisMode is lexical, return unused.
+        if this_mode == ThisMode::Lexical {
+            return;
+        }
+
+        // 3. Let calleeRealm be F.[[Realm]].
+        // 4. Let localEnv be the LexicalEnvironment of calleeContext.
+        let local_environment = Rc::clone(&self.running_execution_context().lexical_environment_record);
+
+        // 5. If thisMode is strict, then
+        //        a. Let thisValue be thisArgument.
+        // 6. Else,
+        let this_value = if this_mode == ThisMode::Strict {
+            this_argument
+        } else if matches!(*this_argument.borrow(), JSValue::Undefined | JSValue::Null) {
+            // a. If thisArgument is either undefined or null, then
+            //        i. Let globalEnv be calleeRealm.[[GlobalEnv]].
+            //        ii. Assert: globalEnv is a Global Environment Record.
+            //        iii. Let thisValue be globalEnv.[[GlobalThisValue]].
+            match &self.global_environment().borrow().environment_record_type {
+                EnvironmentRecordType::GlobalEnvironmentRecord(global_record) => {
+                    Rc::new(RefCell::new(JSValue::Object(Rc::clone(&global_record.borrow().global_this_value))))
+                },
+                _ => { unreachable!() }
+            }
+        } else {
+            // b. Else,
+            //        i. Let thisValue be ! ToObject(thisArgument).
+            // TODO: Primitives should be wrapped in their wrapper objects
+            this_argument
+        };
+
+        // 7. Assert: localEnv is a Function Environment Record.
+        // 8. Assert: The next step never returns an abrupt completion because localEnv.[[ThisBindingStatus]] is not initialized.
+        // 9. Perform ! localEnv.BindThisValue(thisValue).
+        let declarative_record = local_environment.borrow().declarative_record();
+        declarative_record.borrow_mut().function_environment_record.as_mut().unwrap().bind_this_value(this_value);
+
+        // 10. Return unused.
+    }
+
+    // https://tc39.es/ecma262/#sec-ordinarycallevaluatebody
+    fn ordinary_call_evaluate_body(&mut self, function: &Rc<RefCell<JSObject>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+        // 1. Return ? EvaluateBody of F.[[ECMAScriptCode]] with arguments F and argumentsList.
+        // https://tc39.es/ecma262/#sec-runtime-semantics-evaluatefunctionbody
+        // 1. Perform ? FunctionDeclarationInstantiation(functionObject, argumentsList).
+        completion!(self.function_declaration_instantiation(function, arguments));
+
+        // 2. Return ? Evaluation of FunctionStatementList.
+        let code = Rc::clone(&function.borrow().ecmascript_function.as_ref().unwrap().ecmascript_code);
+        let mut value = create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+        for statement in code.statements.iter() {
+            value = completion!(self.execute(statement));
+        }
+
+        return value;
+    }
+
+    // https://tc39.es/ecma262/#sec-functiondeclarationinstantiation
+    // TODO: The arguments object and parameter expressions are not supported
+    fn function_declaration_instantiation(&mut self, function: &Rc<RefCell<JSObject>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+        // 1. Let calleeContext be the running execution context.
+        // 2. Let code be func.[[ECMAScriptCode]].
+        // 4. Let formals be func.[[FormalParameters]].
+        let (code, formals) = {
+            let function_ref = function.borrow();
+            let ecmascript_function = function_ref.ecmascript_function.as_ref().unwrap();
+            (Rc::clone(&ecmascript_function.ecmascript_code), Rc::clone(&ecmascript_function.formal_parameters))
+        };
+
+        // 5. Let parameterNames be the BoundNames of formals.
+        let parameter_names: Vec<Atom> = formals.parameters.iter().map(|parameter| parameter.binding_identifier.lexeme.clone()).collect();
+
+        // 6. If parameterNames has any duplicate entries, let hasDuplicates be true. Otherwise, let hasDuplicates be false.
+        let has_duplicates = parameter_names.iter().enumerate().any(|(index, name)| parameter_names[..index].contains(name));
+
+        // https://tc39.es/ecma262/#sec-function-definitions-static-semantics-early-errors
+        // It is a Syntax Error if any element of the BoundNames of FormalParameters also occurs in the LexicallyDeclaredNames of FunctionBody.
+        let lexical_declarations = Statement::lexically_scoped_declarations(&code.statements);
+        let redeclared_name = Statement::redeclared_lexical_name(&code.statements).or_else(|| {
+            lexical_declarations.iter().map(|declaration| &declaration.binding_identifier).find(|binding_identifier| parameter_names.contains(&binding_identifier.lexeme))
+        });
+        if let Some(binding_identifier) = redeclared_name {
+            return self.throw_redeclaration_error(binding_identifier);
+        }
+
+        // 9. Let varNames be the VarDeclaredNames of code.
+        // 10. Let varDeclarations be the VarScopedDeclarations of code.
+        let function_declarations = Statement::function_declarations(&code.statements);
+        let mut var_names = Statement::var_declared_names(&code.statements);
+        var_names.extend(function_declarations.iter().map(|declaration| declaration.binding_identifier.lexeme.clone()));
+
+        // 12. Let functionNames be a new empty List.
+        // 13. Let functionsToInitialize be a new empty List.
+        // 14. For each element d of varDeclarations, in reverse List order, do
+        //        a. If d is neither a VariableDeclaration nor a ForBinding nor a BindingIdentifier, then
+        //               iii. If functionNames does not contain fn, then
+        //                      1. Insert fn as the first element of functionNames.
+        //                      2. NOTE: If there are multiple function declarations for the same name, the last declaration is used.
+        //                      3. Insert d as the first element of functionsToInitialize.
+        let mut function_names: Vec<Atom> = Vec::new();
+        let mut functions_to_initialize: Vec<&FunctionDeclaration> = Vec::new();
+        for declaration in function_declarations.iter().rev() {
+            let function_name = &declaration.binding_identifier.lexeme;
+            if !function_names.contains(function_name) {
+                function_names.insert(0, function_name.clone());
+                functions_to_initialize.insert(0, declaration);
+            }
+        }
+
+        // 19. If strict is true or hasParameterExpressions is false, then
+        //        a. NOTE: Only a single Environment Record is needed for the parameters, since calls to eval in strict mode code cannot create new bindings which are visible outside of the eval.
+        //        b. Let env be the LexicalEnvironment of calleeContext.
+        let environment = Rc::clone(&self.running_execution_context().lexical_environment_record);
+        let declarative_record = environment.borrow().declarative_record();
+
+        // 21. For each String paramName of parameterNames, do
+        for parameter_name in &parameter_names {
+            // a. Let alreadyDeclared be ! env.HasBinding(paramName).
+            // c. If alreadyDeclared is false, then
+            if !declarative_record.borrow().variable_bindings.contains_key(parameter_name) {
+                // i. Perform ! env.CreateMutableBinding(paramName, false).
+                declarative_record.borrow_mut().create_mutable_binding(parameter_name.clone(), false);
+
+                // ii. If hasDuplicates is true, then
+                //        1. Perform ! env.InitializeBinding(paramName, undefined).
+                if has_duplicates {
+                    declarative_record.borrow_mut().initialize_binding(parameter_name.clone(), Rc::new(RefCell::new(JSValue::Undefined)));
+                }
+            }
+        }
+
+        // 22. TODO: If argumentsObjectNeeded is true, then create the arguments object.
+
+        // 24. Let iteratorRecord be CreateListIteratorRecord(argumentsList).
+        // 25. If hasDuplicates is true, then
+        //        a. Perform ? IteratorBindingInitialization of formals with arguments iteratorRecord and undefined.
+        // 26. Else,
+        //        a. Perform ? IteratorBindingInitialization of formals with arguments iteratorRecord and env.
+        // https://tc39.es/ecma262/#sec-runtime-semantics-iteratorbindinginitialization
+        // Parameters without a matching argument are bound to undefined
+        for (index, parameter_name) in parameter_names.iter().enumerate() {
+            let value = match arguments.get(index) {
+                Some(argument) => Rc::clone(argument),
+                None => Rc::new(RefCell::new(JSValue::Undefined))
+            };
+
+            if has_duplicates {
+                completion!(declarative_record.borrow_mut().set_mutable_binding(parameter_name.clone(), value, false));
+            } else {
+                declarative_record.borrow_mut().initialize_binding(parameter_name.clone(), value);
+            }
+        }
+
+        // 27. If hasParameterExpressions is false, then
+        //        a. NOTE: Only a single Environment Record is needed for the parameters and top-level vars.
+        //        b. Let instantiatedVarNames be a copy of the List parameterBindings.
+        let mut instantiated_var_names = parameter_names.clone();
+
+        //        c. For each element n of varNames, do
+        for var_name in var_names {
+            // i. If instantiatedVarNames does not contain n, then
+            if !instantiated_var_names.contains(&var_name) {
+                // 1. Append n to instantiatedVarNames.
+                instantiated_var_names.push(var_name.clone());
+
+                // 2. Perform ! env.CreateMutableBinding(n, false).
+                declarative_record.borrow_mut().create_mutable_binding(var_name.clone(), false);
+
+                // 3. Perform ! env.InitializeBinding(n, undefined).
+                declarative_record.borrow_mut().initialize_binding(var_name, Rc::new(RefCell::new(JSValue::Undefined)));
+            }
+        }
+
+        //        d. Let varEnv be env.
+        // 30. If strict is false, then
+        //        a. Let lexEnv be NewDeclarativeEnvironment(varEnv).
+        //        b. NOTE: Non-strict functions use a separate Environment Record for top-level lexical declarations so that a direct eval can determine whether any var scoped declarations introduced by the eval code conflict with pre-existing top-level lexically scoped declarations.
+        // 31. Else,
+        //        a. Let lexEnv be varEnv.
+        let lexical_environment = EnvironmentRecord::new_declarative_environment(Some(Rc::clone(&environment)));
+
+        // 32. Set the LexicalEnvironment of calleeContext to lexEnv.
+        self.execution_contexts.last_mut().unwrap().lexical_environment_record = Rc::clone(&lexical_environment);
+
+        // 33. Let lexDeclarations be the LexicallyScopedDeclarations of code.
+        // 34. For each element d of lexDeclarations, do
+        //        a. NOTE: A lexically declared name cannot be the same as a function/generator declaration, formal parameter, or a var name. Lexically declared names are only instantiated here but not initialized.
+        //        b. For each element dn of the BoundNames of d, do
+        //               i. If IsConstantDeclaration of d is true, then
+        //                      1. Perform ! lexEnv.CreateImmutableBinding(dn, true).
+        //               ii. Else,
+        //                      1. Perform ! lexEnv.CreateMutableBinding(dn, false).
+        lexical_environment.borrow().declarative_record().borrow_mut().create_lexical_bindings(&lexical_declarations);
+
+        // 36. For each Parse Node f of functionsToInitialize, do
+        for declaration in functions_to_initialize {
+            // a. Let fn be the sole element of the BoundNames of f.
+            // b. Let fo be InstantiateFunctionObject of f with arguments lexEnv and privateEnv.
+            let function_object = self.instantiate_ordinary_function_object(declaration, Rc::clone(&lexical_environment));
+
+            // c. Perform ! varEnv.SetMutableBinding(fn, fo, false).
+            declarative_record.borrow_mut().set_mutable_binding(declaration.binding_identifier.lexeme.clone(), Rc::new(RefCell::new(JSValue::Object(function_object))), false);
+        }
+
+        // 37. Return unused.
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+    }
+
+    // https://tc39.es/ecma262/#sec-globaldeclarationinstantiation
+    // TODO: The CanDeclareGlobalFunction and CanDeclareGlobalVar checks are not supported
+    fn global_declaration_instantiation(&mut self, statements: &[Statement]) -> CompletionRecord {
+        let environment = self.global_environment();
+        let global_record = match &environment.borrow().environment_record_type {
+            EnvironmentRecordType::GlobalEnvironmentRecord(global_record) => Rc::clone(global_record),
+            _ => { unreachable!() }
+        };
+
+        // https://tc39.es/ecma262/#sec-scripts-static-semantics-early-errors
+        if let Some(binding_identifier) = Statement::redeclared_lexical_name(statements) {
+            return self.throw_redeclaration_error(binding_identifier);
+        }
+
+        // 1. Let lexNames be the LexicallyDeclaredNames of script.
+        // 2. Let varNames be the VarDeclaredNames of script.
+        // 5. Let varDeclarations be the VarScopedDeclarations of script.
+        let lexical_declarations = Statement::lexically_scoped_declarations(statements);
+        let function_declarations = Statement::function_declarations(statements);
+        let mut var_names = Statement::var_declared_names(statements);
+        var_names.extend(function_declarations.iter().map(|declaration| declaration.binding_identifier.lexeme.clone()));
+
+        // 3. For each element name of lexNames, do
+        for declaration in &lexical_declarations {
+            let name = &declaration.binding_identifier.lexeme;
+
+            // a. If HasVarDeclaration(env, name) is true, throw a SyntaxError exception.
+            // b. If HasLexicalDeclaration(env, name) is true, throw a SyntaxError exception.
+            // c. Let hasRestrictedGlobal be ? HasRestrictedGlobalProperty(env, name).
+            // d. NOTE: Global var and function bindings (except those that are introduced by non-strict direct eval) are non-configurable and are therefore restricted global properties.
+            // e. If hasRestrictedGlobal is true, throw a SyntaxError exception.
+            let is_declared = {
+                let global_record = global_record.borrow();
+                global_record.has_var_declaration(name) || global_record.has_lexical_declaration(name) || global_record.has_restricted_global_property(name)
+            };
+            if is_declared {
+                return self.throw_redeclaration_error(&declaration.binding_identifier);
+            }
+        }
+
+        // 4. For each element name of varNames, do
+        //        a. If HasLexicalDeclaration(env, name) is true, throw a SyntaxError exception.
+        let redeclared_name = var_names.into_iter().find(|name| global_record.borrow().has_lexical_declaration(name));
+        if let Some(name) = redeclared_name {
+            return self.throw_error("SyntaxError", format!("Identifier '{}' has already been declared", name));
+        }
+
+        // 6. Let functionsToInitialize be a new empty List.
+        // 7. Let declaredFunctionNames be a new empty List.
+        let mut functions_to_initialize: Vec<&FunctionDeclaration> = Vec::new();
+        let mut declared_function_names: Vec<Atom> = Vec::new();
+
+        // 8. For each element d of varDeclarations, in reverse List order, do
+        for declaration in function_declarations.iter().rev() {
+            // a. If d is not either a VariableDeclaration, a ForBinding, or a BindingIdentifier, then
+            //        iii. Let fn be the sole element of the BoundNames of d.
+            let function_name = &declaration.binding_identifier.lexeme;
+
+            //        iv. If declaredFunctionNames does not contain fn, then
+            if !declared_function_names.contains(function_name) {
+                // 3. Append fn to declaredFunctionNames.
+                declared_function_names.push(function_name.clone());
+
+                // 4. Insert d as the first element of functionsToInitialize.
+                functions_to_initialize.insert(0, declaration);
+            }
+        }
+
+        // 9. Let declaredVarNames be a new empty List.
+        // 10. For each element d of varDeclarations, do
+        //        a. If d is either a VariableDeclaration, a ForBinding, or a BindingIdentifier, then
+        //               i. For each String vn of the BoundNames of d, do
+        //                      1. If declaredFunctionNames does not contain vn, then
+        //                             c. If declaredVarNames does not contain vn, then
+        //                                    i. Append vn to declaredVarNames.
+        let mut declared_var_names: Vec<Atom> = Vec::new();
+        for var_name in Statement::var_declared_names(statements) {
+            if !declared_function_names.contains(&var_name) && !declared_var_names.contains(&var_name) {
+                declared_var_names.push(var_name);
+            }
+        }
+
+        // 15. Let lexDeclarations be the LexicallyScopedDeclarations of script.
+        // 16. For each element d of lexDeclarations, do
+        //        a. NOTE: Lexically declared names are only instantiated here but not initialized.
+        //        b. For each element dn of the BoundNames of d, do
+        //               i. If IsConstantDeclaration of d is true, then
+        //                      1. Perform ? env.CreateImmutableBinding(dn, true).
+        //               ii. Else,
+        //                      1. Perform ? env.CreateMutableBinding(dn, false).
+        global_record.borrow().declarative_environment_record.borrow_mut().create_lexical_bindings(&lexical_declarations);
+
+        // 17. For each Parse Node f of functionsToInitialize, do
+        for declaration in functions_to_initialize {
+            // a. Let fn be the sole element of the BoundNames of f.
+            // b. Let fo be InstantiateFunctionObject of f with arguments env and privateEnv.
+            let function_object = self.instantiate_ordinary_function_object(declaration, Rc::clone(&environment));
+
+            // c. Perform ? env.CreateGlobalFunctionBinding(fn, fo, false).
+            completion!(global_record.borrow().create_global_function_binding(declaration.binding_identifier.lexeme.clone(), Rc::new(RefCell::new(JSValue::Object(function_object))), false));
+        }
+
+        // 18. For each String vn of declaredVarNames, do
+        for var_name in declared_var_names {
+            // a. Perform ? env.CreateGlobalVarBinding(vn, false).
+            completion!(global_record.borrow().create_global_var_binding(var_name, false));
+        }
+
+        // 19. Return unused.
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+    }
+
+    // https://tc39.es/ecma262/#sec-ordinaryfunctioncreate
+    fn ordinary_function_create(&self, formal_parameters: &Rc<FormalParameters>, body: &Rc<FunctionBody>, lexical_this: bool, environment: Rc<RefCell<EnvironmentRecord>>) -> Rc<RefCell<JSObject>> {
+        // 1. Let internalSlotsList be the internal slots listed in Table 30.
+        // 2. Let F be OrdinaryObjectCreate(functionPrototype, internalSlotsList).
+        let mut function = JSObject::new();
+        function.extensible = true;
+        function.prototype = self.intrinsic("Function.prototype");
+
+        // 3. Set F.[[Call]] to the definition specified in 10.2.1.
+        // 4. Set F.[[SourceText]] to sourceText.
+        // 5. Set F.[[FormalParameters]] to ParameterList.
+        // 6. Set F.[[ECMAScriptCode]] to Body.
+        // 7. Let Strict be IsStrict(Body).
+        // 8. Set F.[[Strict]] to Strict.
+        let strict = body.strict;
+
+        // 9. If thisMode is lexical-this, set F.[[ThisMode]] to lexical.
+        // 10. Else if Strict is true, set F.[[ThisMode]] to strict.
+        // 11. Else, set F.[[ThisMode]] to global.
+        let this_mode = if lexical_this { ThisMode::Lexical } else if strict { ThisMode::Strict } else { ThisMode::Global };
+
+        // 12. Set F.[[IsClassConstructor]] to false.
+        // 13. Set F.[[Environment]] to env.
+        // 14. TODO: Set F.[[PrivateEnvironment]] to privateEnv.
+        function.ecmascript_function = Some(ECMAScriptFunction {
+            environment,
+            formal_parameters: Rc::clone(formal_parameters),
+            ecmascript_code: Rc::clone(body),
+            this_mode,
+            strict,
+            script_name: Rc::clone(&self.running_execution_context().script_name),
+            constructor: false,
+        });
+        let function = self.heap.allocate(function);
+
+        // 15. Set F.[[ScriptOrModule]] to GetActiveScriptOrModule().
+        // 16-20. TODO: Set F.[[Realm]], [[HomeObject]], [[Fields]], [[PrivateMethods]] and [[ClassFieldInitializerName]].
+        // 21. Let len be the ExpectedArgumentCount of ParameterList.
+        // 22. Perform SetFunctionLength(F, len).
+        Interpreter::set_function_length(&function, formal_parameters.parameters.len() as Number);
+
+        // 23. Return F.
+        return function;
+    }
+
+    // https://tc39.es/ecma262/#sec-setfunctionname
+    // TODO: Symbol names and the prefix argument are not supported
+    pub(crate) fn set_function_name(function: &Rc<RefCell<JSObject>>, name: &str) {
+        // 1. Assert: F is an extensible object that does not have a "name" own property.
+        // 6. Perform ! DefinePropertyOrThrow(F, "name", PropertyDescriptor { [[Value]]: name, [[Writable]]: false, [[Enumerable]]: false, [[Configurable]]: true }).
+        Interpreter::define_property_or_throw(function, "name", Rc::new(RefCell::new(JSValue::String(name.to_string()))), false, false, true);
+
+        // 7. Return unused.
+    }
+
+    // https://tc39.es/ecma262/#sec-setfunctionlength
+    fn set_function_length(function: &Rc<RefCell<JSObject>>, length: Number) {
+        // 1. Assert: F is an extensible object that does not have a "length" own property.
+        // 2. Perform ! DefinePropertyOrThrow(F, "length", PropertyDescriptor { [[Value]]: 𝔽(length), [[Writable]]: false, [[Enumerable]]: false, [[Configurable]]: true }).
+        Interpreter::define_property_or_throw(function, "length", Rc::new(RefCell::new(JSValue::Numeric(length))), false, false, true);
+
+        // 3. Return unused.
+    }
+
+    // https://tc39.es/ecma262/#sec-runtime-semantics-instantiateordinaryfunctionobject
+    fn instantiate_ordinary_function_object(&mut self, declaration: &FunctionDeclaration, environment: Rc<RefCell<EnvironmentRecord>>) -> Rc<RefCell<JSObject>> {
+        // 1. Let name be the StringValue of BindingIdentifier.
+        // 2. Let sourceText be the source text matched by FunctionDeclaration.
+        // 3. Let F be OrdinaryFunctionCreate(%Function.prototype%, sourceText, FormalParameters, FunctionBody, non-lexical-this, env, privateEnv).
+        let function = self.ordinary_function_create(&declaration.formal_parameters, &declaration.function_body, false, environment);
+
+        // 4. Perform SetFunctionName(F, name).
+        Interpreter::set_function_name(&function, &declaration.binding_identifier.lexeme);
+
+        // 5. Perform MakeConstructor(F).
+        self.make_constructor(&function);
+
+        // 6. Return F.
+        return function;
+    }
+
+    // https://tc39.es/ecma262/#sec-runtime-semantics-instantiateordinaryfunctionexpression
+    fn instantiate_ordinary_function_expression(&mut self, expression: &FunctionExpression, name: Option<&str>) -> Rc<RefCell<JSObject>> {
+        let outer_environment = Rc::clone(&self.running_execution_context().lexical_environment_record);
+
+        match &expression.binding_identifier {
+            // FunctionExpression : function ( FormalParameters ) { FunctionBody }
+            None => {
+                // 1. If name is not present, set name to "".
+                let name = name.unwrap_or("");
+
+                // 2. Let env be the LexicalEnvironment of the running execution context.
+                // 3. Let privateEnv be the running execution context's PrivateEnvironment.
+                // 4. Let sourceText be the source text matched by FunctionExpression.
+                // 5. Let closure be OrdinaryFunctionCreate(%Function.prototype%, sourceText, FormalParameters, FunctionBody, non-lexical-this, env, privateEnv).
+                let closure = self.ordinary_function_create(&expression.formal_parameters, &expression.function_body, false, outer_environment);
+
+                // 6. Perform SetFunctionName(closure, name).
+                Interpreter::set_function_name(&closure, name);
+
+                // 7. Perform MakeConstructor(closure).
+                self.make_constructor(&closure);
+
+                // 8. Return closure.
+                return closure;
+            },
+            // FunctionExpression : function BindingIdentifier ( FormalParameters ) { FunctionBody }
+            Some(binding_identifier) => {
+                // 1. Assert: name is not present.
+                // 2. Set name to the StringValue of BindingIdentifier.
+                let name = binding_identifier.lexeme.clone();
+
+                // 3. Let outerEnv be the running execution context's LexicalEnvironment.
+                // 4. Let funcEnv be NewDeclarativeEnvironment(outerEnv).
+                // The function can refer to itself by name from inside its body
+                let function_environment = EnvironmentRecord::new_declarative_environment(Some(outer_environment));
+                let declarative_record = function_environment.borrow().declarative_record();
+
+                // 5. Perform ! funcEnv.CreateImmutableBinding(name, false).
+                declarative_record.borrow_mut().create_immutable_binding(name.clone(), false);
+
+                // 6. Let privateEnv be the running execution context's PrivateEnvironment.
+                // 7. Let sourceText be the source text matched by FunctionExpression.
+                // 8. Let closure be OrdinaryFunctionCreate(%Function.prototype%, sourceText, FormalParameters, FunctionBody, non-lexical-this, funcEnv, privateEnv).
+                let closure = self.ordinary_function_create(&expression.formal_parameters, &expression.function_body, false, function_environment);
+
+                // 9. Perform SetFunctionName(closure, name).
+                Interpreter::set_function_name(&closure, &name);
+
+                // 10. Perform MakeConstructor(closure).
+                self.make_constructor(&closure);
+
+                // 11. Perform ! funcEnv.InitializeBinding(name, closure).
+                declarative_record.borrow_mut().initialize_binding(name, Rc::new(RefCell::new(JSValue::Object(Rc::clone(&closure)))));
+
+                // 12. Return closure.
+                return closure;
+            }
+        }
+    }
+
+    // https://tc39.es/ecma262/#sec-runtime-semantics-instantiatearrowfunctionexpression
+    fn instantiate_arrow_function_expression(&mut self, expression: &ArrowFunction, name: Option<&str>) -> Rc<RefCell<JSObject>> {
+        // 1. If name is not present, set name to "".
+        let name = name.unwrap_or("");
+
+        // 2. Let env be the LexicalEnvironment of the running execution context.
+        let environment = Rc::clone(&self.running_execution_context().lexical_environment_record);
+
+        // 3. Let privateEnv be the running execution context's PrivateEnvironment.
+        // 4. Let sourceText be the source text matched by ArrowFunction.
+        // 5. Let closure be OrdinaryFunctionCreate(%Function.prototype%, sourceText, ArrowParameters, ConciseBody, lexical-this, env, privateEnv).
+        let closure = self.ordinary_function_create(&expression.formal_parameters, &expression.function_body, true, environment);
+
+        // 6. Perform SetFunctionName(closure, name).
+        Interpreter::set_function_name(&closure, name);
+
+        // 7. Return closure.
+        return closure;
+    }
+
+    // https://tc39.es/ecma262/#sec-runtime-semantics-namedevaluation
+    // Anonymous functions take the name of the binding or property they are assigned to
+    fn named_evaluation(&mut self, expression: &ExpressionStatement, name: &str) -> CompletionRecord {
+        let closure = match expression {
+            // 1. Return InstantiateOrdinaryFunctionExpression of FunctionExpression with argument name.
+            ExpressionStatement::FunctionExpression(function_expression) => self.instantiate_ordinary_function_expression(function_expression, Some(name)),
+            // 1. Return InstantiateArrowFunctionExpression of ArrowFunction with argument name.
+            ExpressionStatement::ArrowFunction(arrow_function) => self.instantiate_arrow_function_expression(arrow_function, Some(name)),
+            // 1. Assert: IsAnonymousFunctionDefinition(Expression) is true.
+            // 2. Return ? NamedEvaluation of Expression with argument name.
+            ExpressionStatement::ParenthesizedExpression(parenthesized_expression) => {
+                return self.named_evaluation(&parenthesized_expression.expression, name);
+            },
+            _ => { unreachable!() }
+        };
+
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Object(closure))))));
+    }
+
+    // The SyntaxError for a let or const declaration whose name is already declared in the same scope
+    fn throw_redeclaration_error(&mut self, binding_identifier: &Token) -> CompletionRecord {
+        self.set_position(binding_identifier);
+        return self.throw_error("SyntaxError", format!("Identifier '{}' has already been declared", binding_identifier.lexeme));
+    }
+
+    // https://tc39.es/ecma262/#sec-native-error-types-used-in-this-standard-typeerror
+    pub(crate) fn throw_type_error(&mut self, message: String) -> CompletionRecord {
+        return self.throw_error("TypeError", message);
+    }
+
+    // Throws an instance of the native error named name, host errors such as DOMExceptions use this too
+    pub(crate) fn throw_error(&mut self, name: &str, message: String) -> CompletionRecord {
+        let error = create_error_object(name, message);
+        complete_error_object(self, &error);
+        return create_throw_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Object(error))))));
+    }
+
+    // The frames of the execution context stack from the running one outwards, each at the script, line and column it was at,
+    // e.g. handler (inline-script-2:14:7), or inline-script-2:20:1 for script code and functions without a name
+    pub(crate) fn stack_trace(&self) -> Vec<String> {
+        let mut frames = Vec::new();
+        for execution_context in self.execution_contexts.iter().rev().take(STACK_TRACE_LIMIT) {
+            let location = format!("{}:{}:{}", execution_context.script_name, execution_context.line, execution_context.column);
+            match &execution_context.function {
+                Some(function) => {
+                    match function.borrow().own_property(&PropertyKey::String(String::from("name"))).as_deref() {
+                        Some(PropertyType::DataProperty(data_property)) => match &*data_property.value.borrow() {
+                            JSValue::String(name) if !name.is_empty() => frames.push(format!("{} ({})", name, location)),
+                            _ => frames.push(location)
+                        },
+                        _ => frames.push(location)
+                    };
+                },
+                None => frames.push(location)
+            }
+        }
+        return frames;
+    }
+
+    // Records the position of the token being evaluated in the running execution context
+    fn set_position(&mut self, token: &Token) {
+        let execution_context = self.execution_contexts.last_mut().unwrap();
+        execution_context.line = token.line;
+        execution_context.column = token.column;
+    }
+
+    // Used for error messages only
+    fn referenced_name_to_string(referenced_name: &JSValue) -> String {
+        match referenced_name {
+            JSValue::String(name) => name.clone(),
+            _ => format!("{:?}", referenced_name)
+        }
+    }
+
+    // https://tc39.es/ecma262/#sec-typeof-operator
+    fn type_name(value: &JSValue) -> &'static str {
+        match value {
+            JSValue::Undefined => "undefined",
+            JSValue::Null => "null",
+            JSValue::Boolean(_) => "boolean",
+            JSValue::String(_) => "string",
+            JSValue::Symbol(_) => "symbol",
+            JSValue::Numeric(_) => "number",
+            JSValue::Object(_) => "object",
+        }
+    }
+
+    // Defines a property on the global object, used by the host to expose objects such as document to scripts
+    pub(crate) fn define_global_property(&mut self, key: &str, value: Rc<RefCell<JSValue>>) {
+        let global_object = self.global_object();
+        Interpreter::create_data_property(&global_object, key, value);
+    }
+
+    // https://tc39.es/ecma262/#sec-createintrinsics
+    // Creates the fundamental objects every other intrinsic inherits from, the rest are installed by their own modules
+    fn create_intrinsics(&mut self) {
+        // https://tc39.es/ecma262/#sec-properties-of-the-object-prototype-object
+        // The Object prototype object has a [[Prototype]] internal slot whose value is null
+        let mut object_prototype = JSObject::new();
+        object_prototype.extensible = true;
+        let object_prototype = Rc::new(RefCell::new(object_prototype));
+
+        // https://tc39.es/ecma262/#sec-properties-of-the-function-prototype-object
+        // The Function prototype object is itself a built-in function object that accepts any arguments and returns undefined
+        let function_prototype = Interpreter::create_builtin_function(function_prototype_call);
+        function_prototype.borrow_mut().prototype = Some(Rc::clone(&object_prototype));
+        Interpreter::set_function_length(&function_prototype, 0.0);
+        Interpreter::set_function_name(&function_prototype, "");
+
+        // The global object inherits from Object.prototype too
+        self.global_object().borrow_mut().prototype = Some(Rc::clone(&object_prototype));
+
+        self.set_intrinsic("Object.prototype", object_prototype);
+        self.set_intrinsic("Function.prototype", function_prototype);
+    }
+
+    // https://tc39.es/ecma262/#sec-well-known-intrinsic-objects
+    // Host defined intrinsics such as interface prototype objects are stored here too
+    pub(crate) fn intrinsic(&self, name: &str) -> Option<Rc<RefCell<JSObject>>> {
+        return self.intrinsics.get(name).cloned();
+    }
+
+    pub(crate) fn set_intrinsic(&mut self, name: &str, object: Rc<RefCell<JSObject>>) {
+        self.intrinsics.insert(name.to_string(), object);
+    }
+
+    // https://tc39.es/ecma262/#sec-ordinaryobjectcreate
+    // OrdinaryObjectCreate(%Object.prototype%), the plain object an object literal or JSON.parse creates
+    pub(crate) fn create_ordinary_object(&self) -> Rc<RefCell<JSObject>> {
+        let mut object = JSObject::new();
+        object.extensible = true;
+        object.prototype = self.intrinsic("Object.prototype");
+        return self.heap.allocate(object);
+    }
+
+    // https://tc39.es/ecma262/#sec-arraycreate
+    pub(crate) fn array_create(&self, length: u32) -> Rc<RefCell<JSObject>> {
+        // 1. If length > 2^32 - 1, throw a RangeError exception.
+        // 2. If proto is not present, set proto to %Array.prototype%.
+        // 3. Let A be MakeBasicObject(« [[Prototype]], [[Extensible]] »).
+        let mut array = JSObject::new();
+        array.extensible = true;
+
+        // 4. Set A.[[Prototype]] to proto.
+        array.prototype = self.intrinsic("Array.prototype");
 
-// https://tc39.es/ecma262/#sec-completion-record-specification-type
-#[derive(Debug)]
-struct CompletionRecord {
-    // https://tc39.es/ecma262/#table-completion-record-fields
-    type_: CompletionRecordType,
-    value: Rc<ReferenceRecordOrJsValue>,
-    target: Option<String>
-}
+        // 5. Set A.[[DefineOwnProperty]] as specified in 10.4.2.1.
+        array.array = true;
 
-#[derive(PartialEq)]
-enum ObjectInternalSlot {
-    Prototype,
-    Extensible,
-    PrivateElements
-}
+        // 6. Perform ! OrdinaryDefineOwnProperty(A, "length", PropertyDescriptor { [[Value]]: 𝔽(length), [[Writable]]: true, [[Enumerable]]: false, [[Configurable]]: false }).
+        array.set_array_length(length, true);
 
-impl Interpreter {
-    pub fn new() -> Interpreter {
-        Interpreter {
-            had_error: false,
-            execution_contexts: vec![
-                ExecutionContext {
-                    lexical_environment_record: Rc::new(RefCell::new(EnvironmentRecord::new(EnvironmentRecordType::GlobalEnvironmentRecord(Rc::new(RefCell::new(GlobalEnvironmentRecord {
-                        global_this_value: None, // Should not be none, temporary
-                        object_environment_record: Option::from(Rc::new(RefCell::new(ObjectEnvironmentRecord {
-                            binding_object: Rc::new(RefCell::new(JSObject {
-                                values: HashMap::new(),
-                                prototype: None,
-                                extensible: false,
-                            })),
-                            is_with_environment: false
-                        }))), // Should not be none, temporary
-                        declarative_environment_record: RefCell::new(DeclarativeEnvironmentRecord { variable_bindings: HashMap::new(), function_environment_record: None })
-                    })))))),
-                    variable_environment_record: Rc::new(RefCell::new(EnvironmentRecord {
-                        outer_environment_record: None,
-                        environment_record_type: EnvironmentRecordType::DeclarativeEnvironmentRecord(
-                            Rc::new(RefCell::new(DeclarativeEnvironmentRecord { variable_bindings: HashMap::new(), function_environment_record: None }))
-                        )
-                    })),
-                }
-            ]
-        }
+        // 7. Return A.
+        return self.heap.allocate(array);
     }
-    // https://tc39.es/ecma262/#sec-ordinaryobjectcreate
-    fn ordinary_object_create(&mut self, proto: Option<JSObject>, mut additional_internal_slots: Vec<ObjectInternalSlot>) -> JSObject {
-        // 1. Let internalSlotsList be « [[Prototype]], [[Extensible]] ».
-        let mut internal_slots = vec![ObjectInternalSlot::Prototype, ObjectInternalSlot::Extensible];
 
-        // 2. If additionalInternalSlotsList is present, set internalSlotsList to the list-concatenation of internalSlotsList and additionalInternalSlotsList.
-        if additional_internal_slots.len() > 0 {
-            internal_slots.append(&mut additional_internal_slots);
+    // https://tc39.es/ecma262/#sec-createarrayfromlist
+    pub(crate) fn create_array_from_list(&self, elements: Vec<Rc<RefCell<JSValue>>>) -> Rc<RefCell<JSObject>> {
+        // 1. Let array be ! ArrayCreate(0).
+        let array = self.array_create(0);
+
+        // 2. Let n be 0.
+        // 3. For each element e of elements, do
+        for (n, element) in elements.into_iter().enumerate() {
+            // a. Perform ! CreateDataPropertyOrThrow(array, ! ToString(𝔽(n)), e).
+            // b. Set n to n + 1.
+            Interpreter::create_data_property(&array, &n.to_string(), element);
         }
 
-        // 3. Let O be MakeBasicObject(internalSlotsList).
-        let mut object = self.make_basic_object(internal_slots);
-
-        // 4. Set O.[[Prototype]] to proto.
-        object.prototype = Some(Rc::new(proto.unwrap()));
+        // 4. Return array.
+        return array;
+    }
 
-        // 5. Return O.
-        return object;
+    // https://tc39.es/ecma262/#sec-isarray
+    pub(crate) fn is_array(value: &JSValue) -> bool {
+        match value {
+            // 2. If argument is an Array exotic object, return true.
+            JSValue::Object(object) => object.borrow().array,
+            // 1. If argument is not an Object, return false.
+            _ => false
+        }
     }
 
-    // https://tc39.es/ecma262/#sec-set-o-p-v-throw
-    pub fn set(object: &Rc<RefCell<JSObject>>, key: Rc<PropertyKey>, value: Rc<RefCell<JSValue>>, throw: bool) -> CompletionRecord {
-        // 1. Let success be ? O.[[Set]](P, V, O).
-        let success =  completion!(object.borrow_mut().set(key.clone(), value.clone(), object));
-        // 2. If success is false and Throw is true, throw a TypeError exception. TODO
-        // 3. Return unused.
-        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+    // https://tc39.es/ecma262/#sec-isstrictlyequal
+    pub(crate) fn is_strictly_equal(left: &JSValue, right: &JSValue) -> bool {
+        // 1. If SameType(x, y) is false, return false.
+        if !Interpreter::same_type(left, right) {
+            return false;
+        }
+
+        match (left, right) {
+            // 2. If x is a Number, then
+            //     a. Return Number::equal(x, y).
+            (JSValue::Numeric(left), JSValue::Numeric(right)) => left == right,
+            // 3. Return SameValueNonNumber(x, y).
+            // https://tc39.es/ecma262/#sec-samevaluenonnumber
+            (JSValue::String(left), JSValue::String(right)) => left == right,
+            (JSValue::Boolean(left), JSValue::Boolean(right)) => left == right,
+            (JSValue::Object(left), JSValue::Object(right)) => Rc::ptr_eq(left, right),
+            // TODO: Symbols are copied rather than shared, so two symbols are only equal if they are the same value
+            (JSValue::Symbol(_), JSValue::Symbol(_)) => std::ptr::eq(left, right),
+            _ => true
+        }
     }
 
     fn make_basic_object(&self, mut internal_slots: Vec<ObjectInternalSlot>) -> JSObject {
@@ -1215,8 +4147,23 @@ impl Interpreter {
         return &self.execution_contexts[self.execution_contexts.len() - 1];
     }
 
-    fn global_object(&self) -> Rc<RefCell<JSObject>> {
-        match &self.running_execution_context().lexical_environment_record.borrow().environment_record_type {
+    // https://tc39.es/ecma262/#sec-code-realms
+    // The realm's [[GlobalEnv]], the outermost environment of the script execution context at the bottom of the stack
+    // The script's LexicalEnvironment is a block's environment while that block is evaluated
+    fn global_environment(&self) -> Rc<RefCell<EnvironmentRecord>> {
+        let mut environment = Rc::clone(&self.execution_contexts[0].lexical_environment_record);
+        loop {
+            let outer = environment.borrow().outer_environment_record.clone();
+            match outer {
+                Some(outer) => environment = outer,
+                None => return environment
+            }
+        }
+    }
+
+    // https://tc39.es/ecma262/#sec-getglobalobject
+    pub(crate) fn global_object(&self) -> Rc<RefCell<JSObject>> {
+        match &self.global_environment().borrow().environment_record_type {
             EnvironmentRecordType::GlobalEnvironmentRecord(record) => {
                 return record.borrow_mut().object_environment_record.clone().unwrap().borrow_mut().binding_object.clone();
             },
@@ -1229,13 +4176,17 @@ impl Interpreter {
             // 1. If V is not a Reference Record, throw a ReferenceError exception.
             ReferenceRecordOrJsValue::JSValue(_) => {
                 // FIXME: The value here in throw and everywhere else we throw should be a Error object and and not just the base object
-                return CompletionRecord { type_: CompletionRecordType::Throw, value: Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Object(JSObject { values: HashMap::new(), prototype: None, extensible: false }))))), target: None }
+                return CompletionRecord { type_: CompletionRecordType::Throw, value: Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Object(Rc::new(RefCell::new(JSObject::new()))))))), target: None }
             }
             ReferenceRecordOrJsValue::ReferenceRecord(reference_record) => {
                 //     2. If IsUnresolvableReference(V) is true, throw a ReferenceError exception.
                 match reference_record.base.as_ref() {
                     BaseValue::Unresolvable => {
-                        // TODO: If V.[[Strict]] is true, throw a ReferenceError exception.
+                        // a. If V.[[Strict]] is true, throw a ReferenceError exception.
+                        if reference_record.strict {
+                            return self.throw_error("ReferenceError", format!("{} is not defined", Interpreter::referenced_name_to_string(&reference_record.referenced_name)));
+                        }
+
                         //     b. Let globalObj be GetGlobalObject().
                         let global_object = self.global_object();
 
@@ -1254,21 +4205,44 @@ impl Interpreter {
                         return CompletionRecord { type_: CompletionRecordType::Normal, value: Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))), target: None }
                     },
                     _ => {
-                        // TODO: 3. If IsPropertyReference(V) is true, then
+                        // 3. If IsPropertyReference(V) is true, then
                         if Interpreter::is_property_reference(&reference_record) {
-                            todo!();
-                            // 1. Let baseObj be ? ToObject(V.[[Base]]).
-                            //     b. If IsPrivateReference(V) is true, then
-                            //
+                            let base = match reference_record.base.as_ref() {
+                                BaseValue::JSValue(base) => Rc::clone(base),
+                                _ => { unreachable!() }
+                            };
+
+                            // a. Let baseObj be ? ToObject(V.[[Base]]).
+                            // TODO: Primitives should be wrapped in their wrapper objects, setting a property on one does nothing for now
+                            let base_object = match &*base.borrow() {
+                                JSValue::Object(object) => Rc::clone(object),
+                                JSValue::Undefined | JSValue::Null => {
+                                    return self.throw_type_error(format!("Cannot set properties of {} (setting '{}')", Interpreter::type_name(&base.borrow()), Interpreter::referenced_name_to_string(&reference_record.referenced_name)));
+                                },
+                                _ => {
+                                    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+                                }
+                            };
+
+                            //     b. TODO: If IsPrivateReference(V) is true, then
                             // i. Return ? PrivateSet(baseObj, V.[[ReferencedName]], W).
-                            //
                             //     c. If V.[[ReferencedName]] is not a property key, then
-                            //
                             // i. Set V.[[ReferencedName]] to ? ToPropertyKey(V.[[ReferencedName]]).
-                            //
+                            let property_key = match &reference_record.referenced_name {
+                                JSValue::String(name) => PropertyKey::String(name.clone()),
+                                _ => { todo!("Support Symbols Properly") }
+                            };
+
                             //     d. Let succeeded be ? baseObj.[[Set]](V.[[ReferencedName]], W, GetThisValue(V)).
+                            let succeeded = completion!(self.set_property(&base_object, property_key, value.clone(), base));
+
                             //     e. If succeeded is false and V.[[Strict]] is true, throw a TypeError exception.
+                            if reference_record.strict && matches!(&*succeeded.value, ReferenceRecordOrJsValue::JSValue(succeeded) if matches!(*succeeded.borrow(), JSValue::Boolean(false))) {
+                                return self.throw_type_error(format!("Cannot assign to read only property '{}' of object", Interpreter::referenced_name_to_string(&reference_record.referenced_name)));
+                            }
+
                             //     f. Return unused.
+                            return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
                         } else {
                             //4. Else,
 
@@ -1278,16 +4252,22 @@ impl Interpreter {
                             // b. Assert: base is an Environment Record.
                             match &**base {
                                 BaseValue::EnvironmentRecord(env_record) => {
+                                    let referenced_name = match &reference_record.referenced_name {
+                                        JSValue::String(referenced_name) => Atom::from(referenced_name),
+                                        _ => { unreachable!() }
+                                    };
+
+                                    //c. Return ? base.SetMutableBinding(V.[[ReferencedName]], W, V.[[Strict]]) (see 9.1).
                                     match &env_record.borrow().environment_record_type {
                                         EnvironmentRecordType::DeclarativeEnvironmentRecord(dec_record) => {
-                                            match &reference_record.referenced_name {
-                                                JSValue::String(referenced_name) => {
-                                                    //c. Return ? base.SetMutableBinding(V.[[ReferencedName]], W, V.[[Strict]]) (see 9.1).
-                                                    return completion!(dec_record.borrow_mut().set_mutable_binding(referenced_name.to_string(), value.clone(), false));
-                                                },
-                                                _ => { unreachable!() }
-                                            }
-                                        }
+                                            return completion!(dec_record.borrow_mut().set_mutable_binding(referenced_name, value.clone(), reference_record.strict));
+                                        },
+                                        EnvironmentRecordType::ObjectEnvironmentRecord(obj_record) => {
+                                            return completion!(obj_record.borrow().set_mutable_binding(referenced_name, value.clone(), reference_record.strict));
+                                        },
+                                        EnvironmentRecordType::GlobalEnvironmentRecord(global_record) => {
+                                            return completion!(global_record.borrow().set_mutable_binding(referenced_name, value.clone(), reference_record.strict));
+                                        },
                                         _ => { unreachable!() }
                                     }
                                 },
@@ -1302,7 +4282,7 @@ impl Interpreter {
     }
 
     // https://tc39.es/ecma262/#sec-getvalue
-    fn get_value(reference_record_or_js_value: Rc<ReferenceRecordOrJsValue>) -> CompletionRecord {
+    fn get_value(&mut self, reference_record_or_js_value: Rc<ReferenceRecordOrJsValue>) -> CompletionRecord {
         match &*reference_record_or_js_value {
             // If V is not a Reference Record, return V.
             ReferenceRecordOrJsValue::JSValue(value) => {
@@ -1312,8 +4292,7 @@ impl Interpreter {
                 //     2. If IsUnresolvableReference(V) is true, throw a ReferenceError exception.
                 match reference_record.base.as_ref() {
                     BaseValue::Unresolvable => {
-                        // FIXME: The value here in throw and everywhere else we throw should be a Error object and and not just the base object
-                        return CompletionRecord { type_: CompletionRecordType::Throw, value: Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Object(JSObject { values: HashMap::new(), prototype: None, extensible: false }))))), target: None }
+                        return self.throw_error("ReferenceError", format!("{} is not defined", Interpreter::referenced_name_to_string(&reference_record.referenced_name)));
                     },
 
                     // 4. Else,
@@ -1329,7 +4308,7 @@ impl Interpreter {
                                 match &reference_record.referenced_name {
                                     JSValue::String(value) => {
                                         // c. Return ? base.GetBindingValue(V.[[ReferencedName]], V.[[Strict]]) (see 9.1).
-                                        let binding_value =  completion!(dec_record.borrow().get_binding_value(value.to_string(), false));
+                                        let binding_value =  completion!(dec_record.borrow().get_binding_value(Atom::from(value), false));
                                         return CompletionRecord { type_: CompletionRecordType::Normal, value: binding_value.value, target: None }
                                     },
                                     _ => { unreachable!() }
@@ -1341,7 +4320,7 @@ impl Interpreter {
                                 match &reference_record.referenced_name {
                                     JSValue::String(value) => {
                                         // c. Return ? base.GetBindingValue(V.[[ReferencedName]], V.[[Strict]]) (see 9.1).
-                                        let binding_value =  completion!(obj_record.borrow().get_binding_value(value.to_string(), false));
+                                        let binding_value =  completion!(obj_record.borrow().get_binding_value(Atom::from(value), false));
                                         return CompletionRecord { type_: CompletionRecordType::Normal, value: binding_value.value, target: None }
                                     },
                                     _ => { unreachable!() }
@@ -1353,7 +4332,7 @@ impl Interpreter {
                                 match &reference_record.referenced_name {
                                     JSValue::String(value) => {
                                         // c. Return ? base.GetBindingValue(V.[[ReferencedName]], V.[[Strict]]) (see 9.1).
-                                        let binding_value =  completion!(global_record.borrow().get_binding_value(value.to_string(), false));
+                                        let binding_value =  completion!(global_record.borrow().get_binding_value(Atom::from(value), false));
                                         return CompletionRecord { type_: CompletionRecordType::Normal, value: binding_value.value, target: None }
                                     },
                                     _ => { unreachable!() }
@@ -1366,18 +4345,55 @@ impl Interpreter {
                             // Maybe we can use something a bit more dynamic?
                         }
                     },
-                    _ => {
-                        todo!();
-                        //     3. If IsPropertyReference(V) is true, then
-                        //  if Interpreter::is_property_reference(&reference_record) {
-
+                    //     3. If IsPropertyReference(V) is true, then
+                    BaseValue::JSValue(base) => {
                         // a. Let baseObj be ? ToObject(V.[[Base]]).
-                        //     b. If IsPrivateReference(V) is true, then
+                        // TODO: Primitives should be wrapped in their wrapper objects, only the length of a string is supported for now
+                        let base_object = match &*base.borrow() {
+                            JSValue::Object(object) => Rc::clone(object),
+                            JSValue::String(string) => {
+                                if matches!(&reference_record.referenced_name, JSValue::String(name) if name == "length") {
+                                    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Numeric(string.encode_utf16().count() as Number))))));
+                                }
+
+                                // https://tc39.es/ecma262/#sec-stringgetownproperty
+                                // Each code unit of a string is exposed as an array index property
+                                if let JSValue::String(name) = &reference_record.referenced_name {
+                                    if let Some(index) = Interpreter::array_index(name) {
+                                        if let Some(code_unit) = string.encode_utf16().nth(index as usize) {
+                                            return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::String(String::from_utf16_lossy(&[code_unit])))))));
+                                        }
+                                    }
+                                }
+
+                                // ToObject would create a String object whose prototype is %String.prototype%,
+                                // the primitive itself is still passed along as the this value
+                                match self.intrinsic("String.prototype") {
+                                    Some(string_prototype) => string_prototype,
+                                    None => {
+                                        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+                                    }
+                                }
+                            },
+                            JSValue::Undefined | JSValue::Null => {
+                                return self.throw_type_error(format!("Cannot read properties of {} (reading '{}')", Interpreter::type_name(&base.borrow()), Interpreter::referenced_name_to_string(&reference_record.referenced_name)));
+                            },
+                            _ => {
+                                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Undefined)))));
+                            }
+                        };
+
+                        //     b. TODO: If IsPrivateReference(V) is true, then
                         // i. Return ? PrivateGet(baseObj, V.[[ReferencedName]]).
                         //     c. If V.[[ReferencedName]] is not a property key, then
                         // i. Set V.[[ReferencedName]] to ? ToPropertyKey(V.[[ReerencedName]]).
+                        let property_key = match &reference_record.referenced_name {
+                            JSValue::String(name) => PropertyKey::String(name.clone()),
+                            _ => { todo!("Support Symbols Properly") }
+                        };
+
                         //     d. Return ? baseObj.[[Get]](V.[[ReferencedName]], GetThisValue(V)).
-                        // }
+                        return completion!(self.get(&base_object, &property_key, Rc::clone(base)));
                     },
                 }
             },
@@ -1385,6 +4401,105 @@ impl Interpreter {
         }
     }
 
+    // https://tc39.es/ecma262/#sec-topropertykey
+    // TODO: Symbols can not be property keys yet, so the key is always returned as a String
+    pub(crate) fn to_property_key(&mut self, argument: Rc<RefCell<JSValue>>) -> CompletionRecord {
+        // 1. Let key be ? ToPrimitive(argument, string).
+        // TODO: ToPrimitive is not implemented for objects, they get the result of Object.prototype.toString
+        let key = match &*argument.borrow() {
+            JSValue::Object(_) => Rc::new(RefCell::new(JSValue::String(String::from("[object Object]")))),
+            JSValue::Symbol(_) => {
+                return self.throw_type_error(String::from("Symbol property keys are not supported"));
+            },
+            _ => Rc::clone(&argument)
+        };
+
+        // 2. If key is a Symbol, then
+        //     a. Return key.
+        // 3. Return ! ToString(key).
+        return Interpreter::to_string(key);
+    }
+
+    // https://tc39.es/ecma262/#sec-touint32
+    pub(crate) fn to_uint32(number: Number) -> u32 {
+        // 2. If number is not finite or number is either +0𝔽 or -0𝔽, return +0𝔽.
+        if !number.is_finite() {
+            return 0;
+        }
+
+        // 3. Let int be truncate(ℝ(number)).
+        // 4. Let int32bit be int modulo 2^32.
+        // 5. Return 𝔽(int32bit).
+        return number.trunc().rem_euclid(4294967296.0) as u32;
+    }
+
+    // https://tc39.es/ecma262/#sec-ordinarytoprimitive
+    pub(crate) fn ordinary_to_primitive(&mut self, object: &Rc<RefCell<JSObject>>, hint: &str) -> CompletionRecord {
+        // 1. If hint is string, then
+        //     a. Let methodNames be « "toString", "valueOf" ».
+        // 2. Else,
+        //     a. Let methodNames be « "valueOf", "toString" ».
+        let method_names = if hint == "string" { ["toString", "valueOf"] } else { ["valueOf", "toString"] };
+
+        // 3. For each element name of methodNames, do
+        for name in method_names {
+            // a. Let method be ? Get(O, name).
+            let method = match &*completion!(self.get(object, &PropertyKey::String(name.to_string()), Rc::new(RefCell::new(JSValue::Object(Rc::clone(object)))))).value {
+                ReferenceRecordOrJsValue::JSValue(method) => Rc::clone(method),
+                _ => { unreachable!() }
+            };
+
+            // b. If IsCallable(method) is true, then
+            if Interpreter::is_callable(&method.borrow()) {
+                // i. Let result be ? Call(method, O).
+                let result = completion!(self.call(method, Rc::new(RefCell::new(JSValue::Object(Rc::clone(object)))), Vec::new()));
+
+                // ii. If result is not an Object, return result.
+                if !matches!(&*result.value, ReferenceRecordOrJsValue::JSValue(value) if matches!(*value.borrow(), JSValue::Object(_))) {
+                    return result;
+                }
+            }
+        }
+
+        // 4. Throw a TypeError exception.
+        return self.throw_type_error(String::from("Cannot convert object to primitive value"));
+    }
+
+    // https://tc39.es/ecma262/#sec-tostring
+    // Unlike to_string, objects are converted to a primitive by calling their toString or valueOf methods
+    pub(crate) fn to_string_coercing_objects(&mut self, value: Rc<RefCell<JSValue>>) -> CompletionRecord {
+        let object = match &*value.borrow() {
+            JSValue::Object(object) => Rc::clone(object),
+            JSValue::Symbol(_) => {
+                return self.throw_type_error(String::from("Cannot convert a Symbol value to a string"));
+            },
+            _ => {
+                return Interpreter::to_string(Rc::clone(&value));
+            }
+        };
+
+        // 10. Let primValue be ? ToPrimitive(argument, string).
+        let primitive_value = match &*completion!(self.ordinary_to_primitive(&object, "string")).value {
+            ReferenceRecordOrJsValue::JSValue(primitive_value) => Rc::clone(primitive_value),
+            _ => { unreachable!() }
+        };
+
+        // 11. Assert: primValue is not an Object.
+        // 12. Return ? ToString(primValue).
+        return self.to_string_coercing_objects(primitive_value);
+    }
+
+    // https://tc39.es/ecma262/#array-index
+    // An array index is an integer index whose numeric value is in the inclusive interval from +0 to 2^32 - 2
+    pub(crate) fn array_index(property_key: &str) -> Option<u32> {
+        // Only the canonical numeric string is an index, so "01" and "+1" are not
+        let index = property_key.parse::<u32>().ok()?;
+        if index == u32::MAX || index.to_string() != property_key {
+            return None;
+        }
+        return Some(index);
+    }
+
     // https://tc39.es/ecma262/#sec-ispropertyreference
     fn is_property_reference(reference_record: &ReferenceRecord) -> bool {
         match *reference_record.base {
@@ -1402,27 +4517,29 @@ impl Interpreter {
 
     // https://tc39.es/ecma262/#sec-resolvebinding
     //TODO: environment can also be 'undefined' type
-    fn resolve_binding(&self, name: String, environment: Option<Rc<RefCell<EnvironmentRecord>>>) -> CompletionRecord {
+    fn resolve_binding(&self, name: Atom, environment: Option<Rc<RefCell<EnvironmentRecord>>>) -> CompletionRecord {
         match environment {
             // 1. If env is not present or env is undefined, then
             None => {
                 // a. Set env to the running execution context's LexicalEnvironment.
                 let env = Rc::clone(&self.running_execution_context().lexical_environment_record);
                 // 2. Assert: env is an Environment Record.
-                // 3. TODO: Let strict be IsStrict(the syntactic production that is being evaluated).
+                // 3. Let strict be IsStrict(the syntactic production that is being evaluated).
+                let strict = self.running_execution_context().strict;
                 // 4. Return ? GetIdentifierReference(env, name, strict).
-                return completion!(Interpreter::get_identifier_reference(name.clone(), &Option::from(env.clone()), false));
+                return completion!(Interpreter::get_identifier_reference(name.clone(), &Option::from(env.clone()), strict));
             }
             Some(env_record) => {
-                // 3. TODO: Let strict be IsStrict(the syntactic production that is being evaluated).
+                // 3. Let strict be IsStrict(the syntactic production that is being evaluated).
+                let strict = self.running_execution_context().strict;
                 // 4. Return ? GetIdentifierReference(env, name, strict).
-                return  completion!(Interpreter::get_identifier_reference(name.clone(), &Option::from(env_record.clone()), false));
+                return  completion!(Interpreter::get_identifier_reference(name.clone(), &Option::from(env_record.clone()), strict));
             },
         }
     }
 
     // https://tc39.es/ecma262/#sec-getidentifierreference
-    fn get_identifier_reference(name: String, environment: &Option<Rc<RefCell<EnvironmentRecord>>>, strict: bool) -> CompletionRecord {
+    fn get_identifier_reference(name: Atom, environment: &Option<Rc<RefCell<EnvironmentRecord>>>, strict: bool) -> CompletionRecord {
         match environment {
             // 1. If env is null, then
             None => {
@@ -1432,8 +4549,8 @@ impl Interpreter {
                     value: Rc::new(ReferenceRecordOrJsValue::ReferenceRecord(
                         ReferenceRecord {
                             base: Rc::new(BaseValue::Unresolvable),
-                            referenced_name: JSValue::String(name),
-                            strict: false, // TODO: Should be passed in
+                            referenced_name: JSValue::String(name.to_string()),
+                            strict,
                             this_value: None,
                         }
                     )),
@@ -1456,8 +4573,8 @@ impl Interpreter {
                                         value: Rc::new(ReferenceRecordOrJsValue::ReferenceRecord(
                                             ReferenceRecord {
                                                 base: Rc::new(BaseValue::EnvironmentRecord(Rc::clone(env_record))),
-                                                referenced_name: JSValue::String(name),
-                                                strict: false,
+                                                referenced_name: JSValue::String(name.to_string()),
+                                                strict,
                                                 this_value: None,
                                             }
                                         )),
@@ -1482,11 +4599,16 @@ impl Interpreter {
     }
 
     pub fn run_file(&mut self, path: String) {
-        let file = File::open(path).expect("File could not opened!");
+        let file = File::open(&path).expect("File could not opened!");
         let mut reader = BufReader::new(file);
         let mut source = String::new();
         reader.read_to_string(&mut source).expect("File could not be read!");
-        self.run(source, ExecutionMode::Script);
+        self.run_source(source, &path);
+    }
+
+    // Runs a script that is not read from a file, e.g. one piped to stdin, the name is what stack traces call the script
+    pub fn run_source(&mut self, source: String, script_name: &str) {
+        self.run(source, script_name, ExecutionMode::Script);
 
         if self.had_error {
             std::process::exit(65);
@@ -1494,27 +4616,217 @@ impl Interpreter {
     }
 
     pub fn run_prompt(&mut self) {
+        let mut line_editor = LineEditor::new();
+
+        // Lines are collected until they form a complete input, such as a function declaration written over several lines
+        let mut input = String::new();
         loop {
-            print!("> ");
-            std::io::stdout().flush().unwrap();
-            let mut line = String::new();
-            std::io::stdin().read_line(&mut line).expect("Failed to read line");
-            self.run(line, ExecutionMode::Shell);
+            let prompt = if input.is_empty() { "> " } else { "... " };
+            let line = match line_editor.read_line(prompt) {
+                ReadLine::Line(line) => line,
+                ReadLine::Interrupted => {
+                    input.clear();
+                    continue;
+                },
+                // End of input, e.g. Ctrl-D or a piped file
+                ReadLine::Eof => break
+            };
+            line_editor.add_history(&line);
+
+            // Meta-commands start with a colon and are only recognised at the start of an input
+            if input.is_empty() && line.trim_start().starts_with(':') {
+                if !self.run_command(line.trim()) {
+                    break;
+                }
+                continue;
+            }
+
+            input.push_str(&line);
+            input.push('\n');
+            if Scanner::is_incomplete(input.clone()) {
+                continue;
+            }
+
+            self.run(std::mem::take(&mut input), "repl", ExecutionMode::Shell);
             self.had_error = false;
+
+            // Timers that are already due run before the next input, the REPL does not wait for the others
+            self.run_event_loop(Some(Instant::now()));
+        }
+    }
+
+    // Runs a REPL meta-command, returns false when the REPL should exit
+    fn run_command(&mut self, command: &str) -> bool {
+        let (name, argument) = match command.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, argument.trim()),
+            None => (command, "")
+        };
+
+        match name {
+            ":help" => {
+                println!(":tokens <source>  Print the tokens the scanner produces for the source");
+                println!(":ast <source>     Print the syntax tree the parser produces for the source");
+                println!(":env              Print the bindings declared in the global environment");
+                println!(":help             Print this list of commands");
+                println!(":quit             Exit the REPL");
+            },
+            ":tokens" if !argument.is_empty() => {
+                let mut scanner = Scanner::new(argument.to_string());
+                for token in scanner.scan_tokens() {
+                    println!("{}", token.to_string());
+                }
+            },
+            ":ast" if !argument.is_empty() => {
+                match Interpreter::parse_script(argument.to_string(), false) {
+                    Ok(script) => {
+                        for statement in script.statements.iter() {
+                            println!("{}", statement.accept(&mut ASTPrettyPrinter));
+                        }
+                    },
+                    Err(errors) => {
+                        for error in errors {
+                            println!("{}", error);
+                        }
+                    }
+                }
+            },
+            ":tokens" | ":ast" => {
+                println!("Usage: {} <source>", name);
+            },
+            ":env" => {
+                self.print_global_environment();
+            },
+            ":quit" | ":exit" => {
+                return false;
+            },
+            _ => {
+                println!("Unknown command {}, type :help for a list of commands", name);
+            }
+        }
+
+        return true;
+    }
+
+    // Prints the var and function declarations bound on the global object, then the let and const declarations
+    fn print_global_environment(&self) {
+        let global_object = self.global_object();
+        let environment = self.global_environment();
+        let global_record = match &environment.borrow().environment_record_type {
+            EnvironmentRecordType::GlobalEnvironmentRecord(global_record) => Rc::clone(global_record),
+            _ => { unreachable!() }
+        };
+        let global_record = global_record.borrow();
+
+        for name in global_record.var_names.borrow().iter() {
+            if let Some(property) = global_object.borrow().own_property(&PropertyKey::String(name.to_string())) {
+                if let PropertyType::DataProperty(data_property) = &*property {
+                    println!("var {} = {}", name, inspect_value(&data_property.value.borrow()));
+                }
+            }
+        }
+
+        // Bindings are kept in a HashMap, so they are sorted to print them in the same order every time
+        let declarative_record = global_record.declarative_environment_record.borrow();
+        let mut names: Vec<&Atom> = declarative_record.variable_bindings.keys().collect();
+        names.sort();
+        for name in names {
+            let (kind, value) = match &declarative_record.variable_bindings[name] {
+                Binding::MutableBinding(value) => ("let", value),
+                Binding::ImmutableBinding { value, .. } => ("const", value)
+            };
+            match value {
+                Some(value) => println!("{} {} = {}", kind, name, inspect_value(&value.borrow())),
+                None => println!("{} {} <uninitialized>", kind, name)
+            }
+        }
+    }
+
+    // Runs the tasks scripts have queued, such as timers, until there are none left or the deadline is reached
+    pub fn run_event_loop(&mut self, deadline: Option<Instant>) {
+        event_loop::run(self, deadline);
+    }
+
+    // Frees the cycles of objects script can no longer reach, and returns how many objects were unlinked
+    pub fn collect_garbage(&mut self) -> usize {
+        return self.heap.collect_cycles();
+    }
+
+    // Runs the cycle collector once enough objects were allocated since it last ran
+    pub(crate) fn collect_garbage_if_needed(&mut self) {
+        if self.heap.should_collect() {
+            self.heap.collect_cycles();
+        }
+    }
+
+    // Runs the text of a script element against the global environment shared by every script in the document
+    // External scripts are named by their URL in stack traces, the others are numbered in the order they run
+    pub fn run_script(&mut self, source: String, url: Option<&Url>) {
+        let script_name = match url {
+            Some(url) => url.href(),
+            None => {
+                self.inline_script_count += 1;
+                format!("inline-script-{}", self.inline_script_count)
+            }
+        };
+        self.run(source, &script_name, ExecutionMode::Document);
+        self.had_error = false;
+
+        // https://html.spec.whatwg.org/multipage/webappapis.html#clean-up-after-running-script
+        // 3. If the JavaScript execution context stack is now empty, perform a microtask checkpoint.
+        event_loop::perform_a_microtask_checkpoint(self);
+        self.collect_garbage_if_needed();
+    }
+
+    fn run(&mut self, source: String, script_name: &str, execution_mode: ExecutionMode) {
+        match Interpreter::parse_script(source, self.optimize_scripts) {
+            Ok(script) => {
+                self.reset_script_budget();
+                // The script is evaluated in the execution context at the bottom of the stack, which every script shares
+                let script_context = &mut self.execution_contexts[0];
+                script_context.script_name = Rc::from(script_name);
+                script_context.strict = script.strict;
+                self.interpret(script, execution_mode);
+            },
+            // https://html.spec.whatwg.org/multipage/webappapis.html#creating-a-classic-script
+            // A script with early errors is not evaluated at all, each error is reported instead
+            Err(errors) => {
+                for error in errors {
+                    self.print(LogLevel::Error, &format!("Uncaught SyntaxError: {} ({}:{}:{})", error.message, script_name, error.line, error.column));
+                }
+                self.had_error = true;
+            }
         }
     }
 
-    fn run(&mut self, source: String, execution_mode: ExecutionMode) {
+    // https://tc39.es/ecma262/#sec-parse-script
+    // Scans and parses the source of a script, and folds the parts of the syntax tree that do not depend on anything at run time unless optimize is false
+    // The errors of both the scanner and the parser are returned in the order they appear in the source
+    pub fn parse_script(source: String, optimize: bool) -> Result<Script, Vec<SyntaxError>> {
         let mut scanner = Scanner::new(source);
         let tokens = scanner.scan_tokens().clone();
+        let mut errors = scanner.take_errors();
 
-        for token in tokens.iter() {
-            println!("{}", token.to_string());
+        let mut parser = Parser::new(tokens);
+        match parser.parse() {
+            Ok(mut script) if errors.is_empty() => {
+                if optimize {
+                    optimizer::optimize(&mut script.statements);
+                }
+                return Ok(script);
+            },
+            Ok(_) => {},
+            Err(parser_errors) => errors.extend(parser_errors),
         }
 
-        let mut parser = Parser::new(tokens);
-        let statements = parser.parse();
-        self.interpret(statements, execution_mode);
+        errors.sort_by_key(|error| (error.line, error.column));
+        return Err(errors);
+    }
+
+    // https://html.spec.whatwg.org/multipage/webappapis.html#report-an-exception
+    // Exceptions nothing caught are printed, the script or callback that threw them has already stopped
+    pub(crate) fn report_exception(&mut self, exception: &Rc<RefCell<JSValue>>) {
+        let message = format!("Uncaught {}", format_error(&exception.borrow()));
+        self.print(LogLevel::Error, &message);
     }
 
     fn error(line: usize, message: String) {
@@ -1527,42 +4839,91 @@ impl Interpreter {
     }
 
     fn execute(&mut self, statement: &Statement) -> CompletionRecord {
-        statement.accept(self)
+        if let Some(completion_record) = self.check_script_limits() {
+            return completion_record;
+        }
+
+        let completion_record = statement.accept(self);
+
+        // Errors thrown by environment records are completed before anything can catch them, while the stack is still the one they were thrown from
+        if let (CompletionRecordType::Throw, ReferenceRecordOrJsValue::JSValue(value)) = (&completion_record.type_, &*completion_record.value) {
+            if let JSValue::Object(error) = &*value.borrow() {
+                complete_error_object(self, error);
+            }
+        }
+
+        return completion_record;
     }
 
     // https://tc39.es/ecma262/#sec-evaluation
     // https://tc39.es/ecma262/#sec-completion-record-specification-type
     fn evaluate(&mut self, expression_statement: &ExpressionStatement) -> CompletionRecord {
+        if let Some(completion_record) = self.check_script_limits() {
+            return completion_record;
+        }
+
         expression_statement.accept(self)
     }
 
-    fn interpret(&mut self, statements: Vec<Statement>, execution_mode: ExecutionMode) {
+    // https://tc39.es/ecma262/#sec-runtime-semantics-scriptevaluation
+    fn interpret(&mut self, script: Script, execution_mode: ExecutionMode) {
+        let statements = script.statements;
+
+        // 13. Let result be Completion(GlobalDeclarationInstantiation(script, globalEnv)).
+        // Function declarations and var names are hoisted before any statement runs
+        let result = self.global_declaration_instantiation(&statements);
+        if let CompletionRecordType::Throw = result.type_ {
+            if let ReferenceRecordOrJsValue::JSValue(value) = &*result.value {
+                self.report_exception(value);
+            }
+            if let ExecutionMode::Script = execution_mode {
+                exit(1);
+            }
+            return;
+        }
+
+        // 14. If result is a normal completion, then
+        //        a. Set result to Completion(Evaluation of script).
         for statement in statements.iter() {
             let result = self.execute(statement);
             match result.type_ {
                 CompletionRecordType::Normal => {
-                    let mut pretty_printer = ASTPrettyPrinter;
-                    let expression_ast = statement.accept(&mut pretty_printer);
-                    println!("Parsed expression {}", expression_ast);
-                    println!("{:?}", result);
+                    // The REPL shows the value of each statement
+                    if let ExecutionMode::Shell = execution_mode {
+                        let value = self.get_value(result.value.clone());
+                        match (&value.type_, &*value.value) {
+                            (CompletionRecordType::Normal, ReferenceRecordOrJsValue::JSValue(value)) => {
+                                println!("{}", inspect_value(&value.borrow()));
+                            },
+                            (CompletionRecordType::Throw, ReferenceRecordOrJsValue::JSValue(value)) => {
+                                self.report_exception(value);
+                            },
+                            _ => {}
+                        }
+                    }
                 },
                 CompletionRecordType::Throw => {
-                    println!("Uncaught {:?}", result.value);
+                    let message = match &*result.value {
+                        ReferenceRecordOrJsValue::JSValue(value) => format_error(&value.borrow()),
+                        value => format!("{:?}", value)
+                    };
+                    self.print(LogLevel::Error, &format!("Uncaught {}", message));
                     match execution_mode {
                         ExecutionMode::Script => {
                             exit(1);
                         }
-                        ExecutionMode::Shell => {},
+                        ExecutionMode::Shell | ExecutionMode::Document => {},
                     }
                 },
-                _ => { unimplemented!() }
+                // A return outside of a function has already been reported as a SyntaxError by the parser
+                _ => {}
             }
         }
     }
 
     // https://tc39.es/ecma262/#sec-tonumber
     // TODO: Return a normal completion or throw a completion
-    fn to_number(value: Rc<RefCell<JSValue>>) -> CompletionRecord {
+    pub(crate) fn to_number(value: Rc<RefCell<JSValue>>) -> CompletionRecord {
         match value.borrow().deref() {
             // 1. If argument is a Number, return argument.
             JSValue::Numeric(val) => {
@@ -1575,9 +4936,7 @@ impl Interpreter {
             // 3. If argument is undefined, return NaN.
             // TODO: Support undefined as a global object
             JSValue::Undefined => {
-                // TODO: Implement NaN as a global object and not a string
-                // https://tc39.es/ecma262/#sec-value-properties-of-the-global-object-nan
-                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::String("NaN".to_string()))))))
+                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Numeric(Number::NAN))))))
             },
             // 4. If argument is either null or false, return +0𝔽.
             JSValue::Null | JSValue::Boolean(false) => {
@@ -1589,7 +4948,7 @@ impl Interpreter {
             }
             //6. If argument is a String, return StringToNumber(argument).
             JSValue::String(value) => {
-                todo!();
+                return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::new(RefCell::new(JSValue::Numeric(Interpreter::string_to_number(value)))))));
             }
             // 7. Assert: argument is an Object.
             JSValue::Object(value) => {
@@ -1602,6 +4961,41 @@ impl Interpreter {
     }
 
 
+    // https://tc39.es/ecma262/#sec-stringtonumber
+    fn string_to_number(string: &str) -> Number {
+        // 1. Let literal be ParseText(str, StringNumericLiteral).
+        // 2. If literal is a List of errors, return NaN.
+        // 3. Return the StringNumericValue of literal.
+        let literal = string.trim();
+
+        // https://tc39.es/ecma262/#prod-StrWhiteSpace
+        if literal.is_empty() {
+            return 0.0;
+        }
+
+        // https://tc39.es/ecma262/#prod-NonDecimalIntegerLiteral
+        for (prefix, radix) in [("0x", 16), ("0X", 16), ("0o", 8), ("0O", 8), ("0b", 2), ("0B", 2)] {
+            if let Some(digits) = literal.strip_prefix(prefix) {
+                return match u64::from_str_radix(digits, radix) {
+                    Ok(value) => value as Number,
+                    Err(_) => Number::NAN
+                };
+            }
+        }
+
+        // https://tc39.es/ecma262/#prod-StrUnsignedDecimalLiteral
+        // Rust also accepts spellings such as "inf" and "nan" which are not valid here
+        let unsigned_literal = literal.strip_prefix(['+', '-']).unwrap_or(literal);
+        if unsigned_literal == "Infinity" {
+            return if literal.starts_with('-') { Number::NEG_INFINITY } else { Number::INFINITY };
+        }
+        if !unsigned_literal.starts_with(|character: char| character.is_ascii_digit() || character == '.') {
+            return Number::NAN;
+        }
+
+        return literal.parse::<Number>().unwrap_or(Number::NAN);
+    }
+
     // https://tc39.es/ecma262/#sec-toprimitive
     fn to_primitive(value: Rc<RefCell<JSValue>>, preferred_type: Option<JSValue>) -> CompletionRecord {
         match &*value.borrow() {
@@ -1633,7 +5027,7 @@ impl Interpreter {
     }
 
     // https://tc39.es/ecma262/#sec-toboolean
-    fn to_boolean(value: Rc<RefCell<JSValue>>) -> Rc<RefCell<JSValue>> {
+    pub(crate) fn to_boolean(value: Rc<RefCell<JSValue>>) -> Rc<RefCell<JSValue>> {
         match value.borrow().deref() {
             //1. If argument is a Boolean, return argument.
             JSValue::Boolean(value) => {
@@ -1646,9 +5040,10 @@ impl Interpreter {
             JSValue::String(ref s) if s.is_empty() => {
                 return Rc::new(RefCell::new(JSValue::Boolean(false)));
             },
-            // 3. If argument is an Object and argument has an [[IsHTMLDDA]] internal slot, return false.
-            JSValue::Object(value) => {
-                todo!();
+            // 3. TODO: If argument is an Object and argument has an [[IsHTMLDDA]] internal slot, return false.
+            // 4. Return true.
+            JSValue::Object(_) => {
+                return Rc::new(RefCell::new(JSValue::Boolean(true)));
             }
             // Handle other cases
             _ => {
@@ -1715,15 +5110,15 @@ impl Interpreter {
                         // c. If lPrim is a String or rPrim is a String, then
                         ReferenceRecordOrJsValue::JSValue(right_val) if matches!(&*right_val.borrow(), JSValue::String(_)) => {
                             // i. Let lStr be ? ToString(lPrim).
-                            let left_string = completion!(Interpreter::to_string(right_val.clone()));
-                            // ii. Let rStr be ? ToString(rPrim).
-                            let right_string = completion!(Interpreter::to_string(match right_primitive.value.deref() {
-                                 ReferenceRecordOrJsValue::JSValue(val) => val.clone(),
-                                    _ => { unreachable!("Encountered a reference record") }
+                            let left_string = completion!(Interpreter::to_string(match left_primitive.value.deref() {
+                                ReferenceRecordOrJsValue::JSValue(val) => val.clone(),
+                                _ => { unreachable!("Encountered a reference record") }
                             }));
+                            // ii. Let rStr be ? ToString(rPrim).
+                            let right_string = completion!(Interpreter::to_string(right_val.clone()));
 
                             // Extract the string values and return the concatenation result
-                            let left_str = match right_string.value.deref() {
+                            let left_str = match left_string.value.deref() {
                                 ReferenceRecordOrJsValue::JSValue(val) => {
                                     if let JSValue::String(s) = &*val.borrow() {
                                         s.clone()
@@ -1966,9 +5361,69 @@ impl Interpreter {
     }
 
     // https://tc39.es/ecma262/#sec-numeric-types-number-tostring
-    // TODO: Implement this to spec, for now we'll just use Rust's default implementation of to_string on numbers
-    fn number_to_string(value: Number) -> String {
-        return value.to_string();
+    // TODO: Only radix 10 is supported
+    pub(crate) fn number_to_string(value: Number) -> String {
+        // 1. If x is NaN, return "NaN".
+        if value.is_nan() {
+            return String::from("NaN");
+        }
+
+        // 2. If x is either +0𝔽 or -0𝔽, return "0".
+        if value == 0.0 {
+            return String::from("0");
+        }
+
+        // 3. If x < -0𝔽, return the string-concatenation of "-" and Number::toString(-x, radix).
+        if value < 0.0 {
+            return format!("-{}", Interpreter::number_to_string(-value));
+        }
+
+        // 4. If x is +∞𝔽, return "Infinity".
+        if value.is_infinite() {
+            return String::from("Infinity");
+        }
+
+        // 5. Let n, k, and s be integers such that k ≥ 1, radix^(k - 1) ≤ s < radix^k, 𝔽(s × radix^(n - k)) is x, and k is as small as possible.
+        // Rust's exponential formatting gives the shortest digits that round trip, which is the smallest k
+        let exponential = format!("{:e}", value);
+        let (significand, exponent) = exponential.split_once('e').unwrap();
+        let digits: String = significand.chars().filter(|character| *character != '.').collect();
+        let k = digits.len() as i64;
+        let n = exponent.parse::<i64>().unwrap() + 1;
+
+        // 6. If radix ≠ 10 or n is in the inclusive interval from -5 to 21, then
+        if n >= -5 && n <= 21 {
+            // a. If n ≥ k, then
+            if n >= k {
+                // i. Return the string-concatenation of the code units of the k digits of the representation of s and n - k occurrences of the code unit 0x0030 (DIGIT ZERO).
+                return format!("{}{}", digits, "0".repeat((n - k) as usize));
+            }
+
+            // b. Else if n > 0, then
+            if n > 0 {
+                // i. Return the string-concatenation of the most significant n digits of the representation of s, the code unit 0x002E (FULL STOP), and the remaining k - n digits of the representation of s.
+                return format!("{}.{}", &digits[..n as usize], &digits[n as usize..]);
+            }
+
+            // c. Else,
+            // i. Assert: n ≤ 0.
+            // ii. Return the string-concatenation of the code unit 0x0030 (DIGIT ZERO), the code unit 0x002E (FULL STOP), -n occurrences of the code unit 0x0030 (DIGIT ZERO), and the code units of the k digits of the representation of s.
+            return format!("0.{}{}", "0".repeat((-n) as usize), digits);
+        }
+
+        // 7. NOTE: In this case, the input will be represented using scientific E notation, such as 1.2e+3.
+        // 8. Assert: radix is 10.
+        // 9. If n < 0, then let exponentSign be the code unit 0x002D (HYPHEN-MINUS). Otherwise, let exponentSign be the code unit 0x002B (PLUS SIGN).
+        let exponent_sign = if n < 0 { '-' } else { '+' };
+
+        // 10. If k = 1, then
+        if k == 1 {
+            // a. Return the string-concatenation of the code unit of the single digit of s, the code unit 0x0065 (LATIN SMALL LETTER E), exponentSign, and the code units of the decimal representation of abs(n - 1).
+            return format!("{}e{}{}", digits, exponent_sign, (n - 1).abs());
+        }
+
+        // 11. Return the string-concatenation of the code unit of the most significant digit of the decimal representation of s, the code unit 0x002E (FULL STOP), the code units of the remaining k - 1 digits of the decimal representation of s, the code unit 0x0065 (LATIN SMALL LETTER E), exponentSign, and the code units of the decimal representation of abs(n - 1).
+        return format!("{}.{}e{}{}", &digits[..1], &digits[1..], exponent_sign, (n - 1).abs());
     }
 
     // https://tc39.es/ecma262/#sec-tostring
@@ -2058,5 +5513,7 @@ impl Interpreter {
 
 enum ExecutionMode {
     Shell,
-    Script
+    Script,
+    // Scripts run by the HTML parser, an uncaught exception is reported but the document keeps parsing
+    Document
 }
\ No newline at end of file
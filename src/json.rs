@@ -0,0 +1,758 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::interpreter::{argument, completion_value, create_normal_completion, create_value_completion, return_if_abrupt, CompletionRecord, Interpreter, JSObject, JSValue, PropertyKey, PropertyType, ReferenceRecordOrJsValue};
+
+// https://tc39.es/ecma262/#sec-json-object
+// JSON text is parsed straight into the interpreter's values and serialized from them, without going through serde_json,
+// so the objects JSON.parse creates are ordinary objects and arrays of the realm that the reviver and the rest of the script see
+// TODO: There are no Number, String or Boolean wrapper objects, so they are never unwrapped, and the JSON object has no @@toStringTag
+
+// The number of arrays and objects JSON text can be nested in, parsing and serializing are recursive, so deeper values would overflow the stack
+const MAX_NESTING_DEPTH: usize = 512;
+
+pub(crate) fn install_json(interpreter: &mut Interpreter) {
+    // The JSON object is an ordinary object whose [[Prototype]] is %Object.prototype%, it is not a function object
+    let json = interpreter.create_ordinary_object();
+
+    Interpreter::define_builtin_function(&json, "parse", json_parse);
+    Interpreter::define_builtin_function(&json, "stringify", json_stringify);
+
+    interpreter.define_global_property("JSON", Rc::new(RefCell::new(JSValue::Object(json))));
+}
+
+// https://tc39.es/ecma262/#sec-json.parse
+fn json_parse(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let jsonString be ? ToString(text).
+    let json_string = return_if_abrupt!(to_string(interpreter, argument(&arguments, 0)));
+
+    // 2. Let unfiltered be ? ParseJSON(jsonString).
+    let unfiltered = return_if_abrupt!(JSONParser::new(interpreter, &json_string).parse());
+
+    // 3. If IsCallable(reviver) is true, then
+    let reviver = argument(&arguments, 1);
+    if Interpreter::is_callable(&reviver.borrow()) {
+        // a. Let root be OrdinaryObjectCreate(%Object.prototype%).
+        let root = interpreter.create_ordinary_object();
+
+        // b. Let rootName be the empty String.
+        // c. Perform ! CreateDataPropertyOrThrow(root, rootName, unfiltered).
+        Interpreter::create_data_property(&root, "", unfiltered);
+
+        // d. Return ? InternalizeJSONProperty(root, rootName, reviver).
+        let value = return_if_abrupt!(internalize_json_property(interpreter, &root, String::new(), &reviver));
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(value)));
+    }
+
+    // 4. Else,
+    // a. Return unfiltered.
+    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(unfiltered)));
+}
+
+// https://tc39.es/ecma262/#sec-ParseJSON
+// https://www.rfc-editor.org/rfc/rfc8259
+// Parses JSON text, each value is evaluated as the ECMAScript literal it is written as, a later property of an object with the same name overwrites an earlier one
+struct JSONParser<'a> {
+    interpreter: &'a mut Interpreter,
+    text: Vec<char>,
+    position: usize,
+    depth: usize,
+}
+
+impl<'a> JSONParser<'a> {
+    fn new(interpreter: &'a mut Interpreter, text: &str) -> JSONParser<'a> {
+        return JSONParser { interpreter, text: text.chars().collect(), position: 0, depth: 0 };
+    }
+
+    // JSON-text = ws value ws
+    fn parse(&mut self) -> Result<Rc<RefCell<JSValue>>, CompletionRecord> {
+        let value = self.parse_value()?;
+        self.skip_whitespace();
+        // 2. If StringToCodePoints(text) is not a valid JSON text as specified in ECMA-404, throw a SyntaxError exception.
+        if self.position < self.text.len() {
+            return Err(self.unexpected_token());
+        }
+        return Ok(value);
+    }
+
+    // ws = *( %x20 / %x09 / %x0A / %x0D )
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some('\u{0020}' | '\u{0009}' | '\u{000A}' | '\u{000D}')) {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        return self.text.get(self.position).copied();
+    }
+
+    // Consumes character if it is next, after any whitespace
+    fn consume(&mut self, character: char) -> Result<(), CompletionRecord> {
+        self.skip_whitespace();
+        if self.peek() != Some(character) {
+            return Err(self.unexpected_token());
+        }
+        self.position += 1;
+        return Ok(());
+    }
+
+    // value = false / null / true / object / array / number / string
+    fn parse_value(&mut self) -> Result<Rc<RefCell<JSValue>>, CompletionRecord> {
+        self.skip_whitespace();
+        let value = match self.peek() {
+            Some('{') => return self.parse_object(),
+            Some('[') => return self.parse_array(),
+            Some('"') => JSValue::String(self.parse_string()?),
+            Some('-' | '0'..='9') => JSValue::Numeric(self.parse_number()?),
+            Some('t') => { self.parse_literal("true")?; JSValue::Boolean(true) },
+            Some('f') => { self.parse_literal("false")?; JSValue::Boolean(false) },
+            Some('n') => { self.parse_literal("null")?; JSValue::Null },
+            _ => return Err(self.unexpected_token())
+        };
+        return Ok(Rc::new(RefCell::new(value)));
+    }
+
+    fn parse_literal(&mut self, literal: &str) -> Result<(), CompletionRecord> {
+        for character in literal.chars() {
+            if self.peek() != Some(character) {
+                return Err(self.unexpected_token());
+            }
+            self.position += 1;
+        }
+        return Ok(());
+    }
+
+    // object = begin-object [ member *( value-separator member ) ] end-object
+    // member = string name-separator value
+    fn parse_object(&mut self) -> Result<Rc<RefCell<JSValue>>, CompletionRecord> {
+        self.enter_nesting()?;
+        self.consume('{')?;
+        // The object is what an ObjectLiteral evaluates to, OrdinaryObjectCreate(%Object.prototype%) with a data property for each member
+        // A member named "__proto__" is an own property like any other, unlike in an object literal
+        let object = self.interpreter.create_ordinary_object();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.position += 1;
+        } else {
+            loop {
+                self.skip_whitespace();
+                if self.peek() != Some('"') {
+                    return Err(self.unexpected_token());
+                }
+                let name = self.parse_string()?;
+                self.consume(':')?;
+                let value = self.parse_value()?;
+                Interpreter::create_data_property(&object, &name, value);
+
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(',') => self.position += 1,
+                    Some('}') => { self.position += 1; break; },
+                    _ => return Err(self.unexpected_token())
+                }
+            }
+        }
+        self.depth -= 1;
+        return Ok(Rc::new(RefCell::new(JSValue::Object(object))));
+    }
+
+    // array = begin-array [ value *( value-separator value ) ] end-array
+    fn parse_array(&mut self) -> Result<Rc<RefCell<JSValue>>, CompletionRecord> {
+        self.enter_nesting()?;
+        self.consume('[')?;
+        let mut elements = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.position += 1;
+        } else {
+            loop {
+                elements.push(self.parse_value()?);
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(',') => self.position += 1,
+                    Some(']') => { self.position += 1; break; },
+                    _ => return Err(self.unexpected_token())
+                }
+            }
+        }
+        self.depth -= 1;
+        return Ok(Rc::new(RefCell::new(JSValue::Object(self.interpreter.create_array_from_list(elements)))));
+    }
+
+    // string = quotation-mark *char quotation-mark
+    // An escaped UTF-16 surrogate pair is one character, a lone surrogate can not be represented in a String and is replaced with U+FFFD
+    fn parse_string(&mut self) -> Result<String, CompletionRecord> {
+        self.position += 1;
+        let mut code_units: Vec<u16> = Vec::new();
+        loop {
+            let character = match self.peek() {
+                Some(character) => character,
+                None => return Err(self.unexpected_token())
+            };
+            match character {
+                '"' => {
+                    self.position += 1;
+                    return Ok(String::from_utf16_lossy(&code_units));
+                },
+                '\\' => {
+                    self.position += 1;
+                    let escaped = match self.peek() {
+                        Some('"') => '"' as u16,
+                        Some('\\') => '\\' as u16,
+                        Some('/') => '/' as u16,
+                        Some('b') => 0x0008,
+                        Some('f') => 0x000C,
+                        Some('n') => 0x000A,
+                        Some('r') => 0x000D,
+                        Some('t') => 0x0009,
+                        Some('u') => {
+                            let hex_digits: String = self.text.iter().skip(self.position + 1).take(4).collect();
+                            if hex_digits.len() != 4 || !hex_digits.chars().all(|digit| digit.is_ascii_hexdigit()) {
+                                self.position += 1 + hex_digits.chars().take_while(|digit| digit.is_ascii_hexdigit()).count();
+                                return Err(self.unexpected_token());
+                            }
+                            self.position += 4;
+                            u16::from_str_radix(&hex_digits, 16).unwrap()
+                        },
+                        _ => return Err(self.unexpected_token())
+                    };
+                    code_units.push(escaped);
+                },
+                // The control characters U+0000 to U+001F must be escaped
+                '\u{0000}'..='\u{001F}' => return Err(self.unexpected_token()),
+                _ => {
+                    let mut buffer = [0u16; 2];
+                    code_units.extend_from_slice(character.encode_utf16(&mut buffer));
+                }
+            }
+            self.position += 1;
+        }
+    }
+
+    // number = [ minus ] int [ frac ] [ exp ]
+    fn parse_number(&mut self) -> Result<f64, CompletionRecord> {
+        let start = self.position;
+        if self.peek() == Some('-') {
+            self.position += 1;
+        }
+        // int = zero / ( digit1-9 *DIGIT ), leading zeros are not allowed
+        match self.peek() {
+            Some('0') => self.position += 1,
+            Some('1'..='9') => self.skip_digits(),
+            _ => return Err(self.unexpected_token())
+        }
+        // frac = decimal-point 1*DIGIT
+        if self.peek() == Some('.') {
+            self.position += 1;
+            if !self.peek().is_some_and(|character| character.is_ascii_digit()) {
+                return Err(self.unexpected_token());
+            }
+            self.skip_digits();
+        }
+        // exp = e [ minus / plus ] 1*DIGIT
+        if matches!(self.peek(), Some('e' | 'E')) {
+            self.position += 1;
+            if matches!(self.peek(), Some('-' | '+')) {
+                self.position += 1;
+            }
+            if !self.peek().is_some_and(|character| character.is_ascii_digit()) {
+                return Err(self.unexpected_token());
+            }
+            self.skip_digits();
+        }
+        // The grammar is a subset of Rust's float syntax, which rounds to the nearest double like the ECMAScript NumericLiteral does
+        let number: String = self.text[start..self.position].iter().collect();
+        return Ok(number.parse::<f64>().unwrap());
+    }
+
+    fn skip_digits(&mut self) {
+        while self.peek().is_some_and(|character| character.is_ascii_digit()) {
+            self.position += 1;
+        }
+    }
+
+    fn enter_nesting(&mut self) -> Result<(), CompletionRecord> {
+        self.depth += 1;
+        if self.depth > MAX_NESTING_DEPTH {
+            return Err(self.interpreter.throw_error("RangeError", String::from("Maximum nesting depth of JSON exceeded")));
+        }
+        return Ok(());
+    }
+
+    // The SyntaxError for the character at the current position, or the end of the text
+    fn unexpected_token(&mut self) -> CompletionRecord {
+        let message = match self.peek() {
+            Some(character) => format!("Unexpected token '{}' in JSON at position {}", character, self.position),
+            None => String::from("Unexpected end of JSON input")
+        };
+        return self.interpreter.throw_error("SyntaxError", message);
+    }
+}
+
+// https://tc39.es/ecma262/#sec-internalizejsonproperty
+fn internalize_json_property(interpreter: &mut Interpreter, holder: &Rc<RefCell<JSObject>>, name: String, reviver: &Rc<RefCell<JSValue>>) -> Result<Rc<RefCell<JSValue>>, CompletionRecord> {
+    // 1. Let val be ? Get(holder, name).
+    let value = get(interpreter, holder, name.clone())?;
+
+    // 2. If val is an Object, then
+    let object = match &*value.borrow() {
+        JSValue::Object(object) => Some(Rc::clone(object)),
+        _ => None
+    };
+    if let Some(object) = object {
+        // a. Let isArray be ? IsArray(val).
+        // b. If isArray is true, then
+        //     i. Let len be ? LengthOfArrayLike(val).
+        //     ii. Let I be 0.
+        //     iii. Repeat, while I < len, ... Let prop be ! ToString(𝔽(I)).
+        // c. Else,
+        //     i. Let keys be ? EnumerableOwnProperties(val, key).
+        let is_array = object.borrow().array;
+        let keys = if is_array {
+            let length = length_of_array_like(interpreter, &object)?;
+            (0..length as u64).map(|index| index.to_string()).collect()
+        } else {
+            enumerable_own_property_names(&object)
+        };
+
+        //     ii. For each String P of keys, do
+        for key in keys {
+            // 1. Let newElement be ? InternalizeJSONProperty(val, P, reviver).
+            let new_element = internalize_json_property(interpreter, &object, key.clone(), reviver)?;
+
+            // 2. If newElement is undefined, then
+            if matches!(*new_element.borrow(), JSValue::Undefined) {
+                // a. Perform ? val.[[Delete]](P).
+                object.borrow_mut().delete(&PropertyKey::String(key));
+            } else {
+                // 3. Else,
+                // a. Perform ? CreateDataProperty(val, P, newElement).
+                Interpreter::create_data_property(&object, &key, new_element);
+            }
+        }
+    }
+
+    // 3. Return ? Call(reviver, holder, « name, val »).
+    let holder = Rc::new(RefCell::new(JSValue::Object(Rc::clone(holder))));
+    return completion_value(interpreter.call(Rc::clone(reviver), holder, vec![Rc::new(RefCell::new(JSValue::String(name))), value]));
+}
+
+// https://tc39.es/ecma262/#sec-json-serialization-record
+struct JSONSerializationRecord {
+    // [[ReplacerFunction]]
+    replacer_function: Option<Rc<RefCell<JSValue>>>,
+    // [[Stack]], the objects being serialized, which a cyclic structure would serialize again
+    stack: Vec<Rc<RefCell<JSObject>>>,
+    // [[Indent]]
+    indent: String,
+    // [[Gap]]
+    gap: String,
+    // [[PropertyList]], the names the replacer array allows
+    property_list: Option<Vec<String>>,
+    // The bytes of the members serialized so far, which are held in strings the heap cannot see until the text is returned
+    serialized_length: usize,
+}
+
+// https://tc39.es/ecma262/#sec-json.stringify
+fn json_stringify(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let value = argument(&arguments, 0);
+    let replacer = argument(&arguments, 1);
+    let space = argument(&arguments, 2);
+
+    // 1. Let stack be a new empty List.
+    // 2. Let indent be the empty String.
+    // 3. Let PropertyList be undefined.
+    // 4. Let ReplacerFunction be undefined.
+    let mut state = JSONSerializationRecord { replacer_function: None, stack: Vec::new(), indent: String::new(), gap: String::new(), property_list: None, serialized_length: 0 };
+
+    // 5. If replacer is an Object, then
+    let replacer_object = match &*replacer.borrow() {
+        JSValue::Object(replacer_object) => Some(Rc::clone(replacer_object)),
+        _ => None
+    };
+    if let Some(replacer_object) = replacer_object {
+        // a. If IsCallable(replacer) is true, then
+        if Interpreter::is_callable(&replacer.borrow()) {
+            // i. Set ReplacerFunction to replacer.
+            state.replacer_function = Some(Rc::clone(&replacer));
+        } else if replacer_object.borrow().array {
+            // b. Else,
+            // i. Let isArray be ? IsArray(replacer).
+            // ii. If isArray is true, then
+            // 1. Set PropertyList to a new empty List.
+            let mut property_list: Vec<String> = Vec::new();
+
+            // 2. Let len be ? LengthOfArrayLike(replacer).
+            let length = return_if_abrupt!(length_of_array_like(interpreter, &replacer_object));
+
+            // 3. Let k be 0.
+            // 4. Repeat, while k < len,
+            for k in 0..length as u64 {
+                // a. Let prop be ! ToString(𝔽(k)).
+                // b. Let v be ? Get(replacer, prop).
+                let v = return_if_abrupt!(get(interpreter, &replacer_object, k.to_string()));
+
+                // c. Let item be undefined.
+                // d. If v is a String, then set item to v.
+                // e. Else if v is a Number, then set item to ! ToString(v).
+                // f. Else if v is an Object, then if v has a [[StringData]] or [[NumberData]] internal slot, set item to ? ToString(v).
+                let item = match &*v.borrow() {
+                    JSValue::String(string) => Some(string.clone()),
+                    JSValue::Numeric(number) => Some(Interpreter::number_to_string(*number)),
+                    _ => None
+                };
+
+                // g. If item is not undefined and PropertyList does not contain item, then
+                //     i. Append item to PropertyList.
+                if let Some(item) = item {
+                    if !property_list.contains(&item) {
+                        property_list.push(item);
+                    }
+                }
+
+                // h. Set k to k + 1.
+            }
+            state.property_list = Some(property_list);
+        }
+    }
+
+    // 6. If space is an Object, then
+    //     a. If space has a [[NumberData]] internal slot, then set space to ? ToNumber(space).
+    //     b. Else if space has a [[StringData]] internal slot, then set space to ? ToString(space).
+    state.gap = match &*space.borrow() {
+        // 7. If space is a Number, then
+        JSValue::Numeric(number) => {
+            // a. Let spaceMV be ! ToIntegerOrInfinity(space).
+            // b. Set spaceMV to min(10, spaceMV).
+            // c. If spaceMV < 1, let gap be the empty String; otherwise let gap be the String value containing spaceMV occurrences of the code unit 0x0020 (SPACE).
+            let space_mv = if number.is_nan() { 0.0 } else { number.trunc().clamp(0.0, 10.0) };
+            " ".repeat(space_mv as usize)
+        },
+        // 8. Else if space is a String, then
+        //     a. If the length of space ≤ 10, let gap be space; otherwise let gap be the substring of space from 0 to 10.
+        JSValue::String(string) => {
+            let code_units: Vec<u16> = string.encode_utf16().take(10).collect();
+            String::from_utf16_lossy(&code_units)
+        },
+        // 9. Else,
+        //     a. Let gap be the empty String.
+        _ => String::new()
+    };
+
+    // 10. Let wrapper be OrdinaryObjectCreate(%Object.prototype%).
+    let wrapper = interpreter.create_ordinary_object();
+
+    // 11. Perform ! CreateDataPropertyOrThrow(wrapper, the empty String, value).
+    Interpreter::create_data_property(&wrapper, "", value);
+
+    // 12. Let state be the JSON Serialization Record { [[ReplacerFunction]]: ReplacerFunction, [[Stack]]: stack, [[Indent]]: indent, [[Gap]]: gap, [[PropertyList]]: PropertyList }.
+    // 13. Return ? SerializeJSONProperty(state, the empty String, wrapper).
+    return match return_if_abrupt!(serialize_json_property(interpreter, &mut state, String::new(), &wrapper)) {
+        Some(string) => create_value_completion(JSValue::String(string)),
+        None => create_value_completion(JSValue::Undefined)
+    };
+}
+
+// https://tc39.es/ecma262/#sec-serializejsonproperty
+// None is undefined, the value is left out of the object it is a property of
+fn serialize_json_property(interpreter: &mut Interpreter, state: &mut JSONSerializationRecord, key: String, holder: &Rc<RefCell<JSObject>>) -> Result<Option<String>, CompletionRecord> {
+    // 1. Let value be ? Get(holder, key).
+    let mut value = get(interpreter, holder, key.clone())?;
+
+    // 2. If value is an Object or value is a BigInt, then
+    let object = match &*value.borrow() {
+        JSValue::Object(object) => Some(Rc::clone(object)),
+        _ => None
+    };
+    if let Some(object) = object {
+        // a. Let toJSON be ? GetV(value, "toJSON").
+        let to_json = get(interpreter, &object, String::from("toJSON"))?;
+
+        // b. If IsCallable(toJSON) is true, then
+        if Interpreter::is_callable(&to_json.borrow()) {
+            // i. Set value to ? Call(toJSON, value, « key »).
+            value = completion_value(interpreter.call(Rc::clone(&to_json), Rc::clone(&value), vec![Rc::new(RefCell::new(JSValue::String(key.clone())))]))?;
+        }
+    }
+
+    // 3. If state.[[ReplacerFunction]] is not undefined, then
+    if let Some(replacer_function) = &state.replacer_function {
+        // a. Set value to ? Call(state.[[ReplacerFunction]], holder, « key, value »).
+        let holder = Rc::new(RefCell::new(JSValue::Object(Rc::clone(holder))));
+        value = completion_value(interpreter.call(Rc::clone(replacer_function), holder, vec![Rc::new(RefCell::new(JSValue::String(key))), value]))?;
+    }
+
+    // 4. If value is an Object, then
+    //     a-d. TODO: Number, String, Boolean and BigInt objects are unwrapped to their primitive value
+    let is_callable = Interpreter::is_callable(&value.borrow());
+    let object = match &*value.borrow() {
+        // 5. If value is null, return "null".
+        JSValue::Null => return Ok(Some(String::from("null"))),
+        // 6. If value is true, return "true".
+        JSValue::Boolean(true) => return Ok(Some(String::from("true"))),
+        // 7. If value is false, return "false".
+        JSValue::Boolean(false) => return Ok(Some(String::from("false"))),
+        // 8. If value is a String, return QuoteJSONString(value).
+        JSValue::String(string) => return Ok(Some(quote_json_string(string))),
+        // 9. If value is a Number, then
+        //     a. If value is finite, return ! ToString(value).
+        //     b. Return "null".
+        JSValue::Numeric(number) if number.is_finite() => return Ok(Some(Interpreter::number_to_string(*number))),
+        JSValue::Numeric(_) => return Ok(Some(String::from("null"))),
+        // 10. If value is a BigInt, throw a TypeError exception.
+        // 11. If value is an Object and IsCallable(value) is false, then
+        JSValue::Object(object) if !is_callable => Rc::clone(object),
+        // 12. Return undefined.
+        _ => return Ok(None)
+    };
+
+    // a. Let isArray be ? IsArray(value).
+    // b. If isArray is true, return ? SerializeJSONArray(state, value).
+    // c. Return ? SerializeJSONObject(state, value).
+    let is_array = object.borrow().array;
+    if is_array {
+        return serialize_json_array(interpreter, state, &object).map(Some);
+    }
+    return serialize_json_object(interpreter, state, &object).map(Some);
+}
+
+// https://tc39.es/ecma262/#sec-quotejsonstring
+fn quote_json_string(value: &str) -> String {
+    // 1. Let product be the String value consisting solely of the code unit 0x0022 (QUOTATION MARK).
+    let mut product = String::from("\"");
+
+    // 2. For each code point C of StringToCodePoints(value), do
+    for character in value.chars() {
+        match character {
+            // a. If C is listed in the “Code Point” column of Table 75, then
+            //     i. Set product to the string-concatenation of product and the escape sequence for C as specified in the “Escape Sequence” column of the corresponding row.
+            '\u{0008}' => product.push_str("\\b"),
+            '\u{0009}' => product.push_str("\\t"),
+            '\u{000A}' => product.push_str("\\n"),
+            '\u{000C}' => product.push_str("\\f"),
+            '\u{000D}' => product.push_str("\\r"),
+            '"' => product.push_str("\\\""),
+            '\\' => product.push_str("\\\\"),
+            // b. Else if C has a numeric value less than 0x0020 (SPACE) or C has the same numeric value as a leading surrogate or trailing surrogate, then
+            //     i. Let unit be the code unit whose numeric value is the numeric value of C.
+            //     ii. Set product to the string-concatenation of product and UnicodeEscape(unit).
+            // A String has no lone surrogates, so only the control characters are escaped
+            '\u{0000}'..='\u{001F}' => product.push_str(&format!("\\u{:04x}", character as u32)),
+            // c. Else,
+            //     i. Set product to the string-concatenation of product and UTF16EncodeCodePoint(C).
+            _ => product.push(character)
+        }
+    }
+
+    // 3. Set product to the string-concatenation of product and the code unit 0x0022 (QUOTATION MARK).
+    product.push('"');
+
+    // 4. Return product.
+    return product;
+}
+
+// https://tc39.es/ecma262/#sec-serializejsonobject
+fn serialize_json_object(interpreter: &mut Interpreter, state: &mut JSONSerializationRecord, value: &Rc<RefCell<JSObject>>) -> Result<String, CompletionRecord> {
+    // 1. If state.[[Stack]] contains value, throw a TypeError exception because the structure is cyclical.
+    // 2. Append value to state.[[Stack]].
+    enter_serialization(interpreter, state, value)?;
+
+    // 3. Let stepBack be state.[[Indent]].
+    let step_back = state.indent.clone();
+
+    // 4. Set state.[[Indent]] to the string-concatenation of state.[[Indent]] and state.[[Gap]].
+    state.indent.push_str(&state.gap);
+
+    // 5. If state.[[PropertyList]] is not undefined, then
+    //     a. Let K be state.[[PropertyList]].
+    // 6. Else,
+    //     a. Let K be ? EnumerableOwnProperties(value, key).
+    let keys = match &state.property_list {
+        Some(property_list) => property_list.clone(),
+        None => enumerable_own_property_names(value)
+    };
+
+    // 7. Let partial be a new empty List.
+    let mut partial: Vec<String> = Vec::new();
+
+    // 8. For each element P of K, do
+    for key in keys {
+        // a. Let strP be ? SerializeJSONProperty(state, P, value).
+        // b. If strP is not undefined, then
+        if let Some(string_p) = serialize_json_property(interpreter, state, key.clone(), value)? {
+            // i. Let member be QuoteJSONString(P).
+            // ii. Set member to the string-concatenation of member and ":".
+            // iii. If state.[[Gap]] is not the empty String, then
+            //     1. Set member to the string-concatenation of member and the code unit 0x0020 (SPACE).
+            // iv. Set member to the string-concatenation of member and strP.
+            // v. Append member to partial.
+            let separator = if state.gap.is_empty() { ":" } else { ": " };
+            let member = format!("{}{}{}", quote_json_string(&key), separator, string_p);
+            reserve_member(interpreter, state, &member)?;
+            partial.push(member);
+        }
+    }
+
+    // 9-10. The members joined in braces, one per line when there is a gap
+    let result = join_members(state, &partial, &step_back, '{', '}');
+
+    // 11. Remove the last element of state.[[Stack]].
+    state.stack.pop();
+
+    // 12. Set state.[[Indent]] to stepBack.
+    state.indent = step_back;
+
+    // 13. Return final.
+    return Ok(result);
+}
+
+// https://tc39.es/ecma262/#sec-serializejsonarray
+fn serialize_json_array(interpreter: &mut Interpreter, state: &mut JSONSerializationRecord, value: &Rc<RefCell<JSObject>>) -> Result<String, CompletionRecord> {
+    // 1. If state.[[Stack]] contains value, throw a TypeError exception because the structure is cyclical.
+    // 2. Append value to state.[[Stack]].
+    enter_serialization(interpreter, state, value)?;
+
+    // 3. Let stepBack be state.[[Indent]].
+    let step_back = state.indent.clone();
+
+    // 4. Set state.[[Indent]] to the string-concatenation of state.[[Indent]] and state.[[Gap]].
+    state.indent.push_str(&state.gap);
+
+    // 5. Let partial be a new empty List.
+    let mut partial: Vec<String> = Vec::new();
+
+    // 6. Let len be ? LengthOfArrayLike(value).
+    let length = length_of_array_like(interpreter, value)?;
+
+    // 7. Let index be 0.
+    // 8. Repeat, while index < len,
+    for index in 0..length as u64 {
+        // a. Let strP be ? SerializeJSONProperty(state, ! ToString(𝔽(index)), value).
+        // b. If strP is undefined, then
+        //     i. Append "null" to partial.
+        // c. Else,
+        //     i. Append strP to partial.
+        let string_p = serialize_json_property(interpreter, state, index.to_string(), value)?;
+        let member = string_p.unwrap_or_else(|| String::from("null"));
+        reserve_member(interpreter, state, &member)?;
+        partial.push(member);
+
+        // d. Set index to index + 1.
+    }
+
+    // 9-10. The elements joined in brackets, one per line when there is a gap
+    let result = join_members(state, &partial, &step_back, '[', ']');
+
+    // 11. Remove the last element of state.[[Stack]].
+    state.stack.pop();
+
+    // 12. Set state.[[Indent]] to stepBack.
+    state.indent = step_back;
+
+    // 13. Return final.
+    return Ok(result);
+}
+
+// An object that is referred to many times is serialized each time, so the text can be far longer than the heap, and is counted against its maximum size as it grows
+fn reserve_member(interpreter: &mut Interpreter, state: &mut JSONSerializationRecord, member: &str) -> Result<(), CompletionRecord> {
+    if let Some(range_error) = interpreter.reserve_heap(member.len(), state.serialized_length) {
+        return Err(range_error);
+    }
+    state.serialized_length += member.len();
+    return Ok(());
+}
+
+// Steps 1 and 2 of SerializeJSONObject and SerializeJSONArray
+fn enter_serialization(interpreter: &mut Interpreter, state: &mut JSONSerializationRecord, value: &Rc<RefCell<JSObject>>) -> Result<(), CompletionRecord> {
+    if state.stack.iter().any(|object| Rc::ptr_eq(object, value)) {
+        return Err(interpreter.throw_type_error(String::from("Converting circular structure to JSON")));
+    }
+    if state.stack.len() >= MAX_NESTING_DEPTH {
+        return Err(interpreter.throw_error("RangeError", String::from("Maximum nesting depth of JSON exceeded")));
+    }
+    state.stack.push(Rc::clone(value));
+    return Ok(());
+}
+
+// Steps 9 and 10 of SerializeJSONObject and SerializeJSONArray
+fn join_members(state: &JSONSerializationRecord, partial: &[String], step_back: &str, open: char, close: char) -> String {
+    // 9. If partial is empty, then
+    //     a. Let final be "{}".
+    if partial.is_empty() {
+        return format!("{}{}", open, close);
+    }
+
+    // 10. Else,
+    // a. If state.[[Gap]] is the empty String, then
+    //     i. Let properties be the String value formed by concatenating all the element Strings of partial with each adjacent pair of Strings separated with the code unit 0x002C (COMMA).
+    //     ii. Let final be the string-concatenation of "{", properties, and "}".
+    if state.gap.is_empty() {
+        return format!("{}{}{}", open, partial.join(","), close);
+    }
+
+    // b. Else,
+    //     i. Let separator be the string-concatenation of the code unit 0x002C (COMMA), the code unit 0x000A (LINE FEED), and state.[[Indent]].
+    //     ii. Let properties be the String value formed by concatenating all the element Strings of partial with each adjacent pair of Strings separated with separator.
+    //     iii. Let final be the string-concatenation of "{", the code unit 0x000A (LINE FEED), state.[[Indent]], properties, the code unit 0x000A (LINE FEED), stepBack, and "}".
+    let separator = format!(",\n{}", state.indent);
+    return format!("{}\n{}{}\n{}{}", open, state.indent, partial.join(&separator), step_back, close);
+}
+
+// https://tc39.es/ecma262/#sec-enumerableownproperties
+// The names of the enumerable own String-keyed properties of object, array indices first
+// TODO: Accessor properties have no [[Enumerable]] attribute yet, they are taken to be enumerable
+fn enumerable_own_property_names(object: &Rc<RefCell<JSObject>>) -> Vec<String> {
+    let object = object.borrow();
+    let mut names = Vec::new();
+    // 1. Let ownKeys be ? O.[[OwnPropertyKeys]]().
+    // 3. For each element key of ownKeys, do
+    for key in object.own_property_keys() {
+        // a. If key is a String, then
+        //     i. Let desc be ? O.[[GetOwnProperty]](key).
+        //     ii. If desc is not undefined and desc.[[Enumerable]] is true, then
+        //         1. If kind is key, then append key to results.
+        if let PropertyKey::String(name) = key {
+            let enumerable = match object.own_property(key).as_deref() {
+                Some(PropertyType::DataProperty(data_property)) => data_property.enumerable,
+                Some(PropertyType::AccessorProperty(_)) => true,
+                None => false
+            };
+            if enumerable {
+                names.push(name.clone());
+            }
+        }
+    }
+    // 4. Return results.
+    return names;
+}
+
+// https://tc39.es/ecma262/#sec-get-o-p
+fn get(interpreter: &mut Interpreter, object: &Rc<RefCell<JSObject>>, key: String) -> Result<Rc<RefCell<JSValue>>, CompletionRecord> {
+    // 1. Return ? O.[[Get]](P, O).
+    let receiver = Rc::new(RefCell::new(JSValue::Object(Rc::clone(object))));
+    return completion_value(interpreter.get(object, &PropertyKey::String(key), receiver));
+}
+
+// https://tc39.es/ecma262/#sec-lengthofarraylike
+// FIXME: ToLength is only implemented for primitives, which the length of an array always is
+fn length_of_array_like(interpreter: &mut Interpreter, object: &Rc<RefCell<JSObject>>) -> Result<f64, CompletionRecord> {
+    // 1. Return ℝ(? ToLength(? Get(obj, "length"))).
+    let length = get(interpreter, object, String::from("length"))?;
+    if matches!(*length.borrow(), JSValue::Symbol(_) | JSValue::Object(_)) {
+        return Err(interpreter.throw_type_error(String::from("Cannot convert value to a number")));
+    }
+    let length = match *completion_value(Interpreter::to_number(length))?.borrow() {
+        JSValue::Numeric(number) => number,
+        _ => unreachable!()
+    };
+    if length.is_nan() {
+        return Ok(0.0);
+    }
+    return Ok(length.trunc().clamp(0.0, 9007199254740991.0));
+}
+
+fn to_string(interpreter: &mut Interpreter, value: Rc<RefCell<JSValue>>) -> Result<String, CompletionRecord> {
+    match &*completion_value(interpreter.to_string_coercing_objects(value))?.borrow() {
+        JSValue::String(string) => Ok(string.clone()),
+        _ => unreachable!()
+    }
+}
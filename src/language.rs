@@ -0,0 +1,153 @@
+use unicode_bidi::{bidi_class, BidiClass};
+use crate::microsyntaxes::is_ascii_whitespace;
+use crate::node::{descendants, NodeData, RefNode};
+use crate::unicode::{ascii_lowercase, is_ascii_case_insensitive_match};
+
+// https://html.spec.whatwg.org/multipage/dom.html#the-lang-and-xml:lang-attributes
+// https://html.spec.whatwg.org/multipage/dom.html#the-dir-attribute
+// The language and the directionality of the nodes of a document, each inherited from the parent unless the element sets its own with a lang or dir attribute
+// The text renderer aligns right-to-left blocks to the right, and the accessibility tree exposes where the language or direction changes
+
+// https://html.spec.whatwg.org/multipage/dom.html#the-directionality
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+impl Direction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Ltr => "ltr",
+            Direction::Rtl => "rtl"
+        }
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/dom.html#language
+// The language tag of node, e.g. "en-GB", the empty string when the language is unknown
+// TODO: The language is not checked to be a valid BCP 47 language tag
+pub fn language(node: &RefNode) -> String {
+    let mut current = Some(node.clone());
+    while let Some(node) = current {
+        if let NodeData::Element(element) = &node.borrow().data {
+            // If the node is an element that has a lang attribute in the XML namespace set or is an HTML element and has a lang in no namespace attribute set,
+            // then that attribute's value, lang="" is an unknown language
+            // The xml:lang attribute is only parsed as an attribute in the XML namespace in foreign content, in HTML its name is simply "xml:lang"
+            if let Some(lang) = element.get_attribute("xml:lang").or_else(|| element.get_attribute("lang")) {
+                return lang.trim_matches(is_ascii_whitespace).to_string();
+            }
+        }
+        // If neither the node nor any of the node's ancestors, including the root element, have either attribute set, but there is a pragma-set default language set,
+        // then that is the language of the node.
+        let parent = node.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+        if parent.is_none() {
+            if let NodeData::Document(_) = &node.borrow().data {
+                return pragma_set_default_language(&node).unwrap_or_default();
+            }
+        }
+        current = parent;
+    }
+    // If there is no pragma-set default language set, then language information from a higher-level protocol (such as HTTP), if any, must be used as the final fallback language instead.
+    // In the absence of any such language information, and in cases where the higher-level protocol reports multiple languages, the language of the node is unknown
+    // TODO: The Content-Language header of the response is not kept with the document
+    return String::new();
+}
+
+// https://html.spec.whatwg.org/multipage/semantics.html#attr-meta-http-equiv-content-language
+// The language of the last <meta http-equiv="content-language"> of the document, each one sets it as it is inserted
+fn pragma_set_default_language(document: &RefNode) -> Option<String> {
+    let mut pragma_set_default_language = None;
+    for descendant in descendants(document) {
+        let content = match &descendant.borrow().data {
+            NodeData::Element(element) if element.local_name() == "meta"
+                && element.get_attribute("http-equiv").is_some_and(|http_equiv| is_ascii_case_insensitive_match(http_equiv.trim(), "content-language")) => element.get_attribute("content").cloned(),
+            _ => None
+        };
+        // 1. If the meta element has no content attribute, then return.
+        // 2. If the element's content attribute contains a U+002C COMMA character (,) then return.
+        let content = match content {
+            Some(content) if !content.contains(',') => content,
+            _ => continue
+        };
+        // 3-6. Let candidate be the sequence of code points that are not ASCII whitespace after any leading ASCII whitespace, if candidate is the empty string, return.
+        let candidate: String = content.trim_start_matches(is_ascii_whitespace).chars().take_while(|character| !is_ascii_whitespace(*character)).collect();
+        if !candidate.is_empty() {
+            // 7. Set the pragma-set default language to candidate.
+            pragma_set_default_language = Some(candidate);
+        }
+    }
+    return pragma_set_default_language;
+}
+
+// https://html.spec.whatwg.org/multipage/dom.html#the-directionality
+// The directionality of node, a text node and a document have the directionality of their parent, and the document is left-to-right
+// TODO: The auto directionality of input and textarea elements is taken from their children rather than their value
+pub fn directionality(node: &RefNode) -> Direction {
+    let mut current = Some(node.clone());
+    while let Some(node) = current {
+        let dir = match &node.borrow().data {
+            NodeData::Element(element) => Some((element.local_name().to_string(), element.get_attribute("dir").map(|dir| ascii_lowercase(dir.trim()).into_owned()))),
+            _ => None
+        };
+        if let Some((local_name, dir)) = dir {
+            match dir.as_deref() {
+                // If element's dir attribute is in the ltr state: Return "ltr".
+                Some("ltr") => return Direction::Ltr,
+                // If element's dir attribute is in the rtl state: Return "rtl".
+                Some("rtl") => return Direction::Rtl,
+                // If element's dir attribute is in the auto state, or element is a bdi element and its dir attribute is not in a defined state:
+                // Let result be the auto directionality of element. If result is null, then return "ltr". Return result.
+                Some("auto") => return auto_directionality(&node).unwrap_or(Direction::Ltr),
+                _ if local_name == "bdi" => return auto_directionality(&node).unwrap_or(Direction::Ltr),
+                _ => ()
+            }
+        }
+        // If element's dir attribute is in the undefined state: If element's parent element is null, then return "ltr". Return the directionality of element's parent element.
+        current = node.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+    }
+    return Direction::Ltr;
+}
+
+// https://html.spec.whatwg.org/multipage/dom.html#auto-directionality
+// https://html.spec.whatwg.org/multipage/dom.html#contained-text-auto-directionality
+// The direction of the first strong character of the text in element, None when there is none
+fn auto_directionality(element: &RefNode) -> Option<Direction> {
+    // 1. For each node descendant of element's descendants, in tree order:
+    for child in &element.borrow().childNodes {
+        let child_direction = match &child.borrow().data {
+            // 1. If descendant, or any of its ancestor elements that are descendants of element, is one of a bdi element, a script element, a style element,
+            // a textarea element or an element whose dir attribute is not in the undefined state, then continue.
+            NodeData::Element(element) if matches!(element.local_name().as_str(), "bdi" | "script" | "style" | "textarea")
+                || element.get_attribute("dir").is_some_and(|dir| matches!(ascii_lowercase(dir.trim()).as_ref(), "ltr" | "rtl" | "auto")) => None,
+            NodeData::Element(_) => auto_directionality(child),
+            // 2. If descendant is not a Text node, then continue.
+            // 3. Let result be the text node directionality of descendant.
+            NodeData::Text(text) => first_strong_direction(&text.character_data.data),
+            _ => None
+        };
+        // 4. If result is not null, then return result.
+        if child_direction.is_some() {
+            return child_direction;
+        }
+    }
+    // 2. Return null.
+    return None;
+}
+
+// https://html.spec.whatwg.org/multipage/dom.html#text-node-directionality
+// The direction of the first character of bidirectional character type L, AL or R in text
+pub fn first_strong_direction(text: &str) -> Option<Direction> {
+    return text.chars().find_map(strong_direction);
+}
+
+// https://www.unicode.org/reports/tr9/#Bidirectional_Character_Types
+// Whether character is a strong left-to-right (L) or right-to-left (R or AL) character, None for the weak and neutral ones
+pub fn strong_direction(character: char) -> Option<Direction> {
+    return match bidi_class(character) {
+        BidiClass::L => Some(Direction::Ltr),
+        BidiClass::R | BidiClass::AL => Some(Direction::Rtl),
+        _ => None
+    };
+}
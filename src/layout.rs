@@ -0,0 +1,373 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use indexmap::IndexMap;
+use crate::counters::{counter_properties, resolve_counters, Content, CounterProperties};
+use crate::cssom::{document_css_style_sheets, CSSStyleSheet};
+use crate::node::{Node, NodeData, RefNode};
+use crate::css_values::{CssWideKeyword, PropertyValue};
+use crate::selector::PseudoElement;
+use crate::style::{has_pseudo_element_rules, pseudo_element_specified_values, specified_values};
+use crate::unicode::ascii_lowercase;
+
+// https://drafts.csswg.org/css-display-3/#box-tree
+// The boxes the elements and text of a document generate, the first stage of layout
+// The box tree is kept by the document, and when it is built again only the boxes of the nodes that were invalidated since (see style::invalidate_style) are generated again
+// TODO: Boxes are not sized or positioned, no anonymous block boxes are generated around inline content that is next to a block, and table, flex and grid containers are laid out as blocks
+
+// https://drafts.csswg.org/css-display-3/#the-display-properties
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Display {
+    Block,
+    Inline,
+    ListItem,
+    None,
+}
+
+impl Display {
+    // TODO: Only the keywords are parsed, the multi-keyword syntax (e.g. "block flow") is not
+    pub(crate) fn parse(value: &str) -> Option<Display> {
+        match ascii_lowercase(value.trim()).as_ref() {
+            "block" | "flow-root" | "flex" | "grid" | "table" | "table-caption" | "table-row-group" | "table-header-group"
+            | "table-footer-group" | "table-row" | "table-cell" => Some(Display::Block),
+            // The contents of an inline-block are laid out as if they were inline, and display: contents is treated as inline as it generates no box of its own
+            "inline" | "inline-block" | "inline-flex" | "inline-grid" | "inline-table" | "contents" => Some(Display::Inline),
+            "list-item" => Some(Display::ListItem),
+            "none" => Some(Display::None),
+            _ => None
+        }
+    }
+}
+
+// https://drafts.csswg.org/css-text-3/#white-space-property
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WhiteSpace {
+    #[default]
+    Normal,
+    Pre,
+    Nowrap,
+    PreWrap,
+    PreLine,
+}
+
+impl WhiteSpace {
+    pub(crate) fn parse(value: &str) -> Option<WhiteSpace> {
+        match ascii_lowercase(value.trim()).as_ref() {
+            "normal" => Some(WhiteSpace::Normal),
+            "pre" => Some(WhiteSpace::Pre),
+            "nowrap" => Some(WhiteSpace::Nowrap),
+            "pre-wrap" => Some(WhiteSpace::PreWrap),
+            "pre-line" => Some(WhiteSpace::PreLine),
+            _ => None
+        }
+    }
+
+    // Whether lines may be broken at spaces to fit the available width
+    pub fn wraps(&self) -> bool {
+        return !matches!(self, WhiteSpace::Pre | WhiteSpace::Nowrap);
+    }
+
+    // Whether spaces and tabs are kept as they are rather than collapsed
+    pub fn preserves_spaces(&self) -> bool {
+        return matches!(self, WhiteSpace::Pre | WhiteSpace::PreWrap);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoxType {
+    Block,
+    ListItem,
+    Inline,
+    // The text of a text node, after white space processing
+    Text(String),
+}
+
+#[derive(Clone)]
+pub struct LayoutBox {
+    pub box_type: BoxType,
+    // The element or text node that generated the box, the originating element for the box of a pseudo-element and the text in it
+    pub node: RefNode,
+    // https://drafts.csswg.org/css-pseudo-4/#generated-content
+    // The pseudo-element that generated the box, its text is generated by counters::resolve_counters
+    pub pseudo_element: Option<PseudoElement>,
+    pub white_space: WhiteSpace,
+    pub counter_properties: CounterProperties,
+    pub children: Vec<LayoutBox>,
+}
+
+impl LayoutBox {
+    pub(crate) fn new(box_type: BoxType, node: RefNode, white_space: WhiteSpace) -> LayoutBox {
+        return LayoutBox { box_type, node, pseudo_element: None, white_space, counter_properties: CounterProperties::default(), children: Vec::new() };
+    }
+
+    // The local name of the element that generated the box, empty for a text box and the box of a pseudo-element
+    pub fn local_name(&self) -> String {
+        match &self.node.borrow().data {
+            NodeData::Element(element) if self.pseudo_element.is_none() => element.local_name().to_string(),
+            _ => String::new()
+        }
+    }
+
+    pub fn get_attribute(&self, qualified_name: &str) -> Option<String> {
+        match &self.node.borrow().data {
+            NodeData::Element(element) => element.get_attribute(qualified_name).cloned(),
+            _ => None
+        }
+    }
+}
+
+// The box tree of the last layout of a document
+pub(crate) struct BoxTreeCache {
+    // The rules of the style sheets the boxes were generated with, as scripts can change the rules through the CSSOM without changing the DOM
+    style_sheets_text: String,
+    root: Option<LayoutBox>,
+}
+
+// https://drafts.csswg.org/css-display-3/#box-generation
+// The box tree of document, rooted at the box of the document element, None when it has no document element or it is display: none
+pub fn build_box_tree(document: &RefNode) -> Option<LayoutBox> {
+    let style_sheets = document_css_style_sheets(document);
+    let document_element = document.borrow().childNodes.iter().find(|child| matches!(child.borrow().data, NodeData::Element(_))).cloned()?;
+
+    let style_sheets_text = style_sheets.iter()
+        .flat_map(|style_sheet| style_sheet.borrow().css_rules.iter().map(|rule| rule.borrow().css_text()).collect::<Vec<String>>())
+        .collect::<Vec<String>>()
+        .join("\n");
+    let cache = match &mut document.borrow_mut().data {
+        NodeData::Document(document) => document.box_tree_cache.take(),
+        _ => None
+    };
+
+    // Every element is styled again when the style sheets changed, or the document itself was invalidated (e.g. the first time it is laid out)
+    let restyle = document.borrow().needs_style || cache.as_ref().is_none_or(|cache| cache.style_sheets_text != style_sheets_text);
+    document.borrow_mut().needs_style = false;
+    document.borrow_mut().child_needs_style = false;
+    let previous_root = cache.and_then(|cache| cache.root).filter(|root| Rc::ptr_eq(&root.node, &document_element));
+
+    let pseudo_elements: Vec<PseudoElement> = [PseudoElement::Before, PseudoElement::After, PseudoElement::Marker].into_iter()
+        .filter(|pseudo_element| has_pseudo_element_rules(&style_sheets, *pseudo_element))
+        .collect();
+    let mut root = update_box(&document_element, previous_root, &style_sheets, &pseudo_elements, WhiteSpace::Normal, restyle);
+    // The counters are resolved again every time, as a box that changed changes the counters of every box after it
+    if let Some(root) = &mut root {
+        resolve_counters(root);
+    }
+    if let NodeData::Document(document) = &mut document.borrow_mut().data {
+        document.box_tree_cache = Some(BoxTreeCache { style_sheets_text, root: root.clone() });
+    }
+    return root;
+}
+
+// The box of node, previous is the box it generated the last time and is used again when neither node nor anything under it was invalidated since
+// restyle is set when an ancestor was styled again, which node has to be as well, and pseudo_elements are the pseudo-elements the style sheets have rules for
+fn update_box(node: &RefNode, previous: Option<LayoutBox>, style_sheets: &[Rc<RefCell<CSSStyleSheet>>], pseudo_elements: &[PseudoElement], parent_white_space: WhiteSpace, restyle: bool) -> Option<LayoutBox> {
+    let restyle = restyle || node.borrow().needs_style;
+    let children_changed = node.borrow().child_needs_style;
+    node.borrow_mut().needs_style = false;
+    node.borrow_mut().child_needs_style = false;
+    if !restyle && !children_changed {
+        return previous;
+    }
+
+    let (local_name, hidden) = match &node.borrow().data {
+        NodeData::Element(element) => (element.local_name().to_string(), element.has_attribute("hidden")),
+        NodeData::Text(text) => {
+            let text = process_white_space(&text.character_data.data, parent_white_space);
+            return Some(LayoutBox::new(BoxType::Text(text), Rc::clone(node), parent_white_space));
+        },
+        // Comments, processing instructions and doctypes do not generate boxes
+        _ => return None
+    };
+
+    let computed_display_and_white_space = node.borrow().computed_display_and_white_space;
+    let mut values = None;
+    let (display, white_space) = match computed_display_and_white_space {
+        Some(computed_display_and_white_space) if !restyle => computed_display_and_white_space,
+        _ => display_and_white_space(values.insert(specified_values(node, style_sheets)), &local_name, hidden, parent_white_space)
+    };
+    node.borrow_mut().computed_display_and_white_space = Some((display, white_space));
+
+    let box_type = match display {
+        // https://drafts.csswg.org/css-display-3/#valdef-display-none
+        // The element and its descendants generate no boxes
+        Display::None => return None,
+        Display::Block => BoxType::Block,
+        Display::ListItem => BoxType::ListItem,
+        Display::Inline => BoxType::Inline
+    };
+
+    // The counter properties and the boxes of the pseudo-elements of the element are only generated again when it is styled again
+    let (counter_properties, previous_pseudo_element_boxes, previous_children) = match previous {
+        Some(previous) if !restyle => {
+            let (pseudo_element_boxes, children): (Vec<LayoutBox>, Vec<LayoutBox>) = previous.children.into_iter().partition(|child| child.pseudo_element.is_some());
+            (previous.counter_properties, Some(pseudo_element_boxes), children)
+        },
+        _ => {
+            let values = values.get_or_insert_with(|| specified_values(node, style_sheets));
+            (counter_properties(node, None, values), None, Vec::new())
+        }
+    };
+    let pseudo_element_boxes = previous_pseudo_element_boxes.unwrap_or_else(|| pseudo_element_boxes(node, &local_name, &box_type, style_sheets, pseudo_elements, white_space));
+
+    // The boxes the children generated the last time, by the node that generated them, none are used again when the children are styled again
+    let mut previous_children: HashMap<*const RefCell<Node>, LayoutBox> = previous_children.into_iter().map(|child| (Rc::as_ptr(&child.node), child)).collect();
+    let child_nodes = node.borrow().childNodes.clone();
+    let mut children: Vec<LayoutBox> = child_nodes.iter().filter_map(|child| update_box(child, previous_children.remove(&Rc::as_ptr(child)), style_sheets, pseudo_elements, white_space, restyle)).collect();
+
+    // A list item's marker is its first child, and the boxes of ::before and ::after are before and after the boxes of its children
+    let (after, before): (Vec<LayoutBox>, Vec<LayoutBox>) = pseudo_element_boxes.into_iter().partition(|pseudo_element_box| pseudo_element_box.pseudo_element == Some(PseudoElement::After));
+    children.splice(0..0, before);
+    children.extend(after);
+    return Some(LayoutBox { box_type, node: Rc::clone(node), pseudo_element: None, white_space, counter_properties, children });
+}
+
+// https://drafts.csswg.org/css-lists-3/#marker-pseudo
+// https://drafts.csswg.org/css-pseudo-4/#generated-content
+// The boxes of the marker of a list item and of the ::before and ::after pseudo-elements of an element, without their text, in the order they are in the box tree
+// ::before and ::after only generate a box when their content is not none or normal, and replaced elements (e.g. img) have neither
+// TODO: The marker of a list item is always an inline box, and a ::before or ::after with display: list-item is a block without a marker of its own
+fn pseudo_element_boxes(element: &RefNode, local_name: &str, box_type: &BoxType, style_sheets: &[Rc<RefCell<CSSStyleSheet>>], pseudo_elements: &[PseudoElement], white_space: WhiteSpace) -> Vec<LayoutBox> {
+    let pseudo_element_values = |pseudo_element: PseudoElement| match pseudo_elements.contains(&pseudo_element) {
+        true => pseudo_element_specified_values(element, pseudo_element, style_sheets),
+        false => IndexMap::new()
+    };
+    let pseudo_element_box = |box_type: BoxType, pseudo_element: PseudoElement, white_space: WhiteSpace, counter_properties: CounterProperties| LayoutBox {
+        box_type,
+        node: Rc::clone(element),
+        pseudo_element: Some(pseudo_element),
+        white_space,
+        counter_properties,
+        children: Vec::new()
+    };
+
+    let mut boxes = Vec::new();
+    // ::marker { white-space: pre; }
+    if *box_type == BoxType::ListItem {
+        let values = pseudo_element_values(PseudoElement::Marker);
+        boxes.push(pseudo_element_box(BoxType::Inline, PseudoElement::Marker, WhiteSpace::Pre, counter_properties(element, Some(PseudoElement::Marker), &values)));
+    }
+
+    let replaced = matches!(local_name, "audio" | "br" | "canvas" | "embed" | "iframe" | "img" | "input" | "object" | "select" | "textarea" | "video");
+    for pseudo_element in [PseudoElement::Before, PseudoElement::After] {
+        if replaced || !pseudo_elements.contains(&pseudo_element) {
+            continue;
+        }
+        let values = pseudo_element_values(pseudo_element);
+        let counter_properties = counter_properties(element, Some(pseudo_element), &values);
+        if !matches!(counter_properties.content, Content::Items(_)) {
+            continue;
+        }
+        let box_type = match values.get("display") {
+            Some(PropertyValue::Display(Display::None)) => continue,
+            Some(PropertyValue::Display(Display::Block | Display::ListItem)) => BoxType::Block,
+            _ => BoxType::Inline
+        };
+        let white_space = match values.get("white-space") {
+            Some(PropertyValue::WhiteSpace(white_space)) => *white_space,
+            _ => white_space
+        };
+        boxes.push(pseudo_element_box(box_type, pseudo_element, white_space, counter_properties));
+    }
+    return boxes;
+}
+
+// The display and white-space of an element, which is also how other passes over the document (e.g. minifying it) tell which of its whitespace is rendered
+pub(crate) fn element_display_and_white_space(element: &RefNode, style_sheets: &[Rc<RefCell<CSSStyleSheet>>], parent_white_space: WhiteSpace) -> (Display, WhiteSpace) {
+    let (local_name, hidden) = match &element.borrow().data {
+        NodeData::Element(element) => (element.local_name().to_string(), element.has_attribute("hidden")),
+        _ => (String::new(), false)
+    };
+    return display_and_white_space(&specified_values(element, style_sheets), &local_name, hidden, parent_white_space);
+}
+
+// The author's declarations win over the user agent's, which are the rendering the HTML standard suggests
+fn display_and_white_space(specified_values: &IndexMap<String, PropertyValue>, local_name: &str, hidden: bool, parent_white_space: WhiteSpace) -> (Display, WhiteSpace) {
+    // The initial value of display is inline
+    // TODO: display: inherit uses the user agent's display, as the display of the parent is not known here
+    let display = match specified_values.get("display") {
+        Some(PropertyValue::Display(display)) => *display,
+        Some(PropertyValue::CssWideKeyword(CssWideKeyword::Initial | CssWideKeyword::Unset)) => Display::Inline,
+        _ => default_display(local_name, hidden)
+    };
+    // https://drafts.csswg.org/css2/#dis-pos-flo
+    // An absolutely positioned box is a block, whatever its display
+    let display = match specified_values.get("position") {
+        Some(PropertyValue::Position(position)) if position.is_out_of_flow() && display == Display::Inline => Display::Block,
+        _ => display
+    };
+    // https://drafts.csswg.org/css-text-3/#white-space-property
+    // white-space is inherited, and its initial value is normal
+    let white_space = match specified_values.get("white-space") {
+        Some(PropertyValue::WhiteSpace(white_space)) => *white_space,
+        Some(PropertyValue::CssWideKeyword(CssWideKeyword::Initial)) => WhiteSpace::Normal,
+        Some(PropertyValue::CssWideKeyword(CssWideKeyword::Inherit | CssWideKeyword::Unset)) => parent_white_space,
+        _ => default_white_space(local_name).unwrap_or(parent_white_space)
+    };
+    return (display, white_space);
+}
+
+// https://html.spec.whatwg.org/multipage/rendering.html#hidden-elements
+// https://html.spec.whatwg.org/multipage/rendering.html#flow-content-3
+// The display of the user agent style sheet, for elements the author gives no display
+fn default_display(local_name: &str, hidden: bool) -> Display {
+    if hidden {
+        return Display::None;
+    }
+    match local_name {
+        "area" | "base" | "basefont" | "datalist" | "head" | "link" | "meta" | "noembed" | "noframes" | "param" | "rp"
+        | "script" | "style" | "template" | "title" => Display::None,
+        "html" | "body" | "address" | "blockquote" | "center" | "dialog" | "div" | "figure" | "figcaption" | "footer" | "form"
+        | "header" | "hr" | "legend" | "listing" | "main" | "p" | "plaintext" | "pre" | "search" | "xmp" | "article" | "aside"
+        | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "hgroup" | "nav" | "section" | "dir" | "dd" | "dl" | "dt" | "menu" | "ol" | "ul"
+        | "details" | "summary" | "fieldset" | "optgroup" | "table" | "caption" | "thead" | "tbody" | "tfoot" | "tr" | "td" | "th" => Display::Block,
+        "li" => Display::ListItem,
+        _ => Display::Inline
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/rendering.html#flow-content-3
+// The white-space of the user agent style sheet, None when the element inherits it
+fn default_white_space(local_name: &str) -> Option<WhiteSpace> {
+    match local_name {
+        "listing" | "plaintext" | "pre" | "xmp" => Some(WhiteSpace::Pre),
+        "textarea" => Some(WhiteSpace::PreWrap),
+        "nobr" => Some(WhiteSpace::Nowrap),
+        _ => None
+    }
+}
+
+// https://drafts.csswg.org/css-text-3/#white-space-phase-1
+// Collapses each run of spaces, tabs and (unless they are preserved) segment breaks into a single space
+// Collapsing across the boundaries of text nodes and removing the spaces at the start and end of lines is left to line breaking
+pub(crate) fn process_white_space(text: &str, white_space: WhiteSpace) -> String {
+    if white_space.preserves_spaces() {
+        // TODO: Tabs are expanded to eight spaces rather than to the next tab stop
+        return text.replace("\r\n", "\n").replace('\t', "        ");
+    }
+
+    let mut processed = String::new();
+    let mut previous_was_space = false;
+    for character in text.chars() {
+        match character {
+            '\n' if white_space == WhiteSpace::PreLine => {
+                // The spaces before a preserved segment break are removed
+                while processed.ends_with(' ') {
+                    processed.pop();
+                }
+                processed.push('\n');
+                previous_was_space = true;
+            },
+            ' ' | '\t' | '\n' | '\r' | '\u{C}' => {
+                if !previous_was_space {
+                    processed.push(' ');
+                }
+                previous_was_space = true;
+            },
+            _ => {
+                processed.push(character);
+                previous_was_space = false;
+            }
+        }
+    }
+    return processed;
+}
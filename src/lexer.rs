@@ -1,72 +1,180 @@
+#[cfg(feature = "fs")]
 use std::io::{BufReader, Read};
+#[cfg(feature = "fs")]
 use std::fs::File;
+use std::borrow::Cow;
+use std::rc::Rc;
+use crate::unicode::nfc;
 
 pub struct Lexer { 
     position: usize,
-    tokens: Vec<u8>,
-    pub tokens_length: usize
+    // Shared with the tokens whose text is a range of the input
+    tokens: Rc<Vec<u8>>,
+    pub tokens_length: usize,
+    // Whether the input is normalized to NFC, see normalize_to_nfc
+    normalize: bool,
 }
 
 impl Lexer { 
+    #[cfg(feature = "fs")]
     pub fn new(source: String) -> Self { 
+        return Lexer::from_file(&source).expect("File could not be read!");
+    }
 
-        let position = 0;
-        
-        let file = File::open(source.clone()).expect("File could not opened!");
+    // Reads the input from the file at path, an Err when it could not be opened or read
+    #[cfg(feature = "fs")]
+    pub fn from_file(path: &str) -> std::io::Result<Self> {
+        let file = File::open(path)?;
         let mut reader = BufReader::new(file);
 
         let mut tokens = Vec::new();
 
-        reader.read_to_end(&mut tokens).expect("File could not be read!");
+        reader.read_to_end(&mut tokens)?;
+
+        return Ok(Lexer::from_bytes(tokens));
+    }
+
+    pub fn from_string(source: String) -> Self { 
+        return Lexer::from_bytes(source.into_bytes());
+    }
 
+    // Any bytes are input, they are decoded as UTF-8 and a byte that is not part of a valid UTF-8 sequence is read as U+FFFD REPLACEMENT CHARACTER
+    pub fn from_bytes(tokens: Vec<u8>) -> Self {
         let tokens_length = tokens.len();
 
-        Self { position, tokens, tokens_length }
+        Self { position: 0, tokens: Rc::new(tokens), tokens_length, normalize: false }
     }
 
     pub fn peek(&mut self) -> Option<char> {
-        if self.position != self.tokens_length { 
-            let peeked_character = self.tokens[self.position] as char;
-            return Some(peeked_character);
-        } else { 
-            None
-        }
-      
+        return self.peek_ahead(0);
     }
 
     pub fn peekNext(&mut self) -> Option<char> { 
-        if self.position != self.tokens_length { 
-            let peeked_character = self.tokens[self.position + 1] as char;
-            return Some(peeked_character);
-        } else { 
-            None
+        return self.peek_ahead(1);
+    }
+
+    // Like peek, but looks `amount` characters further ahead without consuming anything
+    pub fn peek_ahead(&self, amount: usize) -> Option<char> {
+        let mut position = self.position;
+        for _ in 0..amount {
+            position += self.character_at(position)?.1;
         }
+        return self.character_at(position).map(|(character, _)| character);
     }
 
     pub fn advance(&mut self) { 
-        self.position += 1;
+        self.position += self.character_at(self.position).map_or(1, |(_, length)| length);
     }
 
     pub fn rewindAndPeek(&mut self, amount: usize) -> Option<char> { 
-        if self.position != self.tokens_length { 
-            let peeked_character = self.tokens[self.position - amount] as char;
-            return Some(peeked_character);
-        } else { 
-            None
+        let mut position = self.position;
+        for _ in 0..amount {
+            position = self.character_start_before(position)?;
         }
+        return self.character_at(position).map(|(character, _)| character);
     }
 
+    // Rewinding past the start of the input stops at the start
     pub fn rewind(&mut self, amount: usize) { 
-        self.position -= amount;
+        for _ in 0..amount {
+            match self.character_start_before(self.position) {
+                Some(position) => self.position = position,
+                None => break
+            }
+        }
     }
 
+    // The character that was consumed last, which is the last character of the input once it has all been consumed so it can still be reconsumed
     pub fn previous(&mut self) -> Option<char> {
-        if self.position != self.tokens_length { 
-            let peeked_character = self.tokens[self.position - 1] as char;
-            return Some(peeked_character);
-        } else { 
-            None
+        if self.position > self.tokens_length { 
+            return None;
+        }
+        let position = self.previous_position()?;
+        return self.character_at(position).map(|(character, _)| character);
+    }
+
+    // The index the character that was consumed last starts at, None before any character has been consumed
+    pub fn previous_position(&self) -> Option<usize> {
+        return self.character_start_before(self.position);
+    }
+
+    // https://encoding.spec.whatwg.org/#utf-8-decoder
+    // The character starting at position and how many bytes it takes up
+    // A byte that does not start a valid UTF-8 sequence is a U+FFFD REPLACEMENT CHARACTER on its own, so the input is never read past the bytes of one character
+    fn character_at(&self, position: usize) -> Option<(char, usize)> {
+        let lead_byte = *self.tokens.get(position)?;
+        let length = match lead_byte {
+            0x00..=0x7F => return Some((lead_byte as char, 1)),
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            _ => return Some((char::REPLACEMENT_CHARACTER, 1))
+        };
+        let bytes = self.tokens.get(position..position + length);
+        match bytes.and_then(|bytes| std::str::from_utf8(bytes).ok()).and_then(|text| text.chars().next()) {
+            Some(character) => return Some((character, length)),
+            None => return Some((char::REPLACEMENT_CHARACTER, 1))
+        }
+    }
+
+    // The index of the character that ends at position, stepping back over the bytes that continue a UTF-8 sequence
+    // The longest valid sequence that ends at position is the character, a byte on its own is only a character when none does
+    fn character_start_before(&self, position: usize) -> Option<usize> {
+        let position = position.min(self.tokens_length);
+        if self.tokens.get(position.checked_sub(1)?).is_some_and(|byte| byte.is_ascii()) {
+            return Some(position - 1);
+        }
+        for length in (2..=4.min(position)).rev() {
+            let start = position - length;
+            if let Some((_, character_length)) = self.character_at(start) {
+                if start + character_length == position {
+                    return Some(start);
+                }
+            }
+        }
+        return position.checked_sub(1);
+    }
+
+    // Adds input to the end, for input that arrives in chunks (e.g. from the network)
+    // The input is only copied when tokens still have ranges of it
+    // TODO: A combining character at the start of a chunk is not composed with the last character of the chunk before it when the input is normalized
+    pub fn append(&mut self, source: &str) {
+        let source = if self.normalize { nfc(source) } else { source.into() };
+        Rc::make_mut(&mut self.tokens).extend_from_slice(source.as_bytes());
+        self.tokens_length = self.tokens.len();
+    }
+
+    // https://www.unicode.org/reports/tr15/#Norm_Forms
+    // Normalizes the input that is not consumed yet, and the input appended from now on, to NFC, so text written with combining characters is the same as text written precomposed
+    // The HTML standard does not normalize its input, so this is only done when ParserOptions::normalize_unicode asks for it, and the source positions are of the normalized input
+    // Bytes that are not valid UTF-8 are decoded as U+FFFD REPLACEMENT CHARACTER first
+    pub fn normalize_to_nfc(&mut self) {
+        self.normalize = true;
+        let start = self.position.min(self.tokens_length);
+        let remaining = String::from_utf8_lossy(&self.tokens[start..]);
+        let normalized = nfc(&remaining);
+        // The input is left as it is when it is valid UTF-8 that is already normalized
+        if let (Cow::Borrowed(_), Cow::Borrowed(_)) = (&remaining, &normalized) {
+            return;
         }
-      
+        let mut tokens = self.tokens[..start].to_vec();
+        tokens.extend_from_slice(normalized.as_bytes());
+        self.tokens = Rc::new(tokens);
+        self.tokens_length = self.tokens.len();
     }
-}
\ No newline at end of file
+
+    // The number of bytes of the input that have not been consumed yet
+    pub fn remaining(&self) -> usize {
+        return self.tokens_length.saturating_sub(self.position);
+    }
+
+    // The input, for text that is a range of it
+    pub fn source(&self) -> &Rc<Vec<u8>> {
+        return &self.tokens;
+    }
+
+    // The index of the byte the next character to be consumed starts at
+    pub fn position(&self) -> usize {
+        return self.position;
+    }
+}
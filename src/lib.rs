@@ -1,5 +1,22 @@
+pub mod tokenizer;
+pub mod named_character_references;
+pub mod entities;
+pub mod html_token;
+pub mod token_sink;
+#[cfg(feature = "trace")]
+pub mod trace;
+#[cfg(feature = "trace")]
+pub mod replay;
+pub mod atom;
+pub mod token_text;
+pub mod source_location;
+pub mod parse_error;
+pub mod html_document_parser;
 pub mod lexer;
+pub mod unicode;
 pub mod node;
+pub mod microsyntaxes;
+pub mod srcset;
 pub mod comment;
 pub mod character_data;
 pub mod token;
@@ -7,4 +24,80 @@ pub mod scanner;
 pub mod ast;
 pub mod parser;
 pub mod ast_printer;
+pub mod optimizer;
 pub mod interpreter;
+pub mod gc;
+pub mod script_limits;
+pub mod tree_limits;
+pub mod selector;
+pub mod shared_document;
+pub mod sax;
+pub mod css_parser;
+pub mod css_values;
+pub mod css_variables;
+pub mod cssom;
+pub mod style;
+pub mod transitions;
+pub mod text;
+pub mod layout;
+pub mod counters;
+pub mod display_list;
+pub mod scrolling;
+pub mod svg_renderer;
+pub mod png_renderer;
+pub mod text_renderer;
+#[cfg(feature = "fs")]
+pub mod reftest;
+#[cfg(feature = "fs")]
+pub mod snapshot;
+#[cfg(feature = "differential")]
+pub mod differential;
+pub mod a11y;
+pub mod metadata;
+pub mod language;
+pub mod bidi;
+pub mod links;
+#[cfg(feature = "fs")]
+pub mod batch;
+pub mod ffi;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod markdown;
+pub mod dom_diff;
+pub mod serializer;
+pub mod document_stats;
+pub mod formatter;
+pub mod minifier;
+pub mod lint;
+pub mod webidl;
+pub mod dom_bindings;
+pub mod events;
+pub mod forms;
+pub mod mutation_observer;
+pub mod event_loop;
+pub mod timers;
+pub mod promise;
+pub mod fetch;
+pub mod resource_loader;
+#[cfg(feature = "fs")]
+pub mod http_cache;
+pub mod robots;
+#[cfg(feature = "net")]
+pub mod server;
+#[cfg(all(feature = "net", feature = "fs"))]
+pub mod preview;
+#[cfg(feature = "net")]
+pub mod tls;
+pub mod session;
+pub mod window;
+pub mod url;
+pub mod console;
+pub mod array;
+pub mod error;
+pub mod string;
+pub mod json;
+pub mod math;
+pub mod date;
+pub mod regexp;
+pub mod regexp_matcher;
+pub mod line_editor;
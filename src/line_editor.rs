@@ -0,0 +1,214 @@
+// A small line editor for the JS REPL with history and cursor movement, it needs no crates
+// The terminal is put into raw mode with stty, when stdin is not a terminal (e.g. a piped file) lines are read as they are
+// TODO: Lines longer than the width of the terminal are not redrawn correctly as wrapping is not handled
+
+use std::io::{IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
+
+pub enum ReadLine {
+    Line(String),
+    // Ctrl-C discards the line being edited
+    Interrupted,
+    // Ctrl-D on an empty line or the end of a piped input
+    Eof,
+}
+
+#[derive(Default)]
+pub struct LineEditor {
+    history: Vec<String>,
+}
+
+// Restores the terminal settings that were saved with stty -g when dropped
+struct RawMode {
+    saved_settings: String,
+}
+
+impl RawMode {
+    fn enable() -> Option<RawMode> {
+        let output = Command::new("stty").arg("-g").stdin(Stdio::inherit()).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let saved_settings = String::from_utf8(output.stdout).ok()?.trim().to_string();
+
+        // Characters are read one at a time without being echoed, Ctrl-C is read as a character instead of sending SIGINT
+        let status = Command::new("stty").args(["-icanon", "-echo", "-isig", "min", "1"]).stdin(Stdio::inherit()).status().ok()?;
+        if !status.success() {
+            return None;
+        }
+
+        return Some(RawMode { saved_settings });
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = Command::new("stty").arg(&self.saved_settings).stdin(Stdio::inherit()).status();
+    }
+}
+
+impl LineEditor {
+    pub fn new() -> LineEditor {
+        LineEditor { history: Vec::new() }
+    }
+
+    pub fn add_history(&mut self, line: &str) {
+        if line.trim().is_empty() || self.history.last().map(String::as_str) == Some(line) {
+            return;
+        }
+        self.history.push(line.to_string());
+    }
+
+    pub fn read_line(&mut self, prompt: &str) -> ReadLine {
+        if std::io::stdin().is_terminal() {
+            if let Some(_raw_mode) = RawMode::enable() {
+                return self.read_line_raw(prompt);
+            }
+        }
+
+        print!("{}", prompt);
+        std::io::stdout().flush().unwrap();
+
+        let mut line = String::new();
+        match std::io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => ReadLine::Eof,
+            Ok(_) => ReadLine::Line(line.trim_end_matches(['\n', '\r']).to_string())
+        }
+    }
+
+    fn read_line_raw(&mut self, prompt: &str) -> ReadLine {
+        let mut line: Vec<char> = Vec::new();
+        let mut cursor = 0;
+
+        // Browsing the history with the arrow keys keeps the line being edited, so it can be returned to
+        let mut history_index = self.history.len();
+        let mut edited_line: Vec<char> = Vec::new();
+
+        LineEditor::refresh(prompt, &line, cursor);
+        loop {
+            let byte = match LineEditor::read_byte() {
+                Some(byte) => byte,
+                None => {
+                    print!("\r\n");
+                    return ReadLine::Eof;
+                }
+            };
+
+            match byte {
+                b'\r' | b'\n' => {
+                    print!("\r\n");
+                    std::io::stdout().flush().unwrap();
+                    return ReadLine::Line(line.iter().collect());
+                },
+                // Ctrl-C
+                3 => {
+                    print!("^C\r\n");
+                    std::io::stdout().flush().unwrap();
+                    return ReadLine::Interrupted;
+                },
+                // Ctrl-D
+                4 => {
+                    if line.is_empty() {
+                        print!("\r\n");
+                        return ReadLine::Eof;
+                    }
+                    if cursor < line.len() {
+                        line.remove(cursor);
+                    }
+                },
+                // Ctrl-A and Ctrl-E
+                1 => cursor = 0,
+                5 => cursor = line.len(),
+                // Backspace
+                8 | 127 => {
+                    if cursor > 0 {
+                        cursor -= 1;
+                        line.remove(cursor);
+                    }
+                },
+                // Escape sequences of the arrow, home, end and delete keys
+                27 => {
+                    if LineEditor::read_byte() != Some(b'[') {
+                        continue;
+                    }
+                    match LineEditor::read_byte() {
+                        // Up
+                        Some(b'A') if history_index > 0 => {
+                            if history_index == self.history.len() {
+                                edited_line = line.clone();
+                            }
+                            history_index -= 1;
+                            line = self.history[history_index].chars().collect();
+                            cursor = line.len();
+                        },
+                        // Down
+                        Some(b'B') if history_index < self.history.len() => {
+                            history_index += 1;
+                            line = if history_index == self.history.len() { edited_line.clone() } else { self.history[history_index].chars().collect() };
+                            cursor = line.len();
+                        },
+                        // Right
+                        Some(b'C') => cursor = (cursor + 1).min(line.len()),
+                        // Left
+                        Some(b'D') => cursor = cursor.saturating_sub(1),
+                        Some(b'H') => cursor = 0,
+                        Some(b'F') => cursor = line.len(),
+                        // Delete is ESC [ 3 ~
+                        Some(b'3') if LineEditor::read_byte() == Some(b'~') && cursor < line.len() => {
+                            line.remove(cursor);
+                        },
+                        _ => {}
+                    }
+                },
+                // Other control characters are ignored
+                0..=31 => {},
+                _ => {
+                    if let Some(character) = LineEditor::read_utf8_character(byte) {
+                        line.insert(cursor, character);
+                        cursor += 1;
+                    }
+                }
+            }
+
+            LineEditor::refresh(prompt, &line, cursor);
+        }
+    }
+
+    // Redraws the prompt and line, then moves the terminal cursor to the cursor in the line
+    fn refresh(prompt: &str, line: &[char], cursor: usize) {
+        let text: String = line.iter().collect();
+        print!("\r{}{}\x1b[K\r", prompt, text);
+
+        let column = prompt.chars().count() + cursor;
+        if column > 0 {
+            print!("\x1b[{}C", column);
+        }
+        std::io::stdout().flush().unwrap();
+    }
+
+    fn read_byte() -> Option<u8> {
+        let mut buffer = [0u8; 1];
+        match std::io::stdin().read(&mut buffer) {
+            Ok(1) => Some(buffer[0]),
+            _ => None
+        }
+    }
+
+    // Reads the continuation bytes of a character whose first byte has been read
+    fn read_utf8_character(first_byte: u8) -> Option<char> {
+        let length = match first_byte {
+            0x00..=0x7F => 1,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => return None
+        };
+
+        let mut bytes = vec![first_byte];
+        for _ in 1..length {
+            bytes.push(LineEditor::read_byte()?);
+        }
+
+        return std::str::from_utf8(&bytes).ok()?.chars().next();
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+use crate::html_document_parser::ParserOptions;
+use crate::metadata::{refresh, Refresh};
+use crate::node::{descendants, encoding_parse_url, text_content, NodeData, RefNode};
+use crate::resource_loader::ResourceLoader;
+use crate::robots::RobotsTxt;
+use crate::tokenizer::Tokenizer;
+use crate::url::Url;
+
+// The hyperlinks and external resource links of a document, with their URLs resolved against the document base URL
+// A crawler fetches a page and reads the links out of it without running its scripts
+
+// https://html.spec.whatwg.org/multipage/links.html#hyperlink
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    pub url: Url,
+    // The local name of the element the link is from, a, area or link
+    pub element: String,
+    // https://html.spec.whatwg.org/multipage/links.html#linkTypes
+    // The link types of the rel attribute in ASCII lowercase, e.g. nofollow or stylesheet
+    pub rel: Vec<String>,
+    // The text of an a element, the alt of an area element and the title of a link element, with whitespace stripped and collapsed
+    pub text: String,
+}
+
+// https://html.spec.whatwg.org/multipage/links.html#links-created-by-a-and-area-elements
+// The links of the a, area and link elements of document that have an href attribute, in tree order
+// An href that is not a valid URL is not a link
+pub fn links(document: &RefNode) -> Vec<Link> {
+    let mut links = Vec::new();
+    for node in descendants(document) {
+        let (element, href, rel, label) = match &node.borrow().data {
+            NodeData::Element(element) => {
+                let label = match element.local_name().as_str() {
+                    "a" => None,
+                    "area" => element.get_attribute("alt").cloned(),
+                    "link" => element.get_attribute("title").cloned(),
+                    _ => continue
+                };
+                let href = match element.get_attribute("href") {
+                    Some(href) => href.clone(),
+                    None => continue
+                };
+                (element.local_name().to_string(), href, element.get_attribute("rel").cloned().unwrap_or_default(), label)
+            },
+            _ => continue
+        };
+
+        let url = match encoding_parse_url(&node, &href) {
+            Ok(url) => url,
+            Err(_) => continue
+        };
+        let text = label.or_else(|| text_content(&node)).unwrap_or_default();
+        links.push(Link {
+            url,
+            element,
+            rel: rel.split_ascii_whitespace().map(|link_type| link_type.to_ascii_lowercase()).collect(),
+            text: text.split_ascii_whitespace().collect::<Vec<&str>>().join(" "),
+        });
+    }
+    return links;
+}
+
+// Fetches the page at url with loader and returns its links, an Err when the page could not be loaded
+// The page is parsed with scripting disabled, so only the links in its markup are found
+// TODO: The body is decoded as UTF-8 whatever the Content-Type says
+pub fn crawl(url: &Url, loader: &mut dyn ResourceLoader) -> Result<Vec<Link>, String> {
+    return Ok(links(&load_document(url, loader)?));
+}
+
+// The document of the page at url, parsed with scripting disabled
+fn load_document(url: &Url, loader: &mut dyn ResourceLoader) -> Result<RefNode, String> {
+    let response = loader.load(url)?;
+
+    // Links are resolved against the URL the page was loaded from
+    let document_url = Url::parse(&response.url, None).unwrap_or_else(|_| url.clone());
+    // The page is untrusted input, so it is parsed within the default tree limits and its parse errors are not printed
+    let mut tokenizer = Tokenizer::from_bytes(response.body, ParserOptions { scripting_enabled: false, print_parse_errors: false, ..ParserOptions::default() });
+    tokenizer.set_document_url(document_url);
+    tokenizer.run();
+    return Ok(tokenizer.document());
+}
+
+// The limits a crawl that follows links from page to page keeps to, so it does not overload the sites it visits
+#[derive(Debug, Clone)]
+pub struct CrawlOptions {
+    // How many links away from the start page a page may be, 0 only loads the start page
+    pub max_depth: usize,
+    // How many pages are loaded at most, the pages robots.txt disallows are not counted
+    pub max_pages: usize,
+    // The least time between two requests to the same host, a longer Crawl-delay in its robots.txt is used instead
+    pub delay: Duration,
+    // The product token robots.txt rules are matched against
+    pub user_agent: String,
+    pub obey_robots_txt: bool,
+    // Whether the URL of a <meta http-equiv="refresh"> is followed as if it were a link, whatever its time
+    pub follow_refresh: bool,
+}
+
+impl Default for CrawlOptions {
+    fn default() -> Self {
+        CrawlOptions { max_depth: 0, max_pages: 100, delay: Duration::from_secs(1), user_agent: String::from("web_engine"), obey_robots_txt: true, follow_refresh: false }
+    }
+}
+
+// A page the crawl came to, with its links or why they could not be read
+#[derive(Debug, Clone)]
+pub struct CrawledPage {
+    pub url: Url,
+    // How many links away from the start page it is
+    pub depth: usize,
+    pub links: Result<Vec<Link>, String>,
+    // Where the page's <meta http-equiv="refresh"> navigates to, see metadata::refresh
+    pub refresh: Option<Refresh>,
+}
+
+// Keeps the time of the last request to each host and the robots.txt of each origin, or why it could not be loaded
+struct Politeness {
+    last_request: HashMap<String, Instant>,
+    robots_txts: HashMap<String, Result<RobotsTxt, String>>,
+}
+
+impl Politeness {
+    // Waits until the delay since the last request to the host of url has passed, file: URLs are never waited for
+    fn wait_for_turn(&mut self, url: &Url, delay: Duration) {
+        if url.scheme == "file" {
+            return;
+        }
+        let host = url.origin();
+        if let Some(last_request) = self.last_request.get(&host) {
+            let elapsed = last_request.elapsed();
+            if elapsed < delay {
+                std::thread::sleep(delay - elapsed);
+            }
+        }
+        self.last_request.insert(host, Instant::now());
+    }
+
+    // https://www.rfc-editor.org/rfc/rfc9309.html#section-2.3
+    // The robots.txt of the origin of url, loaded the first time a page of the origin is crawled
+    // An Err is a robots.txt that could not be loaded, and none of the pages of the origin may be crawled
+    fn robots_txt(&mut self, url: &Url, loader: &mut dyn ResourceLoader, options: &CrawlOptions) -> Result<RobotsTxt, String> {
+        if !matches!(url.scheme.as_str(), "http" | "https") {
+            return Ok(RobotsTxt::allow_all());
+        }
+        let origin = url.origin();
+        if let Some(robots_txt) = self.robots_txts.get(&origin) {
+            return robots_txt.clone();
+        }
+
+        let robots_txt = match Url::parse("/robots.txt", Some(url)) {
+            Ok(robots_url) => {
+                self.wait_for_turn(&robots_url, options.delay);
+                match loader.load(&robots_url) {
+                    Ok(response) if (200..=299).contains(&response.status) => Ok(RobotsTxt::parse(&String::from_utf8_lossy(&response.body))),
+                    // If the server responds with a status code in the 400-499 range, the crawler may access any resources on the server
+                    Ok(response) if (400..=499).contains(&response.status) => Ok(RobotsTxt::allow_all()),
+                    // If the server or network is unreachable, the crawler must assume complete disallow
+                    Ok(response) => Err(format!("{} could not be loaded: {} {}", robots_url, response.status, response.status_message)),
+                    Err(error) => Err(format!("{} could not be loaded: {}", robots_url, error))
+                }
+            },
+            Err(_) => Ok(RobotsTxt::allow_all())
+        };
+        self.robots_txts.insert(origin, robots_txt.clone());
+        return robots_txt;
+    }
+}
+
+// Crawls from the page at start breadth first, following the hyperlinks of a and area elements that are not rel=nofollow,
+// and returns the pages in the order they were crawled
+// Each URL is crawled once, without its fragment, and only http:, https: and file: links are followed
+pub fn crawl_site(start: &Url, loader: &mut dyn ResourceLoader, options: &CrawlOptions) -> Vec<CrawledPage> {
+    let mut politeness = Politeness { last_request: HashMap::new(), robots_txts: HashMap::new() };
+    let mut pages = Vec::new();
+    let mut loaded_pages = 0;
+
+    let mut start = start.clone();
+    start.fragment = None;
+    let mut seen: HashSet<String> = HashSet::from([start.href()]);
+    let mut queue: VecDeque<(Url, usize)> = VecDeque::from([(start, 0)]);
+
+    while let Some((url, depth)) = queue.pop_front() {
+        if loaded_pages >= options.max_pages {
+            break;
+        }
+
+        let delay = if options.obey_robots_txt {
+            let robots_txt = match politeness.robots_txt(&url, loader, options) {
+                Ok(robots_txt) => robots_txt,
+                Err(error) => {
+                    pages.push(CrawledPage { url, depth, links: Err(error), refresh: None });
+                    continue;
+                }
+            };
+            let mut path = url.serialize_path();
+            if let Some(query) = &url.query {
+                path.push('?');
+                path.push_str(query);
+            }
+            if !robots_txt.is_allowed(&options.user_agent, &path) {
+                pages.push(CrawledPage { url, depth, links: Err(String::from("Disallowed by robots.txt")), refresh: None });
+                continue;
+            }
+            robots_txt.crawl_delay(&options.user_agent).map_or(options.delay, |crawl_delay| crawl_delay.max(options.delay))
+        } else {
+            options.delay
+        };
+
+        politeness.wait_for_turn(&url, delay);
+        loaded_pages += 1;
+        let document = load_document(&url, loader);
+        let refresh = document.as_ref().ok().and_then(refresh);
+        let links = document.map(|document| links(&document));
+        if let (Ok(links), true) = (&links, depth < options.max_depth) {
+            let link_urls = links.iter().filter(|link| matches!(link.element.as_str(), "a" | "area") && !link.rel.iter().any(|link_type| link_type == "nofollow")).map(|link| &link.url);
+            let refresh_url = refresh.iter().filter(|_| options.follow_refresh).map(|refresh| &refresh.url);
+            for next_url in link_urls.chain(refresh_url) {
+                if !matches!(next_url.scheme.as_str(), "http" | "https" | "file") {
+                    continue;
+                }
+                let mut next = next_url.clone();
+                next.fragment = None;
+                if seen.insert(next.href()) {
+                    queue.push_back((next, depth + 1));
+                }
+            }
+        }
+        pages.push(CrawledPage { url, depth, links, refresh });
+    }
+    return pages;
+}
@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+use std::fmt;
+use serde::Serialize;
+use crate::html_document_parser::ParserOptions;
+use crate::html_token::{HtmlToken, HtmlTokenType};
+use crate::node::{descendants, NodeData, RefNode};
+use crate::serializer::is_void_element;
+use crate::source_location::SourcePosition;
+use crate::tokenizer::Tokenizer;
+
+// Checks an HTML document for problems, both the parse errors of the input and problems a valid document can still have (e.g. an image with no alt text)
+// Each problem is found by a rule, the linter runs its rules over a document and reports what they find at the severity of the rule
+// The rules a linter starts with are the ones below, others can be added by implementing Rule
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error")
+        }
+    }
+}
+
+impl Severity {
+    pub fn parse(severity: &str) -> Option<Severity> {
+        return match severity {
+            "info" => Some(Severity::Info),
+            "warning" => Some(Severity::Warning),
+            "error" => Some(Severity::Error),
+            _ => None
+        };
+    }
+}
+
+// A problem a rule found, at the position in the input it was found at
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub message: String,
+    pub position: Option<SourcePosition>,
+}
+
+// A finding as it is reported, with the rule that found it and its severity
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    pub position: Option<SourcePosition>,
+}
+
+// What the rules check, the input is parsed once for all of them
+pub struct LintContext {
+    // The document as it was parsed, before any of its scripts have run
+    pub document: RefNode,
+    // The parse errors in the order they were found, with where they were found
+    pub parse_errors: Vec<(String, Option<SourcePosition>)>,
+    // The tokens of the input, for rules about the markup itself rather than the document it builds (e.g. tags that were not closed)
+    pub tokens: Vec<HtmlToken>,
+}
+
+impl LintContext {
+    pub fn new(html: &[u8]) -> LintContext {
+        let mut tokenizer = Tokenizer::from_bytes(html.to_vec(), lint_parser_options());
+        tokenizer.run();
+        let parse_errors = tokenizer.parse_errors().iter().cloned().zip(tokenizer.parse_error_positions().iter().copied()).collect();
+        let tokens = Tokenizer::from_bytes(html.to_vec(), lint_parser_options()).collect();
+        return LintContext { document: tokenizer.document(), parse_errors, tokens };
+    }
+
+    // The elements of the document in tree order
+    pub fn elements(&self) -> Vec<RefNode> {
+        return descendants(&self.document).into_iter().filter(|node| matches!(node.borrow().data, NodeData::Element(_))).collect();
+    }
+}
+
+fn lint_parser_options() -> ParserOptions {
+    return ParserOptions { scripting_enabled: false, print_parse_errors: false, ..ParserOptions::default() };
+}
+
+pub trait Rule {
+    // The name the rule is reported as and turned off by, e.g. "duplicate-id"
+    fn name(&self) -> &'static str;
+
+    // The severity of what the rule finds unless the linter is told otherwise
+    fn default_severity(&self) -> Severity;
+
+    fn check(&self, context: &LintContext) -> Vec<Finding>;
+}
+
+pub struct Linter {
+    rules: Vec<(Box<dyn Rule>, Severity)>,
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        return Linter::new();
+    }
+}
+
+impl Linter {
+    // A linter with the built in rules
+    pub fn new() -> Linter {
+        let mut linter = Linter::empty();
+        linter.add_rule(Box::new(ParseErrors));
+        linter.add_rule(Box::new(MissingAlt));
+        linter.add_rule(Box::new(DuplicateId));
+        linter.add_rule(Box::new(UnclosedTag));
+        linter.add_rule(Box::new(DeprecatedElement));
+        return linter;
+    }
+
+    pub fn empty() -> Linter {
+        return Linter { rules: Vec::new() };
+    }
+
+    pub fn add_rule(&mut self, rule: Box<dyn Rule>) {
+        let severity = rule.default_severity();
+        self.rules.push((rule, severity));
+    }
+
+    pub fn rule_names(&self) -> Vec<&'static str> {
+        return self.rules.iter().map(|(rule, _)| rule.name()).collect();
+    }
+
+    // Turns off the rule with the given name, false when there is no such rule
+    pub fn disable(&mut self, name: &str) -> bool {
+        let rule_count = self.rules.len();
+        self.rules.retain(|(rule, _)| rule.name() != name);
+        return self.rules.len() != rule_count;
+    }
+
+    // Reports what the rule with the given name finds at severity instead of at its default severity, false when there is no such rule
+    pub fn set_severity(&mut self, name: &str, severity: Severity) -> bool {
+        match self.rules.iter_mut().find(|(rule, _)| rule.name() == name) {
+            Some((_, rule_severity)) => {
+                *rule_severity = severity;
+                return true;
+            },
+            None => return false
+        }
+    }
+
+    pub fn lint(&self, html: &[u8]) -> Vec<Diagnostic> {
+        return self.lint_context(&LintContext::new(html));
+    }
+
+    // The diagnostics of every rule in the order of where they are in the input, those with no position last
+    pub fn lint_context(&self, context: &LintContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for (rule, severity) in &self.rules {
+            for finding in rule.check(context) {
+                diagnostics.push(Diagnostic { rule: rule.name(), severity: *severity, message: finding.message, position: finding.position });
+            }
+        }
+        diagnostics.sort_by_key(|diagnostic| diagnostic.position.map_or(usize::MAX, |position| position.offset));
+        return diagnostics;
+    }
+}
+
+// Each diagnostic on a line of its own, like a compiler reports errors, e.g. page.html:3:5: error: <img> has no alt attribute (missing-alt)
+pub fn report_text(path: &str, diagnostics: &[Diagnostic]) -> String {
+    let mut report = String::new();
+    for diagnostic in diagnostics {
+        match diagnostic.position {
+            Some(position) => report.push_str(&format!("{}:{}: ", path, position)),
+            None => report.push_str(&format!("{}: ", path))
+        }
+        report.push_str(&format!("{}: {} ({})\n", diagnostic.severity, diagnostic.message, diagnostic.rule));
+    }
+    return report;
+}
+
+pub fn report_json(diagnostics: &[Diagnostic]) -> String {
+    return serde_json::to_string_pretty(diagnostics).unwrap_or_default();
+}
+
+fn start_position(node: &RefNode) -> Option<SourcePosition> {
+    return node.borrow().source_location().map(|source_location| source_location.start);
+}
+
+// https://html.spec.whatwg.org/multipage/parsing.html#parse-errors
+// The parse errors of the tokenizer and the tree builder
+struct ParseErrors;
+
+impl Rule for ParseErrors {
+    fn name(&self) -> &'static str {
+        return "parse-error";
+    }
+
+    fn default_severity(&self) -> Severity {
+        return Severity::Error;
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        return context.parse_errors.iter().map(|(message, position)| Finding { message: message.to_string(), position: *position }).collect();
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/images.html#alt
+// Images with no text alternative, for users who can not see them
+struct MissingAlt;
+
+impl Rule for MissingAlt {
+    fn name(&self) -> &'static str {
+        return "missing-alt";
+    }
+
+    fn default_severity(&self) -> Severity {
+        return Severity::Error;
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for element_node in context.elements() {
+            if let NodeData::Element(element) = &element_node.borrow().data {
+                let needs_alt = match element.local_name().as_str() {
+                    "img" => true,
+                    "area" => element.has_attribute("href"),
+                    "input" => element.get_attribute("type").is_some_and(|input_type| input_type.eq_ignore_ascii_case("image")),
+                    _ => false
+                };
+                if needs_alt && !element.has_attribute("alt") {
+                    findings.push(Finding { message: format!("<{}> has no alt attribute", element.local_name()), position: start_position(&element_node) });
+                }
+            }
+        }
+        return findings;
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/dom.html#the-id-attribute
+// The value must be unique amongst all the IDs in the element's tree
+struct DuplicateId;
+
+impl Rule for DuplicateId {
+    fn name(&self) -> &'static str {
+        return "duplicate-id";
+    }
+
+    fn default_severity(&self) -> Severity {
+        return Severity::Error;
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let mut first_positions: HashMap<String, Option<SourcePosition>> = HashMap::new();
+        for element_node in context.elements() {
+            let id = match &element_node.borrow().data {
+                NodeData::Element(element) => element.get_attribute("id").cloned(),
+                _ => None
+            };
+            let Some(id) = id else { continue };
+            let position = start_position(&element_node);
+            match first_positions.get(&id) {
+                Some(Some(first_position)) => findings.push(Finding { message: format!("The id \"{}\" is already used at {}", id, first_position), position }),
+                Some(None) => findings.push(Finding { message: format!("The id \"{}\" is already used", id), position }),
+                None => {
+                    first_positions.insert(id, position);
+                }
+            }
+        }
+        return findings;
+    }
+}
+
+// Elements whose end tag is missing, where the end tag can not be left out
+// The parser closes them where it can, which is often not where the author meant them to end
+struct UnclosedTag;
+
+impl Rule for UnclosedTag {
+    fn name(&self) -> &'static str {
+        return "unclosed-tag";
+    }
+
+    fn default_severity(&self) -> Severity {
+        return Severity::Warning;
+    }
+
+    // The start tags are matched with the end tags of the same name like a stack, the tags a matching end tag closes on its way down the stack were not closed
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let mut open_tags: Vec<(String, Option<SourcePosition>)> = Vec::new();
+        let mut report_unclosed = |open_tags: &mut Vec<(String, Option<SourcePosition>)>, length: usize| {
+            for (tag_name, position) in open_tags.drain(length..) {
+                if !end_tag_can_be_omitted(&tag_name) {
+                    findings.push(Finding { message: format!("<{}> is not closed", tag_name), position });
+                }
+            }
+        };
+
+        for html_token in &context.tokens {
+            let position = html_token.source_location.map(|source_location| source_location.start);
+            match html_token.token_type {
+                HtmlTokenType::StartTag if !html_token.self_closing && !is_void_element(&html_token.tag_name) && html_token.tag_name.as_str() != "plaintext" => {
+                    open_tags.push((html_token.tag_name.to_string(), position));
+                },
+                HtmlTokenType::EndTag => {
+                    if let Some(index) = open_tags.iter().rposition(|(tag_name, _)| *tag_name == *html_token.tag_name) {
+                        report_unclosed(&mut open_tags, index + 1);
+                        open_tags.pop();
+                    }
+                },
+                HtmlTokenType::EndOfFile => report_unclosed(&mut open_tags, 0),
+                _ => ()
+            }
+        }
+        return findings;
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/syntax.html#optional-tags
+fn end_tag_can_be_omitted(tag_name: &str) -> bool {
+    return matches!(tag_name, "html" | "head" | "body" | "li" | "dt" | "dd" | "p" | "rt" | "rp" | "optgroup" | "option" | "colgroup" | "caption" | "thead" | "tbody" | "tfoot" | "tr" | "td" | "th");
+}
+
+// https://html.spec.whatwg.org/multipage/obsolete.html#non-conforming-features
+// Elements that are entirely obsolete and must not be used by authors
+struct DeprecatedElement;
+
+impl Rule for DeprecatedElement {
+    fn name(&self) -> &'static str {
+        return "deprecated-element";
+    }
+
+    fn default_severity(&self) -> Severity {
+        return Severity::Warning;
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for element_node in context.elements() {
+            let position = start_position(&element_node);
+            if let NodeData::Element(element) = &element_node.borrow().data {
+                // Only elements written in the input, not those the parser created
+                if position.is_some() && is_obsolete_element(element.local_name()) {
+                    findings.push(Finding { message: format!("<{}> is obsolete", element.local_name()), position });
+                }
+            }
+        }
+        return findings;
+    }
+}
+
+fn is_obsolete_element(local_name: &str) -> bool {
+    return matches!(local_name,
+        "applet" | "acronym" | "bgsound" | "dir" | "frame" | "frameset" | "noframes" | "isindex" | "keygen" | "listing" | "menuitem" | "nextid" | "noembed" | "plaintext" | "rb" | "rtc" | "strike" | "xmp"
+        | "basefont" | "big" | "blink" | "center" | "font" | "marquee" | "multicol" | "nobr" | "spacer" | "tt");
+}
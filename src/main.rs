@@ -1,36 +1,1281 @@
-use std::{env, borrow::Borrow};
-use std::ops::Deref;
-use web_engine::node::{Node, NodeData};
+mod cli;
+
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::time::{Duration, Instant};
+use web_engine::ast::Accept;
+use web_engine::ast_printer::ASTPrettyPrinter;
+use web_engine::batch;
+use web_engine::console::{LogLevel, Logger};
+use web_engine::display_list::{build_display_list, build_viewport_display_list};
+#[cfg(feature = "differential")]
+use web_engine::differential;
+use web_engine::dom_diff::{self, DiffOptions};
+use web_engine::formatter::{self, AttributeOrder, FormatOptions};
+use web_engine::html_document_parser::ParserOptions;
+use web_engine::html_token::{HtmlToken, HtmlTokenType};
+use web_engine::http_cache::{CachingResourceLoader, DirectoryCacheStorage};
 use web_engine::interpreter::Interpreter;
+use web_engine::line_editor::{LineEditor, ReadLine};
+use web_engine::links::{self, CrawlOptions};
+use web_engine::lint::{self, Linter, Severity};
+use web_engine::markdown;
+use web_engine::minifier::{self, MinifyOptions};
+use web_engine::preview::{self, PreviewOptions};
+#[cfg(feature = "trace")]
+use web_engine::replay::ParseSession;
+use web_engine::reftest::{self, Outcome, Relation};
+use web_engine::snapshot;
+use web_engine::script_limits::ScriptLimits;
+use web_engine::tree_limits::TreeLimits;
+use web_engine::resource_loader::{DefaultResourceLoader, ResourceLoader};
+use web_engine::serializer::{serialize_as_json, serialize_children};
+use web_engine::server::{self, RequestLog, ServerOptions};
+use web_engine::scrolling::{scroll_viewport, viewport_scroll_position, ScrollPosition};
+use web_engine::session::Session;
+use web_engine::svg_renderer;
+use web_engine::text::BuiltinFontMetrics;
+use web_engine::text_renderer::ExtractTextOptions;
+use web_engine::tokenizer::Tokenizer;
+use web_engine::url::Url;
+use web_engine::window::Viewport;
+use cli::{read_input, write_output, Arguments, Command, OptionSpec, Verbosity, OUTPUT_OPTION, OUTPUT_OPTIONS};
 
-mod tokenizer;
-mod html_token;
-mod lexer;
-mod parse_error;
-mod node;
-mod comment;
-mod character_data;
-mod html_document_parser;
+// How a page is loaded before it is dumped or rendered
+const PAGE_OPTIONS: &[OptionSpec] = &[
+    // Script elements are still parsed into the document but their contents are not run
+    OptionSpec { name: "animation-time", short: None, value: Some("MS"), help: "Shows the page as it is once its transitions have run for MS milliseconds longer" },
+    OptionSpec { name: "disable-scripting", short: None, value: None, help: "Does not run the scripts of the page" },
+    OptionSpec { name: "event", short: None, value: Some("TYPE[@SELECTOR]"), help: "Fires an event once the page has loaded, at the first element matching the selector, e.g. click@#submit" },
+    OptionSpec { name: "max-time", short: None, value: Some("MS"), help: "How long the event loop may run for, until there are no tasks left otherwise" },
+    OptionSpec { name: "trace-tree-builder", short: None, value: None, help: "Prints each token the tree builder processes on stderr, with its insertion mode and the stack of open elements. Needs the trace feature" },
+    OptionSpec { name: "viewport", short: None, value: Some("WxH"), help: "The size of the viewport in CSS pixels, e.g. 1280x720" },
+];
 
+// How much the scripts that are run may do before they are stopped with a RangeError, for running scripts that are not trusted
+const SCRIPT_LIMIT_OPTIONS: &[OptionSpec] = &[
+    OptionSpec { name: "max-steps", short: None, value: Some("N"), help: "The number of statements and expressions a script or task may evaluate" },
+    OptionSpec { name: "max-script-time", short: None, value: Some("MS"), help: "How long a script or task may run for" },
+    OptionSpec { name: "max-heap-objects", short: None, value: Some("N"), help: "The number of objects scripts may have alive at once" },
+    OptionSpec { name: "max-heap-size", short: None, value: Some("BYTES"), help: "The number of bytes the objects and strings scripts can reach may take up" },
+    OptionSpec { name: "max-call-depth", short: None, value: Some("N"), help: "The number of calls that may be on the stack at once, 1000 by default" },
+];
 
-fn main() {
-    let mut source_html_file_path: String = String::from("");
+// The tree the parser may build, see tree_limits.rs
+const TREE_LIMIT_OPTIONS: &[OptionSpec] = &[
+    OptionSpec { name: "max-tree-depth", short: None, value: Some("N"), help: "The number of ancestors an element may have (default 512), deeper documents can overflow the stack when they are rendered" },
+    OptionSpec { name: "max-nodes", short: None, value: Some("N"), help: "The number of nodes the parser may create" },
+    OptionSpec { name: "max-attributes", short: None, value: Some("N"), help: "The number of attributes an element may have" },
+    OptionSpec { name: "max-attribute-length", short: None, value: Some("BYTES"), help: "The length an attribute value may have" },
+];
+
+// How resources are loaded over the network
+const NETWORK_OPTIONS: &[OptionSpec] = &[
+    // The cache is given as a directory, which is created when the first response is stored in it
+    OptionSpec { name: "cache-dir", short: None, value: Some("DIR"), help: "Caches responses in DIR in between runs" },
+    OptionSpec { name: "insecure", short: None, value: None, help: "Accepts any certificate from https: servers" },
+    OptionSpec { name: "timeout", short: None, value: Some("MS"), help: "How long connecting and each read may take" },
+];
+
+const COLUMNS_OPTION: OptionSpec = OptionSpec { name: "columns", short: None, value: Some("N"), help: "The width text is rendered at, 80 by default" };
+
+static PARSE: Command = Command {
+    name: "parse",
+    paths: "[PATH...]",
+    about: "Parses HTML files, every HTML file in directories or stdin, and reports their parse errors. Scripts are not run.",
+    formats: &["text", "json"],
+    options: &[OUTPUT_OPTIONS, &[
+        OptionSpec { name: "jobs", short: Some('j'), value: Some("N"), help: "The number of threads files are parsed with" },
+        OptionSpec { name: "stats", short: None, value: None, help: "Prints the node counts, text bytes and estimated heap usage of each document" },
+    ], TREE_LIMIT_OPTIONS],
+};
+
+static TOKENS: Command = Command {
+    name: "tokens",
+    paths: "[PATH]",
+    about: "Prints the tokens the tokenizer emits for an HTML file or stdin.",
+    formats: &["text", "json"],
+    options: &[OUTPUT_OPTIONS],
+};
+
+static TRACE: Command = Command {
+    name: "trace",
+    paths: "[PATH]",
+    about: "Prints the tokenizer states, tokens and insertion modes an HTML file is parsed through, as a timeline, as Chrome trace JSON or as a session to replay. Scripts are not run. Needs the trace feature.",
+    formats: &["timeline", "chrome", "session"],
+    options: &[OUTPUT_OPTIONS],
+};
+
+static REPLAY: Command = Command {
+    name: "replay",
+    paths: "[SESSION]",
+    about: "Parses the input of a session written by trace --format session again, and reports where the parse differs from the recorded one. Needs the trace feature.",
+    formats: &["text", "tree"],
+    options: &[OUTPUT_OPTIONS],
+};
+
+static DUMP: Command = Command {
+    name: "dump",
+    paths: "[PATH]",
+    about: "Parses an HTML file or stdin, runs its scripts and events, and prints the document.",
+    formats: &["tree", "html", "json", "a11y", "markdown"],
+    options: &[OUTPUT_OPTIONS, PAGE_OPTIONS, SCRIPT_LIMIT_OPTIONS, TREE_LIMIT_OPTIONS, NETWORK_OPTIONS],
+};
+
+static RENDER: Command = Command {
+    name: "render",
+    paths: "[PATH]",
+    about: "Parses an HTML file or stdin, runs its scripts and events, and renders the document.",
+    formats: &["text", "svg", "display-list"],
+    options: &[OUTPUT_OPTIONS, PAGE_OPTIONS, NETWORK_OPTIONS, &[
+        COLUMNS_OPTION,
+        // The svg and display-list formats are of the whole page otherwise
+        OptionSpec { name: "in-viewport", short: None, value: None, help: "Renders only what is in the viewport, where the page scrolled it to" },
+        OptionSpec { name: "scroll", short: None, value: Some("Y"), help: "Scrolls the viewport Y CSS pixels down and renders what is in it, e.g. --scroll 720 for the second screenful" },
+    ], TREE_LIMIT_OPTIONS],
+};
+
+static JS: Command = Command {
+    name: "js",
+    paths: "[PATH]",
+    about: "Runs a script file or stdin, or reads scripts from a prompt when no path is given and stdin is a terminal.",
+    formats: &["text", "ast"],
+    options: &[OUTPUT_OPTIONS, &[
+        OptionSpec { name: "max-time", short: None, value: Some("MS"), help: "How long the event loop may run for, until there are no tasks left otherwise" },
+        OptionSpec { name: "viewport", short: None, value: Some("WxH"), help: "The size of the viewport reported to the script, e.g. 1280x720" },
+        OptionSpec { name: "no-optimize", short: None, value: None, help: "Runs the script as it is written, without folding constants" },
+    ], SCRIPT_LIMIT_OPTIONS],
+};
+
+static LINT: Command = Command {
+    name: "lint",
+    paths: "[PATH...]",
+    about: "Checks HTML files or stdin for mistakes. The exit status is 1 when an error is found.",
+    formats: &["text", "json"],
+    options: &[OUTPUT_OPTIONS, &[
+        OptionSpec { name: "disable", short: None, value: Some("RULE"), help: "Turns a rule off" },
+        OptionSpec { name: "severity", short: None, value: Some("RULE=LEVEL"), help: "Changes the severity of a rule to info, warning or error" },
+    ]],
+};
+
+static DIFF: Command = Command {
+    name: "diff",
+    paths: "A [B]",
+    about: "Compares the documents two HTML files parse into, B is stdin when it is not given. The exit status is 1 when they differ.",
+    formats: &[],
+    options: &[&[OUTPUT_OPTION, OptionSpec { name: "ignore-whitespace", short: None, value: None, help: "Ignores differences in whitespace-only text" }]],
+};
+
+static FMT: Command = Command {
+    name: "fmt",
+    paths: "[PATH]",
+    about: "Prints an HTML file formatted.",
+    formats: &[],
+    options: &[&[
+        OUTPUT_OPTION,
+        OptionSpec { name: "indent", short: None, value: Some("N"), help: "Indents with N spaces" },
+        OptionSpec { name: "tabs", short: None, value: None, help: "Indents with tabs" },
+        OptionSpec { name: "width", short: None, value: Some("N"), help: "The width lines are wrapped at, 0 does not wrap them" },
+        OptionSpec { name: "sort-attributes", short: None, value: None, help: "Sorts attributes by name" },
+    ]],
+};
+
+static MINIFY: Command = Command {
+    name: "minify",
+    paths: "[PATH]",
+    about: "Prints an HTML file minified.",
+    formats: &[],
+    options: &[&[OUTPUT_OPTION]],
+};
+
+static TEXT: Command = Command {
+    name: "text",
+    paths: "[PATH]",
+    about: "Prints the text of an HTML file or stdin for indexing, with its paragraphs separated by blank lines and its table cells by tabs.",
+    formats: &[],
+    options: &[&[
+        OUTPUT_OPTION,
+        OptionSpec { name: "links", short: None, value: None, help: "Follows the text of each link with its URL in parentheses" },
+        OptionSpec { name: "no-alt-text", short: None, value: None, help: "Leaves out the alternative text of images" },
+    ]],
+};
+
+static REFTEST: Command = Command {
+    name: "reftest",
+    paths: "[MANIFEST]",
+    about: "Runs the reftests of a manifest, tests/ref/reftest.list by default.",
+    formats: &[],
+    options: &[&[OUTPUT_OPTION, OptionSpec { name: "width", short: None, value: Some("PX"), help: "The width of the viewport the files are rendered in, 800 by default" }]],
+};
+
+static SNAPSHOT: Command = Command {
+    name: "snapshot",
+    paths: "[DIRECTORY]",
+    about: "Checks the DOM snapshots of a directory, tests/snapshots by default, against the trees their HTML parses into now.",
+    formats: &[],
+    options: &[&[OUTPUT_OPTION, OptionSpec { name: "update", short: None, value: None, help: "Writes the trees that differ into their snapshots instead of failing" }]],
+};
+
+static DIFFERENTIAL: Command = Command {
+    name: "differential",
+    paths: "[PATH...]",
+    about: "Parses HTML files, or every HTML file in directories, with this parser and with html5ever, and reports where their trees differ. Needs the differential feature.",
+    formats: &[],
+    options: &[&[OUTPUT_OPTION, OptionSpec { name: "trees", short: None, value: None, help: "Prints both trees of each file that differs" }]],
+};
+
+static CRAWL: Command = Command {
+    name: "crawl",
+    paths: "URL",
+    about: "Prints the links of a page, and follows them with --max-depth.",
+    formats: &[],
+    options: &[NETWORK_OPTIONS, &[
+        OUTPUT_OPTION,
+        OptionSpec { name: "max-depth", short: None, value: Some("N"), help: "Follows links N links away from the page" },
+        OptionSpec { name: "max-pages", short: None, value: Some("N"), help: "Stops after N pages" },
+        OptionSpec { name: "delay", short: None, value: Some("MS"), help: "Waits between requests to a host" },
+        OptionSpec { name: "ignore-robots-txt", short: None, value: None, help: "Follows links robots.txt disallows" },
+        OptionSpec { name: "follow-refresh", short: None, value: None, help: "Follows the URL of a <meta http-equiv=refresh> like a link" },
+    ]],
+};
+
+static BROWSE: Command = Command {
+    name: "browse",
+    paths: "URL",
+    about: "Shows a page as text and follows its links by number.",
+    formats: &[],
+    options: &[NETWORK_OPTIONS, &[
+        OUTPUT_OPTION,
+        COLUMNS_OPTION,
+        OptionSpec { name: "disable-scripting", short: None, value: None, help: "Does not run the scripts of pages" },
+    ]],
+};
+
+static BENCH: Command = Command {
+    name: "bench",
+    paths: "[PATH...]",
+    about: "Prints how fast HTML files are tokenized and parsed. For repeatable measurements use cargo bench.",
+    formats: &[],
+    options: &[&[OUTPUT_OPTION, OptionSpec { name: "iterations", short: None, value: Some("N"), help: "The number of times each file is parsed, 10 by default" }]],
+};
+
+static SERVE: Command = Command {
+    name: "serve",
+    paths: "[ADDRESS]",
+    about: "Serves the engine over HTTP at ADDRESS, 127.0.0.1:8080 by default. GET / lists what HTML can be POSTed to, and each request is logged on stderr.",
+    formats: &["dom", "parse-errors", "text", "png"],
+    options: &[&[
+        OptionSpec { name: "format", short: Some('f'), value: Some("FORMAT"), help: "What HTML POSTed to / is answered with" },
+        OptionSpec { name: "quiet", short: Some('q'), value: None, help: "Logs only the requests the engine failed on" },
+        OptionSpec { name: "verbose", short: Some('v'), value: None, help: "Logs the size of the HTML and of the response of each request as well" },
+        OptionSpec { name: "output", short: Some('o'), value: Some("FILE"), help: "Writes the request log to FILE instead of stderr" },
+        OptionSpec { name: "max-body-size", short: None, value: Some("BYTES"), help: "The biggest HTML a request can send, 16 MiB by default" },
+        OptionSpec { name: "script-time", short: None, value: Some("MS"), help: "How long the scripts of a page run for with ?scripting=on, 1000 by default" },
+    ], TREE_LIMIT_OPTIONS],
+};
 
+static PREVIEW: Command = Command {
+    name: "preview",
+    paths: "PATH",
+    about: "Serves the rendering of an HTML file as an image and as text, and renders it again whenever the file changes. Scripts are not run.",
+    formats: &[],
+    options: &[&[
+        OptionSpec { name: "address", short: None, value: Some("ADDRESS"), help: "Where to listen, 127.0.0.1:8000 by default" },
+        COLUMNS_OPTION,
+        OptionSpec { name: "width", short: None, value: Some("PX"), help: "The width the image is rendered at, the width of the default viewport by default" },
+        OptionSpec { name: "interval", short: None, value: Some("MS"), help: "How often the file is checked for changes, 250 by default" },
+        OptionSpec { name: "output", short: Some('o'), value: Some("FILE"), help: "Writes the text rendering to FILE each time the file is rendered" },
+    ]],
+};
+
+static COMMANDS: &[&Command] = &[&PARSE, &TOKENS, &TRACE, &REPLAY, &DUMP, &RENDER, &JS, &LINT, &DIFF, &FMT, &MINIFY, &TEXT, &REFTEST, &SNAPSHOT, &DIFFERENTIAL, &CRAWL, &BROWSE, &BENCH, &SERVE, &PREVIEW];
+
+fn main() {
     let args: Vec<String> = env::args().collect();
 
-        if args.len() == 2 {
-            if args[1] == "js" {
-                let mut interpreter = Interpreter::new();
-                interpreter.run_prompt();
-            } else {
-                source_html_file_path = args[1].to_string();
-                let mut tokenizer = tokenizer::Tokenizer::new(String::from(source_html_file_path));
-                tokenizer.start();
+    let name = match args.get(1) {
+        Some(name) => name.as_str(),
+        None => {
+            eprint!("{}", commands_usage());
+            exit(2);
+        }
+    };
+    if name == "help" || name == "--help" || name == "-h" {
+        match args.get(2).and_then(|name| COMMANDS.iter().find(|command| command.name == name)) {
+            Some(command) => print!("{}", command.usage()),
+            None => print!("{}", commands_usage())
+        }
+        return;
+    }
+
+    let command: &'static Command = match COMMANDS.iter().find(|command| command.name == name) {
+        Some(command) => command,
+        None => {
+            eprintln!("Unknown command {}", name);
+            // Documents used to be given without a command
+            if Path::new(name).is_file() {
+                eprintln!("To print a document use: web_engine dump {}", name);
+            }
+            eprint!("\n{}", commands_usage());
+            exit(2);
+        }
+    };
+    if args[2..].iter().any(|argument| argument == "--help" || argument == "-h") {
+        print!("{}", command.usage());
+        return;
+    }
+    let arguments = match command.parse(&args[2..]) {
+        Ok(arguments) => arguments,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, command.usage());
+            exit(2);
+        }
+    };
+
+    match command.name {
+        "parse" => parse(&arguments),
+        "tokens" => tokens(&arguments),
+        "trace" => trace(&arguments),
+        "replay" => replay(&arguments),
+        "dump" => dump(&arguments),
+        "render" => render(&arguments),
+        "js" => js(&arguments),
+        "lint" => lint(&arguments),
+        "diff" => diff(&arguments),
+        "fmt" => fmt(&arguments),
+        "minify" => minify(&arguments),
+        "text" => text(&arguments),
+        "reftest" => reftest(&arguments),
+        "snapshot" => snapshot(&arguments),
+        "differential" => differential(&arguments),
+        "crawl" => crawl(&arguments),
+        "browse" => browse(&arguments),
+        "bench" => bench(&arguments),
+        "serve" => serve(&arguments),
+        "preview" => preview(&arguments),
+        _ => unreachable!()
+    }
+}
+
+fn commands_usage() -> String {
+    let mut usage = String::from("Usage: web_engine COMMAND [OPTIONS]\n\nCommands:\n");
+    for command in COMMANDS {
+        usage.push_str(&format!("  {:<8} {}\n", command.name, command.about));
+    }
+    usage.push_str("\nweb_engine COMMAND --help prints the options of a command. The input is read from stdin when no path is given or the path is -.\n");
+    return usage;
+}
+
+// The options for parsing a document the command line prints, the parse errors are printed by the command according to --quiet and --verbose
+fn parser_options(scripting_enabled: bool) -> ParserOptions {
+    return ParserOptions { scripting_enabled, print_parse_errors: false, ..ParserOptions::default() };
+}
+
+// How a path is named in messages
+fn input_name(path: &str) -> &str {
+    return if path == "-" { "stdin" } else { path };
+}
+
+// With --verbose each parse error of the document is printed on stderr with where it is, they are not failures as every document has a result
+fn report_parse_errors(arguments: &Arguments, path: &str, tokenizer: &Tokenizer) {
+    if arguments.verbosity() < Verbosity::Verbose {
+        return;
+    }
+    for (parse_error, position) in tokenizer.parse_errors().iter().zip(tokenizer.parse_error_positions()) {
+        match position {
+            Some(position) => eprintln!("{}:{}: {}", input_name(path), position, parse_error),
+            None => eprintln!("{}: {}", input_name(path), parse_error)
+        }
+    }
+}
+
+// Each file is printed with its parse errors indented under it, each at the line:column it was found, then the totals of the batch
+// With --quiet only the totals are printed, with --verbose how long the files took as well, and with --stats how big their documents are
+fn parse(arguments: &Arguments) {
+    let jobs = arguments.parsed_value::<usize>("jobs", "--jobs 8").unwrap_or_else(|| std::thread::available_parallelism().map(|jobs| jobs.get()).unwrap_or(1));
+    if jobs == 0 {
+        arguments.usage_error("--jobs needs a number of threads, e.g. --jobs 8");
+    }
+
+    let mut files = Vec::new();
+    let mut stdin = false;
+    for path in arguments.input_paths() {
+        if path == "-" {
+            stdin = true;
+            continue;
+        }
+        match batch::html_files(Path::new(path)) {
+            Ok(html_files) => files.extend(html_files),
+            Err(message) => {
+                eprintln!("{}", message);
+                exit(1);
+            }
+        }
+    }
+    let tree_limits = tree_limits(arguments, TreeLimits::default());
+    let stats = arguments.flag("stats");
+    let mut report = batch::parse_files(&files, jobs, tree_limits, stats);
+    if stdin {
+        report.files.push(batch::parse_bytes(Path::new("stdin"), read_input("-"), tree_limits, stats));
+    }
+
+    let verbosity = arguments.verbosity();
+    let mut output = arguments.output();
+    if arguments.format() == "json" {
+        let files: Vec<_> = report.files.iter().map(|file| {
+            let mut json = serde_json::json!({
+                "path": file.path.display().to_string(),
+                "bytes": file.bytes,
+                "parseTime": file.parse_time.as_secs_f64() * 1000.0,
+                "parseErrors": file.parse_errors.iter().zip(&file.parse_error_positions).map(|(message, position)| serde_json::json!({
+                    "message": message,
+                    "line": position.map(|position| position.line),
+                    "column": position.map(|position| position.column),
+                })).collect::<Vec<_>>(),
+                "failure": file.failure,
+            });
+            if let Some(stats) = &file.stats {
+                json["stats"] = stats.to_json();
+            }
+            json
+        }).collect();
+        write_output(&mut output, &format!("{}\n", serde_json::to_string_pretty(&files).unwrap_or_default()));
+    } else {
+        let mut text = String::new();
+        for file in &report.files {
+            match &file.failure {
+                Some(failure) if verbosity == Verbosity::Quiet => eprintln!("{}: failed, {}", file.path.display(), failure),
+                _ if verbosity == Verbosity::Quiet => {},
+                Some(failure) => text.push_str(&format!("{}: failed, {}\n", file.path.display(), failure)),
+                None if verbosity == Verbosity::Verbose => text.push_str(&format!("{}: {} bytes in {:?}, {} parse errors\n", file.path.display(), file.bytes, file.parse_time, file.parse_errors.len())),
+                None => text.push_str(&format!("{}: {} bytes, {} parse errors\n", file.path.display(), file.bytes, file.parse_errors.len()))
+            }
+            if verbosity > Verbosity::Quiet {
+                for (parse_error, position) in file.parse_errors.iter().zip(&file.parse_error_positions) {
+                    match position {
+                        Some(position) => text.push_str(&format!("  {}: {}\n", position, parse_error)),
+                        None => text.push_str(&format!("  {}\n", parse_error))
+                    }
+                }
+                if let Some(stats) = file.stats.as_ref().filter(|_| file.failure.is_none()) {
+                    for line in stats.to_text().lines() {
+                        text.push_str(&format!("  {}\n", line));
+                    }
+                }
+            }
+        }
+        text.push_str(&format!("{} files, {} bytes, {} parse errors, {} failed\n", report.files.len(), report.total_bytes(), report.total_parse_errors(), report.failures()));
+        if stats && report.files.len() > 1 {
+            text.push_str(&report.total_stats().to_text());
+        }
+        if verbosity == Verbosity::Verbose {
+            text.push_str(&format!("{:?} wall time, {:?} parse time with {} jobs\n", report.wall_time, report.total_parse_time(), jobs));
+        }
+        write_output(&mut output, &text);
+    }
+
+    // The exit status is 1 when a file could not be parsed, parse errors are not failures as every document has a result
+    exit(if report.failures() > 0 { 1 } else { 0 });
+}
+
+// Each token on a line of its own, or as a JSON array of objects
+// The tokens are taken before the tree builder sees them, see the Iterator of Tokenizer, and with --verbose each line starts with where the token is
+fn tokens(arguments: &Arguments) {
+    let path = arguments.path();
+    let mut tokenizer = Tokenizer::from_bytes(read_input(path), parser_options(false));
+    let tokens: Vec<HtmlToken> = tokenizer.by_ref().collect();
+
+    let mut output = arguments.output();
+    if arguments.format() == "json" {
+        let tokens: Vec<_> = tokens.iter().map(token_to_json).collect();
+        write_output(&mut output, &format!("{}\n", serde_json::to_string_pretty(&tokens).unwrap_or_default()));
+    } else {
+        let mut text = String::new();
+        for token in &tokens {
+            if arguments.verbosity() == Verbosity::Verbose {
+                if let Some(source_location) = token.source_location {
+                    text.push_str(&format!("{}\t", source_location.start));
+                }
+            }
+            text.push_str(&format!("{}\n", token.describe()));
+        }
+        write_output(&mut output, &text);
+    }
+    report_parse_errors(arguments, path, &tokenizer);
+}
+
+// e.g. trace page.html --format chrome --output trace.json, and then open trace.json in https://ui.perfetto.dev/
+#[cfg(feature = "trace")]
+fn trace(arguments: &Arguments) {
+    let path = arguments.path();
+    if arguments.format() == "session" {
+        let session = ParseSession::record(read_input(path), &parser_options(false));
+        write_output(&mut arguments.output(), &format!("{}\n", session.to_json()));
+        return;
+    }
+    let mut tokenizer = Tokenizer::from_bytes(read_input(path), parser_options(false));
+    let trace = tokenizer.enable_trace();
+    tokenizer.run();
+    let text = match arguments.format() {
+        "chrome" => format!("{}\n", trace.borrow().chrome_trace_json()),
+        _ => trace.borrow().timeline()
+    };
+    write_output(&mut arguments.output(), &text);
+    report_parse_errors(arguments, path, &tokenizer);
+}
+
+#[cfg(not(feature = "trace"))]
+fn trace(_arguments: &Arguments) {
+    eprintln!("web_engine was built without tracing, build it with cargo build --features trace");
+    exit(1);
+}
+
+// Exits with 1 when the replay differs from the session, so a fix can be checked against the session of its bug report
+// With --format tree the document of the replay is printed after the report
+#[cfg(feature = "trace")]
+fn replay(arguments: &Arguments) {
+    let path = arguments.path();
+    let session = match ParseSession::from_json(&String::from_utf8_lossy(&read_input(path))) {
+        Ok(session) => session,
+        Err(message) => {
+            eprintln!("{}: {}", input_name(path), message);
+            exit(1);
+        }
+    };
+    let replay = session.replay();
+    let mut text = match &replay.divergence {
+        Some(divergence) => format!("The replay differs from the session recorded by version {}\n{}\n", session.engine_version, divergence),
+        None => format!("The replay is the same as the session, {} events and {} parse errors\n", session.events.len(), session.parse_errors.len())
+    };
+    if arguments.format() == "tree" {
+        text.push_str(&replay.tokenizer.document_tree());
+    }
+    write_output(&mut arguments.output(), &text);
+    if replay.divergence.is_some() {
+        exit(1);
+    }
+}
+
+#[cfg(not(feature = "trace"))]
+fn replay(arguments: &Arguments) {
+    trace(arguments);
+}
+
+fn token_to_json(token: &HtmlToken) -> serde_json::Value {
+    return match token.token_type {
+        HtmlTokenType::DocType => serde_json::json!({ "type": "doctype", "name": token.name, "publicId": token.public_identifier, "systemId": token.system_identifier, "forceQuirks": token.force_quirks }),
+        HtmlTokenType::StartTag => {
+            let attributes: Vec<_> = token.attributes.iter().map(|attribute| serde_json::json!([attribute.name.as_str(), attribute.value.as_str()])).collect();
+            serde_json::json!({ "type": "startTag", "name": token.tag_name.as_str(), "attributes": attributes, "selfClosing": token.self_closing })
+        },
+        HtmlTokenType::EndTag => serde_json::json!({ "type": "endTag", "name": token.tag_name.as_str() }),
+        HtmlTokenType::Comment => serde_json::json!({ "type": "comment", "data": token.data.as_str() }),
+        HtmlTokenType::Character => serde_json::json!({ "type": "character", "data": token.data.as_str() }),
+        HtmlTokenType::EndOfFile => serde_json::json!({ "type": "endOfFile" })
+    };
+}
+
+// Parses the page at the path, runs its scripts and then fires the events given with --event at it, running the event loop after each
+fn load_page(arguments: &Arguments) -> Tokenizer {
+    let path = arguments.path();
+    let start = Instant::now();
+    let options = ParserOptions { tree_limits: tree_limits(arguments, TreeLimits::default()), ..parser_options(!arguments.flag("disable-scripting")) };
+    let mut tokenizer = Tokenizer::from_bytes(read_input(path), options);
+    // The document's URL is the file URL of the file it is read from, so the resources it loads are found next to it
+    if path != "-" {
+        tokenizer.set_document_url(Url::from_file_path(Path::new(path)));
+    }
+    tokenizer.set_viewport(viewport(arguments));
+    tokenizer.set_script_limits(script_limits(arguments));
+    tokenizer.set_resource_loader(NetworkOptions::from_arguments(arguments).resource_loader());
+
+    #[cfg(feature = "trace")]
+    let trace = arguments.flag("trace-tree-builder").then(|| tokenizer.enable_trace());
+    #[cfg(not(feature = "trace"))]
+    if arguments.flag("trace-tree-builder") {
+        eprintln!("web_engine was built without tracing, build it with cargo build --features trace");
+        exit(1);
+    }
+
+    tokenizer.run();
+    #[cfg(feature = "trace")]
+    if let Some(trace) = trace {
+        eprint!("{}", trace.borrow().tree_builder_timeline());
+    }
+    if let Some(error) = tokenizer.tree_limit_error() {
+        eprintln!("{}: {}", input_name(path), error);
+        exit(1);
+    }
+    let deadline = max_time(arguments).map(|max_time| Instant::now() + max_time);
+    tokenizer.run_event_loop(deadline);
+    for event in arguments.values("event") {
+        let (event_type, selector) = match event.split_once('@') {
+            Some((event_type, selector)) => (event_type, Some(selector)),
+            None => (event, None)
+        };
+        if let Err(message) = tokenizer.fire_synthetic_event(event_type, selector) {
+            if arguments.verbosity() > Verbosity::Quiet {
+                eprintln!("Could not fire {}: {}", event_type, message);
+            }
+        }
+        tokenizer.run_event_loop(deadline);
+    }
+    if let Some(animation_time) = arguments.parsed_value::<u64>("animation-time", "--animation-time 500") {
+        tokenizer.advance_animations(Duration::from_millis(animation_time));
+    }
+
+    report_parse_errors(arguments, path, &tokenizer);
+    if arguments.verbosity() == Verbosity::Verbose {
+        eprintln!("{}: loaded in {:?}, {} parse errors", input_name(path), start.elapsed(), tokenizer.parse_errors().len());
+    }
+    return tokenizer;
+}
+
+fn dump(arguments: &Arguments) {
+    let tokenizer = load_page(arguments);
+    let text = match arguments.format() {
+        "html" => format!("{}\n", serialize_children(&tokenizer.document())),
+        "json" => format!("{}\n", serialize_as_json(&tokenizer.document())),
+        "a11y" => format!("{}\n", tokenizer.accessibility_tree_json()),
+        "markdown" => markdown::to_markdown(&tokenizer.document()),
+        _ => tokenizer.document_tree()
+    };
+    write_output(&mut arguments.output(), &text);
+}
+
+// The display list is what painting the page in a viewport as wide as --viewport draws, or only what is seen in the viewport with --in-viewport or --scroll
+fn render(arguments: &Arguments) {
+    let columns = arguments.parsed_value::<usize>("columns", "--columns 80").unwrap_or(80).max(1);
+    let viewport = viewport(arguments);
+    let scroll = arguments.parsed_value::<f32>("scroll", "--scroll 720");
+    let tokenizer = load_page(arguments);
+    if let Some(y) = scroll {
+        let position = ScrollPosition { y, ..viewport_scroll_position(&tokenizer.document()) };
+        scroll_viewport(&tokenizer.document(), viewport, position);
+    }
+    let display_list = || match arguments.flag("in-viewport") || scroll.is_some() {
+        true => build_viewport_display_list(&tokenizer.document(), viewport, &BuiltinFontMetrics::default()),
+        false => build_display_list(&tokenizer.document(), viewport.width as f32, &BuiltinFontMetrics::default())
+    };
+    let text = match arguments.format() {
+        "svg" => svg_renderer::render_as_svg(&display_list()),
+        "display-list" => format!("{}\n", display_list().to_json()),
+        _ => tokenizer.render_as_text(columns)
+    };
+    write_output(&mut arguments.output(), &text);
+}
+
+// What scripts log goes to the output, and their warnings and errors to stderr unless --quiet is given
+struct CommandLineLogger {
+    output: Box<dyn Write>,
+    verbosity: Verbosity,
+}
+
+impl Logger for CommandLineLogger {
+    fn print(&mut self, log_level: LogLevel, message: &str) {
+        match log_level {
+            LogLevel::Log => write_output(self.output.as_mut(), &format!("{}\n", message)),
+            LogLevel::Warn | LogLevel::Error => {
+                if self.verbosity > Verbosity::Quiet {
+                    eprintln!("{}", message);
+                }
+            }
+        }
+    }
+}
+
+// With --format ast the syntax tree the script would run as is printed instead of running it
+fn js(arguments: &Arguments) {
+    let optimize = !arguments.flag("no-optimize");
+    // Without a script the prompt is shown when someone is there to type at it, and the script is read from stdin when it is piped in
+    if arguments.paths.is_empty() && arguments.format() != "ast" && io::stdin().is_terminal() {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_optimize_scripts(optimize);
+        interpreter.set_script_limits(script_limits(arguments));
+        interpreter.run_prompt();
+        return;
+    }
+
+    let path = arguments.path();
+    let source = String::from_utf8_lossy(&read_input(path)).to_string();
+    if arguments.format() == "ast" {
+        let script = match Interpreter::parse_script(source, optimize) {
+            Ok(script) => script,
+            Err(errors) => {
+                for error in errors {
+                    eprintln!("{}", error);
+                }
+                exit(65);
             }
-        } else if args.len() == 3 {
-            if args[1] == "js" {
-                let mut interpreter = Interpreter::new();
-                interpreter.run_file(args[2].to_string());
+        };
+        let mut ast = String::new();
+        for statement in script.statements {
+            ast.push_str(&format!("{}\n", statement.accept(&mut ASTPrettyPrinter)));
+        }
+        write_output(&mut arguments.output(), &ast);
+        return;
+    }
+
+    let start = Instant::now();
+    let mut interpreter = Interpreter::new();
+    interpreter.set_logger(Box::new(CommandLineLogger { output: arguments.output(), verbosity: arguments.verbosity() }));
+    interpreter.set_optimize_scripts(optimize);
+    interpreter.set_viewport(viewport(arguments));
+    interpreter.set_script_limits(script_limits(arguments));
+    // Relative URLs in the script, e.g. those it fetches, are resolved against the script's own URL
+    if path != "-" {
+        interpreter.set_document_url(Url::from_file_path(Path::new(path)));
+    }
+    interpreter.run_source(source, input_name(path));
+    interpreter.run_event_loop(max_time(arguments).map(|max_time| Instant::now() + max_time));
+    if arguments.verbosity() == Verbosity::Verbose {
+        eprintln!("{}: ran in {:?}", input_name(path), start.elapsed());
+    }
+}
+
+// e.g. lint page.html --disable deprecated-element --severity unclosed-tag=error
+// With --quiet only errors are reported, and with --verbose the number of each severity found is printed on stderr
+fn lint(arguments: &Arguments) {
+    let mut linter = Linter::new();
+    for name in arguments.values("disable") {
+        if !linter.disable(name) {
+            arguments.usage_error(&format!("--disable needs the name of a rule, one of {}", linter.rule_names().join(", ")));
+        }
+    }
+    for setting in arguments.values("severity") {
+        let changed = setting.split_once('=').and_then(|(name, severity)| Some((name, Severity::parse(severity)?)))
+            .is_some_and(|(name, severity)| linter.set_severity(name, severity));
+        if !changed {
+            arguments.usage_error("--severity needs a rule and a severity of info, warning or error, e.g. --severity unclosed-tag=error");
+        }
+    }
+
+    // Like a compiler, the exit status is 1 when an error was found, warnings alone do not fail
+    let mut errors = false;
+    let mut output = arguments.output();
+    for path in arguments.input_paths() {
+        let mut diagnostics = linter.lint(&read_input(path));
+        errors |= diagnostics.iter().any(|diagnostic| diagnostic.severity == Severity::Error);
+        if arguments.verbosity() == Verbosity::Quiet {
+            diagnostics.retain(|diagnostic| diagnostic.severity == Severity::Error);
+        }
+        if arguments.verbosity() == Verbosity::Verbose {
+            let count = |severity| diagnostics.iter().filter(|diagnostic| diagnostic.severity == severity).count();
+            eprintln!("{}: {} errors, {} warnings, {} infos", input_name(path), count(Severity::Error), count(Severity::Warning), count(Severity::Info));
+        }
+        if arguments.format() == "json" {
+            write_output(&mut output, &format!("{}\n", lint::report_json(&diagnostics)));
+        } else {
+            write_output(&mut output, &lint::report_text(input_name(path), &diagnostics));
+        }
+    }
+    exit(if errors { 1 } else { 0 });
+}
+
+// The documents are compared as they are parsed, before any of their scripts have changed them
+fn diff(arguments: &Arguments) {
+    let paths = match arguments.paths.as_slice() {
+        [a] => [a.as_str(), "-"],
+        [a, b] => [a.as_str(), b.as_str()],
+        _ => arguments.usage_error("diff needs two HTML files, e.g. diff a.html b.html, or one and the other on stdin")
+    };
+    if paths == ["-", "-"] {
+        arguments.usage_error("diff can only read one of the files from stdin");
+    }
+    let options = DiffOptions { ignore_whitespace: arguments.flag("ignore-whitespace"), ..DiffOptions::default() };
+
+    let documents: Vec<_> = paths.iter().map(|path| {
+        let mut tokenizer = Tokenizer::from_bytes(read_input(path), parser_options(false));
+        tokenizer.run();
+        tokenizer.document()
+    }).collect();
+
+    // Like diff(1), the exit status is 0 when the documents are the same and 1 when they differ
+    let edits = dom_diff::diff_with_options(&documents[0], &documents[1], options);
+    let mut output = arguments.output();
+    for edit in &edits {
+        write_output(&mut output, &format!("{}\n", edit));
+    }
+    exit(if edits.is_empty() { 0 } else { 1 });
+}
+
+// The document is formatted as it was parsed, before any of its scripts have changed it, and the parse errors are not printed with it
+fn fmt(arguments: &Arguments) {
+    let mut options = FormatOptions::default();
+    if let Some(spaces) = arguments.parsed_value::<usize>("indent", "--indent 4") {
+        options.indent = " ".repeat(spaces);
+    }
+    if arguments.flag("tabs") {
+        options.indent = "\t".to_string();
+    }
+    if let Some(line_width) = arguments.parsed_value::<usize>("width", "--width 100") {
+        options.line_width = line_width;
+    }
+    if arguments.flag("sort-attributes") {
+        options.attribute_order = AttributeOrder::Alphabetical;
+    }
+
+    let mut tokenizer = Tokenizer::from_bytes(read_input(arguments.path()), parser_options(false));
+    tokenizer.run();
+    write_output(&mut arguments.output(), &formatter::format_document(&tokenizer.document(), &options));
+}
+
+// Like fmt, the document is minified as it was parsed, before any of its scripts have changed it
+fn minify(arguments: &Arguments) {
+    let mut tokenizer = Tokenizer::from_bytes(read_input(arguments.path()), parser_options(false));
+    tokenizer.run();
+    write_output(&mut arguments.output(), &minifier::minify_document(&tokenizer.document(), &MinifyOptions::default()));
+}
+
+fn text(arguments: &Arguments) {
+    let mut tokenizer = Tokenizer::from_bytes(read_input(arguments.path()), parser_options(false));
+    tokenizer.run();
+    let options = ExtractTextOptions { preserve_links: arguments.flag("links"), include_alt_text: !arguments.flag("no-alt-text") };
+    write_output(&mut arguments.output(), &tokenizer.extract_text(&options));
+}
+
+// Each reftest is printed with whether it passed, and a failure with the first pixel whose rendering differs
+fn reftest(arguments: &Arguments) {
+    let manifest = match arguments.paths.as_slice() {
+        [] => "tests/ref/reftest.list",
+        [manifest] => manifest.as_str(),
+        _ => arguments.usage_error("reftest takes one manifest, e.g. reftest tests/ref/reftest.list")
+    };
+    let width = arguments.parsed_value::<u32>("width", "--width 800").unwrap_or(800).clamp(1, 16384);
+
+    let reftests = match reftest::parse_manifest(Path::new(manifest)) {
+        Ok(reftests) => reftests,
+        Err(message) => {
+            eprintln!("{}", message);
+            exit(2);
+        }
+    };
+
+    let mut failures = 0;
+    let mut output = arguments.output();
+    for reftest in &reftests {
+        let relation = match reftest.relation {
+            Relation::Equal => "==",
+            Relation::NotEqual => "!="
+        };
+        let name = format!("{} {} {}", reftest.test.display(), relation, reftest.reference.display());
+        match reftest::run(reftest, width) {
+            Outcome::Pass => write_output(&mut output, &format!("PASS {}\n", name)),
+            Outcome::Fail(comparison) => {
+                failures += 1;
+                write_output(&mut output, &format!("FAIL {} ({} pixels differ, by up to {})\n", name, comparison.differing_pixels, comparison.max_difference));
+                if let Some((x, y, test_pixel, reference_pixel)) = comparison.first_difference {
+                    write_output(&mut output, &format!("  pixel {},{} of the test:      {:?}\n", x, y, test_pixel));
+                    write_output(&mut output, &format!("  pixel {},{} of the reference: {:?}\n", x, y, reference_pixel));
+                }
+            },
+            Outcome::Error(message) => {
+                failures += 1;
+                write_output(&mut output, &format!("ERROR {} ({})\n", name, message));
+            }
+        }
+    }
+    write_output(&mut output, &format!("{} reftests, {} passed, {} failed\n", reftests.len(), reftests.len() - failures, failures));
+    exit(if failures > 0 { 1 } else { 0 });
+}
+
+// Each snapshot is printed with whether its tree is the same, and a failure with the first line of the tree that differs
+fn snapshot(arguments: &Arguments) {
+    let directory = match arguments.paths.as_slice() {
+        [] => "tests/snapshots",
+        [directory] => directory.as_str(),
+        _ => arguments.usage_error("snapshot takes one directory, e.g. snapshot tests/snapshots")
+    };
+    let update = arguments.flag("update") || snapshot::update_requested();
+
+    let paths = match snapshot::snapshot_files(Path::new(directory)) {
+        Ok(paths) => paths,
+        Err(message) => {
+            eprintln!("{}", message);
+            exit(2);
+        }
+    };
+
+    let mut failures = 0;
+    let mut output = arguments.output();
+    for path in &paths {
+        let name = path.display();
+        match snapshot::check_file(path, update) {
+            snapshot::Outcome::Pass => write_output(&mut output, &format!("PASS {}\n", name)),
+            snapshot::Outcome::Updated => write_output(&mut output, &format!("UPDATED {}\n", name)),
+            snapshot::Outcome::Missing => {
+                failures += 1;
+                write_output(&mut output, &format!("MISSING {}\n", name));
+            },
+            snapshot::Outcome::Fail { first_difference: (line, actual, expected), .. } => {
+                failures += 1;
+                write_output(&mut output, &format!("FAIL {}\n", name));
+                write_output(&mut output, &format!("  line {} of the tree now: {}\n", line + 1, actual));
+                write_output(&mut output, &format!("  line {} of the snapshot: {}\n", line + 1, expected));
+            },
+            snapshot::Outcome::Error(message) => {
+                failures += 1;
+                write_output(&mut output, &format!("ERROR {} ({})\n", name, message));
+            }
+        }
+    }
+    write_output(&mut output, &format!("{} snapshots, {} passed, {} failed\n", paths.len(), paths.len() - failures, failures));
+    exit(if failures > 0 { 1 } else { 0 });
+}
+
+// Each file that differs is printed with the first line where its trees differ, followed by the differences found in the most files
+// The corpus is benches/corpus when no path is given, and the exit status is 1 when a file differs
+#[cfg(feature = "differential")]
+fn differential(arguments: &Arguments) {
+    let paths: Vec<&str> = match arguments.paths.as_slice() {
+        [] => vec!["benches/corpus"],
+        paths => paths.iter().map(|path| path.as_str()).collect()
+    };
+    let mut files = Vec::new();
+    for path in paths {
+        match batch::html_files(Path::new(path)) {
+            Ok(html_files) => files.extend(html_files),
+            Err(message) => {
+                eprintln!("{}", message);
+                exit(2);
+            }
+        }
+    }
+
+    let report = differential::compare_files(&files);
+    let mut output = arguments.output();
+    for file in &report.files {
+        if let Some(failure) = &file.failure {
+            write_output(&mut output, &format!("ERROR {} ({})\n", file.path.display(), failure));
+        } else if let Some(difference) = &file.difference {
+            write_output(&mut output, &format!("DIFF {}\n", file.path.display()));
+            write_output(&mut output, &format!("  line {} of this parser's tree: {}\n", difference.line + 1, difference.engine_line));
+            write_output(&mut output, &format!("  line {} of html5ever's tree:   {}\n", difference.line + 1, difference.html5ever_line));
+            if arguments.flag("trees") {
+                let html = std::fs::read(&file.path).unwrap_or_default();
+                write_output(&mut output, &format!("this parser's tree:\n{}html5ever's tree:\n{}", differential::engine_tree(&html), differential::html5ever_tree(&html)));
+            }
+        }
+    }
+
+    let common_differences = report.common_differences();
+    if !common_differences.is_empty() {
+        write_output(&mut output, "The most common differences:\n");
+        for ((engine_line, html5ever_line), count) in common_differences.iter().take(10) {
+            write_output(&mut output, &format!("  {} files: {} instead of {}\n", count, engine_line, html5ever_line));
+        }
+    }
+    let (differing_files, failures) = (report.differing_files(), report.failures());
+    write_output(&mut output, &format!("{} files, {} the same, {} differ, {} failed\n", report.files.len(), report.files.len() - differing_files - failures, differing_files, failures));
+    exit(if differing_files + failures > 0 { 1 } else { 0 });
+}
+
+#[cfg(not(feature = "differential"))]
+fn differential(_arguments: &Arguments) {
+    eprintln!("web_engine was built without html5ever, build it with cargo build --features differential");
+    exit(1);
+}
+
+// The page is given as a URL, or as the path of a file
+// There is no page on stdin, the URL of a page is where its links are resolved against
+fn page_url(arguments: &Arguments) -> Url {
+    if arguments.paths.is_empty() {
+        arguments.usage_error("A page is needed, e.g. https://example.com/ or page.html");
+    }
+    let page = arguments.path();
+    return Url::parse(page, None).unwrap_or_else(|_| Url::from_file_path(Path::new(page)));
+}
+
+// Each outgoing link is printed on a line of its own as its URL, element, link types and text separated by tabs
+// When links are followed, each page is printed first with its depth, and its links are indented under it
+fn crawl(arguments: &Arguments) {
+    let url = page_url(arguments);
+    let mut crawl_options = CrawlOptions::default();
+    if let Some(max_depth) = arguments.parsed_value("max-depth", "--max-depth 2") {
+        crawl_options.max_depth = max_depth;
+    }
+    if let Some(max_pages) = arguments.parsed_value("max-pages", "--max-pages 50") {
+        crawl_options.max_pages = max_pages;
+    }
+    if let Some(delay) = arguments.parsed_value("delay", "--delay 1000") {
+        crawl_options.delay = Duration::from_millis(delay);
+    }
+    if arguments.flag("ignore-robots-txt") {
+        crawl_options.obey_robots_txt = false;
+    }
+    if arguments.flag("follow-refresh") {
+        crawl_options.follow_refresh = true;
+    }
+
+    let pages = links::crawl_site(&url, NetworkOptions::from_arguments(arguments).resource_loader().as_mut(), &crawl_options);
+    let mut failures = 0;
+    let mut output = arguments.output();
+    for page in &pages {
+        let indent = if crawl_options.max_depth > 0 { "  " } else { "" };
+        if crawl_options.max_depth > 0 {
+            write_output(&mut output, &format!("{} (depth {})\n", page.url, page.depth));
+        }
+        match &page.links {
+            Ok(links) => {
+                for link in links {
+                    write_output(&mut output, &format!("{}{}\t{}\t{}\t{}\n", indent, link.url, link.element, link.rel.join(" "), link.text));
+                }
+                // A refresh is listed like a link of the meta element, with its time in seconds as its text
+                if let Some(refresh) = &page.refresh {
+                    write_output(&mut output, &format!("{}{}\tmeta\trefresh\t{}s\n", indent, refresh.url, refresh.time.as_secs()));
+                }
+            },
+            Err(message) => {
+                failures += 1;
+                eprintln!("Could not crawl {}: {}", page.url, message);
             }
         }
+    }
+    exit(if failures > 0 { 1 } else { 0 });
+}
+
+// Shows the page at the URL as text and then reads commands to follow its numbered links and move through the history, until quit or the end of the input
+fn browse(arguments: &Arguments) {
+    let url = page_url(arguments);
+    let columns = arguments.parsed_value::<usize>("columns", "--columns 80").unwrap_or(80).max(1);
+
+    let mut session = Session::new(NetworkOptions::from_arguments(arguments).resource_loader());
+    session.set_scripting_enabled(!arguments.flag("disable-scripting"));
+    if let Err(message) = session.navigate(&url) {
+        eprintln!("Could not load {}: {}", url, message);
+        exit(1);
+    }
+    let mut output = arguments.output();
+    print_page(output.as_mut(), &session, columns);
+
+    let mut line_editor = LineEditor::new();
+    loop {
+        let line = match line_editor.read_line("browse> ") {
+            ReadLine::Line(line) => line,
+            ReadLine::Interrupted => continue,
+            ReadLine::Eof => break
+        };
+        line_editor.add_history(&line);
+
+        let (command, argument) = match line.trim().split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (line.trim(), "")
+        };
+        // Each command says whether it moved to another page, which is then printed
+        let navigated = match command {
+            "" => Ok(false),
+            "q" | "quit" => break,
+            "b" | "back" => session.back(),
+            "f" | "forward" => session.forward(),
+            "r" | "reload" => session.reload(),
+            "g" | "go" => {
+                match session.current_url().map_or_else(|| Url::parse(argument, None), |current_url| current_url.join(argument)) {
+                    Ok(url) => session.navigate(&url).map(|_| true),
+                    Err(error) => Err(format!("{}: {}", argument, error))
+                }
+            },
+            "h" | "history" => {
+                for (index, url) in session.history().iter().enumerate() {
+                    let marker = if index == session.current_index() { "*" } else { " " };
+                    write_output(output.as_mut(), &format!("{} {} {}\n", marker, index + 1, url));
+                }
+                Ok(false)
+            },
+            "?" | "help" => {
+                write_output(output.as_mut(), "A number follows that link, go <url> loads a page, back, forward, reload, history and quit do as they say\n");
+                Ok(false)
+            },
+            _ => match command.parse::<usize>() {
+                Ok(number) => session.follow_link(number).map(|_| true),
+                Err(_) => Err(format!("Unknown command {}, help lists the commands", command))
+            }
+        };
+        match navigated {
+            Ok(true) => print_page(output.as_mut(), &session, columns),
+            Ok(false) => (),
+            Err(message) => eprintln!("{}", message)
+        }
+    }
+}
+
+fn print_page(output: &mut dyn Write, session: &Session, columns: usize) {
+    if let (Some(url), Some((text, _))) = (session.current_url(), session.render_as_text(columns)) {
+        write_output(output, &format!("{}\n\n{}", url, text));
+    }
+}
+
+// Prints how many tokens per second the tokenizer produces for each HTML file, and how long it takes to parse it into a document, averaged over --iterations
+// Scripting is disabled so the parse time is that of the parser and not of the scripts on the page
+// For repeatable measurements use the benches, `cargo bench --bench parser`
+fn bench(arguments: &Arguments) {
+    let iterations = arguments.parsed_value::<u32>("iterations", "--iterations 20").unwrap_or(10);
+    if iterations == 0 {
+        arguments.usage_error("--iterations needs a number of iterations, e.g. --iterations 20");
+    }
+
+    let mut output = arguments.output();
+    for path in arguments.input_paths() {
+        let html = String::from_utf8_lossy(&read_input(path)).to_string();
+
+        let mut token_count = 0;
+        let start = Instant::now();
+        for _ in 0..iterations {
+            token_count = Tokenizer::from_html(html.clone()).count();
+        }
+        let tokenize_time = start.elapsed() / iterations;
+
+        let start = Instant::now();
+        for _ in 0..iterations {
+            let mut tokenizer = Tokenizer::with_options(html.clone(), parser_options(false));
+            tokenizer.run();
+        }
+        let parse_time = start.elapsed() / iterations;
+
+        let megabytes = html.len() as f64 / (1024.0 * 1024.0);
+        write_output(&mut output, &format!("{}: {} bytes, {} tokens\n", input_name(path), html.len(), token_count));
+        write_output(&mut output, &format!("  tokenize: {:?} per iteration, {:.0} tokens/s, {:.2} MiB/s\n", tokenize_time, token_count as f64 / tokenize_time.as_secs_f64(), megabytes / tokenize_time.as_secs_f64()));
+        write_output(&mut output, &format!("  parse:    {:?} per iteration, {:.2} MiB/s\n", parse_time, megabytes / parse_time.as_secs_f64()));
+    }
+}
+
+// e.g. serve 127.0.0.1:9000, and then curl --data-binary @page.html http://127.0.0.1:9000/dom
+fn serve(arguments: &Arguments) {
+    let address = match arguments.paths.as_slice() {
+        [] => "127.0.0.1:8080",
+        [address] => address.as_str(),
+        _ => arguments.usage_error("serve takes one address, e.g. serve 127.0.0.1:8080")
+    };
+    let mut options = ServerOptions::default();
+    if let Some(max_body_size) = arguments.parsed_value("max-body-size", "--max-body-size 1048576") {
+        options.max_body_size = max_body_size;
+    }
+    if let Some(script_time) = arguments.parsed_value("script-time", "--script-time 500") {
+        options.script_time = Duration::from_millis(script_time);
+    }
+    options.tree_limits = tree_limits(arguments, options.tree_limits);
+    // The formats are the paths without their /
+    options.default_path = server::PATHS.iter().find(|path| path[1..] == *arguments.format()).unwrap();
+    options.request_log = match arguments.verbosity() {
+        Verbosity::Quiet => RequestLog::Failures,
+        Verbosity::Normal => RequestLog::Requests,
+        Verbosity::Verbose => RequestLog::Details
+    };
+    options.log_path = arguments.value("output").map(PathBuf::from);
+
+    let listener = match TcpListener::bind(address) {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("Could not listen on {}: {}", address, error);
+            exit(1);
+        }
+    };
+    eprintln!("Listening on http://{}/", listener.local_addr().map(|address| address.to_string()).unwrap_or(address.to_string()));
+    if let Err(error) = server::serve(listener, options) {
+        eprintln!("{}", error);
+        exit(1);
+    }
+}
+
+// e.g. preview index.html, and then open http://127.0.0.1:8000/ and edit index.html
+fn preview(arguments: &Arguments) {
+    if arguments.paths.is_empty() {
+        arguments.usage_error("preview needs an HTML file to watch, e.g. preview index.html");
+    }
+    let path = Path::new(arguments.path());
+    let mut options = PreviewOptions::default();
+    if let Some(columns) = arguments.parsed_value::<usize>("columns", "--columns 80") {
+        options.columns = columns.max(1);
+    }
+    if let Some(width) = arguments.parsed_value::<u32>("width", "--width 1280") {
+        options.width = width.clamp(1, 16384);
+    }
+    if let Some(interval) = arguments.parsed_value("interval", "--interval 500") {
+        options.interval = Duration::from_millis(interval);
+    }
+    options.output = arguments.value("output").map(PathBuf::from);
+
+    let address = arguments.value("address").unwrap_or("127.0.0.1:8000");
+    let listener = match TcpListener::bind(address) {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("Could not listen on {}: {}", address, error);
+            exit(1);
+        }
+    };
+    eprintln!("Previewing {} on http://{}/", path.display(), listener.local_addr().map(|address| address.to_string()).unwrap_or(address.to_string()));
+    if let Err(error) = preview::preview(path, listener, options) {
+        eprintln!("{}", error);
+        exit(1);
+    }
+}
+
+// The budget is given in milliseconds, e.g. --max-time 500
+fn max_time(arguments: &Arguments) -> Option<Duration> {
+    return arguments.parsed_value::<u64>("max-time", "--max-time 500").map(Duration::from_millis);
+}
+
+// e.g. --max-steps 1000000 --max-script-time 500 --max-heap-objects 100000 --max-heap-size 67108864 --max-call-depth 200
+// The limits that are not given keep their value in default
+fn tree_limits(arguments: &Arguments, default: TreeLimits) -> TreeLimits {
+    return TreeLimits {
+        max_depth: arguments.parsed_value::<usize>("max-tree-depth", "--max-tree-depth 512").or(default.max_depth),
+        max_nodes: arguments.parsed_value::<usize>("max-nodes", "--max-nodes 1000000").or(default.max_nodes),
+        max_attributes: arguments.parsed_value::<usize>("max-attributes", "--max-attributes 1024").or(default.max_attributes),
+        max_attribute_length: arguments.parsed_value::<usize>("max-attribute-length", "--max-attribute-length 65536").or(default.max_attribute_length),
+    };
+}
+
+fn script_limits(arguments: &Arguments) -> ScriptLimits {
+    let default = ScriptLimits::default();
+    return ScriptLimits {
+        max_steps: arguments.parsed_value::<u64>("max-steps", "--max-steps 1000000"),
+        max_time: arguments.parsed_value::<u64>("max-script-time", "--max-script-time 500").map(Duration::from_millis),
+        max_heap_objects: arguments.parsed_value::<usize>("max-heap-objects", "--max-heap-objects 100000"),
+        max_heap_size: arguments.parsed_value::<usize>("max-heap-size", "--max-heap-size 67108864"),
+        max_call_depth: arguments.parsed_value::<usize>("max-call-depth", "--max-call-depth 200").unwrap_or(default.max_call_depth),
+    };
+}
+
+// The viewport is given as a width and height in CSS pixels, e.g. --viewport 1280x720
+fn viewport(arguments: &Arguments) -> Viewport {
+    let value = match arguments.value("viewport") {
+        Some(value) => value,
+        None => return Viewport::default()
+    };
+    let size = value.split_once('x').and_then(|(width, height)| Some((width.parse::<u32>().ok()?, height.parse::<u32>().ok()?)));
+    match size {
+        Some((width, height)) => return Viewport { width, height },
+        None => arguments.usage_error("--viewport needs a width and height, e.g. --viewport 1280x720")
+    }
+}
+
+// How resources are loaded over the network, the options the commands that load pages share
+struct NetworkOptions {
+    cache_dir: Option<PathBuf>,
+    insecure: bool,
+    timeout: Option<Duration>,
+}
+
+impl NetworkOptions {
+    fn from_arguments(arguments: &Arguments) -> NetworkOptions {
+        return NetworkOptions {
+            cache_dir: arguments.value("cache-dir").map(PathBuf::from),
+            insecure: arguments.flag("insecure"),
+            timeout: arguments.parsed_value::<u64>("timeout", "--timeout 5000").map(Duration::from_millis),
+        };
+    }
+
+    fn resource_loader(&self) -> Box<dyn ResourceLoader> {
+        let mut loader = DefaultResourceLoader { verify_certificates: !self.insecure, ..DefaultResourceLoader::default() };
+        if let Some(timeout) = self.timeout {
+            loader.timeout = timeout;
+        }
+        match &self.cache_dir {
+            Some(directory) => return Box::new(CachingResourceLoader::new(Box::new(loader), Box::new(DirectoryCacheStorage::new(directory)))),
+            None => return Box::new(loader)
+        }
+    }
 }
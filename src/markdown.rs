@@ -0,0 +1,317 @@
+use crate::node::{text_content, NodeData, RefNode};
+use crate::srcset::select_source;
+use crate::window::Viewport;
+
+// https://spec.commonmark.org/
+// Converts a document into Markdown, the CommonMark syntax with GitHub Flavored Markdown tables
+// Every block is separated from the next by a blank line, and inline elements are converted in place
+// TODO: Characters that only have a meaning at the start of a line (e.g. # and >) are not escaped, and runs of blank lines inside code blocks are collapsed like those between blocks
+
+// The Markdown of document, the head and the other elements that are not rendered have none
+pub fn to_markdown(document: &RefNode) -> String {
+    let mut output = collapse_blank_lines(&convert_children(document));
+    if !output.is_empty() {
+        output.push('\n');
+    }
+    return output;
+}
+
+fn convert_children(node: &RefNode) -> String {
+    let mut markdown = String::new();
+    for child in &node.borrow().childNodes {
+        let child_markdown = convert_node(child);
+        // The space collapsed from the whitespace after a line break is not part of the next line
+        if markdown.ends_with('\n') {
+            markdown.push_str(child_markdown.trim_start_matches(' '));
+        } else {
+            markdown.push_str(&child_markdown);
+        }
+    }
+    return markdown;
+}
+
+fn convert_node(node: &RefNode) -> String {
+    let (local_name, hidden) = match &node.borrow().data {
+        NodeData::Text(text) => return escape_text(&collapse_white_space(&text.character_data.data)),
+        NodeData::Element(element) => (element.local_name().to_string(), element.has_attribute("hidden")),
+        NodeData::Document(_) => return convert_children(node),
+        _ => return String::new()
+    };
+    if hidden {
+        return String::new();
+    }
+
+    match local_name.as_str() {
+        // Elements that are not rendered have no Markdown
+        "head" | "script" | "style" | "template" | "title" | "meta" | "link" | "base" | "noscript" => String::new(),
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            // An ATX heading is on a single line
+            let level = local_name[1..].parse::<usize>().unwrap_or(1);
+            let content = collapse_white_space(&convert_children(node).replace('\n', " "));
+            block(&format!("{} {}", "#".repeat(level), content.trim()))
+        },
+        "p" | "div" | "section" | "article" | "aside" | "header" | "footer" | "nav" | "main" | "address" | "figure" | "figcaption"
+        | "form" | "fieldset" | "details" | "summary" | "dl" | "dt" | "dd" | "center" | "caption" => block(&convert_children(node)),
+        "br" => "  \n".to_string(),
+        "hr" => block("---"),
+        "em" | "i" | "cite" | "var" | "dfn" => wrap_inline(&convert_children(node), "*"),
+        "strong" | "b" => wrap_inline(&convert_children(node), "**"),
+        "del" | "s" | "strike" => wrap_inline(&convert_children(node), "~~"),
+        "code" | "kbd" | "samp" | "tt" => code_span(&text_content(node).unwrap_or_default()),
+        "pre" => code_block(node),
+        "a" => link(node),
+        "img" => image(node),
+        "ul" | "ol" | "menu" => list(node, local_name == "ol"),
+        "li" => list_item(&convert_children(node), "- "),
+        "blockquote" => {
+            let content = collapse_blank_lines(&convert_children(node));
+            let quoted: Vec<String> = content.lines().map(|line| if line.is_empty() { ">".to_string() } else { format!("> {}", line) }).collect();
+            block(&quoted.join("\n"))
+        },
+        "table" => table(node),
+        _ => convert_children(node)
+    }
+}
+
+// A block is separated from the blocks around it by blank lines
+fn block(content: &str) -> String {
+    let content = content.trim();
+    if content.is_empty() {
+        return String::new();
+    }
+    return format!("\n\n{}\n\n", content);
+}
+
+// Emphasis cannot start or end with whitespace, so the whitespace around the content is moved outside the delimiters
+fn wrap_inline(content: &str, delimiter: &str) -> String {
+    let trimmed = content.trim();
+    if trimmed.is_empty() {
+        return content.to_string();
+    }
+    let leading = if content.starts_with(char::is_whitespace) { " " } else { "" };
+    let trailing = if content.ends_with(char::is_whitespace) { " " } else { "" };
+    return format!("{}{}{}{}{}", leading, delimiter, trimmed, delimiter, trailing);
+}
+
+// https://spec.commonmark.org/0.31.2/#code-spans
+// The code is delimited by a run of backticks longer than any run inside it
+fn code_span(code: &str) -> String {
+    let code = collapse_white_space(code);
+    if code.is_empty() {
+        return String::new();
+    }
+    let fence = "`".repeat(longest_run(&code, '`') + 1);
+    // A space is added inside the fence when the code starts or ends with a backtick, and is stripped again when it is rendered
+    let padding = if code.starts_with('`') || code.ends_with('`') { " " } else { "" };
+    return format!("{}{}{}{}{}", fence, padding, code, padding, fence);
+}
+
+// https://spec.commonmark.org/0.31.2/#fenced-code-blocks
+// The language is that of a code element inside the pre with a language-* class, as highlighters use
+fn code_block(pre: &RefNode) -> String {
+    let code = text_content(pre).unwrap_or_default();
+    let code = code.strip_suffix('\n').unwrap_or(&code);
+
+    let language = pre.borrow().childNodes.iter().find_map(|child| match &child.borrow().data {
+        NodeData::Element(element) if element.local_name() == "code" => {
+            element.get_attribute("class").and_then(|class| class.split_ascii_whitespace().find_map(|class| class.strip_prefix("language-").map(|language| language.to_string())))
+        },
+        _ => None
+    }).unwrap_or_default();
+
+    let fence = "`".repeat(longest_run(code, '`').max(2) + 1);
+    return format!("\n\n{}{}\n{}\n{}\n\n", fence, language, code, fence);
+}
+
+// https://spec.commonmark.org/0.31.2/#links
+// An a element without an href is not a link, so only its content is kept
+fn link(a: &RefNode) -> String {
+    let content = convert_children(a);
+    let (href, title) = match &a.borrow().data {
+        NodeData::Element(element) => (element.get_attribute("href").cloned(), element.get_attribute("title").cloned()),
+        _ => (None, None)
+    };
+    let href = match href {
+        Some(href) => href,
+        None => return content
+    };
+
+    let text = content.trim();
+    let text = if text.is_empty() { escape_text(&href) } else { text.replace('\n', " ") };
+    return format!("[{}]({}{})", text, link_destination(&href), link_title(title));
+}
+
+// https://spec.commonmark.org/0.31.2/#images
+// The image is the one a browser with the default viewport would show, which is the src unless there is a srcset or picture
+fn image(img: &RefNode) -> String {
+    let (alt, title) = match &img.borrow().data {
+        NodeData::Element(element) => (element.get_attribute("alt").cloned().unwrap_or_default(), element.get_attribute("title").cloned()),
+        _ => return String::new()
+    };
+    let src = select_source(img, &Viewport::default(), 1.0).unwrap_or_default();
+    if src.is_empty() {
+        return String::new();
+    }
+    return format!("![{}]({}{})", escape_text(&collapse_white_space(&alt)), link_destination(&src), link_title(title));
+}
+
+// https://spec.commonmark.org/0.31.2/#link-destination
+// A destination with spaces or parentheses is written between angle brackets
+fn link_destination(url: &str) -> String {
+    if url.is_empty() || url.contains([' ', '(', ')']) {
+        return format!("<{}>", url.replace('<', "%3C").replace('>', "%3E"));
+    }
+    return url.to_string();
+}
+
+// https://spec.commonmark.org/0.31.2/#link-title
+fn link_title(title: Option<String>) -> String {
+    match title.filter(|title| !title.trim().is_empty()) {
+        Some(title) => format!(" \"{}\"", collapse_white_space(&title).replace('\\', "\\\\").replace('"', "\\\"")),
+        None => String::new()
+    }
+}
+
+// https://spec.commonmark.org/0.31.2/#lists
+// The items of an ol are numbered from its start attribute, those of ul and menu are bullets
+fn list(list: &RefNode, ordered: bool) -> String {
+    let mut number = match &list.borrow().data {
+        NodeData::Element(element) => element.get_attribute("start").and_then(|start| start.trim().parse::<i64>().ok()).unwrap_or(1),
+        _ => 1
+    };
+
+    let mut items = Vec::new();
+    for child in &list.borrow().childNodes {
+        let is_list_item = matches!(&child.borrow().data, NodeData::Element(element) if element.local_name() == "li" && !element.has_attribute("hidden"));
+        if !is_list_item {
+            // The whitespace between the items is not content of the list
+            continue;
+        }
+
+        let marker = if ordered { format!("{}. ", number) } else { "- ".to_string() };
+        number += 1;
+        items.push(list_item(&convert_children(child), &marker).trim_end().to_string());
+    }
+    return block(&items.join("\n"));
+}
+
+// The lines after the first are indented to the content of the first, so nested lists and paragraphs stay inside the item
+fn list_item(content: &str, marker: &str) -> String {
+    let indent = " ".repeat(marker.len());
+    let lines: Vec<String> = collapse_blank_lines(content).lines().enumerate().map(|(index, line)| {
+        if index == 0 {
+            format!("{}{}", marker, line.trim_start())
+        } else if line.is_empty() {
+            String::new()
+        } else {
+            format!("{}{}", indent, line)
+        }
+    }).collect();
+    if lines.is_empty() {
+        return format!("{}\n", marker.trim_end());
+    }
+    return format!("{}\n", lines.join("\n"));
+}
+
+// https://github.github.com/gfm/#tables-extension-
+// The first row is the header row, as a table cannot be written without one, and rows with fewer cells are padded with empty cells
+fn table(table: &RefNode) -> String {
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    collect_table_rows(table, &mut rows);
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    if columns == 0 {
+        return String::new();
+    }
+
+    let mut lines = Vec::new();
+    for (index, row) in rows.iter().enumerate() {
+        let mut cells = row.clone();
+        cells.resize(columns, String::new());
+        lines.push(format!("| {} |", cells.join(" | ")));
+        if index == 0 {
+            lines.push(format!("| {} |", vec!["---"; columns].join(" | ")));
+        }
+    }
+    return block(&lines.join("\n"));
+}
+
+// The cells of the tr elements of a table, including those in its thead, tbody and tfoot, but not those of tables nested in its cells
+fn collect_table_rows(node: &RefNode, rows: &mut Vec<Vec<String>>) {
+    for child in &node.borrow().childNodes {
+        let local_name = match &child.borrow().data {
+            NodeData::Element(element) => element.local_name().to_string(),
+            _ => continue
+        };
+        match local_name.as_str() {
+            "thead" | "tbody" | "tfoot" => collect_table_rows(child, rows),
+            "tr" => {
+                let cells = child.borrow().childNodes.iter().filter(|cell| matches!(&cell.borrow().data, NodeData::Element(element) if matches!(element.local_name().as_str(), "td" | "th")))
+                    // A cell is on a single line, and a pipe in it would end the cell
+                    .map(|cell| collapse_white_space(&convert_children(cell).replace('\n', " ")).replace('|', "\\|"))
+                    .collect();
+                rows.push(cells);
+            },
+            _ => {}
+        }
+    }
+}
+
+// https://spec.commonmark.org/0.31.2/#backslash-escapes
+// The characters that would otherwise start emphasis, code spans, links or HTML
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::new();
+    for character in text.chars() {
+        if matches!(character, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+    return escaped;
+}
+
+// The blank lines between blocks are collapsed, so nested blocks are not separated by more than one
+fn collapse_blank_lines(markdown: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in markdown.trim().lines() {
+        // A line that is only whitespace would not end a paragraph in every renderer, and two spaces at the end of a line are a hard line break
+        let line = if line.trim().is_empty() { "" } else if line.ends_with("  ") { line } else { line.trim_end() };
+        if line.is_empty() && lines.last().is_some_and(|last_line| last_line.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    return lines.join("\n");
+}
+
+// Whitespace in HTML text is a single space wherever it is not preformatted
+fn collapse_white_space(text: &str) -> String {
+    let mut collapsed = String::new();
+    let mut previous_was_whitespace = false;
+    for character in text.chars() {
+        if character.is_ascii_whitespace() {
+            if !previous_was_whitespace {
+                collapsed.push(' ');
+            }
+            previous_was_whitespace = true;
+        } else {
+            collapsed.push(character);
+            previous_was_whitespace = false;
+        }
+    }
+    return collapsed;
+}
+
+// The length of the longest run of character in text
+fn longest_run(text: &str, character: char) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    for text_character in text.chars() {
+        if text_character == character {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    return longest;
+}
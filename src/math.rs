@@ -0,0 +1,301 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::interpreter::{argument, create_value_completion, return_if_abrupt, to_number, CompletionRecord, Interpreter, JSValue};
+
+// https://tc39.es/ecma262/#sec-math-object
+// The Math object, its value properties and its functions, each of which converts its arguments with ToNumber
+// The functions that the spec leaves implementation-approximated (e.g. Math.sin) use the platform's libm through f64
+
+// A function of one number, e.g. Math.sqrt(x), which returns the operation applied to ToNumber(x)
+macro_rules! math_function {
+    ($function:ident, $operation:expr) => {
+        fn $function(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+            // 1. Let n be ? ToNumber(x).
+            let number = return_if_abrupt!(to_number(interpreter, argument(&arguments, 0)));
+            let operation: fn(f64) -> f64 = $operation;
+            return create_value_completion(JSValue::Numeric(operation(number)));
+        }
+    };
+}
+
+math_function!(math_abs, f64::abs);
+math_function!(math_acos, f64::acos);
+math_function!(math_acosh, f64::acosh);
+math_function!(math_asin, f64::asin);
+math_function!(math_asinh, f64::asinh);
+math_function!(math_atan, f64::atan);
+math_function!(math_atanh, f64::atanh);
+math_function!(math_cbrt, f64::cbrt);
+math_function!(math_ceil, f64::ceil);
+math_function!(math_cos, f64::cos);
+math_function!(math_cosh, f64::cosh);
+math_function!(math_exp, f64::exp);
+math_function!(math_expm1, f64::exp_m1);
+math_function!(math_floor, f64::floor);
+math_function!(math_fround, |number| number as f32 as f64);
+math_function!(math_log, f64::ln);
+math_function!(math_log1p, f64::ln_1p);
+math_function!(math_log10, f64::log10);
+math_function!(math_log2, f64::log2);
+math_function!(math_round, round);
+math_function!(math_sign, sign);
+math_function!(math_sin, f64::sin);
+math_function!(math_sinh, f64::sinh);
+math_function!(math_sqrt, f64::sqrt);
+math_function!(math_tan, f64::tan);
+math_function!(math_tanh, f64::tanh);
+math_function!(math_trunc, f64::trunc);
+
+thread_local! {
+    // The state of the generator Math.random draws from, seeded from the time the first number is drawn
+    static RANDOM_STATE: Cell<u64> = const { Cell::new(0) };
+}
+
+pub(crate) fn install_math(interpreter: &mut Interpreter) {
+    let math = interpreter.create_ordinary_object();
+
+    // https://tc39.es/ecma262/#sec-value-properties-of-the-math-object
+    // Each property has the attributes { [[Writable]]: false, [[Enumerable]]: false, [[Configurable]]: false }.
+    let constants = [
+        ("E", std::f64::consts::E),
+        ("LN10", std::f64::consts::LN_10),
+        ("LN2", std::f64::consts::LN_2),
+        ("LOG10E", std::f64::consts::LOG10_E),
+        ("LOG2E", std::f64::consts::LOG2_E),
+        ("PI", std::f64::consts::PI),
+        ("SQRT1_2", std::f64::consts::FRAC_1_SQRT_2),
+        ("SQRT2", std::f64::consts::SQRT_2),
+    ];
+    for (name, value) in constants {
+        Interpreter::define_property_or_throw(&math, name, Rc::new(RefCell::new(JSValue::Numeric(value))), false, false, false);
+    }
+
+    // https://tc39.es/ecma262/#sec-function-properties-of-the-math-object
+    Interpreter::define_builtin_function(&math, "abs", math_abs);
+    Interpreter::define_builtin_function(&math, "acos", math_acos);
+    Interpreter::define_builtin_function(&math, "acosh", math_acosh);
+    Interpreter::define_builtin_function(&math, "asin", math_asin);
+    Interpreter::define_builtin_function(&math, "asinh", math_asinh);
+    Interpreter::define_builtin_function(&math, "atan", math_atan);
+    Interpreter::define_builtin_function(&math, "atanh", math_atanh);
+    Interpreter::define_builtin_function(&math, "atan2", math_atan2);
+    Interpreter::define_builtin_function(&math, "cbrt", math_cbrt);
+    Interpreter::define_builtin_function(&math, "ceil", math_ceil);
+    Interpreter::define_builtin_function(&math, "clz32", math_clz32);
+    Interpreter::define_builtin_function(&math, "cos", math_cos);
+    Interpreter::define_builtin_function(&math, "cosh", math_cosh);
+    Interpreter::define_builtin_function(&math, "exp", math_exp);
+    Interpreter::define_builtin_function(&math, "expm1", math_expm1);
+    Interpreter::define_builtin_function(&math, "floor", math_floor);
+    Interpreter::define_builtin_function(&math, "fround", math_fround);
+    Interpreter::define_builtin_function(&math, "hypot", math_hypot);
+    Interpreter::define_builtin_function(&math, "imul", math_imul);
+    Interpreter::define_builtin_function(&math, "log", math_log);
+    Interpreter::define_builtin_function(&math, "log1p", math_log1p);
+    Interpreter::define_builtin_function(&math, "log10", math_log10);
+    Interpreter::define_builtin_function(&math, "log2", math_log2);
+    Interpreter::define_builtin_function(&math, "max", math_max);
+    Interpreter::define_builtin_function(&math, "min", math_min);
+    Interpreter::define_builtin_function(&math, "pow", math_pow);
+    Interpreter::define_builtin_function(&math, "random", math_random);
+    Interpreter::define_builtin_function(&math, "round", math_round);
+    Interpreter::define_builtin_function(&math, "sign", math_sign);
+    Interpreter::define_builtin_function(&math, "sin", math_sin);
+    Interpreter::define_builtin_function(&math, "sinh", math_sinh);
+    Interpreter::define_builtin_function(&math, "sqrt", math_sqrt);
+    Interpreter::define_builtin_function(&math, "tan", math_tan);
+    Interpreter::define_builtin_function(&math, "tanh", math_tanh);
+    Interpreter::define_builtin_function(&math, "trunc", math_trunc);
+
+    interpreter.define_global_property("Math", Rc::new(RefCell::new(JSValue::Object(math))));
+}
+
+// https://tc39.es/ecma262/#sec-math.round
+fn round(number: f64) -> f64 {
+    // 2. If n is not finite or n is an integral Number, return n.
+    if !number.is_finite() || number == number.trunc() {
+        return number;
+    }
+
+    // 3. If n < 0.5𝔽 and n > +0𝔽, return +0𝔽.
+    // 4. If n < -0𝔽 and n ≥ -0.5𝔽, return -0𝔽.
+    if number < 0.0 && number >= -0.5 {
+        return -0.0;
+    }
+
+    // 5. Return the integral Number closest to n, preferring the Number closer to +∞ in the case of a tie.
+    let floor = number.floor();
+    if number - floor >= 0.5 {
+        return floor + 1.0;
+    }
+    return floor;
+}
+
+// https://tc39.es/ecma262/#sec-math.sign
+fn sign(number: f64) -> f64 {
+    // 2. If n is one of NaN, +0𝔽, or -0𝔽, return n.
+    if number.is_nan() || number == 0.0 {
+        return number;
+    }
+
+    // 3. If n < -0𝔽, return -1𝔽.
+    // 4. Return 1𝔽.
+    return number.signum();
+}
+
+// https://tc39.es/ecma262/#sec-math.atan2
+fn math_atan2(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let ny be ? ToNumber(y).
+    let y = return_if_abrupt!(to_number(interpreter, argument(&arguments, 0)));
+
+    // 2. Let nx be ? ToNumber(x).
+    let x = return_if_abrupt!(to_number(interpreter, argument(&arguments, 1)));
+
+    // 3-16. Return an implementation-approximated Number value representing the result of the inverse tangent of the quotient ℝ(ny) / ℝ(nx).
+    return create_value_completion(JSValue::Numeric(y.atan2(x)));
+}
+
+// https://tc39.es/ecma262/#sec-math.clz32
+fn math_clz32(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let n be ? ToUint32(x).
+    let number = Interpreter::to_uint32(return_if_abrupt!(to_number(interpreter, argument(&arguments, 0))));
+
+    // 2. Let p be the number of leading zero bits in the unsigned 32-bit binary representation of n.
+    // 3. Return 𝔽(p).
+    return create_value_completion(JSValue::Numeric(number.leading_zeros() as f64));
+}
+
+// https://tc39.es/ecma262/#sec-math.hypot
+fn math_hypot(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let coerced be a new empty List.
+    // 2. For each element arg of args, do
+    //        a. Let n be ? ToNumber(arg).
+    //        b. Append n to coerced.
+    let coerced = return_if_abrupt!(to_numbers(interpreter, &arguments));
+
+    // 3. For each element number of coerced, do
+    //        a. If number is either +∞𝔽 or -∞𝔽, return +∞𝔽.
+    if coerced.iter().any(|number| number.is_infinite()) {
+        return create_value_completion(JSValue::Numeric(f64::INFINITY));
+    }
+
+    // 4. Let onlyZero be true.
+    // 5. For each element number of coerced, do
+    //        a. If number is NaN, return NaN.
+    //        b. If number is neither +0𝔽 nor -0𝔽, set onlyZero to false.
+    // 6. If onlyZero is true, return +0𝔽.
+    // 7. Return an implementation-approximated Number value representing the square root of the sum of squares of the mathematical values of the elements of coerced.
+    return create_value_completion(JSValue::Numeric(coerced.iter().fold(0.0, |sum: f64, number| sum.hypot(*number))));
+}
+
+// https://tc39.es/ecma262/#sec-math.imul
+fn math_imul(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let a be ℝ(? ToUint32(x)).
+    let a = Interpreter::to_uint32(return_if_abrupt!(to_number(interpreter, argument(&arguments, 0))));
+
+    // 2. Let b be ℝ(? ToUint32(y)).
+    let b = Interpreter::to_uint32(return_if_abrupt!(to_number(interpreter, argument(&arguments, 1))));
+
+    // 3. Let product be (a × b) modulo 2^32.
+    // 4. If product ≥ 2^31, return 𝔽(product - 2^32); otherwise return 𝔽(product).
+    return create_value_completion(JSValue::Numeric(a.wrapping_mul(b) as i32 as f64));
+}
+
+// https://tc39.es/ecma262/#sec-math.max
+fn math_max(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let coerced be a new empty List.
+    // 2. For each element arg of args, do
+    //        a. Let n be ? ToNumber(arg).
+    //        b. Append n to coerced.
+    let coerced = return_if_abrupt!(to_numbers(interpreter, &arguments));
+
+    // 3. Let highest be -∞𝔽.
+    // 4. For each element number of coerced, do
+    //        a. If number is NaN, return NaN.
+    //        b. If number is +0𝔽 and highest is -0𝔽, set highest to +0𝔽.
+    //        c. If number > highest, set highest to number.
+    // 5. Return highest.
+    let mut highest = f64::NEG_INFINITY;
+    for number in coerced {
+        if number.is_nan() {
+            return create_value_completion(JSValue::Numeric(f64::NAN));
+        }
+        if (number == 0.0 && number.is_sign_positive() && highest == 0.0) || number > highest {
+            highest = number;
+        }
+    }
+    return create_value_completion(JSValue::Numeric(highest));
+}
+
+// https://tc39.es/ecma262/#sec-math.min
+fn math_min(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let coerced be a new empty List.
+    // 2. For each element arg of args, do
+    //        a. Let n be ? ToNumber(arg).
+    //        b. Append n to coerced.
+    let coerced = return_if_abrupt!(to_numbers(interpreter, &arguments));
+
+    // 3. Let lowest be +∞𝔽.
+    // 4. For each element number of coerced, do
+    //        a. If number is NaN, return NaN.
+    //        b. If number is -0𝔽 and lowest is +0𝔽, set lowest to -0𝔽.
+    //        c. If number < lowest, set lowest to number.
+    // 5. Return lowest.
+    let mut lowest = f64::INFINITY;
+    for number in coerced {
+        if number.is_nan() {
+            return create_value_completion(JSValue::Numeric(f64::NAN));
+        }
+        if (number == 0.0 && number.is_sign_negative() && lowest == 0.0) || number < lowest {
+            lowest = number;
+        }
+    }
+    return create_value_completion(JSValue::Numeric(lowest));
+}
+
+// https://tc39.es/ecma262/#sec-math.pow
+fn math_pow(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Set base to ? ToNumber(base).
+    let base = return_if_abrupt!(to_number(interpreter, argument(&arguments, 0)));
+
+    // 2. Set exponent to ? ToNumber(exponent).
+    let exponent = return_if_abrupt!(to_number(interpreter, argument(&arguments, 1)));
+
+    // 3. Return Number::exponentiate(base, exponent).
+    // https://tc39.es/ecma262/#sec-numeric-types-number-exponentiate
+    // 1. If exponent is NaN, return NaN.
+    // 9. If exponent is +∞𝔽, then
+    //        b. If abs(ℝ(base)) = 1, return NaN.
+    // 10. If exponent is -∞𝔽, then
+    //        b. If abs(ℝ(base)) = 1, return NaN.
+    // Unlike the spec, powf returns 1 for these
+    if exponent.is_nan() || (exponent.is_infinite() && base.abs() == 1.0) {
+        return create_value_completion(JSValue::Numeric(f64::NAN));
+    }
+    return create_value_completion(JSValue::Numeric(base.powf(exponent)));
+}
+
+// https://tc39.es/ecma262/#sec-math.random
+// Returns a Number value with positive sign, greater than or equal to +0𝔽 but strictly less than 1𝔽
+// The numbers come from an xorshift64* generator, which is fast but not cryptographically secure, as the spec allows
+fn math_random(_interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let random = RANDOM_STATE.with(|state| {
+        let mut x = state.get();
+        if x == 0 {
+            x = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0x2545F4914F6CDD1D, |duration| duration.as_nanos() as u64) | 1;
+        }
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        state.set(x);
+        x.wrapping_mul(0x2545F4914F6CDD1D)
+    });
+
+    // The top 53 bits make a double in [0, 1)
+    return create_value_completion(JSValue::Numeric((random >> 11) as f64 / (1u64 << 53) as f64));
+}
+
+fn to_numbers(interpreter: &mut Interpreter, arguments: &[Rc<RefCell<JSValue>>]) -> Result<Vec<f64>, CompletionRecord> {
+    return arguments.iter().map(|value| to_number(interpreter, Rc::clone(value))).collect();
+}
@@ -0,0 +1,290 @@
+use std::time::Duration;
+use indexmap::IndexMap;
+use crate::html_document_parser::script_type;
+use crate::microsyntaxes::is_ascii_whitespace;
+use crate::node::{descendants, encoding_parse_url, node_document, text_content, NodeData, RefNode};
+use crate::url::Url;
+
+// The metadata of a document that scrapers ask for: its title, its meta elements, its canonical URL, its Open Graph properties, the outline of its headings and its data blocks
+// TODO: Elements inside template contents are not excluded, and the outline does not use sectioning elements
+
+// https://html.spec.whatwg.org/multipage/dom.html#document.title
+// The child text content of the first title element, with whitespace stripped and collapsed, the empty string when there is no title element
+pub fn document_title(document: &RefNode) -> String {
+    // 1. If the document element is an SVG svg element, then let value be the child text content of the first SVG title element that is a child of the document element.
+    // TODO: SVG is not supported, so the title element is always looked up in the HTML namespace
+    // 2. Otherwise, let value be the child text content of the title element, or the empty string if the title element is null.
+    let value = match elements(document).into_iter().find(|element| local_name(element) == "title") {
+        Some(title) => child_text_content(&title),
+        None => String::new()
+    };
+
+    // 3. Strip and collapse ASCII whitespace in value.
+    // 4. Return value.
+    return strip_and_collapse_ascii_whitespace(&value);
+}
+
+// https://html.spec.whatwg.org/multipage/semantics.html#standard-metadata-names
+// The content of the meta elements with a name attribute, keyed by the name in ASCII lowercase, e.g. description or author
+// The first meta element with a name wins, as it does for the standard metadata names
+pub fn meta(document: &RefNode) -> IndexMap<String, String> {
+    let mut meta = IndexMap::new();
+    for element in elements(document).into_iter().filter(|element| local_name(element) == "meta") {
+        if let (Some(name), Some(content)) = (get_attribute(&element, "name"), get_attribute(&element, "content")) {
+            meta.entry(name.to_ascii_lowercase()).or_insert(content);
+        }
+    }
+    return meta;
+}
+
+// https://datatracker.ietf.org/doc/html/rfc6596
+// The URL of the first link element with the canonical link type, resolved against the document base URL
+pub fn canonical_url(document: &RefNode) -> Option<Url> {
+    for element in elements(document).into_iter().filter(|element| local_name(element) == "link") {
+        // https://html.spec.whatwg.org/multipage/links.html#linkTypes
+        // Link types are ASCII case-insensitive
+        let is_canonical = get_attribute(&element, "rel").is_some_and(|rel| rel.split_ascii_whitespace().any(|link_type| link_type.eq_ignore_ascii_case("canonical")));
+        if !is_canonical {
+            continue;
+        }
+        if let Some(url) = get_attribute(&element, "href").and_then(|href| encoding_parse_url(&element, &href).ok()) {
+            return Some(url);
+        }
+    }
+    return None;
+}
+
+// https://ogp.me/
+// The Open Graph properties of the meta elements whose property attribute starts with "og:", e.g. og:title
+// A property can be given more than once (e.g. og:image for a page with several images), so every value is kept in document order
+pub fn open_graph(document: &RefNode) -> IndexMap<String, Vec<String>> {
+    let mut properties: IndexMap<String, Vec<String>> = IndexMap::new();
+    for element in elements(document).into_iter().filter(|element| local_name(element) == "meta") {
+        if let (Some(property), Some(content)) = (get_attribute(&element, "property"), get_attribute(&element, "content")) {
+            if property.starts_with("og:") {
+                properties.entry(property).or_default().push(content);
+            }
+        }
+    }
+    return properties;
+}
+
+// https://html.spec.whatwg.org/multipage/semantics.html#attr-meta-http-equiv-refresh
+// Where and when a <meta http-equiv="refresh"> says to navigate to, e.g. content="5; url=/next" is /next after 5 seconds
+// The document is not navigated, the refresh is kept on it for whoever loaded it to follow, e.g. crawl --follow-refresh
+#[derive(Debug, Clone, PartialEq)]
+pub struct Refresh {
+    pub time: Duration,
+    // The URL resolved against the document base URL, the document's own URL when the refresh only reloads it
+    pub url: Url,
+}
+
+// The refresh of the first meta element with a valid refresh the parser inserted into document, None when there is none
+pub fn refresh(document: &RefNode) -> Option<Refresh> {
+    return match &document.borrow().data {
+        NodeData::Document(document) => document.refresh.clone(),
+        _ => None
+    };
+}
+
+// https://html.spec.whatwg.org/multipage/semantics.html#pragma-directives
+// Called when the parser inserts a meta element, the refresh state is the only http-equiv state that is acted on
+// TODO: A meta element inserted by a script is not processed
+pub(crate) fn process_http_equiv(meta: &RefNode) {
+    let is_refresh = get_attribute(meta, "http-equiv").is_some_and(|http_equiv| http_equiv.trim().eq_ignore_ascii_case("refresh"));
+    if !is_refresh {
+        return;
+    }
+    // 1. If the meta element has no content attribute, or if that attribute's value is the empty string, then return.
+    let input = match get_attribute(meta, "content") {
+        Some(content) if !content.is_empty() => content,
+        _ => return
+    };
+    let document = match node_document(meta) {
+        Some(document) => document,
+        None => return
+    };
+    // https://html.spec.whatwg.org/multipage/browsing-the-web.html#shared-declarative-refresh-steps
+    // 1. If document's will declaratively refresh is true, then return.
+    if refresh(&document).is_some() {
+        return;
+    }
+    if let Some(refresh) = parse_refresh(&input, meta) {
+        if let NodeData::Document(document) = &mut document.borrow_mut().data {
+            document.refresh = Some(refresh);
+        }
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/browsing-the-web.html#shared-declarative-refresh-steps
+// The time and URL of the content of a refresh, the URL is resolved against the document of node
+pub fn parse_refresh(input: &str, node: &RefNode) -> Option<Refresh> {
+    let is_whitespace = |character: char| matches!(character, '\u{0009}' | '\u{000A}' | '\u{000C}' | '\u{000D}' | '\u{0020}');
+    // 3. Skip ASCII whitespace within input given position.
+    let mut rest = input.trim_start_matches(is_whitespace);
+    // 5. Collect a sequence of code points that are ASCII digits from input given position, and let the result be timeString.
+    let time_string = &rest[..rest.bytes().take_while(|byte| byte.is_ascii_digit()).count()];
+    rest = &rest[time_string.len()..];
+    // 6. If timeString is the empty string, then: If the code point in input pointed to by position is not U+002E (.), then return.
+    // 7. Otherwise, set time to the result of parsing timeString using the rules for parsing non-negative integers.
+    if time_string.is_empty() && !rest.starts_with('.') {
+        return None;
+    }
+    let time = if time_string.is_empty() { 0 } else { time_string.parse::<u64>().unwrap_or(u64::MAX) };
+    // 8. Collect a sequence of code points that are ASCII digits and U+002E FULL STOP characters (.) from input given position. Ignore any collected characters.
+    rest = rest.trim_start_matches(|character: char| character.is_ascii_digit() || character == '.');
+
+    // 9. Let urlRecord be document's URL.
+    let mut url = node_document(node).and_then(|document| match &document.borrow().data {
+        NodeData::Document(document) => Some(document.url.clone()),
+        _ => None
+    }).unwrap_or_else(Url::about_blank);
+
+    // 10. If position is not past the end of input, then:
+    if !rest.is_empty() {
+        // 10.1. If the code point in input pointed to by position is not U+003B (;), U+002C (,), or ASCII whitespace, then return.
+        if !rest.starts_with([';', ',']) && !rest.starts_with(is_whitespace) {
+            return None;
+        }
+        // 10.2-10.4. Skip ASCII whitespace, a ; or , and ASCII whitespace again
+        rest = rest.trim_start_matches(is_whitespace);
+        rest = rest.strip_prefix([';', ',']).unwrap_or(rest);
+        rest = rest.trim_start_matches(is_whitespace);
+    }
+
+    // 11. If position is not past the end of input, then:
+    if !rest.is_empty() {
+        // 11.1. Let urlString be the code point substring from position to the end of input.
+        // 11.2-11.7. If it starts with "url" in any case, skip it, then ASCII whitespace, and if that is followed by a U+003D (=), skip it and ASCII whitespace,
+        // otherwise urlString is parsed as it is
+        let mut url_string = rest;
+        let mut skip_quotes = true;
+        if rest.starts_with(['U', 'u']) {
+            skip_quotes = false;
+            if rest.len() >= 3 && rest[..3].eq_ignore_ascii_case("url") {
+                let after_url = rest[3..].trim_start_matches(is_whitespace);
+                if let Some(after_equals) = after_url.strip_prefix('=') {
+                    url_string = after_equals.trim_start_matches(is_whitespace);
+                    skip_quotes = true;
+                }
+            }
+        }
+        // 11.8. Skip quotes: If the code point in input pointed to by position is U+0027 (') or U+0022 ("), then let quote be that code point, and advance position to the next code point.
+        // 11.9-11.10. Let urlString be the rest of input, and if quote is not the empty string and there is a quote in urlString, then truncate it before the first quote.
+        if skip_quotes {
+            if let Some(quote) = url_string.chars().next().filter(|character| matches!(character, '\'' | '"')) {
+                url_string = &url_string[1..];
+                if let Some(end) = url_string.find(quote) {
+                    url_string = &url_string[..end];
+                }
+            }
+        }
+        // 11.11. Parse: Set urlRecord to the result of encoding-parsing a URL given urlString, relative to document. If urlRecord is failure, then return.
+        url = encoding_parse_url(node, url_string).ok()?;
+    }
+
+    return Some(Refresh { time: Duration::from_secs(time), url });
+}
+
+// https://html.spec.whatwg.org/multipage/sections.html#headings-and-outlines-2
+// A heading and the headings of lower rank that follow it, before the next heading of the same or higher rank
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineEntry {
+    // The rank of the heading, 1 for h1 through 6 for h6
+    pub level: u32,
+    pub text: String,
+    pub children: Vec<OutlineEntry>,
+}
+
+// The headings of document nested by their rank, a heading that skips a rank (e.g. an h4 after an h2) is nested in the heading before it
+pub fn heading_outline(document: &RefNode) -> Vec<OutlineEntry> {
+    let mut outline: Vec<OutlineEntry> = Vec::new();
+    for element in elements(document) {
+        let level = match local_name(&element).as_str() {
+            "h1" => 1, "h2" => 2, "h3" => 3, "h4" => 4, "h5" => 5, "h6" => 6,
+            _ => continue
+        };
+        let text = strip_and_collapse_ascii_whitespace(&text_content(&element).unwrap_or_default());
+        insert_heading(&mut outline, OutlineEntry { level, text, children: Vec::new() });
+    }
+    return outline;
+}
+
+// Appends heading to the children of the last entry of lower level, or to entries when there is none
+fn insert_heading(entries: &mut Vec<OutlineEntry>, heading: OutlineEntry) {
+    match entries.last_mut() {
+        Some(last_entry) if last_entry.level < heading.level => insert_heading(&mut last_entry.children, heading),
+        _ => entries.push(heading)
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/scripting.html#data-block
+// A script element whose type is not one of a script, so it is never run, which carries data for the page (e.g. application/json, or application/ld+json structured data)
+// or a template its scripts fill in (e.g. text/template)
+pub struct DataBlock {
+    pub element: RefNode,
+    // The type attribute with leading and trailing whitespace stripped, e.g. "application/ld+json"
+    pub mime_type: String,
+    pub id: Option<String>,
+    // The child text content of the element
+    pub text: String,
+}
+
+impl DataBlock {
+    // The text of the block parsed as JSON, an Err with why when it is not JSON
+    pub fn json(&self) -> Result<serde_json::Value, String> {
+        return serde_json::from_str(&self.text).map_err(|error| error.to_string());
+    }
+}
+
+// The data blocks of document in tree order, see html_document_parser::script_type
+pub fn data_blocks(document: &RefNode) -> Vec<DataBlock> {
+    let mut data_blocks = Vec::new();
+    for element in elements(document).into_iter().filter(|element| local_name(element) == "script") {
+        if script_type(&element).is_some() {
+            continue;
+        }
+        let mime_type = get_attribute(&element, "type").or_else(|| get_attribute(&element, "language").map(|language| format!("text/{}", language))).unwrap_or_default();
+        let mime_type = mime_type.trim_matches(is_ascii_whitespace).to_string();
+        let id = get_attribute(&element, "id");
+        let text = child_text_content(&element);
+        data_blocks.push(DataBlock { element, mime_type, id, text });
+    }
+    return data_blocks;
+}
+
+// The element descendants of document in tree order
+fn elements(document: &RefNode) -> Vec<RefNode> {
+    return descendants(document).into_iter().filter(|node| matches!(node.borrow().data, NodeData::Element(_))).collect();
+}
+
+fn local_name(element: &RefNode) -> String {
+    match &element.borrow().data {
+        NodeData::Element(element) => element.local_name().to_string(),
+        _ => String::new()
+    }
+}
+
+fn get_attribute(element: &RefNode, qualified_name: &str) -> Option<String> {
+    match &element.borrow().data {
+        NodeData::Element(element) => element.get_attribute(qualified_name).cloned(),
+        _ => None
+    }
+}
+
+// https://dom.spec.whatwg.org/#concept-child-text-content
+// The concatenation of the data of all the Text node children of node, in tree order
+fn child_text_content(node: &RefNode) -> String {
+    let mut text = String::new();
+    for child in &node.borrow().childNodes {
+        if let NodeData::Text(child_text) = &child.borrow().data {
+            text.push_str(&child_text.character_data.data);
+        }
+    }
+    return text;
+}
+
+// https://infra.spec.whatwg.org/#strip-and-collapse-ascii-whitespace
+fn strip_and_collapse_ascii_whitespace(text: &str) -> String {
+    return text.split_ascii_whitespace().collect::<Vec<&str>>().join(" ");
+}
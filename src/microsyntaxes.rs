@@ -0,0 +1,116 @@
+// https://html.spec.whatwg.org/multipage/common-microsyntaxes.html
+// The rules for parsing the values of attributes, each returns None where the spec says to return an error
+// Element has an accessor for each of them (e.g. Element::integer_attribute), so a value is parsed the same way wherever it is read
+
+// https://infra.spec.whatwg.org/#ascii-whitespace
+pub(crate) fn is_ascii_whitespace(character: char) -> bool {
+    return matches!(character, '\u{0009}' | '\u{000A}' | '\u{000C}' | '\u{000D}' | '\u{0020}');
+}
+
+// https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#rules-for-parsing-integers
+// Leading whitespace and a sign are allowed, and anything after the digits is ignored, e.g. " -12px" is -12
+pub fn parse_integer(input: &str) -> Option<i64> {
+    // 4. Skip ASCII whitespace within input given position.
+    let input = input.trim_start_matches(is_ascii_whitespace);
+    // 6. If the character indicated by position is a U+002D HYPHEN-MINUS character (-), let sign be "negative" and advance position, if it is a U+002B PLUS SIGN character (+), advance position.
+    let (negative, input) = match input.as_bytes().first() {
+        Some(b'-') => (true, &input[1..]),
+        Some(b'+') => (false, &input[1..]),
+        _ => (false, input)
+    };
+    // 7. If the character indicated by position is not an ASCII digit, then return an error.
+    // 8. Collect a sequence of code points that are ASCII digits from input given position, and interpret the resulting sequence as a base-ten integer.
+    let digits = &input[..input.bytes().take_while(|byte| byte.is_ascii_digit()).count()];
+    if digits.is_empty() {
+        return None;
+    }
+    // A number too big for an i64 is an error rather than wrapping around
+    let value = digits.parse::<i64>().ok()?;
+    // 9. If sign is "positive", return value, otherwise return the result of subtracting value from zero.
+    return Some(if negative { -value } else { value });
+}
+
+// https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#rules-for-parsing-non-negative-integers
+pub fn parse_non_negative_integer(input: &str) -> Option<u64> {
+    // 2. If value is an error, return an error. 3. If value is less than zero, return an error.
+    return parse_integer(input).and_then(|value| u64::try_from(value).ok());
+}
+
+// https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#rules-for-parsing-floating-point-number-values
+// Like integers leading whitespace is allowed and anything after the number is ignored, e.g. "1.5e3 meters" is 1500
+pub fn parse_floating_point_number(input: &str) -> Option<f64> {
+    // 4. Skip ASCII whitespace within input given position.
+    let input = input.trim_start_matches(is_ascii_whitespace);
+    let bytes = input.as_bytes();
+    let mut position = 0;
+    // 5-6. A - makes the value negative, and a + is allowed before the digits
+    let negative = match bytes.first() {
+        Some(b'-') => { position += 1; true },
+        Some(b'+') => { position += 1; false },
+        _ => false
+    };
+    let digit_count = |position: usize| bytes[position..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+
+    // 8. If the character indicated by position is a U+002E FULL STOP (.), and that is not the last character in input, and the character after the character indicated by position is an ASCII digit,
+    // then set value to zero and jump to the step labeled fraction.
+    // 9. If the character indicated by position is not an ASCII digit, then return an error.
+    let integer_digits = digit_count(position);
+    let starts_with_fraction = integer_digits == 0 && bytes.get(position) == Some(&b'.') && bytes.get(position + 1).is_some_and(|byte| byte.is_ascii_digit());
+    if integer_digits == 0 && !starts_with_fraction {
+        return None;
+    }
+    let mut end = position + integer_digits;
+
+    // 11. Fraction: If the character indicated by position is a U+002E FULL STOP (.), the digits after it are the fraction of the value, and an exponent can follow it even without them
+    if bytes.get(end) == Some(&b'.') {
+        end += 1 + digit_count(end + 1);
+    }
+
+    // 12. If the character indicated by position is U+0065 (e) or a U+0045 (E), followed by an optional - or + and digits, they are the exponent
+    // An e that is not followed by digits is ignored along with the rest of the input
+    if matches!(bytes.get(end), Some(b'e') | Some(b'E')) {
+        let sign_length = if matches!(bytes.get(end + 1), Some(b'-') | Some(b'+')) { 1 } else { 0 };
+        let exponent_digits = digit_count(end + 1 + sign_length);
+        if exponent_digits > 0 {
+            end += 1 + sign_length + exponent_digits;
+        }
+    }
+
+    // Rust parses the digits, fraction and exponent the way the steps add them up, only more precisely
+    let value: f64 = input[position..end].parse().ok()?;
+    // 14. Conversion: If value is infinite (too big to be a double), return an error.
+    if !value.is_finite() {
+        return None;
+    }
+    // 15. Return the result of rounding value * sign to the nearest number in the set of IEEE 754 double-precision floating point values, -0 becoming 0.
+    let value = if negative { -value } else { value };
+    return Some(if value == 0.0 { 0.0 } else { value });
+}
+
+// https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#set-of-space-separated-tokens
+// https://infra.spec.whatwg.org/#split-on-ascii-whitespace
+// The tokens of e.g. a class or rel attribute in order, without the tokens that are repeated
+pub fn split_on_ascii_whitespace(input: &str) -> Vec<&str> {
+    let mut tokens: Vec<&str> = Vec::new();
+    for token in input.split(is_ascii_whitespace).filter(|token| !token.is_empty()) {
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
+    return tokens;
+}
+
+// https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#comma-separated-tokens
+// https://infra.spec.whatwg.org/#split-on-commas
+// The tokens between the commas of e.g. an accept attribute with the whitespace around them removed, a token can be empty, e.g. "a, ,b" is "a", "" and "b"
+pub fn split_on_commas(input: &str) -> Vec<&str> {
+    if input.is_empty() {
+        return Vec::new();
+    }
+    let mut tokens: Vec<&str> = input.split(',').map(|token| token.trim_matches(is_ascii_whitespace)).collect();
+    // The position is past the end of the input after a trailing comma, so there is no token after it
+    if input.ends_with(',') {
+        tokens.pop();
+    }
+    return tokens;
+}
@@ -0,0 +1,338 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::cssom::{document_css_style_sheets, CSSStyleSheet};
+use crate::layout::{element_display_and_white_space, Display, WhiteSpace};
+use crate::node::{Attr, Node, NodeData, RefNode};
+use crate::serializer::{escape_text, is_raw_text_element, is_void_element, needs_leading_line_feed, serialize_attribute};
+
+// Serializes a document again as the shortest HTML that renders the same, leaving out what the parser or the renderer does not need
+// Which whitespace is rendered is decided the way layout decides it, from the display and white-space of the elements around it,
+// so only whitespace that collapses away is removed and whitespace that white-space preserves (e.g. in a pre) is kept as it is
+// TODO: Start tags that can be omitted (e.g. of html, head and body) are kept, and attribute values are always quoted
+// TODO: The end tags are omitted where a conforming parser implies them, but the tree builder here does not imply all of them yet (e.g. a li start tag does not close the li before it)
+
+#[derive(Debug, Clone)]
+pub struct MinifyOptions {
+    // Runs of whitespace become a single space, and whitespace at the start and end of a block, which is not rendered, is removed
+    pub collapse_whitespace: bool,
+    // Conditional comments (e.g. <!--[if IE]>) are kept as they are read by old browsers
+    pub remove_comments: bool,
+    // Attributes whose value is the one the element has without them, e.g. type="text/javascript" on a script
+    pub remove_default_attributes: bool,
+    // disabled="disabled" becomes disabled
+    pub shorten_boolean_attributes: bool,
+    // End tags the parser implies where they are left out, e.g. the </li> before another li
+    pub omit_optional_end_tags: bool,
+}
+
+impl Default for MinifyOptions {
+    fn default() -> Self {
+        return MinifyOptions { collapse_whitespace: true, remove_comments: true, remove_default_attributes: true, shorten_boolean_attributes: true, omit_optional_end_tags: true };
+    }
+}
+
+// A child of an element that is kept in the output, with its text already minified
+enum Item {
+    Text(String),
+    Comment(String),
+    // The element and its index in the children
+    Element(RefNode, usize),
+    DocumentType(String),
+}
+
+struct Minifier<'a> {
+    options: &'a MinifyOptions,
+    style_sheets: Vec<Rc<RefCell<CSSStyleSheet>>>,
+    // The display of each element looked at so far, the cascade is only run once for each of them
+    displays: HashMap<*const RefCell<Node>, Display>,
+    // Where the text of a plaintext element ends in the output, the input ends with it as there is no end tag for it
+    plaintext_end: Option<usize>,
+}
+
+pub fn minify_document(document: &RefNode, options: &MinifyOptions) -> String {
+    let mut minifier = Minifier { options, style_sheets: document_css_style_sheets(document), displays: HashMap::new(), plaintext_end: None };
+    let mut html = String::new();
+    minifier.minify_children(document, WhiteSpace::Normal, true, true, &mut html);
+    if let Some(plaintext_end) = minifier.plaintext_end {
+        html.truncate(plaintext_end);
+    }
+    return html;
+}
+
+impl Minifier<'_> {
+    // The children of node, which starts and ends a line when block_at_start and block_at_end are true (e.g. node is a block)
+    fn minify_children(&mut self, node: &RefNode, white_space: WhiteSpace, block_at_start: bool, block_at_end: bool, html: &mut String) {
+        let children: Vec<RefNode> = node.borrow().childNodes.to_vec();
+        let literal_text = match &node.borrow().data {
+            NodeData::Element(element) => is_raw_text_element(element.local_name()),
+            _ => false
+        };
+        let collapse_whitespace = self.options.collapse_whitespace && !literal_text && matches!(white_space, WhiteSpace::Normal | WhiteSpace::Nowrap);
+
+        let mut items = Vec::new();
+        for (index, child) in children.iter().enumerate() {
+            let item = match &child.borrow().data {
+                NodeData::Text(text) if collapse_whitespace => {
+                    let mut data = collapse_ascii_whitespace(&text.character_data.data);
+                    // Spaces at the start and end of a line are removed by line breaking, so they are not rendered
+                    if self.block_before(&children, index, block_at_start) {
+                        data = data.trim_start_matches(' ').to_string();
+                    }
+                    if self.block_after(&children, index, block_at_end) {
+                        data = data.trim_end_matches(' ').to_string();
+                    }
+                    Item::Text(escape_text(&data))
+                },
+                NodeData::Text(text) if literal_text => Item::Text(text.character_data.data.to_string()),
+                NodeData::Text(text) => Item::Text(escape_text(&text.character_data.data)),
+                NodeData::CharacterData(character_data) => Item::Text(escape_text(&character_data.data)),
+                NodeData::Comment(comment) if self.options.remove_comments && !is_conditional_comment(&comment.character_data.data) => continue,
+                NodeData::Comment(comment) => Item::Comment(comment.character_data.data.to_string()),
+                NodeData::DocumentType(doctype) => Item::DocumentType(doctype.name.to_string()),
+                NodeData::Element(_) => Item::Element(Rc::clone(child), index),
+                NodeData::Document(_) => continue
+            };
+            if !matches!(&item, Item::Text(text) if text.is_empty()) {
+                items.push(item);
+            }
+        }
+
+        let parent_local_name = match &node.borrow().data {
+            NodeData::Element(element) => element.local_name().to_string(),
+            _ => String::new()
+        };
+        for (index, item) in items.iter().enumerate() {
+            match item {
+                Item::Text(text) => html.push_str(text),
+                Item::Comment(data) => html.push_str(&format!("<!--{}-->", data)),
+                Item::DocumentType(name) => html.push_str(&format!("<!DOCTYPE {}>", name)),
+                Item::Element(element, child_index) => {
+                    let child_index = *child_index;
+                    let block_before = self.block_before(&children, child_index, block_at_start);
+                    let block_after = self.block_after(&children, child_index, block_at_end);
+                    self.minify_element(element, white_space, block_before, block_after, html);
+
+                    let omit_end_tag = self.options.omit_optional_end_tags && end_tag_can_be_omitted(element, items.get(index + 1), &parent_local_name);
+                    if let NodeData::Element(element) = &element.borrow().data {
+                        if element.local_name() == "plaintext" {
+                            self.plaintext_end = self.plaintext_end.or(Some(html.len()));
+                        } else if !omit_end_tag && !is_void_element(element.local_name()) {
+                            html.push_str(&format!("</{}>", element.local_name()));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // The start tag and the contents of element, which is followed by its end tag unless that can be omitted
+    fn minify_element(&mut self, element_node: &RefNode, parent_white_space: WhiteSpace, block_before: bool, block_after: bool, html: &mut String) {
+        let (display, white_space) = element_display_and_white_space(element_node, &self.style_sheets, parent_white_space);
+        self.displays.insert(Rc::as_ptr(element_node), display);
+
+        let local_name = match &element_node.borrow().data {
+            NodeData::Element(element) => {
+                html.push('<');
+                html.push_str(element.local_name());
+                for attribute in element.attribute_list() {
+                    if self.options.remove_default_attributes && self.is_default_attribute(element.local_name(), attribute) {
+                        continue;
+                    }
+                    html.push(' ');
+                    if self.options.shorten_boolean_attributes && is_boolean_attribute(&attribute.local_name) {
+                        html.push_str(&attribute.local_name);
+                    } else {
+                        html.push_str(&serialize_attribute(attribute));
+                    }
+                }
+                html.push('>');
+                element.local_name().to_string()
+            },
+            _ => return
+        };
+        if is_void_element(&local_name) {
+            return;
+        }
+
+        if needs_leading_line_feed(&element_node.borrow()) {
+            html.push('\n');
+        }
+        // The contents of an inline element are on the line of what is around it, the contents of anything else start and end a line of their own
+        match display {
+            Display::Inline => self.minify_children(element_node, white_space, block_before, block_after, html),
+            Display::Block | Display::ListItem | Display::None => self.minify_children(element_node, white_space, true, true, html)
+        }
+    }
+
+    // Whether the line is broken right before the child at index of children, so a space at the start of the child is not rendered
+    // Elements that are not rendered and comments are skipped over, the line is broken when a block comes before, or when nothing does and the parent starts a line
+    fn block_before(&mut self, children: &[RefNode], index: usize, block_at_start: bool) -> bool {
+        for sibling in children[..index].iter().rev() {
+            if let Some(block) = self.breaks_line(sibling) {
+                return block;
+            }
+        }
+        return block_at_start;
+    }
+
+    fn block_after(&mut self, children: &[RefNode], index: usize, block_at_end: bool) -> bool {
+        for sibling in &children[index + 1..] {
+            if let Some(block) = self.breaks_line(sibling) {
+                return block;
+            }
+        }
+        return block_at_end;
+    }
+
+    // Some(true) for a block, Some(false) for content that is on the line (text or an inline element), None for what is not rendered
+    // Text that is only whitespace collapses into the space next to it, so it is skipped over too
+    fn breaks_line(&mut self, node: &RefNode) -> Option<bool> {
+        match &node.borrow().data {
+            NodeData::Text(text) => return if collapse_ascii_whitespace(&text.character_data.data).trim_start_matches(' ').is_empty() { None } else { Some(false) },
+            NodeData::Element(_) => (),
+            _ => return None
+        }
+        let display = match self.displays.get(&Rc::as_ptr(node)) {
+            Some(display) => *display,
+            None => {
+                let display = element_display_and_white_space(node, &self.style_sheets, WhiteSpace::Normal).0;
+                self.displays.insert(Rc::as_ptr(node), display);
+                display
+            }
+        };
+        match display {
+            Display::Block | Display::ListItem => return Some(true),
+            Display::Inline => return Some(false),
+            Display::None => return None
+        }
+    }
+
+    // An attribute whose value is what the element would behave as without it
+    // It is kept when a selector of the document's style sheets refers to it, e.g. input[type=text], which stops matching without it
+    fn is_default_attribute(&self, local_name: &str, attribute: &Attr) -> bool {
+        let value = attribute.value.trim().to_ascii_lowercase();
+        let default = match (local_name, attribute.local_name.as_str()) {
+            // https://html.spec.whatwg.org/multipage/scripting.html#attr-script-type
+            ("script", "type") => value == "text/javascript",
+            ("style", "type") | ("link", "type") => value == "text/css",
+            // https://html.spec.whatwg.org/multipage/forms.html#attr-fs-method
+            ("form", "method") => value == "get",
+            ("form", "enctype") => value == "application/x-www-form-urlencoded",
+            // https://html.spec.whatwg.org/multipage/input.html#attr-input-type
+            ("input", "type") => value == "text",
+            // https://html.spec.whatwg.org/multipage/form-elements.html#attr-button-type
+            ("button", "type") => value == "submit",
+            // https://html.spec.whatwg.org/multipage/image-maps.html#attr-area-shape
+            ("area", "shape") => value == "rect",
+            // https://html.spec.whatwg.org/multipage/tables.html#attr-tdth-colspan
+            ("td", "colspan") | ("th", "colspan") | ("td", "rowspan") | ("th", "rowspan") => value == "1",
+            _ => false
+        };
+        if !default {
+            return false;
+        }
+
+        let attribute_selector = format!("[{}", attribute.local_name);
+        return !self.style_sheets.iter().any(|style_sheet| {
+            style_sheet.borrow().css_rules.iter().any(|rule| rule.borrow().selector_text.to_ascii_lowercase().contains(&attribute_selector))
+        });
+    }
+}
+
+// Each run of ASCII whitespace becomes a single space
+fn collapse_ascii_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut previous_was_whitespace = false;
+    for character in text.chars() {
+        if matches!(character, '\t' | '\n' | '\u{000C}' | '\r' | ' ') {
+            if !previous_was_whitespace {
+                collapsed.push(' ');
+            }
+            previous_was_whitespace = true;
+        } else {
+            collapsed.push(character);
+            previous_was_whitespace = false;
+        }
+    }
+    return collapsed;
+}
+
+fn is_conditional_comment(data: &str) -> bool {
+    return data.starts_with("[if ") || data.starts_with("<![endif]");
+}
+
+// https://html.spec.whatwg.org/multipage/indices.html#attributes-3
+// The presence of a boolean attribute is what matters and not its value
+fn is_boolean_attribute(local_name: &str) -> bool {
+    return matches!(local_name,
+        "allowfullscreen" | "async" | "autofocus" | "autoplay" | "checked" | "controls" | "default" | "defer" | "disabled" | "formnovalidate" | "hidden" | "inert" | "ismap" | "itemscope"
+        | "loop" | "multiple" | "muted" | "nomodule" | "novalidate" | "open" | "playsinline" | "readonly" | "required" | "reversed" | "selected");
+}
+
+// https://html.spec.whatwg.org/multipage/syntax.html#optional-tags
+// Whether the end tag of element can be left out given what comes right after it in the output, None when nothing does
+fn end_tag_can_be_omitted(element: &RefNode, next_item: Option<&Item>, parent_local_name: &str) -> bool {
+    let local_name = match &element.borrow().data {
+        NodeData::Element(element) => element.local_name().to_string(),
+        _ => return false
+    };
+    let next_element = match next_item {
+        Some(Item::Element(next, _)) => match &next.borrow().data {
+            NodeData::Element(next) => Some(next.local_name().to_string()),
+            _ => None
+        },
+        _ => None
+    };
+    let next_element = next_element.as_deref();
+    let followed_by_whitespace_or_comment = match next_item {
+        Some(Item::Text(text)) => text.starts_with(['\t', '\n', '\u{000C}', '\r', ' ']),
+        Some(Item::Comment(_)) => true,
+        _ => false
+    };
+
+    match local_name.as_str() {
+        // An html element's end tag may be omitted if the html element is not immediately followed by a comment.
+        // A body element's end tag may be omitted if the body element is not immediately followed by a comment.
+        "html" | "body" => return !matches!(next_item, Some(Item::Comment(_))),
+        // A head element's end tag may be omitted if the head element is not immediately followed by ASCII whitespace or a comment.
+        // A colgroup element's end tag may be omitted if the colgroup element is not immediately followed by ASCII whitespace or a comment.
+        // A caption element's end tag may be omitted if the caption element is not immediately followed by ASCII whitespace or a comment.
+        "head" | "colgroup" | "caption" => return !followed_by_whitespace_or_comment,
+        // An li element's end tag may be omitted if the li element is immediately followed by another li element or if there is no more content in the parent element.
+        "li" => return next_item.is_none() || next_element == Some("li"),
+        // A dt element's end tag may be omitted if the dt element is immediately followed by another dt element or a dd element.
+        "dt" => return matches!(next_element, Some("dt" | "dd")),
+        // A dd element's end tag may be omitted if the dd element is immediately followed by another dd element or a dt element, or if there is no more content in the parent element.
+        "dd" => return next_item.is_none() || matches!(next_element, Some("dt" | "dd")),
+        // A p element's end tag may be omitted if the p element is immediately followed by an address, article, aside, blockquote, details, dialog, div, dl, fieldset, figcaption, figure, footer, form, h1, h2, h3, h4, h5, h6, header, hgroup, hr, main, menu, nav, ol, p, pre, search, section, table, or ul element,
+        // or if there is no more content in the parent element and the parent element is an HTML element that is not an a, audio, del, ins, map, noscript, or video element, or an autonomous custom element.
+        // A table start tag does not close a p in quirks mode, so the end tag is kept before a table
+        "p" => {
+            if next_item.is_none() {
+                return !parent_local_name.is_empty() && !parent_local_name.contains('-') && !matches!(parent_local_name, "a" | "audio" | "del" | "ins" | "map" | "noscript" | "video");
+            }
+            return matches!(next_element, Some("address" | "article" | "aside" | "blockquote" | "details" | "dialog" | "div" | "dl" | "fieldset" | "figcaption" | "figure" | "footer" | "form"
+                | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "header" | "hgroup" | "hr" | "main" | "menu" | "nav" | "ol" | "p" | "pre" | "search" | "section" | "ul"));
+        },
+        // An rt element's end tag may be omitted if the rt element is immediately followed by an rt or rp element, or if there is no more content in the parent element.
+        // An rp element's end tag may be omitted if the rp element is immediately followed by an rt or rp element, or if there is no more content in the parent element.
+        "rt" | "rp" => return next_item.is_none() || matches!(next_element, Some("rt" | "rp")),
+        // An optgroup element's end tag may be omitted if the optgroup element is immediately followed by another optgroup element, if it is immediately followed by an hr element, or if there is no more content in the parent element.
+        "optgroup" => return next_item.is_none() || matches!(next_element, Some("optgroup" | "hr")),
+        // An option element's end tag may be omitted if the option element is immediately followed by another option element, if it is immediately followed by an optgroup element, if it is immediately followed by an hr element, or if there is no more content in the parent element.
+        "option" => return next_item.is_none() || matches!(next_element, Some("option" | "optgroup" | "hr")),
+        // A thead element's end tag may be omitted if the thead element is immediately followed by a tbody or tfoot element.
+        "thead" => return matches!(next_element, Some("tbody" | "tfoot")),
+        // A tbody element's end tag may be omitted if the tbody element is immediately followed by a tbody or tfoot element, or if there is no more content in the parent element.
+        "tbody" => return next_item.is_none() || matches!(next_element, Some("tbody" | "tfoot")),
+        // A tfoot element's end tag may be omitted if there is no more content in the parent element.
+        "tfoot" => return next_item.is_none(),
+        // A tr element's end tag may be omitted if the tr element is immediately followed by another tr element, or if there is no more content in the parent element.
+        "tr" => return next_item.is_none() || next_element == Some("tr"),
+        // A td element's end tag may be omitted if the td element is immediately followed by a td or th element, or if there is no more content in the parent element.
+        // A th element's end tag may be omitted if the th element is immediately followed by a td or th element, or if there is no more content in the parent element.
+        "td" | "th" => return next_item.is_none() || matches!(next_element, Some("td" | "th")),
+        _ => return false
+    }
+}
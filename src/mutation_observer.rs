@@ -0,0 +1,568 @@
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use crate::dom_bindings::{argument_to_dom_string, create_interface_prototype_object, install_interface_object};
+use crate::event_loop::Task;
+use crate::events::dictionary_boolean_member;
+use crate::gc::{Trace, Tracer};
+use crate::interpreter::{completion_value, create_normal_completion, CompletionRecord, CompletionRecordType, Interpreter, JSObject, JSValue, PlatformObject, PropertyKey, ReferenceRecordOrJsValue};
+use crate::node::{DOMString, RefNode, WeakNode};
+use crate::webidl::{attribute_getter, create_platform_object, define_attributes, define_operations, operation, Exception, FromJSValue, ToJSValue};
+
+// https://dom.spec.whatwg.org/#mutation-observers
+// Observers are told about the changes made to the nodes they observe, in a microtask after the changes, with the records of all of them
+// TODO: The parser does not queue mutation records for the nodes it inserts, and there are no transient registered observers,
+//       so an observer of a subtree does not see the changes made to a node after it was removed from that subtree
+
+// https://dom.spec.whatwg.org/#dictdef-mutationobserverinit
+// A member that is None was omitted, observe fills in attributes and characterData from the other members
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MutationObserverInit {
+    pub child_list: bool,
+    pub attributes: Option<bool>,
+    pub character_data: Option<bool>,
+    pub subtree: bool,
+    pub attribute_old_value: Option<bool>,
+    pub character_data_old_value: Option<bool>,
+    pub attribute_filter: Option<Vec<DOMString>>,
+}
+
+// https://dom.spec.whatwg.org/#registered-observer
+#[derive(Clone)]
+pub(crate) struct RegisteredObserver {
+    observer: Rc<RefCell<MutationObserver>>,
+    options: MutationObserverInit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MutationRecordType {
+    ChildList,
+    Attributes,
+    CharacterData,
+}
+
+impl MutationRecordType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MutationRecordType::ChildList => "childList",
+            MutationRecordType::Attributes => "attributes",
+            MutationRecordType::CharacterData => "characterData",
+        }
+    }
+}
+
+// https://dom.spec.whatwg.org/#interface-mutationrecord
+#[derive(Clone)]
+pub struct MutationRecord {
+    pub type_: MutationRecordType,
+    pub target: RefNode,
+    pub added_nodes: Vec<RefNode>,
+    pub removed_nodes: Vec<RefNode>,
+    pub previous_sibling: Option<RefNode>,
+    pub next_sibling: Option<RefNode>,
+    pub attribute_name: Option<DOMString>,
+    pub old_value: Option<DOMString>,
+}
+
+impl MutationRecord {
+    // https://dom.spec.whatwg.org/#queue-a-tree-mutation-record
+    pub(crate) fn child_list(target: &RefNode, added_nodes: Vec<RefNode>, removed_nodes: Vec<RefNode>, previous_sibling: Option<RefNode>, next_sibling: Option<RefNode>) -> MutationRecord {
+        MutationRecord { type_: MutationRecordType::ChildList, target: Rc::clone(target), added_nodes, removed_nodes, previous_sibling, next_sibling, attribute_name: None, old_value: None }
+    }
+
+    pub(crate) fn attributes(target: &RefNode, attribute_name: &str, old_value: Option<DOMString>) -> MutationRecord {
+        MutationRecord { type_: MutationRecordType::Attributes, target: Rc::clone(target), added_nodes: Vec::new(), removed_nodes: Vec::new(), previous_sibling: None, next_sibling: None, attribute_name: Some(attribute_name.to_string()), old_value }
+    }
+
+    pub(crate) fn character_data(target: &RefNode, old_value: DOMString) -> MutationRecord {
+        MutationRecord { type_: MutationRecordType::CharacterData, target: Rc::clone(target), added_nodes: Vec::new(), removed_nodes: Vec::new(), previous_sibling: None, next_sibling: None, attribute_name: None, old_value: Some(old_value) }
+    }
+}
+
+// https://dom.spec.whatwg.org/#interface-mutationobserver
+// An observer without a callback is never notified, its records are kept until they are taken, e.g. by a test of the engine
+pub struct MutationObserver {
+    // https://dom.spec.whatwg.org/#concept-mo-callback
+    callback: Option<Rc<RefCell<JSObject>>>,
+    // https://dom.spec.whatwg.org/#mutationobserver-node-list
+    node_list: Vec<WeakNode>,
+    // https://dom.spec.whatwg.org/#concept-mo-queue
+    record_queue: Vec<MutationRecord>,
+}
+
+impl Trace for RegisteredObserver {
+    fn trace(&self, tracer: &mut Tracer) {
+        tracer.visit(&self.observer);
+    }
+}
+
+// The nodes of the node list are weak references, the records that were not delivered yet hold theirs strongly
+impl Trace for MutationObserver {
+    fn trace(&self, tracer: &mut Tracer) {
+        if let Some(callback) = &self.callback {
+            tracer.visit(callback);
+        }
+        for record in &self.record_queue {
+            tracer.visit(&record.target);
+            for node in record.added_nodes.iter().chain(record.removed_nodes.iter()).chain(record.previous_sibling.iter()).chain(record.next_sibling.iter()) {
+                tracer.visit(node);
+            }
+        }
+    }
+}
+
+impl MutationObserver {
+    pub fn new() -> Rc<RefCell<MutationObserver>> {
+        return MutationObserver::with_callback(None);
+    }
+
+    fn with_callback(callback: Option<Rc<RefCell<JSObject>>>) -> Rc<RefCell<MutationObserver>> {
+        let observer = Rc::new(RefCell::new(MutationObserver { callback, node_list: Vec::new(), record_queue: Vec::new() }));
+        // https://dom.spec.whatwg.org/#dom-mutationobserver-mutationobserver
+        // 2. Append mo to mo's relevant agent's mutation observers.
+        AGENT.with(|agent| agent.borrow_mut().mutation_observers.push(Rc::downgrade(&observer)));
+        return observer;
+    }
+
+    // https://dom.spec.whatwg.org/#dom-mutationobserver-observe
+    // An Err is the message of the TypeError the options throw
+    pub fn observe(observer: &Rc<RefCell<MutationObserver>>, target: &RefNode, mut options: MutationObserverInit) -> Result<(), String> {
+        // 1. If either options["attributeOldValue"] or options["attributeFilter"] exists, and options["attributes"] does not exist, then set options["attributes"] to true.
+        if (options.attribute_old_value.is_some() || options.attribute_filter.is_some()) && options.attributes.is_none() {
+            options.attributes = Some(true);
+        }
+
+        // 2. If options["characterDataOldValue"] exists and options["characterData"] does not exist, then set options["characterData"] to true.
+        if options.character_data_old_value.is_some() && options.character_data.is_none() {
+            options.character_data = Some(true);
+        }
+
+        // 3. If none of options["childList"], options["attributes"], and options["characterData"] is true, then throw a TypeError.
+        let attributes = options.attributes == Some(true);
+        let character_data = options.character_data == Some(true);
+        if !options.child_list && !attributes && !character_data {
+            return Err(String::from("The options object must set at least one of 'attributes', 'characterData', or 'childList' to true."));
+        }
+
+        // 4. If options["attributeOldValue"] is true and options["attributes"] is false, then throw a TypeError.
+        if options.attribute_old_value == Some(true) && !attributes {
+            return Err(String::from("The options object may only set 'attributeOldValue' to true when 'attributes' is true or not present."));
+        }
+
+        // 5. If options["attributeFilter"] is present and options["attributes"] is false, then throw a TypeError.
+        if options.attribute_filter.is_some() && !attributes {
+            return Err(String::from("The options object may only set 'attributeFilter' when 'attributes' is true or not present."));
+        }
+
+        // 6. If options["characterDataOldValue"] is true and options["characterData"] is false, then throw a TypeError.
+        if options.character_data_old_value == Some(true) && !character_data {
+            return Err(String::from("The options object may only set 'characterDataOldValue' to true when 'characterData' is true or not present."));
+        }
+
+        // 7. For each registered of target's registered observer list, if registered's observer is this:
+        //        2. Set registered's options to options.
+        let mut target_node = target.borrow_mut();
+        if let Some(registered) = target_node.registered_observer_list.iter_mut().find(|registered| Rc::ptr_eq(&registered.observer, observer)) {
+            registered.options = options;
+            return Ok(());
+        }
+
+        // 8. Otherwise:
+        //        1. Append a new registered observer whose observer is this and options is options to target's registered observer list.
+        //        2. Append a weak reference to target to this's node list.
+        target_node.registered_observer_list.push(RegisteredObserver { observer: Rc::clone(observer), options });
+        observer.borrow_mut().node_list.push(Rc::downgrade(target));
+        return Ok(());
+    }
+
+    // https://dom.spec.whatwg.org/#dom-mutationobserver-disconnect
+    pub fn disconnect(observer: &Rc<RefCell<MutationObserver>>) {
+        // 1. For each node of this's node list, remove any registered observer from node's registered observer list for which this is the observer.
+        let node_list = std::mem::take(&mut observer.borrow_mut().node_list);
+        for node in node_list.iter().filter_map(|node| node.upgrade()) {
+            node.borrow_mut().registered_observer_list.retain(|registered| !Rc::ptr_eq(&registered.observer, observer));
+        }
+
+        // 2. Empty this's record queue.
+        observer.borrow_mut().record_queue.clear();
+    }
+
+    // https://dom.spec.whatwg.org/#dom-mutationobserver-takerecords
+    pub fn take_records(&mut self) -> Vec<MutationRecord> {
+        // 1. Let records be a clone of this's record queue.
+        // 2. Empty this's record queue.
+        // 3. Return records.
+        return std::mem::take(&mut self.record_queue);
+    }
+}
+
+// https://dom.spec.whatwg.org/#mutation-observers
+// The state of the similar-origin window agent, which is the thread the DOM is changed on
+#[derive(Default)]
+struct Agent {
+    // https://dom.spec.whatwg.org/#mutation-observer-microtask-queued
+    mutation_observer_microtask_queued: bool,
+    // Set when the microtask was queued but has not been put in the microtask queue of the event loop yet,
+    // as the DOM is changed without access to the event loop, it is put there by the next microtask checkpoint
+    microtask_waiting: bool,
+    // https://dom.spec.whatwg.org/#mutation-observer-list
+    // In the order they were created, an observer nothing refers to anymore is removed when the observers are notified
+    mutation_observers: Vec<Weak<RefCell<MutationObserver>>>,
+}
+
+thread_local! {
+    static AGENT: RefCell<Agent> = RefCell::new(Agent::default());
+}
+
+// https://dom.spec.whatwg.org/#queue-a-mutation-record
+// The old value of the record is only given to the observers that asked for it
+pub(crate) fn queue_a_mutation_record(record: MutationRecord) {
+    // 1. Let interestedObservers be an empty map.
+    let mut interested_observers: Vec<(Rc<RefCell<MutationObserver>>, Option<DOMString>)> = Vec::new();
+
+    // 2. Let nodes be the inclusive ancestors of target.
+    // 3. For each node in nodes, and then for each registered of node's registered observer list:
+    let mut node = Some(Rc::clone(&record.target));
+    while let Some(current) = node {
+        for registered in &current.borrow().registered_observer_list {
+            // 1. Let options be registered's options.
+            let options = &registered.options;
+
+            // 2. If none of the following are true
+            //        node is not target and options["subtree"] is false
+            //        type is "attributes" and options["attributes"] either does not exist or is false
+            //        type is "attributes", options["attributeFilter"] exists, and options["attributeFilter"] does not contain name or namespace is non-null
+            //        type is "characterData" and options["characterData"] either does not exist or is false
+            //        type is "childList" and options["childList"] is false
+            let excluded = (!Rc::ptr_eq(&current, &record.target) && !options.subtree)
+                || (record.type_ == MutationRecordType::Attributes && options.attributes != Some(true))
+                || (record.type_ == MutationRecordType::Attributes && options.attribute_filter.as_ref().is_some_and(|filter| !record.attribute_name.as_ref().is_some_and(|name| filter.contains(name))))
+                || (record.type_ == MutationRecordType::CharacterData && options.character_data != Some(true))
+                || (record.type_ == MutationRecordType::ChildList && !options.child_list);
+            if excluded {
+                continue;
+            }
+
+            // 1. Let mo be registered's observer.
+            // 2. If interestedObservers[mo] does not exist, then set interestedObservers[mo] to null.
+            let index = match interested_observers.iter().position(|(observer, _)| Rc::ptr_eq(observer, &registered.observer)) {
+                Some(index) => index,
+                None => {
+                    interested_observers.push((Rc::clone(&registered.observer), None));
+                    interested_observers.len() - 1
+                }
+            };
+
+            // 3. If either type is "attributes" and options["attributeOldValue"] is true, or type is "characterData" and options["characterDataOldValue"] is true, then set interestedObservers[mo] to oldValue.
+            if (record.type_ == MutationRecordType::Attributes && options.attribute_old_value == Some(true)) || (record.type_ == MutationRecordType::CharacterData && options.character_data_old_value == Some(true)) {
+                interested_observers[index].1 = record.old_value.clone();
+            }
+        }
+        node = current.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+    }
+
+    // 4. For each observer → mappedOldValue of interestedObservers:
+    //        1. Let record be a new MutationRecord object with its type set to type, target set to target, attributeName set to name, attributeNamespace set to namespace, oldValue set to mappedOldValue,
+    //           addedNodes set to addedNodes, removedNodes set to removedNodes, previousSibling set to previousSibling, and nextSibling set to nextSibling.
+    //        2. Enqueue record to observer's record queue.
+    if interested_observers.is_empty() {
+        return;
+    }
+    for (observer, mapped_old_value) in interested_observers {
+        observer.borrow_mut().record_queue.push(MutationRecord { old_value: mapped_old_value, ..record.clone() });
+    }
+
+    // 5. Queue a mutation observer microtask.
+    queue_a_mutation_observer_microtask();
+}
+
+// https://dom.spec.whatwg.org/#queue-a-mutation-observer-compound-microtask
+fn queue_a_mutation_observer_microtask() {
+    AGENT.with(|agent| {
+        let mut agent = agent.borrow_mut();
+        // 1. If the surrounding agent's mutation observer microtask queued is true, then return.
+        if agent.mutation_observer_microtask_queued {
+            return;
+        }
+
+        // 2. Set the surrounding agent's mutation observer microtask queued to true.
+        // 3. Queue a microtask to notify mutation observers.
+        agent.mutation_observer_microtask_queued = true;
+        agent.microtask_waiting = true;
+    });
+}
+
+// The microtask to notify mutation observers, if one was queued since the last time this was called
+pub(crate) fn take_mutation_observer_microtask() -> Option<Task> {
+    let waiting = AGENT.with(|agent| std::mem::take(&mut agent.borrow_mut().microtask_waiting));
+    if !waiting {
+        return None;
+    }
+    return Some(Box::new(notify_mutation_observers));
+}
+
+// https://dom.spec.whatwg.org/#notify-mutation-observers
+fn notify_mutation_observers(interpreter: &mut Interpreter) {
+    // 1. Set the surrounding agent's mutation observer microtask queued to false.
+    // 2. Let notifySet be a clone of the surrounding agent's mutation observers.
+    let notify_set: Vec<Rc<RefCell<MutationObserver>>> = AGENT.with(|agent| {
+        let mut agent = agent.borrow_mut();
+        agent.mutation_observer_microtask_queued = false;
+        agent.mutation_observers.retain(|observer| observer.strong_count() > 0);
+        agent.mutation_observers.iter().filter_map(|observer| observer.upgrade()).collect()
+    });
+
+    // 5. For each mo of notifySet:
+    for observer in notify_set {
+        let callback = match observer.borrow().callback.clone() {
+            Some(callback) => callback,
+            None => continue
+        };
+
+        // 1. Let records be a clone of mo's record queue.
+        // 2. Empty mo's record queue.
+        let records = observer.borrow_mut().take_records();
+
+        // 4. If records is not empty, then invoke mo's callback with « records, mo » and "report", and with callback this value mo.
+        if records.is_empty() {
+            continue;
+        }
+        let records = records.to_js_value(interpreter);
+        let observer_value = Rc::clone(&observer).to_js_value(interpreter);
+        let callback = Rc::new(RefCell::new(JSValue::Object(callback)));
+        let completion_record = interpreter.call(callback, Rc::clone(&observer_value), vec![records, observer_value]);
+        if let (CompletionRecordType::Throw, ReferenceRecordOrJsValue::JSValue(exception)) = (&completion_record.type_, &*completion_record.value) {
+            interpreter.report_exception(exception);
+        }
+    }
+}
+
+pub(crate) fn install_mutation_observer(interpreter: &mut Interpreter) {
+    let mutation_observer_prototype = create_interface_prototype_object(interpreter.intrinsic("Object.prototype"));
+    define_operations(&mutation_observer_prototype, &[
+        ("observe", operation!("MutationObserver", "observe", mutation_observer_observe(observer: Rc<RefCell<MutationObserver>>, target: RefNode, options: MutationObserverInit))),
+        ("disconnect", operation!("MutationObserver", "disconnect", mutation_observer_disconnect(observer: Rc<RefCell<MutationObserver>>))),
+        ("takeRecords", operation!("MutationObserver", "takeRecords", mutation_observer_take_records(observer: Rc<RefCell<MutationObserver>>))),
+    ]);
+    interpreter.set_intrinsic("MutationObserver.prototype", mutation_observer_prototype);
+
+    let mutation_observer_interface_object = install_interface_object(interpreter, "MutationObserver");
+    mutation_observer_interface_object.borrow_mut().call = Some(mutation_observer_call);
+    mutation_observer_interface_object.borrow_mut().construct = Some(mutation_observer_construct);
+    Interpreter::define_property_or_throw(&mutation_observer_interface_object, "length", Rc::new(RefCell::new(JSValue::Numeric(1.0))), false, false, true);
+
+    let mutation_record_prototype = create_interface_prototype_object(interpreter.intrinsic("Object.prototype"));
+    define_attributes(&mutation_record_prototype, &[
+        ("type", attribute_getter!("MutationRecord", "type", mutation_record_type(record: Rc<MutationRecord>)), None),
+        ("target", attribute_getter!("MutationRecord", "target", mutation_record_target(record: Rc<MutationRecord>)), None),
+        ("addedNodes", attribute_getter!("MutationRecord", "addedNodes", mutation_record_added_nodes(record: Rc<MutationRecord>)), None),
+        ("removedNodes", attribute_getter!("MutationRecord", "removedNodes", mutation_record_removed_nodes(record: Rc<MutationRecord>)), None),
+        ("previousSibling", attribute_getter!("MutationRecord", "previousSibling", mutation_record_previous_sibling(record: Rc<MutationRecord>)), None),
+        ("nextSibling", attribute_getter!("MutationRecord", "nextSibling", mutation_record_next_sibling(record: Rc<MutationRecord>)), None),
+        ("attributeName", attribute_getter!("MutationRecord", "attributeName", mutation_record_attribute_name(record: Rc<MutationRecord>)), None),
+        ("attributeNamespace", attribute_getter!("MutationRecord", "attributeNamespace", mutation_record_attribute_namespace(record: Rc<MutationRecord>)), None),
+        ("oldValue", attribute_getter!("MutationRecord", "oldValue", mutation_record_old_value(record: Rc<MutationRecord>)), None),
+    ]);
+    interpreter.set_intrinsic("MutationRecord.prototype", mutation_record_prototype);
+    install_interface_object(interpreter, "MutationRecord");
+}
+
+impl FromJSValue for Rc<RefCell<MutationObserver>> {
+    const TYPE_NAME: &'static str = "MutationObserver";
+
+    fn from_js_value(_interpreter: &mut Interpreter, value: &Rc<RefCell<JSValue>>) -> Result<Option<Self>, CompletionRecord> {
+        if let JSValue::Object(object) = &*value.borrow() {
+            if let Some(PlatformObject::MutationObserver(observer)) = &object.borrow().platform_object {
+                return Ok(Some(Rc::clone(observer)));
+            }
+        }
+        return Ok(None);
+    }
+}
+
+// TODO: The same observer gets a new wrapper every time it is exposed, like nodes do
+impl ToJSValue for Rc<RefCell<MutationObserver>> {
+    fn to_js_value(self, interpreter: &mut Interpreter) -> Rc<RefCell<JSValue>> {
+        return create_platform_object(interpreter, "MutationObserver.prototype", PlatformObject::MutationObserver(self));
+    }
+}
+
+impl FromJSValue for Rc<MutationRecord> {
+    const TYPE_NAME: &'static str = "MutationRecord";
+
+    fn from_js_value(_interpreter: &mut Interpreter, value: &Rc<RefCell<JSValue>>) -> Result<Option<Self>, CompletionRecord> {
+        if let JSValue::Object(object) = &*value.borrow() {
+            if let Some(PlatformObject::MutationRecord(record)) = &object.borrow().platform_object {
+                return Ok(Some(Rc::clone(record)));
+            }
+        }
+        return Ok(None);
+    }
+}
+
+impl ToJSValue for MutationRecord {
+    fn to_js_value(self, interpreter: &mut Interpreter) -> Rc<RefCell<JSValue>> {
+        return create_platform_object(interpreter, "MutationRecord.prototype", PlatformObject::MutationRecord(Rc::new(self)));
+    }
+}
+
+// Constructing a MutationObserver without new throws like other constructors of platform objects
+fn mutation_observer_call(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return interpreter.throw_type_error(String::from("Failed to construct 'MutationObserver': Please use the 'new' operator, this DOM object constructor cannot be called as a function."));
+}
+
+// https://dom.spec.whatwg.org/#dom-mutationobserver-mutationobserver
+fn mutation_observer_construct(interpreter: &mut Interpreter, arguments: Vec<Rc<RefCell<JSValue>>>, new_target: Rc<RefCell<JSObject>>) -> CompletionRecord {
+    let callback = match arguments.first() {
+        Some(callback) if Interpreter::is_callable(&callback.borrow()) => match &*callback.borrow() {
+            JSValue::Object(callback) => Rc::clone(callback),
+            _ => unreachable!()
+        },
+        _ => return interpreter.throw_type_error(String::from("Failed to construct 'MutationObserver': parameter 1 is not of type 'MutationCallback'."))
+    };
+
+    let object = match completion_value(interpreter.ordinary_create_from_constructor(&new_target, "MutationObserver.prototype")) {
+        Ok(object) => object,
+        Err(completion_record) => return completion_record
+    };
+
+    // 1. Set this's callback to callback.
+    if let JSValue::Object(observer_object) = &*object.borrow() {
+        observer_object.borrow_mut().platform_object = Some(PlatformObject::MutationObserver(MutationObserver::with_callback(Some(callback))));
+    }
+    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(object)));
+}
+
+// https://dom.spec.whatwg.org/#dom-mutationobserver-observe
+fn mutation_observer_observe(observer: Rc<RefCell<MutationObserver>>, target: RefNode, options: MutationObserverInit) -> Result<(), Exception> {
+    return MutationObserver::observe(&observer, &target, options).map_err(Exception::TypeError);
+}
+
+// https://dom.spec.whatwg.org/#dictdef-mutationobserverinit
+// The dictionary argument is optional as none of its members are required
+impl FromJSValue for MutationObserverInit {
+    const TYPE_NAME: &'static str = "MutationObserverInit";
+    const OPTIONAL: bool = true;
+
+    fn from_js_value(interpreter: &mut Interpreter, value: &Rc<RefCell<JSValue>>) -> Result<Option<Self>, CompletionRecord> {
+        let dictionary = match &*value.borrow() {
+            JSValue::Undefined | JSValue::Null => None,
+            JSValue::Object(object) => Some(Rc::clone(object)),
+            _ => return Ok(None)
+        };
+        return Ok(Some(MutationObserverInit {
+            child_list: dictionary_boolean_member(interpreter, &dictionary, "childList")?,
+            attributes: dictionary_optional_boolean_member(interpreter, &dictionary, "attributes")?,
+            character_data: dictionary_optional_boolean_member(interpreter, &dictionary, "characterData")?,
+            subtree: dictionary_boolean_member(interpreter, &dictionary, "subtree")?,
+            attribute_old_value: dictionary_optional_boolean_member(interpreter, &dictionary, "attributeOldValue")?,
+            character_data_old_value: dictionary_optional_boolean_member(interpreter, &dictionary, "characterDataOldValue")?,
+            attribute_filter: dictionary_string_sequence_member(interpreter, &dictionary, "attributeFilter")?,
+        }));
+    }
+}
+
+// https://webidl.spec.whatwg.org/#es-dictionary
+// Members without a default value do not exist when they are undefined
+fn dictionary_member(interpreter: &mut Interpreter, dictionary: &Option<Rc<RefCell<JSObject>>>, key: &str) -> Result<Option<Rc<RefCell<JSValue>>>, CompletionRecord> {
+    let dictionary = match dictionary {
+        Some(dictionary) => dictionary,
+        None => return Ok(None)
+    };
+
+    // 1. Let jsMemberValue be ? Get(jsDict, key).
+    let receiver = Rc::new(RefCell::new(JSValue::Object(Rc::clone(dictionary))));
+    let value = completion_value(interpreter.get(dictionary, &PropertyKey::String(key.to_string()), receiver))?;
+    if matches!(*value.borrow(), JSValue::Undefined) {
+        return Ok(None);
+    }
+    return Ok(Some(value));
+}
+
+fn dictionary_optional_boolean_member(interpreter: &mut Interpreter, dictionary: &Option<Rc<RefCell<JSObject>>>, key: &str) -> Result<Option<bool>, CompletionRecord> {
+    let value = dictionary_member(interpreter, dictionary, key)?;
+    return Ok(value.map(|value| matches!(*Interpreter::to_boolean(value).borrow(), JSValue::Boolean(true))));
+}
+
+// https://webidl.spec.whatwg.org/#es-sequence
+// FIXME: The sequence is read as an array-like object rather than with its iterator
+fn dictionary_string_sequence_member(interpreter: &mut Interpreter, dictionary: &Option<Rc<RefCell<JSObject>>>, key: &str) -> Result<Option<Vec<DOMString>>, CompletionRecord> {
+    let value = match dictionary_member(interpreter, dictionary, key)? {
+        Some(value) => value,
+        None => return Ok(None)
+    };
+
+    let object = match &*value.borrow() {
+        JSValue::Object(object) => Rc::clone(object),
+        _ => return Err(interpreter.throw_type_error(format!("Failed to read the '{}' property from 'MutationObserverInit': The provided value cannot be converted to a sequence.", key)))
+    };
+
+    let length = completion_value(interpreter.get(&object, &PropertyKey::String(String::from("length")), Rc::clone(&value)))?;
+    let length = match &*completion_value(Interpreter::to_number(length))?.borrow() {
+        JSValue::Numeric(length) if length.is_finite() && *length > 0.0 => *length as usize,
+        _ => 0
+    };
+
+    let mut strings = Vec::new();
+    for index in 0..length {
+        let element = completion_value(interpreter.get(&object, &PropertyKey::String(index.to_string()), Rc::clone(&value)))?;
+        strings.push(argument_to_dom_string(interpreter, &[element], 0)?);
+    }
+    return Ok(Some(strings));
+}
+
+// https://dom.spec.whatwg.org/#dom-mutationobserver-disconnect
+fn mutation_observer_disconnect(observer: Rc<RefCell<MutationObserver>>) {
+    MutationObserver::disconnect(&observer);
+}
+
+// https://dom.spec.whatwg.org/#dom-mutationobserver-takerecords
+fn mutation_observer_take_records(observer: Rc<RefCell<MutationObserver>>) -> Vec<MutationRecord> {
+    return observer.borrow_mut().take_records();
+}
+
+// https://dom.spec.whatwg.org/#dom-mutationrecord-type
+fn mutation_record_type(record: Rc<MutationRecord>) -> DOMString {
+    return record.type_.as_str().to_string();
+}
+
+// https://dom.spec.whatwg.org/#dom-mutationrecord-target
+fn mutation_record_target(record: Rc<MutationRecord>) -> RefNode {
+    return Rc::clone(&record.target);
+}
+
+// https://dom.spec.whatwg.org/#dom-mutationrecord-addednodes
+// FIXME: This is an array of the nodes rather than a NodeList, as there is no NodeList interface yet
+fn mutation_record_added_nodes(record: Rc<MutationRecord>) -> Vec<RefNode> {
+    return record.added_nodes.clone();
+}
+
+// https://dom.spec.whatwg.org/#dom-mutationrecord-removednodes
+fn mutation_record_removed_nodes(record: Rc<MutationRecord>) -> Vec<RefNode> {
+    return record.removed_nodes.clone();
+}
+
+// https://dom.spec.whatwg.org/#dom-mutationrecord-previoussibling
+fn mutation_record_previous_sibling(record: Rc<MutationRecord>) -> Option<RefNode> {
+    return record.previous_sibling.clone();
+}
+
+// https://dom.spec.whatwg.org/#dom-mutationrecord-nextsibling
+fn mutation_record_next_sibling(record: Rc<MutationRecord>) -> Option<RefNode> {
+    return record.next_sibling.clone();
+}
+
+// https://dom.spec.whatwg.org/#dom-mutationrecord-attributename
+fn mutation_record_attribute_name(record: Rc<MutationRecord>) -> Option<DOMString> {
+    return record.attribute_name.clone();
+}
+
+// https://dom.spec.whatwg.org/#dom-mutationrecord-attributenamespace
+// Attributes are not namespaced yet, so this is always null
+fn mutation_record_attribute_namespace(_record: Rc<MutationRecord>) -> Option<DOMString> {
+    return None;
+}
+
+// https://dom.spec.whatwg.org/#dom-mutationrecord-oldvalue
+fn mutation_record_old_value(record: Rc<MutationRecord>) -> Option<DOMString> {
+    return record.old_value.clone();
+}
@@ -0,0 +1,64 @@
+// https://html.spec.whatwg.org/#named-character-references
+// The table is generated by build.rs from src/entities.json and is sorted by name (without the leading '&').
+include!(concat!(env!("OUT_DIR"), "/named_character_references.rs"));
+
+// Returns the characters for a named character reference, e.g. "amp;" -> "&"
+pub fn lookup(name: &str) -> Option<&'static str> {
+    match NAMED_CHARACTER_REFERENCES.binary_search_by(|(reference_name, _)| (*reference_name).cmp(name)) {
+        Ok(index) => Some(NAMED_CHARACTER_REFERENCES[index].1),
+        Err(_) => None
+    }
+}
+
+// Returns true if any named character reference starts with the given characters
+pub fn has_prefix(prefix: &str) -> bool {
+    let index = NAMED_CHARACTER_REFERENCES.partition_point(|(reference_name, _)| *reference_name < prefix);
+
+    match NAMED_CHARACTER_REFERENCES.get(index) {
+        Some((reference_name, _)) => reference_name.starts_with(prefix),
+        None => false
+    }
+}
+
+// The names of every named character reference in order, with and without the ; for the ones that can be written without it
+pub fn names() -> impl Iterator<Item = &'static str> {
+    return NAMED_CHARACTER_REFERENCES.iter().map(|(reference_name, _)| *reference_name);
+}
+
+// The known references closest to an unknown name, at most count of them, for the parse error of e.g. "nbps;" to suggest "nbsp;"
+// Closeness is the number of characters inserted, deleted, replaced or swapped with their neighbour to get from one name to the other,
+// a name more than a third of its length away from any reference (and at least one) has no suggestions
+// Only references ending in ; are suggested, as the ones without it are kept for old documents
+pub fn nearest(name: &str, count: usize) -> Vec<&'static str> {
+    let name: Vec<char> = name.chars().collect();
+    let max_distance = (name.len() / 3).max(1);
+    let mut candidates: Vec<(usize, &'static str)> = names()
+        .filter(|reference_name| reference_name.ends_with(';') && reference_name.len().abs_diff(name.len()) <= max_distance)
+        .filter_map(|reference_name| {
+            let distance = edit_distance(&name, &reference_name.chars().collect::<Vec<char>>());
+            return if distance <= max_distance { Some((distance, reference_name)) } else { None };
+        })
+        .collect();
+    candidates.sort();
+    return candidates.into_iter().take(count).map(|(_, reference_name)| reference_name).collect();
+}
+
+// https://en.wikipedia.org/wiki/Damerau%E2%80%93Levenshtein_distance#Optimal_string_alignment_distance
+// Insertions, deletions, substitutions and transpositions of adjacent characters each count one
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    // The rows of the distances between the prefixes of a and b, two rows back for transpositions
+    let mut before_previous: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut current = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            current[j] = (previous[j] + 1).min(current[j - 1] + 1).min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before_previous[j - 2] + 1);
+            }
+        }
+        before_previous = std::mem::replace(&mut previous, current);
+    }
+    return previous[b.len()];
+}
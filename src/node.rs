@@ -1,7 +1,21 @@
 use std::cell::RefCell;
 use std::rc::{Rc, Weak};
+use crate::atom::Atom;
 use crate::character_data::CharacterData;
 use crate::comment::Comment;
+use crate::cssom::{CSSStyleDeclaration, CSSStyleSheet};
+use crate::events::EventListener;
+use crate::forms::{FormControlState, FormSubmission};
+use crate::gc::{Trace, Tracer};
+use crate::layout::{BoxTreeCache, Display, WhiteSpace};
+use crate::metadata::Refresh;
+use crate::microsyntaxes;
+use crate::mutation_observer::{queue_a_mutation_record, MutationRecord, RegisteredObserver};
+use crate::scrolling::ScrollPosition;
+use crate::source_location::SourceLocation;
+use crate::style::{invalidate_children, invalidate_style};
+use crate::url::{Url, UrlParseError};
+use crate::transitions::{DocumentTimeline, ElementTransitions};
 
 #[derive(Debug)]
 pub enum NodeType {
@@ -35,18 +49,58 @@ pub struct Node {
     nextSibling: Weak<Option<Child>>,
     nodeValue: Option<DOMString>,
     textContent: Option<DOMString>,
+    // https://dom.spec.whatwg.org/#eventtarget-event-listener-list
+    pub(crate) event_listener_list: Vec<Rc<EventListener>>,
+    // https://dom.spec.whatwg.org/#registered-observer-list
+    pub(crate) registered_observer_list: Vec<RegisteredObserver>,
+    // Where the parser found the node in its input, None for a node that is not in the input (e.g. one a script created or the parser implied)
+    pub(crate) source_location: Option<SourceLocation>,
+    // Set when the style of the node, and so of its descendants, has to be computed again, see style::invalidate_style
+    pub(crate) needs_style: bool,
+    // Set when a descendant has to be styled again or the children changed, so only the paths down to the nodes that changed are walked
+    pub(crate) child_needs_style: bool,
+    // The display and white-space of an element as of the last time it was styled
+    pub(crate) computed_display_and_white_space: Option<(Display, WhiteSpace)>,
+    // The before-change style and running transitions of an element, see transitions.rs
+    pub(crate) transitions: Option<ElementTransitions>,
 }
 
 // https://dom.spec.whatwg.org/#interface-document
-pub struct Document {}
+pub struct Document {
+    // https://dom.spec.whatwg.org/#concept-document-url
+    pub url: Url,
+    // https://dom.spec.whatwg.org/#concept-document-mode
+    pub mode: DocumentMode,
+    // The box tree of the last layout, the boxes of the elements that did not change since are used again
+    pub(crate) box_tree_cache: Option<BoxTreeCache>,
+    // The forms that were submitted, in the order they were submitted, as there is no navigation to act on them
+    pub form_submissions: Vec<FormSubmission>,
+    // https://html.spec.whatwg.org/multipage/browsing-the-web.html#will-declaratively-refresh
+    // The refresh of the first meta element that declared one, see metadata::refresh
+    pub refresh: Option<Refresh>,
+    // The clock the document's transitions run on
+    pub(crate) timeline: DocumentTimeline,
+    // https://drafts.csswg.org/cssom-view/#scroll-position
+    // Where the viewport the document is shown in is scrolled to
+    pub(crate) scroll_position: ScrollPosition,
+}
 
 impl Document {
     pub fn new() -> Self {
-        Self {}
+        Self { url: Url::about_blank(), mode: DocumentMode::NoQuirks, box_tree_cache: None, form_submissions: Vec::new(), refresh: None, timeline: DocumentTimeline::new(), scroll_position: ScrollPosition::default() }
     }
 
 }
 
+// https://dom.spec.whatwg.org/#concept-document-mode
+// Set by the parser from the DOCTYPE, a document in quirks mode is laid out the way browsers did before the standards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentMode {
+    NoQuirks,
+    Quirks,
+    LimitedQuirks,
+}
+
 // https://dom.spec.whatwg.org/#interface-document-type
 pub struct DocumentType {
     pub name: DOMString,
@@ -66,27 +120,58 @@ pub struct DOMTokenList {
 
 // https://dom.spec.whatwg.org/#namednodemap
 pub struct NamedNodeMap {
+    // https://dom.spec.whatwg.org/#concept-element-attribute
+    attribute_list: Vec<Attr>,
+}
 
+// https://dom.spec.whatwg.org/#interface-attr
+// TODO: Attr should be a Node and support namespaces
+pub struct Attr {
+    pub local_name: Atom,
+    pub value: DOMString,
+    source_location: Option<SourceLocation>,
 }
+
+impl Attr {
+    // Where the parser found the attribute in its input, from the start of its name to the end of its value
+    pub fn source_location(&self) -> Option<SourceLocation> {
+        return self.source_location;
+    }
+}
+
 // https://dom.spec.whatwg.org/#interface-element
 pub struct Element {
     namespace_URI: Option<DOMString>,
     prefix: Option<DOMString>,
-    local_name: DOMString,
+    local_name: Atom,
     tag_name: DOMString,
     id: DOMString,
     class_list: DOMString,
     slot: DOMString,
     classList: DOMTokenList,
     attributes: NamedNodeMap,
+    // https://drafts.csswg.org/cssom/#associated-css-style-sheet
+    pub(crate) css_style_sheet: Option<Rc<RefCell<CSSStyleSheet>>>,
+    // https://drafts.csswg.org/cssom/#dom-elementcssinlinestyle-style
+    pub(crate) style_declaration_block: Option<Rc<RefCell<CSSStyleDeclaration>>>,
+    // The value, checkedness and selectedness of a form control once they no longer follow its content attributes
+    pub(crate) form_control_state: FormControlState,
+    // https://drafts.csswg.org/cssom-view/#scroll-position
+    // Where the content of the element is scrolled to when it is a scroll container
+    pub(crate) scroll_position: ScrollPosition,
 }
 
 
 
 impl Element {
-    pub fn new(local_name: DOMString) -> Self {
+    pub fn new(local_name: Atom) -> Self {
+        return Self::new_in_namespace(local_name, None);
+    }
+
+    // The parser creates HTML elements with no namespace, and SVG and MathML elements in theirs
+    pub fn new_in_namespace(local_name: Atom, namespace: Option<DOMString>) -> Self {
         Self {
-            namespace_URI: None,
+            namespace_URI: namespace,
             prefix: None,
             local_name,
             tag_name: "".to_string(),
@@ -94,8 +179,128 @@ impl Element {
             class_list: "".to_string(),
             slot: "".to_string(),
             classList: DOMTokenList {},
-            attributes: NamedNodeMap {},
+            attributes: NamedNodeMap { attribute_list: Vec::new() },
+            css_style_sheet: None,
+            style_declaration_block: None,
+            form_control_state: FormControlState::default(),
+            scroll_position: ScrollPosition::default(),
+        }
+    }
+
+    // https://dom.spec.whatwg.org/#dom-element-getattribute
+    pub fn get_attribute(&self, qualified_name: &str) -> Option<&DOMString> {
+        // TODO: 1. If this is in the HTML namespace and its node document is an HTML document, then set qualifiedName to qualifiedName in ASCII lowercase.
+        return self.attributes.attribute_list.iter().find(|attr| attr.local_name == qualified_name).map(|attr| &attr.value);
+    }
+
+    // https://dom.spec.whatwg.org/#dom-element-setattribute
+    pub fn set_attribute(&mut self, qualified_name: &str, value: DOMString) {
+        if qualified_name == "style" {
+            self.style_attribute_changed(&value);
         }
+
+        // 3. Let attribute be the first attribute in this's attribute list whose qualified name is qualifiedName, and null otherwise.
+        match self.attributes.attribute_list.iter_mut().find(|attr| attr.local_name == qualified_name) {
+            // 5. Change attribute to value.
+            Some(attribute) => {
+                attribute.value = value;
+            },
+            // 4. If attribute is null, create an attribute whose local name is qualifiedName, value is value, and node document is this's node document, then append this attribute to this, and then return.
+            None => {
+                self.attributes.attribute_list.push(Attr { local_name: Atom::new(qualified_name), value, source_location: None });
+            }
+        }
+    }
+
+    // Makes room for the attributes of a token before they are appended one by one
+    pub(crate) fn reserve_attributes(&mut self, additional: usize) {
+        self.attributes.attribute_list.reserve_exact(additional);
+    }
+
+    // https://dom.spec.whatwg.org/#concept-element-attributes-append
+    // For the attributes of a token, whose names are interned already and are not on the element yet
+    pub(crate) fn append_attribute(&mut self, local_name: Atom, value: DOMString) {
+        self.attributes.attribute_list.push(Attr { local_name, value, source_location: None });
+    }
+
+    // https://dom.spec.whatwg.org/#dom-element-removeattribute
+    pub fn remove_attribute(&mut self, qualified_name: &str) {
+        if qualified_name == "style" {
+            self.style_attribute_changed("");
+        }
+        self.attributes.attribute_list.retain(|attr| attr.local_name != qualified_name);
+    }
+
+    // https://dom.spec.whatwg.org/#dom-element-hasattribute
+    pub fn has_attribute(&self, qualified_name: &str) -> bool {
+        return self.get_attribute(qualified_name).is_some();
+    }
+
+    // https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#boolean-attributes
+    // The presence of a boolean attribute is its value, e.g. disabled="false" is still disabled
+    pub fn boolean_attribute(&self, qualified_name: &str) -> bool {
+        return self.has_attribute(qualified_name);
+    }
+
+    // https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#signed-integers
+    // e.g. tabindex, None when the attribute is missing or is not an integer
+    pub fn integer_attribute(&self, qualified_name: &str) -> Option<i64> {
+        return self.get_attribute(qualified_name).and_then(|value| microsyntaxes::parse_integer(value));
+    }
+
+    // https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#non-negative-integers
+    // e.g. colspan, maxlength and size
+    pub fn non_negative_integer_attribute(&self, qualified_name: &str) -> Option<u64> {
+        return self.get_attribute(qualified_name).and_then(|value| microsyntaxes::parse_non_negative_integer(value));
+    }
+
+    // https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#floating-point-numbers
+    // e.g. the min, max and value of a meter or progress element
+    pub fn float_attribute(&self, qualified_name: &str) -> Option<f64> {
+        return self.get_attribute(qualified_name).and_then(|value| microsyntaxes::parse_floating_point_number(value));
+    }
+
+    // https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#set-of-space-separated-tokens
+    // e.g. class and rel, empty when the attribute is missing
+    pub fn space_separated_attribute(&self, qualified_name: &str) -> Vec<&str> {
+        return self.get_attribute(qualified_name).map(|value| microsyntaxes::split_on_ascii_whitespace(value)).unwrap_or_default();
+    }
+
+    // https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#comma-separated-tokens
+    // e.g. accept, empty when the attribute is missing
+    pub fn comma_separated_attribute(&self, qualified_name: &str) -> Vec<&str> {
+        return self.get_attribute(qualified_name).map(|value| microsyntaxes::split_on_commas(value)).unwrap_or_default();
+    }
+
+    pub fn attribute_list(&self) -> &Vec<Attr> {
+        return &self.attributes.attribute_list;
+    }
+
+    pub(crate) fn set_attribute_source_location(&mut self, qualified_name: &str, source_location: SourceLocation) {
+        if let Some(attribute) = self.attributes.attribute_list.iter_mut().find(|attr| attr.local_name == qualified_name) {
+            attribute.source_location = Some(source_location);
+        }
+    }
+
+    // https://drafts.csswg.org/cssom/#the-elementcssinlinestyle-mixin
+    // When the style attribute is set or removed, the declarations are parsed from its new value, unless the declaration block is the one setting it
+    fn style_attribute_changed(&self, value: &str) {
+        if let Some(declaration_block) = &self.style_declaration_block {
+            let mut declaration_block = declaration_block.borrow_mut();
+            if !declaration_block.updating {
+                declaration_block.set_css_text(value);
+            }
+        }
+    }
+
+    // https://dom.spec.whatwg.org/#dom-element-namespaceuri
+    pub fn namespace_uri(&self) -> Option<&DOMString> {
+        return self.namespace_URI.as_ref();
+    }
+
+    // https://dom.spec.whatwg.org/#dom-element-localname
+    pub fn local_name(&self) -> &Atom {
+        return &self.local_name;
     }
 }
 
@@ -124,16 +329,303 @@ pub type WeakNode = Weak<RefCell<Node>>;
 pub type Children = Vec<Child>;
 pub type Child = RefNode;
 
+// The parent and node document are weak references, so only the edges down the tree and to script are followed
+impl Trace for Node {
+    fn trace(&self, tracer: &mut Tracer) {
+        for child in &self.childNodes {
+            tracer.visit(child);
+        }
+        for event_listener in &self.event_listener_list {
+            tracer.visit(event_listener);
+        }
+        for registered_observer in &self.registered_observer_list {
+            registered_observer.trace(tracer);
+        }
+    }
+}
+
 impl Node { 
     pub fn new(data: NodeData, node_type: NodeType) -> Self {
-        Self { nodeType: node_type, nodeName: "".to_string(), baseURI: "".to_string(), isConnected: false, ownerDocument: None, parentNode: None, childNodes: Vec::new(), firstChild: Default::default(), lastChild: Default::default(), previousSibling: Default::default(), nextSibling: Default::default(), nodeValue: Option::from("".to_string()), textContent: Option::from("".to_string()), data }
+        Self { nodeType: node_type, nodeName: "".to_string(), baseURI: "".to_string(), isConnected: false, ownerDocument: None, parentNode: None, childNodes: Vec::new(), firstChild: Default::default(), lastChild: Default::default(), previousSibling: Default::default(), nextSibling: Default::default(), nodeValue: Option::from("".to_string()), textContent: Option::from("".to_string()), event_listener_list: Vec::new(), registered_observer_list: Vec::new(), source_location: None, needs_style: true, child_needs_style: false, computed_display_and_white_space: None, transitions: None, data }
+    }
+
+    // Where the parser found the node in its input
+    // For an element it is its start tag, for a text node it is from the first to the last of the characters it was made from, and for a comment or doctype it is all of its markup
+    pub fn source_location(&self) -> Option<SourceLocation> {
+        return self.source_location;
     }
 
     // https://dom.spec.whatwg.org/#concept-node-append
     // TODO: Not to spec
     pub fn append_child(&mut self, child_node: RefNode) {
+        if self.childNodes.capacity() == 0 {
+            self.childNodes.reserve_exact(self.expected_child_count());
+        }
         self.childNodes.push(child_node);
     }
+
+    // The bytes the node has allocated on the heap besides its own allocation, its strings, its child list and its attribute list
+    // Atoms are shared by every node with the same name so they are left out, as are children, style sheets and listeners, which are allocations of their own
+    pub(crate) fn heap_size(&self) -> usize {
+        let string_size = |string: &DOMString| string.capacity();
+        let mut size = string_size(&self.nodeName) + string_size(&self.baseURI) + self.nodeValue.as_ref().map_or(0, string_size) + self.textContent.as_ref().map_or(0, string_size);
+        size += self.childNodes.capacity() * std::mem::size_of::<Child>();
+        size += self.event_listener_list.capacity() * std::mem::size_of::<Rc<EventListener>>();
+        size += match &self.data {
+            NodeData::Element(element) => {
+                let strings = [&element.tag_name, &element.id, &element.class_list, &element.slot].into_iter().map(string_size).sum::<usize>()
+                    + element.namespace_URI.as_ref().map_or(0, string_size) + element.prefix.as_ref().map_or(0, string_size);
+                let attributes = element.attributes.attribute_list.capacity() * std::mem::size_of::<Attr>() + element.attributes.attribute_list.iter().map(|attribute| string_size(&attribute.value)).sum::<usize>();
+                strings + attributes
+            },
+            NodeData::Text(text) => string_size(&text.character_data.data),
+            NodeData::CharacterData(character_data) => string_size(&character_data.data),
+            NodeData::Comment(comment) => string_size(&comment.character_data.data),
+            NodeData::DocumentType(doctype) => string_size(&doctype.name) + string_size(&doctype.public_id) + string_size(&doctype.system_id),
+            NodeData::Document(_) => 0
+        };
+        return size;
+    }
+
+    // How many children the child list is allocated for when the first one is appended, most nodes do not have any and are never allocated one
+    // Elements that usually only hold a run of text get a list for exactly one child, the rest get the list a Vec grows to by itself
+    fn expected_child_count(&self) -> usize {
+        return match &self.data {
+            NodeData::Document(_) => 2,
+            NodeData::Element(element) => match element.local_name().as_str() {
+                "title" | "style" | "script" | "textarea" | "option" | "a" | "b" | "i" | "em" | "strong" | "span" | "code" | "label" | "button"
+                    | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "td" | "th" | "dt" | "small" | "sub" | "sup" => 1,
+                _ => 4
+            },
+            _ => 4
+        };
+    }
+}
+
+// https://dom.spec.whatwg.org/#concept-node-append
+// TODO: Pre-insertion validity checks are not done
+pub fn append(parent: &RefNode, node: &RefNode) {
+    // https://dom.spec.whatwg.org/#concept-node-remove
+    // A node can only have one parent, so remove it from its old parent first
+    let old_parent = node.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+    if let Some(old_parent) = old_parent {
+        let (old_previous_sibling, old_next_sibling) = siblings(&old_parent, node);
+        old_parent.borrow_mut().childNodes.retain(|child| !Rc::ptr_eq(child, node));
+        invalidate_children(&old_parent);
+        // Sibling combinators may match the siblings that were after the node differently now
+        if let Some(old_next_sibling) = &old_next_sibling {
+            invalidate_style(old_next_sibling);
+        }
+
+        // 13. Queue a tree mutation record for parent with « », « node », oldPreviousSibling, and oldNextSibling.
+        queue_a_mutation_record(MutationRecord::child_list(&old_parent, Vec::new(), vec![Rc::clone(node)], old_previous_sibling, old_next_sibling));
+    }
+
+    // https://dom.spec.whatwg.org/#concept-node-insert
+    // 6. Let previousSibling be child's previous sibling or parent's last child if child is null.
+    let previous_sibling = parent.borrow().childNodes.last().cloned();
+    node.borrow_mut().parentNode = Some(Rc::downgrade(parent));
+    parent.borrow_mut().append_child(Rc::clone(node));
+    // The node inherits from its new parent
+    invalidate_style(node);
+
+    // 10. If suppress observers flag is unset, then queue a tree mutation record for parent with nodes, « », previousSibling, and child.
+    queue_a_mutation_record(MutationRecord::child_list(parent, vec![Rc::clone(node)], Vec::new(), previous_sibling, None));
+}
+
+// The previous and next sibling of child within parent
+fn siblings(parent: &RefNode, child: &RefNode) -> (Option<RefNode>, Option<RefNode>) {
+    let parent = parent.borrow();
+    match parent.childNodes.iter().position(|other| Rc::ptr_eq(other, child)) {
+        Some(index) => (index.checked_sub(1).map(|index| Rc::clone(&parent.childNodes[index])), parent.childNodes.get(index + 1).cloned()),
+        None => (None, None)
+    }
+}
+
+// https://dom.spec.whatwg.org/#concept-element-attributes-set-value
+// Sets the attribute of the element node, or adds it when there is none, and restyles the element
+pub fn set_attribute_value(node: &RefNode, qualified_name: &str, value: DOMString) {
+    let old_value = match &node.borrow().data {
+        NodeData::Element(element) => element.get_attribute(qualified_name).cloned(),
+        _ => return
+    };
+
+    // https://dom.spec.whatwg.org/#concept-element-attributes-change
+    // 1. Queue a mutation record of "attributes" for element with attribute's local name, attribute's namespace, and attribute's value.
+    queue_a_mutation_record(MutationRecord::attributes(node, qualified_name, old_value));
+
+    if let NodeData::Element(element) = &mut node.borrow_mut().data {
+        element.set_attribute(qualified_name, value);
+    }
+    invalidate_style(node);
+}
+
+// https://dom.spec.whatwg.org/#concept-element-attributes-remove-by-name
+pub fn remove_attribute_by_name(node: &RefNode, qualified_name: &str) {
+    // 2. If attr is null, then return null.
+    let old_value = match &node.borrow().data {
+        NodeData::Element(element) => element.get_attribute(qualified_name).cloned(),
+        _ => None
+    };
+    let old_value = match old_value {
+        Some(old_value) => old_value,
+        None => return
+    };
+
+    // https://dom.spec.whatwg.org/#concept-element-attributes-remove
+    // 1. Queue a mutation record of "attributes" for element with attribute's local name, attribute's namespace, and attribute's value.
+    queue_a_mutation_record(MutationRecord::attributes(node, qualified_name, Some(old_value)));
+
+    if let NodeData::Element(element) = &mut node.borrow_mut().data {
+        element.remove_attribute(qualified_name);
+    }
+    invalidate_style(node);
+}
+
+// https://dom.spec.whatwg.org/#dom-node-textcontent
+pub fn text_content(node: &RefNode) -> Option<DOMString> {
+    match &node.borrow().data {
+        // The descendant text content of this.
+        NodeData::Element(_) => {
+            let mut text_content = String::from("");
+            for descendant in descendants(node) {
+                if let NodeData::Text(ref text) = descendant.borrow().data {
+                    text_content.push_str(&text.character_data.data);
+                }
+            }
+            return Some(text_content);
+        },
+        // This's data.
+        NodeData::Text(text) => Some(text.character_data.data.clone()),
+        NodeData::Comment(comment) => Some(comment.character_data.data.clone()),
+        NodeData::CharacterData(character_data) => Some(character_data.data.clone()),
+        // Null.
+        _ => None
+    }
+}
+
+// https://dom.spec.whatwg.org/#string-replace-all
+pub fn set_text_content(node: &RefNode, value: DOMString) {
+    let is_element = matches!(node.borrow().data, NodeData::Element(_));
+    if !is_element {
+        // https://dom.spec.whatwg.org/#concept-cd-replace
+        // 4. Queue a mutation record of "characterData" for node with null, null, node's data, « », « », null, and null.
+        let old_value = text_content(node);
+        if let Some(old_value) = old_value {
+            queue_a_mutation_record(MutationRecord::character_data(node, old_value));
+        }
+
+        match &mut node.borrow_mut().data {
+            NodeData::Text(text) => text.character_data.data = value,
+            NodeData::Comment(comment) => comment.character_data.data = value,
+            NodeData::CharacterData(character_data) => character_data.data = value,
+            _ => {}
+        }
+        invalidate_style(node);
+        return;
+    }
+
+    // 1. Let node be null.
+    // 2. If string is not the empty string, then set node to a new Text node whose data is string and node document is parent's node document.
+    // 3. Replace all with node within parent.
+    // https://dom.spec.whatwg.org/#concept-node-replace-all
+    // 2. Let removedNodes be parent's children.
+    let removed_nodes = std::mem::take(&mut node.borrow_mut().childNodes);
+    for removed_node in &removed_nodes {
+        removed_node.borrow_mut().parentNode = None;
+    }
+    invalidate_children(node);
+
+    // 3. Let addedNodes be the empty set.
+    // 4. If node is a DocumentFragment node, then set addedNodes to node's children.
+    // 5. Otherwise, if node is non-null, set addedNodes to « node ».
+    let mut added_nodes = Vec::new();
+    if !value.is_empty() {
+        let text_node = create_ref_node(NodeData::Text(Text::new(Some(value))), NodeType::TEXT_NODE);
+        text_node.borrow_mut().ownerDocument = node.borrow().ownerDocument.clone();
+        text_node.borrow_mut().parentNode = Some(Rc::downgrade(node));
+        node.borrow_mut().append_child(Rc::clone(&text_node));
+        invalidate_style(&text_node);
+        added_nodes.push(text_node);
+    }
+
+    // 8. If either addedNodes or removedNodes is not empty, then queue a tree mutation record for parent with addedNodes, removedNodes, null, and null.
+    if !added_nodes.is_empty() || !removed_nodes.is_empty() {
+        queue_a_mutation_record(MutationRecord::child_list(node, added_nodes, removed_nodes, None, None));
+    }
+}
+
+// https://dom.spec.whatwg.org/#concept-tree-descendant
+// Returns the descendants of node in tree order
+pub fn descendants(node: &RefNode) -> Vec<RefNode> {
+    let mut descendants = Vec::new();
+    for child in &node.borrow().childNodes {
+        descendants.push(Rc::clone(child));
+        descendants.append(&mut self::descendants(child));
+    }
+    return descendants;
+}
+
+// https://dom.spec.whatwg.org/#concept-node-document
+// A document is its own node document
+pub fn node_document(node: &RefNode) -> Option<RefNode> {
+    if let NodeData::Document(_) = node.borrow().data {
+        return Some(Rc::clone(node));
+    }
+    return node.borrow().ownerDocument.as_ref().and_then(|document| document.upgrade());
+}
+
+// https://html.spec.whatwg.org/multipage/urls-and-fetching.html#document-base-url
+// TODO: The base URL is found again each time a URL is resolved rather than frozen when the base element is inserted, which is the same unless a script changes it
+pub fn document_base_url(document: &RefNode) -> Url {
+    // https://html.spec.whatwg.org/multipage/urls-and-fetching.html#fallback-base-url
+    // 3. Return document's URL.
+    let fallback_base_url = match &document.borrow().data {
+        NodeData::Document(document) => document.url.clone(),
+        _ => return Url::about_blank()
+    };
+
+    // 1. If there is no base element that has an href attribute in the Document, then return the Document's fallback base URL.
+    // 2. Otherwise, return the frozen base URL of the first base element in the Document that has an href attribute, in tree order.
+    let href = match first_base_element_href(document) {
+        Some(href) => href,
+        None => return fallback_base_url
+    };
+    // https://html.spec.whatwg.org/multipage/semantics.html#set-the-frozen-base-url
+    // 3. Let urlRecord be the result of parsing the value of element's href content attribute with document's fallback base URL, and document's character encoding.
+    // 4. If urlRecord is failure or running Is base allowed for Document? on the resulting URL record and document returns "Blocked", then set element's frozen base URL to document's fallback base URL and return.
+    // TODO: Content Security Policy is not supported, so no base URL is blocked
+    return fallback_base_url.join(&href).unwrap_or(fallback_base_url);
+}
+
+// The href of the first base element in tree order that has one, which is usually one of the first elements of the document so the walk stops early
+fn first_base_element_href(document: &RefNode) -> Option<DOMString> {
+    let mut stack: Vec<RefNode> = document.borrow().childNodes.iter().rev().cloned().collect();
+    while let Some(node) = stack.pop() {
+        let node = node.borrow();
+        if let NodeData::Element(element) = &node.data {
+            if element.local_name() == "base" {
+                if let Some(href) = element.get_attribute("href") {
+                    return Some(href.clone());
+                }
+            }
+        }
+        stack.extend(node.childNodes.iter().rev().cloned());
+    }
+    return None;
+}
+
+// https://html.spec.whatwg.org/multipage/urls-and-fetching.html#encoding-parsing-a-url
+// Resolves a URL written in the document of node, such as the value of an href or src attribute
+pub fn encoding_parse_url(node: &RefNode, url: &str) -> Result<Url, UrlParseError> {
+    // 2. Let baseURL be environment's base URL, if environment is a Document object; otherwise the document base URL of environment.
+    let base_url = match node_document(node) {
+        Some(document) => document_base_url(&document),
+        None => Url::about_blank()
+    };
+
+    // 4. Return the result of applying the URL parser to url, with baseURL and encoding.
+    return base_url.join(url);
 }
 
 pub fn create_ref_node(data: NodeData, node_type: NodeType) -> RefNode {
@@ -0,0 +1,222 @@
+// This file contains the optimization pass that runs over the AST between the Parser and Interpreter.
+
+use std::rc::Rc;
+use crate::ast::{ExpressionStatement, FunctionBody, LiteralExpression, MemberProperty, PropertyName, Statement};
+use crate::interpreter::Interpreter;
+use crate::token::{Literal, Token, TokenType};
+
+// Rewrites the parts of a script that do not depend on anything at run time, so they are not evaluated each time the script runs them
+// - Arithmetic on number literals and concatenation with string literals are folded, e.g. 60 * 60 * 1000 or "width: " + 100 + "px"
+// - A member expression with a literal key, e.g. object["name"] or array[0], becomes object.name, so its property key is computed once
+// - Statements after a return or throw are removed, except for declarations, as var and function declarations are hoisted
+// Only rewrites that can not change what the script does are made, e.g. x + 1 + 2 is left alone, as (x + 1) + 2 is not x + 3 when x is a string
+// TODO: Dead branches on literal conditions are not removed, as if statements and the conditional operator are not parsed yet
+pub fn optimize(statements: &mut Vec<Statement>) {
+    remove_unreachable_statements(statements);
+    for statement in statements.iter_mut() {
+        optimize_statement(statement);
+    }
+}
+
+fn optimize_statement(statement: &mut Statement) {
+    match statement {
+        Statement::VariableStatement(declaration) => {
+            if let Some(initializer) = &mut declaration.initializer {
+                optimize_shared_expression(&mut initializer.expression);
+            }
+        },
+        Statement::LexicalDeclaration(declaration) => {
+            if let Some(initializer) = &mut declaration.initializer {
+                optimize_expression(initializer);
+            }
+        },
+        Statement::ExpressionStatement(expression) => optimize_expression(expression),
+        Statement::BlockStatement(block) => optimize(&mut block.statements),
+        Statement::FunctionDeclaration(declaration) => optimize_function_body(&mut declaration.function_body),
+        Statement::ReturnStatement(return_statement) => {
+            if let Some(expression) = &mut return_statement.expression {
+                optimize_expression(expression);
+            }
+        },
+        Statement::ThrowStatement(throw_statement) => optimize_expression(&mut throw_statement.expression),
+        Statement::TryStatement(try_statement) => {
+            optimize(&mut try_statement.block.statements);
+            if let Some(catch) = &mut try_statement.catch {
+                optimize(&mut catch.block.statements);
+            }
+            if let Some(finally) = &mut try_statement.finally {
+                optimize(&mut finally.statements);
+            }
+        }
+    }
+}
+
+// Function bodies are shared with the function objects created from them, so one is only rewritten before it is shared
+fn optimize_function_body(function_body: &mut Rc<FunctionBody>) {
+    if let Some(function_body) = Rc::get_mut(function_body) {
+        optimize(&mut function_body.statements);
+    }
+}
+
+fn optimize_shared_expression(expression: &mut Rc<ExpressionStatement>) {
+    if let Some(expression) = Rc::get_mut(expression) {
+        optimize_expression(expression);
+    }
+}
+
+fn optimize_expression(expression: &mut ExpressionStatement) {
+    match expression {
+        ExpressionStatement::BinaryExpression(binary_expression) => {
+            optimize_expression(&mut binary_expression.left);
+            optimize_expression(&mut binary_expression.right);
+            if let (Some(left), Some(right)) = (literal_value(&binary_expression.left), literal_value(&binary_expression.right)) {
+                if let Some(value) = fold_binary_operator(left, right, &binary_expression.operator.token_type) {
+                    *expression = literal_expression(value);
+                }
+            }
+        },
+        ExpressionStatement::UnaryExpression(unary_expression) => {
+            optimize_expression(&mut unary_expression.right);
+            if let Some(Literal::Numeric(value)) = literal_value(&unary_expression.right) {
+                match unary_expression.operator.token_type {
+                    TokenType::MINUS => *expression = literal_expression(Literal::Numeric(-value)),
+                    TokenType::PLUS => *expression = literal_expression(Literal::Numeric(*value)),
+                    _ => {}
+                }
+            }
+        },
+        ExpressionStatement::ParenthesizedExpression(parenthesized_expression) => {
+            optimize_expression(&mut parenthesized_expression.expression);
+            if let Some(value) = literal_value(&parenthesized_expression.expression) {
+                *expression = literal_expression(value.clone());
+            }
+        },
+        ExpressionStatement::CallExpression(call_expression) => {
+            optimize_expression(&mut call_expression.callee);
+            call_expression.arguments.iter_mut().for_each(optimize_expression);
+        },
+        ExpressionStatement::NewExpression(new_expression) => {
+            optimize_expression(&mut new_expression.callee);
+            new_expression.arguments.iter_mut().for_each(optimize_expression);
+        },
+        ExpressionStatement::ObjectLiteralExpression(object_literal_expression) => {
+            for property_definition in &mut object_literal_expression.property_definitions {
+                if let PropertyName::ComputedPropertyName(property_name) = &mut property_definition.property_name {
+                    optimize_expression(property_name);
+                }
+                optimize_shared_expression(&mut property_definition.assignment_expression.expression);
+            }
+        },
+        ExpressionStatement::ArrayLiteralExpression(array_literal_expression) => {
+            array_literal_expression.element_list.iter_mut().flatten().for_each(optimize_expression);
+        },
+        ExpressionStatement::FunctionExpression(function_expression) => optimize_function_body(&mut function_expression.function_body),
+        ExpressionStatement::ArrowFunction(arrow_function) => optimize_function_body(&mut arrow_function.function_body),
+        ExpressionStatement::TemplateLiteral(template_literal) => {
+            template_literal.substitutions.iter_mut().for_each(optimize_expression);
+        },
+        ExpressionStatement::AssignmentExpression(assignment_expression) => {
+            optimize_shared_expression(&mut assignment_expression.left_hand_side_expression);
+            optimize_shared_expression(&mut assignment_expression.expression);
+        },
+        ExpressionStatement::MemberExpression(member_expression) => {
+            optimize_expression(&mut member_expression.object);
+            if let MemberProperty::Expression(property_expression) = &mut member_expression.property {
+                optimize_expression(property_expression);
+
+                // https://tc39.es/ecma262/#sec-topropertykey
+                // The key of a literal is the same every time, so it is accessed like an identifier name
+                if let Some(property_name) = literal_value(property_expression).map(literal_to_string) {
+                    member_expression.property = MemberProperty::IdentifierName(Token::new(TokenType::IDENTIFIER, property_name, None, 0, 0));
+                }
+            }
+        },
+        ExpressionStatement::LiteralExpression(_) | ExpressionStatement::IdentifierExpression(_) | ExpressionStatement::ThisExpression(_) => {}
+    }
+}
+
+fn literal_value(expression: &ExpressionStatement) -> Option<&Literal> {
+    match expression {
+        ExpressionStatement::LiteralExpression(literal_expression) => Some(&literal_expression.value),
+        _ => None
+    }
+}
+
+fn literal_expression(value: Literal) -> ExpressionStatement {
+    return ExpressionStatement::LiteralExpression(Box::new(LiteralExpression { value }));
+}
+
+// https://tc39.es/ecma262/#sec-applystringornumericbinaryoperator
+// The operands are primitives, so ToPrimitive returns them as they are and nothing can throw
+// TODO: Strings are not converted to numbers for -, * and /, and comparisons are left to the interpreter
+fn fold_binary_operator(left: &Literal, right: &Literal, operator: &TokenType) -> Option<Literal> {
+    // A regular expression literal evaluates to an object, whose conversion to a primitive can be changed by the script
+    if matches!(left, Literal::RegExp { .. }) || matches!(right, Literal::RegExp { .. }) {
+        return None;
+    }
+
+    // 1. If opText is +, then
+    //        c. If lPrim is a String or rPrim is a String, then
+    //               iii. Return the string-concatenation of lStr and rStr.
+    if *operator == TokenType::PLUS && (matches!(left, Literal::String(_)) || matches!(right, Literal::String(_))) {
+        return Some(Literal::String(format!("{}{}", literal_to_string(left), literal_to_string(right))));
+    }
+
+    // 3. Let lNum be ? ToNumeric(lVal).
+    // 4. Let rNum be ? ToNumeric(rVal).
+    let left = literal_to_number(left)?;
+    let right = literal_to_number(right)?;
+
+    // 8. Return operation(lNum, rNum).
+    return match operator {
+        TokenType::PLUS => Some(Literal::Numeric(left + right)),
+        TokenType::MINUS => Some(Literal::Numeric(left - right)),
+        TokenType::STAR => Some(Literal::Numeric(left * right)),
+        TokenType::SLASH => Some(Literal::Numeric(left / right)),
+        _ => None
+    };
+}
+
+// https://tc39.es/ecma262/#sec-tostring
+fn literal_to_string(value: &Literal) -> String {
+    return match value {
+        Literal::String(value) => value.clone(),
+        Literal::Numeric(value) => Interpreter::number_to_string(*value),
+        Literal::Boolean(value) => value.to_string(),
+        Literal::Null() => String::from("null"),
+        Literal::RegExp { pattern, flags } => format!("/{}/{}", pattern, flags),
+    };
+}
+
+// https://tc39.es/ecma262/#sec-tonumber
+fn literal_to_number(value: &Literal) -> Option<f64> {
+    return match value {
+        Literal::Numeric(value) => Some(*value),
+        Literal::Boolean(value) => Some(if *value { 1.0 } else { 0.0 }),
+        Literal::Null() => Some(0.0),
+        Literal::String(_) | Literal::RegExp { .. } => None,
+    };
+}
+
+// Nothing after a return or throw in a statement list runs, but the names it declares are still bound
+fn remove_unreachable_statements(statements: &mut Vec<Statement>) {
+    let end = match statements.iter().position(|statement| matches!(statement, Statement::ReturnStatement(_) | Statement::ThrowStatement(_))) {
+        Some(index) => index + 1,
+        None => return
+    };
+
+    let mut index = 0;
+    statements.retain(|statement| {
+        index += 1;
+        return index <= end || declares_names(statement);
+    });
+}
+
+// Blocks and try statements are kept as well when var or function declarations are nested in them
+fn declares_names(statement: &Statement) -> bool {
+    if matches!(statement, Statement::LexicalDeclaration(_)) {
+        return true;
+    }
+    let statements = std::slice::from_ref(statement);
+    return !Statement::var_declared_names(statements).is_empty() || !Statement::function_declarations(statements).is_empty();
+}
@@ -25,8 +25,10 @@ pub enum ParseError {
     MissingDoctypeName,
     InvalidCharacterSequenceAfterDoctypeName,
     MissingSemicolonAfterCharacterReference,
-    UnknownNamedCharacterReference,
+    // The name with its ; (e.g. "nbps;"), and the known references closest to it
+    UnknownNamedCharacterReference { name: String, suggestions: Vec<&'static str> },
     AbsenceOfDigitsInNumericCharacterReference,
+    NullCharacterReference,
     CharacterReferenceOutsideUnicodeRange,
     SurrogateCharacterReference,
     NonCharacterReference,
@@ -41,7 +43,11 @@ pub enum ParseError {
     MissingDoctypeSystemIdentifier,
     AbruptDoctypeSystemIdentifier,
     UnexpectedCharacterAfterDoctypeSystemIdentifier,
-    EndOfFileInCData
+    EndOfFileInCData,
+    CdataInHtmlContent,
+    EndTagWithAttributes,
+    EndTagWithTrailingSolidus,
+    NonVoidHtmlElementStartTagWithTrailingSolidus
 }
 
 impl fmt::Display for ParseError { 
@@ -71,8 +77,16 @@ impl fmt::Display for ParseError {
             ParseError::MissingDoctypeName => write!(f, "Missing doctype name"),
             ParseError::InvalidCharacterSequenceAfterDoctypeName =>  write!(f, "Invalid character sequence after doctype name"),
             ParseError::MissingSemicolonAfterCharacterReference => write!(f, "Missing semicolon after character reference"),
-            ParseError::UnknownNamedCharacterReference => write!(f, "Unknown named character reference"),
+            ParseError::UnknownNamedCharacterReference { name, suggestions } => {
+                write!(f, "Unknown named character reference &{}", name)?;
+                match suggestions.split_last() {
+                    Some((last, [])) => write!(f, ", did you mean &{}?", last),
+                    Some((last, rest)) => write!(f, ", did you mean &{} or &{}?", rest.join(", &"), last),
+                    None => Ok(())
+                }
+            },
             ParseError::AbsenceOfDigitsInNumericCharacterReference => write!(f, "Absence of digits in numeric character reference"),
+            ParseError::NullCharacterReference => write!(f, "Null character reference"),
             ParseError::CharacterReferenceOutsideUnicodeRange => write!(f, "Character reference outside unicode range"),
             ParseError::SurrogateCharacterReference => write!(f, "Surrogate character reference"),
             ParseError::NonCharacterReference => write!(f, "Non character reference"),
@@ -88,6 +102,10 @@ impl fmt::Display for ParseError {
             ParseError::AbruptDoctypeSystemIdentifier =>  write!(f, "Abrupt doctype system identifier"),
             ParseError::UnexpectedCharacterAfterDoctypeSystemIdentifier => write!(f, "Unexpected character after doctype system identifier"),
             ParseError::EndOfFileInCData => write!(f, "End of file in c data"),
+            ParseError::CdataInHtmlContent => write!(f, "CDATA in HTML content"),
+            ParseError::EndTagWithAttributes => write!(f, "End tag with attributes"),
+            ParseError::EndTagWithTrailingSolidus => write!(f, "End tag with trailing solidus"),
+            ParseError::NonVoidHtmlElementStartTagWithTrailingSolidus => write!(f, "Non void HTML element start tag with trailing solidus"),
         }
     }
 }
\ No newline at end of file
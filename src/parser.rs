@@ -2,17 +2,25 @@
 
 use std::rc::Rc;
 use serde_json::de::Read;
-use crate::token::{Token, TokenType, Literal};
-use crate::ast::{Statement, VariableDeclarationStatement, ExpressionStatement, BinaryExpression, LiteralExpression, ParenthesizedExpression, UnaryExpression, IdentifierExpression, CallExpression, BlockStatement, ObjectLiteralExpression, AssignmentExpression, PropertyDefinition, PropertyName};
+use crate::token::{Token, TokenType, Literal, SyntaxError};
+use crate::ast::{Statement, VariableDeclarationStatement, ExpressionStatement, BinaryExpression, LiteralExpression, ParenthesizedExpression, UnaryExpression, IdentifierExpression, CallExpression, BlockStatement, ObjectLiteralExpression, ArrayLiteralExpression, AssignmentExpression, PropertyDefinition, PropertyName, MemberExpression, MemberProperty, FunctionDeclaration, FunctionExpression, ArrowFunction, FormalParameter, FormalParameters, FunctionBody, ReturnStatement, ThisExpression, NewExpression, ThrowStatement, TryStatement, Catch, TemplateLiteral, LexicalDeclaration, Script};
 
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    // Number of function bodies enclosing the current token, return is only allowed inside one
+    function_depth: usize,
+    // https://tc39.es/ecma262/#sec-strict-mode-code
+    // Whether the code being parsed is strict mode code, nested functions inherit it from the code around them
+    strict: bool,
+    // https://tc39.es/ecma262/#early-error
+    // The parser carries on after an error so all of them are reported at once
+    errors: Vec<SyntaxError>,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Parser {
-        Parser { tokens, current: 0 }
+        Parser { tokens, current: 0, function_depth: 0, strict: false, errors: Vec::new() }
     }
 
     pub fn expression(&mut self) -> ExpressionStatement {
@@ -20,20 +28,25 @@ impl Parser {
     }
 
     fn assignment_expression(&mut self) -> ExpressionStatement {
+        // https://tc39.es/ecma262/#prod-ArrowFunction
+        if self.is_arrow_function() {
+            return self.arrow_function();
+        }
+
         let expression = self.equality();
 
         if self.match_token(vec![TokenType::EQUAL]) {
-            let equals = &self.previous();
+            let equals = self.previous().clone();
 
             match expression {
-                ExpressionStatement::IdentifierExpression(var_expr) => {
+                ExpressionStatement::IdentifierExpression(_) | ExpressionStatement::MemberExpression(_) => {
                     return ExpressionStatement::AssignmentExpression(Box::new(AssignmentExpression {
-                        left_hand_side_expression: Rc::new(ExpressionStatement::IdentifierExpression(Box::new(*var_expr))),
+                        left_hand_side_expression: Rc::new(expression),
                         expression: Rc::new(self.assignment_expression())
                     }))
                 },
                 _ => {
-                    println!("{:?}: Invalid assignment target.", equals);
+                    self.error(&equals, "Invalid assignment target".to_string());
                 }
             }
         }
@@ -48,14 +61,109 @@ impl Parser {
             self.advance();
         } else if self.match_token(vec![TokenType::LEFT_BRACE]) {
             return self.block_statement();
+        } else if self.match_token(vec![TokenType::RETURN]) {
+            return self.return_statement();
+        } else if self.match_token(vec![TokenType::THROW]) {
+            return self.throw_statement();
+        } else if self.match_token(vec![TokenType::TRY]) {
+            return self.try_statement();
+        } else if self.match_token(vec![TokenType::WITH]) {
+            // https://tc39.es/ecma262/#sec-with-statement-static-semantics-early-errors
+            // It is a Syntax Error if the source text matched by this production is contained in strict mode code
+            let with = self.previous().clone();
+            if self.strict {
+                self.error(&with, "Strict mode code may not include a with statement".to_string());
+            } else {
+                // TODO: with statements are not supported outside strict mode code either
+                self.error(&with, "with statements are not supported".to_string());
+            }
         }
         return self.expression_statement()
     }
 
+    // https://tc39.es/ecma262/#prod-ReturnStatement
+    fn return_statement(&mut self) -> Statement {
+        let return_token = self.previous().clone();
+        if self.function_depth == 0 {
+            self.error(&return_token, "Illegal return statement".to_string());
+        }
+
+        // https://tc39.es/ecma262/#sec-no-lineterminator-here-automatic-semicolon-insertion-list
+        // return [no LineTerminator here] Expression, a return at the end of a line returns undefined
+        let mut expression = None;
+        if !self.check(TokenType::SEMICOLON) && !self.check(TokenType::RIGHT_BRACE) && !self.is_at_end() && self.peek().line == return_token.line {
+            expression = Some(self.expression());
+        }
+
+        if self.check(TokenType::SEMICOLON) {
+            self.advance();
+        }
+
+        return Statement::ReturnStatement(Box::new(ReturnStatement { expression }));
+    }
+
+    // https://tc39.es/ecma262/#prod-ThrowStatement
+    fn throw_statement(&mut self) -> Statement {
+        let keyword = self.previous().clone();
+
+        // throw [no LineTerminator here] Expression, unlike return there is no automatic semicolon insertion
+        if self.is_at_end() || self.peek().line != keyword.line {
+            self.error(&keyword, "Illegal newline after throw".to_string());
+        }
+
+        let expression = self.expression();
+
+        if self.check(TokenType::SEMICOLON) {
+            self.advance();
+        }
+
+        return Statement::ThrowStatement(Box::new(ThrowStatement { keyword, expression }));
+    }
+
+    // https://tc39.es/ecma262/#prod-TryStatement
+    fn try_statement(&mut self) -> Statement {
+        let block = self.block();
+
+        let mut catch = None;
+        if self.match_token(vec![TokenType::CATCH]) {
+            // https://tc39.es/ecma262/#prod-Catch
+            // The catch parameter is optional, catch Block
+            let mut parameter = None;
+            if self.match_token(vec![TokenType::LeftParen]) {
+                parameter = Some(self.consume(TokenType::IDENTIFIER, "Expect identifier as the catch parameter.".to_string()).clone());
+                self.consume(TokenType::RIGHT_PAREN, "Expect ')' after catch parameter.".to_string());
+            }
+
+            catch = Some(Catch { parameter, block: self.block() });
+        }
+
+        let mut finally = None;
+        if self.match_token(vec![TokenType::FINALLY]) {
+            finally = Some(self.block());
+        }
+
+        if catch.is_none() && finally.is_none() {
+            self.error_at_current("Missing catch or finally after try".to_string());
+        }
+
+        return Statement::TryStatement(Box::new(TryStatement { block, catch, finally }));
+    }
+
+    // https://tc39.es/ecma262/#prod-Block
+    fn block(&mut self) -> BlockStatement {
+        self.consume(TokenType::LEFT_BRACE, "Expect '{' before block.".to_string());
+        match self.block_statement() {
+            Statement::BlockStatement(block) => *block,
+            _ => { unreachable!() }
+        }
+    }
+
     pub fn block_statement(&mut self) -> Statement {
         let mut statements: Vec<Statement> = Vec::new();
         while !self.check(TokenType::RIGHT_BRACE) && !self.is_at_end() {
+            let start = self.current;
             statements.push(self.declaration());
+            self.skip_unexpected_token(start);
         }
 
         if self.peek().token_type == TokenType::SEMICOLON {
@@ -72,6 +180,16 @@ impl Parser {
             return self.var_declaration();
         }
 
+        // https://tc39.es/ecma262/#prod-LexicalDeclaration
+        if self.match_token(vec![TokenType::LET, TokenType::CONST]) {
+            return self.lexical_declaration();
+        }
+
+        // https://tc39.es/ecma262/#prod-FunctionDeclaration
+        if self.match_token(vec![TokenType::FUNCTION]) {
+            return self.function_declaration();
+        }
+
         return self.statement();
 
         // TODO: Error handling
@@ -87,17 +205,234 @@ impl Parser {
                 expression: Rc::new(self.expression()),
             }));
 
+            if self.check(TokenType::SEMICOLON) {
+                self.advance();
+            }
+
             return Statement::VariableStatement(Box::new(VariableDeclarationStatement {
                 binding_identifier: name,
                 initializer }))
         }
 
+        if self.check(TokenType::SEMICOLON) {
+            self.advance();
+        }
+
         return Statement::VariableStatement(Box::new(VariableDeclarationStatement { binding_identifier: name, initializer }))
     }
 
+    // https://tc39.es/ecma262/#prod-LexicalDeclaration
+    // LetOrConst BindingIdentifier Initializer, the initializer is optional for let
+    fn lexical_declaration(&mut self) -> Statement {
+        let let_or_const = self.previous().clone();
+        let binding_identifier = self.consume(TokenType::IDENTIFIER, "missing variable name".to_string()).clone();
+
+        let mut initializer = None;
+        if self.match_token(vec![TokenType::EQUAL]) {
+            initializer = Some(self.expression());
+        } else if let_or_const.token_type == TokenType::CONST {
+            // https://tc39.es/ecma262/#sec-let-and-const-declarations-static-semantics-early-errors
+            self.error(&binding_identifier, "Missing initializer in const declaration".to_string());
+        }
+
+        if self.check(TokenType::SEMICOLON) {
+            self.advance();
+        }
+
+        return Statement::LexicalDeclaration(Box::new(LexicalDeclaration { let_or_const, binding_identifier, initializer }));
+    }
+
+    // https://tc39.es/ecma262/#prod-FunctionDeclaration
+    // function BindingIdentifier ( FormalParameters ) { FunctionBody }
+    fn function_declaration(&mut self) -> Statement {
+        let binding_identifier = self.consume(TokenType::IDENTIFIER, "Function statements require a function name".to_string()).clone();
+        let formal_parameters = self.formal_parameters();
+        let function_body = self.function_body();
+        if function_body.strict {
+            self.check_duplicate_parameters(&formal_parameters);
+        }
+
+        return Statement::FunctionDeclaration(Box::new(FunctionDeclaration {
+            binding_identifier,
+            formal_parameters: Rc::new(formal_parameters),
+            function_body: Rc::new(function_body)
+        }))
+    }
+
+    // https://tc39.es/ecma262/#prod-FunctionExpression
+    // function BindingIdentifier(opt) ( FormalParameters ) { FunctionBody }
+    fn function_expression(&mut self) -> ExpressionStatement {
+        let mut binding_identifier = None;
+        if self.check(TokenType::IDENTIFIER) {
+            binding_identifier = Some(self.advance().clone());
+        }
+        let formal_parameters = self.formal_parameters();
+        let function_body = self.function_body();
+        if function_body.strict {
+            self.check_duplicate_parameters(&formal_parameters);
+        }
+
+        return ExpressionStatement::FunctionExpression(Box::new(FunctionExpression {
+            binding_identifier,
+            formal_parameters: Rc::new(formal_parameters),
+            function_body: Rc::new(function_body)
+        }))
+    }
+
+    // https://tc39.es/ecma262/#prod-FormalParameters
+    fn formal_parameters(&mut self) -> FormalParameters {
+        let mut parameters: Vec<FormalParameter> = Vec::new();
+        self.consume(TokenType::LeftParen, "missing ( before formal parameters".to_string());
+
+        while !self.check(TokenType::RIGHT_PAREN) && !self.is_at_end() {
+            if !self.check(TokenType::IDENTIFIER) {
+                self.error_at_current(format!("Unexpected token '{}' in formal parameters", self.peek().lexeme));
+                break;
+            }
+            parameters.push(FormalParameter { binding_identifier: self.advance().clone() });
+
+            // A trailing comma is allowed after the last parameter
+            if !self.match_token(vec![TokenType::COMMA]) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RIGHT_PAREN, "missing ) after formal parameters".to_string());
+        return FormalParameters { parameters };
+    }
+
+    // https://tc39.es/ecma262/#sec-function-definitions-static-semantics-early-errors
+    // It is a Syntax Error if IsStrict(FunctionBody) is true and BoundNames of FormalParameters contains any duplicate elements,
+    // which is checked after the body because a directive in it makes the parameters strict too
+    fn check_duplicate_parameters(&mut self, formal_parameters: &FormalParameters) {
+        for (index, parameter) in formal_parameters.parameters.iter().enumerate() {
+            let name = &parameter.binding_identifier;
+            if formal_parameters.parameters[..index].iter().any(|previous| previous.binding_identifier.lexeme == name.lexeme) {
+                self.error(name, "Duplicate parameter name not allowed in this context".to_string());
+            }
+        }
+    }
+
+    // https://tc39.es/ecma262/#prod-FunctionBody
+    fn function_body(&mut self) -> FunctionBody {
+        self.consume(TokenType::LEFT_BRACE, "missing { before function body".to_string());
+
+        // A directive only makes this function strict, the code after it is parsed with the strictness it had
+        let enclosing_strict = self.strict;
+        self.directive_prologue();
+
+        self.function_depth += 1;
+        let mut statements: Vec<Statement> = Vec::new();
+        while !self.check(TokenType::RIGHT_BRACE) && !self.is_at_end() {
+            let start = self.current;
+            statements.push(self.declaration());
+            self.skip_unexpected_token(start);
+        }
+        self.function_depth -= 1;
+
+        self.consume(TokenType::RIGHT_BRACE, "missing } after function body".to_string());
+
+        let strict = self.strict;
+        self.strict = enclosing_strict;
+        return FunctionBody { statements, strict };
+    }
+
+    // https://tc39.es/ecma262/#directive-prologue
+    // The string literal statements at the start of a script or function body, a Use Strict Directive among them makes the code strict.
+    // The prologue is looked at before it is parsed so the directives after a Use Strict Directive are checked as strict mode code too
+    fn directive_prologue(&mut self) {
+        let mut index = self.current;
+        while self.tokens[index].token_type == TokenType::STRING {
+            // A string followed by anything else starts an expression, e.g. "a" + b
+            let next = &self.tokens[index + 1];
+            let ends_statement = matches!(next.token_type, TokenType::SEMICOLON | TokenType::RIGHT_BRACE | TokenType::EOF) || next.line != self.tokens[index].line;
+            if !ends_statement {
+                return;
+            }
+
+            // https://tc39.es/ecma262/#use-strict-directive
+            // The exact code points "use strict" or 'use strict', so it can not contain escape sequences
+            let lexeme = &self.tokens[index].lexeme;
+            if &lexeme[1..lexeme.len() - 1] == "use strict" {
+                self.strict = true;
+            }
+
+            index += 1;
+            if self.tokens[index].token_type == TokenType::SEMICOLON {
+                index += 1;
+            }
+        }
+    }
+
+    // https://tc39.es/ecma262/#prod-ArrowFunction
+    // Looks ahead for the => that follows ArrowParameters, which is either a single identifier or a parenthesized list
+    fn is_arrow_function(&self) -> bool {
+        if self.check(TokenType::IDENTIFIER) {
+            return self.tokens[self.current + 1].token_type == TokenType::ARROW;
+        }
+
+        if !self.check(TokenType::LeftParen) {
+            return false;
+        }
+
+        let mut depth = 0;
+        for (index, token) in self.tokens.iter().enumerate().skip(self.current) {
+            match token.token_type {
+                TokenType::LeftParen => depth += 1,
+                TokenType::RIGHT_PAREN => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return self.tokens[index + 1].token_type == TokenType::ARROW;
+                    }
+                },
+                TokenType::EOF => return false,
+                _ => {}
+            }
+        }
+
+        return false;
+    }
+
+    // https://tc39.es/ecma262/#prod-ArrowFunction
+    // ArrowParameters [no LineTerminator here] => ConciseBody
+    fn arrow_function(&mut self) -> ExpressionStatement {
+        // https://tc39.es/ecma262/#prod-ArrowParameters
+        let formal_parameters = if self.check(TokenType::IDENTIFIER) {
+            FormalParameters { parameters: vec![FormalParameter { binding_identifier: self.advance().clone() }] }
+        } else {
+            self.formal_parameters()
+        };
+
+        self.consume(TokenType::ARROW, "Expect '=>' after arrow function parameters.".to_string());
+
+        // https://tc39.es/ecma262/#sec-arrow-function-definitions-static-semantics-early-errors
+        // ArrowFormalParameters are UniqueFormalParameters, so the names must be unique whether or not the code is strict
+        self.check_duplicate_parameters(&formal_parameters);
+
+        // https://tc39.es/ecma262/#prod-ConciseBody
+        let function_body = if self.check(TokenType::LEFT_BRACE) {
+            self.function_body()
+        } else {
+            // ExpressionBody, which evaluates to the value of the expression as if it was returned
+            let expression = self.assignment_expression();
+            FunctionBody { statements: vec![Statement::ReturnStatement(Box::new(ReturnStatement { expression: Some(expression) }))], strict: self.strict }
+        };
+
+        return ExpressionStatement::ArrowFunction(Box::new(ArrowFunction {
+            formal_parameters: Rc::new(formal_parameters),
+            function_body: Rc::new(function_body)
+        }))
+    }
 
     fn expression_statement(&mut self) -> Statement {
         let expression = self.expression();
+
+        // https://tc39.es/ecma262/#prod-ExpressionStatement
+        // Consume the terminating semicolon so it is not parsed as a statement of its own
+        if self.check(TokenType::SEMICOLON) {
+            self.advance();
+        }
+
         return Statement::ExpressionStatement(Box::new(expression));
     }
 
@@ -115,7 +450,7 @@ impl Parser {
     fn comparison(&mut self) -> ExpressionStatement {
         let mut expression: ExpressionStatement = self.term();
 
-        while self.match_token(vec![TokenType::GREATER, TokenType::GREATER_EQUAL, TokenType::LESS, TokenType::LESS_EQUAL]) {
+        while self.match_token(vec![TokenType::GREATER, TokenType::GREATER_EQUAL, TokenType::LESS, TokenType::LESS_EQUAL, TokenType::INSTANCEOF]) {
             let operator = self.previous().clone();
             let right = self.term();
             expression = ExpressionStatement::BinaryExpression(Box::new(BinaryExpression { left: Box::new(expression), right: Box::new(right), operator }));
@@ -149,7 +484,7 @@ impl Parser {
     }
 
     fn unary(&mut self) -> ExpressionStatement {
-        if self.match_token(vec![TokenType::BANG, TokenType::MINUS, TokenType::PLUS]) {
+        if self.match_token(vec![TokenType::BANG, TokenType::MINUS, TokenType::PLUS, TokenType::DELETE]) {
             let operator = self.previous().clone();
             let right = self.unary();
             return ExpressionStatement::UnaryExpression(Box::new(UnaryExpression { operator, right: Box::new(right) }))
@@ -159,10 +494,20 @@ impl Parser {
     }
 
     fn call_expression(&mut self) -> ExpressionStatement {
-        let mut expression: ExpressionStatement = self.primary();
+        let mut expression: ExpressionStatement = if self.match_token(vec![TokenType::NEW]) {
+            self.new_expression()
+        } else {
+            self.primary()
+        };
+
         loop {
             if self.match_token(vec![TokenType::LeftParen]) {
                 expression = self.finish_call(expression);
+            } else if self.check(TokenType::DOT) || self.check(TokenType::LEFT_BRACKET) {
+                match self.member_property() {
+                    Some(property) => expression = ExpressionStatement::MemberExpression(Box::new(MemberExpression { object: Box::new(expression), property })),
+                    None => break
+                }
             } else {
                 break;
             }
@@ -171,20 +516,108 @@ impl Parser {
         return expression;
     }
 
+    // https://tc39.es/ecma262/#prod-NewExpression
+    // The callee is a MemberExpression, so the first argument list belongs to new rather than to a call
+    fn new_expression(&mut self) -> ExpressionStatement {
+        let keyword = self.previous().clone();
+        let mut callee: ExpressionStatement = if self.match_token(vec![TokenType::NEW]) {
+            self.new_expression()
+        } else {
+            self.primary()
+        };
+
+        while self.check(TokenType::DOT) || self.check(TokenType::LEFT_BRACKET) {
+            match self.member_property() {
+                Some(property) => callee = ExpressionStatement::MemberExpression(Box::new(MemberExpression { object: Box::new(callee), property })),
+                None => break
+            }
+        }
+
+        // NewExpression : new NewExpression, which is called without arguments
+        let mut arguments: Vec<ExpressionStatement> = Vec::new();
+        if self.match_token(vec![TokenType::LeftParen]) {
+            arguments = self.arguments();
+        }
+
+        return ExpressionStatement::NewExpression(Box::new(NewExpression { keyword, callee: Box::new(callee), arguments }));
+    }
+
+    // https://tc39.es/ecma262/#prod-MemberExpression
+    // Parses the property of a member expression, the next token is either a . or a [
+    fn member_property(&mut self) -> Option<MemberProperty> {
+        if self.match_token(vec![TokenType::DOT]) {
+            // MemberExpression . IdentifierName
+            if !self.check_identifier_name() {
+                self.error_at_current("Expect property name after '.'".to_string());
+                return None;
+            }
+            return Some(MemberProperty::IdentifierName(self.advance().clone()));
+        }
+
+        // MemberExpression [ Expression ]
+        self.consume(TokenType::LEFT_BRACKET, "Expect '[' before property expression.".to_string());
+        let property_expression = self.expression();
+        self.consume(TokenType::RIGHT_BRACKET, "Expect ']' after property expression.".to_string());
+        return Some(MemberProperty::Expression(Box::new(property_expression)));
+    }
+
     fn finish_call(&mut self, callee: ExpressionStatement) -> ExpressionStatement {
+        let arguments = self.arguments();
+        let paren = self.previous().clone();
+
+        return ExpressionStatement::CallExpression(Box::new(CallExpression { callee: Box::new(callee), paren, arguments }))
+    }
+
+    // https://tc39.es/ecma262/#prod-Arguments
+    // Parses the argument list after the opening parenthesis, including the closing one
+    fn arguments(&mut self) -> Vec<ExpressionStatement> {
         let mut arguments: Vec<ExpressionStatement> = Vec::new();
+        if !self.check(TokenType::RIGHT_PAREN) {
             arguments.push(self.expression());
 
             while self.match_token(vec![TokenType::COMMA]) {
-                arguments.push(self.expression());
                 if self.check(TokenType::RIGHT_PAREN) {
                     break;
                 }
+                arguments.push(self.expression());
+            }
+        }
+
+        self.consume(TokenType::RIGHT_PAREN, "Expect ')' after arguments.".to_string());
+        return arguments;
+    }
+
+    // https://tc39.es/ecma262/#prod-SubstitutionTemplate
+    // TemplateHead Expression TemplateSpans, where each span is a TemplateMiddle followed by an Expression or the TemplateTail
+    fn template_literal(&mut self) -> ExpressionStatement {
+        let mut strings = vec![self.template_string()];
+        let mut substitutions = Vec::new();
+
+        if self.previous().token_type == TokenType::TEMPLATE_HEAD {
+            loop {
+                substitutions.push(self.expression());
+
+                if self.match_token(vec![TokenType::TEMPLATE_MIDDLE]) {
+                    strings.push(self.template_string());
+                } else if self.match_token(vec![TokenType::TEMPLATE_TAIL]) {
+                    strings.push(self.template_string());
+                    break;
+                } else {
+                    self.error_at_current("Expect '}' after template substitution".to_string());
+                    strings.push(String::new());
+                    break;
+                }
             }
+        }
 
-        let paren = self.consume(TokenType::RIGHT_PAREN, "Expect ')' after arguments.".to_string());
+        return ExpressionStatement::TemplateLiteral(Box::new(TemplateLiteral { strings, substitutions }));
+    }
 
-        return ExpressionStatement::CallExpression(Box::new(CallExpression { callee: Box::new(callee), paren: paren.clone(), arguments }))
+    fn template_string(&self) -> String {
+        match &self.previous().literal {
+            Some(Literal::String(value)) => value.clone(),
+            _ => { unreachable!() }
+        }
     }
 
     fn primary(&mut self) -> ExpressionStatement {
@@ -200,39 +633,79 @@ impl Parser {
             return ExpressionStatement::LiteralExpression(Box::new(LiteralExpression { value: Literal::Null() }))
         }
 
-        if self.match_token(vec![TokenType::NUMBER, TokenType::STRING]) {
+        // https://tc39.es/ecma262/#prod-PrimaryExpression
+        if self.match_token(vec![TokenType::THIS]) {
+            return ExpressionStatement::ThisExpression(Box::new(ThisExpression))
+        }
+
+        if self.match_token(vec![TokenType::NUMBER, TokenType::STRING, TokenType::REGULAR_EXPRESSION]) {
+            let literal = self.previous().clone();
+            self.check_strict_literal(&literal);
             let literal_value = self.previous().literal.clone().unwrap();
             return ExpressionStatement::LiteralExpression(Box::new(LiteralExpression { value: literal_value }))
         }
 
+        // https://tc39.es/ecma262/#prod-TemplateLiteral
+        if self.match_token(vec![TokenType::NO_SUBSTITUTION_TEMPLATE, TokenType::TEMPLATE_HEAD]) {
+            return self.template_literal();
+        }
+
         // https://tc39.es/ecma262/#prod-VariableDeclaration
         if self.match_token(vec![TokenType::IDENTIFIER]) {
             return ExpressionStatement::IdentifierExpression(Box::new(IdentifierExpression { binding_identifier: self.previous().clone() }))
         }
 
+        // https://tc39.es/ecma262/#prod-FunctionExpression
+        if self.match_token(vec![TokenType::FUNCTION]) {
+            return self.function_expression();
+        }
+
+        // https://tc39.es/ecma262/#prod-ObjectLiteral
         if self.match_token(vec![TokenType::LEFT_BRACE]) {
-            // https://tc39.es/ecma262/#sec-static-semantics-propertynamelist
-            let mut property_name_list: Vec<PropertyDefinition> = Vec::new();
-
-            match self.create_property_definition() {
-                Some(property_name) => {
-                    property_name_list.push(property_name);
-
-                    while self.match_token(vec![TokenType::COMMA]) {
-                        property_name_list.push(self.create_property_definition().unwrap());
-                        if self.check(TokenType::RIGHT_BRACE) {
-                            break;
-                        }
-                    }
-                    self.consume(TokenType::RIGHT_BRACE, "Expect '}' after expression.".to_string());
+            // https://tc39.es/ecma262/#prod-PropertyDefinitionList
+            let mut property_definitions: Vec<PropertyDefinition> = Vec::new();
 
-                },
-                None => {
-                    self.consume(TokenType::RIGHT_BRACE, "Expect '}' after expression.".to_string());
+            while !self.check(TokenType::RIGHT_BRACE) && !self.is_at_end() {
+                match self.create_property_definition() {
+                    Some(property_definition) => property_definitions.push(property_definition),
+                    None => break
+                }
+
+                // A trailing comma is allowed after the last property definition
+                if !self.match_token(vec![TokenType::COMMA]) {
+                    break;
                 }
             }
 
-            return ExpressionStatement::ObjectLiteralExpression(Box::new(ObjectLiteralExpression { property_definitions: property_name_list }))
+            self.consume(TokenType::RIGHT_BRACE, "Expect '}' after expression.".to_string());
+
+            return ExpressionStatement::ObjectLiteralExpression(Box::new(ObjectLiteralExpression { property_definitions }))
+        }
+
+        // https://tc39.es/ecma262/#prod-ArrayLiteral
+        if self.match_token(vec![TokenType::LEFT_BRACKET]) {
+            // https://tc39.es/ecma262/#prod-ElementList
+            let mut element_list: Vec<Option<ExpressionStatement>> = Vec::new();
+
+            while !self.check(TokenType::RIGHT_BRACKET) && !self.is_at_end() {
+                // https://tc39.es/ecma262/#prod-Elision
+                // A comma with no element before it leaves a hole in the array
+                if self.match_token(vec![TokenType::COMMA]) {
+                    element_list.push(None);
+                    continue;
+                }
+
+                element_list.push(Some(self.assignment_expression()));
+
+                // A trailing comma after the last element does not add a hole
+                if !self.match_token(vec![TokenType::COMMA]) {
+                    break;
+                }
+            }
+
+            self.consume(TokenType::RIGHT_BRACKET, "Expect ']' after array elements.".to_string());
+
+            return ExpressionStatement::ArrayLiteralExpression(Box::new(ArrayLiteralExpression { element_list }))
         }
 
         if self.match_token(vec![TokenType::LeftParen]) {
@@ -246,40 +719,55 @@ impl Parser {
     }
 
 
-    // https://tc39.es/ecma262/#sec-static-semantics-propertynamelist
+    // https://tc39.es/ecma262/#prod-PropertyDefinition
+    // TODO: Method definitions, getters, setters and spread properties are not supported
     fn create_property_definition(&mut self) -> Option<PropertyDefinition> {
-        if self.match_token(vec![TokenType::IDENTIFIER, TokenType::NUMBER, TokenType::STRING]) {
-            // 1. Let propName be the PropName of PropertyDefinition.
-
-            // TODO: Implement proper getting of PropName https://tc39.es/ecma262/#sec-static-semantics-propname
-            let prop_name = self.previous().clone();
-
-            self.consume(TokenType::COLON, "Uncaught SyntaxError: missing : after property id".to_string());
-
-            if prop_name.token_type == TokenType::IDENTIFIER {
-                let expression = self.expression();
-
-                // 3. Return « propName ».
-                return Some(PropertyDefinition { property_name: PropertyName::IdentifierName(prop_name.clone()),
-                    assignment_expression: AssignmentExpression { left_hand_side_expression: Rc::new(ExpressionStatement::IdentifierExpression(Box::new(IdentifierExpression { binding_identifier: prop_name }))), expression: Rc::new(expression) }});
-            } else {
-                match prop_name.literal {
-                    Some(Literal::String(ref value)) => {
-                        let expression = self.expression();
-                        // 3. Return « propName ».
-                        return Some(PropertyDefinition { property_name:  PropertyName::LiteralPropertyName(Literal::String(value.clone())),
-                            assignment_expression: AssignmentExpression { left_hand_side_expression: Rc::new(ExpressionStatement::IdentifierExpression(Box::new(IdentifierExpression { binding_identifier: prop_name }))), expression: Rc::new(expression) }});
-
-                    },
-                    _ => { unimplemented!() }
-                }
-            }
+        // https://tc39.es/ecma262/#prod-PropertyName
+        let property_name_token = self.peek().clone();
+        let property_name = if self.match_token(vec![TokenType::LEFT_BRACKET]) {
+            // https://tc39.es/ecma262/#prod-ComputedPropertyName
+            let expression = self.expression();
+            self.consume(TokenType::RIGHT_BRACKET, "Expect ']' after computed property name.".to_string());
+            PropertyName::ComputedPropertyName(Box::new(expression))
+        } else if self.match_token(vec![TokenType::STRING, TokenType::NUMBER]) {
+            let literal = self.previous().clone();
+            self.check_strict_literal(&literal);
+            PropertyName::LiteralPropertyName(self.previous().literal.clone().unwrap())
+        } else if self.check_identifier_name() {
+            PropertyName::IdentifierName(self.advance().clone())
+        } else {
+            self.error(&property_name_token, format!("Unexpected token '{}' in object literal", property_name_token.lexeme));
+            return None;
+        };
+
+        // https://tc39.es/ecma262/#prod-IdentifierReference
+        // PropertyDefinition : IdentifierReference
+        // The shorthand { a } is the same as { a: a }
+        let expression = if property_name_token.token_type == TokenType::IDENTIFIER && (self.check(TokenType::COMMA) || self.check(TokenType::RIGHT_BRACE)) {
+            ExpressionStatement::IdentifierExpression(Box::new(IdentifierExpression { binding_identifier: property_name_token.clone() }))
+        } else {
+            // PropertyDefinition : PropertyName : AssignmentExpression
+            self.consume(TokenType::COLON, "missing : after property id".to_string());
+            self.expression()
+        };
+
+        return Some(PropertyDefinition {
+            property_name,
+            assignment_expression: AssignmentExpression { left_hand_side_expression: Rc::new(ExpressionStatement::IdentifierExpression(Box::new(IdentifierExpression { binding_identifier: property_name_token }))), expression: Rc::new(expression) }
+        });
+    }
 
-            // https://tc39.es/ecma262/#sec-object-initializer-static-semantics-early-errors
+    // https://tc39.es/ecma262/#prod-IdentifierName
+    // Reserved words are valid IdentifierNames, so they can be used as property names
+    fn check_identifier_name(&self) -> bool {
+        if self.is_at_end() {
+            return false;
         }
 
-        // 2. If propName is empty, return a new empty List.
-        return None;
+        return match self.peek().lexeme.chars().next() {
+            Some(character) => character.is_alphabetic() || character == '_' || character == '$',
+            None => false
+        } && self.peek().token_type != TokenType::STRING;
     }
 
     fn consume(&mut self, token_type: TokenType, message: String) -> &Token {
@@ -288,12 +776,57 @@ impl Parser {
             return token;
         }
 
-        if (token_type == TokenType::EOF) {
-            println!("Uncaught SyntaxError: {} at end", message);
-            return self.peek();
-        } else {
-            println!("Uncaught SyntaxError: {} at line {}", message, self.peek().line);
-            return self.peek();
+        self.error_at_current(message);
+        return self.peek();
+    }
+
+    fn error(&mut self, token: &Token, message: String) {
+        self.errors.push(SyntaxError { message, line: token.line, column: token.column });
+    }
+
+    fn error_at_current(&mut self, message: String) {
+        let token = self.peek().clone();
+        self.error(&token, message);
+    }
+
+    // https://tc39.es/ecma262/#sec-additional-syntax-numeric-literals
+    // https://tc39.es/ecma262/#sec-additional-syntax-string-literals
+    // The legacy octal forms of numeric and string literals are not allowed in strict mode code
+    fn check_strict_literal(&mut self, token: &Token) {
+        if !self.strict {
+            return;
+        }
+
+        let lexeme = token.lexeme.to_string();
+        match token.token_type {
+            // LegacyOctalIntegerLiteral and NonOctalDecimalIntegerLiteral both start with a 0 followed by more digits
+            TokenType::NUMBER if lexeme.len() > 1 && lexeme.starts_with('0') && lexeme.chars().nth(1).unwrap().is_ascii_digit() => {
+                if lexeme.chars().all(|digit| digit.is_ascii_digit() && digit <= '7') {
+                    self.error(token, "Octal literals are not allowed in strict mode".to_string());
+                } else {
+                    self.error(token, "Decimals with leading zeros are not allowed in strict mode".to_string());
+                }
+            },
+            // LegacyOctalEscapeSequence is a \ followed by an octal digit other than a lone \0, NonOctalDecimalEscapeSequence is \8 or \9
+            TokenType::STRING => {
+                let characters: Vec<char> = lexeme.chars().collect();
+                let mut index = 1;
+                while index + 1 < characters.len() {
+                    if characters[index] != '\\' {
+                        index += 1;
+                        continue;
+                    }
+                    let escaped = characters[index + 1];
+                    let followed_by_digit = characters.get(index + 2).map_or(false, |character| character.is_ascii_digit());
+                    if escaped == '8' || escaped == '9' {
+                        self.error(token, "\\8 and \\9 are not allowed in strict mode".to_string());
+                    } else if ('1'..='7').contains(&escaped) || (escaped == '0' && followed_by_digit) {
+                        self.error(token, "Octal escape sequences are not allowed in strict mode".to_string());
+                    }
+                    index += 2;
+                }
+            },
+            _ => {}
         }
     }
 
@@ -338,12 +871,30 @@ impl Parser {
         return &self.tokens[self.current - 1];
     }
 
-    pub fn parse(&mut self) -> Vec<Statement> {
+    // https://tc39.es/ecma262/#sec-parse-script
+    // Returns the Script, or the early errors found in the source text
+    pub fn parse(&mut self) -> Result<Script, Vec<SyntaxError>> {
+        self.directive_prologue();
+
         let mut statements: Vec<Statement> = Vec::new();
         while !self.is_at_end() {
+            let start = self.current;
             statements.push(self.declaration());
+            self.skip_unexpected_token(start);
         }
 
-        return statements;
+        if !self.errors.is_empty() {
+            return Err(std::mem::take(&mut self.errors));
+        }
+
+        return Ok(Script { statements, strict: self.strict });
+    }
+
+    // Unsupported syntax would otherwise leave the parser on the same token forever, so report it and move past it
+    fn skip_unexpected_token(&mut self, start: usize) {
+        if self.current == start && !self.is_at_end() {
+            self.error_at_current(format!("Unexpected token '{}'", self.peek().lexeme));
+            self.advance();
+        }
     }
 }
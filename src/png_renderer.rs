@@ -0,0 +1,185 @@
+use std::io::Write;
+use flate2::write::ZlibEncoder;
+use flate2::{Compression, Crc};
+use crate::css_values::{BorderStyle, Color};
+use crate::display_list::{DisplayItem, DisplayList, Rect};
+use crate::text::FontMetrics;
+
+// https://www.w3.org/TR/png-3/
+// Draws a display list into pixels and encodes them as a PNG image, one pixel for each CSS pixel on a white canvas
+// Items are drawn in the order of the list, each blended over what is under it with the alpha of its color
+// TODO: There are no glyph outlines to draw text with, so each character is drawn as a block as wide as its advance and as tall as the ascent of the font, like a greeked page
+// TODO: Images are not loaded, they are drawn as a gray box where they would be
+// TODO: Borders are drawn as rectangles along each side, so the corners are not split diagonally, and dashed and dotted borders are drawn solid
+
+// The biggest width and height that is drawn, a longer document is cut off at the bottom
+const MAX_SIZE: usize = 16384;
+
+// The pixels of an image, in rows from the top, as red, green, blue and alpha bytes
+pub struct Pixmap {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<u8>,
+    // The pixels that are drawn, those outside it are left as they are
+    pub clip: Option<Rect>,
+}
+
+impl Pixmap {
+    // An opaque white image
+    pub fn new(width: usize, height: usize) -> Pixmap {
+        return Pixmap { width, height, data: vec![0xFF; width * height * 4], clip: None };
+    }
+
+    // Blends color over the pixels rect covers, a pixel is covered when its center is inside rect
+    pub fn fill_rect(&mut self, rect: &Rect, color: Color) {
+        let (red, green, blue, alpha) = match color {
+            Color::Rgba { red, green, blue, alpha } => (red, green, blue, alpha.clamp(0.0, 1.0)),
+            Color::CurrentColor => return
+        };
+        if alpha <= 0.0 {
+            return;
+        }
+        let rect = self.clip.map_or(*rect, |clip| rect.intersection(&clip));
+        let left = (rect.x.round().max(0.0) as usize).min(self.width);
+        let top = (rect.y.round().max(0.0) as usize).min(self.height);
+        let right = ((rect.x + rect.width).round().max(0.0) as usize).min(self.width);
+        let bottom = ((rect.y + rect.height).round().max(0.0) as usize).min(self.height);
+        let blend = |under: u8, over: u8| (over as f32 * alpha + under as f32 * (1.0 - alpha)).round() as u8;
+        for y in top..bottom {
+            for x in left..right {
+                let pixel = &mut self.data[(y * self.width + x) * 4..][..4];
+                pixel[0] = blend(pixel[0], red);
+                pixel[1] = blend(pixel[1], green);
+                pixel[2] = blend(pixel[2], blue);
+            }
+        }
+    }
+
+    // https://www.w3.org/TR/png-3/#5DataRep
+    // An 8-bit RGBA image, every row is filtered with filter type None
+    pub fn encode_png(&self) -> Vec<u8> {
+        let mut scanlines = Vec::with_capacity(self.height * (self.width * 4 + 1));
+        for row in self.data.chunks(self.width * 4) {
+            scanlines.push(0);
+            scanlines.extend_from_slice(row);
+        }
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        // Writing to a Vec can not fail
+        encoder.write_all(&scanlines).unwrap();
+        let image_data = encoder.finish().unwrap();
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        // https://www.w3.org/TR/png-3/#11IHDR
+        // The width and height, a bit depth of 8, color type 6 (RGBA), and the default compression, filter and interlace methods
+        let mut header = Vec::new();
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        header.extend_from_slice(&[8, 6, 0, 0, 0]);
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(&mut png, b"IDAT", &image_data);
+        write_chunk(&mut png, b"IEND", &[]);
+        return png;
+    }
+}
+
+// https://www.w3.org/TR/png-3/#5Chunk-layout
+// The length, type, data and the CRC of the type and data
+fn write_chunk(png: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(chunk_type);
+    png.extend_from_slice(data);
+    let mut crc = Crc::new();
+    crc.update(chunk_type);
+    crc.update(data);
+    png.extend_from_slice(&crc.sum().to_be_bytes());
+}
+
+// The pixels of a display list, text is measured with the metrics it was laid out with
+pub fn rasterize(display_list: &DisplayList, metrics: &dyn FontMetrics) -> Pixmap {
+    let width = (display_list.width.ceil().max(1.0) as usize).min(MAX_SIZE);
+    let height = (display_list.height.ceil().max(1.0) as usize).min(MAX_SIZE);
+    let mut pixmap = Pixmap::new(width, height);
+
+    // The clips the items are in, the innermost last, each already cut down to the ones around it
+    let mut clips: Vec<Rect> = Vec::new();
+    for item in &display_list.items {
+        match item {
+            DisplayItem::Rect { rect, color } => pixmap.fill_rect(rect, *color),
+            DisplayItem::Border { rect, widths, styles, colors } => {
+                for side in 0..4 {
+                    draw_border_side(&mut pixmap, rect, widths, side, styles[side], colors[side]);
+                }
+            },
+            DisplayItem::Text { x, baseline, text, font_size, color } => {
+                let ascent = metrics.ascent(*font_size) * 0.7;
+                let mut x = *x;
+                for character in text.chars() {
+                    let advance = metrics.advance_width(character, *font_size);
+                    if !character.is_whitespace() {
+                        // A gap is left between blocks so the characters of a word can still be told apart
+                        let gap = (advance * 0.15).min(1.0);
+                        pixmap.fill_rect(&Rect { x, y: baseline - ascent, width: advance - gap, height: ascent }, *color);
+                    }
+                    x += advance;
+                }
+            },
+            DisplayItem::Image { rect, .. } => pixmap.fill_rect(rect, Color::Rgba { red: 0xC0, green: 0xC0, blue: 0xC0, alpha: 1.0 }),
+            DisplayItem::Clip { rect } => {
+                let clip = clips.last().map_or(*rect, |outer| rect.intersection(outer));
+                clips.push(clip);
+                pixmap.clip = Some(clip);
+            },
+            DisplayItem::EndClip => {
+                clips.pop();
+                pixmap.clip = clips.last().copied();
+            }
+        }
+    }
+    return pixmap;
+}
+
+// A display list as a PNG image
+pub fn render_as_png(display_list: &DisplayList, metrics: &dyn FontMetrics) -> Vec<u8> {
+    return rasterize(display_list, metrics).encode_png();
+}
+
+// https://drafts.csswg.org/css-backgrounds-3/#border-style
+// The side is given as an index into the top, right, bottom and left widths, like the sides of the SVG renderer
+fn draw_border_side(pixmap: &mut Pixmap, rect: &Rect, widths: &[f32; 4], side: usize, style: BorderStyle, color: Color) {
+    if widths[side] <= 0.0 {
+        return;
+    }
+    let top_or_left = side == 0 || side == 3;
+    let dark = match color {
+        Color::Rgba { red, green, blue, alpha } => Color::Rgba { red: red / 2, green: green / 2, blue: blue / 2, alpha },
+        Color::CurrentColor => color
+    };
+    match style {
+        BorderStyle::None | BorderStyle::Hidden => (),
+        BorderStyle::Double => {
+            draw_band(pixmap, rect, widths, side, 0.0, 1.0 / 3.0, color);
+            draw_band(pixmap, rect, widths, side, 2.0 / 3.0, 1.0, color);
+        },
+        BorderStyle::Inset => draw_band(pixmap, rect, widths, side, 0.0, 1.0, if top_or_left { dark } else { color }),
+        BorderStyle::Outset => draw_band(pixmap, rect, widths, side, 0.0, 1.0, if top_or_left { color } else { dark }),
+        BorderStyle::Groove | BorderStyle::Ridge => {
+            let outer_dark = top_or_left == (style == BorderStyle::Groove);
+            draw_band(pixmap, rect, widths, side, 0.0, 0.5, if outer_dark { dark } else { color });
+            draw_band(pixmap, rect, widths, side, 0.5, 1.0, if outer_dark { color } else { dark });
+        },
+        _ => draw_band(pixmap, rect, widths, side, 0.0, 1.0, color)
+    }
+}
+
+// Fills the band of a side between the outer and inner fractions of its width, 0 being the outer edge of the border and 1 the inner edge
+fn draw_band(pixmap: &mut Pixmap, rect: &Rect, widths: &[f32; 4], side: usize, outer_fraction: f32, inner_fraction: f32, color: Color) {
+    let outer = widths[side] * outer_fraction;
+    let thickness = widths[side] * (inner_fraction - outer_fraction);
+    let band = match side {
+        0 => Rect { x: rect.x, y: rect.y + outer, width: rect.width, height: thickness },
+        1 => Rect { x: rect.x + rect.width - outer - thickness, y: rect.y, width: thickness, height: rect.height },
+        2 => Rect { x: rect.x, y: rect.y + rect.height - outer - thickness, width: rect.width, height: thickness },
+        _ => Rect { x: rect.x + outer, y: rect.y, width: thickness, height: rect.height }
+    };
+    pixmap.fill_rect(&band, color);
+}
@@ -0,0 +1,206 @@
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use crate::display_list::build_display_list;
+use crate::dom_diff;
+use crate::html_document_parser::ParserOptions;
+use crate::png_renderer::render_as_png;
+use crate::serializer::escape_text;
+use crate::server::{read_request, write_response, Response};
+use crate::text::BuiltinFontMetrics;
+use crate::tokenizer::Tokenizer;
+use crate::url::Url;
+use crate::window::Viewport;
+
+// Serves the rendering of a local HTML file and renders it again each time the file changes, e.g. web_engine preview index.html
+// The page at / shows the file rendered as an image and as text, and reloads itself when a new rendering is ready
+// The file is parsed again when it changes, and the document that is shown is patched into the new one (see dom_diff::patch)
+// rather than replaced, so only the nodes that changed are styled and laid out again
+// Scripts are not run, the patched document would run them again each time it changes
+// TODO: The file is polled for changes, there is no file system notification
+
+pub struct PreviewOptions {
+    pub columns: usize,
+    // The width of the viewport and of the image in CSS pixels
+    pub width: u32,
+    // How often the file is checked for changes
+    pub interval: Duration,
+    // The file the text rendering is written to each time the file is rendered, so it can be followed without a browser
+    pub output: Option<PathBuf>,
+}
+
+impl Default for PreviewOptions {
+    fn default() -> Self {
+        return PreviewOptions { columns: 80, width: Viewport::default().width, interval: Duration::from_millis(250), output: None };
+    }
+}
+
+// The latest rendering of the file, the version goes up by one each time it is rendered
+#[derive(Default)]
+struct Rendering {
+    version: u64,
+    text: String,
+    png: Vec<u8>,
+    // Why the file could not be read the last time it changed, the rendering before it is still shown
+    error: Option<String>,
+    render_time: Duration,
+}
+
+// The rendering and a condition variable that is notified when there is a new one
+type SharedRendering = Arc<(Mutex<Rendering>, Condvar)>;
+
+// How long a request for the next version waits before it is answered with the current one, so the connection is not held forever
+const LONG_POLL_TIMEOUT: Duration = Duration::from_secs(25);
+
+// Renders the file, and then serves its renderings on listener while it is rendered again in the background whenever it changes
+pub fn preview(path: &Path, listener: TcpListener, options: PreviewOptions) -> std::io::Result<()> {
+    let rendering: SharedRendering = Arc::new((Mutex::new(Rendering::default()), Condvar::new()));
+    let watched_rendering = rendering.clone();
+    let path = path.to_path_buf();
+    // The document is not Send, so it lives on the thread that watches the file
+    thread::spawn(move || watch(path, options, watched_rendering));
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue
+        };
+        let rendering = rendering.clone();
+        thread::spawn(move || handle_connection(stream, &rendering));
+    }
+    return Ok(());
+}
+
+// The time the file was last modified and its size, which change when it is written
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    return Some((metadata.modified().ok()?, metadata.len()));
+}
+
+fn watch(path: PathBuf, options: PreviewOptions, rendering: SharedRendering) {
+    let parser_options = || ParserOptions { scripting_enabled: false, print_parse_errors: false, ..ParserOptions::default() };
+    let mut shown: Option<Tokenizer> = None;
+    // None before the file is first read, and Some(None) while it can not be
+    let mut last_stamp = None;
+    loop {
+        let stamp = Some(file_stamp(&path));
+        if stamp == last_stamp {
+            thread::sleep(options.interval);
+            continue;
+        }
+        last_stamp = stamp;
+
+        let start = Instant::now();
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                let (lock, condvar) = &*rendering;
+                let mut rendering = lock.lock().unwrap();
+                rendering.error = Some(format!("Could not read {}: {}", path.display(), error));
+                rendering.version += 1;
+                condvar.notify_all();
+                drop(rendering);
+                thread::sleep(options.interval);
+                continue;
+            }
+        };
+        let mut tokenizer = Tokenizer::from_bytes(bytes, parser_options());
+        tokenizer.set_document_url(Url::from_file_path(&path));
+        tokenizer.set_viewport(Viewport { width: options.width, ..Viewport::default() });
+        tokenizer.run();
+        match &shown {
+            Some(shown) => dom_diff::patch(&shown.document(), &tokenizer.document()),
+            None => shown = Some(tokenizer)
+        }
+
+        let shown = shown.as_ref().unwrap();
+        let text = shown.render_as_text(options.columns);
+        let metrics = BuiltinFontMetrics::default();
+        let png = render_as_png(&build_display_list(&shown.document(), options.width as f32, &metrics), &metrics);
+        let output_error = match &options.output {
+            Some(output) => std::fs::write(output, &text).err().map(|error| format!("Could not write {}: {}", output.display(), error)),
+            None => None
+        };
+
+        let (lock, condvar) = &*rendering;
+        let mut rendering = lock.lock().unwrap();
+        rendering.version += 1;
+        rendering.text = text;
+        rendering.png = png;
+        rendering.error = output_error;
+        rendering.render_time = start.elapsed();
+        condvar.notify_all();
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, rendering: &SharedRendering) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(30)));
+    let request = match read_request(&mut stream, 0, &[]) {
+        Ok(request) => request,
+        Err(response) => return write_response(&mut stream, &response)
+    };
+    if request.method != "GET" {
+        return write_response(&mut stream, &Response::error(405, "The preview is only read with GET requests"));
+    }
+
+    let (lock, condvar) = &**rendering;
+    let current = lock.lock().unwrap();
+    let response = match request.path.as_str() {
+        "/" => Response::new(200, "text/html; charset=utf-8", preview_page(&current).into_bytes()),
+        "/render.txt" => Response::new(200, "text/plain; charset=utf-8", current.text.clone().into_bytes()),
+        "/render.png" => Response::new(200, "image/png", current.png.clone()),
+        // Answered once there is a rendering newer than ?after=N, which is how the page knows to reload
+        "/version" => {
+            let after = request.query_value("after").and_then(|after| after.parse::<u64>().ok()).unwrap_or(0);
+            let (current, _) = condvar.wait_timeout_while(current, LONG_POLL_TIMEOUT, |rendering| rendering.version <= after).unwrap();
+            Response::new(200, "text/plain; charset=utf-8", current.version.to_string().into_bytes())
+        },
+        _ => Response::error(404, &format!("There is nothing at {}", request.path))
+    };
+    write_response(&mut stream, &response);
+}
+
+// The page that shows the rendering, its script asks for the next version and reloads the page when it is ready
+fn preview_page(rendering: &Rendering) -> String {
+    let error = match &rendering.error {
+        Some(error) => format!("<p class=\"error\">{}</p>\n", escape_text(error)),
+        None => String::new()
+    };
+    return format!(r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Preview</title>
+<style>
+body {{ font-family: sans-serif; margin: 1em; }}
+.error {{ color: #b00; }}
+img {{ border: 1px solid #ccc; display: block; margin-bottom: 1em; }}
+pre {{ border: 1px solid #ccc; padding: 0.5em; }}
+</style>
+</head>
+<body>
+<p>Version {version}, rendered in {render_time:?}</p>
+{error}<img src="/render.png?version={version}" alt="The rendering">
+<pre>{text}</pre>
+<script>
+async function waitForChange() {{
+    try {{
+        const response = await fetch("/version?after={version}");
+        if (Number(await response.text()) > {version}) {{
+            location.reload();
+            return;
+        }}
+    }} catch (error) {{
+        // The server is not running, it is asked again after a while
+        await new Promise(resolve => setTimeout(resolve, 1000));
+    }}
+    waitForChange();
+}}
+waitForChange();
+</script>
+</body>
+</html>
+"#, version = rendering.version, render_time = rendering.render_time, error = error, text = escape_text(&rendering.text));
+}
@@ -0,0 +1,736 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use crate::interpreter::{argument, completion_value, create_normal_completion, create_throw_completion, create_value_completion, return_if_abrupt, CompletionRecord, CompletionRecordType, Interpreter, JSObject, JSValue, PropertyKey, ReferenceRecordOrJsValue};
+
+// https://tc39.es/ecma262/#sec-promise-objects
+// TODO: Promise.all, Promise.allSettled, Promise.any and Promise.race are not implemented
+
+// https://tc39.es/ecma262/#sec-properties-of-promise-instances
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum PromiseState {
+    Pending,
+    Fulfilled,
+    Rejected,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum PromiseReactionType {
+    Fulfill,
+    Reject,
+}
+
+// https://tc39.es/ecma262/#sec-promisecapability-records
+#[derive(Clone)]
+pub(crate) struct PromiseCapability {
+    pub(crate) promise: Rc<RefCell<JSValue>>,
+    resolve: Rc<RefCell<JSValue>>,
+    reject: Rc<RefCell<JSValue>>,
+}
+
+// https://tc39.es/ecma262/#sec-promisereaction-records
+#[derive(Clone)]
+struct PromiseReaction {
+    // None when the reaction was added by an await or a host, which have no derived promise to settle
+    capability: Option<PromiseCapability>,
+    type_: PromiseReactionType,
+    // None stands for empty, the argument is passed through to the derived promise
+    handler: Option<Rc<RefCell<JSValue>>>,
+}
+
+// https://tc39.es/ecma262/#table-internal-slots-of-promise-instances
+pub(crate) struct PromiseData {
+    pub(crate) state: PromiseState,
+    pub(crate) result: Rc<RefCell<JSValue>>,
+    fulfill_reactions: Vec<PromiseReaction>,
+    reject_reactions: Vec<PromiseReaction>,
+    pub(crate) is_handled: bool,
+}
+
+// The reactions usually refer back to the promises that were derived from this one, so they are left out
+impl std::fmt::Debug for PromiseData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PromiseData({:?})", self.state)
+    }
+}
+
+// https://tc39.es/ecma262/#sec-properties-of-the-promise-constructor
+// https://tc39.es/ecma262/#sec-properties-of-the-promise-prototype-object
+pub(crate) fn install_promise(interpreter: &mut Interpreter) {
+    let mut promise_prototype = JSObject::new();
+    promise_prototype.extensible = true;
+    promise_prototype.prototype = interpreter.intrinsic("Object.prototype");
+    let promise_prototype = Rc::new(RefCell::new(promise_prototype));
+    Interpreter::define_builtin_function(&promise_prototype, "then", promise_prototype_then);
+    Interpreter::define_builtin_function(&promise_prototype, "catch", promise_prototype_catch);
+    Interpreter::define_builtin_function(&promise_prototype, "finally", promise_prototype_finally);
+
+    let promise_constructor = Interpreter::create_builtin_function(promise_call);
+    promise_constructor.borrow_mut().construct = Some(promise_construct);
+    promise_constructor.borrow_mut().prototype = interpreter.intrinsic("Function.prototype");
+    Interpreter::define_property_or_throw(&promise_constructor, "length", Rc::new(RefCell::new(JSValue::Numeric(1.0))), false, false, true);
+    Interpreter::set_function_name(&promise_constructor, "Promise");
+    Interpreter::define_builtin_function(&promise_constructor, "resolve", promise_resolve_static);
+    Interpreter::define_builtin_function(&promise_constructor, "reject", promise_reject_static);
+
+    // https://tc39.es/ecma262/#sec-promise.prototype
+    // This property has the attributes { [[Writable]]: false, [[Enumerable]]: false, [[Configurable]]: false }.
+    Interpreter::define_property_or_throw(&promise_constructor, "prototype", Rc::new(RefCell::new(JSValue::Object(Rc::clone(&promise_prototype)))), false, false, false);
+
+    // https://tc39.es/ecma262/#sec-promise.prototype.constructor
+    Interpreter::define_property_or_throw(&promise_prototype, "constructor", Rc::new(RefCell::new(JSValue::Object(Rc::clone(&promise_constructor)))), true, false, true);
+
+    interpreter.set_intrinsic("Promise", Rc::clone(&promise_constructor));
+    interpreter.set_intrinsic("Promise.prototype", promise_prototype);
+    interpreter.define_global_property("Promise", Rc::new(RefCell::new(JSValue::Object(promise_constructor))));
+}
+
+// The value of a normal or throw completion
+fn completion_record_value(completion_record: &CompletionRecord) -> Rc<RefCell<JSValue>> {
+    match &*completion_record.value {
+        ReferenceRecordOrJsValue::JSValue(value) => Rc::clone(value),
+        _ => unreachable!()
+    }
+}
+
+fn undefined() -> Rc<RefCell<JSValue>> {
+    return Rc::new(RefCell::new(JSValue::Undefined));
+}
+
+// https://tc39.es/ecma262/#sec-ispromise
+fn is_promise(value: &JSValue) -> Option<Rc<RefCell<JSObject>>> {
+    match value {
+        // 2. If x does not have a [[PromiseState]] internal slot, return false.
+        JSValue::Object(object) if object.borrow().promise_data.is_some() => Some(Rc::clone(object)),
+        // 1. If x is not an Object, return false.
+        _ => None
+    }
+}
+
+// https://tc39.es/ecma262/#sec-createresolvingfunctions
+fn create_resolving_functions(promise: &Rc<RefCell<JSObject>>) -> (Rc<RefCell<JSValue>>, Rc<RefCell<JSValue>>) {
+    // 1. Let alreadyResolved be the Record { [[Value]]: false }.
+    let already_resolved = Rc::new(Cell::new(false));
+
+    // 2. Let stepsResolve be the algorithm steps defined in Promise Resolve Functions.
+    // 4. Let resolve be CreateBuiltinFunction(stepsResolve, lengthResolve, "", « [[Promise]], [[AlreadyResolved]] »).
+    let resolve = {
+        let promise = Rc::clone(promise);
+        let already_resolved = Rc::clone(&already_resolved);
+        Interpreter::create_builtin_closure(move |interpreter, _this_value, arguments| {
+            return promise_resolve_function(interpreter, &promise, &already_resolved, argument(&arguments, 0));
+        })
+    };
+
+    // 7. Let stepsReject be the algorithm steps defined in Promise Reject Functions.
+    // 9. Let reject be CreateBuiltinFunction(stepsReject, lengthReject, "", « [[Promise]], [[AlreadyResolved]] »).
+    let reject = {
+        let promise = Rc::clone(promise);
+        Interpreter::create_builtin_closure(move |interpreter, _this_value, arguments| {
+            // https://tc39.es/ecma262/#sec-promise-reject-functions
+            // 5. If alreadyResolved.[[Value]] is true, return undefined.
+            if already_resolved.get() {
+                return create_value_completion(JSValue::Undefined);
+            }
+
+            // 6. Set alreadyResolved.[[Value]] to true.
+            already_resolved.set(true);
+
+            // 7. Perform RejectPromise(promise, reason).
+            reject_promise(interpreter, &promise, argument(&arguments, 0));
+
+            // 8. Return undefined.
+            return create_value_completion(JSValue::Undefined);
+        })
+    };
+
+    // 12. Return the Record { [[Resolve]]: resolve, [[Reject]]: reject }.
+    return (Rc::new(RefCell::new(JSValue::Object(resolve))), Rc::new(RefCell::new(JSValue::Object(reject))));
+}
+
+// https://tc39.es/ecma262/#sec-promise-resolve-functions
+fn promise_resolve_function(interpreter: &mut Interpreter, promise: &Rc<RefCell<JSObject>>, already_resolved: &Cell<bool>, resolution: Rc<RefCell<JSValue>>) -> CompletionRecord {
+    // 5. If alreadyResolved.[[Value]] is true, return undefined.
+    if already_resolved.get() {
+        return create_value_completion(JSValue::Undefined);
+    }
+
+    // 6. Set alreadyResolved.[[Value]] to true.
+    already_resolved.set(true);
+
+    let resolution_object = match &*resolution.borrow() {
+        JSValue::Object(resolution_object) => Some(Rc::clone(resolution_object)),
+        _ => None
+    };
+
+    let resolution_object = match resolution_object {
+        // 7. If SameValue(resolution, promise) is true, then
+        Some(resolution_object) if Rc::ptr_eq(&resolution_object, promise) => {
+            // a. Let selfResolutionError be a newly created TypeError object.
+            let self_resolution_error = completion_record_value(&interpreter.throw_type_error(String::from("Chaining cycle detected for promise #<Promise>")));
+
+            // b. Perform RejectPromise(promise, selfResolutionError).
+            reject_promise(interpreter, promise, self_resolution_error);
+
+            // c. Return undefined.
+            return create_value_completion(JSValue::Undefined);
+        },
+        Some(resolution_object) => resolution_object,
+        // 8. If resolution is not an Object, then
+        None => {
+            // a. Perform FulfillPromise(promise, resolution).
+            fulfill_promise(interpreter, promise, resolution);
+
+            // b. Return undefined.
+            return create_value_completion(JSValue::Undefined);
+        }
+    };
+
+    // 9. Let then be Completion(Get(resolution, "then")).
+    let then = match completion_value(interpreter.get(&resolution_object, &PropertyKey::String(String::from("then")), Rc::clone(&resolution))) {
+        Ok(then) => then,
+        // 10. If then is an abrupt completion, then
+        Err(completion_record) => {
+            // a. Perform RejectPromise(promise, then.[[Value]]).
+            reject_promise(interpreter, promise, completion_record_value(&completion_record));
+
+            // b. Return undefined.
+            return create_value_completion(JSValue::Undefined);
+        }
+    };
+
+    // 12. If IsCallable(thenAction) is false, then
+    if !Interpreter::is_callable(&then.borrow()) {
+        // a. Perform FulfillPromise(promise, resolution).
+        fulfill_promise(interpreter, promise, resolution);
+
+        // b. Return undefined.
+        return create_value_completion(JSValue::Undefined);
+    }
+
+    // 13. Let thenJobCallback be HostMakeJobCallback(thenAction).
+    // 14. Let job be NewPromiseResolveThenableJob(promise, resolution, thenJobCallback).
+    // 15. Perform HostEnqueuePromiseJob(job.[[Job]], job.[[Realm]]).
+    let promise_to_resolve = Rc::clone(promise);
+    host_enqueue_promise_job(interpreter, Box::new(move |interpreter: &mut Interpreter| {
+        // https://tc39.es/ecma262/#sec-newpromiseresolvethenablejob
+        // a. Let resolvingFunctions be CreateResolvingFunctions(promiseToResolve).
+        let (resolve, reject) = create_resolving_functions(&promise_to_resolve);
+
+        // b. Let thenCallResult be Completion(HostCallJobCallback(then, thenable, « resolvingFunctions.[[Resolve]], resolvingFunctions.[[Reject]] »)).
+        let then_call_result = interpreter.call(then, resolution, vec![resolve, Rc::clone(&reject)]);
+
+        // c. If thenCallResult is an abrupt completion, then
+        //        i. Return ? Call(resolvingFunctions.[[Reject]], undefined, « thenCallResult.[[Value]] »).
+        if let CompletionRecordType::Throw = then_call_result.type_ {
+            let reason = completion_record_value(&then_call_result);
+            let completion_record = interpreter_call(interpreter, reject, vec![reason]);
+            report_if_abrupt(interpreter, completion_record);
+        }
+    }));
+
+    // 16. Return undefined.
+    return create_value_completion(JSValue::Undefined);
+}
+
+fn interpreter_call(interpreter: &mut Interpreter, function: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return interpreter.call(function, undefined(), arguments);
+}
+
+// Jobs have nowhere to return an abrupt completion to, so it is reported like an uncaught exception
+fn report_if_abrupt(interpreter: &mut Interpreter, completion_record: CompletionRecord) {
+    if let CompletionRecordType::Throw = completion_record.type_ {
+        interpreter.report_exception(&completion_record_value(&completion_record));
+    }
+}
+
+// https://tc39.es/ecma262/#sec-fulfillpromise
+fn fulfill_promise(interpreter: &mut Interpreter, promise: &Rc<RefCell<JSObject>>, value: Rc<RefCell<JSValue>>) {
+    let reactions = {
+        let mut promise = promise.borrow_mut();
+        let promise_data = promise.promise_data.as_mut().unwrap();
+
+        // 1. Assert: The value of promise.[[PromiseState]] is pending.
+        // 2. Let reactions be promise.[[PromiseFulfillReactions]].
+        let reactions = std::mem::take(&mut promise_data.fulfill_reactions);
+
+        // 3. Set promise.[[PromiseResult]] to value.
+        promise_data.result = Rc::clone(&value);
+
+        // 4. Set promise.[[PromiseFulfillReactions]] to undefined.
+        // 5. Set promise.[[PromiseRejectReactions]] to undefined.
+        promise_data.reject_reactions.clear();
+
+        // 6. Set promise.[[PromiseState]] to fulfilled.
+        promise_data.state = PromiseState::Fulfilled;
+        reactions
+    };
+
+    // 7. Perform TriggerPromiseReactions(reactions, value).
+    trigger_promise_reactions(interpreter, reactions, value);
+}
+
+// https://tc39.es/ecma262/#sec-rejectpromise
+fn reject_promise(interpreter: &mut Interpreter, promise: &Rc<RefCell<JSObject>>, reason: Rc<RefCell<JSValue>>) {
+    let (reactions, is_handled) = {
+        let mut promise = promise.borrow_mut();
+        let promise_data = promise.promise_data.as_mut().unwrap();
+
+        // 1. Assert: The value of promise.[[PromiseState]] is pending.
+        // 2. Let reactions be promise.[[PromiseRejectReactions]].
+        let reactions = std::mem::take(&mut promise_data.reject_reactions);
+
+        // 3. Set promise.[[PromiseResult]] to reason.
+        promise_data.result = Rc::clone(&reason);
+
+        // 4. Set promise.[[PromiseFulfillReactions]] to undefined.
+        // 5. Set promise.[[PromiseRejectReactions]] to undefined.
+        promise_data.fulfill_reactions.clear();
+
+        // 6. Set promise.[[PromiseState]] to rejected.
+        promise_data.state = PromiseState::Rejected;
+        (reactions, promise_data.is_handled)
+    };
+
+    // 7. If promise.[[PromiseIsHandled]] is false, perform HostPromiseRejectionTracker(promise, "reject").
+    if !is_handled {
+        interpreter.event_loop.track_rejected_promise(promise);
+    }
+
+    // 8. Perform TriggerPromiseReactions(reactions, reason).
+    trigger_promise_reactions(interpreter, reactions, reason);
+}
+
+// https://tc39.es/ecma262/#sec-triggerpromisereactions
+fn trigger_promise_reactions(interpreter: &mut Interpreter, reactions: Vec<PromiseReaction>, argument: Rc<RefCell<JSValue>>) {
+    // 1. For each element reaction of reactions, do
+    for reaction in reactions {
+        // a. Let job be NewPromiseReactionJob(reaction, argument).
+        // b. Perform HostEnqueuePromiseJob(job.[[Job]], job.[[Realm]]).
+        host_enqueue_promise_job(interpreter, new_promise_reaction_job(reaction, Rc::clone(&argument)));
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/webappapis.html#hostenqueuepromisejob
+fn host_enqueue_promise_job(interpreter: &mut Interpreter, job: Box<dyn FnOnce(&mut Interpreter)>) {
+    // 2. Queue a microtask to perform the following steps:
+    interpreter.event_loop.queue_microtask(job);
+}
+
+// https://tc39.es/ecma262/#sec-newpromisereactionjob
+fn new_promise_reaction_job(reaction: PromiseReaction, argument: Rc<RefCell<JSValue>>) -> Box<dyn FnOnce(&mut Interpreter)> {
+    // 1. Let job be a new Job Abstract Closure with no parameters that captures reaction and argument and performs the following steps when called:
+    return Box::new(move |interpreter: &mut Interpreter| {
+        let handler_result = match reaction.handler {
+            // e. If handler is empty, then
+            None => {
+                match reaction.type_ {
+                    // i. If type is fulfill, then let handlerResult be NormalCompletion(argument).
+                    PromiseReactionType::Fulfill => create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(argument))),
+                    // ii. Else, let handlerResult be ThrowCompletion(argument).
+                    PromiseReactionType::Reject => create_throw_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(argument)))
+                }
+            },
+            // f. Else, let handlerResult be Completion(HostCallJobCallback(handler, undefined, « argument »)).
+            Some(handler) => interpreter_call(interpreter, handler, vec![argument])
+        };
+
+        // g. If promiseCapability is undefined, then
+        let capability = match reaction.capability {
+            Some(capability) => capability,
+            None => {
+                report_if_abrupt(interpreter, handler_result);
+                return;
+            }
+        };
+
+        // h. If handlerResult is an abrupt completion, then
+        //        i. Return ? Call(promiseCapability.[[Reject]], undefined, « handlerResult.[[Value]] »).
+        // i. Else,
+        //        i. Return ? Call(promiseCapability.[[Resolve]], undefined, « handlerResult.[[Value]] »).
+        let value = completion_record_value(&handler_result);
+        let completion_record = match handler_result.type_ {
+            CompletionRecordType::Throw => interpreter_call(interpreter, capability.reject, vec![value]),
+            _ => interpreter_call(interpreter, capability.resolve, vec![value])
+        };
+        report_if_abrupt(interpreter, completion_record);
+    });
+}
+
+// https://tc39.es/ecma262/#sec-newpromisecapability
+fn new_promise_capability(interpreter: &mut Interpreter, constructor: &Rc<RefCell<JSValue>>) -> Result<PromiseCapability, CompletionRecord> {
+    // 1. If IsConstructor(C) is false, throw a TypeError exception.
+    let constructor_object = match &*constructor.borrow() {
+        JSValue::Object(object) if Interpreter::is_constructor(&object.borrow()) => Rc::clone(object),
+        _ => return Err(interpreter.throw_type_error(String::from("Promise resolve or reject function is not callable")))
+    };
+
+    // 3. Let resolvingFunctions be the Record { [[Resolve]]: undefined, [[Reject]]: undefined }.
+    let resolving_functions = Rc::new(RefCell::new((undefined(), undefined())));
+
+    // 4. Let executorClosure be a new Abstract Closure with parameters (resolve, reject) that captures resolvingFunctions and performs the following steps when called:
+    // 5. Let executor be CreateBuiltinFunction(executorClosure, 2, "", « »).
+    let executor = {
+        let resolving_functions = Rc::clone(&resolving_functions);
+        Interpreter::create_builtin_closure(move |interpreter, _this_value, arguments| {
+            let mut resolving_functions = resolving_functions.borrow_mut();
+
+            // a. If resolvingFunctions.[[Resolve]] is not undefined, throw a TypeError exception.
+            // b. If resolvingFunctions.[[Reject]] is not undefined, throw a TypeError exception.
+            let is_undefined = |value: &Rc<RefCell<JSValue>>| matches!(*value.borrow(), JSValue::Undefined);
+            if !is_undefined(&resolving_functions.0) || !is_undefined(&resolving_functions.1) {
+                return interpreter.throw_type_error(String::from("Promise executor has already been invoked with non-undefined arguments"));
+            }
+
+            // c. Set resolvingFunctions.[[Resolve]] to resolve.
+            // d. Set resolvingFunctions.[[Reject]] to reject.
+            *resolving_functions = (argument(&arguments, 0), argument(&arguments, 1));
+
+            // e. Return undefined.
+            return create_value_completion(JSValue::Undefined);
+        })
+    };
+
+    // 6. Let promise be ? Construct(C, « executor »).
+    let promise = completion_value(interpreter.construct(&constructor_object, vec![Rc::new(RefCell::new(JSValue::Object(executor)))], None))?;
+
+    // 7. If IsCallable(resolvingFunctions.[[Resolve]]) is false, throw a TypeError exception.
+    // 8. If IsCallable(resolvingFunctions.[[Reject]]) is false, throw a TypeError exception.
+    let (resolve, reject) = resolving_functions.borrow().clone();
+    if !Interpreter::is_callable(&resolve.borrow()) || !Interpreter::is_callable(&reject.borrow()) {
+        return Err(interpreter.throw_type_error(String::from("Promise resolve or reject function is not callable")));
+    }
+
+    // 9. Return the PromiseCapability Record { [[Promise]]: promise, [[Resolve]]: resolvingFunctions.[[Resolve]], [[Reject]]: resolvingFunctions.[[Reject]] }.
+    return Ok(PromiseCapability { promise, resolve, reject });
+}
+
+// https://tc39.es/ecma262/#sec-promise-resolve
+fn promise_resolve(interpreter: &mut Interpreter, constructor: &Rc<RefCell<JSValue>>, x: Rc<RefCell<JSValue>>) -> Result<Rc<RefCell<JSValue>>, CompletionRecord> {
+    // 1. If IsPromise(x) is true, then
+    let x_promise = is_promise(&x.borrow());
+    if let Some(x_promise) = x_promise {
+        // a. Let xConstructor be ? Get(x, "constructor").
+        let x_constructor = completion_value(interpreter.get(&x_promise, &PropertyKey::String(String::from("constructor")), Rc::clone(&x)))?;
+
+        // b. If SameValue(xConstructor, C) is true, return x.
+        if Interpreter::is_strictly_equal(&x_constructor.borrow(), &constructor.borrow()) {
+            return Ok(x);
+        }
+    }
+
+    // 2. Let promiseCapability be ? NewPromiseCapability(C).
+    let promise_capability = new_promise_capability(interpreter, constructor)?;
+
+    // 3. Perform ? Call(promiseCapability.[[Resolve]], undefined, « x »).
+    completion_value(interpreter_call(interpreter, promise_capability.resolve, vec![x]))?;
+
+    // 4. Return promiseCapability.[[Promise]].
+    return Ok(promise_capability.promise);
+}
+
+// https://tc39.es/ecma262/#sec-speciesconstructor
+// TODO: There are no well-known symbols yet, so C[@@species] is taken to be the inherited getter that returns C
+fn species_constructor(interpreter: &mut Interpreter, object: &Rc<RefCell<JSObject>>, default_constructor: &str) -> Result<Rc<RefCell<JSValue>>, CompletionRecord> {
+    // 2. Let C be ? Get(O, "constructor").
+    let receiver = Rc::new(RefCell::new(JSValue::Object(Rc::clone(object))));
+    let constructor = completion_value(interpreter.get(object, &PropertyKey::String(String::from("constructor")), receiver))?;
+
+    let is_constructor = match &*constructor.borrow() {
+        // 3. If C is undefined, return defaultConstructor.
+        JSValue::Undefined => return Ok(Rc::new(RefCell::new(JSValue::Object(interpreter.intrinsic(default_constructor).unwrap())))),
+        JSValue::Object(constructor) => Interpreter::is_constructor(&constructor.borrow()),
+        // 4. If C is not an Object, throw a TypeError exception.
+        _ => false
+    };
+
+    // 7. If IsConstructor(S) is true, return S.
+    // 8. Throw a TypeError exception.
+    if !is_constructor {
+        return Err(interpreter.throw_type_error(String::from("object.constructor is not a constructor")));
+    }
+    return Ok(constructor);
+}
+
+// https://tc39.es/ecma262/#sec-performpromisethen
+fn perform_promise_then(interpreter: &mut Interpreter, promise: &Rc<RefCell<JSObject>>, on_fulfilled: Rc<RefCell<JSValue>>, on_rejected: Rc<RefCell<JSValue>>, result_capability: Option<PromiseCapability>) -> Rc<RefCell<JSValue>> {
+    // 3. If IsCallable(onFulfilled) is false, then
+    //        a. Let onFulfilledJobCallback be empty.
+    let on_fulfilled = if Interpreter::is_callable(&on_fulfilled.borrow()) { Some(on_fulfilled) } else { None };
+
+    // 5. If IsCallable(onRejected) is false, then
+    //        a. Let onRejectedJobCallback be empty.
+    let on_rejected = if Interpreter::is_callable(&on_rejected.borrow()) { Some(on_rejected) } else { None };
+
+    // 7. Let fulfillReaction be the PromiseReaction Record { [[Capability]]: resultCapability, [[Type]]: fulfill, [[Handler]]: onFulfilledJobCallback }.
+    let fulfill_reaction = PromiseReaction { capability: result_capability.clone(), type_: PromiseReactionType::Fulfill, handler: on_fulfilled };
+
+    // 8. Let rejectReaction be the PromiseReaction Record { [[Capability]]: resultCapability, [[Type]]: reject, [[Handler]]: onRejectedJobCallback }.
+    let reject_reaction = PromiseReaction { capability: result_capability.clone(), type_: PromiseReactionType::Reject, handler: on_rejected };
+
+    let (state, result, is_handled) = {
+        let promise = promise.borrow();
+        let promise_data = promise.promise_data.as_ref().unwrap();
+        (promise_data.state, Rc::clone(&promise_data.result), promise_data.is_handled)
+    };
+
+    match state {
+        // 9. If promise.[[PromiseState]] is pending, then
+        PromiseState::Pending => {
+            // a. Append fulfillReaction to promise.[[PromiseFulfillReactions]].
+            // b. Append rejectReaction to promise.[[PromiseRejectReactions]].
+            let mut promise = promise.borrow_mut();
+            let promise_data = promise.promise_data.as_mut().unwrap();
+            promise_data.fulfill_reactions.push(fulfill_reaction);
+            promise_data.reject_reactions.push(reject_reaction);
+        },
+        // 10. Else if promise.[[PromiseState]] is fulfilled, then
+        PromiseState::Fulfilled => {
+            // a. Let value be promise.[[PromiseResult]].
+            // b. Let fulfillJob be NewPromiseReactionJob(fulfillReaction, value).
+            // c. Perform HostEnqueuePromiseJob(fulfillJob.[[Job]], fulfillJob.[[Realm]]).
+            host_enqueue_promise_job(interpreter, new_promise_reaction_job(fulfill_reaction, result));
+        },
+        // 11. Else,
+        PromiseState::Rejected => {
+            // b. Let reason be promise.[[PromiseResult]].
+            // c. If promise.[[PromiseIsHandled]] is false, perform HostPromiseRejectionTracker(promise, "handle").
+            if !is_handled {
+                interpreter.event_loop.untrack_rejected_promise(promise);
+            }
+
+            // d. Let rejectJob be NewPromiseReactionJob(rejectReaction, reason).
+            // e. Perform HostEnqueuePromiseJob(rejectJob.[[Job]], rejectJob.[[Realm]]).
+            host_enqueue_promise_job(interpreter, new_promise_reaction_job(reject_reaction, result));
+        }
+    }
+
+    // 12. Set promise.[[PromiseIsHandled]] to true.
+    promise.borrow_mut().promise_data.as_mut().unwrap().is_handled = true;
+
+    // 13. If resultCapability is undefined, then
+    //        a. Return undefined.
+    // 14. Else,
+    //        a. Return resultCapability.[[Promise]].
+    match result_capability {
+        Some(result_capability) => result_capability.promise,
+        None => undefined()
+    }
+}
+
+// https://tc39.es/ecma262/#sec-invoke
+fn invoke(interpreter: &mut Interpreter, value: &Rc<RefCell<JSValue>>, property_key: &str, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 2. Let func be ? GetV(V, P).
+    // FIXME: GetV does not convert primitives with ToObject yet
+    let object = match &*value.borrow() {
+        JSValue::Object(object) => Rc::clone(object),
+        _ => return interpreter.throw_type_error(format!("Cannot read properties of a primitive ('{}')", property_key))
+    };
+    let function = return_if_abrupt!(completion_value(interpreter.get(&object, &PropertyKey::String(property_key.to_string()), Rc::clone(value))));
+
+    // 3. Return ? Call(func, V, argumentsList).
+    return interpreter.call(function, Rc::clone(value), arguments);
+}
+
+// https://tc39.es/ecma262/#sec-promise-executor
+// Calling the Promise constructor without new throws
+fn promise_call(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. If NewTarget is undefined, throw a TypeError exception.
+    return interpreter.throw_type_error(String::from("Promise constructor cannot be invoked without 'new'"));
+}
+
+// https://tc39.es/ecma262/#sec-promise-executor
+fn promise_construct(interpreter: &mut Interpreter, arguments: Vec<Rc<RefCell<JSValue>>>, new_target: Rc<RefCell<JSObject>>) -> CompletionRecord {
+    // 2. If IsCallable(executor) is false, throw a TypeError exception.
+    let executor = argument(&arguments, 0);
+    if !Interpreter::is_callable(&executor.borrow()) {
+        return interpreter.throw_type_error(String::from("Promise resolver is not a function"));
+    }
+
+    // 3. Let promise be ? OrdinaryCreateFromConstructor(NewTarget, "%Promise.prototype%", « [[PromiseState]], [[PromiseResult]], [[PromiseFulfillReactions]], [[PromiseRejectReactions]], [[PromiseIsHandled]] »).
+    let promise_value = return_if_abrupt!(completion_value(interpreter.ordinary_create_from_constructor(&new_target, "Promise.prototype")));
+    let promise = match &*promise_value.borrow() {
+        JSValue::Object(promise) => Rc::clone(promise),
+        _ => unreachable!()
+    };
+
+    // 4. Set promise.[[PromiseState]] to pending.
+    // 5. Set promise.[[PromiseFulfillReactions]] to a new empty List.
+    // 6. Set promise.[[PromiseRejectReactions]] to a new empty List.
+    // 7. Set promise.[[PromiseIsHandled]] to false.
+    promise.borrow_mut().promise_data = Some(PromiseData { state: PromiseState::Pending, result: undefined(), fulfill_reactions: Vec::new(), reject_reactions: Vec::new(), is_handled: false });
+
+    // 8. Let resolvingFunctions be CreateResolvingFunctions(promise).
+    let (resolve, reject) = create_resolving_functions(&promise);
+
+    // 9. Let completion be Completion(Call(executor, undefined, « resolvingFunctions.[[Resolve]], resolvingFunctions.[[Reject]] »)).
+    let completion = interpreter_call(interpreter, executor, vec![resolve, Rc::clone(&reject)]);
+
+    // 10. If completion is an abrupt completion, then
+    //        a. Perform ? Call(resolvingFunctions.[[Reject]], undefined, « completion.[[Value]] »).
+    if let CompletionRecordType::Throw = completion.type_ {
+        return_if_abrupt!(completion_value(interpreter_call(interpreter, reject, vec![completion_record_value(&completion)])));
+    }
+
+    // 11. Return promise.
+    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(promise_value)));
+}
+
+// https://tc39.es/ecma262/#sec-promise.resolve
+fn promise_resolve_static(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let C be the this value.
+    // 2. If C is not an Object, throw a TypeError exception.
+    if !matches!(*this_value.borrow(), JSValue::Object(_)) {
+        return interpreter.throw_type_error(String::from("PromiseResolve called on non-object"));
+    }
+
+    // 3. Return ? PromiseResolve(C, x).
+    let promise = return_if_abrupt!(promise_resolve(interpreter, &this_value, argument(&arguments, 0)));
+    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(promise)));
+}
+
+// https://tc39.es/ecma262/#sec-promise.reject
+fn promise_reject_static(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let C be the this value.
+    // 2. Let promiseCapability be ? NewPromiseCapability(C).
+    let promise_capability = return_if_abrupt!(new_promise_capability(interpreter, &this_value));
+
+    // 3. Perform ? Call(promiseCapability.[[Reject]], undefined, « r »).
+    return_if_abrupt!(completion_value(interpreter_call(interpreter, promise_capability.reject, vec![argument(&arguments, 0)])));
+
+    // 4. Return promiseCapability.[[Promise]].
+    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(promise_capability.promise)));
+}
+
+// https://tc39.es/ecma262/#sec-promise.prototype.then
+fn promise_prototype_then(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let promise be the this value.
+    // 2. If IsPromise(promise) is false, throw a TypeError exception.
+    let promise = match is_promise(&this_value.borrow()) {
+        Some(promise) => promise,
+        None => return interpreter.throw_type_error(String::from("Method Promise.prototype.then called on incompatible receiver"))
+    };
+
+    // 3. Let C be ? SpeciesConstructor(promise, %Promise%).
+    let constructor = return_if_abrupt!(species_constructor(interpreter, &promise, "Promise"));
+
+    // 4. Let resultCapability be ? NewPromiseCapability(C).
+    let result_capability = return_if_abrupt!(new_promise_capability(interpreter, &constructor));
+
+    // 5. Return PerformPromiseThen(promise, onFulfilled, onRejected, resultCapability).
+    let result = perform_promise_then(interpreter, &promise, argument(&arguments, 0), argument(&arguments, 1), Some(result_capability));
+    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(result)));
+}
+
+// https://tc39.es/ecma262/#sec-promise.prototype.catch
+fn promise_prototype_catch(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let promise be the this value.
+    // 2. Return ? Invoke(promise, "then", « undefined, onRejected »).
+    return invoke(interpreter, &this_value, "then", vec![undefined(), argument(&arguments, 0)]);
+}
+
+// https://tc39.es/ecma262/#sec-promise.prototype.finally
+fn promise_prototype_finally(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let promise be the this value.
+    // 2. If promise is not an Object, throw a TypeError exception.
+    let promise = match &*this_value.borrow() {
+        JSValue::Object(promise) => Rc::clone(promise),
+        _ => return interpreter.throw_type_error(String::from("Promise.prototype.finally called on a non-object"))
+    };
+
+    // 3. Let C be ? SpeciesConstructor(promise, %Promise%).
+    let constructor = return_if_abrupt!(species_constructor(interpreter, &promise, "Promise"));
+
+    let on_finally = argument(&arguments, 0);
+    let (then_finally, catch_finally) = if !Interpreter::is_callable(&on_finally.borrow()) {
+        // 5. If IsCallable(onFinally) is false, then
+        //        a. Let thenFinally be onFinally.
+        //        b. Let catchFinally be onFinally.
+        (Rc::clone(&on_finally), Rc::clone(&on_finally))
+    } else {
+        // 6. Else,
+        //        a. Let thenFinallyClosure be a new Abstract Closure with parameters (value) that captures onFinally and C and performs the following steps when called:
+        let then_finally = {
+            let on_finally = Rc::clone(&on_finally);
+            let constructor = Rc::clone(&constructor);
+            Interpreter::create_builtin_closure(move |interpreter, _this_value, arguments| {
+                let value = argument(&arguments, 0);
+
+                // i. Let result be ? Call(onFinally, undefined).
+                let result = return_if_abrupt!(completion_value(interpreter_call(interpreter, Rc::clone(&on_finally), Vec::new())));
+
+                // ii. Let p be ? PromiseResolve(C, result).
+                let promise = return_if_abrupt!(promise_resolve(interpreter, &constructor, result));
+
+                // iii. Let returnValue be a new Abstract Closure with no parameters that captures value and performs the following steps when called:
+                //          1. Return value.
+                // iv. Let valueThunk be CreateBuiltinFunction(returnValue, 0, "", « »).
+                let value_thunk = Interpreter::create_builtin_closure(move |_interpreter, _this_value, _arguments| {
+                    return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::clone(&value))));
+                });
+
+                // v. Return ? Invoke(p, "then", « valueThunk »).
+                return invoke(interpreter, &promise, "then", vec![Rc::new(RefCell::new(JSValue::Object(value_thunk)))]);
+            })
+        };
+
+        //     c. Let catchFinallyClosure be a new Abstract Closure with parameters (reason) that captures onFinally and C and performs the following steps when called:
+        let catch_finally = {
+            let on_finally = Rc::clone(&on_finally);
+            let constructor = Rc::clone(&constructor);
+            Interpreter::create_builtin_closure(move |interpreter, _this_value, arguments| {
+                let reason = argument(&arguments, 0);
+
+                // i. Let result be ? Call(onFinally, undefined).
+                let result = return_if_abrupt!(completion_value(interpreter_call(interpreter, Rc::clone(&on_finally), Vec::new())));
+
+                // ii. Let p be ? PromiseResolve(C, result).
+                let promise = return_if_abrupt!(promise_resolve(interpreter, &constructor, result));
+
+                // iii. Let throwReason be a new Abstract Closure with no parameters that captures reason and performs the following steps when called:
+                //          1. Return ThrowCompletion(reason).
+                // iv. Let thrower be CreateBuiltinFunction(throwReason, 0, "", « »).
+                let thrower = Interpreter::create_builtin_closure(move |_interpreter, _this_value, _arguments| {
+                    return create_throw_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(Rc::clone(&reason))));
+                });
+
+                // v. Return ? Invoke(p, "then", « thrower »).
+                return invoke(interpreter, &promise, "then", vec![Rc::new(RefCell::new(JSValue::Object(thrower)))]);
+            })
+        };
+
+        (Rc::new(RefCell::new(JSValue::Object(then_finally))), Rc::new(RefCell::new(JSValue::Object(catch_finally))))
+    };
+
+    // 7. Return ? Invoke(promise, "then", « thenFinally, catchFinally »).
+    return invoke(interpreter, &this_value, "then", vec![then_finally, catch_finally]);
+}
+
+// https://webidl.spec.whatwg.org/#a-new-promise
+// Platform APIs settle the promises they return with resolve_a_promise and reject_a_promise once their work is done
+pub(crate) fn a_new_promise(interpreter: &mut Interpreter) -> PromiseCapability {
+    // 2. Let constructor be realm.[[Intrinsics]].[[%Promise%]].
+    let constructor = Rc::new(RefCell::new(JSValue::Object(interpreter.intrinsic("Promise").unwrap())));
+
+    // 3. Return ? NewPromiseCapability(constructor).
+    // The intrinsic %Promise% always provides callable resolving functions
+    match new_promise_capability(interpreter, &constructor) {
+        Ok(promise_capability) => promise_capability,
+        Err(_) => unreachable!()
+    }
+}
+
+// https://webidl.spec.whatwg.org/#resolve
+pub(crate) fn resolve_a_promise(interpreter: &mut Interpreter, promise_capability: &PromiseCapability, value: Rc<RefCell<JSValue>>) -> Rc<RefCell<JSValue>> {
+    // 3. Perform ! Call(p.[[Resolve]], undefined, « value »).
+    let completion_record = interpreter_call(interpreter, Rc::clone(&promise_capability.resolve), vec![value]);
+    report_if_abrupt(interpreter, completion_record);
+    return Rc::clone(&promise_capability.promise);
+}
+
+// https://webidl.spec.whatwg.org/#reject
+pub(crate) fn reject_a_promise(interpreter: &mut Interpreter, promise_capability: &PromiseCapability, reason: Rc<RefCell<JSValue>>) -> Rc<RefCell<JSValue>> {
+    // 1. Perform ! Call(p.[[Reject]], undefined, « reason »).
+    let completion_record = interpreter_call(interpreter, Rc::clone(&promise_capability.reject), vec![reason]);
+    report_if_abrupt(interpreter, completion_record);
+    return Rc::clone(&promise_capability.promise);
+}
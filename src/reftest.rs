@@ -0,0 +1,165 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use crate::display_list::build_display_list;
+use crate::html_document_parser::ParserOptions;
+use crate::png_renderer::{rasterize, Pixmap};
+use crate::text::BuiltinFontMetrics;
+use crate::tokenizer::Tokenizer;
+use crate::url::Url;
+use crate::window::Viewport;
+
+// https://web-platform-tests.org/writing-tests/reftests.html
+// A reftest renders a test file and a reference file that reaches the same rendering another way, and compares the two renderings
+// The reftests are listed in a manifest, a line for each of them, e.g.
+//   # Runs of spaces collapse into one
+//   == collapse.html collapse-ref.html
+//   != pre.html pre-notref.html
+//   fuzzy(2,300) == wrap.html wrap-ref.html
+// == passes when the renderings are the same and != when they differ
+// The paths are relative to the manifest, and everything after a # is a comment
+// A document is rendered into pixels by the PNG renderer and the pixels are compared, see png_renderer.rs for what it draws
+// TODO: The PNG renderer draws each character as a block, so renderings that differ only in which characters of the same width are drawn (e.g. the number of a list item) are the same
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Equal,
+    NotEqual,
+}
+
+// https://web-platform-tests.org/writing-tests/reftests.html#fuzzy-matching
+// How far apart two renderings may be and still be the same, e.g. fuzzy(2,300) lets up to 300 pixels differ by up to 2 in each of their channels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Fuzzy {
+    // The most a channel of a pixel may differ by
+    pub max_difference: u8,
+    // The number of pixels that may differ
+    pub total_pixels: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Reftest {
+    pub relation: Relation,
+    pub test: PathBuf,
+    pub reference: PathBuf,
+    pub fuzzy: Fuzzy,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail(Comparison),
+    // A file could not be read
+    Error(String),
+}
+
+// How two renderings differ, a pixel differs when one of its channels does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    pub differing_pixels: usize,
+    // The most any channel of a pixel differs by
+    pub max_difference: u8,
+    // The first pixel that differs in rows from the top, its x and y and its red, green, blue and alpha in the test's and in the reference's rendering
+    pub first_difference: Option<(usize, usize, [u8; 4], [u8; 4])>,
+}
+
+impl Comparison {
+    // Two renderings of different sizes are compared as if the smaller one went on with white pixels, the color of the canvas
+    pub fn new(test: &Pixmap, reference: &Pixmap) -> Comparison {
+        let pixel = |pixmap: &Pixmap, x: usize, y: usize| -> [u8; 4] {
+            if x >= pixmap.width || y >= pixmap.height {
+                return [0xFF; 4];
+            }
+            let index = (y * pixmap.width + x) * 4;
+            return [pixmap.data[index], pixmap.data[index + 1], pixmap.data[index + 2], pixmap.data[index + 3]];
+        };
+
+        let mut comparison = Comparison { differing_pixels: 0, max_difference: 0, first_difference: None };
+        for y in 0..test.height.max(reference.height) {
+            for x in 0..test.width.max(reference.width) {
+                let (test_pixel, reference_pixel) = (pixel(test, x, y), pixel(reference, x, y));
+                let difference = test_pixel.iter().zip(reference_pixel.iter()).map(|(test_channel, reference_channel)| test_channel.abs_diff(*reference_channel)).max().unwrap_or(0);
+                if difference == 0 {
+                    continue;
+                }
+                comparison.differing_pixels += 1;
+                comparison.max_difference = comparison.max_difference.max(difference);
+                if comparison.first_difference.is_none() {
+                    comparison.first_difference = Some((x, y, test_pixel, reference_pixel));
+                }
+            }
+        }
+        return comparison;
+    }
+
+    // Whether the renderings are the same within fuzzy
+    pub fn is_within(&self, fuzzy: &Fuzzy) -> bool {
+        return self.differing_pixels == 0 || (self.max_difference <= fuzzy.max_difference && self.differing_pixels <= fuzzy.total_pixels);
+    }
+}
+
+// The reftests of the manifest at path
+pub fn parse_manifest(path: &Path) -> Result<Vec<Reftest>, String> {
+    let manifest = fs::read_to_string(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+    let directory = path.parent().unwrap_or(Path::new(""));
+
+    let mut reftests = Vec::new();
+    for (index, line) in manifest.lines().enumerate() {
+        let mut fields = line.split('#').next().unwrap_or_default().split_ascii_whitespace().peekable();
+        if fields.peek().is_none() {
+            continue;
+        }
+        let line_error = |message: &str| format!("{}:{}: {}", path.display(), index + 1, message);
+
+        let mut fuzzy = Fuzzy::default();
+        if let Some(arguments) = fields.peek().and_then(|field| field.strip_prefix("fuzzy(")).and_then(|field| field.strip_suffix(')')) {
+            fuzzy = match arguments.split_once(',').map(|(max_difference, total_pixels)| (max_difference.parse::<u8>(), total_pixels.parse::<usize>())) {
+                Some((Ok(max_difference), Ok(total_pixels))) => Fuzzy { max_difference, total_pixels },
+                _ => return Err(line_error("fuzzy needs the most a channel may differ by and a number of pixels, e.g. fuzzy(2,300)"))
+            };
+            fields.next();
+        }
+        let relation = match fields.next() {
+            Some("==") => Relation::Equal,
+            Some("!=") => Relation::NotEqual,
+            _ => return Err(line_error("expected == or !="))
+        };
+        let (test, reference) = match (fields.next(), fields.next(), fields.next()) {
+            (Some(test), Some(reference), None) => (directory.join(test), directory.join(reference)),
+            _ => return Err(line_error("expected a test file and a reference file"))
+        };
+        reftests.push(Reftest { relation, test, reference, fuzzy });
+    }
+    return Ok(reftests);
+}
+
+// Renders the HTML file at path in a viewport width CSS pixels wide, as a document with the given URL
+// Its scripts run, as a test may build its content with them, and its event loop runs for at most a second so a test with a repeating timer still finishes
+pub fn render(path: &Path, url: Url, width: u32) -> Result<Pixmap, String> {
+    let html = fs::read(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+    let mut tokenizer = Tokenizer::from_bytes(html, ParserOptions { print_parse_errors: false, ..ParserOptions::default() });
+    tokenizer.set_document_url(url);
+    tokenizer.set_viewport(Viewport { width, ..Viewport::default() });
+    tokenizer.run();
+    tokenizer.run_event_loop(Some(Instant::now() + Duration::from_secs(1)));
+
+    let metrics = BuiltinFontMetrics::default();
+    return Ok(rasterize(&build_display_list(&tokenizer.document(), width as f32, &metrics), &metrics));
+}
+
+pub fn run(reftest: &Reftest, width: u32) -> Outcome {
+    // The reference is rendered with the URL of the test, so the URLs in both resolve the same (e.g. of a stylesheet next to the test)
+    let url = Url::from_file_path(&reftest.test);
+    let (test, reference) = match (render(&reftest.test, url.clone(), width), render(&reftest.reference, url, width)) {
+        (Ok(test), Ok(reference)) => (test, reference),
+        (Err(message), _) | (_, Err(message)) => return Outcome::Error(message)
+    };
+
+    let comparison = Comparison::new(&test, &reference);
+    let same = comparison.is_within(&reftest.fuzzy);
+    match reftest.relation {
+        Relation::Equal if same => return Outcome::Pass,
+        Relation::NotEqual if !same => return Outcome::Pass,
+        _ => return Outcome::Fail(comparison)
+    }
+}
@@ -0,0 +1,1033 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::interpreter::{argument, completion_value, create_normal_completion, create_value_completion, return_if_abrupt, to_number, CompletionRecord, Interpreter, JSObject, JSValue, PropertyKey, ReferenceRecordOrJsValue};
+use crate::regexp_matcher::{Captures, RegExpMatcher};
+use crate::string::get_substitution;
+
+// https://tc39.es/ecma262/#sec-regexp-regular-expression-objects
+// The RegExp constructor and prototype, and the algorithms String.prototype.match, replace, search and split use when given a regular expression
+// The pattern is compiled by regexp_matcher when the RegExp is created and matched against the UTF-16 code units of a string
+// TODO: There are no well-known symbols yet, so the algorithms are called for RegExp objects rather than through Symbol.match and friends,
+// and RegExpExec always uses the built-in exec rather than looking up an overridden exec method
+
+// https://tc39.es/ecma262/#table-internal-slots-of-regexp-instances
+pub(crate) struct RegExpData {
+    // [[OriginalSource]]
+    pub(crate) original_source: String,
+    // [[OriginalFlags]]
+    pub(crate) original_flags: String,
+    // [[RegExpMatcher]]
+    matcher: RegExpMatcher,
+}
+
+// The compiled pattern is left out, the source and flags describe it
+impl std::fmt::Debug for RegExpData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "/{}/{}", self.original_source, self.original_flags)
+    }
+}
+
+// https://tc39.es/ecma262/#sec-get-regexp.prototype.flags
+// The flags in the order the flags getter lists them, each with the property that tells whether it is set
+const FLAGS: [(char, &str); 8] = [('d', "hasIndices"), ('g', "global"), ('i', "ignoreCase"), ('m', "multiline"), ('s', "dotAll"), ('u', "unicode"), ('v', "unicodeSets"), ('y', "sticky")];
+
+// https://tc39.es/ecma262/#sec-regexphasflag
+// Each flag getter returns whether the flag is set, and undefined for RegExp.prototype itself
+macro_rules! regexp_flag_getter {
+    ($getter:ident, $flag:literal, $name:literal) => {
+        fn $getter(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+            return regexp_has_flag(interpreter, &this_value, $flag, $name);
+        }
+    };
+}
+
+regexp_flag_getter!(regexp_prototype_has_indices, 'd', "hasIndices");
+regexp_flag_getter!(regexp_prototype_global, 'g', "global");
+regexp_flag_getter!(regexp_prototype_ignore_case, 'i', "ignoreCase");
+regexp_flag_getter!(regexp_prototype_multiline, 'm', "multiline");
+regexp_flag_getter!(regexp_prototype_dot_all, 's', "dotAll");
+regexp_flag_getter!(regexp_prototype_unicode, 'u', "unicode");
+regexp_flag_getter!(regexp_prototype_unicode_sets, 'v', "unicodeSets");
+regexp_flag_getter!(regexp_prototype_sticky, 'y', "sticky");
+
+// https://tc39.es/ecma262/#sec-properties-of-the-regexp-constructor
+// https://tc39.es/ecma262/#sec-properties-of-the-regexp-prototype-object
+pub(crate) fn install_regexp(interpreter: &mut Interpreter) {
+    // The RegExp prototype object is an ordinary object, it is not a RegExp instance and does not have a [[RegExpMatcher]] internal slot
+    let regexp_prototype = interpreter.create_ordinary_object();
+    Interpreter::define_builtin_function(&regexp_prototype, "exec", regexp_prototype_exec);
+    Interpreter::define_builtin_function(&regexp_prototype, "test", regexp_prototype_test);
+    Interpreter::define_builtin_function(&regexp_prototype, "toString", regexp_prototype_to_string);
+    Interpreter::define_accessor_property(&regexp_prototype, "flags", regexp_prototype_flags, None);
+    Interpreter::define_accessor_property(&regexp_prototype, "source", regexp_prototype_source, None);
+    Interpreter::define_accessor_property(&regexp_prototype, "hasIndices", regexp_prototype_has_indices, None);
+    Interpreter::define_accessor_property(&regexp_prototype, "global", regexp_prototype_global, None);
+    Interpreter::define_accessor_property(&regexp_prototype, "ignoreCase", regexp_prototype_ignore_case, None);
+    Interpreter::define_accessor_property(&regexp_prototype, "multiline", regexp_prototype_multiline, None);
+    Interpreter::define_accessor_property(&regexp_prototype, "dotAll", regexp_prototype_dot_all, None);
+    Interpreter::define_accessor_property(&regexp_prototype, "unicode", regexp_prototype_unicode, None);
+    Interpreter::define_accessor_property(&regexp_prototype, "unicodeSets", regexp_prototype_unicode_sets, None);
+    Interpreter::define_accessor_property(&regexp_prototype, "sticky", regexp_prototype_sticky, None);
+
+    let regexp_constructor = Interpreter::create_builtin_function(regexp_call);
+    regexp_constructor.borrow_mut().construct = Some(regexp_construct);
+    regexp_constructor.borrow_mut().prototype = interpreter.intrinsic("Function.prototype");
+    Interpreter::define_property_or_throw(&regexp_constructor, "length", Rc::new(RefCell::new(JSValue::Numeric(2.0))), false, false, true);
+    Interpreter::set_function_name(&regexp_constructor, "RegExp");
+
+    // https://tc39.es/ecma262/#sec-regexp.prototype
+    // This property has the attributes { [[Writable]]: false, [[Enumerable]]: false, [[Configurable]]: false }.
+    Interpreter::define_property_or_throw(&regexp_constructor, "prototype", Rc::new(RefCell::new(JSValue::Object(Rc::clone(&regexp_prototype)))), false, false, false);
+
+    // https://tc39.es/ecma262/#sec-regexp.prototype.constructor
+    Interpreter::define_property_or_throw(&regexp_prototype, "constructor", Rc::new(RefCell::new(JSValue::Object(Rc::clone(&regexp_constructor)))), true, false, true);
+
+    interpreter.set_intrinsic("RegExp", Rc::clone(&regexp_constructor));
+    interpreter.set_intrinsic("RegExp.prototype", regexp_prototype);
+    interpreter.define_global_property("RegExp", Rc::new(RefCell::new(JSValue::Object(regexp_constructor))));
+}
+
+// https://tc39.es/ecma262/#sec-regexp-pattern-flags
+fn regexp_call(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return construct_regexp(interpreter, &arguments, None);
+}
+
+fn regexp_construct(interpreter: &mut Interpreter, arguments: Vec<Rc<RefCell<JSValue>>>, new_target: Rc<RefCell<JSObject>>) -> CompletionRecord {
+    return construct_regexp(interpreter, &arguments, Some(new_target));
+}
+
+// https://tc39.es/ecma262/#sec-regexp-pattern-flags
+fn construct_regexp(interpreter: &mut Interpreter, arguments: &[Rc<RefCell<JSValue>>], new_target: Option<Rc<RefCell<JSObject>>>) -> CompletionRecord {
+    let pattern = argument(arguments, 0);
+    let flags = argument(arguments, 1);
+
+    // 1. Let patternIsRegExp be ? IsRegExp(pattern).
+    let pattern_regexp = is_regexp(&pattern.borrow());
+
+    // 2. If NewTarget is undefined, then
+    let new_target = match new_target {
+        Some(new_target) => new_target,
+        None => {
+            // a. Let newTarget be the active function object.
+            let new_target = interpreter.intrinsic("RegExp").unwrap();
+
+            // b. If patternIsRegExp is true and flags is undefined, then
+            if let Some(pattern_regexp) = &pattern_regexp {
+                if matches!(*flags.borrow(), JSValue::Undefined) {
+                    // i. Let patternConstructor be ? Get(pattern, "constructor").
+                    let pattern_constructor = return_if_abrupt!(get(interpreter, pattern_regexp, "constructor"));
+
+                    // ii. If SameValue(newTarget, patternConstructor) is true, return pattern.
+                    if matches!(&*pattern_constructor.borrow(), JSValue::Object(constructor) if Rc::ptr_eq(constructor, &new_target)) {
+                        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(pattern)));
+                    }
+                }
+            }
+            new_target
+        }
+    };
+
+    // 4. If pattern is an Object and pattern has a [[RegExpMatcher]] internal slot, then
+    //        a. Let P be pattern.[[OriginalSource]].
+    //        b. If flags is undefined, let F be pattern.[[OriginalFlags]].
+    //        c. Else, let F be flags.
+    // 6. Else,
+    //        a. Let P be pattern.
+    //        b. Let F be flags.
+    let (pattern, flags) = match pattern_regexp.and_then(|pattern_regexp| pattern_regexp.borrow().regexp_data.clone()) {
+        Some(regexp_data) => {
+            let original_source = Rc::new(RefCell::new(JSValue::String(regexp_data.original_source.clone())));
+            if matches!(*flags.borrow(), JSValue::Undefined) {
+                (original_source, Rc::new(RefCell::new(JSValue::String(regexp_data.original_flags.clone()))))
+            } else {
+                (original_source, flags)
+            }
+        },
+        None => (pattern, flags)
+    };
+
+    // 7. Let O be ? RegExpAlloc(newTarget).
+    let object = return_if_abrupt!(regexp_alloc(interpreter, &new_target));
+
+    // 8. Return ? RegExpInitialize(O, P, F).
+    return regexp_initialize(interpreter, object, pattern, flags);
+}
+
+// https://tc39.es/ecma262/#sec-regexpalloc
+fn regexp_alloc(interpreter: &mut Interpreter, new_target: &Rc<RefCell<JSObject>>) -> Result<Rc<RefCell<JSObject>>, CompletionRecord> {
+    // 1. Let obj be ? OrdinaryCreateFromConstructor(newTarget, "%RegExp.prototype%", « [[OriginalSource]], [[OriginalFlags]], [[RegExpRecord]], [[RegExpMatcher]] »).
+    let object = completion_value(interpreter.ordinary_create_from_constructor(new_target, "RegExp.prototype"))?;
+    let object = match &*object.borrow() {
+        JSValue::Object(object) => Rc::clone(object),
+        _ => unreachable!()
+    };
+
+    // 2. Perform ! DefinePropertyOrThrow(obj, "lastIndex", PropertyDescriptor { [[Writable]]: true, [[Enumerable]]: false, [[Configurable]]: false }).
+    Interpreter::define_property_or_throw(&object, "lastIndex", Rc::new(RefCell::new(JSValue::Undefined)), true, false, false);
+
+    // 3. Return obj.
+    return Ok(object);
+}
+
+// https://tc39.es/ecma262/#sec-regexpinitialize
+fn regexp_initialize(interpreter: &mut Interpreter, object: Rc<RefCell<JSObject>>, pattern: Rc<RefCell<JSValue>>, flags: Rc<RefCell<JSValue>>) -> CompletionRecord {
+    // 1. If pattern is undefined, let P be the empty String.
+    // 2. Else, let P be ? ToString(pattern).
+    let pattern = if matches!(*pattern.borrow(), JSValue::Undefined) { String::new() } else { return_if_abrupt!(to_string(interpreter, Rc::clone(&pattern))) };
+
+    // 3. If flags is undefined, let F be the empty String.
+    // 4. Else, let F be ? ToString(flags).
+    let flags = if matches!(*flags.borrow(), JSValue::Undefined) { String::new() } else { return_if_abrupt!(to_string(interpreter, Rc::clone(&flags))) };
+
+    // 5. If F contains any code unit other than "d", "g", "i", "m", "s", "u", "v", or "y", or if F contains any code unit more than once, throw a SyntaxError exception.
+    // 6-9. Let i, m, s, u and v be whether F contains "i", "m", "s", "u" and "v".
+    // 10. If u is true and v is true, throw a SyntaxError exception.
+    let invalid_flags = flags.chars().enumerate().any(|(index, flag)| !"dgimsuvy".contains(flag) || flags[..index].contains(flag)) || (flags.contains('u') && flags.contains('v'));
+    if invalid_flags {
+        return interpreter.throw_error("SyntaxError", format!("Invalid flags supplied to RegExp constructor '{}'", flags));
+    }
+
+    // 13. Let parseResult be ParsePattern(patternText, u, v).
+    // 14. If parseResult is a non-empty List of SyntaxError objects, throw a SyntaxError exception.
+    let matcher = match RegExpMatcher::new(&pattern, flags.contains('i'), flags.contains('m'), flags.contains('s'), flags.contains('u') || flags.contains('v')) {
+        Ok(matcher) => matcher,
+        Err(message) => return interpreter.throw_error("SyntaxError", format!("Invalid regular expression: /{}/{}: {}", pattern, flags, message))
+    };
+
+    // 16. Set obj.[[OriginalSource]] to P.
+    // 17. Set obj.[[OriginalFlags]] to F.
+    // 20. Set obj.[[RegExpMatcher]] to CompilePattern of parseResult with argument rer.
+    object.borrow_mut().regexp_data = Some(Rc::new(RegExpData { original_source: pattern, original_flags: flags, matcher }));
+
+    // 21. Perform ? Set(obj, "lastIndex", +0𝔽, true).
+    return_if_abrupt!(set(interpreter, &object, "lastIndex", JSValue::Numeric(0.0)));
+
+    // 22. Return obj.
+    return create_value_completion(JSValue::Object(object));
+}
+
+// https://tc39.es/ecma262/#sec-regexpcreate
+// Creates a RegExp from a regular expression literal, or from the pattern String.prototype.match and search are given
+pub(crate) fn regexp_create(interpreter: &mut Interpreter, pattern: Rc<RefCell<JSValue>>, flags: Rc<RefCell<JSValue>>) -> CompletionRecord {
+    // 1. Let obj be ! RegExpAlloc(%RegExp%).
+    let regexp_constructor = interpreter.intrinsic("RegExp").unwrap();
+    let object = return_if_abrupt!(regexp_alloc(interpreter, &regexp_constructor));
+
+    // 2. Return ? RegExpInitialize(obj, P, F).
+    return regexp_initialize(interpreter, object, pattern, flags);
+}
+
+// https://tc39.es/ecma262/#sec-isregexp
+// The object when value is a RegExp
+// TODO: An object with a truthy Symbol.match property should be treated as a regular expression too
+pub(crate) fn is_regexp(value: &JSValue) -> Option<Rc<RefCell<JSObject>>> {
+    match value {
+        JSValue::Object(object) if object.borrow().regexp_data.is_some() => Some(Rc::clone(object)),
+        _ => None
+    }
+}
+
+// https://tc39.es/ecma262/#sec-regexpbuiltinexec
+// The captures of the next match of regexp in string, updating lastIndex for a global or sticky regular expression
+fn regexp_builtin_exec(interpreter: &mut Interpreter, regexp: &Rc<RefCell<JSObject>>, string: &[u16]) -> Result<Option<Captures>, CompletionRecord> {
+    let regexp_data = regexp.borrow().regexp_data.clone().unwrap();
+
+    // 1. Let length be the length of S.
+    let length = string.len();
+
+    // 2. Let lastIndex be ℝ(? ToLength(! Get(R, "lastIndex"))).
+    let last_index = get(interpreter, regexp, "lastIndex")?;
+    let mut last_index = to_length(interpreter, last_index)?;
+
+    // 3. Let flags be R.[[OriginalFlags]].
+    // 4. If flags contains "g", let global be true; else let global be false.
+    // 5. If flags contains "y", let sticky be true; else let sticky be false.
+    let global = regexp_data.original_flags.contains('g');
+    let sticky = regexp_data.original_flags.contains('y');
+    let full_unicode = regexp_data.original_flags.contains('u') || regexp_data.original_flags.contains('v');
+
+    // 7. If global is false and sticky is false, set lastIndex to 0.
+    if !global && !sticky {
+        last_index = 0;
+    }
+
+    // 11. Let matchSucceeded be false.
+    // 13. Repeat, while matchSucceeded is false,
+    let captures = loop {
+        // a. If lastIndex > length, then
+        if last_index > length {
+            // i. If global is true or sticky is true, then
+            //        1. Perform ? Set(R, "lastIndex", +0𝔽, true).
+            if global || sticky {
+                set(interpreter, regexp, "lastIndex", JSValue::Numeric(0.0))?;
+            }
+
+            // ii. Return null.
+            return Ok(None);
+        }
+
+        // b. Let inputIndex be the index into input of the character that was obtained from element lastIndex of S.
+        // c. Let r be matcher(input, inputIndex).
+        let captures = match regexp_data.matcher.match_at(string, last_index) {
+            Ok(captures) => captures,
+            Err(_) => return Err(interpreter.throw_error("RangeError", String::from("Maximum call stack size exceeded")))
+        };
+
+        match captures {
+            // d. If r is failure, then
+            None => {
+                // i. If sticky is true, then
+                if sticky {
+                    // 1. Perform ? Set(R, "lastIndex", +0𝔽, true).
+                    set(interpreter, regexp, "lastIndex", JSValue::Numeric(0.0))?;
+
+                    // 2. Return null.
+                    return Ok(None);
+                }
+
+                // ii. Set lastIndex to AdvanceStringIndex(S, lastIndex, fullUnicode).
+                last_index = advance_string_index(string, last_index, full_unicode);
+            },
+            // e. Else,
+            //        ii. Set matchSucceeded to true.
+            Some(captures) => break captures
+        }
+    };
+
+    // 14. Let e be r.[[EndIndex]].
+    let end = captures[0].unwrap().1;
+
+    // 16. If global is true or sticky is true, then
+    //        a. Perform ? Set(R, "lastIndex", 𝔽(e), true).
+    if global || sticky {
+        set(interpreter, regexp, "lastIndex", JSValue::Numeric(end as f64))?;
+    }
+    return Ok(Some(captures));
+}
+
+// https://tc39.es/ecma262/#sec-regexpbuiltinexec
+// The match array exec returns, the whole match and each capture, with the index and input of the match and the named groups
+fn create_match_array(interpreter: &mut Interpreter, regexp: &Rc<RefCell<JSObject>>, string: &[u16], captures: &Captures) -> Rc<RefCell<JSObject>> {
+    let regexp_data = regexp.borrow().regexp_data.clone().unwrap();
+    let (start, _) = captures[0].unwrap();
+
+    // 19. Let n be the number of elements in r.[[Captures]].
+    // 20. Let A be ! ArrayCreate(n + 1).
+    let array = interpreter.array_create(captures.len() as u32);
+
+    // 22. Perform ! CreateDataPropertyOrThrow(A, "index", 𝔽(lastIndex)).
+    Interpreter::create_data_property(&array, "index", Rc::new(RefCell::new(JSValue::Numeric(start as f64))));
+
+    // 23. Perform ! CreateDataPropertyOrThrow(A, "input", S).
+    Interpreter::create_data_property(&array, "input", Rc::new(RefCell::new(JSValue::String(String::from_utf16_lossy(string)))));
+
+    // 28. Let matchedSubstr be GetMatchString(S, match).
+    // 29. Perform ! CreateDataPropertyOrThrow(A, "0", matchedSubstr).
+    // 33. For each integer i such that 1 ≤ i ≤ n, in ascending order, do
+    //        d. Perform ! CreateDataPropertyOrThrow(A, ! ToString(𝔽(i)), capturedValue).
+    for (index, capture) in captures.iter().enumerate() {
+        Interpreter::create_data_property(&array, &index.to_string(), capture_value(string, capture));
+    }
+
+    // 30. If R contains any GroupName, then
+    //        a. Let groups be OrdinaryObjectCreate(null).
+    // 31. Else,
+    //        a. Let groups be undefined.
+    // 32. Perform ! CreateDataPropertyOrThrow(A, "groups", groups).
+    let groups = create_groups_object(interpreter, &regexp_data.matcher, string, captures);
+    Interpreter::create_data_property(&array, "groups", Rc::clone(&groups));
+
+    // 34. If hasIndices is true, then
+    //        a. Let indicesArray be MakeMatchIndicesIndexPairArray(S, indices, groupNames, hasGroups).
+    //        b. Perform ! CreateDataPropertyOrThrow(A, "indices", indicesArray).
+    if regexp_data.original_flags.contains('d') {
+        let indices = make_match_indices_index_pair_array(interpreter, &regexp_data.matcher, captures);
+        Interpreter::create_data_property(&array, "indices", Rc::new(RefCell::new(JSValue::Object(indices))));
+    }
+
+    // 35. Return A.
+    return array;
+}
+
+// A captured substring, undefined for a group that did not take part in the match
+fn capture_value(string: &[u16], capture: &Option<(usize, usize)>) -> Rc<RefCell<JSValue>> {
+    return Rc::new(RefCell::new(match capture {
+        Some((start, end)) => JSValue::String(String::from_utf16_lossy(&string[*start..*end])),
+        None => JSValue::Undefined
+    }));
+}
+
+// https://tc39.es/ecma262/#sec-regexpbuiltinexec
+// The groups object of a match, with the capture of each named group, undefined when the pattern has no named groups
+fn create_groups_object(interpreter: &mut Interpreter, matcher: &RegExpMatcher, string: &[u16], captures: &Captures) -> Rc<RefCell<JSValue>> {
+    if matcher.group_names.iter().all(Option::is_none) {
+        return Rc::new(RefCell::new(JSValue::Undefined));
+    }
+    let groups = interpreter.create_ordinary_object();
+    groups.borrow_mut().prototype = None;
+    for (index, name) in matcher.group_names.iter().enumerate() {
+        // 33. e. If the ith capture of R was defined with a GroupName, then
+        //            i. Let s be the CapturingGroupName of that GroupName.
+        //            iv. Perform ! CreateDataPropertyOrThrow(groups, s, capturedValue).
+        if let Some(name) = name {
+            Interpreter::create_data_property(&groups, name, capture_value(string, &captures[index + 1]));
+        }
+    }
+    return Rc::new(RefCell::new(JSValue::Object(groups)));
+}
+
+// https://tc39.es/ecma262/#sec-makematchindicesindexpairarray
+fn make_match_indices_index_pair_array(interpreter: &mut Interpreter, matcher: &RegExpMatcher, captures: &Captures) -> Rc<RefCell<JSObject>> {
+    // 4. Let A be ! ArrayCreate(n).
+    let array = interpreter.array_create(captures.len() as u32);
+
+    // 6-8. If hasGroups is true, then
+    //        a. Let groups be OrdinaryObjectCreate(null).
+    //      Perform ! CreateDataPropertyOrThrow(A, "groups", groups).
+    let groups = if matcher.group_names.iter().any(Option::is_some) {
+        let groups = interpreter.create_ordinary_object();
+        groups.borrow_mut().prototype = None;
+        Some(groups)
+    } else {
+        None
+    };
+    let groups_value = groups.clone().map_or(JSValue::Undefined, JSValue::Object);
+    Interpreter::create_data_property(&array, "groups", Rc::new(RefCell::new(groups_value)));
+
+    // 9. For each integer i such that 0 ≤ i < n, in ascending order, do
+    for (index, capture) in captures.iter().enumerate() {
+        // a. Let matchIndices be indices[i].
+        // b. If matchIndices is not undefined, then
+        //        i. Let matchIndexPair be GetMatchIndexPair(S, matchIndices).
+        // c. Else,
+        //        i. Let matchIndexPair be undefined.
+        let match_index_pair = Rc::new(RefCell::new(match capture {
+            Some((start, end)) => {
+                let pair = vec![Rc::new(RefCell::new(JSValue::Numeric(*start as f64))), Rc::new(RefCell::new(JSValue::Numeric(*end as f64)))];
+                JSValue::Object(interpreter.create_array_from_list(pair))
+            },
+            None => JSValue::Undefined
+        }));
+
+        // d. Perform ! CreateDataPropertyOrThrow(A, ! ToString(𝔽(i)), matchIndexPair).
+        Interpreter::create_data_property(&array, &index.to_string(), Rc::clone(&match_index_pair));
+
+        // e. If i > 0 and groupNames[i - 1] is not undefined, then
+        //        ii. Perform ! CreateDataPropertyOrThrow(groups, groupNames[i - 1], matchIndexPair).
+        if let (Some(groups), Some(Some(name))) = (&groups, index.checked_sub(1).and_then(|group| matcher.group_names.get(group))) {
+            Interpreter::create_data_property(groups, name, match_index_pair);
+        }
+    }
+
+    // 10. Return A.
+    return array;
+}
+
+// https://tc39.es/ecma262/#sec-advancestringindex
+fn advance_string_index(string: &[u16], index: usize, unicode: bool) -> usize {
+    // 2. If unicode is false, return index + 1.
+    // 3. Let length be the length of S.
+    // 4. If index + 1 ≥ length, return index + 1.
+    if !unicode || index + 1 >= string.len() {
+        return index + 1;
+    }
+
+    // 5. Let cp be CodePointAt(S, index).
+    // 6. Return index + cp.[[CodeUnitCount]].
+    if (0xD800..=0xDBFF).contains(&string[index]) && (0xDC00..=0xDFFF).contains(&string[index + 1]) {
+        return index + 2;
+    }
+    return index + 1;
+}
+
+// The this value of a method that requires a [[RegExpMatcher]] internal slot
+fn this_regexp_value(interpreter: &mut Interpreter, this_value: &Rc<RefCell<JSValue>>, method: &str) -> Result<Rc<RefCell<JSObject>>, CompletionRecord> {
+    match is_regexp(&this_value.borrow()) {
+        Some(regexp) => Ok(regexp),
+        None => Err(interpreter.throw_type_error(format!("RegExp.prototype.{} called on incompatible receiver", method)))
+    }
+}
+
+// https://tc39.es/ecma262/#sec-regexp.prototype.exec
+fn regexp_prototype_exec(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let R be the this value.
+    // 2. Perform ? RequireInternalSlot(R, [[RegExpMatcher]]).
+    let regexp = return_if_abrupt!(this_regexp_value(interpreter, &this_value, "exec"));
+
+    // 3. Let S be ? ToString(string).
+    let string = return_if_abrupt!(to_code_units(interpreter, argument(&arguments, 0)));
+
+    // 4. Return ? RegExpBuiltinExec(R, S).
+    return match return_if_abrupt!(regexp_builtin_exec(interpreter, &regexp, &string)) {
+        Some(captures) => create_value_completion(JSValue::Object(create_match_array(interpreter, &regexp, &string, &captures))),
+        None => create_value_completion(JSValue::Null)
+    };
+}
+
+// https://tc39.es/ecma262/#sec-regexp.prototype.test
+fn regexp_prototype_test(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let R be the this value.
+    // 2. If R is not an Object, throw a TypeError exception.
+    let regexp = return_if_abrupt!(this_regexp_value(interpreter, &this_value, "test"));
+
+    // 3. Let string be ? ToString(S).
+    let string = return_if_abrupt!(to_code_units(interpreter, argument(&arguments, 0)));
+
+    // 4. Let match be ? RegExpExec(R, string).
+    let captures = return_if_abrupt!(regexp_builtin_exec(interpreter, &regexp, &string));
+
+    // 5. If match is not null, return true; else return false.
+    return create_value_completion(JSValue::Boolean(captures.is_some()));
+}
+
+// https://tc39.es/ecma262/#sec-regexp.prototype.tostring
+fn regexp_prototype_to_string(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let R be the this value.
+    // 2. If R is not an Object, throw a TypeError exception.
+    let regexp = match &*this_value.borrow() {
+        JSValue::Object(regexp) => Rc::clone(regexp),
+        _ => return interpreter.throw_type_error(String::from("RegExp.prototype.toString called on incompatible receiver"))
+    };
+
+    // 3. Let pattern be ? ToString(? Get(R, "source")).
+    let pattern = return_if_abrupt!(get(interpreter, &regexp, "source"));
+    let pattern = return_if_abrupt!(to_string(interpreter, pattern));
+
+    // 4. Let flags be ? ToString(? Get(R, "flags")).
+    let flags = return_if_abrupt!(get(interpreter, &regexp, "flags"));
+    let flags = return_if_abrupt!(to_string(interpreter, flags));
+
+    // 5. Let result be the string-concatenation of "/", pattern, "/", and flags.
+    // 6. Return result.
+    return create_value_completion(JSValue::String(format!("/{}/{}", pattern, flags)));
+}
+
+// https://tc39.es/ecma262/#sec-get-regexp.prototype.flags
+fn regexp_prototype_flags(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let R be the this value.
+    // 2. If R is not an Object, throw a TypeError exception.
+    let regexp = match &*this_value.borrow() {
+        JSValue::Object(regexp) => Rc::clone(regexp),
+        _ => return interpreter.throw_type_error(String::from("RegExp.prototype.flags getter called on non-object"))
+    };
+
+    // 3. Let codeUnits be a new empty List.
+    let mut code_units = String::new();
+
+    // 4-19. For each flag, let it be ToBoolean(? Get(R, name)), if it is true, append the flag's code unit to codeUnits.
+    for (flag, name) in FLAGS {
+        let value = return_if_abrupt!(get(interpreter, &regexp, name));
+        if matches!(*Interpreter::to_boolean(value).borrow(), JSValue::Boolean(true)) {
+            code_units.push(flag);
+        }
+    }
+
+    // 20. Return the String value whose code units are the elements of the List codeUnits.
+    return create_value_completion(JSValue::String(code_units));
+}
+
+// https://tc39.es/ecma262/#sec-get-regexp.prototype.source
+fn regexp_prototype_source(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let R be the this value.
+    // 2. If R is not an Object, throw a TypeError exception.
+    // 3. If R does not have an [[OriginalSource]] internal slot, then
+    //        a. If SameValue(R, %RegExp.prototype%) is true, return "(?:)".
+    //        b. Otherwise, throw a TypeError exception.
+    let regexp_data = match &*this_value.borrow() {
+        JSValue::Object(regexp) => regexp.borrow().regexp_data.clone().ok_or_else(|| interpreter.intrinsic("RegExp.prototype").is_some_and(|prototype| Rc::ptr_eq(&prototype, regexp))),
+        _ => Err(false)
+    };
+    let regexp_data = match regexp_data {
+        Ok(regexp_data) => regexp_data,
+        Err(true) => return create_value_completion(JSValue::String(String::from("(?:)"))),
+        Err(false) => return interpreter.throw_type_error(String::from("RegExp.prototype.source getter called on incompatible receiver"))
+    };
+
+    // 4. Assert: R has an [[OriginalFlags]] internal slot.
+    // 5. Let src be R.[[OriginalSource]].
+    // 6. Let flags be R.[[OriginalFlags]].
+    // 7. Return EscapeRegExpPattern(src, flags).
+    return create_value_completion(JSValue::String(escape_regexp_pattern(&regexp_data.original_source)));
+}
+
+// https://tc39.es/ecma262/#sec-escaperegexppattern
+// The source of a pattern as it would be written in a regular expression literal, a / is escaped and the empty pattern is "(?:)"
+pub(crate) fn escape_regexp_pattern(source: &str) -> String {
+    if source.is_empty() {
+        return String::from("(?:)");
+    }
+    let mut escaped = String::new();
+    let mut in_class = false;
+    let mut characters = source.chars();
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' => {
+                escaped.push('\\');
+                if let Some(next) = characters.next() {
+                    escaped.push(next);
+                }
+                continue;
+            },
+            '/' if !in_class => escaped.push_str("\\/"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\u{2028}' => escaped.push_str("\\u2028"),
+            '\u{2029}' => escaped.push_str("\\u2029"),
+            _ => escaped.push(character)
+        }
+        match character {
+            '[' => in_class = true,
+            ']' => in_class = false,
+            _ => ()
+        }
+    }
+    return escaped;
+}
+
+// https://tc39.es/ecma262/#sec-regexphasflag
+fn regexp_has_flag(interpreter: &mut Interpreter, this_value: &Rc<RefCell<JSValue>>, flag: char, name: &str) -> CompletionRecord {
+    // 1. If R is not an Object, throw a TypeError exception.
+    // 2. If R does not have an [[OriginalFlags]] internal slot, then
+    //        a. If SameValue(R, %RegExp.prototype%) is true, return undefined.
+    //        b. Otherwise, throw a TypeError exception.
+    let regexp_data = match &*this_value.borrow() {
+        JSValue::Object(regexp) => regexp.borrow().regexp_data.clone().ok_or_else(|| interpreter.intrinsic("RegExp.prototype").is_some_and(|prototype| Rc::ptr_eq(&prototype, regexp))),
+        _ => Err(false)
+    };
+    return match regexp_data {
+        // 3. Let flags be R.[[OriginalFlags]].
+        // 4. If flags contains codeUnit, return true.
+        // 5. Return false.
+        Ok(regexp_data) => create_value_completion(JSValue::Boolean(regexp_data.original_flags.contains(flag))),
+        Err(true) => create_value_completion(JSValue::Undefined),
+        Err(false) => interpreter.throw_type_error(format!("RegExp.prototype.{} getter called on incompatible receiver", name))
+    };
+}
+
+// https://tc39.es/ecma262/#sec-regexp.prototype-%symbol.match%
+pub(crate) fn regexp_match(interpreter: &mut Interpreter, regexp: &Rc<RefCell<JSObject>>, string: &[u16]) -> CompletionRecord {
+    // 3. Let S be ? ToString(string).
+    // 4. Let flags be ? ToString(? Get(rx, "flags")).
+    let flags = return_if_abrupt!(get(interpreter, regexp, "flags"));
+    let flags = return_if_abrupt!(to_string(interpreter, flags));
+
+    // 5. If flags does not contain "g", then
+    //        a. Return ? RegExpExec(rx, S).
+    if !flags.contains('g') {
+        return match return_if_abrupt!(regexp_builtin_exec(interpreter, regexp, string)) {
+            Some(captures) => create_value_completion(JSValue::Object(create_match_array(interpreter, regexp, string, &captures))),
+            None => create_value_completion(JSValue::Null)
+        };
+    }
+
+    // 6. Else,
+    //        a. If flags contains "u" or flags contains "v", let fullUnicode be true. Otherwise, let fullUnicode be false.
+    let full_unicode = flags.contains('u') || flags.contains('v');
+
+    //        b. Perform ? Set(rx, "lastIndex", +0𝔽, true).
+    return_if_abrupt!(set(interpreter, regexp, "lastIndex", JSValue::Numeric(0.0)));
+
+    //        c. Let A be ! ArrayCreate(0).
+    //        d. Let n be 0.
+    let mut matches = Vec::new();
+
+    //        e. Repeat,
+    loop {
+        // i. Let result be ? RegExpExec(rx, S).
+        let captures = match return_if_abrupt!(regexp_builtin_exec(interpreter, regexp, string)) {
+            Some(captures) => captures,
+            // ii. If result is null, then
+            //         1. If n = 0, return null.
+            //         2. Return A.
+            None if matches.is_empty() => return create_value_completion(JSValue::Null),
+            None => return create_value_completion(JSValue::Object(interpreter.create_array_from_list(matches)))
+        };
+
+        // iii. Else,
+        //          1. Let matchStr be ? ToString(? Get(result, "0")).
+        //          2. Perform ! CreateDataPropertyOrThrow(A, ! ToString(𝔽(n)), matchStr).
+        let (start, end) = captures[0].unwrap();
+        matches.push(capture_value(string, &captures[0]));
+
+        //          3. If matchStr is the empty String, then
+        if start == end {
+            // a. Let thisIndex be ℝ(? ToLength(? Get(rx, "lastIndex"))).
+            // b. Let nextIndex be AdvanceStringIndex(S, thisIndex, fullUnicode).
+            // c. Perform ? Set(rx, "lastIndex", 𝔽(nextIndex), true).
+            return_if_abrupt!(advance_last_index(interpreter, regexp, string, full_unicode));
+        }
+
+        //          4. Set n to n + 1.
+    }
+}
+
+// https://tc39.es/ecma262/#sec-regexp.prototype-%symbol.search%
+pub(crate) fn regexp_search(interpreter: &mut Interpreter, regexp: &Rc<RefCell<JSObject>>, string: &[u16]) -> CompletionRecord {
+    // 4. Let previousLastIndex be ? Get(rx, "lastIndex").
+    let previous_last_index = return_if_abrupt!(get(interpreter, regexp, "lastIndex"));
+
+    // 5. If previousLastIndex is not +0𝔽, then
+    //        a. Perform ? Set(rx, "lastIndex", +0𝔽, true).
+    let previous_last_index_is_zero = matches!(*previous_last_index.borrow(), JSValue::Numeric(number) if number == 0.0 && number.is_sign_positive());
+    if !previous_last_index_is_zero {
+        return_if_abrupt!(set(interpreter, regexp, "lastIndex", JSValue::Numeric(0.0)));
+    }
+
+    // 6. Let result be ? RegExpExec(rx, S).
+    let captures = return_if_abrupt!(regexp_builtin_exec(interpreter, regexp, string));
+
+    // 7. Let currentLastIndex be ? Get(rx, "lastIndex").
+    // 8. If SameValue(currentLastIndex, previousLastIndex) is false, then
+    //        a. Perform ? Set(rx, "lastIndex", previousLastIndex, true).
+    let current_last_index = return_if_abrupt!(get(interpreter, regexp, "lastIndex"));
+    if !Interpreter::is_strictly_equal(&current_last_index.borrow(), &previous_last_index.borrow()) {
+        return_if_abrupt!(set_value(interpreter, regexp, "lastIndex", previous_last_index));
+    }
+
+    // 9. If result is null, return -1𝔽.
+    // 10. Return ? Get(result, "index").
+    return match captures {
+        Some(captures) => create_value_completion(JSValue::Numeric(captures[0].unwrap().0 as f64)),
+        None => create_value_completion(JSValue::Numeric(-1.0))
+    };
+}
+
+// https://tc39.es/ecma262/#sec-regexp.prototype-%symbol.replace%
+pub(crate) fn regexp_replace(interpreter: &mut Interpreter, regexp: &Rc<RefCell<JSObject>>, string: &[u16], replace_value: Rc<RefCell<JSValue>>) -> CompletionRecord {
+    // 4. Let lengthS be the length of S.
+    let length = string.len();
+
+    // 5. Let functionalReplace be IsCallable(replaceValue).
+    let functional_replace = Interpreter::is_callable(&replace_value.borrow());
+
+    // 6. If functionalReplace is false, then
+    //        a. Set replaceValue to ? ToString(replaceValue).
+    let replace_template = if functional_replace {
+        Vec::new()
+    } else {
+        return_if_abrupt!(to_code_units(interpreter, Rc::clone(&replace_value)))
+    };
+
+    // 7. Let flags be ? ToString(? Get(rx, "flags")).
+    let flags = return_if_abrupt!(get(interpreter, regexp, "flags"));
+    let flags = return_if_abrupt!(to_string(interpreter, flags));
+
+    // 8. If flags contains "g", let global be true. Otherwise, let global be false.
+    let global = flags.contains('g');
+
+    // 9. If global is true, then
+    //        a. If flags contains "u" or flags contains "v", let fullUnicode be true. Otherwise, let fullUnicode be false.
+    //        b. Perform ? Set(rx, "lastIndex", +0𝔽, true).
+    let full_unicode = flags.contains('u') || flags.contains('v');
+    if global {
+        return_if_abrupt!(set(interpreter, regexp, "lastIndex", JSValue::Numeric(0.0)));
+    }
+
+    // 10. Let results be a new empty List.
+    let mut results = Vec::new();
+
+    // 11. Let done be false.
+    // 12. Repeat, while done is false,
+    loop {
+        // a. Let result be ? RegExpExec(rx, S).
+        // b. If result is null, then
+        //        i. Set done to true.
+        let captures = match return_if_abrupt!(regexp_builtin_exec(interpreter, regexp, string)) {
+            Some(captures) => captures,
+            None => break
+        };
+
+        // c. Else,
+        //        i. Append result to results.
+        let (start, end) = captures[0].unwrap();
+        results.push(captures);
+
+        //        ii. If global is false, then
+        //                1. Set done to true.
+        if !global {
+            break;
+        }
+
+        //        iii. Else,
+        //                 1. Let matchStr be ? ToString(? Get(result, "0")).
+        //                 2. If matchStr is the empty String, then
+        //                        a. Let thisIndex be ℝ(? ToLength(? Get(rx, "lastIndex"))).
+        //                        b. Let nextIndex be AdvanceStringIndex(S, thisIndex, fullUnicode).
+        //                        c. Perform ? Set(rx, "lastIndex", 𝔽(nextIndex), true).
+        if start == end {
+            return_if_abrupt!(advance_last_index(interpreter, regexp, string, full_unicode));
+        }
+    }
+
+    // 13. Let accumulatedResult be the empty String.
+    let mut accumulated_result = Vec::new();
+
+    // 14. Let nextSourcePosition be 0.
+    let mut next_source_position = 0;
+
+    let matcher_data = regexp.borrow().regexp_data.clone().unwrap();
+
+    // 15. For each element result of results, do
+    for captures in results {
+        // b. Let matched be ? ToString(? Get(result, "0")).
+        // c. Let matchLength be the length of matched.
+        // d. Let position be ? ToIntegerOrInfinity(? Get(result, "index")).
+        // e. Set position to the result of clamping position between 0 and lengthS.
+        let (position, end) = captures[0].unwrap();
+        let matched = &string[position..end];
+
+        // f. Let captures be a new empty List.
+        // g. Let n be 1.
+        // h. Repeat, while n ≤ nCaptures,
+        //        i. Let capN be ? Get(result, ! ToString(𝔽(n))).
+        //        ii. If capN is not undefined, then
+        //                1. Set capN to ? ToString(capN).
+        //        iii. Append capN to captures.
+        let capture_strings: Vec<Option<Vec<u16>>> = captures[1..].iter().map(|capture| capture.map(|(start, end)| string[start..end].to_vec())).collect();
+
+        // i. Let namedCaptures be ? Get(result, "groups").
+        let named_captures: Option<Vec<(String, Option<Vec<u16>>)>> = if matcher_data.matcher.group_names.iter().any(Option::is_some) {
+            Some(matcher_data.matcher.group_names.iter().zip(&capture_strings).filter_map(|(name, capture)| name.clone().map(|name| (name, capture.clone()))).collect())
+        } else {
+            None
+        };
+
+        // j. If functionalReplace is true, then
+        let replacement = if functional_replace {
+            // i. Let replacerArgs be the list-concatenation of « matched », captures, and « 𝔽(position), S ».
+            let mut replacer_arguments = vec![Rc::new(RefCell::new(JSValue::String(String::from_utf16_lossy(matched))))];
+            replacer_arguments.extend(captures[1..].iter().map(|capture| capture_value(string, capture)));
+            replacer_arguments.push(Rc::new(RefCell::new(JSValue::Numeric(position as f64))));
+            replacer_arguments.push(Rc::new(RefCell::new(JSValue::String(String::from_utf16_lossy(string)))));
+
+            // ii. If namedCaptures is not undefined, then
+            //         1. Append namedCaptures to replacerArgs.
+            if named_captures.is_some() {
+                replacer_arguments.push(create_groups_object(interpreter, &matcher_data.matcher, string, &captures));
+            }
+
+            // iii. Let replValue be ? Call(replaceValue, undefined, replacerArgs).
+            // iv. Let replacement be ? ToString(replValue).
+            let replace_result = return_if_abrupt!(completion_value(interpreter.call(Rc::clone(&replace_value), Rc::new(RefCell::new(JSValue::Undefined)), replacer_arguments)));
+            return_if_abrupt!(to_code_units(interpreter, replace_result))
+        } else {
+            // k. Else,
+            //        i. If namedCaptures is not undefined, then
+            //               1. Set namedCaptures to ? ToObject(namedCaptures).
+            //        ii. Let replacement be ? GetSubstitution(matched, S, position, captures, namedCaptures, replaceValue).
+            get_substitution(matched, string, position, &capture_strings, named_captures.as_deref(), &replace_template)
+        };
+
+        // l. If position ≥ nextSourcePosition, then
+        if position >= next_source_position {
+            // i. NOTE: position should not normally move backwards. If it does, it is an indication of an ill-behaving RegExp subclass or use of an
+            //    access triggered side-effect to change the global flag or other characteristics of rx. In such cases, the corresponding substitution is ignored.
+            // ii. Set accumulatedResult to the string-concatenation of accumulatedResult, the substring of S from nextSourcePosition to position, and replacement.
+            // A global replacement can make the result much longer than S, so it is counted against the maximum heap size as it grows
+            if let Some(range_error) = interpreter.reserve_heap(position - next_source_position + replacement.len(), accumulated_result.len()) {
+                return range_error;
+            }
+            accumulated_result.extend_from_slice(&string[next_source_position..position]);
+            accumulated_result.extend_from_slice(&replacement);
+
+            // iii. Set nextSourcePosition to position + matchLength.
+            next_source_position = end;
+        }
+    }
+
+    // 16. If nextSourcePosition ≥ lengthS, return accumulatedResult.
+    // 17. Return the string-concatenation of accumulatedResult and the substring of S from nextSourcePosition.
+    if next_source_position < length {
+        accumulated_result.extend_from_slice(&string[next_source_position..]);
+    }
+    return create_value_completion(JSValue::String(String::from_utf16_lossy(&accumulated_result)));
+}
+
+// https://tc39.es/ecma262/#sec-regexp.prototype-%symbol.split%
+// The splitter is matched at each position of the string as if it had the y flag, rather than being constructed as a new sticky RegExp
+pub(crate) fn regexp_split(interpreter: &mut Interpreter, regexp: &Rc<RefCell<JSObject>>, string: &[u16], limit: Rc<RefCell<JSValue>>) -> CompletionRecord {
+    let regexp_data = regexp.borrow().regexp_data.clone().unwrap();
+
+    // 5. Let flags be ? ToString(? Get(rx, "flags")).
+    // 6. If flags contains "u" or flags contains "v", let unicodeMatching be true.
+    // 7. Else, let unicodeMatching be false.
+    let flags = return_if_abrupt!(get(interpreter, regexp, "flags"));
+    let flags = return_if_abrupt!(to_string(interpreter, flags));
+    let unicode_matching = flags.contains('u') || flags.contains('v');
+
+    // 11. Let A be ! ArrayCreate(0).
+    // 12. Let lengthA be 0.
+    let mut substrings = Vec::new();
+
+    // 13. If limit is undefined, let lim be 2^32 - 1; else let lim be ℝ(? ToUint32(limit)).
+    let limit = if matches!(*limit.borrow(), JSValue::Undefined) {
+        u32::MAX
+    } else {
+        Interpreter::to_uint32(return_if_abrupt!(to_number(interpreter, Rc::clone(&limit))))
+    } as usize;
+
+    // 14. If lim = 0, return A.
+    if limit == 0 {
+        return create_value_completion(JSValue::Object(interpreter.create_array_from_list(substrings)));
+    }
+
+    // 15. If S is the empty String, then
+    if string.is_empty() {
+        // a. Let z be ? RegExpExec(splitter, S).
+        let captures = match regexp_data.matcher.match_at(string, 0) {
+            Ok(captures) => captures,
+            Err(_) => return interpreter.throw_error("RangeError", String::from("Maximum call stack size exceeded"))
+        };
+
+        // b. If z is not null, return A.
+        // c. Perform ! CreateDataPropertyOrThrow(A, "0", S).
+        // d. Return A.
+        if captures.is_none() {
+            substrings.push(Rc::new(RefCell::new(JSValue::String(String::new()))));
+        }
+        return create_value_completion(JSValue::Object(interpreter.create_array_from_list(substrings)));
+    }
+
+    // 16. Let size be the length of S.
+    let size = string.len();
+
+    // 17. Let p be 0.
+    let mut p = 0;
+
+    // 18. Let q be p.
+    let mut q = p;
+
+    // 19. Repeat, while q < size,
+    while q < size {
+        // a. Perform ? Set(splitter, "lastIndex", 𝔽(q), true).
+        // b. Let z be ? RegExpExec(splitter, S).
+        let captures = match regexp_data.matcher.match_at(string, q) {
+            Ok(captures) => captures,
+            Err(_) => return interpreter.throw_error("RangeError", String::from("Maximum call stack size exceeded"))
+        };
+
+        let captures = match captures {
+            // c. If z is null, then
+            //        i. Set q to AdvanceStringIndex(S, q, unicodeMatching).
+            None => {
+                q = advance_string_index(string, q, unicode_matching);
+                continue;
+            },
+            Some(captures) => captures
+        };
+
+        // d. Else,
+        //        i. Let e be ℝ(? ToLength(? Get(splitter, "lastIndex"))).
+        //        ii. Set e to min(e, size).
+        let e = captures[0].unwrap().1.min(size);
+
+        //        iii. If e = p, then
+        //                 1. Set q to AdvanceStringIndex(S, q, unicodeMatching).
+        if e == p {
+            q = advance_string_index(string, q, unicode_matching);
+            continue;
+        }
+
+        //        iv. Else,
+        //                1. Let T be the substring of S from p to q.
+        //                2. Perform ! CreateDataPropertyOrThrow(A, ! ToString(𝔽(lengthA)), T).
+        //                3. Set lengthA to lengthA + 1.
+        //                4. If lengthA = lim, return A.
+        substrings.push(Rc::new(RefCell::new(JSValue::String(String::from_utf16_lossy(&string[p..q])))));
+        if substrings.len() == limit {
+            return create_value_completion(JSValue::Object(interpreter.create_array_from_list(substrings)));
+        }
+
+        //                5. Set p to e.
+        p = e;
+
+        //                6. Let numberOfCaptures be ? LengthOfArrayLike(z).
+        //                7. Set numberOfCaptures to max(numberOfCaptures - 1, 0).
+        //                8. Let i be 1.
+        //                9. Repeat, while i ≤ numberOfCaptures,
+        //                       a. Let nextCapture be ? Get(z, ! ToString(𝔽(i))).
+        //                       b. Perform ! CreateDataPropertyOrThrow(A, ! ToString(𝔽(lengthA)), nextCapture).
+        //                       d. Set lengthA to lengthA + 1.
+        //                       e. If lengthA = lim, return A.
+        for capture in &captures[1..] {
+            substrings.push(capture_value(string, capture));
+            if substrings.len() == limit {
+                return create_value_completion(JSValue::Object(interpreter.create_array_from_list(substrings)));
+            }
+        }
+
+        //                10. Set q to p.
+        q = p;
+    }
+
+    // 20. Let T be the substring of S from p to size.
+    // 21. Perform ! CreateDataPropertyOrThrow(A, ! ToString(𝔽(lengthA)), T).
+    // 22. Return A.
+    substrings.push(Rc::new(RefCell::new(JSValue::String(String::from_utf16_lossy(&string[p..size])))));
+    return create_value_completion(JSValue::Object(interpreter.create_array_from_list(substrings)));
+}
+
+// Sets lastIndex past an empty match of a global regular expression, so the next match does not find it again
+fn advance_last_index(interpreter: &mut Interpreter, regexp: &Rc<RefCell<JSObject>>, string: &[u16], full_unicode: bool) -> Result<(), CompletionRecord> {
+    // a. Let thisIndex be ℝ(? ToLength(? Get(rx, "lastIndex"))).
+    let this_index = get(interpreter, regexp, "lastIndex")?;
+    let this_index = to_length(interpreter, this_index)?;
+
+    // b. Let nextIndex be AdvanceStringIndex(S, thisIndex, fullUnicode).
+    let next_index = advance_string_index(string, this_index, full_unicode);
+
+    // c. Perform ? Set(rx, "lastIndex", 𝔽(nextIndex), true).
+    set(interpreter, regexp, "lastIndex", JSValue::Numeric(next_index as f64))?;
+    return Ok(());
+}
+
+// https://tc39.es/ecma262/#sec-tolength
+// Lengths beyond the end of any string are clamped to usize::MAX rather than 2^53 - 1
+fn to_length(interpreter: &mut Interpreter, value: Rc<RefCell<JSValue>>) -> Result<usize, CompletionRecord> {
+    // 1. Let len be ? ToIntegerOrInfinity(argument).
+    let length = to_number(interpreter, value)?;
+
+    // 2. If len ≤ 0, return +0𝔽.
+    if length.is_nan() || length <= 0.0 {
+        return Ok(0);
+    }
+
+    // 3. Return 𝔽(min(len, 2^53 - 1)).
+    return Ok(length.trunc().min(9007199254740991.0) as usize);
+}
+
+fn get(interpreter: &mut Interpreter, object: &Rc<RefCell<JSObject>>, key: &str) -> Result<Rc<RefCell<JSValue>>, CompletionRecord> {
+    // 1. Return ? O.[[Get]](P, O).
+    let receiver = Rc::new(RefCell::new(JSValue::Object(Rc::clone(object))));
+    return completion_value(interpreter.get(object, &PropertyKey::String(key.to_string()), receiver));
+}
+
+fn set(interpreter: &mut Interpreter, object: &Rc<RefCell<JSObject>>, key: &str, value: JSValue) -> Result<(), CompletionRecord> {
+    return set_value(interpreter, object, key, Rc::new(RefCell::new(value)));
+}
+
+// https://tc39.es/ecma262/#sec-set-o-p-v-throw
+fn set_value(interpreter: &mut Interpreter, object: &Rc<RefCell<JSObject>>, key: &str, value: Rc<RefCell<JSValue>>) -> Result<(), CompletionRecord> {
+    // 1. Let success be ? O.[[Set]](P, V, O).
+    let receiver = Rc::new(RefCell::new(JSValue::Object(Rc::clone(object))));
+    let success = completion_value(interpreter.set_property(object, PropertyKey::String(key.to_string()), value, receiver))?;
+
+    // 2. If success is false and Throw is true, throw a TypeError exception.
+    if matches!(*success.borrow(), JSValue::Boolean(false)) {
+        return Err(interpreter.throw_type_error(format!("Cannot assign to read only property '{}' of object", key)));
+    }
+
+    // 3. Return unused.
+    return Ok(());
+}
+
+fn to_string(interpreter: &mut Interpreter, value: Rc<RefCell<JSValue>>) -> Result<String, CompletionRecord> {
+    match &*completion_value(interpreter.to_string_coercing_objects(value))?.borrow() {
+        JSValue::String(string) => Ok(string.clone()),
+        _ => unreachable!()
+    }
+}
+
+fn to_code_units(interpreter: &mut Interpreter, value: Rc<RefCell<JSValue>>) -> Result<Vec<u16>, CompletionRecord> {
+    return Ok(to_string(interpreter, value)?.encode_utf16().collect());
+}
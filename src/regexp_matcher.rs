@@ -0,0 +1,900 @@
+// https://tc39.es/ecma262/#sec-regexp-regular-expression-objects
+// https://tc39.es/ecma262/#sec-pattern-semantics
+// The pattern of a regular expression compiled into a tree of nodes, which is matched by backtracking through the alternatives and repetitions
+// in the order the spec's continuation-passing semantics tries them, so a match and its captures are the ones the spec gives
+// Like the spec without the u flag, patterns are matched against the UTF-16 code units of the input, and the Annex B extensions for web
+// compatibility are supported, e.g. a } or ] on its own is a literal and \8 is an identity escape
+// TODO: With the u and v flags a surrogate pair should be matched as one character, \p{...} and lookbehind assertions are not supported
+
+// The number of nodes the matcher can be nested in, each repetition of a group nests the rest of the match in it,
+// so repeating a group over a long input would overflow the stack
+const MAX_DEPTH: usize = 2000;
+
+// https://tc39.es/ecma262/#sec-compiletomatcher
+#[derive(Debug)]
+enum Node {
+    Empty,
+    // A code unit of the pattern, e.g. a or \n
+    CodeUnit(u16),
+    // . matches any code unit but a line terminator, unless the s flag is set
+    Any,
+    // https://tc39.es/ecma262/#sec-compiletocharset
+    Class(CharacterClass),
+    // ^ and $, which match at the start and end of each line with the m flag
+    LineStart,
+    LineEnd,
+    // \b and \B
+    WordBoundary { negated: bool },
+    // (...) and (?:...), index is the number of a capturing group
+    Group { node: Box<Node>, index: Option<usize> },
+    // (?=...) and (?!...)
+    Lookahead { node: Box<Node>, negated: bool },
+    // \1 and \k<name>
+    Backreference(usize),
+    // https://tc39.es/ecma262/#sec-runtime-semantics-repeatmatcher-abstract-operation
+    // The captures of the groups inside are cleared before each repetition
+    Quantified { node: Box<Node>, min: usize, max: Option<usize>, greedy: bool, first_group: usize, group_count: usize },
+    Sequence(Vec<Node>),
+    Alternation(Vec<Node>),
+}
+
+#[derive(Debug)]
+struct CharacterClass {
+    items: Vec<ClassItem>,
+    negated: bool,
+}
+
+#[derive(Debug)]
+enum ClassItem {
+    Range(u16, u16),
+    // \d and \D
+    Digit(bool),
+    // \s and \S
+    Space(bool),
+    // \w and \W
+    Word(bool),
+}
+
+// What a class atom is before it is known whether it is the end of a range
+enum ClassAtom {
+    CodeUnit(u16),
+    Escape(ClassItem),
+}
+
+// The start and end of the match and of each capturing group, by its number, None for a group that did not take part in the match
+pub(crate) type Captures = Vec<Option<(usize, usize)>>;
+
+// The match could not be completed without overflowing the stack
+#[derive(Debug)]
+pub(crate) struct StackOverflow;
+
+// https://tc39.es/ecma262/#sec-regexp-abstract-closure
+// The [[RegExpMatcher]] of a regular expression
+#[derive(Debug)]
+pub(crate) struct RegExpMatcher {
+    node: Node,
+    // The number of capturing groups, they are numbered from 1
+    pub(crate) capture_count: usize,
+    // The name of each capturing group that has one, by its number - 1
+    pub(crate) group_names: Vec<Option<String>>,
+    ignore_case: bool,
+    multiline: bool,
+    dot_all: bool,
+}
+
+impl RegExpMatcher {
+    // https://tc39.es/ecma262/#sec-parsepattern
+    // The error is the message of the SyntaxError for a pattern that does not parse
+    pub(crate) fn new(pattern: &str, ignore_case: bool, multiline: bool, dot_all: bool, unicode: bool) -> Result<RegExpMatcher, String> {
+        let pattern: Vec<u16> = pattern.encode_utf16().collect();
+        let group_names = capture_group_names(&pattern)?;
+        let mut parser = PatternParser { pattern: &pattern, position: 0, capture_count: 0, group_names: &group_names, unicode };
+        let node = parser.parse_disjunction()?;
+        if parser.position < pattern.len() {
+            return Err(String::from("Unmatched ')'"));
+        }
+        return Ok(RegExpMatcher { node, capture_count: group_names.len(), group_names, ignore_case, multiline, dot_all });
+    }
+
+    // Matches the pattern starting at exactly position of input, the captures of the match have the whole match as capture 0
+    pub(crate) fn match_at(&self, input: &[u16], position: usize) -> Result<Option<Captures>, StackOverflow> {
+        let mut matcher = Matcher { program: self, input, captures: vec![None; self.capture_count + 1], depth: 0, overflow: false };
+        let mut end = None;
+        let matched = matcher.match_node(&self.node, position, &mut |_, end_index| {
+            end = Some(end_index);
+            return true;
+        });
+        if matcher.overflow {
+            return Err(StackOverflow);
+        }
+        if !matched {
+            return Ok(None);
+        }
+        let mut captures = matcher.captures;
+        captures[0] = Some((position, end.unwrap()));
+        return Ok(Some(captures));
+    }
+}
+
+// The names of the capturing groups of pattern in order, which a backreference before the group can refer to too
+fn capture_group_names(pattern: &[u16]) -> Result<Vec<Option<String>>, String> {
+    let mut names: Vec<Option<String>> = Vec::new();
+    let mut index = 0;
+    let mut in_class = false;
+    while index < pattern.len() {
+        match pattern[index] {
+            0x5C => index += 1,
+            0x5B => in_class = true,
+            0x5D => in_class = false,
+            0x28 if !in_class => {
+                if pattern.get(index + 1) != Some(&0x3F) {
+                    names.push(None);
+                } else if pattern.get(index + 2) == Some(&0x3C) && !matches!(pattern.get(index + 3), Some(&0x3D) | Some(&0x21)) {
+                    let name_start = index + 3;
+                    let name_length = pattern[name_start..].iter().position(|code_unit| *code_unit == 0x3E).ok_or(String::from("Invalid capture group name"))?;
+                    let name = String::from_utf16_lossy(&pattern[name_start..name_start + name_length]);
+                    if !is_group_name(&name) {
+                        return Err(String::from("Invalid capture group name"));
+                    }
+                    if names.iter().any(|existing| existing.as_deref() == Some(name.as_str())) {
+                        return Err(String::from("Duplicate capture group name"));
+                    }
+                    names.push(Some(name));
+                }
+            },
+            _ => ()
+        }
+        index += 1;
+    }
+    return Ok(names);
+}
+
+// https://tc39.es/ecma262/#prod-RegExpIdentifierName
+fn is_group_name(name: &str) -> bool {
+    let mut characters = name.chars();
+    return match characters.next() {
+        Some(first) => (first.is_alphabetic() || first == '$' || first == '_') && characters.all(|character| character.is_alphanumeric() || character == '$' || character == '_'),
+        None => false
+    };
+}
+
+// https://tc39.es/ecma262/#sec-patterns
+struct PatternParser<'a> {
+    pattern: &'a [u16],
+    position: usize,
+    // The number of capturing groups parsed so far, the number of the next one is one more
+    capture_count: usize,
+    group_names: &'a [Option<String>],
+    unicode: bool,
+}
+
+impl<'a> PatternParser<'a> {
+    fn peek(&self) -> Option<u16> {
+        return self.pattern.get(self.position).copied();
+    }
+
+    fn peek_is(&self, offset: usize, character: char) -> bool {
+        return self.pattern.get(self.position + offset) == Some(&(character as u16));
+    }
+
+    fn eat(&mut self, character: char) -> bool {
+        if self.peek_is(0, character) {
+            self.position += 1;
+            return true;
+        }
+        return false;
+    }
+
+    // Disjunction :: Alternative | Alternative | Disjunction
+    fn parse_disjunction(&mut self) -> Result<Node, String> {
+        let mut alternatives = vec![self.parse_alternative()?];
+        while self.eat('|') {
+            alternatives.push(self.parse_alternative()?);
+        }
+        if alternatives.len() == 1 {
+            return Ok(alternatives.pop().unwrap());
+        }
+        return Ok(Node::Alternation(alternatives));
+    }
+
+    // Alternative :: [empty] | Alternative Term
+    fn parse_alternative(&mut self) -> Result<Node, String> {
+        let mut terms = Vec::new();
+        while self.peek().is_some() && !self.peek_is(0, '|') && !self.peek_is(0, ')') {
+            terms.push(self.parse_term()?);
+        }
+        return Ok(match terms.len() {
+            0 => Node::Empty,
+            1 => terms.pop().unwrap(),
+            _ => Node::Sequence(terms)
+        });
+    }
+
+    // Term :: Assertion | Atom | Atom Quantifier
+    fn parse_term(&mut self) -> Result<Node, String> {
+        // Assertion :: ^ | $ | \b | \B, which can not be repeated
+        let assertion = if self.eat('^') {
+            Some(Node::LineStart)
+        } else if self.eat('$') {
+            Some(Node::LineEnd)
+        } else if self.peek_is(0, '\\') && (self.peek_is(1, 'b') || self.peek_is(1, 'B')) {
+            let negated = self.peek_is(1, 'B');
+            self.position += 2;
+            Some(Node::WordBoundary { negated })
+        } else if self.peek_is(0, '(') && self.peek_is(1, '?') && self.peek_is(2, '<') && (self.peek_is(3, '=') || self.peek_is(3, '!')) {
+            return Err(String::from("Lookbehind assertions are not supported"));
+        } else {
+            None
+        };
+        if let Some(assertion) = assertion {
+            if self.parse_quantifier()?.is_some() {
+                return Err(String::from("Nothing to repeat"));
+            }
+            return Ok(assertion);
+        }
+
+        let first_group = self.capture_count;
+        let atom = self.parse_atom()?;
+
+        // Quantifier :: QuantifierPrefix | QuantifierPrefix ?
+        return match self.parse_quantifier()? {
+            Some((min, max)) => {
+                let greedy = !self.eat('?');
+                Ok(Node::Quantified { node: Box::new(atom), min, max, greedy, first_group, group_count: self.capture_count - first_group })
+            },
+            None => Ok(atom)
+        };
+    }
+
+    // QuantifierPrefix :: * | + | ? | { DecimalDigits } | { DecimalDigits , } | { DecimalDigits , DecimalDigits }
+    // A { that does not start one is a literal
+    fn parse_quantifier(&mut self) -> Result<Option<(usize, Option<usize>)>, String> {
+        if self.eat('*') {
+            return Ok(Some((0, None)));
+        }
+        if self.eat('+') {
+            return Ok(Some((1, None)));
+        }
+        if self.eat('?') {
+            return Ok(Some((0, Some(1))));
+        }
+        if self.peek_is(0, '{') {
+            if let Some((min, max, length)) = self.braced_quantifier() {
+                self.position += length;
+                if max.is_some_and(|max| max < min) {
+                    return Err(String::from("numbers out of order in {} quantifier"));
+                }
+                return Ok(Some((min, max)));
+            }
+        }
+        return Ok(None);
+    }
+
+    // The min, max and length of the {n}, {n,} or {n,m} at the position, if there is one
+    fn braced_quantifier(&self) -> Option<(usize, Option<usize>, usize)> {
+        let rest = &self.pattern[self.position + 1..];
+        let digits = |start: usize| rest[start..].iter().take_while(|code_unit| (0x30..=0x39).contains(*code_unit)).count();
+        let number = |start: usize, length: usize| String::from_utf16_lossy(&rest[start..start + length]).parse::<usize>().unwrap_or(usize::MAX);
+
+        let min_length = digits(0);
+        if min_length == 0 {
+            return None;
+        }
+        let min = number(0, min_length);
+        match rest.get(min_length) {
+            Some(0x7D) => return Some((min, Some(min), min_length + 2)),
+            Some(0x2C) => {
+                let max_length = digits(min_length + 1);
+                if rest.get(min_length + 1 + max_length) != Some(&0x7D) {
+                    return None;
+                }
+                let max = if max_length == 0 { None } else { Some(number(min_length + 1, max_length)) };
+                return Some((min, max, min_length + max_length + 3));
+            },
+            _ => return None
+        }
+    }
+
+    // Atom :: PatternCharacter | . | \ AtomEscape | CharacterClass | ( GroupSpecifier Disjunction ) | (?: Disjunction )
+    fn parse_atom(&mut self) -> Result<Node, String> {
+        let code_unit = self.peek().unwrap();
+        match char::from_u32(code_unit as u32) {
+            Some('.') => {
+                self.position += 1;
+                return Ok(Node::Any);
+            },
+            Some('(') => return self.parse_group(),
+            Some('[') => return self.parse_class(),
+            Some('\\') => return self.parse_atom_escape(),
+            Some('*' | '+' | '?') => return Err(String::from("Nothing to repeat")),
+            Some('{') if self.braced_quantifier().is_some() => return Err(String::from("Nothing to repeat")),
+            Some('{' | '}' | ']') if self.unicode => return Err(String::from("Lone quantifier brackets")),
+            _ => ()
+        }
+        self.position += 1;
+        return Ok(Node::CodeUnit(code_unit));
+    }
+
+    fn parse_group(&mut self) -> Result<Node, String> {
+        self.position += 1;
+        let node = if self.peek_is(0, '?') && (self.peek_is(1, '=') || self.peek_is(1, '!')) {
+            // (?= Disjunction ) and (?! Disjunction )
+            let negated = self.peek_is(1, '!');
+            self.position += 2;
+            Node::Lookahead { node: Box::new(self.parse_disjunction()?), negated }
+        } else if self.peek_is(0, '?') && self.peek_is(1, ':') {
+            // (?: Disjunction )
+            self.position += 2;
+            Node::Group { node: Box::new(self.parse_disjunction()?), index: None }
+        } else if self.peek_is(0, '?') && self.peek_is(1, '<') {
+            // ( GroupSpecifier Disjunction ), the name was checked when the groups were counted
+            let name_length = self.pattern[self.position..].iter().position(|code_unit| *code_unit == 0x3E).unwrap();
+            self.position += name_length + 1;
+            self.capture_count += 1;
+            let index = self.capture_count;
+            Node::Group { node: Box::new(self.parse_disjunction()?), index: Some(index) }
+        } else if self.peek_is(0, '?') {
+            return Err(String::from("Invalid group"));
+        } else {
+            self.capture_count += 1;
+            let index = self.capture_count;
+            Node::Group { node: Box::new(self.parse_disjunction()?), index: Some(index) }
+        };
+        if !self.eat(')') {
+            return Err(String::from("Unterminated group"));
+        }
+        return Ok(node);
+    }
+
+    // AtomEscape :: DecimalEscape | CharacterClassEscape | CharacterEscape | k GroupName
+    fn parse_atom_escape(&mut self) -> Result<Node, String> {
+        self.position += 1;
+        let code_unit = match self.peek() {
+            Some(code_unit) => code_unit,
+            None => return Err(String::from("\\ at end of pattern"))
+        };
+        if let Some(item) = class_escape(code_unit) {
+            self.position += 1;
+            return Ok(Node::Class(CharacterClass { items: vec![item], negated: false }));
+        }
+
+        // https://tc39.es/ecma262/#sec-backreference-matcher
+        // A number greater than the number of groups is a legacy octal escape, or an identity escape for 8 and 9
+        if (0x31..=0x39).contains(&code_unit) {
+            let length = self.pattern[self.position..].iter().take_while(|code_unit| (0x30..=0x39).contains(*code_unit)).count();
+            let number = String::from_utf16_lossy(&self.pattern[self.position..self.position + length]).parse::<usize>().unwrap_or(usize::MAX);
+            if number <= self.group_names.len() {
+                self.position += length;
+                return Ok(Node::Backreference(number));
+            }
+            if self.unicode {
+                return Err(String::from("Invalid escape"));
+            }
+        }
+
+        if code_unit == 'k' as u16 && (self.unicode || self.group_names.iter().any(Option::is_some)) {
+            self.position += 1;
+            let name_length = match self.pattern[self.position..].iter().position(|code_unit| *code_unit == 0x3E) {
+                Some(name_length) if self.peek_is(0, '<') => name_length,
+                _ => return Err(String::from("Invalid named reference"))
+            };
+            let name = String::from_utf16_lossy(&self.pattern[self.position + 1..self.position + name_length]);
+            self.position += name_length + 1;
+            return match self.group_names.iter().position(|group_name| group_name.as_deref() == Some(name.as_str())) {
+                Some(index) => Ok(Node::Backreference(index + 1)),
+                None => Err(String::from("Invalid named capture referenced"))
+            };
+        }
+
+        let code_point = self.parse_character_escape()?;
+        return Ok(code_point_node(code_point));
+    }
+
+    // https://tc39.es/ecma262/#prod-CharacterEscape
+    // The escape after the \, as a code point
+    fn parse_character_escape(&mut self) -> Result<u32, String> {
+        let code_unit = self.peek().unwrap();
+        self.position += 1;
+        let value = match char::from_u32(code_unit as u32) {
+            Some('f') => 0x0C,
+            Some('n') => 0x0A,
+            Some('r') => 0x0D,
+            Some('t') => 0x09,
+            Some('v') => 0x0B,
+            // c AsciiLetter is the letter's code modulo 32, a \c that is not followed by one is a \ followed by c
+            Some('c') => match self.peek() {
+                Some(letter) if (letter as u8 as u16 == letter) && (letter as u8).is_ascii_alphabetic() => {
+                    self.position += 1;
+                    (letter % 32) as u32
+                },
+                _ if self.unicode => return Err(String::from("Invalid unicode escape")),
+                _ => {
+                    self.position -= 1;
+                    0x5C
+                }
+            },
+            // 0 [lookahead ∉ DecimalDigit], the Annex B legacy octal escapes are up to three octal digits
+            Some('0'..='7') if !self.unicode || (code_unit == 0x30 && !self.peek().is_some_and(|next| (0x30..=0x39).contains(&next))) => {
+                let mut value = (code_unit - 0x30) as u32;
+                while value * 8 <= 0o377 && self.peek().is_some_and(|next| (0x30..=0x37).contains(&next)) {
+                    value = value * 8 + (self.peek().unwrap() - 0x30) as u32;
+                    self.position += 1;
+                }
+                value
+            },
+            Some('x') => match self.hex_digits(2) {
+                Some(value) => value,
+                None if self.unicode => return Err(String::from("Invalid escape")),
+                None => 'x' as u32
+            },
+            Some('u') => {
+                if self.unicode && self.eat('{') {
+                    let length = self.pattern[self.position..].iter().take_while(|code_unit| char::from_u32(**code_unit as u32).is_some_and(|character| character.is_ascii_hexdigit())).count();
+                    let value = u32::from_str_radix(&String::from_utf16_lossy(&self.pattern[self.position..self.position + length]), 16).ok().filter(|value| *value <= 0x10FFFF);
+                    self.position += length;
+                    match value {
+                        Some(value) if self.eat('}') => value,
+                        _ => return Err(String::from("Invalid Unicode escape"))
+                    }
+                } else {
+                    match self.hex_digits(4) {
+                        // With the u flag an escaped surrogate pair is one code point
+                        Some(lead) if self.unicode && (0xD800..=0xDBFF).contains(&lead) && self.peek_is(0, '\\') && self.peek_is(1, 'u') => {
+                            self.position += 2;
+                            match self.hex_digits(4) {
+                                Some(trail) if (0xDC00..=0xDFFF).contains(&trail) => 0x10000 + ((lead - 0xD800) << 10) + (trail - 0xDC00),
+                                Some(trail) => {
+                                    self.position -= 6;
+                                    let _ = trail;
+                                    lead
+                                },
+                                None => return Err(String::from("Invalid Unicode escape"))
+                            }
+                        },
+                        Some(value) => value,
+                        None if self.unicode => return Err(String::from("Invalid Unicode escape")),
+                        None => 'u' as u32
+                    }
+                }
+            },
+            // IdentityEscape, with the u flag only syntax characters and / can be escaped
+            _ if self.unicode && !"^$\\.*+?()[]{}|/-".encode_utf16().any(|syntax_character| syntax_character == code_unit) => {
+                return Err(String::from("Invalid escape"));
+            },
+            _ => code_unit as u32
+        };
+        return Ok(value);
+    }
+
+    // The value of length hexadecimal digits at the position, which are only consumed when they are all there
+    fn hex_digits(&mut self, length: usize) -> Option<u32> {
+        let digits = self.pattern.get(self.position..self.position + length)?;
+        if !digits.iter().all(|code_unit| char::from_u32(*code_unit as u32).is_some_and(|character| character.is_ascii_hexdigit())) {
+            return None;
+        }
+        self.position += length;
+        return u32::from_str_radix(&String::from_utf16_lossy(digits), 16).ok();
+    }
+
+    // CharacterClass :: [ ClassContents ] | [^ ClassContents ]
+    fn parse_class(&mut self) -> Result<Node, String> {
+        self.position += 1;
+        let negated = self.eat('^');
+        let mut items = Vec::new();
+        loop {
+            if self.peek().is_none() {
+                return Err(String::from("Unterminated character class"));
+            }
+            if self.eat(']') {
+                break;
+            }
+            let start = self.parse_class_atom()?;
+            // ClassAtom - ClassAtom, a - before the ] is a literal
+            if self.peek_is(0, '-') && !self.peek_is(1, ']') && self.pattern.get(self.position + 1).is_some() {
+                self.position += 1;
+                let end = self.parse_class_atom()?;
+                match (start, end) {
+                    (ClassAtom::CodeUnit(start), ClassAtom::CodeUnit(end)) => {
+                        if start > end {
+                            return Err(String::from("Range out of order in character class"));
+                        }
+                        items.push(ClassItem::Range(start, end));
+                    },
+                    // Annex B: a range with a class escape at either end is the escape, a - and the other atom
+                    (start, end) => {
+                        if self.unicode {
+                            return Err(String::from("Invalid character class"));
+                        }
+                        items.push(class_atom_item(start));
+                        items.push(ClassItem::Range(0x2D, 0x2D));
+                        items.push(class_atom_item(end));
+                    }
+                }
+            } else {
+                items.push(class_atom_item(start));
+            }
+        }
+        return Ok(Node::Class(CharacterClass { items, negated }));
+    }
+
+    // ClassAtom :: - | ClassAtomNoDash, in a class \b is a backspace and \- is a -
+    fn parse_class_atom(&mut self) -> Result<ClassAtom, String> {
+        let code_unit = self.peek().unwrap();
+        if code_unit != 0x5C {
+            self.position += 1;
+            return Ok(ClassAtom::CodeUnit(code_unit));
+        }
+        self.position += 1;
+        let escaped = match self.peek() {
+            Some(escaped) => escaped,
+            None => return Err(String::from("\\ at end of pattern"))
+        };
+        if let Some(item) = class_escape(escaped) {
+            self.position += 1;
+            return Ok(ClassAtom::Escape(item));
+        }
+        if escaped == 'b' as u16 {
+            self.position += 1;
+            return Ok(ClassAtom::CodeUnit(0x08));
+        }
+        if escaped == '-' as u16 {
+            self.position += 1;
+            return Ok(ClassAtom::CodeUnit(0x2D));
+        }
+        let code_point = self.parse_character_escape()?;
+        // TODO: A code point outside the Basic Multilingual Plane is matched as its lead surrogate
+        return Ok(ClassAtom::CodeUnit(char::from_u32(code_point).map_or(code_point as u16, |character| character.encode_utf16(&mut [0u16; 2])[0])));
+    }
+}
+
+// https://tc39.es/ecma262/#prod-CharacterClassEscape
+fn class_escape(code_unit: u16) -> Option<ClassItem> {
+    return match char::from_u32(code_unit as u32)? {
+        'd' => Some(ClassItem::Digit(false)),
+        'D' => Some(ClassItem::Digit(true)),
+        's' => Some(ClassItem::Space(false)),
+        'S' => Some(ClassItem::Space(true)),
+        'w' => Some(ClassItem::Word(false)),
+        'W' => Some(ClassItem::Word(true)),
+        _ => None
+    };
+}
+
+fn class_atom_item(atom: ClassAtom) -> ClassItem {
+    return match atom {
+        ClassAtom::CodeUnit(code_unit) => ClassItem::Range(code_unit, code_unit),
+        ClassAtom::Escape(item) => item
+    };
+}
+
+// A code point of the pattern, which is its two surrogates when it is outside the Basic Multilingual Plane
+fn code_point_node(code_point: u32) -> Node {
+    return match char::from_u32(code_point) {
+        Some(character) if code_point > 0xFFFF => {
+            let mut buffer = [0u16; 2];
+            Node::Sequence(character.encode_utf16(&mut buffer).iter().map(|code_unit| Node::CodeUnit(*code_unit)).collect())
+        },
+        _ => Node::CodeUnit(code_point as u16)
+    };
+}
+
+// https://tc39.es/ecma262/#prod-LineTerminator
+fn is_line_terminator(code_unit: u16) -> bool {
+    return matches!(code_unit, 0x000A | 0x000D | 0x2028 | 0x2029);
+}
+
+// https://tc39.es/ecma262/#sec-iswordchar
+fn is_word_character(code_unit: u16) -> bool {
+    return matches!(code_unit, 0x30..=0x39 | 0x41..=0x5A | 0x61..=0x7A | 0x5F);
+}
+
+// https://tc39.es/ecma262/#prod-WhiteSpace
+fn is_white_space(code_unit: u16) -> bool {
+    return matches!(code_unit, 0x0009 | 0x000B | 0x000C | 0x0020 | 0x00A0 | 0x1680 | 0x2000..=0x200A | 0x202F | 0x205F | 0x3000 | 0xFEFF) || is_line_terminator(code_unit);
+}
+
+// https://tc39.es/ecma262/#sec-runtime-semantics-canonicalize-ch
+// Without the u flag a code unit is compared by its simple uppercase, unless that maps a non-ASCII code unit to an ASCII one, e.g. ſ is not s
+fn canonicalize(code_unit: u16) -> u16 {
+    let character = match char::from_u32(code_unit as u32) {
+        Some(character) => character,
+        None => return code_unit
+    };
+    let mut uppercase = character.to_uppercase();
+    let upper = match (uppercase.next(), uppercase.next()) {
+        (Some(upper), None) => upper as u32,
+        _ => return code_unit
+    };
+    if upper > 0xFFFF || (code_unit >= 128 && upper < 128) {
+        return code_unit;
+    }
+    return upper as u16;
+}
+
+// The state of one attempt to match at a position
+struct Matcher<'a> {
+    program: &'a RegExpMatcher,
+    input: &'a [u16],
+    captures: Captures,
+    depth: usize,
+    overflow: bool,
+}
+
+impl<'a> Matcher<'a> {
+    // https://tc39.es/ecma262/#sec-compiletomatcher
+    // Matches node at position and then the rest of the pattern, which is the continuation, backtracking into node when the continuation fails
+    fn match_node(&mut self, node: &'a Node, position: usize, continuation: &mut dyn FnMut(&mut Matcher<'a>, usize) -> bool) -> bool {
+        if self.overflow {
+            return false;
+        }
+        if self.depth >= MAX_DEPTH {
+            self.overflow = true;
+            return false;
+        }
+        self.depth += 1;
+        let matched = self.match_node_with_continuation(node, position, continuation);
+        self.depth -= 1;
+        return matched;
+    }
+
+    fn match_node_with_continuation(&mut self, node: &'a Node, position: usize, continuation: &mut dyn FnMut(&mut Matcher<'a>, usize) -> bool) -> bool {
+        match node {
+            Node::Empty => return continuation(self, position),
+            Node::CodeUnit(_) | Node::Any | Node::Class(_) => {
+                if position < self.input.len() && self.matches_code_unit(node, self.input[position]) {
+                    return continuation(self, position + 1);
+                }
+                return false;
+            },
+            // https://tc39.es/ecma262/#sec-compiletomatcher Assertion :: ^
+            Node::LineStart => {
+                if position == 0 || (self.program.multiline && is_line_terminator(self.input[position - 1])) {
+                    return continuation(self, position);
+                }
+                return false;
+            },
+            // Assertion :: $
+            Node::LineEnd => {
+                if position == self.input.len() || (self.program.multiline && is_line_terminator(self.input[position])) {
+                    return continuation(self, position);
+                }
+                return false;
+            },
+            // Assertion :: \b and \B
+            Node::WordBoundary { negated } => {
+                let a = position > 0 && is_word_character(self.input[position - 1]);
+                let b = position < self.input.len() && is_word_character(self.input[position]);
+                if (a != b) != *negated {
+                    return continuation(self, position);
+                }
+                return false;
+            },
+            // Atom :: ( GroupSpecifier Disjunction ), the capture is set when the group matched and is undone when the rest of the pattern fails
+            Node::Group { node, index: Some(index) } => {
+                let index = *index;
+                return self.match_node(node, position, &mut |matcher: &mut Matcher<'a>, end: usize| {
+                    let saved = matcher.captures[index];
+                    matcher.captures[index] = Some((position, end));
+                    if continuation(matcher, end) {
+                        return true;
+                    }
+                    matcher.captures[index] = saved;
+                    return false;
+                });
+            },
+            Node::Group { node, index: None } => return self.match_node(node, position, continuation),
+            // Assertion :: (?= Disjunction ), only the first way the lookahead matches is tried, and its captures are kept
+            Node::Lookahead { node, negated: false } => {
+                let saved = self.captures.clone();
+                if self.match_node(node, position, &mut |_, _| true) && continuation(self, position) {
+                    return true;
+                }
+                self.captures = saved;
+                return false;
+            },
+            // Assertion :: (?! Disjunction ), the captures of a negative lookahead are always undefined outside it
+            Node::Lookahead { node, negated: true } => {
+                let saved = self.captures.clone();
+                let matched = self.match_node(node, position, &mut |_, _| true);
+                self.captures = saved;
+                if matched || self.overflow {
+                    return false;
+                }
+                return continuation(self, position);
+            },
+            // https://tc39.es/ecma262/#sec-backreference-matcher
+            // A backreference to a group that did not take part in the match matches the empty string
+            Node::Backreference(index) => {
+                let (start, end) = match self.captures[*index] {
+                    Some(capture) => capture,
+                    None => return continuation(self, position)
+                };
+                let length = end - start;
+                if position + length > self.input.len() {
+                    return false;
+                }
+                let equal = (0..length).all(|offset| self.code_units_equal(self.input[start + offset], self.input[position + offset]));
+                if !equal {
+                    return false;
+                }
+                return continuation(self, position + length);
+            },
+            Node::Quantified { node, min, max, greedy, first_group, group_count } => {
+                // A single code unit without groups is repeated in a loop rather than by nesting, so e.g. .* can match a long input
+                if matches!(**node, Node::CodeUnit(_) | Node::Any | Node::Class(_)) {
+                    return self.repeat_code_unit(node, *min, *max, *greedy, position, continuation);
+                }
+                let repetition = Repetition { node, min: *min, max: *max, greedy: *greedy, first_group: *first_group, group_count: *group_count };
+                return self.repeat(&repetition, 0, position, continuation);
+            },
+            Node::Sequence(nodes) => return self.match_sequence(nodes, position, continuation),
+            // https://tc39.es/ecma262/#sec-compiletomatcher Disjunction :: Alternative | Disjunction, the alternatives are tried from left to right
+            Node::Alternation(alternatives) => {
+                for alternative in alternatives {
+                    if self.match_node(alternative, position, continuation) {
+                        return true;
+                    }
+                    if self.overflow {
+                        return false;
+                    }
+                }
+                return false;
+            }
+        }
+    }
+
+    // Alternative :: Alternative Term, each term is matched with the rest of the sequence as its continuation
+    fn match_sequence(&mut self, nodes: &'a [Node], position: usize, continuation: &mut dyn FnMut(&mut Matcher<'a>, usize) -> bool) -> bool {
+        return match nodes.split_first() {
+            None => continuation(self, position),
+            Some((first, rest)) => self.match_node(first, position, &mut |matcher: &mut Matcher<'a>, end: usize| matcher.match_sequence(rest, end, continuation))
+        };
+    }
+
+    // https://tc39.es/ecma262/#sec-runtime-semantics-repeatmatcher-abstract-operation
+    // count is the number of repetitions matched so far
+    fn repeat(&mut self, repetition: &Repetition<'a>, count: usize, position: usize, continuation: &mut dyn FnMut(&mut Matcher<'a>, usize) -> bool) -> bool {
+        // 1. If max = 0, return c(x).
+        if repetition.max == Some(count) {
+            return continuation(self, position);
+        }
+        // 4. If min > 0, return m(xr, d).
+        if count < repetition.min {
+            return self.repeat_once(repetition, count, position, continuation);
+        }
+        // 10. If greedy is false, then
+        //     a. Let z be c(x).
+        //     b. If z is not failure, return z.
+        //     c. Return m(xr, d).
+        if !repetition.greedy {
+            if continuation(self, position) {
+                return true;
+            }
+            return !self.overflow && self.repeat_once(repetition, count, position, continuation);
+        }
+        // 11. Let z be m(xr, d).
+        // 12. If z is not failure, return z.
+        // 13. Return c(x).
+        if self.repeat_once(repetition, count, position, continuation) {
+            return true;
+        }
+        return !self.overflow && continuation(self, position);
+    }
+
+    // One more repetition of the atom, with the captures of its groups cleared first, followed by the repetitions after it
+    fn repeat_once(&mut self, repetition: &Repetition<'a>, count: usize, position: usize, continuation: &mut dyn FnMut(&mut Matcher<'a>, usize) -> bool) -> bool {
+        // 2. Let d be a new MatcherContinuation with parameters (y) that captures m, min, max, greedy, x, c, parenIndex, and parenCount and performs the following steps when called:
+        //     a. If min = 0 and y.[[EndIndex]] = x.[[EndIndex]], return failure.
+        //     d. Return RepeatMatcher(m, min2, max2, greedy, y, c, parenIndex, parenCount).
+        // 5-9. Let xr be the MatchState (Input, e, cap) where the captures of parenIndex + 1 to parenIndex + parenCount are undefined
+        let groups = repetition.first_group + 1..repetition.first_group + 1 + repetition.group_count;
+        let saved = self.captures[groups.clone()].to_vec();
+        for index in groups.clone() {
+            self.captures[index] = None;
+        }
+        let matched = self.match_node(repetition.node, position, &mut |matcher: &mut Matcher<'a>, end: usize| {
+            if count >= repetition.min && end == position {
+                return false;
+            }
+            return matcher.repeat(repetition, count + 1, end, continuation);
+        });
+        if !matched {
+            self.captures.splice(groups, saved);
+        }
+        return matched;
+    }
+
+    // The RepeatMatcher of an atom that matches one code unit, the positions the repetitions can end at are tried in order
+    fn repeat_code_unit(&mut self, node: &'a Node, min: usize, max: Option<usize>, greedy: bool, position: usize, continuation: &mut dyn FnMut(&mut Matcher<'a>, usize) -> bool) -> bool {
+        let matches_at = |matcher: &Matcher<'a>, index: usize| index < matcher.input.len() && matcher.matches_code_unit(node, matcher.input[index]);
+        if greedy {
+            let mut count = 0;
+            while max.map_or(true, |max| count < max) && matches_at(self, position + count) {
+                count += 1;
+            }
+            if count < min {
+                return false;
+            }
+            for count in (min..=count).rev() {
+                if continuation(self, position + count) {
+                    return true;
+                }
+                if self.overflow {
+                    return false;
+                }
+            }
+            return false;
+        }
+        let mut count = 0;
+        while count < min {
+            if !matches_at(self, position + count) {
+                return false;
+            }
+            count += 1;
+        }
+        loop {
+            if continuation(self, position + count) {
+                return true;
+            }
+            if self.overflow || max == Some(count) || !matches_at(self, position + count) {
+                return false;
+            }
+            count += 1;
+        }
+    }
+
+    // Whether the atom of a single code unit matches code_unit
+    fn matches_code_unit(&self, node: &Node, code_unit: u16) -> bool {
+        return match node {
+            Node::CodeUnit(expected) => self.code_units_equal(*expected, code_unit),
+            Node::Any => self.program.dot_all || !is_line_terminator(code_unit),
+            Node::Class(class) => self.class_matches(class, code_unit) != class.negated,
+            _ => false
+        };
+    }
+
+    fn code_units_equal(&self, a: u16, b: u16) -> bool {
+        if self.program.ignore_case {
+            return canonicalize(a) == canonicalize(b);
+        }
+        return a == b;
+    }
+
+    // https://tc39.es/ecma262/#sec-runtime-semantics-charactersetmatcher-rer-a-invert-direction
+    // With the i flag a range matches a code unit when some code unit in it has the same canonical value
+    // TODO: Only the code unit itself, its canonical value and its lowercase are looked for in a range
+    fn class_matches(&self, class: &CharacterClass, code_unit: u16) -> bool {
+        return class.items.iter().any(|item| match item {
+            ClassItem::Range(start, end) => {
+                if (*start..=*end).contains(&code_unit) {
+                    return true;
+                }
+                if !self.program.ignore_case {
+                    return false;
+                }
+                let canonical = canonicalize(code_unit);
+                let lowercase = char::from_u32(code_unit as u32).and_then(|character| {
+                    let mut lowercase = character.to_lowercase();
+                    match (lowercase.next(), lowercase.next()) {
+                        (Some(lower), None) if (lower as u32) <= 0xFFFF => Some(lower as u16),
+                        _ => None
+                    }
+                });
+                (*start..=*end).contains(&canonical) || lowercase.is_some_and(|lowercase| (*start..=*end).contains(&lowercase))
+            },
+            ClassItem::Digit(negated) => (0x30..=0x39).contains(&code_unit) != *negated,
+            ClassItem::Space(negated) => is_white_space(code_unit) != *negated,
+            ClassItem::Word(negated) => is_word_character(code_unit) != *negated,
+        });
+    }
+}
+
+// The RepeatMatcher parameters of a quantified atom that is not a single code unit
+struct Repetition<'a> {
+    node: &'a Node,
+    min: usize,
+    max: Option<usize>,
+    greedy: bool,
+    first_group: usize,
+    group_count: usize,
+}
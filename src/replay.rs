@@ -0,0 +1,246 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::html_document_parser::ParserOptions;
+use crate::node::DocumentMode;
+use crate::source_location::SourcePosition;
+use crate::tokenizer::Tokenizer;
+use crate::trace::{TraceEvent, TraceRecord};
+use crate::tree_limits::TreeLimits;
+
+// A parse session is everything needed to parse a document again the same way: its input, the options it was parsed with,
+// and what the tokenizer and tree builder did with it, so a bug report can attach one file that reproduces the parse, e.g.
+//     let session = ParseSession::record(html.into_bytes(), &ParserOptions::default());
+//     std::fs::write("session.json", session.to_json())?;
+// and later, or with another build of the engine:
+//     let replay = ParseSession::from_json(&std::fs::read_to_string("session.json")?)?.replay();
+//     if let Some(divergence) = &replay.divergence { println!("{}", divergence); }
+// Replaying parses the input again with the recorded options, and compares every tokenizer state switch, token, insertion mode switch and parse error
+// with the recorded ones, the first that differs is where the two builds of the engine part
+// The times of the trace are left out of the session, as they are never the same twice
+// TODO: Scripts can make a parse depend on more than its input (e.g. Date.now() given to document.write), a session recorded with scripting enabled
+// only replays the same way when its scripts do not
+
+// The version of the session format, a session of another version is refused rather than replayed wrongly
+const SESSION_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParseSession {
+    pub version: u32,
+    // The version of the engine that recorded the session, for the reader of a bug report
+    pub engine_version: String,
+    // The input as text when it is UTF-8, and as hexadecimal bytes otherwise so it is replayed byte for byte
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_hex: Option<String>,
+    pub options: SessionOptions,
+    pub events: Vec<SessionEvent>,
+    pub parse_errors: Vec<SessionParseError>,
+}
+
+// The ParserOptions that change how a document is parsed, printing parse errors does not
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionOptions {
+    pub scripting_enabled: bool,
+    pub iframe_srcdoc_document: bool,
+    pub fragment_context: Option<String>,
+    // "no-quirks", "quirks" or "limited-quirks"
+    pub quirks_mode_override: Option<String>,
+    // Sessions recorded before the input could be normalized leave it out
+    #[serde(default)]
+    pub normalize_unicode: bool,
+}
+
+// A record of the trace without its time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionEvent {
+    #[serde(flatten)]
+    pub event: TraceEvent,
+    pub position: Option<SourcePosition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionParseError {
+    pub message: String,
+    pub position: Option<SourcePosition>,
+}
+
+// The first thing the replay did differently from the recorded session, None on either side when one of them ended first
+#[derive(Debug, Clone, PartialEq)]
+pub enum Divergence {
+    Event { index: usize, expected: Option<SessionEvent>, actual: Option<SessionEvent> },
+    ParseError { index: usize, expected: Option<SessionParseError>, actual: Option<SessionParseError> },
+}
+
+// The document parsed again, and where the parse differed from the recorded one, None when it did not
+pub struct Replay {
+    pub tokenizer: Tokenizer,
+    pub divergence: Option<Divergence>,
+}
+
+impl ParseSession {
+    // Parses bytes with options and records the session, parse errors are not printed whatever the options say
+    pub fn record(bytes: Vec<u8>, options: &ParserOptions) -> ParseSession {
+        let session_options = SessionOptions::from_parser_options(options);
+        let (input, input_hex) = match String::from_utf8(bytes) {
+            Ok(input) => (Some(input), None),
+            Err(error) => (None, Some(error.as_bytes().iter().map(|byte| format!("{:02x}", byte)).collect()))
+        };
+        let mut session = ParseSession {
+            version: SESSION_VERSION,
+            engine_version: env!("CARGO_PKG_VERSION").to_string(),
+            input,
+            input_hex,
+            options: session_options,
+            events: Vec::new(),
+            parse_errors: Vec::new(),
+        };
+        let (tokenizer, trace) = session.parse();
+        session.events = events(&trace);
+        session.parse_errors = parse_errors(&tokenizer);
+        return session;
+    }
+
+    // The session as pretty printed JSON, to attach to a bug report
+    pub fn to_json(&self) -> String {
+        return serde_json::to_string_pretty(self).unwrap_or_default();
+    }
+
+    pub fn from_json(json: &str) -> Result<ParseSession, String> {
+        let session: ParseSession = serde_json::from_str(json).map_err(|error| format!("The session is not valid: {}", error))?;
+        if session.version != SESSION_VERSION {
+            return Err(format!("The session is of version {}, only version {} sessions can be replayed", session.version, SESSION_VERSION));
+        }
+        match (&session.input, &session.input_hex) {
+            (Some(_), None) => (),
+            (None, Some(input_hex)) if decode_hex(input_hex).is_some() => (),
+            _ => return Err("The session needs either an input or an input_hex of hexadecimal bytes".to_string())
+        }
+        return Ok(session);
+    }
+
+    // The input the session was recorded with
+    pub fn input(&self) -> Vec<u8> {
+        return match (&self.input, &self.input_hex) {
+            (Some(input), _) => input.as_bytes().to_vec(),
+            (None, Some(input_hex)) => decode_hex(input_hex).unwrap_or_default(),
+            (None, None) => Vec::new()
+        };
+    }
+
+    // Parses the input again with the recorded options, and compares what happened with the recorded session
+    pub fn replay(&self) -> Replay {
+        let (tokenizer, trace) = self.parse();
+        let actual_events = events(&trace);
+        let actual_parse_errors = parse_errors(&tokenizer);
+        let divergence = first_difference(&self.events, &actual_events).map(|(index, expected, actual)| Divergence::Event { index, expected, actual })
+            .or_else(|| first_difference(&self.parse_errors, &actual_parse_errors).map(|(index, expected, actual)| Divergence::ParseError { index, expected, actual }));
+        return Replay { tokenizer, divergence };
+    }
+
+    // The document parsed from the input, and the records of its trace
+    fn parse(&self) -> (Tokenizer, Vec<TraceRecord>) {
+        let mut tokenizer = Tokenizer::from_bytes(self.input(), self.options.to_parser_options());
+        let trace = tokenizer.enable_trace();
+        tokenizer.run();
+        let records = trace.borrow().records.clone();
+        return (tokenizer, records);
+    }
+}
+
+impl SessionOptions {
+    fn from_parser_options(options: &ParserOptions) -> SessionOptions {
+        let quirks_mode_override = options.quirks_mode_override.map(|mode| match mode {
+            DocumentMode::NoQuirks => "no-quirks",
+            DocumentMode::Quirks => "quirks",
+            DocumentMode::LimitedQuirks => "limited-quirks"
+        }.to_string());
+        return SessionOptions {
+            scripting_enabled: options.scripting_enabled,
+            iframe_srcdoc_document: options.iframe_srcdoc_document,
+            fragment_context: options.fragment_context.clone(),
+            quirks_mode_override,
+            normalize_unicode: options.normalize_unicode,
+        };
+    }
+
+    // A mode that is not known is left to the DOCTYPE
+    pub fn to_parser_options(&self) -> ParserOptions {
+        let quirks_mode_override = match self.quirks_mode_override.as_deref() {
+            Some("no-quirks") => Some(DocumentMode::NoQuirks),
+            Some("quirks") => Some(DocumentMode::Quirks),
+            Some("limited-quirks") => Some(DocumentMode::LimitedQuirks),
+            _ => None
+        };
+        return ParserOptions {
+            scripting_enabled: self.scripting_enabled,
+            iframe_srcdoc_document: self.iframe_srcdoc_document,
+            fragment_context: self.fragment_context.clone(),
+            quirks_mode_override,
+            print_parse_errors: false,
+            normalize_unicode: self.normalize_unicode,
+            tree_limits: TreeLimits::default(),
+        };
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return None;
+    }
+    return (0..hex.len()).step_by(2).map(|index| u8::from_str_radix(&hex[index..index + 2], 16).ok()).collect();
+}
+
+// The steps of the tree builder are left out, every token and insertion mode switch they are made of is already in the session
+fn events(records: &[TraceRecord]) -> Vec<SessionEvent> {
+    return records.iter().filter(|record| !matches!(record.event, TraceEvent::TreeBuilderStep { .. })).map(|record| SessionEvent { event: record.event.clone(), position: record.position }).collect();
+}
+
+fn parse_errors(tokenizer: &Tokenizer) -> Vec<SessionParseError> {
+    return tokenizer.parse_errors().iter().zip(tokenizer.parse_error_positions())
+        .map(|(message, position)| SessionParseError { message: message.clone(), position: *position }).collect();
+}
+
+// The index of the first item that differs, with the item on each side, None when every item is the same
+fn first_difference<T: PartialEq + Clone>(expected: &[T], actual: &[T]) -> Option<(usize, Option<T>, Option<T>)> {
+    for index in 0..expected.len().max(actual.len()) {
+        let (expected, actual) = (expected.get(index), actual.get(index));
+        if expected != actual {
+            return Some((index, expected.cloned(), actual.cloned()));
+        }
+    }
+    return None;
+}
+
+impl fmt::Display for SessionEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let position = self.position.map(|position| position.to_string()).unwrap_or_else(|| "-".to_string());
+        match &self.event {
+            TraceEvent::TokenizerState { from, to } => write!(f, "{} tokenizer state {} -> {}", position, from, to),
+            TraceEvent::Token { description } => write!(f, "{} token {}", position, description),
+            TraceEvent::InsertionMode { from, to } => write!(f, "{} insertion mode {} -> {}", position, from, to),
+            TraceEvent::TreeBuilderStep { insertion_mode, token, open_elements } => write!(f, "{} tree builder {}: {} [{}]", position, insertion_mode, token, open_elements.join(" "))
+        }
+    }
+}
+
+impl fmt::Display for SessionParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.position {
+            Some(position) => write!(f, "{} {}", position, self.message),
+            None => write!(f, "{}", self.message)
+        }
+    }
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let side = |item: Option<String>| item.unwrap_or_else(|| "nothing, it ended".to_string());
+        match self {
+            Divergence::Event { index, expected, actual } => write!(f, "Event {} differs\n  recorded: {}\n  replayed: {}",
+                index, side(expected.as_ref().map(|event| event.to_string())), side(actual.as_ref().map(|event| event.to_string()))),
+            Divergence::ParseError { index, expected, actual } => write!(f, "Parse error {} differs\n  recorded: {}\n  replayed: {}",
+                index, side(expected.as_ref().map(|parse_error| parse_error.to_string())), side(actual.as_ref().map(|parse_error| parse_error.to_string())))
+        }
+    }
+}
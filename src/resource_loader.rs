@@ -0,0 +1,321 @@
+// Loads the resources scripts ask for, embedders provide their own loader to decide where they come from
+// TODO: Redirects are not followed
+// Response bodies are decoded from the gzip and deflate content codings, and from br with the brotli feature, before they are handed on
+
+use std::collections::HashMap;
+#[cfg(feature = "net")]
+use std::io::{Read, Write};
+#[cfg(feature = "net")]
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+#[cfg(feature = "net")]
+use crate::tls;
+use crate::url::Url;
+
+// https://fetch.spec.whatwg.org/#concept-response
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub url: String,
+    pub status: u16,
+    pub status_message: String,
+    pub header_list: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn new(url: &str, status: u16, body: &[u8]) -> Response {
+        Response { url: url.to_string(), status, status_message: String::new(), header_list: Vec::new(), body: body.to_vec() }
+    }
+}
+
+// An Err is a network error, the message says why the resource could not be loaded
+pub trait ResourceLoader {
+    fn load(&mut self, url: &Url) -> Result<Response, String>;
+
+    // A load that sends extra request headers, e.g. the validators of a conditional request
+    // Loaders that can not send headers load the URL as usual, which answers with the full response
+    fn load_with_headers(&mut self, url: &Url, _header_list: &[(String, String)]) -> Result<Response, String> {
+        return self.load(url);
+    }
+}
+
+// Loads http: and https: URLs over the network and file: URLs from the file system
+// Each is only loaded when the crate is built with the feature for it, net and fs, and is a network error otherwise, e.g. in a WebAssembly build
+pub struct DefaultResourceLoader {
+    // Whether the certificates of https: servers are verified, turned off for servers with self-signed certificates
+    pub verify_certificates: bool,
+    // How long connecting to a server may take, and then how long each read or write may wait
+    pub timeout: Duration,
+}
+
+impl Default for DefaultResourceLoader {
+    fn default() -> Self {
+        DefaultResourceLoader { verify_certificates: true, timeout: Duration::from_secs(30) }
+    }
+}
+
+impl ResourceLoader for DefaultResourceLoader {
+    fn load(&mut self, url: &Url) -> Result<Response, String> {
+        return self.load_with_headers(url, &[]);
+    }
+
+    #[cfg_attr(not(feature = "net"), allow(unused_variables))]
+    fn load_with_headers(&mut self, url: &Url, header_list: &[(String, String)]) -> Result<Response, String> {
+        match url.scheme.as_str() {
+            #[cfg(feature = "net")]
+            "http" | "https" => self.http_get(url, header_list),
+            #[cfg(feature = "fs")]
+            "file" => {
+                let path = url.to_file_path().unwrap();
+                match std::fs::read(&path) {
+                    Ok(body) => Ok(Response::new(&url.href(), 200, &body)),
+                    Err(error) => Err(format!("{}: {}", path, error))
+                }
+            },
+            scheme => Err(format!("{}: is not supported", scheme))
+        }
+    }
+}
+
+// Answers with the responses it has been given, so scripts can be run without a network or file system
+// Loading a URL without a response is a network error
+#[derive(Default)]
+pub struct StubResourceLoader {
+    responses: HashMap<String, Response>,
+}
+
+impl StubResourceLoader {
+    pub fn new() -> StubResourceLoader {
+        StubResourceLoader { responses: HashMap::new() }
+    }
+
+    // The response is keyed by its serialized URL, so it matches however the URL is written when it is fetched
+    pub fn add_response(&mut self, response: Response) {
+        let url = Url::parse(&response.url, None).map(|url| url.href()).unwrap_or(response.url.clone());
+        self.responses.insert(url, response);
+    }
+}
+
+impl ResourceLoader for StubResourceLoader {
+    fn load(&mut self, url: &Url) -> Result<Response, String> {
+        match self.responses.get(&url.href()) {
+            Some(response) => Ok(response.clone()),
+            None => Err(format!("No response for {}", url))
+        }
+    }
+}
+
+#[cfg(feature = "net")]
+impl DefaultResourceLoader {
+    // A GET request over HTTP/1.1 with the given headers, over TLS for https: URLs, the connection is closed by the server after the response
+    fn http_get(&self, url: &Url, header_list: &[(String, String)]) -> Result<Response, String> {
+        // The request target is the path and query, the fragment is never sent
+        let mut target = url.serialize_path();
+        if let Some(query) = &url.query {
+            target.push('?');
+            target.push_str(query);
+        }
+        let is_https = url.scheme == "https";
+        let hostname = url.hostname().trim_start_matches('[').trim_end_matches(']').to_string();
+        let address = format!("{}:{}", hostname, url.port.unwrap_or(if is_https { 443 } else { 80 }));
+
+        // Each address the host resolves to is tried in turn until one accepts the connection in time
+        let mut connect_error = format!("{}: The host has no addresses", address);
+        let mut stream = None;
+        for socket_address in address.to_socket_addrs().map_err(|error| format!("{}: {}", address, error))? {
+            match TcpStream::connect_timeout(&socket_address, self.timeout) {
+                Ok(connected) => {
+                    stream = Some(connected);
+                    break;
+                },
+                Err(error) => connect_error = format!("{}: {}", address, error)
+            }
+        }
+        let stream = stream.ok_or(connect_error)?;
+        stream.set_read_timeout(Some(self.timeout)).map_err(|error| error.to_string())?;
+        stream.set_write_timeout(Some(self.timeout)).map_err(|error| error.to_string())?;
+
+        let mut request = format!("GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: web_engine\r\nAccept: */*\r\nAccept-Encoding: {}\r\nConnection: close\r\n", target, url.host(), ACCEPT_ENCODING);
+        for (name, value) in header_list {
+            request.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        request.push_str("\r\n");
+
+        let bytes = if is_https {
+            let mut stream = tls::connect(stream, &hostname, self.verify_certificates)?;
+            exchange(&mut stream, &request)?
+        } else {
+            let mut stream = stream;
+            exchange(&mut stream, &request)?
+        };
+        return parse_http_response(&url.href(), &bytes);
+    }
+}
+
+// A response is not read past this size, which leaves room for the head and chunk sizes around the largest body
+#[cfg(feature = "net")]
+const MAX_RESPONSE_SIZE: u64 = MAX_DECODED_BODY_SIZE + 1024 * 1024;
+
+// Writes the request and reads the response until the server closes the connection
+#[cfg(feature = "net")]
+fn exchange(stream: &mut (impl Read + Write), request: &str) -> Result<Vec<u8>, String> {
+    stream.write_all(request.as_bytes()).map_err(|error| error.to_string())?;
+    let mut bytes = Vec::new();
+    match (&mut *stream).take(MAX_RESPONSE_SIZE + 1).read_to_end(&mut bytes) {
+        Ok(_) if bytes.len() as u64 > MAX_RESPONSE_SIZE => return Err(format!("The response is larger than {} bytes", MAX_RESPONSE_SIZE)),
+        Ok(_) => return Ok(bytes),
+        // Many servers close a TLS connection without a close_notify alert, which is only a problem when the response is cut short
+        // The response is kept when its end can be told from it, otherwise it could have been cut short by an attacker
+        Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof && is_complete_response(&bytes) => return Ok(bytes),
+        Err(error) => return Err(error.to_string())
+    }
+}
+
+// https://httpwg.org/specs/rfc9112.html#message.body.length
+// Whether the response has all of its body, from its Content-Length or the last chunk of a chunked body
+// A body that only ends when the connection is closed can not be told from one that was cut short
+#[cfg(feature = "net")]
+fn is_complete_response(bytes: &[u8]) -> bool {
+    let (status, _, header_list, body_start) = match parse_head(bytes) {
+        Ok(head) => head,
+        Err(_) => return false
+    };
+    // Informational, 204 No Content and 304 Not Modified responses have no body
+    if status < 200 || status == 204 || status == 304 {
+        return true;
+    }
+    let body = &bytes[body_start..];
+    if header_list.iter().any(|(name, value)| name == "transfer-encoding" && value.eq_ignore_ascii_case("chunked")) {
+        return decode_chunked(body).is_ok();
+    }
+    return match header_list.iter().find(|(name, _)| name == "content-length").and_then(|(_, value)| value.parse::<usize>().ok()) {
+        Some(content_length) => body.len() >= content_length,
+        None => false
+    };
+}
+
+#[cfg(feature = "net")]
+fn parse_http_response(url: &str, bytes: &[u8]) -> Result<Response, String> {
+    let (status, status_message, header_list, body_start) = parse_head(bytes)?;
+    let mut body = bytes[body_start..].to_vec();
+    let is_chunked = header_list.iter().any(|(name, value)| name == "transfer-encoding" && value.eq_ignore_ascii_case("chunked"));
+    if is_chunked {
+        body = decode_chunked(&body)?;
+    }
+    // A 304 has no body to decode, and neither does the response to a HEAD request
+    if let Some((_, content_coding)) = header_list.iter().find(|(name, _)| name == "content-encoding") {
+        if !body.is_empty() {
+            body = decode_content_codings(content_coding, body)?;
+        }
+    }
+
+    return Ok(Response { url: url.to_string(), status, status_message, header_list, body });
+}
+
+// The status, status message and headers of a response, with the header names in lowercase, and where its body starts
+#[cfg(feature = "net")]
+fn parse_head(bytes: &[u8]) -> Result<(u16, String, Vec<(String, String)>, usize), String> {
+    let header_end = match bytes.windows(4).position(|window| window == b"\r\n\r\n") {
+        Some(header_end) => header_end,
+        None => return Err(String::from("The response ended before its headers"))
+    };
+    let head = String::from_utf8_lossy(&bytes[..header_end]);
+    let mut lines = head.split("\r\n");
+
+    // The status line, e.g. HTTP/1.1 404 Not Found
+    let status_line = lines.next().unwrap_or("");
+    let mut status_line = status_line.splitn(3, ' ');
+    let status = match (status_line.next(), status_line.next().and_then(|status| status.parse::<u16>().ok())) {
+        (Some(version), Some(status)) if version.starts_with("HTTP/") => status,
+        _ => return Err(String::from("The response has an invalid status line"))
+    };
+    let status_message = status_line.next().unwrap_or("").to_string();
+
+    let mut header_list = Vec::new();
+    for line in lines {
+        if let Some((name, value)) = line.split_once(':') {
+            header_list.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+
+    return Ok((status, status_message, header_list, header_end + 4));
+}
+
+// https://httpwg.org/specs/rfc9110.html#field.accept-encoding
+// The content codings the response body can be in, br only when the brotli feature is enabled
+#[cfg(all(feature = "net", feature = "brotli"))]
+const ACCEPT_ENCODING: &str = "gzip, deflate, br";
+#[cfg(all(feature = "net", not(feature = "brotli")))]
+const ACCEPT_ENCODING: &str = "gzip, deflate";
+
+// A body is not decoded past this size, as a small compressed body can decode to a huge one
+#[cfg(feature = "net")]
+const MAX_DECODED_BODY_SIZE: u64 = 64 * 1024 * 1024;
+
+// https://httpwg.org/specs/rfc9110.html#field.content-encoding
+// The codings are listed in the order they were applied, so they are undone from the last to the first
+#[cfg(feature = "net")]
+fn decode_content_codings(content_codings: &str, mut body: Vec<u8>) -> Result<Vec<u8>, String> {
+    for content_coding in content_codings.split(',').map(|content_coding| content_coding.trim().to_ascii_lowercase()).rev() {
+        body = match content_coding.as_str() {
+            "identity" | "" => body,
+            "gzip" | "x-gzip" => read_decoded(flate2::read::MultiGzDecoder::new(body.as_slice()), &content_coding)?,
+            // deflate is meant to be the zlib format, but some servers send a raw deflate stream without the zlib header
+            "deflate" => match read_decoded(flate2::read::ZlibDecoder::new(body.as_slice()), &content_coding) {
+                Ok(decoded) => decoded,
+                Err(_) => read_decoded(flate2::read::DeflateDecoder::new(body.as_slice()), &content_coding)?
+            },
+            #[cfg(feature = "brotli")]
+            "br" => read_decoded(brotli_decompressor::Decompressor::new(body.as_slice(), 4096), &content_coding)?,
+            _ => return Err(format!("The response body is in the {} content coding, which is not supported", content_coding))
+        };
+    }
+    return Ok(body);
+}
+
+#[cfg(feature = "net")]
+fn read_decoded(decoder: impl Read, content_coding: &str) -> Result<Vec<u8>, String> {
+    let mut decoded = Vec::new();
+    // One byte more than the maximum is read to find out whether the body is over it
+    decoder.take(MAX_DECODED_BODY_SIZE + 1).read_to_end(&mut decoded).map_err(|error| format!("The {} response body could not be decoded: {}", content_coding, error))?;
+    if decoded.len() as u64 > MAX_DECODED_BODY_SIZE {
+        return Err(format!("The {} response body decodes to more than {} bytes", content_coding, MAX_DECODED_BODY_SIZE));
+    }
+    return Ok(decoded);
+}
+
+// Each chunk is its size in hexadecimal on its own line followed by its data, a chunk of size 0 ends the body
+#[cfg(feature = "net")]
+fn decode_chunked(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    let mut position = 0;
+    loop {
+        let line_end = match bytes[position..].windows(2).position(|window| window == b"\r\n") {
+            Some(line_end) => position + line_end,
+            None => return Err(String::from("The response ended inside a chunk"))
+        };
+        let size_line = String::from_utf8_lossy(&bytes[position..line_end]);
+        let size = size_line.split(';').next().unwrap().trim();
+        let size = usize::from_str_radix(size, 16).map_err(|_| format!("Invalid chunk size {}", size))?;
+        if size == 0 {
+            return Ok(body);
+        }
+
+        let data_start = line_end + 2;
+        let data_end = match data_start.checked_add(size) {
+            Some(data_end) => data_end,
+            None => return Err(format!("Invalid chunk size {}", size))
+        };
+        if data_end > bytes.len() {
+            return Err(String::from("The response ended inside a chunk"));
+        }
+        body.extend_from_slice(&bytes[data_start..data_end]);
+        // The chunk data is followed by CRLF
+        match bytes.get(data_end..data_end + 2) {
+            Some(b"\r\n") => {},
+            Some(_) => return Err(String::from("A chunk is not followed by CRLF")),
+            None => return Err(String::from("The response ended inside a chunk"))
+        }
+        position = data_end + 2;
+    }
+}
@@ -0,0 +1,159 @@
+use std::time::Duration;
+
+// https://www.rfc-editor.org/rfc/rfc9309.html
+// The rules a site gives crawlers in its /robots.txt, so the crawler only loads the pages it is allowed to
+
+// https://www.rfc-editor.org/rfc/rfc9309.html#section-2.1
+// The rules of the user-agent lines that start the group, in the order they were written
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Group {
+    user_agents: Vec<String>,
+    rules: Vec<Rule>,
+    // Crawl-delay is not part of the protocol but many sites use it to ask for fewer requests
+    crawl_delay: Option<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    allow: bool,
+    path: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RobotsTxt {
+    groups: Vec<Group>,
+}
+
+impl RobotsTxt {
+    // Allows everything, e.g. when the site has no /robots.txt
+    pub fn allow_all() -> RobotsTxt {
+        RobotsTxt { groups: Vec::new() }
+    }
+
+    // https://www.rfc-editor.org/rfc/rfc9309.html#section-2.3.1.4
+    // Disallows everything, e.g. when /robots.txt could not be loaded because the server failed
+    pub fn disallow_all() -> RobotsTxt {
+        RobotsTxt { groups: vec![Group { user_agents: vec![String::from("*")], rules: vec![Rule { allow: false, path: String::from("/") }], crawl_delay: None }] }
+    }
+
+    // https://www.rfc-editor.org/rfc/rfc9309.html#section-2.2
+    // Lines that can not be parsed are ignored, as are the fields other than user-agent, allow, disallow and crawl-delay, e.g. sitemap
+    pub fn parse(text: &str) -> RobotsTxt {
+        let mut groups: Vec<Group> = Vec::new();
+        // Whether the last line was a user-agent line, consecutive user-agent lines start the same group
+        let mut in_user_agents = false;
+        for line in text.lines() {
+            let line = match line.find('#') {
+                Some(comment_start) => &line[..comment_start],
+                None => line
+            };
+            let (field, value) = match line.split_once(':') {
+                Some((field, value)) => (field.trim().to_ascii_lowercase(), value.trim()),
+                None => continue
+            };
+
+            match field.as_str() {
+                "user-agent" => {
+                    if !in_user_agents {
+                        groups.push(Group::default());
+                    }
+                    in_user_agents = true;
+                    groups.last_mut().unwrap().user_agents.push(value.to_ascii_lowercase());
+                },
+                // Rules before the first user-agent line are not in a group and are ignored
+                "allow" | "disallow" => {
+                    in_user_agents = false;
+                    // A disallow rule with an empty path disallows nothing
+                    if let (Some(group), false) = (groups.last_mut(), value.is_empty()) {
+                        group.rules.push(Rule { allow: field == "allow", path: value.to_string() });
+                    }
+                },
+                "crawl-delay" => {
+                    in_user_agents = false;
+                    if let (Some(group), Ok(seconds)) = (groups.last_mut(), value.parse::<f64>()) {
+                        if seconds.is_finite() && seconds >= 0.0 {
+                            group.crawl_delay = Some(Duration::from_secs_f64(seconds));
+                        }
+                    }
+                },
+                _ => ()
+            }
+        }
+        return RobotsTxt { groups };
+    }
+
+    // https://www.rfc-editor.org/rfc/rfc9309.html#section-2.2.1
+    // The groups whose user-agent matches the product token of the crawler case-insensitively, combined when there is more than one,
+    // or the groups of * when there are none
+    fn matching_groups(&self, user_agent: &str) -> Vec<&Group> {
+        let user_agent = user_agent.to_ascii_lowercase();
+        let matching: Vec<&Group> = self.groups.iter().filter(|group| group.user_agents.contains(&user_agent)).collect();
+        if !matching.is_empty() {
+            return matching;
+        }
+        return self.groups.iter().filter(|group| group.user_agents.iter().any(|group_user_agent| group_user_agent == "*")).collect();
+    }
+
+    // https://www.rfc-editor.org/rfc/rfc9309.html#section-2.2.2
+    // Whether the crawler with the product token user_agent may load path, which is the path and query of the URL
+    // The most specific rule, the one with the longest path, is used, and an allow rule wins over a disallow rule that is as specific
+    pub fn is_allowed(&self, user_agent: &str, path: &str) -> bool {
+        // The /robots.txt URI is implicitly allowed.
+        if path == "/robots.txt" {
+            return true;
+        }
+
+        let mut matched: Option<&Rule> = None;
+        for rule in self.matching_groups(user_agent).into_iter().flat_map(|group| &group.rules) {
+            if !path_matches(&rule.path, path) {
+                continue;
+            }
+            let more_specific = match matched {
+                Some(matched) => rule.path.len() > matched.path.len() || (rule.path.len() == matched.path.len() && rule.allow),
+                None => true
+            };
+            if more_specific {
+                matched = Some(rule);
+            }
+        }
+        return matched.map(|rule| rule.allow).unwrap_or(true);
+    }
+
+    // The longest crawl delay of the groups that apply to the crawler, if any
+    pub fn crawl_delay(&self, user_agent: &str) -> Option<Duration> {
+        return self.matching_groups(user_agent).into_iter().filter_map(|group| group.crawl_delay).max();
+    }
+}
+
+// https://www.rfc-editor.org/rfc/rfc9309.html#section-2.2.3
+// A rule path matches a path it is a prefix of, where * matches any sequence of characters and a $ at the end matches the end of the path
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false)
+    };
+
+    let mut parts = pattern.split('*');
+    // The part before the first * has to be at the start of the path
+    let first = parts.next().unwrap_or("");
+    if !path.starts_with(first) {
+        return false;
+    }
+    let mut position = first.len();
+    let rest: Vec<&str> = parts.collect();
+    if rest.is_empty() {
+        return !anchored || position == path.len();
+    }
+
+    // Each part after a * is matched as early as it can be, except the last part of an anchored pattern, which has to end the path
+    for (index, part) in rest.iter().enumerate() {
+        if anchored && index == rest.len() - 1 {
+            return path.len() >= position + part.len() && path.ends_with(part);
+        }
+        match path[position..].find(part) {
+            Some(offset) => position += offset + part.len(),
+            None => return false
+        }
+    }
+    return true;
+}
@@ -0,0 +1,293 @@
+use crate::atom::Atom;
+use crate::html_token::{HtmlToken, HtmlTokenType};
+use crate::selector::{parse_selector_list, SelectorElement, SelectorList};
+use crate::serializer::is_void_element;
+use crate::token_sink::{TokenSink, TokenSinkResult};
+use crate::tokenizer::Tokenizer;
+
+// Streaming extraction from HTML, without building a document
+// A handler is called as the tokens go by for each element that starts and ends and for each run of text, with the path of open elements from the outermost one to where it is,
+// so only the elements that are open are held in memory at a time, however big the document is:
+//     let links = sax::extract_attribute(html, "html > body a[href]", "href")?;
+// The path is matched against with selectors, as an ElementPathMatcher, so "nav a" matches a link anywhere inside a nav
+// The path is made of the tags as they are written, there is no tree builder:
+// TODO: Elements the tree builder would insert (e.g. html and body when the tags are left out) are not on the path,
+//       and elements it would close (e.g. a p when another p starts) stay open until their end tag or the end tag of an element they are in
+// TODO: The tokenizer keeps the input that has been fed to it, so it is only the document that is not held in memory
+pub trait SaxHandler {
+    // A start tag, the path ends with the element it starts
+    fn start_element(&mut self, _path: &ElementPath) {}
+
+    // The end of an element, the path still ends with the element that ends
+    // Void elements (e.g. img) and elements whose end tag is missing end as well, the former right after they start
+    fn end_element(&mut self, _path: &ElementPath) {}
+
+    // Character data, in the element the path ends with
+    fn text(&mut self, _text: &str, _path: &ElementPath) {}
+
+    fn comment(&mut self, _data: &str, _path: &ElementPath) {}
+
+    // The end of the input, after every open element has ended
+    fn end_document(&mut self) {}
+}
+
+// An open element, with the attributes of its start tag
+pub struct SaxElement {
+    pub local_name: Atom,
+    pub attributes: Vec<(Atom, String)>,
+}
+
+impl SaxElement {
+    pub fn get_attribute(&self, qualified_name: &str) -> Option<&str> {
+        return self.attributes.iter().find(|(name, _)| name == qualified_name).map(|(_, value)| value.as_str());
+    }
+}
+
+// The open elements, from the outermost one to the innermost one
+#[derive(Default)]
+pub struct ElementPath {
+    elements: Vec<SaxElement>,
+}
+
+impl ElementPath {
+    pub fn elements(&self) -> &[SaxElement] {
+        return &self.elements;
+    }
+
+    // The innermost open element, None outside of any element
+    pub fn current(&self) -> Option<&SaxElement> {
+        return self.elements.last();
+    }
+
+    // The number of open elements
+    pub fn depth(&self) -> usize {
+        return self.elements.len();
+    }
+
+    // Whether the innermost open element matches the path
+    pub fn matches(&self, matcher: &ElementPathMatcher) -> bool {
+        return matcher.matches(self);
+    }
+}
+
+// A path of elements written as selectors, e.g. "html > body a[href]" or "ul.results > li"
+// TODO: Only the selectors that look at an element and its ancestors can be matched, so a selector with a sibling combinator is not a path
+pub struct ElementPathMatcher {
+    selector_list: SelectorList,
+}
+
+impl ElementPathMatcher {
+    // Returns None if the path is not a valid selector list, or has a selector that looks at the siblings of an element
+    pub fn parse(path: &str) -> Option<ElementPathMatcher> {
+        let selector_list = parse_selector_list(path)?;
+        if !selector_list.only_looks_at_ancestors() {
+            return None;
+        }
+        return Some(ElementPathMatcher { selector_list });
+    }
+
+    // Whether the innermost open element matches the path, false outside of any element
+    pub fn matches(&self, path: &ElementPath) -> bool {
+        if path.elements.is_empty() {
+            return false;
+        }
+        return self.selector_list.matches(&PathElement { elements: &path.elements });
+    }
+}
+
+// The last of the elements, whose parent is the one before it
+#[derive(Clone, Copy)]
+struct PathElement<'a> {
+    elements: &'a [SaxElement],
+}
+
+impl<'a> PathElement<'a> {
+    fn element(&self) -> &'a SaxElement {
+        return &self.elements[self.elements.len() - 1];
+    }
+}
+
+impl<'a> SelectorElement for PathElement<'a> {
+    fn is_element(&self) -> bool {
+        return true;
+    }
+
+    fn has_local_name(&self, local_name: &str) -> bool {
+        return self.element().local_name.eq_ignore_ascii_case(local_name);
+    }
+
+    fn attribute_matches(&self, name: &str, predicate: &dyn Fn(&str) -> bool) -> bool {
+        return self.element().get_attribute(name).map(predicate).unwrap_or(false);
+    }
+
+    fn parent_element(&self) -> Option<PathElement<'a>> {
+        if self.elements.len() < 2 {
+            return None;
+        }
+        return Some(PathElement { elements: &self.elements[..self.elements.len() - 1] });
+    }
+
+    // The elements before an open element are closed, so they are not in the path, see ElementPathMatcher::parse
+    fn previous_element_sibling(&self) -> Option<PathElement<'a>> {
+        return None;
+    }
+
+    fn is_root(&self) -> bool {
+        return self.elements.len() == 1;
+    }
+}
+
+// Keeps the path of open elements from the tokens and calls the handler
+struct SaxSink<H: SaxHandler> {
+    handler: H,
+    path: ElementPath,
+}
+
+impl<H: SaxHandler> SaxSink<H> {
+    fn end_innermost_element(&mut self) {
+        self.handler.end_element(&self.path);
+        self.path.elements.pop();
+    }
+}
+
+impl<H: SaxHandler> TokenSink for SaxSink<H> {
+    fn handle_token(&mut self, html_token: &HtmlToken) -> TokenSinkResult {
+        match html_token.token_type {
+            HtmlTokenType::StartTag => {
+                let local_name = html_token.tag_name.to_atom();
+                let attributes = html_token.attributes.iter().map(|attribute| (attribute.name.to_atom(), attribute.value.to_string())).collect();
+                let void = is_void_element(&local_name) || html_token.self_closing;
+                self.path.elements.push(SaxElement { local_name, attributes });
+                self.handler.start_element(&self.path);
+                if void {
+                    self.end_innermost_element();
+                }
+            },
+            HtmlTokenType::EndTag => {
+                // An end tag closes the innermost open element with its name, and the elements in it that were left open
+                // An end tag for an element that is not open is ignored, as the tree builder ignores one
+                if let Some(index) = self.path.elements.iter().rposition(|element| element.local_name == *html_token.tag_name) {
+                    while self.path.elements.len() > index {
+                        self.end_innermost_element();
+                    }
+                }
+            },
+            HtmlTokenType::Character => self.handler.text(&html_token.data, &self.path),
+            HtmlTokenType::Comment => self.handler.comment(&html_token.data, &self.path),
+            HtmlTokenType::EndOfFile => {
+                while !self.path.elements.is_empty() {
+                    self.end_innermost_element();
+                }
+            },
+            HtmlTokenType::DocType => {}
+        }
+
+        // The contents of elements like script and textarea are text, as they are in the body of a document
+        return TokenSinkResult::in_body(html_token, false);
+    }
+
+    fn end(&mut self) {
+        self.handler.end_document();
+    }
+}
+
+// Calls a handler for HTML that is given in chunks with feed as it arrives, finish is called after the last chunk
+pub struct SaxParser<H: SaxHandler> {
+    tokenizer: Tokenizer<SaxSink<H>>,
+}
+
+impl<H: SaxHandler> SaxParser<H> {
+    pub fn new(handler: H) -> SaxParser<H> {
+        return SaxParser { tokenizer: Tokenizer::with_sink(String::new(), SaxSink { handler, path: ElementPath::default() }) };
+    }
+
+    pub fn feed(&mut self, chunk: &str) {
+        self.tokenizer.feed(chunk);
+    }
+
+    // The handler, once it has been called for the rest of the input
+    pub fn finish(mut self) -> H {
+        self.tokenizer.finish();
+        return self.tokenizer.into_sink().handler;
+    }
+}
+
+// Calls a handler for the whole of the HTML and returns it
+pub fn parse<H: SaxHandler>(html: String, handler: H) -> H {
+    let mut tokenizer = Tokenizer::with_sink(html, SaxSink { handler, path: ElementPath::default() });
+    tokenizer.run();
+    return tokenizer.into_sink().handler;
+}
+
+// Collects the text of each element the path matches, elements that match inside one that matched are part of its text
+pub struct TextExtractor {
+    matcher: ElementPathMatcher,
+    // The depth of the element whose text is being collected and the text so far
+    current: Option<(usize, String)>,
+    pub texts: Vec<String>,
+}
+
+impl TextExtractor {
+    pub fn new(matcher: ElementPathMatcher) -> TextExtractor {
+        return TextExtractor { matcher, current: None, texts: Vec::new() };
+    }
+}
+
+impl SaxHandler for TextExtractor {
+    fn start_element(&mut self, path: &ElementPath) {
+        if self.current.is_none() && self.matcher.matches(path) {
+            self.current = Some((path.depth(), String::new()));
+        }
+    }
+
+    fn end_element(&mut self, path: &ElementPath) {
+        if matches!(self.current, Some((depth, _)) if depth == path.depth()) {
+            let (_, text) = self.current.take().unwrap();
+            self.texts.push(text);
+        }
+    }
+
+    fn text(&mut self, text: &str, _path: &ElementPath) {
+        if let Some((_, current_text)) = &mut self.current {
+            current_text.push_str(text);
+        }
+    }
+}
+
+// Collects the value of an attribute of each element the path matches, elements that do not have the attribute are skipped
+pub struct AttributeExtractor {
+    matcher: ElementPathMatcher,
+    name: String,
+    pub values: Vec<String>,
+}
+
+impl AttributeExtractor {
+    pub fn new(matcher: ElementPathMatcher, name: &str) -> AttributeExtractor {
+        return AttributeExtractor { matcher, name: name.to_string(), values: Vec::new() };
+    }
+}
+
+impl SaxHandler for AttributeExtractor {
+    fn start_element(&mut self, path: &ElementPath) {
+        if !self.matcher.matches(path) {
+            return;
+        }
+        if let Some(value) = path.current().and_then(|element| element.get_attribute(&self.name)) {
+            self.values.push(value.to_string());
+        }
+    }
+}
+
+fn parse_path(path: &str) -> Result<ElementPathMatcher, String> {
+    return ElementPathMatcher::parse(path).ok_or(format!("'{}' is not a valid element path", path));
+}
+
+// The text of each element the path matches, in the order they start
+pub fn extract_text(html: String, path: &str) -> Result<Vec<String>, String> {
+    return Ok(parse(html, TextExtractor::new(parse_path(path)?)).texts);
+}
+
+// The value of the attribute of each element the path matches, in the order they start
+pub fn extract_attribute(html: String, path: &str, name: &str) -> Result<Vec<String>, String> {
+    return Ok(parse(html, AttributeExtractor::new(parse_path(path)?, name)).values);
+}
@@ -1,13 +1,24 @@
 use std::collections::HashMap;
-use super::token::{Token, TokenType, Literal};
+use super::token::{Token, TokenType, Literal, SyntaxError};
 
 pub struct Scanner {
-    source: String,
+    // The characters of the source, the positions below are indices of characters rather than of bytes so text that is not ASCII can be sliced anywhere
+    source: Vec<char>,
     tokens: Vec<Token>,
     start: usize,
     current: usize,
     line: usize,
-    reserved_keywords: HashMap<String, TokenType>
+    // The index of the first character of the current line, and the line and column the token being scanned starts at
+    line_start: usize,
+    start_line: usize,
+    start_column: usize,
+    reserved_keywords: HashMap<String, TokenType>,
+    // The number of unclosed braces inside each template substitution being scanned, the } that closes a substitution continues its template
+    template_brace_depths: Vec<usize>,
+    // Set when the source ends inside a string or template literal
+    unterminated: bool,
+    // The errors found in the source, the scanner carries on after each one
+    errors: Vec<SyntaxError>,
 }
 
 impl Scanner {
@@ -52,29 +63,66 @@ impl Scanner {
             ("while".to_string(), TokenType::WHILE),
             ("with".to_string(), TokenType::WITH),
             ("yield".to_string(), TokenType::YIELD),
+            ("let".to_string(), TokenType::LET),
         ].iter().cloned().collect();
 
         Scanner { 
-            source, 
+            source: source.chars().collect(), 
             tokens: Vec::new(), 
             start: 0, 
             current: 0, 
-            line: 0,
-            reserved_keywords 
+            line: 1,
+            line_start: 0,
+            start_line: 1,
+            start_column: 1,
+            reserved_keywords,
+            template_brace_depths: Vec::new(),
+            unterminated: false,
+            errors: Vec::new(),
         }
     }
 
+    // The REPL keeps reading lines while the input so far ends inside a literal or has unclosed brackets,
+    // for example the first line of a function declaration
+    pub fn is_incomplete(source: String) -> bool {
+        let mut scanner = Scanner::new(source);
+        scanner.scan_tokens();
+
+        if scanner.unterminated {
+            return true;
+        }
+
+        let mut depth: i64 = 0;
+        for token in &scanner.tokens {
+            match token.token_type {
+                TokenType::LeftParen | TokenType::LEFT_BRACE | TokenType::LEFT_BRACKET | TokenType::TEMPLATE_HEAD => depth += 1,
+                TokenType::RIGHT_PAREN | TokenType::RIGHT_BRACE | TokenType::RIGHT_BRACKET | TokenType::TEMPLATE_TAIL => depth -= 1,
+                _ => {}
+            }
+        }
+
+        // Too many closing brackets is a syntax error that reading more lines will not fix
+        return depth > 0;
+    }
+
     pub fn scan_tokens(&mut self) -> &Vec<Token> {
         while !self.is_at_end() {
             self.start = self.current;
+            self.start_line = self.line;
+            self.start_column = self.current - self.line_start + 1;
             self.scan_token();
         }
 
-        self.tokens.push(Token::new(TokenType::EOF, String::from(""), None, self.line));
+        self.tokens.push(Token::new(TokenType::EOF, String::from(""), None, self.line, self.current - self.line_start + 1));
 
         return &self.tokens;
     }
 
+    // The errors found while scanning, they are reported with the parser's
+    pub fn take_errors(&mut self) -> Vec<SyntaxError> {
+        return std::mem::take(&mut self.errors);
+    }
+
     fn is_at_end(&self) -> bool {
         return self.current >= self.source.len();
     }
@@ -89,10 +137,31 @@ impl Scanner {
                 self.add_token(TokenType::RIGHT_PAREN, None);
             },
             '{' => {
+                if let Some(depth) = self.template_brace_depths.last_mut() {
+                    *depth += 1;
+                }
                 self.add_token(TokenType::LEFT_BRACE, None);
             },
             '}' => {
-                self.add_token(TokenType::RIGHT_BRACE, None);
+                match self.template_brace_depths.last_mut() {
+                    Some(0) => {
+                        self.template_brace_depths.pop();
+                        self.template(false);
+                    },
+                    Some(depth) => {
+                        *depth -= 1;
+                        self.add_token(TokenType::RIGHT_BRACE, None);
+                    },
+                    None => {
+                        self.add_token(TokenType::RIGHT_BRACE, None);
+                    }
+                }
+            },
+            '[' => {
+                self.add_token(TokenType::LEFT_BRACKET, None);
+            },
+            ']' => {
+                self.add_token(TokenType::RIGHT_BRACKET, None);
             },
             ',' => {
                 self.add_token(TokenType::COMMA, None);
@@ -128,6 +197,8 @@ impl Scanner {
             '=' => {
                 if self.match_token('=') {
                     self.add_token(TokenType::EQUAL_EQUAL, None);
+                } else if self.match_token('>') {
+                    self.add_token(TokenType::ARROW, None);
                 } else {
                     self.add_token(TokenType::EQUAL, None);
                 }
@@ -152,6 +223,8 @@ impl Scanner {
                     while self.peek() != '\n' && !self.is_at_end() {
                         self.advance();
                     }
+                } else if self.regular_expression_allowed() {
+                    self.regular_expression();
                 } else {
                     self.add_token(TokenType::SLASH, None);
                 }
@@ -160,31 +233,34 @@ impl Scanner {
                 // Ignore whitespace.
             },
             '\n' => {
-                 self.line += 1;
+                self.new_line();
             },
-            '"' => { self.string() },
+            '"' | '\'' => { self.string(*c) },
+            '`' => { self.template(true) },
             _ => {
                 if self.is_digit(*c) {
                     self.number();
                 } else if self.is_alpha_numeric(*c) {
                     self.identifier();
                 } else {
-                    Self::error(self.line, "Unexpected character: ".to_string() + &c.to_string());
+                    self.error("Unexpected character: ".to_string() + &c.to_string());
                 }
             }
         }
     }
 
-    fn string(&mut self) {
-        while self.peek() != '"' && !self.is_at_end() {
-            if self.peek() == '\n' {
-                self.line += 1;
+    // https://tc39.es/ecma262/#sec-literals-string-literals
+    // TODO: Escape sequences are not supported
+    fn string(&mut self, quote: char) {
+        while self.peek() != quote && !self.is_at_end() {
+            if self.advance() == '\n' {
+                self.new_line();
             }
-            self.advance();
         }
 
         if self.is_at_end() {
-            Self::error(self.line, "Unterminated string.".to_string());
+            self.unterminated = true;
+            self.error("Unterminated string.".to_string());
             return;
         }
 
@@ -193,15 +269,132 @@ impl Scanner {
         self.advance();
 
         // Trim the surrounding quotes.
-        let value = self.source[self.start + 1..self.current - 1].to_string();
+        let value = self.text(self.start + 1, self.current - 1);
         self.add_token(TokenType::STRING, Some(Literal::String(value)));
     }
 
+    // https://tc39.es/ecma262/#sec-ecmascript-language-lexical-grammar
+    // A / starts a regular expression literal where an expression can start, and is a division operator after an operand,
+    // which is told apart by the token before it rather than by the parser's goal symbol
+    // TODO: A } that ends a block is taken to end an object literal, so a regular expression literal can not start a statement after a block
+    fn regular_expression_allowed(&self) -> bool {
+        return match self.tokens.last() {
+            Some(token) => !matches!(token.token_type, TokenType::IDENTIFIER | TokenType::NUMBER | TokenType::STRING | TokenType::REGULAR_EXPRESSION
+                | TokenType::RIGHT_PAREN | TokenType::RIGHT_BRACKET | TokenType::RIGHT_BRACE | TokenType::THIS | TokenType::SUPER
+                | TokenType::TRUE | TokenType::FALSE | TokenType::NULL | TokenType::NO_SUBSTITUTION_TEMPLATE | TokenType::TEMPLATE_TAIL),
+            None => true
+        };
+    }
+
+    // https://tc39.es/ecma262/#sec-literals-regular-expression-literals
+    // Scans the body up to the / that is not escaped or in a class, and the flags after it, the pattern is only parsed when the literal is evaluated
+    fn regular_expression(&mut self) {
+        let mut in_class = false;
+        loop {
+            // RegularExpressionNonTerminator :: SourceCharacter but not LineTerminator
+            if self.is_at_end() || self.peek() == '\n' {
+                self.error("Unterminated regular expression literal.".to_string());
+                return;
+            }
+
+            match self.advance() {
+                // RegularExpressionBackslashSequence :: \ RegularExpressionNonTerminator
+                '\\' if self.peek() != '\n' && !self.is_at_end() => {
+                    self.advance();
+                },
+                '[' => in_class = true,
+                ']' => in_class = false,
+                '/' if !in_class => break,
+                _ => ()
+            }
+        }
+        let pattern = self.text(self.start + 1, self.current - 1);
+
+        // RegularExpressionFlags :: RegularExpressionFlags IdentifierPartChar
+        let flags_start = self.current;
+        while !self.is_at_end() && self.is_alpha_numeric(self.peek()) {
+            self.advance();
+        }
+        let flags = self.text(flags_start, self.current);
+        self.add_token(TokenType::REGULAR_EXPRESSION, Some(Literal::RegExp { pattern, flags }));
+    }
+
+    // https://tc39.es/ecma262/#sec-template-literal-lexical-components
+    // Scans the characters of a template up to the closing ` or the ${ of a substitution, the literal is the cooked value
+    // TODO: Unicode and hexadecimal escape sequences are not supported
+    fn template(&mut self, starts_template: bool) {
+        let mut value = String::new();
+        loop {
+            if self.is_at_end() {
+                self.unterminated = true;
+                self.error("Unterminated template literal.".to_string());
+                return;
+            }
+
+            match self.advance() {
+                '`' => {
+                    let token_type = if starts_template { TokenType::NO_SUBSTITUTION_TEMPLATE } else { TokenType::TEMPLATE_TAIL };
+                    self.add_token(token_type, Some(Literal::String(value)));
+                    return;
+                },
+                '$' if self.peek() == '{' => {
+                    self.advance();
+                    self.template_brace_depths.push(0);
+                    let token_type = if starts_template { TokenType::TEMPLATE_HEAD } else { TokenType::TEMPLATE_MIDDLE };
+                    self.add_token(token_type, Some(Literal::String(value)));
+                    return;
+                },
+                // https://tc39.es/ecma262/#prod-TemplateEscapeSequence
+                '\\' => {
+                    if self.is_at_end() {
+                        continue;
+                    }
+                    match self.advance() {
+                        'n' => value.push('\n'),
+                        't' => value.push('\t'),
+                        'r' => value.push('\r'),
+                        'b' => value.push('\u{8}'),
+                        'f' => value.push('\u{c}'),
+                        'v' => value.push('\u{b}'),
+                        '0' => value.push('\0'),
+                        // A line continuation is not part of the value
+                        '\n' => self.new_line(),
+                        escaped => value.push(escaped),
+                    }
+                },
+                '\n' => {
+                    self.new_line();
+                    value.push('\n');
+                },
+                // https://tc39.es/ecma262/#sec-static-semantics-tv
+                // <CR><LF> and <CR> are normalized to <LF> in the value
+                '\r' => {
+                    if self.peek() == '\n' {
+                        self.advance();
+                    }
+                    self.new_line();
+                    value.push('\n');
+                },
+                character => value.push(character),
+            }
+        }
+    }
+
     fn number(&mut self) {
         while self.is_digit(self.peek()) {
             self.advance();
         }
 
+        // https://tc39.es/ecma262/#prod-annexB-LegacyOctalIntegerLiteral
+        // A 0 followed only by octal digits is an octal integer, the parser reports it in strict mode code
+        let digits = self.text(self.start, self.current);
+        if digits.len() > 1 && digits.starts_with('0') && digits.chars().all(|digit| digit <= '7') {
+            // Folded into a float rather than parsed as an integer, which a long enough literal would overflow
+            let value = digits.chars().fold(0.0, |value, digit| value * 8.0 + digit.to_digit(8).unwrap_or(0) as f64);
+            self.add_token(TokenType::NUMBER, Some(Literal::Numeric(value)));
+            return;
+        }
+
         // Look for a fractional part
         if self.peek() == '.' && self.is_digit(self.peek_next()) {
             // Consume the "."
@@ -211,29 +404,25 @@ impl Scanner {
             }
         }
 
-        let chars: Vec<char> = self.source.chars().collect();
-        self.add_token(TokenType::NUMBER, Option::from(Literal::Numeric(self.source[self.start..self.current].parse::<f64>().unwrap())));
+        self.add_token(TokenType::NUMBER, Option::from(Literal::Numeric(self.text(self.start, self.current).parse::<f64>().unwrap())));
     }
 
     fn peek_next(&self) -> char {
         if self.current + 1 >= self.source.len() {
             return '\0';
         }
-        let chars: Vec<char> = self.source.chars().collect();
-        return chars[self.current + 1];
+        return self.source[self.current + 1];
     }
 
     fn peek(&self) -> char {
-        let chars: Vec<char> = self.source.chars().collect();
         if self.is_at_end() {
             return '\0';
         }
-        return chars[self.current];
+        return self.source[self.current];
     }
 
     fn advance(&mut self) -> char {
-        let chars: Vec<char> = self.source.chars().collect();
-        let current_char = chars[self.current];
+        let current_char = self.source[self.current];
         self.current = self.current + 1;
         return current_char;
     }
@@ -241,19 +430,29 @@ impl Scanner {
     fn add_token(&mut self, token_type: TokenType, literal: Option<Literal>) {
         match literal {
             Some(literal) => {
-                let text: String = self.source[self.start..self.current].to_string();
-                self.tokens.push(Token::new(token_type, text, Option::from(literal), self.line));
+                let text = self.text(self.start, self.current);
+                self.tokens.push(Token::new(token_type, text, Option::from(literal), self.start_line, self.start_column));
             },
             None => {
-                let text: String = self.source[self.start..self.current].to_string();
-                self.tokens.push(Token::new(token_type, text, None, self.line));
+                let text = self.text(self.start, self.current);
+                self.tokens.push(Token::new(token_type, text, None, self.start_line, self.start_column));
             }
         }
     }
 
+    // The source from the character at start up to the one at end
+    fn text(&self, start: usize, end: usize) -> String {
+        return self.source[start..end].iter().collect();
+    }
+
+    // Called after a line terminator is consumed, the next character starts column 1 of the next line
+    fn new_line(&mut self) {
+        self.line += 1;
+        self.line_start = self.current;
+    }
+
     fn match_token(&mut self, expected: char) -> bool {
-        let chars: Vec<char> = self.source.chars().collect();
-        if self.is_at_end() || chars[self.current] != expected {
+        if self.is_at_end() || self.source[self.current] != expected {
             return false;
         }
         self.current += 1;
@@ -269,21 +468,22 @@ impl Scanner {
             self.advance();
         }
 
-        let text = self.source[self.start..self.current].to_string();
+        let text = self.text(self.start, self.current);
         let token_type = self.reserved_keywords.get(&text).unwrap_or(&TokenType::IDENTIFIER).clone();
 
         self.add_token(token_type, None);
     }
 
     fn is_alpha(&self, c: char) -> bool {
-        return (c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || c == '_';
+        return (c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || c == '_' || c == '$';
     }
 
     fn is_alpha_numeric(&self, c: char) -> bool {
         return self.is_alpha(c) || self.is_digit(c);
     }
 
-    fn error(line: usize, message: String) {
-        println!("Error on line {}: {}", line, message);
+    // Errors are reported at the start of the token being scanned
+    fn error(&mut self, message: String) {
+        self.errors.push(SyntaxError { message, line: self.start_line, column: self.start_column });
     }
 }
@@ -0,0 +1,80 @@
+use std::time::{Duration, Instant};
+
+// Limits on how much a script may do, so untrusted page scripts can be run without hanging or running out of memory
+// A script or callback that goes over one is stopped with a RangeError, and every step it tries after that throws again,
+// so catching the error does not let it carry on. The budget starts over for each script and each task of the event loop
+
+// The number of steps in between the checks that are too slow to make on every step, e.g. reading the clock
+const CHECK_INTERVAL: u64 = 1024;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ScriptLimits {
+    // The number of statements and expressions a script or task may evaluate
+    pub max_steps: Option<u64>,
+    // How long a script or task may run for
+    pub max_time: Option<Duration>,
+    // The number of objects script may have alive at once, counted after the cycle collector has run
+    pub max_heap_objects: Option<usize>,
+    // The number of bytes the objects, environments and strings script can reach may take up, checked whenever a string is concatenated or an object grows
+    pub max_heap_size: Option<usize>,
+    // The number of calls that may be on the stack at once, every call is evaluated recursively so this also bounds the native stack
+    pub max_call_depth: usize,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> ScriptLimits {
+        ScriptLimits { max_steps: None, max_time: None, max_heap_objects: None, max_heap_size: None, max_call_depth: 1000 }
+    }
+}
+
+// What the running script or task has used of its limits
+pub(crate) struct ScriptBudget {
+    steps: u64,
+    started: Instant,
+    // Why the script was stopped, once it has gone over a limit
+    exceeded: Option<String>,
+}
+
+impl ScriptBudget {
+    pub(crate) fn new() -> ScriptBudget {
+        ScriptBudget { steps: 0, started: Instant::now(), exceeded: None }
+    }
+
+    // Counts a step, and returns why the script has to stop when it went over the step or time limit
+    pub(crate) fn step(&mut self, limits: &ScriptLimits) -> Option<String> {
+        if self.exceeded.is_some() {
+            return self.exceeded.clone();
+        }
+
+        self.steps += 1;
+        if let Some(max_steps) = limits.max_steps {
+            if self.steps > max_steps {
+                return self.exceed(format!("Script exceeded the maximum of {} steps", max_steps));
+            }
+        }
+
+        if let Some(max_time) = limits.max_time {
+            if self.is_check_step() && self.started.elapsed() > max_time {
+                return self.exceed(format!("Script ran for longer than the maximum of {} ms", max_time.as_millis()));
+            }
+        }
+
+        return None;
+    }
+
+    // Whether this step makes the checks that are too slow for every step, e.g. counting the objects on the heap
+    pub(crate) fn is_check_step(&self) -> bool {
+        return self.steps % CHECK_INTERVAL == 0;
+    }
+
+    // Why the script was stopped, once it has gone over a limit
+    pub(crate) fn exceeded(&self) -> Option<String> {
+        return self.exceeded.clone();
+    }
+
+    // Stops the script, the steps it tries after this fail with the same message
+    pub(crate) fn exceed(&mut self, message: String) -> Option<String> {
+        self.exceeded = Some(message);
+        return self.exceeded.clone();
+    }
+}
@@ -0,0 +1,318 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::display_list::{document_scroll_size, paint_document, BoxGeometry};
+use crate::dom_bindings::{document_body, document_document_element};
+use crate::interpreter::{Interpreter, JSObject};
+use crate::node::{node_document, DocumentMode, NodeData, RefNode};
+use crate::text::BuiltinFontMetrics;
+use crate::webidl::{attribute_getter, attribute_setter, define_attributes, define_operations, operation, ElementNode};
+use crate::window::Viewport;
+
+// https://drafts.csswg.org/cssom-view/#scrolling
+// Scroll containers (boxes whose overflow is hidden, scroll or auto) and the viewport each have a scroll position, which moves their content up and to the left when they are painted
+// Scripts scroll them with scrollTop, scrollLeft and scrollIntoView, and the viewport is scrolled through its scrolling element (the root element, or the body in quirks mode)
+// TODO: Scrolling is always instant, no scroll events are fired and window has no scrollX, scrollY or scrollTo
+
+// https://drafts.csswg.org/cssom-view/#scroll-position
+// How far the content of a box is scrolled, in CSS pixels from the start of its scrolling area
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScrollPosition {
+    pub x: f32,
+    pub y: f32,
+}
+
+// https://drafts.csswg.org/cssom-view/#dom-scrollintoviewoptions-block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollLogicalPosition {
+    Start,
+    End,
+    Nearest,
+}
+
+pub(crate) fn install_scrolling(document_prototype: &Rc<RefCell<JSObject>>, element_prototype: &Rc<RefCell<JSObject>>) {
+    define_attributes(document_prototype, &[
+        ("scrollingElement", attribute_getter!("Document", "scrollingElement", document_scrolling_element(document: RefNode)), None),
+    ]);
+    define_operations(element_prototype, &[
+        ("scrollIntoView", operation!("Element", "scrollIntoView", element_scroll_into_view(&mut interpreter, element: ElementNode, align_to_top: Option<bool>))),
+    ]);
+    define_attributes(element_prototype, &[
+        ("scrollTop", attribute_getter!("Element", "scrollTop", element_scroll_top(element: ElementNode)), Some(attribute_setter!("Element", "scrollTop", element_set_scroll_top(&mut interpreter, element: ElementNode, y: f64)))),
+        ("scrollLeft", attribute_getter!("Element", "scrollLeft", element_scroll_left(element: ElementNode)), Some(attribute_setter!("Element", "scrollLeft", element_set_scroll_left(&mut interpreter, element: ElementNode, x: f64)))),
+        ("scrollWidth", attribute_getter!("Element", "scrollWidth", element_scroll_width(&mut interpreter, element: ElementNode)), None),
+        ("scrollHeight", attribute_getter!("Element", "scrollHeight", element_scroll_height(&mut interpreter, element: ElementNode)), None),
+        ("clientWidth", attribute_getter!("Element", "clientWidth", element_client_width(&mut interpreter, element: ElementNode)), None),
+        ("clientHeight", attribute_getter!("Element", "clientHeight", element_client_height(&mut interpreter, element: ElementNode)), None),
+    ]);
+}
+
+// The scroll position of a scroll container, which is 0, 0 until it is scrolled
+pub fn element_scroll_position(element: &RefNode) -> ScrollPosition {
+    match &element.borrow().data {
+        NodeData::Element(element) => element.scroll_position,
+        _ => ScrollPosition::default()
+    }
+}
+
+fn set_element_scroll_position(element: &RefNode, position: ScrollPosition) {
+    if let NodeData::Element(element) = &mut element.borrow_mut().data {
+        element.scroll_position = position;
+    }
+}
+
+// The scroll position of the viewport document is shown in
+pub fn viewport_scroll_position(document: &RefNode) -> ScrollPosition {
+    match &document.borrow().data {
+        NodeData::Document(document) => document.scroll_position,
+        _ => ScrollPosition::default()
+    }
+}
+
+// https://drafts.csswg.org/cssom-view/#perform-a-scroll
+// Scrolls the viewport document is shown in to position, as far as the document goes
+pub fn scroll_viewport(document: &RefNode, viewport: Viewport, position: ScrollPosition) {
+    let metrics = BuiltinFontMetrics::default();
+    let (display_list, _) = paint_document(document, viewport, &metrics);
+    let position = clamp_scroll_position(position, document_scroll_size(&display_list, &metrics), (viewport.width as f32, viewport.height as f32));
+    if let NodeData::Document(document) = &mut document.borrow_mut().data {
+        document.scroll_position = position;
+    }
+}
+
+// https://drafts.csswg.org/cssom-view/#scrolling-area
+// A box can be scrolled from its start until the end of its scrolling area is at the end of the box, and not at all in an axis its content fits in
+pub(crate) fn clamp_scroll_position(position: ScrollPosition, scroll_size: (f32, f32), client_size: (f32, f32)) -> ScrollPosition {
+    let clamp = |value: f32, scroll: f32, client: f32| if value.is_finite() { value.clamp(0.0, (scroll - client).max(0.0)) } else { 0.0 };
+    return ScrollPosition { x: clamp(position.x, scroll_size.0, client_size.0), y: clamp(position.y, scroll_size.1, client_size.1) };
+}
+
+// The geometry of the box of element when its document is laid out in viewport, with its document and the geometry of every box
+// TODO: Inline boxes are not laid out on their own, so an inline element has the geometry of the block it is in
+fn layout_element(element: &RefNode, viewport: Viewport) -> Option<(RefNode, BoxGeometry)> {
+    let document = node_document(element)?;
+    let (_, geometry) = paint_document(&document, viewport, &BuiltinFontMetrics::default());
+    let mut ancestor = Some(Rc::clone(element));
+    while let Some(current) = ancestor {
+        if let Some((_, box_geometry)) = geometry.iter().find(|(node, _)| Rc::ptr_eq(node, &current)) {
+            return Some((document, *box_geometry));
+        }
+        ancestor = current.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+    }
+    return None;
+}
+
+fn is_quirks_mode(document: &RefNode) -> bool {
+    return matches!(&document.borrow().data, NodeData::Document(document) if document.mode == DocumentMode::Quirks);
+}
+
+// https://drafts.csswg.org/cssom-view/#dom-document-scrollingelement
+// The document when element is its scrolling element, whose scroll position is the viewport's, which is the root element, or the body in quirks mode
+// TODO: In quirks mode the body is the scrolling element even when it is potentially scrollable itself
+fn scrolled_document(element: &RefNode) -> Option<RefNode> {
+    let document = node_document(element)?;
+    let parent = element.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade())?;
+    let is_root = Rc::ptr_eq(&parent, &document);
+    let is_body = matches!(&element.borrow().data, NodeData::Element(element) if element.local_name() == "body")
+        && parent.borrow().parentNode.as_ref().and_then(|grandparent| grandparent.upgrade()).is_some_and(|grandparent| Rc::ptr_eq(&grandparent, &document));
+    let quirks_mode = is_quirks_mode(&document);
+    if (is_root && !quirks_mode) || (is_body && quirks_mode) {
+        return Some(document);
+    }
+    return None;
+}
+
+// https://drafts.csswg.org/cssom-view/#dom-document-scrollingelement
+fn document_scrolling_element(document: RefNode) -> Option<RefNode> {
+    // 1. If the Document is in quirks mode, follow these substeps:
+    //     1. If the body element exists, and it is not potentially scrollable, return the body element and abort these steps.
+    //     2. Return null and abort these steps.
+    if is_quirks_mode(&document) {
+        return document_body(document);
+    }
+    // 2. If there is a root element, return the root element and abort these steps.
+    // 3. Return null.
+    return document_document_element(document);
+}
+
+// https://drafts.csswg.org/cssom-view/#dom-element-scrolltop
+fn element_scroll_top(ElementNode(element): ElementNode) -> f64 {
+    // 6. If the element is the root element return the value of scrollY on window.
+    // 7. If the element is the body element, document is in quirks mode, and the element is not potentially scrollable, return the value of scrollY on window.
+    // 9. Return the y-coordinate of the scrolling area at the alignment point with the top of the padding edge of the element.
+    return match scrolled_document(&element) {
+        Some(document) => viewport_scroll_position(&document).y,
+        None => element_scroll_position(&element).y
+    } as f64;
+}
+
+// https://drafts.csswg.org/cssom-view/#dom-element-scrollleft
+fn element_scroll_left(ElementNode(element): ElementNode) -> f64 {
+    return match scrolled_document(&element) {
+        Some(document) => viewport_scroll_position(&document).x,
+        None => element_scroll_position(&element).x
+    } as f64;
+}
+
+fn element_set_scroll_top(interpreter: &mut Interpreter, ElementNode(element): ElementNode, y: f64) {
+    let position = ScrollPosition { y: y as f32, ..current_scroll_position(&element) };
+    scroll_element(&element, interpreter.viewport(), position);
+}
+
+fn element_set_scroll_left(interpreter: &mut Interpreter, ElementNode(element): ElementNode, x: f64) {
+    let position = ScrollPosition { x: x as f32, ..current_scroll_position(&element) };
+    scroll_element(&element, interpreter.viewport(), position);
+}
+
+fn current_scroll_position(element: &RefNode) -> ScrollPosition {
+    match scrolled_document(element) {
+        Some(document) => viewport_scroll_position(&document),
+        None => element_scroll_position(element)
+    }
+}
+
+// https://drafts.csswg.org/cssom-view/#dom-element-scrolltop
+// The steps of setting scrollTop and scrollLeft once the position to scroll to is known
+fn scroll_element(element: &RefNode, viewport: Viewport, position: ScrollPosition) {
+    // 8. If the element is the root element invoke scroll() on window with scrollX on window as first argument and y as second argument, and terminate these steps.
+    if let Some(document) = scrolled_document(element) {
+        scroll_viewport(&document, viewport, position);
+        return;
+    }
+
+    // 10. If the element does not have any associated box, the element has no associated scrolling box, or the element has no overflow, terminate these steps.
+    let geometry = match layout_element(element, viewport) {
+        Some((_, geometry)) if geometry.scrollable => geometry,
+        _ => return
+    };
+
+    // 11. Scroll the element to scrollLeft, y, with the scroll behavior being "auto".
+    let position = clamp_scroll_position(position, (geometry.scroll_width, geometry.scroll_height), (geometry.padding_box.width, geometry.padding_box.height));
+    set_element_scroll_position(element, position);
+}
+
+// https://drafts.csswg.org/cssom-view/#dom-element-scrollwidth
+fn element_scroll_width(interpreter: &mut Interpreter, ElementNode(element): ElementNode) -> f64 {
+    return scroll_size(&element, interpreter.viewport()).0.round() as f64;
+}
+
+// https://drafts.csswg.org/cssom-view/#dom-element-scrollheight
+fn element_scroll_height(interpreter: &mut Interpreter, ElementNode(element): ElementNode) -> f64 {
+    return scroll_size(&element, interpreter.viewport()).1.round() as f64;
+}
+
+fn scroll_size(element: &RefNode, viewport: Viewport) -> (f32, f32) {
+    // 3. If the element is the root element and document is not in quirks mode return max(viewport scrolling area width, viewport width).
+    if let Some(document) = scrolled_document(element) {
+        let metrics = BuiltinFontMetrics::default();
+        let (display_list, _) = paint_document(&document, viewport, &metrics);
+        let (width, height) = document_scroll_size(&display_list, &metrics);
+        return (width.max(viewport.width as f32), height.max(viewport.height as f32));
+    }
+    // 5. If the element does not have any associated box return zero and terminate these steps.
+    // 6. Return the width of the element's scrolling area.
+    match layout_element(element, viewport) {
+        Some((_, geometry)) => return (geometry.scroll_width, geometry.scroll_height),
+        None => return (0.0, 0.0)
+    }
+}
+
+// https://drafts.csswg.org/cssom-view/#dom-element-clientwidth
+fn element_client_width(interpreter: &mut Interpreter, ElementNode(element): ElementNode) -> f64 {
+    return client_size(&element, interpreter.viewport()).0.round() as f64;
+}
+
+// https://drafts.csswg.org/cssom-view/#dom-element-clientheight
+fn element_client_height(interpreter: &mut Interpreter, ElementNode(element): ElementNode) -> f64 {
+    return client_size(&element, interpreter.viewport()).1.round() as f64;
+}
+
+fn client_size(element: &RefNode, viewport: Viewport) -> (f32, f32) {
+    // 2. If the element is the root element and the element's node document is not in quirks mode, or if the element is the HTML body element and the element's node document is in quirks mode, return the viewport width excluding the size of a rendered scroll bar (if any).
+    if scrolled_document(element).is_some() {
+        return (viewport.width as f32, viewport.height as f32);
+    }
+    // 1. If the element has no associated CSS layout box or if the CSS layout box is inline, return zero.
+    // 3. Return the width of the padding edge excluding the width of any rendered scrollbar between the padding edge and the border edge, ignoring any transforms that apply to the element and its ancestors.
+    match layout_element(element, viewport) {
+        Some((_, geometry)) => return (geometry.padding_box.width, geometry.padding_box.height),
+        None => return (0.0, 0.0)
+    }
+}
+
+// https://drafts.csswg.org/cssom-view/#dom-element-scrollintoview
+fn element_scroll_into_view(interpreter: &mut Interpreter, ElementNode(element): ElementNode, align_to_top: Option<bool>) {
+    // 1. Let behavior be "auto".
+    // 2. Let block be "start".
+    // 3. Let inline be "nearest".
+    // 5. Otherwise, if arg is false, then set block to "end".
+    // TODO: A ScrollIntoViewOptions dictionary is not supported, an object is treated as true
+    let block = match align_to_top {
+        Some(false) => ScrollLogicalPosition::End,
+        _ => ScrollLogicalPosition::Start
+    };
+    // 7. Scroll an element into view element with behavior, block, inline, and container.
+    scroll_into_view(&element, interpreter.viewport(), block, ScrollLogicalPosition::Nearest);
+}
+
+// https://drafts.csswg.org/cssom-view/#scroll-an-element-into-view
+// Scrolls each scroll container element is in and then the viewport, so that element is seen at the block and inline positions of each
+pub fn scroll_into_view(element: &RefNode, viewport: Viewport, block: ScrollLogicalPosition, inline: ScrollLogicalPosition) {
+    // 1. For each ancestor element or viewport that establishes a scrolling box scrolling box, in order of innermost to outermost scrolling box, run these substeps:
+    let mut ancestor = element.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+    while let Some(current) = ancestor {
+        if matches!(current.borrow().data, NodeData::Element(_)) && scrolled_document(&current).is_none() {
+            // Each scrolling box is laid out again, as scrolling the ones inside it moved element
+            if let (Some((_, target)), Some((_, container))) = (layout_element(element, viewport), layout_element(&current, viewport)) {
+                if container.scrollable {
+                    let position = element_scroll_position(&current);
+                    let position = ScrollPosition {
+                        x: position.x + scroll_delta(inline, (target.border_box.x, target.border_box.width), (container.padding_box.x, container.padding_box.width)),
+                        y: position.y + scroll_delta(block, (target.border_box.y, target.border_box.height), (container.padding_box.y, container.padding_box.height)),
+                    };
+                    let position = clamp_scroll_position(position, (container.scroll_width, container.scroll_height), (container.padding_box.width, container.padding_box.height));
+                    set_element_scroll_position(&current, position);
+                }
+            }
+        }
+        ancestor = current.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+    }
+
+    // The viewport is the outermost scrolling box, its scrolling box is the document from its scroll position
+    let (document, target) = match layout_element(element, viewport) {
+        Some(layout) => layout,
+        None => return
+    };
+    let position = viewport_scroll_position(&document);
+    let position = ScrollPosition {
+        x: position.x + scroll_delta(inline, (target.border_box.x, target.border_box.width), (position.x, viewport.width as f32)),
+        y: position.y + scroll_delta(block, (target.border_box.y, target.border_box.height), (position.y, viewport.height as f32)),
+    };
+    scroll_viewport(&document, viewport, position);
+}
+
+// https://drafts.csswg.org/cssom-view/#determine-the-scroll-into-view-position
+// How far a scrolling box has to scroll in one axis so that the element's edge is aligned with the scrolling box's, each given as a start and a size
+fn scroll_delta(position: ScrollLogicalPosition, element: (f32, f32), scrolling_box: (f32, f32)) -> f32 {
+    let (element_start, element_size) = element;
+    let (box_start, box_size) = scrolling_box;
+    let (element_end, box_end) = (element_start + element_size, box_start + box_size);
+    match position {
+        // Align element edge A with scrolling box edge A.
+        ScrollLogicalPosition::Start => return element_start - box_start,
+        // Align element edge B with scrolling box edge B.
+        ScrollLogicalPosition::End => return element_end - box_end,
+        ScrollLogicalPosition::Nearest => {
+            // If element edge A and element edge B are both outside scrolling box edge A and scrolling box edge B, do nothing.
+            // If element edge A and element edge B are both inside scrolling box edge A and scrolling box edge B, do nothing.
+            if (element_start <= box_start && element_end >= box_end) || (element_start >= box_start && element_end <= box_end) {
+                return 0.0;
+            }
+            // If element edge A is outside scrolling box edge A and element width is less than scrolling box width, or element edge B is outside scrolling box edge B and element width is greater than scrolling box width, align element edge A with scrolling box edge A.
+            if (element_start < box_start && element_size < box_size) || (element_end > box_end && element_size > box_size) {
+                return element_start - box_start;
+            }
+            // Otherwise align element edge B with scrolling box edge B.
+            return element_end - box_end;
+        }
+    }
+}
@@ -0,0 +1,466 @@
+use std::rc::Rc;
+use crate::node::{NodeData, RefNode};
+use crate::unicode::{ascii_lowercase, is_ascii_case_insensitive_match};
+
+// https://drafts.csswg.org/selectors-4/#selector-list
+// TODO: Only type, universal, id, class and attribute selectors, the :root pseudo-class, the descendant, child, next-sibling and subsequent-sibling combinators, and the ::before, ::after and ::marker pseudo-elements, are supported
+pub struct SelectorList {
+    complex_selectors: Vec<ComplexSelector>,
+}
+
+// https://drafts.csswg.org/selectors-4/#complex
+// Stored right to left, the first compound selector is the subject of the selector
+struct ComplexSelector {
+    compound_selectors: Vec<(CompoundSelector, Option<Combinator>)>,
+    // https://drafts.csswg.org/selectors-4/#pseudo-element-selectors
+    // The pseudo-element at the end of the selector, which the selector represents rather than the element its compound selectors match
+    pseudo_element: Option<PseudoElement>,
+}
+
+// https://drafts.csswg.org/css-pseudo-4/#treelike
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PseudoElement {
+    Before,
+    After,
+    Marker,
+}
+
+// https://drafts.csswg.org/selectors-4/#compound
+#[derive(Default)]
+struct CompoundSelector {
+    type_selector: Option<String>,
+    id_selectors: Vec<String>,
+    class_selectors: Vec<String>,
+    attribute_selectors: Vec<AttributeSelector>,
+    pseudo_classes: Vec<PseudoClass>,
+}
+
+// https://drafts.csswg.org/selectors-4/#pseudo-classes
+#[derive(Clone, Copy)]
+enum PseudoClass {
+    // https://drafts.csswg.org/selectors-4/#the-root-pseudo
+    Root,
+}
+
+// https://drafts.csswg.org/selectors-4/#attribute-selectors
+struct AttributeSelector {
+    name: String,
+    value: Option<String>,
+}
+
+// https://drafts.csswg.org/selectors-4/#specificity-rules
+// Compared by the number of ID selectors, then class and attribute selectors, then type selectors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Specificity(pub u32, pub u32, pub u32);
+
+// https://drafts.csswg.org/selectors-4/#combinators
+#[derive(Clone, Copy)]
+enum Combinator {
+    Descendant,
+    Child,
+    // +
+    NextSibling,
+    // ~
+    SubsequentSibling,
+}
+
+// https://drafts.csswg.org/selectors-4/#parse-a-selector
+// Returns None if the selector is invalid
+pub fn parse_selector_list(selectors: &str) -> Option<SelectorList> {
+    let mut complex_selectors = Vec::new();
+
+    for complex_selector in selectors.split(',') {
+        complex_selectors.push(parse_complex_selector(complex_selector.trim())?);
+    }
+
+    return Some(SelectorList { complex_selectors });
+}
+
+fn parse_complex_selector(input: &str) -> Option<ComplexSelector> {
+    let characters: Vec<char> = input.chars().collect();
+    let mut position = 0;
+    let mut compound_selectors: Vec<(CompoundSelector, Option<Combinator>)> = Vec::new();
+    let mut combinator: Option<Combinator> = None;
+
+    let mut pseudo_element = None;
+
+    while position < characters.len() {
+        // A pseudo-element can only be at the end of the selector
+        if pseudo_element.is_some() {
+            return None;
+        }
+        let compound_selector = parse_compound_selector(&characters, &mut position)?;
+        compound_selectors.push((compound_selector, combinator));
+        pseudo_element = parse_pseudo_element(&characters, &mut position)?;
+
+        // Whitespace is a descendant combinator unless it surrounds another combinator
+        let mut seen_whitespace = false;
+        while position < characters.len() && characters[position].is_whitespace() {
+            seen_whitespace = true;
+            position += 1;
+        }
+
+        if position == characters.len() {
+            break;
+        }
+
+        let explicit_combinator = match characters[position] {
+            '>' => Some(Combinator::Child),
+            '+' => Some(Combinator::NextSibling),
+            '~' => Some(Combinator::SubsequentSibling),
+            _ => None
+        };
+        if explicit_combinator.is_some() {
+            combinator = explicit_combinator;
+            position += 1;
+            while position < characters.len() && characters[position].is_whitespace() {
+                position += 1;
+            }
+            if position == characters.len() {
+                return None;
+            }
+        } else if seen_whitespace {
+            combinator = Some(Combinator::Descendant);
+        } else {
+            return None;
+        }
+    }
+
+    if compound_selectors.is_empty() {
+        return None;
+    }
+
+    // Each compound selector is paired with the combinator joining it to the compound selector on its left,
+    // which is the next one to match once the list is reversed
+    compound_selectors.reverse();
+
+    return Some(ComplexSelector { compound_selectors, pseudo_element });
+}
+
+// https://drafts.csswg.org/selectors-4/#pseudo-element-syntax
+// The pseudo-element after a compound selector, Some(None) when there is none and None when it is not one that is supported
+// ::before and ::after can also be written with one colon, as they were in CSS 2
+fn parse_pseudo_element(characters: &[char], position: &mut usize) -> Option<Option<PseudoElement>> {
+    if *position == characters.len() || characters[*position] != ':' {
+        return Some(None);
+    }
+    *position += 1;
+    let legacy = *position == characters.len() || characters[*position] != ':';
+    if !legacy {
+        *position += 1;
+    }
+
+    match ascii_lowercase(&consume_identifier(characters, position)).as_ref() {
+        "before" => return Some(Some(PseudoElement::Before)),
+        "after" => return Some(Some(PseudoElement::After)),
+        "marker" if !legacy => return Some(Some(PseudoElement::Marker)),
+        _ => return None
+    }
+}
+
+fn parse_compound_selector(characters: &[char], position: &mut usize) -> Option<CompoundSelector> {
+    let mut compound_selector = CompoundSelector::default();
+    let start = *position;
+
+    if *position < characters.len() && characters[*position] == '*' {
+        *position += 1;
+    } else if *position < characters.len() && is_identifier_character(characters[*position]) {
+        compound_selector.type_selector = Some(ascii_lowercase(&consume_identifier(characters, position)).into_owned());
+    }
+
+    while *position < characters.len() {
+        match characters[*position] {
+            '#' => {
+                *position += 1;
+                let id = consume_identifier(characters, position);
+                if id.is_empty() {
+                    return None;
+                }
+                compound_selector.id_selectors.push(id);
+            },
+            '.' => {
+                *position += 1;
+                let class = consume_identifier(characters, position);
+                if class.is_empty() {
+                    return None;
+                }
+                compound_selector.class_selectors.push(class);
+            },
+            '[' => {
+                *position += 1;
+                compound_selector.attribute_selectors.push(parse_attribute_selector(characters, position)?);
+            },
+            // A pseudo-element is left for parse_pseudo_element, as it can only be at the end of the selector
+            ':' => {
+                let mut after_colon = *position + 1;
+                match ascii_lowercase(&consume_identifier(characters, &mut after_colon)).as_ref() {
+                    "root" => compound_selector.pseudo_classes.push(PseudoClass::Root),
+                    _ => break
+                }
+                *position = after_colon;
+            },
+            _ => break
+        }
+    }
+
+    // A compound selector can be left out in front of a pseudo-element, e.g. ::before is *::before
+    if *position == start && characters.get(*position) != Some(&':') {
+        return None;
+    }
+
+    return Some(compound_selector);
+}
+
+fn parse_attribute_selector(characters: &[char], position: &mut usize) -> Option<AttributeSelector> {
+    let name = ascii_lowercase(&consume_identifier(characters, position)).into_owned();
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut value = None;
+    if *position < characters.len() && characters[*position] == '=' {
+        *position += 1;
+
+        let quote = characters.get(*position).copied().filter(|character| *character == '"' || *character == '\'');
+        match quote {
+            Some(quote) => {
+                *position += 1;
+                let mut quoted_value = String::new();
+                while *position < characters.len() && characters[*position] != quote {
+                    quoted_value.push(characters[*position]);
+                    *position += 1;
+                }
+                if *position == characters.len() {
+                    return None;
+                }
+                *position += 1;
+                value = Some(quoted_value);
+            },
+            None => {
+                value = Some(consume_identifier(characters, position));
+            }
+        }
+    }
+
+    if *position == characters.len() || characters[*position] != ']' {
+        return None;
+    }
+    *position += 1;
+
+    return Some(AttributeSelector { name, value });
+}
+
+fn is_identifier_character(character: char) -> bool {
+    return character.is_alphanumeric() || character == '-' || character == '_' || !character.is_ascii();
+}
+
+fn consume_identifier(characters: &[char], position: &mut usize) -> String {
+    let mut identifier = String::new();
+    while *position < characters.len() && is_identifier_character(characters[*position]) {
+        identifier.push(characters[*position]);
+        *position += 1;
+    }
+    return identifier;
+}
+
+// An element selectors are matched against, a node of a document or of a shared document
+// Only the parts of an element selectors look at are asked for, so each kind of node can answer from what it stores
+pub trait SelectorElement: Sized {
+    fn is_element(&self) -> bool;
+    // Compared ASCII case-insensitively, as HTML element names are
+    fn has_local_name(&self, local_name: &str) -> bool;
+    // Whether the element has the attribute and its value is one the predicate accepts
+    fn attribute_matches(&self, name: &str, predicate: &dyn Fn(&str) -> bool) -> bool;
+    // https://dom.spec.whatwg.org/#parent-element
+    fn parent_element(&self) -> Option<Self>;
+    // https://dom.spec.whatwg.org/#dom-nondocumenttypechildnode-previouselementsibling
+    fn previous_element_sibling(&self) -> Option<Self>;
+    // https://drafts.csswg.org/selectors-4/#the-root-pseudo
+    // Whether the element is the root of the document, the document element
+    fn is_root(&self) -> bool;
+}
+
+impl SelectorList {
+    // https://drafts.csswg.org/selectors-4/#match-a-selector-against-an-element
+    // A selector that ends in a pseudo-element represents the pseudo-element, so it matches no element
+    pub fn matches<E: SelectorElement>(&self, element: &E) -> bool {
+        return self.complex_selectors.iter()
+            .any(|complex_selector| complex_selector.pseudo_element.is_none() && complex_selector_matches(&complex_selector.compound_selectors, element));
+    }
+
+    // https://drafts.csswg.org/selectors-4/#specificity-rules
+    // The specificity of a selector list is that of the most specific of its selectors that match the element, None when none of them do
+    pub fn matching_specificity<E: SelectorElement>(&self, element: &E) -> Option<Specificity> {
+        return self.matching_pseudo_element_specificity(element, None);
+    }
+
+    // matching_specificity of the selectors that represent pseudo_element of the element, or the element itself when pseudo_element is None
+    pub fn matching_pseudo_element_specificity<E: SelectorElement>(&self, element: &E, pseudo_element: Option<PseudoElement>) -> Option<Specificity> {
+        return self.complex_selectors.iter()
+            .filter(|complex_selector| complex_selector.pseudo_element == pseudo_element && complex_selector_matches(&complex_selector.compound_selectors, element))
+            .map(|complex_selector| complex_selector.specificity())
+            .max();
+    }
+
+    // Whether matching the selectors only looks at an element and its ancestors, and not at its siblings
+    pub fn only_looks_at_ancestors(&self) -> bool {
+        return self.complex_selectors.iter()
+            .all(|complex_selector| complex_selector.compound_selectors.iter().all(|(_, combinator)| !matches!(combinator, Some(Combinator::NextSibling | Combinator::SubsequentSibling))));
+    }
+
+    // The pseudo-elements the selectors of the list represent
+    pub fn pseudo_elements(&self) -> impl Iterator<Item = PseudoElement> + '_ {
+        return self.complex_selectors.iter().filter_map(|complex_selector| complex_selector.pseudo_element);
+    }
+}
+
+impl ComplexSelector {
+    // https://drafts.csswg.org/selectors-4/#specificity-rules
+    fn specificity(&self) -> Specificity {
+        let mut specificity = Specificity::default();
+        for (compound_selector, _) in &self.compound_selectors {
+            // count the number of ID selectors in the selector (= A)
+            specificity.0 += compound_selector.id_selectors.len() as u32;
+            // count the number of class selectors, attributes selectors, and pseudo-classes in the selector (= B)
+            specificity.1 += (compound_selector.class_selectors.len() + compound_selector.attribute_selectors.len() + compound_selector.pseudo_classes.len()) as u32;
+            // count the number of type selectors and pseudo-elements in the selector (= C)
+            if compound_selector.type_selector.is_some() {
+                specificity.2 += 1;
+            }
+        }
+        if self.pseudo_element.is_some() {
+            specificity.2 += 1;
+        }
+        return specificity;
+    }
+}
+
+fn complex_selector_matches<E: SelectorElement>(compound_selectors: &[(CompoundSelector, Option<Combinator>)], element: &E) -> bool {
+    let (compound_selector, combinator) = &compound_selectors[0];
+
+    if !compound_selector_matches(compound_selector, element) {
+        return false;
+    }
+
+    match combinator {
+        None => true,
+        Some(Combinator::Child) => {
+            match element.parent_element() {
+                Some(parent) => complex_selector_matches(&compound_selectors[1..], &parent),
+                None => false
+            }
+        },
+        Some(Combinator::Descendant) => {
+            let mut ancestor = element.parent_element();
+            while let Some(current) = ancestor {
+                if complex_selector_matches(&compound_selectors[1..], &current) {
+                    return true;
+                }
+                ancestor = current.parent_element();
+            }
+            false
+        },
+        Some(Combinator::NextSibling) => {
+            match element.previous_element_sibling() {
+                Some(sibling) => complex_selector_matches(&compound_selectors[1..], &sibling),
+                None => false
+            }
+        },
+        Some(Combinator::SubsequentSibling) => {
+            let mut sibling = element.previous_element_sibling();
+            while let Some(current) = sibling {
+                if complex_selector_matches(&compound_selectors[1..], &current) {
+                    return true;
+                }
+                sibling = current.previous_element_sibling();
+            }
+            false
+        }
+    }
+}
+
+fn compound_selector_matches<E: SelectorElement>(compound_selector: &CompoundSelector, element: &E) -> bool {
+    if !element.is_element() {
+        return false;
+    }
+
+    if let Some(type_selector) = &compound_selector.type_selector {
+        if !element.has_local_name(type_selector) {
+            return false;
+        }
+    }
+
+    for id in &compound_selector.id_selectors {
+        if !element.attribute_matches("id", &|value| value == id) {
+            return false;
+        }
+    }
+
+    for class in &compound_selector.class_selectors {
+        if !element.attribute_matches("class", &|classes| classes.split_ascii_whitespace().any(|name| name == class)) {
+            return false;
+        }
+    }
+
+    for attribute_selector in &compound_selector.attribute_selectors {
+        let matches = match &attribute_selector.value {
+            Some(expected_value) => element.attribute_matches(&attribute_selector.name, &|value| value == expected_value),
+            None => element.attribute_matches(&attribute_selector.name, &|_| true)
+        };
+        if !matches {
+            return false;
+        }
+    }
+
+    for pseudo_class in &compound_selector.pseudo_classes {
+        let matches = match pseudo_class {
+            PseudoClass::Root => element.is_root()
+        };
+        if !matches {
+            return false;
+        }
+    }
+
+    return true;
+}
+
+impl SelectorElement for RefNode {
+    fn is_element(&self) -> bool {
+        return matches!(self.borrow().data, NodeData::Element(_));
+    }
+
+    fn has_local_name(&self, local_name: &str) -> bool {
+        return match &self.borrow().data {
+            NodeData::Element(element) => is_ascii_case_insensitive_match(element.local_name(), local_name),
+            _ => false
+        };
+    }
+
+    fn attribute_matches(&self, name: &str, predicate: &dyn Fn(&str) -> bool) -> bool {
+        return match &self.borrow().data {
+            NodeData::Element(element) => element.get_attribute(name).map(|value| predicate(value)).unwrap_or(false),
+            _ => false
+        };
+    }
+
+    fn parent_element(&self) -> Option<RefNode> {
+        let parent = self.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade())?;
+        let is_element = matches!(parent.borrow().data, NodeData::Element(_));
+        if is_element {
+            return Some(Rc::clone(&parent));
+        }
+        return None;
+    }
+
+    fn previous_element_sibling(&self) -> Option<RefNode> {
+        let parent = self.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade())?;
+        let parent = parent.borrow();
+        let index = parent.childNodes.iter().position(|child| Rc::ptr_eq(child, self))?;
+        return parent.childNodes[..index].iter().rev().find(|child| child.is_element()).cloned();
+    }
+
+    fn is_root(&self) -> bool {
+        let parent = self.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+        return self.is_element() && parent.is_some_and(|parent| matches!(parent.borrow().data, NodeData::Document(_)));
+    }
+}
@@ -0,0 +1,243 @@
+use std::rc::Rc;
+use serde_json::{json, Map, Value};
+use crate::node::{Attr, Node, NodeData, RefNode};
+use crate::html_document_parser::{MATHML_NAMESPACE, SVG_NAMESPACE};
+
+// https://html.spec.whatwg.org/multipage/parsing.html#serialising-html-fragments
+// Turns nodes back into HTML, parsing the HTML gives the same nodes again
+// The HTML and tree formats walk the nodes with a stack rather than recursion, so a deeply nested tree (e.g. one a script built) cannot overflow the call stack
+// TODO: Namespaces are not supported, so every element is serialized as an HTML element, and template contents are not serialized
+
+// The HTML of the children of node, which for a document is the whole document
+pub fn serialize_children(node: &RefNode) -> String {
+    let mut html = String::new();
+    serialize_children_into(node, &mut html);
+    return html;
+}
+
+// The HTML of node itself and its children, e.g. the outerHTML of an element
+pub fn serialize_node(node: &RefNode) -> String {
+    let mut html = String::new();
+    serialize_node_into(node, &mut html);
+    return html;
+}
+
+// The node and its descendants as JSON, for programs that read a parsed document without an HTML parser of their own
+// Each node is an object with its type, an element has its name, its attributes as an object and its children, and text and comments have their data
+pub fn serialize_as_json(node: &RefNode) -> String {
+    return serde_json::to_string_pretty(&node_to_json(node)).unwrap_or_default();
+}
+
+// https://github.com/html5lib/html5lib-tests/tree/master/tree-construction
+// The descendants of node in the format of the html5lib tree construction tests, a line for each node indented by its depth, e.g.
+//   | <html>
+//   |   <head>
+//   |   <body>
+//   |     class="a"
+//   |     "text"
+// The attributes of an element are sorted by name, so the same tree is always written the same way
+pub fn serialize_as_tree(node: &RefNode) -> String {
+    let mut tree = String::new();
+    // The nodes still to write with their depths, last child first so the first child is written first
+    let mut nodes: Vec<(RefNode, usize)> = node.borrow().childNodes.iter().rev().map(|child| (Rc::clone(child), 0)).collect();
+    while let Some((node, depth)) = nodes.pop() {
+        write_tree_node(&node, depth, &mut tree);
+        nodes.extend(node.borrow().childNodes.iter().rev().map(|child| (Rc::clone(child), depth + 1)));
+    }
+    return tree;
+}
+
+// The lines of node itself, without its children
+fn write_tree_node(node: &RefNode, depth: usize, tree: &mut String) {
+    let indent = format!("| {}", "  ".repeat(depth));
+    let node = node.borrow();
+    match &node.data {
+        NodeData::Element(element) => {
+            // An element that is not an HTML one has its namespace in front of its name
+            let prefix = match element.namespace_uri().map(String::as_str) {
+                Some(SVG_NAMESPACE) => "svg ",
+                Some(MATHML_NAMESPACE) => "math ",
+                _ => ""
+            };
+            tree.push_str(&format!("{}<{}{}>\n", indent, prefix, element.local_name()));
+            let mut attributes: Vec<&Attr> = element.attribute_list().iter().collect();
+            attributes.sort_by(|a, b| a.local_name.as_str().cmp(b.local_name.as_str()));
+            for attribute in attributes {
+                tree.push_str(&format!("{}  {}=\"{}\"\n", indent, attribute.local_name, attribute.value));
+            }
+        },
+        NodeData::Text(text) => tree.push_str(&format!("{}\"{}\"\n", indent, text.character_data.data)),
+        NodeData::CharacterData(character_data) => tree.push_str(&format!("{}\"{}\"\n", indent, character_data.data)),
+        NodeData::Comment(comment) => tree.push_str(&format!("{}<!-- {} -->\n", indent, comment.character_data.data)),
+        // The identifiers are only written when the DOCTYPE has one of them
+        NodeData::DocumentType(doctype) if doctype.public_id.is_empty() && doctype.system_id.is_empty() => tree.push_str(&format!("{}<!DOCTYPE {}>\n", indent, doctype.name)),
+        NodeData::DocumentType(doctype) => tree.push_str(&format!("{}<!DOCTYPE {} \"{}\" \"{}\">\n", indent, doctype.name, doctype.public_id, doctype.system_id)),
+        NodeData::Document(_) => {}
+    }
+}
+
+fn node_to_json(node: &RefNode) -> Value {
+    let node = node.borrow();
+    let children = || Value::Array(node.childNodes.iter().map(node_to_json).collect());
+    return match &node.data {
+        NodeData::Document(_) => json!({ "type": "document", "children": children() }),
+        NodeData::DocumentType(doctype) => json!({ "type": "doctype", "name": doctype.name, "publicId": doctype.public_id, "systemId": doctype.system_id }),
+        NodeData::Element(element) => {
+            let attributes: Map<String, Value> = element.attribute_list().iter().map(|attribute| (attribute.local_name.to_string(), Value::String(attribute.value.clone()))).collect();
+            json!({ "type": "element", "name": element.local_name().to_string(), "attributes": attributes, "children": children() })
+        },
+        NodeData::Text(text) => json!({ "type": "text", "data": text.character_data.data }),
+        NodeData::CharacterData(character_data) => json!({ "type": "text", "data": character_data.data }),
+        NodeData::Comment(comment) => json!({ "type": "comment", "data": comment.character_data.data }),
+    };
+}
+
+// What is left to serialize, a node with its descendants or text that is written as it is, e.g. an end tag
+enum Step {
+    Node(RefNode),
+    Literal(String),
+}
+
+fn serialize_children_into(node: &RefNode, html: &mut String) {
+    let mut steps = Vec::new();
+    push_children(node, html, &mut steps);
+    serialize_steps(steps, html);
+}
+
+fn serialize_node_into(node: &RefNode, html: &mut String) {
+    serialize_steps(vec![Step::Node(Rc::clone(node))], html);
+}
+
+// Serializes the steps, last one first, the children of a node are pushed as steps of their own after its end tag so they are written before it
+fn serialize_steps(mut steps: Vec<Step>, html: &mut String) {
+    while let Some(step) = steps.pop() {
+        let node = match step {
+            Step::Node(node) => node,
+            Step::Literal(text) => {
+                html.push_str(&text);
+                continue;
+            }
+        };
+        match &node.borrow().data {
+            NodeData::Element(element) => {
+                html.push_str(&start_tag(element.local_name(), &element.attribute_list().iter().collect::<Vec<&Attr>>()));
+
+                // If current node serializes as void, then continue on to the next child node at this point.
+                if is_void_element(element.local_name()) {
+                    continue;
+                }
+                // Append the value of running the HTML fragment serialization algorithm on current node, followed by a U+003C LESS-THAN SIGN character (<), a U+002F SOLIDUS character (/), tagname again, and finally a U+003E GREATER-THAN SIGN character (>).
+                steps.push(Step::Literal(format!("</{}>", element.local_name())));
+            },
+            // Append the value of running the escaping algorithm on current node's data.
+            NodeData::Text(text) => {
+                html.push_str(&escape_text(&text.character_data.data));
+                continue;
+            },
+            NodeData::CharacterData(character_data) => {
+                html.push_str(&escape_text(&character_data.data));
+                continue;
+            },
+            // Append the literal string "<!--" (U+003C LESS-THAN SIGN, U+0021 EXCLAMATION MARK, U+002D HYPHEN-MINUS, U+002D HYPHEN-MINUS), followed by the value of current node's data IDL attribute, followed by the literal string "-->".
+            NodeData::Comment(comment) => {
+                html.push_str(&format!("<!--{}-->", comment.character_data.data));
+                continue;
+            },
+            // Append the literal string "<!DOCTYPE" (U+003C LESS-THAN SIGN, U+0021 EXCLAMATION MARK, U+0044 LATIN CAPITAL LETTER D, U+004F LATIN CAPITAL LETTER O, U+0043 LATIN CAPITAL LETTER C, U+0054 LATIN CAPITAL LETTER T, U+0059 LATIN CAPITAL LETTER Y, U+0050 LATIN CAPITAL LETTER P, U+0045 LATIN CAPITAL LETTER E), followed by a space (U+0020 SPACE), followed by the value of current node's name IDL attribute, followed by the literal string ">" (U+003E GREATER-THAN SIGN).
+            NodeData::DocumentType(doctype) => {
+                html.push_str(&format!("<!DOCTYPE {}>", doctype.name));
+                continue;
+            },
+            NodeData::Document(_) => {}
+        }
+        push_children(&node, html, &mut steps);
+    }
+}
+
+// Pushes the children of node as steps, last child first so the first child is serialized first
+fn push_children(node: &RefNode, html: &mut String, steps: &mut Vec<Step>) {
+    let node = node.borrow();
+
+    // If current node is a pre, textarea, or listing element, and the first child node of the element, if any, is a Text node whose character data has as its first character a U+000A LINE FEED (LF) character, then append a U+000A LINE FEED (LF) character.
+    // The parser ignores a newline right after the start tag of these elements, so one is added for the newline to survive parsing the HTML again
+    if needs_leading_line_feed(&node) {
+        html.push('\n');
+    }
+
+    let literal_text = match &node.data {
+        NodeData::Element(element) => is_raw_text_element(element.local_name()),
+        _ => false
+    };
+    for child in node.childNodes.iter().rev() {
+        if literal_text {
+            if let NodeData::Text(text) = &child.borrow().data {
+                steps.push(Step::Literal(text.character_data.data.clone()));
+                continue;
+            }
+        }
+        steps.push(Step::Node(Rc::clone(child)));
+    }
+}
+
+// Whether node is a pre, textarea or listing element whose text starts with a line feed, which needs another line feed in front of it to survive parsing
+pub(crate) fn needs_leading_line_feed(node: &Node) -> bool {
+    if let NodeData::Element(element) = &node.data {
+        let starts_with_line_feed = node.childNodes.first().is_some_and(|child| matches!(&child.borrow().data, NodeData::Text(text) if text.character_data.data.starts_with('\n')));
+        return matches!(element.local_name().as_str(), "pre" | "textarea" | "listing") && starts_with_line_feed;
+    }
+    return false;
+}
+
+// Append a U+003C LESS-THAN SIGN character (<), followed by tagname.
+// For each attribute that the element has, append a U+0020 SPACE character, the attribute's serialized name, a U+003D EQUALS SIGN character (=), a U+0022 QUOTATION MARK character ("),
+// the attribute's value, escaped as described below in attribute mode, and a second U+0022 QUOTATION MARK character (").
+// Append a U+003E GREATER-THAN SIGN character (>).
+pub(crate) fn start_tag(local_name: &str, attributes: &[&Attr]) -> String {
+    let mut start_tag = format!("<{}", local_name);
+    for attribute in attributes {
+        start_tag.push(' ');
+        start_tag.push_str(&serialize_attribute(attribute));
+    }
+    start_tag.push('>');
+    return start_tag;
+}
+
+pub(crate) fn serialize_attribute(attribute: &Attr) -> String {
+    return format!("{}=\"{}\"", attribute.local_name, escape_attribute_value(&attribute.value));
+}
+
+// https://html.spec.whatwg.org/multipage/parsing.html#escapingString
+// 1. Replace any occurrence of the "&" character by the string "&amp;".
+// 2. Replace any occurrences of the U+00A0 NO-BREAK SPACE character by the string "&nbsp;".
+// 4. Replace any occurrences of the "<" character by the string "&lt;".
+// 5. Replace any occurrences of the ">" character by the string "&gt;".
+pub fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '\u{00A0}' => escaped.push_str("&nbsp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(character)
+        }
+    }
+    return escaped;
+}
+
+// 3. If the algorithm was invoked in the attribute mode, then replace any occurrences of the """ character by the string "&quot;".
+pub fn escape_attribute_value(value: &str) -> String {
+    return escape_text(value).replace('"', "&quot;");
+}
+
+// https://html.spec.whatwg.org/multipage/syntax.html#void-elements
+// An element that serializes as void has no end tag and no contents
+pub(crate) fn is_void_element(local_name: &str) -> bool {
+    return matches!(local_name, "area" | "base" | "basefont" | "bgsound" | "br" | "col" | "embed" | "frame" | "hr" | "img" | "input" | "keygen" | "link" | "meta" | "param" | "source" | "track" | "wbr");
+}
+
+// If the parent of current node is a style, script, xmp, iframe, noembed, noframes, or plaintext element, or if the parent of current node is a noscript element and scripting is enabled for the node, then append the value of current node's data IDL attribute literally.
+// TODO: The text of a noscript element is escaped, as documents are serialized as if scripting was disabled
+pub(crate) fn is_raw_text_element(local_name: &str) -> bool {
+    return matches!(local_name, "style" | "script" | "xmp" | "iframe" | "noembed" | "noframes" | "plaintext");
+}
@@ -0,0 +1,328 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::display_list::build_display_list;
+use crate::html_document_parser::ParserOptions;
+use crate::png_renderer::render_as_png;
+use crate::serializer::serialize_as_json;
+use crate::text::BuiltinFontMetrics;
+use crate::tokenizer::Tokenizer;
+use crate::tree_limits::TreeLimits;
+use crate::window::Viewport;
+
+// https://httpwg.org/specs/rfc9112.html
+// The engine as a local HTTP service, so it can be used from other languages and CI without bindings
+// The HTML to parse is POSTed as the body of a request, and the path says what is sent back:
+//     POST /dom            the document as JSON, as dump --format json prints it
+//     POST /parse-errors   the parse errors as a JSON array of their messages, lines and columns
+//     POST /text           the document rendered as plain text, ?columns=N wide
+//     POST /png            the document rendered as a PNG image, ?width=N pixels wide
+//     POST /               the same as the default path of the options, /dom unless the server is started with another
+// e.g. curl --data-binary @page.html http://127.0.0.1:8080/dom
+// Scripts are only run with ?scripting=on, for as long as the script time of the options
+// Each connection is served on a thread of its own and is closed after one request
+// Requests are logged on stderr, or in the log file of the options, as their request log says, e.g. POST /text?columns=60 200 12.5ms
+// TODO: The body needs a Content-Length, a chunked request body is refused
+// TODO: There is no authentication, the server is meant to listen on a loopback address
+
+pub struct ServerOptions {
+    // A request whose body is bigger is refused, so a client can not make the server hold any amount of input
+    pub max_body_size: usize,
+    // How long the event loop of a page runs for when its scripts are run
+    pub script_time: Duration,
+    // How long reading a request may take
+    pub read_timeout: Duration,
+    // The tree a request's HTML may parse into, a document that goes over a limit is refused
+    pub tree_limits: TreeLimits,
+    // The path HTML POSTed to / is answered as, one of PATHS
+    pub default_path: &'static str,
+    pub request_log: RequestLog,
+    // The file requests are logged in instead of stderr, it is written over when the server starts
+    pub log_path: Option<PathBuf>,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        return ServerOptions {
+            max_body_size: 16 * 1024 * 1024,
+            script_time: Duration::from_millis(1000),
+            read_timeout: Duration::from_secs(30),
+            tree_limits: TreeLimits::for_untrusted_input(),
+            default_path: "/dom",
+            request_log: RequestLog::Failures,
+            log_path: None
+        };
+    }
+}
+
+// Which requests are logged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestLog {
+    // Only the requests the engine failed on
+    Failures,
+    // A line for each request with its status and how long it took
+    Requests,
+    // The line of each request with the size of its body and of its response as well
+    Details,
+}
+
+// The paths HTML can be POSTed to, besides / which is answered as the default path
+pub const PATHS: &[&str] = &["/dom", "/parse-errors", "/text", "/png"];
+
+// A request, the preview server shares the reading and writing of requests with this one
+pub(crate) struct Request {
+    pub(crate) method: String,
+    pub(crate) path: String,
+    pub(crate) query: Vec<(String, String)>,
+    pub(crate) body: Vec<u8>,
+}
+
+impl Request {
+    pub(crate) fn query_value(&self, name: &str) -> Option<&str> {
+        return self.query.iter().find(|(query_name, _)| query_name == name).map(|(_, value)| value.as_str());
+    }
+}
+
+pub(crate) struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    pub(crate) fn new(status: u16, content_type: &'static str, body: Vec<u8>) -> Response {
+        return Response { status, content_type, body };
+    }
+
+    // An error is sent as JSON so clients can tell it apart from a document the same way for every path
+    pub(crate) fn error(status: u16, message: &str) -> Response {
+        return Response::new(status, "application/json", format!("{}\n", serde_json::json!({ "error": message })).into_bytes());
+    }
+}
+
+// Accepts connections until the listener fails, e.g. serve(TcpListener::bind("127.0.0.1:8080")?, ServerOptions::default())
+pub fn serve(listener: TcpListener, options: ServerOptions) -> std::io::Result<()> {
+    let log: Box<dyn Write + Send> = match &options.log_path {
+        Some(path) => Box::new(File::create(path).map_err(|error| io::Error::new(error.kind(), format!("Could not write {}: {}", path.display(), error)))?),
+        None => Box::new(io::stderr())
+    };
+    let log = Arc::new(Mutex::new(log));
+    let options = Arc::new(options);
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            // A connection that was reset before it was accepted does not stop the server
+            Err(_) => continue
+        };
+        let (options, log) = (options.clone(), log.clone());
+        thread::spawn(move || handle_connection(stream, &options, &log));
+    }
+    return Ok(());
+}
+
+fn handle_connection(mut stream: TcpStream, options: &ServerOptions, log: &Mutex<Box<dyn Write + Send>>) {
+    let start = Instant::now();
+    let _ = stream.set_read_timeout(Some(options.read_timeout));
+    let body_paths = [&["/"], PATHS].concat();
+    let (request, response) = match read_request(&mut stream, options.max_body_size, &body_paths) {
+        // A document the parser panics on is a bug in the engine, the client is told rather than left without a response
+        Ok(request) => {
+            let response = panic::catch_unwind(AssertUnwindSafe(|| respond(&request, options)))
+                .unwrap_or_else(|_| Response::error(500, "The engine failed on the document"));
+            (Some(request), response)
+        },
+        Err(response) => (None, response)
+    };
+    write_response(&mut stream, &response);
+    if let Some(line) = request_log_line(options.request_log, request.as_ref(), &response, start.elapsed()) {
+        // A log that can not be written to does not stop requests from being served
+        let _ = writeln!(log.lock().unwrap(), "{}", line);
+    }
+}
+
+// A request that could not be read is logged with a - for its method and target
+fn request_log_line(request_log: RequestLog, request: Option<&Request>, response: &Response, duration: Duration) -> Option<String> {
+    if request_log == RequestLog::Failures && response.status != 500 {
+        return None;
+    }
+    let target = match request {
+        Some(request) if request.query.is_empty() => format!("{} {}", request.method, request.path),
+        Some(request) => {
+            let query: Vec<String> = request.query.iter().map(|(name, value)| format!("{}={}", name, value)).collect();
+            format!("{} {}?{}", request.method, request.path, query.join("&"))
+        },
+        None => String::from("- -")
+    };
+    let mut line = format!("{} {} {:.1?}", target, response.status, duration);
+    if request_log == RequestLog::Details {
+        let body_size = request.map_or(0, |request| request.body.len());
+        line.push_str(&format!(", {} bytes of HTML, {} bytes of {}", body_size, response.body.len(), response.content_type));
+    }
+    return Some(line);
+}
+
+// The response is the last thing sent on a connection
+pub(crate) fn write_response(stream: &mut TcpStream, response: &Response) {
+    let head = format!("HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status, reason_phrase(response.status), response.content_type, response.body.len());
+    // The client may have gone away, there is nobody to tell
+    let _ = stream.write_all(head.as_bytes()).and_then(|_| stream.write_all(&response.body)).and_then(|_| stream.flush());
+}
+
+// https://httpwg.org/specs/rfc9112.html#message.format
+// The request line, the headers up to an empty line and a body of Content-Length bytes
+// Only a POST to one of body_paths has its body read, the others are answered without it
+pub(crate) fn read_request(stream: &mut TcpStream, max_body_size: usize, body_paths: &[&str]) -> Result<Request, Response> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return Err(Response::error(400, "The request line could not be read"));
+    }
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(target), Some(version)) if version.starts_with("HTTP/1.") => (method.to_string(), target.to_string()),
+        _ => return Err(Response::error(400, "The request line is not an HTTP/1.1 request line"))
+    };
+
+    let mut content_length = None;
+    let mut chunked = false;
+    loop {
+        let mut line = String::new();
+        match reader.read_line(&mut line) {
+            Ok(0) | Err(_) => return Err(Response::error(400, "The request ended before its headers")),
+            Ok(_) => ()
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            match name.trim().to_ascii_lowercase().as_str() {
+                "content-length" => content_length = Some(value.trim().parse::<usize>().map_err(|_| Response::error(400, "The Content-Length is not a number"))?),
+                "transfer-encoding" => chunked = true,
+                _ => ()
+            }
+        }
+    }
+
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), parse_query(query)),
+        None => (target, Vec::new())
+    };
+    let mut request = Request { method, path, query, body: Vec::new() };
+    // The body of a request that is refused is not read
+    if request.method != "POST" || !body_paths.contains(&request.path.as_str()) {
+        return Ok(request);
+    }
+
+    // https://httpwg.org/specs/rfc9110.html#status.411
+    let content_length = match content_length {
+        Some(content_length) if !chunked => content_length,
+        _ => return Err(Response::error(411, "The HTML has to be sent with a Content-Length"))
+    };
+    if content_length > max_body_size {
+        return Err(Response::error(413, &format!("The HTML can be at most {} bytes", max_body_size)));
+    }
+    request.body = vec![0; content_length];
+    if reader.read_exact(&mut request.body).is_err() {
+        return Err(Response::error(400, "The request ended before its body"));
+    }
+    return Ok(request);
+}
+
+// https://url.spec.whatwg.org/#concept-urlencoded-parser
+// TODO: Names and values are not percent-decoded, every value the server takes is a number or a word
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    return query.split('&').filter(|pair| !pair.is_empty()).map(|pair| match pair.split_once('=') {
+        Some((name, value)) => (name.to_string(), value.to_string()),
+        None => (pair.to_string(), String::new())
+    }).collect();
+}
+
+fn respond(request: &Request, options: &ServerOptions) -> Response {
+    let path = match request.path.as_str() {
+        "/" => options.default_path,
+        path => path
+    };
+    let known_path = PATHS.contains(&path);
+    match (request.method.as_str(), known_path) {
+        ("GET", _) if request.path == "/" => return Response::new(200, "text/plain; charset=utf-8", usage(options.default_path).into_bytes()),
+        ("POST", true) => (),
+        (_, true) => return Response::error(405, &format!("{} takes the HTML as a POST request", request.path)),
+        _ => return Response::error(404, &format!("There is nothing at {}, GET / lists the paths", request.path))
+    }
+
+    let columns = match request.query_value("columns").map(|columns| columns.parse::<usize>()) {
+        Some(Ok(columns)) if columns > 0 => columns,
+        Some(_) => return Response::error(400, "columns has to be a number of columns, e.g. ?columns=80"),
+        None => 80
+    };
+    let width = match request.query_value("width").map(|width| width.parse::<u32>()) {
+        Some(Ok(width)) if width > 0 && width <= 16384 => width,
+        Some(_) => return Response::error(400, "width has to be a number of pixels up to 16384, e.g. ?width=1280"),
+        None => Viewport::default().width
+    };
+    let scripting_enabled = match request.query_value("scripting") {
+        Some("on") => true,
+        Some("off") | None => false,
+        Some(_) => return Response::error(400, "scripting has to be on or off")
+    };
+
+    let parser_options = ParserOptions { scripting_enabled, print_parse_errors: false, tree_limits: options.tree_limits, ..ParserOptions::default() };
+    let mut tokenizer = Tokenizer::from_bytes(request.body.clone(), parser_options);
+    tokenizer.set_viewport(Viewport { width, ..Viewport::default() });
+    tokenizer.run();
+    if let Some(error) = tokenizer.tree_limit_error() {
+        return Response::error(413, &error.to_string());
+    }
+    if scripting_enabled {
+        tokenizer.run_event_loop(Some(Instant::now() + options.script_time));
+    }
+
+    match path {
+        "/dom" => return Response::new(200, "application/json", format!("{}\n", serialize_as_json(&tokenizer.document())).into_bytes()),
+        "/parse-errors" => {
+            let parse_errors: Vec<_> = tokenizer.parse_errors().iter().zip(tokenizer.parse_error_positions()).map(|(message, position)| serde_json::json!({
+                "message": message,
+                "line": position.map(|position| position.line),
+                "column": position.map(|position| position.column),
+            })).collect();
+            return Response::new(200, "application/json", format!("{}\n", serde_json::to_string_pretty(&parse_errors).unwrap_or_default()).into_bytes());
+        },
+        "/text" => return Response::new(200, "text/plain; charset=utf-8", tokenizer.render_as_text(columns).into_bytes()),
+        _ => {
+            let metrics = BuiltinFontMetrics::default();
+            let display_list = build_display_list(&tokenizer.document(), width as f32, &metrics);
+            return Response::new(200, "image/png", render_as_png(&display_list, &metrics));
+        }
+    }
+}
+
+fn usage(default_path: &str) -> String {
+    return format!("POST the HTML of a document to one of:
+  /dom            the document as JSON
+  /parse-errors   the parse errors as JSON
+  /text           the document rendered as text, ?columns=N wide
+  /png            the document rendered as a PNG image, ?width=N pixels wide
+  /               the same as {}
+Scripts are run with ?scripting=on
+", default_path);
+}
+
+// https://httpwg.org/specs/rfc9110.html#status.codes
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Content Too Large",
+        _ => "Internal Server Error"
+    }
+}
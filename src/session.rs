@@ -0,0 +1,170 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Instant;
+use crate::html_document_parser::ParserOptions;
+use crate::node::RefNode;
+use crate::resource_loader::{ResourceLoader, Response};
+use crate::text_renderer::render_as_text_with_links;
+use crate::tokenizer::Tokenizer;
+use crate::url::Url;
+
+// https://html.spec.whatwg.org/multipage/document-sequences.html#tab
+// A browsing session, which loads the pages it navigates to, keeps the document of the current one and remembers where it has been
+// TODO: Documents are not kept in the session history, going back or forward loads the page again (the loader may answer from its cache)
+// TODO: Pages are parsed as HTML whatever their Content-Type is, and redirects and form submissions do not navigate
+
+// Lets the session and the interpreters of its documents load resources with the same loader, e.g. so they share one cache
+#[derive(Clone)]
+struct SharedResourceLoader(Rc<RefCell<Box<dyn ResourceLoader>>>);
+
+impl ResourceLoader for SharedResourceLoader {
+    fn load(&mut self, url: &Url) -> Result<Response, String> {
+        return self.0.borrow_mut().load(url);
+    }
+
+    fn load_with_headers(&mut self, url: &Url, header_list: &[(String, String)]) -> Result<Response, String> {
+        return self.0.borrow_mut().load_with_headers(url, header_list);
+    }
+}
+
+pub struct Session {
+    loader: SharedResourceLoader,
+    // https://html.spec.whatwg.org/multipage/document-sequences.html#tn-session-history-entries
+    history: Vec<Url>,
+    // The index of the current entry in history
+    current_index: usize,
+    // The parser of the current document, whose interpreter runs its scripts
+    current: Option<Tokenizer>,
+    scripting_enabled: bool,
+}
+
+impl Session {
+    pub fn new(loader: Box<dyn ResourceLoader>) -> Session {
+        Session { loader: SharedResourceLoader(Rc::new(RefCell::new(loader))), history: Vec::new(), current_index: 0, current: None, scripting_enabled: true }
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#scripting-flag
+    // Applies to the documents loaded after it is set
+    pub fn set_scripting_enabled(&mut self, scripting_enabled: bool) {
+        self.scripting_enabled = scripting_enabled;
+    }
+
+    // The URL of the current session history entry, None before the first navigation
+    pub fn current_url(&self) -> Option<&Url> {
+        return self.history.get(self.current_index);
+    }
+
+    pub fn document(&self) -> Option<RefNode> {
+        return self.current.as_ref().map(|tokenizer| tokenizer.document());
+    }
+
+    pub fn history(&self) -> &[Url] {
+        return &self.history;
+    }
+
+    // The index of the current entry in history
+    pub fn current_index(&self) -> usize {
+        return self.current_index;
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        return !self.history.is_empty() && self.current_index > 0;
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        return self.current_index + 1 < self.history.len();
+    }
+
+    // https://html.spec.whatwg.org/multipage/browsing-the-web.html#navigate
+    // Loads the page at url and makes it the current entry, the entries after the current one are removed from the history
+    // An Err is a network error, the current page and the history stay as they were
+    pub fn navigate(&mut self, url: &Url) -> Result<(), String> {
+        // https://html.spec.whatwg.org/multipage/browsing-the-web.html#navigate-fragid
+        // When only the fragment is different, the navigation scrolls the same document instead of loading it again
+        let is_fragment_navigation = url.fragment.is_some() && self.current.is_some() && self.current_url().is_some_and(|current_url| {
+            let (mut current_url, mut url) = (current_url.clone(), url.clone());
+            current_url.fragment = None;
+            url.fragment = None;
+            current_url == url
+        });
+        if is_fragment_navigation {
+            if let Some(tokenizer) = &mut self.current {
+                tokenizer.set_document_url(url.clone());
+            }
+        } else {
+            self.current = Some(self.load_document(url)?);
+        }
+
+        if !self.history.is_empty() {
+            self.history.truncate(self.current_index + 1);
+        }
+        self.history.push(url.clone());
+        self.current_index = self.history.len() - 1;
+        return Ok(());
+    }
+
+    // https://html.spec.whatwg.org/multipage/browsing-the-web.html#traverse-the-history-by-a-delta
+    // Makes the entry before the current one current, false when there is none
+    pub fn back(&mut self) -> Result<bool, String> {
+        if !self.can_go_back() {
+            return Ok(false);
+        }
+        self.traverse(self.current_index - 1)?;
+        return Ok(true);
+    }
+
+    // Makes the entry after the current one current, false when there is none
+    pub fn forward(&mut self) -> Result<bool, String> {
+        if !self.can_go_forward() {
+            return Ok(false);
+        }
+        self.traverse(self.current_index + 1)?;
+        return Ok(true);
+    }
+
+    // https://html.spec.whatwg.org/multipage/browsing-the-web.html#reload
+    // Loads the current page again without changing the history, false when there is no current page
+    pub fn reload(&mut self) -> Result<bool, String> {
+        if self.current_url().is_none() {
+            return Ok(false);
+        }
+        self.traverse(self.current_index)?;
+        return Ok(true);
+    }
+
+    fn traverse(&mut self, index: usize) -> Result<(), String> {
+        let url = self.history[index].clone();
+        self.current = Some(self.load_document(&url)?);
+        self.current_index = index;
+        return Ok(());
+    }
+
+    // The current document as text with the URLs of its numbered links, as the text renderer numbers them
+    pub fn render_as_text(&self, columns: usize) -> Option<(String, Vec<String>)> {
+        return self.document().map(|document| render_as_text_with_links(&document, columns));
+    }
+
+    // Navigates to the link of the current page with the given number, an Err when there is no such link
+    pub fn follow_link(&mut self, number: usize) -> Result<(), String> {
+        let links = self.render_as_text(80).map(|(_, links)| links).unwrap_or_default();
+        let href = match number.checked_sub(1).and_then(|index| links.get(index)) {
+            Some(href) => href.clone(),
+            None => return Err(format!("There is no link {}", number))
+        };
+        let url = Url::parse(&href, self.current_url()).map_err(|error| format!("{}: {}", href, error))?;
+        return self.navigate(&url);
+    }
+
+    // Loads and parses the page at url, running its scripts and the tasks that are due once it has been parsed
+    fn load_document(&mut self, url: &Url) -> Result<Tokenizer, String> {
+        let response = self.loader.load(url)?;
+        let options = ParserOptions { scripting_enabled: self.scripting_enabled, print_parse_errors: false, ..ParserOptions::default() };
+        let mut tokenizer = Tokenizer::from_bytes(response.body, options);
+        // Relative URLs are resolved against the URL of the response, as the loader may have answered for another URL
+        tokenizer.set_document_url(Url::parse(&response.url, None).unwrap_or_else(|_| url.clone()));
+        tokenizer.set_resource_loader(Box::new(self.loader.clone()));
+        tokenizer.run();
+        tokenizer.run_event_loop(Some(Instant::now()));
+        return Ok(tokenizer);
+    }
+}
@@ -0,0 +1,295 @@
+use std::rc::Rc;
+use crate::html_document_parser::ParserOptions;
+use crate::node::{DocumentMode, NodeData, RefNode};
+use crate::selector::{parse_selector_list, SelectorElement};
+use crate::source_location::SourceLocation;
+use crate::tokenizer::Tokenizer;
+use crate::url::Url;
+
+// A read-only copy of a document that can be shared between threads, e.g. parsed once by a server and queried by each of its request handlers
+// A document made of nodes is reference counted and borrowed at run time (Rc<RefCell<Node>>), so it can not leave the thread that parsed it
+// The nodes of a shared document are kept in an arena instead, in tree order, and refer to each other by their index in it
+// It holds no reference counted or interned values, so it is Send and Sync and can be put in an Arc as it is
+// Scripts and events need the nodes a script can change, so a shared document is a snapshot of a document once it has been parsed (and its scripts run)
+pub struct SharedDocument {
+    nodes: Vec<NodeEntry>,
+    // https://dom.spec.whatwg.org/#concept-document-url
+    pub url: Url,
+    // https://dom.spec.whatwg.org/#concept-document-mode
+    pub mode: DocumentMode,
+    // The parse errors of the document in the order they were found, empty for a document copied from nodes
+    pub parse_errors: Vec<String>,
+}
+
+// The index of a node in the arena of its shared document, the document itself is the first one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    // The position of the node in tree order, which is how a node is named outside of Rust (see ffi)
+    pub fn index(&self) -> usize {
+        return self.0;
+    }
+}
+
+struct NodeEntry {
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    // The descendants of a node come right after it in tree order, so they are the nodes up to this index
+    descendants_end: usize,
+    data: SharedNodeData,
+    source_location: Option<SourceLocation>,
+}
+
+// What a node is, with the data of each kind of node copied out of it
+#[derive(Debug, Clone, PartialEq)]
+pub enum SharedNodeData {
+    Document,
+    DocumentType { name: String, public_id: String, system_id: String },
+    Element { local_name: String, attributes: Vec<(String, String)> },
+    Text(String),
+    Comment(String),
+}
+
+// Shared documents are made to cross threads, this stops them from building if a field that can not is ever added
+const _: fn() = || {
+    fn assert_send_and_sync<T: Send + Sync>() {}
+    assert_send_and_sync::<SharedDocument>();
+};
+
+impl SharedDocument {
+    // Parses HTML into a shared document, scripting is disabled as there is nothing to run them against once the document is shared
+    pub fn parse(html: String) -> SharedDocument {
+        return SharedDocument::parse_with(Tokenizer::with_options(html, SharedDocument::parser_options()));
+    }
+
+    // Parses input that is not known to be text, see Tokenizer::from_bytes
+    pub fn parse_bytes(bytes: Vec<u8>) -> SharedDocument {
+        return SharedDocument::parse_with(Tokenizer::from_bytes(bytes, SharedDocument::parser_options()));
+    }
+
+    fn parser_options() -> ParserOptions {
+        return ParserOptions { scripting_enabled: false, print_parse_errors: false, ..ParserOptions::default() };
+    }
+
+    fn parse_with(mut tokenizer: Tokenizer) -> SharedDocument {
+        tokenizer.run();
+
+        let mut shared_document = SharedDocument::from_document(&tokenizer.document());
+        shared_document.parse_errors = tokenizer.parse_errors().to_vec();
+        return shared_document;
+    }
+
+    // Copies a document, or any other node and its descendants, into a shared document, the node is its root
+    pub fn from_document(document: &RefNode) -> SharedDocument {
+        let (url, mode) = match &document.borrow().data {
+            NodeData::Document(document) => (document.url.clone(), document.mode),
+            _ => (Url::about_blank(), DocumentMode::NoQuirks)
+        };
+
+        let mut shared_document = SharedDocument { nodes: Vec::new(), url, mode, parse_errors: Vec::new() };
+        shared_document.copy_node(document, None);
+        return shared_document;
+    }
+
+    // Copies node and its descendants in tree order, with a stack of the nodes still to copy rather than recursion so a deeply nested document cannot overflow the call stack
+    fn copy_node(&mut self, node: &RefNode, parent: Option<NodeId>) {
+        let first = self.nodes.len();
+        let mut nodes = vec![(Rc::clone(node), parent)];
+        while let Some((node, parent)) = nodes.pop() {
+            let id = NodeId(self.nodes.len());
+            let node = node.borrow();
+            let data = match &node.data {
+                NodeData::Document(_) => SharedNodeData::Document,
+                NodeData::DocumentType(document_type) => SharedNodeData::DocumentType {
+                    name: document_type.name.clone(),
+                    public_id: document_type.public_id.clone(),
+                    system_id: document_type.system_id.clone(),
+                },
+                NodeData::Element(element) => SharedNodeData::Element {
+                    local_name: element.local_name().to_string(),
+                    attributes: element.attribute_list().iter().map(|attribute| (attribute.local_name.to_string(), attribute.value.clone())).collect(),
+                },
+                NodeData::Text(text) => SharedNodeData::Text(text.character_data.data.clone()),
+                NodeData::CharacterData(character_data) => SharedNodeData::Text(character_data.data.clone()),
+                NodeData::Comment(comment) => SharedNodeData::Comment(comment.character_data.data.clone()),
+            };
+            self.nodes.push(NodeEntry { parent, children: Vec::with_capacity(node.childNodes.len()), descendants_end: id.0 + 1, data, source_location: node.source_location() });
+            if let Some(parent) = parent {
+                self.nodes[parent.0].children.push(id);
+            }
+            // Pushed last child first, so the first child is the next node copied
+            nodes.extend(node.childNodes.iter().rev().map(|child| (Rc::clone(child), Some(id))));
+        }
+
+        // The descendants of a node end where those of its last child end, and its children come after it, so going backwards each child's end is known before its parent's
+        for index in (first..self.nodes.len()).rev() {
+            if let Some(last_child) = self.nodes[index].children.last() {
+                self.nodes[index].descendants_end = self.nodes[last_child.0].descendants_end;
+            }
+        }
+    }
+
+    // The node everything else in the shared document descends from, the document unless it was copied from another node
+    pub fn root(&self) -> SharedNode<'_> {
+        return self.node(NodeId(0));
+    }
+
+    pub fn node(&self, id: NodeId) -> SharedNode<'_> {
+        assert!(id.0 < self.nodes.len(), "{:?} is not a node of this document", id);
+        return SharedNode { document: self, id };
+    }
+
+    // The node at a position in tree order, None past the last node
+    pub fn node_at(&self, index: usize) -> Option<SharedNode<'_>> {
+        if index < self.nodes.len() {
+            return Some(self.node(NodeId(index)));
+        }
+        return None;
+    }
+
+    // The number of nodes in the document, including the document itself
+    pub fn len(&self) -> usize {
+        return self.nodes.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.nodes.is_empty();
+    }
+
+    // https://dom.spec.whatwg.org/#dom-document-documentelement
+    pub fn document_element(&self) -> Option<SharedNode<'_>> {
+        return self.root().children().find(|child| child.is_element());
+    }
+
+    // https://dom.spec.whatwg.org/#dom-nonelementparentnode-getelementbyid
+    pub fn get_element_by_id(&self, id: &str) -> Option<SharedNode<'_>> {
+        return self.root().descendants().find(|node| node.get_attribute("id") == Some(id));
+    }
+
+    // https://dom.spec.whatwg.org/#dom-parentnode-queryselector
+    // Returns None when the selectors are not valid, as well as when no element matches them
+    pub fn query_selector(&self, selectors: &str) -> Option<SharedNode<'_>> {
+        let selector_list = parse_selector_list(selectors)?;
+        return self.root().descendants().find(|node| selector_list.matches(node));
+    }
+
+    // https://dom.spec.whatwg.org/#dom-parentnode-queryselectorall
+    pub fn query_selector_all(&self, selectors: &str) -> Result<Vec<SharedNode<'_>>, String> {
+        let selector_list = parse_selector_list(selectors).ok_or(format!("'{}' is not a valid selector", selectors))?;
+        return Ok(self.root().descendants().filter(|node| selector_list.matches(node)).collect());
+    }
+}
+
+// A node of a shared document, which borrows the document rather than owning a part of it
+#[derive(Clone, Copy)]
+pub struct SharedNode<'a> {
+    document: &'a SharedDocument,
+    id: NodeId,
+}
+
+impl<'a> SharedNode<'a> {
+    fn entry(&self) -> &'a NodeEntry {
+        return &self.document.nodes[self.id.0];
+    }
+
+    pub fn id(&self) -> NodeId {
+        return self.id;
+    }
+
+    pub fn data(&self) -> &'a SharedNodeData {
+        return &self.entry().data;
+    }
+
+    // Where the parser found the node in its input, see Node::source_location
+    pub fn source_location(&self) -> Option<SourceLocation> {
+        return self.entry().source_location;
+    }
+
+    // https://dom.spec.whatwg.org/#dom-node-parentnode
+    pub fn parent(&self) -> Option<SharedNode<'a>> {
+        return self.entry().parent.map(|parent| self.document.node(parent));
+    }
+
+    // https://dom.spec.whatwg.org/#concept-tree-child
+    pub fn children(&self) -> impl Iterator<Item = SharedNode<'a>> + 'a {
+        let document = self.document;
+        return self.entry().children.iter().map(move |child| document.node(*child));
+    }
+
+    // https://dom.spec.whatwg.org/#concept-tree-descendant
+    // In tree order, which is the order of the arena
+    pub fn descendants(&self) -> impl Iterator<Item = SharedNode<'a>> + 'a {
+        let document = self.document;
+        return (self.id.0 + 1..self.entry().descendants_end).map(move |index| document.node(NodeId(index)));
+    }
+
+    pub fn is_element(&self) -> bool {
+        return matches!(self.data(), SharedNodeData::Element { .. });
+    }
+
+    // https://dom.spec.whatwg.org/#dom-element-localname
+    pub fn local_name(&self) -> Option<&'a str> {
+        return match self.data() {
+            SharedNodeData::Element { local_name, .. } => Some(local_name),
+            _ => None
+        };
+    }
+
+    // The attributes of an element in the order they were appended, empty for any other node
+    pub fn attributes(&self) -> &'a [(String, String)] {
+        return match self.data() {
+            SharedNodeData::Element { attributes, .. } => attributes,
+            _ => &[]
+        };
+    }
+
+    // https://dom.spec.whatwg.org/#dom-element-getattribute
+    pub fn get_attribute(&self, qualified_name: &str) -> Option<&'a str> {
+        return self.attributes().iter().find(|(name, _)| name == qualified_name).map(|(_, value)| value.as_str());
+    }
+
+    // https://dom.spec.whatwg.org/#dom-node-textcontent
+    pub fn text_content(&self) -> Option<String> {
+        return match self.data() {
+            SharedNodeData::Element { .. } => {
+                let mut text_content = String::new();
+                for descendant in self.descendants() {
+                    if let SharedNodeData::Text(text) = descendant.data() {
+                        text_content.push_str(text);
+                    }
+                }
+                Some(text_content)
+            },
+            SharedNodeData::Text(data) | SharedNodeData::Comment(data) => Some(data.clone()),
+            SharedNodeData::Document | SharedNodeData::DocumentType { .. } => None
+        };
+    }
+}
+
+impl<'a> SelectorElement for SharedNode<'a> {
+    fn is_element(&self) -> bool {
+        return SharedNode::is_element(self);
+    }
+
+    fn has_local_name(&self, local_name: &str) -> bool {
+        return self.local_name().map(|name| name.eq_ignore_ascii_case(local_name)).unwrap_or(false);
+    }
+
+    fn attribute_matches(&self, name: &str, predicate: &dyn Fn(&str) -> bool) -> bool {
+        return self.get_attribute(name).map(predicate).unwrap_or(false);
+    }
+
+    fn parent_element(&self) -> Option<SharedNode<'a>> {
+        return self.parent().filter(|parent| parent.is_element());
+    }
+
+    fn previous_element_sibling(&self) -> Option<SharedNode<'a>> {
+        let id = self.id();
+        return self.parent()?.children().take_while(|sibling| sibling.id() != id).filter(|sibling| sibling.is_element()).last();
+    }
+
+    fn is_root(&self) -> bool {
+        return self.is_element() && self.parent().is_some_and(|parent| matches!(parent.data(), SharedNodeData::Document));
+    }
+}
@@ -0,0 +1,174 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::html_document_parser::ParserOptions;
+use crate::serializer::serialize_as_tree;
+use crate::tokenizer::Tokenizer;
+
+// Snapshot tests of the tree builder, the tree an HTML string parses into is compared against a tree checked in next to the tests
+// A snapshot is a file in the format of the html5lib tree construction tests, with the HTML after #data and its tree after #document, e.g.
+//   #data
+//   <p>One<p>Two
+//   #document
+//   | <html>
+//   |   <head>
+//   |   <body>
+//   |     <p>
+//   |       "One"
+//   |     <p>
+//   |       "Two"
+// A test checks a snapshot with assert_dom_snapshot!, and the snapshot command checks every snapshot in a directory, tests/snapshots by default
+// When UPDATE_SNAPSHOTS is set (or the snapshot command is given --update) snapshots that are missing or differ are written instead of failing
+// TODO: Parse errors are not part of a snapshot yet, so there is no #errors section
+
+pub const UPDATE_VARIABLE: &str = "UPDATE_SNAPSHOTS";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    // The snapshot was written, as it was missing or differed and snapshots are being updated
+    Updated,
+    // The snapshot does not exist
+    Missing,
+    // The tree the HTML parses into now, and the line that first differs from the snapshot's tree with the two versions of it
+    Fail { tree: String, first_difference: (usize, String, String) },
+    // A file could not be read or written, or is not a snapshot
+    Error(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub html: String,
+    pub tree: String,
+}
+
+impl Snapshot {
+    pub fn parse(text: &str) -> Option<Snapshot> {
+        let html = text.strip_prefix("#data\n")?;
+        let (html, tree) = html.split_once("\n#document\n").or_else(|| html.strip_prefix("#document\n").map(|tree| ("", tree)))?;
+        return Some(Snapshot { html: html.to_string(), tree: tree.to_string() });
+    }
+
+    pub fn to_text(&self) -> String {
+        return format!("#data\n{}\n#document\n{}", self.html, self.tree);
+    }
+}
+
+// The tree html parses into as a whole document with scripting disabled, a line for each node
+pub fn dom_snapshot(html: &str) -> String {
+    let mut tokenizer = Tokenizer::from_bytes(html.as_bytes().to_vec(), ParserOptions { scripting_enabled: false, print_parse_errors: false, ..ParserOptions::default() });
+    tokenizer.run();
+    return serialize_as_tree(&tokenizer.document());
+}
+
+// Whether snapshots are written instead of compared, from UPDATE_SNAPSHOTS
+pub fn update_requested() -> bool {
+    return env::var(UPDATE_VARIABLE).is_ok_and(|value| !matches!(value.as_str(), "" | "0" | "no"));
+}
+
+// Compares the tree html parses into against the snapshot at path, or writes it there when update is set
+pub fn check(path: &Path, html: &str, update: bool) -> Outcome {
+    let tree = dom_snapshot(html);
+    let snapshot = Snapshot { html: html.to_string(), tree };
+
+    let expected = match fs::read_to_string(path) {
+        Ok(text) => match Snapshot::parse(&text) {
+            Some(expected) => Some(expected),
+            None => return Outcome::Error(format!("{}: not a snapshot, it needs a #data and a #document section", path.display()))
+        },
+        Err(_) if !path.exists() => None,
+        Err(error) => return Outcome::Error(format!("{}: {}", path.display(), error))
+    };
+    if expected.as_ref() == Some(&snapshot) {
+        return Outcome::Pass;
+    }
+
+    if update {
+        if let Some(directory) = path.parent() {
+            let _ = fs::create_dir_all(directory);
+        }
+        return match fs::write(path, snapshot.to_text()) {
+            Ok(()) => Outcome::Updated,
+            Err(error) => Outcome::Error(format!("{}: {}", path.display(), error))
+        };
+    }
+    return match expected {
+        None => Outcome::Missing,
+        Some(expected) if expected.html != snapshot.html => Outcome::Error(format!("{}: the snapshot is of different HTML, update it or give the test another name", path.display())),
+        Some(expected) => {
+            let first_difference = first_difference(&snapshot.tree, &expected.tree);
+            Outcome::Fail { tree: snapshot.tree, first_difference }
+        }
+    };
+}
+
+// Checks the snapshot at path against the HTML it was taken of, which is how the snapshots of a directory are run
+pub fn check_file(path: &Path, update: bool) -> Outcome {
+    let snapshot = match fs::read_to_string(path).map(|text| Snapshot::parse(&text)) {
+        Ok(Some(snapshot)) => snapshot,
+        Ok(None) => return Outcome::Error(format!("{}: not a snapshot, it needs a #data and a #document section", path.display())),
+        Err(error) => return Outcome::Error(format!("{}: {}", path.display(), error))
+    };
+    return check(path, &snapshot.html, update);
+}
+
+// The .snap files of directory, sorted so they are run in the same order everywhere
+pub fn snapshot_files(directory: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(directory).map_err(|error| format!("{}: {}", directory.display(), error))?;
+    let mut paths: Vec<PathBuf> = entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).filter(|path| path.extension().is_some_and(|extension| extension == "snap")).collect();
+    paths.sort();
+    return Ok(paths);
+}
+
+// The name of the snapshot of a test, from the path of the function assert_dom_snapshot! is used in
+// e.g. tree_builder::tables::implied_tbody::{{closure}} becomes tree_builder__tables__implied_tbody, without the closures
+pub fn snapshot_name(function_path: &str) -> String {
+    return function_path.split("::").filter(|segment| !segment.starts_with('{')).collect::<Vec<&str>>().join("__");
+}
+
+// The path of the snapshot called name, in the tests/snapshots directory of the crate at manifest_directory
+pub fn snapshot_path(manifest_directory: &str, name: &str) -> PathBuf {
+    return Path::new(manifest_directory).join("tests").join("snapshots").join(format!("{}.snap", name));
+}
+
+// Panics with what differs unless the tree html parses into is the one in the snapshot at path
+pub fn assert_snapshot(path: &Path, html: &str) {
+    match check(path, html, update_requested()) {
+        Outcome::Pass | Outcome::Updated => {},
+        Outcome::Missing => panic!("there is no snapshot at {}, run the test with {}=1 to write it\n{}", path.display(), UPDATE_VARIABLE, dom_snapshot(html)),
+        Outcome::Fail { tree, first_difference: (line, actual, expected) } => {
+            panic!("the tree differs from the snapshot at {} on line {}\n  now:      {}\n  snapshot: {}\nthe whole tree is now\n{}run the test with {}=1 to update the snapshot", path.display(), line + 1, actual, expected, tree, UPDATE_VARIABLE)
+        },
+        Outcome::Error(message) => panic!("{}", message)
+    }
+}
+
+fn first_difference(tree: &str, other: &str) -> (usize, String, String) {
+    let (lines, other_lines): (Vec<&str>, Vec<&str>) = (tree.lines().collect(), other.lines().collect());
+    let line = (0..lines.len().max(other_lines.len())).find(|line| lines.get(*line) != other_lines.get(*line)).unwrap_or(0);
+    let text = |lines: &[&str]| lines.get(line).map_or("(no line)".to_string(), |line| line.to_string());
+    return (line, text(&lines), text(&other_lines));
+}
+
+// Parses html and compares its tree against the snapshot in tests/snapshots of the crate the test is in, e.g.
+//   #[test]
+//   fn implied_tbody() {
+//       assert_dom_snapshot!("<table><tr><td>1</table>");
+//   }
+// The snapshot is named after the test's module and function, or given a name when a test has more than one
+//   assert_dom_snapshot!("implied_tbody_caption", "<table><caption>A<tr><td>1</table>");
+#[macro_export]
+macro_rules! assert_dom_snapshot {
+    ($html:expr) => {{
+        fn function() {}
+        fn type_name<T>(_: T) -> &'static str {
+            return ::std::any::type_name::<T>();
+        }
+        let function_path = type_name(function).trim_end_matches("::function");
+        let name = $crate::snapshot::snapshot_name(function_path);
+        $crate::snapshot::assert_snapshot(&$crate::snapshot::snapshot_path(env!("CARGO_MANIFEST_DIR"), &name), $html);
+    }};
+    ($name:expr, $html:expr) => {{
+        $crate::snapshot::assert_snapshot(&$crate::snapshot::snapshot_path(env!("CARGO_MANIFEST_DIR"), $name), $html);
+    }};
+}
@@ -0,0 +1,116 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+// Where something is in the input a document was parsed from, for tools that map nodes back to their markup (e.g. linters and formatters)
+// Offsets are byte offsets into the input, lines and columns start at 1 and a column counts the characters before it on its line plus one
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourcePosition {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+// The start is the position of the first character and the end is the position just after the last one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub start: SourcePosition,
+    pub end: SourcePosition,
+}
+
+impl SourceLocation {
+    // The range of the input between the start and the end, to slice the input with
+    pub fn range(&self) -> std::ops::Range<usize> {
+        return self.start.offset..self.end.offset;
+    }
+
+    // The location without the line feed it starts with, for text whose first newline the parser ignores (e.g. after a pre start tag)
+    pub fn after_leading_line_feed(&self) -> SourceLocation {
+        if self.start.offset >= self.end.offset {
+            return *self;
+        }
+        let start = SourcePosition { offset: self.start.offset + 1, line: self.start.line + 1, column: 1 };
+        return SourceLocation { start, end: self.end };
+    }
+
+    // The location split after leading, which is the text the location starts with as it is in the input (e.g. the whitespace a run of characters starts with)
+    // Text that came from a character reference is longer in the input, so the split is only exact for text that is exactly as it is in the input
+    pub fn split_after(&self, leading: &str) -> (SourceLocation, SourceLocation) {
+        let mut middle = self.start;
+        for character in leading.chars() {
+            if middle.offset >= self.end.offset {
+                break;
+            }
+            middle.offset += character.len_utf8();
+            if character == '\n' {
+                middle.line += 1;
+                middle.column = 1;
+            } else {
+                middle.column += 1;
+            }
+        }
+        middle.offset = middle.offset.min(self.end.offset);
+        return (SourceLocation { start: self.start, end: middle }, SourceLocation { start: middle, end: self.end });
+    }
+}
+
+impl fmt::Display for SourcePosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+// Turns byte offsets into lines and columns, the input is scanned for line feeds only as far as the furthest offset asked for
+// The tokenizer asks for offsets mostly in order, so the column of the last position is kept and counted on from rather than counting from the start of the line each time (a minified page can be one long line)
+pub(crate) struct LineIndex {
+    // The offset each line starts at, of the lines scanned so far
+    line_starts: Vec<usize>,
+    scanned: usize,
+    last_position: SourcePosition,
+}
+
+impl LineIndex {
+    pub(crate) fn new() -> Self {
+        return LineIndex { line_starts: vec![0], scanned: 0, last_position: SourcePosition { offset: 0, line: 1, column: 1 } };
+    }
+
+    pub(crate) fn position(&mut self, source: &[u8], offset: usize) -> SourcePosition {
+        let offset = offset.min(source.len());
+        while self.scanned < offset {
+            if source[self.scanned] == b'\n' {
+                self.line_starts.push(self.scanned + 1);
+            }
+            self.scanned += 1;
+        }
+
+        let line_index = match self.line_starts.binary_search(&offset) {
+            Ok(line_index) => line_index,
+            Err(line_index) => line_index - 1
+        };
+        let line = line_index + 1;
+
+        // The column is counted from the last position when it is earlier on the same line, otherwise from the start of the line
+        let (counted_from, column) = if self.last_position.line == line && self.last_position.offset <= offset {
+            (self.last_position.offset, self.last_position.column)
+        } else {
+            (self.line_starts[line_index], 1)
+        };
+        // A byte that continues a UTF-8 sequence is not the start of a character
+        let column = column + source[counted_from..offset].iter().filter(|byte| (**byte & 0xC0) != 0x80).count();
+
+        self.last_position = SourcePosition { offset, line, column };
+        return self.last_position;
+    }
+
+    pub(crate) fn location(&mut self, source: &[u8], start: usize, end: usize) -> SourceLocation {
+        let start = self.position(source, start);
+        let end = self.position(source, end.max(start.offset));
+        return SourceLocation { start, end };
+    }
+}
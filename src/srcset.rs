@@ -0,0 +1,418 @@
+use crate::microsyntaxes::{is_ascii_whitespace, parse_floating_point_number, split_on_commas};
+use crate::node::{NodeData, RefNode};
+use crate::window::Viewport;
+
+// https://html.spec.whatwg.org/multipage/images.html#srcset-attributes
+// The image candidates of the srcset and sizes attributes of img and source elements, and the choice of the candidate to load for a viewport, e.g.
+//     <img src="small.jpg" srcset="large.jpg 1600w, medium.jpg 800w" sizes="(max-width: 600px) 100vw, 50vw">
+// is medium.jpg in a 1280 pixel wide viewport at a device pixel ratio of 1, as the image is shown 640 pixels wide, and large.jpg at a ratio of 2
+// TODO: Media conditions are only (min-width), (max-width), (min-height) and (max-height) joined with and, optionally after not, and calc() sizes are not supported
+// TODO: The type of a source element is only compared with a list of the image formats browsers commonly decode, as images are not decoded
+
+// https://html.spec.whatwg.org/multipage/images.html#image-candidate-string
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageCandidate {
+    // The URL as written, to be resolved against the document base URL
+    pub url: String,
+    pub descriptor: CandidateDescriptor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CandidateDescriptor {
+    // A width descriptor, e.g. 800w, the width of the image in image pixels
+    Width(u64),
+    // A pixel density descriptor, e.g. 2x, a candidate without a descriptor is 1x
+    Density(f64),
+}
+
+// https://html.spec.whatwg.org/multipage/images.html#parse-a-srcset-attribute
+// Candidates with invalid descriptors are dropped, the rest are in the order they are written
+pub fn parse_srcset(input: &str) -> Vec<ImageCandidate> {
+    let characters: Vec<char> = input.chars().collect();
+    let mut position = 0;
+    let mut candidates = Vec::new();
+    loop {
+        // 4. Splitting loop: Collect a sequence of code points that are ASCII whitespace or U+002C COMMA characters from input given position.
+        while position < characters.len() && (is_ascii_whitespace(characters[position]) || characters[position] == ',') {
+            position += 1;
+        }
+        // 5. If position is past the end of input, return candidates.
+        if position >= characters.len() {
+            return candidates;
+        }
+        // 6. Collect a sequence of code points that are not ASCII whitespace from input given position, and let that be url.
+        let url_start = position;
+        while position < characters.len() && !is_ascii_whitespace(characters[position]) {
+            position += 1;
+        }
+        let mut url: String = characters[url_start..position].iter().collect();
+        // 7. Let descriptors be a new empty list.
+        let mut descriptors = Vec::new();
+        // 8. If url ends with U+002C (,), then remove all trailing U+002C COMMA characters from url, and there are no descriptors
+        if url.ends_with(',') {
+            url = url.trim_end_matches(',').to_string();
+        } else {
+            position = tokenize_descriptors(&characters, position, &mut descriptors);
+        }
+        // 13. If error is still no, then append a new image source to candidates whose URL is url, associated with a width width if not absent and a pixel density density if not absent.
+        if let Some(descriptor) = parse_descriptors(&descriptors) {
+            if !url.is_empty() {
+                candidates.push(ImageCandidate { url, descriptor });
+            }
+        }
+    }
+}
+
+// 8.2-8.6. The descriptors after a URL, up to the comma that ends the candidate, a comma inside parentheses does not end it
+// Returns the position after the candidate
+fn tokenize_descriptors(characters: &[char], mut position: usize, descriptors: &mut Vec<String>) -> usize {
+    enum State { InDescriptor, InParens, AfterDescriptor }
+    // 8.2. Descriptor tokenizer: Skip ASCII whitespace within input given position.
+    while position < characters.len() && is_ascii_whitespace(characters[position]) {
+        position += 1;
+    }
+    let mut current_descriptor = String::new();
+    let mut state = State::InDescriptor;
+    loop {
+        let character = characters.get(position).copied();
+        match state {
+            State::InDescriptor => match character {
+                Some(character) if is_ascii_whitespace(character) => {
+                    if !current_descriptor.is_empty() {
+                        descriptors.push(std::mem::take(&mut current_descriptor));
+                    }
+                    state = State::AfterDescriptor;
+                },
+                Some(',') => {
+                    if !current_descriptor.is_empty() {
+                        descriptors.push(current_descriptor);
+                    }
+                    return position + 1;
+                },
+                Some('(') => {
+                    current_descriptor.push('(');
+                    state = State::InParens;
+                },
+                Some(character) => current_descriptor.push(character),
+                None => {
+                    if !current_descriptor.is_empty() {
+                        descriptors.push(current_descriptor);
+                    }
+                    return position;
+                }
+            },
+            State::InParens => match character {
+                Some(')') => {
+                    current_descriptor.push(')');
+                    state = State::InDescriptor;
+                },
+                Some(character) => current_descriptor.push(character),
+                None => {
+                    descriptors.push(current_descriptor);
+                    return position;
+                }
+            },
+            State::AfterDescriptor => match character {
+                Some(character) if is_ascii_whitespace(character) => (),
+                Some(_) => {
+                    // Reconsume the character in the in descriptor state
+                    state = State::InDescriptor;
+                    continue;
+                },
+                None => return position
+            }
+        }
+        position += 1;
+    }
+}
+
+// 10-12. The width or density the descriptors give the candidate, None when they are invalid
+// A height descriptor is allowed with a width descriptor for future compatibility, and is ignored
+fn parse_descriptors(descriptors: &[String]) -> Option<CandidateDescriptor> {
+    let (mut width, mut density, mut height) = (None, None, None);
+    for descriptor in descriptors {
+        let (value, last) = descriptor.split_at(descriptor.len() - descriptor.chars().last()?.len_utf8());
+        match last {
+            "w" if width.is_none() && density.is_none() => width = Some(parse_valid_non_negative_integer(value).filter(|width| *width > 0)?),
+            "x" if width.is_none() && density.is_none() && height.is_none() => density = Some(parse_valid_floating_point_number(value).filter(|density| *density >= 0.0)?),
+            "h" if height.is_none() && density.is_none() => height = Some(parse_valid_non_negative_integer(value).filter(|height| *height > 0)?),
+            _ => return None
+        }
+    }
+    // 12. If future-compat-h is not absent and width is absent, let error be yes.
+    if height.is_some() && width.is_none() {
+        return None;
+    }
+    return Some(match (width, density) {
+        (Some(width), _) => CandidateDescriptor::Width(width),
+        (None, density) => CandidateDescriptor::Density(density.unwrap_or(1.0))
+    });
+}
+
+// https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#valid-non-negative-integer
+// Descriptors have to be exactly a number, unlike attributes that are parsed up to the first character that is not one
+fn parse_valid_non_negative_integer(value: &str) -> Option<u64> {
+    if value.is_empty() || !value.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    return value.parse().ok();
+}
+
+// https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#valid-floating-point-number
+fn parse_valid_floating_point_number(value: &str) -> Option<f64> {
+    if value.is_empty() || floating_point_number_length(value) != value.len() {
+        return None;
+    }
+    return parse_floating_point_number(value);
+}
+
+// The length of the valid floating-point number value starts with: an optional -, digits, a . and digits, or both, and an optional exponent
+fn floating_point_number_length(value: &str) -> usize {
+    let bytes = value.as_bytes();
+    let digit_count = |position: usize| bytes[position.min(bytes.len())..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+    let mut position = if bytes.first() == Some(&b'-') { 1 } else { 0 };
+    let integer_digits = digit_count(position);
+    position += integer_digits;
+    let fraction_digits = if bytes.get(position) == Some(&b'.') { digit_count(position + 1) } else { 0 };
+    if fraction_digits > 0 {
+        position += 1 + fraction_digits;
+    }
+    if integer_digits == 0 && fraction_digits == 0 {
+        return 0;
+    }
+    if matches!(bytes.get(position), Some(b'e') | Some(b'E')) {
+        let sign_length = if matches!(bytes.get(position + 1), Some(b'-') | Some(b'+')) { 1 } else { 0 };
+        let exponent_digits = digit_count(position + 1 + sign_length);
+        if exponent_digits > 0 {
+            position += 1 + sign_length + exponent_digits;
+        }
+    }
+    return position;
+}
+
+// https://html.spec.whatwg.org/multipage/images.html#parse-a-sizes-attribute
+// The width in CSS pixels the image is shown at: the size of the first entry whose media condition matches the viewport, or of the last entry, which has no condition
+pub fn parse_sizes(input: &str, viewport: &Viewport) -> f64 {
+    // 1. Let unparsed sizes list be the result of parsing a comma-separated list of component values from the value of element's sizes attribute
+    let unparsed_sizes_list = split_on_top_level_commas(input);
+    for unparsed_size in unparsed_sizes_list {
+        // 3.1-3.3. If the last component value in unparsed size is a valid non-negative <source-size-value>, then set size to its value and remove the component value from unparsed size.
+        // Otherwise, this is a parse error, continue.
+        let unparsed_size = unparsed_size.trim_end_matches(is_ascii_whitespace);
+        let (media_condition, size_value) = match last_component(unparsed_size) {
+            Some(split) => split,
+            None => continue
+        };
+        let size = match source_size_value(size_value, viewport) {
+            Some(size) => size,
+            None => continue
+        };
+        // 3.4. If unparsed size is now empty: If this was not the last item in unparsed sizes list, that is a parse error. Return size.
+        let media_condition = media_condition.trim_matches(is_ascii_whitespace);
+        // A size without a media condition before the last entry is a parse error, and still the size
+        if media_condition.is_empty() {
+            return size;
+        }
+        // 3.5-3.6. Parse the remaining component values in unparsed size as a <media-condition>. If it does not parse correctly, or it does parse correctly but the <media-condition> evaluates to false, continue.
+        if media_condition_matches(media_condition, viewport) == Some(true) {
+            return size;
+        }
+    }
+    // 4. Return 100vw.
+    return viewport.width as f64;
+}
+
+// The entries of a sizes attribute, a comma inside parentheses does not end one
+fn split_on_top_level_commas(input: &str) -> Vec<&str> {
+    let mut entries = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, character) in input.char_indices() {
+        match character {
+            '(' => depth += 1,
+            ')' => depth = (depth - 1).max(0),
+            ',' if depth == 0 => {
+                entries.push(&input[start..index]);
+                start = index + 1;
+            },
+            _ => ()
+        }
+    }
+    entries.push(&input[start..]);
+    return entries;
+}
+
+// The text before the last component value of an entry, and the last component value, which is after the last whitespace outside parentheses
+fn last_component(entry: &str) -> Option<(&str, &str)> {
+    if entry.is_empty() {
+        return None;
+    }
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, character) in entry.char_indices() {
+        match character {
+            '(' => depth += 1,
+            ')' => depth = (depth - 1).max(0),
+            _ if depth == 0 && is_ascii_whitespace(character) => start = index + character.len_utf8(),
+            _ => ()
+        }
+    }
+    return Some((&entry[..start], &entry[start..]));
+}
+
+// https://html.spec.whatwg.org/multipage/images.html#source-size-value
+// A non-negative length in CSS pixels, the font relative units are relative to the initial font size of 16 pixels
+// TODO: auto is only a valid size for lazily loaded images, which are not supported, so it is not valid here
+fn source_size_value(value: &str, viewport: &Viewport) -> Option<f64> {
+    let length = css_length(value, viewport)?;
+    return if length >= 0.0 { Some(length) } else { None };
+}
+
+// https://drafts.csswg.org/css-values-4/#lengths
+fn css_length(value: &str, viewport: &Viewport) -> Option<f64> {
+    let value = value.trim_matches(is_ascii_whitespace).to_ascii_lowercase();
+    if value == "0" {
+        return Some(0.0);
+    }
+    let (number, unit) = value.split_at(floating_point_number_length(&value));
+    let number = parse_valid_floating_point_number(number)?;
+    let pixels_per_unit = match unit {
+        "px" => 1.0,
+        "em" | "rem" => 16.0,
+        "vw" => viewport.width as f64 / 100.0,
+        "vh" => viewport.height as f64 / 100.0,
+        "vmin" => viewport.width.min(viewport.height) as f64 / 100.0,
+        "vmax" => viewport.width.max(viewport.height) as f64 / 100.0,
+        "in" => 96.0,
+        "cm" => 96.0 / 2.54,
+        "mm" => 96.0 / 25.4,
+        "pt" => 96.0 / 72.0,
+        "pc" => 16.0,
+        _ => return None
+    };
+    return Some(number * pixels_per_unit);
+}
+
+// https://drafts.csswg.org/mediaqueries-4/#typedef-media-condition
+// None when the condition is not one that is supported, which is treated as not matching
+pub fn media_condition_matches(condition: &str, viewport: &Viewport) -> Option<bool> {
+    let condition = condition.trim_matches(is_ascii_whitespace).to_ascii_lowercase();
+    let (negated, condition) = match condition.strip_prefix("not") {
+        Some(rest) if rest.starts_with(is_ascii_whitespace) || rest.starts_with('(') => (true, rest.trim_start_matches(is_ascii_whitespace).to_string()),
+        _ => (false, condition)
+    };
+    let mut matches = true;
+    for feature in condition.split(" and ") {
+        let feature = feature.trim_matches(is_ascii_whitespace).strip_prefix('(')?.strip_suffix(')')?;
+        let (name, value) = feature.split_once(':')?;
+        let length = css_length(value, viewport)?;
+        let (width, height) = (viewport.width as f64, viewport.height as f64);
+        matches &= match name.trim_matches(is_ascii_whitespace) {
+            "min-width" => width >= length,
+            "max-width" => width <= length,
+            "min-height" => height >= length,
+            "max-height" => height <= length,
+            _ => return None
+        };
+    }
+    return Some(matches != negated);
+}
+
+// https://html.spec.whatwg.org/multipage/images.html#select-an-image-source
+// The URL as written of the image an img element shows in viewport at device_pixel_ratio, from the source elements before it in a picture element,
+// its srcset and sizes, or its src, None when it has no image
+// Resolve it against the document base URL with encoding_parse_url
+pub fn select_source(img: &RefNode, viewport: &Viewport, device_pixel_ratio: f64) -> Option<String> {
+    let (candidates, source_size) = source_set(img, viewport)?;
+    // Any candidate can be chosen, the one with the smallest density that is still at least the device pixel ratio is chosen so the image is sharp without loading more than it needs
+    // A width descriptor gives the density the image has when it is shown source size pixels wide
+    let density = |candidate: &ImageCandidate| match candidate.descriptor {
+        CandidateDescriptor::Width(width) if source_size > 0.0 => width as f64 / source_size,
+        CandidateDescriptor::Width(_) => f64::INFINITY,
+        CandidateDescriptor::Density(density) => density
+    };
+    let sharp_enough = candidates.iter().filter(|candidate| density(candidate) >= device_pixel_ratio).min_by(|a, b| density(a).total_cmp(&density(b)));
+    let best = sharp_enough.or_else(|| candidates.iter().max_by(|a, b| density(a).total_cmp(&density(b))));
+    return best.map(|candidate| candidate.url.clone());
+}
+
+// https://html.spec.whatwg.org/multipage/images.html#update-the-source-set
+// The candidates of the first source element of a picture that matches, or else of the img itself, with the size the image is shown at
+fn source_set(img: &RefNode, viewport: &Viewport) -> Option<(Vec<ImageCandidate>, f64)> {
+    let attribute = |node: &RefNode, name: &str| match &node.borrow().data {
+        NodeData::Element(element) => element.get_attribute(name).cloned(),
+        _ => None
+    };
+    let local_name = |node: &RefNode| match &node.borrow().data {
+        NodeData::Element(element) => Some(element.local_name().to_string()),
+        _ => None
+    };
+
+    // 4. If parent is a picture element, let elements be the children of parent before el, each source element of them is a candidate for the source set
+    let parent = img.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+    if let Some(parent) = parent.filter(|parent| local_name(parent).as_deref() == Some("picture")) {
+        let children = parent.borrow().childNodes.clone();
+        for child in children.iter().take_while(|child| !std::rc::Rc::ptr_eq(child, img)) {
+            if local_name(child).as_deref() != Some("source") {
+                continue;
+            }
+            // 1. If child does not have a srcset attribute, continue to the next child.
+            // 2. Parse child's srcset attribute and let the returned source set be source set. 3. If source set has zero image sources, continue to the next child.
+            let candidates = parse_srcset(&attribute(child, "srcset").unwrap_or_default());
+            if candidates.is_empty() {
+                continue;
+            }
+            // 4. If child has a media attribute, and its value does not match the environment, continue to the next child.
+            if attribute(child, "media").is_some_and(|media| !media_query_list_matches(&media, viewport)) {
+                continue;
+            }
+            // 6. If child has a type attribute, and its value is an unknown or unsupported MIME type, continue to the next child.
+            if attribute(child, "type").is_some_and(|mime_type| !is_supported_image_type(&mime_type)) {
+                continue;
+            }
+            // 7-8. Parse child's sizes attribute, and let source set's source size be the returned value.
+            let source_size = parse_sizes(&attribute(child, "sizes").unwrap_or_default(), viewport);
+            return Some((candidates, source_size));
+        }
+    }
+
+    // The img element itself: its srcset, and its src as a 1x candidate when the srcset does not have one
+    let mut candidates = parse_srcset(&attribute(img, "srcset").unwrap_or_default());
+    let has_width_descriptor = candidates.iter().any(|candidate| matches!(candidate.descriptor, CandidateDescriptor::Width(_)));
+    let has_1x = candidates.iter().any(|candidate| candidate.descriptor == CandidateDescriptor::Density(1.0));
+    if let Some(src) = attribute(img, "src").filter(|src| !src.is_empty()) {
+        if !has_width_descriptor && !has_1x {
+            candidates.push(ImageCandidate { url: src, descriptor: CandidateDescriptor::Density(1.0) });
+        }
+    }
+    if candidates.is_empty() {
+        return None;
+    }
+    let source_size = parse_sizes(&attribute(img, "sizes").unwrap_or_default(), viewport);
+    return Some((candidates, source_size));
+}
+
+// https://drafts.csswg.org/mediaqueries-4/#mq-list
+// A list matches when any of its queries does, an empty list always matches, and "all" and "screen" are the media types of the viewport
+fn media_query_list_matches(media: &str, viewport: &Viewport) -> bool {
+    let queries = split_on_commas(media);
+    if queries.iter().all(|query| query.is_empty()) {
+        return true;
+    }
+    return queries.iter().any(|query| {
+        let query = query.to_ascii_lowercase();
+        let query = query.strip_prefix("only ").unwrap_or(&query);
+        let condition = match query.split_once(" and ") {
+            Some((media_type, condition)) if matches!(media_type.trim(), "all" | "screen") => condition,
+            _ if matches!(query.trim(), "all" | "screen") => return true,
+            _ => query
+        };
+        return media_condition_matches(condition, viewport) == Some(true);
+    });
+}
+
+// https://mimesniff.spec.whatwg.org/#image-mime-type
+fn is_supported_image_type(mime_type: &str) -> bool {
+    let essence = mime_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    return matches!(essence.as_str(), "image/png" | "image/jpeg" | "image/gif" | "image/webp" | "image/avif" | "image/svg+xml" | "image/bmp" | "image/x-icon" | "image/vnd.microsoft.icon");
+}
@@ -0,0 +1,554 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::interpreter::{argument, completion_value, create_value_completion, return_if_abrupt, CompletionRecord, Interpreter, JSObject, JSValue};
+use crate::regexp::{is_regexp, regexp_create, regexp_match, regexp_replace, regexp_search, regexp_split};
+
+// https://tc39.es/ecma262/#sec-properties-of-the-string-prototype-object
+// TODO: Only the methods scripts commonly use are implemented, and there is no String constructor yet
+// Strings are indexed by UTF-16 code units like the spec, so the methods work on the code units of a string
+
+pub(crate) fn install_string(interpreter: &mut Interpreter) {
+    // FIXME: The String prototype object should be a String exotic object whose [[StringData]] is the empty String
+    let mut string_prototype = JSObject::new();
+    string_prototype.extensible = true;
+    string_prototype.prototype = interpreter.intrinsic("Object.prototype");
+    let string_prototype = Rc::new(RefCell::new(string_prototype));
+
+    Interpreter::define_builtin_function(&string_prototype, "includes", string_prototype_includes);
+    Interpreter::define_builtin_function(&string_prototype, "startsWith", string_prototype_starts_with);
+    Interpreter::define_builtin_function(&string_prototype, "slice", string_prototype_slice);
+    Interpreter::define_builtin_function(&string_prototype, "split", string_prototype_split);
+    Interpreter::define_builtin_function(&string_prototype, "replace", string_prototype_replace);
+    Interpreter::define_builtin_function(&string_prototype, "match", string_prototype_match);
+    Interpreter::define_builtin_function(&string_prototype, "search", string_prototype_search);
+    Interpreter::define_builtin_function(&string_prototype, "trim", string_prototype_trim);
+
+    interpreter.set_intrinsic("String.prototype", string_prototype);
+}
+
+// https://tc39.es/ecma262/#sec-string.prototype.includes
+fn string_prototype_includes(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let O be ? RequireObjectCoercible(this value).
+    // 2. Let S be ? ToString(O).
+    let string = return_if_abrupt!(this_string_value(interpreter, &this_value, "includes"));
+
+    // 3. TODO: Let isRegExp be ? IsRegExp(searchString).
+    // 4. If isRegExp is true, throw a TypeError exception.
+    // 5. Let searchStr be ? ToString(searchString).
+    let search_string = return_if_abrupt!(to_code_units(interpreter, argument(&arguments, 0)));
+
+    // 6. Let pos be ? ToIntegerOrInfinity(position).
+    // 7. Assert: If position is undefined, then pos is 0.
+    let position = return_if_abrupt!(to_integer_or_infinity(interpreter, &argument(&arguments, 1)));
+
+    // 8. Let len be the length of S.
+    // 9. Let start be the result of clamping pos between 0 and len.
+    let start = position.clamp(0.0, string.len() as f64) as usize;
+
+    // 10. Let index be StringIndexOf(S, searchStr, start).
+    // 11. If index is not not-found, return true.
+    // 12. Return false.
+    return create_value_completion(JSValue::Boolean(string_index_of(&string, &search_string, start).is_some()));
+}
+
+// https://tc39.es/ecma262/#sec-string.prototype.startswith
+fn string_prototype_starts_with(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let O be ? RequireObjectCoercible(this value).
+    // 2. Let S be ? ToString(O).
+    let string = return_if_abrupt!(this_string_value(interpreter, &this_value, "startsWith"));
+
+    // 3. TODO: Let isRegExp be ? IsRegExp(searchString).
+    // 4. If isRegExp is true, throw a TypeError exception.
+    // 5. Let searchStr be ? ToString(searchString).
+    let search_string = return_if_abrupt!(to_code_units(interpreter, argument(&arguments, 0)));
+
+    // 6. Let len be the length of S.
+    // 7. If position is undefined, let pos be 0; else let pos be ? ToIntegerOrInfinity(position).
+    let position = return_if_abrupt!(to_integer_or_infinity(interpreter, &argument(&arguments, 1)));
+
+    // 8. Let start be the result of clamping pos between 0 and len.
+    let start = position.clamp(0.0, string.len() as f64) as usize;
+
+    // 9. Let searchLength be the length of searchStr.
+    // 10. If searchLength = 0, return true.
+    // 11. Let end be start + searchLength.
+    // 12. If end > len, return false.
+    // 13. Let substring be the substring of S from start to end.
+    // 14. If substring is searchStr, return true.
+    // 15. Return false.
+    return create_value_completion(JSValue::Boolean(string[start..].starts_with(&search_string)));
+}
+
+// https://tc39.es/ecma262/#sec-string.prototype.slice
+fn string_prototype_slice(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let O be ? RequireObjectCoercible(this value).
+    // 2. Let S be ? ToString(O).
+    let string = return_if_abrupt!(this_string_value(interpreter, &this_value, "slice"));
+
+    // 3. Let len be the length of S.
+    let length = string.len() as f64;
+
+    // 4. Let intStart be ? ToIntegerOrInfinity(start).
+    let relative_start = return_if_abrupt!(to_integer_or_infinity(interpreter, &argument(&arguments, 0)));
+
+    // 5. If intStart = -∞, let from be 0.
+    // 6. Else if intStart < 0, let from be max(len + intStart, 0).
+    // 7. Else, let from be min(intStart, len).
+    let from = relative_index(relative_start, length);
+
+    // 8. If end is undefined, let intEnd be len; else let intEnd be ? ToIntegerOrInfinity(end).
+    let end = argument(&arguments, 1);
+    let relative_end = if matches!(*end.borrow(), JSValue::Undefined) {
+        length
+    } else {
+        return_if_abrupt!(to_integer_or_infinity(interpreter, &end))
+    };
+
+    // 9. If intEnd = -∞, let to be 0.
+    // 10. Else if intEnd < 0, let to be max(len + intEnd, 0).
+    // 11. Else, let to be min(intEnd, len).
+    let to = relative_index(relative_end, length);
+
+    // 12. If from ≥ to, return the empty String.
+    if from >= to {
+        return create_value_completion(JSValue::String(String::new()));
+    }
+
+    // 13. Return the substring of S from from to to.
+    return create_value_completion(JSValue::String(String::from_utf16_lossy(&string[from as usize..to as usize])));
+}
+
+// https://tc39.es/ecma262/#sec-string.prototype.split
+fn string_prototype_split(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let O be ? RequireObjectCoercible(this value).
+    // 2. If separator is neither undefined nor null, then
+    //        a. Let splitter be ? GetMethod(separator, %Symbol.split%).
+    //        b. If splitter is not undefined, then
+    //               i. Return ? Call(splitter, separator, « O, limit »).
+    // 3. Let S be ? ToString(O).
+    let string = return_if_abrupt!(this_string_value(interpreter, &this_value, "split"));
+    if let Some(regexp) = is_regexp(&argument(&arguments, 0).borrow()) {
+        return regexp_split(interpreter, &regexp, &string, argument(&arguments, 1));
+    }
+
+    // 4. If limit is undefined, let lim be 2^32 - 1; else let lim be ℝ(? ToUint32(limit)).
+    let limit = argument(&arguments, 1);
+    let limit = if matches!(*limit.borrow(), JSValue::Undefined) {
+        u32::MAX
+    } else {
+        let limit = return_if_abrupt!(to_integer_or_infinity(interpreter, &limit));
+        Interpreter::to_uint32(limit)
+    } as usize;
+
+    // 5. Let R be ? ToString(separator).
+    let separator = argument(&arguments, 0);
+    let separator_is_undefined = matches!(*separator.borrow(), JSValue::Undefined);
+    let separator = return_if_abrupt!(to_code_units(interpreter, separator));
+
+    // 6. If lim = 0, then
+    //        a. Return CreateArrayFromList(« »).
+    if limit == 0 {
+        return create_array_completion(interpreter, Vec::new());
+    }
+
+    // 7. If separator is undefined, then
+    //        a. Return CreateArrayFromList(« S »).
+    if separator_is_undefined {
+        return create_array_completion(interpreter, vec![string]);
+    }
+
+    // 8. Let separatorLength be the length of R.
+    // 9. If separatorLength = 0, then
+    if separator.is_empty() {
+        // a. Let head be the substring of S from 0 to lim.
+        // b. Let codeUnits be a List consisting of the sequence of code units that are the elements of head.
+        // c. Return CreateArrayFromList(codeUnits).
+        let code_units = string.iter().take(limit).map(|code_unit| vec![*code_unit]).collect();
+        return create_array_completion(interpreter, code_units);
+    }
+
+    // 10. If S is the empty String, return CreateArrayFromList(« S »).
+    if string.is_empty() {
+        return create_array_completion(interpreter, vec![string]);
+    }
+
+    // 11. Let substrings be a new empty List.
+    let mut substrings = Vec::new();
+
+    // 12. Let i be 0.
+    let mut i = 0;
+
+    // 13. Let j be StringIndexOf(S, R, 0).
+    let mut j = string_index_of(&string, &separator, 0);
+
+    // 14. Repeat, while j is not not-found,
+    while let Some(index) = j {
+        // a. Let T be the substring of S from i to j.
+        // b. Append T to substrings.
+        substrings.push(string[i..index].to_vec());
+
+        // c. If the number of elements in substrings is lim, return CreateArrayFromList(substrings).
+        if substrings.len() == limit {
+            return create_array_completion(interpreter, substrings);
+        }
+
+        // d. Set i to j + separatorLength.
+        i = index + separator.len();
+
+        // e. Set j to StringIndexOf(S, R, i).
+        j = string_index_of(&string, &separator, i);
+    }
+
+    // 15. Let T be the substring of S from i.
+    // 16. Append T to substrings.
+    substrings.push(string[i..].to_vec());
+
+    // 17. Return CreateArrayFromList(substrings).
+    return create_array_completion(interpreter, substrings);
+}
+
+// https://tc39.es/ecma262/#sec-string.prototype.replace
+fn string_prototype_replace(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let O be ? RequireObjectCoercible(this value).
+    // 2. If searchValue is neither undefined nor null, then
+    //        a. Let replacer be ? GetMethod(searchValue, %Symbol.replace%).
+    //        b. If replacer is not undefined, then
+    //               i. Return ? Call(replacer, searchValue, « O, replaceValue »).
+    // 3. Let string be ? ToString(O).
+    let string = return_if_abrupt!(this_string_value(interpreter, &this_value, "replace"));
+    if let Some(regexp) = is_regexp(&argument(&arguments, 0).borrow()) {
+        return regexp_replace(interpreter, &regexp, &string, argument(&arguments, 1));
+    }
+
+    // 4. Let searchString be ? ToString(searchValue).
+    let search_string = return_if_abrupt!(to_code_units(interpreter, argument(&arguments, 0)));
+
+    // 5. Let functionalReplace be IsCallable(replaceValue).
+    let replace_value = argument(&arguments, 1);
+    let functional_replace = Interpreter::is_callable(&replace_value.borrow());
+
+    // 6. If functionalReplace is false, then
+    //        a. Set replaceValue to ? ToString(replaceValue).
+    let replace_template = if functional_replace {
+        Vec::new()
+    } else {
+        return_if_abrupt!(to_code_units(interpreter, Rc::clone(&replace_value)))
+    };
+
+    // 7. Let searchLength be the length of searchString.
+    // 8. Let position be StringIndexOf(string, searchString, 0).
+    // 9. If position is not-found, return string.
+    let position = match string_index_of(&string, &search_string, 0) {
+        Some(position) => position,
+        None => {
+            return create_value_completion(JSValue::String(String::from_utf16_lossy(&string)));
+        }
+    };
+
+    // 10. Let preceding be the substring of string from 0 to position.
+    // 11. Let following be the substring of string from position + searchLength.
+    let preceding = &string[..position];
+    let following = &string[position + search_string.len()..];
+
+    // 12. If functionalReplace is true, then
+    let replacement = if functional_replace {
+        // a. Let replacement be ? ToString(? Call(replaceValue, undefined, « searchString, 𝔽(position), string »)).
+        let call_arguments = vec![
+            Rc::new(RefCell::new(JSValue::String(String::from_utf16_lossy(&search_string)))),
+            Rc::new(RefCell::new(JSValue::Numeric(position as f64))),
+            Rc::new(RefCell::new(JSValue::String(String::from_utf16_lossy(&string)))),
+        ];
+        let result = return_if_abrupt!(completion_value(interpreter.call(replace_value, Rc::new(RefCell::new(JSValue::Undefined)), call_arguments)));
+        return_if_abrupt!(to_code_units(interpreter, result))
+    } else {
+        // 13. Else,
+        //        a. Assert: replaceValue is a String.
+        //        b. Let captures be a new empty List.
+        //        c. Let replacement be ! GetSubstitution(searchString, string, position, captures, undefined, replaceValue).
+        get_substitution(&search_string, &string, position, &[], None, &replace_template)
+    };
+
+    // 14. Return the string-concatenation of preceding, replacement, and following.
+    let mut result = preceding.to_vec();
+    result.extend_from_slice(&replacement);
+    result.extend_from_slice(following);
+    return create_value_completion(JSValue::String(String::from_utf16_lossy(&result)));
+}
+
+// https://tc39.es/ecma262/#sec-string.prototype.match
+fn string_prototype_match(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let O be ? RequireObjectCoercible(this value).
+    // 2. If regexp is neither undefined nor null, then
+    //        a. Let matcher be ? GetMethod(regexp, %Symbol.match%).
+    //        b. If matcher is not undefined, then
+    //               i. Return ? Call(matcher, regexp, « O »).
+    // 3. Let S be ? ToString(O).
+    let string = return_if_abrupt!(this_string_value(interpreter, &this_value, "match"));
+    let regexp = argument(&arguments, 0);
+    if let Some(regexp) = is_regexp(&regexp.borrow()) {
+        return regexp_match(interpreter, &regexp, &string);
+    }
+
+    // 4. Let rx be ? RegExpCreate(regexp, undefined).
+    let regexp = return_if_abrupt!(completion_value(regexp_create(interpreter, regexp, Rc::new(RefCell::new(JSValue::Undefined)))));
+    let regexp = is_regexp(&regexp.borrow()).unwrap();
+
+    // 5. Return ? Invoke(rx, %Symbol.match%, « S »).
+    return regexp_match(interpreter, &regexp, &string);
+}
+
+// https://tc39.es/ecma262/#sec-string.prototype.search
+fn string_prototype_search(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let O be ? RequireObjectCoercible(this value).
+    // 2. If regexp is neither undefined nor null, then
+    //        a. Let searcher be ? GetMethod(regexp, %Symbol.search%).
+    //        b. If searcher is not undefined, then
+    //               i. Return ? Call(searcher, regexp, « O »).
+    // 3. Let string be ? ToString(O).
+    let string = return_if_abrupt!(this_string_value(interpreter, &this_value, "search"));
+    let regexp = argument(&arguments, 0);
+    if let Some(regexp) = is_regexp(&regexp.borrow()) {
+        return regexp_search(interpreter, &regexp, &string);
+    }
+
+    // 4. Let rx be ? RegExpCreate(regexp, undefined).
+    let regexp = return_if_abrupt!(completion_value(regexp_create(interpreter, regexp, Rc::new(RefCell::new(JSValue::Undefined)))));
+    let regexp = is_regexp(&regexp.borrow()).unwrap();
+
+    // 5. Return ? Invoke(rx, %Symbol.search%, « string »).
+    return regexp_search(interpreter, &regexp, &string);
+}
+
+// https://tc39.es/ecma262/#sec-getsubstitution
+// captures are the substrings of the capturing groups of a regular expression match, None for a group that did not take part in it,
+// and namedCaptures the capture of each named group, None when the regular expression has no named groups
+pub(crate) fn get_substitution(matched: &[u16], string: &[u16], position: usize, captures: &[Option<Vec<u16>>], named_captures: Option<&[(String, Option<Vec<u16>>)]>, replacement_template: &[u16]) -> Vec<u16> {
+    let dollar = '$' as u16;
+    let is_digit = |code_unit: Option<u16>| code_unit.is_some_and(|code_unit| (0x30..=0x39).contains(&code_unit));
+
+    // 1. Let stringLength be the length of str.
+    // 2. Assert: position ≤ stringLength.
+    // 3. Let result be the empty String.
+    let mut result = Vec::new();
+
+    // 4. Let templateRemainder be replacementTemplate.
+    let mut index = 0;
+
+    // 5. Repeat, while templateRemainder is not the empty String,
+    while index < replacement_template.len() {
+        let current = replacement_template[index];
+        let next = replacement_template.get(index + 1).copied();
+
+        // a. NOTE: The following steps isolate ref (a prefix of templateRemainder), determine refReplacement (its replacement), and then append that replacement to result.
+        let ref_length = if current == dollar && next == Some(dollar) {
+            // b. If templateRemainder starts with "$$", then
+            //        i. Let ref be "$$".
+            //        ii. Let refReplacement be "$".
+            result.push(dollar);
+            2
+        } else if current == dollar && next == Some('&' as u16) {
+            // c. Else if templateRemainder starts with "$&", then
+            //        i. Let ref be "$&".
+            //        ii. Let refReplacement be matched.
+            result.extend_from_slice(matched);
+            2
+        } else if current == dollar && next == Some('`' as u16) {
+            // d. Else if templateRemainder starts with "$`", then
+            //        i. Let ref be "$`".
+            //        ii. Let refReplacement be the substring of str from 0 to position.
+            result.extend_from_slice(&string[..position]);
+            2
+        } else if current == dollar && next == Some('\'' as u16) {
+            // e. Else if templateRemainder starts with "$'", then
+            //        i. Let ref be "$'".
+            //        ii. Let matchLength be the length of matched.
+            //        iii. Let tailPos be position + matchLength.
+            //        iv. Let refReplacement be the substring of str from min(tailPos, stringLength).
+            let tail_position = (position + matched.len()).min(string.len());
+            result.extend_from_slice(&string[tail_position..]);
+            2
+        } else if current == dollar && is_digit(next) {
+            // f. Else if templateRemainder starts with "$" followed by 1 or more decimal digits, then
+            //        i. If templateRemainder starts with "$" followed by 2 or more decimal digits, let digitCount be 2. Otherwise, let digitCount be 1.
+            //        ii. Let digits be the substring of templateRemainder from 1 to 1 + digitCount.
+            //        iii. Let index be ℝ(StringToNumber(digits)).
+            let first_digit = (next.unwrap() - 0x30) as usize;
+            let mut digit_count = if is_digit(replacement_template.get(index + 2).copied()) { 2 } else { 1 };
+            let mut capture_index = if digit_count == 2 { first_digit * 10 + (replacement_template[index + 2] - 0x30) as usize } else { first_digit };
+
+            //        v. Let captureLen be the number of elements in captures.
+            //        vi. If index > captureLen and digitCount = 2, then
+            //                2. Set digitCount to 1.
+            //                3. Set digits to the substring of digits from 0 to 1.
+            //                4. Set index to ℝ(StringToNumber(digits)).
+            if capture_index > captures.len() && digit_count == 2 {
+                digit_count = 1;
+                capture_index = first_digit;
+            }
+
+            //        vii. Let ref be the substring of templateRemainder from 0 to 1 + digitCount.
+            //        viii. If 1 ≤ index ≤ captureLen, then
+            //                  1. Let capture be captures[index - 1].
+            //                  2. If capture is undefined, then
+            //                         a. Let refReplacement be the empty String.
+            //                  3. Else,
+            //                         a. Let refReplacement be capture.
+            //        ix. Else,
+            //                1. Let refReplacement be ref.
+            if (1..=captures.len()).contains(&capture_index) {
+                if let Some(capture) = &captures[capture_index - 1] {
+                    result.extend_from_slice(capture);
+                }
+            } else {
+                result.extend_from_slice(&replacement_template[index..index + 1 + digit_count]);
+            }
+            1 + digit_count
+        } else if current == dollar && next == Some('<' as u16) {
+            // g. Else if templateRemainder starts with "$<", then
+            //        i. Let gtPos be StringIndexOf(templateRemainder, ">", 0).
+            let greater_than_position = string_index_of(&replacement_template[index..], &['>' as u16], 0);
+            match (greater_than_position, named_captures) {
+                //    iii. Else,
+                //             1. Let ref be the substring of templateRemainder from 0 to gtPos + 1.
+                //             2. Let groupName be the substring of templateRemainder from 2 to gtPos.
+                //             4. Let capture be ? Get(namedCaptures, groupName).
+                //             5. If capture is undefined, then
+                //                    a. Let refReplacement be the empty String.
+                //             6. Else,
+                //                    a. Let refReplacement be ? ToString(capture).
+                (Some(greater_than_position), Some(named_captures)) => {
+                    let group_name = String::from_utf16_lossy(&replacement_template[index + 2..index + greater_than_position]);
+                    if let Some((_, Some(capture))) = named_captures.iter().find(|(name, _)| *name == group_name) {
+                        result.extend_from_slice(capture);
+                    }
+                    greater_than_position + 1
+                },
+                //    ii. If gtPos = -1 or namedCaptures is undefined, then
+                //            1. Let ref be "$<".
+                //            2. Let refReplacement be ref.
+                _ => {
+                    result.extend_from_slice(&replacement_template[index..index + 2]);
+                    2
+                }
+            }
+        } else {
+            // h. Else,
+            //        i. Let ref be the substring of templateRemainder from 0 to 1.
+            //        ii. Let refReplacement be ref.
+            result.push(current);
+            1
+        };
+
+        // i. Let refLength be the length of ref.
+        // j. Set templateRemainder to the substring of templateRemainder from refLength.
+        // k. Set result to the string-concatenation of result and refReplacement.
+        index += ref_length;
+    }
+
+    // 6. Return result.
+    return result;
+}
+
+// https://tc39.es/ecma262/#sec-string.prototype.trim
+fn string_prototype_trim(interpreter: &mut Interpreter, this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // 1. Let S be the this value.
+    // 2. Return ? TrimString(S, start+end).
+    // https://tc39.es/ecma262/#sec-trimstring
+    // 1. Let str be ? RequireObjectCoercible(string).
+    // 2. Let S be ? ToString(str).
+    let string = return_if_abrupt!(this_string_value(interpreter, &this_value, "trim"));
+    let string = String::from_utf16_lossy(&string);
+
+    // 3. If where is start+end, let T be the String value that is a copy of S with both leading and trailing white space removed.
+    // 5. Return T.
+    return create_value_completion(JSValue::String(string.trim_matches(is_white_space_or_line_terminator).to_string()));
+}
+
+// https://tc39.es/ecma262/#prod-WhiteSpace
+// https://tc39.es/ecma262/#prod-LineTerminator
+fn is_white_space_or_line_terminator(character: char) -> bool {
+    match character {
+        '\u{0009}' | '\u{000B}' | '\u{000C}' | '\u{FEFF}' => true,
+        '\u{000A}' | '\u{000D}' | '\u{2028}' | '\u{2029}' => true,
+        // Any code point in general category "Space_Separator"
+        '\u{0020}' | '\u{00A0}' | '\u{1680}' | '\u{2000}'..='\u{200A}' | '\u{202F}' | '\u{205F}' | '\u{3000}' => true,
+        _ => false
+    }
+}
+
+// https://tc39.es/ecma262/#sec-stringindexof
+fn string_index_of(string: &[u16], search_value: &[u16], from_index: usize) -> Option<usize> {
+    // 1. Let len be the length of string.
+    let length = string.len();
+
+    // 2. If searchValue is the empty String and fromIndex ≤ len, return fromIndex.
+    if search_value.is_empty() && from_index <= length {
+        return Some(from_index);
+    }
+
+    // 3. Let searchLen be the length of searchValue.
+    // 4. For each integer i such that fromIndex ≤ i ≤ len - searchLen, in ascending order, do
+    //        a. Let candidate be the substring of string from i to i + searchLen.
+    //        b. If candidate is searchValue, return i.
+    // 5. Return not-found.
+    if search_value.len() > length {
+        return None;
+    }
+    return (from_index..=length - search_value.len()).find(|i| string[*i..*i + search_value.len()] == *search_value);
+}
+
+// https://tc39.es/ecma262/#sec-requireobjectcoercible
+// Returns the code units of ToString(this value)
+fn this_string_value(interpreter: &mut Interpreter, this_value: &Rc<RefCell<JSValue>>, method: &str) -> Result<Vec<u16>, CompletionRecord> {
+    if matches!(*this_value.borrow(), JSValue::Undefined | JSValue::Null) {
+        return Err(interpreter.throw_type_error(format!("String.prototype.{} called on null or undefined", method)));
+    }
+    return to_code_units(interpreter, Rc::clone(this_value));
+}
+
+fn to_code_units(interpreter: &mut Interpreter, value: Rc<RefCell<JSValue>>) -> Result<Vec<u16>, CompletionRecord> {
+    match &*completion_value(interpreter.to_string_coercing_objects(value))?.borrow() {
+        JSValue::String(string) => Ok(string.encode_utf16().collect()),
+        _ => unreachable!()
+    }
+}
+
+// https://tc39.es/ecma262/#sec-tointegerorinfinity
+fn to_integer_or_infinity(interpreter: &mut Interpreter, value: &Rc<RefCell<JSValue>>) -> Result<f64, CompletionRecord> {
+    // FIXME: ToNumber is not implemented for symbols and objects yet
+    let is_primitive = !matches!(*value.borrow(), JSValue::Symbol(_) | JSValue::Object(_));
+    if !is_primitive {
+        return Err(interpreter.throw_type_error(String::from("Cannot convert value to a number")));
+    }
+
+    // 1. Let number be ? ToNumber(argument).
+    let number = match *completion_value(Interpreter::to_number(Rc::clone(value)))?.borrow() {
+        JSValue::Numeric(number) => number,
+        _ => unreachable!()
+    };
+
+    // 2. If number is one of NaN, +0𝔽, or -0𝔽, return 0.
+    if number.is_nan() || number == 0.0 {
+        return Ok(0.0);
+    }
+
+    // 3. If number is +∞𝔽, return +∞.
+    // 4. If number is -∞𝔽, return -∞.
+    // 5. Return truncate(ℝ(number)).
+    return Ok(number.trunc());
+}
+
+// Resolves a relative index the way slice does, negative indices count back from the end
+fn relative_index(relative: f64, length: f64) -> f64 {
+    if relative < 0.0 {
+        return (length + relative).max(0.0);
+    }
+    return relative.min(length);
+}
+
+// https://tc39.es/ecma262/#sec-createarrayfromlist
+fn create_array_completion(interpreter: &mut Interpreter, strings: Vec<Vec<u16>>) -> CompletionRecord {
+    let elements = strings.iter().map(|string| Rc::new(RefCell::new(JSValue::String(String::from_utf16_lossy(string))))).collect();
+    let array = interpreter.create_array_from_list(elements);
+    return create_value_completion(JSValue::Object(array));
+}
@@ -0,0 +1,198 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use indexmap::IndexMap;
+use crate::css_values::{longhand_declarations, parse_specified_value, CssWideKeyword, PropertyValue};
+use crate::css_variables::{contains_var, custom_properties, substitute_var};
+use crate::cssom::{document_css_style_sheets, inline_style, is_custom_property, CSSDeclaration, CSSStyleSheet};
+use crate::node::{node_document, RefNode};
+use crate::selector::{parse_selector_list, PseudoElement, Specificity};
+use crate::transitions::apply_transitions;
+
+// https://drafts.csswg.org/css-cascade-4/#cascading
+// Resolves which declarations apply to an element, the style sheets are read again every time and the elements whose style has to be computed again are marked when the DOM changes
+// TODO: Only the author origin is cascaded, there is no user agent style sheet, and apart from custom properties nothing is inherited or computed yet
+
+// https://drafts.csswg.org/css-cascade-4/#cascade-sort
+// Sorted by importance, then whether the declaration is attached to the element by its style attribute, then specificity, then order of appearance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct CascadePrecedence {
+    important: bool,
+    style_attribute: bool,
+    specificity: Specificity,
+    order_of_appearance: usize,
+}
+
+// https://drafts.csswg.org/css-cascade-4/#cascaded-value
+// The declaration that wins the cascade for each property that has one, keyed by property name
+pub fn cascaded_values(element: &RefNode) -> IndexMap<String, CSSDeclaration> {
+    let style_sheets = node_document(element).map(|document| document_css_style_sheets(&document)).unwrap_or_default();
+    return cascade(element, &style_sheets);
+}
+
+// The cascaded values of element given its document's style sheets, so they are only collected once when every element is styled
+pub fn cascade(element: &RefNode, style_sheets: &[Rc<RefCell<CSSStyleSheet>>]) -> IndexMap<String, CSSDeclaration> {
+    return cascade_pseudo_element(element, None, style_sheets);
+}
+
+// The cascaded values of pseudo_element of element, or of element itself when pseudo_element is None
+// A pseudo-element has no style attribute, so only the style rules whose selectors end in it apply to it
+fn cascade_pseudo_element(element: &RefNode, pseudo_element: Option<PseudoElement>, style_sheets: &[Rc<RefCell<CSSStyleSheet>>]) -> IndexMap<String, CSSDeclaration> {
+    let mut declarations: Vec<(CascadePrecedence, CSSDeclaration)> = Vec::new();
+
+    // https://drafts.csswg.org/css-cascade-4/#filtering
+    // The declarations of the style rules whose selectors match the element, a rule whose selectors are not supported matches no element
+    for style_sheet in style_sheets {
+        for rule in &style_sheet.borrow().css_rules {
+            let rule = rule.borrow();
+            let specificity = match parse_selector_list(&rule.selector_text).and_then(|selector_list| selector_list.matching_pseudo_element_specificity(element, pseudo_element)) {
+                Some(specificity) => specificity,
+                None => continue
+            };
+
+            for declaration in &rule.style.borrow().declarations {
+                if let Some(declaration) = valid_declaration(declaration) {
+                    let precedence = CascadePrecedence { important: declaration.important, style_attribute: false, specificity, order_of_appearance: declarations.len() };
+                    declarations.push((precedence, declaration));
+                }
+            }
+        }
+    }
+
+    // https://drafts.csswg.org/css-cascade-4/#style-attr
+    // The declarations of the style attribute win over the author's style rules of the same importance
+    if let Some(declaration_block) = inline_style(element).filter(|_| pseudo_element.is_none()) {
+        for declaration in &declaration_block.borrow().declarations {
+            if let Some(declaration) = valid_declaration(declaration) {
+                let precedence = CascadePrecedence { important: declaration.important, style_attribute: true, specificity: Specificity::default(), order_of_appearance: declarations.len() };
+                declarations.push((precedence, declaration));
+            }
+        }
+    }
+
+    // The output of the cascade is a (potentially empty) sorted list of declared values for each property on each element.
+    // The declarations are applied from the lowest precedence to the highest, so the last declaration of each property wins
+    declarations.sort_by_key(|(precedence, _)| *precedence);
+    let mut cascaded_values = IndexMap::new();
+    for (_, declaration) in declarations {
+        cascaded_values.insert(declaration.property_name.clone(), declaration);
+    }
+    return cascaded_values;
+}
+
+// https://drafts.csswg.org/css-syntax-3/#css-syntax-3-declaration-validity
+// A declaration whose value is invalid for its property is ignored, so it does not override an earlier valid one
+// The declarations of a block are longhands (see CSSStyleDeclaration), so only their values are checked here
+fn valid_declaration(declaration: &CSSDeclaration) -> Option<CSSDeclaration> {
+    // https://drafts.csswg.org/css-variables/#variables-in-shorthands
+    // A declaration with var() in it is assumed to be valid until var() is substituted
+    if contains_var(&declaration.value) {
+        return Some(declaration.clone());
+    }
+
+    return longhand_declarations(&declaration.property_name, &declaration.value).into_iter()
+        .map(|(property_name, value)| CSSDeclaration { property_name, value, important: declaration.important, pending_substitution: None })
+        .next();
+}
+
+// https://drafts.csswg.org/css-cascade-4/#specified
+// The cascaded values of element parsed into the types of their properties, with its running transitions at their values as of the last frame
+// Custom properties are left out, they are computed with their inherited values by css_variables::custom_properties
+// TODO: A property with no cascaded value is left out rather than given its inherited or initial value
+pub fn specified_values(element: &RefNode, style_sheets: &[Rc<RefCell<CSSStyleSheet>>]) -> IndexMap<String, PropertyValue> {
+    let mut specified_values = unanimated_specified_values(element, style_sheets);
+    apply_transitions(element, &mut specified_values);
+    return specified_values;
+}
+
+// The specified values of element without its transitions, which is the style transitions start from and run to
+pub(crate) fn unanimated_specified_values(element: &RefNode, style_sheets: &[Rc<RefCell<CSSStyleSheet>>]) -> IndexMap<String, PropertyValue> {
+    return parse_cascaded_values(element, cascade(element, style_sheets), style_sheets);
+}
+
+// https://drafts.csswg.org/css-pseudo-4/#treelike
+// The specified values of pseudo_element of element, which substitutes var() with the custom properties of element
+// TODO: Pseudo-elements are not transitioned
+pub fn pseudo_element_specified_values(element: &RefNode, pseudo_element: PseudoElement, style_sheets: &[Rc<RefCell<CSSStyleSheet>>]) -> IndexMap<String, PropertyValue> {
+    return parse_cascaded_values(element, cascade_pseudo_element(element, Some(pseudo_element), style_sheets), style_sheets);
+}
+
+// Whether any rule of the style sheets has a selector that ends in pseudo_element, so the pseudo-elements no rule styles are not cascaded for every element
+pub(crate) fn has_pseudo_element_rules(style_sheets: &[Rc<RefCell<CSSStyleSheet>>], pseudo_element: PseudoElement) -> bool {
+    return style_sheets.iter().any(|style_sheet| style_sheet.borrow().css_rules.iter().any(|rule| {
+        parse_selector_list(&rule.borrow().selector_text).is_some_and(|selector_list| selector_list.pseudo_elements().any(|rule_pseudo_element| rule_pseudo_element == pseudo_element))
+    }));
+}
+
+// The cascaded values of element, or of one of its pseudo-elements, parsed into the types of their properties
+fn parse_cascaded_values(element: &RefNode, cascaded_values: IndexMap<String, CSSDeclaration>, style_sheets: &[Rc<RefCell<CSSStyleSheet>>]) -> IndexMap<String, PropertyValue> {
+    // The custom properties are only computed for an element that has a var() to substitute
+    let mut element_custom_properties = None;
+    let mut specified_values = IndexMap::new();
+    for (property_name, declaration) in cascaded_values {
+        if is_custom_property(&property_name) {
+            continue;
+        }
+
+        let value = match contains_var(&declaration.value) {
+            true => {
+                let element_custom_properties = element_custom_properties.get_or_insert_with(|| custom_properties(element, style_sheets));
+                substitute_declaration(&declaration, element_custom_properties)
+            },
+            false => Some(declaration.value)
+        };
+        // https://drafts.csswg.org/css-variables/#invalid-at-computed-value-time
+        // A declaration that is invalid once var() is substituted computes as if its value were unset
+        let specified_value = value.and_then(|value| parse_specified_value(&property_name, &value)).unwrap_or(PropertyValue::CssWideKeyword(CssWideKeyword::Unset));
+        specified_values.insert(property_name, specified_value);
+    }
+    return specified_values;
+}
+
+// The value of declaration with its var() functions substituted, for a longhand pending substitution its part of the substituted shorthand
+fn substitute_declaration(declaration: &CSSDeclaration, custom_properties: &IndexMap<String, String>) -> Option<String> {
+    let value = substitute_var(&declaration.value, custom_properties)?;
+    match &declaration.pending_substitution {
+        Some(shorthand) => {
+            return longhand_declarations(shorthand, &value).into_iter()
+                .find(|(longhand, _)| *longhand == declaration.property_name)
+                .map(|(_, value)| value);
+        },
+        None => return Some(value)
+    }
+}
+
+// The value of the declaration that wins the cascade for property, None when the element has no declaration for it
+pub fn cascaded_value(element: &RefNode, property: &str) -> Option<String> {
+    return cascaded_values(element).get(property).map(|declaration| declaration.value.clone());
+}
+
+// Marks node as having to be styled again, and its descendants with it, as they inherit from it and descendant and child selectors may match them differently now
+// The siblings after it are marked too, as sibling combinators may match them differently now
+// Nothing is styled until the next layout, so a script that changes the document many times only has it styled once
+pub fn invalidate_style(node: &RefNode) {
+    node.borrow_mut().needs_style = true;
+    let parent = node.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+    if let Some(parent) = parent {
+        // The siblings are walked from the last one, so a node that was appended (as the parser does) has none to walk past
+        for sibling in parent.borrow().childNodes.iter().rev() {
+            if Rc::ptr_eq(sibling, node) {
+                break;
+            }
+            sibling.borrow_mut().needs_style = true;
+        }
+        invalidate_children(&parent);
+    }
+}
+
+// Marks node and its ancestors as having children that changed or have to be styled again, so the next layout walks down to them
+pub fn invalidate_children(node: &RefNode) {
+    let mut ancestor = Some(Rc::clone(node));
+    while let Some(current) = ancestor {
+        // The ancestors of a node that is already marked are marked too
+        if current.borrow().child_needs_style {
+            return;
+        }
+        current.borrow_mut().child_needs_style = true;
+        ancestor = current.borrow().parentNode.as_ref().and_then(|parent| parent.upgrade());
+    }
+}
@@ -0,0 +1,144 @@
+use crate::bidi::needs_reordering;
+use crate::css_values::{BorderStyle, Color};
+use crate::display_list::{DisplayItem, DisplayList, Rect};
+use crate::language::Direction;
+
+// https://www.w3.org/TR/SVG2/
+// Draws a display list as an SVG document, with an element for each item in the order of the list so later items are drawn over earlier ones
+// The document is as big as the display list, in CSS pixels, on a white canvas
+// TODO: Text is drawn in the generic sans-serif font rather than the font it was measured with, so it can be a little wider or narrower than where it was laid out
+// TODO: Groove, ridge, inset and outset borders are drawn in two fixed shades of their color rather than the shades a browser picks
+
+pub fn render_as_svg(display_list: &DisplayList) -> String {
+    let mut svg = String::new();
+    svg.push_str(&format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" font-family=\"sans-serif\" xml:space=\"preserve\">\n",
+        number(display_list.width), number(display_list.height), number(display_list.width), number(display_list.height)));
+    svg.push_str("<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n");
+
+    // Each clip is a clipPath the group of the items it clips refers to
+    let mut clip_count = 0;
+    for item in &display_list.items {
+        match item {
+            DisplayItem::Rect { rect, color } => svg.push_str(&format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                number(rect.x), number(rect.y), number(rect.width), number(rect.height), color)),
+            DisplayItem::Border { rect, widths, styles, colors } => {
+                for side in 0..4 {
+                    render_border_side(&mut svg, rect, widths, side, styles[side], colors[side]);
+                }
+            },
+            // The text of the display list is already in the order it is displayed, so an SVG viewer is told not to reorder right-to-left text again
+            DisplayItem::Text { x, baseline, text, font_size, color } => {
+                let bidi_override = if needs_reordering(text, Direction::Ltr) { " direction=\"ltr\" unicode-bidi=\"bidi-override\"" } else { "" };
+                svg.push_str(&format!("<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"{}\"{}>{}</text>\n",
+                    number(*x), number(*baseline), number(*font_size), color, bidi_override, escape_xml(text)))
+            },
+            DisplayItem::Image { rect, url } => svg.push_str(&format!("<image x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" href=\"{}\" preserveAspectRatio=\"none\"/>\n",
+                number(rect.x), number(rect.y), number(rect.width), number(rect.height), escape_xml(url))),
+            DisplayItem::Clip { rect } => {
+                clip_count += 1;
+                svg.push_str(&format!("<clipPath id=\"clip{}\"><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/></clipPath>\n<g clip-path=\"url(#clip{})\">\n",
+                    clip_count, number(rect.x), number(rect.y), number(rect.width), number(rect.height), clip_count));
+            },
+            DisplayItem::EndClip => svg.push_str("</g>\n")
+        }
+    }
+    svg.push_str("</svg>\n");
+    return svg;
+}
+
+// https://drafts.csswg.org/css-backgrounds-3/#border-style
+// A side of a border is a trapezoid, so the corners where sides of different colors meet are split diagonally
+// The side is given as an index into the top, right, bottom and left widths
+fn render_border_side(svg: &mut String, rect: &Rect, widths: &[f32; 4], side: usize, style: BorderStyle, color: Color) {
+    let width = widths[side];
+    if width <= 0.0 {
+        return;
+    }
+    // The top and left sides are the ones in shadow for an inset border
+    let top_or_left = side == 0 || side == 3;
+    let (dark, light) = (shade(color, 0.5), color);
+    match style {
+        BorderStyle::None | BorderStyle::Hidden => (),
+        BorderStyle::Solid => render_band(svg, rect, widths, side, 0.0, 1.0, color),
+        // Two lines with a gap between them, each a third of the width
+        BorderStyle::Double => {
+            render_band(svg, rect, widths, side, 0.0, 1.0 / 3.0, color);
+            render_band(svg, rect, widths, side, 2.0 / 3.0, 1.0, color);
+        },
+        BorderStyle::Inset => render_band(svg, rect, widths, side, 0.0, 1.0, if top_or_left { dark } else { light }),
+        BorderStyle::Outset => render_band(svg, rect, widths, side, 0.0, 1.0, if top_or_left { light } else { dark }),
+        // The outer half is shaded like an inset border and the inner half like an outset border, and the other way round for ridge
+        BorderStyle::Groove | BorderStyle::Ridge => {
+            let outer_dark = top_or_left == (style == BorderStyle::Groove);
+            render_band(svg, rect, widths, side, 0.0, 0.5, if outer_dark { dark } else { light });
+            render_band(svg, rect, widths, side, 0.5, 1.0, if outer_dark { light } else { dark });
+        },
+        // A line along the middle of the side, broken into dashes or round dots as long as the side is wide
+        BorderStyle::Dashed | BorderStyle::Dotted => {
+            let (left, top, right, bottom) = inset(rect, widths, 0.5);
+            let (start, end) = match side {
+                0 => ((left, top), (right, top)),
+                1 => ((right, top), (right, bottom)),
+                2 => ((right, bottom), (left, bottom)),
+                _ => ((left, bottom), (left, top))
+            };
+            let dashes = match style {
+                BorderStyle::Dashed => format!("stroke-dasharray=\"{} {}\"", number(width * 3.0), number(width * 3.0)),
+                _ => format!("stroke-dasharray=\"0 {}\" stroke-linecap=\"round\"", number(width * 2.0))
+            };
+            svg.push_str(&format!("<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"{}\" {}/>\n",
+                number(start.0), number(start.1), number(end.0), number(end.1), color, number(width), dashes));
+        }
+    }
+}
+
+// Fills the band of a side between the outer and inner fractions of its width, 0 being the outer edge of the border and 1 the inner edge
+fn render_band(svg: &mut String, rect: &Rect, widths: &[f32; 4], side: usize, outer_fraction: f32, inner_fraction: f32, color: Color) {
+    let (outer, inner) = (inset(rect, widths, outer_fraction), inset(rect, widths, inner_fraction));
+    let points = match side {
+        0 => [(outer.0, outer.1), (outer.2, outer.1), (inner.2, inner.1), (inner.0, inner.1)],
+        1 => [(outer.2, outer.1), (outer.2, outer.3), (inner.2, inner.3), (inner.2, inner.1)],
+        2 => [(outer.2, outer.3), (outer.0, outer.3), (inner.0, inner.3), (inner.2, inner.3)],
+        _ => [(outer.0, outer.3), (outer.0, outer.1), (inner.0, inner.1), (inner.0, inner.3)]
+    };
+    let points = points.iter().map(|(x, y)| format!("{},{}", number(*x), number(*y))).collect::<Vec<String>>().join(" ");
+    svg.push_str(&format!("<polygon points=\"{}\" fill=\"{}\"/>\n", points, color));
+}
+
+// The left, top, right and bottom edges of rect moved in by fraction of the width of each side
+fn inset(rect: &Rect, widths: &[f32; 4], fraction: f32) -> (f32, f32, f32, f32) {
+    let [top, right, bottom, left] = widths.map(|width| width * fraction);
+    return (rect.x + left, rect.y + top, rect.x + rect.width - right, rect.y + rect.height - bottom);
+}
+
+// The color with its red, green and blue scaled by factor, e.g. 0.5 for a darker shade
+fn shade(color: Color, factor: f32) -> Color {
+    match color {
+        Color::Rgba { red, green, blue, alpha } => {
+            let scale = |component: u8| (component as f32 * factor).round() as u8;
+            return Color::Rgba { red: scale(red), green: scale(green), blue: scale(blue), alpha };
+        },
+        Color::CurrentColor => return color
+    }
+}
+
+// A number without the noise of f32 arithmetic, to two decimal places
+fn number(value: f32) -> String {
+    return ((value * 100.0).round() / 100.0).to_string();
+}
+
+// https://www.w3.org/TR/xml/#syntax
+// & and < can not appear literally in text, and " can not appear in an attribute value in quotes
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(character)
+        }
+    }
+    return escaped;
+}
@@ -0,0 +1,210 @@
+// Measures text for inline layout, so lines can be broken the same way on every machine without a system font
+// The built-in metrics are tables of advance widths, the font-files feature measures text with the metrics of a real font instead
+
+// https://drafts.csswg.org/css-fonts-4/#font-metrics
+// Every metric is in CSS pixels for the given font size
+pub trait FontMetrics {
+    // The horizontal advance of character, the distance to the start of the next character on the line
+    fn advance_width(&self, character: char, font_size: f32) -> f32;
+
+    // https://drafts.csswg.org/css-inline-3/#ascent-descent
+    fn ascent(&self, font_size: f32) -> f32;
+    fn descent(&self, font_size: f32) -> f32;
+    fn line_gap(&self, font_size: f32) -> f32;
+
+    // https://drafts.csswg.org/css-inline-3/#valdef-line-height-normal
+    // The used value of line-height: normal is the sum of the ascent, descent and line gap of the font
+    fn line_height(&self, font_size: f32) -> f32 {
+        return self.ascent(font_size) + self.descent(font_size) + self.line_gap(font_size);
+    }
+
+    // The width of text set on a single line, without kerning or shaping
+    fn measure(&self, text: &str, font_size: f32) -> f32 {
+        return text.chars().map(|character| self.advance_width(character, font_size)).sum();
+    }
+}
+
+// Advance widths of the printable ASCII characters from space (U+0020) to tilde (U+007E), in thousandths of an em
+// These are the widths of Helvetica, which Arial and most sans-serif fonts share
+const SANS_SERIF_ADVANCE_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, // space ! " # $ % & ' ( ) * + , - . /
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, // 0-9 : ; < = > ?
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778, // @ A-O
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556, // P-Z [ \ ] ^ _
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556, // ` a-o
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584, // p-z { | } ~
+];
+
+// Characters outside the table are measured as the width of a digit
+const SANS_SERIF_FALLBACK_ADVANCE_WIDTH: u16 = 556;
+
+// Every character of a monospace font has the same advance, which is 600 for Courier
+const MONOSPACE_ADVANCE_WIDTH: u16 = 600;
+
+// Metrics built into the engine, so text can be measured without loading a font
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BuiltinFontMetrics {
+    #[default]
+    SansSerif,
+    Monospace,
+}
+
+impl BuiltinFontMetrics {
+    // https://drafts.csswg.org/css-fonts-4/#generic-font-families
+    // Serif and the other generic families are measured as sans-serif
+    pub fn for_generic_family(family: &str) -> BuiltinFontMetrics {
+        match family.to_ascii_lowercase().as_str() {
+            "monospace" | "ui-monospace" => BuiltinFontMetrics::Monospace,
+            _ => BuiltinFontMetrics::SansSerif
+        }
+    }
+
+    // The ascent, descent and line gap in thousandths of an em, the vertical metrics of Arial and Courier New
+    fn vertical_metrics(&self) -> (u16, u16, u16) {
+        match self {
+            BuiltinFontMetrics::SansSerif => (905, 212, 33),
+            BuiltinFontMetrics::Monospace => (833, 300, 0)
+        }
+    }
+}
+
+impl FontMetrics for BuiltinFontMetrics {
+    fn advance_width(&self, character: char, font_size: f32) -> f32 {
+        let advance_width = match self {
+            BuiltinFontMetrics::Monospace => MONOSPACE_ADVANCE_WIDTH,
+            BuiltinFontMetrics::SansSerif => {
+                match character {
+                    ' '..='~' => SANS_SERIF_ADVANCE_WIDTHS[character as usize - ' ' as usize],
+                    // A no-break space has the width of a space
+                    '\u{A0}' => SANS_SERIF_ADVANCE_WIDTHS[0],
+                    // TODO: Combining marks, zero width and wide (e.g. CJK) characters are measured as one narrow character
+                    _ => SANS_SERIF_FALLBACK_ADVANCE_WIDTH
+                }
+            }
+        };
+        return advance_width as f32 * font_size / 1000.0;
+    }
+
+    fn ascent(&self, font_size: f32) -> f32 {
+        return self.vertical_metrics().0 as f32 * font_size / 1000.0;
+    }
+
+    fn descent(&self, font_size: f32) -> f32 {
+        return self.vertical_metrics().1 as f32 * font_size / 1000.0;
+    }
+
+    fn line_gap(&self, font_size: f32) -> f32 {
+        return self.vertical_metrics().2 as f32 * font_size / 1000.0;
+    }
+}
+
+// A line of text and the width it was measured at
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLine {
+    pub text: String,
+    pub width: f32,
+}
+
+// https://drafts.csswg.org/css-text-3/#line-breaking
+// Breaks text into lines no wider than available_width, at spaces and at forced line breaks
+// A word wider than the available width is put on a line of its own and overflows it, the spaces at a break are not part of either line
+// TODO: Only spaces are soft wrap opportunities, there is no hyphenation and no breaking inside words with overflow-wrap
+pub fn break_lines(text: &str, metrics: &dyn FontMetrics, font_size: f32, available_width: f32) -> Vec<TextLine> {
+    let space_width = metrics.advance_width(' ', font_size);
+    let mut lines = Vec::new();
+
+    // https://drafts.csswg.org/css-text-3/#forced-line-break
+    for paragraph in text.split('\n') {
+        let mut line = TextLine { text: String::new(), width: 0.0 };
+        for word in paragraph.split(' ').filter(|word| !word.is_empty()) {
+            let word_width = metrics.measure(word, font_size);
+            if line.text.is_empty() {
+                line = TextLine { text: word.to_string(), width: word_width };
+            } else if line.width + space_width + word_width <= available_width {
+                line.text.push(' ');
+                line.text.push_str(word);
+                line.width += space_width + word_width;
+            } else {
+                lines.push(line);
+                line = TextLine { text: word.to_string(), width: word_width };
+            }
+        }
+        lines.push(line);
+    }
+    return lines;
+}
+
+// The metrics of a TrueType or OpenType font file, advance widths are read from its hmtx table for the characters its cmap table maps
+#[cfg(feature = "font-files")]
+pub struct FontFileMetrics {
+    units_per_em: f32,
+    ascender: f32,
+    descender: f32,
+    line_gap: f32,
+    advance_widths: std::collections::HashMap<char, u16>,
+    // The advance of the .notdef glyph, which is drawn for characters the font does not have
+    missing_glyph_advance_width: u16,
+}
+
+#[cfg(feature = "font-files")]
+impl FontFileMetrics {
+    pub fn from_file(path: &std::path::Path) -> Result<FontFileMetrics, String> {
+        let data = std::fs::read(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        return FontFileMetrics::from_bytes(&data, 0).map_err(|error| format!("{}: {}", path.display(), error));
+    }
+
+    // The index is the font to use from a font collection, it is 0 for a file with a single font
+    pub fn from_bytes(data: &[u8], index: u32) -> Result<FontFileMetrics, String> {
+        let face = ttf_parser::Face::parse(data, index).map_err(|error| error.to_string())?;
+
+        let mut advance_widths = std::collections::HashMap::new();
+        if let Some(cmap) = face.tables().cmap {
+            for subtable in cmap.subtables.into_iter().filter(|subtable| subtable.is_unicode()) {
+                subtable.codepoints(|code_point| {
+                    let character = match char::from_u32(code_point) {
+                        Some(character) => character,
+                        None => return
+                    };
+                    let advance_width = subtable.glyph_index(code_point).and_then(|glyph_id| face.glyph_hor_advance(glyph_id));
+                    if let Some(advance_width) = advance_width {
+                        advance_widths.insert(character, advance_width);
+                    }
+                });
+            }
+        }
+
+        return Ok(FontFileMetrics {
+            units_per_em: face.units_per_em() as f32,
+            ascender: face.ascender() as f32,
+            // The descender is negative as it is below the baseline
+            descender: -(face.descender() as f32),
+            line_gap: face.line_gap() as f32,
+            advance_widths,
+            missing_glyph_advance_width: face.glyph_hor_advance(ttf_parser::GlyphId(0)).unwrap_or(0),
+        });
+    }
+
+    fn to_pixels(&self, units: f32, font_size: f32) -> f32 {
+        return units * font_size / self.units_per_em;
+    }
+}
+
+#[cfg(feature = "font-files")]
+impl FontMetrics for FontFileMetrics {
+    fn advance_width(&self, character: char, font_size: f32) -> f32 {
+        let advance_width = self.advance_widths.get(&character).copied().unwrap_or(self.missing_glyph_advance_width);
+        return self.to_pixels(advance_width as f32, font_size);
+    }
+
+    fn ascent(&self, font_size: f32) -> f32 {
+        return self.to_pixels(self.ascender, font_size);
+    }
+
+    fn descent(&self, font_size: f32) -> f32 {
+        return self.to_pixels(self.descender, font_size);
+    }
+
+    fn line_gap(&self, font_size: f32) -> f32 {
+        return self.to_pixels(self.line_gap, font_size);
+    }
+}
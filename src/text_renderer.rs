@@ -0,0 +1,514 @@
+use std::rc::Rc;
+use crate::bidi::reorder_line;
+use crate::language::{directionality, Direction};
+use crate::layout::{build_box_tree, BoxType, LayoutBox, WhiteSpace};
+use crate::node::{encoding_parse_url, node_document, text_content, RefNode};
+use crate::selector::PseudoElement;
+use crate::text::{break_lines, FontMetrics};
+
+// Renders the box tree of a document as plain text for a terminal, the way lynx and w3m do
+// Headings are underlined, list items get their markers (see counters.rs), and each link is numbered and listed with its URL under References at the end
+// The lines of right-to-left blocks (see language::directionality) are aligned to the right, and the characters of each line are in the order they are displayed (see bidi.rs)
+// TODO: Tables are rendered a cell per line, and form controls other than buttons are not rendered
+// TODO: The list markers of right-to-left lists stay on the left
+
+// Every character takes up one cell of the terminal
+// TODO: Wide (e.g. CJK) characters take up two cells and combining marks none
+struct CharacterCells;
+
+impl FontMetrics for CharacterCells {
+    fn advance_width(&self, _character: char, _font_size: f32) -> f32 {
+        return 1.0;
+    }
+
+    fn ascent(&self, _font_size: f32) -> f32 {
+        return 1.0;
+    }
+
+    fn descent(&self, _font_size: f32) -> f32 {
+        return 0.0;
+    }
+
+    fn line_gap(&self, _font_size: f32) -> f32 {
+        return 0.0;
+    }
+}
+
+struct TextRenderer {
+    columns: usize,
+    lines: Vec<String>,
+    // The inline content of the block being rendered, which is broken into lines when the block or a block inside it starts or ends
+    inline_content: String,
+    white_space: WhiteSpace,
+    direction: Direction,
+    indent: usize,
+    // The marker of the list item whose first line has not been written yet
+    pending_marker: Option<String>,
+    // Whether the next line written is separated from the lines before by a blank line, the margin around paragraphs, headings and lists
+    pending_blank_line: bool,
+    // How many lists (ul, ol, menu and dir) the box being rendered is in
+    list_depth: usize,
+    // The URLs of the links in the order they appear, a link is referred to by its index plus one
+    links: Vec<String>,
+    // Set when the text is extracted rather than rendered for a terminal
+    extraction: Option<ExtractTextOptions>,
+}
+
+// What extract_text keeps of a document besides its text
+#[derive(Debug, Clone)]
+pub struct ExtractTextOptions {
+    // The URL of each link follows its text in parentheses, e.g. "the spec (https://html.spec.whatwg.org/)"
+    pub preserve_links: bool,
+    // The alternative text of images is kept where the image is
+    pub include_alt_text: bool,
+}
+
+impl Default for ExtractTextOptions {
+    fn default() -> Self {
+        return ExtractTextOptions { preserve_links: false, include_alt_text: true };
+    }
+}
+
+// Renders document as lines of at most columns characters, except for preformatted lines and words that are longer
+pub fn render_as_text(document: &RefNode, columns: usize) -> String {
+    return render_as_text_with_links(document, columns).0;
+}
+
+// The text of document with the URLs of its numbered links, link n is at index n - 1, so a reader can follow a link by its number
+pub fn render_as_text_with_links(document: &RefNode, columns: usize) -> (String, Vec<String>) {
+    let mut renderer = TextRenderer {
+        columns,
+        lines: Vec::new(),
+        inline_content: String::new(),
+        white_space: WhiteSpace::Normal,
+        direction: Direction::Ltr,
+        indent: 0,
+        pending_marker: None,
+        pending_blank_line: false,
+        list_depth: 0,
+        links: Vec::new(),
+        extraction: None,
+    };
+
+    if let Some(root) = build_box_tree(document) {
+        renderer.render_box(&root);
+    }
+    renderer.flush_inline_content();
+    renderer.write_references();
+
+    let mut text = renderer.lines.join("\n");
+    if !text.is_empty() {
+        text.push('\n');
+    }
+    return (text, renderer.links);
+}
+
+// The text of document for indexing and scraping, unlike innerText its paragraphs are separated by blank lines, list items keep their bullet or number and the cells of
+// a table row are separated by tabs, and unlike render_as_text its lines are not wrapped and headings are not underlined
+// What is not rendered (e.g. display: none, script and style elements) is left out
+pub fn extract_text(document: &RefNode, options: &ExtractTextOptions) -> String {
+    let mut renderer = TextRenderer {
+        columns: usize::MAX,
+        lines: Vec::new(),
+        inline_content: String::new(),
+        white_space: WhiteSpace::Normal,
+        direction: Direction::Ltr,
+        indent: 0,
+        pending_marker: None,
+        pending_blank_line: false,
+        list_depth: 0,
+        links: Vec::new(),
+        extraction: Some(options.clone()),
+    };
+
+    if let Some(root) = build_box_tree(document) {
+        renderer.render_box(&root);
+    }
+    renderer.flush_inline_content();
+
+    let mut text = renderer.lines.join("\n");
+    if !text.is_empty() {
+        text.push('\n');
+    }
+    return text;
+}
+
+impl TextRenderer {
+    fn render_box(&mut self, layout_box: &LayoutBox) {
+        match &layout_box.box_type {
+            BoxType::Text(text) => self.inline_content.push_str(text),
+            BoxType::Inline => self.render_inline_box(layout_box),
+            BoxType::Block | BoxType::ListItem => self.render_block_box(layout_box)
+        }
+    }
+
+    fn render_children(&mut self, layout_box: &LayoutBox) {
+        for child in &layout_box.children {
+            self.render_box(child);
+        }
+    }
+
+    fn render_inline_box(&mut self, layout_box: &LayoutBox) {
+        match layout_box.local_name().as_str() {
+            // https://html.spec.whatwg.org/multipage/rendering.html#phrasing-content-3
+            // A br is a forced line break
+            "br" => self.inline_content.push('\n'),
+            // https://html.spec.whatwg.org/multipage/rendering.html#images-3
+            // An image is replaced by its alternative text
+            "img" => {
+                if let Some(alt) = layout_box.get_attribute("alt").filter(|alt| !alt.trim().is_empty()) {
+                    match &self.extraction {
+                        Some(options) if options.include_alt_text => self.inline_content.push_str(alt.trim()),
+                        Some(_) => (),
+                        None => {
+                            self.inline_content.push('[');
+                            self.inline_content.push_str(alt.trim());
+                            self.inline_content.push(']');
+                        }
+                    }
+                }
+            },
+            // The text of a link is followed by the number of its URL in the References
+            // Extracted text has the URL in parentheses instead, when the links are preserved
+            "a" => {
+                self.render_children(layout_box);
+                if let Some(href) = layout_box.get_attribute("href") {
+                    let url = encoding_parse_url(&layout_box.node, &href).map(|url| url.href()).unwrap_or(href);
+                    match &self.extraction {
+                        Some(options) if options.preserve_links => self.inline_content.push_str(&format!(" ({})", url)),
+                        Some(_) => (),
+                        None => {
+                            self.links.push(url);
+                            self.inline_content.push_str(&format!("[{}]", self.links.len()));
+                        }
+                    }
+                }
+            },
+            _ => self.render_children(layout_box)
+        }
+    }
+
+    fn render_block_box(&mut self, layout_box: &LayoutBox) {
+        // The inline content before the block is a paragraph of its own
+        self.flush_inline_content();
+
+        let local_name = layout_box.local_name();
+        let inside_list_item = self.list_depth > 0;
+        let has_margins = match local_name.as_str() {
+            "p" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "pre" | "listing" | "xmp" | "plaintext" | "blockquote" | "dl" | "figure" | "hr" => true,
+            // Nested lists are not separated from the items around them
+            "ul" | "ol" | "menu" | "dir" => !inside_list_item,
+            _ => false
+        };
+        if has_margins {
+            self.pending_blank_line = true;
+        }
+
+        let previous_indent = self.indent;
+        let previous_white_space = self.white_space;
+        let previous_direction = self.direction;
+        self.white_space = layout_box.white_space;
+        self.direction = directionality(&layout_box.node);
+        let first_line = self.lines.len();
+
+        match local_name.as_str() {
+            // An hr only separates the paragraphs around it in extracted text
+            "hr" if self.extraction.is_some() => (),
+            // The cells of a row in extracted text are on one line, separated by tabs
+            "tr" if self.extraction.is_some() => self.render_table_row(layout_box),
+            "hr" => {
+                let width = self.columns.saturating_sub(self.indent).max(1);
+                self.write_lines(vec!["-".repeat(width)]);
+            },
+            "ul" | "ol" | "menu" | "dir" => {
+                self.list_depth += 1;
+                self.indent += 2;
+                self.render_children(layout_box);
+                self.list_depth -= 1;
+            },
+            "blockquote" | "dd" => {
+                self.indent += 4;
+                self.render_children(layout_box);
+            },
+            _ if layout_box.box_type == BoxType::ListItem => self.render_list_item(layout_box),
+            _ => self.render_children(layout_box)
+        }
+
+        self.flush_inline_content();
+
+        // Headings are underlined as wide as their longest line, with = for h1 and - for the others
+        let underline = match local_name.as_str() {
+            _ if self.extraction.is_some() => None,
+            "h1" => Some('='),
+            "h2" | "h3" | "h4" | "h5" | "h6" => Some('-'),
+            _ => None
+        };
+        if let Some(underline) = underline {
+            let width = self.lines[first_line..].iter().map(|line| line.chars().count().saturating_sub(self.indent)).max().unwrap_or(0);
+            if width > 0 {
+                self.write_lines(vec![underline.to_string().repeat(width)]);
+            }
+        }
+
+        self.indent = previous_indent;
+        self.white_space = previous_white_space;
+        self.direction = previous_direction;
+        if has_margins {
+            self.pending_blank_line = true;
+        }
+    }
+
+    // https://html.spec.whatwg.org/multipage/rendering.html#lists
+    // The marker is written before the first line of the item, and the lines after it are indented to line up with the first
+    // A terminal may not have the bullets of disc, circle and square, so they are written as *, + and -
+    fn render_list_item(&mut self, layout_box: &LayoutBox) {
+        let marker_box = layout_box.children.iter().find(|child| child.pseudo_element == Some(PseudoElement::Marker));
+        let marker: String = marker_box.map(|marker_box| marker_box.children.iter().filter_map(|child| match &child.box_type {
+            BoxType::Text(text) => Some(text.as_str()),
+            _ => None
+        }).collect()).unwrap_or_default();
+        let marker = match marker.as_str() {
+            "• " => "* ".to_string(),
+            "◦ " => "+ ".to_string(),
+            "▪ " => "- ".to_string(),
+            _ => marker
+        };
+
+        self.indent += marker.chars().count();
+        if !marker.is_empty() {
+            self.pending_marker = Some(marker);
+        }
+        for child in layout_box.children.iter().filter(|child| child.pseudo_element != Some(PseudoElement::Marker)) {
+            self.render_box(child);
+        }
+        self.flush_inline_content();
+
+        // An empty item still gets its marker
+        if self.pending_marker.is_some() {
+            self.write_lines(vec![String::new()]);
+        }
+    }
+
+    // The text of each cell of the row with its whitespace collapsed, the cells of a row in the box tree are blocks which would each be a line of their own
+    fn render_table_row(&mut self, layout_box: &LayoutBox) {
+        let mut cells = Vec::new();
+        for cell in &layout_box.children {
+            let inline_content = std::mem::take(&mut self.inline_content);
+            self.render_box_contents(cell);
+            let cell_text = std::mem::replace(&mut self.inline_content, inline_content);
+            cells.push(cell_text.split(' ').filter(|word| !word.is_empty()).collect::<Vec<&str>>().join(" "));
+        }
+        self.inline_content.push_str(&cells.join("\t"));
+    }
+
+    // The children of a box as inline content, whatever its box type
+    fn render_box_contents(&mut self, layout_box: &LayoutBox) {
+        match &layout_box.box_type {
+            BoxType::Text(text) => self.inline_content.push_str(text),
+            _ => self.render_inline_box(layout_box)
+        }
+    }
+
+    // Breaks the inline content gathered since the last block started or ended into lines and writes them
+    fn flush_inline_content(&mut self) {
+        let inline_content = std::mem::take(&mut self.inline_content);
+        if inline_content.trim().is_empty() {
+            return;
+        }
+
+        let mut lines: Vec<String> = if self.white_space.preserves_spaces() {
+            // TODO: pre-wrap is not wrapped
+            inline_content.split('\n').map(|line| line.to_string()).collect()
+        } else {
+            let available_width = if self.white_space.wraps() { self.columns.saturating_sub(self.indent).max(1) as f32 } else { f32::INFINITY };
+            break_lines(&inline_content, &CharacterCells, 1.0, available_width).into_iter().map(|line| line.text).collect()
+        };
+
+        // The line breaks and spaces at the end of the content do not make lines of their own
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+        self.write_lines(lines);
+    }
+
+    fn write_lines(&mut self, lines: Vec<String>) {
+        for line in lines {
+            if self.pending_blank_line {
+                if self.lines.last().is_some_and(|last_line| !last_line.is_empty()) {
+                    self.lines.push(String::new());
+                }
+                self.pending_blank_line = false;
+            }
+
+            let prefix = match self.pending_marker.take() {
+                Some(marker) => format!("{}{}", " ".repeat(self.indent.saturating_sub(marker.chars().count())), marker),
+                None => " ".repeat(self.indent)
+            };
+            // Extracted text is kept in the order it is written, which is the order it is searched in
+            let line = match self.extraction {
+                Some(_) => line,
+                None => reorder_line(&line, self.direction)
+            };
+            let line = format!("{}{}", prefix, line).trim_end().to_string();
+            // Extracted text is not aligned, as it is not laid out for a width
+            let line = match self.direction {
+                Direction::Rtl if self.extraction.is_none() && !line.is_empty() => format!("{}{}", " ".repeat(self.columns.saturating_sub(line.chars().count())), line),
+                _ => line
+            };
+            self.lines.push(line);
+        }
+    }
+
+    // The URL of each link, numbered as they are referred to in the text
+    fn write_references(&mut self) {
+        if self.links.is_empty() {
+            return;
+        }
+
+        self.indent = 0;
+        self.pending_blank_line = true;
+        self.write_lines(vec!["References".to_string()]);
+        self.pending_blank_line = true;
+        let lines = self.links.iter().enumerate().map(|(index, url)| format!("{:>4}. {}", index + 1, url)).collect();
+        self.write_lines(lines);
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/dom.html#rendered-text-collection-steps
+// The text of the boxes, and how many line breaks have to be between the text before them and the text after
+enum RenderedTextItem {
+    // The text of a box, and whether its spaces are collapsible
+    Text(String, bool),
+    RequiredLineBreakCount(usize),
+}
+
+// https://html.spec.whatwg.org/multipage/dom.html#the-innertext-idl-attribute
+// The text of element as it is rendered, without the text of its display: none descendants and with line breaks around its blocks, e.g. the innerText of
+// <div>a<p>b</p><span style="display: none">c</span>d</div> is "a\n\nb\n\nd"
+// TODO: visibility, text-transform and select, optgroup and option elements are not taken into account
+pub fn inner_text(element: &RefNode) -> String {
+    // 1. If this is not being rendered or if the user agent is a non-CSS user agent, then return this's descendant text content.
+    let element_box = node_document(element).and_then(|document| build_box_tree(&document)).and_then(|root| find_box(&root, element).cloned());
+    let element_box = match element_box {
+        Some(element_box) => element_box,
+        None => return text_content(element).unwrap_or_default()
+    };
+
+    // 2. Let results be a new empty list.
+    // 3. For each child node node of this: Let current be the list resulting in running the rendered text collection steps with node. For each item item in current, append item to results.
+    let mut results = Vec::new();
+    collect_children(&element_box, &mut results);
+
+    // 4-7. Remove the empty strings and the required line break counts at the start and end of results, and replace each run of required line break counts
+    // with as many line feeds as the largest of them
+    // The collapsible spaces at the start and end of each line are removed as well, which the line breaking of layout would otherwise do
+    let mut text = String::new();
+    let mut pending_line_breaks = 0;
+    let mut trailing_collapsible_space = false;
+    for item in results {
+        let (item_text, collapsible) = match item {
+            RenderedTextItem::Text(item_text, collapsible) => (item_text, collapsible),
+            RenderedTextItem::RequiredLineBreakCount(count) => {
+                pending_line_breaks = pending_line_breaks.max(count);
+                continue;
+            }
+        };
+        for character in item_text.chars() {
+            if collapsible && character == ' ' {
+                if text.is_empty() || text.ends_with('\n') || trailing_collapsible_space || pending_line_breaks > 0 {
+                    continue;
+                }
+                text.push(' ');
+                trailing_collapsible_space = true;
+                continue;
+            }
+            if pending_line_breaks > 0 || character == '\n' {
+                if trailing_collapsible_space {
+                    text.pop();
+                }
+                if !text.is_empty() {
+                    text.push_str(&"\n".repeat(pending_line_breaks));
+                }
+                pending_line_breaks = 0;
+            }
+            text.push(character);
+            trailing_collapsible_space = false;
+        }
+    }
+    if trailing_collapsible_space {
+        text.pop();
+    }
+
+    // 8. Return the concatenation of the string items in results.
+    return text;
+}
+
+// The box element generated, None when it generated none
+fn find_box<'a>(layout_box: &'a LayoutBox, element: &RefNode) -> Option<&'a LayoutBox> {
+    if Rc::ptr_eq(&layout_box.node, element) {
+        return Some(layout_box);
+    }
+    return layout_box.children.iter().find_map(|child| find_box(child, element));
+}
+
+// https://html.spec.whatwg.org/multipage/dom.html#rendered-text-collection-steps
+// Only boxes are walked, so the nodes that are not being rendered (1. and 2.) are skipped along with everything under them
+fn rendered_text_collection_steps(layout_box: &LayoutBox, last_table_cell: bool, items: &mut Vec<RenderedTextItem>) {
+    let local_name = layout_box.local_name();
+    match &layout_box.box_type {
+        // 4. If node is a Text node, then for each CSS text box produced by node, in content order, compute the text of the box after application of the CSS 'white-space' processing rules
+        // and 'text-transform' rules, set items to the list of the resulting strings, and return items.
+        BoxType::Text(text) => {
+            items.push(RenderedTextItem::Text(text.clone(), !layout_box.white_space.preserves_spaces()));
+            return;
+        },
+        // 5. If node is a br element, then append a string containing a single U+000A LF code point to items.
+        _ if local_name == "br" => {
+            items.push(RenderedTextItem::Text("\n".to_string(), false));
+            return;
+        },
+        _ => ()
+    }
+
+    // 8. If node is a p element, then append 2 (a required line break count) at the beginning and end of items.
+    // 9. If node's used value of 'display' is block-level or 'table-caption', then append 1 (a required line break count) at the beginning and end of items.
+    // Table cells are laid out as blocks, but they are separated by tabs rather than line breaks
+    let table_cell = is_table_cell(layout_box);
+    let required_line_break_count = match layout_box.box_type {
+        _ if local_name == "p" => Some(2),
+        BoxType::Block | BoxType::ListItem if !table_cell => Some(1),
+        _ => None
+    };
+
+    if let Some(count) = required_line_break_count {
+        items.push(RenderedTextItem::RequiredLineBreakCount(count));
+    }
+    // 3. Let items be the result of running the rendered text collection steps with each child node of node in tree order, and then concatenating the results to a single list.
+    collect_children(layout_box, items);
+    if let Some(count) = required_line_break_count {
+        items.push(RenderedTextItem::RequiredLineBreakCount(count));
+    }
+
+    // 6. If node's used value of 'display' is 'table-cell', and node's CSS box is not the last 'table-cell' box of its enclosing 'table-row' box, then append a string containing a single
+    // U+0009 TAB code point to items.
+    if table_cell && !last_table_cell {
+        items.push(RenderedTextItem::Text("\t".to_string(), false));
+    }
+}
+
+// The rendered text collection steps of each child box, with whether a table cell box is followed by another in its row
+// The boxes of pseudo-elements (e.g. list item markers) are not of any node, so their text is left out
+fn collect_children(layout_box: &LayoutBox, items: &mut Vec<RenderedTextItem>) {
+    for (index, child) in layout_box.children.iter().enumerate() {
+        if child.pseudo_element.is_some() {
+            continue;
+        }
+        let last_table_cell = !layout_box.children[index + 1..].iter().any(is_table_cell);
+        rendered_text_collection_steps(child, last_table_cell, items);
+    }
+}
+
+fn is_table_cell(layout_box: &LayoutBox) -> bool {
+    let local_name = layout_box.local_name();
+    return local_name == "td" || local_name == "th";
+}
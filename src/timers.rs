@@ -0,0 +1,169 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::interpreter::{completion_value, create_value_completion, CompletionRecord, CompletionRecordType, Interpreter, JSValue, ReferenceRecordOrJsValue};
+
+// https://html.spec.whatwg.org/multipage/timers-and-user-prompts.html#timers
+
+// https://html.spec.whatwg.org/multipage/timers-and-user-prompts.html#timerhandler
+#[derive(Clone)]
+enum TimerHandler {
+    Function(Rc<RefCell<JSValue>>),
+    // A string is compiled and run as a classic script when the timer fires
+    String(String),
+}
+
+// https://html.spec.whatwg.org/multipage/timers-and-user-prompts.html#windoworworkerglobalscope-mixin
+pub(crate) fn install_timers(interpreter: &mut Interpreter) {
+    let global_object = interpreter.global_object();
+    Interpreter::define_builtin_function(&global_object, "setTimeout", set_timeout);
+    Interpreter::define_builtin_function(&global_object, "setInterval", set_interval);
+    Interpreter::define_builtin_function(&global_object, "clearTimeout", clear_timeout);
+    Interpreter::define_builtin_function(&global_object, "clearInterval", clear_timeout);
+}
+
+// https://webidl.spec.whatwg.org/#es-long
+// The optional long arguments of the timer methods default to 0
+fn argument_to_long(interpreter: &mut Interpreter, arguments: &[Rc<RefCell<JSValue>>], index: usize) -> Result<i32, CompletionRecord> {
+    let value = match arguments.get(index) {
+        Some(value) if !matches!(*value.borrow(), JSValue::Undefined) => Rc::clone(value),
+        _ => return Ok(0)
+    };
+
+    // FIXME: ToNumber is not implemented for objects yet
+    if matches!(*value.borrow(), JSValue::Object(_)) {
+        let primitive = completion_value(interpreter.to_string_coercing_objects(value))?;
+        return argument_to_long(interpreter, &[primitive], 0);
+    }
+
+    // 1. Let x be ? ToNumber(V).
+    // 2-6. The conversion for long without [EnforceRange] or [Clamp] is the same as ToInt32.
+    let number = match &*completion_value(Interpreter::to_number(value))?.borrow() {
+        JSValue::Numeric(number) => *number,
+        _ => unreachable!()
+    };
+    return Ok(Interpreter::to_uint32(number) as i32);
+}
+
+// https://html.spec.whatwg.org/multipage/timers-and-user-prompts.html#dom-settimeout
+fn set_timeout(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // The setTimeout(handler, timeout, ...arguments) method steps are to return the result of running the timer initialization steps given this, handler, timeout, arguments, and false.
+    return start_timer(interpreter, arguments, false);
+}
+
+// https://html.spec.whatwg.org/multipage/timers-and-user-prompts.html#dom-setinterval
+fn set_interval(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // The setInterval(handler, timeout, ...arguments) method steps are to return the result of running the timer initialization steps given this, handler, timeout, arguments, and true.
+    return start_timer(interpreter, arguments, true);
+}
+
+// Converts the arguments shared by setTimeout and setInterval
+fn start_timer(interpreter: &mut Interpreter, arguments: Vec<Rc<RefCell<JSValue>>>, repeat: bool) -> CompletionRecord {
+    let handler = match arguments.first() {
+        Some(handler) if Interpreter::is_callable(&handler.borrow()) => TimerHandler::Function(Rc::clone(handler)),
+        Some(handler) => {
+            match completion_value(interpreter.to_string_coercing_objects(Rc::clone(handler))) {
+                Ok(source) => {
+                    match &*source.borrow() {
+                        JSValue::String(source) => TimerHandler::String(source.clone()),
+                        _ => unreachable!()
+                    }
+                },
+                Err(completion_record) => return completion_record
+            }
+        },
+        None => TimerHandler::String(String::new())
+    };
+
+    let timeout = match argument_to_long(interpreter, &arguments, 1) {
+        Ok(timeout) => timeout,
+        Err(completion_record) => return completion_record
+    };
+
+    let arguments = arguments.into_iter().skip(2).collect();
+    let id = timer_initialization_steps(interpreter, handler, timeout, arguments, repeat, None);
+    return create_value_completion(JSValue::Numeric(id as f64));
+}
+
+// https://html.spec.whatwg.org/multipage/timers-and-user-prompts.html#timer-initialisation-steps
+fn timer_initialization_steps(interpreter: &mut Interpreter, handler: TimerHandler, timeout: i32, arguments: Vec<Rc<RefCell<JSValue>>>, repeat: bool, previous_id: Option<i32>) -> i32 {
+    // 2. If previousId was given, let id be previousId; otherwise, let id be an implementation-defined integer that is greater than zero and does not already exist in global's map of active timers.
+    let id = match previous_id {
+        Some(previous_id) => previous_id,
+        None => interpreter.event_loop.create_timer_id()
+    };
+
+    // 3. If the surrounding agent's event loop's currently running task is a task that was created by this algorithm, then let nesting level be the task's timer nesting level. Otherwise, let nesting level be 0.
+    let nesting_level = interpreter.event_loop.timer_nesting_level;
+
+    // 4. If timeout is less than 0, then set timeout to 0.
+    // 5. If nesting level is greater than 5, and timeout is less than 4, then set timeout to 4.
+    let mut timeout = timeout.max(0);
+    if nesting_level > 5 && timeout < 4 {
+        timeout = 4;
+    }
+
+    // 9. Increment nesting level by one.
+    // 10. Let task's timer nesting level be nesting level.
+    let nesting_level = nesting_level + 1;
+
+    // 8. Let task be a task that runs the following substeps:
+    let task = Box::new(move |interpreter: &mut Interpreter| {
+        // 1. If id does not exist in global's map of active timers, then abort these steps.
+        if !interpreter.event_loop.is_active_timer(id) {
+            return;
+        }
+
+        interpreter.event_loop.timer_nesting_level = nesting_level;
+        match &handler {
+            // 3. If handler is a Function, then invoke handler given arguments and "report", and with callback this value set to thisArg.
+            TimerHandler::Function(function) => {
+                let this_value = Rc::new(RefCell::new(JSValue::Object(interpreter.global_object())));
+                let completion_record = interpreter.call(Rc::clone(function), this_value, arguments.clone());
+                if let (CompletionRecordType::Throw, ReferenceRecordOrJsValue::JSValue(exception)) = (&completion_record.type_, &*completion_record.value) {
+                    interpreter.report_exception(exception);
+                }
+            },
+            // 4. Otherwise, create a classic script with handler and run it.
+            TimerHandler::String(source) => {
+                interpreter.run_script(source.clone(), None);
+            }
+        }
+        interpreter.event_loop.timer_nesting_level = 0;
+
+        // 5. If id does not exist in global's map of active timers, then abort these steps.
+        // This happens when the handler cleared the timer.
+        if !interpreter.event_loop.is_active_timer(id) {
+            return;
+        }
+
+        // 6. If repeat is true, then perform the timer initialization steps again, given global, handler, timeout, arguments, true, and id.
+        // 7. Otherwise, remove global's map of active timers[id].
+        if repeat {
+            timer_initialization_steps(interpreter, handler, timeout, arguments, true, Some(id));
+        } else {
+            interpreter.event_loop.remove_timer(id);
+        }
+    });
+
+    // 12. Let completionStep be an algorithm step which queues a global task on the timer task source given global to run task.
+    // 13. Set uniqueHandle to the result of running steps after a timeout given global, "setTimeout/setInterval", timeout, and completionStep.
+    // 14. Set global's map of active timers[id] to uniqueHandle.
+    interpreter.event_loop.activate_timer(id);
+    interpreter.event_loop.run_steps_after_a_timeout(id, timeout as u32, task);
+
+    // 15. Return id.
+    return id;
+}
+
+// https://html.spec.whatwg.org/multipage/timers-and-user-prompts.html#dom-cleartimeout
+// https://html.spec.whatwg.org/multipage/timers-and-user-prompts.html#dom-clearinterval
+fn clear_timeout(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    let id = match argument_to_long(interpreter, &arguments, 0) {
+        Ok(id) => id,
+        Err(completion_record) => return completion_record
+    };
+
+    // The clearTimeout(id) and clearInterval(id) method steps are to remove this's map of active timers[id].
+    interpreter.event_loop.remove_timer(id);
+    return create_value_completion(JSValue::Undefined);
+}
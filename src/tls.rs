@@ -0,0 +1,54 @@
+use std::net::TcpStream;
+use std::sync::Arc;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, ClientConnection, DigitallySignedStruct, RootCertStore, SignatureScheme, StreamOwned};
+
+// https://www.rfc-editor.org/rfc/rfc8446.html
+// The TLS connections https: URLs are loaded over, with the certificate of the server verified against the Mozilla root certificates
+// TODO: The root certificates are built in, the certificate store of the operating system is not used
+
+pub(crate) type TlsStream = StreamOwned<ClientConnection, TcpStream>;
+
+// Starts a TLS session with the server stream is connected to, hostname is the name its certificate has to be for
+// Without verify_certificates any certificate is accepted, e.g. a self-signed one, although the handshake signatures are still checked
+pub(crate) fn connect(stream: TcpStream, hostname: &str, verify_certificates: bool) -> Result<TlsStream, String> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ClientConfig::builder_with_provider(Arc::clone(&provider)).with_safe_default_protocol_versions().map_err(|error| error.to_string())?;
+    let config = if verify_certificates {
+        let mut roots = RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        builder.with_root_certificates(roots).with_no_client_auth()
+    } else {
+        builder.dangerous().with_custom_certificate_verifier(Arc::new(AcceptAnyCertificate { provider })).with_no_client_auth()
+    };
+
+    let server_name = ServerName::try_from(hostname.to_string()).map_err(|error| format!("{}: {}", hostname, error))?;
+    let connection = ClientConnection::new(Arc::new(config), server_name).map_err(|error| error.to_string())?;
+    return Ok(StreamOwned::new(connection, stream));
+}
+
+// The verifier of --insecure, which trusts the certificate the server presents
+#[derive(Debug)]
+struct AcceptAnyCertificate {
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(&self, _end_entity: &CertificateDer<'_>, _intermediates: &[CertificateDer<'_>], _server_name: &ServerName<'_>, _ocsp_response: &[u8], _now: UnixTime) -> Result<ServerCertVerified, rustls::Error> {
+        return Ok(ServerCertVerified::assertion());
+    }
+
+    fn verify_tls12_signature(&self, message: &[u8], certificate: &CertificateDer<'_>, signature: &DigitallySignedStruct) -> Result<HandshakeSignatureValid, rustls::Error> {
+        return verify_tls12_signature(message, certificate, signature, &self.provider.signature_verification_algorithms);
+    }
+
+    fn verify_tls13_signature(&self, message: &[u8], certificate: &CertificateDer<'_>, signature: &DigitallySignedStruct) -> Result<HandshakeSignatureValid, rustls::Error> {
+        return verify_tls13_signature(message, certificate, signature, &self.provider.signature_verification_algorithms);
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        return self.provider.signature_verification_algorithms.supported_schemes();
+    }
+}
@@ -1,21 +1,31 @@
+use std::fmt;
+use crate::atom::Atom;
+
 #[derive(Debug)]
 #[derive(Clone)]
 #[derive(PartialEq)]
 pub enum TokenType {
     // Single-character tokens.
     LeftParen, RIGHT_PAREN, LEFT_BRACE, RIGHT_BRACE,
+    LEFT_BRACKET, RIGHT_BRACKET,
     COMMA, DOT, MINUS, PLUS, SEMICOLON, SLASH, STAR,
     BITWISE_NOT, COLON,
 
     // One or two character tokens.
     BANG, BANG_EQUAL,
-    EQUAL, EQUAL_EQUAL,
+    EQUAL, EQUAL_EQUAL, ARROW,
     GREATER, GREATER_EQUAL,
     LESS, LESS_EQUAL,
 
     // Literals.
     IDENTIFIER, STRING, NUMBER,
 
+    // https://tc39.es/ecma262/#prod-RegularExpressionLiteral
+    REGULAR_EXPRESSION,
+
+    // https://tc39.es/ecma262/#sec-template-literal-lexical-components
+    NO_SUBSTITUTION_TEMPLATE, TEMPLATE_HEAD, TEMPLATE_MIDDLE, TEMPLATE_TAIL,
+
     // Reserved keywords.
     // https://tc39.es/ecma262/#prod-ReservedWord
     CLASS, ELSE, FALSE, FOR, IF, NULL,
@@ -25,6 +35,10 @@ pub enum TokenType {
     FUNCTION, IMPORT, IN, INSTANCEOF, NEW, SWITCH,
     THROW, TRY, TYPEOF, VOID, WITH, YIELD,
 
+    // https://tc39.es/ecma262/#sec-keywords-and-reserved-words
+    // let is only reserved in strict mode code, but it is always scanned as a keyword
+    LET,
+
     EOF
 }
 
@@ -35,21 +49,26 @@ pub enum Literal {
     String(String),
     Numeric(f64),
     Boolean(bool),
-    Null()
+    Null(),
+    // https://tc39.es/ecma262/#sec-literals-regular-expression-literals
+    // The body and flags of a regular expression literal, which evaluates to a new RegExp object each time
+    RegExp { pattern: String, flags: String }
 }
 
 #[derive(Clone)]
 #[derive(Debug)]
 pub struct Token {
     pub token_type: TokenType,
-    pub lexeme: String,
+    pub lexeme: Atom,
     pub literal: Option<Literal>,
-    pub line: usize
+    // Where the token starts, lines and columns start at 1 and a column counts the characters before it on its line plus one
+    pub line: usize,
+    pub column: usize,
 }
 
 impl Token {
-    pub fn new(token_type: TokenType, lexeme: String, literal: Option<Literal>, line: usize) -> Token {
-        Token { token_type, lexeme, literal, line }
+    pub fn new(token_type: TokenType, lexeme: String, literal: Option<Literal>, line: usize, column: usize) -> Token {
+        Token { token_type, lexeme: Atom::from(lexeme), literal, line, column }
     }
 
     pub fn to_string(&self) -> String {
@@ -62,4 +81,20 @@ impl Token {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+// https://tc39.es/ecma262/#early-error
+// An error the scanner or parser found in the source text, a script with any of them is not evaluated
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct SyntaxError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SyntaxError: {} at line {}, column {}", self.message, self.line, self.column)
+    }
+}
@@ -0,0 +1,85 @@
+use crate::html_document_parser::tokenizer_state_for_text_element;
+use crate::html_token::{HtmlToken, HtmlTokenType};
+use crate::parse_error::ParseError;
+use crate::source_location::SourcePosition;
+use crate::tokenizer::HTMLTokenizerState;
+
+// What a Tokenizer gives its tokens to, the tree construction stage of an HTMLDocumentParser by default
+// Another sink takes the tokens without a document being built at all, e.g. to count the elements of a page, to drop the tags a sanitizer does not allow,
+// or to pick out the text of some elements as the tokens go by:
+//     let mut tokenizer = Tokenizer::with_sink(html, ElementCounter::default());
+//     tokenizer.run();
+//     let element_count = tokenizer.into_sink().element_count;
+// The tree builder is what tells the tokenizer when to treat the contents of an element as text (e.g. of a script), a sink that does not build a tree
+// can return TokenSinkResult::in_body for each token to have it tokenized as the tree builder would in the body of a document
+pub trait TokenSink {
+    // Called with each token in the order they are emitted, the last one is the end of file token
+    fn handle_token(&mut self, html_token: &HtmlToken) -> TokenSinkResult;
+
+    // Called once every token has been handled, after the end of file token
+    fn end(&mut self);
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#parse-errors
+    // A parse error found by the tokenizer, at position in the input, they are ignored unless the sink keeps them
+    fn parse_error(&mut self, _parse_error: &ParseError, _position: SourcePosition) {}
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#parser-pause-flag
+    // Whether the tokenizer has to stop giving tokens to the sink until it is run again
+    fn is_paused(&self) -> bool {
+        return false;
+    }
+
+    // https://html.spec.whatwg.org/multipage/parsing.html#acknowledge-self-closing-flag
+    // Called after each token, whether the sink acknowledged the self-closing flag of the start tag it was just given
+    // The tokenizer reports the non-void-html-element-start-tag-with-trailing-solidus parse error when it did not, a sink that does not know which elements are void acknowledges them all
+    fn take_self_closing_flag_acknowledged(&mut self) -> bool {
+        return true;
+    }
+}
+
+// The state the tokenizer goes on in once the sink has handled a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenSinkResult {
+    // The state the tokenizer is in
+    Continue,
+    // https://html.spec.whatwg.org/multipage/parsing.html#rcdata-state
+    RcData,
+    // https://html.spec.whatwg.org/multipage/parsing.html#rawtext-state
+    RawText,
+    // https://html.spec.whatwg.org/multipage/parsing.html#script-data-state
+    ScriptData,
+    // https://html.spec.whatwg.org/multipage/parsing.html#plaintext-state
+    PlainText,
+}
+
+impl TokenSinkResult {
+    // The state the tree builder switches the tokenizer to after the token in the "in body" insertion mode, e.g. script data after a script start tag
+    // With the scripting flag set the contents of a noscript element are text as well
+    pub fn in_body(html_token: &HtmlToken, scripting_flag: bool) -> TokenSinkResult {
+        if !matches!(html_token.token_type, HtmlTokenType::StartTag) {
+            return TokenSinkResult::Continue;
+        }
+        return TokenSinkResult::from_tokenizer_state(tokenizer_state_for_text_element(&html_token.tag_name, scripting_flag));
+    }
+
+    // The tree builder only ever switches the tokenizer to the states for the contents of text elements
+    pub(crate) fn from_tokenizer_state(tokenizer_state: Option<HTMLTokenizerState>) -> TokenSinkResult {
+        return match tokenizer_state {
+            Some(HTMLTokenizerState::RCData) => TokenSinkResult::RcData,
+            Some(HTMLTokenizerState::RawText) => TokenSinkResult::RawText,
+            Some(HTMLTokenizerState::ScriptData) => TokenSinkResult::ScriptData,
+            Some(HTMLTokenizerState::PlainText) => TokenSinkResult::PlainText,
+            _ => TokenSinkResult::Continue
+        };
+    }
+
+    pub(crate) fn tokenizer_state(&self) -> Option<HTMLTokenizerState> {
+        return match self {
+            TokenSinkResult::Continue => None,
+            TokenSinkResult::RcData => Some(HTMLTokenizerState::RCData),
+            TokenSinkResult::RawText => Some(HTMLTokenizerState::RawText),
+            TokenSinkResult::ScriptData => Some(HTMLTokenizerState::ScriptData),
+            TokenSinkResult::PlainText => Some(HTMLTokenizerState::PlainText),
+        };
+    }
+}
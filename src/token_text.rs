@@ -0,0 +1,196 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::rc::Rc;
+use crate::atom::Atom;
+
+// The text of a token (a tag name, an attribute name or value, or character data) kept as a range of the input while it is exactly as it is in the input,
+// so the tokenizer does not copy it character by character into a string of its own
+// Text that differs from the input, e.g. a tag name that was lowercased or data with a character reference in it, is owned instead
+// Tag and attribute names are interned once the tokenizer is done with them, so the elements created for them share the atom
+#[derive(Clone)]
+pub struct TokenText {
+    repr: Repr,
+}
+
+#[derive(Clone)]
+enum Repr {
+    Span {
+        source: Rc<Vec<u8>>,
+        start: usize,
+        end: usize,
+    },
+    Owned(String),
+    Atom(Atom),
+}
+
+impl TokenText {
+    pub fn new() -> Self {
+        return TokenText { repr: Repr::Owned(String::new()) };
+    }
+
+    pub fn as_str(&self) -> &str {
+        match &self.repr {
+            // A range only ever has ASCII characters in it
+            Repr::Span { source, start, end } => return std::str::from_utf8(&source[*start..*end]).unwrap_or_default(),
+            Repr::Owned(text) => return text,
+            Repr::Atom(atom) => return atom
+        }
+    }
+
+    // Replaces the text with the atom for it
+    pub(crate) fn intern(&mut self) {
+        if !matches!(self.repr, Repr::Atom(_)) {
+            self.repr = Repr::Atom(Atom::new(self.as_str()));
+        }
+    }
+
+    // The atom for the text, which is only looked up in the atom table when the text was not interned already
+    pub fn to_atom(&self) -> Atom {
+        match &self.repr {
+            Repr::Atom(atom) => return atom.clone(),
+            _ => return Atom::new(self.as_str())
+        }
+    }
+
+    // Whether the text is a range of the input rather than a copy of it
+    pub fn is_span(&self) -> bool {
+        return matches!(self.repr, Repr::Span { .. });
+    }
+
+    // Appends character, which is the input character at position in source when position is given
+    // The text stays a range of source for as long as each character appended is the one after the range
+    // A character that is not ASCII takes up more than one byte of the input, so it makes the text owned
+    pub(crate) fn push(&mut self, character: char, source: &Rc<Vec<u8>>, position: Option<usize>) {
+        if let Some(position) = position {
+            if character.is_ascii() && source.get(position) == Some(&(character as u8)) {
+                match &mut self.repr {
+                    Repr::Owned(text) if text.is_empty() => {
+                        self.repr = Repr::Span { source: Rc::clone(source), start: position, end: position + 1 };
+                        return;
+                    },
+                    Repr::Span { source: span_source, end, .. } if *end == position && Rc::ptr_eq(span_source, source) => {
+                        *end += 1;
+                        return;
+                    },
+                    _ => ()
+                }
+            }
+        }
+
+        self.to_mut().push(character);
+    }
+
+    pub(crate) fn push_str(&mut self, text: &str) {
+        if !text.is_empty() {
+            self.to_mut().push_str(text);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return match &self.repr {
+            Repr::Span { start, end, .. } => start == end,
+            Repr::Owned(text) => text.is_empty(),
+            Repr::Atom(atom) => atom.is_empty()
+        };
+    }
+
+    // Copies a range of the input into a String of its own, so the text no longer keeps the input alive
+    // The tokenizer does this to the tokens it is building before more input is appended, so the input is not copied instead because it is shared
+    pub(crate) fn detach(&mut self) {
+        self.to_mut();
+    }
+
+    fn to_mut(&mut self) -> &mut String {
+        if !matches!(self.repr, Repr::Owned(_)) {
+            self.repr = Repr::Owned(self.as_str().to_string());
+        }
+        match &mut self.repr {
+            Repr::Owned(text) => return text,
+            Repr::Span { .. } | Repr::Atom(_) => unreachable!()
+        }
+    }
+}
+
+impl Default for TokenText {
+    fn default() -> Self {
+        return TokenText::new();
+    }
+}
+
+impl Deref for TokenText {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        return self.as_str();
+    }
+}
+
+impl From<String> for TokenText {
+    fn from(text: String) -> Self {
+        return TokenText { repr: Repr::Owned(text) };
+    }
+}
+
+impl From<&str> for TokenText {
+    fn from(text: &str) -> Self {
+        return TokenText { repr: Repr::Owned(text.to_string()) };
+    }
+}
+
+impl PartialEq for TokenText {
+    fn eq(&self, other: &TokenText) -> bool {
+        return self.as_str() == other.as_str();
+    }
+}
+
+impl Eq for TokenText {}
+
+impl PartialEq<str> for TokenText {
+    fn eq(&self, other: &str) -> bool {
+        return self.as_str() == other;
+    }
+}
+
+impl PartialEq<&str> for TokenText {
+    fn eq(&self, other: &&str) -> bool {
+        return self.as_str() == *other;
+    }
+}
+
+impl PartialEq<String> for TokenText {
+    fn eq(&self, other: &String) -> bool {
+        return self.as_str() == other.as_str();
+    }
+}
+
+impl PartialOrd for TokenText {
+    fn partial_cmp(&self, other: &TokenText) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Ord for TokenText {
+    fn cmp(&self, other: &TokenText) -> Ordering {
+        return self.as_str().cmp(other.as_str());
+    }
+}
+
+impl Hash for TokenText {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl fmt::Display for TokenText {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return f.write_str(self.as_str());
+    }
+}
+
+impl fmt::Debug for TokenText {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return fmt::Debug::fmt(self.as_str(), f);
+    }
+}
@@ -0,0 +1,135 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use crate::source_location::SourcePosition;
+
+// A record of what the parser did, for debugging where it parts from the spec
+// With the trace feature the tokenizer records each switch of its state and each token it emits, and the tree builder each token it processes and each switch of its insertion mode,
+// each at the position of the input it happened at, e.g.
+//     let trace = tokenizer.enable_trace();
+//     tokenizer.run();
+//     print!("{}", trace.borrow().timeline());
+// The trace can also be written as Chrome trace event JSON, which chrome://tracing and https://ui.perfetto.dev/ open, with a track for the tokenizer states,
+// one for the insertion modes and one for the tokens
+// Recording is left out of the build without the feature, so parsing does not pay for it
+
+// Written into a parse session as {"kind": "tokenizer-state", "from": ..., "to": ...}, see replay.rs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum TraceEvent {
+    // https://html.spec.whatwg.org/multipage/parsing.html#tokenization
+    TokenizerState { from: String, to: String },
+    // A token given to the tree construction stage, or to another TokenSink, described as the tokens command prints it
+    Token { description: String },
+    // https://html.spec.whatwg.org/multipage/parsing.html#insertion-mode
+    InsertionMode { from: String, to: String },
+    // A token the tree builder processes, with the insertion mode it is processed in and the local names of the stack of open elements, the first one pushed first
+    // A token that is reprocessed is recorded again for each insertion mode it goes through
+    TreeBuilderStep { insertion_mode: String, token: String, open_elements: Vec<String> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceRecord {
+    pub event: TraceEvent,
+    // Where in the input it happened, None at the end of the input or before any input was consumed
+    pub position: Option<SourcePosition>,
+    // How long after the trace was started
+    pub time: Duration,
+}
+
+pub struct Trace {
+    start: Instant,
+    pub records: Vec<TraceRecord>,
+}
+
+// The tokenizer and the tree builder record into the same trace
+pub type SharedTrace = Rc<RefCell<Trace>>;
+
+impl Default for Trace {
+    fn default() -> Self {
+        return Trace { start: Instant::now(), records: Vec::new() };
+    }
+}
+
+impl Trace {
+    pub fn new_shared() -> SharedTrace {
+        return Rc::new(RefCell::new(Trace::default()));
+    }
+
+    pub fn record(&mut self, event: TraceEvent, position: Option<SourcePosition>) {
+        self.records.push(TraceRecord { event, position, time: self.start.elapsed() });
+    }
+
+    // A line for each record, with its position in the input and what happened, e.g.
+    //     1:6     insertion mode   BeforeHtml -> BeforeHead
+    pub fn timeline(&self) -> String {
+        let mut timeline = String::new();
+        for record in &self.records {
+            let position = record.position.map(|position| position.to_string()).unwrap_or_else(|| "-".to_string());
+            let line = match &record.event {
+                TraceEvent::TokenizerState { from, to } => format!("{:<8}tokenizer        {} -> {}\n", position, from, to),
+                TraceEvent::Token { description } => format!("{:<8}token            {}\n", position, description),
+                TraceEvent::InsertionMode { from, to } => format!("{:<8}insertion mode   {} -> {}\n", position, from, to),
+                TraceEvent::TreeBuilderStep { insertion_mode, token, open_elements } => format!("{:<8}tree builder     {}: {} [{}]\n", position, insertion_mode, token, open_elements.join(" "))
+            };
+            timeline.push_str(&line);
+        }
+        return timeline;
+    }
+
+    // The lines of the timeline for what the tree builder did, each token in the insertion mode it was processed in with the stack of open elements, and each switch of insertion mode, e.g.
+    //     1:1     tree builder     Initial: StartTag p []
+    //     1:1     insertion mode   Initial -> BeforeHtml
+    pub fn tree_builder_timeline(&self) -> String {
+        let records = self.records.iter().filter(|record| matches!(record.event, TraceEvent::InsertionMode { .. } | TraceEvent::TreeBuilderStep { .. })).cloned().collect();
+        return Trace { start: self.start, records }.timeline();
+    }
+
+    // https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU/
+    // Each tokenizer state and insertion mode is a complete ("X") event that lasts until the next one starts, and each token is an instant ("i") event
+    // The times are in microseconds, and the position of each event is in its args
+    pub fn chrome_trace_json(&self) -> String {
+        const TOKENIZER_THREAD: u32 = 1;
+        const TREE_BUILDER_THREAD: u32 = 2;
+        const TOKENS_THREAD: u32 = 3;
+
+        let microseconds = |time: Duration| time.as_secs_f64() * 1_000_000.0;
+        let args = |record: &TraceRecord| match record.position {
+            Some(position) => json!({ "line": position.line, "column": position.column, "offset": position.offset }),
+            None => json!({})
+        };
+        let end = self.records.last().map(|record| record.time).unwrap_or_default();
+        let mut events = vec![
+            json!({ "name": "thread_name", "ph": "M", "pid": 1, "tid": TOKENIZER_THREAD, "args": { "name": "Tokenizer state" } }),
+            json!({ "name": "thread_name", "ph": "M", "pid": 1, "tid": TREE_BUILDER_THREAD, "args": { "name": "Insertion mode" } }),
+            json!({ "name": "thread_name", "ph": "M", "pid": 1, "tid": TOKENS_THREAD, "args": { "name": "Tokens" } }),
+        ];
+        for (index, record) in self.records.iter().enumerate() {
+            // A state lasts until the next record of the same kind, or the end of the trace
+            let next_of_kind = |is_kind: fn(&TraceEvent) -> bool| self.records[index + 1..].iter().find(|next| is_kind(&next.event)).map_or(end, |next| next.time);
+            let event = match &record.event {
+                TraceEvent::TokenizerState { to, .. } => {
+                    let duration = next_of_kind(|event| matches!(event, TraceEvent::TokenizerState { .. })) - record.time;
+                    json!({ "name": to, "cat": "tokenizer", "ph": "X", "ts": microseconds(record.time), "dur": microseconds(duration), "pid": 1, "tid": TOKENIZER_THREAD, "args": args(record) })
+                },
+                TraceEvent::InsertionMode { to, .. } => {
+                    let duration = next_of_kind(|event| matches!(event, TraceEvent::InsertionMode { .. })) - record.time;
+                    json!({ "name": to, "cat": "tree-builder", "ph": "X", "ts": microseconds(record.time), "dur": microseconds(duration), "pid": 1, "tid": TREE_BUILDER_THREAD, "args": args(record) })
+                },
+                TraceEvent::Token { description } => {
+                    json!({ "name": description, "cat": "token", "ph": "i", "s": "t", "ts": microseconds(record.time), "pid": 1, "tid": TOKENS_THREAD, "args": args(record) })
+                },
+                TraceEvent::TreeBuilderStep { insertion_mode, token, open_elements } => {
+                    let mut args = args(record);
+                    args["insertionMode"] = json!(insertion_mode);
+                    args["openElements"] = json!(open_elements);
+                    json!({ "name": token, "cat": "tree-builder", "ph": "i", "s": "t", "ts": microseconds(record.time), "pid": 1, "tid": TREE_BUILDER_THREAD, "args": args })
+                }
+            };
+            events.push(event);
+        }
+        return serde_json::to_string_pretty(&json!({ "traceEvents": events, "displayTimeUnit": "ns" })).unwrap_or_default();
+    }
+}
@@ -0,0 +1,561 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Instant;
+use indexmap::IndexMap;
+use crate::css_parser::{CSSToken, ComponentValue};
+use crate::css_values::{single_component, Color, FontSize, Length, PropertyValue};
+use crate::cssom::{document_css_style_sheets, inline_style, CSSStyleSheet};
+use crate::node::{descendants, NodeData, RefNode};
+use crate::style::{invalidate_style, unanimated_specified_values};
+
+// https://drafts.csswg.org/css-transitions-1/
+// A transition animates a property from its old value to its new one when its value changes, over the duration transition-duration gives it
+// Animations are run in frames (see run_animation_frame), which the event loop runs after each task. Each frame is a style change event,
+// which starts the transitions of the values that changed since the last one and samples the running ones at the time of the frame
+// The values a frame samples win over the cascade (see apply_transitions) until the next frame, and their elements are invalidated so they are styled again
+// TODO: No transition events are fired, there are no CSS animations or Web Animations API, and transition-behavior is not supported so discrete values never transition
+
+// The time in between frames in milliseconds, frames are run as if the display was refreshed at 60 Hz
+const FRAME_INTERVAL: f64 = 1000.0 / 60.0;
+
+// https://drafts.csswg.org/css-transitions-1/#animatable-css
+// The properties a transition can animate, as long as both the old and new value are of a type that can be interpolated (see interpolate)
+const TRANSITIONABLE_PROPERTIES: &[&str] = &[
+    "opacity", "color", "background-color", "border-top-color", "border-right-color", "border-bottom-color", "border-left-color", "outline-color", "text-decoration-color",
+    "margin-top", "margin-right", "margin-bottom", "margin-left", "padding-top", "padding-right", "padding-bottom", "padding-left",
+    "border-top-width", "border-right-width", "border-bottom-width", "border-left-width",
+    "width", "height", "min-width", "min-height", "top", "right", "bottom", "left", "text-indent", "font-size",
+];
+
+// https://drafts.csswg.org/web-animations-1/#document-timelines
+// The clock of a document's animations, in milliseconds since the document was created
+pub(crate) struct DocumentTimeline {
+    origin: Instant,
+    // How far the timeline was moved ahead of the real time, see advance_animations
+    skipped: f64,
+    // https://drafts.csswg.org/web-animations-1/#timeline-current-time
+    // The time of the last frame, which is a whole number of frames
+    current_time: f64,
+}
+
+impl DocumentTimeline {
+    pub(crate) fn new() -> DocumentTimeline {
+        return DocumentTimeline { origin: Instant::now(), skipped: 0.0, current_time: 0.0 };
+    }
+
+    // Moves the timeline on to the time of the latest frame
+    fn tick(&mut self) -> f64 {
+        let elapsed = self.origin.elapsed().as_secs_f64() * 1000.0 + self.skipped;
+        self.current_time = self.current_time.max((elapsed / FRAME_INTERVAL).floor() * FRAME_INTERVAL);
+        return self.current_time;
+    }
+}
+
+// https://drafts.csswg.org/css-easing-1/#step-position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepPosition {
+    JumpStart,
+    JumpEnd,
+    JumpNone,
+    JumpBoth,
+}
+
+// https://drafts.csswg.org/css-easing-1/#typedef-easing-function
+// TODO: The linear() function is not supported
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EasingFunction {
+    Linear,
+    // https://drafts.csswg.org/css-easing-1/#cubic-bezier-easing-functions
+    // The x and y of the two control points in between (0, 0) and (1, 1)
+    CubicBezier(f64, f64, f64, f64),
+    // https://drafts.csswg.org/css-easing-1/#step-easing-functions
+    Steps(u32, StepPosition),
+}
+
+impl EasingFunction {
+    pub fn parse(value: &str) -> Option<EasingFunction> {
+        match single_component(value)? {
+            ComponentValue::Token(CSSToken::Ident(ident)) => {
+                match ident.to_ascii_lowercase().as_str() {
+                    "linear" => return Some(EasingFunction::Linear),
+                    "ease" => return Some(EasingFunction::CubicBezier(0.25, 0.1, 0.25, 1.0)),
+                    "ease-in" => return Some(EasingFunction::CubicBezier(0.42, 0.0, 1.0, 1.0)),
+                    "ease-out" => return Some(EasingFunction::CubicBezier(0.0, 0.0, 0.58, 1.0)),
+                    "ease-in-out" => return Some(EasingFunction::CubicBezier(0.42, 0.0, 0.58, 1.0)),
+                    "step-start" => return Some(EasingFunction::Steps(1, StepPosition::JumpStart)),
+                    "step-end" => return Some(EasingFunction::Steps(1, StepPosition::JumpEnd)),
+                    _ => return None
+                }
+            },
+            ComponentValue::Function(function) => {
+                let arguments = function_arguments(&function.value)?;
+                match function.name.to_ascii_lowercase().as_str() {
+                    // cubic-bezier(<number [0,1]>, <number>, <number [0,1]>, <number>)
+                    "cubic-bezier" => {
+                        let numbers: Vec<f64> = arguments.iter().map(|argument| match argument.as_slice() {
+                            [ComponentValue::Token(CSSToken::Number(number))] => Some(number.value),
+                            _ => None
+                        }).collect::<Option<Vec<f64>>>()?;
+                        match numbers.as_slice() {
+                            [x1, y1, x2, y2] if (0.0..=1.0).contains(x1) && (0.0..=1.0).contains(x2) => return Some(EasingFunction::CubicBezier(*x1, *y1, *x2, *y2)),
+                            _ => return None
+                        }
+                    },
+                    // steps(<integer>, <step-position>?), the number of steps is at least 1, or 2 for jump-none
+                    "steps" => {
+                        let steps = match arguments.first()?.as_slice() {
+                            [ComponentValue::Token(CSSToken::Number(number))] if number.is_integer && number.value >= 1.0 => number.value.min(u32::MAX as f64) as u32,
+                            _ => return None
+                        };
+                        let position = match arguments.get(1).map(|argument| argument.as_slice()) {
+                            None => StepPosition::JumpEnd,
+                            Some([ComponentValue::Token(CSSToken::Ident(ident))]) => {
+                                match ident.to_ascii_lowercase().as_str() {
+                                    "jump-start" | "start" => StepPosition::JumpStart,
+                                    "jump-end" | "end" => StepPosition::JumpEnd,
+                                    "jump-none" => StepPosition::JumpNone,
+                                    "jump-both" => StepPosition::JumpBoth,
+                                    _ => return None
+                                }
+                            },
+                            Some(_) => return None
+                        };
+                        if arguments.len() > 2 || (position == StepPosition::JumpNone && steps < 2) {
+                            return None;
+                        }
+                        return Some(EasingFunction::Steps(steps, position));
+                    },
+                    _ => return None
+                }
+            },
+            _ => return None
+        }
+    }
+
+    // The output progress for an input progress, before_flag is set while the transition is waiting for its delay to pass
+    pub fn apply(&self, input_progress: f64, before_flag: bool) -> f64 {
+        match *self {
+            EasingFunction::Linear => return input_progress,
+            EasingFunction::CubicBezier(x1, y1, x2, y2) => return cubic_bezier(x1, y1, x2, y2, input_progress),
+            // https://drafts.csswg.org/css-easing-1/#step-easing-algo
+            EasingFunction::Steps(steps, position) => {
+                // 1. Calculate the current step as floor(input progress value × steps).
+                let mut current_step = (input_progress * steps as f64).floor();
+                // 2. If the step position property is one of jump-start or jump-both, increment current step by one.
+                if matches!(position, StepPosition::JumpStart | StepPosition::JumpBoth) {
+                    current_step += 1.0;
+                }
+                // 3. If both of the following conditions are true: the before flag is set, and input progress value × steps mod 1 equals zero, decrement current step by one.
+                if before_flag && (input_progress * steps as f64) % 1.0 == 0.0 {
+                    current_step -= 1.0;
+                }
+                // 4. If input progress value ≥ 0 and current step < 0, let current step be zero.
+                if input_progress >= 0.0 && current_step < 0.0 {
+                    current_step = 0.0;
+                }
+                // 5. Calculate jumps based on the step position.
+                let jumps = match position {
+                    StepPosition::JumpStart | StepPosition::JumpEnd => steps as f64,
+                    StepPosition::JumpNone => steps as f64 - 1.0,
+                    StepPosition::JumpBoth => steps as f64 + 1.0
+                };
+                // 6. If input progress value ≤ 1 and current step > jumps, let current step be jumps.
+                if input_progress <= 1.0 && current_step > jumps {
+                    current_step = jumps;
+                }
+                // 7. The output progress value is current step / jumps.
+                return current_step / jumps;
+            }
+        }
+    }
+}
+
+// The arguments of a function separated by its commas, each without the whitespace around it
+pub(crate) fn function_arguments(value: &[ComponentValue]) -> Option<Vec<Vec<ComponentValue>>> {
+    let mut arguments = vec![Vec::new()];
+    for component in value {
+        match component {
+            ComponentValue::Token(CSSToken::Whitespace) => continue,
+            ComponentValue::Token(CSSToken::Comma) => arguments.push(Vec::new()),
+            component => arguments.last_mut().unwrap().push(component.clone())
+        }
+    }
+    if arguments.iter().any(|argument| argument.is_empty()) {
+        return None;
+    }
+    return Some(arguments);
+}
+
+// https://drafts.csswg.org/css-easing-1/#cubic-bezier-algo
+// The y of the curve at the x of input_progress, found by Newton's method and bisection when it does not converge
+// Outside of 0 and 1 the curve is extended by the tangent at its end
+fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, input_progress: f64) -> f64 {
+    let bezier = |p1: f64, p2: f64, t: f64| 3.0 * (1.0 - t) * (1.0 - t) * t * p1 + 3.0 * (1.0 - t) * t * t * p2 + t * t * t;
+    let derivative = |p1: f64, p2: f64, t: f64| 3.0 * (1.0 - t) * (1.0 - t) * p1 + 6.0 * (1.0 - t) * t * (p2 - p1) + 3.0 * t * t * (1.0 - p2);
+
+    if input_progress < 0.0 {
+        let gradient = if x1 > 0.0 { y1 / x1 } else if y1 == 0.0 && x2 > 0.0 { y2 / x2 } else { 0.0 };
+        return gradient * input_progress;
+    }
+    if input_progress > 1.0 {
+        let gradient = if x2 < 1.0 { (y2 - 1.0) / (x2 - 1.0) } else if y2 == 1.0 && x1 < 1.0 { (y1 - 1.0) / (x1 - 1.0) } else { 0.0 };
+        return 1.0 + gradient * (input_progress - 1.0);
+    }
+
+    let mut t = input_progress;
+    for _ in 0..8 {
+        let error = bezier(x1, x2, t) - input_progress;
+        if error.abs() < 1e-7 {
+            return bezier(y1, y2, t);
+        }
+        let slope = derivative(x1, x2, t);
+        if slope.abs() < 1e-6 {
+            break;
+        }
+        t -= error / slope;
+    }
+
+    let (mut low, mut high) = (0.0, 1.0);
+    t = input_progress;
+    while high - low > 1e-7 {
+        if bezier(x1, x2, t) < input_progress {
+            low = t;
+        } else {
+            high = t;
+        }
+        t = (low + high) / 2.0;
+    }
+    return bezier(y1, y2, t);
+}
+
+// https://drafts.csswg.org/css-values-4/#interpolation
+// The value progress of the way from from to to, None when the values can not be interpolated and so the property does not transition
+// A progress outside of 0 and 1, which some easing functions output, extrapolates
+pub fn interpolate(from: &PropertyValue, to: &PropertyValue, progress: f64) -> Option<PropertyValue> {
+    match (from, to) {
+        (PropertyValue::Number(from), PropertyValue::Number(to)) => return Some(PropertyValue::Number(lerp(*from, *to, progress))),
+        (PropertyValue::Length(from), PropertyValue::Length(to)) => return interpolate_length(from, to, progress).map(PropertyValue::Length),
+        (PropertyValue::FontSize(FontSize::Length(from)), PropertyValue::FontSize(FontSize::Length(to))) => {
+            return interpolate_length(from, to, progress).map(|length| PropertyValue::FontSize(FontSize::Length(length)));
+        },
+        (PropertyValue::Color(from), PropertyValue::Color(to)) => return interpolate_color(from, to, progress).map(PropertyValue::Color),
+        _ => return None
+    }
+}
+
+fn lerp(from: f32, to: f32, progress: f64) -> f32 {
+    return (from as f64 + (to as f64 - from as f64) * progress) as f32;
+}
+
+// https://drafts.csswg.org/css-values-4/#combine-dimensions
+// TODO: Lengths in different units would interpolate through calc(), which is not supported, so only lengths in the same unit interpolate
+fn interpolate_length(from: &Length, to: &Length, progress: f64) -> Option<Length> {
+    match (from, to) {
+        (Length::Px(from), Length::Px(to)) => return Some(Length::Px(lerp(*from, *to, progress))),
+        (Length::Em(from), Length::Em(to)) => return Some(Length::Em(lerp(*from, *to, progress))),
+        (Length::Percentage(from), Length::Percentage(to)) => return Some(Length::Percentage(lerp(*from, *to, progress))),
+        // 0 is a length in every unit
+        (Length::Px(from), to) if *from == 0.0 => return interpolate_length(&zero_length(to)?, to, progress),
+        (from, Length::Px(to)) if *to == 0.0 => return interpolate_length(from, &zero_length(from)?, progress),
+        _ => return None
+    }
+}
+
+fn zero_length(length: &Length) -> Option<Length> {
+    match length {
+        Length::Px(_) => Some(Length::Px(0.0)),
+        Length::Em(_) => Some(Length::Em(0.0)),
+        Length::Percentage(_) => Some(Length::Percentage(0.0)),
+        Length::Auto => None
+    }
+}
+
+// https://drafts.csswg.org/css-color-4/#interpolation
+// https://drafts.csswg.org/css-color-4/#interpolation-alpha
+// Legacy sRGB colors interpolate in sRGB, with the channels premultiplied by the alpha so a transparent color has no hue of its own
+fn interpolate_color(from: &Color, to: &Color, progress: f64) -> Option<Color> {
+    let (Color::Rgba { red: from_red, green: from_green, blue: from_blue, alpha: from_alpha }, Color::Rgba { red: to_red, green: to_green, blue: to_blue, alpha: to_alpha }) = (from, to) else {
+        // TODO: currentcolor would interpolate as the color it resolves to, which is not known until painting
+        return None;
+    };
+    let alpha = lerp(*from_alpha, *to_alpha, progress).clamp(0.0, 1.0);
+    let channel = |from: u8, to: u8| -> u8 {
+        if alpha == 0.0 {
+            return 0;
+        }
+        let premultiplied = lerp(from as f32 * from_alpha, to as f32 * to_alpha, progress);
+        return (premultiplied / alpha).round().clamp(0.0, 255.0) as u8;
+    };
+    return Some(Color::Rgba { red: channel(*from_red, *to_red), green: channel(*from_green, *to_green), blue: channel(*from_blue, *to_blue), alpha });
+}
+
+// https://drafts.csswg.org/css-transitions-1/#transition-property-property
+// The duration, delay and timing function a property transitions with
+struct MatchingTransition {
+    duration: f64,
+    delay: f64,
+    timing_function: EasingFunction,
+}
+
+impl MatchingTransition {
+    // https://drafts.csswg.org/css-transitions-1/#transition-combined-duration
+    fn combined_duration(&self) -> f64 {
+        return self.duration.max(0.0) + self.delay;
+    }
+}
+
+// The entry of the transition lists of an element that matches property, the last one when several do
+// The duration, delay and timing function lists are repeated to as many entries as transition-property has, as if by the list matching of css-values-4
+fn matching_transition(values: &IndexMap<String, PropertyValue>, property: &str) -> Option<MatchingTransition> {
+    let properties = match values.get("transition-property") {
+        Some(PropertyValue::TransitionProperties(properties)) => properties.clone(),
+        _ => vec![String::from("all")]
+    };
+    let times = |property: &str| -> Vec<f32> {
+        match values.get(property) {
+            Some(PropertyValue::Times(times)) if !times.is_empty() => times.clone(),
+            _ => vec![0.0]
+        }
+    };
+    let durations = times("transition-duration");
+    let delays = times("transition-delay");
+    let timing_functions = match values.get("transition-timing-function") {
+        Some(PropertyValue::EasingFunctions(timing_functions)) if !timing_functions.is_empty() => timing_functions.clone(),
+        _ => vec![EasingFunction::CubicBezier(0.25, 0.1, 0.25, 1.0)]
+    };
+
+    let index = properties.iter().rposition(|name| name == "all" || name == property)?;
+    return Some(MatchingTransition {
+        duration: durations[index % durations.len()] as f64,
+        delay: delays[index % delays.len()] as f64,
+        timing_function: timing_functions[index % timing_functions.len()],
+    });
+}
+
+// https://drafts.csswg.org/css-transitions-1/#running-transition
+struct RunningTransition {
+    property: String,
+    start_value: PropertyValue,
+    end_value: PropertyValue,
+    // https://drafts.csswg.org/css-transitions-1/#transition-reversing-adjusted-start-value
+    reversing_adjusted_start_value: PropertyValue,
+    // https://drafts.csswg.org/css-transitions-1/#transition-reversing-shortening-factor
+    reversing_shortening_factor: f64,
+    // The times are in the time of the document timeline, the start time is after the delay
+    start_time: f64,
+    end_time: f64,
+    timing_function: EasingFunction,
+    // The value as of the last frame
+    current_value: PropertyValue,
+}
+
+impl RunningTransition {
+    fn new(property: &str, start_value: PropertyValue, end_value: PropertyValue, matching_transition: &MatchingTransition, current_time: f64) -> RunningTransition {
+        let start_time = current_time + matching_transition.delay;
+        return RunningTransition {
+            property: property.to_string(),
+            reversing_adjusted_start_value: start_value.clone(),
+            current_value: start_value.clone(),
+            start_value,
+            end_value,
+            reversing_shortening_factor: 1.0,
+            start_time,
+            end_time: start_time + matching_transition.duration.max(0.0),
+            timing_function: matching_transition.timing_function,
+        };
+    }
+
+    // https://drafts.csswg.org/web-animations-1/#calculating-the-transformed-progress
+    // A transition fills backwards, so it is at its start value while it waits for its delay to pass
+    fn output_progress(&self, time: f64) -> f64 {
+        if time < self.start_time {
+            return self.timing_function.apply(0.0, true);
+        }
+        if self.end_time <= self.start_time {
+            return self.timing_function.apply(1.0, false);
+        }
+        let input_progress = ((time - self.start_time) / (self.end_time - self.start_time)).min(1.0);
+        return self.timing_function.apply(input_progress, false);
+    }
+
+    fn sample(&mut self, time: f64) {
+        let output_progress = self.output_progress(time);
+        if let Some(value) = interpolate(&self.start_value, &self.end_value, output_progress) {
+            self.current_value = value;
+        }
+    }
+}
+
+// The transitions of an element, kept by the element from one style change event to the next
+pub(crate) struct ElementTransitions {
+    // https://drafts.csswg.org/css-transitions-1/#after-change-style
+    // The values of the transitionable properties as of the last style change event, without the transitions
+    after_change_style: IndexMap<String, PropertyValue>,
+    running: Vec<RunningTransition>,
+}
+
+// https://drafts.csswg.org/css-transitions-1/#starting
+// Runs a frame of document's animations at the time of its latest frame, see advance_animations to run one later than that
+pub(crate) fn run_animation_frame(document: &RefNode) {
+    let current_time = match &mut document.borrow_mut().data {
+        NodeData::Document(document) => document.timeline.tick(),
+        _ => return
+    };
+
+    let style_sheets = document_css_style_sheets(document);
+    let elements: Vec<RefNode> = descendants(document).into_iter().filter(|node| matches!(node.borrow().data, NodeData::Element(_))).collect();
+    // A document that never declares a transition has nothing to animate, so the style of its elements is not computed each frame
+    let declares_transitions = declares_transitions(&style_sheets, &elements);
+    for element in &elements {
+        if declares_transitions {
+            update_transitions(element, &style_sheets, current_time);
+        } else if element.borrow_mut().transitions.take().is_some_and(|transitions| !transitions.running.is_empty()) {
+            invalidate_style(element);
+        }
+    }
+}
+
+// Moves the timeline of document milliseconds ahead and runs a frame, e.g. to render a page as it is once its transitions have run for a while
+pub fn advance_animations(document: &RefNode, milliseconds: f64) {
+    if let NodeData::Document(document) = &mut document.borrow_mut().data {
+        document.timeline.skipped += milliseconds;
+    }
+    run_animation_frame(document);
+}
+
+fn declares_transitions(style_sheets: &[Rc<RefCell<CSSStyleSheet>>], elements: &[RefNode]) -> bool {
+    let is_transition = |property_name: &str| property_name.starts_with("transition");
+    let in_style_sheets = style_sheets.iter().any(|style_sheet| {
+        return style_sheet.borrow().css_rules.iter().any(|rule| rule.borrow().style.borrow().declarations.iter().any(|declaration| is_transition(&declaration.property_name)));
+    });
+    return in_style_sheets || elements.iter().any(|element| {
+        return inline_style(element).is_some_and(|style| style.borrow().declarations.iter().any(|declaration| is_transition(&declaration.property_name)));
+    });
+}
+
+// The after-change style of an element, the values of its transitionable properties
+// TODO: A property that is left out of the cascade has no value, so it does not transition from or to its initial or user agent value, other than opacity and background-color
+fn after_change_style(values: &IndexMap<String, PropertyValue>) -> IndexMap<String, PropertyValue> {
+    let mut after_change_style = IndexMap::new();
+    for property in TRANSITIONABLE_PROPERTIES {
+        let value = match (values.get(*property), *property) {
+            (Some(value), _) => value.clone(),
+            (None, "opacity") => PropertyValue::Number(1.0),
+            (None, "background-color") => PropertyValue::Color(Color::Rgba { red: 0, green: 0, blue: 0, alpha: 0.0 }),
+            (None, _) => continue
+        };
+        after_change_style.insert(property.to_string(), value);
+    }
+    return after_change_style;
+}
+
+// https://drafts.csswg.org/css-transitions-1/#starting
+// Starts, reverses and cancels the transitions of element for the values that changed since the last style change event, and samples them at current_time
+fn update_transitions(element: &RefNode, style_sheets: &[Rc<RefCell<CSSStyleSheet>>], current_time: f64) {
+    let values = unanimated_specified_values(element, style_sheets);
+    let after_change_style = after_change_style(&values);
+    let previous_transitions = element.borrow_mut().transitions.take();
+    let mut transitions = match previous_transitions {
+        Some(transitions) => transitions,
+        // An element that is styled for the first time has no before-change style, so nothing transitions
+        None => {
+            element.borrow_mut().transitions = Some(ElementTransitions { after_change_style, running: Vec::new() });
+            return;
+        }
+    };
+
+    // The running transitions are at their values as of the current time, the ones that have run their course are completed and removed
+    let was_running = !transitions.running.is_empty();
+    for running in &mut transitions.running {
+        running.sample(current_time);
+    }
+    transitions.running.retain(|running| current_time < running.end_time);
+
+    for property in TRANSITIONABLE_PROPERTIES {
+        let after_change_value = after_change_style.get(*property);
+        let matching_transition = matching_transition(&values, property);
+        let running_index = transitions.running.iter().position(|running| running.property == *property);
+
+        // https://drafts.csswg.org/css-transitions-1/#before-change-style
+        // The values of the last style change event, with the running transitions at their current value
+        let before_change_value = match running_index {
+            Some(index) => Some(transitions.running[index].current_value.clone()),
+            None => transitions.after_change_style.get(*property).cloned()
+        };
+        let can_transition = |start_value: &PropertyValue, end_value: &PropertyValue| -> bool {
+            return matching_transition.as_ref().is_some_and(|matching_transition| matching_transition.combined_duration() > 0.0)
+                && start_value != end_value
+                && interpolate(start_value, end_value, 0.5).is_some();
+        };
+
+        let running = match running_index {
+            Some(index) => {
+                // 4. If the element has a running transition for the property, there is a matching transition-property value, and the end value of the running transition is equal to the value of the property in the after-change style, then the running transition continues
+                if matching_transition.is_some() && Some(&transitions.running[index].end_value) == after_change_value {
+                    continue;
+                }
+                // A running transition whose property no longer matches transition-property, or whose end value changed, is cancelled
+                transitions.running.remove(index)
+            },
+            None => {
+                // 1. If the element does not have a running transition for the property, the before-change style is different from the after-change style for that property,
+                //    the values are transitionable and the combined duration is greater than 0s, then implementations must start a transition
+                if let (Some(before_change_value), Some(after_change_value), Some(matching_transition)) = (&before_change_value, after_change_value, &matching_transition) {
+                    if can_transition(before_change_value, after_change_value) {
+                        let mut transition = RunningTransition::new(property, before_change_value.clone(), after_change_value.clone(), matching_transition, current_time);
+                        transition.sample(current_time);
+                        transitions.running.push(transition);
+                    }
+                }
+                continue;
+            }
+        };
+
+        // 3. If the element has a running transition for the property, there is a matching transition-property value, and the end value of the running transition is not equal to the value of the property in the after-change style, then:
+        let (Some(after_change_value), Some(matching_transition)) = (after_change_value, &matching_transition) else {
+            continue;
+        };
+        // 1. If the current value of the property in the running transition is equal to the value of the property in the after-change style, or if these two values are not transitionable, then implementations must cancel the running transition.
+        if !can_transition(&running.current_value, after_change_value) {
+            continue;
+        }
+        let mut transition = RunningTransition::new(property, running.current_value.clone(), after_change_value.clone(), matching_transition, current_time);
+        // 3. Otherwise, if the reversing-adjusted start value of the running transition is the same as the value of the property in the after-change style, implementations must cancel the running transition and start a new transition whose:
+        if running.reversing_adjusted_start_value == *after_change_value {
+            // reversing shortening factor is the absolute value, clamped to the range [0, 1], of the sum of:
+            //     1. the output of the timing function of the old transition at the time of the style change event, times the reversing shortening factor of the old transition
+            //     2. 1 minus the reversing shortening factor of the old transition.
+            let factor = (running.output_progress(current_time) * running.reversing_shortening_factor + 1.0 - running.reversing_shortening_factor).abs().clamp(0.0, 1.0);
+            // start time is the time of the style change event plus: if the matching transition delay is nonnegative, the matching transition delay, or if the matching transition delay is negative, the product of the new transition's reversing shortening factor and the matching transition delay,
+            let delay = if matching_transition.delay < 0.0 { matching_transition.delay * factor } else { matching_transition.delay };
+            transition.start_time = current_time + delay;
+            // end time is the start time plus the product of the matching transition duration and the new transition's reversing shortening factor,
+            transition.end_time = transition.start_time + matching_transition.duration.max(0.0) * factor;
+            // reversing-adjusted start value is the end value of the running transition,
+            transition.reversing_adjusted_start_value = running.end_value.clone();
+            transition.reversing_shortening_factor = factor;
+        }
+        // 4. Otherwise, implementations must cancel the running transition and start a new transition whose start value is the current value of the property in the running transition
+        transition.sample(current_time);
+        transitions.running.push(transition);
+    }
+
+    let is_running = !transitions.running.is_empty();
+    let changed = transitions.after_change_style != after_change_style;
+    transitions.after_change_style = after_change_style;
+    element.borrow_mut().transitions = Some(transitions);
+    // The element is styled again with the values of this frame
+    if was_running || is_running || changed {
+        invalidate_style(element);
+    }
+}
+
+// https://drafts.csswg.org/css-cascade-4/#cascade-origin-animation
+// The values of the running transitions of element as of the last frame, which win over every other declaration
+pub(crate) fn apply_transitions(element: &RefNode, values: &mut IndexMap<String, PropertyValue>) {
+    if let Some(transitions) = &element.borrow().transitions {
+        for running in &transitions.running {
+            values.insert(running.property.clone(), running.current_value.clone());
+        }
+    }
+}
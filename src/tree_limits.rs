@@ -0,0 +1,96 @@
+use std::fmt;
+use crate::html_token::{HtmlToken, HtmlTokenType};
+use crate::source_location::SourcePosition;
+
+// Limits on the tree the parser builds, so a server can parse untrusted input without a parser bomb (e.g. a hundred thousand nested divs) exhausting its memory or the stack of the recursive tree walks
+// Once the input goes over a limit the tree builder stops, the tokens after the one that went over are ignored and the document is left as it was built up to there
+// Tree construction has no way to fail, so the error is kept by the parser, see Tokenizer::tree_limit_error
+// The depth is limited by default, as styling, layout, rendering and the JSON and accessibility trees walk the document recursively and would overflow the call stack on a deep enough one
+// TODO: Walk the tree with a stack everywhere, so the default depth can be lifted
+
+// 512 is the depth Blink stops nesting elements at, and deep enough for any document that is not made to be deep
+pub const DEFAULT_MAX_DEPTH: usize = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeLimits {
+    // The number of ancestors an element may have, the children of the document are at depth 1
+    // DEFAULT_MAX_DEPTH by default, None is no limit, which is only safe when the document is not going to be styled, laid out or rendered
+    pub max_depth: Option<usize>,
+    // The number of nodes the parser may create, counted after each token so the token that goes over the limit still adds its nodes
+    pub max_nodes: Option<usize>,
+    // The number of attributes an element may have
+    pub max_attributes: Option<usize>,
+    // The bytes an attribute value may have
+    pub max_attribute_length: Option<usize>,
+}
+
+impl Default for TreeLimits {
+    fn default() -> Self {
+        return TreeLimits { max_depth: Some(DEFAULT_MAX_DEPTH), max_nodes: None, max_attributes: None, max_attribute_length: None };
+    }
+}
+
+impl TreeLimits {
+    // The limits the serve command parses untrusted input with
+    pub fn for_untrusted_input() -> TreeLimits {
+        return TreeLimits { max_depth: Some(DEFAULT_MAX_DEPTH), max_nodes: Some(1_000_000), max_attributes: Some(1024), max_attribute_length: Some(1024 * 1024) };
+    }
+
+    // The error for the attributes of a start tag token, which are checked before its element is created
+    pub(crate) fn check_attributes(&self, html_token: &HtmlToken, position: Option<SourcePosition>) -> Option<TreeLimitError> {
+        if !matches!(html_token.token_type, HtmlTokenType::StartTag) {
+            return None;
+        }
+        if let Some(max_attributes) = self.max_attributes {
+            if html_token.attributes.len() > max_attributes {
+                return Some(TreeLimitError { limit: TreeLimit::Attributes, maximum: max_attributes, tag_name: Some(html_token.tag_name.to_string()), position });
+            }
+        }
+        if let Some(max_attribute_length) = self.max_attribute_length {
+            if html_token.attributes.iter().any(|attribute| attribute.value.len() > max_attribute_length) {
+                return Some(TreeLimitError { limit: TreeLimit::AttributeLength, maximum: max_attribute_length, tag_name: Some(html_token.tag_name.to_string()), position });
+            }
+        }
+        return None;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeLimit {
+    Depth,
+    Nodes,
+    Attributes,
+    AttributeLength,
+}
+
+// The limit the input went over, and where
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeLimitError {
+    pub limit: TreeLimit,
+    pub maximum: usize,
+    // The element that went over the limit, None for the node limit which any kind of node can go over
+    pub tag_name: Option<String>,
+    // The start of the token that went over the limit
+    pub position: Option<SourcePosition>,
+}
+
+impl fmt::Display for TreeLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let element = self.tag_name.as_ref().map(|tag_name| format!("a <{}> element", tag_name)).unwrap_or_else(|| "the document".to_string());
+        match self.limit {
+            TreeLimit::Depth => write!(f, "The document is nested too deeply, {} would be deeper than the maximum depth of {}", element, self.maximum)?,
+            TreeLimit::Nodes => write!(f, "The document has more than the maximum of {} nodes", self.maximum)?,
+            TreeLimit::Attributes => write!(f, "{} has more than the maximum of {} attributes", capitalized(&element), self.maximum)?,
+            TreeLimit::AttributeLength => write!(f, "{} has an attribute value longer than the maximum of {} bytes", capitalized(&element), self.maximum)?,
+        }
+        if let Some(position) = self.position {
+            write!(f, " at {}", position)?;
+        }
+        return write!(f, ", the rest of the input was not parsed");
+    }
+}
+
+fn capitalized(text: &str) -> String {
+    let mut characters = text.chars();
+    return characters.next().map(|first| first.to_ascii_uppercase().to_string() + characters.as_str()).unwrap_or_default();
+}
@@ -0,0 +1,47 @@
+use std::borrow::Cow;
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+// The case and normalization rules names and text are compared by, in one place so each comparison uses the one its spec asks for
+// HTML tag and attribute names, CSS type selectors and attribute names, and keywords (e.g. of the type attribute) are compared ASCII case-insensitively,
+// the non-ASCII letters of a name are never folded, e.g. <DİV> is not a div
+// Case folding and normalization are for comparing text, e.g. what a user searches for, and are never applied to markup unless asked for (see ParserOptions::normalize_unicode)
+
+// https://infra.spec.whatwg.org/#ascii-lowercase
+// input with ASCII upper alphas replaced by their lowercase, borrowed when it has none
+pub fn ascii_lowercase(input: &str) -> Cow<'_, str> {
+    if input.bytes().any(|byte| byte.is_ascii_uppercase()) {
+        return Cow::Owned(input.to_ascii_lowercase());
+    }
+    return Cow::Borrowed(input);
+}
+
+// https://infra.spec.whatwg.org/#ascii-case-insensitive
+// Whether a and b are the same once their ASCII upper alphas are lowercased
+pub fn is_ascii_case_insensitive_match(a: &str, b: &str) -> bool {
+    return a.eq_ignore_ascii_case(b);
+}
+
+// https://www.unicode.org/reports/tr44/#CaseFolding.txt
+// The full default case folding of input, so text that only differs in case compares equal, e.g. "Straße" and "STRASSE" both fold to "strasse"
+// TODO: Each character is uppercased and then lowercased, which is the full case folding of the Unicode Character Database
+// for all but a few characters (e.g. the Cherokee letters fold to uppercase), and the Turkic dotted and dotless i are folded the locale-independent way
+pub fn case_fold(input: &str) -> String {
+    if input.is_ascii() {
+        return input.to_ascii_lowercase();
+    }
+    return input.chars().flat_map(char::to_uppercase).flat_map(char::to_lowercase).collect();
+}
+
+// Whether a and b are the same once case folded and normalized, e.g. "É" written as one character and as E followed by a combining acute accent
+pub fn is_case_insensitive_match(a: &str, b: &str) -> bool {
+    return nfc(&case_fold(a)) == nfc(&case_fold(b));
+}
+
+// https://www.unicode.org/reports/tr15/#Norm_Forms
+// input in Normalization Form C, where each character that has a precomposed form is written as it, borrowed when it already is
+pub fn nfc(input: &str) -> Cow<'_, str> {
+    if input.is_ascii() || is_nfc_quick(input.chars()) == IsNormalized::Yes {
+        return Cow::Borrowed(input);
+    }
+    return Cow::Owned(input.nfc().collect());
+}
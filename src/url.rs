@@ -0,0 +1,1295 @@
+use std::fmt;
+use std::net::Ipv6Addr;
+use std::path::Path;
+
+// https://url.spec.whatwg.org/#url-parsing
+// TODO: Domains are lowercased but not converted with IDNA, and there is no state override for the URL setters
+
+// https://url.spec.whatwg.org/#concept-url
+#[derive(Debug, Clone, PartialEq)]
+pub struct Url {
+    pub scheme: String,
+    pub username: String,
+    pub password: String,
+    // The serialized host, None for URLs such as about:blank that have no host
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub path: UrlPath,
+    pub query: Option<String>,
+    pub fragment: Option<String>,
+}
+
+// https://url.spec.whatwg.org/#concept-url-path
+#[derive(Debug, Clone, PartialEq)]
+pub enum UrlPath {
+    // https://url.spec.whatwg.org/#url-opaque-path
+    Opaque(String),
+    Segments(Vec<String>),
+}
+
+// https://url.spec.whatwg.org/#concept-url-parser
+// Only the validation errors that make parsing fail are reported, the others are ignored
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UrlParseError {
+    MissingSchemeNonRelativeUrl,
+    HostMissing,
+    DomainInvalidCodePoint,
+    HostInvalidCodePoint,
+    Ipv4TooManyParts,
+    Ipv4NonNumericPart,
+    Ipv4OutOfRangePart,
+    Ipv6Unclosed,
+    Ipv6Invalid,
+    PortOutOfRange,
+    PortInvalid,
+}
+
+impl fmt::Display for UrlParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            UrlParseError::MissingSchemeNonRelativeUrl => "missing-scheme-non-relative-URL",
+            UrlParseError::HostMissing => "host-missing",
+            UrlParseError::DomainInvalidCodePoint => "domain-invalid-code-point",
+            UrlParseError::HostInvalidCodePoint => "host-invalid-code-point",
+            UrlParseError::Ipv4TooManyParts => "IPv4-too-many-parts",
+            UrlParseError::Ipv4NonNumericPart => "IPv4-non-numeric-part",
+            UrlParseError::Ipv4OutOfRangePart => "IPv4-out-of-range-part",
+            UrlParseError::Ipv6Unclosed => "IPv6-unclosed",
+            UrlParseError::Ipv6Invalid => "IPv6-invalid",
+            UrlParseError::PortOutOfRange => "port-out-of-range",
+            UrlParseError::PortInvalid => "port-invalid",
+        };
+        write!(f, "{}", message)
+    }
+}
+
+// https://url.spec.whatwg.org/#scheme-state
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    SchemeStart,
+    Scheme,
+    NoScheme,
+    SpecialRelativeOrAuthority,
+    PathOrAuthority,
+    Relative,
+    RelativeSlash,
+    SpecialAuthoritySlashes,
+    SpecialAuthorityIgnoreSlashes,
+    Authority,
+    Host,
+    Port,
+    File,
+    FileSlash,
+    FileHost,
+    PathStart,
+    Path,
+    OpaquePath,
+    Query,
+    Fragment,
+}
+
+// https://url.spec.whatwg.org/#special-scheme
+fn special_scheme_default_port(scheme: &str) -> Option<Option<u16>> {
+    match scheme {
+        "ftp" => Some(Some(21)),
+        "file" => Some(None),
+        "http" | "ws" => Some(Some(80)),
+        "https" | "wss" => Some(Some(443)),
+        _ => None
+    }
+}
+
+fn is_special_scheme(scheme: &str) -> bool {
+    return special_scheme_default_port(scheme).is_some();
+}
+
+// https://url.spec.whatwg.org/#default-port
+fn default_port(scheme: &str) -> Option<u16> {
+    return special_scheme_default_port(scheme).flatten();
+}
+
+// https://url.spec.whatwg.org/#c0-control-percent-encode-set
+fn in_c0_control_percent_encode_set(c: char) -> bool {
+    return c <= '\u{1F}' || c > '~';
+}
+
+// https://url.spec.whatwg.org/#fragment-percent-encode-set
+fn in_fragment_percent_encode_set(c: char) -> bool {
+    return in_c0_control_percent_encode_set(c) || matches!(c, ' ' | '"' | '<' | '>' | '`');
+}
+
+// https://url.spec.whatwg.org/#query-percent-encode-set
+fn in_query_percent_encode_set(c: char) -> bool {
+    return in_c0_control_percent_encode_set(c) || matches!(c, ' ' | '"' | '#' | '<' | '>');
+}
+
+// https://url.spec.whatwg.org/#special-query-percent-encode-set
+fn in_special_query_percent_encode_set(c: char) -> bool {
+    return in_query_percent_encode_set(c) || c == '\'';
+}
+
+// https://url.spec.whatwg.org/#path-percent-encode-set
+fn in_path_percent_encode_set(c: char) -> bool {
+    return in_query_percent_encode_set(c) || matches!(c, '?' | '^' | '`' | '{' | '}');
+}
+
+// https://url.spec.whatwg.org/#userinfo-percent-encode-set
+fn in_userinfo_percent_encode_set(c: char) -> bool {
+    return in_path_percent_encode_set(c) || matches!(c, '/' | ':' | ';' | '=' | '@' | '['..='^' | '|');
+}
+
+// https://url.spec.whatwg.org/#component-percent-encode-set
+fn in_component_percent_encode_set(c: char) -> bool {
+    return in_userinfo_percent_encode_set(c) || matches!(c, '$'..='&' | '+' | ',');
+}
+
+// https://url.spec.whatwg.org/#application-x-www-form-urlencoded-percent-encode-set
+fn in_application_x_www_form_urlencoded_percent_encode_set(c: char) -> bool {
+    return in_component_percent_encode_set(c) || matches!(c, '!' | '\''..=')' | '~');
+}
+
+// https://url.spec.whatwg.org/#code-point-percent-encode-after-encoding
+fn percent_encode(c: char, in_percent_encode_set: fn(char) -> bool, output: &mut String) {
+    if !in_percent_encode_set(c) {
+        output.push(c);
+        return;
+    }
+
+    let mut bytes = [0; 4];
+    for byte in c.encode_utf8(&mut bytes).bytes() {
+        output.push_str(&format!("%{:02X}", byte));
+    }
+}
+
+// https://url.spec.whatwg.org/#concept-urlencoded-serializer
+// The name-value pairs as a query string or form body, e.g. name=J%C3%BCrgen+Smith&age=40
+pub fn form_urlencoded_serialize(tuples: &[(String, String)]) -> String {
+    // 3. Let output be the empty string.
+    let mut output = String::new();
+    for (name, value) in tuples {
+        // 4.2. Let name be the result of running percent-encode after encoding with encoding, tuple's name, the application/x-www-form-urlencoded percent-encode set, and true.
+        // 4.3. Let value be the result of running percent-encode after encoding with encoding, tuple's value, the application/x-www-form-urlencoded percent-encode set, and true.
+        // 4.4. If output is not the empty string, then append U+0026 (&) to output.
+        if !output.is_empty() {
+            output.push('&');
+        }
+        // 4.5. Append name, followed by U+003D (=), followed by value, to output.
+        form_urlencoded_encode(name, &mut output);
+        output.push('=');
+        form_urlencoded_encode(value, &mut output);
+    }
+    return output;
+}
+
+// https://url.spec.whatwg.org/#string-percent-encode-after-encoding
+// With spaceAsPlus, a space is encoded as U+002B (+) rather than as %20
+fn form_urlencoded_encode(input: &str, output: &mut String) {
+    for c in input.chars() {
+        if c == ' ' {
+            output.push('+');
+        } else {
+            percent_encode(c, in_application_x_www_form_urlencoded_percent_encode_set, output);
+        }
+    }
+}
+
+// https://url.spec.whatwg.org/#percent-decode
+pub fn percent_decode(input: &str) -> Vec<u8> {
+    let bytes = input.as_bytes();
+    let mut output = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        // 1. If byte is not 0x25 (%), then append byte to output.
+        // 2. Otherwise, if byte is 0x25 (%) and the next two bytes after byte in input are not in the ranges 0x30 (0) to 0x39 (9), 0x41 (A) to 0x46 (F), and 0x61 (a) to 0x66 (f), all inclusive, append byte to output.
+        let hex = bytes.get(index + 1..index + 3).and_then(|hex| std::str::from_utf8(hex).ok()).and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (byte, hex) {
+            // 3.2. Append a byte whose value is bytePoint to output.
+            // 3.3. Skip the next two bytes in input.
+            (b'%', Some(decoded)) if bytes[index + 1].is_ascii_hexdigit() && bytes[index + 2].is_ascii_hexdigit() => {
+                output.push(decoded);
+                index += 3;
+            },
+            _ => {
+                output.push(byte);
+                index += 1;
+            }
+        }
+    }
+    return output;
+}
+
+// https://url.spec.whatwg.org/#windows-drive-letter
+fn is_windows_drive_letter(segment: &str, normalized: bool) -> bool {
+    let characters: Vec<char> = segment.chars().collect();
+    return characters.len() == 2 && characters[0].is_ascii_alphabetic() && (characters[1] == ':' || (!normalized && characters[1] == '|'));
+}
+
+// https://url.spec.whatwg.org/#start-with-a-windows-drive-letter
+fn starts_with_windows_drive_letter(remaining: &[char]) -> bool {
+    // 1. Its length is greater than or equal to 2.
+    // 2. Its first two code points are a Windows drive letter.
+    // 3. Its length is 2 or its third code point is U+002F (/), U+005C (\), U+003F (?), or U+0023 (#).
+    return remaining.len() >= 2
+        && is_windows_drive_letter(&remaining[..2].iter().collect::<String>(), false)
+        && (remaining.len() == 2 || matches!(remaining[2], '/' | '\\' | '?' | '#'));
+}
+
+// https://url.spec.whatwg.org/#single-dot-path-segment
+fn is_single_dot_path_segment(segment: &str) -> bool {
+    return segment == "." || segment.eq_ignore_ascii_case("%2e");
+}
+
+// https://url.spec.whatwg.org/#double-dot-path-segment
+fn is_double_dot_path_segment(segment: &str) -> bool {
+    let segment = segment.to_ascii_lowercase();
+    return matches!(segment.as_str(), ".." | ".%2e" | "%2e." | "%2e%2e");
+}
+
+// https://url.spec.whatwg.org/#forbidden-host-code-point
+fn is_forbidden_host_code_point(c: char) -> bool {
+    return matches!(c, '\0' | '\t' | '\n' | '\r' | ' ' | '#' | '/' | ':' | '<' | '>' | '?' | '@' | '[' | '\\' | ']' | '^' | '|');
+}
+
+// https://url.spec.whatwg.org/#forbidden-domain-code-point
+fn is_forbidden_domain_code_point(c: char) -> bool {
+    return is_forbidden_host_code_point(c) || c <= '\u{1F}' || matches!(c, '%' | '\u{7F}');
+}
+
+// https://url.spec.whatwg.org/#concept-host-parser
+fn parse_host(input: &str, is_opaque: bool) -> Result<String, UrlParseError> {
+    // 1. If input starts with U+005B ([), then:
+    if let Some(address) = input.strip_prefix('[') {
+        // 1. If input does not end with U+005D (]), IPv6-unclosed validation error, return failure.
+        // 2. Return the result of IPv6 parsing input with its leading U+005B ([) and trailing U+005D (]) removed.
+        let address = address.strip_suffix(']').ok_or(UrlParseError::Ipv6Unclosed)?;
+        let address: Ipv6Addr = address.parse().map_err(|_| UrlParseError::Ipv6Invalid)?;
+        return Ok(format!("[{}]", serialize_ipv6(&address)));
+    }
+
+    // 2. If isOpaque is true, then return the result of opaque-host parsing input.
+    if is_opaque {
+        // https://url.spec.whatwg.org/#concept-opaque-host-parser
+        // 1. If input contains a forbidden host code point, host-invalid-code-point validation error, return failure.
+        if input.chars().any(is_forbidden_host_code_point) {
+            return Err(UrlParseError::HostInvalidCodePoint);
+        }
+
+        // 4. Return the result of running UTF-8 percent-encode on input using the C0 control percent-encode set.
+        let mut output = String::new();
+        for c in input.chars() {
+            percent_encode(c, in_c0_control_percent_encode_set, &mut output);
+        }
+        return Ok(output);
+    }
+
+    // 4. Let domain be the result of running UTF-8 decode without BOM on the percent-decoding of input.
+    let domain = String::from_utf8_lossy(&percent_decode(input)).into_owned();
+
+    // 5. Let asciiDomain be the result of running domain to ASCII with domain and false.
+    let ascii_domain = domain.to_lowercase();
+    if ascii_domain.is_empty() {
+        return Err(UrlParseError::HostMissing);
+    }
+
+    // 7. If asciiDomain contains a forbidden domain code point, domain-invalid-code-point validation error, return failure.
+    if ascii_domain.chars().any(is_forbidden_domain_code_point) {
+        return Err(UrlParseError::DomainInvalidCodePoint);
+    }
+
+    // 8. If asciiDomain ends in a number, then return the result of IPv4 parsing asciiDomain.
+    if ends_in_a_number(&ascii_domain) {
+        let address = parse_ipv4(&ascii_domain)?;
+        return Ok(serialize_ipv4(address));
+    }
+
+    // 9. Return asciiDomain.
+    return Ok(ascii_domain);
+}
+
+// https://url.spec.whatwg.org/#ends-in-a-number-checker
+fn ends_in_a_number(input: &str) -> bool {
+    // 1. Let parts be the result of strictly splitting input on U+002E (.).
+    let mut parts: Vec<&str> = input.split('.').collect();
+
+    // 2. If the last item in parts is the empty string, then:
+    if parts.last() == Some(&"") {
+        // 1. If parts's size is 1, then return false.
+        if parts.len() == 1 {
+            return false;
+        }
+        // 2. Remove the last item from parts.
+        parts.pop();
+    }
+
+    // 3. Let last be the last item in parts.
+    let last = parts.last().unwrap();
+
+    // 4. If last is non-empty and contains only ASCII digits, then return true.
+    if !last.is_empty() && last.chars().all(|c| c.is_ascii_digit()) {
+        return true;
+    }
+
+    // 5. If parsing last as an IPv4 number does not return failure, then return true.
+    return parse_ipv4_number(last).is_some();
+}
+
+// https://url.spec.whatwg.org/#concept-ipv4-parser
+fn parse_ipv4(input: &str) -> Result<u32, UrlParseError> {
+    // 1. Let parts be the result of strictly splitting input on U+002E (.).
+    let mut parts: Vec<&str> = input.split('.').collect();
+
+    // 2. If the last item in parts is the empty string, then remove the last item from parts.
+    if parts.last() == Some(&"") && parts.len() > 1 {
+        parts.pop();
+    }
+
+    // 3. If parts's size is greater than 4, IPv4-too-many-parts validation error, return failure.
+    if parts.len() > 4 {
+        return Err(UrlParseError::Ipv4TooManyParts);
+    }
+
+    // 4. Let numbers be an empty list.
+    // 5. For each part of parts, let result be the result of parsing part. If result is failure, IPv4-non-numeric-part validation error, return failure.
+    let mut numbers = Vec::new();
+    for part in parts {
+        numbers.push(parse_ipv4_number(part).ok_or(UrlParseError::Ipv4NonNumericPart)?);
+    }
+
+    // 7. If any but the last item in numbers is greater than 255, then return failure.
+    // 8. If the last item in numbers is greater than or equal to 256^(5 − numbers's size), then return failure.
+    let (last, rest) = numbers.split_last().unwrap();
+    if rest.iter().any(|number| *number > 255) || *last >= 256u64.pow(5 - numbers.len() as u32) {
+        return Err(UrlParseError::Ipv4OutOfRangePart);
+    }
+
+    // 9. Let ipv4 be the last item in numbers.
+    // 11. Let counter be 0.
+    // 12. For each n of numbers: Increment ipv4 by n × 256^(3 − counter).
+    let mut ipv4 = *last;
+    for (counter, number) in rest.iter().enumerate() {
+        ipv4 += number * 256u64.pow(3 - counter as u32);
+    }
+
+    // 13. Return ipv4.
+    return Ok(ipv4 as u32);
+}
+
+// https://url.spec.whatwg.org/#ipv4-number-parser
+fn parse_ipv4_number(input: &str) -> Option<u64> {
+    // 1. If input is the empty string, then return failure.
+    if input.is_empty() {
+        return None;
+    }
+
+    // 2-5. A 0x or 0X prefix is hexadecimal, a leading 0 is octal, otherwise the number is decimal.
+    let (digits, radix) = if let Some(digits) = input.strip_prefix("0x").or_else(|| input.strip_prefix("0X")) {
+        (digits, 16)
+    } else if input.len() > 1 && input.starts_with('0') {
+        (&input[1..], 8)
+    } else {
+        (input, 10)
+    };
+
+    // 6. If input is the empty string, then return (0, true).
+    if digits.is_empty() {
+        return Some(0);
+    }
+
+    // 7. If input contains a code point that is not a radix-R digit, then return failure.
+    if !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+
+    // 8. Let output be the mathematical integer value that is represented by input in radix-R notation.
+    // Numbers too large for a u64 are out of range anyway
+    return Some(u64::from_str_radix(digits, radix).unwrap_or(u64::MAX));
+}
+
+// https://url.spec.whatwg.org/#concept-ipv4-serializer
+fn serialize_ipv4(address: u32) -> String {
+    return address.to_be_bytes().iter().map(|byte| byte.to_string()).collect::<Vec<String>>().join(".");
+}
+
+// https://url.spec.whatwg.org/#concept-ipv6-serializer
+fn serialize_ipv6(address: &Ipv6Addr) -> String {
+    let pieces = address.segments();
+
+    // 2. Let compress be an index to the first IPv6 piece in the first longest sequences of address's IPv6 pieces that are 0.
+    // 3. If there is no sequence of address's IPv6 pieces that are 0 that is longer than 1, then set compress to null.
+    let mut compress: Option<(usize, usize)> = None;
+    let mut index = 0;
+    while index < 8 {
+        if pieces[index] != 0 {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        while index < 8 && pieces[index] == 0 {
+            index += 1;
+        }
+        let length = index - start;
+        if length > 1 && compress.map(|(_, longest)| length > longest).unwrap_or(true) {
+            compress = Some((start, length));
+        }
+    }
+
+    // 5. For each pieceIndex in the range 0 to 7, inclusive:
+    let mut output = String::new();
+    let mut piece_index = 0;
+    while piece_index < 8 {
+        // 3. If compress is pieceIndex, then append "::" to output if pieceIndex is 0, or U+003A (:) otherwise, and skip the sequence.
+        if let Some((start, length)) = compress {
+            if piece_index == start {
+                output.push_str(if piece_index == 0 { "::" } else { ":" });
+                piece_index += length;
+                continue;
+            }
+        }
+
+        // 4. Append address[pieceIndex], represented as the shortest possible lowercase hexadecimal number, to output.
+        output.push_str(&format!("{:x}", pieces[piece_index]));
+
+        // 5. If pieceIndex is not 7, then append U+003A (:) to output.
+        if piece_index != 7 {
+            output.push(':');
+        }
+        piece_index += 1;
+    }
+    return output;
+}
+
+impl Url {
+    // https://url.spec.whatwg.org/#concept-basic-url-parser
+    pub fn parse(input: &str, base: Option<&Url>) -> Result<Url, UrlParseError> {
+        return UrlParser::new(input, base).run();
+    }
+
+    // Resolves input against this URL, as the relative URLs of a document are resolved against its base URL
+    pub fn join(&self, input: &str) -> Result<Url, UrlParseError> {
+        return Url::parse(input, Some(self));
+    }
+
+    // The URL of a local file, the path is made absolute against the current directory
+    pub fn from_file_path(path: &Path) -> Url {
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let mut serialized_path = String::new();
+        for c in path.display().to_string().chars() {
+            percent_encode(c, in_path_percent_encode_set, &mut serialized_path);
+        }
+        if !serialized_path.starts_with('/') {
+            serialized_path.insert(0, '/');
+        }
+        return Url::parse(&format!("file://{}", serialized_path), None).unwrap();
+    }
+
+    // about:blank, the URL of a document that was not loaded from anywhere
+    pub fn about_blank() -> Url {
+        return Url::parse("about:blank", None).unwrap();
+    }
+
+    // https://url.spec.whatwg.org/#is-special
+    pub fn is_special(&self) -> bool {
+        return is_special_scheme(&self.scheme);
+    }
+
+    // https://url.spec.whatwg.org/#include-credentials
+    fn includes_credentials(&self) -> bool {
+        return !self.username.is_empty() || !self.password.is_empty();
+    }
+
+    // https://url.spec.whatwg.org/#shorten-a-urls-path
+    fn shorten_path(&mut self) {
+        let is_file = self.scheme == "file";
+        if let UrlPath::Segments(segments) = &mut self.path {
+            // 3. If url's scheme is "file", path's size is 1, and path[0] is a normalized Windows drive letter, then return.
+            if is_file && segments.len() == 1 && is_windows_drive_letter(&segments[0], true) {
+                return;
+            }
+
+            // 4. Remove path's last item, if any.
+            segments.pop();
+        }
+    }
+
+    fn push_path_segment(&mut self, segment: String) {
+        if let UrlPath::Segments(segments) = &mut self.path {
+            segments.push(segment);
+        }
+    }
+
+    fn path_segments(&self) -> &[String] {
+        match &self.path {
+            UrlPath::Segments(segments) => segments,
+            UrlPath::Opaque(_) => &[]
+        }
+    }
+
+    // https://url.spec.whatwg.org/#url-path-serializer
+    pub fn serialize_path(&self) -> String {
+        match &self.path {
+            // 1. If url has an opaque path, then return url's path.
+            UrlPath::Opaque(path) => path.clone(),
+            // 3. For each segment of url's path: append U+002F (/) followed by segment to output.
+            UrlPath::Segments(segments) => segments.iter().map(|segment| format!("/{}", segment)).collect()
+        }
+    }
+
+    // https://url.spec.whatwg.org/#concept-url-serializer
+    pub fn serialize(&self, exclude_fragment: bool) -> String {
+        // 1. Let output be url's scheme and U+003A (:) concatenated.
+        let mut output = format!("{}:", self.scheme);
+
+        // 2. If url's host is non-null:
+        if let Some(host) = &self.host {
+            output.push_str("//");
+            if self.includes_credentials() {
+                output.push_str(&self.username);
+                if !self.password.is_empty() {
+                    output.push(':');
+                    output.push_str(&self.password);
+                }
+                output.push('@');
+            }
+            output.push_str(host);
+            if let Some(port) = self.port {
+                output.push_str(&format!(":{}", port));
+            }
+        }
+
+        // 3. If url's host is null, url does not have an opaque path, url's path's size is greater than 1, and url's path[0] is the empty string, then append U+002F (/) followed by U+002E (.) to output.
+        if self.host.is_none() && self.path_segments().len() > 1 && self.path_segments()[0].is_empty() {
+            output.push_str("/.");
+        }
+
+        // 4. Append the result of URL path serializing url to output.
+        output.push_str(&self.serialize_path());
+
+        // 5. If url's query is non-null, append U+003F (?), followed by url's query, to output.
+        if let Some(query) = &self.query {
+            output.push('?');
+            output.push_str(query);
+        }
+
+        // 6. If exclude fragment is false and url's fragment is non-null, then append U+0023 (#), followed by url's fragment, to output.
+        if !exclude_fragment {
+            if let Some(fragment) = &self.fragment {
+                output.push('#');
+                output.push_str(fragment);
+            }
+        }
+
+        // 7. Return output.
+        return output;
+    }
+
+    // https://url.spec.whatwg.org/#dom-url-href
+    pub fn href(&self) -> String {
+        return self.serialize(false);
+    }
+
+    // https://url.spec.whatwg.org/#concept-url-origin
+    // https://html.spec.whatwg.org/multipage/browsers.html#ascii-serialisation-of-an-origin
+    // Opaque origins, such as those of file: URLs, serialize as "null"
+    pub fn origin(&self) -> String {
+        match self.scheme.as_str() {
+            "ftp" | "http" | "https" | "ws" | "wss" => {
+                let mut origin = format!("{}://{}", self.scheme, self.host.as_deref().unwrap_or(""));
+                if let Some(port) = self.port {
+                    origin.push_str(&format!(":{}", port));
+                }
+                origin
+            },
+            _ => String::from("null")
+        }
+    }
+
+    // https://url.spec.whatwg.org/#dom-url-protocol
+    pub fn protocol(&self) -> String {
+        return format!("{}:", self.scheme);
+    }
+
+    // https://url.spec.whatwg.org/#dom-url-host
+    pub fn host(&self) -> String {
+        match (&self.host, self.port) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.clone(),
+            (None, _) => String::new()
+        }
+    }
+
+    // https://url.spec.whatwg.org/#dom-url-hostname
+    pub fn hostname(&self) -> String {
+        return self.host.clone().unwrap_or_default();
+    }
+
+    // https://url.spec.whatwg.org/#dom-url-port
+    pub fn port_string(&self) -> String {
+        return self.port.map(|port| port.to_string()).unwrap_or_default();
+    }
+
+    // https://url.spec.whatwg.org/#dom-url-pathname
+    pub fn pathname(&self) -> String {
+        return self.serialize_path();
+    }
+
+    // https://url.spec.whatwg.org/#dom-url-search
+    pub fn search(&self) -> String {
+        match &self.query {
+            Some(query) if !query.is_empty() => format!("?{}", query),
+            _ => String::new()
+        }
+    }
+
+    // https://url.spec.whatwg.org/#dom-url-hash
+    pub fn hash(&self) -> String {
+        match &self.fragment {
+            Some(fragment) if !fragment.is_empty() => format!("#{}", fragment),
+            _ => String::new()
+        }
+    }
+
+    // The file system path of a file: URL
+    pub fn to_file_path(&self) -> Option<String> {
+        if self.scheme != "file" {
+            return None;
+        }
+        return Some(String::from_utf8_lossy(&percent_decode(&self.serialize_path())).into_owned());
+    }
+}
+
+impl fmt::Display for Url {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.href())
+    }
+}
+
+// The state of the basic URL parser while it walks over the input
+struct UrlParser<'a> {
+    input: Vec<char>,
+    base: Option<&'a Url>,
+    url: Url,
+    state: State,
+    buffer: String,
+    at_sign_seen: bool,
+    inside_brackets: bool,
+    password_token_seen: bool,
+    pointer: usize,
+}
+
+impl<'a> UrlParser<'a> {
+    fn new(input: &str, base: Option<&'a Url>) -> UrlParser<'a> {
+        // 1.2. Remove any leading and trailing C0 control or space from input.
+        let input = input.trim_matches(|c: char| c <= ' ');
+
+        // 3. Remove all ASCII tab or newline from input.
+        let input: Vec<char> = input.chars().filter(|c| !matches!(c, '\t' | '\n' | '\r')).collect();
+
+        // 1.1. Set url to a new URL.
+        let url = Url { scheme: String::new(), username: String::new(), password: String::new(), host: None, port: None, path: UrlPath::Segments(Vec::new()), query: None, fragment: None };
+        UrlParser { input, base, url, state: State::SchemeStart, buffer: String::new(), at_sign_seen: false, inside_brackets: false, password_token_seen: false, pointer: 0 }
+    }
+
+    // None stands for the EOF code point
+    fn c(&self) -> Option<char> {
+        return self.input.get(self.pointer).copied();
+    }
+
+    // https://url.spec.whatwg.org/#remaining
+    fn remaining(&self) -> &[char] {
+        return self.input.get(self.pointer + 1..).unwrap_or(&[]);
+    }
+
+    fn remaining_starts_with(&self, prefix: &str) -> bool {
+        let remaining = self.remaining();
+        let prefix: Vec<char> = prefix.chars().collect();
+        return remaining.len() >= prefix.len() && remaining[..prefix.len()] == prefix[..];
+    }
+
+    fn base(&self) -> &'a Url {
+        return self.base.unwrap();
+    }
+
+    fn run(mut self) -> Result<Url, UrlParseError> {
+        // 9. Keep running the following state machine by switching on state. If after a run pointer points to the EOF code point, go to the next step. Otherwise, increase pointer by 1 and continue with the state machine.
+        loop {
+            let c = self.c();
+            match self.state {
+                // https://url.spec.whatwg.org/#scheme-start-state
+                State::SchemeStart => {
+                    match c {
+                        // 1. If c is an ASCII alpha, append c, lowercased, to buffer, and set state to scheme state.
+                        Some(c) if c.is_ascii_alphabetic() => {
+                            self.buffer.push(c.to_ascii_lowercase());
+                            self.state = State::Scheme;
+                        },
+                        // 2. Otherwise, if state override is not given, set state to no scheme state and decrease pointer by 1.
+                        _ => {
+                            self.state = State::NoScheme;
+                            self.reconsume();
+                        }
+                    }
+                },
+                // https://url.spec.whatwg.org/#scheme-state
+                State::Scheme => {
+                    match c {
+                        // 1. If c is an ASCII alphanumeric, U+002B (+), U+002D (-), or U+002E (.), append c, lowercased, to buffer.
+                        Some(c) if c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.') => {
+                            self.buffer.push(c.to_ascii_lowercase());
+                        },
+                        // 2. Otherwise, if c is U+003A (:), then:
+                        Some(':') => {
+                            // 2. Set url's scheme to buffer.
+                            // 4. Set buffer to the empty string.
+                            self.url.scheme = std::mem::take(&mut self.buffer);
+
+                            if self.url.scheme == "file" {
+                                // 5. If url's scheme is "file", then set state to file state.
+                                self.state = State::File;
+                            } else if self.url.is_special() && self.base.is_some_and(|base| base.scheme == self.url.scheme) {
+                                // 6. Otherwise, if url is special, base is non-null, and base's scheme is url's scheme, set state to special relative or authority state.
+                                self.state = State::SpecialRelativeOrAuthority;
+                            } else if self.url.is_special() {
+                                // 7. Otherwise, if url is special, set state to special authority slashes state.
+                                self.state = State::SpecialAuthoritySlashes;
+                            } else if self.remaining_starts_with("/") {
+                                // 8. Otherwise, if remaining starts with an U+002F (/), set state to path or authority state and increase pointer by 1.
+                                self.state = State::PathOrAuthority;
+                                self.pointer += 1;
+                            } else {
+                                // 9. Otherwise, set url's path to the empty string and set state to opaque path state.
+                                self.url.path = UrlPath::Opaque(String::new());
+                                self.state = State::OpaquePath;
+                            }
+                        },
+                        // 3. Otherwise, if state override is not given, set buffer to the empty string, state to no scheme state, and start over (from the first code point in input).
+                        _ => {
+                            self.buffer.clear();
+                            self.state = State::NoScheme;
+                            self.pointer = 0;
+                            continue;
+                        }
+                    }
+                },
+                // https://url.spec.whatwg.org/#no-scheme-state
+                State::NoScheme => {
+                    let base = match self.base {
+                        // 1. If base is null, or base has an opaque path and c is not U+0023 (#), missing-scheme-non-relative-URL validation error, return failure.
+                        None => return Err(UrlParseError::MissingSchemeNonRelativeUrl),
+                        Some(base) => base
+                    };
+                    let base_has_opaque_path = matches!(base.path, UrlPath::Opaque(_));
+
+                    if base_has_opaque_path && c != Some('#') {
+                        return Err(UrlParseError::MissingSchemeNonRelativeUrl);
+                    } else if base_has_opaque_path {
+                        // 2. Otherwise, if base has an opaque path and c is U+0023 (#), set url's scheme to base's scheme, url's path to base's path, url's query to base's query, url's fragment to the empty string, and set state to fragment state.
+                        self.url.scheme = base.scheme.clone();
+                        self.url.path = base.path.clone();
+                        self.url.query = base.query.clone();
+                        self.url.fragment = Some(String::new());
+                        self.state = State::Fragment;
+                    } else if base.scheme != "file" {
+                        // 3. Otherwise, if base's scheme is not "file", set state to relative state and decrease pointer by 1.
+                        self.state = State::Relative;
+                        self.reconsume();
+                    } else {
+                        // 4. Otherwise, set state to file state and decrease pointer by 1.
+                        self.state = State::File;
+                        self.reconsume();
+                    }
+                },
+                // https://url.spec.whatwg.org/#special-relative-or-authority-state
+                State::SpecialRelativeOrAuthority => {
+                    if c == Some('/') && self.remaining_starts_with("/") {
+                        // 1. If c is U+002F (/) and remaining starts with U+002F (/), then set state to special authority ignore slashes state and increase pointer by 1.
+                        self.state = State::SpecialAuthorityIgnoreSlashes;
+                        self.pointer += 1;
+                    } else {
+                        // 2. Otherwise, special-scheme-missing-following-solidus validation error, set state to relative state and decrease pointer by 1.
+                        self.state = State::Relative;
+                        self.reconsume();
+                    }
+                },
+                // https://url.spec.whatwg.org/#path-or-authority-state
+                State::PathOrAuthority => {
+                    if c == Some('/') {
+                        // 1. If c is U+002F (/), then set state to authority state.
+                        self.state = State::Authority;
+                    } else {
+                        // 2. Otherwise, set state to path state, and decrease pointer by 1.
+                        self.state = State::Path;
+                        self.reconsume();
+                    }
+                },
+                // https://url.spec.whatwg.org/#relative-state
+                State::Relative => {
+                    let base = self.base();
+
+                    // 2. Set url's scheme to base's scheme.
+                    self.url.scheme = base.scheme.clone();
+
+                    if c == Some('/') {
+                        // 3. If c is U+002F (/), then set state to relative slash state.
+                        self.state = State::RelativeSlash;
+                    } else if self.url.is_special() && c == Some('\\') {
+                        // 4. Otherwise, if url is special and c is U+005C (\), invalid-reverse-solidus validation error, set state to relative slash state.
+                        self.state = State::RelativeSlash;
+                    } else {
+                        // 5. Otherwise:
+                        // 1. Set url's username to base's username, url's password to base's password, url's host to base's host, url's port to base's port, url's path to a clone of base's path, and url's query to base's query.
+                        self.url.username = base.username.clone();
+                        self.url.password = base.password.clone();
+                        self.url.host = base.host.clone();
+                        self.url.port = base.port;
+                        self.url.path = base.path.clone();
+                        self.url.query = base.query.clone();
+
+                        match c {
+                            // 2. If c is U+003F (?), then set url's query to the empty string, and state to query state.
+                            Some('?') => {
+                                self.url.query = Some(String::new());
+                                self.state = State::Query;
+                            },
+                            // 3. Otherwise, if c is U+0023 (#), set url's fragment to the empty string and state to fragment state.
+                            Some('#') => {
+                                self.url.fragment = Some(String::new());
+                                self.state = State::Fragment;
+                            },
+                            // 4. Otherwise, if c is not the EOF code point:
+                            Some(_) => {
+                                // 1. Set url's query to null.
+                                // 2. Shorten url's path.
+                                // 3. Set state to path state and decrease pointer by 1.
+                                self.url.query = None;
+                                self.url.shorten_path();
+                                self.state = State::Path;
+                                self.reconsume();
+                            },
+                            None => {}
+                        }
+                    }
+                },
+                // https://url.spec.whatwg.org/#relative-slash-state
+                State::RelativeSlash => {
+                    if self.url.is_special() && matches!(c, Some('/') | Some('\\')) {
+                        // 1. If url is special and c is U+002F (/) or U+005C (\), then set state to special authority ignore slashes state.
+                        self.state = State::SpecialAuthorityIgnoreSlashes;
+                    } else if c == Some('/') {
+                        // 2. Otherwise, if c is U+002F (/), then set state to authority state.
+                        self.state = State::Authority;
+                    } else {
+                        // 3. Otherwise, set url's username to base's username, url's password to base's password, url's host to base's host, url's port to base's port, state to path state, and then, decrease pointer by 1.
+                        let base = self.base();
+                        self.url.username = base.username.clone();
+                        self.url.password = base.password.clone();
+                        self.url.host = base.host.clone();
+                        self.url.port = base.port;
+                        self.state = State::Path;
+                        self.reconsume();
+                    }
+                },
+                // https://url.spec.whatwg.org/#special-authority-slashes-state
+                State::SpecialAuthoritySlashes => {
+                    if c == Some('/') && self.remaining_starts_with("/") {
+                        // 1. If c is U+002F (/) and remaining starts with U+002F (/), then set state to special authority ignore slashes state and increase pointer by 1.
+                        self.state = State::SpecialAuthorityIgnoreSlashes;
+                        self.pointer += 1;
+                    } else {
+                        // 2. Otherwise, special-scheme-missing-following-solidus validation error, set state to special authority ignore slashes state and decrease pointer by 1.
+                        self.state = State::SpecialAuthorityIgnoreSlashes;
+                        self.reconsume();
+                    }
+                },
+                // https://url.spec.whatwg.org/#special-authority-ignore-slashes-state
+                State::SpecialAuthorityIgnoreSlashes => {
+                    // 1. If c is neither U+002F (/) nor U+005C (\), then set state to authority state and decrease pointer by 1.
+                    // 2. Otherwise, special-scheme-missing-following-solidus validation error.
+                    if !matches!(c, Some('/') | Some('\\')) {
+                        self.state = State::Authority;
+                        self.reconsume();
+                    }
+                },
+                // https://url.spec.whatwg.org/#authority-state
+                State::Authority => {
+                    if c == Some('@') {
+                        // 1. If c is U+0040 (@), then:
+                        // 2. If atSignSeen is true, then prepend "%40" to buffer.
+                        if self.at_sign_seen {
+                            self.buffer.insert_str(0, "%40");
+                        }
+
+                        // 3. Set atSignSeen to true.
+                        self.at_sign_seen = true;
+
+                        // 4. For each codePoint in buffer:
+                        for code_point in std::mem::take(&mut self.buffer).chars() {
+                            // 1. If codePoint is U+003A (:) and passwordTokenSeen is false, then set passwordTokenSeen to true and continue.
+                            if code_point == ':' && !self.password_token_seen {
+                                self.password_token_seen = true;
+                                continue;
+                            }
+
+                            // 2. Let encodedCodePoints be the result of running UTF-8 percent-encode codePoint using the userinfo percent-encode set.
+                            // 3. If passwordTokenSeen is true, then append encodedCodePoints to url's password.
+                            // 4. Otherwise, append encodedCodePoints to url's username.
+                            if self.password_token_seen {
+                                percent_encode(code_point, in_userinfo_percent_encode_set, &mut self.url.password);
+                            } else {
+                                percent_encode(code_point, in_userinfo_percent_encode_set, &mut self.url.username);
+                            }
+                        }
+                    } else if c.is_none() || matches!(c, Some('/') | Some('?') | Some('#')) || (self.url.is_special() && c == Some('\\')) {
+                        // 2. Otherwise, if one of the following is true: c is the EOF code point, U+002F (/), U+003F (?), or U+0023 (#); url is special and c is U+005C (\)
+                        // 1. If atSignSeen is true and buffer is the empty string, host-missing validation error, return failure.
+                        if self.at_sign_seen && self.buffer.is_empty() {
+                            return Err(UrlParseError::HostMissing);
+                        }
+
+                        // 2. Decrease pointer by buffer's code point length + 1, set buffer to the empty string, and set state to host state.
+                        self.pointer -= self.buffer.chars().count();
+                        self.buffer.clear();
+                        self.state = State::Host;
+                        continue;
+                    } else {
+                        // 3. Otherwise, append c to buffer.
+                        self.buffer.push(c.unwrap());
+                    }
+                },
+                // https://url.spec.whatwg.org/#host-state
+                State::Host => {
+                    if c == Some(':') && !self.inside_brackets {
+                        // 2. Otherwise, if c is U+003A (:) and insideBrackets is false, then:
+                        // 1. If buffer is the empty string, host-missing validation error, return failure.
+                        if self.buffer.is_empty() {
+                            return Err(UrlParseError::HostMissing);
+                        }
+
+                        // 3. Let host be the result of host parsing buffer with url is not special.
+                        // 5. Set url's host to host, buffer to the empty string, and state to port state.
+                        self.url.host = Some(parse_host(&self.buffer, !self.url.is_special())?);
+                        self.buffer.clear();
+                        self.state = State::Port;
+                    } else if c.is_none() || matches!(c, Some('/') | Some('?') | Some('#')) || (self.url.is_special() && c == Some('\\')) {
+                        // 3. Otherwise, if one of the following is true: c is the EOF code point, U+002F (/), U+003F (?), or U+0023 (#); url is special and c is U+005C (\)
+                        // then decrease pointer by 1, and then:
+                        // 1. If url is special and buffer is the empty string, host-missing validation error, return failure.
+                        if self.url.is_special() && self.buffer.is_empty() {
+                            return Err(UrlParseError::HostMissing);
+                        }
+
+                        // 4. Let host be the result of host parsing buffer with url is not special.
+                        // 6. Set url's host to host, buffer to the empty string, and state to path start state.
+                        self.url.host = Some(if self.buffer.is_empty() { String::new() } else { parse_host(&self.buffer, !self.url.is_special())? });
+                        self.buffer.clear();
+                        self.state = State::PathStart;
+                        self.reconsume();
+                    } else {
+                        // 4. Otherwise:
+                        // 1. If c is U+005B ([), then set insideBrackets to true.
+                        // 2. If c is U+005D (]), then set insideBrackets to false.
+                        // 3. Append c to buffer.
+                        let c = c.unwrap();
+                        if c == '[' {
+                            self.inside_brackets = true;
+                        }
+                        if c == ']' {
+                            self.inside_brackets = false;
+                        }
+                        self.buffer.push(c);
+                    }
+                },
+                // https://url.spec.whatwg.org/#port-state
+                State::Port => {
+                    match c {
+                        // 1. If c is an ASCII digit, append c to buffer.
+                        Some(c) if c.is_ascii_digit() => self.buffer.push(c),
+                        // 2. Otherwise, if one of the following is true: c is the EOF code point, U+002F (/), U+003F (?), or U+0023 (#); url is special and c is U+005C (\)
+                        _ if c.is_none() || matches!(c, Some('/') | Some('?') | Some('#')) || (self.url.is_special() && c == Some('\\')) => {
+                            // 1. If buffer is not the empty string, then:
+                            if !self.buffer.is_empty() {
+                                // 1. Let port be the mathematical integer value that is represented by buffer in radix-10 using ASCII digits for digits with values 0 through 9.
+                                // 2. If port is greater than 2^16 − 1, port-out-of-range validation error, return failure.
+                                let port = self.buffer.parse::<u16>().map_err(|_| UrlParseError::PortOutOfRange)?;
+
+                                // 3. Set url's port to null, if port is url's scheme's default port; otherwise to port.
+                                self.url.port = if default_port(&self.url.scheme) == Some(port) { None } else { Some(port) };
+
+                                // 4. Set buffer to the empty string.
+                                self.buffer.clear();
+                            }
+
+                            // 3. Set state to path start state and decrease pointer by 1.
+                            self.state = State::PathStart;
+                            self.reconsume();
+                        },
+                        // 3. Otherwise, port-invalid validation error, return failure.
+                        _ => return Err(UrlParseError::PortInvalid)
+                    }
+                },
+                // https://url.spec.whatwg.org/#file-state
+                State::File => {
+                    // 1. Set url's scheme to "file".
+                    // 2. Set url's host to the empty string.
+                    self.url.scheme = String::from("file");
+                    self.url.host = Some(String::new());
+
+                    if matches!(c, Some('/') | Some('\\')) {
+                        // 3. If c is U+002F (/) or U+005C (\), then set state to file slash state.
+                        self.state = State::FileSlash;
+                    } else if let Some(base) = self.base.filter(|base| base.scheme == "file") {
+                        // 4. Otherwise, if base is non-null and base's scheme is "file":
+                        // 1. Set url's host to base's host, url's path to a clone of base's path, and url's query to base's query.
+                        self.url.host = base.host.clone();
+                        self.url.path = base.path.clone();
+                        self.url.query = base.query.clone();
+
+                        match c {
+                            // 2. If c is U+003F (?), then set url's query to the empty string and state to query state.
+                            Some('?') => {
+                                self.url.query = Some(String::new());
+                                self.state = State::Query;
+                            },
+                            // 3. Otherwise, if c is U+0023 (#), set url's fragment to the empty string and state to fragment state.
+                            Some('#') => {
+                                self.url.fragment = Some(String::new());
+                                self.state = State::Fragment;
+                            },
+                            // 4. Otherwise, if c is not the EOF code point:
+                            Some(_) => {
+                                // 1. Set url's query to null.
+                                self.url.query = None;
+
+                                // 2. If the code point substring from pointer to the end of input does not start with a Windows drive letter, then shorten url's path.
+                                // 3. Otherwise, set url's path to « ».
+                                if !starts_with_windows_drive_letter(&self.input[self.pointer..]) {
+                                    self.url.shorten_path();
+                                } else {
+                                    self.url.path = UrlPath::Segments(Vec::new());
+                                }
+
+                                // 4. Set state to path state and decrease pointer by 1.
+                                self.state = State::Path;
+                                self.reconsume();
+                            },
+                            None => {}
+                        }
+                    } else {
+                        // 5. Otherwise, set state to path state, and decrease pointer by 1.
+                        self.state = State::Path;
+                        self.reconsume();
+                    }
+                },
+                // https://url.spec.whatwg.org/#file-slash-state
+                State::FileSlash => {
+                    if matches!(c, Some('/') | Some('\\')) {
+                        // 1. If c is U+002F (/) or U+005C (\), then set state to file host state.
+                        self.state = State::FileHost;
+                    } else {
+                        // 2. Otherwise:
+                        // 1. If base is non-null and base's scheme is "file", then:
+                        if let Some(base) = self.base.filter(|base| base.scheme == "file") {
+                            // 1. Set url's host to base's host.
+                            self.url.host = base.host.clone();
+
+                            // 2. If the code point substring from pointer to the end of input does not start with a Windows drive letter and base's path[0] is a normalized Windows drive letter, then append base's path[0] to url's path.
+                            let base_drive_letter = base.path_segments().first().filter(|segment| is_windows_drive_letter(segment, true)).cloned();
+                            if let Some(base_drive_letter) = base_drive_letter {
+                                if !starts_with_windows_drive_letter(&self.input[self.pointer..]) {
+                                    self.url.push_path_segment(base_drive_letter);
+                                }
+                            }
+                        }
+
+                        // 2. Set state to path state, and decrease pointer by 1.
+                        self.state = State::Path;
+                        self.reconsume();
+                    }
+                },
+                // https://url.spec.whatwg.org/#file-host-state
+                State::FileHost => {
+                    if let Some(c) = c.filter(|c| !matches!(c, '/' | '\\' | '?' | '#')) {
+                        // 2. Otherwise, append c to buffer.
+                        self.buffer.push(c);
+                    } else {
+                        // 1. If c is the EOF code point, U+002F (/), U+005C (\), U+003F (?), or U+0023 (#), then decrease pointer by 1 and then:
+                        self.reconsume();
+
+                        if is_windows_drive_letter(&self.buffer, false) {
+                            // 1. If state override is not given and buffer is a Windows drive letter, file-invalid-Windows-drive-letter-host validation error, set state to path state.
+                            // This is a (platform-independent) Windows drive letter quirk. buffer is not reset here and instead used in the path state.
+                            self.state = State::Path;
+                        } else if self.buffer.is_empty() {
+                            // 2. Otherwise, if buffer is the empty string, then set url's host to the empty string and state to path start state.
+                            self.url.host = Some(String::new());
+                            self.state = State::PathStart;
+                        } else {
+                            // 3. Otherwise, run these steps:
+                            // 1. Let host be the result of host parsing buffer with url is not special.
+                            // 3. If host is "localhost", then set host to the empty string.
+                            let mut host = parse_host(&self.buffer, !self.url.is_special())?;
+                            if host == "localhost" {
+                                host = String::new();
+                            }
+
+                            // 4. Set url's host to host.
+                            // 6. Set buffer to the empty string and state to path start state.
+                            self.url.host = Some(host);
+                            self.buffer.clear();
+                            self.state = State::PathStart;
+                        }
+                    }
+                },
+                // https://url.spec.whatwg.org/#path-start-state
+                State::PathStart => {
+                    if self.url.is_special() {
+                        // 1. If url is special, then:
+                        // 2. Set state to path state.
+                        // 3. If c is neither U+002F (/) nor U+005C (\), then decrease pointer by 1.
+                        self.state = State::Path;
+                        if !matches!(c, Some('/') | Some('\\')) {
+                            self.reconsume();
+                        }
+                    } else if c == Some('?') {
+                        // 2. Otherwise, if state override is not given and c is U+003F (?), set url's query to the empty string and state to query state.
+                        self.url.query = Some(String::new());
+                        self.state = State::Query;
+                    } else if c == Some('#') {
+                        // 3. Otherwise, if state override is not given and c is U+0023 (#), set url's fragment to the empty string and state to fragment state.
+                        self.url.fragment = Some(String::new());
+                        self.state = State::Fragment;
+                    } else if c.is_some() {
+                        // 4. Otherwise, if c is not the EOF code point:
+                        // 1. Set state to path state.
+                        // 2. If c is not U+002F (/), then decrease pointer by 1.
+                        self.state = State::Path;
+                        if c != Some('/') {
+                            self.reconsume();
+                        }
+                    }
+                },
+                // https://url.spec.whatwg.org/#path-state
+                State::Path => {
+                    let is_segment_end = c.is_none() || c == Some('/') || (self.url.is_special() && c == Some('\\')) || matches!(c, Some('?') | Some('#'));
+                    if is_segment_end {
+                        // 1. If one of the following is true: c is the EOF code point or U+002F (/); url is special and c is U+005C (\); state override is not given and c is U+003F (?) or U+0023 (#)
+                        let slash = c == Some('/') || (self.url.is_special() && c == Some('\\'));
+                        let buffer = std::mem::take(&mut self.buffer);
+
+                        if is_double_dot_path_segment(&buffer) {
+                            // 2. If buffer is a double-dot URL path segment, then:
+                            // 1. Shorten url's path.
+                            self.url.shorten_path();
+
+                            // 2. If neither c is U+002F (/), nor url is special and c is U+005C (\), append the empty string to url's path.
+                            if !slash {
+                                self.url.push_path_segment(String::new());
+                            }
+                        } else if is_single_dot_path_segment(&buffer) && !slash {
+                            // 3. Otherwise, if buffer is a single-dot URL path segment and if neither c is U+002F (/), nor url is special and c is U+005C (\), append the empty string to url's path.
+                            self.url.push_path_segment(String::new());
+                        } else if !is_single_dot_path_segment(&buffer) {
+                            // 4. Otherwise, if buffer is not a single-dot URL path segment, then:
+                            // 1. If url's scheme is "file", url's path is empty, and buffer is a Windows drive letter, then replace the second code point in buffer with U+003A (:).
+                            let mut buffer = buffer;
+                            if self.url.scheme == "file" && self.url.path_segments().is_empty() && is_windows_drive_letter(&buffer, false) {
+                                buffer = format!("{}:", &buffer[..1]);
+                            }
+
+                            // 2. Append buffer to url's path.
+                            self.url.push_path_segment(buffer);
+                        }
+
+                        // 6. If c is U+003F (?), then set url's query to the empty string and state to query state.
+                        // 7. If c is U+0023 (#), then set url's fragment to the empty string and state to fragment state.
+                        match c {
+                            Some('?') => {
+                                self.url.query = Some(String::new());
+                                self.state = State::Query;
+                            },
+                            Some('#') => {
+                                self.url.fragment = Some(String::new());
+                                self.state = State::Fragment;
+                            },
+                            _ => {}
+                        }
+                    } else {
+                        // 2. Otherwise, run these steps:
+                        // 3. UTF-8 percent-encode c using the path percent-encode set and append the result to buffer.
+                        percent_encode(c.unwrap(), in_path_percent_encode_set, &mut self.buffer);
+                    }
+                },
+                // https://url.spec.whatwg.org/#cannot-be-a-base-url-path-state
+                State::OpaquePath => {
+                    match c {
+                        // 1. If c is U+003F (?), then set url's query to the empty string and state to query state.
+                        Some('?') => {
+                            self.url.query = Some(String::new());
+                            self.state = State::Query;
+                        },
+                        // 2. Otherwise, if c is U+0023 (#), then set url's fragment to the empty string and state to fragment state.
+                        Some('#') => {
+                            self.url.fragment = Some(String::new());
+                            self.state = State::Fragment;
+                        },
+                        // 4. Otherwise, if c is not the EOF code point, UTF-8 percent-encode c using the C0 control percent-encode set and append the result to url's path.
+                        Some(c) => {
+                            if let UrlPath::Opaque(path) = &mut self.url.path {
+                                percent_encode(c, in_c0_control_percent_encode_set, path);
+                            }
+                        },
+                        None => {}
+                    }
+                },
+                // https://url.spec.whatwg.org/#query-state
+                State::Query => {
+                    match c {
+                        // 2. If c is U+0023 (#) or c is the EOF code point, then:
+                        Some('#') | None => {
+                            // 1. Let queryPercentEncodeSet be the special-query percent-encode set if url is special; otherwise the query percent-encode set.
+                            let query_percent_encode_set = if self.url.is_special() { in_special_query_percent_encode_set } else { in_query_percent_encode_set };
+
+                            // 2. Percent-encode after encoding, with encoding, buffer, and queryPercentEncodeSet, and append the result to url's query.
+                            let query = self.url.query.get_or_insert_with(String::new);
+                            for code_point in self.buffer.chars() {
+                                percent_encode(code_point, query_percent_encode_set, query);
+                            }
+
+                            // 3. Set buffer to the empty string.
+                            self.buffer.clear();
+
+                            // 4. If c is U+0023 (#), then set url's fragment to the empty string and state to fragment state.
+                            if c == Some('#') {
+                                self.url.fragment = Some(String::new());
+                                self.state = State::Fragment;
+                            }
+                        },
+                        // 3. Otherwise, if c is not the EOF code point, append c to buffer.
+                        Some(c) => self.buffer.push(c)
+                    }
+                },
+                // https://url.spec.whatwg.org/#fragment-state
+                State::Fragment => {
+                    // 1. If c is not the EOF code point, UTF-8 percent-encode c using the fragment percent-encode set and append the result to url's fragment.
+                    if let Some(c) = c {
+                        let fragment = self.url.fragment.get_or_insert_with(String::new);
+                        percent_encode(c, in_fragment_percent_encode_set, fragment);
+                    }
+                }
+            }
+
+            if self.pointer == self.input.len() {
+                break;
+            }
+            self.pointer = self.pointer.wrapping_add(1);
+        }
+
+        // 10. Return url.
+        return Ok(self.url);
+    }
+
+    // Decreases the pointer by 1 so the current code point is consumed again by the next state, wrapping around when the pointer is at the start as it is increased straight after
+    fn reconsume(&mut self) {
+        self.pointer = self.pointer.wrapping_sub(1);
+    }
+}
@@ -0,0 +1,141 @@
+use wasm_bindgen::prelude::*;
+
+use crate::html_document_parser::ParserOptions;
+use crate::node::{descendants, text_content, NodeData, RefNode};
+use crate::selector::{parse_selector_list, SelectorList};
+use crate::serializer::{serialize_children, serialize_node};
+use crate::tokenizer::Tokenizer;
+
+// The engine as JavaScript sees it, in a browser or in Node, through the bindings wasm-bindgen generates for the exports of this module
+// Build the crate for wasm32-unknown-unknown without the net and fs features, there is no file system or socket to use there, and run wasm-bindgen on it:
+//     cargo build --lib --release --target wasm32-unknown-unknown --no-default-features --features wasm
+//     wasm-bindgen --target web target/wasm32-unknown-unknown/release/web_engine.wasm --out-dir pkg
+// Scripts in the document are not run, the event loop they need waits on std::time::Instant, which panics on wasm32-unknown-unknown
+// The names follow the DOM, so const document = parse(html); document.querySelector("a").getAttribute("href") reads as it would in a browser
+
+// https://html.spec.whatwg.org/multipage/dynamic-markup-insertion.html#dom-domparser-parsefromstring
+#[wasm_bindgen]
+pub fn parse(html: &str) -> HtmlDocument {
+    return HtmlDocument::new(html);
+}
+
+// A parsed document
+#[wasm_bindgen]
+pub struct HtmlDocument {
+    document: RefNode,
+    parse_errors: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl HtmlDocument {
+    #[wasm_bindgen(constructor)]
+    pub fn new(html: &str) -> HtmlDocument {
+        let options = ParserOptions { scripting_enabled: false, print_parse_errors: false, ..ParserOptions::default() };
+        let mut tokenizer = Tokenizer::with_options(html.to_string(), options);
+        tokenizer.run();
+        return HtmlDocument { document: tokenizer.document(), parse_errors: tokenizer.parse_errors().to_vec() };
+    }
+
+    // The parse errors in the order they were found
+    #[wasm_bindgen(getter, js_name = parseErrors)]
+    pub fn parse_errors(&self) -> Vec<String> {
+        return self.parse_errors.clone();
+    }
+
+    // The HTML of the whole document, which parses into the same document again
+    pub fn serialize(&self) -> String {
+        return serialize_children(&self.document);
+    }
+
+    // https://dom.spec.whatwg.org/#dom-document-documentelement
+    #[wasm_bindgen(getter, js_name = documentElement)]
+    pub fn document_element(&self) -> Option<HtmlNode> {
+        let document_element = self.document.borrow().childNodes.iter().find(|child| matches!(child.borrow().data, NodeData::Element(_))).cloned();
+        return document_element.map(|node| HtmlNode { node });
+    }
+
+    // https://dom.spec.whatwg.org/#dom-parentnode-queryselector
+    #[wasm_bindgen(js_name = querySelector)]
+    pub fn query_selector(&self, selectors: &str) -> Result<Option<HtmlNode>, JsError> {
+        return query_selector(&self.document, selectors);
+    }
+
+    // https://dom.spec.whatwg.org/#dom-parentnode-queryselectorall
+    #[wasm_bindgen(js_name = querySelectorAll)]
+    pub fn query_selector_all(&self, selectors: &str) -> Result<Vec<HtmlNode>, JsError> {
+        return query_selector_all(&self.document, selectors);
+    }
+}
+
+// A node of a document, which keeps the node alive for as long as JavaScript holds it
+#[wasm_bindgen]
+pub struct HtmlNode {
+    node: RefNode,
+}
+
+#[wasm_bindgen]
+impl HtmlNode {
+    // https://dom.spec.whatwg.org/#dom-element-localname
+    #[wasm_bindgen(getter, js_name = localName)]
+    pub fn local_name(&self) -> Option<String> {
+        return match &self.node.borrow().data {
+            NodeData::Element(element) => Some(element.local_name().to_string()),
+            _ => None
+        };
+    }
+
+    // https://dom.spec.whatwg.org/#dom-node-textcontent
+    #[wasm_bindgen(getter, js_name = textContent)]
+    pub fn text_content(&self) -> Option<String> {
+        return text_content(&self.node);
+    }
+
+    // https://dom.spec.whatwg.org/#dom-element-getattribute
+    #[wasm_bindgen(js_name = getAttribute)]
+    pub fn get_attribute(&self, qualified_name: &str) -> Option<String> {
+        return match &self.node.borrow().data {
+            NodeData::Element(element) => element.get_attribute(qualified_name).cloned(),
+            _ => None
+        };
+    }
+
+    // https://html.spec.whatwg.org/multipage/dynamic-markup-insertion.html#dom-element-outerhtml
+    #[wasm_bindgen(getter, js_name = outerHTML)]
+    pub fn outer_html(&self) -> String {
+        return serialize_node(&self.node);
+    }
+
+    // https://html.spec.whatwg.org/multipage/dynamic-markup-insertion.html#dom-element-innerhtml
+    #[wasm_bindgen(getter, js_name = innerHTML)]
+    pub fn inner_html(&self) -> String {
+        return serialize_children(&self.node);
+    }
+
+    // https://dom.spec.whatwg.org/#dom-parentnode-queryselector
+    #[wasm_bindgen(js_name = querySelector)]
+    pub fn query_selector(&self, selectors: &str) -> Result<Option<HtmlNode>, JsError> {
+        return query_selector(&self.node, selectors);
+    }
+
+    // https://dom.spec.whatwg.org/#dom-parentnode-queryselectorall
+    #[wasm_bindgen(js_name = querySelectorAll)]
+    pub fn query_selector_all(&self, selectors: &str) -> Result<Vec<HtmlNode>, JsError> {
+        return query_selector_all(&self.node, selectors);
+    }
+}
+
+// https://dom.spec.whatwg.org/#scope-match-a-selectors-string
+// An invalid selector is thrown to JavaScript as an error, as querySelector throws a SyntaxError
+fn parse_selectors(selectors: &str) -> Result<SelectorList, JsError> {
+    return parse_selector_list(selectors).ok_or_else(|| JsError::new(&format!("'{}' is not a valid selector.", selectors)));
+}
+
+fn query_selector(node: &RefNode, selectors: &str) -> Result<Option<HtmlNode>, JsError> {
+    let selector_list = parse_selectors(selectors)?;
+    return Ok(descendants(node).into_iter().find(|descendant| selector_list.matches(descendant)).map(|node| HtmlNode { node }));
+}
+
+fn query_selector_all(node: &RefNode, selectors: &str) -> Result<Vec<HtmlNode>, JsError> {
+    let selector_list = parse_selectors(selectors)?;
+    return Ok(descendants(node).into_iter().filter(|descendant| selector_list.matches(descendant)).map(|node| HtmlNode { node }).collect());
+}
@@ -0,0 +1,378 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::cssom::{wrap_css_style_declaration, wrap_css_style_sheet, CSSStyleDeclaration, CSSStyleSheet};
+use crate::dom_bindings::{unwrap_node, wrap_node};
+use crate::interpreter::{create_normal_completion, CompletionRecord, CompletionRecordType, Interpreter, JSObject, JSValue, NativeFunction, PlatformObject, ReferenceRecordOrJsValue};
+use crate::node::{DOMString, NodeData, RefNode};
+
+// https://webidl.spec.whatwg.org/#javascript-binding
+// The operations and attributes of interfaces are written as Rust functions of the DOM types they take and return,
+// operation!, attribute_getter! and attribute_setter! turn them into the built-in functions of the interface prototype objects,
+// converting the this value and the arguments from JavaScript values and the result or exception back to them
+// TODO: The EventTarget, Event, CSSOM and Response bindings are still written against the interpreter directly
+
+// https://webidl.spec.whatwg.org/#es-type-mapping
+// Converts a JavaScript value to the IDL type, Ok(None) when it is not a value of the type, e.g. a string where a Node is expected
+pub(crate) trait FromJSValue: Sized {
+    // The name of the type in the TypeError thrown for values that are not of the type
+    const TYPE_NAME: &'static str;
+    // https://webidl.spec.whatwg.org/#dfn-optional-argument
+    // Whether the argument can be left out, it is converted from undefined then
+    const OPTIONAL: bool = false;
+
+    fn from_js_value(interpreter: &mut Interpreter, value: &Rc<RefCell<JSValue>>) -> Result<Option<Self>, CompletionRecord>;
+}
+
+// Converts the IDL value an operation or attribute returns to a JavaScript value
+pub(crate) trait ToJSValue {
+    fn to_js_value(self, interpreter: &mut Interpreter) -> Rc<RefCell<JSValue>>;
+}
+
+// https://webidl.spec.whatwg.org/#idl-exceptions
+// The exceptions the Rust functions of operations and attributes throw
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Exception {
+    // https://webidl.spec.whatwg.org/#exceptiondef-typeerror
+    TypeError(String),
+    // https://webidl.spec.whatwg.org/#idl-DOMException
+    // The name, e.g. "InvalidStateError", and the message
+    DOMException(&'static str, String),
+}
+
+// An element, which is how the this value of the operations and attributes of Element and the interfaces inheriting from it is given
+pub(crate) struct ElementNode(pub(crate) RefNode);
+
+// https://webidl.spec.whatwg.org/#idl-undefined
+// The value of an attribute that only some of the elements sharing the Element prototype have, undefined for the others
+// TODO: Not needed once elements get the interfaces of their local names, e.g. HTMLInputElement
+pub(crate) enum OrUndefined<T> {
+    Value(T),
+    Undefined,
+}
+
+// The result of the Rust function of an operation or attribute as a completion record, a throw completion when it threw
+pub(crate) trait IntoCompletion {
+    fn into_completion(self, interpreter: &mut Interpreter) -> CompletionRecord;
+}
+
+impl<T: ToJSValue> IntoCompletion for T {
+    fn into_completion(self, interpreter: &mut Interpreter) -> CompletionRecord {
+        let value = self.to_js_value(interpreter);
+        return create_normal_completion(Rc::new(ReferenceRecordOrJsValue::JSValue(value)));
+    }
+}
+
+impl<T: ToJSValue> IntoCompletion for Result<T, Exception> {
+    fn into_completion(self, interpreter: &mut Interpreter) -> CompletionRecord {
+        match self {
+            Ok(value) => value.into_completion(interpreter),
+            Err(Exception::TypeError(message)) => interpreter.throw_type_error(message),
+            Err(Exception::DOMException(name, message)) => interpreter.throw_error(name, message)
+        }
+    }
+}
+
+// Functions that run scripts, e.g. by firing events, return the completion record of the script
+impl IntoCompletion for CompletionRecord {
+    fn into_completion(self, _interpreter: &mut Interpreter) -> CompletionRecord {
+        return self;
+    }
+}
+
+// https://webidl.spec.whatwg.org/#ref-for-dfn-perform-a-security-check
+// Converts the this value of a built-in function, which throws a TypeError when it is not an object implementing the interface
+pub(crate) fn convert_this_value<T: FromJSValue>(interpreter: &mut Interpreter, this_value: &Rc<RefCell<JSValue>>) -> Result<T, CompletionRecord> {
+    match T::from_js_value(interpreter, this_value)? {
+        Some(value) => Ok(value),
+        None => Err(interpreter.throw_type_error(String::from("Illegal invocation")))
+    }
+}
+
+// https://webidl.spec.whatwg.org/#js-overloads
+// Converts the argument at index, context is how the TypeError thrown for a value of the wrong type starts, e.g. "Failed to execute 'appendChild' on 'Node'"
+pub(crate) fn convert_argument<T: FromJSValue>(interpreter: &mut Interpreter, arguments: &[Rc<RefCell<JSValue>>], index: usize, context: &str) -> Result<T, CompletionRecord> {
+    let value = match arguments.get(index) {
+        Some(value) => Rc::clone(value),
+        None => Rc::new(RefCell::new(JSValue::Undefined))
+    };
+
+    match T::from_js_value(interpreter, &value)? {
+        Some(value) => Ok(value),
+        None => Err(interpreter.throw_type_error(format!("{}: parameter {} is not of type '{}'.", context, index + 1, T::TYPE_NAME)))
+    }
+}
+
+// https://webidl.spec.whatwg.org/#dfn-create-operation-function
+// 2.1.4. If args's size is less than the number of required arguments of the operation, then throw a TypeError.
+pub(crate) fn check_argument_count(interpreter: &mut Interpreter, arguments: &[Rc<RefCell<JSValue>>], optional: &[bool], context: &str) -> Result<(), CompletionRecord> {
+    // The arguments after the last required one are optional
+    let required = optional.iter().rposition(|optional| !optional).map(|index| index + 1).unwrap_or(0);
+    if arguments.len() >= required {
+        return Ok(());
+    }
+
+    let plural = if required == 1 { "argument" } else { "arguments" };
+    return Err(interpreter.throw_type_error(format!("{}: {} {} required, but only {} present.", context, required, plural, arguments.len())));
+}
+
+// Turns a Rust function into the NativeFunction of an operation or attribute
+// The function is given the converted this value and arguments, and the interpreter too when its parameters start with &mut interpreter
+macro_rules! native_function {
+    (@function $context:expr, $interpreter:ident, $function:path, [$($interpreter_argument:ident)?], $this:ident: $this_type:ty $(, $argument:ident: $argument_type:ty)*) => {
+        |$interpreter: &mut $crate::interpreter::Interpreter, this_value: std::rc::Rc<std::cell::RefCell<$crate::interpreter::JSValue>>, arguments: Vec<std::rc::Rc<std::cell::RefCell<$crate::interpreter::JSValue>>>| -> $crate::interpreter::CompletionRecord {
+            let $this: $this_type = match $crate::webidl::convert_this_value($interpreter, &this_value) {
+                Ok(value) => value,
+                Err(completion_record) => return completion_record
+            };
+
+            let context: String = $context;
+            let optional: &[bool] = &[$(<$argument_type as $crate::webidl::FromJSValue>::OPTIONAL),*];
+            if let Err(completion_record) = $crate::webidl::check_argument_count($interpreter, &arguments, optional, &context) {
+                return completion_record;
+            }
+            #[allow(unused_variables)]
+            let index = 0;
+            $(
+                let $argument: $argument_type = match $crate::webidl::convert_argument($interpreter, &arguments, index, &context) {
+                    Ok(value) => value,
+                    Err(completion_record) => return completion_record
+                };
+                #[allow(unused_variables)]
+                let index = index + 1;
+            )*
+
+            let result = $function($($interpreter_argument,)? $this $(, $argument)*);
+            return $crate::webidl::IntoCompletion::into_completion(result, $interpreter);
+        }
+    };
+    ($context:expr, $function:path, &mut interpreter, $($parameters:tt)*) => {
+        $crate::webidl::native_function!(@function $context, interpreter, $function, [interpreter], $($parameters)*)
+    };
+    ($context:expr, $function:path, $($parameters:tt)*) => {
+        $crate::webidl::native_function!(@function $context, _interpreter, $function, [], $($parameters)*)
+    };
+}
+
+// https://webidl.spec.whatwg.org/#dfn-create-operation-function
+// e.g. operation!("Node", "appendChild", node_append_child(parent: RefNode, child: RefNode))
+macro_rules! operation {
+    ($interface:literal, $name:literal, $function:ident ( $($parameters:tt)* )) => {
+        $crate::webidl::native_function!(format!("Failed to execute '{}' on '{}'", $name, $interface), $function, $($parameters)*)
+    };
+}
+
+// https://webidl.spec.whatwg.org/#dfn-attribute-getter
+macro_rules! attribute_getter {
+    ($interface:literal, $name:literal, $function:ident ( $($parameters:tt)* )) => {
+        $crate::webidl::native_function!(format!("Failed to read the '{}' property from '{}'", $name, $interface), $function, $($parameters)*)
+    };
+}
+
+// https://webidl.spec.whatwg.org/#dfn-attribute-setter
+// The function is given the value as the argument after the this value
+macro_rules! attribute_setter {
+    ($interface:literal, $name:literal, $function:ident ( $($parameters:tt)* )) => {
+        $crate::webidl::native_function!(format!("Failed to set the '{}' property on '{}'", $name, $interface), $function, $($parameters)*)
+    };
+}
+
+pub(crate) use {attribute_getter, attribute_setter, native_function, operation};
+
+// https://webidl.spec.whatwg.org/#es-operations
+pub(crate) fn define_operations(prototype: &Rc<RefCell<JSObject>>, operations: &[(&str, NativeFunction)]) {
+    for (name, function) in operations {
+        Interpreter::define_builtin_function(prototype, name, *function);
+    }
+}
+
+// https://webidl.spec.whatwg.org/#es-attributes
+// The attributes with their getter and, unless they are readonly, their setter
+pub(crate) fn define_attributes(prototype: &Rc<RefCell<JSObject>>, attributes: &[(&str, NativeFunction, Option<NativeFunction>)]) {
+    for (name, getter, setter) in attributes {
+        Interpreter::define_accessor_property(prototype, name, *getter, *setter);
+    }
+}
+
+// https://webidl.spec.whatwg.org/#es-platform-objects
+pub(crate) fn create_platform_object(interpreter: &Interpreter, prototype: &str, platform_object: PlatformObject) -> Rc<RefCell<JSValue>> {
+    let mut object = JSObject::new();
+    object.extensible = true;
+    object.prototype = interpreter.intrinsic(prototype);
+    object.platform_object = Some(platform_object);
+    return Rc::new(RefCell::new(JSValue::Object(Rc::new(RefCell::new(object)))));
+}
+
+// https://webidl.spec.whatwg.org/#es-DOMString
+impl FromJSValue for DOMString {
+    const TYPE_NAME: &'static str = "DOMString";
+
+    fn from_js_value(interpreter: &mut Interpreter, value: &Rc<RefCell<JSValue>>) -> Result<Option<Self>, CompletionRecord> {
+        // 2. Let x be ? ToString(V).
+        let completion_record = interpreter.to_string_coercing_objects(Rc::clone(value));
+        if let CompletionRecordType::Throw = completion_record.type_ {
+            return Err(completion_record);
+        }
+
+        match &*completion_record.value {
+            ReferenceRecordOrJsValue::JSValue(value) => match &*value.borrow() {
+                JSValue::String(string) => Ok(Some(string.clone())),
+                _ => unreachable!()
+            },
+            _ => unreachable!()
+        }
+    }
+}
+
+impl ToJSValue for DOMString {
+    fn to_js_value(self, _interpreter: &mut Interpreter) -> Rc<RefCell<JSValue>> {
+        return Rc::new(RefCell::new(JSValue::String(self)));
+    }
+}
+
+// https://webidl.spec.whatwg.org/#es-boolean
+impl FromJSValue for bool {
+    const TYPE_NAME: &'static str = "boolean";
+
+    fn from_js_value(_interpreter: &mut Interpreter, value: &Rc<RefCell<JSValue>>) -> Result<Option<Self>, CompletionRecord> {
+        // 1. Let x be the result of computing ToBoolean(V).
+        return Ok(Some(matches!(*Interpreter::to_boolean(Rc::clone(value)).borrow(), JSValue::Boolean(true))));
+    }
+}
+
+impl ToJSValue for bool {
+    fn to_js_value(self, _interpreter: &mut Interpreter) -> Rc<RefCell<JSValue>> {
+        return Rc::new(RefCell::new(JSValue::Boolean(self)));
+    }
+}
+
+// https://webidl.spec.whatwg.org/#es-unrestricted-double
+impl FromJSValue for f64 {
+    const TYPE_NAME: &'static str = "unrestricted double";
+
+    fn from_js_value(_interpreter: &mut Interpreter, value: &Rc<RefCell<JSValue>>) -> Result<Option<Self>, CompletionRecord> {
+        // 1. Let x be ? ToNumber(V).
+        let completion_record = Interpreter::to_number(Rc::clone(value));
+        if let CompletionRecordType::Throw = completion_record.type_ {
+            return Err(completion_record);
+        }
+
+        match &*completion_record.value {
+            ReferenceRecordOrJsValue::JSValue(value) => match &*value.borrow() {
+                JSValue::Numeric(number) => Ok(Some(*number)),
+                _ => unreachable!()
+            },
+            _ => unreachable!()
+        }
+    }
+}
+
+impl ToJSValue for f64 {
+    fn to_js_value(self, _interpreter: &mut Interpreter) -> Rc<RefCell<JSValue>> {
+        return Rc::new(RefCell::new(JSValue::Numeric(self)));
+    }
+}
+
+// https://webidl.spec.whatwg.org/#es-undefined
+impl ToJSValue for () {
+    fn to_js_value(self, _interpreter: &mut Interpreter) -> Rc<RefCell<JSValue>> {
+        return Rc::new(RefCell::new(JSValue::Undefined));
+    }
+}
+
+// https://webidl.spec.whatwg.org/#es-any
+impl FromJSValue for Rc<RefCell<JSValue>> {
+    const TYPE_NAME: &'static str = "any";
+
+    fn from_js_value(_interpreter: &mut Interpreter, value: &Rc<RefCell<JSValue>>) -> Result<Option<Self>, CompletionRecord> {
+        return Ok(Some(Rc::clone(value)));
+    }
+}
+
+impl ToJSValue for Rc<RefCell<JSValue>> {
+    fn to_js_value(self, _interpreter: &mut Interpreter) -> Rc<RefCell<JSValue>> {
+        return self;
+    }
+}
+
+// https://webidl.spec.whatwg.org/#es-nullable-type
+// Null and undefined are None, which also makes an argument of the type optional
+impl<T: FromJSValue> FromJSValue for Option<T> {
+    const TYPE_NAME: &'static str = T::TYPE_NAME;
+    const OPTIONAL: bool = true;
+
+    fn from_js_value(interpreter: &mut Interpreter, value: &Rc<RefCell<JSValue>>) -> Result<Option<Self>, CompletionRecord> {
+        if matches!(*value.borrow(), JSValue::Null | JSValue::Undefined) {
+            return Ok(Some(None));
+        }
+        return Ok(T::from_js_value(interpreter, value)?.map(Some));
+    }
+}
+
+impl<T: ToJSValue> ToJSValue for Option<T> {
+    fn to_js_value(self, interpreter: &mut Interpreter) -> Rc<RefCell<JSValue>> {
+        match self {
+            Some(value) => value.to_js_value(interpreter),
+            None => Rc::new(RefCell::new(JSValue::Null))
+        }
+    }
+}
+
+impl<T: ToJSValue> ToJSValue for OrUndefined<T> {
+    fn to_js_value(self, interpreter: &mut Interpreter) -> Rc<RefCell<JSValue>> {
+        match self {
+            OrUndefined::Value(value) => value.to_js_value(interpreter),
+            OrUndefined::Undefined => Rc::new(RefCell::new(JSValue::Undefined))
+        }
+    }
+}
+
+// https://webidl.spec.whatwg.org/#es-sequence
+// FIXME: Sequences are returned as arrays of the values at the time they were read rather than as live collections, e.g. NodeList
+impl<T: ToJSValue> ToJSValue for Vec<T> {
+    fn to_js_value(self, interpreter: &mut Interpreter) -> Rc<RefCell<JSValue>> {
+        let values = self.into_iter().map(|value| value.to_js_value(interpreter)).collect();
+        return Rc::new(RefCell::new(JSValue::Object(interpreter.create_array_from_list(values))));
+    }
+}
+
+// https://webidl.spec.whatwg.org/#es-interface
+impl FromJSValue for RefNode {
+    const TYPE_NAME: &'static str = "Node";
+
+    fn from_js_value(_interpreter: &mut Interpreter, value: &Rc<RefCell<JSValue>>) -> Result<Option<Self>, CompletionRecord> {
+        return Ok(unwrap_node(value));
+    }
+}
+
+impl ToJSValue for RefNode {
+    fn to_js_value(self, interpreter: &mut Interpreter) -> Rc<RefCell<JSValue>> {
+        return wrap_node(interpreter, &self);
+    }
+}
+
+impl FromJSValue for ElementNode {
+    const TYPE_NAME: &'static str = "Element";
+
+    fn from_js_value(_interpreter: &mut Interpreter, value: &Rc<RefCell<JSValue>>) -> Result<Option<Self>, CompletionRecord> {
+        let node = unwrap_node(value).filter(|node| matches!(node.borrow().data, NodeData::Element(_)));
+        return Ok(node.map(ElementNode));
+    }
+}
+
+impl ToJSValue for ElementNode {
+    fn to_js_value(self, interpreter: &mut Interpreter) -> Rc<RefCell<JSValue>> {
+        return wrap_node(interpreter, &self.0);
+    }
+}
+
+impl ToJSValue for Rc<RefCell<CSSStyleSheet>> {
+    fn to_js_value(self, interpreter: &mut Interpreter) -> Rc<RefCell<JSValue>> {
+        return wrap_css_style_sheet(interpreter, &self);
+    }
+}
+
+impl ToJSValue for Rc<RefCell<CSSStyleDeclaration>> {
+    fn to_js_value(self, interpreter: &mut Interpreter) -> Rc<RefCell<JSValue>> {
+        return wrap_css_style_declaration(interpreter, &self);
+    }
+}
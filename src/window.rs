@@ -0,0 +1,152 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::interpreter::{create_value_completion, CompletionRecord, Interpreter, JSObject, JSValue, NativeFunction};
+
+// https://html.spec.whatwg.org/multipage/nav-history-apis.html#the-window-object
+// The global object is the Window, so window, self and the global environment are the same object
+// TODO: There is no WindowProxy, Window.prototype or browsing context, and location can not be navigated
+
+// https://drafts.csswg.org/cssom-view/#viewport
+// The size scripts see for the viewport, in CSS pixels
+#[derive(Debug, Clone, Copy)]
+pub struct Viewport {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for Viewport {
+    fn default() -> Viewport {
+        Viewport { width: 1280, height: 720 }
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/system-state.html#dom-navigator-useragent
+const USER_AGENT: &str = concat!("Mozilla/5.0 (compatible; web_engine/", env!("CARGO_PKG_VERSION"), ")");
+
+pub(crate) fn install_window(interpreter: &mut Interpreter) {
+    let global_object = interpreter.global_object();
+
+    // https://html.spec.whatwg.org/multipage/nav-history-apis.html#dom-window
+    // https://html.spec.whatwg.org/multipage/nav-history-apis.html#dom-self
+    // https://tc39.es/ecma262/#sec-globalthis
+    for name in ["window", "self", "globalThis"] {
+        interpreter.define_global_property(name, Rc::new(RefCell::new(JSValue::Object(Rc::clone(&global_object)))));
+    }
+
+    define_viewport_properties(interpreter);
+
+    // https://html.spec.whatwg.org/multipage/system-state.html#the-navigator-object
+    let mut navigator = JSObject::new();
+    navigator.extensible = true;
+    navigator.prototype = interpreter.intrinsic("Object.prototype");
+    let navigator = Rc::new(RefCell::new(navigator));
+    let navigator_attributes: [(&str, NativeFunction); 4] = [
+        ("userAgent", navigator_user_agent),
+        ("appName", navigator_app_name),
+        ("platform", navigator_platform),
+        ("language", navigator_language),
+    ];
+    for (name, getter) in navigator_attributes {
+        Interpreter::define_accessor_property(&navigator, name, getter, None);
+    }
+    interpreter.define_global_property("navigator", Rc::new(RefCell::new(JSValue::Object(navigator))));
+
+    // https://html.spec.whatwg.org/multipage/nav-history-apis.html#the-location-interface
+    let mut location = JSObject::new();
+    location.extensible = true;
+    location.prototype = interpreter.intrinsic("Object.prototype");
+    let location = Rc::new(RefCell::new(location));
+    let location_attributes: [(&str, NativeFunction); 9] = [
+        ("href", location_href),
+        ("origin", location_origin),
+        ("protocol", location_protocol),
+        ("host", location_host),
+        ("hostname", location_hostname),
+        ("port", location_port),
+        ("pathname", location_pathname),
+        ("search", location_search),
+        ("hash", location_hash),
+    ];
+    for (name, getter) in location_attributes {
+        Interpreter::define_accessor_property(&location, name, getter, None);
+    }
+    // https://html.spec.whatwg.org/multipage/nav-history-apis.html#dom-location-tostring
+    Interpreter::define_builtin_function(&location, "toString", location_href);
+    interpreter.define_global_property("location", Rc::new(RefCell::new(JSValue::Object(location))));
+}
+
+// https://drafts.csswg.org/cssom-view/#dom-window-innerwidth
+// https://drafts.csswg.org/cssom-view/#dom-window-innerheight
+// FIXME: These are data properties as getters on the global object can not be run when they are read as identifiers, they are redefined whenever the viewport changes
+pub(crate) fn define_viewport_properties(interpreter: &mut Interpreter) {
+    let viewport = interpreter.viewport();
+    interpreter.define_global_property("innerWidth", Rc::new(RefCell::new(JSValue::Numeric(viewport.width as f64))));
+    interpreter.define_global_property("innerHeight", Rc::new(RefCell::new(JSValue::Numeric(viewport.height as f64))));
+}
+
+fn navigator_user_agent(_interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return create_value_completion(JSValue::String(USER_AGENT.to_string()));
+}
+
+// https://html.spec.whatwg.org/multipage/system-state.html#dom-navigator-appname
+fn navigator_app_name(_interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    // Must return "Netscape".
+    return create_value_completion(JSValue::String(String::from("Netscape")));
+}
+
+// https://html.spec.whatwg.org/multipage/system-state.html#dom-navigator-platform
+// Browsers are encouraged to return the empty string
+fn navigator_platform(_interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return create_value_completion(JSValue::String(String::new()));
+}
+
+// https://html.spec.whatwg.org/multipage/system-state.html#dom-navigator-language
+fn navigator_language(_interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return create_value_completion(JSValue::String(String::from("en-US")));
+}
+
+// https://html.spec.whatwg.org/multipage/nav-history-apis.html#dom-location-href
+// The getter steps return this's url, serialized.
+fn location_href(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return create_value_completion(JSValue::String(interpreter.document_url().href()));
+}
+
+// https://html.spec.whatwg.org/multipage/nav-history-apis.html#dom-location-origin
+fn location_origin(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return create_value_completion(JSValue::String(interpreter.document_url().origin()));
+}
+
+// https://html.spec.whatwg.org/multipage/nav-history-apis.html#dom-location-protocol
+fn location_protocol(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return create_value_completion(JSValue::String(interpreter.document_url().protocol()));
+}
+
+// https://html.spec.whatwg.org/multipage/nav-history-apis.html#dom-location-host
+fn location_host(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return create_value_completion(JSValue::String(interpreter.document_url().host()));
+}
+
+// https://html.spec.whatwg.org/multipage/nav-history-apis.html#dom-location-hostname
+fn location_hostname(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return create_value_completion(JSValue::String(interpreter.document_url().hostname()));
+}
+
+// https://html.spec.whatwg.org/multipage/nav-history-apis.html#dom-location-port
+fn location_port(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return create_value_completion(JSValue::String(interpreter.document_url().port_string()));
+}
+
+// https://html.spec.whatwg.org/multipage/nav-history-apis.html#dom-location-pathname
+fn location_pathname(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return create_value_completion(JSValue::String(interpreter.document_url().pathname()));
+}
+
+// https://html.spec.whatwg.org/multipage/nav-history-apis.html#dom-location-search
+fn location_search(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return create_value_completion(JSValue::String(interpreter.document_url().search()));
+}
+
+// https://html.spec.whatwg.org/multipage/nav-history-apis.html#dom-location-hash
+fn location_hash(interpreter: &mut Interpreter, _this_value: Rc<RefCell<JSValue>>, _arguments: Vec<Rc<RefCell<JSValue>>>) -> CompletionRecord {
+    return create_value_completion(JSValue::String(interpreter.document_url().hash()));
+}
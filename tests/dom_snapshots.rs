@@ -0,0 +1,42 @@
+#![cfg(feature = "fs")]
+
+use std::path::Path;
+use web_engine::assert_dom_snapshot;
+use web_engine::snapshot::{self, Outcome};
+
+// Every snapshot in tests/snapshots, the same ones the snapshot command checks
+#[test]
+fn snapshots_directory() {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("snapshots");
+    let paths = snapshot::snapshot_files(&directory).unwrap();
+    assert!(!paths.is_empty(), "there are no snapshots in {}", directory.display());
+
+    let mut failures = Vec::new();
+    for path in &paths {
+        match snapshot::check_file(path, snapshot::update_requested()) {
+            Outcome::Pass | Outcome::Updated => {},
+            Outcome::Missing => failures.push(format!("{}: missing", path.display())),
+            Outcome::Fail { first_difference: (line, actual, expected), .. } => {
+                failures.push(format!("{}: line {} is now {} instead of {}", path.display(), line + 1, actual, expected))
+            },
+            Outcome::Error(message) => failures.push(message)
+        }
+    }
+    assert!(failures.is_empty(), "{} of {} snapshots failed\n{}", failures.len(), paths.len(), failures.join("\n"));
+}
+
+#[test]
+fn table_sections() {
+    assert_dom_snapshot!("<table><tbody><tr><td>1</td></tr></tbody></table>");
+}
+
+#[test]
+fn end_tag_closes_paragraph() {
+    assert_dom_snapshot!("<div><p>One</div>Two");
+}
+
+#[test]
+fn head_and_body() {
+    assert_dom_snapshot!("title_in_head", "<title>A &amp; B</title><p>x");
+    assert_dom_snapshot!("closed_list_items", "<ul><li>One</li><li>Two</li></ul>");
+}
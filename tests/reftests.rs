@@ -0,0 +1,23 @@
+#![cfg(feature = "fs")]
+
+use std::path::Path;
+use web_engine::reftest::{self, Outcome};
+
+// Every reftest of tests/ref/reftest.list, the same ones the reftest command runs
+#[test]
+fn reftest_manifest() {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("ref").join("reftest.list");
+    let reftests = reftest::parse_manifest(&manifest).unwrap();
+    assert!(!reftests.is_empty(), "there are no reftests in {}", manifest.display());
+
+    let mut failures = Vec::new();
+    for reftest in &reftests {
+        let name = format!("{} {:?} {}", reftest.test.display(), reftest.relation, reftest.reference.display());
+        match reftest::run(reftest, 800) {
+            Outcome::Pass => {},
+            Outcome::Fail(comparison) => failures.push(format!("{}: {} pixels differ, the first at {:?}", name, comparison.differing_pixels, comparison.first_difference)),
+            Outcome::Error(message) => failures.push(format!("{}: {}", name, message))
+        }
+    }
+    assert!(failures.is_empty(), "{} of {} reftests failed\n{}", failures.len(), reftests.len(), failures.join("\n"));
+}